@@ -10,9 +10,12 @@
 //! let mut client = CrownyClient::new("http://localhost:7293");
 //! let result = client.run("넣어 42\n종료");
 //! println!("{}", result.state);
+//!
+//! // 보류(O)면 백오프하며 다시 조회
+//! let done = client.wait_for_result(result.task_id);
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::time::{Duration, Instant};
 
@@ -63,6 +66,15 @@ impl Trit {
         if p > t { Trit::P } else if t > p { Trit::T } else { Trit::O }
     }
 
+    /// 신뢰도 가중 합의 — 점수 = Σ(trit·신뢰도)/Σ신뢰도, |점수| ≤ deadband면 O
+    pub fn weighted_consensus(votes: &[(Trit, f64)], deadband: f64) -> (Trit, f64) {
+        let total: f64 = votes.iter().map(|(_, c)| c.clamp(0.0, 1.0)).sum();
+        if total <= 0.0 { return (Trit::O, 0.0); }
+        let score = votes.iter().map(|(t, c)| t.to_i8() as f64 * c.clamp(0.0, 1.0)).sum::<f64>() / total;
+        let trit = if score > deadband { Trit::P } else if score < -deadband { Trit::T } else { Trit::O };
+        (trit, score)
+    }
+
     pub fn from_str(s: &str) -> Self {
         let s = s.to_uppercase();
         if s.contains('P') || s.contains("성공") || s.contains("SUCCESS") { Trit::P }
@@ -193,30 +205,227 @@ impl Default for CtpHeader {
     fn default() -> Self { Self::new() }
 }
 
+/// 이 SDK가 말할 수 있는 CTP 프로토콜 버전 범위
+pub const PROTOCOL_VERSIONS: (u8, u8) = (1, 2);
+/// 27-Trit 확장 헤더 이름 — 9-trit X-Crowny-Trit과 함께 보낸다
+pub const CTP_V2_HEADER: &str = "X-Crowny-Trit-V2";
+const CTP_V2_FORMAT: u8 = 2;
+const REQUEST_ID_SPAN: u64 = 19_683;
+pub const MAX_HOPS: u8 = 13;
+
+fn put_balanced(out: &mut [Trit], value: i64) {
+    let mut v = value;
+    for slot in out.iter_mut().rev() {
+        let low = (v + 1).rem_euclid(3) - 1;
+        *slot = Trit::from_i8(low as i8);
+        v = (v - low) / 3;
+    }
+}
+
+fn get_balanced(trits: &[Trit]) -> i64 {
+    trits.iter().fold(0, |acc, t| acc * 3 + t.to_i8() as i64)
+}
+
+/// 27-Trit CTP 헤더 — 서버 webserver::CtpHeaderV2와 같은 배치
+///   [0..9] 9-trit 헤더 · [9..11] 형식(2) · [11..15] 최소/최대 버전 · [15..24] 요청 ID · [24..27] 홉 수
+#[derive(Debug, Clone)]
+pub struct CtpHeaderV2 {
+    pub base: CtpHeader,
+    pub versions: (u8, u8),
+    pub request_id: u16,
+    pub hops: u8,
+}
+
+impl CtpHeaderV2 {
+    pub fn new(base: CtpHeader, request_id: u64) -> Self {
+        Self { base, versions: PROTOCOL_VERSIONS, request_id: (request_id % REQUEST_ID_SPAN) as u16, hops: 0 }
+    }
+
+    /// 27-trit이 아니거나 형식 뱅크가 다르면 None
+    pub fn parse(s: &str) -> Option<Self> {
+        let trits: Vec<Trit> = s.trim().chars().map(|c| match c {
+            'P' | '+' | '1' => Trit::P,
+            'T' | '-' => Trit::T,
+            _ => Trit::O,
+        }).collect();
+        let version = |i: usize| get_balanced(&trits[i..i + 2]);
+        if trits.len() != 27 || version(9) != CTP_V2_FORMAT as i64 {
+            return None;
+        }
+        let mut base = CtpHeader::new();
+        base.trits.copy_from_slice(&trits[..9]);
+        Some(Self {
+            base,
+            versions: (version(11).max(0) as u8, version(13).max(0) as u8),
+            request_id: (get_balanced(&trits[15..24]) + (REQUEST_ID_SPAN as i64 - 1) / 2) as u16,
+            hops: get_balanced(&trits[24..27]).clamp(0, MAX_HOPS as i64) as u8,
+        })
+    }
+}
+
+impl fmt::Display for CtpHeaderV2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut t = [Trit::O; 27];
+        t[..9].copy_from_slice(&self.base.trits);
+        put_balanced(&mut t[9..11], CTP_V2_FORMAT as i64);
+        put_balanced(&mut t[11..13], self.versions.0 as i64);
+        put_balanced(&mut t[13..15], self.versions.1 as i64);
+        put_balanced(&mut t[15..24], self.request_id as i64 - (REQUEST_ID_SPAN as i64 - 1) / 2);
+        put_balanced(&mut t[24..27], self.hops.min(MAX_HOPS) as i64);
+        for trit in &t { write!(f, "{}", trit)?; }
+        Ok(())
+    }
+}
+
+/// 서버와 합의한 헤더 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CtpMode {
+    /// 아직 응답을 못 받음 — 9-trit과 27-trit을 함께 보낸다
+    Unknown,
+    /// 서버가 27-trit으로 답함
+    V2,
+    /// 구버전 서버 — 이후 9-trit만 보낸다
+    V1,
+}
+
+// ═══════════════════════════════════════════════
+// RetryPolicy
+// ═══════════════════════════════════════════════
+
+/// 보류(O) 결과 재조회 정책 — 지수 백오프 + 지터
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// 첫 요청을 포함한 최대 시도 횟수
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// 0.0~1.0 — 대기 시간을 ±비율만큼 흔들어 동시 재시도를 흩뜨린다
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// 재시도 없음 — 보류를 그대로 돌려준다
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    pub fn new(max_attempts: u32) -> Self {
+        Self { max_attempts: max_attempts.max(1), ..Self::default() }
+    }
+
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// retry번째 재시도 전 대기 시간 (retry는 0부터, unit은 [0,1) 난수)
+    pub fn delay(&self, retry: u32, unit: f64) -> Duration {
+        let base = self.initial_backoff.as_secs_f64() * self.multiplier.powi(retry.min(64) as i32);
+        let capped = base.min(self.max_backoff.as_secs_f64());
+        let spread = 1.0 + self.jitter * (unit.clamp(0.0, 1.0) * 2.0 - 1.0);
+        Duration::from_secs_f64((capped * spread).max(0.0))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════
 // CrownyClient
 // ═══════════════════════════════════════════════
 
+/// 보류로 끝난 요청 — 재조회 때 다시 보낸다
+#[derive(Debug, Clone)]
+struct PendingTask {
+    task_type: String,
+    subject: String,
+    payload: String,
+    params: HashMap<String, String>,
+}
+
 /// Crowny 서버 클라이언트
 pub struct CrownyClient {
     base_url: String,
     timeout: Duration,
     ctp: CtpHeader,
     task_counter: u64,
-    history: Vec<TritResult>,
+    /// 최근 결과 링 — 용량을 넘으면 가장 오래된 결과부터 버린다
+    history: VecDeque<TritResult>,
+    history_capacity: usize,
+    /// Some이면 submit_sync가 보류를 자동으로 재조회
+    retry: Option<RetryPolicy>,
+    pending: HashMap<u64, PendingTask>,
+    rng: u64,
+    ctp_mode: CtpMode,
+    deadband: f64,
 }
 
 impl CrownyClient {
     pub fn new(base_url: &str) -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
         Self {
             base_url: base_url.trim_end_matches('/').to_string(),
             timeout: Duration::from_secs(30),
             ctp: CtpHeader::success(),
             task_counter: 0,
-            history: Vec::new(),
+            history: VecDeque::new(),
+            history_capacity: DEFAULT_HISTORY_CAPACITY,
+            retry: None,
+            pending: HashMap::new(),
+            rng: seed | 1,
+            ctp_mode: CtpMode::Unknown,
+            deadband: DEFAULT_DEADBAND,
         }
     }
 
+    /// 서버와 합의된 CTP 헤더 형식
+    pub fn ctp_mode(&self) -> CtpMode {
+        self.ctp_mode
+    }
+
+    /// 보류(O) 자동 재조회 켜기
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// 결과 기록 용량 (최소 1)
+    pub fn with_history_capacity(mut self, capacity: usize) -> Self {
+        self.history_capacity = capacity.max(1);
+        while self.history.len() > self.history_capacity {
+            self.history.pop_front();
+        }
+        self
+    }
+
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
@@ -235,18 +444,104 @@ impl CrownyClient {
         payload: &str,
         params: HashMap<String, String>,
     ) -> TritResult {
-        let start = Instant::now();
         self.task_counter += 1;
         let task_id = self.task_counter;
+        let task = PendingTask {
+            task_type: task_type.to_string(),
+            subject: subject.to_string(),
+            payload: payload.to_string(),
+            params,
+        };
+        let result = self.send(task_id, &task);
+        if result.is_pending() {
+            self.pending.insert(task_id, task);
+            if let Some(policy) = self.retry.clone() {
+                return self.retry_pending(task_id, &policy, result);
+            }
+        }
+        self.record(result.clone());
+        result
+    }
 
-        // HTTP 요청 (blocking — async 버전은 별도)
-        let result = match ureq_post(
+    /// 보류된 작업을 정책(없으면 기본값)에 따라 P/T가 될 때까지 재조회
+    /// 서버에 작업 조회 엔드포인트가 없으므로 같은 요청을 다시 보낸다
+    pub fn wait_for_result(&mut self, task_id: u64) -> TritResult {
+        if !self.pending.contains_key(&task_id) {
+            return match self.history.iter().rev().find(|r| r.task_id == task_id) {
+                Some(done) => done.clone(),
+                None => TritResult::failed(ResultData::Text(format!("알 수 없는 작업: {}", task_id)), 0, task_id),
+            };
+        }
+        let policy = self.retry.clone().unwrap_or_default();
+        let last = self.poll(task_id);
+        if !last.is_pending() || policy.max_attempts <= 1 {
+            return self.settle(task_id, last);
+        }
+        self.retry_pending(task_id, &policy, last)
+    }
+
+    /// 아직 보류 중인 작업 ID
+    pub fn pending_tasks(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self.pending.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// 첫 결과 이후 남은 시도 동안 백오프하며 재조회
+    fn retry_pending(&mut self, task_id: u64, policy: &RetryPolicy, first: TritResult) -> TritResult {
+        let start = Instant::now();
+        let mut last = first;
+        for retry in 0..policy.max_attempts.saturating_sub(1) {
+            if !last.is_pending() {
+                break;
+            }
+            let unit = self.next_unit();
+            std::thread::sleep(policy.delay(retry, unit));
+            last = self.poll(task_id);
+        }
+        last.elapsed_ms += start.elapsed().as_millis() as u64;
+        self.settle(task_id, last)
+    }
+
+    fn poll(&mut self, task_id: u64) -> TritResult {
+        match self.pending.get(&task_id).cloned() {
+            Some(task) => self.send(task_id, &task),
+            None => TritResult::failed(ResultData::Text(format!("알 수 없는 작업: {}", task_id)), 0, task_id),
+        }
+    }
+
+    /// 결과 기록 — P/T로 끝나면 보류 목록에서 뺀다
+    fn settle(&mut self, task_id: u64, result: TritResult) -> TritResult {
+        if !result.is_pending() {
+            self.pending.remove(&task_id);
+        }
+        self.record(result.clone());
+        result
+    }
+
+    fn record(&mut self, result: TritResult) {
+        if self.history.len() == self.history_capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(result);
+    }
+
+    /// HTTP 요청 (blocking — async 버전은 별도)
+    fn send(&mut self, task_id: u64, task: &PendingTask) -> TritResult {
+        let start = Instant::now();
+        let v2 = (self.ctp_mode != CtpMode::V1).then(|| CtpHeaderV2::new(self.ctp.clone(), task_id));
+        match ureq_post(
             &format!("{}/run", self.base_url),
             &self.ctp,
-            task_type, subject, payload, &params,
+            v2.as_ref(),
+            &task.task_type, &task.subject, &task.payload, &task.params,
         ) {
-            Ok((state, data, resp_ctp)) => {
+            Ok((state, data, resp_ctp, resp_v2)) => {
                 if let Some(c) = resp_ctp { self.ctp = c; }
+                // 27-trit 응답이 없으면 구버전 서버로 보고 9-trit으로 내려간다
+                if v2.is_some() {
+                    self.ctp_mode = if resp_v2.is_some() { CtpMode::V2 } else { CtpMode::V1 };
+                }
                 TritResult { state, data, elapsed_ms: start.elapsed().as_millis() as u64, task_id }
             }
             Err(e) => {
@@ -256,10 +551,15 @@ impl CrownyClient {
                     task_id,
                 )
             }
-        };
+        }
+    }
 
-        self.history.push(result.clone());
-        result
+    /// 지터용 [0,1) 난수 (xorshift)
+    fn next_unit(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
     }
 
     /// 한선어 소스 실행
@@ -294,20 +594,32 @@ impl CrownyClient {
         let mut results = Vec::new();
         for model in &models {
             let r = self.ask_model(prompt, model);
-            results.push(ModelResult { model: model.to_string(), result: r });
+            let confidence = response_confidence(&r.data);
+            results.push(ModelResult { model: model.to_string(), result: r, confidence });
         }
 
         let trits: Vec<Trit> = results.iter().map(|r| r.result.state).collect();
         let con = Trit::consensus(&trits);
+        let votes: Vec<(Trit, f64)> = results.iter().map(|r| (r.result.state, r.confidence)).collect();
+        let (weighted, weighted_score) = Trit::weighted_consensus(&votes, self.deadband);
 
         ConsensusResult {
             consensus: con,
+            tally: Tally::of(&trits),
             models: results,
             trits,
+            weighted,
+            weighted_score,
             elapsed_ms: start.elapsed().as_millis() as u64,
         }
     }
 
+    /// 가중 합의의 O 데드밴드 (기본 DEFAULT_DEADBAND)
+    pub fn with_deadband(mut self, deadband: f64) -> Self {
+        self.deadband = deadband;
+        self
+    }
+
     /// 서버 핑
     pub fn ping(&mut self) -> TritResult {
         let start = Instant::now();
@@ -324,8 +636,11 @@ impl CrownyClient {
         }
     }
 
-    pub fn history(&self) -> &[TritResult] { &self.history }
+    /// 최근 결과 (오래된 순, 최대 history_capacity개)
+    pub fn history(&self) -> &VecDeque<TritResult> { &self.history }
+
 
+    /// 통계는 기록 링에 남은 결과 기준
     pub fn stats(&self) -> (usize, usize, usize, usize) {
         let total = self.history.len();
         let p = self.history.iter().filter(|r| r.state == Trit::P).count();
@@ -335,13 +650,45 @@ impl CrownyClient {
     }
 }
 
-/// 합의 결과
+/// 클라이언트 결과 기록 기본 용량
+pub const DEFAULT_HISTORY_CAPACITY: usize = 1_000;
+
+/// 가중 합의 O 데드밴드 기본값
+pub const DEFAULT_DEADBAND: f64 = 0.2;
+
+/// 원시 집계
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tally {
+    pub p: usize,
+    pub o: usize,
+    pub t: usize,
+}
+
+impl Tally {
+    pub fn of(trits: &[Trit]) -> Self {
+        let count = |x: Trit| trits.iter().filter(|t| **t == x).count();
+        Self { p: count(Trit::P), o: count(Trit::O), t: count(Trit::T) }
+    }
+}
+
+/// 합의 결과 — 다수결(consensus)과 신뢰도 가중(weighted)을 함께 담는다
 #[derive(Debug)]
 pub struct ConsensusResult {
     pub consensus: Trit,
     pub models: Vec<ModelResult>,
     pub trits: Vec<Trit>,
     pub elapsed_ms: u64,
+    pub tally: Tally,
+    pub weighted: Trit,
+    /// -1.0 ~ 1.0
+    pub weighted_score: f64,
+}
+
+impl ConsensusResult {
+    /// 다수결과 가중 결과가 다른가
+    pub fn diverges(&self) -> bool {
+        self.consensus != self.weighted
+    }
 }
 
 /// 단일 모델 결과
@@ -349,6 +696,17 @@ pub struct ConsensusResult {
 pub struct ModelResult {
     pub model: String,
     pub result: TritResult,
+    /// 응답의 `"confidence"` (없으면 1.0)
+    pub confidence: f64,
+}
+
+/// 응답 본문의 `"confidence":0.83` — 없거나 숫자가 아니면 1.0
+fn response_confidence(data: &ResultData) -> f64 {
+    let (ResultData::Json(body) | ResultData::Text(body)) = data else { return 1.0 };
+    body.find("\"confidence\":")
+        .map(|at| &body[at + "\"confidence\":".len()..])
+        .and_then(|rest| rest.trim_start().split(|c: char| !(c.is_ascii_digit() || c == '.')).next()?.parse().ok())
+        .unwrap_or(1.0)
 }
 
 // ── HTTP helper (no external deps) ──
@@ -356,11 +714,12 @@ pub struct ModelResult {
 fn ureq_post(
     url: &str,
     ctp: &CtpHeader,
+    ctp_v2: Option<&CtpHeaderV2>,
     task_type: &str,
     subject: &str,
     payload: &str,
     _params: &HashMap<String, String>,
-) -> Result<(Trit, ResultData, Option<CtpHeader>), String> {
+) -> Result<(Trit, ResultData, Option<CtpHeader>, Option<CtpHeaderV2>), String> {
     // Minimal HTTP POST without external deps
     use std::io::{Read, Write};
     use std::net::TcpStream;
@@ -376,9 +735,10 @@ fn ureq_post(
         payload.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
     );
 
+    let v2_line = ctp_v2.map(|h| format!("{}: {}\r\n", CTP_V2_HEADER, h)).unwrap_or_default();
     let request = format!(
-        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nX-Crowny-Trit: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-        path, host, ctp, body.len(), body
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nX-Crowny-Trit: {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path, host, ctp, v2_line, body.len(), body
     );
 
     let mut stream = TcpStream::connect(host).map_err(|e| e.to_string())?;
@@ -392,12 +752,18 @@ fn ureq_post(
     let resp_ctp = response.lines()
         .find(|l| l.to_lowercase().starts_with("x-crowny-trit:"))
         .map(|l| CtpHeader::parse(l.split(':').nth(1).unwrap_or("").trim()));
+    let resp_v2 = response.lines()
+        .take_while(|l| !l.is_empty())
+        .find_map(|l| l.split_once(':').filter(|(k, _)| k.trim().eq_ignore_ascii_case(CTP_V2_HEADER)))
+        .and_then(|(_, v)| CtpHeaderV2::parse(v));
 
     // Extract body (after \r\n\r\n)
     let body_text = response.split("\r\n\r\n").nth(1).unwrap_or("");
 
-    // Simple state detection
-    let state = if body_text.contains("성공") || body_text.contains("Success") || body_text.contains("\"P\"") {
+    // 서버의 안정 스키마 우선, 없으면 단순 감지
+    let state = if let Some(t) = schema_state(body_text) {
+        t
+    } else if body_text.contains("성공") || body_text.contains("Success") || body_text.contains("\"P\"") {
         Trit::P
     } else if body_text.contains("실패") || body_text.contains("Failed") || body_text.contains("\"T\"") {
         Trit::T
@@ -405,7 +771,20 @@ fn ureq_post(
         Trit::O
     };
 
-    Ok((state, ResultData::Json(body_text.to_string()), resp_ctp))
+    Ok((state, ResultData::Json(body_text.to_string()), resp_ctp, resp_v2))
+}
+
+/// `"schema":"crowny.*"` 객체의 `"state":"P|O|T"` — 서버 to_json() 형식
+fn schema_state(body: &str) -> Option<Trit> {
+    let at = body.find("\"schema\":\"crowny.")?;
+    let rest = &body[at..];
+    let state = rest.find("\"state\":\"")? + "\"state\":\"".len();
+    match rest[state..].chars().next()? {
+        'P' => Some(Trit::P),
+        'O' => Some(Trit::O),
+        'T' => Some(Trit::T),
+        _ => None,
+    }
 }
 
 // ═══════════════════════════════════════════════
@@ -459,6 +838,28 @@ mod tests {
         assert_eq!(Trit::consensus(&[Trit::P, Trit::O, Trit::T]), Trit::O);
     }
 
+    #[test]
+    fn test_weighted_consensus_deadband() {
+        let votes = [(Trit::P, 0.3), (Trit::P, 0.3), (Trit::T, 0.95)];
+        assert_eq!(Trit::consensus(&[Trit::P, Trit::P, Trit::T]), Trit::P);
+        let (t, score) = Trit::weighted_consensus(&votes, DEFAULT_DEADBAND);
+        assert_eq!(t, Trit::T);
+        assert!(score < -DEFAULT_DEADBAND);
+        assert_eq!(Trit::weighted_consensus(&votes, 0.3).0, Trit::O);
+        assert_eq!(Trit::weighted_consensus(&[], 0.2), (Trit::O, 0.0));
+
+        assert_eq!(response_confidence(&ResultData::Json(r#"{"state":"P","confidence": 0.25}"#.into())), 0.25);
+        assert_eq!(response_confidence(&ResultData::Json(r#"{"state":"P"}"#.into())), 1.0);
+        assert_eq!(Tally::of(&[Trit::P, Trit::O, Trit::P]), Tally { p: 2, o: 1, t: 0 });
+    }
+
+    #[test]
+    fn test_schema_state() {
+        let body = r#"{"상태":"P(성공)","trit_result":{"schema":"crowny.trit_result","schema_version":1,"state":"T"}}"#;
+        assert_eq!(schema_state(body), Some(Trit::T));
+        assert_eq!(schema_state(r#"{"상태":"P"}"#), None);
+    }
+
     #[test]
     fn test_ctp_header() {
         let h = CtpHeader::success();
@@ -492,6 +893,127 @@ mod tests {
         assert!(!r.is_failed());
     }
 
+    #[test]
+    fn test_retry_policy_backoff() {
+        let p = RetryPolicy::new(6)
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+            .with_jitter(0.0);
+        assert_eq!(p.delay(0, 0.9), Duration::from_millis(100));
+        assert_eq!(p.delay(2, 0.9), Duration::from_millis(400));
+        assert_eq!(p.delay(3, 0.9), Duration::from_millis(500));
+        let j = p.with_jitter(0.5);
+        assert_eq!(j.delay(0, 0.0), Duration::from_millis(50));
+        assert_eq!(j.delay(0, 0.5), Duration::from_millis(100));
+        assert!(j.delay(0, 0.999) < Duration::from_millis(150));
+        assert_eq!(RetryPolicy::new(0).max_attempts, 1);
+        assert_eq!(RetryPolicy::none().max_attempts, 1);
+    }
+
+    /// 순서대로 상태를 돌려주는 1회용 서버
+    fn scripted_server(states: &'static [char]) -> String {
+        serve(states, false).0
+    }
+
+    /// 받은 요청 원문을 돌려주는 서버 — v2면 27-trit 헤더로 답하는 신버전 서버
+    fn serve(states: &'static [char], v2: bool) -> (String, std::sync::mpsc::Receiver<String>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for state in states {
+                let (mut s, _) = listener.accept().unwrap();
+                let mut req = Vec::new();
+                let mut buf = [0u8; 1024];
+                while let Ok(n) = s.read(&mut buf) {
+                    req.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&req);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let len = text.lines().find_map(|l| l.strip_prefix("Content-Length: ")).and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+                        if req.len() >= end + 4 + len { break; }
+                    }
+                    if n == 0 { break; }
+                }
+                let text = String::from_utf8_lossy(&req).to_string();
+                let echo = text.lines()
+                    .find_map(|l| l.strip_prefix(&format!("{}: ", CTP_V2_HEADER)))
+                    .filter(|_| v2)
+                    .map(|h| format!("{}: {}\r\n", CTP_V2_HEADER, h))
+                    .unwrap_or_default();
+                let _ = tx.send(text);
+                let body = format!(r#"{{"trit_result":{{"schema":"crowny.trit_result","state":"{}"}}}}"#, state);
+                let _ = write!(s, "HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}", echo, body.len(), body);
+            }
+        });
+        (format!("http://{}", addr), rx)
+    }
+
+    #[test]
+    fn test_ctp_v2_header_and_fallback() {
+        let mut h = CtpHeaderV2::new(CtpHeader::success(), 19_683 + 77);
+        h.hops = 4;
+        let s = h.to_string();
+        assert_eq!(s.len(), 27);
+        assert!(s.starts_with("PPPOOOOOO"));
+        let back = CtpHeaderV2::parse(&s).unwrap();
+        assert_eq!((back.request_id, back.hops, back.versions), (77, 4, PROTOCOL_VERSIONS));
+        assert!(CtpHeaderV2::parse("PPPOOOOOO").is_none());
+
+        // 구버전 서버 — 첫 응답에 27-trit이 없으면 이후엔 9-trit만
+        let (url, requests) = serve(&['P', 'P'], false);
+        let mut c = CrownyClient::new(&url);
+        assert_eq!(c.ctp_mode(), CtpMode::Unknown);
+        c.run("넣어 1");
+        assert!(requests.recv().unwrap().contains(CTP_V2_HEADER));
+        assert_eq!(c.ctp_mode(), CtpMode::V1);
+        c.run("넣어 2");
+        assert!(!requests.recv().unwrap().contains(CTP_V2_HEADER));
+
+        // 신버전 서버 — 계속 27-trit, 요청 ID는 작업 번호
+        let (url, requests) = serve(&['P', 'P'], true);
+        let mut c = CrownyClient::new(&url);
+        c.run("넣어 1");
+        c.run("넣어 2");
+        assert_eq!(c.ctp_mode(), CtpMode::V2);
+        let second = requests.iter().nth(1).unwrap();
+        let sent = second.lines().find_map(|l| l.strip_prefix("X-Crowny-Trit-V2: ")).unwrap();
+        assert_eq!(CtpHeaderV2::parse(sent).unwrap().request_id, 2);
+    }
+
+    #[test]
+    fn test_wait_for_result_polls_until_resolved() {
+        let fast = RetryPolicy::new(5).with_backoff(Duration::ZERO, Duration::ZERO);
+        let mut c = CrownyClient::new(&scripted_server(&['O', 'O', 'O', 'P']));
+        let first = c.run("넣어 1");
+        assert!(first.is_pending());
+        assert_eq!(c.pending_tasks(), vec![first.task_id]);
+
+        let mut c = c.with_retry(fast.clone());
+        let done = c.wait_for_result(first.task_id);
+        assert!(done.is_success());
+        assert_eq!(done.task_id, first.task_id);
+        assert!(c.pending_tasks().is_empty());
+        // 이미 끝난 작업은 기록에서, 모르는 작업은 실패
+        assert!(c.wait_for_result(first.task_id).is_success());
+        assert!(c.wait_for_result(99).is_failed());
+
+        // 자동 재조회 — 시도 횟수를 다 쓰면 보류로 남는다
+        let mut c = CrownyClient::new(&scripted_server(&['O', 'O'])).with_retry(RetryPolicy::new(2).with_backoff(Duration::ZERO, Duration::ZERO));
+        let r = c.run("넣어 2");
+        assert!(r.is_pending());
+        assert_eq!(c.pending_tasks(), vec![r.task_id]);
+        assert_eq!(c.stats(), (1, 0, 1, 0));
+    }
+
+    #[test]
+    fn test_history_ring_bounded() {
+        let mut c = CrownyClient::new(&scripted_server(&['P', 'T', 'P'])).with_history_capacity(2);
+        let ids: Vec<u64> = (0..3).map(|i| c.run(&format!("넣어 {}", i)).task_id).collect();
+        assert_eq!(c.history().len(), 2);
+        assert_eq!(c.history().iter().map(|r| r.task_id).collect::<Vec<_>>(), ids[1..]);
+        assert_eq!(c.stats(), (2, 1, 0, 1));
+    }
+
     #[test]
     fn test_client_stats() {
         let c = CrownyClient::new("http://localhost:7293");
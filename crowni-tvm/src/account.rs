@@ -0,0 +1,479 @@
+// ═══════════════════════════════════════════════════════════════
+// 계정 — 모듈 공통 신원 (주소 · 공개키 · 프로필 · 3진 평판)
+//
+//   주소: "crwn:" + sha256(첫 공개키) 앞 20바이트 hex
+//   이전: 기존 모듈은 "alice" 같은 문자열로 잔액을 기록한다
+//         legacy("alice") → 결정적 주소의 계정을 만들고 "alice"를 별칭으로
+//         link_alias로 실제 키 계정에 옛 이름을 붙일 수 있다
+//   조회: resolve(주소 | 별칭), holdings()는 모든 이름으로 각 원장을 합산
+//         subject()는 토큰 주체 → 주소 (서버는 모든 원장을 이 주소로 조회)
+//   저장: TritStore "account.<주소>" (한 곳에서 관리, 바뀐 계정은 바로 기록)
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::crypto;
+use crate::output::JsonObject;
+use crate::trit_store::{StoreValue, TritStore};
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+pub const ADDRESS_PREFIX: &str = "crwn:";
+const KEY_PREFIX: &str = "account.";
+/// 평판 점수가 이 이상이면 P, 음수로 이만큼이면 T
+pub const REPUTATION_BAND: i64 = 10;
+
+/// 계정 주소
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AccountId(String);
+
+impl AccountId {
+    fn derive(seed: &[u8]) -> Self {
+        Self(format!("{}{}", ADDRESS_PREFIX, crypto::to_hex(&crypto::sha256(seed)[..20])))
+    }
+
+    /// 공개키 → 주소
+    pub fn from_key(public_key: &[u8]) -> Self {
+        Self::derive(public_key)
+    }
+
+    /// 옛 문자열 이름 → 결정적 주소 (이전 전용)
+    pub fn legacy(name: &str) -> Self {
+        Self::derive(format!("legacy:{}", name).as_bytes())
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let hex = s.strip_prefix(ADDRESS_PREFIX)?;
+        (hex.len() == 40 && crypto::from_hex(hex).is_some()).then(|| Self(s.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// 기존 &str API에 그대로 넘길 수 있도록
+impl Deref for AccountId {
+    type Target = str;
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountKey {
+    pub label: String,
+    pub public_hex: String,
+    pub revoked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    pub id: AccountId,
+    pub display_name: String,
+    pub keys: Vec<AccountKey>,
+    pub metadata: BTreeMap<String, String>,
+    /// 평판 점수 — trit()으로 P/O/T
+    pub reputation: i64,
+    /// 옛 모듈에서 쓰던 이름들
+    pub aliases: Vec<String>,
+    pub created_at: u64,
+}
+
+impl Account {
+    pub fn trit(&self) -> i8 {
+        if self.reputation >= REPUTATION_BAND { 1 } else if self.reputation <= -REPUTATION_BAND { -1 } else { 0 }
+    }
+
+    pub fn active_keys(&self) -> impl Iterator<Item = &AccountKey> {
+        self.keys.iter().filter(|k| !k.revoked)
+    }
+
+    /// 원장 조회에 쓸 이름 — 주소 + 별칭
+    pub fn names(&self) -> Vec<&str> {
+        std::iter::once(self.id.as_str()).chain(self.aliases.iter().map(String::as_str)).collect()
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.account")
+            .trit("state", self.trit())
+            .str("id", &self.id)
+            .str("display_name", &self.display_name)
+            .int("reputation", self.reputation)
+            .strs("aliases", &self.aliases)
+            .objects("keys", self.keys.iter()
+                .map(|k| JsonObject::new().str("label", &k.label).str("public", &k.public_hex).bool("revoked", k.revoked))
+                .collect())
+            .objects("metadata", self.metadata.iter()
+                .map(|(k, v)| JsonObject::new().str("key", k).str("value", v))
+                .collect())
+    }
+
+    fn to_store(&self) -> StoreValue {
+        let text = |s: &str| StoreValue::Text(s.to_string());
+        let mut m = HashMap::new();
+        m.insert("display_name".to_string(), text(&self.display_name));
+        m.insert("reputation".to_string(), StoreValue::Int(self.reputation));
+        m.insert("created_at".to_string(), StoreValue::Int(self.created_at as i64));
+        m.insert("aliases".to_string(), StoreValue::List(self.aliases.iter().map(|a| text(a)).collect()));
+        m.insert("keys".to_string(), StoreValue::List(self.keys.iter().map(|k| {
+            let mut km = HashMap::new();
+            km.insert("label".to_string(), text(&k.label));
+            km.insert("public".to_string(), text(&k.public_hex));
+            km.insert("revoked".to_string(), StoreValue::Bool(k.revoked));
+            StoreValue::Map(km)
+        }).collect()));
+        m.insert("metadata".to_string(), StoreValue::Map(self.metadata.iter().map(|(k, v)| (k.clone(), text(v))).collect()));
+        StoreValue::Map(m)
+    }
+
+    fn from_store(id: AccountId, value: &StoreValue) -> Option<Self> {
+        let StoreValue::Map(m) = value else { return None };
+        let text = |v: Option<&StoreValue>| match v { Some(StoreValue::Text(t)) => Some(t.clone()), _ => None };
+        let list = |k: &str| match m.get(k) { Some(StoreValue::List(l)) => l.clone(), _ => Vec::new() };
+        let keys = list("keys").iter().filter_map(|k| match k {
+            StoreValue::Map(km) => Some(AccountKey {
+                label: text(km.get("label"))?,
+                public_hex: text(km.get("public"))?,
+                revoked: matches!(km.get("revoked"), Some(StoreValue::Bool(true))),
+            }),
+            _ => None,
+        }).collect();
+        let metadata = match m.get("metadata") {
+            Some(StoreValue::Map(md)) => md.iter().filter_map(|(k, v)| Some((k.clone(), text(Some(v))?))).collect(),
+            _ => BTreeMap::new(),
+        };
+        Some(Self {
+            id,
+            display_name: text(m.get("display_name"))?,
+            keys,
+            metadata,
+            reputation: match m.get("reputation") { Some(StoreValue::Int(r)) => *r, _ => 0 },
+            aliases: list("aliases").iter().filter_map(|a| text(Some(a))).collect(),
+            created_at: match m.get("created_at") { Some(StoreValue::Int(t)) => *t as u64, _ => 0 },
+        })
+    }
+}
+
+/// 계정별 잔액을 내주는 원장 — 각 모듈이 구현 (옛 문자열 키 그대로)
+pub trait AccountLedger {
+    fn ledger_name(&self) -> &str;
+    /// 이름 하나의 보유 자산 (자산, 수량)
+    fn holdings_of(&self, name: &str) -> Vec<(String, u64)>;
+}
+
+pub type SharedAccounts = Rc<RefCell<AccountRegistry>>;
+
+/// 중앙 계정 저장소
+#[derive(Default)]
+pub struct AccountRegistry {
+    accounts: BTreeMap<AccountId, Account>,
+    aliases: HashMap<String, AccountId>,
+    store: Option<TritStore>,
+}
+
+impl AccountRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 저장소에서 적재 — 이후 바뀐 계정은 같은 저장소에 기록
+    pub fn open(store: TritStore) -> Self {
+        let mut reg = Self::new();
+        for key in store.keys() {
+            let Some(id) = key.strip_prefix(KEY_PREFIX).and_then(AccountId::parse) else { continue };
+            if let Some(acc) = store.peek(key).and_then(|v| Account::from_store(id.clone(), v)) {
+                for alias in &acc.aliases {
+                    reg.aliases.insert(alias.clone(), id.clone());
+                }
+                reg.accounts.insert(id, acc);
+            }
+        }
+        reg.store = Some(store);
+        reg
+    }
+
+    pub fn store(&self) -> Option<&TritStore> {
+        self.store.as_ref()
+    }
+
+    /// 계정 하나를 저장소에 반영 (없어졌으면 삭제)
+    fn persist(&mut self, id: &AccountId) {
+        let Some(store) = self.store.as_mut() else { return };
+        let key = format!("{}{}", KEY_PREFIX, id);
+        match self.accounts.get(id) {
+            Some(acc) => {
+                store.set(&key, acc.to_store());
+                store.set_trit_state(&key, acc.trit());
+            }
+            None => {
+                store.delete(&key);
+            }
+        }
+    }
+
+    pub fn shared(self) -> SharedAccounts {
+        Rc::new(RefCell::new(self))
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.values()
+    }
+
+    fn insert(&mut self, id: AccountId, display_name: &str, keys: Vec<AccountKey>) -> Result<AccountId, String> {
+        if self.accounts.contains_key(&id) {
+            return Err(format!("이미 있는 계정: {}", id));
+        }
+        self.accounts.insert(id.clone(), Account {
+            id: id.clone(), display_name: display_name.into(), keys, metadata: BTreeMap::new(),
+            reputation: 0, aliases: Vec::new(), created_at: now_ms(),
+        });
+        self.persist(&id);
+        Ok(id)
+    }
+
+    /// 공개키로 새 계정
+    pub fn create(&mut self, display_name: &str, public_key: &[u8]) -> Result<AccountId, String> {
+        if public_key.is_empty() {
+            return Err("공개키 필요".into());
+        }
+        let key = AccountKey { label: "primary".into(), public_hex: crypto::to_hex(public_key), revoked: false };
+        self.insert(AccountId::from_key(public_key), display_name, vec![key])
+    }
+
+    /// 이전 심(shim) — 옛 이름의 계정 (없으면 만든다)
+    pub fn legacy(&mut self, name: &str) -> AccountId {
+        if let Some(id) = self.aliases.get(name) {
+            return id.clone();
+        }
+        let id = AccountId::legacy(name);
+        if !self.accounts.contains_key(&id) {
+            let _ = self.insert(id.clone(), name, Vec::new());
+        }
+        self.attach(&id, name);
+        id
+    }
+
+    /// 토큰 주체 → 계정 주소 — 등록된 주소는 그대로, 그 밖의 이름은 legacy 계정
+    pub fn subject(&mut self, name: &str) -> AccountId {
+        match AccountId::parse(name) {
+            Some(id) if self.accounts.contains_key(&id) => id,
+            _ => self.legacy(name),
+        }
+    }
+
+    fn attach(&mut self, id: &AccountId, alias: &str) {
+        if let Some(acc) = self.accounts.get_mut(id) {
+            if !acc.aliases.iter().any(|a| a == alias) {
+                acc.aliases.push(alias.into());
+            }
+        }
+        self.aliases.insert(alias.into(), id.clone());
+        self.persist(id);
+    }
+
+    /// 옛 이름을 계정에 연결 — 키 없는 legacy 계정이 쓰던 이름이면 흡수
+    pub fn link_alias(&mut self, id: &AccountId, alias: &str) -> Result<(), String> {
+        if !self.accounts.contains_key(id) {
+            return Err(format!("계정 없음: {}", id));
+        }
+        if let Some(owner) = self.aliases.get(alias).cloned() {
+            if owner == *id {
+                return Ok(());
+            }
+            let absorbable = self.accounts.get(&owner).is_some_and(|a| a.keys.is_empty() && owner == AccountId::legacy(alias));
+            if !absorbable {
+                return Err(format!("'{}'은 이미 {}의 이름", alias, owner));
+            }
+            if let Some(old) = self.accounts.remove(&owner) {
+                self.persist(&owner);
+                let acc = self.accounts.get_mut(id).unwrap();
+                acc.reputation += old.reputation;
+                for (k, v) in old.metadata {
+                    acc.metadata.entry(k).or_insert(v);
+                }
+                for a in old.aliases.iter().filter(|a| *a != alias) {
+                    self.aliases.insert(a.clone(), id.clone());
+                    self.accounts.get_mut(id).unwrap().aliases.push(a.clone());
+                }
+            }
+        }
+        self.attach(id, alias);
+        Ok(())
+    }
+
+    /// 주소 또는 별칭으로 찾기
+    pub fn resolve(&self, name: &str) -> Option<&Account> {
+        match AccountId::parse(name) {
+            Some(id) => self.accounts.get(&id),
+            None => self.aliases.get(name).and_then(|id| self.accounts.get(id)),
+        }
+    }
+
+    fn get_mut(&mut self, id: &AccountId) -> Result<&mut Account, String> {
+        self.accounts.get_mut(id).ok_or_else(|| format!("계정 없음: {}", id))
+    }
+
+    pub fn add_key(&mut self, id: &AccountId, label: &str, public_key: &[u8]) -> Result<(), String> {
+        let hex = crypto::to_hex(public_key);
+        let acc = self.get_mut(id)?;
+        if acc.keys.iter().any(|k| k.public_hex == hex || k.label == label) {
+            return Err(format!("이미 있는 키: {}", label));
+        }
+        acc.keys.push(AccountKey { label: label.into(), public_hex: hex, revoked: false });
+        self.persist(id);
+        Ok(())
+    }
+
+    /// 키 폐기 — 마지막 활성 키는 폐기할 수 없다
+    pub fn revoke_key(&mut self, id: &AccountId, label: &str) -> Result<(), String> {
+        let acc = self.get_mut(id)?;
+        if acc.active_keys().count() <= 1 {
+            return Err("마지막 활성 키".into());
+        }
+        let key = acc.keys.iter_mut().find(|k| k.label == label && !k.revoked).ok_or_else(|| format!("키 없음: {}", label))?;
+        key.revoked = true;
+        self.persist(id);
+        Ok(())
+    }
+
+    pub fn set_display_name(&mut self, id: &AccountId, name: &str) -> Result<(), String> {
+        self.get_mut(id)?.display_name = name.into();
+        self.persist(id);
+        Ok(())
+    }
+
+    pub fn set_meta(&mut self, id: &AccountId, key: &str, value: &str) -> Result<(), String> {
+        self.get_mut(id)?.metadata.insert(key.into(), value.into());
+        self.persist(id);
+        Ok(())
+    }
+
+    /// 평판 조정 → 새 트릿
+    pub fn adjust_reputation(&mut self, id: &AccountId, delta: i64) -> Result<i8, String> {
+        let acc = self.get_mut(id)?;
+        acc.reputation = acc.reputation.saturating_add(delta);
+        let trit = acc.trit();
+        self.persist(id);
+        Ok(trit)
+    }
+
+    /// 모든 원장에서 계정의 모든 이름으로 합산 → (원장, 자산, 수량)
+    pub fn holdings(&self, id: &AccountId, ledgers: &[&dyn AccountLedger]) -> Vec<(String, String, u64)> {
+        let Some(acc) = self.accounts.get(id) else { return Vec::new() };
+        let mut out: BTreeMap<(String, String), u64> = BTreeMap::new();
+        for ledger in ledgers {
+            for name in acc.names() {
+                for (asset, amount) in ledger.holdings_of(name) {
+                    *out.entry((ledger.ledger_name().to_string(), asset)).or_insert(0) += amount;
+                }
+            }
+        }
+        out.into_iter().filter(|(_, n)| *n > 0).map(|((l, a), n)| (l, a, n)).collect()
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_legacy_and_alias_linking() {
+        let mut reg = AccountRegistry::new();
+        let legacy = reg.legacy("alice");
+        assert_eq!(reg.legacy("alice"), legacy);
+        assert_eq!(legacy, AccountId::legacy("alice"));
+        reg.adjust_reputation(&legacy, 4).unwrap();
+
+        let real = reg.create("Alice Kim", b"alice-public-key").unwrap();
+        assert!(real.starts_with(ADDRESS_PREFIX) && real.len() == 45);
+        assert!(reg.create("dup", b"alice-public-key").is_err());
+        // 키 없는 legacy 계정은 흡수 (평판 포함)
+        reg.link_alias(&real, "alice").unwrap();
+        assert_eq!(reg.resolve("alice").unwrap().id, real);
+        assert_eq!(reg.resolve(&real).unwrap().reputation, 4);
+        assert!(reg.resolve(&legacy.to_string()).is_none());
+        assert_eq!(reg.legacy("alice"), real);
+
+        let bob = reg.create("Bob", b"bob-key").unwrap();
+        assert!(reg.link_alias(&bob, "alice").is_err());
+        assert!(reg.resolve("nobody").is_none());
+    }
+
+    #[test]
+    fn test_keys_reputation_and_persistence() {
+        let mut reg = AccountRegistry::open(TritStore::new());
+        let id = reg.create("운영자", b"k1").unwrap();
+        reg.add_key(&id, "laptop", b"k2").unwrap();
+        assert!(reg.add_key(&id, "laptop", b"k3").is_err());
+        reg.revoke_key(&id, "primary").unwrap();
+        assert!(reg.revoke_key(&id, "laptop").is_err());
+        let active: Vec<_> = reg.resolve(&id.to_string()).unwrap().active_keys().map(|k| k.label.as_str()).collect();
+        assert_eq!(active, vec!["laptop"]);
+        reg.set_meta(&id, "email", "ops@crowny.io").unwrap();
+        assert_eq!(reg.adjust_reputation(&id, 12).unwrap(), 1);
+        assert_eq!(reg.adjust_reputation(&id, -30).unwrap(), -1);
+        reg.link_alias(&id, "ops").unwrap();
+        // 흡수된 legacy 계정은 저장소에서도 사라진다
+        let legacy = reg.legacy("old-ops");
+        reg.link_alias(&id, "old-ops").unwrap();
+        assert_eq!(reg.subject("old-ops"), id);
+        assert_eq!(reg.subject(&id), id);
+
+        let store = reg.store().unwrap();
+        assert!(!store.exists(&format!("account.{}", legacy)));
+        assert_eq!(store.get_trit_state(&format!("account.{}", id)), Some(-1));
+        let loaded = AccountRegistry::open(TritStore::replay(&store.wal_entries()));
+        assert_eq!(loaded.resolve(&id.to_string()), reg.resolve(&id.to_string()));
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.resolve("ops").unwrap().metadata["email"], "ops@crowny.io");
+    }
+
+    #[test]
+    fn test_holdings_across_modules() {
+        use crate::dex::CrownyDEX;
+        use crate::nft::{CrownyNFT, NFTMetadata, NFTRarity};
+        use crate::token::TokenEngine;
+
+        let mut reg = AccountRegistry::new();
+        let id = reg.create("Alice", b"alice-key").unwrap();
+        reg.link_alias(&id, "alice").unwrap();
+
+        let mut engine = TokenEngine::new("Crowny", "CRWN", 1_000, "alice");
+        engine.transfer("alice", &id, 100);
+        let mut dex = CrownyDEX::new();
+        dex.mint("alice", "ETH", 7);
+        let mut market = CrownyNFT::new();
+        let col = market.create_collection("T", "T", "alice", "d", None, 0);
+        market.mint(&col, &id, NFTMetadata::new("A", "d", "i"), NFTRarity::Common).unwrap();
+
+        let h = reg.holdings(&id, &[&engine, &dex, &market]);
+        let find = |l: &str, a: &str| h.iter().find(|(x, y, _)| x == l && y == a).map(|t| t.2);
+        // 주소와 옛 이름 양쪽 잔액 합
+        assert_eq!(find("token", "CRWN"), Some(1_000));
+        assert_eq!(find("dex", "ETH"), Some(7));
+        assert_eq!(find("nft", "nft"), Some(1));
+        assert!(reg.resolve(&id).unwrap().to_json().build().contains(r#""aliases":["alice"]"#));
+    }
+}
@@ -0,0 +1,444 @@
+// ═══════════════════════════════════════════════════════════════
+// 관리자 상태 덮어쓰기 — 보류(O)로 멈춘 작업 · 전송을 P 또는 T로 강제
+//
+//   대상:  task (CAR 작업 기록) · transfer (브릿지 전송)
+//   권한:  admin.override 범위를 이름으로 가진 서명 토큰만 — "*" · "admin.*" 불가
+//          설정 · 웹훅 관리자 API도 같은 확인 (admin.config · admin.webhooks)
+//          운영자 = 토큰 subject
+//   기록:  운영자 · 사유 · 이전/이후 상태를 해시 체인 감사 로그에 추가
+//          hash = SHA-256(prev_hash ‖ 항목) → 중간 수정 · 삭제 · 순서 변경이 드러난다
+//   통지:  state.overridden 웹훅
+//   원격:  `override apply`가 실행 중인 서버의 POST /admin/override를 호출
+//
+//   파일: 한 줄에 한 항목 (TSV, 추가 전용)
+//         seq  at  operator  kind  target  before  after  justification  prev_hash  hash
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::capability::{CapabilityToken, TokenError, TokenSigner, TOKEN_HEADER};
+use crate::car::{CrownyRuntime, TritState};
+use crate::cron::{escape, unescape};
+use crate::crossbridge::CrownyBridge;
+use crate::crypto::{sha256, to_hex};
+use crate::integrations::{http_request, EventKind, SharedWebhooks};
+use crate::output::{trit_symbol, JsonObject};
+use crate::webserver::form_body;
+
+/// 덮어쓰기에 필요한 능력 범위
+pub const OVERRIDE_SCOPE: &str = "admin.override";
+/// 설정 조회 · 재적재 범위
+pub const CONFIG_SCOPE: &str = "admin.config";
+/// 웹훅 구독 · 데드레터 재전송 범위
+pub const WEBHOOK_SCOPE: &str = "admin.webhooks";
+/// 커널 능력 승인 요청 판정 범위
+pub const APPROVAL_SCOPE: &str = "admin.approvals";
+/// 첫 항목의 prev_hash
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// 덮어쓸 수 있는 대상 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Task,
+    Transfer,
+}
+
+impl TargetKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            TargetKind::Task => "task",
+            TargetKind::Transfer => "transfer",
+        }
+    }
+}
+
+impl std::str::FromStr for TargetKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "task" | "작업" => Ok(TargetKind::Task),
+            "transfer" | "전송" => Ok(TargetKind::Transfer),
+            _ => Err(format!("알 수 없는 대상 '{}' (가능: task, transfer)", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// 목표 트릿 파싱 — P/T만 (O로 되돌리는 덮어쓰기는 없음)
+pub fn parse_target_state(s: &str) -> Result<i8, String> {
+    match s {
+        "P" | "p" | "1" | "+1" => Ok(1),
+        "T" | "t" | "-1" => Ok(-1),
+        _ => Err(format!("목표 상태는 P 또는 T: {}", s)),
+    }
+}
+
+/// 덮어쓰기 요청
+#[derive(Debug, Clone)]
+pub struct OverrideRequest {
+    pub kind: TargetKind,
+    pub target: String,
+    pub state: i8,
+    pub justification: String,
+}
+
+// ─────────────────────────────────────────────
+// 감사 로그
+// ─────────────────────────────────────────────
+
+/// 감사 항목 — 운영자 · 사유 · 이전/이후 트릿
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub at: u64,
+    pub operator: String,
+    pub kind: TargetKind,
+    pub target: String,
+    pub before: i8,
+    pub after: i8,
+    pub justification: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// prev_hash를 포함한 항목 해시
+    pub fn digest(&self) -> String {
+        let body = format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.prev_hash, self.seq, self.at, escape(&self.operator), self.kind,
+            escape(&self.target), self.before, self.after, escape(&self.justification));
+        to_hex(&sha256(body.as_bytes()))
+    }
+
+    fn encode(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.seq, self.at, escape(&self.operator), self.kind, escape(&self.target),
+            self.before, self.after, escape(&self.justification), self.prev_hash, self.hash)
+    }
+
+    fn decode(line: &str) -> Result<Self, String> {
+        let f: Vec<&str> = line.split('\t').collect();
+        let [seq, at, operator, kind, target, before, after, justification, prev_hash, hash] = f[..] else {
+            return Err(format!("필드 수 {} (10 필요)", f.len()));
+        };
+        let num = |s: &str| s.parse::<u64>().map_err(|_| format!("정수가 아님: {}", s));
+        let trit = |s: &str| s.parse::<i8>().ok().filter(|t| (-1..=1).contains(t))
+            .ok_or_else(|| format!("트릿이 아님: {}", s));
+        Ok(Self {
+            seq: num(seq)?,
+            at: num(at)?,
+            operator: unescape(operator),
+            kind: kind.parse()?,
+            target: unescape(target),
+            before: trit(before)?,
+            after: trit(after)?,
+            justification: unescape(justification),
+            prev_hash: prev_hash.to_string(),
+            hash: hash.to_string(),
+        })
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .int("seq", self.seq as i64)
+            .int("at", self.at as i64)
+            .str("operator", &self.operator)
+            .str("kind", self.kind.name())
+            .str("target", &self.target)
+            .trit("before", self.before)
+            .trit("after", self.after)
+            .str("justification", &self.justification)
+            .str("prev_hash", &self.prev_hash)
+            .str("hash", &self.hash)
+    }
+}
+
+impl std::fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} {} {} {}→{} by {} — {} [{}…]", self.seq, self.kind, self.target,
+            trit_symbol(self.before), trit_symbol(self.after), self.operator, self.justification, &self.hash[..12])
+    }
+}
+
+/// 해시 체인 감사 로그 — 파일이 있으면 항목마다 한 줄 추가
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedAudit = Rc<RefCell<AuditLog>>;
+
+pub fn shared(log: AuditLog) -> SharedAudit {
+    Rc::new(RefCell::new(log))
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 파일에서 복원 (없으면 빈 로그) — 체인 검증은 verify()
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = Self::new();
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                for (no, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+                    let entry = AuditEntry::decode(line)
+                        .map_err(|e| format!("{}:{}: {}", path.display(), no + 1, e))?;
+                    log.entries.push(entry);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        }
+        log.path = Some(path);
+        Ok(log)
+    }
+
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// 마지막 항목 해시 (비었으면 GENESIS_HASH)
+    pub fn head(&self) -> &str {
+        self.entries.last().map_or(GENESIS_HASH, |e| e.hash.as_str())
+    }
+
+    /// 체인 검증 — 항목 수, 또는 처음 어긋난 항목
+    pub fn verify(&self) -> Result<usize, String> {
+        let mut prev = GENESIS_HASH;
+        for (i, e) in self.entries.iter().enumerate() {
+            if e.seq != i as u64 + 1 {
+                return Err(format!("감사 항목 #{}: 순번 불일치 (기대 {})", e.seq, i + 1));
+            }
+            if e.prev_hash != prev {
+                return Err(format!("감사 항목 #{}: 이전 해시 불일치 (체인 끊김)", e.seq));
+            }
+            if e.digest() != e.hash {
+                return Err(format!("감사 항목 #{}: 해시 불일치 (내용 변조)", e.seq));
+            }
+            prev = &e.hash;
+        }
+        Ok(self.entries.len())
+    }
+
+    /// 항목 추가 — 변조된 로그에는 이어 쓰지 않는다
+    pub fn append(&mut self, operator: &str, req: &OverrideRequest, before: i8, at: u64) -> Result<&AuditEntry, String> {
+        self.verify()?;
+        let mut entry = AuditEntry {
+            seq: self.entries.len() as u64 + 1,
+            at,
+            operator: operator.to_string(),
+            kind: req.kind,
+            target: req.target.clone(),
+            before,
+            after: req.state,
+            justification: req.justification.clone(),
+            prev_hash: self.head().to_string(),
+            hash: String::new(),
+        };
+        entry.hash = entry.digest();
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+            }
+            std::fs::OpenOptions::new().create(true).append(true).open(path)
+                .and_then(|mut f| writeln!(f, "{}", entry.encode()))
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        self.entries.push(entry);
+        Ok(self.entries.last().unwrap())
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let verified = self.verify();
+        JsonObject::new()
+            .trit("state", if verified.is_ok() { 1 } else { -1 })
+            .str("head", self.head())
+            .str("error", verified.err().as_deref().unwrap_or(""))
+            .objects("entries", self.entries.iter().map(AuditEntry::to_json).collect())
+    }
+}
+
+// ─────────────────────────────────────────────
+// 권한 · 실행
+// ─────────────────────────────────────────────
+
+/// 상승 권한 확인 — 서명 · 만료에 더해 admin.override가 토큰에 그대로 적혀 있어야 한다
+pub fn authorize(signer: &TokenSigner, header: Option<&str>, now: u64) -> Result<CapabilityToken, TokenError> {
+    authorize_scope(signer, header, OVERRIDE_SCOPE, now)
+}
+
+/// 관리자 범위 하나를 와일드카드 없이 요구
+pub fn authorize_scope(signer: &TokenSigner, header: Option<&str>, scope: &str, now: u64) -> Result<CapabilityToken, TokenError> {
+    let token = signer.verify_at(header, scope, now)?;
+    if !token.scopes.iter().any(|s| s == scope) {
+        return Err(TokenError::ScopeDenied(scope.to_string()));
+    }
+    Ok(token)
+}
+
+/// 덮어쓰기 실행 — 사유 필수, 보류(O)인 대상만, 성공하면 감사 기록 후 웹훅
+pub fn apply(
+    req: &OverrideRequest,
+    operator: &str,
+    car: &mut CrownyRuntime,
+    bridge: &RefCell<CrownyBridge>,
+    audit: &mut AuditLog,
+    hooks: Option<&SharedWebhooks>,
+    now: u64,
+) -> Result<AuditEntry, String> {
+    if req.justification.trim().is_empty() {
+        return Err("덮어쓰기 사유가 비어 있음".into());
+    }
+    if req.state == 0 {
+        return Err("목표 상태는 P 또는 T".into());
+    }
+    // 체인이 깨졌으면 상태를 바꾸기 전에 거부
+    audit.verify()?;
+
+    let before = match req.kind {
+        TargetKind::Task => {
+            let id = req.target.parse::<u64>().map_err(|_| format!("작업 ID는 정수: {}", req.target))?;
+            car.override_task(id, TritState::from_i8(req.state))? as i8
+        }
+        TargetKind::Transfer => bridge.borrow_mut().force_settle(&req.target, req.state)?,
+    };
+    let entry = audit.append(operator, req, before, now)?.clone();
+
+    if let Some(hooks) = hooks {
+        hooks.borrow_mut().emit(EventKind::StateOverridden, JsonObject::new()
+            .int("seq", entry.seq as i64)
+            .str("operator", &entry.operator)
+            .str("kind", entry.kind.name())
+            .str("target", &entry.target)
+            .trit("before", entry.before)
+            .trit("after", entry.after)
+            .str("justification", &entry.justification)
+            .str("hash", &entry.hash));
+    }
+    Ok(entry)
+}
+
+/// 실행 중인 서버에 덮어쓰기 요청 — 성공하면 서버가 기록한 감사 항목 JSON
+pub fn submit_remote(base_url: &str, token: &str, req: &OverrideRequest, timeout: Duration) -> Result<String, String> {
+    let body = form_body(&[
+        ("kind", req.kind.name()),
+        ("id", &req.target),
+        ("state", trit_symbol(req.state)),
+        ("reason", &req.justification),
+    ]);
+    let headers = [
+        (TOKEN_HEADER.to_string(), token.to_string()),
+        ("Content-Type".to_string(), "application/x-www-form-urlencoded".to_string()),
+    ];
+    let url = format!("{}/admin/override", base_url.trim_end_matches('/'));
+    let reply = http_request("POST", &url, &headers, &body, timeout)?;
+    if reply.status != 200 {
+        return Err(format!("HTTP {}: {}", reply.status, reply.body.chars().take(200).collect::<String>()));
+    }
+    Ok(reply.body)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::car::{AppTask, ResultData, TaskType};
+    use crate::crossbridge::{BridgeTxStatus, Chain};
+    use crate::integrations::{MemoryTransport, WebhookDispatcher};
+
+    fn stuck_task(car: &mut CrownyRuntime) -> u64 {
+        car.submit(AppTask::new(TaskType::Execute, "alice", "stuck"), |_| {
+            (TritState::Pending, ResultData::None)
+        }).task_id
+    }
+
+    fn request(kind: TargetKind, target: &str, state: i8) -> OverrideRequest {
+        OverrideRequest { kind, target: target.into(), state, justification: "릴레이 장애 복구".into() }
+    }
+
+    #[test]
+    fn test_override_requires_explicit_scope() {
+        let signer = TokenSigner::new("키");
+        let admin = signer.issue("ops", &[OVERRIDE_SCOPE], 60_000).encode();
+        let wildcard = signer.issue("root", &["*"], 60_000).encode();
+        let now = crate::cron::now_ms();
+        assert_eq!(authorize(&signer, Some(&admin), now).unwrap().subject, "ops");
+        assert_eq!(authorize(&signer, Some(&wildcard), now).unwrap_err().status(), 403);
+        assert_eq!(authorize(&signer, None, now).unwrap_err().status(), 401);
+    }
+
+    #[test]
+    fn test_override_task_and_transfer_with_audit_and_webhook() {
+        let mut car = CrownyRuntime::new();
+        let task = stuck_task(&mut car);
+        let mut bridge = CrownyBridge::new();
+        bridge.mint("bob", "CRWN", 1_000);
+        let idx = bridge.initiate_transfer("bob", "bob", "CRWN", 1_000, Chain::Crowny, Chain::Ethereum).unwrap();
+        bridge.transactions[idx].status = BridgeTxStatus::Relayed;
+        let tx = bridge.transactions[idx].id.clone();
+        let bridge = RefCell::new(bridge);
+
+        let transport = MemoryTransport::default();
+        let sent = transport.sent.clone();
+        let hooks = WebhookDispatcher::new(Box::new(transport)).shared();
+        hooks.borrow_mut().register("http://ops.local/", &[EventKind::StateOverridden], "ops-secret").unwrap();
+        let mut audit = AuditLog::new();
+
+        let e = apply(&request(TargetKind::Task, &task.to_string(), 1), "ops", &mut car, &bridge, &mut audit, Some(&hooks), 10).unwrap();
+        assert_eq!((e.before, e.after, e.prev_hash.as_str()), (0, 1, GENESIS_HASH));
+        assert_eq!(car.history().iter().last().unwrap().state, TritState::Success);
+
+        let e = apply(&request(TargetKind::Transfer, &tx, -1), "ops", &mut car, &bridge, &mut audit, Some(&hooks), 20).unwrap();
+        assert_eq!(e.prev_hash, audit.entries()[0].hash);
+        assert_eq!(bridge.borrow().transactions[idx].status, BridgeTxStatus::Refunded);
+        assert_eq!(bridge.borrow().balance("bob", "CRWN"), 1_000);
+
+        // 이미 정산된 대상 · 빈 사유는 거부, 감사 로그는 그대로
+        assert!(apply(&request(TargetKind::Task, &task.to_string(), -1), "ops", &mut car, &bridge, &mut audit, None, 30).is_err());
+        let mut empty = request(TargetKind::Transfer, &tx, 1);
+        empty.justification = " ".into();
+        assert!(apply(&empty, "ops", &mut car, &bridge, &mut audit, None, 30).is_err());
+        assert_eq!(audit.verify(), Ok(2));
+
+        hooks.borrow_mut().deliver_due(u64::MAX);
+        let sent = sent.borrow();
+        assert_eq!(sent.len(), 2);
+        assert!(sent[0].2.contains("state.overridden") && sent[0].2.contains("릴레이 장애 복구"));
+    }
+
+    #[test]
+    fn test_audit_chain_detects_tampering_in_file() {
+        let path = std::env::temp_dir().join(format!("crowny-audit-{}.tsv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut log = AuditLog::open(&path).unwrap();
+        log.append("ops", &request(TargetKind::Task, "1", 1), 0, 1).unwrap();
+        log.append("ops", &request(TargetKind::Transfer, "BRIDGE-000000", -1), 0, 2).unwrap();
+
+        let reopened = AuditLog::open(&path).unwrap();
+        assert_eq!(reopened.verify(), Ok(2));
+        assert_eq!(reopened.head(), log.head());
+
+        // 첫 항목의 이후 상태를 T로 바꿔치기
+        let text = std::fs::read_to_string(&path).unwrap().replacen("\t0\t1\t", "\t0\t-1\t", 1);
+        std::fs::write(&path, text).unwrap();
+        let mut tampered = AuditLog::open(&path).unwrap();
+        assert!(tampered.verify().unwrap_err().contains("#1"));
+        assert!(tampered.append("ops", &request(TargetKind::Task, "2", 1), 0, 3).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,279 @@
+// ═══════════════════════════════════════════════════════════════
+// 에어드롭 — 주소 목록에 토큰 · NFT 배포 (청크 단위 · 중단 후 재개)
+//
+//   목록: CSV ("address,amount", # 주석, 머리행 허용)
+//   청크: 블록 tx 한도 · 가스 한도 안에서 — 청크 하나가 원자 단위
+//   재개: TritStore "airdrop.<id>.*"에 커서 · 실패 목록 · 목록 지문 저장
+//         같은 id로 다시 열면 마지막으로 끝난 청크 다음부터
+//   배포기: 토큰 (TokenEngine 전송) · NFT (CrownyNFT 배치 민트)
+// ═══════════════════════════════════════════════════════════════
+
+use crate::crypto;
+use crate::nft::{CrownyNFT, NFTMetadata};
+use crate::output::JsonObject;
+use crate::params::{ParamRegistry, GAS_TRANSFER, MAX_BLOCK_TXS};
+use crate::token::{TokenEngine, TxState};
+use crate::trit_store::{StoreValue, TritStore};
+
+/// 수령자 한 줄
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipient {
+    pub address: String,
+    pub amount: u64,
+}
+
+/// CSV 목록 — 수량 생략 시 1 (NFT 한 개), 중복 주소는 오류
+pub fn parse_csv(text: &str) -> Result<Vec<Recipient>, String> {
+    let mut out: Vec<Recipient> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut cols = line.split(',').map(str::trim);
+        let address = cols.next().unwrap_or("");
+        let amount = cols.next().unwrap_or("1");
+        if n == 0 && address.eq_ignore_ascii_case("address") {
+            continue;
+        }
+        if address.is_empty() {
+            return Err(format!("{}행: 주소 없음", n + 1));
+        }
+        let amount: u64 = amount.parse().map_err(|_| format!("{}행: 잘못된 수량 '{}'", n + 1, amount))?;
+        if out.iter().any(|r| r.address == address) {
+            return Err(format!("{}행: 중복 주소 '{}'", n + 1, address));
+        }
+        out.push(Recipient { address: address.to_string(), amount });
+    }
+    Ok(out)
+}
+
+/// 청크 한도
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkLimits {
+    pub max_items: usize,
+    pub gas_limit: u64,
+    pub gas_per_item: u64,
+}
+
+impl Default for ChunkLimits {
+    fn default() -> Self {
+        Self { max_items: 100, gas_limit: 1_000_000, gas_per_item: 2_100 }
+    }
+}
+
+impl ChunkLimits {
+    /// 프로토콜 파라미터 (블록 tx 한도 · 전송 가스)에서
+    pub fn from_params(params: &ParamRegistry, gas_limit: u64) -> Self {
+        Self { max_items: params.get(MAX_BLOCK_TXS) as usize, gas_limit, gas_per_item: params.get(GAS_TRANSFER) }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        let by_gas = (self.gas_limit / self.gas_per_item.max(1)) as usize;
+        self.max_items.min(by_gas).max(1)
+    }
+}
+
+/// 청크 하나 결과
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkReport {
+    pub start: usize,
+    pub delivered: usize,
+    pub failed: usize,
+    pub remaining: usize,
+}
+
+/// 재개 가능한 에어드롭
+#[derive(Debug, Clone)]
+pub struct Airdrop {
+    pub id: String,
+    pub recipients: Vec<Recipient>,
+    pub chunk_size: usize,
+    pub cursor: usize,
+    pub failed: Vec<(String, String)>,
+}
+
+impl Airdrop {
+    fn key(&self, field: &str) -> String {
+        format!("airdrop.{}.{}", self.id, field)
+    }
+
+    /// 목록 지문 — 다른 목록으로 재개하는 실수를 막는다
+    pub fn digest(recipients: &[Recipient]) -> String {
+        let canon: String = recipients.iter().map(|r| format!("{},{}\n", r.address, r.amount)).collect();
+        crypto::to_hex(&crypto::sha256(canon.as_bytes()))
+    }
+
+    /// 새로 시작하거나, 저장된 진행 상황에서 재개
+    pub fn open(id: &str, recipients: Vec<Recipient>, limits: ChunkLimits, store: &mut TritStore) -> Result<Self, String> {
+        let mut airdrop = Self { id: id.to_string(), recipients, chunk_size: limits.chunk_size(), cursor: 0, failed: Vec::new() };
+        let digest = Self::digest(&airdrop.recipients);
+        match store.peek(&airdrop.key("digest")) {
+            Some(StoreValue::Text(saved)) if *saved != digest => {
+                return Err(format!("에어드롭 '{}'의 수령자 목록이 바뀜", id));
+            }
+            Some(_) => {
+                if let Some(StoreValue::Int(c)) = store.peek(&airdrop.key("cursor")) {
+                    airdrop.cursor = (*c as usize).min(airdrop.recipients.len());
+                }
+                if let Some(StoreValue::List(items)) = store.peek(&airdrop.key("failed")) {
+                    airdrop.failed = items.iter().filter_map(|v| match v {
+                        StoreValue::Text(t) => t.split_once('\t').map(|(a, e)| (a.to_string(), e.to_string())),
+                        _ => None,
+                    }).collect();
+                }
+            }
+            None => store.set(&airdrop.key("digest"), StoreValue::Text(digest)),
+        }
+        Ok(airdrop)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.cursor >= self.recipients.len()
+    }
+
+    /// 다음 청크 배포 — deliver는 청크의 항목별 결과를 돌려준다
+    /// 커서 · 실패 목록은 한 트랜잭션으로 저장
+    pub fn run_chunk(&mut self, store: &mut TritStore,
+                     mut deliver: impl FnMut(&[Recipient]) -> Vec<Result<(), String>>) -> Option<ChunkReport> {
+        if self.is_done() {
+            return None;
+        }
+        let start = self.cursor;
+        let end = (start + self.chunk_size).min(self.recipients.len());
+        let results = deliver(&self.recipients[start..end]);
+        let mut failed = 0;
+        for (i, r) in self.recipients[start..end].iter().enumerate() {
+            let outcome = results.get(i).cloned().unwrap_or_else(|| Err("결과 없음".into()));
+            if let Err(e) = outcome {
+                self.failed.push((r.address.clone(), e));
+                failed += 1;
+            }
+        }
+        self.cursor = end;
+
+        store.begin();
+        store.set(&self.key("cursor"), StoreValue::Int(end as i64));
+        store.set(&self.key("failed"), StoreValue::List(self.failed.iter()
+            .map(|(a, e)| StoreValue::Text(format!("{}\t{}", a, e)))
+            .collect()));
+        store.commit();
+        Some(ChunkReport { start, delivered: end - start - failed, failed, remaining: self.recipients.len() - end })
+    }
+
+    /// 남은 청크 — 최대 max_chunks개
+    pub fn run(&mut self, store: &mut TritStore, max_chunks: usize,
+               mut deliver: impl FnMut(&[Recipient]) -> Vec<Result<(), String>>) -> Vec<ChunkReport> {
+        std::iter::from_fn(|| self.run_chunk(store, &mut deliver)).take(max_chunks).collect()
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.airdrop")
+            .trit("state", if !self.is_done() { 0 } else if self.failed.is_empty() { 1 } else { -1 })
+            .str("id", &self.id)
+            .int("total", self.recipients.len() as i64)
+            .int("cursor", self.cursor as i64)
+            .int("chunk_size", self.chunk_size as i64)
+            .objects("failed", self.failed.iter()
+                .map(|(a, e)| JsonObject::new().str("address", a).str("error", e))
+                .collect())
+    }
+}
+
+/// 토큰 배포 — 청크 총액(수수료 포함)을 먼저 확인해 청크 단위로 전부 아니면 전무
+pub fn deliver_tokens(engine: &mut TokenEngine, from: &str, chunk: &[Recipient]) -> Vec<Result<(), String>> {
+    let need: u64 = chunk.iter().map(|r| r.amount + r.amount / 1000).sum();
+    let available = engine.wallets.get(from).map(|w| w.available()).unwrap_or(0);
+    if available < need {
+        let e = format!("배포자 잔액 부족: {} < {}", available, need);
+        return chunk.iter().map(|_| Err(e.clone())).collect();
+    }
+    chunk.iter().map(|r| match engine.transfer(from, &r.address, r.amount).state {
+        TxState::Confirmed => Ok(()),
+        other => Err(format!("전송 거부: {}", other)),
+    }).collect()
+}
+
+/// NFT 배포 — 수령자마다 amount개, 청크 전체를 배치 민트 (상태 루트 1회)
+pub fn deliver_nfts(market: &mut CrownyNFT, collection_id: &str, template: &NFTMetadata, chunk: &[Recipient]) -> Vec<Result<(), String>> {
+    let items: Vec<(String, NFTMetadata)> = chunk.iter()
+        .flat_map(|r| (0..r.amount).map(move |i| (r, i)))
+        .map(|(r, i)| {
+            let mut meta = template.clone();
+            meta.name = format!("{} → {} #{}", template.name, r.address, i + 1);
+            (r.address.clone(), meta)
+        })
+        .collect();
+    match market.mint_batch_to(collection_id, items) {
+        Ok(_) => chunk.iter().map(|_| Ok(())).collect(),
+        Err(e) => chunk.iter().map(|_| Err(e.clone())).collect(),
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_and_chunk_limits() {
+        let list = parse_csv("address,amount\n# 초기 후원자\nalice, 100\nbob,5\n\ncarol\n").unwrap();
+        assert_eq!(list.iter().map(|r| (r.address.as_str(), r.amount)).collect::<Vec<_>>(),
+            vec![("alice", 100), ("bob", 5), ("carol", 1)]);
+        assert!(parse_csv("alice,1\nalice,2").unwrap_err().contains("중복"));
+        assert!(parse_csv("alice,many").unwrap_err().starts_with("1행"));
+
+        let limits = ChunkLimits { max_items: 50, gas_limit: 21_000, gas_per_item: 2_100 };
+        assert_eq!(limits.chunk_size(), 10);
+        assert_eq!(ChunkLimits::from_params(&ParamRegistry::new(), 1_000_000).chunk_size(), 100);
+    }
+
+    #[test]
+    fn test_token_airdrop_resumes_after_interruption() {
+        let recipients: Vec<Recipient> = (0..25).map(|i| Recipient { address: format!("u{:02}", i), amount: 1_000 }).collect();
+        let mut engine = TokenEngine::new("Crowny", "CRWN", 1_000_000, "treasury");
+        let mut store = TritStore::new();
+        let limits = ChunkLimits { max_items: 10, ..ChunkLimits::default() };
+
+        let mut first = Airdrop::open("genesis", recipients.clone(), limits, &mut store).unwrap();
+        let r = first.run_chunk(&mut store, |c| deliver_tokens(&mut engine, "treasury", c)).unwrap();
+        assert_eq!((r.delivered, r.remaining), (10, 15));
+        drop(first); // 중단
+
+        // WAL로 복구한 저장소에서 재개
+        let mut store = TritStore::replay(&store.wal_entries());
+        let mut resumed = Airdrop::open("genesis", recipients.clone(), limits, &mut store).unwrap();
+        assert_eq!(resumed.cursor, 10);
+        let reports = resumed.run(&mut store, usize::MAX, |c| deliver_tokens(&mut engine, "treasury", c));
+        assert_eq!(reports.iter().map(|r| r.delivered).collect::<Vec<_>>(), vec![10, 5]);
+        assert!(resumed.is_done() && resumed.failed.is_empty());
+        assert!(recipients.iter().all(|r| engine.balance_of(&r.address) == 1_000));
+        assert!(resumed.run_chunk(&mut store, |_| unreachable!()).is_none());
+
+        let mut changed = recipients.clone();
+        changed[0].amount = 9;
+        assert!(Airdrop::open("genesis", changed, limits, &mut store).is_err());
+    }
+
+    #[test]
+    fn test_nft_airdrop_chunks_and_failures() {
+        let mut market = CrownyNFT::new();
+        let col = market.create_collection("Drop", "DRP", "alice", "d", Some(4), 0);
+        let recipients = parse_csv("bob,2\ncarol\ndave\neve").unwrap();
+        let mut store = TritStore::new();
+        let limits = ChunkLimits { max_items: 2, ..ChunkLimits::default() };
+        let template = NFTMetadata::new("Genesis", "d", "i");
+
+        let mut airdrop = Airdrop::open("nft", recipients, limits, &mut store).unwrap();
+        let reports = airdrop.run(&mut store, usize::MAX, |c| deliver_nfts(&mut market, &col, &template, c));
+        // 성공한 청크마다 상태 루트 1회 · 발행량 4 → 두 번째 청크(2개 더)는 통째로 실패
+        assert_eq!(market.root_updates, 1);
+        assert_eq!(reports.iter().map(|r| (r.delivered, r.failed)).collect::<Vec<_>>(), vec![(2, 0), (0, 2)]);
+        assert_eq!(market.nfts_by_owner("bob").len(), 2);
+        assert_eq!(airdrop.failed.iter().map(|(a, _)| a.as_str()).collect::<Vec<_>>(), vec!["dave", "eve"]);
+        assert!(airdrop.to_json().build().contains(r#""state":"T""#));
+        let reopened = Airdrop::open("nft", airdrop.recipients.clone(), limits, &mut store).unwrap();
+        assert_eq!((reopened.cursor, reopened.failed.len()), (4, 2));
+    }
+}
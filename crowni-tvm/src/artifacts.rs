@@ -0,0 +1,318 @@
+// ═══════════════════════════════════════════════════════════════
+// 아티팩트 저장소 — 내용 주소 기반 (해시 → wasm/바이트코드/소스맵)
+// CAR · 플랫폼 배포 · 컨트랙트 VM이 하나의 저장소를 공유한다
+//   같은 내용은 한 번만 저장 (중복 제거)
+//   참조 카운트 + 고정(pin) → gc()는 참조 0 · 비고정만 회수
+// 배포는 바이트 대신 해시를 참조한다
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::output::JsonObject;
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 모듈 간 공유 핸들
+pub type SharedArtifacts = Rc<RefCell<ArtifactStore>>;
+
+pub fn shared() -> SharedArtifacts {
+    Rc::new(RefCell::new(ArtifactStore::new()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactKind {
+    Source,
+    Wasm,
+    Bytecode,
+    SourceMap,
+    Contract,
+}
+
+impl ArtifactKind {
+    pub fn code(self) -> &'static str {
+        match self {
+            ArtifactKind::Source => "source",
+            ArtifactKind::Wasm => "wasm",
+            ArtifactKind::Bytecode => "bytecode",
+            ArtifactKind::SourceMap => "source_map",
+            ArtifactKind::Contract => "contract",
+        }
+    }
+}
+
+/// 내용 해시 — 종류가 다르면 같은 바이트라도 다른 해시
+pub fn content_hash(kind: ArtifactKind, bytes: &[u8]) -> String {
+    let mut tagged = Vec::with_capacity(bytes.len() + 16);
+    tagged.extend_from_slice(kind.code().as_bytes());
+    tagged.push(b':');
+    tagged.extend_from_slice(bytes);
+    crate::chain::trit_hash_bytes(&tagged)
+}
+
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub hash: String,
+    pub kind: ArtifactKind,
+    pub bytes: Vec<u8>,
+    /// 처음 저장할 때의 이름 (표시용)
+    pub label: String,
+    pub refs: usize,
+    pub pinned: bool,
+    pub created_at: u64,
+}
+
+impl Artifact {
+    pub fn short_hash(&self) -> String {
+        self.hash.chars().take(12).collect()
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("hash", &self.hash)
+            .str("kind", self.kind.code())
+            .str("label", &self.label)
+            .int("bytes", self.bytes.len() as i64)
+            .int("refs", self.refs as i64)
+            .bool("pinned", self.pinned)
+            .int("created_at", self.created_at as i64)
+    }
+}
+
+impl std::fmt::Display for Artifact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} \"{}\" {}B refs:{}{}", self.short_hash(), self.kind.code(),
+            self.label, self.bytes.len(), self.refs, if self.pinned { " 📌" } else { "" })
+    }
+}
+
+/// 컴파일 산출물 묶음 — 소스 하나에서 나온 해시들
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildArtifacts {
+    pub source: String,
+    pub wasm: String,
+    pub bytecode: String,
+    pub source_map: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GcReport {
+    pub removed: usize,
+    pub bytes_freed: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StoreStats {
+    pub artifacts: usize,
+    pub bytes: usize,
+    pub pinned: usize,
+    pub unreferenced: usize,
+    /// 중복 put 횟수 / 그로 인해 저장하지 않은 바이트
+    pub dedup_hits: u64,
+    pub bytes_saved: u64,
+}
+
+pub struct ArtifactStore {
+    artifacts: HashMap<String, Artifact>,
+    dedup_hits: u64,
+    bytes_saved: u64,
+}
+
+impl ArtifactStore {
+    pub fn new() -> Self {
+        Self { artifacts: HashMap::new(), dedup_hits: 0, bytes_saved: 0 }
+    }
+
+    /// 저장 (참조는 늘리지 않음) → 해시
+    /// 같은 해시에 다른 내용이면 충돌로 거부
+    pub fn put(&mut self, kind: ArtifactKind, bytes: &[u8], label: &str) -> Result<String, String> {
+        let hash = content_hash(kind, bytes);
+        if let Some(existing) = self.artifacts.get(&hash) {
+            if existing.bytes != bytes {
+                return Err(format!("해시 충돌: {}", hash));
+            }
+            self.dedup_hits += 1;
+            self.bytes_saved += bytes.len() as u64;
+            return Ok(hash);
+        }
+        self.artifacts.insert(hash.clone(), Artifact {
+            hash: hash.clone(), kind, bytes: bytes.to_vec(), label: label.into(),
+            refs: 0, pinned: false, created_at: now_ms(),
+        });
+        Ok(hash)
+    }
+
+    /// 저장 + 참조 1 증가
+    pub fn put_ref(&mut self, kind: ArtifactKind, bytes: &[u8], label: &str) -> Result<String, String> {
+        let hash = self.put(kind, bytes, label)?;
+        self.retain(&hash)?;
+        Ok(hash)
+    }
+
+    pub fn get(&self, hash: &str) -> Option<&Artifact> {
+        self.artifacts.get(hash)
+    }
+
+    fn entry(&mut self, hash: &str) -> Result<&mut Artifact, String> {
+        self.artifacts.get_mut(hash).ok_or_else(|| format!("아티팩트 없음: {}", hash))
+    }
+
+    /// 참조 증가 → 새 참조 수
+    pub fn retain(&mut self, hash: &str) -> Result<usize, String> {
+        let a = self.entry(hash)?;
+        a.refs += 1;
+        Ok(a.refs)
+    }
+
+    /// 참조 감소 → 새 참조 수 (0이어도 gc 전까지 남는다)
+    pub fn release(&mut self, hash: &str) -> Result<usize, String> {
+        let a = self.entry(hash)?;
+        if a.refs == 0 {
+            return Err(format!("참조 없음: {}", hash));
+        }
+        a.refs -= 1;
+        Ok(a.refs)
+    }
+
+    pub fn pin(&mut self, hash: &str) -> Result<(), String> {
+        self.entry(hash)?.pinned = true;
+        Ok(())
+    }
+
+    pub fn unpin(&mut self, hash: &str) -> Result<(), String> {
+        self.entry(hash)?.pinned = false;
+        Ok(())
+    }
+
+    /// 참조 0 · 비고정 아티팩트 회수
+    pub fn gc(&mut self) -> GcReport {
+        let mut report = GcReport::default();
+        self.artifacts.retain(|_, a| {
+            let keep = a.pinned || a.refs > 0;
+            if !keep {
+                report.removed += 1;
+                report.bytes_freed += a.bytes.len();
+            }
+            keep
+        });
+        report
+    }
+
+    /// 해시 순 목록
+    pub fn list(&self) -> Vec<&Artifact> {
+        let mut all: Vec<&Artifact> = self.artifacts.values().collect();
+        all.sort_by(|a, b| a.hash.cmp(&b.hash));
+        all
+    }
+
+    pub fn stats(&self) -> StoreStats {
+        StoreStats {
+            artifacts: self.artifacts.len(),
+            bytes: self.artifacts.values().map(|a| a.bytes.len()).sum(),
+            pinned: self.artifacts.values().filter(|a| a.pinned).count(),
+            unreferenced: self.artifacts.values().filter(|a| a.refs == 0).count(),
+            dedup_hits: self.dedup_hits,
+            bytes_saved: self.bytes_saved,
+        }
+    }
+
+    /// 소스 → wasm + 바이트코드 + 소스맵을 저장하고 각각 참조 1 증가
+    pub fn store_build(&mut self, label: &str, source: &str) -> Result<BuildArtifacts, String> {
+        let program = crate::assembler::assemble(source);
+        if program.is_empty() {
+            return Err("빈 프로그램".into());
+        }
+        let wasm = crate::compiler::compile_to_wasm(&program, "crowny")?;
+        let bytecode = crate::bytecode::serialize(&program);
+        let map = crate::assembler::source_map(source);
+        Ok(BuildArtifacts {
+            source: self.put_ref(ArtifactKind::Source, source.as_bytes(), label)?,
+            wasm: self.put_ref(ArtifactKind::Wasm, &wasm, label)?,
+            bytecode: self.put_ref(ArtifactKind::Bytecode, &bytecode, label)?,
+            source_map: self.put_ref(ArtifactKind::SourceMap, map.as_bytes(), label)?,
+        })
+    }
+
+    /// 통계 + 해시 순 목록
+    pub fn to_json(&self) -> JsonObject {
+        let s = self.stats();
+        JsonObject::schema("crowny.artifacts")
+            .int("bytes", s.bytes as i64)
+            .int("pinned", s.pinned as i64)
+            .int("unreferenced", s.unreferenced as i64)
+            .int("dedup_hits", s.dedup_hits as i64)
+            .int("bytes_saved", s.bytes_saved as i64)
+            .objects("artifacts", self.list().into_iter().map(Artifact::to_json).collect())
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_and_refcount() {
+        let mut store = ArtifactStore::new();
+        let a = store.put_ref(ArtifactKind::Wasm, b"\0asm-1", "a").unwrap();
+        let b = store.put_ref(ArtifactKind::Wasm, b"\0asm-1", "b").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(store.get(&a).unwrap().refs, 2);
+        assert_eq!(store.get(&a).unwrap().label, "a");
+        let stats = store.stats();
+        assert_eq!((stats.artifacts, stats.dedup_hits, stats.bytes_saved), (1, 1, 6));
+
+        // 같은 바이트, 다른 종류 → 별도 항목
+        let c = store.put(ArtifactKind::Bytecode, b"\0asm-1", "c").unwrap();
+        assert_ne!(a, c);
+
+        assert_eq!(store.release(&a).unwrap(), 1);
+        assert_eq!(store.release(&a).unwrap(), 0);
+        assert!(store.release(&a).is_err());
+        assert!(store.retain("0t없음").is_err());
+    }
+
+    #[test]
+    fn test_gc_respects_refs_and_pins() {
+        let mut store = ArtifactStore::new();
+        let kept = store.put_ref(ArtifactKind::Wasm, b"kept", "kept").unwrap();
+        let pinned = store.put(ArtifactKind::Wasm, b"pinned", "pinned").unwrap();
+        let loose = store.put(ArtifactKind::Wasm, b"loose", "loose").unwrap();
+        store.pin(&pinned).unwrap();
+
+        let report = store.gc();
+        assert_eq!(report, GcReport { removed: 1, bytes_freed: 5 });
+        assert!(store.get(&kept).is_some() && store.get(&pinned).is_some() && store.get(&loose).is_none());
+
+        store.unpin(&pinned).unwrap();
+        store.release(&kept).unwrap();
+        assert_eq!(store.gc().removed, 2);
+        assert_eq!(store.stats().artifacts, 0);
+    }
+
+    #[test]
+    fn test_same_program_built_twice_stored_once() {
+        let mut store = ArtifactStore::new();
+        let src = "넣어 6\n넣어 7\n곱해\n보여줘\n종료";
+        let first = store.store_build("앱", src).unwrap();
+        let second = store.store_build("앱-복제", src).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(store.stats().artifacts, 4);
+        assert_eq!(store.get(&first.wasm).unwrap().refs, 2);
+        assert_eq!(&store.get(&first.wasm).unwrap().bytes[0..4], b"\0asm");
+        assert_eq!(store.get(&first.source_map).unwrap().kind, ArtifactKind::SourceMap);
+
+        let release = |store: &mut ArtifactStore, b: &BuildArtifacts| {
+            for h in [&b.source, &b.wasm, &b.bytecode, &b.source_map] {
+                store.release(h).unwrap();
+            }
+        };
+        release(&mut store, &first);
+        assert_eq!(store.gc().removed, 0);
+        release(&mut store, &second);
+        assert_eq!(store.gc().removed, 4);
+        assert!(store.to_json().build().contains(r#""artifacts":[]"#));
+    }
+}
@@ -7,97 +7,562 @@
 ///!   더해           ; ADD
 ///!   보여줘         ; PRINT
 ///!   종료           ; HALT
+///!
+///! 문자열 리터럴: "안녕 세상", '따옴표 \' 포함', "줄\n바꿈", "\uD55C\u{AE00}"
+///! 이스케이프: \n \t \r \0 \\ \" \' \uXXXX (서로게이트 쌍 포함) \u{X..}
+///!
+///! 라벨: `이름:` 줄은 다음 명령어 위치 — 점프 · 조건점프 · 호출 · 반복 · 삼분기의 피연산자로 쓴다
+///!   팩토리얼:          ; n → n!
+///!     레지쓰기 0
+///!     ...
+///!     호출 팩토리얼      ; 재귀
+///!     돌려줘
+///!   삼분기 양수 영 음수  ; 트릿 P/O/T에 따라 세 갈래
+///! 스트리밍 어셈블러는 뒤 참조만 바로 푼다 — 앞 참조는 scan_labels로 한 번 먼저 읽고 with_labels로 넘긴다
 
 use std::collections::HashMap;
-use crate::opcode::{OpcodeAddr, build_opcodes, build_name_lookup};
+use crate::opcode::{OpcodeAddr, build_name_lookup};
+use crate::program_limits::{ProgramChecker, ProgramLimitError, ProgramLimits};
 use crate::trit::Trit;
 use crate::value::Value;
 use crate::vm::Instruction;
 
+/// 문자열 리터럴 본문의 이스케이프 해석
+pub fn unescape(body: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('0') => out.push('\0'),
+            Some('\\') => out.push('\\'),
+            Some('"') => out.push('"'),
+            Some('\'') => out.push('\''),
+            Some('u') => {
+                let code = if chars.peek() == Some(&'{') {
+                    chars.next();
+                    let hex: String = chars.by_ref().take_while(|&h| h != '}').collect();
+                    u32::from_str_radix(&hex, 16).map_err(|_| format!("잘못된 \\u{{{}}}", hex))?
+                } else {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let unit = u32::from_str_radix(&hex, 16)
+                        .ok().filter(|_| hex.len() == 4)
+                        .ok_or_else(|| format!("잘못된 \\u{}", hex))?;
+                    // 서로게이트 쌍: \uD83D\uDE00
+                    if (0xD800..0xDC00).contains(&unit) {
+                        let rest: String = chars.clone().take(6).collect();
+                        let low = rest.strip_prefix("\\u").and_then(|h| u32::from_str_radix(h, 16).ok())
+                            .filter(|l| (0xDC00..0xE000).contains(l))
+                            .ok_or_else(|| format!("짝 없는 상위 서로게이트 \\u{}", hex))?;
+                        chars.nth(5);
+                        0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+                    } else {
+                        unit
+                    }
+                };
+                out.push(char::from_u32(code).ok_or_else(|| format!("유효하지 않은 코드포인트 U+{:X}", code))?);
+            }
+            Some(other) => return Err(format!("알 수 없는 이스케이프 \\{}", other)),
+            None => return Err("문자열 끝의 \\".into()),
+        }
+    }
+    Ok(out)
+}
+
+/// 따옴표 밖의 첫 위치 (주석 `;` 찾기용)
+fn find_unquoted(line: &str, target: char) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == target => return Some(i),
+            None => {}
+        }
+    }
+    None
+}
+
+/// 피연산자 문자열을 토큰으로 — 쉼표/공백 구분, 따옴표 안은 한 토큰
+fn split_operands(s: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut cur = String::new();
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in s.chars() {
+        if let Some(q) = quote {
+            cur.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+                tokens.push(std::mem::take(&mut cur));
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' if cur.is_empty() => {
+                quote = Some(c);
+                cur.push(c);
+            }
+            ',' => {
+                if !cur.is_empty() { tokens.push(std::mem::take(&mut cur)); }
+            }
+            c if c.is_whitespace() => {
+                if !cur.is_empty() { tokens.push(std::mem::take(&mut cur)); }
+            }
+            c => cur.push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("닫히지 않은 문자열: {}", cur));
+    }
+    if !cur.is_empty() { tokens.push(cur); }
+    Ok(tokens)
+}
+
 /// 피연산자 파싱
-fn parse_operand(s: &str) -> Option<Value> {
+fn parse_operand(s: &str) -> Result<Option<Value>, String> {
     let s = s.trim();
-    if s.is_empty() { return None; }
+    if s.is_empty() { return Ok(None); }
 
     // 문자열 리터럴
-    if (s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')) {
-        return Some(Value::Str(s[1..s.len()-1].to_string()));
+    if s.len() >= 2 && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\''))) {
+        return unescape(&s[1..s.len()-1]).map(|body| Some(Value::Str(body)));
     }
 
     // 특수 리터럴
     match s {
-        "없다" | "없음" | "nil" | "NIL" => return Some(Value::Nil),
-        "참" | "true" | "TRUE" => return Some(Value::Bool(true)),
-        "거짓" | "false" | "FALSE" => return Some(Value::Bool(false)),
-        "P" => return Some(Value::Trit(Trit::P)),
-        "O" => return Some(Value::Trit(Trit::O)),
-        "T" => return Some(Value::Trit(Trit::T)),
+        "없다" | "없음" | "nil" | "NIL" => return Ok(Some(Value::Nil)),
+        "참" | "true" | "TRUE" => return Ok(Some(Value::Bool(true))),
+        "거짓" | "false" | "FALSE" => return Ok(Some(Value::Bool(false))),
+        "P" => return Ok(Some(Value::Trit(Trit::P))),
+        "O" => return Ok(Some(Value::Trit(Trit::O))),
+        "T" => return Ok(Some(Value::Trit(Trit::T))),
         _ => {}
     }
 
     // 실수 (소수점)
     if s.contains('.') {
         if let Ok(f) = s.parse::<f64>() {
-            return Some(Value::Float(f));
+            return Ok(Some(Value::Float(f)));
         }
     }
 
     // 정수
     if let Ok(n) = s.parse::<i64>() {
-        return Some(Value::Int(n));
+        return Ok(Some(Value::Int(n)));
     }
 
     // 기타 → 문자열
-    Some(Value::Str(s.to_string()))
+    Ok(Some(Value::Str(s.to_string())))
+}
+
+/// 라벨 정의 `이름:` (뒤 주석 허용) — 공백 · 따옴표 없는 한 단어
+fn label_def(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let line = find_unquoted(line, ';').map_or(line, |pos| &line[..pos]).trim();
+    let name = line.strip_suffix(':')?;
+    (!name.is_empty() && !name.contains(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ':'))).then_some(name)
+}
+
+/// 피연산자 라벨을 받는 분기 명령 — 점프 · 조건점프 · 호출 · 반복 · 삼분기
+fn is_branch(addr: &OpcodeAddr) -> bool {
+    addr.sector == 0 && matches!((addr.group, addr.command), (2, 0 | 1 | 2 | 4) | (4, 3))
+}
+
+/// 라벨 위치 기록 — 중복 정의는 오류
+fn define_label(labels: &mut HashMap<String, usize>, name: &str, at: usize) -> Result<(), String> {
+    match labels.insert(name.to_string(), at) {
+        Some(_) => Err(format!("라벨 중복: '{}'", name)),
+        None => Ok(()),
+    }
+}
+
+/// 분기 피연산자의 라벨을 명령어 주소로 — 정의되지 않은 라벨은 (명령어 인덱스, 메시지)
+fn resolve_labels(program: &mut [Instruction], labels: &HashMap<String, usize>) -> Vec<(usize, String)> {
+    let mut errors = Vec::new();
+    for (i, inst) in program.iter_mut().enumerate().filter(|(_, inst)| is_branch(&inst.addr)) {
+        for operand in inst.operands.iter_mut() {
+            if let Value::Str(name) = operand {
+                match labels.get(name.as_str()) {
+                    Some(&at) => *operand = Value::Addr(at),
+                    None => errors.push((i, format!("정의되지 않은 라벨: '{}'", name))),
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// 한 줄 어셈블 — 빈 줄·주석은 Ok(None)
+fn assemble_line(name_lookup: &HashMap<String, OpcodeAddr>, line: &str) -> Result<Option<Instruction>, String> {
+    let line = line.trim();
+
+    // 빈 줄, 주석 무시
+    if line.is_empty() || line.starts_with(';') || line.starts_with("//") || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    // 인라인 주석 제거 (문자열 안의 ;는 유지)
+    let line = if let Some(pos) = find_unquoted(line, ';') {
+        &line[..pos]
+    } else {
+        line
+    }.trim();
+
+    if line.is_empty() { return Ok(None); }
+
+    // 명령어 + 피연산자 분리
+    let parts: Vec<&str> = line.splitn(2, char::is_whitespace).collect();
+    let cmd = parts[0];
+    let arg_str = parts.get(1).map(|s| s.trim()).unwrap_or("");
+
+    // 명령어 조회
+    let addr = name_lookup.get(cmd).ok_or_else(|| format!("인식 불가: '{}'", cmd))?;
+    // 쉼표 또는 공백으로 분리 (따옴표 안은 유지)
+    let operands = split_operands(arg_str)?
+        .iter()
+        .filter_map(|t| parse_operand(t).transpose())
+        .collect::<Result<Vec<Value>, String>>()?;
+    Ok(Some(Instruction::from_addr(*addr, operands)))
 }
 
 /// 어셈블리 소스 → 명령어 벡터
 pub fn assemble(source: &str) -> Vec<Instruction> {
-    let opcodes = build_opcodes();
-    let name_lookup = build_name_lookup(&opcodes);
+    // 섹터 0~8 전체 니모닉 (섹터 1~8은 VM에서 NOP, 권한 사전분석 대상)
+    let name_lookup = build_name_lookup(crate::sectors::all_sectors());
 
-    let mut program = Vec::new();
+    let mut program: Vec<Instruction> = Vec::new();
+    let mut labels = HashMap::new();
+    for (line_no, line) in source.lines().enumerate() {
+        let result = match label_def(line) {
+            Some(name) => define_label(&mut labels, name, program.len()).map(|_| None),
+            None => assemble_line(&name_lookup, line),
+        };
+        match result {
+            Ok(Some(mut inst)) => {
+                inst.line = Some(line_no + 1);
+                program.push(inst);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("[어셈블러:{}행] {}", line_no + 1, e),
+        }
+    }
+    for (i, e) in resolve_labels(&mut program, &labels) {
+        eprintln!("[어셈블러:{}행] {}", program[i].line.unwrap_or(0), e);
+    }
+    program
+}
+
+/// 소스맵 — 명령어 인덱스 → 소스 줄 번호(1부터)
+/// {"schema":"crowny.source_map","schema_version":2,"lines":[...]}
+pub fn source_map(source: &str) -> String {
+    let name_lookup = build_name_lookup(crate::sectors::all_sectors());
+    let lines: Vec<String> = source.lines().enumerate()
+        .filter(|(_, line)| matches!(assemble_line(&name_lookup, line), Ok(Some(_))))
+        .map(|(i, _)| (i + 1).to_string())
+        .collect();
+    crate::output::JsonObject::schema("crowny.source_map")
+        .raw("lines", crate::output::array(lines))
+        .build()
+}
+
+/// 한도 검사 어셈블 — 첫 초과에서 즉시 중단 (나머지 소스는 읽지 않음)
+pub fn assemble_limited(source: &str, limits: &ProgramLimits) -> Result<Vec<Instruction>, ProgramLimitError> {
+    let name_lookup = build_name_lookup(crate::sectors::all_sectors());
+    let mut checker = ProgramChecker::new(*limits);
 
+    let mut program = Vec::new();
+    let mut labels = HashMap::new();
     for (line_no, line) in source.lines().enumerate() {
-        let line = line.trim();
+        let result = match label_def(line) {
+            Some(name) => define_label(&mut labels, name, program.len()).map(|_| None),
+            None => assemble_line(&name_lookup, line),
+        };
+        match result {
+            Ok(Some(mut inst)) => {
+                inst.line = Some(line_no + 1);
+                checker.push(&inst, inst.line)?;
+                program.push(inst);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("[어셈블러:{}행] {}", line_no + 1, e),
+        }
+    }
+    for (i, e) in resolve_labels(&mut program, &labels) {
+        eprintln!("[어셈블러:{}행] {}", program[i].line.unwrap_or(0), e);
+    }
+    Ok(program)
+}
 
-        // 빈 줄, 주석 무시
-        if line.is_empty() || line.starts_with(';') || line.starts_with("//") || line.starts_with('#') {
-            continue;
+// ═══════════════════════════════════════
+// 스트리밍 어셈블러 — 메가바이트급 생성 소스용
+// 한 줄씩 읽어 명령어를 하나씩 내보낸다 (Iterator)
+// 메모리 상한 = 줄 버퍼(max_line_bytes) + 보관 오류(max_errors)
+// ═══════════════════════════════════════
+
+/// 스트리밍 한도
+#[derive(Debug, Clone, Copy)]
+pub struct StreamLimits {
+    /// 한 줄 최대 바이트 — 초과한 줄은 오류로 건너뛴다
+    pub max_line_bytes: usize,
+    /// 보관할 오류 수 (이후는 개수만 센다)
+    pub max_errors: usize,
+    /// 진행 콜백 간격 (줄)
+    pub progress_every: usize,
+}
+
+impl Default for StreamLimits {
+    fn default() -> Self {
+        Self { max_line_bytes: 64 * 1024, max_errors: 100, progress_every: 100_000 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AsmProgress {
+    pub lines: usize,
+    pub bytes: u64,
+    pub instructions: usize,
+    pub errors: usize,
+    /// 알고 있으면 전체 바이트 (파일 크기)
+    pub total_bytes: Option<u64>,
+    /// 줄 버퍼 최대 사용량
+    pub peak_line_bytes: usize,
+    /// 풀리지 않은 라벨 참조 (정의 없음 · 라벨 없이 읽은 앞 참조)
+    pub unresolved_labels: usize,
+}
+
+impl AsmProgress {
+    pub fn percent(&self) -> Option<f64> {
+        self.total_bytes.filter(|t| *t > 0).map(|t| self.bytes as f64 * 100.0 / t as f64)
+    }
+}
+
+type ProgressFn = Box<dyn FnMut(&AsmProgress)>;
+
+pub struct StreamAssembler<R: std::io::BufRead> {
+    reader: R,
+    lookup: HashMap<String, OpcodeAddr>,
+    limits: StreamLimits,
+    line: Vec<u8>,
+    progress: AsmProgress,
+    /// (줄 번호, 메시지) — 최대 max_errors개
+    errors: Vec<(usize, String)>,
+    on_progress: Option<ProgressFn>,
+    done: bool,
+    /// 라벨 → 명령어 위치 (with_labels면 첫 패스 결과로 고정)
+    labels: HashMap<String, usize>,
+    preset_labels: bool,
+    /// 정의 전에 나온 참조 (줄, 이름) — 입력 끝에서 오류로
+    forward_refs: Vec<(usize, String)>,
+}
+
+impl<R: std::io::BufRead> StreamAssembler<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_limits(reader, StreamLimits::default())
+    }
+
+    pub fn with_limits(reader: R, limits: StreamLimits) -> Self {
+        Self {
+            reader,
+            lookup: build_name_lookup(crate::sectors::all_sectors()),
+            limits,
+            line: Vec::new(),
+            progress: AsmProgress::default(),
+            errors: Vec::new(),
+            on_progress: None,
+            done: false,
+            labels: HashMap::new(),
+            preset_labels: false,
+            forward_refs: Vec::new(),
         }
+    }
 
-        // 인라인 주석 제거
-        let line = if let Some(pos) = line.find(';') {
-            &line[..pos]
-        } else {
-            line
-        }.trim();
+    /// 첫 패스(scan_labels)의 라벨 — 앞 참조까지 풀린다
+    pub fn with_labels(mut self, labels: HashMap<String, usize>) -> Self {
+        self.labels = labels;
+        self.preset_labels = true;
+        self
+    }
 
-        if line.is_empty() { continue; }
+    pub fn total_bytes(mut self, total: u64) -> Self {
+        self.progress.total_bytes = Some(total);
+        self
+    }
 
-        // 명령어 + 피연산자 분리
-        let parts: Vec<&str> = line.splitn(2, char::is_whitespace).collect();
-        let cmd = parts[0];
-        let arg_str = parts.get(1).map(|s| s.trim()).unwrap_or("");
+    /// progress_every 줄마다 + 끝에서 한 번 호출
+    pub fn on_progress(mut self, f: impl FnMut(&AsmProgress) + 'static) -> Self {
+        self.on_progress = Some(Box::new(f));
+        self
+    }
 
-        // 명령어 조회
-        if let Some(addr) = name_lookup.get(cmd) {
-            let operands: Vec<Value> = if arg_str.is_empty() {
-                vec![]
+    pub fn progress(&self) -> &AsmProgress {
+        &self.progress
+    }
+
+    pub fn errors(&self) -> &[(usize, String)] {
+        &self.errors
+    }
+
+    fn record_error(&mut self, line: usize, msg: String) {
+        self.progress.errors += 1;
+        if self.errors.len() < self.limits.max_errors {
+            self.errors.push((line, msg));
+        }
+    }
+
+    /// 줄 하나를 버퍼로 — 상한을 넘는 부분은 버리고 true(초과) 반환
+    /// Ok(None): 입력 끝
+    fn read_line(&mut self) -> std::io::Result<Option<bool>> {
+        self.line.clear();
+        let mut overflow = false;
+        let mut read_any = false;
+        loop {
+            let chunk = self.reader.fill_buf()?;
+            if chunk.is_empty() {
+                break;
+            }
+            read_any = true;
+            let (take, found) = match chunk.iter().position(|b| *b == b'\n') {
+                Some(i) => (i + 1, true),
+                None => (chunk.len(), false),
+            };
+            let room = self.limits.max_line_bytes.saturating_sub(self.line.len());
+            if take > room {
+                overflow = true;
+            }
+            self.line.extend_from_slice(&chunk[..take.min(room)]);
+            self.reader.consume(take);
+            self.progress.bytes += take as u64;
+            if found {
+                break;
+            }
+        }
+        self.progress.peak_line_bytes = self.progress.peak_line_bytes.max(self.line.len());
+        Ok(if read_any { Some(overflow) } else { None })
+    }
+
+    fn report(&mut self) {
+        if let Some(f) = self.on_progress.as_mut() {
+            f(&self.progress);
+        }
+    }
+
+    /// 분기 피연산자의 라벨 → 주소. 아직 모르는 이름은 첫 패스 라벨이 있으면 오류, 없으면 앞 참조로 보류
+    fn resolve(&mut self, inst: &mut Instruction, line: usize) {
+        if !is_branch(&inst.addr) {
+            return;
+        }
+        for operand in inst.operands.iter_mut() {
+            let Value::Str(name) = operand else { continue };
+            match self.labels.get(name.as_str()) {
+                Some(&at) => *operand = Value::Addr(at),
+                None if self.preset_labels => {
+                    let msg = format!("정의되지 않은 라벨: '{}'", name);
+                    self.progress.unresolved_labels += 1;
+                    self.record_error(line, msg);
+                }
+                None => self.forward_refs.push((line, name.clone())),
+            }
+        }
+    }
+
+    /// 입력 끝 — 보류한 앞 참조를 오류로
+    fn finish_labels(&mut self) {
+        for (line, name) in std::mem::take(&mut self.forward_refs) {
+            let msg = if self.labels.contains_key(&name) {
+                format!("앞 참조 라벨 '{}' — 스트리밍 한 번 읽기로는 풀 수 없음 (scan_labels 먼저)", name)
             } else {
-                // 쉼표 또는 공백으로 분리
-                arg_str.split(',')
-                    .flat_map(|s| s.split_whitespace())
-                    .filter_map(|s| parse_operand(s))
-                    .collect()
+                format!("정의되지 않은 라벨: '{}'", name)
             };
-            program.push(Instruction::from_addr(*addr, operands));
-        } else {
-            eprintln!("[어셈블러:{}행] 인식 불가: '{}'", line_no + 1, cmd);
+            self.progress.unresolved_labels += 1;
+            self.record_error(line, msg);
         }
     }
+}
 
-    program
+/// 스트리밍 첫 패스 — 명령어는 버리고 라벨 위치만 모은다 (오류는 두 번째 패스에서 보고)
+pub fn scan_labels<R: std::io::BufRead>(reader: R) -> HashMap<String, usize> {
+    let mut stream = StreamAssembler::new(reader);
+    stream.by_ref().for_each(drop);
+    stream.labels
+}
+
+impl<R: std::io::BufRead> Iterator for StreamAssembler<R> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Instruction> {
+        while !self.done {
+            let overflow = match self.read_line() {
+                Ok(Some(overflow)) => overflow,
+                Ok(None) => {
+                    self.done = true;
+                    self.finish_labels();
+                    self.report();
+                    break;
+                }
+                Err(e) => {
+                    self.done = true;
+                    self.finish_labels();
+                    let line = self.progress.lines + 1;
+                    self.record_error(line, format!("읽기 오류: {}", e));
+                    self.report();
+                    break;
+                }
+            };
+            self.progress.lines += 1;
+            let line_no = self.progress.lines;
+            if self.limits.progress_every > 0 && line_no.is_multiple_of(self.limits.progress_every) {
+                self.report();
+            }
+
+            let result = if overflow {
+                Err(format!("줄이 너무 김 (>{} bytes)", self.limits.max_line_bytes))
+            } else {
+                let at = self.progress.instructions;
+                match std::str::from_utf8(&self.line) {
+                    Ok(text) => match label_def(text) {
+                        // 두 번째 패스 — 첫 패스와 같은 위치여야 한다 (다르면 중복 정의)
+                        Some(name) if self.preset_labels => match self.labels.get(name) {
+                            Some(&pos) if pos == at => Ok(None),
+                            _ => Err(format!("라벨 중복: '{}'", name)),
+                        },
+                        Some(name) => define_label(&mut self.labels, name, at).map(|_| None),
+                        None => assemble_line(&self.lookup, text),
+                    },
+                    Err(_) => Err("UTF-8이 아님".into()),
+                }
+            };
+            match result {
+                Ok(Some(mut inst)) => {
+                    self.resolve(&mut inst, line_no);
+                    self.progress.instructions += 1;
+                    return Some(inst);
+                }
+                Ok(None) => {}
+                Err(e) => self.record_error(line_no, e),
+            }
+        }
+        None
+    }
 }
 
 /// 디스어셈블: 명령어 벡터 → 읽기 가능한 문자열
@@ -133,4 +598,216 @@ mod tests {
         let prog = assemble(src);
         assert_eq!(prog.len(), 5);
     }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(r#"줄\n\"따옴\" \\ 탭\t"#).unwrap(), "줄\n\"따옴\" \\ 탭\t");
+        assert_eq!(unescape(r"\uD55C\u{AE00}").unwrap(), "한글");
+        assert_eq!(unescape(r"\uD83D\uDE00 \u{1F1F0}\u{1F1F7}").unwrap(), "😀 🇰🇷");
+        assert!(unescape(r"\uD83D").is_err());
+        assert!(unescape(r"\q").is_err());
+        assert!(unescape(r"\u12").is_err());
+    }
+
+    #[test]
+    fn test_quoted_operands_keep_spaces_and_semicolons() {
+        let prog = assemble("넣어 \"안녕 세상; 👋🏽\"  ; 주석\n넣어 '작은 \\' 따옴표', 7");
+        assert_eq!(prog.len(), 2);
+        assert_eq!(prog[0].operands.len(), 1);
+        assert_eq!(prog[0].operands[0].as_str(), Some("안녕 세상; 👋🏽"));
+        assert_eq!(prog[1].operands[0].as_str(), Some("작은 ' 따옴표"));
+        assert_eq!(prog[1].operands[1].as_int(), Some(7));
+        // 닫히지 않은 문자열은 그 줄만 거부
+        assert_eq!(assemble("넣어 \"끝없음\n종료").len(), 1);
+    }
+
+    #[test]
+    fn test_string_ops_on_hangul_and_emoji() {
+        let src = "넣어 \"가👨‍👩‍👧나🇰🇷\"\n복사\n길이\n레지쓰기 0\n복사\n넣어 1\n인덱스\n레지쓰기 1\n넣어 -2\n넣어 100\n슬라이스\n종료";
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble(src));
+        vm.run().unwrap();
+        assert_eq!(vm.registers[0].as_int(), Some(4));
+        assert_eq!(vm.registers[1].as_str(), Some("👨‍👩‍👧"));
+        assert_eq!(vm.stack.last().and_then(|v| v.as_str()), Some("나🇰🇷"));
+
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble("넣어 \"한\"\n넣어 5\n인덱스"));
+        assert!(vm.run().unwrap_err().to_string().contains("범위"));
+    }
+
+    #[test]
+    fn test_labels_run_recursive_function() {
+        let src = "넣어 \"호출자\"\n레지쓰기 0\n넣어 5\n호출 팩토리얼\n종료\n\
+            팩토리얼:   ; n → n!\n레지쓰기 0\n레지읽기 0\n넣어 2\n작다\n조건점프 기저\n\
+            레지읽기 0\n레지읽기 0\n넣어 1\n빼\n호출 팩토리얼\n곱해\n돌려줘\n\
+            기저:\n넣어 1\n돌려줘";
+        let prog = assemble(src);
+        assert_eq!(prog.len(), 19);
+        assert!(matches!(prog[3].operands[0], Value::Addr(5)));
+        let mut vm = crate::vm::TVM::new();
+        vm.load(prog);
+        vm.run().unwrap();
+        assert_eq!(vm.stack.len(), 1);
+        assert_eq!(vm.stack[0].as_int(), Some(120));
+        // 피호출자의 레지쓰기 0은 프레임 지역
+        assert_eq!(vm.registers[0].as_str(), Some("호출자"));
+        assert!(vm.call_stack.is_empty());
+    }
+
+    #[test]
+    fn test_call_stack_overflow_and_unresolved_labels() {
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble("무한:\n호출 무한"));
+        assert!(matches!(vm.run().unwrap_err().kind, crate::vm::VmErrorKind::StackOverflow(crate::vm::MAX_CALL_DEPTH)));
+
+        // 정의되지 않은 라벨은 문자열로 남아 실행 시 타입 오류
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble("호출 없는곳"));
+        assert!(vm.run().unwrap_err().to_string().contains("주소"));
+        assert_eq!(assemble("가:\n가:\n종료").len(), 1);
+        assert_eq!(label_def("끝: ; 주석"), Some("끝"));
+        assert_eq!(label_def("넣어 \"a:\""), None);
+
+        // 피연산자 없는 호출은 이전처럼 스택의 주소로
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble("넣어 3\n호출\n종료\n넣어 7\n반환"));
+        vm.run().unwrap();
+        assert_eq!(vm.stack.last().and_then(|v| v.as_int()), Some(7));
+    }
+
+    #[test]
+    fn test_gc_on_allocation_pressure() {
+        // 주소를 버리며 100번 할당 (99..0, 반복은 O에서도 계속) — 임계 8에서 수거되어 살아있는 셀이 한정된다
+        let src = "넣어 8\nGC임계\n넣어 \"유지\"\n할당\n레지쓰기 0\n넣어 99\n레지쓰기 1\n\
+            반복:\n넣어 1\n할당\n꺼내\n레지읽기 1\n넣어 1\n빼\n복사\n레지쓰기 1\n반복 반복\nGC실행\nGC통계";
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble(src));
+        vm.run().unwrap();
+        assert_eq!(vm.stack.last().unwrap().as_int(), Some(100));
+        assert_eq!(vm.heap.alive_count(), 1);
+        assert!(vm.heap.capacity() <= 9);
+        assert!(vm.heap.stats().runs > 1);
+        let kept = vm.registers[0].as_addr().unwrap();
+        assert_eq!(vm.heap.get(kept).and_then(|v| v.as_str()), Some("유지"));
+    }
+
+    #[test]
+    fn test_three_way_branch() {
+        let run = |cond: &str| {
+            let src = format!("넣어 {}\n비교\n삼분기 큼 같음 작음\n\
+                큼:\n넣어 \"P\"\n종료\n같음:\n넣어 \"O\"\n종료\n작음:\n넣어 \"T\"\n종료", cond);
+            let mut vm = crate::vm::TVM::new();
+            vm.load(assemble(&format!("넣어 5\n{}", src)));
+            vm.run().unwrap();
+            vm.stack.last().unwrap().as_str().unwrap().to_string()
+        };
+        assert_eq!(run("3"), "P");
+        assert_eq!(run("5"), "O");
+        assert_eq!(run("9"), "T");
+
+        // 피연산자 없이 — 스택의 조건 · P · O · T 주소
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble("거짓\n넣어 7\n넣어 7\n넣어 6\n삼분기\n종료\n넣어 -1\n종료"));
+        vm.run().unwrap();
+        assert_eq!(vm.stack.last().unwrap().as_int(), Some(-1));
+
+        let mut vm = crate::vm::TVM::new();
+        vm.load(assemble("참\n삼분기 0 0"));
+        assert!(vm.run().unwrap_err().to_string().contains("대상 3개"));
+    }
+
+    #[test]
+    fn test_runtime_error_maps_to_source_line() {
+        let src = "; 나눗셈\n넣어 1\n\n넣어 0\n    나눠   ; 0으로\n종료";
+        let program = assemble(src);
+        assert_eq!(program.iter().map(|i| i.line).collect::<Vec<_>>(), vec![Some(2), Some(4), Some(5), Some(6)]);
+
+        let mut vm = crate::vm::TVM::new();
+        vm.load(program);
+        let err = vm.run().unwrap_err();
+        assert!(matches!(err.kind, crate::vm::VmErrorKind::DivisionByZero));
+        assert_eq!((err.ip, err.source_line, err.instruction_name), (2, Some(5), "나눠"));
+        let diag = err.diagnostic("div.hsn", Some(src));
+        assert!(diag.contains("--> div.hsn:5 (IP 2 · 나눠)"));
+        assert!(diag.ends_with("5 |     나눠   ; 0으로\n   |     ^^"));
+        // 소스 없이 — 위치 줄만
+        assert_eq!(err.diagnostic("div.hsn", None).lines().count(), 2);
+    }
+
+    #[test]
+    fn test_source_map_lines() {
+        let src = "; 헤더\n넣어 1\n\n넣어 2 ; 주석\n더해\n없는명령\n종료";
+        assert_eq!(source_map(src), r#"{"schema":"crowny.source_map","schema_version":2,"lines":[2,4,5,7]}"#);
+        assert_eq!(assemble(src).len(), 4);
+    }
+
+    #[test]
+    fn test_stream_matches_assemble() {
+        let src = "; 주석\n넣어 \"a;b\"\r\n넣어 7 ; 인라인\n\n더해\n없는명령\n보여줘\n종료";
+        let progress = std::rc::Rc::new(std::cell::Cell::new(0));
+        let seen = progress.clone();
+        let mut stream = StreamAssembler::with_limits(src.as_bytes(), StreamLimits { progress_every: 2, ..Default::default() })
+            .total_bytes(src.len() as u64)
+            .on_progress(move |_| seen.set(seen.get() + 1));
+        let streamed: Vec<Instruction> = stream.by_ref().collect();
+        let batch = assemble(src);
+        assert_eq!(streamed.len(), batch.len());
+        for (a, b) in streamed.iter().zip(&batch) {
+            assert_eq!(a.addr, b.addr);
+            assert_eq!(format!("{:?}", a.operands), format!("{:?}", b.operands));
+        }
+        let p = stream.progress();
+        assert_eq!((p.lines, p.instructions, p.errors), (8, 5, 1));
+        assert_eq!(p.percent(), Some(100.0));
+        assert_eq!(stream.errors()[0].0, 6);
+        // 2줄마다 4번 + 끝에서 1번
+        assert_eq!(progress.get(), 5);
+    }
+
+    #[test]
+    fn test_stream_caps_line_buffer_and_errors() {
+        let long = format!("넣어 \"{}\"\n", "가".repeat(1000));
+        let src = format!("{}넣어 1\n{}없음\n없음\n없음\n", long, long);
+        let limits = StreamLimits { max_line_bytes: 64, max_errors: 3, progress_every: 0 };
+        let mut stream = StreamAssembler::with_limits(src.as_bytes(), limits);
+        assert_eq!(stream.by_ref().count(), 1);
+        let p = *stream.progress();
+        assert_eq!(p.errors, 5);
+        assert_eq!(stream.errors().len(), 3);
+        assert!(stream.errors()[0].1.contains("너무 김"));
+        assert!(p.peak_line_bytes <= 64);
+        assert_eq!(p.bytes, src.len() as u64);
+    }
+
+    #[test]
+    fn test_stream_labels_two_pass() {
+        let src = "넣어 3\n반복:\n넣어 1\n빼\n복사\n조건점프 끝\n점프 반복\n끝:\n종료\n";
+        let batch = assemble(src);
+
+        // 한 번 읽기 — 뒤 참조는 풀리고 앞 참조는 오류
+        let mut once = StreamAssembler::new(src.as_bytes());
+        let streamed: Vec<Instruction> = once.by_ref().collect();
+        assert!(matches!(streamed[5].operands[0], Value::Addr(1)));
+        assert_eq!(once.progress().unresolved_labels, 1);
+        assert_eq!(once.errors()[0].0, 6);
+        assert!(once.errors()[0].1.contains("앞 참조"));
+
+        // 두 번 읽기 — 일괄 어셈블과 같다
+        let labels = scan_labels(src.as_bytes());
+        assert_eq!(labels.get("끝"), Some(&6));
+        let mut twice = StreamAssembler::new(src.as_bytes()).with_labels(labels);
+        let streamed: Vec<Instruction> = twice.by_ref().collect();
+        assert_eq!(format!("{:?}", streamed.iter().map(|i| &i.operands).collect::<Vec<_>>()),
+            format!("{:?}", batch.iter().map(|i| &i.operands).collect::<Vec<_>>()));
+        assert_eq!(twice.progress().errors, 0);
+
+        // 정의 없는 라벨 · 중복 라벨
+        let bad = "a:\n점프 없는곳\na:\n종료\n";
+        let mut stream = StreamAssembler::new(bad.as_bytes()).with_labels(scan_labels(bad.as_bytes()));
+        stream.by_ref().for_each(drop);
+        let errors: Vec<&str> = stream.errors().iter().map(|(_, e)| e.as_str()).collect();
+        assert_eq!(errors, ["라벨 중복: 'a'", "정의되지 않은 라벨: '없는곳'"]);
+        assert_eq!(stream.progress().unresolved_labels, 1);
+    }
 }
@@ -0,0 +1,234 @@
+// ═══════════════════════════════════════════════════════════════
+// 재현 빌드 증명 (attestation) — 소스 → 배포 아티팩트 일치 검증
+// 고정된 컴파일러 지문으로 소스를 다시 컴파일해 해시를 비교하고,
+// 서명된 증명 레코드를 체인 트랜잭션(TxType::Attestation)으로 남긴다
+//
+//   crowni-tvm attest <소스> <아티팩트> [--attester 이름]
+//
+// 판정: P = 재현 일치 · O = 재현 불가(형식/컴파일러 세대 다름) · T = 불일치
+// ═══════════════════════════════════════════════════════════════
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::artifacts::{content_hash, ArtifactKind};
+use crate::chain::{trit_hash, Transaction, TxType};
+use crate::output::JsonObject;
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 재컴파일에 쓰는 모듈 이름 — 경로에 따라 출력이 달라지지 않도록 고정
+pub const MODULE: &str = "crowny";
+/// 증명 레코드 형식 버전 (트랜잭션 data 접두사)
+const RECORD_PREFIX: &str = "attest:v1";
+/// 증명 트랜잭션 수신 주소
+pub const ATTESTATION_ADDRESS: &str = "attestations";
+
+/// 컴파일러 지문 — 이름 · 버전 · 바이트코드 형식 · 모듈 이름을 묶은 해시
+/// 컴파일러가 바뀌면 지문도 바뀌므로 다른 지문의 증명은 재현 대상이 아니다
+pub fn compiler_fingerprint() -> String {
+    trit_hash(&format!("{}:{}:bytecode-v{}:wasm:{}",
+        crate::cli::BIN, env!("CARGO_PKG_VERSION"), crate::bytecode::VERSION, MODULE))
+}
+
+/// 아티팩트 형식 감지 (wasm 매직 / .크라운 매직)
+pub fn detect_kind(bytes: &[u8]) -> Option<ArtifactKind> {
+    if bytes.starts_with(b"\0asm") {
+        Some(ArtifactKind::Wasm)
+    } else if crate::bytecode::detect_version(bytes).is_some() {
+        Some(ArtifactKind::Bytecode)
+    } else {
+        None
+    }
+}
+
+/// 고정 설정으로 재컴파일
+pub fn rebuild(source: &str, kind: ArtifactKind) -> Result<Vec<u8>, String> {
+    let program = crate::assembler::assemble(source);
+    if program.is_empty() {
+        return Err("빈 프로그램".into());
+    }
+    match kind {
+        ArtifactKind::Wasm => crate::compiler::compile_to_wasm(&program, MODULE),
+        ArtifactKind::Bytecode => Ok(crate::bytecode::serialize(&program)),
+        other => Err(format!("재컴파일 대상이 아님: {}", other.code())),
+    }
+}
+
+/// 서명된 증명 레코드
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attestation {
+    pub source_hash: String,
+    pub artifact_hash: String,
+    /// 재컴파일 결과 해시 (재현 불가면 빈 문자열)
+    pub rebuilt_hash: String,
+    pub kind: ArtifactKind,
+    pub compiler: String,
+    /// P 일치 · O 재현 불가 · T 불일치
+    pub state: i8,
+    pub attester: String,
+    pub timestamp: u64,
+    pub signature: String,
+}
+
+impl Attestation {
+    fn payload(&self) -> String {
+        format!("{}:{}:{}:{}:{}:{}:{}:{}", self.source_hash, self.artifact_hash, self.rebuilt_hash,
+            self.kind.code(), self.compiler, self.state, self.attester, self.timestamp)
+    }
+
+    /// 서명 (체인 트랜잭션과 같은 방식: 증명자 + 내용 해시)
+    fn sign(&self) -> String {
+        trit_hash(&format!("sig:{}:{}", self.attester, self.payload()))
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        self.signature == self.sign()
+    }
+
+    /// 탐색기 표시용 — 재현 일치 + 서명 유효일 때만 "source verified"
+    pub fn source_verified(&self) -> bool {
+        self.state == 1 && self.verify_signature()
+    }
+
+    /// 트랜잭션 data 직렬화
+    pub fn to_tx_data(&self) -> String {
+        format!("{};src={};art={};rebuilt={};kind={};compiler={};state={};ts={};sig={}", RECORD_PREFIX,
+            self.source_hash, self.artifact_hash, self.rebuilt_hash, self.kind.code(),
+            self.compiler, self.state, self.timestamp, self.signature)
+    }
+
+    /// 증명 트랜잭션에서 복원 (증명자 = 보낸 주소)
+    pub fn from_tx(tx: &Transaction) -> Option<Self> {
+        if tx.trit_type != TxType::Attestation {
+            return None;
+        }
+        let mut fields = tx.data.split(';');
+        if fields.next()? != RECORD_PREFIX {
+            return None;
+        }
+        let mut get = std::collections::HashMap::new();
+        for field in fields {
+            let (k, v) = field.split_once('=')?;
+            get.insert(k, v);
+        }
+        let kind = match *get.get("kind")? {
+            "wasm" => ArtifactKind::Wasm,
+            "bytecode" => ArtifactKind::Bytecode,
+            _ => return None,
+        };
+        Some(Self {
+            source_hash: get.get("src")?.to_string(),
+            artifact_hash: get.get("art")?.to_string(),
+            rebuilt_hash: get.get("rebuilt")?.to_string(),
+            kind,
+            compiler: get.get("compiler")?.to_string(),
+            state: get.get("state")?.parse().ok()?,
+            attester: tx.from.clone(),
+            timestamp: get.get("ts")?.parse().ok()?,
+            signature: get.get("sig")?.to_string(),
+        })
+    }
+
+    pub fn to_tx(&self) -> Transaction {
+        Transaction::new(&self.attester, ATTESTATION_ADDRESS, 0, 0, TxType::Attestation, &self.to_tx_data())
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.attestation")
+            .trit("state", self.state)
+            .str("kind", self.kind.code())
+            .str("source_hash", &self.source_hash)
+            .str("artifact_hash", &self.artifact_hash)
+            .str("rebuilt_hash", &self.rebuilt_hash)
+            .str("compiler", &self.compiler)
+            .str("attester", &self.attester)
+            .int("timestamp", self.timestamp as i64)
+            .str("signature", &self.signature)
+            .bool("source_verified", self.source_verified())
+    }
+}
+
+impl std::fmt::Display for Attestation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verdict = match self.state { 1 => "일치", -1 => "불일치", _ => "재현 불가" };
+        write!(f, "[{}] {} {} — {} (증명자: {})", crate::output::trit_symbol(self.state),
+            self.kind.code(), self.artifact_hash, verdict, self.attester)?;
+        if self.source_verified() {
+            write!(f, " ✓ source verified")?;
+        }
+        Ok(())
+    }
+}
+
+/// 소스를 재컴파일해 아티팩트와 비교하고 서명된 증명을 만든다
+pub fn attest(source: &str, artifact: &[u8], attester: &str) -> Result<Attestation, String> {
+    let kind = detect_kind(artifact).ok_or("아티팩트 형식을 알 수 없음 (wasm/.크라운)")?;
+    let artifact_hash = content_hash(kind, artifact);
+    // 이전 세대 바이트코드는 현재 컴파일러로 재현할 수 없다
+    let current = kind != ArtifactKind::Bytecode
+        || crate::bytecode::detect_version(artifact) == Some(crate::bytecode::VERSION as u32);
+    let rebuilt_hash = match rebuild(source, kind) {
+        Ok(bytes) if current => content_hash(kind, &bytes),
+        _ => String::new(),
+    };
+    let state = if rebuilt_hash.is_empty() { 0 } else if rebuilt_hash == artifact_hash { 1 } else { -1 };
+
+    let mut att = Attestation {
+        source_hash: content_hash(ArtifactKind::Source, source.as_bytes()),
+        artifact_hash,
+        rebuilt_hash,
+        kind,
+        compiler: compiler_fingerprint(),
+        state,
+        attester: attester.into(),
+        timestamp: now_ms(),
+        signature: String::new(),
+    };
+    att.signature = att.sign();
+    Ok(att)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = "넣어 6\n넣어 7\n곱해\n보여줘\n종료";
+
+    #[test]
+    fn test_attest_match_and_mismatch() {
+        let wasm = rebuild(SRC, ArtifactKind::Wasm).unwrap();
+        let att = attest(SRC, &wasm, "alice").unwrap();
+        assert_eq!((att.kind, att.state), (ArtifactKind::Wasm, 1));
+        assert!(att.source_verified());
+        assert_eq!(att.compiler, compiler_fingerprint());
+
+        let bc = crate::bytecode::serialize(&crate::assembler::assemble(SRC));
+        assert_eq!(attest(SRC, &bc, "alice").unwrap().state, 1);
+        let other = attest("넣어 1\n종료", &bc, "alice").unwrap();
+        assert_eq!(other.state, -1);
+        assert!(!other.source_verified());
+
+        // 이전 세대 바이트코드 → 재현 불가
+        let v1 = crate::bytecode::serialize_v1(&crate::assembler::assemble(SRC));
+        assert_eq!(attest(SRC, &v1, "alice").unwrap().state, 0);
+        assert!(attest(SRC, b"plain text", "alice").is_err());
+    }
+
+    #[test]
+    fn test_tx_roundtrip_and_tamper() {
+        let wasm = rebuild(SRC, ArtifactKind::Wasm).unwrap();
+        let att = attest(SRC, &wasm, "alice").unwrap();
+        let tx = att.to_tx();
+        assert!(tx.verify());
+        let back = Attestation::from_tx(&tx).unwrap();
+        assert_eq!(back, att);
+
+        let mut forged = back.clone();
+        forged.attester = "mallory".into();
+        assert!(!forged.source_verified());
+        let plain = Transaction::new("a", "b", 1, 0, TxType::Transfer, &tx.data);
+        assert!(Attestation::from_tx(&plain).is_none());
+    }
+}
@@ -0,0 +1,495 @@
+// ═══════════════════════════════════════════════════════════════
+// 2진 vs 3진 실측 벤치마크 — fpga 데모의 고정 표를 호스트 측정으로 대체
+//
+//   밀도:   실제 패커가 쓰는 비트 수 vs 같은 범위를 담는 최소 2진 비트
+//   산술:   Trit 논리 · 12트릿 리플 덧셈 · 부정 vs bool / i32
+//   직렬화: TritWord u16 · TritDWord 3바이트 · TritBuffer ↔ 바이트 vs 정수 LE 바이트
+//
+// 시간 비교는 비율(3진 ns ÷ 2진 ns)로 기록 — 같은 호스트의 2진 기준선이
+// 기계 속도 차이를 상쇄하므로 기록끼리 비교할 수 있다
+// 기록: .crowny/bench.jsonl (한 줄 = 한 실행)
+// ═══════════════════════════════════════════════════════════════
+
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::bridge::{TritDWord, TritMemory, TritWord, Tryte};
+use crate::network::{NetTrit, TritBuffer};
+use crate::output::{JsonObject, JsonValue};
+use crate::trit::{Trit, Word6};
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+pub const HISTORY_FILE: &str = ".crowny/bench.jsonl";
+pub const DEFAULT_ITERATIONS: usize = 200_000;
+/// 직전 기록 대비 비율이 이만큼(%) 나빠지면 회귀
+pub const REGRESSION_PCT: f64 = 20.0;
+/// 벡터 벤치의 원소 수
+const LANES: usize = 1024;
+
+// ═══════════════════════════════════════
+// 밀도
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Density {
+    pub unit: &'static str,
+    pub trits: usize,
+    /// 실제 패킹 결과의 비트 수
+    pub packed_bits: usize,
+}
+
+impl Density {
+    /// 정보량 = trits × log₂3
+    pub fn info_bits(&self) -> f64 {
+        self.trits as f64 * 3f64.log2()
+    }
+
+    /// 같은 범위를 담는 최소 2진 비트
+    pub fn binary_bits(&self) -> usize {
+        self.info_bits().ceil() as usize
+    }
+
+    /// 정보량 ÷ 패킹 비트 (1.0 = 손실 없음)
+    pub fn efficiency(&self) -> f64 {
+        self.info_bits() / self.packed_bits as f64
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("unit", self.unit)
+            .int("trits", self.trits as i64)
+            .int("packed_bits", self.packed_bits as i64)
+            .int("binary_bits", self.binary_bits() as i64)
+            .float("efficiency", round3(self.efficiency()))
+    }
+}
+
+/// 각 단위를 실제로 패킹해 크기를 잰다
+pub fn density_table() -> Vec<Density> {
+    let buffer = TritBuffer::from_trits(vec![NetTrit::P; 729]);
+    let mut mem = TritMemory::new(729 * 6);
+    mem.write_word(0, &TritWord::from_decimal(1));
+    let mut ecc = TritMemory::with_ecc(729 * 6);
+    ecc.write_word(0, &TritWord::from_decimal(1));
+    vec![
+        Density { unit: "Tryte", trits: 3, packed_bits: std::mem::size_of_val(&Tryte::from_decimal(13).to_packed_byte()) * 8 },
+        Density { unit: "TritWord", trits: 6, packed_bits: std::mem::size_of_val(&TritWord::from_decimal(364).to_packed_u16()) * 8 },
+        Density { unit: "TritDWord", trits: 12, packed_bits: TritDWord::from_decimal(265720).to_packed_bytes().len() * 8 },
+        Density { unit: "TritBuffer(729)", trits: 729, packed_bits: buffer.to_bytes().len() * 8 },
+        Density { unit: "TritMemory(729×6)", trits: 729 * 6, packed_bits: mem.packed_bytes() * 8 },
+        Density { unit: "TritMemory+ECC(729×6)", trits: 729 * 6, packed_bits: ecc.packed_bytes() * 8 },
+    ]
+}
+
+// ═══════════════════════════════════════
+// 시간 측정
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub name: String,
+    /// "arith" | "serial"
+    pub category: &'static str,
+    pub ternary_ns: f64,
+    pub binary_ns: f64,
+}
+
+impl Timing {
+    /// 3진 ÷ 2진 (1.0 = 같은 속도, 클수록 3진이 느림)
+    pub fn ratio(&self) -> f64 {
+        if self.binary_ns > 0.0 { self.ternary_ns / self.binary_ns } else { f64::INFINITY }
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("name", &self.name)
+            .str("category", self.category)
+            .float("ternary_ns", round3(self.ternary_ns))
+            .float("binary_ns", round3(self.binary_ns))
+            .float("ratio", round3(self.ratio()))
+    }
+}
+
+fn round3(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+/// 연산 1회당 ns — 1/10 만큼 예열 후 측정
+fn ns_per_op(iterations: usize, mut op: impl FnMut(usize)) -> f64 {
+    for i in 0..iterations / 10 {
+        op(i);
+    }
+    let start = Instant::now();
+    for i in 0..iterations {
+        op(i);
+    }
+    start.elapsed().as_nanos() as f64 / iterations.max(1) as f64
+}
+
+/// 균형3진 리플 덧셈 — 자리마다 합 ∈ [-3, 3], 올림 ∈ {-1, 0, 1}
+pub fn ripple_add(a: &[i8; 12], b: &[i8; 12]) -> [i8; 12] {
+    let mut out = [0i8; 12];
+    let mut carry = 0i8;
+    for i in 0..12 {
+        let s = a[i] + b[i] + carry;
+        (out[i], carry) = match s {
+            2 => (-1, 1),
+            3 => (0, 1),
+            -2 => (1, -1),
+            -3 => (0, -1),
+            s => (s, 0),
+        };
+    }
+    out
+}
+
+fn arith_timings(iterations: usize) -> Vec<Timing> {
+    let trits: Vec<Trit> = (0..LANES).map(|i| Trit::from_i8((i % 3) as i8 - 1)).collect();
+    let bools: Vec<bool> = (0..LANES).map(|i| i % 3 == 0).collect();
+    let dwords: Vec<TritDWord> = (0..LANES as i32).map(|i| TritDWord::from_decimal(i * 97 - 50_000)).collect();
+    let ints: Vec<i32> = dwords.iter().map(TritDWord::to_decimal).collect();
+    let words: Vec<Word6> = (0..LANES as i16).map(|i| Word6::from_decimal(i % 729 - 364)).collect();
+    let shorts: Vec<i16> = words.iter().map(Word6::to_decimal).collect();
+    let m = LANES - 1;
+
+    vec![
+        Timing {
+            name: "논리 AND".into(),
+            category: "arith",
+            ternary_ns: ns_per_op(iterations, |i| { black_box(trits[i & m].and(trits[(i + 1) & m])); }),
+            binary_ns: ns_per_op(iterations, |i| { black_box(bools[i & m] & bools[(i + 1) & m]); }),
+        },
+        Timing {
+            name: "12트릿 덧셈".into(),
+            category: "arith",
+            ternary_ns: ns_per_op(iterations, |i| { black_box(ripple_add(&dwords[i & m].trits, &dwords[(i + 1) & m].trits)); }),
+            binary_ns: ns_per_op(iterations, |i| { black_box(ints[i & m].wrapping_add(ints[(i + 1) & m])); }),
+        },
+        Timing {
+            name: "6트릿 부정".into(),
+            category: "arith",
+            ternary_ns: ns_per_op(iterations, |i| { black_box(Word6::new(words[i & m].trits.map(Trit::not))); }),
+            binary_ns: ns_per_op(iterations, |i| { black_box(-shorts[i & m]); }),
+        },
+    ]
+}
+
+fn serial_timings(iterations: usize) -> Vec<Timing> {
+    let words: Vec<TritWord> = (0..LANES as i16).map(|i| TritWord::from_decimal(i % 729 - 364)).collect();
+    let shorts: Vec<i16> = words.iter().map(TritWord::to_decimal).collect();
+    let dwords: Vec<TritDWord> = (0..LANES as i32).map(|i| TritDWord::from_decimal(i * 97 - 50_000)).collect();
+    let ints: Vec<i32> = dwords.iter().map(TritDWord::to_decimal).collect();
+    let buffer = TritBuffer::from_trits((0..LANES).map(|i| [NetTrit::T, NetTrit::O, NetTrit::P][i % 3]).collect());
+    let bytes: Vec<i8> = (0..LANES).map(|i| (i % 3) as i8 - 1).collect();
+    let m = LANES - 1;
+    // 버퍼 벤치는 1회 = 1K 트릿이라 반복을 줄인다
+    let bulk = (iterations / LANES).max(1);
+
+    vec![
+        Timing {
+            name: "6트릿 u16 패킹 왕복".into(),
+            category: "serial",
+            ternary_ns: ns_per_op(iterations, |i| { black_box(TritWord::from_packed_u16(words[i & m].to_packed_u16())); }),
+            binary_ns: ns_per_op(iterations, |i| { black_box(i16::from_le_bytes(shorts[i & m].to_le_bytes())); }),
+        },
+        Timing {
+            name: "12트릿 3바이트 패킹".into(),
+            category: "serial",
+            ternary_ns: ns_per_op(iterations, |i| { black_box(dwords[i & m].to_packed_bytes()); }),
+            binary_ns: ns_per_op(iterations, |i| { black_box(ints[i & m].to_le_bytes()); }),
+        },
+        Timing {
+            name: "1K 트릿 버퍼 ↔ 바이트".into(),
+            category: "serial",
+            ternary_ns: ns_per_op(bulk, |_| { black_box(TritBuffer::from_bytes(&buffer.to_bytes(), LANES)); }),
+            binary_ns: ns_per_op(bulk, |_| {
+                let raw: Vec<u8> = bytes.iter().map(|&b| b as u8).collect();
+                black_box(raw.iter().map(|&b| b as i8).collect::<Vec<i8>>());
+            }),
+        },
+    ]
+}
+
+// ═══════════════════════════════════════
+// 보고서 · 기록
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub timestamp: u64,
+    pub iterations: usize,
+    pub host: String,
+    pub density: Vec<Density>,
+    pub timings: Vec<Timing>,
+}
+
+pub fn run(iterations: usize) -> BenchReport {
+    let mut timings = arith_timings(iterations);
+    timings.extend(serial_timings(iterations));
+    BenchReport {
+        timestamp: now_ms(),
+        iterations,
+        host: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        density: density_table(),
+        timings,
+    }
+}
+
+/// 직전 기록 대비 비율 변화
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    pub name: String,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl Delta {
+    /// 비율 변화율 (%) — 양수면 3진 쪽이 상대적으로 느려짐
+    pub fn change_pct(&self) -> f64 {
+        if self.before > 0.0 { (self.after / self.before - 1.0) * 100.0 } else { 0.0 }
+    }
+
+    pub fn is_regression(&self) -> bool {
+        self.change_pct() > REGRESSION_PCT
+    }
+}
+
+impl BenchReport {
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.bench")
+            .int("timestamp", self.timestamp as i64)
+            .int("iterations", self.iterations as i64)
+            .str("host", &self.host)
+            .objects("density", self.density.iter().map(Density::to_json).collect())
+            .objects("timings", self.timings.iter().map(Timing::to_json).collect())
+    }
+
+    /// 같은 이름의 벤치끼리 비율 비교 (호스트가 다르면 비교하지 않음)
+    pub fn compare(&self, previous: &JsonValue) -> Vec<Delta> {
+        if previous.get("host").and_then(JsonValue::as_str) != Some(self.host.as_str()) {
+            return Vec::new();
+        }
+        let before = previous.get("timings").and_then(JsonValue::as_array).unwrap_or_default();
+        self.timings.iter().filter_map(|t| {
+            let prev = before.iter().find(|p| p.get("name").and_then(JsonValue::as_str) == Some(t.name.as_str()))?;
+            Some(Delta { name: t.name.clone(), before: prev.get("ratio")?.as_f64()?, after: t.ratio() })
+        }).collect()
+    }
+}
+
+/// 기록 파일의 마지막 실행
+pub fn last_recorded(path: &Path) -> Option<JsonValue> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let line = text.lines().rev().find(|l| !l.trim().is_empty())?;
+    JsonValue::parse(line).ok()
+}
+
+/// 기록 파일에 한 줄 추가 (디렉터리가 없으면 만든다)
+pub fn record(path: &Path, report: &BenchReport) -> Result<(), String> {
+    use std::io::Write;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let mut f = std::fs::OpenOptions::new().create(true).append(true).open(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    writeln!(f, "{}", report.to_json().build()).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::{StreamAssembler, StreamLimits};
+    use crate::conformance::Gen;
+    use crate::ctp_compress::{compress_trits, TritScheme};
+    use crate::network::CtpMessage;
+    use crate::trit_log::{tlog_debug, Category, EventBuilder, Level, TritEventLog};
+
+    #[test]
+    fn test_ripple_add_matches_decimal() {
+        for a in (-5_000..5_000).step_by(37) {
+            for b in [-265720 / 2, -364, -1, 0, 1, 13, 100_000] {
+                let sum = ripple_add(&TritDWord::from_decimal(a).trits, &TritDWord::from_decimal(b).trits);
+                assert_eq!(TritDWord { trits: sum }.to_decimal(), a + b, "{} + {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_density_measures_real_packers() {
+        let table = density_table();
+        let get = |unit: &str| table.iter().find(|d| d.unit == unit).unwrap().clone();
+        // Tryte: 27가지 → 최소 5비트, 실제 1바이트
+        assert_eq!((get("Tryte").packed_bits, get("Tryte").binary_bits()), (8, 5));
+        assert_eq!(get("TritDWord").packed_bits, 24);
+        // 2bit/trit 매핑 효율 ≈ 79.2%
+        assert!((get("TritBuffer(729)").efficiency() - 0.792).abs() < 0.01);
+        assert!(get("TritMemory+ECC(729×6)").packed_bits > get("TritMemory(729×6)").packed_bits);
+        assert!(table.iter().all(|d| d.efficiency() <= 1.0));
+    }
+
+    #[test]
+    fn test_history_roundtrip_and_regression() {
+        let dir = std::env::temp_dir().join(format!("crowny-bench-{}", now_ms()));
+        let path = dir.join("bench.jsonl");
+        let mut report = run(200);
+        assert_eq!(report.timings.len(), 6);
+        assert!(report.timings.iter().all(|t| t.ternary_ns.is_finite() && t.binary_ns.is_finite()));
+        assert!(last_recorded(&path).is_none());
+
+        report.timings = vec![Timing { name: "x".into(), category: "arith", ternary_ns: 10.0, binary_ns: 5.0 }];
+        record(&path, &report).unwrap();
+        let prev = last_recorded(&path).unwrap();
+        assert_eq!(prev.get("schema").and_then(JsonValue::as_str), Some("crowny.bench"));
+
+        report.timings[0].ternary_ns = 15.0;
+        let deltas = report.compare(&prev);
+        assert_eq!(deltas.len(), 1);
+        assert!((deltas[0].change_pct() - 50.0).abs() < 1e-9 && deltas[0].is_regression());
+        report.host = "other".into();
+        assert!(report.compare(&prev).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // ─── 구성 요소 처리량 — 수동 실행 ───
+    // cargo test --release bench_ -- --ignored --nocapture
+
+    /// 합성 소스 — 줄을 그때그때 생성 (입력 자체도 메모리에 두지 않음)
+    struct Synthetic {
+        remaining: usize,
+        pending: Vec<u8>,
+        pos: usize,
+    }
+
+    impl std::io::Read for Synthetic {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pos == self.pending.len() {
+                if self.remaining == 0 {
+                    return Ok(0);
+                }
+                let n = self.remaining;
+                self.remaining -= 1;
+                self.pending = match n % 4 {
+                    0 => format!("넣어 {}\n", n),
+                    1 => format!("넣어 \"값 {}\" ; 문자열\n", n),
+                    2 => "더해\n".to_string(),
+                    _ => "; 주석 줄\n".to_string(),
+                }.into_bytes();
+                self.pos = 0;
+            }
+            let k = buf.len().min(self.pending.len() - self.pos);
+            buf[..k].copy_from_slice(&self.pending[self.pos..self.pos + k]);
+            self.pos += k;
+            Ok(k)
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_stream_1m_lines() {
+        const LINES: usize = 1_000_000;
+        let source = std::io::BufReader::new(Synthetic { remaining: LINES, pending: Vec::new(), pos: 0 });
+        let start = Instant::now();
+        let mut stream = StreamAssembler::new(source);
+        let mut operands = 0usize;
+        for inst in stream.by_ref() {
+            operands += inst.operands.len();
+        }
+        let elapsed = start.elapsed();
+        let p = *stream.progress();
+        assert_eq!(p.lines, LINES);
+        assert_eq!(p.instructions, LINES / 4 * 3);
+        assert_eq!(p.errors, 0);
+        assert!(p.peak_line_bytes <= StreamLimits::default().max_line_bytes);
+        println!("{}행 · {}명령어 · {}피연산자 · {:.1}MB · {:?} ({:.0}행/s) · 줄 버퍼 최대 {}B",
+            p.lines, p.instructions, operands, p.bytes as f64 / 1e6, elapsed,
+            p.lines as f64 / elapsed.as_secs_f64(), p.peak_line_bytes);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_ctp_serialize_throughput() {
+        const ROUNDS: usize = 200_000;
+        let mut payload = TritBuffer::new();
+        for v in 0..100 { payload.push_word6(v * 3 - 150); }
+        let msg = CtpMessage::request(payload);
+
+        let mut bytes = 0usize;
+        let owned = ns_per_op(ROUNDS, |_| {
+            let wire = msg.serialize();
+            bytes += wire.to_bytes().len();
+            bytes += CtpMessage::deserialize(&wire).map(|m| m.payload.len()).unwrap_or(0);
+        });
+
+        let mut wire = TritBuffer::new();
+        let mut out = vec![0u8; 256];
+        let mut view_bytes = 0usize;
+        let borrowed = ns_per_op(ROUNDS, |_| {
+            msg.serialize_into(&mut wire);
+            view_bytes += wire.write_into(&mut out).unwrap_or(0);
+            view_bytes += CtpMessage::parse(wire.as_slice()).map(|v| v.payload.len()).unwrap_or(0);
+        });
+
+        assert_eq!(bytes, view_bytes);
+        // 예열 포함 회수로 나눈 1회 바이트 → MB/s
+        let per_round = bytes as f64 / (ROUNDS + ROUNDS / 10) as f64;
+        let mb = |ns: f64| per_round / ns * 1e3;
+        println!("{}회 · 복사 {:.0}ns ({:.1}MB/s) · 재사용/뷰 {:.0}ns ({:.1}MB/s) · {:.2}배",
+            ROUNDS, owned, mb(owned), borrowed, mb(borrowed), owned / borrowed);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_disabled_debug_logging() {
+        const ROUNDS: usize = 5_000_000;
+        let mut log = TritEventLog::with_capacity(1024);
+        let key = String::from("task.state");
+
+        let baseline = ns_per_op(ROUNDS, |i| { black_box((&key, i)); });
+        let fast = ns_per_op(ROUNDS, |i| {
+            tlog_debug!(log, Category::State, "bench", "{}: {} → {}", key, i, i + 1);
+        });
+        let eager = ns_per_op(ROUNDS, |i| {
+            // 예전 방식 — 포맷 후 레벨 확인
+            log.log(EventBuilder::new(Category::State, &format!("{}: {} → {}", key, i, i + 1)).level(Level::Debug));
+        });
+
+        assert!(log.recent(1).is_empty());
+        println!("{}회 · 기준 {:.2}ns · tlog_debug!(꺼짐) {:.2}ns · 즉시 포맷 {:.2}ns · {:.0}배",
+            ROUNDS, baseline, fast, eager, eager / fast.max(0.01));
+        assert!(fast < eager);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_trit_compression() {
+        const ROUNDS: usize = 2_000;
+        let mut gen = Gen::new(7);
+        let from = |v: Vec<i8>| {
+            let mut b = TritBuffer::new();
+            for t in v { b.push_i8(t); }
+            b
+        };
+        let cases = [
+            ("반복", from([vec![0; 500], vec![1; 70], vec![-1; 3]].concat())),
+            // O가 대부분 (희소 페이로드)
+            ("치우침", from((0..900).map(|_| if gen.next_u64().is_multiple_of(8) { gen.range(-1, 1) as i8 } else { 0 }).collect())),
+            ("무작위", from((0..1001).map(|_| gen.range(-1, 1) as i8).collect())),
+        ];
+        for (name, buf) in cases {
+            let raw = buf.to_bytes().len();
+            for scheme in TritScheme::ALL {
+                let size = compress_trits(&buf.trits, scheme).len();
+                let per = ns_per_op(ROUNDS, |_| {
+                    let packed = compress_trits(&buf.trits, scheme);
+                    assert_eq!(TritBuffer::decompress(&packed).map(|b| b.len()), Ok(buf.len()));
+                });
+                println!("{:<6} {:<8} {:>5}B (2bit 패킹 {}B, {:.1}%) 왕복 {:.0}ns",
+                    name, scheme.name(), size, raw, size as f64 * 100.0 / raw as f64, per);
+            }
+        }
+    }
+}
@@ -0,0 +1,472 @@
+// ═══════════════════════════════════════════════════════════════
+// 자원 계량 · 과금 — 작업별 사용량을 테넌트 × 일 단위로 집계
+//
+//   자원: 사이클(TVM) · 저장 바이트(TritStore 쓰기) · LLM 토큰 · 네트워크 바이트
+//   테넌트: 주체의 '/' 앞부분 ("acme/alice" → acme, 없으면 주체 전체)
+//   하루: 지역 시간 기준 (기본 KST)
+//
+//   예산: 자원별 일 한도 + 경고 비율(기본 80%)
+//     P = 여유 · O = 경고 비율 도달 · T = 한도 초과 → CAR가 새 작업을 막음
+//   내보내기: CSV (date,tenant,tasks,cycles,...,state)
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::cron::{civil_from_days, DEFAULT_UTC_OFFSET_MIN};
+use crate::output::JsonObject;
+
+pub fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 작업별 기록 보관 개수
+const TASK_RECORDS: usize = 4096;
+/// 테넌트 보고서에 싣는 최근 작업 수
+const RECENT_TASKS: usize = 20;
+
+// ─────────────────────────────────────────────
+// 자원 · 사용량
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resource {
+    Cycles,
+    StoreBytes,
+    LlmTokens,
+    NetBytes,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 4] = [Resource::Cycles, Resource::StoreBytes, Resource::LlmTokens, Resource::NetBytes];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::Cycles => "cycles",
+            Resource::StoreBytes => "store_bytes",
+            Resource::LlmTokens => "llm_tokens",
+            Resource::NetBytes => "net_bytes",
+        }
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Resource {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::ALL.into_iter().find(|r| r.name() == s)
+            .ok_or_else(|| format!("알 수 없는 자원 '{}' (cycles/store_bytes/llm_tokens/net_bytes)", s))
+    }
+}
+
+/// 자원 사용량
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub cycles: u64,
+    pub store_bytes: u64,
+    pub llm_tokens: u64,
+    pub net_bytes: u64,
+}
+
+impl Usage {
+    pub fn of(resource: Resource, amount: u64) -> Self {
+        let mut u = Self::default();
+        *u.get_mut(resource) = amount;
+        u
+    }
+
+    pub fn get(&self, resource: Resource) -> u64 {
+        match resource {
+            Resource::Cycles => self.cycles,
+            Resource::StoreBytes => self.store_bytes,
+            Resource::LlmTokens => self.llm_tokens,
+            Resource::NetBytes => self.net_bytes,
+        }
+    }
+
+    fn get_mut(&mut self, resource: Resource) -> &mut u64 {
+        match resource {
+            Resource::Cycles => &mut self.cycles,
+            Resource::StoreBytes => &mut self.store_bytes,
+            Resource::LlmTokens => &mut self.llm_tokens,
+            Resource::NetBytes => &mut self.net_bytes,
+        }
+    }
+
+    pub fn add(&mut self, other: &Usage) {
+        for r in Resource::ALL {
+            let v = self.get_mut(r);
+            *v = v.saturating_add(other.get(r));
+        }
+    }
+
+    pub fn to_json(self) -> JsonObject {
+        Resource::ALL.into_iter().fold(JsonObject::new(), |j, r| j.int(r.name(), self.get(r) as i64))
+    }
+}
+
+/// 주체 → 테넌트
+pub fn tenant_of(subject: &str) -> &str {
+    subject.split('/').next().unwrap_or(subject)
+}
+
+// ─────────────────────────────────────────────
+// 예산
+// ─────────────────────────────────────────────
+
+/// 테넌트 일 예산 — 한도 없는 자원은 무제한
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Budget {
+    limits: HashMap<Resource, u64>,
+    /// 경고 비율 (%) — 0이면 기본 80
+    pub warn_pct: u8,
+}
+
+impl Budget {
+    pub const DEFAULT_WARN_PCT: u8 = 80;
+
+    pub fn new() -> Self {
+        Self { limits: HashMap::new(), warn_pct: Self::DEFAULT_WARN_PCT }
+    }
+
+    pub fn limit(mut self, resource: Resource, cap: u64) -> Self {
+        self.limits.insert(resource, cap);
+        self
+    }
+
+    pub fn warn_at(mut self, pct: u8) -> Self {
+        self.warn_pct = pct;
+        self
+    }
+
+    pub fn cap(&self, resource: Resource) -> Option<u64> {
+        self.limits.get(&resource).copied()
+    }
+
+    /// 사용량 판정 — 가장 나쁜 자원 기준 (트릿, 원인 자원)
+    pub fn judge(&self, usage: &Usage) -> (i8, Option<Resource>) {
+        let warn_pct = if self.warn_pct == 0 { Self::DEFAULT_WARN_PCT } else { self.warn_pct.min(100) } as u128;
+        let mut worst = (1, None);
+        for r in Resource::ALL {
+            let Some(cap) = self.cap(r) else { continue };
+            let used = usage.get(r);
+            let state = if used > cap {
+                -1
+            } else if used as u128 * 100 >= cap as u128 * warn_pct {
+                0
+            } else {
+                1
+            };
+            if state < worst.0 {
+                worst = (state, Some(r));
+            }
+        }
+        worst
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let limits = Resource::ALL.into_iter()
+            .filter_map(|r| self.cap(r).map(|c| (r, c)))
+            .fold(JsonObject::new(), |j, (r, c)| j.int(r.name(), c as i64));
+        JsonObject::new().object("limits", limits).int("warn_pct", self.warn_pct as i64)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 계량 장부
+// ─────────────────────────────────────────────
+
+/// 작업 하나의 사용량
+#[derive(Debug, Clone)]
+pub struct TaskUsage {
+    pub task_id: u64,
+    pub subject: String,
+    pub tenant: String,
+    pub day: i64,
+    pub usage: Usage,
+    pub state: i8,
+}
+
+/// 테넌트 × 일 집계
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DailyUsage {
+    pub tasks: u64,
+    pub usage: Usage,
+}
+
+/// 예산 상태 변화 (P→O, O→T 등)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetTransition {
+    pub tenant: String,
+    pub day: i64,
+    pub from: i8,
+    pub to: i8,
+    pub resource: Option<Resource>,
+}
+
+pub type SharedAccounting = Rc<RefCell<Accounting>>;
+
+pub struct Accounting {
+    daily: BTreeMap<(i64, String), DailyUsage>,
+    tasks: VecDeque<TaskUsage>,
+    budgets: HashMap<String, Budget>,
+    /// 마지막으로 본 테넌트 상태 (전이 감지용)
+    states: HashMap<String, (i64, i8)>,
+    pub transitions: Vec<BudgetTransition>,
+    pub utc_offset_min: i64,
+}
+
+impl Default for Accounting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Accounting {
+    pub fn new() -> Self {
+        Self {
+            daily: BTreeMap::new(),
+            tasks: VecDeque::new(),
+            budgets: HashMap::new(),
+            states: HashMap::new(),
+            transitions: Vec::new(),
+            utc_offset_min: DEFAULT_UTC_OFFSET_MIN,
+        }
+    }
+
+    pub fn shared(self) -> SharedAccounting {
+        Rc::new(RefCell::new(self))
+    }
+
+    /// epoch ms → 지역 일 번호
+    pub fn day_of(&self, ms: u64) -> i64 {
+        (ms as i64 / 60_000 + self.utc_offset_min).div_euclid(1440)
+    }
+
+    pub fn set_budget(&mut self, tenant: &str, budget: Budget) {
+        self.budgets.insert(tenant.to_string(), budget);
+    }
+
+    pub fn clear_budget(&mut self, tenant: &str) -> bool {
+        self.budgets.remove(tenant).is_some()
+    }
+
+    /// 작업 사용량 기록 → 테넌트 상태 (예산 없으면 P)
+    pub fn record_at(&mut self, task_id: u64, subject: &str, usage: Usage, state: i8, now: u64) -> i8 {
+        let tenant = tenant_of(subject).to_string();
+        let day = self.day_of(now);
+        let entry = self.daily.entry((day, tenant.clone())).or_default();
+        entry.tasks += 1;
+        entry.usage.add(&usage);
+        if self.tasks.len() == TASK_RECORDS {
+            self.tasks.pop_front();
+        }
+        self.tasks.push_back(TaskUsage { task_id, subject: subject.to_string(), tenant: tenant.clone(), day, usage, state });
+        self.tenant_state_at(&tenant, now)
+    }
+
+    pub fn record(&mut self, task_id: u64, subject: &str, usage: Usage, state: i8) -> i8 {
+        self.record_at(task_id, subject, usage, state, now_ms())
+    }
+
+    /// 오늘 사용량 기준 테넌트 상태 — 바뀌면 전이 기록
+    pub fn tenant_state_at(&mut self, tenant: &str, now: u64) -> i8 {
+        let day = self.day_of(now);
+        let (state, resource) = match self.budgets.get(tenant) {
+            Some(b) => b.judge(&self.usage(tenant, day)),
+            None => (1, None),
+        };
+        let prev = match self.states.get(tenant) {
+            Some((d, s)) if *d == day => *s,
+            _ => 1,
+        };
+        if prev != state {
+            self.transitions.push(BudgetTransition { tenant: tenant.to_string(), day, from: prev, to: state, resource });
+        }
+        self.states.insert(tenant.to_string(), (day, state));
+        state
+    }
+
+    /// 한도 초과(T) 테넌트는 새 작업 불가
+    pub fn allows_at(&mut self, subject: &str, now: u64) -> bool {
+        self.tenant_state_at(tenant_of(subject), now) != -1
+    }
+
+    pub fn allows(&mut self, subject: &str) -> bool {
+        self.allows_at(subject, now_ms())
+    }
+
+    pub fn usage(&self, tenant: &str, day: i64) -> Usage {
+        self.daily.get(&(day, tenant.to_string())).map(|d| d.usage).unwrap_or_default()
+    }
+
+    /// 일별 집계 (날짜 · 테넌트 순) — tenant가 None이면 전체
+    pub fn daily(&self, tenant: Option<&str>, days: std::ops::RangeInclusive<i64>) -> Vec<(i64, &str, DailyUsage)> {
+        self.daily.range((*days.start(), String::new())..(*days.end() + 1, String::new()))
+            .filter(|((_, t), _)| tenant.is_none_or(|want| want == t))
+            .map(|((d, t), u)| (*d, t.as_str(), *u))
+            .collect()
+    }
+
+    fn day_state(&self, tenant: &str, usage: &Usage) -> i8 {
+        self.budgets.get(tenant).map(|b| b.judge(usage).0).unwrap_or(1)
+    }
+
+    /// 과금 내보내기 — 헤더 + 테넌트·일 행
+    pub fn to_csv(&self, tenant: Option<&str>, days: std::ops::RangeInclusive<i64>) -> String {
+        let mut out = String::from("date,tenant,tasks,cycles,store_bytes,llm_tokens,net_bytes,state\n");
+        for (day, t, d) in self.daily(tenant, days) {
+            let u = d.usage;
+            out.push_str(&format!("{},{},{},{},{},{},{},{}\n", format_day(day), csv_field(t), d.tasks,
+                u.cycles, u.store_bytes, u.llm_tokens, u.net_bytes,
+                crate::output::trit_symbol(self.day_state(t, &u))));
+        }
+        out
+    }
+
+    /// 테넌트 보고서 — 오늘 사용량 · 예산 · 상태 · 최근 일별 · 최근 작업
+    pub fn tenant_json(&mut self, tenant: &str, now: u64) -> JsonObject {
+        let state = self.tenant_state_at(tenant, now);
+        let today = self.day_of(now);
+        let days = self.daily(Some(tenant), today - 30..=today).into_iter()
+            .map(|(d, _, u)| JsonObject::new().str("date", &format_day(d)).int("tasks", u.tasks as i64).object("usage", u.usage.to_json()))
+            .collect();
+        let recent = self.tasks.iter().rev()
+            .filter(|t| t.tenant == tenant)
+            .take(RECENT_TASKS)
+            .map(|t| JsonObject::new().int("task_id", t.task_id as i64).str("subject", &t.subject)
+                .str("date", &format_day(t.day)).trit("state", t.state).object("usage", t.usage.to_json()))
+            .collect();
+        let mut j = JsonObject::schema("crowny.accounting")
+            .str("tenant", tenant)
+            .trit("state", state)
+            .object("today", self.usage(tenant, today).to_json())
+            .objects("days", days)
+            .objects("recent", recent);
+        if let Some(b) = self.budgets.get(tenant) {
+            j = j.object("budget", b.to_json());
+        }
+        j
+    }
+}
+
+/// 일 번호 → YYYY-MM-DD
+pub fn format_day(day: i64) -> String {
+    let (y, m, d) = civil_from_days(day);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// YYYY-MM-DD → 일 번호
+pub fn parse_day(s: &str) -> Result<i64, String> {
+    let bad = || format!("날짜는 YYYY-MM-DD: '{}'", s);
+    let mut parts = s.splitn(3, '-').map(|p| p.parse::<i64>().map_err(|_| bad()));
+    let (y, m, d) = (parts.next().ok_or_else(bad)??, parts.next().ok_or_else(bad)??, parts.next().ok_or_else(bad)??);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return Err(bad());
+    }
+    // civil_from_days의 역산
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let day = era * 146_097 + doe - 719_468;
+    if format_day(day) != s {
+        return Err(bad());
+    }
+    Ok(day)
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: u64 = 86_400_000;
+
+    #[test]
+    fn test_usage_aggregates_per_tenant_per_day() {
+        let mut acc = Accounting::new();
+        let t0 = parse_day("2026-10-15").unwrap() as u64 * DAY_MS; // KST 09:00
+        acc.record_at(1, "acme/alice", Usage { cycles: 100, net_bytes: 40, ..Usage::default() }, 1, t0);
+        acc.record_at(2, "acme/bob", Usage::of(Resource::LlmTokens, 70), 1, t0 + 1000);
+        acc.record_at(3, "globex", Usage::of(Resource::StoreBytes, 9), -1, t0);
+        // KST 자정 이후 → 다음 날
+        acc.record_at(4, "acme/alice", Usage::of(Resource::Cycles, 5), 1, t0 + 15 * 3_600_000);
+
+        let day = acc.day_of(t0);
+        assert_eq!(format_day(day), "2026-10-15");
+        let acme = acc.usage("acme", day);
+        assert_eq!((acme.cycles, acme.llm_tokens, acme.net_bytes), (100, 70, 40));
+        assert_eq!(acc.usage("acme", day + 1).cycles, 5);
+        assert_eq!(acc.daily(Some("acme"), day..=day + 1).len(), 2);
+        assert_eq!(acc.daily(None, day..=day).len(), 2);
+        assert_eq!(acc.tasks.len(), 4);
+
+        let csv = acc.to_csv(None, day..=day + 1);
+        assert_eq!(csv.lines().collect::<Vec<_>>(), vec![
+            "date,tenant,tasks,cycles,store_bytes,llm_tokens,net_bytes,state",
+            "2026-10-15,acme,2,100,0,70,40,P",
+            "2026-10-15,globex,1,0,9,0,0,P",
+            "2026-10-16,acme,1,5,0,0,0,P",
+        ]);
+        assert_eq!(tenant_of("solo"), "solo");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+
+    #[test]
+    fn test_budget_warns_then_blocks() {
+        let mut acc = Accounting::new();
+        let now = 1_792_000_000_000;
+        acc.set_budget("acme", Budget::new().limit(Resource::Cycles, 1000).limit(Resource::LlmTokens, 100));
+        assert_eq!(acc.record_at(1, "acme/a", Usage::of(Resource::Cycles, 500), 1, now), 1);
+        assert_eq!(acc.record_at(2, "acme/a", Usage::of(Resource::LlmTokens, 85), 1, now), 0);
+        assert!(acc.allows_at("acme/b", now));
+        assert_eq!(acc.record_at(3, "acme/b", Usage::of(Resource::Cycles, 600), 1, now), -1);
+        assert!(!acc.allows_at("acme/a", now));
+        assert!(acc.allows_at("other", now));
+        let steps: Vec<_> = acc.transitions.iter().map(|t| (t.from, t.to, t.resource)).collect();
+        assert_eq!(steps, vec![(1, 0, Some(Resource::LlmTokens)), (0, -1, Some(Resource::Cycles))]);
+        // 다음 날은 새 한도
+        assert!(acc.allows_at("acme/a", now + DAY_MS));
+        assert_eq!(acc.transitions.len(), 2);
+        let day = acc.day_of(now);
+        assert!(acc.to_csv(Some("acme"), day..=day).ends_with(",T\n"));
+        let json = acc.tenant_json("acme", now).build();
+        assert!(json.contains("\"state\":\"T\""), "{}", json);
+        assert!(json.contains("\"cycles\":1000"));
+        assert!(json.contains("\"subject\":\"acme/b\""), "{}", json);
+        assert!(!json.contains("\"subject\":\"other"));
+    }
+
+    #[test]
+    fn test_day_parsing_and_resource_names() {
+        for s in ["1970-01-01", "2024-02-29", "2026-10-15", "2099-12-31"] {
+            assert_eq!(format_day(parse_day(s).unwrap()), s);
+        }
+        assert_eq!(parse_day("1970-01-02"), Ok(1));
+        for bad in ["2026-02-30", "2026-13-01", "20261015", "2026-1-x"] {
+            assert!(parse_day(bad).is_err(), "{}", bad);
+        }
+        for r in Resource::ALL {
+            assert_eq!(r.name().parse::<Resource>(), Ok(r));
+        }
+        assert!("gpu".parse::<Resource>().is_err());
+    }
+}
@@ -24,6 +24,9 @@
 ///!
 ///! 이 모듈은 두 모드를 모두 지원하여
 ///! FPGA 전환 시 상위 코드 변경 불필요.
+///!
+///! 신뢰성: TritMemory ECC 모드 — 워드(6 trit)당 패리티 3 trit
+///!   [9,6] 3진 해밍 부호, 단일 trit 오류 정정 · 장애 주입으로 정정률 분석
 
 // ─────────────────────────────────────────────
 // 물리 매핑 상수
@@ -184,6 +187,15 @@ impl TritWord {
         let cmd    = (self.trits[1] * 3 + self.trits[0] + 4) as u8;
         (sector, group, cmd)
     }
+
+    /// 네이티브 산술용 Word6 (트릿 배치 동일 — 변환에 i16을 거치지 않음)
+    pub fn to_word6(self) -> crate::trit::Word6 {
+        crate::trit::Word6::new(self.trits.map(crate::trit::Trit::from_i8))
+    }
+
+    pub fn from_word6(w: &crate::trit::Word6) -> Self {
+        Self { trits: w.trits.map(crate::trit::Trit::to_i8) }
+    }
 }
 
 impl std::fmt::Display for TritWord {
@@ -312,6 +324,109 @@ impl FpgaRegisterBank {
     }
 }
 
+// ─────────────────────────────────────────────
+// 3진 ECC — 단축 해밍 [9,6] over GF(3)
+// ─────────────────────────────────────────────
+
+/// ECC 보호 단위 (TritWord)
+pub const ECC_WORD: usize = 6;
+/// 워드당 패리티 trit
+pub const ECC_PARITY: usize = 3;
+
+/// 데이터 trit별 검사행렬 열 — 패리티 열(단위벡터)과 서로 스칼라배가 아님
+/// → 어떤 위치의 단일 trit 오류(±1)든 신드롬이 유일
+const ECC_COLUMNS: [[i8; 3]; ECC_WORD] = [
+    [1, 1, 0], [1, 0, 1], [0, 1, 1], [1, 1, 1], [1, 1, 2], [1, 2, 1],
+];
+
+/// 균형 trit → GF(3) (T=-1 ≡ 2)
+fn gf3(v: i32) -> i8 {
+    v.rem_euclid(3) as i8
+}
+
+/// GF(3) → 균형 trit
+fn balanced(v: i8) -> i8 {
+    if v == 2 { -1 } else { v }
+}
+
+fn ecc_column(pos: usize) -> [i8; 3] {
+    match pos {
+        0..ECC_WORD => ECC_COLUMNS[pos],
+        _ => {
+            let mut unit = [0; 3];
+            unit[pos - ECC_WORD] = 1;
+            unit
+        }
+    }
+}
+
+/// 데이터 6 trit → 패리티 3 trit (H·[d|p] = 0)
+pub fn ecc_parity(data: &[i8; ECC_WORD]) -> [i8; ECC_PARITY] {
+    std::array::from_fn(|r| {
+        let s: i32 = (0..ECC_WORD).map(|j| ECC_COLUMNS[j][r] as i32 * data[j] as i32).sum();
+        balanced(gf3(-s))
+    })
+}
+
+/// 워드 검사 결과 (P 정상 · O 정정 · T 정정 불가)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EccCheck {
+    Clean,
+    /// 위치 0~5 데이터, 6~8 패리티
+    Corrected { position: usize },
+    Uncorrectable,
+}
+
+/// 신드롬 계산 후 단일 trit 오류를 제자리 정정
+/// 2개 이상 오류는 정정 불가로 검출되거나 (해밍 거리 3의 한계로) 오정정될 수 있다
+pub fn ecc_decode(data: &mut [i8; ECC_WORD], parity: &mut [i8; ECC_PARITY]) -> EccCheck {
+    let syndrome: [i8; 3] = std::array::from_fn(|r| {
+        let s: i32 = (0..ECC_WORD).map(|j| ECC_COLUMNS[j][r] as i32 * data[j] as i32).sum();
+        gf3(s + parity[r] as i32)
+    });
+    if syndrome == [0; 3] {
+        return EccCheck::Clean;
+    }
+    for pos in 0..ECC_WORD + ECC_PARITY {
+        let col = ecc_column(pos);
+        for e in [1, 2] {
+            if (0..3).all(|r| gf3(col[r] as i32 * e) == syndrome[r]) {
+                let cell = if pos < ECC_WORD { &mut data[pos] } else { &mut parity[pos - ECC_WORD] };
+                *cell = balanced(gf3(*cell as i32 - e));
+                return EccCheck::Corrected { position: pos };
+            }
+        }
+    }
+    EccCheck::Uncorrectable
+}
+
+/// ECC 통계 — 신뢰성 분석용
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EccStats {
+    /// 검사한 워드 수
+    pub checked: u64,
+    /// 정정한 단일 trit 오류
+    pub corrected: u64,
+    /// 정정 불가 워드 (워드당 한 번만 집계)
+    pub uncorrectable: u64,
+    /// 주입한 trit 반전
+    pub injected: u64,
+}
+
+impl std::fmt::Display for EccStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "검사:{} 정정:{} 정정불가:{} 주입:{}",
+            self.checked, self.corrected, self.uncorrectable, self.injected)
+    }
+}
+
+struct EccState {
+    parity: Vec<i8>,
+    stats: EccStats,
+    /// 이미 정정 불가로 보고된 워드 (다시 쓰면 해제)
+    poisoned: std::collections::HashSet<usize>,
+}
+
 // ─────────────────────────────────────────────
 // Memory Map (FPGA 메모리 모델)
 // ─────────────────────────────────────────────
@@ -319,11 +434,15 @@ impl FpgaRegisterBank {
 /// FPGA 메모리 영역
 /// 3^12 = 531441 trit-addressable locations (12-trit 주소)
 /// 실제 2진 메모리: 531441 × 3 bytes = ~1.5 MB
+///
+/// ECC 모드: 6-trit 워드마다 패리티 3 trit, 읽기 시 검사·정정 후 되쓰기
 pub struct TritMemory {
     /// 메모리 (각 위치 = TritDWord = 12 trits)
     data: Vec<i8>,
     /// 크기 (trit 단위)
     size: usize,
+    /// ECC 패리티 · 통계 (선택)
+    ecc: Option<EccState>,
 }
 
 impl TritMemory {
@@ -332,28 +451,114 @@ impl TritMemory {
         Self {
             data: vec![0i8; size_trits],
             size: size_trits,
+            ecc: None,
+        }
+    }
+
+    /// ECC 모드로 생성
+    pub fn with_ecc(size_trits: usize) -> Self {
+        let mut mem = Self::new(size_trits);
+        mem.enable_ecc();
+        mem
+    }
+
+    /// ECC 켜기 — 현재 내용으로 패리티 생성
+    pub fn enable_ecc(&mut self) {
+        let mut parity = vec![0i8; self.word_count() * ECC_PARITY];
+        for w in 0..self.word_count() {
+            parity[w * ECC_PARITY..(w + 1) * ECC_PARITY].copy_from_slice(&ecc_parity(&self.word_data(w)));
         }
+        self.ecc = Some(EccState { parity, stats: EccStats::default(), poisoned: Default::default() });
+    }
+
+    pub fn ecc_stats(&self) -> Option<EccStats> {
+        self.ecc.as_ref().map(|e| e.stats)
     }
 
-    /// 3진 주소로 단일 trit 읽기
-    pub fn read_trit(&self, addr: usize) -> i8 {
+    fn word_count(&self) -> usize {
+        self.size.div_ceil(ECC_WORD)
+    }
+
+    /// 워드 데이터 (메모리 끝을 넘는 자리는 O)
+    fn word_data(&self, w: usize) -> [i8; ECC_WORD] {
+        std::array::from_fn(|i| self.data.get(w * ECC_WORD + i).copied().unwrap_or(0))
+    }
+
+    /// 워드 검사·정정 (ECC 꺼져 있으면 Clean)
+    fn check_word(&mut self, w: usize) -> EccCheck {
+        let mut data = self.word_data(w);
+        let size = self.size;
+        let Some(ecc) = &mut self.ecc else { return EccCheck::Clean };
+        let range = w * ECC_PARITY..(w + 1) * ECC_PARITY;
+        let mut parity = [0i8; ECC_PARITY];
+        parity.copy_from_slice(&ecc.parity[range.clone()]);
+
+        let mut check = ecc_decode(&mut data, &mut parity);
+        // 메모리 밖 자리로의 "정정"은 다중 오류의 오정정
+        if let EccCheck::Corrected { position } = check {
+            if position < ECC_WORD && w * ECC_WORD + position >= size {
+                check = EccCheck::Uncorrectable;
+            }
+        }
+        ecc.stats.checked += 1;
+        match check {
+            EccCheck::Clean => {}
+            EccCheck::Corrected { .. } => {
+                ecc.stats.corrected += 1;
+                ecc.parity[range].copy_from_slice(&parity);
+                for (i, t) in data.iter().enumerate() {
+                    if let Some(cell) = self.data.get_mut(w * ECC_WORD + i) {
+                        *cell = *t;
+                    }
+                }
+            }
+            EccCheck::Uncorrectable => {
+                if ecc.poisoned.insert(w) {
+                    ecc.stats.uncorrectable += 1;
+                }
+            }
+        }
+        check
+    }
+
+    /// addr..addr+len 이 걸친 워드를 각각 한 번 검사
+    fn check_range(&mut self, addr: usize, len: usize) {
+        if self.ecc.is_none() || addr >= self.size {
+            return;
+        }
+        let last = (addr + len).min(self.size) - 1;
+        for w in addr / ECC_WORD..=last / ECC_WORD {
+            self.check_word(w);
+        }
+    }
+
+    fn reencode(&mut self, w: usize) {
+        let parity = ecc_parity(&self.word_data(w));
+        if let Some(ecc) = &mut self.ecc {
+            ecc.parity[w * ECC_PARITY..(w + 1) * ECC_PARITY].copy_from_slice(&parity);
+            ecc.poisoned.remove(&w);
+        }
+    }
+
+    fn raw_trit(&self, addr: usize) -> i8 {
         if addr < self.size { self.data[addr] } else { 0 }
     }
 
-    /// 3진 주소로 단일 trit 쓰기
+    /// 3진 주소로 단일 trit 쓰기 (ECC 모드: 읽기-수정-쓰기)
     pub fn write_trit(&mut self, addr: usize, val: i8) {
         if addr < self.size {
+            self.check_range(addr, 1);
             self.data[addr] = val.clamp(-1, 1);
+            if self.ecc.is_some() {
+                self.reencode(addr / ECC_WORD);
+            }
         }
     }
 
     /// TritWord(6 trits) 읽기
-    pub fn read_word(&self, addr: usize) -> TritWord {
-        let mut trits = [0i8; 6];
-        for i in 0..6 {
-            trits[i] = self.read_trit(addr + i);
-        }
-        TritWord { trits }
+    pub fn read_word(&mut self, addr: usize) -> TritWord {
+        self.check_range(addr, 6);
+        TritWord { trits: std::array::from_fn(|i| self.raw_trit(addr + i)) }
     }
 
     /// TritWord(6 trits) 쓰기
@@ -364,12 +569,9 @@ impl TritMemory {
     }
 
     /// TritDWord(12 trits) 읽기
-    pub fn read_dword(&self, addr: usize) -> TritDWord {
-        let mut trits = [0i8; 12];
-        for i in 0..12 {
-            trits[i] = self.read_trit(addr + i);
-        }
-        TritDWord { trits }
+    pub fn read_dword(&mut self, addr: usize) -> TritDWord {
+        self.check_range(addr, 12);
+        TritDWord { trits: std::array::from_fn(|i| self.raw_trit(addr + i)) }
     }
 
     /// TritDWord(12 trits) 쓰기
@@ -379,21 +581,75 @@ impl TritMemory {
         }
     }
 
+    /// 전체 워드 순회 검사 (스크러빙)
+    pub fn scrub(&mut self) -> Option<EccStats> {
+        self.ecc.as_ref()?;
+        for w in 0..self.word_count() {
+            self.check_word(w);
+        }
+        self.ecc_stats()
+    }
+
+    /// 장애 주입 (chaos) — 무작위 위치 trit를 다른 값으로 반전
+    /// ECC 모드면 패리티 trit도 대상. 주입한 위치(데이터 주소, 패리티는 size+인덱스) 반환
+    pub fn inject_faults(&mut self, seed: u64, count: usize) -> Vec<usize> {
+        let mut g = crate::conformance::Gen::new(seed);
+        let parity_len = self.ecc.as_ref().map_or(0, |e| e.parity.len());
+        let total = self.size + parity_len;
+        if total == 0 {
+            return Vec::new();
+        }
+        (0..count).map(|_| {
+            let at = g.range(0, total as i64 - 1) as usize;
+            self.flip(at, g.range(1, 2) as i32);
+            at
+        }).collect()
+    }
+
+    /// 한 워드(데이터+패리티 9 trit)의 서로 다른 자리 n개 반전 — 다중 오류 분석용
+    pub fn inject_word_faults(&mut self, w: usize, n: usize, seed: u64) {
+        let mut g = crate::conformance::Gen::new(seed);
+        let mut positions: Vec<usize> = (0..ECC_WORD + ECC_PARITY)
+            .filter(|&p| p >= ECC_WORD || w * ECC_WORD + p < self.size)
+            .collect();
+        for _ in 0..n.min(positions.len()) {
+            let p = positions.swap_remove(g.range(0, positions.len() as i64 - 1) as usize);
+            let at = if p < ECC_WORD { w * ECC_WORD + p } else { self.size + w * ECC_PARITY + p - ECC_WORD };
+            self.flip(at, g.range(1, 2) as i32);
+        }
+    }
+
+    /// 저장 위치 직접 변경 (ECC 우회)
+    fn flip(&mut self, at: usize, delta: i32) {
+        let cell = if at < self.size {
+            self.data.get_mut(at)
+        } else {
+            self.ecc.as_mut().and_then(|e| e.parity.get_mut(at - self.size))
+        };
+        if let Some(cell) = cell {
+            *cell = balanced(gf3(*cell as i32 + delta));
+            if let Some(ecc) = &mut self.ecc {
+                ecc.stats.injected += 1;
+            }
+        }
+    }
+
     /// 사용량
     pub fn used_trits(&self) -> usize {
         self.data.iter().filter(|&&t| t != 0).count()
     }
 
-    /// 2진 환산 바이트 (패킹 시)
+    /// 2진 환산 바이트 (패킹 시, ECC 패리티 포함)
     pub fn packed_bytes(&self) -> usize {
-        (self.size + 3) / 4  // 4 trits per byte
+        let parity = self.ecc.as_ref().map_or(0, |e| e.parity.len());
+        (self.size + parity).div_ceil(4)  // 4 trits per byte
     }
 
     pub fn dump(&self, start: usize, count: usize) {
         println!("╔══ 3진 메모리 (시작: {}, 표시: {}) ══╗", start, count);
         let end = (start + count).min(self.size);
         for addr in (start..end).step_by(6) {
-            let word = self.read_word(addr);
+            let word = TritWord { trits: std::array::from_fn(|i| self.raw_trit(addr + i)) };
             let val = word.to_decimal();
             if val != 0 {
                 println!("║ [{:06}] {} = {}", addr, word, val);
@@ -403,10 +659,40 @@ impl TritMemory {
             self.used_trits(), self.size,
             self.used_trits() * 100 / self.size.max(1));
         println!("║ 2진 환산: {} bytes (packed)", self.packed_bytes());
+        if let Some(stats) = self.ecc_stats() {
+            println!("║ ECC: {}", stats);
+        }
         println!("╚═══════════════════════════════════════════╝");
     }
 }
 
+/// 신뢰성 시험 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EccTrial {
+    pub stats: EccStats,
+    /// 스크러빙 후에도 데이터가 원본과 다른 워드 (오정정 포함)
+    pub corrupted_words: usize,
+}
+
+/// 워드마다 n개 trit 반전 후 스크러빙 — 오류 개수별 정정률 분석
+pub fn ecc_trial(words: usize, flips_per_word: usize, seed: u64) -> EccTrial {
+    let mut mem = TritMemory::new(words * ECC_WORD);
+    let mut g = crate::conformance::Gen::new(seed);
+    for addr in 0..words * ECC_WORD {
+        mem.data[addr] = g.trit();
+    }
+    let original = mem.data.clone();
+    mem.enable_ecc();
+    for w in 0..words {
+        mem.inject_word_faults(w, flips_per_word, g.next_u64());
+    }
+    let stats = mem.scrub().unwrap_or_default();
+    let corrupted_words = (0..words)
+        .filter(|&w| mem.data[w * ECC_WORD..(w + 1) * ECC_WORD] != original[w * ECC_WORD..(w + 1) * ECC_WORD])
+        .count();
+    EccTrial { stats, corrupted_words }
+}
+
 // ─────────────────────────────────────────────
 // FPGA Transition Roadmap
 // ─────────────────────────────────────────────
@@ -527,6 +813,71 @@ mod tests {
         assert_eq!(read.to_decimal(), 42);
     }
 
+    #[test]
+    fn test_ecc_corrects_every_single_trit_error() {
+        for v in [-364, -1, 0, 42, 364] {
+            let word = TritWord::from_decimal(v).trits;
+            let parity = ecc_parity(&word);
+            for pos in 0..ECC_WORD + ECC_PARITY {
+                for delta in [1, 2] {
+                    let (mut d, mut p) = (word, parity);
+                    let cell = if pos < ECC_WORD { &mut d[pos] } else { &mut p[pos - ECC_WORD] };
+                    *cell = balanced(gf3(*cell as i32 + delta));
+                    assert_eq!(ecc_decode(&mut d, &mut p), EccCheck::Corrected { position: pos });
+                    assert_eq!((d, p), (word, parity));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_trit_memory_ecc_mode() {
+        let mut mem = TritMemory::with_ecc(729 * 6);
+        mem.write_word(0, &TritWord::from_decimal(42));
+        mem.write_dword(100, &TritDWord::from_decimal(-12345));
+
+        // 단일 반전 → 읽기 시 정정·되쓰기
+        mem.data[2] = balanced(gf3(mem.data[2] as i32 + 1));
+        assert_eq!(mem.read_word(0).to_decimal(), 42);
+        assert_eq!(mem.read_word(0).to_decimal(), 42);
+        assert_eq!(mem.ecc_stats().map(|s| s.corrected), Some(1));
+
+        // 한 워드 이중 반전 → 정정 불가 또는 오정정 (해밍 거리 3)
+        mem.inject_word_faults(17, 2, 7);
+        mem.read_dword(100);
+        let stats = mem.scrub().unwrap();
+        assert_eq!(stats.injected, 2);
+        assert!(stats.uncorrectable + stats.corrected >= 2);
+
+        // ECC 없는 메모리는 조용히 손상
+        let mut plain = TritMemory::new(12);
+        plain.write_word(0, &TritWord::from_decimal(42));
+        plain.data[2] = balanced(gf3(plain.data[2] as i32 + 1));
+        assert_ne!(plain.read_word(0).to_decimal(), 42);
+        assert_eq!(plain.ecc_stats(), None);
+    }
+
+    #[test]
+    fn test_ecc_chaos_reliability() {
+        // 워드당 단일 오류 → 전부 정정
+        let single = ecc_trial(243, 1, 3);
+        assert_eq!(single.stats.corrected, 243);
+        assert_eq!((single.stats.uncorrectable, single.corrupted_words), (0, 0));
+        // 워드당 이중 오류 → 정정 불가로 검출되거나 오정정
+        let double = ecc_trial(243, 2, 3);
+        assert_eq!(double.stats.injected, 486);
+        assert!(double.stats.uncorrectable > 0);
+        assert!(double.corrupted_words > 0);
+
+        // 무작위 장애 주입 + 스크러빙
+        let mut mem = TritMemory::with_ecc(729);
+        let hits = mem.inject_faults(9, 20);
+        assert_eq!(hits.len(), 20);
+        let stats = mem.scrub().unwrap();
+        assert_eq!(stats.checked, mem.word_count() as u64);
+        assert!(stats.corrected > 0);
+    }
+
     #[test]
     fn test_fpga_registers() {
         let mut bank = FpgaRegisterBank::new();
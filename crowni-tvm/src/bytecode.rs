@@ -7,8 +7,8 @@
 ///! 파일 구조:
 ///! ┌──────────────────────────────────────────┐
 ///! │ Magic:    0xCB 0x33 0xCB 0x33  (4 bytes) │
-///! │ Version:  0x01                 (1 byte)  │
-///! │ Flags:    0x00                 (1 byte)  │
+///! │ Version:  0x02                 (1 byte)  │
+///! │ Flags:    0x01 = 체크섬         (1 byte)  │
 ///! │ InstCount: u32 LE             (4 bytes)  │
 ///! │ ─── 명령어 블록 (N개) ─────────────────── │
 ///! │  Sector:  u8                   (1 byte)  │
@@ -16,8 +16,12 @@
 ///! │  Command: u8                   (1 byte)  │
 ///! │  OpCount: u8                   (1 byte)  │
 ///! │  Operand[0..N]: tagged values            │
+///! │ ─── v2 ───────────────────────────────── │
+///! │ Checksum: FNV-1a u32 LE        (4 bytes) │
 ///! └──────────────────────────────────────────┘
 ///!
+///! v1 파일(체크섬 없음)은 migrations 모듈이 v2로 올린다.
+///!
 ///! 피연산자 태그:
 ///!   0x00 = None
 ///!   0x01 = Int(i64)     → 8 bytes LE
@@ -26,6 +30,9 @@
 ///!   0x04 = Trit(i8)     → 1 byte
 ///!   0x05 = Str(len+data)→ u16 LE + UTF-8
 ///!   0x06 = Nil          → 0 bytes
+///!   0x07 = Addr(u64)    → 8 bytes LE (어셈블러가 푼 라벨)
+///!
+///! 로드 시 검사: 매직 · 버전 · 체크섬(v2 필수) · 명령어 수 · opcode 주소 범위
 
 use crate::vm::Instruction;
 use crate::opcode::OpcodeAddr;
@@ -34,7 +41,10 @@ use crate::trit::Trit;
 
 /// 매직 넘버: CB33 CB33 (Crowny Balanced 3-3)
 const MAGIC: [u8; 4] = [0xCB, 0x33, 0xCB, 0x33];
-const VERSION: u8 = 1;
+pub const VERSION: u8 = 2;
+/// 헤더 플래그: 파일 끝 4바이트 체크섬
+const FLAG_CHECKSUM: u8 = 0x01;
+const HEADER_LEN: usize = 10;
 
 // 태그 상수
 const TAG_NONE: u8 = 0x00;
@@ -44,6 +54,7 @@ const TAG_BOOL: u8 = 0x03;
 const TAG_TRIT: u8 = 0x04;
 const TAG_STR: u8 = 0x05;
 const TAG_NIL: u8 = 0x06;
+const TAG_ADDR: u8 = 0x07;
 
 /// TVM 프로그램 → .크라운 바이트코드 직렬화
 pub fn serialize(program: &[Instruction]) -> Vec<u8> {
@@ -52,7 +63,7 @@ pub fn serialize(program: &[Instruction]) -> Vec<u8> {
     // Header
     bytes.extend_from_slice(&MAGIC);
     bytes.push(VERSION);
-    bytes.push(0x00); // flags
+    bytes.push(FLAG_CHECKSUM);
 
     // Instruction count
     let count = program.len() as u32;
@@ -60,62 +71,211 @@ pub fn serialize(program: &[Instruction]) -> Vec<u8> {
 
     // Instructions
     for inst in program {
-        // Opcode address (3 bytes)
-        bytes.push(inst.addr.sector);
-        bytes.push(inst.addr.group);
-        bytes.push(inst.addr.command);
+        serialize_instruction(&mut bytes, inst);
+    }
+
+    let sum = checksum(&bytes);
+    bytes.extend_from_slice(&sum.to_le_bytes());
+    bytes
+}
+
+fn serialize_instruction(bytes: &mut Vec<u8>, inst: &Instruction) {
+    // Opcode address (3 bytes)
+    bytes.push(inst.addr.sector);
+    bytes.push(inst.addr.group);
+    bytes.push(inst.addr.command);
+
+    // Operand count
+    bytes.push(inst.operands.len() as u8);
+
+    // Operands
+    for op in &inst.operands {
+        serialize_value(bytes, op);
+    }
+}
+
+const FNV_OFFSET: u32 = 0x811c_9dc5;
 
-        // Operand count
-        bytes.push(inst.operands.len() as u8);
+/// FNV-1a 32비트 (이어서 계산 가능)
+fn fnv_update(hash: u32, data: &[u8]) -> u32 {
+    data.iter().fold(hash, |h, b| (h ^ *b as u32).wrapping_mul(0x0100_0193))
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    fnv_update(FNV_OFFSET, data)
+}
+
+// ═══════════════════════════════════════
+// 스트리밍 쓰기 — 프로그램 전체를 메모리에 두지 않는다
+// 명령어 블록은 <출력>.body.tmp에 쌓고, finish()에서
+// 헤더(개수 확정) + 본문 복사 + 체크섬으로 최종 파일을 만든다
+// ═══════════════════════════════════════
+
+pub struct StreamWriter {
+    path: std::path::PathBuf,
+    body_path: std::path::PathBuf,
+    body: std::io::BufWriter<std::fs::File>,
+    scratch: Vec<u8>,
+    count: u32,
+}
+
+impl StreamWriter {
+    pub fn create(path: &std::path::Path) -> Result<Self, String> {
+        let mut body_path = path.as_os_str().to_os_string();
+        body_path.push(".body.tmp");
+        let body_path = std::path::PathBuf::from(body_path);
+        let file = std::fs::File::create(&body_path)
+            .map_err(|e| format!("{}: {}", body_path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            body_path,
+            body: std::io::BufWriter::new(file),
+            scratch: Vec::new(),
+            count: 0,
+        })
+    }
+
+    pub fn push(&mut self, inst: &Instruction) -> Result<(), String> {
+        use std::io::Write;
+        self.count = self.count.checked_add(1).ok_or("명령어 수 초과 (u32)")?;
+        self.scratch.clear();
+        serialize_instruction(&mut self.scratch, inst);
+        self.body.write_all(&self.scratch).map_err(|e| format!("{}: {}", self.body_path.display(), e))
+    }
 
-        // Operands
-        for op in &inst.operands {
-            serialize_value(&mut bytes, op);
+    /// 최종 파일 작성 — 실패해도 임시 본문은 지운다
+    pub fn finish(self) -> Result<BytecodeInfo, String> {
+        let body_path = self.body_path.clone();
+        let result = self.write_final();
+        let _ = std::fs::remove_file(&body_path);
+        result
+    }
+
+    fn write_final(self) -> Result<BytecodeInfo, String> {
+        use std::io::{Read, Write};
+        let err = |p: &std::path::Path, e: std::io::Error| format!("{}: {}", p.display(), e);
+        self.body.into_inner().map_err(|e| err(&self.body_path, e.into_error()))?;
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(&MAGIC);
+        header.push(VERSION);
+        header.push(FLAG_CHECKSUM);
+        header.extend_from_slice(&self.count.to_le_bytes());
+
+        let out = std::fs::File::create(&self.path).map_err(|e| err(&self.path, e))?;
+        let mut out = std::io::BufWriter::new(out);
+        out.write_all(&header).map_err(|e| err(&self.path, e))?;
+        let mut hash = fnv_update(FNV_OFFSET, &header);
+        let mut size = header.len();
+
+        let mut body = std::fs::File::open(&self.body_path).map_err(|e| err(&self.body_path, e))?;
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            let n = body.read(&mut buf).map_err(|e| err(&self.body_path, e))?;
+            if n == 0 {
+                break;
+            }
+            hash = fnv_update(hash, &buf[..n]);
+            out.write_all(&buf[..n]).map_err(|e| err(&self.path, e))?;
+            size += n;
         }
+        out.write_all(&hash.to_le_bytes()).map_err(|e| err(&self.path, e))?;
+        out.flush().map_err(|e| err(&self.path, e))?;
+        size += 4;
+
+        let count = self.count as usize;
+        Ok(BytecodeInfo {
+            version: VERSION,
+            instruction_count: count,
+            byte_size: size,
+            avg_bytes_per_inst: if count > 0 { size as f32 / count as f32 } else { 0.0 },
+        })
     }
+}
 
-    bytes
+/// 헤더에서 버전만 읽기 (매직 불일치면 None)
+pub fn detect_version(data: &[u8]) -> Option<u32> {
+    if data.len() < HEADER_LEN || data[0..4] != MAGIC {
+        return None;
+    }
+    Some(data[4] as u32)
 }
 
 /// .크라운 바이트코드 → TVM 프로그램 역직렬화
 pub fn deserialize(data: &[u8]) -> Result<Vec<Instruction>, String> {
-    if data.len() < 10 {
+    if data.len() < HEADER_LEN {
         return Err("파일 너무 짧음".into());
     }
 
     // Magic check
-    if &data[0..4] != &MAGIC {
+    if data[0..4] != MAGIC {
         return Err("매직 넘버 불일치 (크라운 파일 아님)".into());
     }
 
     // Version
     let version = data[4];
+    if version == 1 {
+        return Err("v1 바이트코드 — 'crowni-tvm migrate'로 v2 변환 필요".into());
+    }
     if version != VERSION {
         return Err(format!("지원하지 않는 버전: {}", version));
     }
 
+    // Checksum — v2는 항상 체크섬을 단다
+    if data[5] != FLAG_CHECKSUM {
+        return Err(format!("헤더 플래그 0x{:02X} — v2는 체크섬 플래그(0x{:02X})만 허용", data[5], FLAG_CHECKSUM));
+    }
+    if data.len() < HEADER_LEN + 4 {
+        return Err("체크섬 누락".into());
+    }
+    let end = data.len() - 4;
+    let stored = u32::from_le_bytes([data[end], data[end + 1], data[end + 2], data[end + 3]]);
+    if stored != checksum(&data[..end]) {
+        return Err("체크섬 불일치 (파일 손상)".into());
+    }
+
+    parse_instructions(&data[..end])
+}
+
+/// .크라운 파일 로드 — 마이그레이션 없이 읽기만 (실행용)
+pub fn load_file(path: &std::path::Path) -> Result<Vec<Instruction>, String> {
+    let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    deserialize(&data)
+}
+
+/// 헤더 뒤 명령어 블록 파싱 (v1/v2 공통)
+fn parse_instructions(data: &[u8]) -> Result<Vec<Instruction>, String> {
     // Instruction count
     let count = u32::from_le_bytes([data[6], data[7], data[8], data[9]]) as usize;
 
-    let mut pos = 10;
+    // 명령어는 최소 4바이트 — 손상된 개수로 큰 할당을 하지 않는다
+    if count > (data.len() - HEADER_LEN) / 4 {
+        return Err(format!("명령어 수 {}가 본문 {} bytes와 맞지 않음", count, data.len() - HEADER_LEN));
+    }
+
+    let mut pos = HEADER_LEN;
     let mut program = Vec::with_capacity(count);
 
-    for _ in 0..count {
+    for i in 0..count {
         if pos + 4 > data.len() {
-            return Err("명령어 데이터 부족".into());
+            return Err(format!("명령어 {} (오프셋 {}): 데이터 부족", i, pos));
         }
 
         let sector = data[pos];
         let group = data[pos + 1];
         let command = data[pos + 2];
         let op_count = data[pos + 3] as usize;
+        if sector > 8 || group > 8 || command > 8 {
+            return Err(format!("명령어 {} (오프셋 {}): 잘못된 opcode 주소 {}.{}.{}", i, pos, sector, group, command));
+        }
         pos += 4;
 
         let addr = OpcodeAddr::new(sector, group, command);
         let mut operands = Vec::with_capacity(op_count);
 
         for _ in 0..op_count {
-            let (val, consumed) = deserialize_value(&data[pos..])?;
+            let (val, consumed) = deserialize_value(&data[pos..])
+                .map_err(|e| format!("명령어 {} (오프셋 {}): {}", i, pos, e))?;
             operands.push(val);
             pos += consumed;
         }
@@ -123,9 +283,34 @@ pub fn deserialize(data: &[u8]) -> Result<Vec<Instruction>, String> {
         program.push(Instruction::from_addr(addr, operands));
     }
 
+    if pos != data.len() {
+        return Err(format!("명령어 뒤 잉여 데이터 {} bytes", data.len() - pos));
+    }
     Ok(program)
 }
 
+/// 마이그레이션 v1 → v2: 본문은 그대로, 플래그 설정 + 체크섬 부착
+pub fn migrate_v1_to_v2(data: &[u8]) -> Result<Vec<u8>, String> {
+    if detect_version(data) != Some(1) {
+        return Err("v1 크라운 파일이 아님".into());
+    }
+    // 본문이 온전한지 먼저 확인 — 손상 파일은 올리지 않는다
+    parse_instructions(data)?;
+    let mut out = data.to_vec();
+    out[4] = 2;
+    out[5] |= FLAG_CHECKSUM;
+    let sum = checksum(&out);
+    out.extend_from_slice(&sum.to_le_bytes());
+    Ok(out)
+}
+
+/// 파일 열기 — 구버전이면 백업 후 자동 마이그레이션
+pub fn open_file(path: &std::path::Path) -> Result<Vec<Instruction>, String> {
+    let registry = crate::migrations::MigrationRegistry::builtin();
+    crate::migrations::migrate_file(&registry, path, false)?;
+    load_file(path)
+}
+
 fn serialize_value(bytes: &mut Vec<u8>, val: &Value) {
     match val {
         Value::Int(n) => {
@@ -153,6 +338,10 @@ fn serialize_value(bytes: &mut Vec<u8>, val: &Value) {
         Value::Nil => {
             bytes.push(TAG_NIL);
         }
+        Value::Addr(a) => {
+            bytes.push(TAG_ADDR);
+            bytes.extend_from_slice(&(*a as u64).to_le_bytes());
+        }
         _ => {
             bytes.push(TAG_NONE);
         }
@@ -199,11 +388,101 @@ fn deserialize_value(data: &[u8]) -> Result<(Value, usize), String> {
             Ok((Value::Str(s), 3 + len))
         }
         TAG_NIL => Ok((Value::Nil, 1)),
+        TAG_ADDR => {
+            if data.len() < 9 { return Err("Addr 데이터 부족".into()); }
+            let a = u64::from_le_bytes([
+                data[1], data[2], data[3], data[4],
+                data[5], data[6], data[7], data[8],
+            ]);
+            let a = usize::try_from(a).map_err(|_| format!("Addr 범위 초과: {}", a))?;
+            Ok((Value::Addr(a), 9))
+        }
         TAG_NONE => Ok((Value::Nil, 1)),
         _ => Err(format!("알 수 없는 태그: 0x{:02X}", tag)),
     }
 }
 
+// ═══════════════════════════════════════
+// 역어셈블 — 한선어 니모닉 + opcode 주소 + 6트릿 인코딩
+// 라벨(L주소:)과 피연산자 표기는 어셈블러가 다시 읽을 수 있는 형태
+// ═══════════════════════════════════════
+
+/// .크라운 바이트 → 역어셈블 목록 (무결성 검사 포함)
+pub fn disassemble(data: &[u8]) -> Result<String, String> {
+    let program = deserialize(data)?;
+    let mut out = format!("; .크라운 v{} · 명령어 {} · {} bytes · 체크섬 OK\n", data[4], program.len(), data.len());
+    out.push_str(&listing(&program));
+    Ok(out)
+}
+
+/// 명령어 목록 → `니모닉 피연산자 ; 주소 (s,g,c) 트릿` 줄들
+pub fn listing(program: &[Instruction]) -> String {
+    let opcodes = crate::sectors::all_sectors();
+    let mut targets: Vec<usize> = program.iter()
+        .flat_map(|inst| inst.operands.iter())
+        .filter_map(|v| if let Value::Addr(a) = v { Some(*a) } else { None })
+        .collect();
+    targets.sort_unstable();
+    targets.dedup();
+
+    let mut out = String::new();
+    for (ip, inst) in program.iter().enumerate() {
+        if targets.binary_search(&ip).is_ok() {
+            out.push_str(&format!("{}:\n", label_name(ip)));
+        }
+        let a = inst.addr;
+        let name = opcodes.get(&a).map_or_else(|| format!("?{}", a), |m| m.name_kr.to_string());
+        let operands: Vec<String> = inst.operands.iter().map(operand_text).collect();
+        let text = format!("{} {}", name, operands.join(", "));
+        let pad = 28usize.saturating_sub(display_width(text.trim_end()));
+        out.push_str(&format!("    {}{} ; {:04} {} {}\n", text.trim_end(), " ".repeat(pad), ip, a,
+            crate::trit::Word6::encode_opcode(a.sector, a.group, a.command)));
+    }
+    // 프로그램 끝을 가리키는 라벨
+    if targets.binary_search(&program.len()).is_ok() {
+        out.push_str(&format!("{}:\n", label_name(program.len())));
+    }
+    out
+}
+
+fn label_name(ip: usize) -> String {
+    format!("L{:04}", ip)
+}
+
+/// 어셈블러 parse_operand가 같은 값으로 읽는 표기
+fn operand_text(v: &Value) -> String {
+    match v {
+        Value::Str(s) => {
+            let mut q = String::from("\"");
+            for c in s.chars() {
+                match c {
+                    '"' => q.push_str("\\\""),
+                    '\\' => q.push_str("\\\\"),
+                    '\n' => q.push_str("\\n"),
+                    '\t' => q.push_str("\\t"),
+                    '\r' => q.push_str("\\r"),
+                    '\0' => q.push_str("\\0"),
+                    c => q.push(c),
+                }
+            }
+            q.push('"');
+            q
+        }
+        Value::Float(f) => {
+            let s = format!("{:?}", f);
+            if s.contains('.') || s.contains('e') || !f.is_finite() { s } else { format!("{}.0", s) }
+        }
+        Value::Addr(a) => label_name(*a),
+        other => other.to_string(),
+    }
+}
+
+/// 주석 열 맞춤용 폭 (한글 · 전각은 2칸)
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if ('\u{1100}'..='\u{11FF}').contains(&c) || ('\u{2E80}'..='\u{D7A3}').contains(&c)
+        || ('\u{FF00}'..='\u{FF60}').contains(&c) { 2 } else { 1 }).sum()
+}
+
 /// 파일 정보
 pub struct BytecodeInfo {
     pub version: u8,
@@ -214,7 +493,7 @@ pub struct BytecodeInfo {
 
 /// 바이트코드 정보 분석
 pub fn analyze(data: &[u8]) -> Result<BytecodeInfo, String> {
-    if data.len() < 10 || &data[0..4] != &MAGIC {
+    if detect_version(data).is_none() {
         return Err("유효하지 않은 크라운 파일".into());
     }
     let count = u32::from_le_bytes([data[6], data[7], data[8], data[9]]) as usize;
@@ -226,6 +505,16 @@ pub fn analyze(data: &[u8]) -> Result<BytecodeInfo, String> {
     })
 }
 
+/// v1 형식 직렬화 (체크섬 없음) — 마이그레이션 테스트용
+#[cfg(test)]
+pub(crate) fn serialize_v1(program: &[Instruction]) -> Vec<u8> {
+    let mut bytes = serialize(program);
+    bytes.truncate(bytes.len() - 4);
+    bytes[4] = 1;
+    bytes[5] = 0;
+    bytes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -267,6 +556,108 @@ mod tests {
         let bytes = serialize(&program);
         let info = analyze(&bytes).unwrap();
         assert_eq!(info.instruction_count, 4);
-        assert_eq!(info.version, 1);
+        assert_eq!(info.version, VERSION);
+    }
+
+    #[test]
+    fn test_checksum_detects_corruption() {
+        let mut bytes = serialize(&assemble("넣어 42\n보여줘\n종료"));
+        assert!(deserialize(&bytes).is_ok());
+        bytes[12] ^= 0xFF;
+        assert_eq!(deserialize(&bytes).unwrap_err(), "체크섬 불일치 (파일 손상)");
+    }
+
+    #[test]
+    fn test_v1_requires_migration() {
+        let program = assemble("넣어 \"삼진\"\n보여줘\n종료");
+        let v1 = serialize_v1(&program);
+        assert!(deserialize(&v1).unwrap_err().contains("migrate"));
+
+        let v2 = migrate_v1_to_v2(&v1).unwrap();
+        assert_eq!(v2, serialize(&program));
+        assert_eq!(deserialize(&v2).unwrap().len(), 3);
+
+        // 잘린 v1은 올리지 않는다
+        assert!(migrate_v1_to_v2(&v1[..v1.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn test_stream_writer_matches_serialize() {
+        let program = assemble("넣어 42\n넣어 \"한글\"\n넣어 3.5\n더해\n보여줘\n종료");
+        let dir = std::env::temp_dir().join(format!("crowny-bc-stream-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.크라운");
+
+        let mut writer = StreamWriter::create(&path).unwrap();
+        for inst in &program {
+            writer.push(inst).unwrap();
+        }
+        let info = writer.finish().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes, serialize(&program));
+        assert_eq!(info.instruction_count, program.len());
+        assert_eq!(info.byte_size, bytes.len());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_label_roundtrip_runs() {
+        let program = assemble("넣어 3\n머리:\n복사\n보여줘\n넣어 1\n빼\n복사\n넣어 0\n크다\n조건점프 머리\n종료");
+        let restored = deserialize(&serialize(&program)).unwrap();
+        assert!(matches!(restored[8].operands[0], Value::Addr(1)));
+
+        let mut vm = crate::vm::TVM::new();
+        vm.captured = Some(Vec::new());
+        vm.load(restored);
+        vm.run().unwrap();
+        assert_eq!(vm.captured.unwrap(), vec!["3", "2", "1"]);
+    }
+
+    #[test]
+    fn test_integrity_checks() {
+        let bytes = serialize(&assemble("넣어 1\n보여줘\n종료"));
+        let reseal = |mut b: Vec<u8>| {
+            b.truncate(b.len() - 4);
+            let sum = checksum(&b);
+            b.extend_from_slice(&sum.to_le_bytes());
+            b
+        };
+
+        let mut bad = bytes.clone();
+        bad[0] = 0;
+        assert!(deserialize(&bad).unwrap_err().contains("매직"));
+
+        let mut bad = bytes.clone();
+        bad[5] = 0;
+        assert!(deserialize(&bad).unwrap_err().contains("플래그"));
+
+        // 체크섬이 맞아도 개수 · opcode 주소는 따로 검사
+        let mut bad = bytes.clone();
+        bad[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(deserialize(&reseal(bad)).unwrap_err().contains("명령어 수"));
+
+        let mut bad = bytes.clone();
+        bad[HEADER_LEN] = 9;
+        assert_eq!(deserialize(&reseal(bad)).unwrap_err(), "명령어 0 (오프셋 10): 잘못된 opcode 주소 9.3.0");
+    }
+
+    #[test]
+    fn test_disassemble_reassembles() {
+        let program = assemble("넣어 3\n머리:\n넣어 \"a\\\"b\"\n넣어 1.0\n넣어 -1\n더해\n조건점프 머리\n점프 끝\n끝:\n종료");
+        let text = disassemble(&serialize(&program)).unwrap();
+        assert!(text.starts_with("; .크라운 v2 · 명령어 8"), "{}", text);
+        assert!(text.contains("L0001:\n"), "{}", text);
+        assert!(text.contains("조건점프 L0001"), "{}", text);
+        assert!(text.contains("; 0004 (0,1,0)"), "{}", text);
+
+        // 주석·레이블 포함 목록이 그대로 다시 어셈블됨
+        let again = assemble(&text);
+        assert_eq!(again.len(), program.len());
+        for (a, b) in again.iter().zip(&program) {
+            assert_eq!(a.opcode, b.opcode);
+            let ops = |i: &Instruction| i.operands.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+            assert_eq!(ops(a), ops(b));
+        }
     }
 }
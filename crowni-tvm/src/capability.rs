@@ -0,0 +1,504 @@
+//! ═══════════════════════════════════════════════════
+//! Capability Preflight — 실행 전 권한 정적 분석
+//! ═══════════════════════════════════════════════════
+//!
+//! 어셈블된 프로그램을 실행 전에 훑어서
+//! 민감 opcode(파일/HTTP/LLM/토큰 송금 …)가 요구하는 능력 목록(manifest)을 만든다.
+//!
+//! 커널은 manifest를 호출자 권한과 비교:
+//!   전부 P(허용) → 실행
+//!   하나라도 O/T → 실행 전에 거부, 부족한 능력을 정확히 나열
+//!
+//! 실행 도중 실패 대신 시작 전 판정.
+//!
+//! 승인 흐름 (O = 보류):
+//!   부족 능력이 전부 O → 승인 요청 기록(O) 생성, 태스크 보류
+//!   관리자 승인(P) → 태스크 자동 재개 / 거절(T) → 종료
+//!   하나라도 T → 즉시 거부
+//!
+//! 서명된 능력 토큰 (HTTP API):
+//!   subject|범위,범위|만료ms|서명 — X-Crowny-Capability 헤더로 전달
+//!   서버 비밀키로 서명 → 범위("dex.swap", "nft.*", "*")가 경로 요구 범위를 덮어야 통과
+
+use crate::crypto;
+use crate::opcode::OpcodeAddr;
+use crate::permission::{Action, TritPermission};
+use crate::vm::Instruction;
+
+// ─────────────────────────────────────────────
+// 능력 (Capability)
+// ─────────────────────────────────────────────
+
+/// 민감 능력 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Capability {
+    FileRead,       // 파일 읽기
+    FileWrite,      // 파일 쓰기/삭제
+    Http,           // 네트워크/HTTP
+    Llm,            // LLM 호출
+    TokenTransfer,  // 토큰 송금/발행
+    Hardware,       // FPGA/GPIO
+    System,         // 플러그인/FFI/셸
+}
+
+impl Capability {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Capability::FileRead => "file.read",
+            Capability::FileWrite => "file.write",
+            Capability::Http => "http",
+            Capability::Llm => "llm",
+            Capability::TokenTransfer => "token.transfer",
+            Capability::Hardware => "hardware",
+            Capability::System => "system",
+        }
+    }
+
+    pub fn name_kr(&self) -> &'static str {
+        match self {
+            Capability::FileRead => "파일읽기",
+            Capability::FileWrite => "파일쓰기",
+            Capability::Http => "네트워크",
+            Capability::Llm => "LLM",
+            Capability::TokenTransfer => "토큰송금",
+            Capability::Hardware => "하드웨어",
+            Capability::System => "시스템",
+        }
+    }
+
+    /// 권한 엔진 대상 이름
+    pub fn object(&self) -> String {
+        format!("능력:{}", self.code())
+    }
+
+    /// 권한 엔진 행위 — 커널 기본 정책(읽기 P / 쓰기·실행 O)을 그대로 따른다
+    pub fn action(&self) -> Action {
+        match self {
+            Capability::FileRead => Action::Read,
+            Capability::FileWrite | Capability::TokenTransfer => Action::Write,
+            Capability::Http | Capability::Llm | Capability::Hardware => Action::Execute,
+            Capability::System => Action::Admin,
+        }
+    }
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.code(), self.name_kr())
+    }
+}
+
+/// opcode → 요구 능력 (sectors.rs 배치 기준)
+pub fn required_capability(addr: OpcodeAddr) -> Option<Capability> {
+    match (addr.sector, addr.group, addr.command) {
+        // 섹터 1 G0: LLM 호출
+        (1, 0, _) => Some(Capability::Llm),
+        // 섹터 2 G0~G1: FPGA/GPIO
+        (2, 0, _) | (2, 1, _) => Some(Capability::Hardware),
+        // 섹터 2 G2: 파일 — 쓰기/추가/삭제 vs 나머지 읽기 (닫기는 무관)
+        (2, 2, 2) | (2, 2, 3) | (2, 2, 5) => Some(Capability::FileWrite),
+        (2, 2, 4) => None,
+        (2, 2, _) => Some(Capability::FileRead),
+        // 섹터 2 G3: 네트워크/HTTP
+        (2, 3, _) => Some(Capability::Http),
+        // 섹터 5 G1: 토큰 — 잔액/허용량 조회는 무관
+        (5, 1, 0) | (5, 1, 4) => None,
+        (5, 1, _) => Some(Capability::TokenTransfer),
+        // 섹터 8 G0: 플러그인/FFI/셸
+        (8, 0, _) => Some(Capability::System),
+        _ => None,
+    }
+}
+
+// ─────────────────────────────────────────────
+// Manifest
+// ─────────────────────────────────────────────
+
+/// 요구 능력 + 사용 위치(명령어 인덱스)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestEntry {
+    pub capability: Capability,
+    pub sites: Vec<usize>,
+}
+
+/// 프로그램이 요구하는 능력 목록
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CapabilityManifest {
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl CapabilityManifest {
+    /// 어셈블된 프로그램 분석
+    pub fn analyze(program: &[Instruction]) -> Self {
+        let mut entries: Vec<ManifestEntry> = Vec::new();
+        for (ip, inst) in program.iter().enumerate() {
+            if let Some(cap) = required_capability(inst.addr) {
+                match entries.iter_mut().find(|e| e.capability == cap) {
+                    Some(e) => e.sites.push(ip),
+                    None => entries.push(ManifestEntry { capability: cap, sites: vec![ip] }),
+                }
+            }
+        }
+        entries.sort_by_key(|e| e.capability);
+        Self { entries }
+    }
+}
+
+impl std::fmt::Display for CapabilityManifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.entries.is_empty() {
+            return write!(f, "[능력] 없음");
+        }
+        let parts: Vec<String> = self.entries.iter()
+            .map(|e| format!("{}@{}", e.capability.code(),
+                e.sites.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(",")))
+            .collect();
+        write!(f, "[능력] {}", parts.join(" "))
+    }
+}
+
+// ─────────────────────────────────────────────
+// 사전 판정 결과
+// ─────────────────────────────────────────────
+
+/// 부족한 능력 + 권한 엔진 판정 (O=승인 필요, T=차단)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingCapability {
+    pub capability: Capability,
+    pub verdict: TritPermission,
+    pub sites: Vec<usize>,
+}
+
+/// 실행 전 판정 리포트
+#[derive(Debug, Clone)]
+pub struct PreflightReport {
+    pub subject: String,
+    pub manifest: CapabilityManifest,
+    pub missing: Vec<MissingCapability>,
+}
+
+impl PreflightReport {
+    /// 종합 판정 — 부족한 능력 판정의 3진 AND
+    pub fn verdict(&self) -> TritPermission {
+        self.missing.iter()
+            .fold(TritPermission::Allow, |acc, m| acc.and(m.verdict))
+    }
+
+    pub fn is_allowed(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+impl std::fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.missing.is_empty() {
+            return write!(f, "{}: 사전판정 {} {}", self.subject, self.verdict(), self.manifest);
+        }
+        let parts: Vec<String> = self.missing.iter()
+            .map(|m| format!("{}={}", m.capability.code(), m.verdict.symbol()))
+            .collect();
+        write!(f, "{}: 능력 부족 [{}]", self.subject, parts.join(", "))
+    }
+}
+
+// ─────────────────────────────────────────────
+// 승인 요청 (O-상태 보류)
+// ─────────────────────────────────────────────
+
+/// 승인 요청 기록
+/// state: O(대기) → P(승인) / T(거절)
+#[derive(Debug, Clone)]
+pub struct ApprovalRecord {
+    pub id: u64,
+    pub subject: String,
+    pub source: String,
+    pub missing: Vec<Capability>,
+    pub state: TritPermission,
+    pub decided_by: Option<String>,
+    pub reason: String,
+    /// 재개 실행 결과 (승인 후)
+    pub outcome: Option<Result<(), String>>,
+}
+
+impl std::fmt::Display for ApprovalRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let caps: Vec<&str> = self.missing.iter().map(|c| c.code()).collect();
+        write!(f, "#{} {} [{}] {}", self.id, self.subject, caps.join(","), self.state)?;
+        if let Some(by) = &self.decided_by {
+            write!(f, " by {}", by)?;
+        }
+        if !self.reason.is_empty() {
+            write!(f, " ({})", self.reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// 승인 대기열
+#[derive(Debug, Default)]
+pub struct ApprovalQueue {
+    records: Vec<ApprovalRecord>,
+    next_id: u64,
+}
+
+impl ApprovalQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 승인 요청 생성 (O)
+    pub fn open(&mut self, subject: &str, source: &str, missing: Vec<Capability>) -> u64 {
+        self.next_id += 1;
+        self.records.push(ApprovalRecord {
+            id: self.next_id,
+            subject: subject.to_string(),
+            source: source.to_string(),
+            missing,
+            state: TritPermission::Review,
+            decided_by: None,
+            reason: String::new(),
+            outcome: None,
+        });
+        self.next_id
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ApprovalRecord> {
+        self.records.iter().find(|r| r.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut ApprovalRecord> {
+        self.records.iter_mut().find(|r| r.id == id)
+    }
+
+    /// O 상태 요청 목록
+    pub fn pending(&self) -> Vec<&ApprovalRecord> {
+        self.records.iter().filter(|r| r.state == TritPermission::Review).collect()
+    }
+
+    /// 판정 기록 — O 상태에서만 가능
+    pub fn decide(&mut self, id: u64, by: &str, state: TritPermission, reason: &str)
+        -> Result<&mut ApprovalRecord, String>
+    {
+        let rec = self.get_mut(id).ok_or_else(|| format!("승인 요청 #{} 없음", id))?;
+        if rec.state != TritPermission::Review {
+            return Err(format!("승인 요청 #{} 이미 판정됨: {}", id, rec.state));
+        }
+        rec.state = state;
+        rec.decided_by = Some(by.to_string());
+        rec.reason = reason.to_string();
+        Ok(rec)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 서명된 능력 토큰
+// ─────────────────────────────────────────────
+
+/// 요청 헤더 이름
+pub const TOKEN_HEADER: &str = "X-Crowny-Capability";
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// 서명된 능력 토큰 — 누가(subject) 무엇을(scopes) 언제까지(expires_at)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapabilityToken {
+    pub subject: String,
+    pub scopes: Vec<String>,
+    pub expires_at: u64,
+    pub signature: String,
+}
+
+impl CapabilityToken {
+    fn payload(subject: &str, scopes: &[String], expires_at: u64) -> String {
+        format!("{}|{}|{}", subject, scopes.join(","), expires_at)
+    }
+
+    /// 범위 허용 — 정확히 일치, "dex.*" 같은 접두 와일드카드, "*"
+    pub fn allows(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == "*" || s == scope
+            || s.strip_suffix(".*").is_some_and(|p| scope.strip_prefix(p).is_some_and(|r| r.starts_with('.'))))
+    }
+
+    /// 헤더 값으로 직렬화
+    pub fn encode(&self) -> String {
+        format!("{}|{}", Self::payload(&self.subject, &self.scopes, self.expires_at), self.signature)
+    }
+
+    pub fn decode(s: &str) -> Option<Self> {
+        let mut parts = s.trim().rsplitn(4, '|');
+        let signature = parts.next()?.to_string();
+        let expires_at = parts.next()?.parse().ok()?;
+        let scopes: Vec<String> = parts.next()?.split(',').filter(|x| !x.is_empty()).map(String::from).collect();
+        let subject = parts.next()?.to_string();
+        if subject.is_empty() || scopes.is_empty() {
+            return None;
+        }
+        Some(Self { subject, scopes, expires_at, signature })
+    }
+}
+
+/// 토큰 검증 실패
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenError {
+    Missing,
+    Malformed,
+    BadSignature,
+    Expired,
+    ScopeDenied(String),
+}
+
+impl TokenError {
+    /// HTTP 상태 — 인증 실패 401 / 범위 부족 403
+    pub fn status(&self) -> u16 {
+        match self {
+            TokenError::ScopeDenied(_) => 403,
+            _ => 401,
+        }
+    }
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenError::Missing => f.write_str(crate::i18n::t("cap.token_missing")),
+            TokenError::Malformed => f.write_str(crate::i18n::t("cap.token_malformed")),
+            TokenError::BadSignature => f.write_str(crate::i18n::t("cap.token_signature")),
+            TokenError::Expired => f.write_str(crate::i18n::t("cap.token_expired")),
+            TokenError::ScopeDenied(scope) => write!(f, "{}", crate::i18n::tr!("cap.token_scope", scope)),
+        }
+    }
+}
+
+/// 토큰 발급·검증기 (서버 비밀키 보유)
+#[derive(Debug, Clone)]
+pub struct TokenSigner {
+    secret: String,
+}
+
+impl TokenSigner {
+    pub fn new(secret: &str) -> Self {
+        Self { secret: secret.to_string() }
+    }
+
+    /// HMAC-SHA256(secret, "cap:" ‖ payload) — 16진수
+    fn sign(&self, payload: &str) -> String {
+        crypto::to_hex(&crypto::hmac_sha256(self.secret.as_bytes(), &[b"cap:", payload.as_bytes()]))
+    }
+
+    /// 발급 — ttl_ms 후 만료
+    pub fn issue(&self, subject: &str, scopes: &[&str], ttl_ms: u64) -> CapabilityToken {
+        let scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+        let expires_at = now_ms() + ttl_ms;
+        let signature = self.sign(&CapabilityToken::payload(subject, &scopes, expires_at));
+        CapabilityToken { subject: subject.to_string(), scopes, expires_at, signature }
+    }
+
+    /// 헤더 값 검증 — 서명 → 만료 → 범위 순
+    pub fn verify_at(&self, header: Option<&str>, scope: &str, now: u64) -> Result<CapabilityToken, TokenError> {
+        let token = CapabilityToken::decode(header.ok_or(TokenError::Missing)?).ok_or(TokenError::Malformed)?;
+        let expected = self.sign(&CapabilityToken::payload(&token.subject, &token.scopes, token.expires_at));
+        if !crypto::ct_eq(token.signature.as_bytes(), expected.as_bytes()) {
+            return Err(TokenError::BadSignature);
+        }
+        if token.expires_at <= now {
+            return Err(TokenError::Expired);
+        }
+        if !token.allows(scope) {
+            return Err(TokenError::ScopeDenied(scope.to_string()));
+        }
+        Ok(token)
+    }
+
+    pub fn verify(&self, header: Option<&str>, scope: &str) -> Result<CapabilityToken, TokenError> {
+        self.verify_at(header, scope, now_ms())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::assemble;
+
+    #[test]
+    fn test_manifest_analyze() {
+        let program = assemble("넣어 \"a.txt\"\n파일읽기\n질문해\n넣어 \"b.txt\"\n파일쓰기\n질문해\n종료");
+        let manifest = CapabilityManifest::analyze(&program);
+        assert_eq!(manifest.entries.iter().map(|e| e.capability).collect::<Vec<_>>(),
+            vec![Capability::FileRead, Capability::FileWrite, Capability::Llm]);
+        assert_eq!(manifest.entries[2].sites, vec![2, 5]);
+        assert!(manifest.entries.iter().all(|e| e.capability != Capability::TokenTransfer));
+    }
+
+    #[test]
+    fn test_pure_program_empty() {
+        let program = assemble("넣어 1\n넣어 2\n더해\n보여줘\n종료");
+        assert!(CapabilityManifest::analyze(&program).entries.is_empty());
+    }
+
+    #[test]
+    fn test_required_capability_map() {
+        assert_eq!(required_capability(OpcodeAddr::new(5, 1, 1)), Some(Capability::TokenTransfer));
+        assert_eq!(required_capability(OpcodeAddr::new(5, 1, 0)), None);
+        assert_eq!(required_capability(OpcodeAddr::new(2, 3, 0)), Some(Capability::Http));
+        assert_eq!(required_capability(OpcodeAddr::new(0, 3, 5)), None);
+    }
+
+    #[test]
+    fn test_approval_queue() {
+        let mut q = ApprovalQueue::new();
+        let id = q.open("앱", "질문해", vec![Capability::Llm]);
+        assert_eq!(q.pending().len(), 1);
+
+        q.decide(id, "관리자", TritPermission::Deny, "불필요").unwrap();
+        assert!(q.pending().is_empty());
+        assert_eq!(q.get(id).unwrap().state, TritPermission::Deny);
+        // 재판정 불가
+        assert!(q.decide(id, "관리자", TritPermission::Allow, "").is_err());
+        assert!(q.decide(99, "관리자", TritPermission::Allow, "").is_err());
+    }
+
+    #[test]
+    fn test_capability_token() {
+        let signer = TokenSigner::new("비밀");
+        let token = signer.issue("alice", &["dex.*", "nft.buy"], 60_000);
+        let header = token.encode();
+        assert_eq!(CapabilityToken::decode(&header), Some(token.clone()));
+
+        assert_eq!(signer.verify(Some(&header), "dex.swap").unwrap().subject, "alice");
+        assert!(signer.verify(Some(&header), "nft.buy").is_ok());
+        assert_eq!(signer.verify(Some(&header), "nft.list"), Err(TokenError::ScopeDenied("nft.list".into())));
+        assert!(!token.allows("dexter.swap"));
+        assert_eq!(signer.verify(None, "dex.swap").unwrap_err().status(), 401);
+        assert_eq!(signer.verify(Some("쓰레기"), "dex.swap"), Err(TokenError::Malformed));
+
+        // 범위를 바꾸거나 다른 키로 서명하면 거부
+        let forged = header.replacen("dex.*", "*", 1);
+        assert_eq!(signer.verify(Some(&forged), "dex.swap"), Err(TokenError::BadSignature));
+        assert_eq!(TokenSigner::new("다른키").verify(Some(&header), "dex.swap"), Err(TokenError::BadSignature));
+        assert_eq!(signer.verify_at(Some(&header), "dex.swap", token.expires_at), Err(TokenError::Expired));
+    }
+
+    #[test]
+    fn test_capability_token_tamper() {
+        let signer = TokenSigner::new("비밀");
+        let token = signer.issue("alice", &["admin.config"], 60_000);
+        assert_eq!(token.signature.len(), 64);
+        let header = token.encode();
+        // 페이로드(서명 앞부분)의 어느 한 바이트만 바꿔도 거부
+        let payload_len = header.len() - token.signature.len() - 1;
+        for i in (0..payload_len).filter(|&i| header.as_bytes()[i].is_ascii_alphanumeric()) {
+            let mut bytes = header.clone().into_bytes();
+            bytes[i] ^= 0x01;
+            let tampered = String::from_utf8(bytes).unwrap();
+            let result = signer.verify(Some(&tampered), "admin.config");
+            assert!(matches!(result, Err(TokenError::BadSignature) | Err(TokenError::Malformed)), "{}: {:?}", tampered, result);
+        }
+        // 서명 한 글자 변조
+        let mut sig = token.signature.clone().into_bytes();
+        sig[0] = if sig[0] == b'0' { b'1' } else { b'0' };
+        let forged = format!("{}|{}", &header[..payload_len], String::from_utf8(sig).unwrap());
+        assert_eq!(signer.verify(Some(&forged), "admin.config"), Err(TokenError::BadSignature));
+    }
+}
@@ -13,8 +13,22 @@
 ///! 실행 흐름:
 ///!   앱 → CAR.submit(AppTask) → 권한검사 → 스케줄 → TVM 실행 → TritResult
 
-use std::collections::HashMap;
-use std::time::Instant;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::vm::{BudgetHook, ExecLimits, LimitKind, VmError, VmErrorKind};
+use crate::program_limits::{ProgramLimitError, ProgramLimits};
+use crate::artifacts::SharedArtifacts;
+use crate::billing::{Resource, SharedAccounting, Usage};
+use crate::integrations::{EventKind, SharedWebhooks};
+use crate::websocket::EventHub;
+use crate::output::say;
+use crate::output::{self, JsonObject};
+use crate::query::{Page, Query, Queryable};
+use crate::ring_log::{RingLog, Spill};
+use crate::trit_store::{StoreValue, TritStore};
 
 // ─────────────────────────────────────────────
 // TritResult — 표준 반환 타입
@@ -94,6 +108,55 @@ impl std::fmt::Display for ResultData {
     }
 }
 
+impl ResultData {
+    /// JSON 타입 태그 (data_type 필드)
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ResultData::None => "none",
+            ResultData::Integer(_) => "integer",
+            ResultData::Float(_) => "float",
+            ResultData::Text(_) => "text",
+            ResultData::Bytes(_) => "bytes",
+            ResultData::Trit(_) => "trit",
+            ResultData::List(_) => "list",
+            ResultData::Map(_) => "map",
+        }
+    }
+
+    /// JSON 값 — 맵은 키 정렬, 바이트는 16진 문자열
+    pub fn to_json_value(&self) -> String {
+        match self {
+            ResultData::None => "null".into(),
+            ResultData::Integer(n) => n.to_string(),
+            ResultData::Float(v) if v.is_finite() => v.to_string(),
+            ResultData::Float(_) => "null".into(),
+            ResultData::Text(s) => output::escape(s),
+            ResultData::Bytes(b) => output::escape(&b.iter().map(|x| format!("{:02x}", x)).collect::<String>()),
+            ResultData::Trit(t) => output::escape(output::trit_symbol(*t)),
+            ResultData::List(items) => output::array(items.iter().map(|d| d.to_json_value()).collect()),
+            ResultData::Map(m) => {
+                let mut keys: Vec<&String> = m.keys().collect();
+                keys.sort();
+                keys.into_iter()
+                    .fold(JsonObject::new(), |obj, k| obj.raw(k, m[k].to_json_value()))
+                    .build()
+            }
+        }
+    }
+}
+
+impl TritResult {
+    /// 안정 필드 순서 JSON (schema: crowny.trit_result)
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.trit_result")
+            .trit("state", self.state as i8)
+            .int("task_id", self.task_id as i64)
+            .int("elapsed_ms", self.elapsed_ms as i64)
+            .str("data_type", self.data.type_name())
+            .raw("data", self.data.to_json_value())
+    }
+}
+
 // ─────────────────────────────────────────────
 // AppTask — 애플리케이션 작업 정의
 // ─────────────────────────────────────────────
@@ -124,6 +187,58 @@ impl std::fmt::Display for TaskType {
     }
 }
 
+/// 작업별 자원 한도 — 넘으면 실행을 끊고 T + 한도 사유
+/// (ExecLimits 샌드박스와 달리 사이클 · 시간 초과도 재시도 대상이 아니다)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskLimits {
+    pub max_cycles: Option<u64>,
+    pub max_heap: Option<usize>,
+    pub max_ms: Option<u64>,
+}
+
+impl TaskLimits {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn cycles(mut self, n: u64) -> Self { self.max_cycles = Some(n); self }
+    pub fn heap(mut self, objects: usize) -> Self { self.max_heap = Some(objects); self }
+    pub fn ms(mut self, ms: u64) -> Self { self.max_ms = Some(ms); self }
+
+    /// 비어 있는 항목만 fallback으로 채움
+    pub fn or(self, fallback: TaskLimits) -> Self {
+        Self {
+            max_cycles: self.max_cycles.or(fallback.max_cycles),
+            max_heap: self.max_heap.or(fallback.max_heap),
+            max_ms: self.max_ms.or(fallback.max_ms),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// VM 예산 훅 — 취소 토큰이 있으면 함께 확인
+    fn budget_hook(self, cancel: Option<CancelToken>) -> BudgetHook {
+        Box::new(move |u| {
+            if cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+                return Err(VmErrorKind::Custom("취소됨".into()));
+            }
+            if self.max_cycles.is_some_and(|max| u.cycles > max) {
+                return Err(VmErrorKind::BudgetExceeded(LimitKind::Cycles));
+            }
+            if self.max_heap.is_some_and(|max| u.heap > max) {
+                return Err(VmErrorKind::BudgetExceeded(LimitKind::Heap));
+            }
+            // 시계 조회는 256 사이클마다
+            if self.max_ms.is_some_and(|max| u.cycles.is_multiple_of(256) && u.started.elapsed().as_millis() as u64 > max) {
+                return Err(VmErrorKind::BudgetExceeded(LimitKind::WallClock));
+            }
+            Ok(())
+        })
+    }
+}
+
 /// 앱 작업 요청
 #[derive(Debug, Clone)]
 pub struct AppTask {
@@ -131,6 +246,8 @@ pub struct AppTask {
     pub subject: String,     // 요청자
     pub payload: String,     // 페이로드 (소스코드, URL, 프롬프트 등)
     pub params: HashMap<String, String>,  // 추가 파라미터
+    /// 작업 한도 — 비어 있는 항목은 런타임 기본값(with_task_limits)
+    pub limits: TaskLimits,
 }
 
 impl AppTask {
@@ -140,6 +257,7 @@ impl AppTask {
             subject: subject.to_string(),
             payload: payload.to_string(),
             params: HashMap::new(),
+            limits: TaskLimits::unlimited(),
         }
     }
 
@@ -147,6 +265,11 @@ impl AppTask {
         self.params.insert(key.to_string(), val.to_string());
         self
     }
+
+    pub fn with_limits(mut self, limits: TaskLimits) -> Self {
+        self.limits = limits;
+        self
+    }
 }
 
 // ─────────────────────────────────────────────
@@ -163,25 +286,171 @@ pub enum AccessLevel {
 }
 
 /// 작업 이력
-#[derive(Debug)]
-struct TaskLog {
+#[derive(Debug, Clone)]
+pub struct TaskLog {
+    pub task_id: u64,
+    pub task_type: TaskType,
+    pub subject: String,
+    pub state: TritState,
+    pub elapsed_ms: u64,
+    /// 완료 시각 (epoch ms)
+    pub finished_at: u64,
+}
+
+const TASK_TYPES: [TaskType; 7] = [
+    TaskType::Compile, TaskType::Execute, TaskType::WebRequest, TaskType::LlmCall,
+    TaskType::DbQuery, TaskType::FileIO, TaskType::System,
+];
+
+impl Spill for TaskLog {
+    fn to_store(&self) -> StoreValue {
+        let kind = TASK_TYPES.iter().position(|t| *t == self.task_type).unwrap_or(0);
+        StoreValue::List(vec![
+            StoreValue::Int(self.task_id as i64),
+            StoreValue::Int(kind as i64),
+            StoreValue::Text(self.subject.clone()),
+            StoreValue::Trit(self.state as i8),
+            StoreValue::Int(self.elapsed_ms as i64),
+            StoreValue::Int(self.finished_at as i64),
+        ])
+    }
+
+    fn from_store(value: &StoreValue) -> Option<Self> {
+        let StoreValue::List(v) = value else { return None };
+        let int = |i: usize| match v.get(i)? { StoreValue::Int(n) if *n >= 0 => Some(*n as u64), _ => None };
+        Some(Self {
+            task_id: int(0)?,
+            task_type: *TASK_TYPES.get(int(1)? as usize)?,
+            subject: match v.get(2)? { StoreValue::Text(s) => s.clone(), _ => return None },
+            state: match v.get(3)? { StoreValue::Trit(t) => TritState::from_i8(*t), _ => return None },
+            elapsed_ms: int(4)?,
+            finished_at: int(5)?,
+        })
+    }
+}
+
+impl Queryable for TaskLog {
+    fn timestamp(&self) -> u64 { self.finished_at }
+    fn trit(&self) -> i8 { self.state as i8 }
+    fn involves(&self, account: &str) -> bool { self.subject == account }
+}
+
+// ─────────────────────────────────────────────
+// 비동기 작업 — 워커 풀 + 유한 큐
+// ─────────────────────────────────────────────
+
+/// 기본 워커 수 / 큐 용량 (첫 비동기 제출 때 풀 생성)
+pub const DEFAULT_WORKERS: usize = 4;
+pub const DEFAULT_QUEUE: usize = 64;
+/// poll()로 다시 볼 수 있게 남겨 두는 완료 결과 수 — 넘으면 이력(상태만)으로 조회
+const ASYNC_RESULTS: usize = 1024;
+
+/// 취소 토큰 — 실행 중인 작업은 스스로 확인해 멈춘다 (협조적 취소)
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// submit_async 핸들
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskHandle {
+    pub task_id: u64,
+}
+
+type AsyncExecutor = Box<dyn FnOnce(&AppTask, &CancelToken) -> (TritState, ResultData, Usage) + Send>;
+
+struct Job {
+    task_id: u64,
+    task: AppTask,
+    cancel: CancelToken,
+    run: AsyncExecutor,
+}
+
+/// 워커 → 런타임 (ran = false면 큐에서 취소돼 실행하지 않음)
+struct Completion {
     task_id: u64,
-    task_type: TaskType,
-    subject: String,
     state: TritState,
-    elapsed_ms: u64,
+    data: ResultData,
+    usage: Usage,
+    ran: bool,
+}
+
+/// 큐에 있거나 실행 중인 작업 (O)
+struct InFlight {
+    task: AppTask,
+    cancel: CancelToken,
+    submitted: Instant,
+}
+
+/// 워커 풀 — 큐가 닫히면(런타임 drop) 워커도 끝난다
+struct WorkerPool {
+    jobs: mpsc::SyncSender<Job>,
+    done: mpsc::Receiver<Completion>,
+    capacity: usize,
+}
+
+impl WorkerPool {
+    fn new(workers: usize, capacity: usize) -> Self {
+        let (jobs, queue) = mpsc::sync_channel::<Job>(capacity);
+        let queue = Arc::new(Mutex::new(queue));
+        let (done_tx, done) = mpsc::channel();
+        for _ in 0..workers {
+            let queue = Arc::clone(&queue);
+            let done_tx = done_tx.clone();
+            std::thread::spawn(move || loop {
+                let next = queue.lock().ok().and_then(|q| q.recv().ok());
+                let Some(job) = next else { break };
+                let completion = if job.cancel.is_cancelled() {
+                    Completion { task_id: job.task_id, state: TritState::Failed, data: ResultData::None, usage: Usage::default(), ran: false }
+                } else {
+                    let (task, cancel, run) = (job.task, job.cancel, job.run);
+                    // 실행기가 패닉해도 워커는 살아남고 작업은 T로 끝난다
+                    let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run(&task, &cancel)));
+                    let (state, data, usage) = outcome.unwrap_or_else(|_| {
+                        (TritState::Failed, ResultData::Text("실행기 패닉".into()), Usage::default())
+                    });
+                    Completion { task_id: job.task_id, state, data, usage, ran: true }
+                };
+                if done_tx.send(completion).is_err() { break; }
+            });
+        }
+        Self { jobs, done, capacity }
+    }
 }
 
 /// Crowny Application Runtime
 pub struct CrownyRuntime {
     task_counter: u64,
-    history: Vec<TaskLog>,
+    history: RingLog<TaskLog>,
     // 권한 매핑: TaskType → 최소 AccessLevel
     access_rules: HashMap<String, AccessLevel>,
     // 통계
     success_count: u64,
     pending_count: u64,
     failed_count: u64,
+    /// 컴파일 산출물 저장소 (플랫폼 배포·컨트랙트 VM과 공유 가능)
+    pub artifacts: SharedArtifacts,
+    /// 작업 완료 → task.finished 웹훅
+    pub webhooks: Option<SharedWebhooks>,
+    /// 테넌트별 자원 계량 · 예산
+    pub accounting: Option<SharedAccounting>,
+    /// 작업 완료 → WebSocket "task" 프레임
+    pub events: Option<EventHub>,
+    /// 실행 작업 기본 한도 (AppTask.limits가 비어 있는 항목에 적용)
+    pub task_limits: TaskLimits,
+    /// 비동기 작업 — 첫 submit_async 때 기본 크기로 만든다
+    pool: Option<WorkerPool>,
+    in_flight: HashMap<u64, InFlight>,
+    finished: HashMap<u64, TritResult>,
+    finished_order: VecDeque<u64>,
 }
 
 impl CrownyRuntime {
@@ -196,92 +465,322 @@ impl CrownyRuntime {
         access_rules.insert("FileIO".into(), AccessLevel::Admin);
         access_rules.insert("System".into(), AccessLevel::Kernel);
 
-        println!("[CAR] Crowny Application Runtime 시작");
+        say!("[CAR] Crowny Application Runtime 시작");
         Self {
             task_counter: 0,
-            history: Vec::new(),
+            history: RingLog::default(),
             access_rules,
             success_count: 0,
             pending_count: 0,
             failed_count: 0,
+            artifacts: crate::artifacts::shared(),
+            webhooks: None,
+            accounting: None,
+            events: None,
+            task_limits: TaskLimits::unlimited(),
+            pool: None,
+            in_flight: HashMap::new(),
+            finished: HashMap::new(),
+            finished_order: VecDeque::new(),
         }
     }
 
+    /// 다른 모듈과 저장소 공유
+    pub fn with_artifacts(mut self, artifacts: SharedArtifacts) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
+    pub fn with_webhooks(mut self, webhooks: SharedWebhooks) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// 실행 작업 기본 한도 — run_source 등 한도 없이 만든 작업에도 적용
+    pub fn with_task_limits(mut self, limits: TaskLimits) -> Self {
+        self.task_limits = limits;
+        self
+    }
+
+    pub fn with_events(mut self, events: EventHub) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn with_accounting(mut self, accounting: SharedAccounting) -> Self {
+        self.accounting = Some(accounting);
+        self
+    }
+
+    /// 작업 기록 상한 (메모리)
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.history.set_capacity(capacity);
+    }
+
+    /// 상한을 넘은 작업 기록을 저장소로 내보냄 — 조회는 그대로
+    pub fn spill_history(&mut self, store: TritStore) {
+        self.history.spill_to(store, "car.task");
+    }
+
+    pub fn history(&self) -> &RingLog<TaskLog> {
+        &self.history
+    }
+
     /// 핵심 메서드: 작업 제출
     /// 모든 앱은 이것만 호출한다.
     pub fn submit(
         &mut self,
         task: AppTask,
         executor: impl FnOnce(&AppTask) -> (TritState, ResultData),
+    ) -> TritResult {
+        self.submit_metered(task, |t| {
+            let (state, data) = executor(t);
+            (state, data, Usage::default())
+        })
+    }
+
+    /// 자원 사용량을 보고하는 작업 제출 — 계량 장부가 있으면 테넌트·일에 합산
+    /// 예산 한도를 넘긴(T) 테넌트의 작업은 실행하지 않는다
+    pub fn submit_metered(
+        &mut self,
+        task: AppTask,
+        executor: impl FnOnce(&AppTask) -> (TritState, ResultData, Usage),
     ) -> TritResult {
         let start = Instant::now();
         self.task_counter += 1;
         let task_id = self.task_counter;
 
-        // 1. 권한 검사
-        let access_ok = self.check_access(&task);
-        if !access_ok {
-            self.failed_count += 1;
-            self.log_task(task_id, &task, TritState::Failed, 0);
-            return TritResult {
-                state: TritState::Failed,
-                data: ResultData::Text("권한 부족".into()),
-                elapsed_ms: 0,
-                task_id,
-            };
+        // 1. 권한 · 예산 검사
+        if let Some(reason) = self.denial(&task) {
+            return self.finish(task_id, &task, TritState::Failed, ResultData::Text(reason.into()), None, 0);
         }
 
-        // 2. 실행
-        let (state, data) = executor(&task);
+        // 2. 실행 · 계량 → 3. 통계 · 이력 → 표준 결과
+        let (state, data, usage) = executor(&task);
+        self.finish(task_id, &task, state, data, Some(usage), start.elapsed().as_millis() as u64)
+    }
 
-        let elapsed = start.elapsed().as_millis() as u64;
+    /// 권한 · 예산 검사 — 거부 사유
+    fn denial(&self, task: &AppTask) -> Option<&'static str> {
+        if !self.check_access(task) {
+            Some("권한 부족")
+        } else if self.accounting.as_ref().is_some_and(|a| !a.borrow_mut().allows(&task.subject)) {
+            Some("예산 초과")
+        } else {
+            None
+        }
+    }
 
-        // 3. 통계 업데이트
+    /// 작업 종료 처리 — 계량(실행했을 때만) · 통계 · 이력
+    fn finish(&mut self, task_id: u64, task: &AppTask, state: TritState, data: ResultData,
+              usage: Option<Usage>, elapsed_ms: u64) -> TritResult {
+        if let (Some(acc), Some(usage)) = (&self.accounting, usage) {
+            acc.borrow_mut().record(task_id, &task.subject, usage, state as i8);
+        }
         match state {
             TritState::Success => self.success_count += 1,
             TritState::Pending => self.pending_count += 1,
             TritState::Failed => self.failed_count += 1,
         }
+        self.log_task(task_id, task, state, elapsed_ms);
+        TritResult { state, data, elapsed_ms, task_id }
+    }
 
-        // 4. 이력 기록
-        self.log_task(task_id, &task, state, elapsed);
+    /// 비동기 제출 — 즉시 핸들 반환, 작업은 워커가 끝낼 때까지 O
+    /// 권한 · 예산 거부는 바로 T로 끝난 핸들, 큐가 가득 차면 Err
+    pub fn submit_async(
+        &mut self,
+        task: AppTask,
+        executor: impl FnOnce(&AppTask, &CancelToken) -> (TritState, ResultData, Usage) + Send + 'static,
+    ) -> Result<TaskHandle, String> {
+        self.drain_completions();
+        let task_id = self.task_counter + 1;
+        if let Some(reason) = self.denial(&task) {
+            self.task_counter = task_id;
+            let result = self.finish(task_id, &task, TritState::Failed, ResultData::Text(reason.into()), None, 0);
+            self.keep_result(result);
+            return Ok(TaskHandle { task_id });
+        }
 
-        // 5. 표준 결과 반환
-        TritResult { state, data, elapsed_ms: elapsed, task_id }
+        let pool = self.pool.get_or_insert_with(|| WorkerPool::new(DEFAULT_WORKERS, DEFAULT_QUEUE));
+        let cancel = CancelToken::default();
+        let job = Job { task_id, task: task.clone(), cancel: cancel.clone(), run: Box::new(executor) };
+        match pool.jobs.try_send(job) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(_)) => return Err(format!("작업 큐 가득 참 ({})", pool.capacity)),
+            Err(mpsc::TrySendError::Disconnected(_)) => return Err("워커 풀 종료됨".into()),
+        }
+        self.task_counter = task_id;
+        self.in_flight.insert(task_id, InFlight { task, cancel, submitted: Instant::now() });
+        Ok(TaskHandle { task_id })
     }
 
-    /// 간편 실행: 소스코드 컴파일+실행
-    pub fn run_source(&mut self, subject: &str, source: &str) -> TritResult {
+    /// 비동기 한선어 어셈블리 실행 (run_source_limited와 같은 판정)
+    /// 실행 중에도 취소 토큰을 사이클마다 확인한다
+    pub fn run_source_async(&mut self, subject: &str, source: &str, limits: ExecLimits) -> Result<TaskHandle, String> {
         let task = AppTask::new(TaskType::Execute, subject, source);
-        self.submit(task, |t| {
-            // 어셈블 + TVM 실행
-            let program = crate::assembler::assemble(&t.payload);
-            if program.is_empty() {
-                return (TritState::Failed, ResultData::Text("빈 프로그램".into()));
+        let defaults = self.task_limits;
+        self.submit_async(task, move |t, cancel| {
+            if cancel.is_cancelled() {
+                return (TritState::Failed, ResultData::Text("취소됨".into()), Usage::default());
             }
-            let mut vm = crate::vm::TVM::new();
-            vm.load(program);
-            match vm.run() {
-                Ok(()) => {
-                    let top = vm.stack.last()
-                        .and_then(|v| v.as_int())
-                        .unwrap_or(0);
-                    (TritState::Success, ResultData::Integer(top))
-                }
-                Err(e) => (TritState::Failed, ResultData::Text(format!("{:?}", e))),
+            let hook = t.limits.or(defaults).budget_hook(Some(cancel.clone()));
+            execute_source(&t.payload, limits, &ProgramLimits::unlimited(), Some(hook))
+        })
+    }
+
+    /// 작업 상태 — 진행 중이면 O, 끝났으면 P/T 결과 (오래된 결과는 이력의 상태만)
+    pub fn poll(&mut self, task_id: u64) -> Option<TritResult> {
+        self.drain_completions();
+        if let Some(f) = self.in_flight.get(&task_id) {
+            return Some(TritResult {
+                state: TritState::Pending,
+                data: ResultData::None,
+                elapsed_ms: f.submitted.elapsed().as_millis() as u64,
+                task_id,
+            });
+        }
+        if let Some(r) = self.finished.get(&task_id) {
+            return Some(r.clone());
+        }
+        self.history.iter().find(|l| l.task_id == task_id).map(|l| TritResult {
+            state: l.state,
+            data: ResultData::None,
+            elapsed_ms: l.elapsed_ms,
+            task_id,
+        })
+    }
+
+    /// 완료(P/T)될 때까지 최대 timeout 대기 — 시간 초과면 O 그대로
+    pub fn wait(&mut self, task_id: u64, timeout: Duration) -> Option<TritResult> {
+        let deadline = Instant::now() + timeout;
+        while self.in_flight.contains_key(&task_id) {
+            let left = deadline.saturating_duration_since(Instant::now());
+            let next = match &self.pool {
+                Some(pool) if !left.is_zero() => pool.done.recv_timeout(left).ok(),
+                _ => None,
+            };
+            match next {
+                Some(c) => self.complete(c),
+                None => break,
+            }
+        }
+        self.poll(task_id)
+    }
+
+    /// 취소 — 진행 중(O)인 작업만 가능, 바로 T로 끝난다
+    /// 큐에 있던 작업은 실행되지 않고, 실행 중인 작업은 토큰을 보고 멈추며 결과는 버린다
+    pub fn cancel(&mut self, task_id: u64) -> bool {
+        self.drain_completions();
+        let Some(f) = self.in_flight.remove(&task_id) else { return false };
+        f.cancel.cancel();
+        let elapsed = f.submitted.elapsed().as_millis() as u64;
+        let result = self.finish(task_id, &f.task, TritState::Failed, ResultData::Text("취소됨".into()), None, elapsed);
+        self.keep_result(result);
+        true
+    }
+
+    /// 워커가 보낸 완료를 반영 (O → P/T)
+    fn drain_completions(&mut self) {
+        let done: Vec<Completion> = match &self.pool {
+            Some(pool) => pool.done.try_iter().collect(),
+            None => return,
+        };
+        for c in done {
+            self.complete(c);
+        }
+    }
+
+    fn complete(&mut self, c: Completion) {
+        // 취소된 작업(in_flight에 없음)의 늦은 결과는 버린다
+        let Some(f) = self.in_flight.remove(&c.task_id) else { return };
+        let elapsed = f.submitted.elapsed().as_millis() as u64;
+        let usage = if c.ran { Some(c.usage) } else { None };
+        let result = self.finish(c.task_id, &f.task, c.state, c.data, usage, elapsed);
+        self.keep_result(result);
+    }
+
+    fn keep_result(&mut self, result: TritResult) {
+        self.finished_order.push_back(result.task_id);
+        self.finished.insert(result.task_id, result);
+        while self.finished_order.len() > ASYNC_RESULTS {
+            if let Some(old) = self.finished_order.pop_front() {
+                self.finished.remove(&old);
             }
+        }
+    }
+
+    /// 간편 실행: 소스코드 컴파일+실행
+    pub fn run_source(&mut self, subject: &str, source: &str) -> TritResult {
+        self.run_source_limited(subject, source, ExecLimits::unlimited())
+    }
+
+    /// 한도 내 실행 — 한도 초과 시 결과 맵에 "한도" 코드 포함
+    /// 사이클/실행시간 초과 → O (더 높은 권한으로 재시도 가능)
+    /// 힙/출력 초과 → T
+    pub fn run_source_limited(&mut self, subject: &str, source: &str, limits: ExecLimits) -> TritResult {
+        self.run_source_checked(subject, source, limits, &ProgramLimits::unlimited())
+    }
+
+    /// 프로그램 한도 검사 후 실행 — 초과 시 실행 없이 T
+    /// 결과 맵: "한도"(max_instructions 등) · "제한" · "실제" · "행"
+    pub fn run_source_checked(&mut self, subject: &str, source: &str, limits: ExecLimits, program_limits: &ProgramLimits) -> TritResult {
+        let task = AppTask::new(TaskType::Execute, subject, source);
+        let defaults = self.task_limits;
+        self.submit_metered(task, |t| {
+            let budget = t.limits.or(defaults);
+            let hook = (!budget.is_unlimited()).then(|| budget.budget_hook(None));
+            execute_source(&t.payload, limits, program_limits, hook)
+        })
+    }
+
+    /// 실행 작업 제출 — 페이로드를 소스로 실행, 작업 한도(task.limits)를 VM 안에서 강제
+    pub fn run_task(&mut self, task: AppTask) -> TritResult {
+        let defaults = self.task_limits;
+        self.submit_metered(task, |t| {
+            let budget = t.limits.or(defaults);
+            let hook = (!budget.is_unlimited()).then(|| budget.budget_hook(None));
+            execute_source(&t.payload, ExecLimits::unlimited(), &ProgramLimits::unlimited(), hook)
         })
     }
 
     /// 간편 실행: WASM 컴파일
     pub fn compile_wasm(&mut self, subject: &str, source: &str) -> TritResult {
+        self.compile_wasm_checked(subject, source, &ProgramLimits::unlimited())
+    }
+
+    /// 프로그램 한도 검사 후 WASM 컴파일
+    pub fn compile_wasm_checked(&mut self, subject: &str, source: &str, program_limits: &ProgramLimits) -> TritResult {
         let task = AppTask::new(TaskType::Compile, subject, source);
         self.submit(task, |t| {
-            let result = crate::compiler::compile_with_info(&t.payload, "crowny");
-            if result.wasm_bytes.is_empty() {
-                (TritState::Failed, ResultData::Text("컴파일 실패".into()))
-            } else {
-                (TritState::Success, ResultData::Bytes(result.wasm_bytes))
+            let program = match crate::assembler::assemble_limited(&t.payload, program_limits) {
+                Ok(p) => p,
+                Err(e) => return (TritState::Failed, program_limit_data(&e)),
+            };
+            match crate::compiler::compile_program_with_info(&program, "crowny") {
+                Ok(result) if !result.wasm_bytes.is_empty() => (TritState::Success, ResultData::Bytes(result.wasm_bytes)),
+                Ok(_) => (TritState::Failed, ResultData::Text("컴파일 실패".into())),
+                Err(e) => (TritState::Failed, ResultData::Text(format!("컴파일 실패: {}", e))),
+            }
+        })
+    }
+
+    /// 빌드 → 아티팩트 저장소 (같은 소스는 한 번만 저장)
+    /// 결과 맵: source · wasm · bytecode · source_map 해시
+    pub fn build_artifacts(&mut self, subject: &str, source: &str) -> TritResult {
+        let task = AppTask::new(TaskType::Compile, subject, source);
+        let store = self.artifacts.clone();
+        self.submit(task, |t| {
+            match store.borrow_mut().store_build(&t.subject, &t.payload) {
+                Ok(build) => {
+                    let mut m = HashMap::new();
+                    m.insert("source".to_string(), ResultData::Text(build.source));
+                    m.insert("wasm".to_string(), ResultData::Text(build.wasm));
+                    m.insert("bytecode".to_string(), ResultData::Text(build.bytecode));
+                    m.insert("source_map".to_string(), ResultData::Text(build.source_map));
+                    (TritState::Success, ResultData::Map(m))
+                }
+                Err(e) => (TritState::Failed, ResultData::Text(e)),
             }
         })
     }
@@ -299,7 +798,50 @@ impl CrownyRuntime {
             subject: task.subject.clone(),
             state,
             elapsed_ms,
+            finished_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
         });
+        if let Some(hooks) = &self.webhooks {
+            hooks.borrow_mut().emit(EventKind::TaskFinished, JsonObject::new()
+                .int("task_id", task_id as i64)
+                .str("type", &task.task_type.to_string())
+                .str("subject", &task.subject)
+                .trit("state", state as i8)
+                .int("elapsed_ms", elapsed_ms as i64));
+        }
+        if let Some(hub) = &self.events {
+            hub.publish(JsonObject::new()
+                .str("type", "task")
+                .int("task_id", task_id as i64)
+                .str("task_type", &task.task_type.to_string())
+                .str("subject", &task.subject)
+                .trit("state", state as i8)
+                .int("elapsed_ms", elapsed_ms as i64));
+        }
+    }
+
+    /// 보류(O)로 멈춘 작업을 P/T로 강제 — 이전 상태 반환, 통계도 옮긴다
+    /// 권한 · 감사 기록은 호출 측(admin_override) 책임
+    pub fn override_task(&mut self, task_id: u64, state: TritState) -> Result<TritState, String> {
+        if state == TritState::Pending {
+            return Err("목표 상태는 P 또는 T".into());
+        }
+        let log = self.history.iter_mut().find(|t| t.task_id == task_id)
+            .ok_or_else(|| format!("작업 #{} 기록이 메모리에 없음", task_id))?;
+        if log.state != TritState::Pending {
+            return Err(format!("작업 #{}는 보류 상태가 아님 ({})", task_id, log.state));
+        }
+        log.state = state;
+        self.pending_count -= 1;
+        match state {
+            TritState::Success => self.success_count += 1,
+            _ => self.failed_count += 1,
+        }
+        Ok(TritState::Pending)
+    }
+
+    /// 작업 기록 조회 (커서 = 작업 ID, 계정 = 제출 주체)
+    pub fn task_history(&self, q: &Query) -> Page<TaskLog> {
+        self.history.page(q, |_, t| t.task_id)
     }
 
     /// 상태 출력
@@ -312,10 +854,69 @@ impl CrownyRuntime {
             println!("║  [{}] {}:{} → {} ({}ms)",
                 log.task_id, log.subject, log.task_type, log.state, log.elapsed_ms);
         }
+        if !self.in_flight.is_empty() {
+            println!("║ 진행 중(O): {}", self.in_flight.len());
+        }
         println!("╚═══════════════════════════════════════╝");
     }
 }
 
+/// 어셈블(한도 검사) + TVM 실행 — 동기 · 비동기 실행이 같은 판정을 쓴다
+/// 샌드박스 한도(ExecLimits) 중 사이클 · 시간 초과는 O, 작업 예산(budget) 초과는 항상 T
+fn execute_source(source: &str, limits: ExecLimits, program_limits: &ProgramLimits,
+                  budget: Option<BudgetHook>) -> (TritState, ResultData, Usage) {
+    let program = match crate::assembler::assemble_limited(source, program_limits) {
+        Ok(p) => p,
+        Err(e) => return (TritState::Failed, program_limit_data(&e), Usage::default()),
+    };
+    if program.is_empty() {
+        return (TritState::Failed, ResultData::Text("빈 프로그램".into()), Usage::default());
+    }
+    let mut vm = crate::vm::TVM::new();
+    vm.limits = limits;
+    vm.budget = budget;
+    vm.load(program);
+    let outcome = vm.run();
+    let usage = Usage::of(Resource::Cycles, vm.cycles);
+    let (state, data) = match outcome {
+        Ok(()) => {
+            let top = vm.stack.last()
+                .and_then(|v| v.as_int())
+                .unwrap_or(0);
+            (TritState::Success, ResultData::Integer(top))
+        }
+        Err(VmError { kind: err @ (VmErrorKind::LimitExceeded(kind) | VmErrorKind::BudgetExceeded(kind)), .. }) => {
+            let state = match (&err, kind) {
+                (VmErrorKind::LimitExceeded(_), LimitKind::Cycles | LimitKind::WallClock) => TritState::Pending,
+                _ => TritState::Failed,
+            };
+            let mut m = HashMap::new();
+            m.insert("오류".to_string(), ResultData::Text(err.to_string()));
+            m.insert("한도".to_string(), ResultData::Text(kind.code().to_string()));
+            m.insert("사이클".to_string(), ResultData::Integer(vm.cycles as i64));
+            (state, ResultData::Map(m))
+        }
+        Err(VmError { kind: VmErrorKind::Custom(msg), .. }) if msg == "취소됨" => {
+            (TritState::Failed, ResultData::Text(msg))
+        }
+        Err(e) => (TritState::Failed, ResultData::Text(format!("{:?}", e))),
+    };
+    (state, data, usage)
+}
+
+/// 프로그램 한도 초과 → 결과 맵
+fn program_limit_data(e: &ProgramLimitError) -> ResultData {
+    let mut m = HashMap::new();
+    m.insert("오류".to_string(), ResultData::Text(e.to_string()));
+    m.insert("한도".to_string(), ResultData::Text(e.kind.code().to_string()));
+    m.insert("제한".to_string(), ResultData::Integer(e.limit as i64));
+    m.insert("실제".to_string(), ResultData::Integer(e.actual as i64));
+    if let Some(line) = e.line {
+        m.insert("행".to_string(), ResultData::Integer(line as i64));
+    }
+    ResultData::Map(m)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,6 +931,170 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_car_async_submit_and_wait() {
+        let mut car = CrownyRuntime::new();
+        car.pool = Some(WorkerPool::new(2, 8));
+        let a = car.run_source_async("비동기", "넣어 7\n넣어 6\n곱해\n종료", ExecLimits::unlimited()).unwrap();
+        let b = car.run_source_async("비동기", "넣어 1\n넣어 0\n나눠\n종료", ExecLimits::unlimited()).unwrap();
+        let boom = car.submit_async(AppTask::new(TaskType::System, "비동기", ""), |_, _| panic!("실행기 오류")).unwrap();
+        assert_ne!(a, b);
+
+        let ra = car.wait(a.task_id, Duration::from_secs(5)).unwrap();
+        assert_eq!(ra.state, TritState::Success);
+        assert!(matches!(ra.data, ResultData::Integer(42)));
+        assert_eq!(car.wait(b.task_id, Duration::from_secs(5)).unwrap().state, TritState::Failed);
+        let rboom = car.wait(boom.task_id, Duration::from_secs(5)).unwrap();
+        assert_eq!((rboom.state, rboom.data.to_string()), (TritState::Failed, "실행기 패닉".into()));
+
+        // 끝난 결과는 다시 조회 가능, 이력 · 통계에도 반영
+        assert!(matches!(car.poll(a.task_id).unwrap().data, ResultData::Integer(42)));
+        assert_eq!(car.in_flight.len(), 0);
+        assert_eq!((car.success_count, car.failed_count), (1, 2));
+        assert_eq!(car.history().len(), 3);
+        assert!(car.poll(99).is_none());
+    }
+
+    #[test]
+    fn test_car_async_cancel_and_full_queue() {
+        use std::sync::atomic::AtomicBool;
+
+        let mut car = CrownyRuntime::new();
+        car.pool = Some(WorkerPool::new(1, 1));
+        let (started_tx, started) = mpsc::channel();
+        let (release, gate) = mpsc::channel::<()>();
+        let running = car.submit_async(AppTask::new(TaskType::System, "큐", ""), move |_, cancel| {
+            started_tx.send(()).unwrap();
+            gate.recv().ok();
+            let state = if cancel.is_cancelled() { TritState::Failed } else { TritState::Success };
+            (state, ResultData::None, Usage::default())
+        }).unwrap();
+        started.recv().unwrap();
+
+        // 워커 1개가 바쁨 → 큐 1칸 → 세 번째는 거부
+        let ran = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&ran);
+        let queued = car.submit_async(AppTask::new(TaskType::System, "큐", ""), move |_, _| {
+            flag.store(true, Ordering::SeqCst);
+            (TritState::Success, ResultData::None, Usage::default())
+        }).unwrap();
+        let full = car.submit_async(AppTask::new(TaskType::System, "큐", ""), |_, _| {
+            (TritState::Success, ResultData::None, Usage::default())
+        });
+        assert_eq!(full.unwrap_err(), "작업 큐 가득 참 (1)");
+        assert_eq!(car.poll(queued.task_id).unwrap().state, TritState::Pending);
+
+        assert!(car.cancel(queued.task_id));
+        assert!(car.cancel(running.task_id));
+        assert!(!car.cancel(running.task_id));
+        release.send(()).unwrap();
+
+        for h in [running, queued] {
+            let r = car.wait(h.task_id, Duration::from_secs(5)).unwrap();
+            assert_eq!((r.state, r.data.to_string()), (TritState::Failed, "취소됨".into()));
+        }
+        // 큐에서 취소된 작업은 실행되지 않는다 — 워커가 지나갈 때까지 대기
+        // (취소된 작업도 워커가 꺼낼 때까지 큐 한 칸을 차지한다)
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let after = loop {
+            match car.run_source_async("큐", "넣어 1\n종료", ExecLimits::unlimited()) {
+                Ok(h) => break h,
+                Err(_) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(1)),
+                Err(e) => panic!("{}", e),
+            }
+        };
+        assert_eq!(car.wait(after.task_id, Duration::from_secs(5)).unwrap().state, TritState::Success);
+        assert!(!ran.load(Ordering::SeqCst));
+        assert_eq!(car.in_flight.len(), 0);
+    }
+
+    #[test]
+    fn test_car_cycle_limit() {
+        let mut car = CrownyRuntime::new();
+        let limits = ExecLimits { max_cycles: Some(100), ..ExecLimits::unlimited() };
+        // 무한 루프: 넣어 0 → 점프(0)
+        let result = car.run_source_limited("테스트", "넣어 0\n점프", limits);
+        assert_eq!(result.state, TritState::Pending);
+        if let ResultData::Map(m) = &result.data {
+            assert!(matches!(m.get("한도"), Some(ResultData::Text(c)) if c == "cycles"));
+        } else {
+            panic!("한도 맵 필요");
+        }
+    }
+
+    #[test]
+    fn test_car_task_limits_abort_runaway() {
+        let mut car = CrownyRuntime::new();
+        let task = AppTask::new(TaskType::Execute, "테스트", "넣어 0\n점프").with_limits(TaskLimits::unlimited().cycles(50));
+        let result = car.run_task(task);
+        // 샌드박스 한도(O, 재시도)와 달리 작업 예산 초과는 T
+        assert_eq!(result.state, TritState::Failed);
+        let ResultData::Map(m) = &result.data else { panic!("한도 맵 필요") };
+        assert!(matches!(m.get("한도"), Some(ResultData::Text(c)) if c == "cycles"));
+        assert!(matches!(m.get("사이클"), Some(ResultData::Integer(51))));
+
+        let task = AppTask::new(TaskType::Execute, "테스트", "넣어 0\n점프").with_limits(TaskLimits::unlimited().ms(5));
+        let ResultData::Map(m) = car.run_task(task).data else { panic!("한도 맵 필요") };
+        assert!(matches!(m.get("한도"), Some(ResultData::Text(c)) if c == "wall_clock"));
+
+        let ok = car.run_task(AppTask::new(TaskType::Execute, "테스트", "넣어 1\n종료").with_limits(TaskLimits::unlimited().cycles(50)));
+        assert_eq!(ok.state, TritState::Success);
+    }
+
+    #[test]
+    fn test_car_default_task_limits() {
+        let defaults = TaskLimits::unlimited().cycles(20).ms(60_000);
+        assert_eq!(TaskLimits::unlimited().cycles(5).or(defaults), TaskLimits::unlimited().cycles(5).ms(60_000));
+
+        let mut car = CrownyRuntime::new().with_task_limits(defaults);
+        assert_eq!(car.run_source("테스트", "넣어 0\n점프").state, TritState::Failed);
+        let handle = car.run_source_async("테스트", "넣어 0\n점프", ExecLimits::unlimited()).unwrap();
+        let r = car.wait(handle.task_id, Duration::from_secs(5)).unwrap();
+        let ResultData::Map(m) = &r.data else { panic!("한도 맵 필요") };
+        assert!(matches!(m.get("한도"), Some(ResultData::Text(c)) if c == "cycles"));
+    }
+
+    #[test]
+    fn test_car_program_limits() {
+        let mut car = CrownyRuntime::new();
+        let limits = ProgramLimits { max_string_bytes: Some(4), ..ProgramLimits::unlimited() };
+        let result = car.run_source_checked("테스트", "넣어 1\n넣어 \"길다길다\"\n종료", ExecLimits::unlimited(), &limits);
+        assert_eq!(result.state, TritState::Failed);
+        let ResultData::Map(m) = &result.data else { panic!("한도 맵 필요") };
+        assert!(matches!(m.get("한도"), Some(ResultData::Text(c)) if c == "max_string_bytes"));
+        assert!(matches!(m.get("실제"), Some(ResultData::Integer(12))));
+        assert!(matches!(m.get("행"), Some(ResultData::Integer(2))));
+
+        let result = car.compile_wasm_checked("테스트", "넣어 \"길다길다\"", &limits);
+        assert_eq!(result.state, TritState::Failed);
+    }
+
+    #[test]
+    fn test_car_history_bounded_with_spill() {
+        let mut car = CrownyRuntime::new();
+        car.set_history_capacity(3);
+        car.spill_history(TritStore::new());
+        for i in 0..8 {
+            let subject = if i % 2 == 0 { "alice" } else { "bob" };
+            car.submit(AppTask::new(TaskType::DbQuery, subject, ""), |_| (TritState::Success, ResultData::None));
+        }
+        assert_eq!((car.history().len(), car.history().spilled()), (3, 5));
+        let page = car.task_history(&Query::new().account("alice").limit(10));
+        assert_eq!(page.items.iter().map(|t| t.task_id).collect::<Vec<_>>(), vec![7, 5, 3, 1]);
+    }
+
+    #[test]
+    fn test_car_build_artifacts_dedup() {
+        let mut car = CrownyRuntime::new();
+        let a = car.build_artifacts("앱", "넣어 42\n종료");
+        let b = car.build_artifacts("앱", "넣어 42\n종료");
+        assert_eq!(a.state, TritState::Success);
+        let (ResultData::Map(ma), ResultData::Map(mb)) = (&a.data, &b.data) else { panic!("해시 맵 필요") };
+        assert!(matches!((ma.get("wasm"), mb.get("wasm")), (Some(ResultData::Text(x)), Some(ResultData::Text(y))) if x == y));
+        assert_eq!(car.artifacts.borrow().stats().artifacts, 4);
+        assert_eq!(car.build_artifacts("앱", "; 빈 소스").state, TritState::Failed);
+    }
+
     #[test]
     fn test_car_compile_wasm() {
         let mut car = CrownyRuntime::new();
@@ -339,4 +1104,16 @@ mod tests {
             assert_eq!(&wasm[0..4], b"\0asm");
         }
     }
+
+    #[test]
+    fn test_trit_result_json_is_deterministic() {
+        let mut map = HashMap::new();
+        map.insert("z".to_string(), ResultData::Trit(0));
+        map.insert("a".to_string(), ResultData::List(vec![ResultData::Integer(1), ResultData::Bytes(vec![0, 255])]));
+        map.insert("m".to_string(), ResultData::Text("\"따옴\"".into()));
+        let r = TritResult { state: TritState::Pending, data: ResultData::Map(map), elapsed_ms: 3, task_id: 7 };
+        let json = r.to_json().build();
+        assert_eq!(json, r#"{"schema":"crowny.trit_result","schema_version":2,"state":"O","task_id":7,"elapsed_ms":3,"data_type":"map","data":{"a":[1,"00ff"],"m":"\"따옴\"","z":"O"}}"#);
+        assert_eq!(json, r.clone().to_json().build());
+    }
 }
@@ -4,9 +4,20 @@
 // 트랜잭션 풀 · 머클트리 · 밸리데이터 네트워크
 // ═══════════════════════════════════════════════════════════════
 
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::crypto;
+use crate::output::{JsonObject, say};
+use crate::params::{self, SharedParams};
+use crate::consortium::{Consortium, FinalitySignature, Member, MemberChange};
+use crate::integrations::{EventKind, SharedWebhooks};
+use crate::query::{Page, Query, Queryable};
+use crate::mempool::Mempool;
+use crate::watchdog::{ComponentKind, Heartbeat, Watchdog};
+
 fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
 
 // ═══════════════════════════════════════
@@ -51,6 +62,8 @@ pub struct Transaction {
     pub signature: String,
     pub timestamp: u64,
     pub hash: String,
+    /// 계정별 순번 — 있으면 해시에 들어가고, 멤풀 교체(RBF)의 기준이 된다
+    pub nonce: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -62,6 +75,7 @@ pub enum TxType {
     ContractDeploy, // P: 컨트랙트 배포
     ContractCall,   // O: 컨트랙트 호출
     Reward,         // P: 블록 보상
+    Attestation,    // P: 재현 빌드 증명 (attest)
 }
 
 impl std::fmt::Display for TxType {
@@ -74,32 +88,53 @@ impl std::fmt::Display for TxType {
             Self::ContractDeploy => write!(f, "컨트랙트배포"),
             Self::ContractCall => write!(f, "컨트랙트호출"),
             Self::Reward => write!(f, "블록보상"),
+            Self::Attestation => write!(f, "소스증명"),
         }
     }
 }
 
 impl Transaction {
     pub fn new(from: &str, to: &str, amount: u64, fee: u64, tx_type: TxType, data: &str) -> Self {
-        let ts = now_ms();
-        let raw = format!("{}:{}:{}:{}:{}", from, to, amount, ts, data);
-        let hash = trit_hash(&raw);
-        let sig = trit_hash(&format!("sig:{}", raw));
-        Self {
-            id: hash.clone(), from: from.into(), to: to.into(),
+        let mut tx = Self {
+            id: String::new(), from: from.into(), to: to.into(),
             amount, fee, data: data.into(), trit_type: tx_type,
-            signature: sig, timestamp: ts, hash,
+            signature: String::new(), timestamp: now_ms(), hash: String::new(),
+            nonce: None,
+        };
+        tx.seal();
+        tx
+    }
+
+    /// nonce를 붙이고 다시 서명
+    pub fn with_nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self.seal();
+        self
+    }
+
+    fn raw(&self) -> String {
+        let raw = format!("{}:{}:{}:{}:{}", self.from, self.to, self.amount, self.timestamp, self.data);
+        match self.nonce {
+            Some(n) => format!("{}:n{}", raw, n),
+            None => raw,
         }
     }
 
+    fn seal(&mut self) {
+        let raw = self.raw();
+        self.hash = trit_hash(&raw);
+        self.id = self.hash.clone();
+        self.signature = trit_hash(&format!("sig:{}", raw));
+    }
+
     pub fn verify(&self) -> bool {
-        let raw = format!("{}:{}:{}:{}:{}", self.from, self.to, self.amount, self.timestamp, self.data);
-        let expected = trit_hash(&raw);
-        self.hash == expected
+        self.hash == trit_hash(&self.raw())
     }
 
     pub fn trit(&self) -> i8 {
         match &self.trit_type {
-            TxType::Transfer | TxType::Stake | TxType::ContractDeploy | TxType::Reward => 1,
+            TxType::Transfer | TxType::Stake | TxType::ContractDeploy | TxType::Reward
+                | TxType::Attestation => 1,
             TxType::Vote | TxType::ContractCall => 0,
             TxType::Unstake => -1,
         }
@@ -144,21 +179,92 @@ impl MerkleNode {
     }
 }
 
+/// 잎 · 내부 노드 태그 — 내부 노드를 잎으로 내미는 위조 증명을 막는다
+const MERKLE_LEAF: u8 = 0x00;
+const MERKLE_NODE: u8 = 0x01;
+
+/// SHA-256(태그 ‖ 해시들) → hex
+fn merkle_hash(tag: u8, parts: &[String]) -> String {
+    let mut data = vec![tag];
+    for p in parts {
+        data.extend_from_slice(p.as_bytes());
+    }
+    crypto::to_hex(&crypto::sha256(&data))
+}
+
+fn merkle_leaves(tx_hashes: &[String]) -> Vec<String> {
+    tx_hashes.iter().map(|h| merkle_hash(MERKLE_LEAF, std::slice::from_ref(h))).collect()
+}
+
 pub fn build_merkle_root(tx_hashes: &[String]) -> String {
-    if tx_hashes.is_empty() { return trit_hash("empty"); }
-    if tx_hashes.len() == 1 { return tx_hashes[0].clone(); }
+    if tx_hashes.is_empty() { return merkle_hash(MERKLE_NODE, &[]); }
 
-    let mut nodes: Vec<String> = tx_hashes.to_vec();
+    let mut nodes = merkle_leaves(tx_hashes);
     while nodes.len() > 1 {
-        let mut next = Vec::new();
         // 3개씩 묶어서 해시 (3진 머클)
-        for chunk in nodes.chunks(3) {
-            let combined: String = chunk.iter().cloned().collect();
-            next.push(trit_hash(&combined));
-        }
-        nodes = next;
+        nodes = nodes.chunks(3).map(|c| merkle_hash(MERKLE_NODE, c)).collect();
     }
-    nodes[0].clone()
+    nodes.remove(0)
+}
+
+/// 머클 경로 한 단계 — 묶음(최대 3개) 안에서 내 자리와 형제 해시
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleStep {
+    pub position: usize,
+    pub siblings: Vec<String>,
+}
+
+/// 포함 증명 — 라이트 클라이언트는 블록 본문 없이 헤더의 루트와 대조한다
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleProof {
+    pub tx_hash: String,
+    pub block_index: u64,
+    pub merkle_root: String,
+    pub steps: Vec<MerkleStep>,
+}
+
+impl MerkleProof {
+    pub fn to_json(&self) -> JsonObject {
+        let path: Vec<String> = self.steps.iter()
+            .map(|s| format!("{}:{}", s.position, s.siblings.join(",")))
+            .collect();
+        JsonObject::new()
+            .str("tx_hash", &self.tx_hash)
+            .int("block", self.block_index as i64)
+            .str("merkle_root", &self.merkle_root)
+            .str("path", &path.join(";"))
+    }
+}
+
+/// build_merkle_root와 같은 묶음 규칙으로 index번째 잎의 경로 생성
+pub fn build_merkle_path(tx_hashes: &[String], index: usize) -> Option<Vec<MerkleStep>> {
+    if index >= tx_hashes.len() { return None; }
+    let mut steps = Vec::new();
+    let mut nodes = merkle_leaves(tx_hashes);
+    let mut at = index;
+    while nodes.len() > 1 {
+        let start = at / 3 * 3;
+        let chunk = &nodes[start..(start + 3).min(nodes.len())];
+        steps.push(MerkleStep {
+            position: at - start,
+            siblings: chunk.iter().enumerate().filter(|(i, _)| start + i != at).map(|(_, h)| h.clone()).collect(),
+        });
+        nodes = nodes.chunks(3).map(|c| merkle_hash(MERKLE_NODE, c)).collect();
+        at /= 3;
+    }
+    Some(steps)
+}
+
+/// 증명 검증 — 잎에서 경로를 따라 올라가 루트가 같은지
+pub fn verify_proof(proof: &MerkleProof) -> bool {
+    let mut current = merkle_hash(MERKLE_LEAF, std::slice::from_ref(&proof.tx_hash));
+    for step in &proof.steps {
+        if step.siblings.len() > 2 || step.position > step.siblings.len() { return false; }
+        let mut chunk = step.siblings.clone();
+        chunk.insert(step.position, current);
+        current = merkle_hash(MERKLE_NODE, &chunk);
+    }
+    current == proof.merkle_root
 }
 
 // ═══════════════════════════════════════
@@ -180,6 +286,17 @@ pub struct Block {
     pub tx_count: usize,
     pub total_fees: u64,
     pub block_reward: u64,
+    /// 컨소시엄 모드 확정 서명 (공개 모드는 비어 있음)
+    pub signatures: Vec<FinalitySignature>,
+}
+
+impl Queryable for Block {
+    fn timestamp(&self) -> u64 { self.timestamp }
+    fn trit(&self) -> i8 { self.trit_state }
+    /// 검증자이거나 블록 안 트랜잭션의 보낸/받은 쪽
+    fn involves(&self, account: &str) -> bool {
+        self.validator == account || self.transactions.iter().any(|tx| tx.from == account || tx.to == account)
+    }
 }
 
 impl Block {
@@ -211,6 +328,7 @@ impl Block {
             hash, merkle_root, transactions: txs, validator: validator.into(),
             pot_proof: proof, trit_state: consensus_trit,
             ctp_header: ctp, tx_count, total_fees, block_reward,
+            signatures: Vec::new(),
         }
     }
 
@@ -243,9 +361,38 @@ impl Block {
         true
     }
 
+    /// 블록 안 트랜잭션의 포함 증명 (없으면 None)
+    pub fn prove_inclusion(&self, tx_hash: &str) -> Option<MerkleProof> {
+        let tx_hashes: Vec<String> = self.transactions.iter().map(|t| t.hash.clone()).collect();
+        let index = tx_hashes.iter().position(|h| h == tx_hash)?;
+        Some(MerkleProof {
+            tx_hash: tx_hash.to_string(),
+            block_index: self.index,
+            merkle_root: self.merkle_root.clone(),
+            steps: build_merkle_path(&tx_hashes, index)?,
+        })
+    }
+
     pub fn ctp_string(&self) -> String {
         self.ctp_header.iter().map(|t| match t { 1 => 'P', -1 => 'T', _ => 'O' }).collect()
     }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .int("index", self.index as i64)
+            .trit("state", self.trit_state)
+            .str("hash", &self.hash)
+            .str("prev_hash", &self.prev_hash)
+            .str("merkle_root", &self.merkle_root)
+            .str("validator", &self.validator)
+            .int("timestamp", self.timestamp as i64)
+            .int("tx_count", self.tx_count as i64)
+            .int("total_fees", self.total_fees as i64)
+            .int("block_reward", self.block_reward as i64)
+            .str("ctp", &self.ctp_string())
+            .int("signatures", self.signatures.len() as i64)
+            .bool("verified", self.index == 0 || self.verify())
+    }
 }
 
 impl std::fmt::Display for Block {
@@ -343,6 +490,18 @@ impl Validator {
     }
 }
 
+impl Validator {
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("address", &self.address)
+            .str("name", &self.name)
+            .trit("state", self.trit())
+            .int("stake", self.stake as i64)
+            .int("blocks_produced", self.blocks_produced as i64)
+            .float("reputation", self.reputation)
+    }
+}
+
 impl std::fmt::Display for Validator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let trit = match self.trit() { 1 => "P", -1 => "T", _ => "O" };
@@ -356,30 +515,8 @@ impl std::fmt::Display for Validator {
 // 트랜잭션 풀
 // ═══════════════════════════════════════
 
-pub struct TxPool {
-    pub pending: Vec<Transaction>,
-    pub max_size: usize,
-}
-
-impl TxPool {
-    pub fn new(max_size: usize) -> Self { Self { pending: Vec::new(), max_size } }
-
-    pub fn add(&mut self, tx: Transaction) -> bool {
-        if self.pending.len() >= self.max_size { return false; }
-        if !tx.verify() { return false; }
-        self.pending.push(tx);
-        true
-    }
-
-    pub fn take_batch(&mut self, max_txs: usize) -> Vec<Transaction> {
-        // 수수료 높은 순으로 정렬 후 추출
-        self.pending.sort_by(|a, b| b.fee.cmp(&a.fee));
-        let batch: Vec<Transaction> = self.pending.drain(..self.pending.len().min(max_txs)).collect();
-        batch
-    }
-
-    pub fn size(&self) -> usize { self.pending.len() }
-}
+/// 대기 트랜잭션 풀 — 수수료 순서 · nonce · 교체 · 만료는 mempool 모듈
+pub type TxPool = Mempool;
 
 // ═══════════════════════════════════════
 // 블록체인
@@ -393,7 +530,21 @@ pub struct CrownyChain {
     pub stakes: HashMap<String, u64>,
     pub chain_id: String,
     pub block_time_ms: u64,
-    pub max_block_txs: usize,
+    /// 프로토콜 파라미터 (정족수 · 블록 크기 · 수수료) — 거버넌스로만 변경
+    pub params: SharedParams,
+    /// Some이면 허가형 컨소시엄 모드 — 밸리데이터는 허용 목록, 블록은 회원 서명으로 확정
+    pub consortium: Option<Consortium>,
+    /// 블록 확정 → block.finalized 웹훅
+    pub webhooks: Option<SharedWebhooks>,
+    /// 블록 생산 하트비트 (체크포인트: 높이)
+    heartbeat: Option<Heartbeat>,
+}
+
+impl crate::account::AccountLedger for CrownyChain {
+    fn ledger_name(&self) -> &str { "chain" }
+    fn holdings_of(&self, name: &str) -> Vec<(String, u64)> {
+        vec![("CRWN".into(), self.balance_of(name)), ("CRWN:staked".into(), self.stakes.get(name).copied().unwrap_or(0))]
+    }
 }
 
 impl CrownyChain {
@@ -410,11 +561,56 @@ impl CrownyChain {
             stakes: HashMap::new(),
             chain_id: "crowny-mainnet-1".into(),
             block_time_ms: 3000, // 3초 블록타임
-            max_block_txs: 100,
+            params: params::shared(),
+            consortium: None,
+            webhooks: None,
+            heartbeat: None,
+        }
+    }
+
+    /// 워치독에 블록 생산자로 등록 — produce_block 호출마다 하트비트,
+    /// 끊기면 체인을 검증해 마지막 체크포인트 높이 이상으로 온전할 때만 재개
+    pub fn watch(chain: &Rc<RefCell<CrownyChain>>, watchdog: &mut Watchdog) {
+        let mut c = chain.borrow_mut();
+        c.heartbeat = Some(watchdog.heartbeat_handle(ComponentKind::BlockProducer));
+        let chain = Rc::clone(chain);
+        watchdog.register(ComponentKind::BlockProducer, c.block_time_ms, Box::new(move |checkpoint| {
+            let mut c = chain.try_borrow_mut().map_err(|_| "체인 사용 중".to_string())?;
+            let (valid, checked) = c.verify_chain();
+            if !valid {
+                return Err(format!("블록 {} 검증 실패", checked + 1));
+            }
+            let last = checkpoint.and_then(|cp| cp.strip_prefix("height=")).and_then(|h| h.parse::<u64>().ok());
+            if let Some(last) = last.filter(|&h| c.height() < h) {
+                return Err(format!("높이 후퇴 {} < {}", c.height(), last));
+            }
+            // 멈춘 사이 만료된 트랜잭션 정리 후 재개
+            c.tx_pool.expire(now_ms());
+            Ok(())
+        }));
+    }
+
+    /// 허가형 컨소시엄 체인 — 회원이 곧 밸리데이터 (스테이크 없음)
+    pub fn consortium(name: &str, members: Vec<Member>) -> Self {
+        let mut chain = Self::new();
+        chain.chain_id = format!("crowny-consortium-{}", name);
+        for m in &members {
+            chain.validators.push(Validator::new(&m.address, &m.name, 0));
         }
+        chain.consortium = Some(Consortium::new(name, members));
+        chain
+    }
+
+    /// 다른 모듈(NFT · 컨트랙트 VM)과 같은 레지스트리를 공유
+    pub fn with_params(mut self, params: SharedParams) -> Self {
+        params.borrow_mut().advance_to(self.height());
+        self.params = params;
+        self
     }
 
     pub fn add_validator(&mut self, address: &str, name: &str, stake: u64) -> bool {
+        // 컨소시엄은 공개 스테이킹 대신 회원 거버넌스로만 밸리데이터 변경
+        if self.consortium.is_some() { return false; }
         let bal = self.balances.get(address).copied().unwrap_or(0);
         if bal < stake { return false; }
         *self.balances.entry(address.into()).or_insert(0) -= stake;
@@ -430,11 +626,20 @@ impl CrownyChain {
     pub fn transfer(&mut self, from: &str, to: &str, amount: u64, fee: u64) -> bool {
         let bal = self.balances.get(from).copied().unwrap_or(0);
         if bal < amount + fee { return false; }
-        let tx = Transaction::new(from, to, amount, fee, TxType::Transfer, "");
+        if fee < self.params.borrow().get(params::TX_BASE_FEE) { return false; }
+        let tx = Transaction::new(from, to, amount, fee, TxType::Transfer, "").with_nonce(self.tx_pool.next_nonce(from));
         self.tx_pool.add(tx)
     }
 
     pub fn select_validator(&self) -> Option<&Validator> {
+        if let Some(c) = &self.consortium {
+            // 회원 라운드 로빈
+            let active: Vec<&Validator> = self.validators.iter()
+                .filter(|v| v.active && c.is_member(&v.address))
+                .collect();
+            if active.is_empty() { return None; }
+            return Some(active[self.blocks.len() % active.len()]);
+        }
         // 스테이크 가중 선택 (시뮬레이션: 최고 스테이크)
         self.validators.iter()
             .filter(|v| v.active && v.reputation > 0.3)
@@ -442,17 +647,25 @@ impl CrownyChain {
     }
 
     pub fn produce_block(&mut self) -> Option<Block> {
+        if let Some(hb) = &self.heartbeat {
+            hb.beat(Some(&format!("height={}", self.height())));
+        }
         let validator = match self.select_validator() {
             Some(v) => v.name.clone(),
             None => return None,
         };
 
         // TX 배치 추출
-        let mut txs = self.tx_pool.take_batch(self.max_block_txs);
+        let (max_txs, quorum) = {
+            let p = self.params.borrow();
+            (p.get(params::MAX_BLOCK_TXS) as usize, p.get(params::CONSENSUS_QUORUM) as usize)
+        };
+        self.tx_pool.expire(now_ms());
+        let mut txs: Vec<Transaction> = self.tx_pool.get_pending(max_txs).into_iter().cloned().collect();
         if txs.is_empty() { return None; }
 
         // PoT 합의 투표
-        let mut proof = PoTProof::new(self.blocks.len() as u64, 2);
+        let mut proof = PoTProof::new(self.blocks.len() as u64, quorum);
         for v in &self.validators {
             if !v.active { continue; }
             let trit = if v.reputation > 0.5 { 1 } else { 0 };
@@ -466,7 +679,20 @@ impl CrownyChain {
         txs.push(reward_tx);
 
         let prev_hash = self.blocks.last().map(|b| b.hash.clone()).unwrap_or_default();
-        let block = Block::new(self.blocks.len() as u64, &prev_hash, txs, &validator, proof);
+        let mut block = Block::new(self.blocks.len() as u64, &prev_hash, txs, &validator, proof);
+
+        // 컨소시엄 확정 서명 — 부족하면 블록을 버린다 (트랜잭션은 풀에 그대로)
+        if let Some(c) = &self.consortium {
+            block.signatures = self.validators.iter()
+                .filter(|v| v.active && c.is_member(&v.address))
+                .map(|v| FinalitySignature::sign(&v.address, &block.hash))
+                .collect();
+            let pct = self.params.borrow().get(params::CONSORTIUM_FINALITY_PCT);
+            if c.verify_finality(block.index, &block.hash, &block.signatures, pct).is_err() {
+                return None;
+            }
+        }
+        self.tx_pool.remove_included(&block.transactions);
 
         // 잔액 업데이트
         for tx in &block.transactions {
@@ -485,9 +711,80 @@ impl CrownyChain {
         }
 
         self.blocks.push(block.clone());
+        self.params.borrow_mut().advance_to(self.height());
+        self.apply_member_changes();
+        if let Some(hooks) = &self.webhooks {
+            hooks.borrow_mut().emit(EventKind::BlockFinalized, block.to_json());
+        }
         Some(block)
     }
 
+    // ── 컨소시엄 회원 거버넌스 ──
+
+    /// 회원 가입/탈퇴 제안
+    pub fn propose_member(&mut self, proposer: &str, change: MemberChange) -> Result<u64, String> {
+        self.consortium.as_mut().ok_or("컨소시엄 모드 아님")?.propose(proposer, change)
+    }
+
+    /// 회원 투표 — 가결되면 다음 블록부터 밸리데이터 목록에 반영
+    pub fn vote_member(&mut self, id: u64, voter: &str, trit: i8) -> Result<i8, String> {
+        let pct = self.params.borrow().get(params::GOV_QUORUM_PCT);
+        self.consortium.as_mut().ok_or("컨소시엄 모드 아님")?.vote(id, voter, trit, pct)
+    }
+
+    fn apply_member_changes(&mut self) {
+        let next = self.height() + 1;
+        let Some(c) = self.consortium.as_mut() else { return };
+        for change in c.apply_passed(next) {
+            match change {
+                MemberChange::Add(m) => match self.validators.iter_mut().find(|v| v.address == m.address) {
+                    Some(v) => v.active = true,
+                    None => self.validators.push(Validator::new(&m.address, &m.name, 0)),
+                },
+                MemberChange::Remove(addr) => {
+                    if let Some(v) = self.validators.iter_mut().find(|v| v.address == addr) {
+                        v.active = false;
+                    }
+                }
+            }
+        }
+    }
+
+    // ── 파라미터 거버넌스 (스테이크 가중) ──
+
+    pub fn total_stake(&self) -> u64 {
+        match &self.consortium {
+            Some(c) => c.current().len() as u64,
+            None => self.stakes.values().sum(),
+        }
+    }
+
+    /// 투표 가중치 — 공개 모드는 스테이크, 컨소시엄은 회원 1인 1표
+    pub fn voting_weight(&self, address: &str) -> u64 {
+        match &self.consortium {
+            Some(c) => c.is_member(address) as u64,
+            None => self.stakes.get(address).copied().unwrap_or(0),
+        }
+    }
+
+    /// 가중치가 있는 계정만 제안 가능
+    pub fn propose_params(&mut self, proposer: &str, changes: Vec<(String, u64)>, effective_height: u64) -> Result<u64, String> {
+        if self.voting_weight(proposer) == 0 {
+            return Err(format!("{} 스테이크 없음", proposer));
+        }
+        self.params.borrow_mut().propose(proposer, changes, effective_height)
+    }
+
+    pub fn vote_params(&mut self, id: u64, voter: &str, trit: i8) -> Result<(), String> {
+        let weight = self.voting_weight(voter);
+        self.params.borrow_mut().vote(id, voter, weight, trit)
+    }
+
+    pub fn close_params(&mut self, id: u64) -> Result<i8, String> {
+        let total = self.total_stake();
+        self.params.borrow_mut().close(id, total)
+    }
+
     pub fn verify_chain(&self) -> (bool, usize) {
         let mut valid = 0;
         for i in 1..self.blocks.len() {
@@ -495,15 +792,54 @@ impl CrownyChain {
             let prev = &self.blocks[i - 1];
             if block.prev_hash != prev.hash { return (false, i); }
             if !block.verify() { return (false, i); }
+            if let Some(c) = &self.consortium {
+                let pct = self.params.borrow().value_at(params::CONSORTIUM_FINALITY_PCT, block.index).unwrap_or(100);
+                if c.verify_finality(block.index, &block.hash, &block.signatures, pct).is_err() { return (false, i); }
+            }
             valid += 1;
         }
         (true, valid)
     }
 
+    /// 증명 레코드를 트랜잭션으로 제출 (수수료 없음)
+    pub fn record_attestation(&mut self, att: &crate::attest::Attestation) -> bool {
+        self.tx_pool.add(att.to_tx())
+    }
+
+    /// 블록에 포함된 증명 — (블록 번호, 증명), 오래된 순
+    pub fn attestations(&self) -> Vec<(u64, crate::attest::Attestation)> {
+        self.blocks.iter()
+            .flat_map(|b| b.transactions.iter().map(move |tx| (b.index, tx)))
+            .filter_map(|(i, tx)| crate::attest::Attestation::from_tx(tx).map(|a| (i, a)))
+            .collect()
+    }
+
+    /// 탐색기 — 아티팩트의 최신 유효 증명 (source verified)
+    pub fn source_verified(&self, artifact_hash: &str) -> Option<crate::attest::Attestation> {
+        self.attestations().into_iter().rev()
+            .map(|(_, a)| a)
+            .find(|a| a.artifact_hash == artifact_hash && a.source_verified())
+    }
+
     pub fn height(&self) -> u64 { self.blocks.len() as u64 - 1 }
 
+    /// 트랜잭션 해시로 블록을 찾아 포함 증명
+    pub fn prove_inclusion(&self, tx_hash: &str) -> Option<MerkleProof> {
+        self.blocks.iter().find_map(|b| b.prove_inclusion(tx_hash))
+    }
+
+    /// 라이트 클라이언트용 헤더 루트
+    pub fn merkle_root_at(&self, index: u64) -> Option<&str> {
+        self.blocks.get(index as usize).map(|b| b.merkle_root.as_str())
+    }
+
     pub fn latest(&self) -> Option<&Block> { self.blocks.last() }
 
+    /// 블록 조회 (커서 = 블록 번호)
+    pub fn query_blocks(&self, q: &Query) -> Page<&Block> {
+        q.page(self.blocks.iter().map(|b| (b.index, b)))
+    }
+
     pub fn balance_of(&self, address: &str) -> u64 {
         self.balances.get(address).copied().unwrap_or(0)
     }
@@ -523,120 +859,231 @@ impl CrownyChain {
 
 // ═══ 데모 ═══
 
-pub fn demo_chain() {
-    println!("╔═══════════════════════════════════════════════╗");
-    println!("║  Crowny Chain — 3진 블록체인                    ║");
-    println!("║  PoT 합의 · 블록 생성/검증 · 체인 연결           ║");
-    println!("╚═══════════════════════════════════════════════╝");
-    println!();
+// ═══════════════════════════════════════
+// 샘플 체인 (데모 · CLI 조회 공용)
+// ═══════════════════════════════════════
+
+const SAMPLE_DISTRIBUTION: [(&str, u64); 5] = [
+    ("alice", 1_000_000), ("bob", 500_000), ("carol", 300_000),
+    ("dave", 200_000), ("eve", 150_000),
+];
+
+const SAMPLE_VALIDATORS: [(&str, &str, u64); 3] = [
+    ("alice", "Alice-Node", 100_000),
+    ("bob", "Bob-Node", 80_000),
+    ("carol", "Carol-Node", 50_000),
+];
+
+const SAMPLE_TXS: [(&str, &str, u64, u64, &str); 8] = [
+    ("alice", "bob", 10_000, 10, "서비스 대금"),
+    ("bob", "carol", 5_000, 5, "합의 보수"),
+    ("carol", "dave", 2_000, 3, "NFT 구매"),
+    ("alice", "eve", 15_000, 10, "플랫폼 수수료"),
+    ("dave", "alice", 1_000, 2, "리펀드"),
+    ("eve", "bob", 3_000, 5, "스테이킹 대행"),
+    ("alice", "carol", 8_000, 8, "거버넌스 보상"),
+    ("bob", "dave", 4_000, 4, "개발 용역"),
+];
+
+pub const SAMPLE_ACCOUNTS: [&str; 6] = ["treasury", "alice", "bob", "carol", "dave", "eve"];
+
+/// 샘플 분배 (treasury → 계정)
+fn distribute(chain: &mut CrownyChain, addr: &str, amount: u64) {
+    let bal = chain.balances.get_mut("treasury").unwrap();
+    *bal -= amount;
+    chain.balances.insert(addr.to_string(), amount);
+}
 
+/// 라운드별 추가 전송 (0라운드는 풀에 쌓인 TX만)
+fn sample_round_transfers(chain: &mut CrownyChain, round: u64) {
+    if round > 0 {
+        chain.transfer("alice", "bob", 1000 * (round + 1), 5);
+        chain.transfer("bob", "carol", 500 * (round + 1), 3);
+    }
+}
+
+/// 데모와 같은 상태의 체인을 조용히 구성 — CLI 조회(`chain block get` 등)용
+pub fn sample_chain() -> CrownyChain {
     let mut chain = CrownyChain::new();
+    for (addr, amount) in SAMPLE_DISTRIBUTION {
+        distribute(&mut chain, addr, amount);
+    }
+    for (addr, name, stake) in SAMPLE_VALIDATORS {
+        chain.add_validator(addr, name, stake);
+    }
+    for (from, to, amount, fee, memo) in SAMPLE_TXS {
+        chain.submit_tx(Transaction::new(from, to, amount, fee, TxType::Transfer, memo));
+    }
+    for round in 0..3 {
+        sample_round_transfers(&mut chain, round);
+        chain.produce_block();
+    }
+    chain
+}
+
+/// 데모 — 최종 체인 무결성을 Trit으로 반환 (--json이면 요약 객체 출력)
+pub fn demo_chain() -> i8 {
+    crate::output::banner("╔═══════════════════════════════════════════════╗\n\
+                           ║  Crowny Chain — 3진 블록체인                    ║\n\
+                           ║  PoT 합의 · 블록 생성/검증 · 체인 연결           ║\n\
+                           ╚═══════════════════════════════════════════════╝\n");
+
+    // 블록 생산자는 워치독 감시 아래에서 돈다
+    let shared = Rc::new(RefCell::new(CrownyChain::new()));
+    let mut watchdog = Watchdog::new(3, 3);
+    CrownyChain::watch(&shared, &mut watchdog);
+    let mut chain = shared.borrow_mut();
 
     // 1. 제네시스
-    println!("━━━ 1. 제네시스 블록 ━━━");
+    say!("━━━ 1. 제네시스 블록 ━━━");
     let genesis = &chain.blocks[0];
-    println!("  {}", genesis);
-    println!("  머클: {:.20}...", genesis.merkle_root);
-    println!("  Treasury: {} CRWN", chain.balance_of("treasury"));
-    println!();
+    say!("  {}", genesis);
+    say!("  머클: {:.20}...", genesis.merkle_root);
+    say!("  Treasury: {} CRWN", chain.balance_of("treasury"));
+    say!();
 
     // 2. 토큰 분배
-    println!("━━━ 2. 초기 분배 ━━━");
-    let distributions = vec![
-        ("alice", 1_000_000), ("bob", 500_000), ("carol", 300_000),
-        ("dave", 200_000), ("eve", 150_000),
-    ];
-    for (addr, amount) in &distributions {
-        let bal = chain.balances.get_mut("treasury").unwrap();
-        *bal -= amount;
-        chain.balances.insert(addr.to_string(), *amount);
-        println!("  [P] treasury → {} : {} CRWN", addr, amount);
-    }
-    println!("  Treasury 잔액: {} CRWN", chain.balance_of("treasury"));
-    println!();
+    say!("━━━ 2. 초기 분배 ━━━");
+    for (addr, amount) in SAMPLE_DISTRIBUTION {
+        distribute(&mut chain, addr, amount);
+        say!("  [P] treasury → {} : {} CRWN", addr, amount);
+    }
+    say!("  Treasury 잔액: {} CRWN", chain.balance_of("treasury"));
+    say!();
 
     // 3. 밸리데이터 등록
-    println!("━━━ 3. 밸리데이터 등록 ━━━");
-    chain.add_validator("alice", "Alice-Node", 100_000);
-    chain.add_validator("bob", "Bob-Node", 80_000);
-    chain.add_validator("carol", "Carol-Node", 50_000);
+    say!("━━━ 3. 밸리데이터 등록 ━━━");
+    for (addr, name, stake) in SAMPLE_VALIDATORS {
+        chain.add_validator(addr, name, stake);
+    }
     for v in &chain.validators {
-        println!("  {}", v);
+        say!("  {}", v);
     }
-    println!();
+    say!();
 
     // 4. 트랜잭션 제출
-    println!("━━━ 4. 트랜잭션 제출 ━━━");
-    let txs = vec![
-        ("alice", "bob", 10_000, 10, "서비스 대금"),
-        ("bob", "carol", 5_000, 5, "합의 보수"),
-        ("carol", "dave", 2_000, 3, "NFT 구매"),
-        ("alice", "eve", 15_000, 10, "플랫폼 수수료"),
-        ("dave", "alice", 1_000, 2, "리펀드"),
-        ("eve", "bob", 3_000, 5, "스테이킹 대행"),
-        ("alice", "carol", 8_000, 8, "거버넌스 보상"),
-        ("bob", "dave", 4_000, 4, "개발 용역"),
-    ];
-    for (from, to, amount, fee, memo) in &txs {
-        let tx = Transaction::new(from, to, *amount, *fee, TxType::Transfer, memo);
-        println!("  {}", tx);
+    say!("━━━ 4. 트랜잭션 제출 ━━━");
+    for (from, to, amount, fee, memo) in SAMPLE_TXS {
+        let tx = Transaction::new(from, to, amount, fee, TxType::Transfer, memo);
+        say!("  {}", tx);
         chain.submit_tx(tx);
     }
-    println!("  TX풀: {} pending", chain.tx_pool.size());
-    println!();
+    say!("  TX풀: {} pending", chain.tx_pool.size());
+    say!();
 
     // 5. 블록 생성
-    println!("━━━ 5. 블록 생성 (PoT 합의) ━━━");
+    say!("━━━ 5. 블록 생성 (PoT 합의) ━━━");
     for round in 0..3 {
         // 추가 TX
-        if round > 0 {
-            chain.transfer(&format!("alice"), "bob", 1000 * (round + 1) as u64, 5);
-            chain.transfer("bob", "carol", 500 * (round + 1) as u64, 3);
-        }
+        sample_round_transfers(&mut chain, round);
 
         if let Some(block) = chain.produce_block() {
-            println!("  ┌─ {}", block);
-            println!("  │  밸리데이터: {} | 머클: {:.20}...", block.validator, block.merkle_root);
-            println!("  │  PoT: {} 투표 (신뢰도 {:.0}%)", block.pot_proof.votes.len(), block.pot_proof.confidence() * 100.0);
+            say!("  ┌─ {}", block);
+            say!("  │  밸리데이터: {} | 머클: {:.20}...", block.validator, block.merkle_root);
+            if let Some(proof) = block.transactions.first().and_then(|tx| block.prove_inclusion(&tx.hash)) {
+                say!("  │  포함 증명: 첫 TX {} 단계 → {}", proof.steps.len(), if verify_proof(&proof) { "P" } else { "T" });
+            }
+            say!("  │  PoT: {} 투표 (신뢰도 {:.0}%)", block.pot_proof.votes.len(), block.pot_proof.confidence() * 100.0);
             for vote in &block.pot_proof.votes {
                 let trit = match vote.trit { 1 => "P", -1 => "T", _ => "O" };
-                println!("  │    [{}] {} — {}", trit, vote.validator, vote.reason);
+                say!("  │    [{}] {} — {}", trit, vote.validator, vote.reason);
             }
-            println!("  │  prev: {:.20}...", block.prev_hash);
-            println!("  └─ hash: {:.20}...", block.hash);
-            println!();
+            say!("  │  prev: {:.20}...", block.prev_hash);
+            say!("  └─ hash: {:.20}...", block.hash);
+            say!();
         }
     }
 
+    // 5-1. 생산자 감시 — 생산이 멎으면 체인을 검증하고 체크포인트 높이에서 재개
+    say!("━━━ 5-1. 블록 생산자 감시 ━━━");
+    drop(chain);
+    watchdog.tick();
+    say!("  {}", watchdog.summary());
+    let stalled = now_ms() + shared.borrow().block_time_ms * 4;
+    for action in watchdog.tick_at(stalled) {
+        say!("  생산 중단 → {:?}", action);
+    }
+    say!("  {}", watchdog.summary());
+    say!();
+    let mut chain = shared.borrow_mut();
+
     // 6. 체인 검증
-    println!("━━━ 6. 체인 검증 ━━━");
+    say!("━━━ 6. 체인 검증 ━━━");
     let (valid, count) = chain.verify_chain();
-    println!("  체인 무결성: {} ({} 블록 검증)", if valid { "✓ 유효" } else { "✗ 무효" }, count);
+    say!("  체인 무결성: {} ({} 블록 검증)", if valid { "✓ 유효" } else { "✗ 무효" }, count);
     for (i, block) in chain.blocks.iter().enumerate() {
         let trit = match block.trit_state { 1 => "P", -1 => "T", _ => "O" };
         let verified = if i == 0 { true } else { block.verify() };
         let v = if verified { "✓" } else { "✗" };
-        println!("  {} #{} [{}] {} tx | {:.16}.. → {:.16}..",
+        say!("  {} #{} [{}] {} tx | {:.16}.. → {:.16}..",
             v, block.index, trit, block.tx_count,
             block.prev_hash, block.hash);
     }
-    println!();
+    say!();
 
     // 7. 잔액 확인
-    println!("━━━ 7. 최종 잔액 ━━━");
-    let accounts = vec!["treasury", "alice", "bob", "carol", "dave", "eve"];
-    for addr in &accounts {
+    say!("━━━ 7. 최종 잔액 ━━━");
+    for addr in &SAMPLE_ACCOUNTS {
         let bal = chain.balance_of(addr);
         let staked = chain.stakes.get(*addr).copied().unwrap_or(0);
-        println!("  {:<10} {:>12} CRWN  (staked: {})", addr, bal, staked);
+        say!("  {:<10} {:>12} CRWN  (staked: {})", addr, bal, staked);
     }
-    println!();
-
-    // 8. 체인 요약
-    println!("━━━ 8. 체인 요약 ━━━");
-    println!("{}", chain.summary());
-    println!();
-    println!("✓ Crowny Chain 데모 완료");
+    say!();
+
+    // 8. 파라미터 거버넌스
+    say!("━━━ 8. 파라미터 거버넌스 ━━━");
+    let effective = chain.height() + 2;
+    let changes = vec![(params::MAX_BLOCK_TXS.to_string(), 50), (params::MARKET_FEE_BPS.to_string(), 200)];
+    match chain.propose_params("alice", changes, effective) {
+        Ok(id) => {
+            for (voter, trit) in [("alice", 1), ("bob", 1), ("carol", -1)] {
+                let _ = chain.vote_params(id, voter, trit);
+            }
+            let _ = chain.close_params(id);
+            if let Some(p) = chain.params.borrow().proposal(id) {
+                say!("  {}", p);
+            }
+            for round in 3..5 {
+                sample_round_transfers(&mut chain, round);
+                chain.produce_block();
+            }
+            for change in &chain.params.borrow().audit {
+                say!("  [P] {}", change);
+            }
+        }
+        Err(e) => say!("  [T] {}", e),
+    }
+    say!("  {} = {} | {} = {}", params::MAX_BLOCK_TXS, chain.params.borrow().get(params::MAX_BLOCK_TXS),
+        params::MARKET_FEE_BPS, chain.params.borrow().get(params::MARKET_FEE_BPS));
+    say!();
+
+    // 9. 체인 요약
+    say!("━━━ 9. 체인 요약 ━━━");
+    say!("{}", chain.summary());
+    say!();
+    say!("✓ Crowny Chain 데모 완료");
+
+    let state = if valid { 1 } else { -1 };
+    if crate::output::is_json() {
+        let balances = SAMPLE_ACCOUNTS.iter()
+            .fold(JsonObject::new(), |obj, addr| obj.int(addr, chain.balance_of(addr) as i64));
+        let validators = chain.validators.iter()
+            .map(|v| v.address.clone())
+            .collect::<Vec<_>>();
+        let mut obj = JsonObject::new()
+            .str("command", "chain")
+            .trit("state", state)
+            .int("height", chain.height() as i64)
+            .int("verified", count as i64)
+            .int("pending_txs", chain.tx_pool.size() as i64)
+            .strs("validators", &validators)
+            .object("balances", balances);
+        if let Some(head) = chain.latest() {
+            obj = obj.str("head", &head.hash);
+        }
+        obj.emit();
+    }
+    state
 }
 
 // ═══ 테스트 ═══
@@ -672,7 +1119,7 @@ mod tests {
     fn test_merkle_root() {
         let hashes = vec![trit_hash("a"), trit_hash("b"), trit_hash("c")];
         let root = build_merkle_root(&hashes);
-        assert!(root.starts_with("0t"));
+        assert_eq!(root.len(), 64);
         // 같은 입력 → 같은 루트
         let root2 = build_merkle_root(&hashes);
         assert_eq!(root, root2);
@@ -681,7 +1128,8 @@ mod tests {
     #[test]
     fn test_merkle_empty() {
         let root = build_merkle_root(&[]);
-        assert!(root.starts_with("0t"));
+        assert_eq!(root.len(), 64);
+        assert_ne!(root, build_merkle_root(&[String::new()]));
     }
 
     #[test]
@@ -768,6 +1216,87 @@ mod tests {
         assert_eq!(chain.blocks.len(), 2);
     }
 
+    #[test]
+    fn test_block_filled_from_mempool_by_fee_and_nonce() {
+        let mut chain = CrownyChain::new();
+        chain.balances.insert("alice".into(), 1_000_000);
+        chain.balances.insert("bob".into(), 500_000);
+        chain.balances.insert("carol".into(), 1_000_000);
+        chain.add_validator("alice", "Alice", 100_000);
+        chain.add_validator("bob", "Bob", 80_000);
+        assert!(chain.transfer("alice", "bob", 100, 10));
+        assert!(chain.transfer("alice", "bob", 200, 30));
+        assert!(chain.transfer("carol", "bob", 300, 20));
+        assert_eq!(chain.tx_pool.next_nonce("alice"), 2);
+
+        let block = chain.produce_block().unwrap();
+        // 수수료 순이되 alice 는 nonce 0 → 1
+        let order: Vec<(&str, u64)> = block.transactions.iter()
+            .filter(|t| t.trit_type == TxType::Transfer)
+            .map(|t| (t.from.as_str(), t.fee)).collect();
+        assert_eq!(order, vec![("carol", 20), ("alice", 10), ("alice", 30)]);
+        assert_eq!(chain.tx_pool.size(), 0);
+        assert_eq!(chain.tx_pool.confirmed_nonce("alice"), 2);
+
+        // 이미 들어간 nonce 는 다시 받지 않는다
+        let replay = Transaction::new("alice", "bob", 1, 99, TxType::Transfer, "").with_nonce(1);
+        assert!(!chain.submit_tx(replay));
+        assert_eq!(chain.tx_pool.stats.included, 3);
+    }
+
+    #[test]
+    fn test_query_blocks_by_party() {
+        let mut chain = CrownyChain::new();
+        chain.balances.insert("alice".into(), 1_000_000);
+        chain.balances.insert("bob".into(), 500_000);
+        chain.add_validator("alice", "Alice", 100_000);
+        chain.add_validator("bob", "Bob", 80_000);
+        chain.transfer("alice", "bob", 1000, 10);
+        chain.produce_block().unwrap();
+        chain.transfer("alice", "carol", 500, 10);
+        chain.produce_block().unwrap();
+
+        let all = chain.query_blocks(&Query::new().order(crate::query::SortOrder::Asc));
+        assert_eq!(all.items.iter().map(|b| b.index).collect::<Vec<_>>(), vec![0, 1, 2]);
+        // 전송 당사자 bob은 1번 블록에만
+        let bob = chain.query_blocks(&Query::new().account("bob"));
+        assert_eq!(bob.items.iter().map(|b| b.index).collect::<Vec<_>>(), vec![1]);
+        // 커서부터 내림차순 — 다음 커서로 이어 읽기
+        let page = chain.query_blocks(&Query::new().cursor(2).limit(1));
+        assert_eq!((page.items[0].index, page.next_cursor), (2, Some(1)));
+        let next = chain.query_blocks(&Query::new().cursor(1).limit(1));
+        assert_eq!(next.items[0].index, 1);
+    }
+
+    #[test]
+    fn test_block_producer_watchdog_restart() {
+        use crate::watchdog::WatchdogAction;
+
+        let chain = Rc::new(RefCell::new(CrownyChain::new()));
+        let mut wd = Watchdog::new(3, 3);
+        CrownyChain::watch(&chain, &mut wd);
+        {
+            let mut c = chain.borrow_mut();
+            c.balances.insert("alice".into(), 1_000_000);
+            c.balances.insert("bob".into(), 500_000);
+            c.add_validator("alice", "Alice", 100_000);
+            c.add_validator("bob", "Bob", 80_000);
+            c.transfer("alice", "bob", 1000, 10);
+            assert!(c.produce_block().is_some());
+            c.produce_block(); // 빈 풀이어도 생산 루프는 살아 있다
+        }
+        let far = now_ms() + 60_000;
+        // 생산이 멎음 → 검증 통과 → 재개
+        assert_eq!(wd.tick_at(far), vec![WatchdogAction::Restarted(ComponentKind::BlockProducer)]);
+        assert_eq!(wd.component(&ComponentKind::BlockProducer).unwrap().checkpoint.as_deref(), Some("height=1"));
+
+        // 체인이 깨졌으면 재시작 실패
+        chain.borrow_mut().blocks[1].prev_hash = "변조".into();
+        let actions = wd.tick_at(far + 60_000);
+        assert!(matches!(actions.as_slice(), [WatchdogAction::RestartFailed(ComponentKind::BlockProducer, e)] if e.contains("검증 실패")),
+            "{:?}", actions);
+    }
+
     #[test]
     fn test_chain_verify() {
         let mut chain = CrownyChain::new();
@@ -792,4 +1321,175 @@ mod tests {
         let genesis = Block::genesis();
         assert_eq!(genesis.ctp_header[0], 1); // consensus P
     }
+
+    #[test]
+    fn test_param_governance_on_chain() {
+        let shared = params::shared();
+        let mut chain = sample_chain().with_params(shared.clone());
+        let nft = crate::nft::CrownyNFT::new().with_params(shared.clone());
+        let h = chain.height();
+        assert!(chain.propose_params("eve", vec![(params::MARKET_FEE_BPS.into(), 100)], h + 2).is_err());
+
+        let id = chain.propose_params("alice", vec![
+            (params::MARKET_FEE_BPS.into(), 100),
+            (params::TX_BASE_FEE.into(), 20),
+        ], h + 2).unwrap();
+        chain.vote_params(id, "alice", 1).unwrap();
+        assert!(chain.vote_params(id, "eve", 1).is_err());
+        // alice 100k / 230k 스테이크 — 정족수 50% 미달
+        assert_eq!(chain.close_params(id), Ok(-1));
+
+        let id = chain.propose_params("alice", vec![
+            (params::MARKET_FEE_BPS.into(), 100),
+            (params::TX_BASE_FEE.into(), 20),
+        ], h + 2).unwrap();
+        chain.vote_params(id, "alice", 1).unwrap();
+        chain.vote_params(id, "bob", 1).unwrap();
+        assert_eq!(chain.close_params(id), Ok(1));
+
+        // 발효 전 — 기존 수수료로 전송 가능
+        assert!(chain.transfer("alice", "bob", 10, 10));
+        chain.produce_block().unwrap();
+        assert_eq!(nft.market_fee_bps(), 250);
+        assert!(chain.transfer("alice", "bob", 10, 10));
+        chain.produce_block().unwrap();
+        assert_eq!(chain.height(), h + 2);
+        assert_eq!(nft.market_fee_bps(), 100);
+        assert!(!chain.transfer("alice", "bob", 10, 10));
+        assert_eq!(shared.borrow().history(params::TX_BASE_FEE)[0].height, h + 2);
+    }
+
+    #[test]
+    fn test_consortium_finality_and_membership() {
+        let mut chain = CrownyChain::consortium("pilot", vec![
+            Member::new("snuh", "서울대병원", "의료"),
+            Member::new("amc", "아산병원", "의료"),
+            Member::new("kaist", "KAIST", "교육"),
+        ]);
+        chain.balances.insert("snuh".into(), 10_000);
+        assert!(!chain.add_validator("snuh", "snuh-2", 0));
+
+        chain.transfer("snuh", "kaist", 100, 5);
+        assert_eq!(chain.produce_block().unwrap().signatures.len(), 3);
+
+        // 회원 하나가 빠지면 2/3 < 67% — 확정 안 됨, 트랜잭션은 풀에 남음
+        chain.validators[1].active = false;
+        chain.transfer("snuh", "amc", 100, 5);
+        assert!(chain.produce_block().is_none());
+        assert_eq!(chain.tx_pool.size(), 1);
+        chain.validators[1].active = true;
+        assert!(chain.produce_block().is_some());
+
+        // 거버넌스로 교육 기관 가입 — 다음 블록 이후 서명자
+        let id = chain.propose_member("kaist", MemberChange::Add(Member::new("ebs", "EBS", "교육"))).unwrap();
+        assert!(chain.vote_member(id, "ebs", 1).is_err());
+        chain.vote_member(id, "kaist", 1).unwrap();
+        assert_eq!(chain.vote_member(id, "snuh", 1), Ok(1));
+        chain.transfer("snuh", "kaist", 1, 5);
+        assert_eq!(chain.produce_block().unwrap().signatures.len(), 3);
+        chain.transfer("snuh", "kaist", 1, 5);
+        assert_eq!(chain.produce_block().unwrap().signatures.len(), 4);
+        assert!(chain.verify_chain().0);
+
+        // 탈퇴 — 다음 블록 이후 밸리데이터 비활성, 과거 블록 검증은 그대로
+        let id = chain.propose_member("snuh", MemberChange::Remove("amc".into())).unwrap();
+        chain.vote_member(id, "snuh", 1).unwrap();
+        assert_eq!(chain.vote_member(id, "kaist", 1), Ok(1));
+        chain.transfer("snuh", "kaist", 1, 5);
+        assert_eq!(chain.produce_block().unwrap().signatures.len(), 4);
+        chain.transfer("snuh", "kaist", 1, 5);
+        assert_eq!(chain.produce_block().unwrap().signatures.len(), 3);
+        assert!(!chain.validators.iter().find(|v| v.address == "amc").unwrap().active);
+        assert!(chain.verify_chain().0);
+
+        // 서명을 떼어 내면 체인 검증 실패
+        chain.blocks[1].signatures.truncate(1);
+        assert!(!chain.verify_chain().0);
+    }
+
+    #[test]
+    fn test_attestation_on_chain() {
+        use crate::artifacts::ArtifactKind;
+        let src = "넣어 1\n보여줘\n종료";
+        let wasm = crate::attest::rebuild(src, ArtifactKind::Wasm).unwrap();
+        let good = crate::attest::attest(src, &wasm, "alice").unwrap();
+        let bad = crate::attest::attest("넣어 2\n종료", &wasm, "bob").unwrap();
+
+        let mut chain = sample_chain();
+        assert!(chain.record_attestation(&good) && chain.record_attestation(&bad));
+        assert!(chain.source_verified(&good.artifact_hash).is_none());
+        let block = chain.produce_block().unwrap();
+        assert_eq!(block.transactions[0].trit_type, TxType::Attestation);
+        assert!(chain.verify_chain().0);
+
+        assert_eq!(chain.attestations().len(), 2);
+        let found = chain.source_verified(&good.artifact_hash).unwrap();
+        assert_eq!((found.attester.as_str(), found.state), ("alice", 1));
+    }
+
+    #[test]
+    fn test_merkle_inclusion_proofs() {
+        // 7개 → 3묶음(3,3,1) → 1묶음(3) — 남는 묶음까지 루트와 같은 규칙이어야 한다
+        let txs: Vec<Transaction> = (0..7)
+            .map(|i| Transaction::new("alice", "bob", 10 + i, 1, TxType::Transfer, &format!("tx{}", i)))
+            .collect();
+        let block = Block::new(1, "0t0", txs.clone(), "alice", PoTProof::new(1, 1));
+        for tx in &txs {
+            let proof = block.prove_inclusion(&tx.hash).unwrap();
+            assert_eq!(proof.steps.len(), 2);
+            assert!(verify_proof(&proof));
+        }
+        assert!(block.prove_inclusion("0tPPP").is_none());
+
+        // 형제 해시 · 자리 · 잎을 바꾸면 실패
+        let proof = block.prove_inclusion(&txs[4].hash).unwrap();
+        let mut bad = proof.clone();
+        bad.steps[0].siblings[0] = trit_hash("forged");
+        assert!(!verify_proof(&bad));
+        let mut bad = proof.clone();
+        bad.steps[0].position = 0;
+        assert!(!verify_proof(&bad));
+        let mut bad = proof;
+        bad.tx_hash = txs[0].hash.clone();
+        assert!(!verify_proof(&bad));
+
+        // 내부 노드를 잎으로 내밀어도 실패 — 잎 · 노드 태그가 다르다
+        let hashes: Vec<String> = txs.iter().map(|t| t.hash.clone()).collect();
+        let leaves = merkle_leaves(&hashes);
+        let inner = MerkleProof {
+            tx_hash: merkle_hash(MERKLE_NODE, &leaves[0..3]),
+            block_index: 1,
+            merkle_root: block.merkle_root.clone(),
+            steps: vec![MerkleStep {
+                position: 0,
+                siblings: vec![merkle_hash(MERKLE_NODE, &leaves[3..6]), merkle_hash(MERKLE_NODE, &leaves[6..7])],
+            }],
+        };
+        assert!(!verify_proof(&inner));
+    }
+
+    #[test]
+    fn test_chain_proof_for_light_client() {
+        let mut chain = CrownyChain::new();
+        chain.balances.insert("alice".into(), 1_000_000);
+        chain.balances.insert("bob".into(), 500_000);
+        chain.add_validator("alice", "Alice", 100_000);
+        chain.add_validator("bob", "Bob", 80_000);
+        chain.transfer("alice", "bob", 1000, 10);
+        chain.transfer("alice", "carol", 500, 10);
+        let block = chain.produce_block().unwrap();
+        let tx_hash = block.transactions[1].hash.clone();
+
+        let proof = chain.prove_inclusion(&tx_hash).unwrap();
+        assert_eq!(proof.block_index, block.index);
+        let genesis = chain.prove_inclusion(&chain.blocks[0].transactions[0].hash).unwrap();
+        assert!(genesis.steps.is_empty() && verify_proof(&genesis));
+
+        // 라이트 노드는 헤더 루트만 믿는다
+        let mut light = crate::wasm_node::BrowserNode::new("light", crate::wasm_node::BrowserNodeType::Light);
+        let root = chain.merkle_root_at(block.index).unwrap().to_string();
+        assert!(light.verify_inclusion(&proof, &root));
+        assert!(!light.verify_inclusion(&proof, chain.merkle_root_at(0).unwrap()));
+        assert_eq!((light.stats.proofs_verified, light.stats.rejected_messages), (1, 1));
+    }
 }
@@ -0,0 +1,638 @@
+// ═══════════════════════════════════════════════════════════════
+// CLI 명령 파서 — 중첩 하위 명령 · 플래그 · 명령별 도움말 · 셸 자동완성
+// ═══════════════════════════════════════════════════════════════
+//
+// 명령 트리는 선언형으로 구성:
+//   Command::new("chain", "블록체인")
+//       .sub(Command::new("block", "블록 조회")
+//           .sub(Command::new("get", "번호로 조회").arg("번호")))
+//
+// 파싱 결과는 정규 이름 경로 ["chain","block","get"] + 인자 + 플래그.
+// 별칭(한국어 명령)은 파싱 시 정규 이름으로 바뀐다.
+// 전역 플래그(--json, --quiet, --lang)는 어느 위치에 와도 된다.
+// 설명은 한국어 기본 + .en("...") 영어 번역 (i18n 로케일에 따라 선택).
+
+use std::collections::HashMap;
+use crate::i18n::{self, tr};
+
+pub const BIN: &str = "crowni-tvm";
+
+// ─────────────────────────────────────────────
+// 명령 명세
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct Arg {
+    pub name: &'static str,
+    pub required: bool,
+    /// 나머지 인자 전부
+    pub variadic: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Flag {
+    pub long: &'static str,
+    pub short: Option<char>,
+    /// 값 이름 (Some이면 값을 받는다: --max-cycles <N>)
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+    /// 영어 설명 (--lang en)
+    pub help_en: Option<&'static str>,
+    /// 하위 명령 어디서나 허용
+    pub global: bool,
+}
+
+impl Flag {
+    pub fn switch(long: &'static str, help: &'static str) -> Self {
+        Self { long, short: None, value: None, help, help_en: None, global: false }
+    }
+
+    pub fn value(long: &'static str, value: &'static str, help: &'static str) -> Self {
+        Self { long, short: None, value: Some(value), help, help_en: None, global: false }
+    }
+
+    pub fn short(mut self, c: char) -> Self {
+        self.short = Some(c);
+        self
+    }
+
+    pub fn global(mut self) -> Self {
+        self.global = true;
+        self
+    }
+
+    pub fn en(mut self, help: &'static str) -> Self {
+        self.help_en = Some(help);
+        self
+    }
+
+    /// 현재 로케일 설명
+    pub fn description(&self) -> &'static str {
+        i18n::pick(self.help, self.help_en)
+    }
+
+    fn usage(&self) -> String {
+        let mut s = match self.short {
+            Some(c) => format!("--{}, -{}", self.long, c),
+            None => format!("--{}", self.long),
+        };
+        if let Some(v) = self.value {
+            s.push_str(&format!(" <{}>", i18n::placeholder(v)));
+        }
+        s
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub name: &'static str,
+    pub aliases: Vec<&'static str>,
+    pub about: &'static str,
+    /// 영어 설명 (--lang en)
+    pub about_en: Option<&'static str>,
+    pub args: Vec<Arg>,
+    pub flags: Vec<Flag>,
+    pub subcommands: Vec<Command>,
+}
+
+impl Command {
+    pub fn new(name: &'static str, about: &'static str) -> Self {
+        Self { name, aliases: Vec::new(), about, about_en: None, args: Vec::new(), flags: Vec::new(), subcommands: Vec::new() }
+    }
+
+    pub fn en(mut self, about: &'static str) -> Self {
+        self.about_en = Some(about);
+        self
+    }
+
+    /// 현재 로케일 설명
+    pub fn description(&self) -> &'static str {
+        i18n::pick(self.about, self.about_en)
+    }
+
+    pub fn alias(mut self, alias: &'static str) -> Self {
+        self.aliases.push(alias);
+        self
+    }
+
+    /// 필수 인자
+    pub fn arg(mut self, name: &'static str) -> Self {
+        self.args.push(Arg { name, required: true, variadic: false });
+        self
+    }
+
+    /// 선택 인자
+    pub fn opt_arg(mut self, name: &'static str) -> Self {
+        self.args.push(Arg { name, required: false, variadic: false });
+        self
+    }
+
+    /// 선택 가변 인자 (마지막에만)
+    pub fn rest_args(mut self, name: &'static str) -> Self {
+        self.args.push(Arg { name, required: false, variadic: true });
+        self
+    }
+
+    pub fn flag(mut self, flag: Flag) -> Self {
+        self.flags.push(flag);
+        self
+    }
+
+    pub fn sub(mut self, cmd: Command) -> Self {
+        self.subcommands.push(cmd);
+        self
+    }
+
+    pub fn matches_name(&self, word: &str) -> bool {
+        self.name == word || self.aliases.contains(&word)
+    }
+
+    pub fn find(&self, word: &str) -> Option<&Command> {
+        self.subcommands.iter().find(|c| c.matches_name(word))
+    }
+
+    /// 정규 이름 경로로 명령 찾기
+    pub fn lookup(&self, path: &[&str]) -> Option<&Command> {
+        let mut cur = self;
+        for word in path {
+            cur = cur.find(word)?;
+        }
+        Some(cur)
+    }
+
+    fn usage_args(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.subcommands.is_empty() {
+            parts.push(i18n::t("cli.command_placeholder").to_string());
+        }
+        for a in &self.args {
+            let name = i18n::placeholder(a.name);
+            parts.push(match (a.required, a.variadic) {
+                (_, true) => format!("[{}...]", name),
+                (true, false) => format!("<{}>", name),
+                (false, false) => format!("[{}]", name),
+            });
+        }
+        parts.join(" ")
+    }
+}
+
+// ─────────────────────────────────────────────
+// 파싱
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Matches {
+    /// 정규 이름 경로 (루트 제외)
+    pub path: Vec<&'static str>,
+    pub args: Vec<String>,
+    flags: HashMap<&'static str, Option<String>>,
+    /// --help / -h 요청
+    pub help: bool,
+}
+
+impl Matches {
+    pub fn path_str(&self) -> String {
+        self.path.join(" ")
+    }
+
+    pub fn flag(&self, long: &str) -> bool {
+        self.flags.contains_key(long)
+    }
+
+    pub fn value(&self, long: &str) -> Option<&str> {
+        self.flags.get(long).and_then(|v| v.as_deref())
+    }
+
+    pub fn arg(&self, i: usize) -> Option<&str> {
+        self.args.get(i).map(|s| s.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnknownCommand { path: String, word: String },
+    UnknownFlag(String),
+    MissingValue(String),
+    MissingArg { path: String, arg: &'static str },
+    ExtraArg { path: String, word: String },
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ParseError::UnknownCommand { path, word } if path.is_empty() => tr!("cli.unknown_command", word),
+            ParseError::UnknownCommand { path, word } => tr!("cli.unknown_subcommand", path, word),
+            ParseError::UnknownFlag(flag) => tr!("cli.unknown_flag", flag),
+            ParseError::MissingValue(flag) => tr!("cli.missing_value", flag),
+            ParseError::MissingArg { path, arg } => tr!("cli.missing_arg", path, i18n::placeholder(arg)),
+            ParseError::ExtraArg { path, word } => tr!("cli.extra_arg", path, word),
+        };
+        f.write_str(&text)
+    }
+}
+
+/// 음수(-42)는 플래그가 아니라 인자
+fn is_flag(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && !arg[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// 인자 파싱 — `args`는 프로그램 이름을 제외한 나머지
+pub fn parse(root: &Command, args: &[String]) -> Result<Matches, ParseError> {
+    let globals: Vec<&Flag> = root.flags.iter().filter(|f| f.global).collect();
+    let mut cur = root;
+    let mut m = Matches::default();
+    let mut only_positional = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+
+        if !only_positional && arg == "--" {
+            only_positional = true;
+            continue;
+        }
+
+        if !only_positional && is_flag(arg) {
+            let (name, inline) = match arg.strip_prefix("--") {
+                Some(long) => match long.split_once('=') {
+                    Some((n, v)) => (n.to_string(), Some(v.to_string())),
+                    None => (long.to_string(), None),
+                },
+                None => (arg[1..].to_string(), None),
+            };
+            if name == "help" || name == "h" {
+                m.help = true;
+                continue;
+            }
+            let is_short = !arg.starts_with("--");
+            let flag = cur.flags.iter().chain(globals.iter().copied()).find(|f| {
+                if is_short {
+                    f.short.map(|c| c.to_string()) == Some(name.clone())
+                } else {
+                    f.long == name
+                }
+            }).ok_or_else(|| ParseError::UnknownFlag(arg.clone()))?;
+
+            let value = if flag.value.is_some() {
+                match inline {
+                    Some(v) => Some(v),
+                    None if i < args.len() => {
+                        i += 1;
+                        Some(args[i - 1].clone())
+                    }
+                    None => return Err(ParseError::MissingValue(flag.long.into())),
+                }
+            } else {
+                None
+            };
+            m.flags.insert(flag.long, value);
+            continue;
+        }
+
+        // 하위 명령 진입 (인자가 나오기 전까지만)
+        if m.args.is_empty() && !cur.subcommands.is_empty() {
+            match cur.find(arg) {
+                Some(sub) => {
+                    cur = sub;
+                    m.path.push(sub.name);
+                    continue;
+                }
+                None if cur.args.is_empty() => {
+                    if m.help {
+                        continue;
+                    }
+                    return Err(ParseError::UnknownCommand { path: m.path_str(), word: arg.clone() });
+                }
+                None => {}
+            }
+        }
+
+        let variadic = cur.args.last().is_some_and(|a| a.variadic);
+        if m.args.len() >= cur.args.len() && !variadic {
+            if m.help {
+                continue;
+            }
+            return Err(ParseError::ExtraArg { path: m.path_str(), word: arg.clone() });
+        }
+        m.args.push(arg.clone());
+    }
+
+    if !m.help {
+        if let Some(missing) = cur.args.iter().skip(m.args.len()).find(|a| a.required) {
+            return Err(ParseError::MissingArg { path: m.path_str(), arg: missing.name });
+        }
+    }
+    Ok(m)
+}
+
+// ─────────────────────────────────────────────
+// 도움말
+// ─────────────────────────────────────────────
+
+/// 터미널 표시 폭 — 한글·CJK는 2칸
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if ('\u{1100}'..='\u{115F}').contains(&c) || ('\u{2E80}'..='\u{D7A3}').contains(&c) { 2 } else { 1 }).sum()
+}
+
+/// 명령별 도움말 — path는 정규 이름 경로
+pub fn help(root: &Command, path: &[&str]) -> String {
+    let cmd = match root.lookup(path) {
+        Some(c) => c,
+        None => root,
+    };
+    let full = if path.is_empty() { BIN.to_string() } else { format!("{} {}", BIN, path.join(" ")) };
+
+    let mut out = String::new();
+    out.push_str(&format!("{}\n\n", cmd.description()));
+    out.push_str(&format!("{}:\n  {} {}\n", i18n::t("cli.usage"), full, cmd.usage_args()).replace("  \n", "\n"));
+    if !cmd.aliases.is_empty() {
+        out.push_str(&format!("  {}: {}\n", i18n::t("cli.aliases"), cmd.aliases.join(", ")));
+    }
+
+    if !cmd.subcommands.is_empty() {
+        out.push_str(&format!("\n{}:\n", i18n::t("cli.commands")));
+        let rows: Vec<(String, &str)> = cmd.subcommands.iter()
+            .map(|c| (format!("{} {}", c.name, c.usage_args()).trim_end().to_string(), c.description()))
+            .collect();
+        let width = rows.iter().map(|(u, _)| display_width(u)).max().unwrap_or(0);
+        for (usage, about) in rows {
+            let pad = width - display_width(&usage);
+            out.push_str(&format!("  {}{}  {}\n", usage, " ".repeat(pad), about));
+        }
+    }
+
+    let globals: Vec<&Flag> = if path.is_empty() { Vec::new() } else { root.flags.iter().filter(|f| f.global).collect() };
+    let flags: Vec<&Flag> = cmd.flags.iter().chain(globals).collect();
+    if !flags.is_empty() {
+        out.push_str(&format!("\n{}:\n", i18n::t("cli.options")));
+        let width = flags.iter().map(|f| display_width(&f.usage())).max().unwrap_or(0);
+        for f in flags {
+            let usage = f.usage();
+            let pad = width - display_width(&usage);
+            out.push_str(&format!("  {}{}  {}\n", usage, " ".repeat(pad), f.description()));
+        }
+    }
+    out
+}
+
+// ─────────────────────────────────────────────
+// 셸 자동완성 스크립트
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub fn parse(s: &str) -> Option<Shell> {
+        match s.to_ascii_lowercase().as_str() {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// (별칭 조합까지 펼친 경로 목록, 명령) — 트리 전체
+fn walk<'a>(cmd: &'a Command, prefixes: Vec<String>, out: &mut Vec<(Vec<String>, &'a Command)>) {
+    out.push((prefixes.clone(), cmd));
+    for sub in &cmd.subcommands {
+        let mut next = Vec::new();
+        for p in &prefixes {
+            for n in std::iter::once(&sub.name).chain(sub.aliases.iter()) {
+                next.push(if p.is_empty() { n.to_string() } else { format!("{} {}", p, n) });
+            }
+        }
+        walk(sub, next, out);
+    }
+}
+
+fn completion_words(root: &Command, cmd: &Command) -> Vec<String> {
+    let mut words: Vec<String> = cmd.subcommands.iter().map(|c| c.name.to_string()).collect();
+    let globals = root.flags.iter().filter(|f| f.global && !std::ptr::eq(cmd, root));
+    for f in cmd.flags.iter().chain(globals) {
+        words.push(format!("--{}", f.long));
+    }
+    words.push("--help".into());
+    words
+}
+
+fn quote_sh(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+pub fn completions(root: &Command, shell: Shell) -> String {
+    let mut nodes = Vec::new();
+    walk(root, vec![String::new()], &mut nodes);
+    match shell {
+        Shell::Bash => bash_completions(root, &nodes),
+        Shell::Zsh => zsh_completions(root, &nodes),
+        Shell::Fish => fish_completions(&nodes),
+    }
+}
+
+fn func_name() -> String {
+    format!("_{}", BIN.replace('-', "_"))
+}
+
+fn bash_completions(root: &Command, nodes: &[(Vec<String>, &Command)]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("# {} bash 자동완성 — source <({} completions bash)\n", BIN, BIN));
+    out.push_str(&format!("{}() {{\n", func_name()));
+    out.push_str("    local cur cmdpath word opts\n");
+    out.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    out.push_str("    cmdpath=\"\"\n");
+    out.push_str("    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n");
+    out.push_str("        case \"$word\" in -*) ;; *) cmdpath=\"${cmdpath:+$cmdpath }$word\" ;; esac\n");
+    out.push_str("    done\n");
+    out.push_str("    case \"$cmdpath\" in\n");
+    for (paths, cmd) in nodes {
+        let pattern: Vec<String> = paths.iter().map(|p| format!("\"{}\"", p)).collect();
+        out.push_str(&format!("        {}) opts={} ;;\n",
+            pattern.join("|"), quote_sh(&completion_words(root, cmd).join(" "))));
+    }
+    out.push_str("        *) opts=\"\" ;;\n");
+    out.push_str("    esac\n");
+    out.push_str("    COMPREPLY=( $(compgen -W \"$opts\" -- \"$cur\") )\n");
+    out.push_str("}\n");
+    out.push_str(&format!("complete -o default -F {} {}\n", func_name(), BIN));
+    out
+}
+
+fn zsh_completions(root: &Command, nodes: &[(Vec<String>, &Command)]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("#compdef {}\n", BIN));
+    out.push_str(&format!("# {} zsh 자동완성 — {} completions zsh > \"${{fpath[1]}}/_{}\"\n", BIN, BIN, BIN));
+    out.push_str(&format!("{}() {{\n", func_name()));
+    out.push_str("    local -a cands\n");
+    out.push_str("    local cmdpath word\n");
+    out.push_str("    cmdpath=\"\"\n");
+    out.push_str("    for word in \"${(@)words[2,CURRENT-1]}\"; do\n");
+    out.push_str("        [[ \"$word\" == -* ]] || cmdpath=\"${cmdpath:+$cmdpath }$word\"\n");
+    out.push_str("    done\n");
+    out.push_str("    case \"$cmdpath\" in\n");
+    for (paths, cmd) in nodes {
+        let pattern: Vec<String> = paths.iter().map(|p| format!("\"{}\"", p)).collect();
+        let mut items: Vec<String> = cmd.subcommands.iter()
+            .map(|c| quote_sh(&format!("{}:{}", c.name, c.description().replace(':', "\\:"))))
+            .collect();
+        let globals = root.flags.iter().filter(|f| f.global && !std::ptr::eq(*cmd, root));
+        for f in cmd.flags.iter().chain(globals) {
+            items.push(quote_sh(&format!("--{}:{}", f.long, f.description().replace(':', "\\:"))));
+        }
+        out.push_str(&format!("        {}) cands=({}) ;;\n", pattern.join("|"), items.join(" ")));
+    }
+    out.push_str("        *) cands=() ;;\n");
+    out.push_str("    esac\n");
+    out.push_str("    if (( ${#cands} )); then\n");
+    out.push_str("        _describe 'command' cands\n");
+    out.push_str("    fi\n");
+    out.push_str("    _files\n");
+    out.push_str("}\n");
+    out.push_str(&format!("compdef {} {}\n", func_name(), BIN));
+    out
+}
+
+fn fish_completions(nodes: &[(Vec<String>, &Command)]) -> String {
+    let path_fn = format!("__{}_path_is", BIN.replace('-', "_"));
+    let mut out = String::new();
+    out.push_str(&format!("# {} fish 자동완성 — {} completions fish | source\n", BIN, BIN));
+    out.push_str(&format!("function {}\n", path_fn));
+    out.push_str("    set -l words (commandline -opc)\n");
+    out.push_str("    set -e words[1]\n");
+    out.push_str("    set -l cmdpath\n");
+    out.push_str("    for w in $words\n");
+    out.push_str("        string match -q -- '-*' $w; or set cmdpath $cmdpath $w\n");
+    out.push_str("    end\n");
+    out.push_str("    contains -- \"$cmdpath\" $argv\n");
+    out.push_str("end\n");
+    for (paths, cmd) in nodes {
+        let cond = format!("{} {}", path_fn, paths.iter().map(|p| format!("\"{}\"", p)).collect::<Vec<_>>().join(" "));
+        for sub in &cmd.subcommands {
+            out.push_str(&format!("complete -c {} -n {} -a {} -d {}\n",
+                BIN, quote_sh(&cond), sub.name, quote_sh(sub.description())));
+        }
+        for f in &cmd.flags {
+            let short = f.short.map(|c| format!(" -s {}", c)).unwrap_or_default();
+            let req = if f.value.is_some() { " -r" } else { "" };
+            let scope = if f.global { String::new() } else { format!(" -n {}", quote_sh(&cond)) };
+            out.push_str(&format!("complete -c {}{} -l {}{}{} -d {}\n",
+                BIN, scope, f.long, short, req, quote_sh(f.description())));
+        }
+    }
+    out
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> Command {
+        Command::new("crowni-tvm", "테스트")
+            .flag(Flag::switch("json", "JSON").global())
+            .flag(Flag::switch("quiet", "조용히").short('q').global())
+            .sub(Command::new("run", "실행").alias("실행").arg("파일")
+                .flag(Flag::value("max-cycles", "N", "사이클 한도")))
+            .sub(Command::new("trit", "변환").arg("정수"))
+            .sub(Command::new("config", "설정").opt_arg("파일"))
+            .sub(Command::new("help", "도움말").rest_args("명령"))
+            .sub(Command::new("chain", "체인").alias("체인")
+                .sub(Command::new("block", "블록")
+                    .sub(Command::new("get", "조회").arg("번호"))
+                    .sub(Command::new("latest", "최신"))))
+    }
+
+    fn argv(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_nested_subcommands_and_aliases() {
+        let root = spec();
+        let m = parse(&root, &argv("체인 block get 5 --json")).unwrap();
+        assert_eq!(m.path, vec!["chain", "block", "get"]);
+        assert_eq!(m.arg(0), Some("5"));
+        assert!(m.flag("json"));
+
+        let m = parse(&root, &argv("chain block latest")).unwrap();
+        assert_eq!(m.path_str(), "chain block latest");
+        assert!(m.args.is_empty());
+    }
+
+    #[test]
+    fn test_flags_values_and_negative_numbers() {
+        let root = spec();
+        let m = parse(&root, &argv("-q 실행 a.hsn --max-cycles 100")).unwrap();
+        assert_eq!(m.path, vec!["run"]);
+        assert_eq!(m.value("max-cycles"), Some("100"));
+        assert!(m.flag("quiet"));
+
+        let m = parse(&root, &argv("run a.hsn --max-cycles=7")).unwrap();
+        assert_eq!(m.value("max-cycles"), Some("7"));
+
+        let m = parse(&root, &argv("trit -42")).unwrap();
+        assert_eq!(m.arg(0), Some("-42"));
+
+        // 명령 전용 플래그는 다른 명령에서 거부
+        assert_eq!(parse(&root, &argv("trit 1 --max-cycles 3")),
+            Err(ParseError::UnknownFlag("--max-cycles".into())));
+        assert_eq!(parse(&root, &argv("run a.hsn --max-cycles")),
+            Err(ParseError::MissingValue("max-cycles".into())));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let root = spec();
+        assert!(matches!(parse(&root, &argv("nope")), Err(ParseError::UnknownCommand { .. })));
+        assert!(matches!(parse(&root, &argv("chain block remove")), Err(ParseError::UnknownCommand { .. })));
+        assert_eq!(parse(&root, &argv("chain block get")),
+            Err(ParseError::MissingArg { path: "chain block get".into(), arg: "번호" }));
+        assert!(matches!(parse(&root, &argv("trit 1 2")), Err(ParseError::ExtraArg { .. })));
+        // 선택/가변 인자
+        assert!(parse(&root, &argv("config")).unwrap().args.is_empty());
+        assert_eq!(parse(&root, &argv("help chain block")).unwrap().args.len(), 2);
+    }
+
+    #[test]
+    fn test_help_flag_skips_validation() {
+        let root = spec();
+        let m = parse(&root, &argv("chain block get --help")).unwrap();
+        assert!(m.help);
+        assert_eq!(m.path_str(), "chain block get");
+
+        let text = help(&root, &m.path);
+        assert!(text.contains("crowni-tvm chain block get <번호>"));
+        assert!(text.contains("--json"));
+
+        let text = help(&root, &["chain"]);
+        assert!(text.contains("별칭: 체인"));
+        assert!(text.contains("block <명령>"));
+    }
+
+    #[test]
+    fn test_completion_scripts() {
+        let root = spec();
+        let bash = completions(&root, Shell::Bash);
+        assert!(bash.contains("\"chain block\"|\"체인 block\") opts='get latest --json --quiet --help'"));
+        assert!(bash.contains("complete -o default -F _crowni_tvm crowni-tvm"));
+
+        let zsh = completions(&root, Shell::Zsh);
+        assert!(zsh.starts_with("#compdef crowni-tvm"));
+        assert!(zsh.contains("'get:조회'"));
+
+        let fish = completions(&root, Shell::Fish);
+        assert!(fish.contains("-a block -d '블록'"));
+        assert!(fish.contains("complete -c crowni-tvm -l quiet -s q -d '조용히'"));
+
+        assert_eq!(Shell::parse("ZSH"), Some(Shell::Zsh));
+        assert_eq!(Shell::parse("pwsh"), None);
+    }
+}
@@ -0,0 +1,286 @@
+// ═══════════════════════════════════════════════════════════════
+// 커밋-공개 투표 — 늦게 투표하는 노드가 앞선 투표를 베끼지 못하게
+//
+//   1단계 커밋:  해시 = SHA256("crowny-commit" ‖ 라운드 ‖ 투표자 ‖ trit ‖ 솔트)
+//   2단계 공개:  모든 커밋이 도착한 뒤에만 (trit, 솔트)를 받아 해시 대조
+//   타임아웃:    공개 마감까지 공개하지 않은 커밋 → O(보류)로 집계
+//                커밋조차 없는 투표자 → 불참(O)
+// ═══════════════════════════════════════════════════════════════
+
+use std::collections::HashMap;
+
+use crate::crypto::{self, Key};
+
+/// 공개 단계 기본 마감 (커밋이 모두 도착한 시점부터)
+pub const DEFAULT_REVEAL_TIMEOUT_MS: u64 = 5_000;
+
+/// 커밋 해시 — 도메인 구분자로 다른 서명·해시와 섞이지 않게
+pub fn commitment(round: u64, voter: &str, trit: i8, salt: &[u8]) -> [u8; 32] {
+    let mut data = format!("crowny-commit\0{}\0{}\0{}\0", round, voter, trit).into_bytes();
+    data.extend_from_slice(salt);
+    crypto::sha256(&data)
+}
+
+/// 투표자 쪽 봉인 — 공개 전까지 trit과 솔트를 보관
+#[derive(Debug, Clone)]
+pub struct SealedVote {
+    pub trit: i8,
+    pub salt: Key,
+}
+
+impl SealedVote {
+    pub fn new(trit: i8) -> Self {
+        Self { trit, salt: crypto::random_bytes() }
+    }
+
+    pub fn with_salt(trit: i8, salt: Key) -> Self {
+        Self { trit, salt }
+    }
+
+    /// 1단계에 보낼 커밋 (hex)
+    pub fn commitment_hex(&self, round: u64, voter: &str) -> String {
+        crypto::to_hex(&commitment(round, voter, self.trit, &self.salt))
+    }
+
+    pub fn salt_hex(&self) -> String {
+        crypto::to_hex(&self.salt)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Commit,
+    Reveal,
+    Closed,
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Commit => write!(f, "커밋"),
+            Self::Reveal => write!(f, "공개"),
+            Self::Closed => write!(f, "마감"),
+        }
+    }
+}
+
+/// 투표자별 최종 결과 — 공개된 표만 trit을 갖고 나머지는 O
+#[derive(Debug, Clone, PartialEq)]
+pub enum RevealOutcome {
+    Revealed(i8),
+    /// 커밋했지만 마감까지 공개하지 않음
+    Unrevealed,
+    /// 공개한 값이 커밋과 다름
+    Mismatch,
+    /// 커밋하지 않음
+    Absent,
+}
+
+impl RevealOutcome {
+    pub fn trit(&self) -> i8 {
+        match self {
+            Self::Revealed(t) => *t,
+            _ => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for RevealOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Revealed(t) => write!(f, "공개 확인 ({})", match t { 1 => "P", -1 => "T", _ => "O" }),
+            Self::Unrevealed => write!(f, "미공개 — O로 집계"),
+            Self::Mismatch => write!(f, "커밋 불일치 — O로 집계"),
+            Self::Absent => write!(f, "커밋 없음 — O로 집계"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommitRevealError {
+    UnknownVoter(String),
+    DuplicateCommit(String),
+    WrongPhase { expected: Phase, actual: Phase },
+    NotCommitted(String),
+    Mismatch(String),
+    Timeout(String),
+    Malformed(String),
+}
+
+impl std::fmt::Display for CommitRevealError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownVoter(v) => write!(f, "등록되지 않은 투표자: {}", v),
+            Self::DuplicateCommit(v) => write!(f, "중복 커밋: {}", v),
+            Self::WrongPhase { expected, actual } => write!(f, "단계 오류: {} 단계가 아님 (현재 {})", expected, actual),
+            Self::NotCommitted(v) => write!(f, "커밋 없이 공개: {}", v),
+            Self::Mismatch(v) => write!(f, "커밋 불일치: {}", v),
+            Self::Timeout(v) => write!(f, "공개 마감 초과: {}", v),
+            Self::Malformed(what) => write!(f, "형식 오류: {}", what),
+        }
+    }
+}
+
+/// 한 라운드의 커밋-공개 진행 상태 (시각은 호출자가 ms로 넘김)
+#[derive(Debug, Clone)]
+pub struct CommitRevealRound {
+    pub round: u64,
+    voters: Vec<String>,
+    commitments: HashMap<String, [u8; 32]>,
+    outcomes: HashMap<String, RevealOutcome>,
+    phase: Phase,
+    timeout_ms: u64,
+    deadline: Option<u64>,
+}
+
+impl CommitRevealRound {
+    pub fn new(round: u64, voters: Vec<String>, timeout_ms: u64) -> Self {
+        Self {
+            round, voters,
+            commitments: HashMap::new(),
+            outcomes: HashMap::new(),
+            phase: Phase::Commit,
+            timeout_ms,
+            deadline: None,
+        }
+    }
+
+    fn expect(&self, expected: Phase) -> Result<(), CommitRevealError> {
+        if self.phase == expected {
+            Ok(())
+        } else {
+            Err(CommitRevealError::WrongPhase { expected, actual: self.phase })
+        }
+    }
+
+    /// 1단계 — 모든 투표자가 커밋하면 자동으로 공개 단계로
+    pub fn commit(&mut self, voter: &str, hex: &str, now: u64) -> Result<(), CommitRevealError> {
+        self.expect(Phase::Commit)?;
+        if !self.voters.iter().any(|v| v == voter) {
+            return Err(CommitRevealError::UnknownVoter(voter.to_string()));
+        }
+        if self.commitments.contains_key(voter) {
+            return Err(CommitRevealError::DuplicateCommit(voter.to_string()));
+        }
+        let hash: [u8; 32] = crypto::from_hex(hex)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| CommitRevealError::Malformed("커밋".into()))?;
+        self.commitments.insert(voter.to_string(), hash);
+        if self.commitments.len() == self.voters.len() {
+            self.open_reveal(now);
+        }
+        Ok(())
+    }
+
+    /// 커밋 대기를 끝내고 공개 단계 시작 — 남은 투표자는 불참
+    pub fn open_reveal(&mut self, now: u64) {
+        if self.phase == Phase::Commit {
+            self.phase = Phase::Reveal;
+            self.deadline = Some(now + self.timeout_ms);
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.deadline.is_some_and(|d| now > d)
+    }
+
+    /// 2단계 — 커밋과 대조해 공개된 trit을 돌려준다
+    pub fn reveal(&mut self, voter: &str, trit: i8, salt_hex: &str, now: u64) -> Result<i8, CommitRevealError> {
+        self.expect(Phase::Reveal)?;
+        if self.is_expired(now) {
+            return Err(CommitRevealError::Timeout(voter.to_string()));
+        }
+        let expected = *self.commitments.get(voter).ok_or_else(|| CommitRevealError::NotCommitted(voter.to_string()))?;
+        if self.outcomes.contains_key(voter) {
+            return Err(CommitRevealError::DuplicateCommit(voter.to_string()));
+        }
+        let salt = crypto::from_hex(salt_hex).ok_or_else(|| CommitRevealError::Malformed("솔트".into()))?;
+        if !(-1..=1).contains(&trit) || !crypto::ct_eq(&commitment(self.round, voter, trit, &salt), &expected) {
+            self.outcomes.insert(voter.to_string(), RevealOutcome::Mismatch);
+            return Err(CommitRevealError::Mismatch(voter.to_string()));
+        }
+        self.outcomes.insert(voter.to_string(), RevealOutcome::Revealed(trit));
+        if self.outcomes.len() == self.commitments.len() {
+            self.phase = Phase::Closed;
+        }
+        Ok(trit)
+    }
+
+    /// 라운드 마감 — 투표자 순서대로 결과 (미공개 · 불참은 O)
+    pub fn finish(&mut self) -> Vec<(String, RevealOutcome)> {
+        self.phase = Phase::Closed;
+        self.voters.iter().map(|v| {
+            let outcome = match self.outcomes.get(v) {
+                Some(o) => o.clone(),
+                None if self.commitments.contains_key(v) => RevealOutcome::Unrevealed,
+                None => RevealOutcome::Absent,
+            };
+            (v.clone(), outcome)
+        }).collect()
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voters() -> Vec<String> {
+        vec!["a".into(), "b".into(), "c".into()]
+    }
+
+    #[test]
+    fn test_reveal_only_after_all_commitments() {
+        let mut round = CommitRevealRound::new(1, voters(), 1_000);
+        let sealed: Vec<SealedVote> = [1, -1, 1].iter().map(|&t| SealedVote::new(t)).collect();
+        round.commit("a", &sealed[0].commitment_hex(1, "a"), 0).unwrap();
+        // 다른 투표자가 커밋 중일 때는 공개를 받지 않는다
+        assert_eq!(round.reveal("a", 1, &sealed[0].salt_hex(), 10),
+            Err(CommitRevealError::WrongPhase { expected: Phase::Reveal, actual: Phase::Commit }));
+        assert_eq!(round.commit("a", &sealed[0].commitment_hex(1, "a"), 0), Err(CommitRevealError::DuplicateCommit("a".into())));
+        assert!(matches!(round.commit("z", &sealed[0].commitment_hex(1, "z"), 0), Err(CommitRevealError::UnknownVoter(_))));
+        round.commit("b", &sealed[1].commitment_hex(1, "b"), 0).unwrap();
+        round.commit("c", &sealed[2].commitment_hex(1, "c"), 100).unwrap();
+        assert_eq!(round.phase, Phase::Reveal);
+        assert_eq!(round.deadline, Some(1_100));
+
+        for (i, v) in ["a", "b", "c"].iter().enumerate() {
+            assert_eq!(round.reveal(v, sealed[i].trit, &sealed[i].salt_hex(), 200), Ok(sealed[i].trit));
+        }
+        assert_eq!(round.phase, Phase::Closed);
+        let trits: Vec<i8> = round.finish().iter().map(|(_, o)| o.trit()).collect();
+        assert_eq!(trits, vec![1, -1, 1]);
+    }
+
+    #[test]
+    fn test_changed_vote_is_mismatch() {
+        let mut round = CommitRevealRound::new(7, voters(), 1_000);
+        let sealed = SealedVote::with_salt(-1, [9; 32]);
+        round.commit("a", &sealed.commitment_hex(7, "a"), 0).unwrap();
+        round.open_reveal(0);
+        // 남의 P를 보고 T에서 P로 바꿔 공개
+        assert_eq!(round.reveal("a", 1, &sealed.salt_hex(), 5), Err(CommitRevealError::Mismatch("a".into())));
+        // 다른 라운드의 커밋은 재사용 불가
+        assert_ne!(sealed.commitment_hex(7, "a"), sealed.commitment_hex(8, "a"));
+        let results = round.finish();
+        assert_eq!(results[0].1, RevealOutcome::Mismatch);
+        assert_eq!(results[1].1, RevealOutcome::Absent);
+    }
+
+    #[test]
+    fn test_unrevealed_counts_as_o_after_timeout() {
+        let mut round = CommitRevealRound::new(2, voters(), 500);
+        let sealed: Vec<SealedVote> = [1, 1, -1].iter().map(|&t| SealedVote::new(t)).collect();
+        for (i, v) in ["a", "b", "c"].iter().enumerate() {
+            round.commit(v, &sealed[i].commitment_hex(2, v), 1_000).unwrap();
+        }
+        round.reveal("a", 1, &sealed[0].salt_hex(), 1_200).unwrap();
+        assert!(round.is_expired(1_501));
+        assert_eq!(round.reveal("b", 1, &sealed[1].salt_hex(), 1_501), Err(CommitRevealError::Timeout("b".into())));
+        let results = round.finish();
+        assert_eq!(results.iter().map(|(_, o)| o.trit()).collect::<Vec<_>>(), vec![1, 0, 0]);
+        assert_eq!(results[2].1, RevealOutcome::Unrevealed);
+        assert_eq!(results[2].1, RevealOutcome::Unrevealed);
+    }
+}
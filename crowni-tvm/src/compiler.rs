@@ -13,21 +13,284 @@
 ///!   종료   → return
 ///!
 ///! 균형3진 의미는 유지, 실행은 WASM(2진).
+///! 분기 · 반복 · 호출은 기본 블록 + pc 분배 loop로 재구성.
 
 use crate::vm::Instruction;
 use crate::ir::*;
-use crate::wasm_gen::WasmBuilder;
+use crate::wasm_gen::{WasmBuilder, WasmValidationError};
 use crate::value::Value;
 
+// ─────────────────────────────────────────────
+// 런타임 배치
+// ─────────────────────────────────────────────
+//
+// TVM 스택은 선형 메모리에 두고 sp 전역으로 관리한다.
+// 분기 대상은 기본 블록으로 나누고, 메인 함수는
+// pc 로컬을 br_table로 분배하는 loop 하나로 재구성한다.
+//
+//   [0, 72)              레지스터 R0~R8
+//   [FRAME_BASE, ..)     호출 프레임 (복귀 pc · 기준 sp · 레지스터)
+//   [IO_BASE, +64)       WASI 출력 버퍼 (iovec · 쓴 바이트 수 · 숫자)
+//   [STACK_BASE, END)    값 스택 (i64)
+
+const REG_COUNT: u32 = 9;
+const FRAME_BASE: i32 = 128;
+const FRAME_SIZE: i32 = 16 + REG_COUNT as i32 * 8;
+const MAX_FRAMES: i32 = 256;
+const IO_BASE: i32 = 24 * 1024;
+const IO_NWRITTEN: i32 = IO_BASE + 8;
+const IO_BUF_END: i32 = IO_BASE + 64;
+const STACK_BASE: i32 = 32 * 1024;
+const MEMORY_PAGES: u32 = 2;
+const STACK_END: i32 = MEMORY_PAGES as i32 * 64 * 1024;
+
+// 전역: [0] sp, [1] fp, [2..] 변수
+const GLOBAL_SP: u32 = 0;
+const GLOBAL_FP: u32 = 1;
+
+// 메인 로컬: [0] pc(i32), [1] [2] 임시(i64)
+const LOCAL_PC: u32 = 0;
+const LOCAL_T0: u32 = 1;
+const LOCAL_T1: u32 = 2;
+
+/// 출력 대상 — 보여줘/입력해가 어떤 import로 나가는가
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Target {
+    /// env.print / env.input — JS 호스트가 채워 줌
+    #[default]
+    Env,
+    /// wasi_snapshot_preview1.fd_write + `_start` — wasmtime/wasmer에서 바로 실행
+    Wasi,
+}
+
+impl Target {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "env" | "js" | "web" => Some(Target::Env),
+            "wasi" => Some(Target::Wasi),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Target::Env => "env",
+            Target::Wasi => "wasi",
+        }
+    }
+}
+
+/// 런타임 보조 함수 인덱스 (메인 바로 뒤)
+struct Runtime {
+    push: u32,
+    pop: u32,
+    top: u32,
+    sign: u32,
+    enter: u32,
+    leave: u32,
+    /// 보여줘 — env는 import, WASI는 십진 출력 보조 함수
+    print: u32,
+    /// 입력해 — WASI는 아직 없음 (트랩)
+    input: Option<u32>,
+}
+
+impl Runtime {
+    fn new(import_count: u32, target: Target) -> Self {
+        let base = import_count + 1;
+        let (print, input) = match target {
+            Target::Env => (0, Some(2)),
+            Target::Wasi => (base + 6, None),
+        };
+        Self { push: base, pop: base + 1, top: base + 2, sign: base + 3, enter: base + 4, leave: base + 5, print, input }
+    }
+
+    /// WASI 보여줘 — i64를 십진수 + 줄바꿈으로 버퍼 끝부터 채워 fd_write(1)
+    ///
+    /// 음수 쪽으로 모아서 나누므로 i64::MIN도 넘치지 않는다.
+    fn wasi_print(fd_write: u32) -> IrFunction {
+        const POS: u32 = 1; // i32 — 버퍼 쓰기 위치
+        const N: u32 = 2;   // i64 — 남은 값 (≤ 0)
+        let mut print = IrFunction::new("tvm_print");
+        print.params.push(IrType::I64);
+        print.locals = vec![IrType::I32, IrType::I64];
+        print.body = vec![
+            IrOp::ConstI32(IO_BUF_END - 1), IrOp::LocalSet(POS),
+            IrOp::LocalGet(POS), IrOp::Const(b'\n' as i64), IrOp::MemStore8(0),
+            // n = v < 0 ? v : -v
+            IrOp::LocalGet(0), IrOp::Const(0), IrOp::LocalGet(0), IrOp::Sub,
+            IrOp::LocalGet(0), IrOp::Const(0), IrOp::Lt, IrOp::Select, IrOp::LocalSet(N),
+            IrOp::Loop(0),
+            IrOp::LocalGet(POS), IrOp::ConstI32(1), IrOp::I32Sub, IrOp::LocalSet(POS),
+            IrOp::LocalGet(POS), IrOp::Const(b'0' as i64), IrOp::LocalGet(N), IrOp::Const(10), IrOp::Rem, IrOp::Sub,
+            IrOp::MemStore8(0),
+            IrOp::LocalGet(N), IrOp::Const(10), IrOp::Div, IrOp::LocalSet(N),
+            IrOp::LocalGet(N), IrOp::Const(0), IrOp::Ne, IrOp::BrIf(0),
+            IrOp::End,
+            IrOp::LocalGet(0), IrOp::Const(0), IrOp::Lt, IrOp::If(None),
+            IrOp::LocalGet(POS), IrOp::ConstI32(1), IrOp::I32Sub, IrOp::LocalSet(POS),
+            IrOp::LocalGet(POS), IrOp::Const(b'-' as i64), IrOp::MemStore8(0),
+            IrOp::End,
+            // iovec { buf, len } → fd_write(stdout, iovs, 1, &nwritten)
+            IrOp::ConstI32(IO_BASE), IrOp::LocalGet(POS), IrOp::MemStoreI32(0),
+            IrOp::ConstI32(IO_BASE), IrOp::ConstI32(IO_BUF_END), IrOp::LocalGet(POS), IrOp::I32Sub, IrOp::MemStoreI32(4),
+            IrOp::ConstI32(1), IrOp::ConstI32(IO_BASE), IrOp::ConstI32(1), IrOp::ConstI32(IO_NWRITTEN),
+            IrOp::Call(fd_write), IrOp::Drop,
+        ];
+        print
+    }
+
+    /// 보조 함수 본문 — Runtime::new 인덱스 순서
+    fn functions() -> Vec<IrFunction> {
+        let trap_if = |cond: Vec<IrOp>| {
+            let mut ops = cond;
+            ops.extend([IrOp::If(None), IrOp::Unreachable, IrOp::End]);
+            ops
+        };
+
+        // push(v) — 넘치면 트랩
+        let mut push = IrFunction::new("tvm_push");
+        push.params.push(IrType::I64);
+        push.body = trap_if(vec![IrOp::GlobalGet(GLOBAL_SP), IrOp::ConstI32(STACK_END), IrOp::I32GeU]);
+        push.body.extend([
+            IrOp::GlobalGet(GLOBAL_SP), IrOp::LocalGet(0), IrOp::MemStore(0),
+            IrOp::GlobalGet(GLOBAL_SP), IrOp::ConstI32(8), IrOp::I32Add, IrOp::GlobalSet(GLOBAL_SP),
+        ]);
+
+        // pop() → v — 비었으면 트랩 (VM 스택 부족)
+        let mut pop = IrFunction::new("tvm_pop");
+        pop.results.push(IrType::I64);
+        pop.body = trap_if(vec![IrOp::GlobalGet(GLOBAL_SP), IrOp::ConstI32(STACK_BASE), IrOp::I32LeU]);
+        pop.body.extend([
+            IrOp::GlobalGet(GLOBAL_SP), IrOp::ConstI32(8), IrOp::I32Sub, IrOp::GlobalSet(GLOBAL_SP),
+            IrOp::GlobalGet(GLOBAL_SP), IrOp::MemLoad(0),
+        ]);
+
+        // top() → 스택 top (비었으면 0) — 종료 시 반환값
+        let mut top = IrFunction::new("tvm_top");
+        top.results.push(IrType::I64);
+        top.body = vec![
+            IrOp::GlobalGet(GLOBAL_SP), IrOp::ConstI32(STACK_BASE), IrOp::I32LeU,
+            IrOp::If(Some(IrType::I64)),
+            IrOp::Const(0),
+            IrOp::Else,
+            IrOp::GlobalGet(GLOBAL_SP), IrOp::ConstI32(8), IrOp::I32Sub, IrOp::MemLoad(0),
+            IrOp::End,
+        ];
+
+        // sign(x) = (x > 0) - (x < 0) — 값 → 트릿
+        let mut sign = IrFunction::new("tvm_sign");
+        sign.params.push(IrType::I64);
+        sign.results.push(IrType::I64);
+        sign.body = vec![
+            IrOp::LocalGet(0), IrOp::Const(0), IrOp::Gt, IrOp::I64ExtendI32,
+            IrOp::LocalGet(0), IrOp::Const(0), IrOp::Lt, IrOp::I64ExtendI32,
+            IrOp::Sub,
+        ];
+
+        // enter(복귀 pc) — 프레임에 복귀 pc · sp · 레지스터 보관, 레지스터 비움
+        let mut enter = IrFunction::new("tvm_enter");
+        enter.params.push(IrType::I32);
+        enter.body = trap_if(vec![
+            IrOp::GlobalGet(GLOBAL_FP), IrOp::ConstI32(FRAME_BASE + FRAME_SIZE * MAX_FRAMES), IrOp::I32GeU,
+        ]);
+        enter.body.extend([
+            IrOp::GlobalGet(GLOBAL_FP), IrOp::LocalGet(0), IrOp::I64ExtendI32, IrOp::MemStore(0),
+            IrOp::GlobalGet(GLOBAL_FP), IrOp::GlobalGet(GLOBAL_SP), IrOp::I64ExtendI32, IrOp::MemStore(8),
+        ]);
+        for r in 0..REG_COUNT {
+            enter.body.extend([
+                IrOp::GlobalGet(GLOBAL_FP), IrOp::ConstI32(0), IrOp::MemLoad(r * 8), IrOp::MemStore(16 + r * 8),
+                IrOp::ConstI32(0), IrOp::Const(0), IrOp::MemStore(r * 8),
+            ]);
+        }
+        enter.body.extend([
+            IrOp::GlobalGet(GLOBAL_FP), IrOp::ConstI32(FRAME_SIZE), IrOp::I32Add, IrOp::GlobalSet(GLOBAL_FP),
+        ]);
+
+        // leave() → 복귀 pc (프레임이 없으면 -1) — 레지스터 복원
+        let mut leave = IrFunction::new("tvm_leave");
+        leave.results.push(IrType::I32);
+        leave.body = vec![
+            IrOp::GlobalGet(GLOBAL_FP), IrOp::ConstI32(FRAME_BASE), IrOp::I32LeU,
+            IrOp::If(None), IrOp::ConstI32(-1), IrOp::Return, IrOp::End,
+            IrOp::GlobalGet(GLOBAL_FP), IrOp::ConstI32(FRAME_SIZE), IrOp::I32Sub, IrOp::GlobalSet(GLOBAL_FP),
+        ];
+        for r in 0..REG_COUNT {
+            leave.body.extend([
+                IrOp::ConstI32(0), IrOp::GlobalGet(GLOBAL_FP), IrOp::MemLoad(16 + r * 8), IrOp::MemStore(r * 8),
+            ]);
+        }
+        leave.body.extend([IrOp::GlobalGet(GLOBAL_FP), IrOp::MemLoad(0), IrOp::I32WrapI64]);
+
+        vec![push, pop, top, sign, enter, leave]
+    }
+}
+
 // ─────────────────────────────────────────────
 // TVM → IR 변환기
 // ─────────────────────────────────────────────
 
 /// TVM 프로그램을 IR 모듈로 변환
-pub fn tvm_to_ir(program: &[Instruction], module_name: &str) -> IrModule {
+///
+/// 점프 · 조건점프 · 반복 · 삼분기 · 호출 대상마다 기본 블록을 끊고,
+/// `block $exit (loop $dispatch (block… br_table pc))` 로 흐름을 재구성한다.
+/// 분기 대상은 정적으로 정해져야 한다 (피연산자 또는 바로 앞 `넣어 N`) — 아니면 오류.
+/// 힙 · 컬렉션 · 문자열 명령처럼 i64로 표현할 수 없는 명령은 트랩으로 내린다.
+pub fn tvm_to_ir(program: &[Instruction], module_name: &str) -> Result<IrModule, String> {
+    tvm_to_ir_for(program, module_name, Target::Env)
+}
+
+/// 출력 대상을 골라 IR 모듈로 변환
+pub fn tvm_to_ir_for(program: &[Instruction], module_name: &str, target: Target) -> Result<IrModule, String> {
     let mut module = IrModule::new(module_name);
+    module.memory_pages = MEMORY_PAGES;
+
+    match target {
+        Target::Env => add_env_imports(&mut module),
+        // [0] fd_write(fd, iovs, iovs_len, nwritten) → errno
+        Target::Wasi => module.imports.push(IrImport {
+            module: "wasi_snapshot_preview1".into(),
+            name: "fd_write".into(),
+            params: vec![IrType::I32; 4],
+            results: vec![IrType::I32],
+        }),
+    }
+
+    // ── 전역: sp · fp ──
+    module.globals.push(IrGlobal { name: "sp".into(), typ: IrType::I32, mutable: true, init_value: STACK_BASE as i64 });
+    module.globals.push(IrGlobal { name: "fp".into(), typ: IrType::I32, mutable: true, init_value: FRAME_BASE as i64 });
 
-    // ── 표준 import 함수 등록 ──
+    // ── 메인 함수 생성 ──
+    let rt = Runtime::new(module.import_count(), target);
+    let main_idx = module.import_count();
+    let mut lowering = Lowering::new(program, rt)?;
+    let mut main_fn = IrFunction::new("main");
+    main_fn.results.push(IrType::I64); // 반환: 최종 스택 top
+    main_fn.locals = vec![IrType::I32, IrType::I64, IrType::I64];
+    main_fn.is_export = true;
+    main_fn.body = lowering.lower();
+
+    for name in &lowering.variables {
+        module.globals.push(IrGlobal { name: name.clone(), typ: IrType::I64, mutable: true, init_value: 0 });
+    }
+
+    module.add_function(main_fn);
+    for f in Runtime::functions() {
+        module.add_function(f);
+    }
+    if target == Target::Wasi {
+        module.add_function(Runtime::wasi_print(0));
+        // WASI 명령 모듈 진입점 — 최종 값은 버린다
+        let mut start = IrFunction::new("_start");
+        start.body = vec![IrOp::Call(main_idx), IrOp::Drop];
+        start.is_export = true;
+        module.add_function(start);
+    }
+    Ok(module)
+}
+
+/// 표준 JS 호스트 import
+fn add_env_imports(module: &mut IrModule) {
     // [0] env.print(i64) — 출력
     module.imports.push(IrImport {
         module: "env".into(),
@@ -51,200 +314,487 @@ pub fn tvm_to_ir(program: &[Instruction], module_name: &str) -> IrModule {
         params: vec![],
         results: vec![IrType::I64],
     });
+}
 
-    // ── 메인 함수 생성 ──
-    let mut main_fn = IrFunction::new("main");
-    main_fn.results.push(IrType::I64); // 반환: 최종 스택 top
-    main_fn.is_export = true;
+/// 기본 블록 분할 + 명령 변환 상태
+struct Lowering<'a> {
+    program: &'a [Instruction],
+    /// 명령 주소 → 블록 번호 (블록 시작 주소만 Some)
+    block_of: Vec<Option<u32>>,
+    /// 블록 시작 주소 (오름차순)
+    leaders: Vec<usize>,
+    /// 명령별 정적 분기 대상
+    targets: Vec<Vec<usize>>,
+    /// 대상을 바로 앞 `넣어 N`에서 접은 분기 — 스택의 그 값은 버린다
+    folded: Vec<bool>,
+    rt: Runtime,
+    /// 저장해/불러와 이름 → 전역 (GLOBAL_FP 다음부터)
+    variables: Vec<String>,
+    body: Vec<IrOp>,
+    /// 현재 블록에서 dispatch loop까지의 label 깊이
+    loop_depth: u32,
+}
+
+impl<'a> Lowering<'a> {
+    fn new(program: &'a [Instruction], rt: Runtime) -> Result<Self, String> {
+        let (targets, folded): (Vec<Vec<usize>>, Vec<bool>) = (0..program.len())
+            .map(|i| static_targets(program, i))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
+        let mut is_leader = vec![false; program.len()];
+        if !program.is_empty() {
+            is_leader[0] = true;
+        }
+        for (i, inst) in program.iter().enumerate() {
+            if !ends_block(inst) {
+                continue;
+            }
+            if i + 1 < program.len() {
+                is_leader[i + 1] = true;
+            }
+            for &target in &targets[i] {
+                if target < program.len() {
+                    is_leader[target] = true;
+                }
+            }
+        }
+        // 접은 분기로 다른 곳에서 들어오면 스택 top이 그 `넣어 N`이라는 보장이 없다
+        if let Some(i) = (0..program.len()).find(|&i| folded[i] && targets.iter().any(|t| t.contains(&i))) {
+            return Err(format!("{}: 대상이 스택 값인데 다른 분기가 이 명령으로 들어옴 — 정적으로 정할 수 없음", describe(program, i)));
+        }
+        let leaders: Vec<usize> = (0..program.len()).filter(|&i| is_leader[i]).collect();
+        let mut block_of = vec![None; program.len()];
+        for (b, &at) in leaders.iter().enumerate() {
+            block_of[at] = Some(b as u32);
+        }
+        Ok(Self { program, block_of, leaders, targets, folded, rt, variables: Vec::new(), body: Vec::new(), loop_depth: 0 })
+    }
 
-    // TVM 스택을 WASM 로컬로 시뮬레이션할 경우 로컬 할당
-    // 간단한 경우: WASM 스택 직접 사용
-    // 복잡한 경우: 로컬 변수 배열 사용
+    /// 주소 → 블록 번호 (프로그램 끝 이후는 블록 수 = 종료)
+    fn pc_of(&self, addr: usize) -> u32 {
+        self.block_of.get(addr).copied().flatten().unwrap_or(self.leaders.len() as u32)
+    }
 
-    // TVM 명령어 → IR 변환
-    let import_count = module.imports.len() as u32;
-    for inst in program {
-        translate_instruction(&mut main_fn, inst, import_count);
+    fn lower(&mut self) -> Vec<IrOp> {
+        let n = self.leaders.len() as u32;
+        self.body.push(IrOp::Block(0)); // $exit
+        self.body.push(IrOp::Loop(0));  // $dispatch
+        for _ in 0..n {
+            self.body.push(IrOp::Block(0));
+        }
+        self.body.push(IrOp::LocalGet(LOCAL_PC));
+        self.body.push(IrOp::BrTable((0..n).collect(), n + 1));
+        for k in 0..n as usize {
+            self.body.push(IrOp::End);
+            self.loop_depth = n - 1 - k as u32;
+            let end = self.leaders.get(k + 1).copied().unwrap_or(self.program.len());
+            for i in self.leaders[k]..end {
+                self.lower_instruction(i);
+            }
+        }
+        self.body.push(IrOp::End); // loop
+        self.body.push(IrOp::End); // block
+        self.body.push(IrOp::Call(self.rt.top));
+        std::mem::take(&mut self.body)
     }
 
-    // 만약 body가 비어있으면 기본 반환
-    if main_fn.body.is_empty() {
-        main_fn.body.push(IrOp::Const(0));
+    fn emit(&mut self, ops: &[IrOp]) {
+        self.body.extend_from_slice(ops);
     }
 
-    // 마지막이 Return이 아니면 추가
-    if main_fn.body.last() != Some(&IrOp::Return) && main_fn.body.last() != Some(&IrOp::Halt) {
-        // WASM에서는 함수 끝에서 자동으로 스택 top이 반환됨
-        // Return을 명시적으로 넣지 않아도 됨
+    fn pop(&mut self) {
+        self.body.push(IrOp::Call(self.rt.pop));
     }
 
-    module.add_function(main_fn);
-    module
-}
+    fn push(&mut self) {
+        self.body.push(IrOp::Call(self.rt.push));
+    }
+
+    /// pc = 블록, dispatch loop로
+    fn goto(&mut self, pc: u32) {
+        self.emit(&[IrOp::ConstI32(pc as i32), IrOp::LocalSet(LOCAL_PC), IrOp::Br(self.loop_depth)]);
+    }
 
-/// 단일 TVM 명령어 → IR 변환
-fn translate_instruction(func: &mut IrFunction, inst: &Instruction, import_count: u32) {
-    let (sector, group, cmd) = (inst.addr.sector, inst.addr.group, inst.addr.command);
+    /// 스택 top(i32 조건 계산 뒤)이 참이면 taken, 아니면 다음 블록
+    fn branch_if(&mut self, taken: u32, next: u32, cond: &[IrOp]) {
+        self.emit(&[IrOp::ConstI32(taken as i32), IrOp::ConstI32(next as i32)]);
+        self.pop();
+        self.emit(cond);
+        self.emit(&[IrOp::Select, IrOp::LocalSet(LOCAL_PC), IrOp::Br(self.loop_depth)]);
+    }
+
+    /// b → T0, a는 스택에 남긴 채 a op T0 → push
+    fn binary(&mut self, ops: &[IrOp]) {
+        self.pop();
+        self.body.push(IrOp::LocalSet(LOCAL_T0));
+        self.pop();
+        self.body.push(IrOp::LocalGet(LOCAL_T0));
+        self.emit(ops);
+        self.push();
+    }
+
+    /// 3값 비교: (x > y) - (x < y), a=T1 b=T0
+    fn compare(&mut self, x: u32, y: u32) {
+        self.pop();
+        self.body.push(IrOp::LocalSet(LOCAL_T0));
+        self.pop();
+        self.body.push(IrOp::LocalSet(LOCAL_T1));
+        self.emit(&[
+            IrOp::LocalGet(x), IrOp::LocalGet(y), IrOp::Gt, IrOp::I64ExtendI32,
+            IrOp::LocalGet(x), IrOp::LocalGet(y), IrOp::Lt, IrOp::I64ExtendI32,
+            IrOp::Sub,
+        ]);
+        self.push();
+    }
+
+    /// 저장해/불러와 이름 → 전역 인덱스 (VM global_key와 같은 규칙)
+    fn variable(&mut self, inst: &Instruction) -> Option<u32> {
+        let key = match inst.operands.first()? {
+            Value::Str(s) => s.clone(),
+            Value::Int(n) => format!("#{}", n),
+            _ => return None,
+        };
+        let idx = match self.variables.iter().position(|v| *v == key) {
+            Some(i) => i,
+            None => {
+                self.variables.push(key);
+                self.variables.len() - 1
+            }
+        };
+        Some(GLOBAL_FP + 1 + idx as u32)
+    }
+
+    /// 단일 TVM 명령어 → IR 변환
+    fn lower_instruction(&mut self, i: usize) {
+        let inst = &self.program[i];
+        let (sector, group, cmd) = (inst.addr.sector, inst.addr.group, inst.addr.command);
+        let next = self.pc_of(i + 1);
+        let targets = self.targets[i].clone();
+        let target = |k: usize| targets.get(k).copied();
+        if self.folded[i] {
+            // 앞 `넣어 N`이 올린 대상 — VM처럼 꺼내 버린다
+            self.pop();
+            self.body.push(IrOp::Drop);
+        }
+
+        // 다른 섹터: 기억 섹터의 GC 명령만 스택에 닿는다 (힙 없음 → 수거 0)
+        if sector != 0 {
+            match (sector, group, cmd) {
+                (3, 1, 1) => { self.body.push(IrOp::Const(0)); self.push(); }
+                (3, 1, 2) => { self.pop(); self.body.push(IrOp::Drop); }
+                _ => {}
+            }
+            return;
+        }
 
-    // Sector 0 (코어) 명령어 매핑
-    if sector == 0 {
         match (group, cmd) {
             // ── G0 논리 (비교) ──
-            (0, 0) => func.body.push(IrOp::ConstTrit(1)),   // 참 → +1
-            (0, 1) => func.body.push(IrOp::ConstTrit(-1)),  // 거짓 → -1
-            (0, 2) => func.body.push(IrOp::ConstTrit(0)),   // 모름 → 0
-            (0, 3) => func.body.push(IrOp::Eq),             // 같다
-            (0, 4) => func.body.push(IrOp::Ne),             // 다르다
-            (0, 5) => func.body.push(IrOp::Gt),             // 크다
-            (0, 6) => func.body.push(IrOp::Lt),             // 작다
+            (0, 0) => { self.body.push(IrOp::ConstTrit(1)); self.push(); }   // 참 → +1
+            (0, 1) => { self.body.push(IrOp::ConstTrit(-1)); self.push(); }  // 거짓 → -1
+            (0, 2) => { self.body.push(IrOp::ConstTrit(0)); self.push(); }   // 모름 → 0
+            (0, 3) | (0, 4) => {
+                // 같다/다르다 → 참 +1, 거짓 -1
+                let cmp = if cmd == 3 { IrOp::Eq } else { IrOp::Ne };
+                self.binary(&[cmp, IrOp::I64ExtendI32, IrOp::Const(2), IrOp::Mul, IrOp::Const(1), IrOp::Sub]);
+            }
+            (0, 5) | (2, 8) => self.compare(LOCAL_T1, LOCAL_T0), // 크다 · 비교
+            (0, 6) => self.compare(LOCAL_T0, LOCAL_T1),          // 작다
             (0, 7) => {
-                // 아니다 (NOT) → 0 - val
-                func.body.push(IrOp::TritNot);
+                // 아니다 → 0 - sign(a)
+                self.body.push(IrOp::Const(0));
+                self.pop();
+                self.emit(&[IrOp::Call(self.rt.sign), IrOp::Sub]);
+                self.push();
+            }
+            (0, 8) => {
+                // 그리고 → min(sign a, sign b)
+                self.pop();
+                self.emit(&[IrOp::Call(self.rt.sign), IrOp::LocalSet(LOCAL_T0)]);
+                self.pop();
+                self.emit(&[
+                    IrOp::Call(self.rt.sign), IrOp::LocalSet(LOCAL_T1),
+                    IrOp::LocalGet(LOCAL_T0), IrOp::LocalGet(LOCAL_T1),
+                    IrOp::LocalGet(LOCAL_T0), IrOp::LocalGet(LOCAL_T1), IrOp::Lt,
+                    IrOp::Select,
+                ]);
+                self.push();
+            }
+
+            // ── G1 산술 (0으로 나누기는 WASM도 트랩) ──
+            (1, 0) => self.binary(&[IrOp::Add]),
+            (1, 1) => self.binary(&[IrOp::Sub]),
+            (1, 2) => self.binary(&[IrOp::Mul]),
+            (1, 3) => self.binary(&[IrOp::Div]),
+            (1, 4) => self.binary(&[IrOp::Rem]),
+            (1, 5) => {
+                // 음수 → 0 - a
+                self.body.push(IrOp::Const(0));
+                self.pop();
+                self.body.push(IrOp::Sub);
+                self.push();
+            }
+            (1, 6) => {
+                // 절댓값 → select(-a, a, a < 0)
+                self.pop();
+                self.emit(&[
+                    IrOp::LocalSet(LOCAL_T0),
+                    IrOp::Const(0), IrOp::LocalGet(LOCAL_T0), IrOp::Sub,
+                    IrOp::LocalGet(LOCAL_T0),
+                    IrOp::LocalGet(LOCAL_T0), IrOp::Const(0), IrOp::Lt,
+                    IrOp::Select,
+                ]);
+                self.push();
             }
-            (0, 8) => func.body.push(IrOp::TritAnd),        // 그리고 (AND)
-
-            // ── G1 산술 ──
-            (1, 0) => func.body.push(IrOp::Add),            // 더해
-            (1, 1) => func.body.push(IrOp::Sub),            // 빼
-            (1, 2) => func.body.push(IrOp::Mul),            // 곱해
-            (1, 3) => func.body.push(IrOp::Div),            // 나눠
-            (1, 4) => func.body.push(IrOp::Rem),            // 나머지
-            (1, 5) => func.body.push(IrOp::Neg),            // 음수
-            (1, 6) => func.body.push(IrOp::Abs),            // 절댓값
             (1, 7) => {
-                // 제곱: x * x → dup + mul
-                // WASM 스택에서: local.tee + local.get + mul
-                let local_idx = func.params.len() as u32 + func.locals.len() as u32;
-                func.locals.push(IrType::I64);
-                func.body.push(IrOp::LocalSet(local_idx));
-                func.body.push(IrOp::LocalGet(local_idx));
-                func.body.push(IrOp::LocalGet(local_idx));
-                func.body.push(IrOp::Mul);
+                // 제곱 → a * a
+                self.pop();
+                self.emit(&[IrOp::LocalSet(LOCAL_T0), IrOp::LocalGet(LOCAL_T0), IrOp::LocalGet(LOCAL_T0), IrOp::Mul]);
+                self.push();
             }
             (1, 8) => {
-                // 제곱근: float 변환 → sqrt → 다시 int
-                // WASM에 i64.sqrt 없음 → f64 경유
-                func.body.push(IrOp::F64ConvertI64);
-                // f64.sqrt = 0x9F
-                func.body.push(IrOp::Nop); // placeholder, 직접 바이트 삽입 필요
-                func.body.push(IrOp::I64TruncF64);
+                // 제곱근: f64 경유, 정수로 자름
+                self.pop();
+                self.emit(&[IrOp::F64ConvertI64, IrOp::F64Sqrt, IrOp::I64TruncF64]);
+                self.push();
             }
 
             // ── G2 제어 ──
-            (2, 0) => {
-                // 점프 → br
-                let target = inst.operands.first()
-                    .and_then(|v| if let Value::Int(n) = v { Some(*n as u32) } else { None })
-                    .unwrap_or(0);
-                func.body.push(IrOp::Br(target));
-            }
-            (2, 1) => {
-                // 조건점프 → br_if
-                let target = inst.operands.first()
-                    .and_then(|v| if let Value::Int(n) = v { Some(*n as u32) } else { None })
-                    .unwrap_or(0);
-                func.body.push(IrOp::BrIf(target));
-            }
-            (2, 2) => {
-                // 호출 → call
-                let idx = inst.operands.first()
-                    .and_then(|v| if let Value::Int(n) = v { Some(*n as u32 + import_count) } else { None })
-                    .unwrap_or(import_count);
-                func.body.push(IrOp::Call(idx));
-            }
-            (2, 3) => func.body.push(IrOp::Return),         // 반환
-            (2, 7) => func.body.push(IrOp::Halt),           // 종료
-            (2, 8) => {
-                // 비교 → 뺄셈 후 부호
-                func.body.push(IrOp::Sub);
+            (2, 0) => match target(0) {
+                Some(t) => self.goto(self.pc_of(t)),
+                None => self.body.push(IrOp::Unreachable),
+            },
+            // 조건점프: P(> 0)면 이동
+            (2, 1) => match target(0) {
+                Some(t) => self.branch_if(self.pc_of(t), next, &[IrOp::Const(0), IrOp::Gt]),
+                None => self.body.push(IrOp::Unreachable),
+            },
+            // 반복: T가 아니면(>= 0) 이동
+            (2, 4) => match target(0) {
+                Some(t) => self.branch_if(self.pc_of(t), next, &[IrOp::Const(0), IrOp::Ge]),
+                None => self.body.push(IrOp::Unreachable),
+            },
+            (2, 2) => match target(0) {
+                // 호출 → 프레임 보관 후 대상 블록
+                Some(t) => {
+                    self.emit(&[IrOp::ConstI32(next as i32), IrOp::Call(self.rt.enter)]);
+                    self.goto(self.pc_of(t));
+                }
+                None => self.body.push(IrOp::Unreachable),
+            },
+            (2, 3) => {
+                // 반환: 프레임이 없으면(최상위) 무시하고 다음 블록
+                self.emit(&[
+                    IrOp::Call(self.rt.leave), IrOp::LocalSet(LOCAL_PC),
+                    IrOp::ConstI32(next as i32), IrOp::LocalGet(LOCAL_PC),
+                    IrOp::LocalGet(LOCAL_PC), IrOp::ConstI32(0), IrOp::I32LtS,
+                    IrOp::Select, IrOp::LocalSet(LOCAL_PC), IrOp::Br(self.loop_depth),
+                ]);
             }
+            (2, 5) | (2, 6) => {} // 멈춰 · 계속 — VM에서도 NOP
+            (2, 7) => self.emit(&[IrOp::Call(self.rt.top), IrOp::Return]), // 종료
 
             // ── G3 스택 ──
             (3, 0) => {
-                // 넣어 (PUSH)
-                let val = inst.operands.first().cloned().unwrap_or(Value::Int(0));
-                match val {
-                    Value::Int(n) => func.body.push(IrOp::Const(n)),
-                    Value::Float(f) => func.body.push(IrOp::ConstF64(f)),
-                    Value::Bool(b) => func.body.push(IrOp::Const(if b { 1 } else { -1 })),
-                    Value::Trit(t) => func.body.push(IrOp::ConstTrit(t.to_i8())),
-                    Value::Nil => func.body.push(IrOp::Const(0)),
-                    Value::Str(s) => {
-                        func.body.push(IrOp::Const(s.len() as i64));
-                    }
-                    _ => func.body.push(IrOp::Const(0)),
-                }
-            }
-            (3, 1) => func.body.push(IrOp::Drop),           // 꺼내 (POP)
-            (3, 2) => func.body.push(IrOp::Dup),            // 복사 (DUP)
-            (3, 3) => func.body.push(IrOp::Swap),           // 바꿔 (SWAP)
-            (3, 4) => {
-                // 비움 (CLEAR) → 여러 drop
-                func.body.push(IrOp::Drop);
-            }
-            (3, 5) => {
-                // 보여줘 (PRINT) → call $print
-                func.body.push(IrOp::CallImport(0)); // import[0] = print
+                // 넣어 (PUSH) — 실수는 정수로 자르고 문자열은 길이
+                let val = inst.operands.first().cloned().unwrap_or(Value::Nil);
+                let n = match val {
+                    Value::Int(n) => n,
+                    Value::Float(f) => f as i64,
+                    Value::Bool(b) => if b { 1 } else { -1 },
+                    Value::Trit(t) => t.to_i8() as i64,
+                    Value::Str(s) => s.len() as i64,
+                    Value::Addr(a) => a as i64,
+                    _ => 0,
+                };
+                self.body.push(IrOp::Const(n));
+                self.push();
             }
-            (3, 6) => {
-                // 입력해 (INPUT)
-                func.body.push(IrOp::CallImport(2)); // import[2] = input
+            (3, 1) => { self.pop(); self.body.push(IrOp::Drop); } // 꺼내
+            (3, 2) => {
+                // 복사
+                self.pop();
+                self.emit(&[IrOp::LocalSet(LOCAL_T0), IrOp::LocalGet(LOCAL_T0)]);
+                self.push();
+                self.body.push(IrOp::LocalGet(LOCAL_T0));
+                self.push();
             }
-            (3, 7) => {
-                // 저장해 (STORE to global)
-                let idx = inst.operands.first()
-                    .and_then(|v| if let Value::Int(n) = v { Some(*n as u32) } else { None })
-                    .unwrap_or(0);
-                func.body.push(IrOp::GlobalSet(idx));
+            (3, 3) => {
+                // 바꿔
+                self.pop();
+                self.body.push(IrOp::LocalSet(LOCAL_T0));
+                self.pop();
+                self.emit(&[IrOp::LocalSet(LOCAL_T1), IrOp::LocalGet(LOCAL_T0)]);
+                self.push();
+                self.body.push(IrOp::LocalGet(LOCAL_T1));
+                self.push();
             }
-            (3, 8) => {
-                // 불러와 (LOAD from global)
-                let idx = inst.operands.first()
-                    .and_then(|v| if let Value::Int(n) = v { Some(*n as u32) } else { None })
-                    .unwrap_or(0);
-                func.body.push(IrOp::GlobalGet(idx));
+            (3, 4) => self.emit(&[IrOp::ConstI32(STACK_BASE), IrOp::GlobalSet(GLOBAL_SP)]), // 비움
+            (3, 5) => { self.pop(); self.body.push(IrOp::Call(self.rt.print)); } // 보여줘
+            (3, 6) => match self.rt.input {
+                // 입력해 → env.input
+                Some(idx) => { self.body.push(IrOp::Call(idx)); self.push(); }
+                None => self.body.push(IrOp::Unreachable),
+            },
+            (3, 7) => match self.variable(inst) {
+                // 저장해 — 이름이 스택에 있으면 정적으로 정할 수 없음
+                Some(g) => { self.pop(); self.body.push(IrOp::GlobalSet(g)); }
+                None => self.body.push(IrOp::Unreachable),
+            },
+            (3, 8) => match self.variable(inst) {
+                // 불러와
+                Some(g) => { self.body.push(IrOp::GlobalGet(g)); self.push(); }
+                None => self.body.push(IrOp::Unreachable),
+            },
+
+            // ── G4 함수 ──
+            (4, 2) => {
+                // 돌려줘: 값 → 프레임 기준 sp로 정리 후 push, 최상위면 종료
+                self.pop();
+                self.emit(&[
+                    IrOp::LocalSet(LOCAL_T0),
+                    IrOp::GlobalGet(GLOBAL_FP), IrOp::ConstI32(FRAME_BASE), IrOp::I32GtU,
+                    IrOp::If(None),
+                    IrOp::GlobalGet(GLOBAL_FP), IrOp::ConstI32(FRAME_SIZE), IrOp::I32Sub,
+                    IrOp::MemLoad(8), IrOp::I32WrapI64, IrOp::LocalSet(LOCAL_PC),
+                    IrOp::LocalGet(LOCAL_PC), IrOp::GlobalGet(GLOBAL_SP),
+                    IrOp::LocalGet(LOCAL_PC), IrOp::GlobalGet(GLOBAL_SP), IrOp::I32LtU,
+                    IrOp::Select, IrOp::GlobalSet(GLOBAL_SP),
+                    IrOp::End,
+                    IrOp::LocalGet(LOCAL_T0),
+                ]);
+                self.push();
+                self.emit(&[
+                    IrOp::Call(self.rt.leave), IrOp::LocalSet(LOCAL_PC),
+                    IrOp::LocalGet(LOCAL_PC), IrOp::ConstI32(0), IrOp::I32LtS,
+                    IrOp::If(None), IrOp::Call(self.rt.top), IrOp::Return, IrOp::End,
+                    IrOp::Br(self.loop_depth),
+                ]);
             }
+            (4, 3) => match (target(0), target(1), target(2)) {
+                // 삼분기 → pc = select(P, select(O, T, s == 0), s > 0)
+                (Some(p), Some(o), Some(t)) => {
+                    self.pop();
+                    self.emit(&[
+                        IrOp::Call(self.rt.sign), IrOp::LocalSet(LOCAL_T0),
+                        IrOp::ConstI32(self.pc_of(p) as i32),
+                        IrOp::ConstI32(self.pc_of(o) as i32),
+                        IrOp::ConstI32(self.pc_of(t) as i32),
+                        IrOp::LocalGet(LOCAL_T0), IrOp::Eqz, IrOp::Select,
+                        IrOp::LocalGet(LOCAL_T0), IrOp::Const(0), IrOp::Gt, IrOp::Select,
+                        IrOp::LocalSet(LOCAL_PC), IrOp::Br(self.loop_depth),
+                    ]);
+                }
+                _ => self.body.push(IrOp::Unreachable),
+            },
+            (4, _) => {} // 함수 · 매개변수 표식 등 — VM에서도 NOP
 
-            // ── G8 힙/레지스터 ──
-            (8, 3) => {
-                // 할당 → memory.grow
-                func.body.push(IrOp::MemGrow);
+            // ── G5 타입 · G6 예외 ──
+            (5, 0) => {} // 정수로 — 이미 정수
+            (5, 3) => {
+                // 트릿으로 → sign
+                self.pop();
+                self.body.push(IrOp::Call(self.rt.sign));
+                self.push();
             }
-            (8, 5) => {
-                // 읽어 → memory load
-                func.body.push(IrOp::MemLoad(0));
+            (6, 7) => { self.pop(); self.body.push(IrOp::Drop); } // 기록 — 값만 소비
+
+            // ── G8 레지스터 (힙은 미지원) ──
+            (8, 7) => {
+                if let Some(r) = register(inst) {
+                    self.emit(&[IrOp::ConstI32(0), IrOp::MemLoad(r * 8)]);
+                    self.push();
+                }
             }
-            (8, 6) => {
-                // 써 → memory store
-                func.body.push(IrOp::MemStore(0));
+            (8, 8) => {
+                self.pop();
+                match register(inst) {
+                    Some(r) => self.emit(&[
+                        IrOp::LocalSet(LOCAL_T0), IrOp::ConstI32(0), IrOp::LocalGet(LOCAL_T0), IrOp::MemStore(r * 8),
+                    ]),
+                    None => self.body.push(IrOp::Drop),
+                }
             }
 
-            _ => {
-                // 미구현 → NOP
-                func.body.push(IrOp::Nop);
-            }
+            // 던져 · 오류 · 힙 · 컬렉션 · 문자열 변환 → 트랩
+            (5, _) | (6, 2) | (6, 6) | (7, _) | (8, _) => self.body.push(IrOp::Unreachable),
+            _ => {} // 예약 → NOP
         }
-    } else {
-        // 다른 섹터는 아직 NOP
-        func.body.push(IrOp::Nop);
     }
 }
 
+/// 흐름을 끊는 명령 (다음 명령이 새 블록)
+fn ends_block(inst: &Instruction) -> bool {
+    inst.addr.sector == 0 && matches!(
+        (inst.addr.group, inst.addr.command),
+        (2, 0) | (2, 1) | (2, 2) | (2, 3) | (2, 4) | (2, 7) | (4, 2) | (4, 3)
+    )
+}
+
+/// 오류 위치 — 소스 줄이 있으면 줄, 없으면 명령어 번호
+fn describe(program: &[Instruction], i: usize) -> String {
+    let name = match (program[i].addr.group, program[i].addr.command) {
+        (2, 0) => "점프",
+        (2, 1) => "조건점프",
+        (2, 2) => "호출",
+        (2, 4) => "반복",
+        _ => "삼분기",
+    };
+    match program[i].line {
+        Some(line) => format!("{}행 {}", line, name),
+        None => format!("명령어 {} {}", i, name),
+    }
+}
+
+/// 분기 대상 정적 해석 — 피연산자(정수 또는 어셈블러가 푼 라벨 주소),
+/// 피연산자가 없으면 바로 앞 `넣어 N`이 스택에 올린 상수.
+/// (대상들, 앞 넣어를 대상으로 접었는가)
+fn static_targets(program: &[Instruction], i: usize) -> Result<(Vec<usize>, bool), String> {
+    let inst = &program[i];
+    let arity = match (inst.addr.sector, inst.addr.group, inst.addr.command) {
+        (0, 2, 0 | 1 | 2 | 4) => 1,
+        (0, 4, 3) => 3,
+        _ => return Ok((Vec::new(), false)),
+    };
+    if inst.operands.is_empty() && arity == 1 {
+        let pushed = i.checked_sub(1)
+            .map(|p| &program[p])
+            .filter(|p| (p.addr.sector, p.addr.group, p.addr.command) == (0, 3, 0))
+            .and_then(|p| p.operands.first()?.as_addr());
+        return pushed.map(|t| (vec![t], true)).ok_or_else(|| {
+            format!("{}: 대상이 스택 값 — 정적으로 정할 수 없음 (라벨 또는 바로 앞 `넣어 N` 필요)", describe(program, i))
+        });
+    }
+    if inst.operands.len() < arity {
+        return Err(format!("{}: 대상 {}개 필요", describe(program, i), arity));
+    }
+    inst.operands.iter().take(arity)
+        .map(|v| v.as_addr().ok_or_else(|| format!("{}: 대상 '{}'은 주소가 아님", describe(program, i), v)))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|t| (t, false))
+}
+
+/// 레지읽기/레지쓰기 번호 (R0~R8)
+fn register(inst: &Instruction) -> Option<u32> {
+    let n = inst.operands.first().and_then(|v| v.as_int()).unwrap_or(0);
+    (0..REG_COUNT as i64).contains(&n).then_some(n as u32)
+}
+
 // ─────────────────────────────────────────────
 // 전체 파이프라인: TVM → IR → WASM
 // ─────────────────────────────────────────────
 
 /// TVM 프로그램 → .wasm 바이너리 (전체 파이프라인)
-pub fn compile_to_wasm(program: &[Instruction], module_name: &str) -> Vec<u8> {
+pub fn compile_to_wasm(program: &[Instruction], module_name: &str) -> Result<Vec<u8>, String> {
     // Step 1: TVM → IR
-    let ir = tvm_to_ir(program, module_name);
+    let ir = tvm_to_ir(program, module_name)?;
 
     // Step 2: IR → WASM binary
-    WasmBuilder::build(&ir)
+    Ok(WasmBuilder::build(&ir))
 }
 
 /// 한선어 소스 → .wasm 바이너리 (원스톱)
-pub fn compile_source_to_wasm(source: &str, module_name: &str) -> Vec<u8> {
+pub fn compile_source_to_wasm(source: &str, module_name: &str) -> Result<Vec<u8>, String> {
     let program = crate::assembler::assemble(source);
     compile_to_wasm(&program, module_name)
 }
@@ -257,21 +807,41 @@ pub struct CompileResult {
     pub import_count: usize,
 }
 
+impl CompileResult {
+    /// 생성한 바이너리 자체 검증 (wasm_gen::validate)
+    pub fn validate(&self) -> Result<(), WasmValidationError> {
+        crate::wasm_gen::validate(&self.wasm_bytes)
+    }
+}
+
 /// 상세 컴파일 (정보 포함)
-pub fn compile_with_info(source: &str, module_name: &str) -> CompileResult {
-    let program = crate::assembler::assemble(source);
-    let ir = tvm_to_ir(&program, module_name);
+pub fn compile_with_info(source: &str, module_name: &str) -> Result<CompileResult, String> {
+    compile_with_target(source, module_name, Target::Env)
+}
+
+/// 출력 대상을 골라 상세 컴파일
+pub fn compile_with_target(source: &str, module_name: &str, target: Target) -> Result<CompileResult, String> {
+    compile_program_for(&crate::assembler::assemble(source), module_name, target)
+}
+
+/// 어셈블된 프로그램 → 상세 컴파일
+pub fn compile_program_with_info(program: &[Instruction], module_name: &str) -> Result<CompileResult, String> {
+    compile_program_for(program, module_name, Target::Env)
+}
+
+fn compile_program_for(program: &[Instruction], module_name: &str, target: Target) -> Result<CompileResult, String> {
+    let ir = tvm_to_ir_for(program, module_name, target)?;
     let ir_ops: usize = ir.functions.iter().map(|f| f.body.len()).sum();
     let func_count = ir.functions.len();
     let import_count = ir.imports.len();
     let wasm = WasmBuilder::build(&ir);
 
-    CompileResult {
+    Ok(CompileResult {
         wasm_bytes: wasm,
         ir_op_count: ir_ops,
         func_count,
         import_count,
-    }
+    })
 }
 
 #[cfg(test)]
@@ -280,7 +850,7 @@ mod tests {
 
     #[test]
     fn test_simple_compile() {
-        let wasm = compile_source_to_wasm("넣어 5\n넣어 3\n더해\n종료", "test_add");
+        let wasm = compile_source_to_wasm("넣어 5\n넣어 3\n더해\n종료", "test_add").unwrap();
         assert_eq!(&wasm[0..4], b"\0asm");
         assert!(wasm.len() > 20);
         println!("add WASM: {} bytes", wasm.len());
@@ -291,7 +861,7 @@ mod tests {
         let result = compile_with_info(
             "넣어 10\n넣어 20\n더해\n보여줘\n종료",
             "calc"
-        );
+        ).unwrap();
         println!("WASM: {} bytes, IR ops: {}, funcs: {}, imports: {}",
             result.wasm_bytes.len(), result.ir_op_count,
             result.func_count, result.import_count);
@@ -299,11 +869,112 @@ mod tests {
         assert!(result.ir_op_count > 0);
     }
 
+    /// main 본문의 br_table (블록 분배표)
+    fn dispatch_table(ir: &IrModule) -> (Vec<u32>, u32) {
+        ir.functions[0].body.iter().find_map(|op| match op {
+            IrOp::BrTable(labels, default) => Some((labels.clone(), *default)),
+            _ => None,
+        }).expect("br_table 없음")
+    }
+
+    #[test]
+    fn test_three_way_branch_lowering() {
+        let program = crate::assembler::assemble("참\n삼분기 가 나 다\n가:\n나:\n다:\n종료");
+        let ir = tvm_to_ir(&program, "br3").unwrap();
+        // 블록: [참 삼분기] [종료] — 세 대상 모두 블록 1
+        assert_eq!(dispatch_table(&ir), (vec![0, 1], 3));
+        let body = &ir.functions[0].body;
+        assert!(body.windows(3).any(|w| w == [IrOp::ConstI32(1), IrOp::ConstI32(1), IrOp::ConstI32(1)]));
+        assert!(body.contains(&IrOp::Select));
+        assert_eq!(&WasmBuilder::build(&ir)[0..4], b"\0asm");
+    }
+
+    #[test]
+    fn test_loop_blocks_and_back_edge() {
+        // 머리 블록으로 되돌아가는 반복 — pc를 바꾸고 dispatch loop로 br
+        let program = crate::assembler::assemble(
+            "넣어 3\n머리:\n넣어 1\n빼\n복사\n보여줘\n복사\n넣어 0\n크다\n조건점프 머리\n종료");
+        let ir = tvm_to_ir(&program, "loop").unwrap();
+        assert_eq!(dispatch_table(&ir), (vec![0, 1, 2], 4));
+        let body = &ir.functions[0].body;
+        // 블록 1 (머리)에서 loop까지 깊이 = 3 - 1 - 1
+        assert!(body.windows(6).any(|w| w == [
+            IrOp::ConstI32(1), IrOp::ConstI32(2), IrOp::Call(5), IrOp::Const(0), IrOp::Gt, IrOp::Select,
+        ]));
+        assert!(body.windows(2).any(|w| w == [IrOp::LocalSet(LOCAL_PC), IrOp::Br(1)]));
+        assert!(!body.contains(&IrOp::Unreachable));
+        assert_eq!(ir.functions.len(), 7); // main + 런타임 6
+    }
+
+    #[test]
+    fn test_hanseon_if_and_unsupported_ops() {
+        let ir = tvm_to_ir(&crate::hanseon::compile(
+            "변수 x = 5\n만약 x > 3 { 값 1 보여줘 } 아니면 { 값 2 보여줘 }").instructions, "if").unwrap();
+        let (labels, default) = dispatch_table(&ir);
+        assert!(labels.len() >= 3);
+        assert_eq!(default, labels.len() as u32 + 1);
+        // 변수 → 전역 (sp · fp 다음)
+        assert_eq!(ir.globals.len(), 3);
+        assert!(!ir.functions[0].body.contains(&IrOp::Unreachable));
+
+        // 힙 명령은 트랩
+        let ir = tvm_to_ir(&crate::assembler::assemble("넣어 1\n할당\n종료"), "trap").unwrap();
+        assert_eq!(ir.functions[0].body.iter().filter(|op| **op == IrOp::Unreachable).count(), 1);
+    }
+
+    #[test]
+    fn test_stack_branch_targets() {
+        // 바로 앞 `넣어 N` — 대상으로 접고 스택 값은 버린다
+        let program = crate::assembler::assemble("넣어 3\n점프\n넣어 1\n넣어 2\n보여줘\n종료");
+        let ir = tvm_to_ir(&program, "fold").unwrap();
+        assert_eq!(dispatch_table(&ir), (vec![0, 1, 2], 4));
+        let body = &ir.functions[0].body;
+        assert!(body.windows(4).any(|w| w == [IrOp::Call(5), IrOp::Drop, IrOp::ConstI32(2), IrOp::LocalSet(LOCAL_PC)]));
+        assert!(!body.contains(&IrOp::Unreachable));
+        assert_eq!(compile_program_with_info(&program, "fold").unwrap().validate(), Ok(()));
+
+        // 계산된 대상 · 부족한 대상 · 다른 곳에서 들어오는 접힌 분기 → 컴파일 오류
+        let err = |src: &str| tvm_to_ir(&crate::assembler::assemble(src), "err").unwrap_err();
+        assert_eq!(err("넣어 1\n넣어 2\n더해\n호출\n종료"),
+            "4행 호출: 대상이 스택 값 — 정적으로 정할 수 없음 (라벨 또는 바로 앞 `넣어 N` 필요)");
+        assert!(err("참\n조건점프").starts_with("2행 조건점프"));
+        assert_eq!(err("참\n삼분기 가 나\n가:\n나:\n종료"), "2행 삼분기: 대상 3개 필요");
+        assert!(err("넣어 3\n여기:\n점프\n점프 여기").contains("3행 점프: 대상이 스택 값인데 다른 분기"));
+        assert!(compile_source_to_wasm("넣어 1\n넣어 2\n더해\n점프", "err").is_err());
+    }
+
     #[test]
     fn test_trit_compile() {
         // 3진 논리 프로그램
-        let wasm = compile_source_to_wasm("참\n모름\n그리고\n종료", "trit_logic");
+        let wasm = compile_source_to_wasm("참\n모름\n그리고\n종료", "trit_logic").unwrap();
         assert_eq!(&wasm[0..4], b"\0asm");
         println!("trit_logic WASM: {} bytes", wasm.len());
     }
+
+    #[test]
+    fn test_wasi_target() {
+        let program = crate::assembler::assemble("넣어 -42\n보여줘\n입력해\n종료");
+        let ir = tvm_to_ir_for(&program, "wasi", Target::Wasi).unwrap();
+        assert_eq!(ir.imports.len(), 1);
+        assert_eq!((ir.imports[0].module.as_str(), ir.imports[0].name.as_str()), ("wasi_snapshot_preview1", "fd_write"));
+        let start = ir.functions.last().unwrap();
+        assert!(start.is_export && start.name == "_start");
+        // 보여줘 → tvm_print, 입력해 → 트랩
+        let print_idx = ir.import_count() + ir.functions.iter().position(|f| f.name == "tvm_print").unwrap() as u32;
+        assert!(ir.functions[0].body.contains(&IrOp::Call(print_idx)));
+        assert!(ir.functions[0].body.contains(&IrOp::Unreachable));
+
+        let result = compile_with_target("넣어 7\n보여줘", "wasi", Target::Wasi).unwrap();
+        assert_eq!(result.validate(), Ok(()));
+        assert!(result.wasm_bytes.windows(22).any(|w| w == b"wasi_snapshot_preview1"));
+        assert!(!result.wasm_bytes.windows(5).any(|w| w == b"print"));
+    }
+
+    #[test]
+    fn test_target_parse() {
+        assert_eq!(Target::parse("WASI"), Some(Target::Wasi));
+        assert_eq!(Target::parse("env"), Some(Target::Env));
+        assert_eq!(Target::parse("wasm32"), None);
+        assert_eq!(Target::default().name(), "env");
+    }
 }
@@ -0,0 +1,676 @@
+// ═══════════════════════════════════════════════════════════════
+// Crowny Config — crowny.toml 런타임 설정 + 핫 리로드
+// 로그 레벨 · 요청 한도 · 합의 노드 목록 · 수수료 파라미터 · 표시 언어 · 프로그램 한도
+// 검증 실패 시 전체 거부(T), 성공 시 원자적 교체(P) + 변경 항목 로그
+// 재적재 트리거: SIGHUP · 관리자 엔드포인트 · 파일 변경 감시(poll)
+// 적용 대상: attach()로 등록한 Reloadable (요청 한도 · DEX 수수료 · 합의 노드)
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+use crate::car::TritState;
+use crate::i18n::Locale;
+use crate::program_limits::{ProgramLimitKind, ProgramLimits};
+use crate::trit_log::{Category, EventBuilder, Level, TritEventLog};
+
+// ═══════════════════════════════════════
+// TOML 부분집합 파서
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TomlValue {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<TomlValue>),
+}
+
+impl std::fmt::Display for TomlValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TomlValue::Str(s) => write!(f, "\"{}\"", s),
+            TomlValue::Int(n) => write!(f, "{}", n),
+            TomlValue::Float(x) => write!(f, "{}", x),
+            TomlValue::Bool(b) => write!(f, "{}", b),
+            TomlValue::Array(items) => {
+                let parts: Vec<String> = items.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{}]", parts.join(", "))
+            }
+        }
+    }
+}
+
+fn parse_value(raw: &str) -> Option<TomlValue> {
+    let raw = raw.trim();
+    if raw.len() >= 2 && raw.starts_with('"') && raw.ends_with('"') {
+        return Some(TomlValue::Str(raw[1..raw.len() - 1].to_string()));
+    }
+    if raw.starts_with('[') && raw.ends_with(']') {
+        let inner = raw[1..raw.len() - 1].trim();
+        if inner.is_empty() { return Some(TomlValue::Array(Vec::new())); }
+        let items: Option<Vec<TomlValue>> = inner.split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(parse_value)
+            .collect();
+        return items.map(TomlValue::Array);
+    }
+    match raw {
+        "true" => return Some(TomlValue::Bool(true)),
+        "false" => return Some(TomlValue::Bool(false)),
+        _ => {}
+    }
+    let digits = raw.replace('_', "");
+    if let Ok(n) = digits.parse::<i64>() { return Some(TomlValue::Int(n)); }
+    if let Ok(x) = digits.parse::<f64>() { return Some(TomlValue::Float(x)); }
+    None
+}
+
+/// 따옴표 밖의 # 부터 줄 끝까지 제거 — "a #b" 같은 문자열 값은 그대로
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// "섹션.키" → 값
+pub fn parse_toml(text: &str) -> Result<HashMap<String, TomlValue>, Vec<String>> {
+    let mut out = HashMap::new();
+    let mut errors = Vec::new();
+    let mut section = String::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() { continue; }
+
+        if line.starts_with('[') && line.ends_with(']') && !line.contains('=') {
+            section = line[1..line.len() - 1].trim().to_string();
+            continue;
+        }
+        let (k, v) = match line.split_once('=') {
+            Some(kv) => kv,
+            None => { errors.push(format!("{}행: '=' 없음", i + 1)); continue; }
+        };
+        let key = if section.is_empty() { k.trim().to_string() } else { format!("{}.{}", section, k.trim()) };
+        match parse_value(v) {
+            Some(val) => { out.insert(key, val); }
+            None => errors.push(format!("{}행: 값 해석 불가 '{}'", i + 1, v.trim())),
+        }
+    }
+    if errors.is_empty() { Ok(out) } else { Err(errors) }
+}
+
+// ═══════════════════════════════════════
+// 런타임 설정
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeConfig {
+    pub log_level: Level,
+    pub rate_limit_per_min: u64,
+    pub rate_limit_burst: u64,
+    pub consensus_nodes: Vec<String>,
+    pub consensus_quorum: u64,
+    pub dex_fee_bps: u64,
+    pub locale: Locale,
+    /// [limits] — 제출 프로그램 한도 (없으면 무제한)
+    pub program_limits: ProgramLimits,
+    /// [history] — 작업 기록 메모리 상한 (초과분은 축출/저장소)
+    pub history_capacity: u64,
+    /// [history] spill — 축출된 작업 기록을 보관할 저장소 디렉터리 (없으면 버림, 재시작 시 적용)
+    pub history_spill: Option<String>,
+    /// [node] — 노드 메시지 로그 상한
+    pub message_log_capacity: u64,
+    /// [llm] backend — 기본 모델 백엔드 echo · openai · anthropic (API 키는 CROWNY_LLM_API_KEY, 재시작 시 적용)
+    pub llm_backend: String,
+    /// [llm] url · model — 평문 HTTP 엔드포인트와 백엔드 모델명 (비면 백엔드 기본값)
+    pub llm_url: String,
+    pub llm_model: String,
+    /// [llm] cache — 응답 캐시 저장소 디렉터리 (없으면 메모리), cache_ttl 초 · cache_size 항목 수 (0이면 캐시 끔)
+    pub llm_cache: Option<String>,
+    pub llm_cache_ttl_secs: u64,
+    pub llm_cache_size: u64,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            log_level: Level::Info,
+            rate_limit_per_min: 600,
+            rate_limit_burst: 100,
+            consensus_nodes: vec!["127.0.0.1:7293".into()],
+            consensus_quorum: 1,
+            dex_fee_bps: 30,
+            locale: Locale::Ko,
+            program_limits: ProgramLimits::unlimited(),
+            history_capacity: crate::ring_log::DEFAULT_CAPACITY as u64,
+            history_spill: None,
+            message_log_capacity: crate::ring_log::MESSAGE_LOG_CAPACITY as u64,
+            llm_backend: "echo".into(),
+            llm_url: String::new(),
+            llm_model: String::new(),
+            llm_cache: None,
+            llm_cache_ttl_secs: crate::webserver::DEFAULT_LLM_CACHE_TTL_MS / 1000,
+            llm_cache_size: crate::webserver::DEFAULT_LLM_CACHE_CAPACITY as u64,
+        }
+    }
+}
+
+fn parse_level(s: &str) -> Option<Level> {
+    match s.to_ascii_lowercase().as_str() {
+        "trace" => Some(Level::Trace),
+        "debug" => Some(Level::Debug),
+        "info" => Some(Level::Info),
+        "warn" => Some(Level::Warn),
+        "error" => Some(Level::Error),
+        "fatal" => Some(Level::Fatal),
+        _ => None,
+    }
+}
+
+impl RuntimeConfig {
+    /// crowny.toml 해석 + 검증 — 오류는 전부 모아서 반환
+    pub fn from_toml(text: &str) -> Result<Self, Vec<String>> {
+        let table = parse_toml(text)?;
+        let mut cfg = Self::default();
+        let mut errors = Vec::new();
+
+        let mut keys: Vec<&String> = table.keys().collect();
+        keys.sort();
+        for key in keys {
+            let val = &table[key];
+            let uint = || match val {
+                TomlValue::Int(n) if *n >= 0 => Some(*n as u64),
+                _ => None,
+            };
+            let ok = match key.as_str() {
+                "log.level" => match val {
+                    TomlValue::Str(s) => parse_level(s).map(|l| cfg.log_level = l).is_some(),
+                    _ => false,
+                },
+                "rate_limit.requests_per_min" => uint().map(|n| cfg.rate_limit_per_min = n).is_some(),
+                "rate_limit.burst" => uint().map(|n| cfg.rate_limit_burst = n).is_some(),
+                "consensus.nodes" => match val {
+                    TomlValue::Array(items) => {
+                        let nodes: Option<Vec<String>> = items.iter()
+                            .map(|v| match v { TomlValue::Str(s) => Some(s.clone()), _ => None })
+                            .collect();
+                        nodes.map(|n| cfg.consensus_nodes = n).is_some()
+                    }
+                    _ => false,
+                },
+                "consensus.quorum" => uint().map(|n| cfg.consensus_quorum = n).is_some(),
+                "fees.dex_fee_bps" => uint().map(|n| cfg.dex_fee_bps = n).is_some(),
+                "fees.tx_base_fee" => {
+                    // 체인 위 거버넌스 파라미터 — 노드 설정으로 바꾸면 노드마다 달라진다
+                    errors.push(format!("{}: 거버넌스 파라미터 {}로 관리 (설정 불가)", key, crate::params::TX_BASE_FEE));
+                    true
+                }
+                "history.capacity" => uint().map(|n| cfg.history_capacity = n).is_some(),
+                "history.spill" => match val {
+                    TomlValue::Str(s) if !s.is_empty() => { cfg.history_spill = Some(s.clone()); true }
+                    _ => false,
+                },
+                "node.message_log" => uint().map(|n| cfg.message_log_capacity = n).is_some(),
+                "llm.cache" => match val {
+                    TomlValue::Str(s) if !s.is_empty() => { cfg.llm_cache = Some(s.clone()); true }
+                    _ => false,
+                },
+                "llm.cache_ttl" => uint().map(|n| cfg.llm_cache_ttl_secs = n).is_some(),
+                "llm.cache_size" => uint().map(|n| cfg.llm_cache_size = n).is_some(),
+                "llm.backend" | "llm.url" | "llm.model" => match val {
+                    TomlValue::Str(s) => {
+                        let slot = match key.as_str() {
+                            "llm.backend" => &mut cfg.llm_backend,
+                            "llm.url" => &mut cfg.llm_url,
+                            _ => &mut cfg.llm_model,
+                        };
+                        *slot = s.clone();
+                        true
+                    }
+                    _ => false,
+                },
+                "i18n.locale" => match val {
+                    TomlValue::Str(s) => Locale::parse(s).map(|l| cfg.locale = l).is_some(),
+                    _ => false,
+                },
+                k if k.starts_with("limits.") => {
+                    let slot = ProgramLimitKind::from_code(&k["limits.".len()..])
+                        .map(|kind| program_limit_slot(&mut cfg.program_limits, kind));
+                    match (slot, uint()) {
+                        (Some(slot), Some(n)) if n > 0 => { *slot = Some(n as usize); true }
+                        (Some(_), _) => false,
+                        (None, _) => { errors.push(format!("알 수 없는 설정: {}", key)); true }
+                    }
+                }
+                _ => {
+                    // 패키지 매니페스트 섹션은 무시
+                    if !key.starts_with("package.") && !key.starts_with("dependencies.") {
+                        errors.push(format!("알 수 없는 설정: {}", key));
+                    }
+                    true
+                }
+            };
+            if !ok {
+                errors.push(format!("{}: 잘못된 값 {}", key, val));
+            }
+        }
+
+        errors.extend(cfg.validate());
+        if errors.is_empty() { Ok(cfg) } else { Err(errors) }
+    }
+
+    /// 의미 검증
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.rate_limit_per_min == 0 {
+            errors.push("rate_limit.requests_per_min: 0보다 커야 함".into());
+        }
+        if self.rate_limit_burst > self.rate_limit_per_min {
+            errors.push(format!("rate_limit.burst({}) > requests_per_min({})",
+                self.rate_limit_burst, self.rate_limit_per_min));
+        }
+        if self.consensus_nodes.is_empty() {
+            errors.push("consensus.nodes: 최소 1개 필요".into());
+        }
+        let mut seen = std::collections::HashSet::new();
+        for n in &self.consensus_nodes {
+            if !seen.insert(n) {
+                errors.push(format!("consensus.nodes: 중복 노드 {}", n));
+            }
+            if parse_node_addr(n).is_none() {
+                errors.push(format!("consensus.nodes: 주소는 host:port ({})", n));
+            }
+        }
+        if self.consensus_quorum == 0 || self.consensus_quorum as usize > self.consensus_nodes.len() {
+            errors.push(format!("consensus.quorum({}): 1..={} 범위",
+                self.consensus_quorum, self.consensus_nodes.len()));
+        }
+        if self.dex_fee_bps > 1000 {
+            errors.push(format!("fees.dex_fee_bps({}): 최대 1000 (10%)", self.dex_fee_bps));
+        }
+        if self.history_capacity == 0 {
+            errors.push("history.capacity: 0보다 커야 함".into());
+        }
+        if self.message_log_capacity == 0 {
+            errors.push("node.message_log: 0보다 커야 함".into());
+        }
+        match self.llm_backend.as_str() {
+            "echo" => {}
+            "openai" | "anthropic" if self.llm_url.starts_with("http://") => {}
+            "openai" | "anthropic" => errors.push(format!("llm.url: {} 백엔드는 http:// 엔드포인트 필요", self.llm_backend)),
+            other => errors.push(format!("llm.backend: echo · openai · anthropic 중 하나 ({})", other)),
+        }
+        errors
+    }
+
+    /// 비교용 평탄화 목록
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("log.level", self.log_level.to_string().to_lowercase()),
+            ("rate_limit.requests_per_min", self.rate_limit_per_min.to_string()),
+            ("rate_limit.burst", self.rate_limit_burst.to_string()),
+            ("consensus.nodes", format!("[{}]", self.consensus_nodes.join(", "))),
+            ("consensus.quorum", self.consensus_quorum.to_string()),
+            ("fees.dex_fee_bps", self.dex_fee_bps.to_string()),
+            ("i18n.locale", self.locale.code().to_string()),
+            ("limits.max_instructions", limit_str(self.program_limits.max_instructions)),
+            ("limits.max_nesting", limit_str(self.program_limits.max_nesting)),
+            ("limits.max_string_bytes", limit_str(self.program_limits.max_string_bytes)),
+            ("limits.max_functions", limit_str(self.program_limits.max_functions)),
+            ("history.capacity", self.history_capacity.to_string()),
+            ("history.spill", self.history_spill.clone().unwrap_or_default()),
+            ("node.message_log", self.message_log_capacity.to_string()),
+            ("llm.backend", self.llm_backend.clone()),
+            ("llm.url", self.llm_url.clone()),
+            ("llm.model", self.llm_model.clone()),
+            ("llm.cache", self.llm_cache.clone().unwrap_or_default()),
+            ("llm.cache_ttl", self.llm_cache_ttl_secs.to_string()),
+            ("llm.cache_size", self.llm_cache_size.to_string()),
+        ]
+    }
+
+    /// 변경 항목
+    pub fn diff(&self, new: &RuntimeConfig) -> Vec<ConfigChange> {
+        self.entries().into_iter().zip(new.entries())
+            .filter(|((_, a), (_, b))| a != b)
+            .map(|((key, old), (_, new))| ConfigChange { key, old, new })
+            .collect()
+    }
+}
+
+/// "host:port" → (host, port)
+pub fn parse_node_addr(addr: &str) -> Option<(&str, u16)> {
+    let (host, port) = addr.rsplit_once(':')?;
+    Some((host, port.parse().ok()?)).filter(|(h, _)| !h.is_empty())
+}
+
+fn program_limit_slot(limits: &mut ProgramLimits, kind: ProgramLimitKind) -> &mut Option<usize> {
+    match kind {
+        ProgramLimitKind::Instructions => &mut limits.max_instructions,
+        ProgramLimitKind::Nesting => &mut limits.max_nesting,
+        ProgramLimitKind::StringSize => &mut limits.max_string_bytes,
+        ProgramLimitKind::Functions => &mut limits.max_functions,
+    }
+}
+
+fn limit_str(limit: Option<usize>) -> String {
+    limit.map(|n| n.to_string()).unwrap_or_else(|| "unlimited".into())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigChange {
+    pub key: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+impl std::fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} → {}", self.key, self.old, self.new)
+    }
+}
+
+// ═══════════════════════════════════════
+// 핫 리로드 관리자
+// ═══════════════════════════════════════
+
+/// 재적재된 설정을 받아 쓰는 구성 요소 — 교체가 성공했을 때만 불린다
+pub trait Reloadable {
+    fn apply_config(&mut self, config: &RuntimeConfig);
+}
+
+pub type SharedReloadable = Rc<RefCell<dyn Reloadable>>;
+
+static SIGHUP: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_sighup(_signum: i32) {
+    SIGHUP.store(true, Ordering::SeqCst);
+}
+
+/// SIGHUP 수신 시 플래그만 세운다 — 실제 재적재는 take_sighup()을 확인하는 쪽에서
+pub fn install_sighup() {
+    #[cfg(unix)]
+    {
+        extern "C" {
+            fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+        }
+        const SIGHUP_NUM: i32 = 1;
+        // 핸들러는 원자 변수 하나만 건드린다 (async-signal-safe)
+        unsafe { signal(SIGHUP_NUM, on_sighup); }
+    }
+}
+
+/// 마지막 확인 뒤 SIGHUP이 왔는지 (플래그를 지운다)
+pub fn take_sighup() -> bool {
+    SIGHUP.swap(false, Ordering::SeqCst)
+}
+
+pub struct ConfigManager {
+    pub path: Option<PathBuf>,
+    current: RuntimeConfig,
+    pub version: u64,
+    last_mtime: Option<SystemTime>,
+    pub log: TritEventLog,
+    targets: Vec<(String, SharedReloadable)>,
+}
+
+impl ConfigManager {
+    pub fn new(config: RuntimeConfig) -> Self {
+        let mut log = TritEventLog::new();
+        log.set_min_level(config.log_level);
+        Self { path: None, current: config, version: 1, last_mtime: None, log, targets: Vec::new() }
+    }
+
+    /// 적용 대상 등록 — 현재 설정을 바로 한 번 적용
+    pub fn attach(&mut self, name: &str, target: SharedReloadable) {
+        target.borrow_mut().apply_config(&self.current);
+        self.targets.push((name.to_string(), target));
+    }
+
+    /// 파일에서 최초 적재
+    pub fn load_file(path: &Path) -> Result<Self, Vec<String>> {
+        let text = std::fs::read_to_string(path).map_err(|e| vec![format!("{}: {}", path.display(), e)])?;
+        let mut mgr = Self::new(RuntimeConfig::from_toml(&text)?);
+        mgr.path = Some(path.to_path_buf());
+        mgr.last_mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        Ok(mgr)
+    }
+
+    pub fn current(&self) -> &RuntimeConfig {
+        &self.current
+    }
+
+    /// 새 설정 텍스트 적용 — 전부 통과해야 교체 (부분 적용 없음)
+    pub fn apply_str(&mut self, text: &str) -> Result<Vec<ConfigChange>, Vec<String>> {
+        let next = match RuntimeConfig::from_toml(text) {
+            Ok(c) => c,
+            Err(errors) => {
+                self.log.log(EventBuilder::new(Category::System, "설정 거부")
+                    .level(Level::Error).trit(TritState::Failed).source("config")
+                    .field("version", &self.version.to_string())
+                    .field("errors", &errors.join("; ")));
+                return Err(errors);
+            }
+        };
+
+        let changes = self.current.diff(&next);
+        if changes.is_empty() {
+            self.log.log(EventBuilder::new(Category::System, "설정 변경 없음")
+                .trit(TritState::Pending).source("config"));
+            return Ok(changes);
+        }
+
+        self.current = next;
+        self.version += 1;
+        self.log.set_min_level(self.current.log_level);
+        for (_, target) in &self.targets {
+            target.borrow_mut().apply_config(&self.current);
+        }
+        let applied: Vec<&str> = self.targets.iter().map(|(name, _)| name.as_str()).collect();
+        let mut ev = EventBuilder::new(Category::System, "설정 재적재")
+            .level(Level::Warn).trit(TritState::Success).source("config")
+            .field("version", &self.version.to_string())
+            .field("applied", &applied.join(","));
+        for c in &changes {
+            ev = ev.field(c.key, &format!("{} → {}", c.old, c.new));
+        }
+        self.log.log(ev);
+        Ok(changes)
+    }
+
+    /// 설정 파일 다시 읽기
+    pub fn reload(&mut self) -> Result<Vec<ConfigChange>, Vec<String>> {
+        let path = self.path.clone().ok_or_else(|| vec!["설정 파일 경로 없음".to_string()])?;
+        let text = std::fs::read_to_string(&path).map_err(|e| vec![format!("{}: {}", path.display(), e)])?;
+        self.last_mtime = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        self.apply_str(&text)
+    }
+
+    /// 파일 변경 감시 — 수정 시각이 바뀌었을 때만 재적재
+    pub fn poll(&mut self) -> Option<Result<Vec<ConfigChange>, Vec<String>>> {
+        let path = self.path.as_ref()?;
+        let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        if self.last_mtime == Some(mtime) { return None; }
+        Some(self.reload())
+    }
+
+    /// SIGHUP을 받았으면 무조건, 아니면 파일이 바뀌었을 때 재적재
+    pub fn check_reload(&mut self) -> Option<Result<Vec<ConfigChange>, Vec<String>>> {
+        if take_sighup() && self.path.is_some() {
+            return Some(self.reload());
+        }
+        self.poll()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+[log]
+level = "info"
+
+[rate_limit]
+requests_per_min = 600
+burst = 100
+
+[consensus]
+nodes = ["a:7293", "b:7293", "c:7293"]
+quorum = 2
+
+[fees]
+dex_fee_bps = 30   # 0.3%
+"#;
+
+    #[test]
+    fn test_parse_config() {
+        let cfg = RuntimeConfig::from_toml(BASE).unwrap();
+        assert_eq!(cfg.consensus_nodes.len(), 3);
+        assert_eq!(cfg.consensus_quorum, 2);
+        assert_eq!(cfg.dex_fee_bps, 30);
+        assert_eq!(cfg.program_limits, ProgramLimits::unlimited());
+
+        let cfg = RuntimeConfig::from_toml("[limits]\nmax_instructions = 5000\nmax_nesting = 16").unwrap();
+        assert_eq!(cfg.program_limits.max_instructions, Some(5000));
+        assert_eq!(cfg.program_limits.max_nesting, Some(16));
+        assert_eq!(cfg.program_limits.max_functions, None);
+    }
+
+    #[test]
+    fn test_node_and_history_settings() {
+        assert_eq!(RuntimeConfig::default().message_log_capacity, crate::ring_log::MESSAGE_LOG_CAPACITY as u64);
+        let cfg = RuntimeConfig::from_toml("[node]\nmessage_log = 64").unwrap();
+        assert_eq!(cfg.message_log_capacity, 64);
+        assert!(RuntimeConfig::from_toml("[node]\nmessage_log = 0").is_err());
+
+        let cfg = RuntimeConfig::from_toml("[history]\nspill = \".crowny/history\"").unwrap();
+        assert_eq!(cfg.history_spill.as_deref(), Some(".crowny/history"));
+        assert!(RuntimeConfig::from_toml("[history]\nspill = 3").is_err());
+    }
+
+    #[test]
+    fn test_llm_settings() {
+        assert_eq!(RuntimeConfig::default().llm_backend, "echo");
+        let cfg = RuntimeConfig::from_toml("[llm]\nbackend = \"anthropic\"\nurl = \"http://127.0.0.1:8080\"").unwrap();
+        assert_eq!((cfg.llm_backend.as_str(), cfg.llm_model.as_str()), ("anthropic", ""));
+        // https는 앞단 프록시로 — 평문 엔드포인트만
+        assert!(RuntimeConfig::from_toml("[llm]\nbackend = \"openai\"\nurl = \"https://api.openai.com\"").is_err());
+        assert!(RuntimeConfig::from_toml("[llm]\nbackend = \"gpt\"").is_err());
+
+        let cfg = RuntimeConfig::from_toml("[llm]\ncache = \".crowny/llm\"\ncache_ttl = 60\ncache_size = 0").unwrap();
+        assert_eq!((cfg.llm_cache.as_deref(), cfg.llm_cache_ttl_secs, cfg.llm_cache_size), (Some(".crowny/llm"), 60, 0));
+    }
+
+    #[test]
+    fn test_comment_inside_string() {
+        let map = parse_toml("# 머리말\nname = \"a #b\" # 꼬리\ntags = [\"x\", \"#y\"]#붙은 주석").unwrap();
+        assert_eq!(map["name"], TomlValue::Str("a #b".into()));
+        assert_eq!(map["tags"], TomlValue::Array(vec![TomlValue::Str("x".into()), TomlValue::Str("#y".into())]));
+    }
+
+    #[test]
+    fn test_reload_diff() {
+        let mut mgr = ConfigManager::new(RuntimeConfig::from_toml(BASE).unwrap());
+        let next = BASE.replace("level = \"info\"", "level = \"debug\"").replace("dex_fee_bps = 30", "dex_fee_bps = 25");
+        let changes = mgr.apply_str(&next).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].to_string(), "log.level: info → debug");
+        assert_eq!(changes[1].key, "fees.dex_fee_bps");
+        assert_eq!(mgr.version, 2);
+        assert_eq!(mgr.current().log_level, Level::Debug);
+    }
+
+    #[test]
+    fn test_invalid_config_rejected_atomically() {
+        let mut mgr = ConfigManager::new(RuntimeConfig::from_toml(BASE).unwrap());
+        // 수수료는 유효하지만 쿼럼이 노드 수 초과 → 전체 거부
+        let bad = BASE.replace("dex_fee_bps = 30", "dex_fee_bps = 10").replace("quorum = 2", "quorum = 5");
+        let errors = mgr.apply_str(&bad).unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("consensus.quorum")));
+        assert_eq!(mgr.current().dex_fee_bps, 30);
+        assert_eq!(mgr.version, 1);
+        assert_eq!(mgr.log.filter_trit(TritState::Failed).len(), 1);
+
+        assert!(RuntimeConfig::from_toml("[log]\nlevl = \"info\"").is_err());
+        assert!(RuntimeConfig::from_toml("[fees]\ndex_fee_bps = -1").is_err());
+        assert!(RuntimeConfig::from_toml("[limits]\nmax_nesting = 0").is_err());
+        assert!(RuntimeConfig::from_toml("[limits]\nmax_depth = 3").is_err());
+    }
+
+    #[test]
+    fn test_poll_file() {
+        let path = std::env::temp_dir().join(format!("crowny_cfg_{}.toml", std::process::id()));
+        std::fs::write(&path, BASE).unwrap();
+        let mut mgr = ConfigManager::load_file(&path).unwrap();
+        assert!(mgr.poll().is_none());
+
+        std::fs::write(&path, BASE.replace("burst = 100", "burst = 50")).unwrap();
+        let changes = mgr.reload().unwrap();
+        assert_eq!(changes[0].key, "rate_limit.burst");
+        assert_eq!(mgr.current().rate_limit_burst, 50);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_reload_reaches_components() {
+        use crate::dex::CrownyDEX;
+        use crate::live_consensus::LiveConsensus;
+        let mut mgr = ConfigManager::new(RuntimeConfig::from_toml(BASE).unwrap());
+        let mut dex = CrownyDEX::new();
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        let dex = Rc::new(RefCell::new(dex));
+        let consensus = Rc::new(RefCell::new(LiveConsensus::with_nodes(Vec::new())));
+        mgr.attach("dex", dex.clone());
+        mgr.attach("consensus", consensus.clone());
+        assert_eq!(consensus.borrow().nodes.len(), 3);
+        consensus.borrow_mut().nodes[0].name = "첫 노드".into();
+
+        let next = BASE.replace("dex_fee_bps = 30", "dex_fee_bps = 5")
+            .replace("\"c:7293\"]", "\"d:7300\"]").replace("quorum = 2", "quorum = 3");
+        mgr.apply_str(&next).unwrap();
+        assert_eq!(dex.borrow().pools[&pool].fee_bps, 5);
+        let c = consensus.borrow();
+        let addrs: Vec<String> = c.nodes.iter().map(|n| format!("{}:{}", n.host, n.port)).collect();
+        assert_eq!(addrs, ["a:7293", "b:7293", "d:7300"]);
+        assert_eq!(c.nodes[0].name, "첫 노드"); // 남은 노드는 유지
+        assert_eq!(c.quorum, 3);
+
+        // 거버넌스 파라미터 · 잘못된 주소는 거부
+        assert!(RuntimeConfig::from_toml("[fees]\ntx_base_fee = 1").unwrap_err()[0].contains("tx.base_fee"));
+        assert!(RuntimeConfig::from_toml("[consensus]\nnodes = [\"nohost\"]").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sighup_triggers_reload() {
+        let path = std::env::temp_dir().join(format!("crowny_cfg_hup_{}.toml", std::process::id()));
+        std::fs::write(&path, BASE).unwrap();
+        let mut mgr = ConfigManager::load_file(&path).unwrap();
+        install_sighup();
+        assert!(mgr.check_reload().is_none());
+
+        std::process::Command::new("kill").args(["-HUP", &std::process::id().to_string()]).status().unwrap();
+        let start = std::time::Instant::now();
+        while !SIGHUP.load(Ordering::SeqCst) && start.elapsed().as_secs() < 5 {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        // 내용이 같아도 SIGHUP이면 다시 읽는다 → 변경 없음
+        assert_eq!(mgr.check_reload(), Some(Ok(Vec::new())));
+        assert!(mgr.check_reload().is_none());
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,336 @@
+// ═══════════════════════════════════════════════════════════════
+// 형식 호환성 (Conformance) — 3진 인코딩 왕복 불변식
+// SDK · 노드 · FPGA 브리지가 같은 비트를 같은 값으로 읽는지 한곳에서 검사
+//
+//   Word6 ↔ 10진 · bridge TritWord/Tryte 패킹 · TritBuffer ↔ 바이트
+//   CtpHeader ↔ 문자열 · CTP 메시지 · 바이트코드 직렬화/분석
+//   TritStore 스냅샷 복구 · WAL 재생
+//
+// 작은 정의역은 전수 검사, 나머지는 시드 고정 난수 입력 (재현 가능)
+// 실패 시 첫 반례를 보고한다
+// ═══════════════════════════════════════════════════════════════
+
+use crate::bridge::{Tryte, TritWord};
+use crate::bytecode;
+use crate::network::{CtpMessage, MessageType, NetTrit, StatusCode, TritBuffer};
+use crate::opcode::OpcodeAddr;
+use crate::trit::{Trit, Word6};
+use crate::trit_store::{StoreValue, TritStore};
+use crate::trit_test::{AssertResult, TestCase, TestSuite};
+use crate::value::Value;
+use crate::vm::Instruction;
+use crate::webserver::CtpHeader;
+
+pub const DEFAULT_SEED: u64 = 0x3_3333_3333;
+pub const DEFAULT_CASES: usize = 256;
+
+/// 시드 고정 난수 (xorshift64)
+#[derive(Debug, Clone)]
+pub struct Gen(u64);
+
+impl Gen {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// lo..=hi
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as i64
+    }
+
+    pub fn trit(&mut self) -> i8 {
+        self.range(-1, 1) as i8
+    }
+
+    pub fn net_trit(&mut self) -> NetTrit {
+        match self.trit() { 1 => NetTrit::P, -1 => NetTrit::T, _ => NetTrit::O }
+    }
+
+    pub fn text(&mut self, max: usize) -> String {
+        const CHARS: [char; 8] = ['a', 'Z', '0', ' ', '한', '선', '"', '\\'];
+        let len = self.range(0, max as i64) as usize;
+        (0..len).map(|_| CHARS[self.range(0, 7) as usize]).collect()
+    }
+}
+
+/// 입력마다 성질 검사 — 첫 반례에서 멈춤
+pub fn property<T: std::fmt::Debug>(
+    name: &str,
+    inputs: impl IntoIterator<Item = T>,
+    check: impl Fn(&T) -> Result<(), String>,
+) -> AssertResult {
+    let mut count = 0usize;
+    for input in inputs {
+        count += 1;
+        if let Err(why) = check(&input) {
+            return AssertResult {
+                passed: false,
+                name: name.into(),
+                message: why.clone(),
+                expected: "왕복 일치".into(),
+                actual: format!("반례 #{} {:?} — {}", count, input, why),
+            };
+        }
+    }
+    AssertResult {
+        passed: true,
+        name: name.into(),
+        message: format!("{}건 통과", count),
+        expected: "왕복 일치".into(),
+        actual: format!("{}건", count),
+    }
+}
+
+fn expect_eq<A: PartialEq + std::fmt::Debug>(what: &str, a: A, b: A) -> Result<(), String> {
+    if a == b { Ok(()) } else { Err(format!("{}: {:?} ≠ {:?}", what, a, b)) }
+}
+
+// ─────────────────────────────────────────────
+// 워드 · 패킹
+// ─────────────────────────────────────────────
+
+fn word_checks() -> Vec<AssertResult> {
+    vec![
+        property("Word6 ↔ 10진 (전수)", -364i16..=364, |&v| {
+            expect_eq("to_decimal", Word6::from_decimal(v).to_decimal(), v)
+        }),
+        property("Word6 opcode 분해/조립 (전수)", 0u16..729, |&n| {
+            let (s, g, c) = ((n / 81) as u8, (n / 9 % 9) as u8, (n % 9) as u8);
+            expect_eq("decode_opcode", Word6::encode_opcode(s, g, c).decode_opcode(), (s, g, c))
+        }),
+        property("Word6 = bridge TritWord (전수)", -364i16..=364, |&v| {
+            let w = Word6::from_decimal(v);
+            let b = TritWord::from_decimal(v);
+            expect_eq("trits", w.trits.map(Trit::to_i8), b.trits)?;
+            expect_eq("opcode", w.decode_opcode(), b.decode_opcode())
+        }),
+        property("Word6 산술 = 정수 산술 (전수 × 표본)", -364i16..=364, |&a| {
+            let x = Word6::from_decimal(a);
+            for b in (-364i16..=364).step_by(13) {
+                let y = Word6::from_decimal(b);
+                expect_eq("add", x.add(&y).to_wide_decimal(), (a + b) as i32)?;
+                expect_eq("sub", x.sub(&y).to_wide_decimal(), (a - b) as i32)?;
+                expect_eq("mul", x.mul(&y).to_wide_decimal(), a as i32 * b as i32)?;
+                let sum = a + b;
+                expect_eq("checked", x.add(&y).checked().map(|w| w.to_decimal()), (sum.abs() <= Word6::MAX).then_some(sum))?;
+            }
+            for n in 0..=6 {
+                expect_eq("shift", x.shift(n).to_wide_decimal(), a as i32 * 3i32.pow(n as u32))?;
+            }
+            expect_eq("neg", x.neg().to_decimal(), -a)?;
+            expect_eq("bridge", TritWord::from_word6(&x).to_word6(), x)
+        }),
+        property("TritWord u16 패킹 (전수)", -364i16..=364, |&v| {
+            let w = TritWord::from_decimal(v);
+            expect_eq("unpack", TritWord::from_packed_u16(w.to_packed_u16()), w)
+        }),
+        property("Tryte 바이트 패킹 (전수)", -13i8..=13, |&v| {
+            let t = Tryte::from_decimal(v);
+            expect_eq("to_decimal", t.to_decimal(), v)?;
+            expect_eq("unpack", Tryte::from_packed_byte(t.to_packed_byte()), t)
+        }),
+        property("NetTrit 2bit = bridge 패킹 비트", [-1i8, 0, 1], |&v| {
+            let net = match v { 1 => NetTrit::P, -1 => NetTrit::T, _ => NetTrit::O };
+            // Tryte 최하위 트릿의 2bit가 NetTrit 물리 매핑과 같아야 한다
+            let tryte = Tryte { trits: [v, 0, 0] };
+            expect_eq("2bit", tryte.to_packed_byte() & 0b11, net.to_2bit())?;
+            expect_eq("roundtrip", NetTrit::from_2bit(net.to_2bit()), Some(net))
+        }),
+    ]
+}
+
+// ─────────────────────────────────────────────
+// 네트워크 (TritBuffer · CTP)
+// ─────────────────────────────────────────────
+
+fn network_checks(seed: u64, cases: usize) -> Vec<AssertResult> {
+    let mut g = Gen::new(seed);
+    let buffers: Vec<Vec<NetTrit>> = (0..cases)
+        .map(|_| { let n = g.range(0, 64) as usize; (0..n).map(|_| g.net_trit()).collect() })
+        .collect();
+    let headers: Vec<[i8; 9]> = (0..cases).map(|_| std::array::from_fn(|_| g.trit())).collect();
+    let messages: Vec<(i8, i8, Vec<i16>)> = (0..cases)
+        .map(|_| (g.trit(), g.trit(), (0..g.range(0, 20)).map(|_| g.range(-364, 364) as i16).collect()))
+        .collect();
+
+    vec![
+        property("TritBuffer ↔ 바이트", buffers.clone(), |trits| {
+            let buf = TritBuffer::from_trits(trits.clone());
+            let bytes = buf.to_bytes();
+            expect_eq("byte_len", bytes.len(), buf.byte_len())?;
+            expect_eq("trits", TritBuffer::from_bytes(&bytes, trits.len()).trits, trits.clone())?;
+            let mut out = vec![0u8; buf.byte_len()];
+            buf.write_into(&mut out)?;
+            expect_eq("write_into", out, bytes)
+        }),
+        property("TritBuffer word6 = Word6 (MST 먼저)", -364i16..=364, |&v| {
+            let mut buf = TritBuffer::new();
+            buf.push_word6(v);
+            let mut w: Vec<i8> = Word6::from_decimal(v).trits.iter().map(|t| t.to_i8()).collect();
+            w.reverse();
+            expect_eq("trits", buf.trits.iter().map(|t| *t as i8).collect::<Vec<_>>(), w)?;
+            expect_eq("read_word6", buf.read_word6(0), Some(v))
+        }),
+        property("CtpHeader ↔ 문자열", headers, |t| {
+            let h = CtpHeader::from_header_str(&t.iter().map(|v| match v { 1 => 'P', -1 => 'T', _ => 'O' }).collect::<String>());
+            let fields = [h.state, h.permission, h.consensus, h.transaction, h.routing,
+                          h.reserved[0], h.reserved[1], h.reserved[2], h.reserved[3]];
+            expect_eq("fields", fields, *t)?;
+            expect_eq("reparse", CtpHeader::from_header_str(&h.to_header_str()).to_header_str(), h.to_header_str())
+        }),
+        property("CTP 메시지 직렬화", messages, |(kind, status, words)| {
+            let mut payload = TritBuffer::new();
+            words.iter().for_each(|w| payload.push_word6(*w));
+            let msg_type = MessageType::from_trit(match kind { 1 => NetTrit::P, -1 => NetTrit::T, _ => NetTrit::O });
+            let status = StatusCode::from_trit(match status { 1 => NetTrit::P, -1 => NetTrit::T, _ => NetTrit::O });
+            let msg = CtpMessage::new(msg_type, status, payload);
+            let wire = msg.serialize();
+            let back = CtpMessage::deserialize(&TritBuffer::from_bytes(&wire.to_bytes(), wire.len()))?;
+            expect_eq("header", (back.version, back.msg_type, back.status), (msg.version, msg.msg_type, msg.status))?;
+            expect_eq("payload", back.payload.words().collect::<Vec<_>>(), words.clone())
+        }),
+    ]
+}
+
+// ─────────────────────────────────────────────
+// 바이트코드
+// ─────────────────────────────────────────────
+
+fn random_value(g: &mut Gen) -> Value {
+    match g.range(0, 5) {
+        0 => Value::Int(g.next_u64() as i64),
+        1 => Value::Float(g.range(-1_000_000, 1_000_000) as f64 / 7.0),
+        2 => Value::Bool(g.range(0, 1) == 1),
+        3 => Value::Trit(Trit::from_i8(g.trit())),
+        4 => Value::Str(g.text(12)),
+        _ => Value::Nil,
+    }
+}
+
+fn random_program(g: &mut Gen) -> Vec<Instruction> {
+    (0..g.range(0, 24))
+        .map(|_| {
+            let addr = OpcodeAddr { sector: g.range(0, 8) as u8, group: g.range(0, 8) as u8, command: g.range(0, 8) as u8 };
+            let operands = (0..g.range(0, 3)).map(|_| random_value(g)).collect();
+            Instruction::from_addr(addr, operands)
+        })
+        .collect()
+}
+
+fn bytecode_checks(seed: u64, cases: usize) -> Vec<AssertResult> {
+    let mut g = Gen::new(seed ^ 0xB17E);
+    let programs: Vec<Vec<Instruction>> = (0..cases).map(|_| random_program(&mut g)).collect();
+    vec![
+        property("바이트코드 직렬화 ↔ 역직렬화", programs.iter().collect::<Vec<_>>(), |program| {
+            let bytes = bytecode::serialize(program);
+            let back = bytecode::deserialize(&bytes)?;
+            expect_eq("len", back.len(), program.len())?;
+            for (a, b) in program.iter().zip(&back) {
+                expect_eq("opcode", a.opcode, b.opcode)?;
+            }
+            expect_eq("reserialize", bytecode::serialize(&back), bytes)
+        }),
+        property("바이트코드 분석", programs.iter().collect::<Vec<_>>(), |program| {
+            let bytes = bytecode::serialize(program);
+            let info = bytecode::analyze(&bytes)?;
+            expect_eq("version", info.version, bytecode::VERSION)?;
+            expect_eq("count", info.instruction_count, program.len())?;
+            expect_eq("size", info.byte_size, bytes.len())
+        }),
+    ]
+}
+
+// ─────────────────────────────────────────────
+// 저장소 (스냅샷 · WAL)
+// ─────────────────────────────────────────────
+
+/// 비교용 상태 요약 (키 정렬)
+fn store_state(store: &TritStore) -> Vec<String> {
+    let mut keys: Vec<&String> = store.keys();
+    keys.sort();
+    let mut out: Vec<String> = keys.iter()
+        .map(|k| format!("{}={}", k, store.peek(k).map(|v| v.to_string()).unwrap_or_default()))
+        .collect();
+    for state in [-1, 0, 1] {
+        let mut tagged = store.filter_by_trit(state);
+        tagged.sort();
+        out.extend(tagged.iter().map(|k| format!("{}@{}", k, state)));
+    }
+    out
+}
+
+fn random_ops(g: &mut Gen, store: &mut TritStore, n: i64) {
+    for _ in 0..n {
+        let key = format!("k{}", g.range(0, 9));
+        match g.range(0, 4) {
+            0 => store.set(&key, StoreValue::Int(g.range(-1000, 1000))),
+            1 => store.set(&key, StoreValue::Text(g.text(8))),
+            2 => store.set(&key, StoreValue::Trit(g.trit())),
+            3 => store.set_trit_state(&key, g.trit()),
+            _ => { store.delete(&key); }
+        }
+    }
+}
+
+fn store_checks(seed: u64, cases: usize) -> Vec<AssertResult> {
+    let seeds: Vec<u64> = { let mut g = Gen::new(seed ^ 0x5707E); (0..cases / 4).map(|_| g.next_u64()).collect() };
+    vec![
+        property("스냅샷 내보내기 → 복구", seeds.clone(), |&s| {
+            let mut g = Gen::new(s);
+            let mut store = TritStore::new();
+            random_ops(&mut g, &mut store, 20);
+            let expected = store_state(&store);
+            let id = store.snapshot();
+            random_ops(&mut g, &mut store, 20);
+            store.restore(id);
+            expect_eq("state", store_state(&store), expected)
+        }),
+        property("WAL 재생 = 원본", seeds, |&s| {
+            let mut g = Gen::new(s);
+            let mut store = TritStore::new();
+            random_ops(&mut g, &mut store, 40);
+            let replayed = TritStore::replay(&store.wal_entries());
+            expect_eq("state", store_state(&replayed), store_state(&store))?;
+            expect_eq("wal_len", replayed.wal_len(), store.wal_len())
+        }),
+    ]
+}
+
+/// 전체 호환성 스위트
+pub fn conformance_suite(seed: u64, cases: usize) -> TestSuite {
+    let mut suite = TestSuite::new("형식 호환성 테스트");
+    suite.add(TestCase::new("워드_패킹", "Word6 · Tryte · TritWord", word_checks));
+    suite.add(TestCase::new("네트워크", "TritBuffer · CtpHeader · CTP", move || network_checks(seed, cases)));
+    suite.add(TestCase::new("바이트코드", "serialize · deserialize · analyze", move || bytecode_checks(seed, cases)));
+    suite.add(TestCase::new("저장소", "스냅샷 · WAL 재생", move || store_checks(seed, cases)));
+    suite
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conformance_suite_passes() {
+        let result = conformance_suite(DEFAULT_SEED, DEFAULT_CASES).run();
+        assert_eq!(result.failed, 0, "{}", result.report());
+        assert!(result.total >= 14);
+    }
+
+    #[test]
+    fn test_property_reports_counterexample() {
+        let r = property("짝수", [2, 4, 5, 6], |n| if n % 2 == 0 { Ok(()) } else { Err("홀수".into()) });
+        assert!(!r.passed);
+        assert!(r.actual.contains("반례 #3 5"));
+        assert_eq!(Gen::new(7).next_u64(), Gen::new(7).next_u64());
+    }
+}
@@ -0,0 +1,488 @@
+// ═══════════════════════════════════════════════════════════════
+// 허가형 컨소시엄 모드 — 의료 · 교육 기관 파일럿용
+// 밸리데이터 = 거버넌스로 관리되는 고정 허용 목록 (공개 스테이킹 없음)
+//
+//   가입/탈퇴: 회원 제안 → 회원 투표 (governance.quorum_pct) → 다음 블록부터 적용
+//   피어: 목록 밖 노드는 관찰자(Observer)로만 접속
+//   확정: 블록마다 회원 서명이 consortium.finality_pct 이상이어야 체인에 붙는다
+//
+// 회원 이력은 지우지 않고 탈퇴 높이만 기록 — 과거 블록의 서명 검증이 계속 가능하다
+// ═══════════════════════════════════════════════════════════════
+
+use crate::chain::{trit_hash, CrownyChain, Transaction, TxType};
+use crate::node::{DistributedNode, NodeId, NodeState, Peer};
+use crate::output::{JsonObject, say};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub address: String,
+    pub name: String,
+    /// 산업 분야 — "의료" · "교육" 등 (industry 모듈 카테고리)
+    pub sector: String,
+    pub joined_height: u64,
+    pub removed_height: Option<u64>,
+}
+
+impl Member {
+    pub fn new(address: &str, name: &str, sector: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            name: name.to_string(),
+            sector: sector.to_string(),
+            joined_height: 0,
+            removed_height: None,
+        }
+    }
+
+    /// 해당 높이 블록에 서명할 자격
+    pub fn active_at(&self, height: u64) -> bool {
+        self.joined_height <= height && self.removed_height.is_none_or(|r| height < r)
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let obj = JsonObject::new()
+            .str("address", &self.address)
+            .str("name", &self.name)
+            .str("sector", &self.sector)
+            .int("joined_height", self.joined_height as i64);
+        match self.removed_height {
+            Some(h) => obj.int("removed_height", h as i64),
+            None => obj,
+        }
+    }
+}
+
+impl std::fmt::Display for Member {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.removed_height.is_some() { "○" } else { "●" };
+        write!(f, "{} {} ({}) [{}] #{}~", status, self.name, self.address, self.sector, self.joined_height)?;
+        if let Some(h) = self.removed_height {
+            write!(f, "#{}", h)?;
+        }
+        Ok(())
+    }
+}
+
+/// 피어 접속 역할
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PeerRole {
+    Validator,
+    Observer,
+}
+
+/// 블록 확정 서명 — 체인 트랜잭션과 같은 방식 (서명자 + 블록 해시)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinalitySignature {
+    pub signer: String,
+    pub block_hash: String,
+    pub signature: String,
+}
+
+impl FinalitySignature {
+    pub fn sign(signer: &str, block_hash: &str) -> Self {
+        Self {
+            signer: signer.to_string(),
+            block_hash: block_hash.to_string(),
+            signature: trit_hash(&format!("finality:{}:{}", signer, block_hash)),
+        }
+    }
+
+    pub fn verify(&self) -> bool {
+        self.signature == trit_hash(&format!("finality:{}:{}", self.signer, self.block_hash))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberChange {
+    Add(Member),
+    Remove(String),
+}
+
+impl std::fmt::Display for MemberChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Add(m) => write!(f, "가입 {} ({}, {})", m.name, m.address, m.sector),
+            Self::Remove(a) => write!(f, "탈퇴 {}", a),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MemberProposal {
+    pub id: u64,
+    pub proposer: String,
+    pub change: MemberChange,
+    /// (회원, trit)
+    pub votes: Vec<(String, i8)>,
+    /// O 투표 중 · P 가결 · T 부결
+    pub state: i8,
+    pub applied_height: Option<u64>,
+}
+
+impl std::fmt::Display for MemberProposal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self.state { 1 => "P", -1 => "T", _ => "O" };
+        let yes = self.votes.iter().filter(|(_, t)| *t > 0).count();
+        let no = self.votes.iter().filter(|(_, t)| *t < 0).count();
+        write!(f, "[{}] 회원 제안 #{} by {} — {} (찬 {} / 반 {})", s, self.id, self.proposer, self.change, yes, no)
+    }
+}
+
+/// 필요한 회원 수 — 올림 (pct% 이상)
+pub fn threshold(members: usize, pct: u64) -> usize {
+    (members as u64 * pct).div_ceil(100).max(1) as usize
+}
+
+#[derive(Debug, Clone)]
+pub struct Consortium {
+    pub name: String,
+    pub members: Vec<Member>,
+    pub proposals: Vec<MemberProposal>,
+}
+
+impl Consortium {
+    pub fn new(name: &str, members: Vec<Member>) -> Self {
+        Self { name: name.to_string(), members, proposals: Vec::new() }
+    }
+
+    pub fn is_member(&self, address: &str) -> bool {
+        self.members.iter().any(|m| m.address == address && m.removed_height.is_none())
+    }
+
+    pub fn member(&self, address: &str) -> Option<&Member> {
+        self.members.iter().find(|m| m.address == address && m.removed_height.is_none())
+    }
+
+    /// 현재 회원 (탈퇴 제외)
+    pub fn current(&self) -> Vec<&Member> {
+        self.members.iter().filter(|m| m.removed_height.is_none()).collect()
+    }
+
+    pub fn members_at(&self, height: u64) -> Vec<&Member> {
+        self.members.iter().filter(|m| m.active_at(height)).collect()
+    }
+
+    /// 허용 목록 밖 피어는 관찰자로만
+    pub fn admit(&self, peer: &str) -> PeerRole {
+        if self.is_member(peer) { PeerRole::Validator } else { PeerRole::Observer }
+    }
+
+    pub fn allow_list(&self) -> Vec<String> {
+        self.current().iter().map(|m| m.address.clone()).collect()
+    }
+
+    /// 블록 확정 검증 — 그 높이의 회원 중 pct% 이상이 유효 서명, 유효 서명 수 반환
+    pub fn verify_finality(&self, height: u64, block_hash: &str, sigs: &[FinalitySignature], pct: u64) -> Result<usize, String> {
+        let eligible = self.members_at(height);
+        let mut signers: Vec<&str> = Vec::new();
+        for sig in sigs {
+            if sig.block_hash != block_hash || !sig.verify() {
+                return Err(format!("#{} 잘못된 서명: {}", height, sig.signer));
+            }
+            if !eligible.iter().any(|m| m.address == sig.signer) {
+                return Err(format!("#{} 회원 아닌 서명자: {}", height, sig.signer));
+            }
+            if !signers.contains(&sig.signer.as_str()) {
+                signers.push(&sig.signer);
+            }
+        }
+        let need = threshold(eligible.len(), pct);
+        if signers.len() < need {
+            return Err(format!("#{} 서명 부족: {}/{} (필요 {})", height, signers.len(), eligible.len(), need));
+        }
+        Ok(signers.len())
+    }
+
+    // ── 회원 거버넌스 ──
+
+    pub fn propose(&mut self, proposer: &str, change: MemberChange) -> Result<u64, String> {
+        if !self.is_member(proposer) {
+            return Err(format!("{} 회원 아님", proposer));
+        }
+        match &change {
+            MemberChange::Add(m) if self.is_member(&m.address) => return Err(format!("{} 이미 회원", m.address)),
+            MemberChange::Remove(a) if !self.is_member(a) => return Err(format!("{} 회원 아님", a)),
+            MemberChange::Remove(_) if self.current().len() <= 1 => return Err("마지막 회원은 탈퇴 불가".into()),
+            _ => {}
+        }
+        let id = self.proposals.len() as u64 + 1;
+        self.proposals.push(MemberProposal {
+            id,
+            proposer: proposer.to_string(),
+            change,
+            votes: Vec::new(),
+            state: 0,
+            applied_height: None,
+        });
+        Ok(id)
+    }
+
+    /// 회원 1인 1표 — 찬성이 pct%에 닿으면 P, 반대로 더 이상 닿을 수 없으면 T
+    pub fn vote(&mut self, id: u64, voter: &str, trit: i8, pct: u64) -> Result<i8, String> {
+        if !self.is_member(voter) {
+            return Err(format!("{} 회원 아님", voter));
+        }
+        let total = self.current().len();
+        let need = threshold(total, pct);
+        let p = self.proposals.iter_mut().find(|p| p.id == id).ok_or_else(|| format!("제안 없음: #{}", id))?;
+        if p.state != 0 {
+            return Err(format!("제안 #{} 투표 종료", id));
+        }
+        if p.votes.iter().any(|(v, _)| v == voter) {
+            return Err(format!("{} 이미 투표", voter));
+        }
+        p.votes.push((voter.to_string(), trit.signum()));
+        let yes = p.votes.iter().filter(|(_, t)| *t > 0).count();
+        let no = p.votes.iter().filter(|(_, t)| *t < 0).count();
+        if yes >= need {
+            p.state = 1;
+        } else if total - no < need {
+            p.state = -1;
+        }
+        Ok(p.state)
+    }
+
+    /// 가결됐지만 아직 적용 안 된 변경을 해당 높이부터 적용 — 적용된 변경 반환
+    pub fn apply_passed(&mut self, height: u64) -> Vec<MemberChange> {
+        let mut applied = Vec::new();
+        for i in 0..self.proposals.len() {
+            if self.proposals[i].state != 1 || self.proposals[i].applied_height.is_some() {
+                continue;
+            }
+            self.proposals[i].applied_height = Some(height);
+            let change = self.proposals[i].change.clone();
+            match &change {
+                MemberChange::Add(m) => {
+                    let mut m = m.clone();
+                    m.joined_height = height;
+                    m.removed_height = None;
+                    self.members.push(m);
+                }
+                MemberChange::Remove(addr) => {
+                    if let Some(m) = self.members.iter_mut().find(|m| &m.address == addr && m.removed_height.is_none()) {
+                        m.removed_height = Some(height);
+                    }
+                }
+            }
+            applied.push(change);
+        }
+        applied
+    }
+
+    /// 분야별 현재 회원 수
+    pub fn sectors(&self) -> Vec<(String, usize)> {
+        let mut out: Vec<(String, usize)> = Vec::new();
+        for m in self.current() {
+            match out.iter_mut().find(|(s, _)| *s == m.sector) {
+                Some((_, n)) => *n += 1,
+                None => out.push((m.sector.clone(), 1)),
+            }
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("name", &self.name)
+            .objects("members", self.members.iter().map(|m| m.to_json()).collect())
+            .int("proposals", self.proposals.len() as i64)
+    }
+}
+
+// ═══ 데모 ═══
+
+/// 의료 · 교육 파일럿 — 최종 체인 무결성을 Trit으로 반환
+pub fn demo_consortium() -> i8 {
+    crate::output::banner("╔═══════════════════════════════════════════════╗\n\
+                           ║  Crowny Consortium — 허가형 컨소시엄 체인        ║\n\
+                           ║  허용 목록 밸리데이터 · 회원 서명 확정            ║\n\
+                           ╚═══════════════════════════════════════════════╝\n");
+
+    let mut chain = CrownyChain::consortium("med-edu-pilot", vec![
+        Member::new("snuh", "서울대병원", "의료"),
+        Member::new("amc", "아산병원", "의료"),
+        Member::new("kaist", "KAIST", "교육"),
+    ]);
+
+    say!("━━━ 1. 회원 (허용 목록) ━━━");
+    if let Some(c) = &chain.consortium {
+        for m in &c.members {
+            say!("  {}", m);
+        }
+        let sectors: Vec<String> = c.sectors().iter().map(|(s, n)| format!("{} {}", s, n)).collect();
+        say!("  분야: {}", sectors.join(" · "));
+        say!("  공개 스테이킹: {}", if chain.add_validator("stranger", "Stranger", 0) { "허용" } else { "차단" });
+    }
+    say!();
+
+    say!("━━━ 2. 피어 접속 ━━━");
+    // snuh 노드 — 허용 목록 밖 피어는 관찰자로 선거 · 정족수에서 빠진다
+    let peer_ids = ["amc", "kaist", "public-node-7"];
+    let mut node = DistributedNode::new(NodeId::new("snuh", "kr", 0));
+    if let Some(c) = &chain.consortium {
+        node = node.with_allow_list(c.allow_list());
+        for peer in peer_ids {
+            node.add_peer(Peer::new(NodeId::new(peer, "kr", 0), "127.0.0.1", 7293));
+            let role = match c.admit(peer) { PeerRole::Validator => "Validator", PeerRole::Observer => "Observer" };
+            say!("  {:<14} → {}", peer, role);
+        }
+        say!("  snuh 노드 정족수: {} (투표 피어 {})", node.quorum_size(), node.voting_peers().len());
+    }
+    say!();
+
+    say!("━━━ 3. 블록 확정 (회원 서명) ━━━");
+    let records = [
+        ("snuh", "kaist", "의료 AI 판단 기록 공유"),
+        ("kaist", "snuh", "교육 과정 수료 증명"),
+        ("amc", "snuh", "진료 의뢰"),
+    ];
+    for (from, to, memo) in records {
+        chain.submit_tx(Transaction::new(from, to, 0, 0, TxType::Attestation, memo));
+        match chain.produce_block() {
+            Some(b) => say!("  [P] #{} by {} — 서명 {}/{}", b.index, b.validator, b.signatures.len(), chain.validators.len()),
+            None => say!("  [O] 확정 실패"),
+        }
+    }
+    // 회원 하나가 내려가면 정족수 미달
+    chain.validators[1].active = false;
+    chain.submit_tx(Transaction::new("snuh", "kaist", 0, 0, TxType::Attestation, "야간 배치"));
+    let pct = chain.params.borrow().get(crate::params::CONSORTIUM_FINALITY_PCT);
+    match chain.produce_block() {
+        Some(b) => say!("  [P] #{} 확정", b.index),
+        None => say!("  [O] 아산병원 오프라인 — 서명 2/3 < {}%, 대기 TX {}", pct, chain.tx_pool.size()),
+    }
+    chain.validators[1].active = true;
+    if let Some(b) = chain.produce_block() {
+        say!("  [P] #{} 복구 후 확정 — 서명 {}", b.index, b.signatures.len());
+    }
+    say!();
+
+    say!("━━━ 4. 회원 거버넌스 ━━━");
+    match chain.propose_member("kaist", MemberChange::Add(Member::new("ebs", "EBS", "교육"))) {
+        Ok(id) => {
+            for voter in ["kaist", "snuh"] {
+                let _ = chain.vote_member(id, voter, 1);
+            }
+            if let Some(p) = chain.consortium.as_ref().and_then(|c| c.proposals.iter().find(|p| p.id == id)) {
+                say!("  {}", p);
+            }
+        }
+        Err(e) => say!("  [T] {}", e),
+    }
+    for memo in ["가입 반영 블록", "EBS 첫 서명 블록"] {
+        chain.submit_tx(Transaction::new("kaist", "ebs", 0, 0, TxType::Attestation, memo));
+        if let Some(b) = chain.produce_block() {
+            say!("  [P] #{} {} — 서명 {}", b.index, memo, b.signatures.len());
+        }
+    }
+    match chain.propose_member("snuh", MemberChange::Remove("amc".into())) {
+        Ok(id) => {
+            for voter in ["snuh", "kaist"] {
+                let _ = chain.vote_member(id, voter, 1);
+            }
+            if let Some(p) = chain.consortium.as_ref().and_then(|c| c.proposals.iter().find(|p| p.id == id)) {
+                say!("  {}", p);
+            }
+        }
+        Err(e) => say!("  [T] {}", e),
+    }
+    for memo in ["탈퇴 반영 블록", "아산병원 없는 첫 블록"] {
+        chain.submit_tx(Transaction::new("snuh", "kaist", 0, 0, TxType::Attestation, memo));
+        if let Some(b) = chain.produce_block() {
+            say!("  [P] #{} {} — 서명 {}", b.index, memo, b.signatures.len());
+        }
+    }
+    if let Some(c) = &chain.consortium {
+        // 허용 목록이 바뀌면 노드에도 다시 적용 — 탈퇴 회원은 관찰자로
+        node = node.with_allow_list(c.allow_list());
+        let amc = if node.peers.get("amc").is_some_and(|p| p.state == NodeState::Observer) { "Observer" } else { "Validator" };
+        say!("  허용 목록: {} | amc 회원: {} → 노드에서 {}", c.allow_list().join(", "),
+            if c.member("amc").is_some() { "예" } else { "아니오" }, amc);
+    }
+    say!();
+
+    say!("━━━ 5. 검증 ━━━");
+    let (valid, count) = chain.verify_chain();
+    say!("  {} ({} 블록, 확정 서명 포함)", if valid { "✓ 유효" } else { "✗ 무효" }, count);
+    say!();
+
+    let state = if valid { 1 } else { -1 };
+    if crate::output::is_json() {
+        let consortium = chain.consortium.as_ref().map(|c| c.to_json()).unwrap_or_default();
+        JsonObject::new()
+            .str("command", "chain consortium")
+            .trit("state", state)
+            .int("height", chain.height() as i64)
+            .object("consortium", consortium)
+            .emit();
+    }
+    state
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pilot() -> Consortium {
+        Consortium::new("pilot", vec![
+            Member::new("snuh", "서울대병원", "의료"),
+            Member::new("amc", "아산병원", "의료"),
+            Member::new("kaist", "KAIST", "교육"),
+        ])
+    }
+
+    #[test]
+    fn test_threshold_rounds_up() {
+        assert_eq!(threshold(3, 67), 3);
+        assert_eq!(threshold(3, 66), 2);
+        assert_eq!(threshold(4, 50), 2);
+        assert_eq!(threshold(0, 67), 1);
+    }
+
+    #[test]
+    fn test_admission_and_finality() {
+        let c = pilot();
+        assert_eq!(c.admit("kaist"), PeerRole::Validator);
+        assert_eq!(c.admit("stranger"), PeerRole::Observer);
+
+        let sigs = vec![FinalitySignature::sign("snuh", "h1"), FinalitySignature::sign("amc", "h1")];
+        assert_eq!(c.verify_finality(1, "h1", &sigs, 66), Ok(2));
+        assert!(c.verify_finality(1, "h1", &sigs, 67).is_err());
+        assert!(c.verify_finality(1, "h1", &[FinalitySignature::sign("stranger", "h1")], 1).is_err());
+        let mut forged = FinalitySignature::sign("kaist", "h1");
+        forged.block_hash = "h2".into();
+        assert!(c.verify_finality(1, "h2", &[forged], 1).is_err());
+    }
+
+    #[test]
+    fn test_member_governance_keeps_history() {
+        let mut c = pilot();
+        assert!(c.propose("stranger", MemberChange::Remove("amc".into())).is_err());
+        let id = c.propose("snuh", MemberChange::Add(Member::new("ebs", "EBS", "교육"))).unwrap();
+        assert_eq!(c.vote(id, "snuh", 1, 50), Ok(0));
+        assert!(c.vote(id, "stranger", 1, 50).is_err());
+        assert_eq!(c.vote(id, "kaist", 1, 50), Ok(1));
+        assert_eq!(c.apply_passed(5).len(), 1);
+        assert!(c.is_member("ebs"));
+        assert_eq!(c.member("ebs").unwrap().joined_height, 5);
+
+        let id = c.propose("kaist", MemberChange::Remove("amc".into())).unwrap();
+        c.vote(id, "amc", -1, 67).unwrap();
+        c.vote(id, "snuh", -1, 67).unwrap();
+        // 4명 중 67% = 3명 — 반대 2표면 닿을 수 없음
+        assert_eq!(c.proposals[1].state, -1);
+
+        let id = c.propose("kaist", MemberChange::Remove("amc".into())).unwrap();
+        for v in ["kaist", "snuh"] {
+            c.vote(id, v, 1, 50).unwrap();
+        }
+        c.apply_passed(9);
+        assert!(!c.is_member("amc"));
+        // 탈퇴 전 블록의 서명은 계속 유효
+        assert!(c.members_at(8).iter().any(|m| m.address == "amc"));
+        assert!(!c.members_at(9).iter().any(|m| m.address == "amc"));
+        assert!(c.verify_finality(4, "h", &[FinalitySignature::sign("ebs", "h")], 1).is_err());
+    }
+}
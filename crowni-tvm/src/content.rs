@@ -0,0 +1,312 @@
+// ═══════════════════════════════════════════════════════════════
+// 콘텐츠 저장소 — NFT 메타데이터 · 작은 자산을 내용 주소로 보관
+//
+//   주소: crwn://content/<sha256 hex>  (웹: GET /content/<hash>)
+//   저장: TritStore "content.<hash>" → {bytes, mime, pins, created_at}
+//         트릿 색인 P = 고정됨 · O = 고정 없음(gc 대상)
+//   고정(pin): 소유자별 — 모든 소유자가 풀어야 gc()가 회수한다
+//   조회: 꺼낼 때마다 해시를 다시 계산해 손상/변조를 거부
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::crypto;
+use crate::output::JsonObject;
+use crate::trit_store::{StoreValue, TritStore};
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 콘텐츠 하나의 최대 크기 (작은 자산 전용)
+pub const MAX_CONTENT_BYTES: usize = 1 << 20;
+pub const URI_PREFIX: &str = "crwn://content/";
+const KEY_PREFIX: &str = "content.";
+
+/// 내용 해시 (sha256 hex)
+pub fn content_id(bytes: &[u8]) -> String {
+    crypto::to_hex(&crypto::sha256(bytes))
+}
+
+pub fn uri(hash: &str) -> String {
+    format!("{}{}", URI_PREFIX, hash)
+}
+
+/// crwn://content/<hash> → hash (형식이 맞을 때만)
+pub fn parse_uri(uri: &str) -> Option<&str> {
+    uri.strip_prefix(URI_PREFIX).filter(|h| is_hash(h))
+}
+
+pub fn is_hash(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentError {
+    NotFound(String),
+    /// 저장된 바이트의 해시가 주소와 다름
+    Corrupted { expected: String, actual: String },
+    TooLarge(usize),
+    BadHash(String),
+}
+
+impl ContentError {
+    /// HTTP 상태
+    pub fn status(&self) -> u16 {
+        match self {
+            ContentError::NotFound(_) => 404,
+            ContentError::Corrupted { .. } => 500,
+            ContentError::TooLarge(_) => 413,
+            ContentError::BadHash(_) => 400,
+        }
+    }
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentError::NotFound(h) => write!(f, "콘텐츠 없음: {}", h),
+            ContentError::Corrupted { expected, actual } => write!(f, "해시 불일치: {} ≠ {}", expected, actual),
+            ContentError::TooLarge(n) => write!(f, "콘텐츠가 너무 큼: {}B (최대 {}B)", n, MAX_CONTENT_BYTES),
+            ContentError::BadHash(h) => write!(f, "잘못된 콘텐츠 해시: {}", h),
+        }
+    }
+}
+
+/// 검증된 콘텐츠
+#[derive(Debug, Clone, PartialEq)]
+pub struct Content {
+    pub hash: String,
+    pub mime: String,
+    pub bytes: Vec<u8>,
+    pub pins: Vec<String>,
+    pub created_at: u64,
+}
+
+impl Content {
+    pub fn uri(&self) -> String {
+        uri(&self.hash)
+    }
+
+    fn to_store(&self) -> StoreValue {
+        let mut m = HashMap::new();
+        m.insert("bytes".to_string(), StoreValue::Bytes(self.bytes.clone()));
+        m.insert("mime".to_string(), StoreValue::Text(self.mime.clone()));
+        m.insert("pins".to_string(), StoreValue::List(self.pins.iter().map(|p| StoreValue::Text(p.clone())).collect()));
+        m.insert("created_at".to_string(), StoreValue::Int(self.created_at as i64));
+        StoreValue::Map(m)
+    }
+
+    fn from_store(hash: &str, value: &StoreValue) -> Option<Self> {
+        let StoreValue::Map(m) = value else { return None };
+        let StoreValue::Bytes(bytes) = m.get("bytes")? else { return None };
+        let StoreValue::Text(mime) = m.get("mime")? else { return None };
+        let pins = match m.get("pins") {
+            Some(StoreValue::List(l)) => l.iter().filter_map(|p| match p { StoreValue::Text(t) => Some(t.clone()), _ => None }).collect(),
+            _ => Vec::new(),
+        };
+        let created_at = match m.get("created_at") { Some(StoreValue::Int(t)) => *t as u64, _ => 0 };
+        Some(Self { hash: hash.to_string(), mime: mime.clone(), bytes: bytes.clone(), pins, created_at })
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("hash", &self.hash)
+            .str("uri", &self.uri())
+            .str("mime", &self.mime)
+            .int("size", self.bytes.len() as i64)
+            .strs("pins", &self.pins)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ContentGc {
+    pub removed: usize,
+    pub bytes_freed: usize,
+}
+
+pub type SharedContent = Rc<RefCell<ContentStore>>;
+
+/// 내용 주소 저장소 (TritStore 위)
+pub struct ContentStore {
+    pub store: TritStore,
+}
+
+impl Default for ContentStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContentStore {
+    pub fn new() -> Self {
+        Self::with_store(TritStore::new())
+    }
+
+    /// 기존 저장소 위에 (WAL · 스냅샷 공유)
+    pub fn with_store(store: TritStore) -> Self {
+        Self { store }
+    }
+
+    pub fn shared(self) -> SharedContent {
+        Rc::new(RefCell::new(self))
+    }
+
+    fn key(hash: &str) -> String {
+        format!("{}{}", KEY_PREFIX, hash)
+    }
+
+    fn load(&self, hash: &str) -> Result<Content, ContentError> {
+        if !is_hash(hash) {
+            return Err(ContentError::BadHash(hash.to_string()));
+        }
+        self.store.peek(&Self::key(hash))
+            .and_then(|v| Content::from_store(hash, v))
+            .ok_or_else(|| ContentError::NotFound(hash.to_string()))
+    }
+
+    fn save(&mut self, content: &Content) {
+        let key = Self::key(&content.hash);
+        self.store.set(&key, content.to_store());
+        self.store.set_trit_state(&key, if content.pins.is_empty() { 0 } else { 1 });
+    }
+
+    /// 저장 → 해시 (같은 내용은 한 번만)
+    pub fn put(&mut self, bytes: &[u8], mime: &str) -> Result<String, ContentError> {
+        if bytes.len() > MAX_CONTENT_BYTES {
+            return Err(ContentError::TooLarge(bytes.len()));
+        }
+        let hash = content_id(bytes);
+        if self.load(&hash).is_err() {
+            self.save(&Content { hash: hash.clone(), mime: mime.to_string(), bytes: bytes.to_vec(), pins: Vec::new(), created_at: now_ms() });
+        }
+        Ok(hash)
+    }
+
+    /// 저장 + 고정
+    pub fn put_pinned(&mut self, bytes: &[u8], mime: &str, owner: &str) -> Result<String, ContentError> {
+        let hash = self.put(bytes, mime)?;
+        self.pin(&hash, owner)?;
+        Ok(hash)
+    }
+
+    /// 조회 — 해시 재계산으로 검증
+    pub fn get(&self, hash: &str) -> Result<Content, ContentError> {
+        let content = self.load(hash)?;
+        let actual = content_id(&content.bytes);
+        if !crypto::ct_eq(actual.as_bytes(), hash.as_bytes()) {
+            return Err(ContentError::Corrupted { expected: hash.to_string(), actual });
+        }
+        Ok(content)
+    }
+
+    /// 소유자별 고정 — 이미 고정했으면 그대로
+    pub fn pin(&mut self, hash: &str, owner: &str) -> Result<(), ContentError> {
+        let mut content = self.load(hash)?;
+        if !content.pins.iter().any(|p| p == owner) {
+            content.pins.push(owner.to_string());
+            self.save(&content);
+        }
+        Ok(())
+    }
+
+    /// 고정 해제 → 남은 고정 수
+    pub fn unpin(&mut self, hash: &str, owner: &str) -> Result<usize, ContentError> {
+        let mut content = self.load(hash)?;
+        let before = content.pins.len();
+        content.pins.retain(|p| p != owner);
+        if content.pins.len() != before {
+            self.save(&content);
+        }
+        Ok(content.pins.len())
+    }
+
+    /// 고정 없는 콘텐츠 회수
+    pub fn gc(&mut self) -> ContentGc {
+        let mut report = ContentGc::default();
+        for key in self.store.filter_by_trit(0).into_iter().cloned().collect::<Vec<_>>() {
+            let Some(hash) = key.strip_prefix(KEY_PREFIX) else { continue };
+            if let Ok(content) = self.load(hash) {
+                report.removed += 1;
+                report.bytes_freed += content.bytes.len();
+                self.store.delete(&key);
+            }
+        }
+        report
+    }
+
+    /// 해시 순 목록 (바이트 검증 없음)
+    pub fn list(&self) -> Vec<Content> {
+        let mut all: Vec<Content> = self.store.keys().into_iter()
+            .filter_map(|k| k.strip_prefix(KEY_PREFIX))
+            .filter_map(|h| self.load(h).ok())
+            .collect();
+        all.sort_by(|a, b| a.hash.cmp(&b.hash));
+        all
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_get_dedup_and_uri() {
+        let mut cs = ContentStore::new();
+        let a = cs.put(b"\x89PNG art", "image/png").unwrap();
+        assert_eq!(a, content_id(b"\x89PNG art"));
+        assert_eq!(cs.put(b"\x89PNG art", "image/png").unwrap(), a);
+        assert_eq!(cs.list().len(), 1);
+
+        let got = cs.get(parse_uri(&uri(&a)).unwrap()).unwrap();
+        assert_eq!((got.bytes.as_slice(), got.mime.as_str()), (&b"\x89PNG art"[..], "image/png"));
+        assert_eq!(parse_uri("crwn://art/x.png"), None);
+        assert_eq!(cs.get("zz"), Err(ContentError::BadHash("zz".into())));
+        assert_eq!(cs.get(&content_id(b"other")).unwrap_err().status(), 404);
+        assert_eq!(cs.put(&vec![0; MAX_CONTENT_BYTES + 1], "x").unwrap_err().status(), 413);
+    }
+
+    #[test]
+    fn test_fetch_rejects_tampered_bytes() {
+        let mut cs = ContentStore::new();
+        let hash = cs.put(r#"{"name":"용"}"#.as_bytes(), "application/json").unwrap();
+        // 저장소를 직접 고쳐 변조
+        let mut bad = cs.get(&hash).unwrap();
+        bad.bytes = r#"{"name":"뱀"}"#.as_bytes().to_vec();
+        cs.store.set(&format!("content.{}", hash), bad.to_store());
+        match cs.get(&hash) {
+            Err(ContentError::Corrupted { expected, actual }) => {
+                assert_eq!(expected, hash);
+                assert_eq!(actual, content_id(r#"{"name":"뱀"}"#.as_bytes()));
+            }
+            other => panic!("변조 감지 실패: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pins_keep_content_through_gc() {
+        let mut cs = ContentStore::new();
+        let shared = cs.put_pinned(b"shared", "text/plain", "nft-1").unwrap();
+        cs.pin(&shared, "nft-2").unwrap();
+        cs.pin(&shared, "nft-2").unwrap();
+        let loose = cs.put(b"loose", "text/plain").unwrap();
+        assert_eq!(cs.store.get_trit_state(&format!("content.{}", shared)), Some(1));
+
+        assert_eq!(cs.gc(), ContentGc { removed: 1, bytes_freed: 5 });
+        assert!(cs.get(&shared).is_ok() && cs.get(&loose).is_err());
+        assert_eq!(cs.unpin(&shared, "nft-1").unwrap(), 1);
+        assert_eq!(cs.gc().removed, 0);
+        assert_eq!(cs.unpin(&shared, "nft-2").unwrap(), 0);
+        assert_eq!(cs.gc().removed, 1);
+        assert!(cs.pin(&shared, "nft-3").is_err());
+
+        // WAL로 다시 세운 저장소에서도 그대로
+        let id = cs.put_pinned(b"durable", "text/plain", "me").unwrap();
+        let replayed = ContentStore::with_store(TritStore::replay(&cs.store.wal_entries()));
+        assert_eq!(replayed.get(&id).unwrap().pins, vec!["me"]);
+    }
+}
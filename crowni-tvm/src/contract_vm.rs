@@ -5,6 +5,9 @@
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::artifacts::{ArtifactKind, SharedArtifacts};
+use crate::event_log::LogIndex;
+use crate::params::{SharedParams, GAS_SLOAD, GAS_SSTORE, GAS_TRANSFER};
 
 fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
 fn trit_hash(data: &str) -> String {
@@ -82,13 +85,15 @@ pub struct Contract {
     pub storage: HashMap<String, i64>, pub balance: u64,
     pub call_count: u64, pub total_gas: u64, pub trit_state: i8,
     pub deployed_at: u64,
+    /// 코드 아티팩트 해시 — 같은 코드는 저장소에 한 번만
+    pub code_hash: String,
 }
 impl Contract {
     pub fn new(name: &str, owner: &str, code: Vec<COP>, abi: Vec<ABIFunc>) -> Self {
         Self { address: trit_hash(&format!("c:{}:{}:{}", name, owner, now_ms())),
             owner: owner.into(), name: name.into(), code, abi,
             storage: HashMap::new(), balance: 0,
-            call_count: 0, total_gas: 0, trit_state: 1, deployed_at: now_ms() }
+            call_count: 0, total_gas: 0, trit_state: 1, deployed_at: now_ms(), code_hash: String::new() }
     }
     pub fn find_fn(&self, name: &str) -> Option<&ABIFunc> { self.abi.iter().find(|f| f.name == name) }
 }
@@ -138,17 +143,40 @@ pub struct ContractVM {
     pub balances: HashMap<String, u64>,
     pub block_h: u64, pub deploys: u64, pub total_gas: u64,
     pub events: Vec<(String, CEvent)>,
+    /// 블록별 블룸 색인 로그 (eth_getLogs)
+    pub logs: LogIndex,
+    pub artifacts: SharedArtifacts,
+    pub params: SharedParams,
 }
 
 impl ContractVM {
     pub fn new() -> Self {
-        Self { contracts: HashMap::new(), balances: HashMap::new(), block_h: 3, deploys: 0, total_gas: 0, events: Vec::new() }
+        Self { contracts: HashMap::new(), balances: HashMap::new(), block_h: 3, deploys: 0, total_gas: 0, events: Vec::new(),
+            logs: LogIndex::new(), artifacts: crate::artifacts::shared(), params: crate::params::shared() }
     }
+    pub fn with_artifacts(mut self, artifacts: SharedArtifacts) -> Self { self.artifacts = artifacts; self }
+    pub fn with_params(mut self, params: SharedParams) -> Self { self.params = params; self }
+
+    /// 저장소 · 전송 가스는 거버넌스 파라미터, 나머지는 고정 표
+    pub fn op_gas(&self, op: &COP) -> u64 {
+        let p = self.params.borrow();
+        match op {
+            COP::SLoad(_) => p.get(GAS_SLOAD), COP::SStore(_) => p.get(GAS_SSTORE), COP::Transfer => p.get(GAS_TRANSFER),
+            _ => op.gas_cost(),
+        }
+    }
+    /// 다음 블록 — 이후 이벤트는 새 블록의 로그로 색인
+    pub fn advance_block(&mut self) -> u64 { self.block_h += 1; self.block_h }
     pub fn fund(&mut self, a: &str, v: u64) { *self.balances.entry(a.into()).or_insert(0) += v; }
     pub fn balance(&self, a: &str) -> u64 { self.balances.get(a).copied().unwrap_or(0) }
 
     pub fn deploy(&mut self, name: &str, owner: &str, code: Vec<COP>, abi: Vec<ABIFunc>) -> String {
-        let c = Contract::new(name, owner, code, abi);
+        let mut c = Contract::new(name, owner, code, abi);
+        // 코드 직렬화(Debug 표현)를 아티팩트로 — 같은 코드 재배포는 참조만 증가
+        let code_bytes = format!("{:?}", c.code);
+        c.code_hash = self.artifacts.borrow_mut()
+            .put_ref(ArtifactKind::Contract, code_bytes.as_bytes(), name)
+            .unwrap_or_default();
         let addr = c.address.clone();
         self.contracts.insert(addr.clone(), c);
         self.deploys += 1;
@@ -172,7 +200,7 @@ impl ContractVM {
         loop {
             if pc >= contract.code.len() { break; }
             let op = &contract.code[pc];
-            gas += op.gas_cost();
+            gas += self.op_gas(op);
             if gas > ctx.gas_limit {
                 return ExecResult { success: false, ret: None, gas, events: evts, writes, error: Some("가스 한도 초과".into()), trit: -1 };
             }
@@ -224,7 +252,10 @@ impl ContractVM {
         let cm = self.contracts.get_mut(addr).unwrap();
         cm.storage = stor; cm.call_count += 1; cm.total_gas += gas;
         self.total_gas += gas;
-        for e in &evts { self.events.push((addr.into(), e.clone())); }
+        for e in &evts {
+            self.logs.append(self.block_h, addr, &ctx.caller, e);
+            self.events.push((addr.into(), e.clone()));
+        }
 
         let ret = stack.last().copied();
         let trit = if ret.map(|v|v>0).unwrap_or(false) {1} else if ret.map(|v|v<0).unwrap_or(false) {-1} else {0};
@@ -300,6 +331,25 @@ pub fn escrow_contract() -> (Vec<COP>, Vec<ABIFunc>) {
     (code, abi)
 }
 
+/// 분할 소유 금고 — 지분 회계는 fractional.rs, 컨트랙트는 상태 · 로그 (eth_getLogs로 추적)
+pub fn vault_contract() -> (Vec<COP>, Vec<ABIFunc>) {
+    let code = vec![
+        COP::Dup, COP::SStore("supply".into()), COP::Emit("Locked".into()), COP::Return, // 0: lock
+        COP::Dup, COP::SStore("offer".into()), COP::Emit("BuyoutOffered".into()), COP::Return, // 4: offer
+        COP::TritVote, COP::Emit("ShareVote".into()), COP::Return, // 8: vote
+        COP::Dup, COP::SStore("state".into()), COP::Emit("BuyoutSettled".into()), COP::Return, // 11: settle
+        COP::Emit("Redeemed".into()), COP::Return, // 15: redeem
+    ];
+    let abi = vec![
+        ABIFunc { name:"lock".into(), inputs:vec![("supply".into(),ABIType::Int)], outputs:vec![], mutability:Mutability::NonPayable, entry_pc:0 },
+        ABIFunc { name:"offer".into(), inputs:vec![("price".into(),ABIType::Int)], outputs:vec![], mutability:Mutability::Payable, entry_pc:4 },
+        ABIFunc { name:"vote".into(), inputs:vec![("trit".into(),ABIType::Trit),("weight".into(),ABIType::Int)], outputs:vec![], mutability:Mutability::NonPayable, entry_pc:8 },
+        ABIFunc { name:"settle".into(), inputs:vec![("result".into(),ABIType::Trit),("price".into(),ABIType::Int)], outputs:vec![ABIType::Trit], mutability:Mutability::NonPayable, entry_pc:11 },
+        ABIFunc { name:"redeem".into(), inputs:vec![("shares".into(),ABIType::Int),("paid".into(),ABIType::Int)], outputs:vec![], mutability:Mutability::NonPayable, entry_pc:15 },
+    ];
+    (code, abi)
+}
+
 pub fn consensus_contract() -> (Vec<COP>, Vec<ABIFunc>) {
     let code = vec![
         COP::TritVote, COP::SLoad("count".into()), COP::Push(1), COP::TAdd,
@@ -338,7 +388,8 @@ pub fn demo_contract_vm() {
     println!("  mint: {}", vm.call(&ta, "mint", ctx("alice", vec![1_000_000])));
     println!();
 
-    // 2. Voting
+    // 2. Voting — 컨트랙트마다 새 블록 (로그 블룸이 블록 단위로 건너뛰도록)
+    vm.advance_block();
     println!("━━━ 2. DAO 투표 컨트랙트 ━━━");
     let (code, abi) = voting_contract();
     let va = vm.deploy("CrownyDAO", "alice", code, abi);
@@ -351,6 +402,7 @@ pub fn demo_contract_vm() {
     println!();
 
     // 3. Escrow
+    vm.advance_block();
     println!("━━━ 3. 에스크로 컨트랙트 ━━━");
     let (code, abi) = escrow_contract();
     let ea = vm.deploy("CRWNEscrow", "alice", code, abi);
@@ -363,6 +415,7 @@ pub fn demo_contract_vm() {
     println!();
 
     // 4. Consensus
+    vm.advance_block();
     println!("━━━ 4. 온체인 합의 컨트랙트 ━━━");
     let (code, abi) = consensus_contract();
     let ca = vm.deploy("TritConsensus", "alice", code, abi);
@@ -385,6 +438,10 @@ pub fn demo_contract_vm() {
     for (a, e) in vm.events.iter().rev().take(10) {
         println!("  {}.. — {}", &a.chars().take(12).collect::<String>(), e);
     }
+    let transfers = crate::event_log::LogFilter::new().blocks(0, vm.block_h).topic(0, &["Transfer"]);
+    if let Ok(page) = vm.logs.query(&transfers) {
+        println!("  eth_getLogs(Transfer): {}건 · 블룸으로 {}블록 건너뜀", page.logs.len(), page.blocks_skipped);
+    }
     println!();
 
     // 7. Contracts
@@ -408,6 +465,16 @@ mod tests {
         let addr = vm.deploy("T","alice",c,a);
         assert!(vm.contracts.contains_key(&addr));
     }
+    #[test] fn test_same_code_stored_once() {
+        let store = crate::artifacts::shared();
+        let mut vm = ContractVM::new().with_artifacts(store.clone());
+        let (c,a) = token_contract(); let t1 = vm.deploy("T1","alice",c,a);
+        let (c,a) = token_contract(); let t2 = vm.deploy("T2","bob",c,a);
+        let hash = vm.contracts[&t1].code_hash.clone();
+        assert_eq!(hash, vm.contracts[&t2].code_hash);
+        assert_eq!(store.borrow().stats().artifacts, 1);
+        assert_eq!(store.borrow().get(&hash).unwrap().refs, 2);
+    }
     #[test] fn test_token_init() {
         let mut vm = ContractVM::new();
         let (c,a) = token_contract(); let addr = vm.deploy("T","alice",c,a);
@@ -472,6 +539,17 @@ mod tests {
         let r = vm.call(&addr, "init", tctx("alice", vec![]));
         assert!(!r.events.is_empty());
     }
+    #[test] fn test_log_query_by_topic() {
+        let mut vm = ContractVM::new();
+        let (c,a) = token_contract(); let addr = vm.deploy("T","alice",c,a);
+        vm.call(&addr, "init", tctx("alice", vec![]));
+        vm.advance_block();
+        vm.call(&addr, "transfer", tctx("bob", vec![]));
+        let f = crate::event_log::LogFilter { addresses: vec![addr.clone()], ..crate::event_log::LogFilter::new().blocks(0, 10) }
+            .topic(0, &["Transfer"]).topic(1, &["bob"]);
+        let logs = vm.logs.query(&f).unwrap().logs;
+        assert_eq!((logs.len(), logs[0].block, vm.block_h), (1, 4, 4));
+    }
     #[test] fn test_gas_tracking() {
         let mut vm = ContractVM::new();
         let (c,a) = token_contract(); let addr = vm.deploy("T","alice",c,a);
@@ -14,17 +14,22 @@
 ///!
 ///! 구조:
 ///!   crowny.toml → 프로젝트 매니페스트
-///!   .crowny/    → 로컬 패키지 캐시
+///!   crowny.lock → 해석된 버전 + trit-hash 체크섬 (재현 설치)
+///!   ~/.crowny/packages/<이름>/<버전>/{crowny.toml, src/lib.cws} → 설치 위치
 ///!   registry/   → 원격 저장소 (시뮬레이션)
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use crate::car::TritState;
+use crate::chain::trit_hash;
+use crate::integrations::http_request;
+use crate::output::{JsonObject, JsonValue};
 
 // ─────────────────────────────────────────────
 // 버전
 // ─────────────────────────────────────────────
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Version {
     pub major: u32,
     pub minor: u32,
@@ -59,6 +64,72 @@ impl std::fmt::Display for Version {
     }
 }
 
+/// 버전 요구
+///   ">=1.2.0"  이상
+///   "^1.2.0"   major 고정 (0.x는 minor, 0.0.x는 patch까지 고정) — 접두사 없는 "1.2.0"도 같다
+///   "~1.2.0"   minor 고정
+///   "=1.2.0"   정확히
+///   "*"        아무거나
+/// 빠진 자리는 0 ("^1.0" = "^1.0.0")
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionReq {
+    Any,
+    AtLeast(Version),
+    Caret(Version),
+    Tilde(Version),
+    Exact(Version),
+}
+
+impl VersionReq {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() || s == "*" {
+            return Some(VersionReq::Any);
+        }
+        let (ctor, rest): (fn(Version) -> Self, &str) = if let Some(r) = s.strip_prefix(">=") {
+            (VersionReq::AtLeast, r)
+        } else if let Some(r) = s.strip_prefix('^') {
+            (VersionReq::Caret, r)
+        } else if let Some(r) = s.strip_prefix('~') {
+            (VersionReq::Tilde, r)
+        } else if let Some(r) = s.strip_prefix('=') {
+            (VersionReq::Exact, r)
+        } else {
+            (VersionReq::Caret, s)
+        };
+        let mut parts = rest.trim().split('.');
+        let mut next = || parts.next().map_or(Some(0), |p| p.parse().ok());
+        let v = Version::new(next()?, next()?, next()?);
+        parts.next().is_none().then_some(ctor(v))
+    }
+
+    pub fn matches(&self, v: &Version) -> bool {
+        match self {
+            VersionReq::Any => true,
+            VersionReq::AtLeast(min) => v >= min,
+            VersionReq::Exact(want) => v == want,
+            VersionReq::Tilde(min) => v >= min && v.major == min.major && v.minor == min.minor,
+            VersionReq::Caret(min) => v >= min && match (min.major, min.minor) {
+                (0, 0) => v == min,
+                (0, minor) => v.major == 0 && v.minor == minor,
+                (major, _) => v.major == major,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionReq::Any => write!(f, "*"),
+            VersionReq::AtLeast(v) => write!(f, ">={}", v),
+            VersionReq::Caret(v) => write!(f, "^{}", v),
+            VersionReq::Tilde(v) => write!(f, "~{}", v),
+            VersionReq::Exact(v) => write!(f, "={}", v),
+        }
+    }
+}
+
 // ─────────────────────────────────────────────
 // 패키지 메타데이터
 // ─────────────────────────────────────────────
@@ -103,6 +174,35 @@ pub enum TritTrust {
     Untrusted,  // T: 비신뢰
 }
 
+impl Category {
+    /// Display 이름 ("코어") 또는 영문 ("core")
+    pub fn parse(s: &str) -> Option<Self> {
+        const ALL: [Category; 10] = [Category::Core, Category::Ai, Category::Web, Category::Data, Category::Crypto,
+            Category::Edu, Category::Medical, Category::Finance, Category::Iot, Category::Util];
+        ALL.into_iter().find(|c| c.to_string() == s || format!("{:?}", c).eq_ignore_ascii_case(s))
+    }
+}
+
+impl TritTrust {
+    pub fn trit(self) -> i8 {
+        match self {
+            TritTrust::Trusted => 1,
+            TritTrust::Review => 0,
+            TritTrust::Untrusted => -1,
+        }
+    }
+
+    /// Display 형식 ("P(신뢰)") 또는 첫 글자 P/O/T
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.chars().next()? {
+            'P' => Some(TritTrust::Trusted),
+            'O' => Some(TritTrust::Review),
+            'T' => Some(TritTrust::Untrusted),
+            _ => None,
+        }
+    }
+}
+
 impl std::fmt::Display for TritTrust {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -199,6 +299,62 @@ impl Manifest {
 // CPM — 패키지 매니저
 // ─────────────────────────────────────────────
 
+/// 요구 하나 — 누가(루트부터의 경로) 무엇을
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub path: Vec<String>,
+    pub raw: String,
+    /// 해석 불가한 요구는 None — 어떤 버전도 받지 않는다
+    pub req: Option<VersionReq>,
+}
+
+impl Requirement {
+    pub fn accepts(&self, v: &Version) -> bool {
+        self.req.as_ref().is_some_and(|r| r.matches(v))
+    }
+}
+
+/// 충돌 — 모든 요구를 만족하는 버전이 없는 패키지
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub package: String,
+    pub requirements: Vec<Requirement>,
+    /// 레지스트리에 있는 버전 (비었으면 패키지 자체가 없음)
+    pub available: Vec<Version>,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.available.is_empty() {
+            write!(f, "{} — 레지스트리에 없음", self.package)?;
+        } else {
+            let vs: Vec<String> = self.available.iter().map(|v| v.to_string()).collect();
+            write!(f, "{} — 버전 충돌 (사용 가능: {})", self.package, vs.join(", "))?;
+        }
+        for r in &self.requirements {
+            let ok = self.available.iter().any(|v| r.accepts(v));
+            write!(f, "\n  {} → {} {}{}", r.path.join(" → "), self.package, r.raw,
+                if r.req.is_none() { " (해석 불가)" } else if ok { "" } else { " (맞는 버전 없음)" })?;
+        }
+        Ok(())
+    }
+}
+
+/// 해석 결과 — P 전부 해석 · O 충돌 제외 부분 해석 · T 충돌 (설치 순서 없음)
+#[derive(Debug, Clone)]
+pub struct Resolution {
+    pub state: TritState,
+    /// 의존성 먼저 오는 설치 순서
+    pub order: Vec<(String, Version)>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Resolution {
+    pub fn version_of(&self, name: &str) -> Option<&Version> {
+        self.order.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+}
+
 /// 설치 결과
 #[derive(Debug)]
 pub struct InstallResult {
@@ -211,23 +367,75 @@ pub struct InstallResult {
 pub struct CrownyPM {
     // 로컬 레지스트리 (시뮬레이션)
     registry: HashMap<String, Vec<Package>>,
+    // 게시된 소스 ("이름@버전" → 소스, 없으면 exports 스텁)
+    sources: HashMap<String, String>,
     // 설치된 패키지
     installed: HashMap<String, Package>,
     // 설치 이력
     history: Vec<(String, Version, TritState)>,
+    // 디스크 설치 루트 (None = 메모리 전용)
+    root: Option<PathBuf>,
+    // 레지스트리 서버 저장소 (open_registry)
+    registry_dir: Option<PathBuf>,
 }
 
 impl CrownyPM {
     pub fn new() -> Self {
         let mut cpm = Self {
             registry: HashMap::new(),
+            sources: HashMap::new(),
             installed: HashMap::new(),
             history: Vec::new(),
+            root: None,
+            registry_dir: None,
         };
         cpm.seed_registry();
         cpm
     }
 
+    /// 디스크에 설치 — 이미 설치된 패키지(레지스트리에 있는 버전)는 다시 읽어온다
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        for (name, version) in installed_on_disk(&root) {
+            let pkg = self.registry.get(&name).and_then(|v| v.iter().find(|p| p.version == version)).cloned();
+            if let Some(pkg) = pkg {
+                self.installed.insert(name, pkg);
+            }
+        }
+        self.root = Some(root);
+        self
+    }
+
+    /// ~/.crowny/packages 에 설치 (HOME 없으면 메모리 전용)
+    pub fn with_default_root(self) -> Self {
+        match default_root() {
+            Some(root) => self.with_root(root),
+            None => self,
+        }
+    }
+
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// 소스와 함께 패키지 게시
+    pub fn publish(&mut self, pkg: Package, source: &str) {
+        self.sources.insert(format!("{}@{}", pkg.name, pkg.version), source.to_string());
+        self.register(pkg);
+    }
+
+    /// 패키지 소스 — 게시된 소스, 없으면 export마다 이름을 넣고 돌아오는 스텁
+    pub fn source_of(&self, pkg: &Package) -> String {
+        if let Some(src) = self.sources.get(&format!("{}@{}", pkg.name, pkg.version)) {
+            return src.clone();
+        }
+        let mut out = format!("; {} v{} — 스텁\n", pkg.name, pkg.version);
+        for export in &pkg.exports {
+            out.push_str(&format!("{}:\n  넣어 \"{}.{}\"\n  돌려줘\n", export, pkg.name, export));
+        }
+        out
+    }
+
     /// 내장 패키지 등록
     fn seed_registry(&mut self) {
         // 코어 패키지들
@@ -368,59 +576,178 @@ impl CrownyPM {
             .and_then(|versions| versions.last())
     }
 
-    /// 패키지 설치
+    /// 패키지 설치 — 의존성 그래프 전체를 버전 요구에 맞춰 해석한 뒤 의존성부터 설치
     pub fn install(&mut self, name: &str) -> InstallResult {
-        let mut result = InstallResult {
-            state: TritState::Pending,
-            installed: Vec::new(),
-            skipped: Vec::new(),
-            failed: Vec::new(),
-        };
-
         // 이미 설치 확인
         if self.installed.contains_key(name) {
-            result.skipped.push(name.to_string());
-            result.state = TritState::Success;
-            return result;
+            return InstallResult { state: TritState::Success, installed: Vec::new(), skipped: vec![name.to_string()], failed: Vec::new() };
         }
+        let resolution = self.resolve(&[Dependency::new(name, "*")], false);
+        self.install_resolved(&resolution)
+    }
 
-        // 레지스트리 조회
-        let pkg = match self.registry.get(name).and_then(|v| v.last()).cloned() {
-            Some(p) => p,
-            None => {
-                result.failed.push(format!("{} — 레지스트리에 없음", name));
-                result.state = TritState::Failed;
-                return result;
+    /// 매니페스트 의존성 설치 — partial이면 충돌한 패키지만 빼고 설치 (O)
+    pub fn install_manifest(&mut self, manifest: &Manifest, partial: bool) -> InstallResult {
+        let resolution = self.resolve_for(&manifest.name, &manifest.dependencies, partial);
+        self.install_resolved(&resolution)
+    }
+
+    /// 의존성 해석 (루트 이름 "root")
+    pub fn resolve(&self, deps: &[Dependency], partial: bool) -> Resolution {
+        self.resolve_for("root", deps, partial)
+    }
+
+    /// 각 패키지에 모든 요구를 만족하는 가장 높은 버전을 고르고, 고른 버전의 의존성으로
+    /// 요구를 다시 모으기를 선택이 바뀌지 않을 때까지 반복한다
+    pub fn resolve_for(&self, root: &str, deps: &[Dependency], partial: bool) -> Resolution {
+        const MAX_ROUNDS: usize = 32;
+        let mut selected: HashMap<String, Version> = HashMap::new();
+        let mut reqs = self.collect_requirements(root, deps, &selected);
+        for _ in 0..MAX_ROUNDS {
+            let next: HashMap<String, Version> = reqs.iter()
+                .filter_map(|(name, rs)| Some((name.clone(), self.best_match(name, rs)?)))
+                .collect();
+            if next == selected {
+                break;
             }
-        };
+            selected = next;
+            reqs = self.collect_requirements(root, deps, &selected);
+        }
+
+        let mut conflicts: Vec<Conflict> = reqs.into_iter()
+            .filter(|(name, _)| !selected.contains_key(name))
+            .map(|(name, requirements)| Conflict {
+                available: self.registry.get(&name).map(|v| v.iter().map(|p| p.version.clone()).collect()).unwrap_or_default(),
+                package: name,
+                requirements,
+            })
+            .collect();
+        conflicts.sort_by(|a, b| a.package.cmp(&b.package));
+
+        if conflicts.is_empty() {
+            Resolution { state: TritState::Success, order: self.install_order(&selected), conflicts }
+        } else if partial {
+            Resolution { state: TritState::Pending, order: self.install_order(&selected), conflicts }
+        } else {
+            Resolution { state: TritState::Failed, order: Vec::new(), conflicts }
+        }
+    }
+
+    /// 선택된 버전 기준으로 루트부터 그래프를 돌며 요구 수집 — 경로는 처음 발견한 것
+    fn collect_requirements(&self, root: &str, deps: &[Dependency], selected: &HashMap<String, Version>) -> HashMap<String, Vec<Requirement>> {
+        let mut reqs: HashMap<String, Vec<Requirement>> = HashMap::new();
+        let mut queue: std::collections::VecDeque<(&Dependency, Vec<String>)> =
+            deps.iter().map(|d| (d, vec![root.to_string()])).collect();
+        let mut visited = std::collections::HashSet::new();
+        while let Some((dep, path)) = queue.pop_front() {
+            reqs.entry(dep.name.clone()).or_default().push(Requirement {
+                path: path.clone(),
+                raw: dep.version_req.clone(),
+                req: VersionReq::parse(&dep.version_req),
+            });
+            let Some(pkg) = selected.get(&dep.name).and_then(|v| self.package(&dep.name, v)) else { continue };
+            if !visited.insert(dep.name.clone()) {
+                continue;
+            }
+            let mut next = path;
+            next.push(format!("{} {}", pkg.name, pkg.version));
+            for d in &pkg.dependencies {
+                queue.push_back((d, next.clone()));
+            }
+        }
+        reqs
+    }
+
+    fn best_match(&self, name: &str, reqs: &[Requirement]) -> Option<Version> {
+        self.registry.get(name)?.iter()
+            .map(|p| &p.version)
+            .filter(|v| reqs.iter().all(|r| r.accepts(v)))
+            .max()
+            .cloned()
+    }
+
+    fn package(&self, name: &str, version: &Version) -> Option<&Package> {
+        self.registry.get(name)?.iter().find(|p| &p.version == version)
+    }
+
+    /// 의존성 먼저 (후위 순회)
+    fn install_order(&self, selected: &HashMap<String, Version>) -> Vec<(String, Version)> {
+        fn visit(cpm: &CrownyPM, name: &str, selected: &HashMap<String, Version>,
+                 seen: &mut std::collections::HashSet<String>, out: &mut Vec<(String, Version)>) {
+            let Some(version) = selected.get(name) else { return };
+            if !seen.insert(name.to_string()) {
+                return;
+            }
+            if let Some(pkg) = cpm.package(name, version) {
+                for d in &pkg.dependencies {
+                    visit(cpm, &d.name, selected, seen, out);
+                }
+            }
+            out.push((name.to_string(), version.clone()));
+        }
+        let mut names: Vec<&String> = selected.keys().collect();
+        names.sort();
+        let (mut seen, mut out) = (std::collections::HashSet::new(), Vec::new());
+        for name in names {
+            visit(self, name, selected, &mut seen, &mut out);
+        }
+        out
+    }
 
-        // Trit 권한 검사
-        if pkg.trust == TritTrust::Untrusted {
-            result.failed.push(format!("{} — 비신뢰(T) 패키지", name));
+    /// 해석 결과대로 설치 — 비신뢰(T) 패키지가 하나라도 있으면 아무것도 설치하지 않는다
+    pub fn install_resolved(&mut self, resolution: &Resolution) -> InstallResult {
+        let mut result = InstallResult {
+            state: resolution.state,
+            installed: Vec::new(),
+            skipped: Vec::new(),
+            failed: resolution.conflicts.iter().map(|c| c.to_string()).collect(),
+        };
+        if resolution.state == TritState::Failed {
+            return result;
+        }
+        let pkgs: Vec<Package> = resolution.order.iter()
+            .filter_map(|(name, v)| self.package(name, v).cloned())
+            .collect();
+        let untrusted: Vec<String> = pkgs.iter()
+            .filter(|p| p.trust == TritTrust::Untrusted)
+            .map(|p| format!("{} — 비신뢰(T) 패키지", p.name))
+            .collect();
+        if !untrusted.is_empty() {
+            result.failed.extend(untrusted);
             result.state = TritState::Failed;
             return result;
         }
 
-        // 의존성 재귀 설치
-        for dep in &pkg.dependencies {
-            if !self.installed.contains_key(&dep.name) {
-                let dep_result = self.install(&dep.name);
-                result.installed.extend(dep_result.installed);
-                result.failed.extend(dep_result.failed);
+        for pkg in pkgs {
+            if self.installed.get(&pkg.name).is_some_and(|p| p.version == pkg.version) {
+                result.skipped.push(pkg.name.clone());
+                continue;
+            }
+            // 디스크 기록 — 실패하면 설치하지 않은 것으로 본다
+            if let Some(root) = &self.root {
+                if let Err(e) = write_package(root, &pkg, &self.source_of(&pkg)) {
+                    result.failed.push(format!("{} — {}", pkg.name, e));
+                    result.state = TritState::Failed;
+                    continue;
+                }
+                // 버전이 바뀌면 이전 버전 디렉터리 정리
+                if let Some(old) = self.installed.get(&pkg.name) {
+                    let _ = std::fs::remove_dir_all(package_dir(root, &old.name, &old.version));
+                }
             }
+            self.history.push((pkg.name.clone(), pkg.version.clone(), TritState::Success));
+            result.installed.push(format!("{} v{}", pkg.name, pkg.version));
+            self.installed.insert(pkg.name.clone(), pkg);
         }
-
-        // 설치
-        self.installed.insert(name.to_string(), pkg.clone());
-        self.history.push((name.to_string(), pkg.version.clone(), TritState::Success));
-        result.installed.push(format!("{} v{}", name, pkg.version));
-        result.state = TritState::Success;
         result
     }
 
     /// 패키지 제거
     pub fn uninstall(&mut self, name: &str) -> TritState {
-        if self.installed.remove(name).is_some() {
+        if let Some(pkg) = self.installed.remove(name) {
+            if let Some(root) = &self.root {
+                let _ = std::fs::remove_dir_all(package_dir(root, &pkg.name, &pkg.version));
+            }
             self.history.push((name.to_string(), Version::new(0,0,0), TritState::Failed));
             TritState::Success
         } else {
@@ -451,8 +778,122 @@ impl CrownyPM {
     }
 
     /// import 해석: "crowny.ai" → 해당 패키지 exports 반환
+    /// 디스크 설치면 설치된 crowny.toml에서 읽는다 (다른 프로세스가 설치한 것도 보임)
     pub fn resolve_import(&self, import_path: &str) -> Option<Vec<String>> {
-        self.installed.get(import_path).map(|p| p.exports.clone())
+        match &self.root {
+            Some(root) => load_installed(root, import_path).map(|(exports, _)| exports),
+            None => self.installed.get(import_path).map(|p| p.exports.clone()),
+        }
+    }
+
+    /// import 대상의 소스
+    pub fn import_source(&self, import_path: &str) -> Option<String> {
+        match &self.root {
+            Some(root) => load_installed(root, import_path).map(|(_, src)| src),
+            None => self.installed.get(import_path).map(|p| self.source_of(p)),
+        }
+    }
+
+    /// import 문을 풀어 패키지 소스를 프로그램 뒤에 붙인다 → 어셈블러에 바로 넣을 수 있는 소스
+    pub fn link(&self, source: &str) -> Result<String, String> {
+        let mut main = String::new();
+        let mut libs: Vec<(String, String)> = Vec::new();
+        for line in source.lines() {
+            let Some((pkg, items)) = parse_import(line) else {
+                main.push_str(line);
+                main.push('\n');
+                continue;
+            };
+            // 가져와 줄은 빈 줄로 — 진단의 줄 번호가 원본과 맞도록
+            main.push('\n');
+            let exports = self.resolve_import(&pkg).ok_or_else(|| format!("설치되지 않은 패키지: {}", pkg))?;
+            if let Some(missing) = items.iter().find(|i| *i != "*" && !exports.contains(i)) {
+                return Err(format!("{}에 '{}' 없음 (exports: {})", pkg, missing, exports.join(", ")));
+            }
+            if !libs.iter().any(|(name, _)| *name == pkg) {
+                let src = self.import_source(&pkg).ok_or_else(|| format!("{} 소스 없음", pkg))?;
+                libs.push((pkg, src));
+            }
+        }
+        if libs.is_empty() {
+            return Ok(main);
+        }
+        // 본문이 종료 없이 끝나도 라이브러리로 흘러들지 않게
+        main.push_str("종료\n");
+        for (_, src) in libs {
+            main.push_str(&src);
+            if !src.ends_with('\n') {
+                main.push('\n');
+            }
+        }
+        Ok(main)
+    }
+
+    /// 설치 상태의 잠금 파일
+    pub fn lockfile(&self) -> Lockfile {
+        let mut packages: Vec<LockedPackage> = self.installed.values()
+            .map(|p| LockedPackage {
+                name: p.name.clone(),
+                version: p.version.clone(),
+                checksum: trit_hash(&self.source_of(p)),
+                dependencies: p.dependencies.iter().map(|d| d.name.clone()).collect(),
+            })
+            .collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        Lockfile { packages }
+    }
+
+    /// 프로젝트 디렉터리에 crowny.lock 기록
+    pub fn write_lock(&self, project_dir: impl AsRef<Path>) -> Result<PathBuf, String> {
+        let path = project_dir.as_ref().join(LOCK_FILE);
+        std::fs::write(&path, self.lockfile().to_toml()).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
+    /// 잠금 파일대로 설치 — 고정된 버전 · 체크섬이 레지스트리와 다르면 그 패키지는 실패
+    pub fn install_locked(&mut self, lock: &Lockfile) -> InstallResult {
+        let mut result = InstallResult { state: TritState::Success, installed: Vec::new(), skipped: Vec::new(), failed: Vec::new() };
+        for locked in &lock.packages {
+            let pkg = self.registry.get(&locked.name).and_then(|v| v.iter().find(|p| p.version == locked.version)).cloned();
+            let Some(pkg) = pkg else {
+                result.failed.push(format!("{} v{} — 레지스트리에 없음", locked.name, locked.version));
+                continue;
+            };
+            let source = self.source_of(&pkg);
+            if trit_hash(&source) != locked.checksum {
+                result.failed.push(format!("{} v{} — 체크섬 불일치", locked.name, locked.version));
+                continue;
+            }
+            if self.installed.get(&pkg.name).is_some_and(|p| p.version == pkg.version) {
+                result.skipped.push(pkg.name.clone());
+                continue;
+            }
+            if let Some(root) = &self.root {
+                if let Err(e) = write_package(root, &pkg, &source) {
+                    result.failed.push(format!("{} — {}", pkg.name, e));
+                    continue;
+                }
+            }
+            result.installed.push(format!("{} v{}", pkg.name, pkg.version));
+            self.history.push((pkg.name.clone(), pkg.version.clone(), TritState::Success));
+            self.installed.insert(pkg.name.clone(), pkg);
+        }
+        if !result.failed.is_empty() {
+            result.state = TritState::Failed;
+        }
+        result
+    }
+
+    /// 디스크 소스가 잠금 파일 체크섬과 맞는지 → 어긋난 패키지 목록
+    pub fn verify_lock(&self, lock: &Lockfile) -> Vec<String> {
+        let Some(root) = &self.root else { return Vec::new() };
+        lock.packages.iter()
+            .filter(|l| {
+                let path = package_dir(root, &l.name, &l.version).join(SOURCE_FILE);
+                !std::fs::read_to_string(path).is_ok_and(|src| trit_hash(&src) == l.checksum)
+            })
+            .map(|l| format!("{} v{}", l.name, l.version))
+            .collect()
     }
 
     /// 레지스트리 통계
@@ -467,6 +908,449 @@ impl CrownyPM {
     }
 }
 
+// ─────────────────────────────────────────────
+// 디스크 레이아웃 · 잠금 파일
+// ─────────────────────────────────────────────
+
+pub const LOCK_FILE: &str = "crowny.lock";
+pub const MANIFEST_FILE: &str = "crowny.toml";
+pub const SOURCE_FILE: &str = "src/lib.cws";
+
+pub fn default_root() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".crowny").join("packages"))
+}
+
+fn package_dir(root: &Path, name: &str, version: &Version) -> PathBuf {
+    root.join(name).join(version.to_string())
+}
+
+/// 설치된 패키지의 crowny.toml — 프로젝트 매니페스트에 exports · checksum을 더한 것
+fn package_toml(pkg: &Package, checksum: &str) -> String {
+    let quoted: Vec<String> = pkg.exports.iter().map(|e| format!("\"{}\"", e)).collect();
+    let mut out = format!("[package]\nname = \"{}\"\nversion = \"{}\"\nauthor = \"{}\"\ndescription = \"{}\"\n",
+        pkg.name, pkg.version, pkg.author, pkg.description);
+    out.push_str(&format!("entry = \"{}\"\ncategory = \"{}\"\ntrust = \"{}\"\nexports = [{}]\nchecksum = \"{}\"\n",
+        SOURCE_FILE, pkg.category, pkg.trust, quoted.join(", "), checksum));
+    if !pkg.dependencies.is_empty() {
+        out.push_str("\n[dependencies]\n");
+        for dep in &pkg.dependencies {
+            out.push_str(&format!("{} = \"{}\"\n", dep.name, dep.version_req));
+        }
+    }
+    out
+}
+
+fn write_package(root: &Path, pkg: &Package, source: &str) -> Result<PathBuf, String> {
+    let dir = package_dir(root, &pkg.name, &pkg.version);
+    let io = |e: std::io::Error| format!("{}: {}", dir.display(), e);
+    std::fs::create_dir_all(dir.join("src")).map_err(io)?;
+    std::fs::write(dir.join(SOURCE_FILE), source).map_err(io)?;
+    std::fs::write(dir.join(MANIFEST_FILE), package_toml(pkg, &trit_hash(source))).map_err(io)?;
+    Ok(dir)
+}
+
+/// 루트 아래 (이름, 버전) 목록
+fn installed_on_disk(root: &Path) -> Vec<(String, Version)> {
+    let Ok(names) = std::fs::read_dir(root) else { return Vec::new() };
+    names.flatten()
+        .filter_map(|e| Some((e.file_name().into_string().ok()?, e.path())))
+        .filter_map(|(name, path)| Some((name, newest_version(&path)?)))
+        .collect()
+}
+
+fn newest_version(pkg_dir: &Path) -> Option<Version> {
+    std::fs::read_dir(pkg_dir).ok()?
+        .flatten()
+        .filter(|e| e.path().join(MANIFEST_FILE).is_file())
+        .filter_map(|e| Version::parse(e.file_name().to_str()?))
+        .max()
+}
+
+/// 디스크에서 (exports, 소스) — 가장 높은 설치 버전
+fn load_installed(root: &Path, name: &str) -> Option<(Vec<String>, String)> {
+    let version = newest_version(&root.join(name))?;
+    let dir = package_dir(root, name, &version);
+    let toml = std::fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+    let exports = toml.lines()
+        .find_map(|l| l.trim().strip_prefix("exports = "))
+        .map(|list| list.split('"').skip(1).step_by(2).map(String::from).collect())
+        .unwrap_or_default();
+    let source = std::fs::read_to_string(dir.join(SOURCE_FILE)).ok()?;
+    Some((exports, source))
+}
+
+/// crowny.lock 한 항목
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: Version,
+    /// 소스의 trit_hash
+    pub checksum: String,
+    pub dependencies: Vec<String>,
+}
+
+/// crowny.lock — 이름순 [[package]] 목록
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lockfile {
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("# crowny.lock — CPM이 생성합니다. 직접 고치지 마세요.\nversion = 1\n");
+        for p in &self.packages {
+            let deps: Vec<String> = p.dependencies.iter().map(|d| format!("\"{}\"", d)).collect();
+            out.push_str(&format!("\n[[package]]\nname = \"{}\"\nversion = \"{}\"\nchecksum = \"{}\"\ndependencies = [{}]\n",
+                p.name, p.version, p.checksum, deps.join(", ")));
+        }
+        out
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut packages = Vec::new();
+        let mut current: Option<HashMap<String, String>> = None;
+        let finish = |fields: HashMap<String, String>, packages: &mut Vec<LockedPackage>| -> Result<(), String> {
+            let get = |k: &str| fields.get(k).cloned().ok_or_else(|| format!("[[package]]에 {} 없음", k));
+            let version = get("version")?;
+            packages.push(LockedPackage {
+                name: get("name")?,
+                version: Version::parse(&version).ok_or_else(|| format!("잘못된 버전: {}", version))?,
+                checksum: get("checksum")?,
+                dependencies: fields.get("dependencies").map(|d| d.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()).unwrap_or_default(),
+            });
+            Ok(())
+        };
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line == "[[package]]" {
+                if let Some(fields) = current.take() {
+                    finish(fields, &mut packages)?;
+                }
+                current = Some(HashMap::new());
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| format!("{}행: key = value 아님", i + 1))?;
+            let value = value.trim().trim_start_matches('[').trim_end_matches(']').replace('"', "");
+            match &mut current {
+                Some(fields) => { fields.insert(key.trim().to_string(), value); }
+                None if key.trim() == "version" && value.trim() != "1" => return Err(format!("지원하지 않는 잠금 파일 버전: {}", value)),
+                None => {}
+            }
+        }
+        if let Some(fields) = current {
+            finish(fields, &mut packages)?;
+        }
+        Ok(Self { packages })
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&text)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 레지스트리 — 서버 저장소 · 전송 형식 · 클라이언트
+// ─────────────────────────────────────────────
+//
+//   GET  /cpm/search?q=          → {"packages":[메타...]}
+//   GET  /cpm/packages/:name     → {"name":..., "versions":[메타...]}
+//   GET  /cpm/download/:name     ?version= (없으면 최신) → {"package":메타, "source":..., "checksum":...}
+//   POST /cpm/publish            cpm.publish 토큰 + 위 download 형식 본문 → 201
+//
+// 체크섬은 소스의 trit_hash — 게시할 때 서버가, 받을 때 클라이언트가 다시 계산해 비교한다.
+// 게시된 버전은 바꿀 수 없다 (같은 이름@버전 재게시는 409).
+
+pub const PUBLISH_SCOPE: &str = "cpm.publish";
+pub const CHECKSUM_HEADER: &str = "X-Crowny-Checksum";
+pub const DEFAULT_REGISTRY: &str = "http://127.0.0.1:7294";
+
+impl Package {
+    /// 설치 형식 crowny.toml → 패키지 (source_size는 호출 측이 채운다)
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let mut fields: HashMap<String, String> = HashMap::new();
+        let mut dependencies = Vec::new();
+        let mut section = String::new();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            if line.starts_with('[') {
+                section = line.trim_matches(|c| c == '[' || c == ']').to_string();
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else { continue };
+            let (key, value) = (key.trim(), value.trim());
+            match section.as_str() {
+                "dependencies" => dependencies.push(Dependency::new(key, value.trim_matches('"'))),
+                _ => { fields.insert(key.to_string(), value.to_string()); }
+            }
+        }
+        let text_field = |k: &str| fields.get(k).map(|v| v.trim_matches('"').to_string());
+        let name = text_field("name").filter(|n| !n.is_empty()).ok_or("[package] name 없음")?;
+        let version = text_field("version").ok_or("[package] version 없음")?;
+        Ok(Package {
+            version: Version::parse(&version).ok_or_else(|| format!("잘못된 버전: {}", version))?,
+            author: text_field("author").unwrap_or_default(),
+            description: text_field("description").unwrap_or_default(),
+            category: text_field("category").and_then(|c| Category::parse(&c)).unwrap_or(Category::Util),
+            trust: text_field("trust").and_then(|t| TritTrust::parse(&t)).unwrap_or(TritTrust::Review),
+            dependencies,
+            exports: fields.get("exports").map(|l| l.split('"').skip(1).step_by(2).map(String::from).collect()).unwrap_or_default(),
+            source_size: 0,
+            tvm_opcodes: vec![0],
+            name,
+        })
+    }
+
+    /// 레지스트리 메타데이터
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("name", &self.name)
+            .str("version", &self.version.to_string())
+            .str("author", &self.author)
+            .str("description", &self.description)
+            .str("category", &format!("{:?}", self.category))
+            .trit("trust", self.trust.trit())
+            .objects("dependencies", self.dependencies.iter()
+                .map(|d| JsonObject::new().str("name", &d.name).str("req", &d.version_req)).collect())
+            .strs("exports", &self.exports)
+            .int("source_size", self.source_size as i64)
+    }
+
+    pub fn from_json(json: &JsonValue) -> Result<Self, String> {
+        let text = |k: &str| json.get(k).and_then(|v| v.as_str()).map(String::from);
+        let list = |k: &str| json.get(k).and_then(|v| v.as_array()).unwrap_or(&[]);
+        let name = text("name").filter(|n| !n.is_empty()).ok_or("name 없음")?;
+        let version = text("version").ok_or("version 없음")?;
+        Ok(Package {
+            version: Version::parse(&version).ok_or_else(|| format!("잘못된 버전: {}", version))?,
+            author: text("author").unwrap_or_default(),
+            description: text("description").unwrap_or_default(),
+            category: text("category").and_then(|c| Category::parse(&c)).unwrap_or(Category::Util),
+            trust: match json.get("trust").and_then(|t| t.as_str()) {
+                Some(t) => TritTrust::parse(t).ok_or_else(|| format!("잘못된 trust: {}", t))?,
+                None => TritTrust::Review,
+            },
+            dependencies: list("dependencies").iter()
+                .map(|d| Some(Dependency::new(d.get("name")?.as_str()?, d.get("req")?.as_str()?)))
+                .collect::<Option<_>>().ok_or("dependencies 형식 오류")?,
+            exports: list("exports").iter().filter_map(|e| e.as_str().map(String::from)).collect(),
+            source_size: json.get("source_size").and_then(|s| s.as_i64()).unwrap_or(0).max(0) as usize,
+            tvm_opcodes: vec![0],
+            name,
+        })
+    }
+}
+
+/// 업로드 · 다운로드 본문
+pub fn package_bundle(pkg: &Package, source: &str) -> String {
+    JsonObject::new()
+        .object("package", pkg.to_json())
+        .str("source", source)
+        .str("checksum", &trit_hash(source))
+        .build()
+}
+
+/// 본문 → (패키지, 소스) — 선언된 체크섬과 소스가 맞지 않으면 거부
+pub fn parse_bundle(body: &str) -> Result<(Package, String), PublishError> {
+    let json = JsonValue::parse(body).map_err(PublishError::Invalid)?;
+    let pkg = Package::from_json(json.get("package").ok_or(PublishError::Invalid("package 없음".into()))?)
+        .map_err(PublishError::Invalid)?;
+    let source = json.get("source").and_then(|s| s.as_str()).ok_or(PublishError::Invalid("source 없음".into()))?;
+    let declared = json.get("checksum").and_then(|s| s.as_str()).unwrap_or_default();
+    let actual = trit_hash(source);
+    if declared != actual {
+        return Err(PublishError::Checksum { declared: declared.to_string(), actual });
+    }
+    Ok((pkg, source.to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PublishError {
+    Invalid(String),
+    Checksum { declared: String, actual: String },
+    /// 이미 게시된 이름@버전
+    Duplicate(String),
+    Io(String),
+}
+
+impl PublishError {
+    pub fn status(&self) -> u16 {
+        match self {
+            PublishError::Invalid(_) => 400,
+            PublishError::Checksum { .. } => 422,
+            PublishError::Duplicate(_) => 409,
+            PublishError::Io(_) => 500,
+        }
+    }
+}
+
+impl std::fmt::Display for PublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublishError::Invalid(e) => write!(f, "잘못된 패키지: {}", e),
+            PublishError::Checksum { declared, actual } => write!(f, "체크섬 불일치: 선언 {} · 실제 {}", declared, actual),
+            PublishError::Duplicate(id) => write!(f, "이미 게시됨: {}", id),
+            PublishError::Io(e) => write!(f, "저장 실패: {}", e),
+        }
+    }
+}
+
+impl CrownyPM {
+    /// 레지스트리 서버 저장소 — dir 아래 게시본을 읽어 등록하고, 이후 게시본도 여기에 기록
+    pub fn open_registry(dir: impl Into<PathBuf>) -> Result<Self, String> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let mut cpm = CrownyPM::new();
+        for name in std::fs::read_dir(&dir).map_err(|e| e.to_string())?.flatten() {
+            for ver in std::fs::read_dir(name.path()).into_iter().flatten().flatten() {
+                let path = ver.path();
+                let (Ok(toml), Ok(source)) = (std::fs::read_to_string(path.join(MANIFEST_FILE)), std::fs::read_to_string(path.join(SOURCE_FILE))) else { continue };
+                let mut pkg = Package::from_toml(&toml).map_err(|e| format!("{}: {}", path.display(), e))?;
+                pkg.source_size = source.len();
+                if cpm.package(&pkg.name, &pkg.version).is_none() {
+                    cpm.publish(pkg, &source);
+                }
+            }
+        }
+        cpm.registry_dir = Some(dir);
+        Ok(cpm)
+    }
+
+    /// 서버 측 게시 — 체크섬 확인은 parse_bundle에서, 여기서는 불변성 · 신뢰 등급
+    /// 게시자가 P(신뢰)를 자칭할 수는 없다 — 외부 게시본은 O(검토)로 시작
+    pub fn publish_verified(&mut self, mut pkg: Package, source: &str) -> Result<String, PublishError> {
+        let id = format!("{}@{}", pkg.name, pkg.version);
+        if self.package(&pkg.name, &pkg.version).is_some() {
+            return Err(PublishError::Duplicate(id));
+        }
+        if pkg.trust == TritTrust::Trusted {
+            pkg.trust = TritTrust::Review;
+        }
+        pkg.source_size = source.len();
+        if let Some(dir) = &self.registry_dir {
+            write_package(dir, &pkg, source).map_err(PublishError::Io)?;
+        }
+        self.publish(pkg, source);
+        Ok(trit_hash(source))
+    }
+
+    /// 이름의 게시 버전 (오름차순)
+    pub fn versions(&self, name: &str) -> Vec<&Package> {
+        let mut out: Vec<&Package> = self.registry.get(name).map(|v| v.iter().collect()).unwrap_or_default();
+        out.sort_by(|a, b| a.version.cmp(&b.version));
+        out
+    }
+
+    /// 다운로드 — 버전 없으면 최신
+    pub fn download(&self, name: &str, version: Option<&Version>) -> Option<(&Package, String)> {
+        let pkg = match version {
+            Some(v) => self.package(name, v)?,
+            None => self.versions(name).pop()?,
+        };
+        Some((pkg, self.source_of(pkg)))
+    }
+
+    /// 원격 레지스트리에서 받아 로컬 레지스트리에 등록 — 로컬에 없는 의존성도 따라 받는다
+    /// → 받은 "이름 v버전" 목록 (설치는 install로)
+    pub fn fetch(&mut self, client: &RegistryClient, name: &str, version: Option<&Version>) -> Result<Vec<String>, String> {
+        let mut fetched = Vec::new();
+        let mut queue = vec![(name.to_string(), version.cloned())];
+        while let Some((name, version)) = queue.pop() {
+            if version.as_ref().is_some_and(|v| self.package(&name, v).is_some()) {
+                continue;
+            }
+            let (pkg, source) = client.fetch(&name, version.as_ref())?;
+            for dep in &pkg.dependencies {
+                if !self.registry.contains_key(&dep.name) {
+                    queue.push((dep.name.clone(), None));
+                }
+            }
+            if self.package(&pkg.name, &pkg.version).is_none() {
+                fetched.push(format!("{} v{}", pkg.name, pkg.version));
+                self.publish(pkg, &source);
+            }
+        }
+        Ok(fetched)
+    }
+}
+
+/// 레지스트리 HTTP 클라이언트
+pub struct RegistryClient {
+    pub base_url: String,
+    token: Option<String>,
+    pub timeout: std::time::Duration,
+}
+
+impl RegistryClient {
+    pub fn new(base_url: &str) -> Self {
+        Self { base_url: base_url.trim_end_matches('/').to_string(), token: None, timeout: std::time::Duration::from_secs(10) }
+    }
+
+    /// 게시에 쓸 capability 토큰 (cpm.publish)
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    fn get(&self, path: &str) -> Result<JsonValue, String> {
+        let reply = http_request("GET", &format!("{}{}", self.base_url, path), &[], "", self.timeout)?;
+        registry_json(reply.status, &reply.body)
+    }
+
+    /// 게시 → 서버가 기록한 체크섬
+    pub fn publish(&self, pkg: &Package, source: &str) -> Result<String, String> {
+        let token = self.token.as_ref().ok_or("게시 토큰 없음 (CROWNY_CPM_TOKEN)")?;
+        let headers = [(crate::capability::TOKEN_HEADER.to_string(), token.clone())];
+        let reply = http_request("POST", &format!("{}/cpm/publish", self.base_url), &headers, &package_bundle(pkg, source), self.timeout)?;
+        let json = registry_json(reply.status, &reply.body)?;
+        let checksum = json.get("checksum").and_then(|c| c.as_str()).ok_or("응답에 checksum 없음")?;
+        if checksum != trit_hash(source) {
+            return Err(format!("서버 체크섬 불일치: {}", checksum));
+        }
+        Ok(checksum.to_string())
+    }
+
+    pub fn search(&self, query: &str) -> Result<Vec<Package>, String> {
+        let json = self.get(&format!("/cpm/search?q={}", encode_component(query)))?;
+        json.get("packages").and_then(|p| p.as_array()).unwrap_or(&[]).iter().map(Package::from_json).collect()
+    }
+
+    /// 다운로드 — 본문 체크섬 · 헤더 체크섬 · 실제 소스 해시가 모두 같아야 한다
+    pub fn fetch(&self, name: &str, version: Option<&Version>) -> Result<(Package, String), String> {
+        let mut path = format!("/cpm/download/{}", encode_component(name));
+        if let Some(v) = version {
+            path.push_str(&format!("?version={}", v));
+        }
+        let reply = http_request("GET", &format!("{}{}", self.base_url, path), &[], "", self.timeout)?;
+        registry_json(reply.status, &reply.body)?;
+        let (pkg, source) = parse_bundle(&reply.body).map_err(|e| format!("{}: {}", name, e))?;
+        if reply.header(CHECKSUM_HEADER).is_some_and(|h| h != trit_hash(&source)) {
+            return Err(format!("{}: {} 헤더와 본문 체크섬 불일치", name, CHECKSUM_HEADER));
+        }
+        Ok((pkg, source))
+    }
+}
+
+/// 2xx면 JSON, 아니면 서버 오류 메시지
+fn registry_json(status: u16, body: &str) -> Result<JsonValue, String> {
+    let json = JsonValue::parse(body).map_err(|e| format!("HTTP {}: {}", status, e));
+    if (200..300).contains(&status) {
+        return json;
+    }
+    let msg = json.ok().and_then(|j| j.get("오류").and_then(|m| m.as_str()).map(String::from));
+    Err(format!("HTTP {}: {}", status, msg.unwrap_or_else(|| body.chars().take(200).collect())))
+}
+
+fn encode_component(s: &str) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
 // ─────────────────────────────────────────────
 // import 구문 파서
 // ─────────────────────────────────────────────
@@ -566,4 +1450,192 @@ mod tests {
         assert!(toml.contains("my-app"));
         assert!(toml.contains("crowny.core"));
     }
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("crowny-cpm-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_install_to_disk_and_lockfile() {
+        let root = temp_root("disk");
+        let mut cpm = CrownyPM::new().with_root(root.join("packages"));
+        assert_eq!(cpm.install("crowny.ai").state, TritState::Success);
+        let dir = root.join("packages/crowny.ai/0.1.0");
+        assert!(dir.join(SOURCE_FILE).is_file());
+        assert!(std::fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap().contains("exports = [\"LlmCall\""));
+
+        // 다른 프로세스 — 디스크에서 설치 상태 · exports 복원
+        let fresh = CrownyPM::new().with_root(root.join("packages"));
+        assert_eq!(fresh.list_installed().len(), 2);
+        assert_eq!(fresh.resolve_import("crowny.ai").unwrap()[0], "LlmCall");
+        assert!(fresh.resolve_import("crowny.web").is_none());
+
+        // 잠금 파일 왕복 + 변조 감지
+        let lock_path = cpm.write_lock(&root).unwrap();
+        let lock = Lockfile::read(&lock_path).unwrap();
+        assert_eq!(lock, cpm.lockfile());
+        assert_eq!(lock.packages.iter().find(|p| p.name == "crowny.ai").unwrap().dependencies, vec!["crowny.core".to_string()]);
+        assert!(cpm.verify_lock(&lock).is_empty());
+        std::fs::write(root.join("packages/crowny.core/0.3.0").join(SOURCE_FILE), "넣어 666\n").unwrap();
+        assert_eq!(cpm.verify_lock(&lock), vec!["crowny.core v0.3.0".to_string()]);
+
+        // 제거하면 디렉터리도 사라진다
+        cpm.uninstall("crowny.ai");
+        assert!(!dir.exists());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_install_locked_pins_versions() {
+        let mut cpm = CrownyPM::new();
+        cpm.install("crowny.ai");
+        let mut lock = Lockfile::parse(&cpm.lockfile().to_toml()).unwrap();
+
+        let mut other = CrownyPM::new();
+        let result = other.install_locked(&lock);
+        assert_eq!((result.state, result.installed.len()), (TritState::Success, 2));
+        assert_eq!(other.lockfile(), cpm.lockfile());
+
+        lock.packages[0].checksum = "T".repeat(8);
+        let result = CrownyPM::new().install_locked(&lock);
+        assert_eq!(result.state, TritState::Failed);
+        assert!(result.failed[0].contains("체크섬"));
+        assert!(Lockfile::parse("version = 2").is_err());
+    }
+
+    #[test]
+    fn test_linked_program_calls_installed_package() {
+        let root = temp_root("link");
+        let mut pkg = CrownyPM::new().info("crowny.core").unwrap().clone();
+        pkg.name = "acme.math".into();
+        pkg.exports = vec!["두배".into()];
+        {
+            let mut cpm = CrownyPM::new().with_root(&root);
+            cpm.publish(pkg, "두배:\n  넣어 2\n  곱해\n  돌려줘\n");
+            assert_eq!(cpm.install("acme.math").state, TritState::Success);
+        }
+
+        let cpm = CrownyPM::new().with_root(&root);
+        let linked = cpm.link("가져와 acme.math { 두배 }\n넣어 21\n호출 두배\n종료").unwrap();
+        let mut vm = crate::vm::TVM::new();
+        vm.load(crate::assembler::assemble(&linked));
+        vm.run().unwrap();
+        assert_eq!(vm.stack.last().and_then(|v| v.as_int()), Some(42));
+
+        // 링크 후에도 오류 위치는 원본 줄 번호
+        let source = "가져와 acme.math\n넣어 1\n보여줘\n보여줘\n종료";
+        let linked = cpm.link(source).unwrap();
+        let mut vm = crate::vm::TVM::new();
+        vm.load(crate::assembler::assemble(&linked));
+        let err = vm.run().unwrap_err();
+        assert_eq!(err.source_line, Some(4));
+        assert!(err.diagnostic("v.hsn", Some(&linked)).contains("v.hsn:4"));
+
+        assert!(cpm.link("가져와 acme.math { 세배 }").unwrap_err().contains("세배"));
+        assert!(cpm.link("가져와 crowny.web").unwrap_err().contains("설치되지 않은"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_version_req_semantics() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        let req = |s: &str| VersionReq::parse(s).unwrap();
+        assert!(req(">=0.3.0").matches(&v("1.0.0")) && !req(">=0.3.0").matches(&v("0.2.9")));
+        assert!(req("^1.2").matches(&v("1.9.0")) && !req("^1.2").matches(&v("2.0.0")) && !req("^1.2").matches(&v("1.1.9")));
+        assert!(req("^0.3.1").matches(&v("0.3.5")) && !req("^0.3.1").matches(&v("0.4.0")));
+        assert!(req("^0.0.3").matches(&v("0.0.3")) && !req("^0.0.3").matches(&v("0.0.4")));
+        assert!(req("~1.2.3").matches(&v("1.2.9")) && !req("~1.2.3").matches(&v("1.3.0")));
+        assert!(req("=2.3.1").matches(&v("2.3.1")) && !req("=2.3.1").matches(&v("2.3.2")));
+        assert_eq!(req("1.2.0"), VersionReq::Caret(v("1.2.0")));
+        assert_eq!(req("*"), VersionReq::Any);
+        assert!(VersionReq::parse(">=1.x").is_none() && VersionReq::parse("1.2.3.4").is_none());
+    }
+
+    fn versioned(cpm: &CrownyPM, name: &str, version: &str, deps: &[(&str, &str)]) -> Package {
+        let mut pkg = cpm.info("crowny.core").unwrap().clone();
+        pkg.name = name.into();
+        pkg.version = Version::parse(version).unwrap();
+        pkg.dependencies = deps.iter().map(|(n, r)| Dependency::new(n, r)).collect();
+        pkg
+    }
+
+    #[test]
+    fn test_resolver_honors_constraints_across_graph() {
+        let mut cpm = CrownyPM::new();
+        for (name, ver, deps) in [
+            ("crowny.core", "0.4.0", vec![]),
+            ("crowny.core", "1.0.0", vec![]),
+            ("acme.x", "1.4.0", vec![]),
+            ("acme.x", "2.0.0", vec![]),
+            ("acme.app", "1.0.0", vec![("acme.x", "^1.0")]),
+            ("acme.app", "2.0.0", vec![("acme.x", "^2.0")]),
+        ] {
+            let pkg = versioned(&cpm, name, ver, &deps);
+            cpm.register(pkg);
+        }
+
+        // 제약 없으면 최신 · crowny.ai의 >=0.3.0도 만족
+        let res = cpm.resolve(&[Dependency::new("crowny.ai", "*")], false);
+        assert_eq!(res.version_of("crowny.core"), Some(&Version::new(1, 0, 0)));
+        // 루트가 ~0.3으로 고정하면 그래프 전체가 0.3.0
+        let res = cpm.resolve(&[Dependency::new("crowny.ai", "*"), Dependency::new("crowny.core", "~0.3")], false);
+        assert_eq!((res.state, res.version_of("crowny.core")), (TritState::Success, Some(&Version::new(0, 3, 0))));
+        // 고른 버전의 의존성만 따른다 — acme.app 1.x → acme.x 1.x
+        let res = cpm.resolve(&[Dependency::new("acme.app", "^1")], false);
+        assert_eq!(res.version_of("acme.x"), Some(&Version::new(1, 4, 0)));
+        // 의존성이 먼저 설치된다
+        let result = cpm.install("crowny.ai");
+        assert_eq!(result.installed, vec!["crowny.core v1.0.0".to_string(), "crowny.ai v0.1.0".to_string()]);
+    }
+
+    #[test]
+    fn test_conflict_chain_and_partial_resolution() {
+        let mut cpm = CrownyPM::new();
+        let legacy = versioned(&cpm, "acme.legacy", "1.0.0", &[("crowny.core", "~0.3.0"), ("crowny.ai", "*")]);
+        let core = versioned(&cpm, "crowny.core", "1.0.0", &[]);
+        cpm.register(legacy);
+        cpm.register(core);
+        let mut manifest = Manifest::new("my-app");
+        manifest.add_dep("acme.legacy", "^1");
+        manifest.add_dep("crowny.core", "^1.0");
+        manifest.add_dep("acme.missing", "*");
+
+        // 엄격 모드 — T, 아무것도 설치하지 않음
+        let result = cpm.install_manifest(&manifest, false);
+        assert_eq!((result.state, cpm.installed.len()), (TritState::Failed, 0));
+        let res = cpm.resolve_for("my-app", &manifest.dependencies, false);
+        assert_eq!(res.conflicts.len(), 2);
+        assert!(res.conflicts[0].to_string().contains("acme.missing — 레지스트리에 없음"));
+        let chain = res.conflicts[1].to_string();
+        assert!(chain.contains("my-app → acme.legacy 1.0.0 → crowny.core ~0.3.0"), "{}", chain);
+        assert!(chain.contains("my-app → crowny.core ^1.0"), "{}", chain);
+        assert!(chain.contains("my-app → acme.legacy 1.0.0 → crowny.ai 0.1.0 → crowny.core >=0.3.0"), "{}", chain);
+
+        // 부분 모드 — O, 충돌 없는 패키지만 설치
+        let result = cpm.install_manifest(&manifest, true);
+        assert_eq!(result.state, TritState::Pending);
+        assert_eq!(result.failed.len(), 2);
+        assert!(cpm.installed.contains_key("acme.legacy") && cpm.installed.contains_key("crowny.ai"));
+        assert!(!cpm.installed.contains_key("crowny.core"));
+    }
+
+    #[test]
+    fn test_package_wire_formats_round_trip() {
+        let cpm = CrownyPM::new();
+        let pkg = cpm.info("crowny.ai").unwrap().clone();
+        let source = cpm.source_of(&pkg);
+
+        let from_toml = Package::from_toml(&package_toml(&pkg, &trit_hash(&source))).unwrap();
+        assert_eq!((from_toml.category, from_toml.trust), (Category::Ai, TritTrust::Trusted));
+        assert_eq!(from_toml.dependencies[0].version_req, ">=0.3.0");
+        assert_eq!(from_toml.exports, pkg.exports);
+
+        let (back, back_src) = parse_bundle(&package_bundle(&pkg, &source)).unwrap();
+        assert_eq!((back.name, back.version, back_src), (pkg.name.clone(), pkg.version.clone(), source.clone()));
+        let tampered = package_bundle(&pkg, &source).replace("crowny.ai.LlmCall", "crowny.ai.Evil");
+        assert!(matches!(parse_bundle(&tampered), Err(PublishError::Checksum { .. })));
+        assert_eq!(PublishError::Duplicate("x@1.0.0".into()).status(), 409);
+    }
 }
@@ -0,0 +1,645 @@
+// ═══════════════════════════════════════════════════════════════
+// 예약 작업 — cron · iCal RRULE · 한국어 일정 → 한선어 프로그램 주기 실행
+//
+//   cron 5필드: 분 시 일 월 요일   (*, a-b, a,b, */n, a-b/n, JAN · MON · 월 이름)
+//     일·요일이 둘 다 지정되면 둘 중 하나만 맞아도 실행 (표준 cron 규칙)
+//   매크로: @hourly @daily @weekly @monthly @yearly
+//   iCal:  RRULE:FREQ=DAILY;BYHOUR=9;BYMINUTE=0 (MINUTELY~YEARLY, BYDAY/BYMONTHDAY/BYMONTH)
+//   한국어: 매일 09:00 · 평일 18:30 · 주말 10:00 · 매주 월 09:00
+//           매월 1일 00:00 · 매시 30분 · 15분마다 · 2시간마다
+//
+//   시각은 지역 시간 기준 (utc_offset_min, 기본 KST +9:00)
+//   작업 목록은 파일에 즉시 기록 — 재시작 후 지난 실행은 한 번만 따라잡는다
+// ═══════════════════════════════════════════════════════════════
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::car::CrownyRuntime;
+use crate::output::JsonObject;
+
+pub fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 기본 지역 시간대 (KST)
+pub const DEFAULT_UTC_OFFSET_MIN: i64 = 9 * 60;
+const MINUTE_MS: i64 = 60_000;
+/// 다음 실행 탐색 한도 — 윤년 2월 29일 같은 드문 일정도 찾도록 8년
+const SEARCH_DAYS: i64 = 366 * 8;
+
+// ─────────────────────────────────────────────
+// 달력 계산 (1970-01-01 기준 일수)
+// ─────────────────────────────────────────────
+
+/// 일수 → (연, 월, 일)
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + (m <= 2) as i64, m, d)
+}
+
+/// 일요일 = 0
+fn weekday(days: i64) -> u32 {
+    (days + 4).rem_euclid(7) as u32
+}
+
+/// 지역 시각 "YYYY-MM-DD HH:MM"
+pub fn format_local(ms: u64, utc_offset_min: i64) -> String {
+    let minutes = ms as i64 / MINUTE_MS + utc_offset_min;
+    let (y, m, d) = civil_from_days(minutes.div_euclid(1440));
+    let mod_ = minutes.rem_euclid(1440);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", y, m, d, mod_ / 60, mod_ % 60)
+}
+
+// ─────────────────────────────────────────────
+// 일정 식
+// ─────────────────────────────────────────────
+
+const MONTH_NAMES: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+const DAY_NAMES_KO: [&str; 7] = ["일", "월", "화", "수", "목", "금", "토"];
+const ICAL_DAYS: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+/// 해석된 일정 — 필드별 허용 값 비트마스크
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    days_any: bool,
+    weekdays_any: bool,
+    /// 등록할 때 쓴 원문
+    pub source: String,
+}
+
+fn field_value(raw: &str, min: u32, max: u32, names: &[&[&str]]) -> Result<u32, String> {
+    let upper = raw.to_uppercase();
+    for table in names {
+        if let Some(i) = table.iter().position(|n| *n == upper || *n == raw) {
+            return Ok(i as u32 + min);
+        }
+    }
+    let v: u32 = raw.parse().map_err(|_| format!("'{}' 은(는) 숫자가 아님", raw))?;
+    if v < min || v > max {
+        return Err(format!("{} 범위 밖 ({}~{})", v, min, max));
+    }
+    Ok(v)
+}
+
+/// cron 필드 하나 → 비트마스크 (bit i = 값 i 허용)
+fn parse_field(field: &str, min: u32, max: u32, names: &[&[&str]]) -> Result<(u64, bool), String> {
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(|| format!("잘못된 간격 '{}'", s))?),
+            None => (item, 1),
+        };
+        let (lo, hi) = match range {
+            "*" => (min, max),
+            r => match r.split_once('-') {
+                Some((a, b)) => (field_value(a, min, max, names)?, field_value(b, min, max, names)?),
+                None if step > 1 => (field_value(r, min, max, names)?, max),
+                None => { let v = field_value(r, min, max, names)?; (v, v) }
+            },
+        };
+        if lo > hi {
+            return Err(format!("잘못된 범위 {}-{}", lo, hi));
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok((mask, field == "*"))
+}
+
+fn parse_hhmm(s: &str) -> Result<(u32, u32), String> {
+    let (h, m) = s.split_once(':').ok_or_else(|| format!("시각은 HH:MM 형식: '{}'", s))?;
+    let h: u32 = h.parse().ok().filter(|h| *h < 24).ok_or_else(|| format!("잘못된 시 '{}'", h))?;
+    let m: u32 = m.parse().ok().filter(|m| *m < 60).ok_or_else(|| format!("잘못된 분 '{}'", m))?;
+    Ok((h, m))
+}
+
+impl CronExpr {
+    /// cron · 매크로 · RRULE · 한국어 일정 해석
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let s = src.trim();
+        let cron = if let Some(rule) = s.strip_prefix("RRULE:") {
+            Self::rrule_to_cron(rule)?
+        } else if s.starts_with('@') {
+            match s {
+                "@hourly" => "0 * * * *",
+                "@daily" | "@midnight" => "0 0 * * *",
+                "@weekly" => "0 0 * * 0",
+                "@monthly" => "0 0 1 * *",
+                "@yearly" | "@annually" => "0 0 1 1 *",
+                _ => return Err(format!("알 수 없는 매크로 '{}'", s)),
+            }.to_string()
+        } else if s.chars().next().is_some_and(|c| !c.is_ascii()) || s.ends_with("마다") {
+            Self::korean_to_cron(s)?
+        } else {
+            s.to_string()
+        };
+        let mut expr = Self::parse_cron(&cron)?;
+        expr.source = s.to_string();
+        Ok(expr)
+    }
+
+    fn parse_cron(cron: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = cron.split_whitespace().collect();
+        let [min, hour, dom, mon, dow] = fields.as_slice() else {
+            return Err(format!("cron 식은 5필드 (분 시 일 월 요일): '{}'", cron));
+        };
+        let (minutes, _) = parse_field(min, 0, 59, &[])?;
+        let (hours, _) = parse_field(hour, 0, 23, &[])?;
+        let (days, days_any) = parse_field(dom, 1, 31, &[])?;
+        let (months, _) = parse_field(mon, 1, 12, &[&MONTH_NAMES])?;
+        // 요일 7 = 일요일
+        let (weekdays, weekdays_any) = parse_field(dow, 0, 7, &[&DAY_NAMES, &DAY_NAMES_KO])?;
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days: days as u32,
+            months: months as u16,
+            weekdays: ((weekdays | (weekdays >> 7)) & 0x7f) as u8,
+            days_any,
+            weekdays_any,
+            source: cron.to_string(),
+        })
+    }
+
+    fn korean_to_cron(s: &str) -> Result<String, String> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let time = |w: Option<&&str>| w.map(|w| parse_hhmm(w)).unwrap_or(Ok((0, 0)));
+        let every = |suffix: &str| s.strip_suffix(suffix).and_then(|n| n.trim().parse::<u32>().ok()).filter(|n| *n > 0);
+        if let Some(n) = every("분마다") {
+            return Ok(format!("*/{} * * * *", n));
+        }
+        if let Some(n) = every("시간마다") {
+            return Ok(format!("0 */{} * * *", n));
+        }
+        let cron = match words.as_slice() {
+            ["매시간"] => "0 * * * *".to_string(),
+            ["매시", m] => {
+                let m: u32 = m.strip_suffix('분').and_then(|m| m.parse().ok()).filter(|m| *m < 60)
+                    .ok_or_else(|| format!("'매시 N분' 형식: '{}'", s))?;
+                format!("{} * * * *", m)
+            }
+            ["매일", rest @ ..] => { let (h, m) = time(rest.first())?; format!("{} {} * * *", m, h) }
+            ["평일", rest @ ..] => { let (h, m) = time(rest.first())?; format!("{} {} * * 1-5", m, h) }
+            ["주말", rest @ ..] => { let (h, m) = time(rest.first())?; format!("{} {} * * 0,6", m, h) }
+            ["매주", day, rest @ ..] => {
+                let day = day.trim_end_matches("요일");
+                let d = DAY_NAMES_KO.iter().position(|n| *n == day)
+                    .ok_or_else(|| format!("알 수 없는 요일 '{}'", day))?;
+                let (h, m) = time(rest.first())?;
+                format!("{} {} * * {}", m, h, d)
+            }
+            ["매월", day, rest @ ..] => {
+                let d: u32 = day.strip_suffix('일').and_then(|d| d.parse().ok()).filter(|d| (1..=31).contains(d))
+                    .ok_or_else(|| format!("'매월 N일' 형식: '{}'", s))?;
+                let (h, m) = time(rest.first())?;
+                format!("{} {} {} * *", m, h, d)
+            }
+            _ => return Err(format!("알 수 없는 일정 '{}' (예: 매일 09:00 · 평일 18:30 · 매주 월 09:00 · 15분마다)", s)),
+        };
+        Ok(cron)
+    }
+
+    fn rrule_to_cron(rule: &str) -> Result<String, String> {
+        let mut freq = None;
+        let mut interval = 1u32;
+        let (mut minute, mut hour, mut mday, mut month, mut day) = (None, None, None, None, None);
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (k, v) = part.split_once('=').ok_or_else(|| format!("잘못된 RRULE 항목 '{}'", part))?;
+            match k {
+                "FREQ" => freq = Some(v.to_string()),
+                "INTERVAL" => interval = v.parse().ok().filter(|n| *n > 0).ok_or_else(|| format!("잘못된 INTERVAL '{}'", v))?,
+                "BYMINUTE" => minute = Some(v.to_string()),
+                "BYHOUR" => hour = Some(v.to_string()),
+                "BYMONTHDAY" => mday = Some(v.to_string()),
+                "BYMONTH" => month = Some(v.to_string()),
+                "BYDAY" => {
+                    let days: Result<Vec<String>, String> = v.split(',')
+                        .map(|d| ICAL_DAYS.iter().position(|n| *n == d).map(|i| i.to_string())
+                            .ok_or_else(|| format!("알 수 없는 BYDAY '{}'", d)))
+                        .collect();
+                    day = Some(days?.join(","));
+                }
+                _ => return Err(format!("지원하지 않는 RRULE 항목 '{}'", k)),
+            }
+        }
+        let step = |base: &str| if interval > 1 { format!("{}/{}", base, interval) } else { base.to_string() };
+        let or = |v: Option<String>, d: &str| v.unwrap_or_else(|| d.to_string());
+        let freq = freq.ok_or("RRULE에 FREQ 없음")?;
+        if interval > 1 && !matches!(freq.as_str(), "MINUTELY" | "HOURLY") {
+            return Err(format!("INTERVAL은 MINUTELY/HOURLY만 지원 (FREQ={})", freq));
+        }
+        let fields = match freq.as_str() {
+            "MINUTELY" => [or(minute, &step("*")), or(hour, "*"), or(mday, "*"), or(month, "*"), or(day, "*")],
+            "HOURLY" => [or(minute, "0"), or(hour, &step("*")), or(mday, "*"), or(month, "*"), or(day, "*")],
+            "DAILY" => [or(minute, "0"), or(hour, "0"), or(mday, "*"), or(month, "*"), or(day, "*")],
+            "WEEKLY" => [or(minute, "0"), or(hour, "0"), "*".into(), or(month, "*"), day.ok_or("WEEKLY에는 BYDAY 필요")?],
+            "MONTHLY" => [or(minute, "0"), or(hour, "0"), or(mday, "1"), or(month, "*"), or(day, "*")],
+            "YEARLY" => [or(minute, "0"), or(hour, "0"), or(mday, "1"), or(month, "1"), or(day, "*")],
+            other => return Err(format!("지원하지 않는 FREQ '{}'", other)),
+        };
+        Ok(fields.join(" "))
+    }
+
+    fn day_matches(&self, month: u32, dom: u32, wd: u32) -> bool {
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+        let dom_ok = self.days & (1 << dom) != 0;
+        let wd_ok = self.weekdays & (1 << wd) != 0;
+        match (self.days_any, self.weekdays_any) {
+            (true, true) => true,
+            (false, true) => dom_ok,
+            (true, false) => wd_ok,
+            (false, false) => dom_ok || wd_ok,
+        }
+    }
+
+    /// after_ms 이후(초과) 첫 실행 시각 — 없으면 None (예: 2월 30일)
+    pub fn next_after(&self, after_ms: u64, utc_offset_min: i64) -> Option<u64> {
+        let local = after_ms as i64 / MINUTE_MS + 1 + utc_offset_min;
+        let (mut day, mut from) = (local.div_euclid(1440), local.rem_euclid(1440));
+        for _ in 0..SEARCH_DAYS {
+            let (_, month, dom) = civil_from_days(day);
+            if self.day_matches(month, dom, weekday(day)) {
+                let hit = (from..1440).find(|m| self.hours & (1 << (m / 60)) != 0 && self.minutes & (1 << (m % 60)) != 0);
+                if let Some(m) = hit {
+                    return Some(((day * 1440 + m - utc_offset_min) * MINUTE_MS) as u64);
+                }
+            }
+            day += 1;
+            from = 0;
+        }
+        None
+    }
+}
+
+// ─────────────────────────────────────────────
+// 예약 작업
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct ScheduledJob {
+    pub id: u64,
+    pub name: String,
+    pub expr: CronExpr,
+    /// 한선어 소스
+    pub source: String,
+    /// CAR 제출 주체
+    pub owner: String,
+    pub enabled: bool,
+    pub next_run: Option<u64>,
+    /// 마지막 실행 (시각, 트릿)
+    pub last_run: Option<(u64, i8)>,
+    pub runs: u64,
+}
+
+impl ScheduledJob {
+    pub fn last_state(&self) -> Option<i8> {
+        self.last_run.map(|(_, t)| t)
+    }
+
+    pub fn to_json(&self, utc_offset_min: i64) -> JsonObject {
+        let mut j = JsonObject::new()
+            .int("id", self.id as i64)
+            .str("name", &self.name)
+            .str("schedule", &self.expr.source)
+            .str("owner", &self.owner)
+            .bool("enabled", self.enabled)
+            .int("runs", self.runs as i64);
+        if let Some(next) = self.next_run {
+            j = j.int("next_run", next as i64).str("next_run_local", &format_local(next, utc_offset_min));
+        }
+        if let Some((at, trit)) = self.last_run {
+            j = j.int("last_run", at as i64).trit("last_state", trit);
+        }
+        j
+    }
+
+    fn encode(&self) -> String {
+        let opt = |v: Option<u64>| v.map(|v| v.to_string()).unwrap_or_default();
+        [
+            self.id.to_string(),
+            escape(&self.name),
+            escape(&self.expr.source),
+            escape(&self.owner),
+            (self.enabled as u8).to_string(),
+            self.runs.to_string(),
+            opt(self.next_run),
+            opt(self.last_run.map(|(at, _)| at)),
+            self.last_run.map(|(_, t)| t.to_string()).unwrap_or_default(),
+            escape(&self.source),
+        ].join("\t")
+    }
+
+    fn decode(line: &str) -> Result<Self, String> {
+        let f: Vec<&str> = line.split('\t').collect();
+        let [id, name, schedule, owner, enabled, runs, next, last_at, last_state, source] = f.as_slice() else {
+            return Err(format!("필드 수 {} ≠ 10", f.len()));
+        };
+        let num = |s: &str| s.parse::<u64>().map_err(|_| format!("잘못된 숫자 '{}'", s));
+        let opt = |s: &str| if s.is_empty() { Ok(None) } else { num(s).map(Some) };
+        let last_run = match (opt(last_at)?, last_state.parse::<i8>().ok()) {
+            (Some(at), Some(t)) => Some((at, t)),
+            _ => None,
+        };
+        Ok(Self {
+            id: num(id)?,
+            name: unescape(name),
+            expr: CronExpr::parse(&unescape(schedule))?,
+            source: unescape(source),
+            owner: unescape(owner),
+            enabled: *enabled == "1",
+            next_run: opt(next)?,
+            last_run,
+            runs: num(runs)?,
+        })
+    }
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// 예약 작업 목록 — 파일이 있으면 변경마다 기록
+pub struct JobScheduler {
+    jobs: Vec<ScheduledJob>,
+    next_id: u64,
+    path: Option<PathBuf>,
+    pub utc_offset_min: i64,
+}
+
+impl Default for JobScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobScheduler {
+    pub fn new() -> Self {
+        Self { jobs: Vec::new(), next_id: 1, path: None, utc_offset_min: DEFAULT_UTC_OFFSET_MIN }
+    }
+
+    /// 파일에서 복원 (없으면 빈 목록) — 이후 변경은 같은 파일에 기록
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let mut sched = Self::new();
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                for (no, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+                    let job = ScheduledJob::decode(line)
+                        .map_err(|e| format!("{}:{}: {}", path.display(), no + 1, e))?;
+                    sched.next_id = sched.next_id.max(job.id + 1);
+                    sched.jobs.push(job);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        }
+        sched.path = Some(path);
+        Ok(sched)
+    }
+
+    /// 임시 파일 → 이름 바꾸기 (쓰는 중 중단돼도 이전 목록 유지)
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let body: String = self.jobs.iter().map(|j| j.encode() + "\n").collect();
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, body)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn jobs(&self) -> &[ScheduledJob] {
+        &self.jobs
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ScheduledJob> {
+        self.jobs.iter().find(|j| j.id == id)
+    }
+
+    /// 작업 등록 — 일정 해석 실패 · 이름 중복 · 실행 시각 없음은 거부
+    pub fn add(&mut self, name: &str, schedule: &str, source: &str, owner: &str, now: u64) -> Result<u64, String> {
+        if name.trim().is_empty() {
+            return Err("작업 이름이 비어 있음".into());
+        }
+        if self.jobs.iter().any(|j| j.name == name) {
+            return Err(format!("같은 이름의 작업이 있음: {}", name));
+        }
+        let expr = CronExpr::parse(schedule)?;
+        let next_run = expr.next_after(now, self.utc_offset_min)
+            .ok_or_else(|| format!("실행 시각이 없는 일정: {}", schedule))?;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.jobs.push(ScheduledJob {
+            id, name: name.to_string(), expr, source: source.to_string(), owner: owner.to_string(),
+            enabled: true, next_run: Some(next_run), last_run: None, runs: 0,
+        });
+        self.save()?;
+        Ok(id)
+    }
+
+    pub fn remove(&mut self, id: u64) -> Result<bool, String> {
+        let before = self.jobs.len();
+        self.jobs.retain(|j| j.id != id);
+        if self.jobs.len() == before {
+            return Ok(false);
+        }
+        self.save().map(|_| true)
+    }
+
+    pub fn set_enabled(&mut self, id: u64, enabled: bool, now: u64) -> Result<bool, String> {
+        let offset = self.utc_offset_min;
+        let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) else { return Ok(false) };
+        job.enabled = enabled;
+        job.next_run = if enabled { job.expr.next_after(now, offset) } else { None };
+        self.save().map(|_| true)
+    }
+
+    /// 지금 실행할 작업 ID
+    pub fn due(&self, now: u64) -> Vec<u64> {
+        self.jobs.iter()
+            .filter(|j| j.enabled && j.next_run.is_some_and(|t| t <= now))
+            .map(|j| j.id)
+            .collect()
+    }
+
+    /// 때가 된 작업 실행 — runner가 돌려준 트릿을 기록하고 다음 시각 계산
+    /// 여러 번 놓쳤어도 한 번만 실행 (따라잡기)
+    pub fn run_due(&mut self, now: u64, mut runner: impl FnMut(&ScheduledJob) -> i8) -> Result<Vec<(u64, i8)>, String> {
+        let due = self.due(now);
+        let mut results = Vec::with_capacity(due.len());
+        for id in due {
+            let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) else { continue };
+            let state = runner(job);
+            job.last_run = Some((now, state));
+            job.runs += 1;
+            job.next_run = job.expr.next_after(now, self.utc_offset_min);
+            results.push((id, state));
+        }
+        if !results.is_empty() {
+            self.save()?;
+        }
+        Ok(results)
+    }
+
+    /// CAR 경유 실행 (주체 = 작업 소유자)
+    pub fn run_due_with(&mut self, car: &mut CrownyRuntime, now: u64) -> Result<Vec<(u64, i8)>, String> {
+        self.run_due(now, |job| car.run_source(&job.owner, &job.source).state as i8)
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.jobs")
+            .int("utc_offset_min", self.utc_offset_min)
+            .objects("jobs", self.jobs.iter().map(|j| j.to_json(self.utc_offset_min)).collect())
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// UTC "YYYY-MM-DD HH:MM" → ms
+    fn at(y: i64, mo: u32, d: u32, h: i64, mi: i64) -> u64 {
+        // 2026-01-01 = 20454일
+        let mut days = 20_454;
+        while civil_from_days(days) != (y, mo, d) {
+            days += if (y, mo, d) > civil_from_days(days) { 1 } else { -1 };
+        }
+        ((days * 1440 + h * 60 + mi) * MINUTE_MS) as u64
+    }
+
+    fn next(expr: &str, from: u64) -> String {
+        let t = CronExpr::parse(expr).unwrap().next_after(from, 0).unwrap();
+        format_local(t, 0)
+    }
+
+    #[test]
+    fn test_cron_fields_and_next_run() {
+        let now = at(2026, 10, 15, 8, 30); // 목요일
+        assert_eq!(format_local(now, 0), "2026-10-15 08:30");
+        assert_eq!(format_local(now, DEFAULT_UTC_OFFSET_MIN), "2026-10-15 17:30");
+        assert_eq!(weekday(now as i64 / MINUTE_MS / 1440), 4);
+
+        assert_eq!(next("*/15 * * * *", now), "2026-10-15 08:45");
+        assert_eq!(next("0 9 * * *", now), "2026-10-15 09:00");
+        assert_eq!(next("30 8 * * *", now), "2026-10-16 08:30"); // 같은 분은 제외
+        assert_eq!(next("0 9 * * MON-FRI", at(2026, 10, 16, 10, 0)), "2026-10-19 09:00");
+        assert_eq!(next("0 0 1 JAN *", now), "2027-01-01 00:00");
+        assert_eq!(next("0 12 13 * 5", now), "2026-10-16 12:00"); // 일 · 요일 OR
+        assert_eq!(next("0 0 29 2 *", now), "2028-02-29 00:00");
+        assert_eq!(next("0 0 * * 7", now), "2026-10-18 00:00"); // 7 = 일요일
+        assert_eq!(next("@monthly", now), "2026-11-01 00:00");
+        assert!(CronExpr::parse("0 0 30 2 *").unwrap().next_after(now, 0).is_none());
+
+        for bad in ["* * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "0 0 * * XYZ", "@often"] {
+            assert!(CronExpr::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_korean_and_ical_forms() {
+        let now = at(2026, 10, 15, 8, 30);
+        let same = |a: &str, b: &str| {
+            let (x, y) = (CronExpr::parse(a).unwrap(), CronExpr::parse(b).unwrap());
+            assert_eq!((x.minutes, x.hours, x.days, x.months, x.weekdays), (y.minutes, y.hours, y.days, y.months, y.weekdays), "{} ≠ {}", a, b);
+        };
+        same("매일 09:00", "0 9 * * *");
+        same("평일 18:30", "30 18 * * 1-5");
+        same("주말 10:00", "0 10 * * 0,6");
+        same("매주 월요일 09:00", "0 9 * * 1");
+        same("매주 금 07:15", "15 7 * * 5");
+        same("매월 1일 00:00", "0 0 1 * *");
+        same("매시 30분", "30 * * * *");
+        same("매시간", "0 * * * *");
+        same("15분마다", "*/15 * * * *");
+        same("2시간마다", "0 */2 * * *");
+        same("0 9 * * 월", "0 9 * * 1");
+        same("RRULE:FREQ=DAILY;BYHOUR=9;BYMINUTE=0", "0 9 * * *");
+        same("RRULE:FREQ=WEEKLY;BYDAY=MO,WE;BYHOUR=18", "0 18 * * 1,3");
+        same("RRULE:FREQ=MINUTELY;INTERVAL=10", "*/10 * * * *");
+        same("RRULE:FREQ=MONTHLY;BYMONTHDAY=15", "0 0 15 * *");
+        assert_eq!(CronExpr::parse("매일 09:00").unwrap().source, "매일 09:00");
+        assert_eq!(next("매주 월 09:00", now), "2026-10-19 09:00");
+
+        for bad in ["매일 25:00", "매주 월욜 09:00", "매월 32일", "언젠가", "RRULE:FREQ=DAILY;INTERVAL=2",
+                    "RRULE:FREQ=WEEKLY", "RRULE:BYHOUR=9"] {
+            assert!(CronExpr::parse(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_jobs_persist_and_record_last_state() {
+        let path = std::env::temp_dir().join(format!("crowny-jobs-{}.tsv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let now = at(2026, 10, 15, 0, 0); // KST 09:00
+
+        let mut jobs = JobScheduler::open(&path).unwrap();
+        let report = jobs.add("정산", "매일 09:30", "넣어 1\n넣어 2\n더해\n종료", "ops", now).unwrap();
+        let broken = jobs.add("깨짐", "*/10 * * * *", "; 빈 프로그램", "ops", now).unwrap();
+        assert!(jobs.add("정산", "매일 10:00", "종료", "ops", now).is_err());
+        assert!(jobs.add("빈", "0 0 31 2 *", "종료", "ops", now).is_err());
+        assert_eq!(format_local(jobs.get(report).unwrap().next_run.unwrap(), DEFAULT_UTC_OFFSET_MIN), "2026-10-15 09:30");
+        assert!(jobs.due(now).is_empty());
+
+        // 재시작 후 복원 — 한 시간 지남, 둘 다 실행 (10분 작업은 여러 번 놓쳤어도 한 번)
+        let mut jobs = JobScheduler::open(&path).unwrap();
+        assert_eq!(jobs.jobs().len(), 2);
+        let mut car = CrownyRuntime::new();
+        let later = now + 3_600_000;
+        let ran = jobs.run_due_with(&mut car, later).unwrap();
+        assert_eq!(ran.len(), 2);
+        assert_eq!(jobs.get(report).unwrap().last_state(), Some(1));
+        assert_eq!(jobs.get(broken).unwrap().last_state(), Some(-1));
+        assert_eq!(jobs.get(report).unwrap().next_run, Some(now + 86_400_000 + 30 * 60_000));
+        assert_eq!(jobs.get(broken).unwrap().next_run, Some(later + 10 * 60_000));
+
+        let jobs2 = JobScheduler::open(&path).unwrap();
+        let restored = jobs2.get(report).unwrap();
+        assert_eq!((restored.runs, restored.last_run, restored.source.as_str()), (1, Some((later, 1)), "넣어 1\n넣어 2\n더해\n종료"));
+        assert!(jobs2.to_json().build().contains("\"next_run_local\":\"2026-10-16 09:30\""));
+
+        jobs.set_enabled(broken, false, later).unwrap();
+        assert!(jobs.due(later + 86_400_000).iter().all(|id| *id != broken));
+        assert!(jobs.remove(report).unwrap());
+        assert_eq!(JobScheduler::open(&path).unwrap().jobs().len(), 1);
+        std::fs::write(&path, "garbage\n").unwrap();
+        assert!(JobScheduler::open(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
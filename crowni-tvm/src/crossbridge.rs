@@ -369,6 +369,37 @@ impl CrownyBridge {
         Ok(())
     }
 
+    /// 보류(O)로 멈춘 전송을 강제 정산 — P는 멀티시그 없이 민트, T는 송신자에게 환불
+    /// 이전 트릿 반환 (권한 · 감사 기록은 호출 측 책임)
+    pub fn force_settle(&mut self, tx_id: &str, state: i8) -> Result<i8, String> {
+        let idx = self.transactions.iter().position(|t| t.id == tx_id)
+            .ok_or_else(|| format!("TX 없음: {}", tx_id))?;
+        let tx = &self.transactions[idx];
+        let before = tx.trit();
+        if before != 0 {
+            return Err(format!("{}는 보류 상태가 아님 ({})", tx_id, tx.status));
+        }
+        match state {
+            1 => {
+                self.transactions[idx].status = BridgeTxStatus::Verified;
+                self.execute_mint(idx)?;
+            }
+            -1 => {
+                let (sender, token, amount, fee, src) = (tx.sender.clone(), tx.token.clone(), tx.amount, tx.fee, tx.src_chain.clone());
+                *self.balances.entry(sender).or_default().entry(token.clone()).or_insert(0) += amount + fee;
+                if let Some(locked) = self.tokens.get_mut(&token).and_then(|bt| bt.total_locked.get_mut(&src)) {
+                    *locked = locked.saturating_sub(amount);
+                }
+                self.total_fees = self.total_fees.saturating_sub(fee);
+                let tx = &mut self.transactions[idx];
+                tx.status = BridgeTxStatus::Refunded;
+                tx.completed_at = Some(now_ms());
+            }
+            _ => return Err("목표 상태는 P 또는 T".into()),
+        }
+        Ok(before)
+    }
+
     /// 전체 프로세스 (락 → 릴레이 → 검증 → 민트)
     pub fn bridge_transfer(
         &mut self, sender: &str, receiver: &str, token: &str,
@@ -0,0 +1,1029 @@
+// ═══════════════════════════════════════════════════════════════
+// 암호 기본 연산 — 외부 크레이트 없이 노드 간 보안 채널에 필요한 최소 집합
+//
+//   SHA-256 · HMAC-SHA256 · HKDF   (FIPS 180-4 · RFC 2104 · RFC 5869)
+//   X25519 키 교환                  (RFC 7748)
+//   ChaCha20-Poly1305 AEAD          (RFC 8439)
+//   SHA-512 · Ed25519 서명          (FIPS 180-4 · RFC 8032)
+//   SHA-1 · Base64                  (WebSocket 핸드셰이크 전용 — RFC 6455)
+//
+// trit_hash는 표시·식별용 — 기밀성/무결성이 필요한 곳은 이 모듈을 쓴다
+// ═══════════════════════════════════════════════════════════════
+
+pub const KEY_LEN: usize = 32;
+pub const NONCE_LEN: usize = 12;
+pub const TAG_LEN: usize = 16;
+
+pub type Key = [u8; KEY_LEN];
+
+// ─────────────────────────────────────────────
+// SHA-256
+// ─────────────────────────────────────────────
+
+const K256: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// 스트리밍 SHA-256
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    block: [u8; 64],
+    filled: usize,
+    total: u64,
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self {
+            state: [
+                0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+                0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+            ],
+            block: [0; 64],
+            filled: 0,
+            total: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total += data.len() as u64;
+        while !data.is_empty() {
+            let n = (64 - self.filled).min(data.len());
+            self.block[self.filled..self.filled + n].copy_from_slice(&data[..n]);
+            self.filled += n;
+            data = &data[n..];
+            if self.filled == 64 {
+                let block = self.block;
+                self.compress(&block);
+                self.filled = 0;
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> [u8; 32] {
+        let bits = self.total.wrapping_mul(8);
+        self.update(&[0x80]);
+        while self.filled != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_be_bytes());
+        let mut out = [0u8; 32];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut w = [0u32; 64];
+        for (i, chunk) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K256[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g; g = f; f = e;
+            e = d.wrapping_add(t1);
+            d = c; c = b; b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (s, v) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.update(data);
+    h.finish()
+}
+
+pub fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut k = [0u8; 64];
+    if key.len() > 64 {
+        k[..32].copy_from_slice(&sha256(key));
+    } else {
+        k[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(&k.map(|b| b ^ 0x36));
+    for p in parts {
+        inner.update(p);
+    }
+    let mut outer = Sha256::new();
+    outer.update(&k.map(|b| b ^ 0x5c));
+    outer.update(&inner.finish());
+    outer.finish()
+}
+
+/// HKDF-SHA256 — salt + 입력 키 재료 → 32바이트 키 N개
+pub fn hkdf<const N: usize>(salt: &[u8], ikm: &[u8], info: &[u8]) -> [Key; N] {
+    let prk = hmac_sha256(salt, &[ikm]);
+    let mut out = [[0u8; 32]; N];
+    let mut prev: Vec<u8> = Vec::new();
+    for (i, key) in out.iter_mut().enumerate() {
+        *key = hmac_sha256(&prk, &[&prev, info, &[i as u8 + 1]]);
+        prev = key.to_vec();
+    }
+    out
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// 시간 일정 비교 — MAC/태그 검증용
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// OS 난수 (/dev/urandom) — 없으면 시각·주소 기반 해시로 대체
+pub fn random_bytes<const N: usize>() -> [u8; N] {
+    use std::io::Read;
+    let mut out = [0u8; N];
+    if std::fs::File::open("/dev/urandom").and_then(|mut f| f.read_exact(&mut out)).is_ok() {
+        return out;
+    }
+    let seed = format!("{:?}:{:p}", std::time::SystemTime::now(), &out);
+    for (counter, chunk) in out.chunks_mut(32).enumerate() {
+        let block = hmac_sha256(seed.as_bytes(), &[&(counter as u32).to_le_bytes()]);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+    out
+}
+
+// ─────────────────────────────────────────────
+// X25519 (GF(2^255-19), 51비트 5-limb)
+// ─────────────────────────────────────────────
+
+const MASK51: u64 = (1 << 51) - 1;
+
+#[derive(Clone, Copy)]
+struct Fe([u64; 5]);
+
+impl Fe {
+    const ZERO: Fe = Fe([0; 5]);
+    const ONE: Fe = Fe([1, 0, 0, 0, 0]);
+
+    fn from_bytes(b: &[u8; 32]) -> Fe {
+        let load = |i: usize| {
+            let mut w = [0u8; 8];
+            let end = (i + 8).min(32);
+            w[..end - i].copy_from_slice(&b[i..end]);
+            u64::from_le_bytes(w)
+        };
+        Fe([
+            load(0) & MASK51,
+            (load(6) >> 3) & MASK51,
+            (load(12) >> 6) & MASK51,
+            (load(19) >> 1) & MASK51,
+            (load(24) >> 12) & MASK51,
+        ])
+    }
+
+    fn carry(mut self) -> Fe {
+        for _ in 0..2 {
+            for i in 0..4 {
+                self.0[i + 1] += self.0[i] >> 51;
+                self.0[i] &= MASK51;
+            }
+            self.0[0] += 19 * (self.0[4] >> 51);
+            self.0[4] &= MASK51;
+        }
+        self
+    }
+
+    fn to_bytes(self) -> [u8; 32] {
+        let mut h = self.carry().0;
+        // h < 2p → p를 뺄 수 있으면 뺀다
+        let mut q = (h[0] + 19) >> 51;
+        for limb in &h[1..] {
+            q = (limb + q) >> 51;
+        }
+        h[0] += 19 * q;
+        for i in 0..4 {
+            h[i + 1] += h[i] >> 51;
+            h[i] &= MASK51;
+        }
+        h[4] &= MASK51;
+        let words = [
+            h[0] | (h[1] << 51),
+            (h[1] >> 13) | (h[2] << 38),
+            (h[2] >> 26) | (h[3] << 25),
+            (h[3] >> 39) | (h[4] << 12),
+        ];
+        let mut out = [0u8; 32];
+        for (chunk, w) in out.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&w.to_le_bytes());
+        }
+        out
+    }
+
+    fn add(self, o: Fe) -> Fe {
+        let mut r = self.0;
+        for (x, y) in r.iter_mut().zip(o.0) {
+            *x += y;
+        }
+        Fe(r).carry()
+    }
+
+    fn sub(self, o: Fe) -> Fe {
+        // 2p를 더해 음수 방지
+        const TWO_P: [u64; 5] = [0xFFFFFFFFFFFDA, 0xFFFFFFFFFFFFE, 0xFFFFFFFFFFFFE, 0xFFFFFFFFFFFFE, 0xFFFFFFFFFFFFE];
+        let mut r = self.0;
+        for i in 0..5 {
+            r[i] = r[i] + TWO_P[i] - o.0[i];
+        }
+        Fe(r).carry()
+    }
+
+    fn mul(self, o: Fe) -> Fe {
+        let a = self.0.map(|x| x as u128);
+        let b = o.0.map(|x| x as u128);
+        let b19 = b.map(|x| x * 19);
+        let t = [
+            a[0] * b[0] + a[1] * b19[4] + a[2] * b19[3] + a[3] * b19[2] + a[4] * b19[1],
+            a[0] * b[1] + a[1] * b[0] + a[2] * b19[4] + a[3] * b19[3] + a[4] * b19[2],
+            a[0] * b[2] + a[1] * b[1] + a[2] * b[0] + a[3] * b19[4] + a[4] * b19[3],
+            a[0] * b[3] + a[1] * b[2] + a[2] * b[1] + a[3] * b[0] + a[4] * b19[4],
+            a[0] * b[4] + a[1] * b[3] + a[2] * b[2] + a[3] * b[1] + a[4] * b[0],
+        ];
+        let mut r = [0u64; 5];
+        let mut c: u128 = 0;
+        for i in 0..5 {
+            let v = t[i] + c;
+            r[i] = (v as u64) & MASK51;
+            c = v >> 51;
+        }
+        let v = r[0] as u128 + c * 19;
+        r[0] = (v as u64) & MASK51;
+        r[1] += (v >> 51) as u64;
+        Fe(r)
+    }
+
+    fn square(self) -> Fe {
+        self.mul(self)
+    }
+
+    /// z^(p-2) — 페르마 역원
+    fn invert(self) -> Fe {
+        let mut exp = [0xffu8; 32];
+        exp[0] = 0xeb;
+        exp[31] = 0x7f;
+        self.pow(&exp)
+    }
+
+    /// 리틀엔디언 지수 거듭제곱 (지수는 공개값)
+    fn pow(self, exp: &Key) -> Fe {
+        let mut r = Fe::ONE;
+        for bit in (0..255).rev() {
+            r = r.square();
+            if (exp[bit / 8] >> (bit % 8)) & 1 == 1 {
+                r = r.mul(self);
+            }
+        }
+        r
+    }
+
+    fn cswap(a: &mut Fe, b: &mut Fe, swap: u64) {
+        let mask = 0u64.wrapping_sub(swap);
+        for i in 0..5 {
+            let t = mask & (a.0[i] ^ b.0[i]);
+            a.0[i] ^= t;
+            b.0[i] ^= t;
+        }
+    }
+}
+
+/// X25519 스칼라 곱 (몽고메리 사다리)
+pub fn x25519(scalar: &Key, point: &Key) -> Key {
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+
+    let x1 = Fe::from_bytes(point);
+    let (mut x2, mut z2, mut x3, mut z3) = (Fe::ONE, Fe::ZERO, x1, Fe::ONE);
+    let a24 = Fe([121665, 0, 0, 0, 0]);
+    let mut swap = 0u64;
+    for t in (0..255).rev() {
+        let bit = ((k[t / 8] >> (t % 8)) & 1) as u64;
+        swap ^= bit;
+        Fe::cswap(&mut x2, &mut x3, swap);
+        Fe::cswap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        let a = x2.add(z2);
+        let aa = a.square();
+        let b = x2.sub(z2);
+        let bb = b.square();
+        let e = aa.sub(bb);
+        let c = x3.add(z3);
+        let d = x3.sub(z3);
+        let da = d.mul(a);
+        let cb = c.mul(b);
+        x3 = da.add(cb).square();
+        z3 = x1.mul(da.sub(cb).square());
+        x2 = aa.mul(bb);
+        z2 = e.mul(aa.add(a24.mul(e)));
+    }
+    Fe::cswap(&mut x2, &mut x3, swap);
+    Fe::cswap(&mut z2, &mut z3, swap);
+    x2.mul(z2.invert()).to_bytes()
+}
+
+pub fn x25519_base(scalar: &Key) -> Key {
+    let mut base = [0u8; 32];
+    base[0] = 9;
+    x25519(scalar, &base)
+}
+
+// ─────────────────────────────────────────────
+// ChaCha20-Poly1305
+// ─────────────────────────────────────────────
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]); s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]); s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]); s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]); s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+fn le32(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn chacha20_block(key: &Key, counter: u32, nonce: &[u8; NONCE_LEN]) -> [u8; 64] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&[0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]);
+    for i in 0..8 {
+        state[4 + i] = le32(&key[i * 4..]);
+    }
+    state[12] = counter;
+    for i in 0..3 {
+        state[13 + i] = le32(&nonce[i * 4..]);
+    }
+    let mut w = state;
+    for _ in 0..10 {
+        quarter_round(&mut w, 0, 4, 8, 12);
+        quarter_round(&mut w, 1, 5, 9, 13);
+        quarter_round(&mut w, 2, 6, 10, 14);
+        quarter_round(&mut w, 3, 7, 11, 15);
+        quarter_round(&mut w, 0, 5, 10, 15);
+        quarter_round(&mut w, 1, 6, 11, 12);
+        quarter_round(&mut w, 2, 7, 8, 13);
+        quarter_round(&mut w, 3, 4, 9, 14);
+    }
+    let mut out = [0u8; 64];
+    for (i, chunk) in out.chunks_exact_mut(4).enumerate() {
+        chunk.copy_from_slice(&w[i].wrapping_add(state[i]).to_le_bytes());
+    }
+    out
+}
+
+/// ChaCha20 스트림 XOR (제자리)
+pub fn chacha20_xor(key: &Key, counter: u32, nonce: &[u8; NONCE_LEN], data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(64).enumerate() {
+        let ks = chacha20_block(key, counter.wrapping_add(i as u32), nonce);
+        for (b, k) in chunk.iter_mut().zip(ks) {
+            *b ^= k;
+        }
+    }
+}
+
+/// Poly1305 일회용 MAC (26비트 5-limb)
+pub fn poly1305(key: &Key, msg: &[u8]) -> [u8; TAG_LEN] {
+    const M26: u32 = 0x3ffffff;
+    let r = [
+        le32(&key[0..]) & 0x3ffffff,
+        (le32(&key[3..]) >> 2) & 0x3ffff03,
+        (le32(&key[6..]) >> 4) & 0x3ffc0ff,
+        (le32(&key[9..]) >> 6) & 0x3f03fff,
+        (le32(&key[12..]) >> 8) & 0x00fffff,
+    ].map(|x| x as u64);
+    let s = [r[1] * 5, r[2] * 5, r[3] * 5, r[4] * 5];
+    let mut h = [0u32; 5];
+
+    for chunk in msg.chunks(16) {
+        let mut block = [0u8; 17];
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()] = 1;
+        let hibit = (block[16] as u32) << 24;
+        h[0] += le32(&block[0..]) & M26;
+        h[1] += (le32(&block[3..]) >> 2) & M26;
+        h[2] += (le32(&block[6..]) >> 4) & M26;
+        h[3] += (le32(&block[9..]) >> 6) & M26;
+        h[4] += (le32(&block[12..]) >> 8) | hibit;
+
+        let hh = h.map(|x| x as u64);
+        let d = [
+            hh[0] * r[0] + hh[1] * s[3] + hh[2] * s[2] + hh[3] * s[1] + hh[4] * s[0],
+            hh[0] * r[1] + hh[1] * r[0] + hh[2] * s[3] + hh[3] * s[2] + hh[4] * s[1],
+            hh[0] * r[2] + hh[1] * r[1] + hh[2] * r[0] + hh[3] * s[3] + hh[4] * s[2],
+            hh[0] * r[3] + hh[1] * r[2] + hh[2] * r[1] + hh[3] * r[0] + hh[4] * s[3],
+            hh[0] * r[4] + hh[1] * r[3] + hh[2] * r[2] + hh[3] * r[1] + hh[4] * r[0],
+        ];
+        let mut c = 0u64;
+        for i in 0..5 {
+            let v = d[i] + c;
+            h[i] = (v as u32) & M26;
+            c = v >> 26;
+        }
+        h[0] += (c * 5) as u32;
+        h[1] += h[0] >> 26;
+        h[0] &= M26;
+    }
+
+    // 완전 정규화
+    let mut c;
+    for i in 1..5 {
+        c = h[i] >> 26;
+        h[i] &= M26;
+        if i < 4 { h[i + 1] += c } else { h[0] += c * 5 }
+    }
+    c = h[0] >> 26;
+    h[0] &= M26;
+    h[1] += c;
+
+    // h - p 계산, 음수가 아니면 선택
+    let mut g = [0u32; 5];
+    c = 5;
+    for i in 0..5 {
+        let v = h[i] + c;
+        g[i] = v & M26;
+        c = v >> 26;
+    }
+    let g4 = g[4].wrapping_add(c << 26).wrapping_sub(1 << 26);
+    let mask = (g4 >> 31).wrapping_sub(1);
+    g[4] = g4;
+    for i in 0..5 {
+        h[i] = (h[i] & !mask) | (g[i] & mask);
+    }
+
+    let words = [
+        h[0] | (h[1] << 26),
+        (h[1] >> 6) | (h[2] << 20),
+        (h[2] >> 12) | (h[3] << 14),
+        (h[3] >> 18) | (h[4] << 8),
+    ];
+    let mut out = [0u8; TAG_LEN];
+    let mut carry = 0u64;
+    for i in 0..4 {
+        let v = words[i] as u64 + le32(&key[16 + i * 4..]) as u64 + carry;
+        out[i * 4..i * 4 + 4].copy_from_slice(&(v as u32).to_le_bytes());
+        carry = v >> 32;
+    }
+    out
+}
+
+fn aead_tag(key: &Key, nonce: &[u8; NONCE_LEN], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+    let mut otk = [0u8; 32];
+    otk.copy_from_slice(&chacha20_block(key, 0, nonce)[..32]);
+    let pad = |n: usize| (16 - n % 16) % 16;
+    let mut mac = Vec::with_capacity(aad.len() + ciphertext.len() + 48);
+    mac.extend_from_slice(aad);
+    mac.resize(mac.len() + pad(aad.len()), 0);
+    mac.extend_from_slice(ciphertext);
+    mac.resize(mac.len() + pad(ciphertext.len()), 0);
+    mac.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+    mac.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+    poly1305(&otk, &mac)
+}
+
+/// AEAD 암호화 — 암호문 ‖ 태그(16)
+pub fn seal(key: &Key, nonce: &[u8; NONCE_LEN], aad: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(plaintext.len() + TAG_LEN);
+    out.extend_from_slice(plaintext);
+    chacha20_xor(key, 1, nonce, &mut out);
+    let tag = aead_tag(key, nonce, aad, &out);
+    out.extend_from_slice(&tag);
+    out
+}
+
+/// AEAD 복호화 — 태그 불일치면 None (평문을 내놓지 않음)
+pub fn open(key: &Key, nonce: &[u8; NONCE_LEN], aad: &[u8], sealed: &[u8]) -> Option<Vec<u8>> {
+    let (ciphertext, tag) = sealed.split_at_checked(sealed.len().checked_sub(TAG_LEN)?)?;
+    if !ct_eq(&aead_tag(key, nonce, aad, ciphertext), tag) {
+        return None;
+    }
+    let mut out = ciphertext.to_vec();
+    chacha20_xor(key, 1, nonce, &mut out);
+    Some(out)
+}
+
+// ─────────────────────────────────────────────
+// SHA-512 (Ed25519 전용 — 한 번에 해시)
+// ─────────────────────────────────────────────
+
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+pub fn sha512(parts: &[&[u8]]) -> [u8; 64] {
+    let mut state: [u64; 8] = [
+        0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+        0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+    ];
+    let mut data: Vec<u8> = parts.concat();
+    let bits = (data.len() as u128).wrapping_mul(8);
+    data.push(0x80);
+    while data.len() % 128 != 112 {
+        data.push(0);
+    }
+    data.extend_from_slice(&bits.to_be_bytes());
+
+    for block in data.chunks_exact(128) {
+        let mut w = [0u64; 80];
+        for (i, chunk) in block.chunks_exact(8).enumerate() {
+            w[i] = u64::from_be_bytes(chunk.try_into().unwrap_or_default());
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K512[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g; g = f; f = e;
+            e = d.wrapping_add(t1);
+            d = c; c = b; b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+    let mut out = [0u8; 64];
+    for (chunk, word) in out.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+// ─────────────────────────────────────────────
+// SHA-1 · Base64 (WebSocket 핸드셰이크 전용 — 보안 용도로 쓰지 않는다)
+// ─────────────────────────────────────────────
+
+pub fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    let mut data = data.to_vec();
+    let bits = (data.len() as u64).wrapping_mul(8);
+    data.push(0x80);
+    while data.len() % 64 != 56 {
+        data.push(0);
+    }
+    data.extend_from_slice(&bits.to_be_bytes());
+
+    for block in data.chunks_exact(64) {
+        let mut w = [0u32; 80];
+        for (i, chunk) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(chunk.try_into().unwrap_or_default());
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*wi);
+            e = d; d = c;
+            c = b.rotate_left(30);
+            b = a; a = t;
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+    let mut out = [0u8; 20];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+/// 표준 Base64 (패딩 포함)
+pub fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, b)| acc | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// ─────────────────────────────────────────────
+// Ed25519 서명 (RFC 8032) — 노드 신원 · 투표 서명
+// ─────────────────────────────────────────────
+
+pub const SIGNATURE_LEN: usize = 64;
+pub type Signature = [u8; SIGNATURE_LEN];
+
+/// d = -121665/121666
+const ED_D: Key = [
+    0xa3, 0x78, 0x59, 0x13, 0xca, 0x4d, 0xeb, 0x75, 0xab, 0xd8, 0x41, 0x41, 0x4d, 0x0a, 0x70, 0x00,
+    0x98, 0xe8, 0x79, 0x77, 0x79, 0x40, 0xc7, 0x8c, 0x73, 0xfe, 0x6f, 0x2b, 0xee, 0x6c, 0x03, 0x52,
+];
+const ED_D2: Key = [
+    0x59, 0xf1, 0xb2, 0x26, 0x94, 0x9b, 0xd6, 0xeb, 0x56, 0xb1, 0x83, 0x82, 0x9a, 0x14, 0xe0, 0x00,
+    0x30, 0xd1, 0xf3, 0xee, 0xf2, 0x80, 0x8e, 0x19, 0xe7, 0xfc, 0xdf, 0x56, 0xdc, 0xd9, 0x06, 0x24,
+];
+/// √-1
+const SQRT_M1: Key = [
+    0xb0, 0xa0, 0x0e, 0x4a, 0x27, 0x1b, 0xee, 0xc4, 0x78, 0xe4, 0x2f, 0xad, 0x06, 0x18, 0x43, 0x2f,
+    0xa7, 0xd7, 0xfb, 0x3d, 0x99, 0x00, 0x4d, 0x2b, 0x0b, 0xdf, 0xc1, 0x4f, 0x80, 0x24, 0x83, 0x2b,
+];
+/// (p-5)/8
+const EXP_P58: Key = [
+    0xfd, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x0f,
+];
+/// 기준점 B 인코딩 (y = 4/5)
+const ED_BASE: Key = [
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+];
+/// 군의 위수 L = 2^252 + 27742317777372353535851937790883648493
+const ED_L: [u64; 4] = [0x5812631a5cf5d3ed, 0x14def9dea2f79cd6, 0, 0x1000000000000000];
+
+impl Fe {
+    fn neg(self) -> Fe {
+        Fe::ZERO.sub(self)
+    }
+
+    fn is_zero(self) -> bool {
+        self.to_bytes() == [0u8; 32]
+    }
+
+    fn is_negative(self) -> u8 {
+        self.to_bytes()[0] & 1
+    }
+}
+
+/// 확장 좌표 (X:Y:Z:T), x = X/Z, y = Y/Z, xy = T/Z
+#[derive(Clone, Copy)]
+struct EdPoint {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+    t: Fe,
+}
+
+impl EdPoint {
+    const IDENTITY: EdPoint = EdPoint { x: Fe::ZERO, y: Fe::ONE, z: Fe::ONE, t: Fe::ZERO };
+
+    /// 통합 덧셈 (a = -1) — 두 배에도 그대로 쓴다
+    fn add(&self, o: &EdPoint) -> EdPoint {
+        let a = self.y.sub(self.x).mul(o.y.sub(o.x));
+        let b = self.y.add(self.x).mul(o.y.add(o.x));
+        let c = self.t.mul(Fe::from_bytes(&ED_D2)).mul(o.t);
+        let d = self.z.add(self.z).mul(o.z);
+        let (e, f, g, h) = (b.sub(a), d.sub(c), d.add(c), b.add(a));
+        EdPoint { x: e.mul(f), y: g.mul(h), z: f.mul(g), t: e.mul(h) }
+    }
+
+    fn neg(&self) -> EdPoint {
+        EdPoint { x: self.x.neg(), y: self.y, z: self.z, t: self.t.neg() }
+    }
+
+    /// 스칼라 곱 — 비트마다 더한 결과를 조건부 교환 (분기 없음)
+    fn mul(&self, scalar: &Key) -> EdPoint {
+        let mut q = EdPoint::IDENTITY;
+        for bit in (0..256).rev() {
+            q = q.add(&q);
+            let mut r = q.add(self);
+            let swap = ((scalar[bit / 8] >> (bit % 8)) & 1) as u64;
+            Fe::cswap(&mut q.x, &mut r.x, swap);
+            Fe::cswap(&mut q.y, &mut r.y, swap);
+            Fe::cswap(&mut q.z, &mut r.z, swap);
+            Fe::cswap(&mut q.t, &mut r.t, swap);
+        }
+        q
+    }
+
+    fn encode(&self) -> Key {
+        let zi = self.z.invert();
+        let mut out = self.y.mul(zi).to_bytes();
+        out[31] |= self.x.mul(zi).is_negative() << 7;
+        out
+    }
+
+    fn decode(bytes: &Key) -> Option<EdPoint> {
+        let sign = bytes[31] >> 7;
+        let mut yb = *bytes;
+        yb[31] &= 0x7f;
+        let y = Fe::from_bytes(&yb);
+        // y ≥ p 인 비정규 인코딩 거부
+        if y.to_bytes() != yb {
+            return None;
+        }
+        // x² = (y² - 1) / (d·y² + 1)
+        let yy = y.square();
+        let u = yy.sub(Fe::ONE);
+        let v = Fe::from_bytes(&ED_D).mul(yy).add(Fe::ONE);
+        let v3 = v.square().mul(v);
+        let mut x = u.mul(v3).mul(u.mul(v3.square().mul(v)).pow(&EXP_P58));
+        let vxx = v.mul(x.square());
+        if vxx.sub(u).is_zero() {
+        } else if vxx.add(u).is_zero() {
+            x = x.mul(Fe::from_bytes(&SQRT_M1));
+        } else {
+            return None;
+        }
+        if x.is_zero() && sign == 1 {
+            return None;
+        }
+        if x.is_negative() != sign {
+            x = x.neg();
+        }
+        Some(EdPoint { x, y, z: Fe::ONE, t: x.mul(y) })
+    }
+}
+
+/// 리틀엔디언 바이트 (최대 64) mod L — 상위 비트부터 한 비트씩 밀어 넣는다
+fn sc_reduce(bytes: &[u8]) -> Key {
+    let mut acc = [0u64; 4];
+    for bit in (0..bytes.len() * 8).rev() {
+        let mut carry = ((bytes[bit / 8] >> (bit % 8)) & 1) as u64;
+        for limb in acc.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        // acc < 2L < 2^254 → 한 번 빼면 충분
+        if !sc_less_than_l(&acc) {
+            let mut borrow = 0u64;
+            for (limb, l) in acc.iter_mut().zip(ED_L) {
+                let (d1, b1) = limb.overflowing_sub(l);
+                let (d2, b2) = d1.overflowing_sub(borrow);
+                *limb = d2;
+                borrow = (b1 | b2) as u64;
+            }
+        }
+    }
+    let mut out = [0u8; 32];
+    for (chunk, limb) in out.chunks_exact_mut(8).zip(acc) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    out
+}
+
+fn sc_less_than_l(a: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != ED_L[i] {
+            return a[i] < ED_L[i];
+        }
+    }
+    false
+}
+
+fn sc_limbs(b: &Key) -> [u64; 4] {
+    let mut out = [0u64; 4];
+    for (limb, chunk) in out.iter_mut().zip(b.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap_or_default());
+    }
+    out
+}
+
+/// (a·b + c) mod L
+fn sc_muladd(a: &Key, b: &Key, c: &Key) -> Key {
+    let (a, b, c) = (sc_limbs(a), sc_limbs(b), sc_limbs(c));
+    let mut wide = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let v = wide[i + j] as u128 + a[i] as u128 * b[j] as u128 + carry;
+            wide[i + j] = v as u64;
+            carry = v >> 64;
+        }
+        wide[i + 4] = carry as u64;
+    }
+    let mut carry = 0u128;
+    for (i, limb) in wide.iter_mut().enumerate() {
+        let v = *limb as u128 + c.get(i).copied().unwrap_or(0) as u128 + carry;
+        *limb = v as u64;
+        carry = v >> 64;
+    }
+    let bytes: Vec<u8> = wide.iter().flat_map(|w| w.to_le_bytes()).collect();
+    sc_reduce(&bytes)
+}
+
+/// 시드 → (클램프된 비밀 스칼라, 논스 접두사)
+fn ed_expand(seed: &Key) -> (Key, Key) {
+    let h = sha512(&[seed]);
+    let mut a: Key = h[..32].try_into().unwrap_or_default();
+    a[0] &= 248;
+    a[31] &= 127;
+    a[31] |= 64;
+    (a, h[32..].try_into().unwrap_or_default())
+}
+
+fn ed_base() -> EdPoint {
+    EdPoint::decode(&ED_BASE).unwrap_or(EdPoint::IDENTITY)
+}
+
+/// 32바이트 시드 → 공개 키
+pub fn ed25519_public(seed: &Key) -> Key {
+    ed_base().mul(&ed_expand(seed).0).encode()
+}
+
+pub fn ed25519_sign(seed: &Key, msg: &[u8]) -> Signature {
+    let (a, prefix) = ed_expand(seed);
+    let public = ed_base().mul(&a).encode();
+    let r = sc_reduce(&sha512(&[&prefix, msg]));
+    let big_r = ed_base().mul(&r).encode();
+    let k = sc_reduce(&sha512(&[&big_r, &public, msg]));
+    let s = sc_muladd(&k, &a, &r);
+    let mut sig = [0u8; SIGNATURE_LEN];
+    sig[..32].copy_from_slice(&big_r);
+    sig[32..].copy_from_slice(&s);
+    sig
+}
+
+/// [S]B = R + [k]A 검사 — 비정규 S · 잘못된 점 인코딩은 거부
+pub fn ed25519_verify(public: &Key, msg: &[u8], sig: &[u8]) -> bool {
+    let Ok(sig) = <&Signature>::try_from(sig) else {
+        return false;
+    };
+    let r: Key = sig[..32].try_into().unwrap_or_default();
+    let s: Key = sig[32..].try_into().unwrap_or_default();
+    if !sc_less_than_l(&sc_limbs(&s)) {
+        return false;
+    }
+    let (Some(a), Some(_)) = (EdPoint::decode(public), EdPoint::decode(&r)) else {
+        return false;
+    };
+    let k = sc_reduce(&sha512(&[&r, public, msg]));
+    let check = ed_base().mul(&s).add(&a.neg().mul(&k));
+    ct_eq(&check.encode(), &r)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex32(s: &str) -> Key {
+        from_hex(s).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_sha256_and_hmac_vectors() {
+        assert_eq!(to_hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(to_hex(&sha256(&[b'a'; 1000])), to_hex(&{
+            let mut h = Sha256::new();
+            for _ in 0..10 { h.update(&[b'a'; 100]); }
+            h.finish()
+        }));
+        assert_eq!(to_hex(&hmac_sha256(b"Jefe", &[b"what do ya want ", b"for nothing?"])),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn test_sha1_and_base64_vectors() {
+        assert_eq!(to_hex(&sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(to_hex(&sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_sha512_and_ed25519_rfc8032() {
+        assert_eq!(to_hex(&sha512(&[b"a", b"bc"])),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f");
+        assert_eq!(sha512(&[&[7u8; 200]]), sha512(&[&[7u8; 111], &[7u8; 89]]));
+
+        let vectors = [
+            ("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+             "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a", "",
+             "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+            ("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+             "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c", "72",
+             "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+        ];
+        for (seed, public, msg, sig) in vectors {
+            let (seed, public, msg) = (hex32(seed), hex32(public), from_hex(msg).unwrap());
+            assert_eq!(ed25519_public(&seed), public);
+            let signed = ed25519_sign(&seed, &msg);
+            assert_eq!(to_hex(&signed), sig);
+            assert!(ed25519_verify(&public, &msg, &signed));
+
+            // 메시지 · 서명 · 키 변조는 모두 거부
+            assert!(!ed25519_verify(&public, b"tampered", &signed));
+            let mut bad = signed;
+            bad[40] ^= 1;
+            assert!(!ed25519_verify(&public, &msg, &bad));
+            assert!(!ed25519_verify(&ed25519_public(&[9; 32]), &msg, &signed));
+            assert!(!ed25519_verify(&public, &msg, &signed[..63]));
+        }
+    }
+
+    #[test]
+    fn test_x25519_rfc7748() {
+        let a = hex32("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let b = hex32("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let pa = x25519_base(&a);
+        let pb = x25519_base(&b);
+        assert_eq!(to_hex(&pa), "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a");
+        assert_eq!(to_hex(&pb), "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+        let shared = x25519(&a, &pb);
+        assert_eq!(shared, x25519(&b, &pa));
+        assert_eq!(to_hex(&shared), "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_rfc8439() {
+        let otk = hex32("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b");
+        assert_eq!(to_hex(&poly1305(&otk, b"Cryptographic Forum Research Group")),
+            "a8061dc1305136c6c22b8baf0c0127a9");
+
+        let key: Key = std::array::from_fn(|i| 0x80 + i as u8);
+        let nonce = [0x07, 0, 0, 0, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47];
+        let aad = [0x50, 0x51, 0x52, 0x53, 0xc0, 0xc1, 0xc2, 0xc3, 0xc4, 0xc5, 0xc6, 0xc7];
+        let pt = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+        let sealed = seal(&key, &nonce, &aad, pt);
+        assert_eq!(to_hex(&sealed[..16]), "d31a8d34648e60db7b86afbc53ef7ec2");
+        assert_eq!(to_hex(&sealed[pt.len()..]), "1ae10b594f09e26a7e902ecbd0600691");
+        assert_eq!(open(&key, &nonce, &aad, &sealed).as_deref(), Some(&pt[..]));
+
+        // 변조 → 거부
+        let mut bad = sealed.clone();
+        bad[3] ^= 1;
+        assert!(open(&key, &nonce, &aad, &bad).is_none());
+        assert!(open(&key, &nonce, b"other", &sealed).is_none());
+        assert!(open(&key, &nonce, &aad, &sealed[..8]).is_none());
+    }
+}
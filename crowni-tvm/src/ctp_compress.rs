@@ -0,0 +1,645 @@
+// ═══════════════════════════════════════════════════════════════
+// CTP 전송 압축 — 큰 페이로드(상태 동기화 · WASM 아티팩트)용 LZ 압축
+// 핸드셰이크에서 양쪽이 CAP_COMPRESS를 광고했을 때만 켜진다
+//
+//   LZ 토큰 (의존성 없음, 창 64KB):
+//     0xxxxxxx            → 리터럴 x+1 바이트가 뒤따름
+//     1xxxxxxx [거리 2B]   → 거리만큼 앞에서 x+4 바이트 복사
+//
+//   CTP3 프레임(network.rs)의 길이 단어 u32 = [압축 1bit][길이 31bit]
+//     평문   [트릿 수]         본문 = 트릿 2bit 패킹
+//     압축   [압축|본문 길이]   본문 = [트릿 수 4B][LZ] 또는 트릿 압축 [0xC3]...
+//            (트릿 수는 2^24 미만이라 첫 바이트가 0 — 0xC3이면 트릿 압축)
+//   송신 측은 LZ와 트릿 압축 중 더 작은 쪽을 고른다
+//
+//   임계값보다 작은 메시지는 그대로 보낸다 (작은 트릿 메시지)
+//   압축해도 줄지 않으면 원본 그대로 — 통계에 바이트 절감 기록
+//
+//   트릿 압축 (TritBuffer::compress) — 자기 서술 머리 [0xC3][방식][트릿 수 u32]
+//     원본    2bit 패킹 그대로
+//     RLE     [트릿 2bit | 길이-1 6bit] — 같은 트릿이 길게 이어질 때
+//     허프만  트라이트(3트릿, 27기호) 정규 허프만 — 길이표 14B + 비트열
+// ═══════════════════════════════════════════════════════════════
+
+use std::collections::HashMap;
+
+use crate::network::{NetTrit, TritBuffer, CAP_COMPRESS};
+
+/// 압축 본문 표시 (프레임 길이 단어 최상위 비트)
+pub const FRAME_COMPRESSED: u32 = 1 << 31;
+
+const MIN_MATCH: usize = 4;
+const MAX_MATCH: usize = MIN_MATCH + 0x7f;
+const MAX_LITERALS: usize = 0x80;
+const WINDOW: usize = 0xffff;
+const HASH_BITS: u32 = 12;
+
+// ─────────────────────────────────────────────
+// LZ 코덱
+// ─────────────────────────────────────────────
+
+fn hash4(bytes: &[u8]) -> usize {
+    let v = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    (v.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+fn push_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for run in literals.chunks(MAX_LITERALS) {
+        out.push((run.len() - 1) as u8);
+        out.extend_from_slice(run);
+    }
+}
+
+/// 바이트 → LZ 토큰열
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2 + 8);
+    let mut table = vec![usize::MAX; 1 << HASH_BITS];
+    let (mut i, mut literal_start) = (0, 0);
+
+    while i + MIN_MATCH <= input.len() {
+        let h = hash4(&input[i..]);
+        let candidate = std::mem::replace(&mut table[h], i);
+        let hit = candidate != usize::MAX
+            && i - candidate <= WINDOW
+            && input[candidate..candidate + MIN_MATCH] == input[i..i + MIN_MATCH];
+        if !hit {
+            i += 1;
+            continue;
+        }
+        let mut len = MIN_MATCH;
+        while i + len < input.len() && len < MAX_MATCH && input[candidate + len] == input[i + len] {
+            len += 1;
+        }
+        push_literals(&mut out, &input[literal_start..i]);
+        out.push(0x80 | (len - MIN_MATCH) as u8);
+        out.extend_from_slice(&((i - candidate) as u16).to_be_bytes());
+        i += len;
+        literal_start = i;
+    }
+    push_literals(&mut out, &input[literal_start..]);
+    out
+}
+
+/// LZ 토큰열 → 바이트. 원본 길이가 정확히 맞아야 한다
+pub fn decompress(input: &[u8], expected_len: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(expected_len);
+    let mut i = 0;
+    while i < input.len() {
+        let token = input[i];
+        i += 1;
+        if token & 0x80 == 0 {
+            let n = token as usize + 1;
+            let literals = input.get(i..i + n).ok_or("리터럴이 잘림")?;
+            out.extend_from_slice(literals);
+            i += n;
+        } else {
+            let len = (token & 0x7f) as usize + MIN_MATCH;
+            let dist = input.get(i..i + 2).ok_or("거리가 잘림")?;
+            let dist = u16::from_be_bytes([dist[0], dist[1]]) as usize;
+            i += 2;
+            if dist == 0 || dist > out.len() {
+                return Err(format!("잘못된 거리 {} (출력 {}B)", dist, out.len()));
+            }
+            let start = out.len() - dist;
+            for k in 0..len {
+                out.push(out[start + k]);
+            }
+        }
+        if out.len() > expected_len {
+            return Err(format!("원본 길이 초과: {} > {}", out.len(), expected_len));
+        }
+    }
+    if out.len() != expected_len {
+        return Err(format!("원본 길이 불일치: {} ≠ {}", out.len(), expected_len));
+    }
+    Ok(out)
+}
+
+// ─────────────────────────────────────────────
+// 임계값 · 통계
+// ─────────────────────────────────────────────
+
+/// 이 크기(트릿 바이트) 미만 메시지는 압축하지 않는다
+pub const DEFAULT_THRESHOLD: usize = 64;
+
+/// 송신 압축 통계
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompressionStats {
+    pub frames: u64,
+    pub compressed: u64,
+    /// 임계값 미만이라 건너뜀
+    pub skipped_small: u64,
+    /// 압축해도 줄지 않아 원본 전송
+    pub skipped_incompressible: u64,
+    pub raw_bytes: u64,
+    pub wire_bytes: u64,
+    /// 트릿 압축을 고른 프레임 (방식별, 나머지 압축은 LZ)
+    pub trit_schemes: [u64; 3],
+}
+
+impl CompressionStats {
+    pub fn saved(&self) -> u64 {
+        self.raw_bytes.saturating_sub(self.wire_bytes)
+    }
+
+    /// 전송 바이트 / 원본 바이트
+    pub fn ratio(&self) -> f64 {
+        if self.raw_bytes == 0 { 1.0 } else { self.wire_bytes as f64 / self.raw_bytes as f64 }
+    }
+
+    fn record(&mut self, raw: usize, wire: usize) {
+        self.frames += 1;
+        self.raw_bytes += raw as u64;
+        self.wire_bytes += wire as u64;
+    }
+}
+
+impl std::fmt::Display for CompressionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let trit_coded: u64 = self.trit_schemes.iter().sum();
+        let schemes: Vec<String> = TritScheme::ALL.iter()
+            .filter(|s| self.trit_schemes[**s as usize] > 0)
+            .map(|s| format!("{} {}", s.name(), self.trit_schemes[*s as usize]))
+            .collect();
+        write!(f, "프레임 {} (압축 {} [LZ {}{}] · 작음 {} · 무이득 {}) {}B → {}B, 절감 {}B ({:.0}%)",
+            self.frames, self.compressed, self.compressed - trit_coded,
+            schemes.iter().map(|s| format!(" · {}", s)).collect::<String>(),
+            self.skipped_small, self.skipped_incompressible,
+            self.raw_bytes, self.wire_bytes, self.saved(), (1.0 - self.ratio()) * 100.0)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 프레임 본문 코덱
+// ─────────────────────────────────────────────
+
+/// 연결별 압축 상태 — 협상 결과 · 임계값 · 송신 통계
+#[derive(Debug, Clone)]
+pub struct CtpCodec {
+    pub enabled: bool,
+    pub threshold: usize,
+    pub stats: CompressionStats,
+}
+
+impl Default for CtpCodec {
+    fn default() -> Self {
+        Self { enabled: false, threshold: DEFAULT_THRESHOLD, stats: CompressionStats::default() }
+    }
+}
+
+impl CtpCodec {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, ..Self::default() }
+    }
+
+    /// 합의된 능력 비트로 생성
+    pub fn negotiated(caps: i16) -> Self {
+        Self::new(caps & CAP_COMPRESS != 0)
+    }
+
+    /// 트릿 → (프레임 길이 단어, 본문) — 임계값 미만이거나 줄지 않으면 평문
+    pub fn encode(&mut self, trits: &TritBuffer) -> (u32, Vec<u8>) {
+        let raw = trits.to_bytes();
+        match self.squeeze(trits, &raw) {
+            Some(body) => (FRAME_COMPRESSED | body.len() as u32, body),
+            None => (trits.len() as u32, raw),
+        }
+    }
+
+    /// 압축이 이득일 때만 LZ · 트릿 압축 중 작은 본문 — 통계 기록
+    fn squeeze(&mut self, trits: &TritBuffer, raw: &[u8]) -> Option<Vec<u8>> {
+        if !self.enabled || raw.len() < self.threshold {
+            if self.enabled { self.stats.skipped_small += 1; }
+            self.stats.record(raw.len(), raw.len());
+            return None;
+        }
+        let mut lz = (trits.len() as u32).to_be_bytes().to_vec();
+        lz.extend(compress(raw));
+        let body = std::cmp::min_by_key(lz, trits.compress(), Vec::len);
+        if body.len() >= raw.len() {
+            self.stats.skipped_incompressible += 1;
+            self.stats.record(raw.len(), raw.len());
+            return None;
+        }
+        if let Some(scheme) = trit_scheme(&body) {
+            self.stats.trit_schemes[scheme as usize] += 1;
+        }
+        self.stats.compressed += 1;
+        self.stats.record(raw.len(), body.len());
+        Some(body)
+    }
+}
+
+/// 압축 본문 해석 → scratch — 압축 여부는 프레임 길이 단어로 판단 (협상과 무관하게 받아준다)
+pub fn decode_body(body: &[u8], max_trits: usize, scratch: &mut TritBuffer) -> Result<(), String> {
+    if body.first() == Some(&TRIT_MAGIC) {
+        let count = body.get(2..).and_then(read_u32).ok_or("트릿 압축 머리가 짧음")? as usize;
+        if count > max_trits {
+            return Err(format!("트릿 수 {} 초과", count));
+        }
+        *scratch = TritBuffer::decompress(body)?;
+        return Ok(());
+    }
+    let (head, packed) = body.split_first_chunk::<4>().ok_or("압축 본문이 짧음")?;
+    let count = u32::from_be_bytes(*head) as usize;
+    if count > max_trits {
+        return Err(format!("트릿 수 {} 초과", count));
+    }
+    scratch.decode_from(&decompress(packed, count.div_ceil(4))?, count);
+    Ok(())
+}
+
+fn read_u32(bytes: &[u8]) -> Option<u32> {
+    bytes.get(..4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+// ─────────────────────────────────────────────
+// 트릿 압축 (RLE · 3진 허프만)
+// ─────────────────────────────────────────────
+
+/// 트릿 압축 머리 표시
+pub const TRIT_MAGIC: u8 = 0xC3;
+const TRIT_HEADER: usize = 6;
+const MAX_RUN: usize = 64;
+const TRYTES: usize = 27;
+const MAX_CODE_LEN: u8 = 15;
+
+/// 트릿 압축 방식 — 머리 두 번째 바이트
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TritScheme {
+    Raw = 0,
+    Rle = 1,
+    Huffman = 2,
+}
+
+impl TritScheme {
+    pub const ALL: [TritScheme; 3] = [TritScheme::Raw, TritScheme::Rle, TritScheme::Huffman];
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| *s as u8 == code)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TritScheme::Raw => "raw",
+            TritScheme::Rle => "rle",
+            TritScheme::Huffman => "huffman",
+        }
+    }
+}
+
+/// 압축 결과의 방식 (머리만 확인)
+pub fn trit_scheme(bytes: &[u8]) -> Option<TritScheme> {
+    match bytes {
+        [TRIT_MAGIC, code, ..] => TritScheme::from_code(*code),
+        _ => None,
+    }
+}
+
+/// 지정 방식으로 압축
+pub fn compress_trits(trits: &[NetTrit], scheme: TritScheme) -> Vec<u8> {
+    let mut out = vec![TRIT_MAGIC, scheme as u8];
+    out.extend_from_slice(&(trits.len() as u32).to_be_bytes());
+    match scheme {
+        TritScheme::Raw => out.extend(TritBuffer::from_trits(trits.to_vec()).to_bytes()),
+        TritScheme::Rle => rle_encode(trits, &mut out),
+        TritScheme::Huffman => huffman_encode(trits, &mut out),
+    }
+    out
+}
+
+/// 세 방식 중 가장 작은 결과
+pub fn compress_trits_best(trits: &[NetTrit]) -> Vec<u8> {
+    TritScheme::ALL.into_iter()
+        .map(|s| compress_trits(trits, s))
+        .min_by_key(|packed| packed.len())
+        .unwrap_or_default()
+}
+
+/// 머리의 방식대로 복원
+pub fn decompress_trits(bytes: &[u8]) -> Result<TritBuffer, String> {
+    if bytes.len() < TRIT_HEADER || bytes[0] != TRIT_MAGIC {
+        return Err("트릿 압축 머리 아님".into());
+    }
+    let scheme = TritScheme::from_code(bytes[1]).ok_or_else(|| format!("알 수 없는 압축 방식 {}", bytes[1]))?;
+    let count = read_u32(&bytes[2..]).unwrap_or(0) as usize;
+    let body = &bytes[TRIT_HEADER..];
+    let trits = match scheme {
+        TritScheme::Raw if body.len() == count.div_ceil(4) => TritBuffer::from_bytes(body, count),
+        TritScheme::Raw => return Err("원본 길이 불일치".into()),
+        TritScheme::Rle => rle_decode(body, count)?,
+        TritScheme::Huffman => huffman_decode(body, count)?,
+    };
+    if trits.len() != count {
+        return Err(format!("트릿 수 불일치 ({} ≠ {})", trits.len(), count));
+    }
+    Ok(trits)
+}
+
+fn rle_encode(trits: &[NetTrit], out: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < trits.len() {
+        let t = trits[i];
+        let run = trits[i..].iter().take(MAX_RUN).take_while(|&&x| x == t).count();
+        out.push(t.to_2bit() << 6 | (run - 1) as u8);
+        i += run;
+    }
+}
+
+fn rle_decode(body: &[u8], count: usize) -> Result<TritBuffer, String> {
+    let mut buf = TritBuffer::with_capacity(count);
+    for &b in body {
+        let t = NetTrit::from_2bit(b >> 6).ok_or("RLE 트릿 무효")?;
+        let run = (b & 0x3f) as usize + 1;
+        if buf.len() + run > count {
+            return Err("RLE 길이 초과".into());
+        }
+        buf.trits.extend(std::iter::repeat_n(t, run));
+    }
+    Ok(buf)
+}
+
+/// 3트릿 → 0..27 (마지막 묶음은 O로 채움)
+fn trytes(trits: &[NetTrit]) -> impl Iterator<Item = usize> + '_ {
+    trits.chunks(3).map(|c| {
+        (0..3).fold(0, |acc, i| acc * 3 + (c.get(i).map_or(0, |&t| t as i8) + 1) as usize)
+    })
+}
+
+/// 빈도 → 부호 길이 (최대 MAX_CODE_LEN — 넘으면 빈도를 반으로 줄여 다시)
+fn code_lengths(mut freq: [u64; TRYTES]) -> [u8; TRYTES] {
+    loop {
+        let mut len = [0u8; TRYTES];
+        let mut nodes: Vec<(u64, Vec<usize>)> = freq.iter().enumerate()
+            .filter(|(_, &f)| f > 0)
+            .map(|(s, &f)| (f, vec![s]))
+            .collect();
+        if nodes.len() == 1 {
+            len[nodes[0].1[0]] = 1;
+        }
+        while nodes.len() > 1 {
+            nodes.sort_by_key(|n| std::cmp::Reverse(n.0));
+            let (Some((w1, s1)), Some((w2, s2))) = (nodes.pop(), nodes.pop()) else { break };
+            for &s in s1.iter().chain(&s2) {
+                len[s] += 1;
+            }
+            nodes.push((w1 + w2, [s1, s2].concat()));
+        }
+        if len.iter().all(|&l| l <= MAX_CODE_LEN) {
+            return len;
+        }
+        for f in freq.iter_mut().filter(|f| **f > 0) {
+            *f = (*f / 2).max(1);
+        }
+    }
+}
+
+/// 정규 허프만 부호 — (길이, 기호) 순으로 차례대로
+fn canonical_codes(len: &[u8; TRYTES]) -> [u32; TRYTES] {
+    let mut order: Vec<usize> = (0..TRYTES).filter(|&s| len[s] > 0).collect();
+    order.sort_by_key(|&s| (len[s], s));
+    let mut codes = [0u32; TRYTES];
+    let (mut code, mut prev) = (0u32, 0u8);
+    for s in order {
+        code <<= len[s] - prev;
+        codes[s] = code;
+        code += 1;
+        prev = len[s];
+    }
+    codes
+}
+
+fn huffman_encode(trits: &[NetTrit], out: &mut Vec<u8>) {
+    let mut freq = [0u64; TRYTES];
+    for s in trytes(trits) {
+        freq[s] += 1;
+    }
+    let len = code_lengths(freq);
+    let codes = canonical_codes(&len);
+    // 길이표: 기호당 4bit
+    for pair in len.chunks(2) {
+        out.push(pair[0] << 4 | pair.get(1).copied().unwrap_or(0));
+    }
+    let (mut acc, mut bits) = (0u64, 0u32);
+    for s in trytes(trits) {
+        acc = acc << len[s] | codes[s] as u64;
+        bits += len[s] as u32;
+        while bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+        acc &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push((acc << (8 - bits)) as u8);
+    }
+}
+
+fn huffman_decode(body: &[u8], count: usize) -> Result<TritBuffer, String> {
+    let table_len = TRYTES.div_ceil(2);
+    let table = body.get(..table_len).ok_or("허프만 길이표가 짧음")?;
+    let mut len = [0u8; TRYTES];
+    for (s, l) in len.iter_mut().enumerate() {
+        *l = if s % 2 == 0 { table[s / 2] >> 4 } else { table[s / 2] & 0x0f };
+    }
+    let codes = canonical_codes(&len);
+    let lookup: HashMap<(u8, u32), usize> = (0..TRYTES)
+        .filter(|&s| len[s] > 0)
+        .map(|s| ((len[s], codes[s]), s))
+        .collect();
+
+    let mut buf = TritBuffer::with_capacity(count);
+    let mut bits = body[table_len..].iter().flat_map(|&b| (0..8).rev().map(move |i| (b >> i) & 1));
+    while buf.len() < count {
+        let (mut code, mut l) = (0u32, 0u8);
+        let sym = loop {
+            code = code << 1 | bits.next().ok_or("허프만 비트열이 짧음")? as u32;
+            l += 1;
+            if let Some(&s) = lookup.get(&(l, code)) {
+                break s;
+            }
+            if l >= MAX_CODE_LEN {
+                return Err("허프만 부호 무효".into());
+            }
+        };
+        for d in [9, 3, 1] {
+            if buf.len() < count {
+                buf.push_i8((sym / d % 3) as i8 - 1);
+            }
+        }
+    }
+    Ok(buf)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conformance::Gen;
+    use crate::network::{CtpClient, CtpMessage, MessageType, StatusCode, TritNetAdapter, LOCAL_CAPS};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    fn state_chunk(entries: usize) -> Vec<u8> {
+        (0..entries)
+            .map(|i| format!("account:{:04}=balance:{};trit:P\n", i, 1000 + i % 7))
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_lz_roundtrip_and_corruption() {
+        let mut gen = Gen::new(3);
+        let random: Vec<u8> = (0..2000).map(|_| gen.next_u64() as u8).collect();
+        let cases: Vec<Vec<u8>> = vec![
+            vec![],
+            b"abc".to_vec(),
+            vec![b'T'; 1000], // 거리 1 겹침 복사
+            state_chunk(50),
+            random,
+        ];
+        for input in &cases {
+            let packed = compress(input);
+            assert_eq!(decompress(&packed, input.len()).as_ref(), Ok(input));
+        }
+        assert!(compress(&cases[2]).len() < 40);
+
+        let packed = compress(&cases[3]);
+        assert!(decompress(&packed, cases[3].len() - 1).is_err());
+        assert!(decompress(&packed[..packed.len() - 1], cases[3].len()).is_err());
+        assert!(decompress(&[0x80, 0x00, 0x05], 4).is_err()); // 출력 앞을 가리키는 거리
+    }
+
+    #[test]
+    fn test_codec_thresholds_and_stats() {
+        let mut scratch = TritBuffer::new();
+        let mut codec = CtpCodec::negotiated(LOCAL_CAPS);
+        assert!(codec.enabled);
+
+        // 작은 트릿 메시지 — 평문 본문 그대로
+        let mut vote = TritBuffer::new();
+        vote.push_string("vote:P");
+        assert_eq!(codec.encode(&vote), (vote.len() as u32, vote.to_bytes()));
+
+        // 큰 트릿 메시지 — 반복이 많으면 압축
+        let mut big = TritBuffer::new();
+        for _ in 0..60 { big.push_word6(0); }
+        let (word, body) = codec.encode(&big);
+        assert_ne!(word & FRAME_COMPRESSED, 0);
+        assert_eq!((word & !FRAME_COMPRESSED) as usize, body.len());
+        decode_body(&body, 1 << 16, &mut scratch).unwrap();
+        assert_eq!(scratch.to_trit_string(), big.to_trit_string());
+        assert!(decode_body(&body, 100, &mut scratch).is_err());
+        assert!(decode_body(&body[..body.len() - 1], 1 << 16, &mut scratch).is_err());
+
+        // 무작위 트릿 — LZ는 못 줄이지만 허프만은 2bit 패킹보다 작다
+        let mut gen = Gen::new(9);
+        let mut noise = TritBuffer::new();
+        for _ in 0..1000 { noise.push_i8(gen.range(-1, 1) as i8); }
+        let (word, body) = codec.encode(&noise);
+        assert_ne!(word & FRAME_COMPRESSED, 0);
+        assert_eq!(trit_scheme(&body), Some(TritScheme::Huffman));
+        decode_body(&body, 1 << 16, &mut scratch).unwrap();
+        assert_eq!(scratch.to_trit_string(), noise.to_trit_string());
+        assert!(decode_body(&body, 999, &mut scratch).is_err());
+
+        // 짧은 무작위(무이득) 메시지는 원본 — 허프만 길이표가 이득보다 크다
+        noise.trits.truncate(300);
+        assert_eq!(codec.encode(&noise).0 & FRAME_COMPRESSED, 0);
+
+        let s = &codec.stats;
+        assert_eq!((s.frames, s.compressed, s.skipped_small, s.skipped_incompressible), (4, 2, 1, 1));
+        assert_eq!(s.trit_schemes[TritScheme::Huffman as usize], 1);
+        assert!(s.saved() > 0);
+        assert!(s.to_string().contains("huffman 1"));
+
+        // 협상 안 된 연결 · 높인 임계값은 압축하지 않는다
+        assert_eq!(CtpCodec::new(false).encode(&big).0, big.len() as u32);
+        let mut strict = CtpCodec { threshold: 1 << 16, ..CtpCodec::new(true) };
+        strict.encode(&big);
+        assert_eq!((strict.stats.compressed, strict.stats.skipped_small), (0, 1));
+    }
+
+    #[test]
+    fn test_negotiated_compression_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            TritNetAdapter::serve_framed(&mut stream, TritNetAdapter::reply).unwrap()
+        });
+
+        // connect가 협상 — 양쪽 모두 CAP_COMPRESS
+        let wait = Duration::from_secs(5);
+        let mut client = CtpClient::connect(&addr, wait).unwrap();
+        assert!(client.codec.enabled);
+        let mut big = TritBuffer::new();
+        for _ in 0..60 { big.push_word6(0); }
+        let answer = client.request(CtpMessage::new(MessageType::Info, StatusCode::Neutral, big.clone()), wait).unwrap();
+        assert_eq!(answer.payload.to_trit_string(), big.to_trit_string());
+        assert_eq!(client.codec.stats.compressed, 1);
+        drop(client);
+        assert_eq!(server.join().unwrap(), 2);
+
+        // 구버전 노드 — 능력 단어 없는 협상 요청 → 압축 꺼짐
+        let mut old = TritBuffer::new();
+        old.push_word6(1);
+        old.push_word6(2);
+        let hello = CtpMessage::new(MessageType::Handshake, StatusCode::Neutral, old);
+        let answer = hello.answer_handshake((1, 2));
+        assert_eq!((answer.agreed_version(), answer.agreed_caps()), (Ok(2), 0));
+        assert!(!CtpCodec::negotiated(answer.agreed_caps()).enabled);
+    }
+
+    fn trit_cases() -> Vec<(&'static str, TritBuffer)> {
+        let mut gen = Gen::new(7);
+        let from = |v: Vec<i8>| {
+            let mut b = TritBuffer::new();
+            for t in v { b.push_i8(t); }
+            b
+        };
+        vec![
+            ("빈", TritBuffer::new()),
+            ("하나", from(vec![1])),
+            ("반복", from([vec![0; 500], vec![1; 70], vec![-1; 3]].concat())),
+            // O가 대부분 (희소 페이로드)
+            ("치우침", from((0..900).map(|_| if gen.next_u64().is_multiple_of(8) { gen.range(-1, 1) as i8 } else { 0 }).collect())),
+            ("무작위", from((0..1001).map(|_| gen.range(-1, 1) as i8).collect())),
+        ]
+    }
+
+    #[test]
+    fn test_trit_compression_roundtrip() {
+        for (name, buf) in trit_cases() {
+            for scheme in TritScheme::ALL {
+                let packed = compress_trits(&buf.trits, scheme);
+                assert_eq!(trit_scheme(&packed), Some(scheme));
+                let back = TritBuffer::decompress(&packed).unwrap_or_else(|e| panic!("{} {}: {}", name, scheme.name(), e));
+                assert_eq!(back.to_trit_string(), buf.to_trit_string(), "{} {}", name, scheme.name());
+            }
+        }
+        let cases = trit_cases();
+        let raw = |b: &TritBuffer| compress_trits(&b.trits, TritScheme::Raw).len();
+        // 반복 → RLE, 치우침 → 허프만, 무작위도 허프만 (log2 3 ≈ 1.58bit < 2bit 패킹)
+        assert_eq!(trit_scheme(&cases[2].1.compress()), Some(TritScheme::Rle));
+        assert!(cases[2].1.compress().len() * 8 < raw(&cases[2].1));
+        assert_eq!(trit_scheme(&cases[3].1.compress()), Some(TritScheme::Huffman));
+        assert!(cases[3].1.compress().len() * 2 < raw(&cases[3].1));
+        assert!(cases[4].1.compress().len() < raw(&cases[4].1));
+        assert_eq!(trit_scheme(&TritBuffer::new().compress()), Some(TritScheme::Raw));
+    }
+
+    #[test]
+    fn test_trit_compression_rejects_corruption() {
+        let buf = &trit_cases()[3].1;
+        assert!(TritBuffer::decompress(&[]).is_err());
+        assert!(TritBuffer::decompress(b"\xC3\x09\0\0\0\0").unwrap_err().contains("방식"));
+        let mut packed = compress_trits(&buf.trits, TritScheme::Huffman);
+        packed.truncate(packed.len() - 4);
+        assert!(TritBuffer::decompress(&packed).is_err());
+        let mut rle = compress_trits(&buf.trits, TritScheme::Rle);
+        rle[5] = rle[5].wrapping_sub(1); // 트릿 수 줄임 → 길이 초과
+        assert!(TritBuffer::decompress(&rle).is_err());
+    }
+}
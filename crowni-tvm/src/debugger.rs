@@ -14,9 +14,11 @@
 ///!   - 명령어 흐름 트레이스
 ///!   - 권한 충돌 표시
 ///!   - 실행 프로파일링
+///!   - 소스 줄 단위 실행 (한선어 · 어셈블리 — 명령어의 소스 줄로 만든 소스 맵)
 
 use std::collections::HashMap;
-use crate::vm::{TVM, Instruction, VmError};
+use std::ops::Range;
+use crate::vm::{TVM, Instruction, VmError, VmErrorKind};
 use crate::opcode::{OpcodeAddr, build_opcodes, OpMeta};
 use crate::value::Value;
 
@@ -47,6 +49,40 @@ pub enum DebugEvent {
     Halt { pc: usize, final_stack: Vec<String> },
 }
 
+// ─────────────────────────────────────────────
+// 소스 맵
+// ─────────────────────────────────────────────
+
+/// 명령어 위치 ↔ 소스 줄 — 컴파일러 · 어셈블러가 기록한 `Instruction::line`으로 만든다
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    lines: Vec<Option<usize>>,
+}
+
+impl SourceMap {
+    pub fn from_program(program: &[Instruction]) -> Self {
+        Self { lines: program.iter().map(|i| i.line).collect() }
+    }
+
+    /// pc의 소스 줄 (컴파일러가 덧붙인 명령은 None)
+    pub fn line_of(&self, pc: usize) -> Option<usize> {
+        self.lines.get(pc).copied().flatten()
+    }
+
+    /// 한 줄의 명령어 구간들 — 루프 머리처럼 한 줄이 떨어진 구간 여러 개일 수 있다
+    pub fn ranges(&self, line: usize) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (pc, l) in self.lines.iter().enumerate() {
+            if *l != Some(line) { continue; }
+            match ranges.last_mut() {
+                Some(r) if r.end == pc => r.end = pc + 1,
+                _ => ranges.push(pc..pc + 1),
+            }
+        }
+        ranges
+    }
+}
+
 // ─────────────────────────────────────────────
 // 디버거
 // ─────────────────────────────────────────────
@@ -56,8 +92,10 @@ pub struct TritDebugger {
     vm: TVM,
     program: Vec<Instruction>,
     opcodes: HashMap<OpcodeAddr, OpMeta>,
-    // 브레이크포인트
+    // 브레이크포인트 (계속 유지 — 실행 전에 멈춤)
     breakpoints: Vec<usize>,
+    // 방금 멈춘 BP — 재개할 때 이 명령은 건너뛰지 않고 실행
+    stepping_over: Option<usize>,
     // 실행 트레이스
     trace: Vec<DebugEvent>,
     // 실행 통계
@@ -66,6 +104,11 @@ pub struct TritDebugger {
     max_steps: usize,
     // 설정
     trace_enabled: bool,
+    // 소스 줄 단위 실행
+    source_map: SourceMap,
+    source_lines: Vec<String>,
+    // 한선어 변수 이름 → VM 전역 키 (#슬롯)
+    var_keys: HashMap<String, String>,
 }
 
 impl TritDebugger {
@@ -76,18 +119,49 @@ impl TritDebugger {
             program: program.clone(),
             opcodes,
             breakpoints: Vec::new(),
+            stepping_over: None,
             trace: Vec::new(),
             exec_count: HashMap::new(),
             step_count: 0,
             max_steps: 10000,
             trace_enabled: true,
+            source_map: SourceMap::from_program(&program),
+            source_lines: Vec::new(),
+            var_keys: HashMap::new(),
         }
     }
 
     /// 소스에서 디버거 생성
     pub fn from_source(source: &str) -> Self {
         let program = crate::assembler::assemble(source);
-        Self::new(program)
+        Self::new(program).with_source(source)
+    }
+
+    /// 한선어 소스에서 디버거 생성 — 컴파일 오류면 오류 목록
+    pub fn from_hanseon(source: &str) -> Result<Self, Vec<String>> {
+        let output = crate::hanseon::compile(source);
+        if !output.errors.is_empty() {
+            return Err(output.errors);
+        }
+        let mut dbg = Self::new(output.instructions).with_source(source);
+        dbg.var_keys = output.var_slots.into_iter().map(|(name, slot)| (name, format!("#{}", slot))).collect();
+        Ok(dbg)
+    }
+
+    /// 트레이스 · 목록에 붙일 원본 소스
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source_lines = source.lines().map(str::to_string).collect();
+        self
+    }
+
+    /// 다음에 실행할 명령의 소스 줄
+    pub fn current_line(&self) -> Option<usize> {
+        self.source_map.line_of(self.vm.ip)
+    }
+
+    /// 소스 줄 원문 (1부터)
+    pub fn source_line(&self, line: usize) -> Option<&str> {
+        self.source_lines.get(line.checked_sub(1)?).map(|s| s.trim())
     }
 
     /// 브레이크포인트 설정
@@ -102,30 +176,39 @@ impl TritDebugger {
         self.breakpoints.retain(|&bp| bp != pc);
     }
 
+    /// 소스 줄에 브레이크포인트 — 그 줄의 첫 명령. 명령어 없는 줄이면 None
+    pub fn break_at_line(&mut self, line: usize) -> Option<usize> {
+        let pc = self.source_map.ranges(line).first()?.start;
+        self.set_breakpoint(pc);
+        Some(pc)
+    }
+
     /// 최대 실행 스텝 설정
     pub fn set_max_steps(&mut self, max: usize) {
         self.max_steps = max;
     }
 
-    /// 프로그램 로드
+    /// 프로그램 로드 — 새 VM (다시 실행해도 전역 · 레지스터가 남지 않게)
     pub fn load(&mut self) {
+        self.vm = TVM::new();
         self.vm.load(self.program.clone());
         self.trace.clear();
         self.exec_count.clear();
         self.step_count = 0;
+        self.stepping_over = None;
     }
 
     /// 단계 실행 (1스텝)
     pub fn step(&mut self) -> Result<DebugEvent, VmError> {
         self.step_count += 1;
         if self.step_count > self.max_steps {
-            return Err(VmError::Custom("최대 스텝 초과".into()));
+            return Err(VmError::new(VmErrorKind::Custom("최대 스텝 초과".into()), self.vm.ip));
         }
 
         let ip = self.vm.ip;
 
-        // 프로그램 범위 초과
-        if ip >= self.vm.program.len() {
+        // 프로그램 범위 초과 · 이미 종료
+        if ip >= self.vm.program.len() || self.vm.halted {
             let event = DebugEvent::Halt {
                 pc: ip,
                 final_stack: self.stack_snapshot(),
@@ -138,31 +221,27 @@ impl TritDebugger {
         let addr = inst.addr;
         let name = self.opcodes.get(&addr).map(|m| m.name_kr).unwrap_or("???").to_string();
 
+        // 브레이크포인트 — 실행하지 않고 멈춤, 다음 스텝은 이 명령을 실행하고 지나간다
+        if self.breakpoints.contains(&ip) && self.stepping_over != Some(ip) {
+            self.stepping_over = Some(ip);
+            let event = DebugEvent::Breakpoint {
+                pc: ip,
+                reason: format!("BP@{}: {} {}", ip, name, addr),
+            };
+            if self.trace_enabled { self.trace.push(event.clone()); }
+            return Ok(event);
+        }
+        self.stepping_over = None;
+
         // 실행 전 스택
         let stack_before = self.stack_snapshot();
 
-        // 브레이크포인트 체크 (실행 후 BP 이벤트 반환)
-        let hit_bp = self.breakpoints.contains(&ip);
-        if hit_bp {
-            // BP는 1회만 발동 (무한루프 방지)
-            self.breakpoints.retain(|&b| b != ip);
-        }
-
         // 실행
         match self.vm.step() {
             Ok(continue_run) => {
                 let stack_after = self.stack_snapshot();
                 *self.exec_count.entry(addr).or_insert(0) += 1;
 
-                if hit_bp {
-                    let event = DebugEvent::Breakpoint {
-                        pc: ip,
-                        reason: format!("BP@{}: {} {}", ip, name, addr),
-                    };
-                    if self.trace_enabled { self.trace.push(event.clone()); }
-                    return Ok(event);
-                }
-
                 let event = DebugEvent::Execute {
                     pc: ip,
                     addr,
@@ -193,6 +272,22 @@ impl TritDebugger {
         }
     }
 
+    /// 소스 한 줄 실행 — 다른 줄에 들어서거나 BP · 종료까지
+    /// 컴파일러가 덧붙인 명령(줄 없음)은 줄로 치지 않고 지나간다
+    pub fn step_line(&mut self) -> Result<Vec<DebugEvent>, VmError> {
+        let start = self.current_line();
+        let mut events = Vec::new();
+        loop {
+            let event = self.step()?;
+            let stop = matches!(&event, DebugEvent::Breakpoint { .. } | DebugEvent::Halt { .. }) || self.vm.halted;
+            events.push(event);
+            if stop { break; }
+            let line = self.current_line();
+            if line.is_some() && line != start { break; }
+        }
+        Ok(events)
+    }
+
     /// 브레이크포인트까지 실행
     pub fn run_to_breakpoint(&mut self) -> Vec<DebugEvent> {
         let mut events = Vec::new();
@@ -292,7 +387,19 @@ impl TritDebugger {
     pub fn dump_trace(&self) -> String {
         let mut out = String::new();
         out.push_str("┌── 실행 트레이스 ─────────────┐\n");
+        let mut last_line = None;
         for (i, event) in self.trace.iter().enumerate() {
+            // 소스 줄이 바뀌면 원문 표시
+            if let DebugEvent::Execute { pc, .. } | DebugEvent::Breakpoint { pc, .. } | DebugEvent::Error { pc, .. } = event {
+                let line = self.source_map.line_of(*pc);
+                if line.is_some() && line != last_line {
+                    let n = line.unwrap_or(0);
+                    if let Some(text) = self.source_line(n) {
+                        out.push_str(&format!("│ ── {}행: {}\n", n, text));
+                    }
+                    last_line = line;
+                }
+            }
             match event {
                 DebugEvent::Execute { pc, name, stack_before, stack_after, .. } => {
                     let before_len = stack_before.len();
@@ -318,6 +425,119 @@ impl TritDebugger {
         out
     }
 
+    /// 레지스터 덤프 (R0..R8 — 현재 프레임)
+    pub fn dump_registers(&self) -> String {
+        let mut out = String::new();
+        out.push_str("┌── 레지스터 ───────────────────┐\n");
+        for (i, val) in self.vm.registers.iter().enumerate() {
+            out.push_str(&format!("│ R{} = {}\n", i, val));
+        }
+        out.push_str(&format!("│ 호출 깊이: {}\n", self.vm.call_stack.len()));
+        out.push_str("└──────────────────────────────┘\n");
+        out
+    }
+
+    /// 변수 값 — 한선어 이름이면 슬롯으로, 아니면 전역 이름 그대로
+    pub fn variable(&self, name: &str) -> Option<&Value> {
+        let key = self.var_keys.get(name).map(String::as_str).unwrap_or(name);
+        self.vm.globals.get(key)
+    }
+
+    /// 전역 변수 목록 (이름순, 한선어 슬롯은 이름으로)
+    pub fn dump_variables(&self) -> String {
+        let names: HashMap<&str, &str> = self.var_keys.iter().map(|(n, k)| (k.as_str(), n.as_str())).collect();
+        let mut vars: Vec<(&str, &Value)> = self.vm.globals.iter()
+            .map(|(k, v)| (names.get(k.as_str()).copied().unwrap_or(k), v))
+            .collect();
+        vars.sort_by(|a, b| a.0.cmp(b.0));
+        if vars.is_empty() {
+            return "(변수 없음)\n".into();
+        }
+        vars.iter().map(|(n, v)| format!("{} = {}\n", n, v)).collect()
+    }
+
+    /// 현재 위치 · 상태
+    pub fn info(&self) -> String {
+        let mut out = format!("pc {} / {} · 스텝 {} · 스택 {}", self.vm.ip, self.program.len(), self.step_count, self.vm.stack.len());
+        if self.vm.halted || self.vm.ip >= self.program.len() {
+            out.push_str(" · 종료됨");
+        }
+        if !self.breakpoints.is_empty() {
+            let mut bps = self.breakpoints.clone();
+            bps.sort_unstable();
+            out.push_str(&format!(" · BP {:?}", bps));
+        }
+        out.push('\n');
+        out.push_str(&self.where_line());
+        out
+    }
+
+    /// 다음에 실행할 소스 줄 (`→ N행: 원문`)
+    fn where_line(&self) -> String {
+        match self.current_line() {
+            Some(n) => format!("→ {}행: {}\n", n, self.source_line(n).unwrap_or("")),
+            None => String::new(),
+        }
+    }
+
+    fn describe(event: &DebugEvent) -> String {
+        match event {
+            DebugEvent::Execute { pc, name, stack_after, .. } => {
+                format!("[{}] {} → top {}\n", pc, name, stack_after.last().map(String::as_str).unwrap_or("-"))
+            }
+            DebugEvent::Breakpoint { reason, .. } => format!("● {}\n", reason),
+            DebugEvent::Halt { pc, final_stack } => {
+                format!("■ 종료@{} — 최종값 {}\n", pc, final_stack.last().map(String::as_str).unwrap_or("-"))
+            }
+            DebugEvent::Error { pc, message } => format!("✗ ERR@{}: {}\n", pc, message),
+            _ => String::new(),
+        }
+    }
+
+    /// 대화형 명령 하나 실행 — 출력 텍스트, 종료(q)면 None
+    pub fn execute(&mut self, cmd: DebugCmd) -> Option<String> {
+        let out = match cmd {
+            DebugCmd::Step => match self.step() {
+                Ok(event) => Self::describe(&event) + &self.where_line(),
+                Err(e) => format!("✗ {}\n", e),
+            },
+            DebugCmd::Next => match self.step_line() {
+                Ok(events) => events.last().map(Self::describe).unwrap_or_default() + &self.where_line(),
+                Err(e) => format!("✗ {}\n", e),
+            },
+            DebugCmd::Run | DebugCmd::RunToBp => {
+                if matches!(cmd, DebugCmd::Run) {
+                    self.load();
+                }
+                let events = self.run_to_breakpoint();
+                let last = events.last().map(Self::describe).unwrap_or_default();
+                format!("{}개 명령 실행\n{}{}", events.len(), last, self.where_line())
+            }
+            DebugCmd::Stack => self.dump_stack(),
+            DebugCmd::Regs => self.dump_registers(),
+            DebugCmd::Print(name) if name.is_empty() => self.dump_variables(),
+            DebugCmd::Print(name) => match self.variable(&name) {
+                Some(v) => format!("{} = {}\n", name, v),
+                None => format!("변수 없음: {}\n", name),
+            },
+            DebugCmd::Program => self.dump_program(),
+            DebugCmd::Trace => self.dump_trace(),
+            DebugCmd::Profile => self.profile(),
+            DebugCmd::Break(pc) if pc >= self.program.len() => format!("범위 밖: {} (명령어 {}개)\n", pc, self.program.len()),
+            DebugCmd::Break(pc) => { self.set_breakpoint(pc); format!("● BP@{}\n", pc) }
+            DebugCmd::ClearBreak(pc) => { self.clear_breakpoint(pc); format!("○ BP@{} 해제\n", pc) }
+            DebugCmd::BreakLine(n) => match self.break_at_line(n) {
+                Some(pc) => format!("● {}행 → BP@{}\n", n, pc),
+                None => format!("{}행에 명령어 없음\n", n),
+            },
+            DebugCmd::Info => self.info(),
+            DebugCmd::Help => debug_help().to_string(),
+            DebugCmd::Quit => return None,
+            DebugCmd::Unknown => "알 수 없는 명령 — h로 도움말\n".to_string(),
+        };
+        Some(out)
+    }
+
     /// 최종 결과
     pub fn result_value(&self) -> Option<i64> {
         self.vm.stack.last().and_then(|v| v.as_int())
@@ -336,14 +556,18 @@ impl TritDebugger {
 /// 디버그 명령
 pub enum DebugCmd {
     Step,               // s: 한 단계
+    Next,               // n: 소스 한 줄
     Run,                // r: 끝까지 실행
     RunToBp,            // c: 브레이크포인트까지
     Stack,              // stack: 스택 덤프
+    Regs,               // regs: 레지스터
+    Print(String),      // p NAME: 변수 값 (이름 없으면 전체)
     Program,            // prog: 프로그램 보기
     Trace,              // trace: 트레이스
     Profile,            // prof: 프로파일
     Break(usize),       // b N: 브레이크포인트 설정
     ClearBreak(usize),  // cb N: 해제
+    BreakLine(usize),   // bl N: 소스 줄 브레이크포인트
     Info,               // info: 상태 정보
     Quit,               // q: 종료
     Help,               // h: 도움말
@@ -356,13 +580,16 @@ pub fn parse_debug_cmd(input: &str) -> DebugCmd {
     let parts: Vec<&str> = input.split_whitespace().collect();
     match parts.first().map(|s| *s) {
         Some("s") | Some("step") | Some("단계") => DebugCmd::Step,
+        Some("n") | Some("next") | Some("다음") => DebugCmd::Next,
         Some("r") | Some("run") | Some("실행") => DebugCmd::Run,
         Some("c") | Some("continue") | Some("계속") => DebugCmd::RunToBp,
         Some("stack") | Some("스택") => DebugCmd::Stack,
+        Some("regs") | Some("레지스터") => DebugCmd::Regs,
+        Some("p") | Some("print") | Some("변수") => DebugCmd::Print(parts.get(1).unwrap_or(&"").to_string()),
         Some("prog") | Some("program") | Some("프로그램") => DebugCmd::Program,
         Some("trace") | Some("트레이스") => DebugCmd::Trace,
         Some("prof") | Some("profile") | Some("프로파일") => DebugCmd::Profile,
-        Some("b") | Some("break") | Some("중단점") => {
+        Some("b") | Some("bp") | Some("break") | Some("중단점") => {
             let n = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
             DebugCmd::Break(n)
         }
@@ -370,6 +597,10 @@ pub fn parse_debug_cmd(input: &str) -> DebugCmd {
             let n = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
             DebugCmd::ClearBreak(n)
         }
+        Some("bl") | Some("line") | Some("줄") => {
+            let n = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+            DebugCmd::BreakLine(n)
+        }
         Some("info") | Some("정보") => DebugCmd::Info,
         Some("q") | Some("quit") | Some("종료") => DebugCmd::Quit,
         Some("h") | Some("help") | Some("도움") => DebugCmd::Help,
@@ -382,14 +613,18 @@ pub fn debug_help() -> &'static str {
     concat!(
         "┌── Trit Debugger 명령어 ───────┐\n",
         "│ s/step/단계     1스텝 실행      │\n",
+        "│ n/next/다음     소스 한 줄 실행   │\n",
         "│ r/run/실행      전체 실행        │\n",
         "│ c/continue/계속 BP까지 실행      │\n",
         "│ stack/스택      스택 덤프         │\n",
+        "│ regs/레지스터   레지스터 R0..R8    │\n",
+        "│ p/print [이름]  변수 값 (전체)     │\n",
         "│ prog/프로그램   프로그램 보기      │\n",
         "│ trace/트레이스  실행 트레이스      │\n",
         "│ prof/프로파일   실행 통계         │\n",
-        "│ b N/중단점 N    브레이크포인트     │\n",
+        "│ b/bp N/중단점 N 브레이크포인트     │\n",
         "│ cb N/해제 N     BP 해제          │\n",
+        "│ bl N/줄 N       소스 N행에 BP     │\n",
         "│ info/정보       상태 정보         │\n",
         "│ q/quit/종료     디버거 종료       │\n",
         "│ h/help/도움     이 도움말         │\n",
@@ -460,4 +695,111 @@ mod tests {
         let trace = dbg.dump_trace();
         assert!(trace.contains("넣어"));
     }
+
+    const LOOP_SRC: &str = "변수 i = 0\n동안 i < 2 {\n  i 보여줘\n  변수 i = i + 1\n}\n";
+
+    #[test]
+    fn test_source_map_ranges() {
+        let dbg = TritDebugger::from_hanseon(LOOP_SRC).unwrap();
+        let map = &dbg.source_map;
+        assert_eq!((1..=5).filter(|l| !map.ranges(*l).is_empty()).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        // 동안 줄: 조건 구간 + 루프 끝 되돌이 점프
+        assert_eq!(map.ranges(2), vec![2..6, 12..13]);
+        assert_eq!(map.ranges(3), vec![6..8]);
+        assert_eq!(map.line_of(13), None); // 덧붙인 종료
+        assert!(TritDebugger::from_hanseon("함수 {").is_err());
+    }
+
+    #[test]
+    fn test_step_line() {
+        let mut dbg = TritDebugger::from_hanseon(LOOP_SRC).unwrap();
+        dbg.load();
+        let mut visited = vec![dbg.current_line().unwrap()];
+        loop {
+            let events = dbg.step_line().unwrap();
+            if events.iter().any(|e| matches!(e, DebugEvent::Halt { .. })) { break; }
+            match dbg.current_line() {
+                Some(line) => visited.push(line),
+                None => break,
+            }
+        }
+        assert_eq!(visited, vec![1, 2, 3, 4, 2, 3, 4, 2]);
+    }
+
+    #[test]
+    fn test_break_at_line_and_annotated_trace() {
+        let mut dbg = TritDebugger::from_hanseon(LOOP_SRC).unwrap();
+        dbg.load();
+        assert_eq!(dbg.break_at_line(4), Some(8));
+        assert_eq!(dbg.break_at_line(5), None); // '}'만 있는 줄
+        let events = dbg.run_to_breakpoint();
+        assert!(matches!(events.last(), Some(DebugEvent::Breakpoint { pc: 8, .. })));
+        assert_eq!(dbg.current_line(), Some(4));
+
+        let trace = dbg.dump_trace();
+        assert!(trace.contains("── 3행: i 보여줘"), "{}", trace);
+        assert!(trace.contains("── 4행: 변수 i = i + 1"), "{}", trace);
+    }
+
+    #[test]
+    fn test_parse_interactive_cmds() {
+        assert!(matches!(parse_debug_cmd("n"), DebugCmd::Next));
+        assert!(matches!(parse_debug_cmd("bp 3"), DebugCmd::Break(3)));
+        assert!(matches!(parse_debug_cmd("bl 12"), DebugCmd::BreakLine(12)));
+        assert!(matches!(parse_debug_cmd("regs"), DebugCmd::Regs));
+        assert!(matches!(parse_debug_cmd("p i"), DebugCmd::Print(n) if n == "i"));
+        assert!(matches!(parse_debug_cmd("print"), DebugCmd::Print(n) if n.is_empty()));
+        assert!(debug_help().contains("regs"));
+    }
+
+    #[test]
+    fn test_execute_session() {
+        let mut dbg = TritDebugger::from_hanseon(LOOP_SRC).unwrap();
+        dbg.load();
+        let mut run = |line: &str| dbg.execute(parse_debug_cmd(line));
+
+        assert_eq!(run("bl 4").unwrap(), "● 4행 → BP@8\n");
+        let out = run("c").unwrap();
+        assert!(out.contains("● BP@8"), "{}", out);
+        assert_eq!(run("p i").unwrap(), "i = 0\n");
+        assert_eq!(run("p 없음").unwrap(), "변수 없음: 없음\n");
+        assert!(run("regs").unwrap().contains("R8 = "));
+        assert!(run("n").unwrap().ends_with("→ 2행: 동안 i < 2 {\n"));
+        assert!(run("bp 99").unwrap().starts_with("범위 밖"));
+
+        // r: 처음부터 다시 — 전역이 남지 않고, BP는 남아 반복마다 멈춘다
+        assert!(run("r").unwrap().contains("● BP@8"));
+        assert_eq!(run("p i").unwrap(), "i = 0\n");
+        assert!(run("c").unwrap().contains("● BP@8"));
+        assert_eq!(run("p i").unwrap(), "i = 1\n");
+        let out = run("c").unwrap();
+        assert!(out.contains("■ 종료"), "{}", out);
+        assert_eq!(run("p").unwrap(), "i = 2\n");
+        assert!(run("q").is_none());
+    }
+
+    #[test]
+    fn test_breakpoint_persists_in_loop() {
+        let mut dbg = TritDebugger::from_hanseon(LOOP_SRC).unwrap();
+        dbg.load();
+        assert_eq!(dbg.break_at_line(3), Some(6));
+
+        // 반복마다 실행 전에 멈춤 — 멈춘 명령은 아직 실행되지 않았다
+        for i in 0..2 {
+            let events = dbg.run_to_breakpoint();
+            assert!(matches!(events.last(), Some(DebugEvent::Breakpoint { pc: 6, .. })), "{:?}", events.last());
+            assert_eq!(dbg.current_line(), Some(3));
+            assert_eq!(dbg.variable("i").and_then(|v| v.as_int()), Some(i));
+        }
+        let events = dbg.run_to_breakpoint();
+        assert!(matches!(events.last(), Some(DebugEvent::Halt { .. })));
+
+        // 단계 실행도 BP에서 멈추고, 다음 스텝은 그 명령을 실행한다
+        dbg.load();
+        dbg.set_breakpoint(0);
+        assert!(matches!(dbg.step().unwrap(), DebugEvent::Breakpoint { pc: 0, .. }));
+        assert!(matches!(dbg.step().unwrap(), DebugEvent::Execute { pc: 0, .. }));
+        dbg.clear_breakpoint(6);
+        assert!(dbg.run_to_breakpoint().iter().all(|e| !matches!(e, DebugEvent::Breakpoint { .. })));
+    }
 }
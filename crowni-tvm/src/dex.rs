@@ -6,6 +6,10 @@
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::output::{JsonObject, say};
+use crate::trit_store::{StoreValue, TritStore};
+use crate::query::{Page, Query, Queryable};
+use crate::ring_log::RingLog;
 
 fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
 
@@ -151,7 +155,7 @@ impl LiquidityPool {
         self.swap_count += 1;
 
         Ok(SwapResult {
-            pool_id: self.id.clone(),
+            pool_id: self.id.clone(), trader: String::new(),
             token_in: self.token_a.clone(), token_out: self.token_b.clone(),
             amount_in, amount_out: amount_out as u64, fee,
             price_impact, trit: if price_impact < 0.01 { 1 } else if price_impact < 0.05 { 0 } else { -1 },
@@ -183,7 +187,7 @@ impl LiquidityPool {
         self.swap_count += 1;
 
         Ok(SwapResult {
-            pool_id: self.id.clone(),
+            pool_id: self.id.clone(), trader: String::new(),
             token_in: self.token_b.clone(), token_out: self.token_a.clone(),
             amount_in, amount_out: amount_out as u64, fee,
             price_impact, trit: if price_impact < 0.01 { 1 } else if price_impact < 0.05 { 0 } else { -1 },
@@ -234,6 +238,8 @@ impl std::fmt::Display for LiquidityPool {
 #[derive(Debug, Clone)]
 pub struct SwapResult {
     pub pool_id: String,
+    /// 거래자 (CrownyDEX::swap이 채운다, 풀 직접 스왑은 빈 문자열)
+    pub trader: String,
     pub token_in: String,
     pub token_out: String,
     pub amount_in: u64,
@@ -245,6 +251,24 @@ pub struct SwapResult {
     pub timestamp: u64,
 }
 
+impl SwapResult {
+    /// 안정 필드 순서 JSON (schema: crowny.swap_result)
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.swap_result")
+            .trit("state", self.trit)
+            .str("pool_id", &self.pool_id)
+            .str("trader", &self.trader)
+            .str("token_in", &self.token_in)
+            .str("token_out", &self.token_out)
+            .int("amount_in", self.amount_in as i64)
+            .int("amount_out", self.amount_out as i64)
+            .int("fee", self.fee as i64)
+            .float("price_impact", self.price_impact)
+            .str("hash", &self.hash)
+            .int("timestamp", self.timestamp as i64)
+    }
+}
+
 impl std::fmt::Display for SwapResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let trit = match self.trit { 1 => "P", -1 => "T", _ => "O" };
@@ -269,6 +293,21 @@ pub struct LPReceipt {
     pub timestamp: u64,
 }
 
+impl LPReceipt {
+    /// 안정 필드 순서 JSON (schema: crowny.lp_receipt)
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.lp_receipt")
+            .trit("state", self.trit)
+            .str("pool_id", &self.pool_id)
+            .str("provider", &self.provider)
+            .str("action", if self.action == LPAction::Add { "add" } else { "remove" })
+            .int("amount_a", self.amount_a as i64)
+            .int("amount_b", self.amount_b as i64)
+            .int("shares", self.shares_minted as i64)
+            .int("timestamp", self.timestamp as i64)
+    }
+}
+
 impl std::fmt::Display for LPReceipt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let act = if self.action == LPAction::Add { "추가" } else { "제거" };
@@ -277,6 +316,12 @@ impl std::fmt::Display for LPReceipt {
     }
 }
 
+impl Queryable for SwapResult {
+    fn timestamp(&self) -> u64 { self.timestamp }
+    fn trit(&self) -> i8 { self.trit }
+    fn involves(&self, account: &str) -> bool { self.trader == account }
+}
+
 // ═══════════════════════════════════════
 // 오더북 (리밋 주문)
 // ═══════════════════════════════════════
@@ -314,32 +359,205 @@ impl std::fmt::Display for LimitOrder {
     }
 }
 
+impl LimitOrder {
+    /// 아직 체결될 수 있는 주문 (대기 · 부분체결)
+    pub fn is_live(&self) -> bool {
+        matches!(self.status, OrderStatus::Open | OrderStatus::PartialFill)
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.amount.saturating_sub(self.filled)
+    }
+
+    fn apply_fill(&mut self, fill: u64) {
+        self.filled += fill;
+        self.trit = 1;
+        self.settle_status();
+    }
+
+    /// 체결량으로 상태 재계산 (취소 주문은 그대로)
+    fn settle_status(&mut self) {
+        if self.status == OrderStatus::Cancelled {
+            return;
+        }
+        self.status = if self.filled >= self.amount {
+            OrderStatus::Filled
+        } else if self.filled > 0 {
+            OrderStatus::PartialFill
+        } else {
+            OrderStatus::Open
+        };
+    }
+
+    fn to_store(&self) -> StoreValue {
+        let side = if self.side == OrderSide::Buy { "buy" } else { "sell" };
+        let status = match self.status {
+            OrderStatus::Open => "open", OrderStatus::Filled => "filled",
+            OrderStatus::PartialFill => "partial", OrderStatus::Cancelled => "cancelled",
+        };
+        StoreValue::Map(HashMap::from([
+            ("id".to_string(), StoreValue::Text(self.id.clone())),
+            ("owner".to_string(), StoreValue::Text(self.owner.clone())),
+            ("pool".to_string(), StoreValue::Text(self.pool_id.clone())),
+            ("side".to_string(), StoreValue::Text(side.into())),
+            ("price".to_string(), StoreValue::Float(self.price)),
+            ("amount".to_string(), StoreValue::Int(self.amount as i64)),
+            ("filled".to_string(), StoreValue::Int(self.filled as i64)),
+            ("status".to_string(), StoreValue::Text(status.into())),
+            ("trit".to_string(), StoreValue::Trit(self.trit)),
+            ("created".to_string(), StoreValue::Int(self.created_at as i64)),
+        ]))
+    }
+
+    fn from_store(value: &StoreValue) -> Option<Self> {
+        let StoreValue::Map(m) = value else { return None };
+        Some(Self {
+            id: store_text(m, "id")?,
+            owner: store_text(m, "owner")?,
+            pool_id: store_text(m, "pool")?,
+            side: match store_text(m, "side")?.as_str() { "buy" => OrderSide::Buy, "sell" => OrderSide::Sell, _ => return None },
+            price: match m.get("price")? { StoreValue::Float(p) => *p, _ => return None },
+            amount: store_u64(m, "amount")?,
+            filled: store_u64(m, "filled")?,
+            status: match store_text(m, "status")?.as_str() {
+                "open" => OrderStatus::Open, "filled" => OrderStatus::Filled,
+                "partial" => OrderStatus::PartialFill, "cancelled" => OrderStatus::Cancelled,
+                _ => return None,
+            },
+            trit: match m.get("trit")? { StoreValue::Trit(t) => *t, _ => return None },
+            created_at: store_u64(m, "created")?,
+        })
+    }
+}
+
+fn store_text(m: &HashMap<String, StoreValue>, key: &str) -> Option<String> {
+    match m.get(key)? { StoreValue::Text(s) => Some(s.clone()), _ => None }
+}
+
+fn store_u64(m: &HashMap<String, StoreValue>, key: &str) -> Option<u64> {
+    match m.get(key)? { StoreValue::Int(n) if *n >= 0 => Some(*n as u64), _ => None }
+}
+
+/// 체결 기록 — 복구 시 부분체결 수량의 기준
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderFill {
+    pub seq: u64,
+    pub pool_id: String,
+    pub buy_id: String,
+    pub sell_id: String,
+    pub price: f64,
+    pub amount: u64,
+    pub timestamp: u64,
+}
+
+impl OrderFill {
+    fn to_store(&self) -> StoreValue {
+        StoreValue::Map(HashMap::from([
+            ("seq".to_string(), StoreValue::Int(self.seq as i64)),
+            ("pool".to_string(), StoreValue::Text(self.pool_id.clone())),
+            ("buy".to_string(), StoreValue::Text(self.buy_id.clone())),
+            ("sell".to_string(), StoreValue::Text(self.sell_id.clone())),
+            ("price".to_string(), StoreValue::Float(self.price)),
+            ("amount".to_string(), StoreValue::Int(self.amount as i64)),
+            ("ts".to_string(), StoreValue::Int(self.timestamp as i64)),
+        ]))
+    }
+
+    fn from_store(value: &StoreValue) -> Option<Self> {
+        let StoreValue::Map(m) = value else { return None };
+        Some(Self {
+            seq: store_u64(m, "seq")?,
+            pool_id: store_text(m, "pool")?,
+            buy_id: store_text(m, "buy")?,
+            sell_id: store_text(m, "sell")?,
+            price: match m.get("price")? { StoreValue::Float(p) => *p, _ => return None },
+            amount: store_u64(m, "amount")?,
+            timestamp: store_u64(m, "ts")?,
+        })
+    }
+}
+
+/// 재시작 복구 결과
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecoveryReport {
+    pub orders: usize,
+    /// 복구 후에도 체결 가능한 주문 (대기 · 부분체결)
+    pub live: usize,
+    pub fills: usize,
+    /// 체결 기록과 맞지 않아 수량/상태를 고친 주문 ID
+    pub reconciled: Vec<String>,
+    /// 읽을 수 없는 레코드 · 주문이 없는 체결 기록
+    pub skipped: usize,
+}
+
+impl std::fmt::Display for RecoveryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let trit = if self.skipped > 0 { "T" } else if !self.reconciled.is_empty() { "O" } else { "P" };
+        write!(f, "[{}] 주문 {} (체결 가능 {}) · 체결 기록 {} · 보정 {} · 건너뜀 {}",
+            trit, self.orders, self.live, self.fills, self.reconciled.len(), self.skipped)
+    }
+}
+
+const ORDER_KEY: &str = "order:";
+const FILL_KEY: &str = "fill:";
+const COUNTER_KEY: &str = "meta:order_counter";
+
 pub struct OrderBook {
     pub orders: Vec<LimitOrder>,
     pub order_counter: u64,
+    pub trades: Vec<OrderFill>,
+    /// 상태 변경마다 먼저 기록하는 저장소 (None이면 메모리 전용)
+    journal: Option<TritStore>,
 }
 
 impl OrderBook {
-    pub fn new() -> Self { Self { orders: Vec::new(), order_counter: 0 } }
+    pub fn new() -> Self { Self { orders: Vec::new(), order_counter: 0, trades: Vec::new(), journal: None } }
+
+    /// TritStore WAL에 기록하는 오더북
+    pub fn persistent() -> Self {
+        Self { journal: Some(TritStore::new()), ..Self::new() }
+    }
+
+    pub fn journal(&self) -> Option<&TritStore> {
+        self.journal.as_ref()
+    }
+
+    /// 한 번의 상태 변경을 하나의 저장소 트랜잭션으로 기록
+    fn write_ahead(&mut self, records: Vec<(String, StoreValue, i8)>) {
+        let Some(store) = self.journal.as_mut() else { return };
+        store.begin();
+        for (key, value, trit) in records {
+            store.set(&key, value);
+            store.set_trit_state(&key, trit);
+        }
+        store.commit();
+    }
+
+    fn order_record(order: &LimitOrder) -> (String, StoreValue, i8) {
+        (format!("{}{}", ORDER_KEY, order.id), order.to_store(), order.trit)
+    }
 
     pub fn place_order(&mut self, owner: &str, pool_id: &str, side: OrderSide, price: f64, amount: u64) -> &LimitOrder {
         let id = format!("ORD-{}", self.order_counter);
         self.order_counter += 1;
-        self.orders.push(LimitOrder {
+        let order = LimitOrder {
             id, owner: owner.into(), pool_id: pool_id.into(),
             side, price, amount, filled: 0,
             status: OrderStatus::Open, trit: 0, created_at: now_ms(),
-        });
+        };
+        let counter = (COUNTER_KEY.to_string(), StoreValue::Int(self.order_counter as i64), 1);
+        self.write_ahead(vec![Self::order_record(&order), counter]);
+        self.orders.push(order);
         self.orders.last().unwrap()
     }
 
     pub fn match_orders(&mut self, pool_id: &str) -> Vec<(usize, usize, u64)> {
         let mut matches = Vec::new();
         let buys: Vec<usize> = self.orders.iter().enumerate()
-            .filter(|(_, o)| o.pool_id == pool_id && o.side == OrderSide::Buy && o.status == OrderStatus::Open)
+            .filter(|(_, o)| o.pool_id == pool_id && o.side == OrderSide::Buy && o.is_live())
             .map(|(i, _)| i).collect();
         let sells: Vec<usize> = self.orders.iter().enumerate()
-            .filter(|(_, o)| o.pool_id == pool_id && o.side == OrderSide::Sell && o.status == OrderStatus::Open)
+            .filter(|(_, o)| o.pool_id == pool_id && o.side == OrderSide::Sell && o.is_live())
             .map(|(i, _)| i).collect();
 
         for &bi in &buys {
@@ -347,18 +565,26 @@ impl OrderBook {
                 let buy_price = self.orders[bi].price;
                 let sell_price = self.orders[si].price;
                 if buy_price >= sell_price {
-                    let buy_remaining = self.orders[bi].amount - self.orders[bi].filled;
-                    let sell_remaining = self.orders[si].amount - self.orders[si].filled;
-                    let fill = buy_remaining.min(sell_remaining);
+                    let fill = self.orders[bi].remaining().min(self.orders[si].remaining());
                     if fill > 0 {
-                        self.orders[bi].filled += fill;
-                        self.orders[si].filled += fill;
-                        self.orders[bi].trit = 1;
-                        self.orders[si].trit = 1;
-                        if self.orders[bi].filled >= self.orders[bi].amount { self.orders[bi].status = OrderStatus::Filled; }
-                        else { self.orders[bi].status = OrderStatus::PartialFill; }
-                        if self.orders[si].filled >= self.orders[si].amount { self.orders[si].status = OrderStatus::Filled; }
-                        else { self.orders[si].status = OrderStatus::PartialFill; }
+                        let mut buy = self.orders[bi].clone();
+                        let mut sell = self.orders[si].clone();
+                        buy.apply_fill(fill);
+                        sell.apply_fill(fill);
+                        let trade = OrderFill {
+                            seq: self.trades.len() as u64 + 1, pool_id: pool_id.into(),
+                            buy_id: buy.id.clone(), sell_id: sell.id.clone(),
+                            price: sell_price, amount: fill, timestamp: now_ms(),
+                        };
+                        // 체결 기록 + 양쪽 주문을 먼저 기록하고 메모리에 반영
+                        self.write_ahead(vec![
+                            (format!("{}{:08}", FILL_KEY, trade.seq), trade.to_store(), 1),
+                            Self::order_record(&buy),
+                            Self::order_record(&sell),
+                        ]);
+                        self.orders[bi] = buy;
+                        self.orders[si] = sell;
+                        self.trades.push(trade);
                         matches.push((bi, si, fill));
                     }
                 }
@@ -368,14 +594,81 @@ impl OrderBook {
     }
 
     pub fn cancel(&mut self, order_idx: usize) {
-        if let Some(o) = self.orders.get_mut(order_idx) {
-            o.status = OrderStatus::Cancelled;
-            o.trit = -1;
-        }
+        let Some(o) = self.orders.get(order_idx) else { return };
+        let mut cancelled = o.clone();
+        cancelled.status = OrderStatus::Cancelled;
+        cancelled.trit = -1;
+        self.write_ahead(vec![Self::order_record(&cancelled)]);
+        self.orders[order_idx] = cancelled;
     }
 
     pub fn open_orders(&self, pool_id: &str) -> Vec<&LimitOrder> {
-        self.orders.iter().filter(|o| o.pool_id == pool_id && o.status == OrderStatus::Open).collect()
+        self.orders.iter().filter(|o| o.pool_id == pool_id && o.is_live()).collect()
+    }
+
+    /// 저장소에서 오더북 재구성 — 체결 기록을 기준으로 부분체결 수량을 맞춘다
+    /// 보정한 주문은 저장소에도 다시 기록한다
+    pub fn recover(mut store: TritStore) -> (Self, RecoveryReport) {
+        let mut report = RecoveryReport::default();
+        let mut orders = Vec::new();
+        let mut trades = Vec::new();
+        let mut counter = 0;
+        let keys: Vec<String> = store.keys().into_iter().cloned().collect();
+        for key in keys {
+            let value = store.get(&key).cloned();
+            match value {
+                Some(v) if key.starts_with(ORDER_KEY) => match LimitOrder::from_store(&v) {
+                    Some(o) => orders.push(o),
+                    None => report.skipped += 1,
+                },
+                Some(v) if key.starts_with(FILL_KEY) => match OrderFill::from_store(&v) {
+                    Some(f) => trades.push(f),
+                    None => report.skipped += 1,
+                },
+                Some(StoreValue::Int(n)) if key == COUNTER_KEY => counter = n.max(0) as u64,
+                _ => {}
+            }
+        }
+        let seq_of = |id: &str| id.trim_start_matches("ORD-").parse::<u64>().unwrap_or(u64::MAX);
+        orders.sort_by_key(|o| seq_of(&o.id));
+        trades.sort_by_key(|t| t.seq);
+
+        let mut filled: HashMap<String, u64> = HashMap::new();
+        for t in &trades {
+            if !orders.iter().any(|o| o.id == t.buy_id) || !orders.iter().any(|o| o.id == t.sell_id) {
+                report.skipped += 1;
+                continue;
+            }
+            *filled.entry(t.buy_id.clone()).or_insert(0) += t.amount;
+            *filled.entry(t.sell_id.clone()).or_insert(0) += t.amount;
+        }
+        for o in &mut orders {
+            let actual = filled.get(&o.id).copied().unwrap_or(0).min(o.amount);
+            if o.filled != actual {
+                o.filled = actual;
+                if o.status != OrderStatus::Cancelled {
+                    o.trit = if actual > 0 { 1 } else { 0 };
+                }
+                o.settle_status();
+                report.reconciled.push(o.id.clone());
+            }
+        }
+
+        let max_seen = orders.iter().map(|o| seq_of(&o.id)).filter(|n| *n != u64::MAX).max();
+        let order_counter = counter.max(max_seen.map_or(0, |n| n + 1));
+        report.orders = orders.len();
+        report.live = orders.iter().filter(|o| o.is_live()).count();
+        report.fills = trades.len();
+
+        let mut book = Self { orders, order_counter, trades, journal: Some(store) };
+        let fixes: Vec<_> = book.orders.iter()
+            .filter(|o| report.reconciled.contains(&o.id))
+            .map(Self::order_record)
+            .collect();
+        if !fixes.is_empty() {
+            book.write_ahead(fixes);
+        }
+        (book, report)
     }
 }
 
@@ -388,18 +681,39 @@ pub struct CrownyDEX {
     pub tokens: HashMap<String, Token>,
     pub balances: HashMap<String, HashMap<String, u64>>,  // user → token → amount
     pub order_book: OrderBook,
-    pub swap_history: Vec<SwapResult>,
+    /// 스왑 기록 (상한 — 오래된 것부터 축출)
+    pub swap_history: RingLog<SwapResult>,
     pub lp_history: Vec<LPReceipt>,
     pub total_volume: u64,
     pub total_fees: u64,
 }
 
+/// [fees] 재적재 — 운영 설정의 풀 수수료를 모든 풀에 적용 (다음 스왑부터)
+impl crate::config::Reloadable for CrownyDEX {
+    fn apply_config(&mut self, config: &crate::config::RuntimeConfig) {
+        for pool in self.pools.values_mut() {
+            pool.fee_bps = config.dex_fee_bps;
+        }
+    }
+}
+
+impl crate::account::AccountLedger for CrownyDEX {
+    fn ledger_name(&self) -> &str { "dex" }
+    fn holdings_of(&self, name: &str) -> Vec<(String, u64)> {
+        let mut out: Vec<(String, u64)> = self.balances.get(name)
+            .map(|m| m.iter().map(|(t, n)| (t.clone(), *n)).collect())
+            .unwrap_or_default();
+        out.extend(self.pools.values().filter_map(|p| p.lp_holders.get(name).map(|s| (format!("lp:{}", p.id), *s))));
+        out
+    }
+}
+
 impl CrownyDEX {
     pub fn new() -> Self {
         let mut dex = Self {
             pools: HashMap::new(), tokens: HashMap::new(),
-            balances: HashMap::new(), order_book: OrderBook::new(),
-            swap_history: Vec::new(), lp_history: Vec::new(),
+            balances: HashMap::new(), order_book: OrderBook::persistent(),
+            swap_history: RingLog::default(), lp_history: Vec::new(),
             total_volume: 0, total_fees: 0,
         };
         // 기본 토큰
@@ -423,6 +737,26 @@ impl CrownyDEX {
         self.balances.get(user).and_then(|m| m.get(token)).copied().unwrap_or(0)
     }
 
+    /// 계정 간 이동 (금고 예치 · 환불 등)
+    pub fn transfer(&mut self, from: &str, to: &str, token: &str, amount: u64) -> Result<(), String> {
+        let bal = self.balance(from, token);
+        if bal < amount { return Err(format!("{} 잔액 부족 ({})", token, bal)); }
+        *self.balances.get_mut(from).unwrap().get_mut(token).unwrap() -= amount;
+        *self.balances.entry(to.into()).or_default().entry(token.into()).or_insert(0) += amount;
+        Ok(())
+    }
+
+    /// 소각 — 잔액과 총 발행량을 함께 줄인다
+    pub fn burn(&mut self, user: &str, token: &str, amount: u64) -> Result<(), String> {
+        let bal = self.balance(user, token);
+        if bal < amount { return Err(format!("{} 잔액 부족 ({})", token, bal)); }
+        *self.balances.get_mut(user).unwrap().get_mut(token).unwrap() -= amount;
+        if let Some(t) = self.tokens.get_mut(token) {
+            t.total_supply = t.total_supply.saturating_sub(amount);
+        }
+        Ok(())
+    }
+
     pub fn create_pool(&mut self, token_a: &str, token_b: &str, fee_bps: u64) -> String {
         let pool = LiquidityPool::new(token_a, token_b, fee_bps);
         let id = pool.id.clone();
@@ -448,6 +782,9 @@ impl CrownyDEX {
 
     pub fn swap(&mut self, user: &str, pool_id: &str, token_in: &str, amount_in: u64) -> Result<SwapResult, String> {
         let pool = self.pools.get(pool_id).ok_or("풀 없음")?;
+        if token_in != pool.token_a && token_in != pool.token_b {
+            return Err(format!("{} 풀에 없는 토큰: {}", pool_id, token_in));
+        }
         let is_a_to_b = token_in == pool.token_a;
         let token_out = if is_a_to_b { pool.token_b.clone() } else { pool.token_a.clone() };
 
@@ -457,11 +794,12 @@ impl CrownyDEX {
         // 차감
         *self.balances.get_mut(user).unwrap().get_mut(token_in).unwrap() -= amount_in;
 
-        let result = if is_a_to_b {
+        let mut result = if is_a_to_b {
             self.pools.get_mut(pool_id).unwrap().swap_a_to_b(amount_in)?
         } else {
             self.pools.get_mut(pool_id).unwrap().swap_b_to_a(amount_in)?
         };
+        result.trader = user.into();
 
         // 지급
         *self.balances.entry(user.into()).or_default().entry(token_out).or_insert(0) += result.amount_out;
@@ -472,6 +810,18 @@ impl CrownyDEX {
         Ok(result)
     }
 
+    /// 견적 — 풀 사본에서 스왑해 보고 상태는 바꾸지 않는다
+    pub fn quote(&self, pool_id: &str, token_in: &str, amount_in: u64) -> Result<SwapResult, String> {
+        let mut pool = self.pools.get(pool_id).ok_or("풀 없음")?.clone();
+        if token_in == pool.token_a {
+            pool.swap_a_to_b(amount_in)
+        } else if token_in == pool.token_b {
+            pool.swap_b_to_a(amount_in)
+        } else {
+            Err(format!("{} 풀에 없는 토큰: {}", pool_id, token_in))
+        }
+    }
+
     pub fn place_order(&mut self, user: &str, pool_id: &str, side: OrderSide, price: f64, amount: u64) -> String {
         let order = self.order_book.place_order(user, pool_id, side, price, amount);
         order.id.clone()
@@ -481,6 +831,18 @@ impl CrownyDEX {
         self.order_book.match_orders(pool_id)
     }
 
+    /// 스왑 기록 조회 (계정 = 거래자)
+    pub fn swaps(&self, q: &Query) -> Page<SwapResult> {
+        self.swap_history.page(q, |i, _| i)
+    }
+
+    /// 재시작 — 오더북 저장소(WAL 재생본)에서 주문을 다시 올린다
+    pub fn restore_order_book(&mut self, store: TritStore) -> RecoveryReport {
+        let (book, report) = OrderBook::recover(store);
+        self.order_book = book;
+        report
+    }
+
     pub fn summary(&self) -> String {
         let mut lines = Vec::new();
         lines.push(format!("CrownyDEX"));
@@ -496,22 +858,22 @@ impl CrownyDEX {
 
 // ═══ 데모 ═══
 
-pub fn demo_dex() {
-    println!("╔═══════════════════════════════════════════════╗");
-    println!("║  Crowny DEX — 3진 탈중앙 거래소                ║");
-    println!("║  AMM · 유동성 풀 · 오더북 · 스왑 · LP 보상      ║");
-    println!("╚═══════════════════════════════════════════════╝");
-    println!();
+pub fn demo_dex() -> i8 {
+    say!("╔═══════════════════════════════════════════════╗");
+    say!("║  Crowny DEX — 3진 탈중앙 거래소                ║");
+    say!("║  AMM · 유동성 풀 · 오더북 · 스왑 · LP 보상      ║");
+    say!("╚═══════════════════════════════════════════════╝");
+    say!();
 
     let mut dex = CrownyDEX::new();
 
     // 1. 토큰 등록
-    println!("━━━ 1. 등록 토큰 ━━━");
-    for token in dex.tokens.values() { println!("  {}", token); }
-    println!();
+    say!("━━━ 1. 등록 토큰 ━━━");
+    for token in dex.tokens.values() { say!("  {}", token); }
+    say!();
 
     // 2. 사용자 잔액 배정
-    println!("━━━ 2. 초기 잔액 ━━━");
+    say!("━━━ 2. 초기 잔액 ━━━");
     let users = vec![
         ("alice", vec![("CRWN", 500_000), ("USDT", 100_000), ("ETH", 50), ("TRIT", 10_000)]),
         ("bob", vec![("CRWN", 300_000), ("USDT", 80_000), ("BTC", 2), ("TRIT", 5_000)]),
@@ -522,44 +884,44 @@ pub fn demo_dex() {
             dex.mint(user, token, *amount);
         }
         let bals: Vec<String> = tokens.iter().map(|(t, a)| format!("{} {}", a, t)).collect();
-        println!("  {} — {}", user, bals.join(", "));
+        say!("  {} — {}", user, bals.join(", "));
     }
-    println!();
+    say!();
 
     // 3. 유동성 풀 생성
-    println!("━━━ 3. 유동성 풀 ━━━");
+    say!("━━━ 3. 유동성 풀 ━━━");
     let pool_crwn_usdt = dex.create_pool("CRWN", "USDT", 30);
     let pool_crwn_eth = dex.create_pool("CRWN", "ETH", 30);
     let pool_crwn_trit = dex.create_pool("CRWN", "TRIT", 50);
 
     // CRWN-USDT 풀에 유동성 추가
     let r = dex.add_liquidity("alice", &pool_crwn_usdt, 200_000, 25_000).unwrap();
-    println!("  {}", r);
+    say!("  {}", r);
     let r = dex.add_liquidity("bob", &pool_crwn_usdt, 100_000, 12_500).unwrap();
-    println!("  {}", r);
+    say!("  {}", r);
 
     // CRWN-ETH 풀
     let r = dex.add_liquidity("alice", &pool_crwn_eth, 100_000, 10).unwrap();
-    println!("  {}", r);
+    say!("  {}", r);
     let r = dex.add_liquidity("carol", &pool_crwn_eth, 80_000, 8).unwrap();
-    println!("  {}", r);
+    say!("  {}", r);
 
     // CRWN-TRIT 풀
     let r = dex.add_liquidity("alice", &pool_crwn_trit, 50_000, 5_000).unwrap();
-    println!("  {}", r);
-    println!();
+    say!("  {}", r);
+    say!();
 
     // 풀 현황
-    println!("━━━ 4. 풀 현황 ━━━");
+    say!("━━━ 4. 풀 현황 ━━━");
     for pool in dex.pools.values() {
-        println!("  {}", pool);
-        println!("    TVL: {} + {} | LP: {} shares | LP 수: {}",
+        say!("  {}", pool);
+        say!("    TVL: {} + {} | LP: {} shares | LP 수: {}",
             pool.reserve_a, pool.reserve_b, pool.total_lp_shares, pool.lp_holders.len());
     }
-    println!();
+    say!();
 
     // 5. 스왑 실행
-    println!("━━━ 5. 스왑 거래 ━━━");
+    say!("━━━ 5. 스왑 거래 ━━━");
     let swaps = vec![
         ("alice", "CRWN-USDT", "CRWN", 10_000),
         ("bob", "CRWN-USDT", "USDT", 5_000),
@@ -572,65 +934,123 @@ pub fn demo_dex() {
     ];
     for (user, pool_id, token_in, amount) in &swaps {
         match dex.swap(user, pool_id, token_in, *amount) {
-            Ok(r) => println!("  {} — {}", user, r),
-            Err(e) => println!("  [T] {} — {}", user, e),
+            Ok(r) => say!("  {} — {}", user, r),
+            Err(e) => say!("  [T] {} — {}", user, e),
         }
     }
-    println!();
+    say!();
 
     // 6. 오더북
-    println!("━━━ 6. 리밋 주문 ━━━");
+    say!("━━━ 6. 리밋 주문 ━━━");
     dex.place_order("alice", "CRWN-USDT", OrderSide::Buy, 0.130, 5_000);
     dex.place_order("alice", "CRWN-USDT", OrderSide::Buy, 0.128, 3_000);
     dex.place_order("bob", "CRWN-USDT", OrderSide::Sell, 0.125, 4_000);
     dex.place_order("bob", "CRWN-USDT", OrderSide::Sell, 0.132, 2_000);
     dex.place_order("carol", "CRWN-USDT", OrderSide::Buy, 0.126, 6_000);
 
-    println!("  대기 주문:");
+    say!("  대기 주문:");
     for order in &dex.order_book.orders {
-        println!("    {} — {}", order.owner, order);
+        say!("    {} — {}", order.owner, order);
     }
 
     let matches = dex.match_orders("CRWN-USDT");
-    println!("  매칭 결과: {} 체결", matches.len());
+    say!("  매칭 결과: {} 체결", matches.len());
     for (bi, si, fill) in &matches {
-        println!("    매수#{} ↔ 매도#{} — {} 체결",
+        say!("    매수#{} ↔ 매도#{} — {} 체결",
             dex.order_book.orders[*bi].id, dex.order_book.orders[*si].id, fill);
     }
 
-    println!("  주문 상태:");
+    say!("  주문 상태:");
     for order in &dex.order_book.orders {
-        println!("    {} — {}", order.owner, order);
+        say!("    {} — {}", order.owner, order);
     }
-    println!();
+
+    // 재시작: WAL을 재생해 오더북 복구
+    if let Some(journal) = dex.order_book.journal() {
+        let wal = journal.wal_entries();
+        let mut restarted = CrownyDEX::new();
+        let report = restarted.restore_order_book(TritStore::replay(&wal));
+        say!("  재시작 복구 (WAL {}건): {}", wal.len(), report);
+        for order in restarted.order_book.orders.iter().filter(|o| o.is_live()) {
+            say!("    {} — {}", order.owner, order);
+        }
+    }
+    say!();
 
     // 7. 최종 잔액
-    println!("━━━ 7. 최종 잔액 ━━━");
+    say!("━━━ 7. 최종 잔액 ━━━");
     for (user, _) in &users {
         let bals = dex.balances.get(*user).unwrap();
         let parts: Vec<String> = bals.iter()
             .filter(|(_, v)| **v > 0)
             .map(|(t, v)| format!("{} {}", v, t))
             .collect();
-        println!("  {} — {}", user, parts.join(", "));
+        say!("  {} — {}", user, parts.join(", "));
     }
-    println!();
+    say!();
 
     // 8. 풀 최종 상태
-    println!("━━━ 8. 풀 최종 상태 ━━━");
+    say!("━━━ 8. 풀 최종 상태 ━━━");
     for pool in dex.pools.values() {
         let price = pool.price_a_in_b();
         let apr = pool.estimated_apr(0.124, 1.0);
-        println!("  {} | 가격: {:.6} | 수수료: {} | APR: {:.1}%",
+        say!("  {} | 가격: {:.6} | 수수료: {} | APR: {:.1}%",
             pool.id, price, pool.fees_collected, apr);
     }
-    println!();
-
-    // 9. DEX 요약
-    println!("━━━ 9. DEX 요약 ━━━");
-    println!("{}", dex.summary());
-    println!();
-    println!("✓ Crowny DEX 데모 완료");
+    say!();
+
+    // 9. 레버리지 포지션 · 청산 (TWAP 오라클 + 커널 태스크)
+    say!("━━━ 9. 레버리지 · 청산 ━━━");
+    {
+        use crate::margin::{schedule_liquidations, MarginConfig, MarginEngine, PositionSide};
+        use std::sync::{Arc, Mutex};
+        let mut engine = MarginEngine::new(MarginConfig::default(), 60_000);
+        engine.sync_dex(&dex, 0);
+        dex.mint("trader", "USDT", 2_000);
+        engine.deposit(&mut dex, "trader", "USDT", 2_000).ok();
+        for (side, leverage) in [(PositionSide::Long, 10.0), (PositionSide::Short, 3.0)] {
+            match engine.open_position("trader", "CRWN-USDT", side, 1_000, leverage, 0) {
+                Ok(id) => say!("  {}", engine.positions[id as usize - 1]),
+                Err(e) => say!("  [T] {}", e),
+            }
+        }
+        let price = dex.pools["CRWN-USDT"].price_a_in_b();
+        dex.mint("whale", "CRWN", 10_000);
+        if let Ok(r) = dex.swap("whale", "CRWN-USDT", "CRWN", 10_000) {
+            say!("  whale 대량 매도 — {}", r);
+        }
+        engine.sync_dex(&dex, 1);
+        say!("  가격 {:.6} → {:.6} (TWAP {:.6})", price, dex.pools["CRWN-USDT"].price_a_in_b(),
+            engine.oracle.twap("CRWN-USDT", 120_000).unwrap_or(0.0));
+        let engine = Arc::new(Mutex::new(engine));
+        let mut kernel = crate::kernel::CrownyKernel::boot(Default::default());
+        match schedule_liquidations(&mut kernel, &engine, 120_000) {
+            Ok(events) if events.is_empty() => say!("  [P] 청산 검사 — 이상 없음"),
+            Ok(events) => events.iter().for_each(|e| say!("  {}", e)),
+            Err(e) => say!("  [T] {}", e),
+        }
+        let fund = engine.lock().map(|e| e.insurance.clone()).unwrap_or_default();
+        say!("  보험 기금: {} USDT (적립 {} · 지급 {} · 미충당 {})",
+            fund.balance("USDT"), fund.collected, fund.paid, fund.uncovered);
+    }
+    say!();
+
+    // 10. DEX 요약
+    say!("━━━ 10. DEX 요약 ━━━");
+    say!("{}", dex.summary());
+    say!();
+    say!("✓ Crowny DEX 데모 완료");
+
+    if crate::output::is_json() {
+        JsonObject::new()
+            .str("command", "dex")
+            .trit("state", 1)
+            .int("total_volume", dex.total_volume as i64)
+            .int("total_fees", dex.total_fees as i64)
+            .objects("swaps", dex.swap_history.iter().map(|r| r.to_json()).collect())
+            .emit();
+    }
+    1
 }
 
 // ═══ 테스트 ═══
@@ -717,6 +1137,20 @@ mod tests {
         assert!(dex.balance("alice", "USDT") > 10_000);
     }
 
+    #[test]
+    fn test_dex_quote_matches_swap() {
+        let mut dex = CrownyDEX::new();
+        dex.mint("alice", "CRWN", 100_000);
+        dex.mint("alice", "USDT", 20_000);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("alice", &pool, 50_000, 10_000).unwrap();
+        let quote = dex.quote(&pool, "USDT", 500).unwrap();
+        assert_eq!(dex.pools[&pool].swap_count, 0);
+        assert_eq!(dex.swap("alice", &pool, "USDT", 500).unwrap().amount_out, quote.amount_out);
+        assert!(dex.quote(&pool, "ETH", 1).is_err());
+        assert!(dex.swap("alice", &pool, "ETH", 1).is_err());
+    }
+
     #[test]
     fn test_dex_insufficient_balance() {
         let mut dex = CrownyDEX::new();
@@ -757,6 +1191,74 @@ mod tests {
         assert_eq!(ob.orders[0].trit, -1);
     }
 
+    #[test]
+    fn test_order_book_recovery() {
+        let mut ob = OrderBook::persistent();
+        ob.place_order("buyer", "A-B", OrderSide::Buy, 1.0, 100);
+        ob.place_order("seller", "A-B", OrderSide::Sell, 0.9, 60);
+        ob.place_order("other", "A-B", OrderSide::Buy, 0.5, 10);
+        ob.match_orders("A-B");
+        ob.cancel(2);
+
+        // 재시작: WAL만 남은 상태에서 재구성
+        let (mut back, report) = OrderBook::recover(TritStore::replay(&ob.journal().unwrap().wal_entries()));
+        assert_eq!((report.orders, report.live, report.fills, report.skipped), (3, 1, 1, 0));
+        assert!(report.reconciled.is_empty());
+        assert_eq!(back.orders[0].status, OrderStatus::PartialFill);
+        assert_eq!(back.orders[0].filled, 60);
+        assert_eq!(back.orders[2].status, OrderStatus::Cancelled);
+        assert_eq!(back.order_counter, 3);
+
+        // 부분체결 주문은 복구 후에도 계속 체결된다
+        back.place_order("seller2", "A-B", OrderSide::Sell, 1.0, 50);
+        assert_eq!(back.orders[3].id, "ORD-3");
+        assert_eq!(back.match_orders("A-B"), vec![(0, 3, 40)]);
+        assert_eq!(back.orders[0].status, OrderStatus::Filled);
+    }
+
+    #[test]
+    fn test_order_book_restores_from_disk() {
+        let dir = std::env::temp_dir().join(format!("crowny-orderbook-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        {
+            let mut dex = CrownyDEX::new();
+            dex.restore_order_book(TritStore::open(&dir).unwrap());
+            dex.place_order("buyer", "A-B", OrderSide::Buy, 1.0, 100);
+            dex.place_order("seller", "A-B", OrderSide::Sell, 0.9, 60);
+            dex.match_orders("A-B");
+        }
+        // 서버 재시작 — 같은 디렉터리를 다시 열어 복구
+        let mut dex = CrownyDEX::new();
+        let report = dex.restore_order_book(TritStore::open(&dir).unwrap());
+        assert_eq!((report.orders, report.live, report.fills), (2, 1, 1));
+        assert_eq!(dex.order_book.orders[0].filled, 60);
+        assert_eq!(dex.place_order("seller2", "A-B", OrderSide::Sell, 1.0, 40), "ORD-2");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_order_book_reconcile_against_fills() {
+        let mut ob = OrderBook::persistent();
+        ob.place_order("buyer", "A-B", OrderSide::Buy, 1.0, 100);
+        ob.place_order("seller", "A-B", OrderSide::Sell, 0.9, 30);
+        ob.match_orders("A-B");
+        let mut store = TritStore::replay(&ob.journal().unwrap().wal_entries());
+        // 주문 레코드가 체결 전 상태로 남은 경우 (체결 기록이 기준)
+        let mut stale = ob.orders[0].clone();
+        stale.filled = 0;
+        stale.status = OrderStatus::Open;
+        store.set("order:ORD-0", stale.to_store());
+        store.set("order:broken", StoreValue::Int(1));
+
+        let (back, report) = OrderBook::recover(store);
+        assert_eq!(report.reconciled, vec!["ORD-0".to_string()]);
+        assert_eq!(report.skipped, 1);
+        assert_eq!((back.orders[0].filled, back.orders[0].status.clone()), (30, OrderStatus::PartialFill));
+        // 보정 결과도 저장소에 다시 기록
+        let (_, again) = OrderBook::recover(TritStore::replay(&back.journal().unwrap().wal_entries()));
+        assert!(again.reconciled.is_empty());
+    }
+
     #[test]
     fn test_dex_summary() {
         let dex = CrownyDEX::new();
@@ -0,0 +1,477 @@
+// ═══════════════════════════════════════════════════════════════
+// 컨트랙트 이벤트 로그 — 블록별 블룸 색인 · 필터 질의 · 구독
+//
+//   로그 = (블록, 블록 내 순번, 컨트랙트 주소, 토픽[이벤트명, 호출자], 데이터)
+//   블록마다 2048비트 블룸 (주소 · 토픽 각각 3비트) — 질의 시 블룸이
+//   필터와 맞지 않는 블록은 로그를 열어보지 않고 건너뛴다
+//
+//   필터 (eth_getLogs 의미):
+//     fromBlock/toBlock  — 기본 latest · "earliest"/"latest"/"0x10"/16
+//     address            — 주소 하나 또는 목록 (OR)
+//     topics             — 위치별 조건, null = 아무거나, 목록 = OR, 위치끼리 AND
+//   구독: 만든 이후의 새 로그만 — 폴링(eth_getFilterChanges) 또는 푸시 알림
+// ═══════════════════════════════════════════════════════════════
+
+use std::collections::BTreeMap;
+
+use crate::contract_vm::CEvent;
+use crate::output::{JsonObject, JsonValue};
+
+/// 한 번의 질의로 훑을 수 있는 최대 블록 수
+pub const MAX_BLOCK_RANGE: u64 = 10_000;
+/// 한 번의 질의로 돌려줄 수 있는 최대 로그 수
+pub const MAX_RESULTS: usize = 10_000;
+
+/// 0x 접두 16진 수량
+pub fn quantity(n: u64) -> String {
+    format!("0x{:x}", n)
+}
+
+// ─────────────────────────────────────────────
+// 로그
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// 색인 전체 순번 (구독 커서)
+    pub seq: u64,
+    pub block: u64,
+    pub log_index: u32,
+    pub contract: String,
+    pub event: String,
+    pub caller: String,
+    pub data: Vec<i64>,
+    pub timestamp: u64,
+}
+
+impl LogEntry {
+    /// topic0 = 이벤트명, topic1 = 호출자
+    pub fn topics(&self) -> [&str; 2] {
+        [&self.event, &self.caller]
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let topics: Vec<String> = self.topics().iter().map(|t| t.to_string()).collect();
+        let data: Vec<String> = self.data.iter().map(|v| v.to_string()).collect();
+        JsonObject::new()
+            .str("address", &self.contract)
+            .str("blockNumber", &quantity(self.block))
+            .str("logIndex", &quantity(self.log_index as u64))
+            .strs("topics", &topics)
+            .raw("data", crate::output::array(data))
+            .str("event", &self.event)
+            .int("timestamp", self.timestamp as i64)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 블룸 필터
+// ─────────────────────────────────────────────
+
+const BLOOM_BITS: usize = 2048;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bloom([u64; BLOOM_BITS / 64]);
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom([0; BLOOM_BITS / 64])
+    }
+}
+
+impl Bloom {
+    /// 항목 하나 → 비트 3개 (64비트 해시를 11비트씩)
+    fn positions(item: &str) -> [usize; 3] {
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        for b in item.bytes() {
+            h ^= b as u64;
+            h = h.wrapping_mul(0x0100_0000_01b3);
+        }
+        h ^= h >> 29;
+        h = h.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        [0, 1, 2].map(|i| ((h >> (i * 11)) as usize) % BLOOM_BITS)
+    }
+
+    pub fn add(&mut self, item: &str) {
+        for p in Self::positions(item) {
+            self.0[p / 64] |= 1 << (p % 64);
+        }
+    }
+
+    /// false면 확실히 없음, true면 있을 수도 있음
+    pub fn may_contain(&self, item: &str) -> bool {
+        Self::positions(item).iter().all(|p| self.0[p / 64] & (1 << (p % 64)) != 0)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 필터
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogFilter {
+    /// None = 최신 블록
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+    pub addresses: Vec<String>,
+    /// 위치별 OR 목록 (None = 아무거나)
+    pub topics: Vec<Option<Vec<String>>>,
+}
+
+impl LogFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn blocks(mut self, from: u64, to: u64) -> Self {
+        self.from_block = Some(from);
+        self.to_block = Some(to);
+        self
+    }
+
+    /// 위치 i의 토픽 조건 (목록은 OR)
+    pub fn topic(mut self, position: usize, any_of: &[&str]) -> Self {
+        if self.topics.len() <= position {
+            self.topics.resize(position + 1, None);
+        }
+        self.topics[position] = Some(any_of.iter().map(|t| t.to_string()).collect());
+        self
+    }
+
+    pub fn matches(&self, log: &LogEntry) -> bool {
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.contract) {
+            return false;
+        }
+        let topics = log.topics();
+        self.topics.iter().enumerate().all(|(i, want)| match want {
+            None => true,
+            Some(any) => topics.get(i).is_some_and(|t| any.iter().any(|w| w == t)),
+        })
+    }
+
+    /// 블록 블룸으로 미리 거르기 — 조건 목록마다 하나라도 있을 수 있어야 함
+    pub fn may_match(&self, bloom: &Bloom) -> bool {
+        let any = |items: &[String]| items.is_empty() || items.iter().any(|i| bloom.may_contain(i));
+        any(&self.addresses) && self.topics.iter().flatten().all(|t| any(t))
+    }
+
+    /// JSON 필터 객체 해석 — latest는 현재 최신 블록
+    pub fn from_json(v: &JsonValue, latest: u64) -> Result<Self, String> {
+        if !matches!(v, JsonValue::Object(_)) {
+            return Err("필터는 객체여야 함".into());
+        }
+        let block = |key: &str| -> Result<Option<u64>, String> {
+            match v.get(key) {
+                None | Some(JsonValue::Null) => Ok(None),
+                Some(b) => parse_block(b, latest).map(Some),
+            }
+        };
+        let strings = |v: &JsonValue, what: &str| -> Result<Vec<String>, String> {
+            match v {
+                JsonValue::Str(s) => Ok(vec![s.clone()]),
+                JsonValue::Array(items) => items.iter()
+                    .map(|i| i.as_str().map(String::from).ok_or_else(|| format!("{}는 문자열이어야 함", what)))
+                    .collect(),
+                _ => Err(format!("{}는 문자열 또는 목록", what)),
+            }
+        };
+        let addresses = match v.get("address") {
+            None | Some(JsonValue::Null) => Vec::new(),
+            Some(a) => strings(a, "address")?,
+        };
+        let topics = match v.get("topics") {
+            None | Some(JsonValue::Null) => Vec::new(),
+            Some(JsonValue::Array(ts)) => ts.iter()
+                .map(|t| if t.is_null() { Ok(None) } else { strings(t, "topic").map(Some) })
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err("topics는 목록이어야 함".into()),
+        };
+        Ok(Self { from_block: block("fromBlock")?, to_block: block("toBlock")?, addresses, topics })
+    }
+}
+
+/// "latest" · "earliest" · "pending" · "0x1f" · 31
+pub fn parse_block(v: &JsonValue, latest: u64) -> Result<u64, String> {
+    match v {
+        JsonValue::Str(s) => match s.as_str() {
+            "latest" | "pending" | "safe" | "finalized" => Ok(latest),
+            "earliest" => Ok(0),
+            hex => hex.strip_prefix("0x").and_then(|h| u64::from_str_radix(h, 16).ok())
+                .ok_or_else(|| format!("잘못된 블록 번호 '{}'", hex)),
+        },
+        JsonValue::Num(_) => v.as_i64().filter(|n| *n >= 0).map(|n| n as u64)
+            .ok_or_else(|| "블록 번호는 0 이상의 정수".to_string()),
+        _ => Err("블록 번호는 문자열 또는 정수".into()),
+    }
+}
+
+// ─────────────────────────────────────────────
+// 색인
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
+struct BlockLogs {
+    bloom: Bloom,
+    logs: Vec<LogEntry>,
+}
+
+/// 질의 결과 + 블룸 효과
+#[derive(Debug, Clone, Default)]
+pub struct LogPage {
+    pub logs: Vec<LogEntry>,
+    pub blocks_scanned: u64,
+    pub blocks_skipped: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct LogIndex {
+    blocks: BTreeMap<u64, BlockLogs>,
+    next_seq: u64,
+    latest: u64,
+}
+
+impl LogIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 실행된 이벤트 기록 → 로그
+    pub fn append(&mut self, block: u64, contract: &str, caller: &str, event: &CEvent) -> &LogEntry {
+        self.next_seq += 1;
+        self.latest = self.latest.max(block);
+        let entry = self.blocks.entry(block).or_default();
+        let log = LogEntry {
+            seq: self.next_seq,
+            block,
+            log_index: entry.logs.len() as u32,
+            contract: contract.to_string(),
+            event: event.name.clone(),
+            caller: caller.to_string(),
+            data: event.data.clone(),
+            timestamp: event.ts,
+        };
+        entry.bloom.add(&log.contract);
+        for t in log.topics() {
+            entry.bloom.add(t);
+        }
+        entry.logs.push(log);
+        entry.logs.last().expect("방금 추가")
+    }
+
+    /// 마지막 로그 순번 (구독 시작 커서)
+    pub fn last_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    fn scan(&self, filter: &LogFilter, from: u64, to: u64, after_seq: u64) -> Result<LogPage, String> {
+        let mut page = LogPage::default();
+        for (_, block) in self.blocks.range(from..=to) {
+            if block.logs.last().is_none_or(|l| l.seq <= after_seq) {
+                continue;
+            }
+            if !filter.may_match(&block.bloom) {
+                page.blocks_skipped += 1;
+                continue;
+            }
+            page.blocks_scanned += 1;
+            for log in block.logs.iter().filter(|l| l.seq > after_seq && filter.matches(l)) {
+                if page.logs.len() == MAX_RESULTS {
+                    return Err(format!("결과가 {}개를 넘음 — 블록 범위를 줄이세요", MAX_RESULTS));
+                }
+                page.logs.push(log.clone());
+            }
+        }
+        Ok(page)
+    }
+
+    /// eth_getLogs — 범위 · 주소 · 토픽 필터
+    pub fn query(&self, filter: &LogFilter) -> Result<LogPage, String> {
+        let to = filter.to_block.unwrap_or(self.latest);
+        let from = filter.from_block.unwrap_or(self.latest);
+        if from > to {
+            return Err(format!("fromBlock {} > toBlock {}", from, to));
+        }
+        if to - from >= MAX_BLOCK_RANGE {
+            return Err(format!("블록 범위는 최대 {}개", MAX_BLOCK_RANGE));
+        }
+        self.scan(filter, from, to, 0)
+    }
+
+    /// 커서 이후의 새 로그 (구독) — 범위가 없으면 전체 블록
+    pub fn since(&self, filter: &LogFilter, after_seq: u64) -> LogPage {
+        let from = filter.from_block.unwrap_or(0);
+        let to = filter.to_block.unwrap_or(u64::MAX);
+        if from > to {
+            return LogPage::default();
+        }
+        self.scan(filter, from, to, after_seq).unwrap_or_default()
+    }
+}
+
+// ─────────────────────────────────────────────
+// 구독
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionKind {
+    /// eth_newFilter — 클라이언트가 eth_getFilterChanges로 가져감
+    Poll,
+    /// eth_subscribe — 전송 계층이 알림을 밀어줌
+    Push,
+}
+
+#[derive(Debug, Clone)]
+struct Subscription {
+    kind: SubscriptionKind,
+    filter: LogFilter,
+    cursor: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct LogSubscriptions {
+    subs: BTreeMap<u64, Subscription>,
+    next_id: u64,
+}
+
+fn parse_id(id: &str) -> Option<u64> {
+    id.strip_prefix("0x").and_then(|h| u64::from_str_radix(h, 16).ok())
+}
+
+impl LogSubscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 구독 등록 — 지금 이후의 로그만 받는다
+    pub fn add(&mut self, kind: SubscriptionKind, filter: LogFilter, index: &LogIndex) -> String {
+        self.next_id += 1;
+        self.subs.insert(self.next_id, Subscription { kind, filter, cursor: index.last_seq() });
+        quantity(self.next_id)
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        parse_id(id).is_some_and(|n| self.subs.remove(&n).is_some())
+    }
+
+    /// 폴링 구독의 새 로그 — 커서 전진
+    pub fn changes(&mut self, id: &str, index: &LogIndex) -> Result<Vec<LogEntry>, String> {
+        let sub = parse_id(id).and_then(|n| self.subs.get_mut(&n))
+            .filter(|s| s.kind == SubscriptionKind::Poll)
+            .ok_or_else(|| format!("필터 없음: {}", id))?;
+        let logs = index.since(&sub.filter, sub.cursor).logs;
+        sub.cursor = index.last_seq();
+        Ok(logs)
+    }
+
+    /// 푸시 구독 알림 (eth_subscription 형식) — 전송 계층이 그대로 보낸다
+    pub fn notifications(&mut self, index: &LogIndex) -> Vec<String> {
+        let mut out = Vec::new();
+        for (id, sub) in self.subs.iter_mut().filter(|(_, s)| s.kind == SubscriptionKind::Push) {
+            for log in index.since(&sub.filter, sub.cursor).logs {
+                let params = JsonObject::new().str("subscription", &quantity(*id)).object("result", log.to_json());
+                out.push(JsonObject::new()
+                    .str("jsonrpc", "2.0")
+                    .str("method", "eth_subscription")
+                    .object("params", params)
+                    .build());
+            }
+            sub.cursor = index.last_seq();
+        }
+        out
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ev(name: &str, data: &[i64]) -> CEvent {
+        CEvent { name: name.into(), data: data.to_vec(), ts: 0 }
+    }
+
+    /// 블록 1..=200 — 토큰 컨트랙트 Transfer, 가끔 alice
+    fn sample() -> LogIndex {
+        let mut idx = LogIndex::new();
+        for block in 1..=200 {
+            let caller = if block % 50 == 0 { "alice" } else { "bob" };
+            idx.append(block, "0tTOKEN", caller, &ev("Transfer", &[block as i64]));
+            if block % 10 == 0 {
+                idx.append(block, "0tVOTE", "carol", &ev("TritVote", &[1]));
+            }
+        }
+        idx
+    }
+
+    #[test]
+    fn test_bloom_skips_blocks_without_matches() {
+        let idx = sample();
+        let mut bloom = Bloom::default();
+        assert!(!bloom.may_contain("Transfer"));
+        bloom.add("Transfer");
+        assert!(bloom.may_contain("Transfer") && !bloom.may_contain("Approval"));
+
+        // alice의 Transfer, 블록 1~200
+        let filter = LogFilter::new().blocks(1, 200).topic(0, &["Transfer"]).topic(1, &["alice"]);
+        let page = idx.query(&filter).unwrap();
+        let blocks: Vec<u64> = page.logs.iter().map(|l| l.block).collect();
+        assert_eq!(blocks, vec![50, 100, 150, 200]);
+        assert_eq!(page.blocks_scanned + page.blocks_skipped, 200);
+        assert!(page.blocks_skipped > 180, "블룸이 대부분 건너뜀: {:?}", page.blocks_skipped);
+
+        // 주소 OR · 위치 null
+        let votes = LogFilter { addresses: vec!["0tVOTE".into(), "0tNONE".into()], ..LogFilter::new().blocks(95, 125) };
+        let votes = idx.query(&votes).unwrap();
+        assert_eq!(votes.logs.iter().map(|l| (l.block, l.log_index)).collect::<Vec<_>>(), vec![(100, 1), (110, 1), (120, 1)]);
+        let any_event = LogFilter { topics: vec![None, Some(vec!["carol".into()])], ..LogFilter::new().blocks(1, 30) };
+        assert_eq!(idx.query(&any_event).unwrap().logs.len(), 3);
+
+        // 기본 범위 = 최신 블록 · 잘못된 범위
+        assert_eq!(idx.query(&LogFilter::new()).unwrap().logs.len(), 2);
+        assert!(idx.query(&LogFilter::new().blocks(5, 1)).is_err());
+        assert!(idx.query(&LogFilter::new().blocks(0, MAX_BLOCK_RANGE)).is_err());
+    }
+
+    #[test]
+    fn test_filter_from_json() {
+        let json = JsonValue::parse(r#"{"fromBlock":"0x32","toBlock":"latest","address":"0tTOKEN","topics":["Transfer",null,["x","y"]]}"#).unwrap();
+        let f = LogFilter::from_json(&json, 200).unwrap();
+        assert_eq!((f.from_block, f.to_block), (Some(50), Some(200)));
+        assert_eq!(f.addresses, vec!["0tTOKEN"]);
+        assert_eq!(f.topics, vec![Some(vec!["Transfer".into()]), None, Some(vec!["x".into(), "y".into()])]);
+        // topic2는 없으므로 맞는 로그 없음
+        assert!(sample().query(&f).unwrap().logs.is_empty());
+
+        let f = LogFilter::from_json(&JsonValue::parse(r#"{"fromBlock":"earliest","toBlock":7}"#).unwrap(), 9).unwrap();
+        assert_eq!((f.from_block, f.to_block), (Some(0), Some(7)));
+        for bad in [r#"[]"#, r#"{"fromBlock":"0xzz"}"#, r#"{"toBlock":-1}"#, r#"{"address":5}"#, r#"{"topics":"x"}"#, r#"{"topics":[[1]]}"#] {
+            assert!(LogFilter::from_json(&JsonValue::parse(bad).unwrap(), 0).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_subscriptions_see_only_new_logs() {
+        let mut idx = sample();
+        let mut subs = LogSubscriptions::new();
+        let poll = subs.add(SubscriptionKind::Poll, LogFilter::new().topic(1, &["alice"]), &idx);
+        let push = subs.add(SubscriptionKind::Push, LogFilter { addresses: vec!["0tVOTE".into()], ..LogFilter::new() }, &idx);
+        assert_eq!(subs.changes(&poll, &idx).unwrap(), vec![]);
+        assert!(subs.notifications(&idx).is_empty());
+
+        idx.append(201, "0tTOKEN", "alice", &ev("Transfer", &[5]));
+        idx.append(201, "0tVOTE", "alice", &ev("TritVote", &[-1]));
+        idx.append(202, "0tTOKEN", "bob", &ev("Transfer", &[6]));
+        let got = subs.changes(&poll, &idx).unwrap();
+        assert_eq!(got.iter().map(|l| (l.block, l.event.as_str())).collect::<Vec<_>>(), vec![(201, "Transfer"), (201, "TritVote")]);
+        assert!(subs.changes(&poll, &idx).unwrap().is_empty());
+
+        let notes = subs.notifications(&idx);
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].starts_with(r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"subscription":"0x2","result":{"address":"0tVOTE","blockNumber":"0xc9""#), "{}", notes[0]);
+        // 푸시 구독은 폴링 불가 · 해제
+        assert!(subs.changes(&push, &idx).is_err());
+        assert!(subs.remove(&push) && !subs.remove(&push) && !subs.remove("zz"));
+        assert_eq!(subs.subs.len(), 1);
+    }
+}
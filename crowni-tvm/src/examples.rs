@@ -0,0 +1,166 @@
+// ═══════════════════════════════════════════════════════════════
+// 예제 레지스트리 — examples/*.hsn을 바이너리에 내장
+// 각 예제는 `; 기대: <줄>` 주석으로 기대 출력을 선언한다
+// → `crowni-tvm example <이름>` 실행 + 스모크 테스트를 겸한다
+// ═══════════════════════════════════════════════════════════════
+
+use crate::assembler::assemble;
+use crate::vm::{ExecLimits, Instruction, TVM};
+
+/// 예제 실행 사이클 한도
+const EXAMPLE_MAX_CYCLES: u64 = 1_000_000;
+const EXPECT_PREFIX: &str = "; 기대:";
+
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub name: &'static str,
+    pub alias: &'static str,
+    pub title: &'static str,
+    pub source: &'static str,
+}
+
+/// `; 기대: ...` 주석에서 기대 출력 추출
+pub fn expected_output(source: &str) -> Vec<String> {
+    source.lines()
+        .filter_map(|l| l.trim().strip_prefix(EXPECT_PREFIX))
+        .map(|rest| rest.trim().to_string())
+        .collect()
+}
+
+/// 내장 예제 목록
+pub fn registry() -> &'static [Example] {
+    const EXAMPLES: &[Example] = &[
+        Example {
+            name: "fibonacci", alias: "피보나치",
+            title: "피보나치 수열 (처음 10개) — 레지스터 + 조건점프 루프",
+            source: include_str!("../examples/fibonacci.hsn"),
+        },
+        Example {
+            name: "truth-table", alias: "진리표",
+            title: "3진 논리 진리표 — 그리고(min) · 아니다",
+            source: include_str!("../examples/truth-table.hsn"),
+        },
+        Example {
+            name: "consensus-vote", alias: "합의투표",
+            title: "3노드 3진 합의 투표 — 합계 부호로 P/O/T",
+            source: include_str!("../examples/consensus-vote.hsn"),
+        },
+        Example {
+            name: "token-transfer", alias: "토큰전송",
+            title: "토큰 전송 — 잔액 갱신과 총량 보존",
+            source: include_str!("../examples/token-transfer.hsn"),
+        },
+        Example {
+            name: "pythagoras", alias: "피타고라스",
+            title: "피타고라스 (3² + 4² = 5²) — 산술 · 힙 · 레지스터",
+            source: include_str!("../examples/피타고라스.hsn"),
+        },
+        Example {
+            name: "factorial", alias: "팩토리얼",
+            title: "재귀 팩토리얼 (1! ~ 6!) — 호출 · 돌려줘 · 라벨 · 프레임 지역 레지스터",
+            source: include_str!("../examples/factorial.hsn"),
+        },
+    ];
+    EXAMPLES
+}
+
+pub fn find(name: &str) -> Option<&'static Example> {
+    registry().iter().find(|e| e.name == name || e.alias == name)
+}
+
+// ═══════════════════════════════════════
+// 실행 + 검증
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleRun {
+    pub name: String,
+    /// P: 기대 출력 일치 · O: 기대 출력 미선언 · T: 불일치/실행 오류
+    pub state: i8,
+    pub output: Vec<String>,
+    pub expected: Vec<String>,
+    pub cycles: u64,
+    pub error: Option<String>,
+}
+
+impl ExampleRun {
+    /// 첫 불일치 (줄 번호, 기대, 실제)
+    pub fn first_mismatch(&self) -> Option<(usize, String, String)> {
+        let n = self.output.len().max(self.expected.len());
+        (0..n).find_map(|i| {
+            let exp = self.expected.get(i).cloned().unwrap_or_else(|| "(없음)".into());
+            let act = self.output.get(i).cloned().unwrap_or_else(|| "(없음)".into());
+            if exp != act { Some((i + 1, exp, act)) } else { None }
+        })
+    }
+}
+
+/// 소스를 실행하고 기대 출력과 비교 — CPM/파일에서 온 예제에도 사용
+pub fn run_source(name: &str, source: &str) -> ExampleRun {
+    run_program(name, assemble(source), expected_output(source))
+}
+
+/// 이미 어셈블된 프로그램(바이트코드 왕복 등)을 실행하고 기대 출력과 비교
+pub fn run_program(name: &str, program: Vec<Instruction>, expected: Vec<String>) -> ExampleRun {
+    let mut vm = TVM::new();
+    vm.limits = ExecLimits { max_cycles: Some(EXAMPLE_MAX_CYCLES), ..ExecLimits::unlimited() };
+    vm.captured = Some(Vec::new());
+    vm.load(program);
+    let result = vm.run();
+    let output = vm.captured.take().unwrap_or_default();
+
+    let mut run = ExampleRun {
+        name: name.to_string(), state: 1, output, expected,
+        cycles: vm.cycles, error: None,
+    };
+    if let Err(e) = result {
+        run.state = -1;
+        run.error = Some(e.to_string());
+    } else if run.expected.is_empty() {
+        run.state = 0;
+    } else if run.first_mismatch().is_some() {
+        run.state = -1;
+    }
+    run
+}
+
+pub fn run(example: &Example) -> ExampleRun {
+    run_source(example.name, example.source)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_examples_pass() {
+        for ex in registry() {
+            let r = run(ex);
+            assert_eq!(r.state, 1, "{}: {:?} {:?}", ex.name, r.first_mismatch(), r.error);
+        }
+    }
+
+    #[test]
+    fn test_find_by_name_or_alias() {
+        assert_eq!(find("fibonacci").unwrap().name, "fibonacci");
+        assert_eq!(find("피타고라스").unwrap().name, "pythagoras");
+        assert!(find("없는예제").is_none());
+        assert_eq!(expected_output(find("fibonacci").unwrap().source).len(), 10);
+    }
+
+    #[test]
+    fn test_mismatch_and_missing_expectations() {
+        let r = run_source("틀림", "; 기대: 3\n넣어 1\n넣어 1\n더해\n보여줘\n종료");
+        assert_eq!(r.state, -1);
+        assert_eq!(r.first_mismatch(), Some((1, "3".into(), "2".into())));
+
+        let r = run_source("기대없음", "넣어 1\n보여줘\n종료");
+        assert_eq!(r.state, 0);
+
+        let r = run_source("오류", "; 기대: 1\n더해");
+        assert_eq!(r.state, -1);
+        assert!(r.error.is_some());
+    }
+}
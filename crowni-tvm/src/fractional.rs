@@ -0,0 +1,379 @@
+// ═══════════════════════════════════════════════════════════════
+// 분할 소유 NFT — 금고 컨트랙트 · 지분 토큰 · 3진 바이아웃 투표
+//
+//   잠금: NFT → 금고(ContractVM 주소) 소유, 지분 토큰을 DEX에 발행 (거래 가능)
+//   제안: 구매자가 가격(CRWN)을 금고에 예치 — 동시에 제안 하나
+//   투표: 주주가 P(수락) · O(기권) · T(거절) — 보유 지분을 금고에 맡겨 가중
+//         (맡긴 지분은 결과가 나면 돌려준다 → 같은 지분으로 두 번 투표 불가)
+//   결정: P > 유통량 절반 → 즉시 수락 · T ≥ 절반 → 즉시 거절
+//         기한 만료 시 정족수(투표 지분 ≥ 50%) 충족 + P > T → 수락, 아니면 거절
+//   상환: 수락 후 NFT는 구매자에게, 지분을 소각하면 대금을 지분 비율로 (마지막은 나머지 전부)
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::contract_vm::{vault_contract, ContractVM, ExecCtx};
+use crate::dex::CrownyDEX;
+use crate::nft::CrownyNFT;
+use crate::output::JsonObject;
+use crate::token::{CrownyToken, TritPolicy};
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 바이아웃 대금 통화 (DEX 잔액)
+pub const BUYOUT_CURRENCY: &str = "CRWN";
+pub const VOTE_WINDOW_MS: u64 = 3 * 24 * 60 * 60 * 1000;
+pub const QUORUM_PCT: u64 = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VaultState {
+    Locked,
+    /// 바이아웃 완료 — 지분 상환 중
+    Bought { buyer: String, price: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct BuyoutOffer {
+    pub buyer: String,
+    pub price: u64,
+    pub expires_at: u64,
+    /// 투표자 → (트릿, 맡긴 지분)
+    pub votes: BTreeMap<String, (i8, u64)>,
+}
+
+impl BuyoutOffer {
+    /// (P, O, T) 가중 합
+    pub fn tally(&self) -> (u64, u64, u64) {
+        self.votes.values().fold((0, 0, 0), |(p, o, t), &(v, w)| match v {
+            1 => (p + w, o, t),
+            -1 => (p, o, t + w),
+            _ => (p, o + w, t),
+        })
+    }
+
+    /// 1 수락 · 0 대기 · -1 거절
+    pub fn decide_at(&self, outstanding: u64, now: u64) -> i8 {
+        let (p, o, t) = self.tally();
+        if p * 2 > outstanding {
+            1
+        } else if t * 2 >= outstanding {
+            -1
+        } else if now < self.expires_at {
+            0
+        } else if (p + o + t) * 100 >= outstanding * QUORUM_PCT && p > t {
+            1
+        } else {
+            -1
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Vault {
+    /// 금고 컨트랙트 주소 — NFT · 예치금 · 맡긴 지분의 소유 계정
+    pub id: String,
+    pub nft_id: String,
+    pub curator: String,
+    pub share: CrownyToken,
+    pub state: VaultState,
+    pub offer: Option<BuyoutOffer>,
+    /// 남은 상환 대금
+    pub proceeds: u64,
+    pub redeemed: u64,
+    pub rejected_offers: u32,
+}
+
+impl Vault {
+    pub fn outstanding(&self) -> u64 {
+        self.share.total_supply - self.redeemed
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let (state, label) = match &self.state {
+            VaultState::Locked if self.offer.is_some() => (0, "voting"),
+            VaultState::Locked => (1, "locked"),
+            VaultState::Bought { .. } => (-1, "bought"),
+        };
+        let obj = JsonObject::schema("crowny.vault")
+            .trit("state", state)
+            .str("status", label)
+            .str("id", &self.id)
+            .str("nft_id", &self.nft_id)
+            .str("curator", &self.curator)
+            .str("share_symbol", &self.share.symbol)
+            .int("supply", self.share.total_supply as i64)
+            .int("outstanding", self.outstanding() as i64)
+            .int("proceeds", self.proceeds as i64);
+        match &self.offer {
+            Some(o) => {
+                let (p, ab, t) = o.tally();
+                obj.object("offer", JsonObject::new()
+                    .str("buyer", &o.buyer)
+                    .int("price", o.price as i64)
+                    .int("expires_at", o.expires_at as i64)
+                    .int("votes_p", p as i64)
+                    .int("votes_o", ab as i64)
+                    .int("votes_t", t as i64))
+            }
+            None => obj,
+        }
+    }
+}
+
+/// 금고 서비스 — NFT 마켓 · DEX · 컨트랙트 VM을 함께 다룬다
+pub struct FractionalService {
+    pub nft: Rc<RefCell<CrownyNFT>>,
+    pub dex: Rc<RefCell<CrownyDEX>>,
+    pub vm: Rc<RefCell<ContractVM>>,
+    pub vaults: HashMap<String, Vault>,
+}
+
+impl FractionalService {
+    pub fn new(nft: Rc<RefCell<CrownyNFT>>, dex: Rc<RefCell<CrownyDEX>>, vm: Rc<RefCell<ContractVM>>) -> Self {
+        Self { nft, dex, vm, vaults: HashMap::new() }
+    }
+
+    fn log(&self, vault_id: &str, func: &str, caller: &str, args: Vec<i64>) {
+        let ctx = ExecCtx { caller: caller.into(), value: 0, block_h: 0, gas_limit: 100_000, args };
+        self.vm.borrow_mut().call(vault_id, func, ctx);
+    }
+
+    fn vault_mut(&mut self, vault_id: &str) -> Result<&mut Vault, String> {
+        self.vaults.get_mut(vault_id).ok_or_else(|| format!("금고 없음: {}", vault_id))
+    }
+
+    /// NFT 잠금 → 지분 발행 (전량 큐레이터에게)
+    pub fn fractionalize(&mut self, nft_id: &str, curator: &str, symbol: &str, supply: u64) -> Result<String, String> {
+        if supply == 0 { return Err("지분 수는 1 이상".into()); }
+        {
+            let market = self.nft.borrow();
+            let nft = market.nfts.get(nft_id).ok_or("NFT 없음")?;
+            if nft.owner != curator { return Err("소유자만 분할 가능".into()); }
+            if nft.listed { return Err("판매 · 경매 중인 NFT".into()); }
+        }
+        if self.dex.borrow().tokens.contains_key(symbol) {
+            return Err(format!("이미 있는 토큰: {}", symbol));
+        }
+
+        let (code, abi) = vault_contract();
+        let vault_id = self.vm.borrow_mut().deploy(&format!("vault:{}", symbol), curator, code, abi);
+        self.nft.borrow_mut().transfer(nft_id, &vault_id)?;
+        let share = CrownyToken::new(&format!("{} 지분", symbol), symbol, supply, &vault_id, TritPolicy::fractional_shares());
+        {
+            let mut dex = self.dex.borrow_mut();
+            dex.register_token(symbol, &share.name, supply);
+            dex.mint(curator, symbol, supply);
+        }
+        self.log(&vault_id, "lock", curator, vec![supply as i64]);
+        self.vaults.insert(vault_id.clone(), Vault {
+            id: vault_id.clone(), nft_id: nft_id.into(), curator: curator.into(), share,
+            state: VaultState::Locked, offer: None, proceeds: 0, redeemed: 0, rejected_offers: 0,
+        });
+        Ok(vault_id)
+    }
+
+    /// 바이아웃 제안 — 대금을 금고에 예치
+    pub fn offer_at(&mut self, vault_id: &str, buyer: &str, price: u64, now: u64) -> Result<(), String> {
+        let vault = self.vaults.get(vault_id).ok_or("금고 없음")?;
+        if vault.state != VaultState::Locked { return Err("이미 매각된 금고".into()); }
+        if vault.offer.is_some() { return Err("진행 중인 제안 있음".into()); }
+        if price == 0 { return Err("가격은 0보다 커야 함".into()); }
+        self.dex.borrow_mut().transfer(buyer, vault_id, BUYOUT_CURRENCY, price)?;
+        self.vault_mut(vault_id)?.offer = Some(BuyoutOffer {
+            buyer: buyer.into(), price, expires_at: now + VOTE_WINDOW_MS, votes: BTreeMap::new(),
+        });
+        self.log(vault_id, "offer", buyer, vec![price as i64]);
+        Ok(())
+    }
+
+    pub fn offer(&mut self, vault_id: &str, buyer: &str, price: u64) -> Result<(), String> {
+        self.offer_at(vault_id, buyer, price, now_ms())
+    }
+
+    /// 주주 투표 — 보유 지분 전부를 맡기고 그만큼 가중 → 맡긴 지분
+    pub fn vote(&mut self, vault_id: &str, voter: &str, trit: i8) -> Result<u64, String> {
+        let vault = self.vaults.get(vault_id).ok_or("금고 없음")?;
+        let offer = vault.offer.as_ref().ok_or("진행 중인 제안 없음")?;
+        if offer.votes.contains_key(voter) { return Err("이미 투표함".into()); }
+        let symbol = vault.share.symbol.clone();
+        let weight = self.dex.borrow().balance(voter, &symbol);
+        if weight == 0 { return Err("지분 없음".into()); }
+        self.dex.borrow_mut().transfer(voter, vault_id, &symbol, weight)?;
+        let trit = trit.clamp(-1, 1);
+        if let Some(o) = self.vault_mut(vault_id)?.offer.as_mut() {
+            o.votes.insert(voter.into(), (trit, weight));
+        }
+        self.log(vault_id, "vote", voter, vec![trit as i64, weight as i64]);
+        Ok(weight)
+    }
+
+    /// 제안 판정 — 결론이 나면 정산 (1 수락 · 0 대기 · -1 거절)
+    pub fn resolve_at(&mut self, vault_id: &str, now: u64) -> Result<i8, String> {
+        let vault = self.vaults.get(vault_id).ok_or("금고 없음")?;
+        let offer = vault.offer.clone().ok_or("진행 중인 제안 없음")?;
+        let decision = offer.decide_at(vault.outstanding(), now);
+        if decision == 0 { return Ok(0); }
+        let (symbol, nft_id) = (vault.share.symbol.clone(), vault.nft_id.clone());
+
+        {
+            let mut dex = self.dex.borrow_mut();
+            // 맡긴 지분 반환
+            for (voter, (_, weight)) in &offer.votes {
+                dex.transfer(vault_id, voter, &symbol, *weight)?;
+            }
+            if decision < 0 {
+                dex.transfer(vault_id, &offer.buyer, BUYOUT_CURRENCY, offer.price)?;
+            }
+        }
+        if decision > 0 {
+            self.nft.borrow_mut().transfer(&nft_id, &offer.buyer)?;
+        }
+        let vault = self.vault_mut(vault_id)?;
+        vault.offer = None;
+        if decision > 0 {
+            vault.state = VaultState::Bought { buyer: offer.buyer.clone(), price: offer.price };
+            vault.proceeds = offer.price;
+        } else {
+            vault.rejected_offers += 1;
+        }
+        self.log(vault_id, "settle", &offer.buyer, vec![decision as i64, offer.price as i64]);
+        Ok(decision)
+    }
+
+    pub fn resolve(&mut self, vault_id: &str) -> Result<i8, String> {
+        self.resolve_at(vault_id, now_ms())
+    }
+
+    /// 지분 상환 — 보유 지분 전부 소각, 대금 지급 → 지급액
+    pub fn redeem(&mut self, vault_id: &str, holder: &str) -> Result<u64, String> {
+        let vault = self.vaults.get(vault_id).ok_or("금고 없음")?;
+        if !matches!(vault.state, VaultState::Bought { .. }) { return Err("바이아웃 전에는 상환 불가".into()); }
+        let symbol = vault.share.symbol.clone();
+        let shares = self.dex.borrow().balance(holder, &symbol);
+        if shares == 0 { return Err("지분 없음".into()); }
+        let outstanding = vault.outstanding();
+        let paid = if shares >= outstanding {
+            vault.proceeds
+        } else {
+            (vault.proceeds as u128 * shares as u128 / outstanding as u128) as u64
+        };
+        {
+            let mut dex = self.dex.borrow_mut();
+            dex.burn(holder, &symbol, shares)?;
+            dex.transfer(vault_id, holder, BUYOUT_CURRENCY, paid)?;
+        }
+        let vault = self.vault_mut(vault_id)?;
+        vault.redeemed += shares;
+        vault.proceeds -= paid;
+        self.log(vault_id, "redeem", holder, vec![shares as i64, paid as i64]);
+        Ok(paid)
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nft::{NFTMetadata, NFTRarity};
+
+    fn setup() -> (FractionalService, String) {
+        let mut market = CrownyNFT::new();
+        let col = market.create_collection("T", "T", "alice", "d", None, 0);
+        let nft_id = market.mint(&col, "alice", NFTMetadata::new("왕관", "d", "i"), NFTRarity::Mythic).unwrap();
+        let mut dex = CrownyDEX::new();
+        dex.mint("buyer", BUYOUT_CURRENCY, 1_000_000);
+        dex.mint("bob", BUYOUT_CURRENCY, 50_000);
+        let svc = FractionalService::new(Rc::new(RefCell::new(market)), Rc::new(RefCell::new(dex)),
+            Rc::new(RefCell::new(ContractVM::new())));
+        (svc, nft_id)
+    }
+
+    #[test]
+    fn test_fractionalize_trade_and_buyout() {
+        let (mut svc, nft_id) = setup();
+        let vault = svc.fractionalize(&nft_id, "alice", "CRWNF", 1_000).unwrap();
+        assert_eq!(svc.nft.borrow().nfts[&nft_id].owner, vault);
+        assert!(svc.fractionalize(&nft_id, "alice", "X", 1).is_err());
+
+        // 지분은 DEX에서 거래된다
+        {
+            let mut dex = svc.dex.borrow_mut();
+            dex.transfer("alice", "carol", "CRWNF", 100).unwrap();
+            dex.mint("alice", BUYOUT_CURRENCY, 100_000);
+            let pool = dex.create_pool("CRWNF", BUYOUT_CURRENCY, 30);
+            dex.add_liquidity("alice", &pool, 300, 30_000).unwrap();
+            let swap = dex.swap("bob", &pool, BUYOUT_CURRENCY, 10_000).unwrap();
+            assert!(swap.amount_out > 0 && dex.balance("bob", "CRWNF") == swap.amount_out);
+        }
+
+        svc.offer_at(&vault, "buyer", 90_001, 0).unwrap();
+        assert!(svc.offer_at(&vault, "bob", 1, 0).is_err());
+        assert_eq!(svc.vote(&vault, "carol", -1).unwrap(), 100);
+        assert!(svc.vote(&vault, "carol", 1).is_err());
+        assert_eq!(svc.resolve_at(&vault, 1).unwrap(), 0);
+        // alice 600 → 유통량(1000)의 과반 → 즉시 수락
+        assert_eq!(svc.vote(&vault, "alice", 1).unwrap(), 600);
+        assert_eq!(svc.resolve_at(&vault, 2).unwrap(), 1);
+        assert_eq!(svc.nft.borrow().nfts[&nft_id].owner, "buyer");
+        assert_eq!(svc.dex.borrow().balance("carol", "CRWNF"), 100);
+
+        // 상환: 풀 안의 지분은 남아 있다 — 대금 합은 정확히 가격
+        let paid: u64 = ["alice", "carol", "bob"].iter().map(|h| svc.redeem(&vault, h).unwrap()).sum();
+        assert!(svc.redeem(&vault, "alice").is_err());
+        let v = &svc.vaults[&vault];
+        assert_eq!(paid + v.proceeds, 90_001);
+        assert_eq!(v.outstanding(), svc.dex.borrow().pools.values().map(|p| p.reserve_a).sum::<u64>());
+        assert_eq!(svc.dex.borrow().tokens["CRWNF"].total_supply, v.outstanding());
+    }
+
+    #[test]
+    fn test_rejected_offer_refunds_and_returns_shares() {
+        let (mut svc, nft_id) = setup();
+        let vault = svc.fractionalize(&nft_id, "alice", "SHR", 900).unwrap();
+        svc.dex.borrow_mut().transfer("alice", "dave", "SHR", 450).unwrap();
+        svc.offer_at(&vault, "buyer", 5_000, 0).unwrap();
+        assert_eq!(svc.dex.borrow().balance("buyer", BUYOUT_CURRENCY), 995_000);
+        svc.vote(&vault, "dave", -1).unwrap();
+        // T ≥ 절반 → 즉시 거절
+        assert_eq!(svc.resolve_at(&vault, 1).unwrap(), -1);
+        let dex = svc.dex.borrow();
+        assert_eq!(dex.balance("buyer", BUYOUT_CURRENCY), 1_000_000);
+        assert_eq!((dex.balance("dave", "SHR"), dex.balance(&vault, "SHR")), (450, 0));
+        drop(dex);
+        assert_eq!(svc.nft.borrow().nfts[&nft_id].owner, vault);
+        assert!(svc.redeem(&vault, "dave").is_err());
+        assert_eq!(svc.vaults[&vault].rejected_offers, 1);
+    }
+
+    #[test]
+    fn test_expiry_quorum_and_vault_logs() {
+        let (mut svc, nft_id) = setup();
+        let vault = svc.fractionalize(&nft_id, "alice", "Q", 100).unwrap();
+        {
+            let mut dex = svc.dex.borrow_mut();
+            dex.transfer("alice", "p1", "Q", 30).unwrap();
+            dex.transfer("alice", "o1", "Q", 25).unwrap();
+            dex.transfer("alice", "t1", "Q", 20).unwrap();
+        }
+        // 정족수 미달 → 만료 시 거절
+        svc.offer_at(&vault, "buyer", 1_000, 0).unwrap();
+        svc.vote(&vault, "p1", 1).unwrap();
+        assert_eq!(svc.resolve_at(&vault, VOTE_WINDOW_MS - 1).unwrap(), 0);
+        assert_eq!(svc.resolve_at(&vault, VOTE_WINDOW_MS).unwrap(), -1);
+        // 정족수 충족 (75%) + P > T → 수락
+        svc.offer_at(&vault, "buyer", 1_000, 0).unwrap();
+        for (who, t) in [("p1", 1), ("o1", 0), ("t1", -1)] { svc.vote(&vault, who, t).unwrap(); }
+        assert!(svc.vaults[&vault].to_json().build().contains(r#""votes_o":25"#));
+        assert_eq!(svc.resolve_at(&vault, VOTE_WINDOW_MS).unwrap(), 1);
+
+        let vm = svc.vm.borrow();
+        let events: Vec<&str> = vm.events.iter().filter(|(a, _)| *a == vault).map(|(_, e)| e.name.as_str()).collect();
+        assert_eq!(events.first(), Some(&"Locked"));
+        assert_eq!(events.iter().filter(|e| **e == "BuyoutSettled").count(), 2);
+        assert_eq!(events.iter().filter(|e| **e == "ShareVote").count(), 4);
+    }
+}
@@ -0,0 +1,645 @@
+// ═══════════════════════════════════════════════════
+// Gossip — 시드 기반 피어 발견 · 하트비트 생존 확인
+// ═══════════════════════════════════════════════════
+//
+// 각 노드는 자기 하트비트 카운터만 올리고, 주기마다 무작위 피어 몇 개와
+// 알고 있는 멤버 목록(id, 주소, 하트비트)을 CTP 바이너리 프레임으로 교환한다.
+// 더 큰 하트비트를 받은 순간이 그 멤버의 마지막 생존 시각이다.
+//
+// 생존 상태 (3진):
+//   P = 살아 있음 / O = 의심 (suspect_after 경과) / T = 죽음 (dead_after 경과)
+// 죽은 멤버는 evict_after 뒤 목록에서 빠진다.
+//
+// 교환 한 번 (연결 하나에 파이프라이닝):
+//   "P|id|addr|hb"  → 상대가 병합, P 응답
+//   "L|k"           → 상대 목록의 k번째 항목 (P) / 끝이면 O
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::network::{CtpMessage, CtpPool, StatusCode, TritBuffer, TritNetAdapter};
+use crate::node::{DistributedNode, NodeId, NodeState, Peer};
+use crate::watchdog::{ComponentKind, Heartbeat, Watchdog};
+use crate::webserver::ShutdownHandle;
+
+/// 항목 하나가 CTP 페이로드(364 trit = 문자 60개)에 들어가야 한다
+const MAX_ENTRY_CHARS: usize = 60;
+/// 한 번에 받아 올 상대 목록 상한
+const MAX_PULL: usize = 1024;
+/// accept 폴링 간격 (중지 플래그 확인 주기)
+const ACCEPT_POLL: Duration = Duration::from_millis(10);
+
+// ── 생존 상태 ──
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    Alive,   // P
+    Suspect, // O
+    Dead,    // T
+}
+
+impl Health {
+    pub fn trit(self) -> i8 {
+        match self {
+            Health::Alive => 1,
+            Health::Suspect => 0,
+            Health::Dead => -1,
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Health::Alive => 'P',
+            Health::Suspect => 'O',
+            Health::Dead => 'T',
+        }
+    }
+}
+
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Health::Alive => write!(f, "P(생존)"),
+            Health::Suspect => write!(f, "O(의심)"),
+            Health::Dead => write!(f, "T(죽음)"),
+        }
+    }
+}
+
+// ── 설정 ──
+
+#[derive(Debug, Clone)]
+pub struct GossipConfig {
+    /// 처음 접속할 주소 — 아는 멤버가 없을 때도 다시 시도
+    pub seeds: Vec<String>,
+    /// 주기마다 교환할 피어 수
+    pub fanout: usize,
+    pub interval: Duration,
+    pub suspect_after: Duration,
+    pub dead_after: Duration,
+    pub evict_after: Duration,
+    /// 교환 요청 하나의 응답 대기
+    pub io_timeout: Duration,
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            seeds: Vec::new(),
+            fanout: 3,
+            interval: Duration::from_secs(1),
+            suspect_after: Duration::from_secs(3),
+            dead_after: Duration::from_secs(10),
+            evict_after: Duration::from_secs(30),
+            io_timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+impl GossipConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_seeds(mut self, seeds: &[&str]) -> Self {
+        self.seeds = seeds.iter().map(|s| s.to_string()).collect();
+        self
+    }
+}
+
+// ── 멤버 목록 ──
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub id: String,
+    pub addr: String,
+    pub heartbeat: u64,
+    pub last_seen: Instant,
+    pub health: Health,
+}
+
+/// 교환 단위 (id, 주소, 하트비트)
+pub type Digest = (String, String, u64);
+
+#[derive(Debug)]
+pub struct Membership {
+    pub id: String,
+    pub addr: String,
+    pub heartbeat: u64,
+    pub config: GossipConfig,
+    members: HashMap<String, Member>,
+    /// 피어 연결 — 주기마다 다시 열지 않는다
+    pool: Arc<CtpPool>,
+    pub stats_rounds: u64,
+    pub stats_failed: u64,
+}
+
+impl Membership {
+    pub fn new(id: &str, addr: &str, config: GossipConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            addr: addr.to_string(),
+            heartbeat: 0,
+            pool: Arc::new(CtpPool::new(config.io_timeout)),
+            config,
+            members: HashMap::new(),
+            stats_rounds: 0,
+            stats_failed: 0,
+        }
+    }
+
+    /// 자기 하트비트 증가
+    pub fn beat(&mut self) -> u64 {
+        self.heartbeat += 1;
+        self.heartbeat
+    }
+
+    /// 받은 항목 병합 — 새로 알게 된 멤버면 true
+    /// 하트비트가 커졌을 때만 생존 시각을 갱신 (같은 값의 소문은 증거가 아님)
+    pub fn merge(&mut self, id: &str, addr: &str, heartbeat: u64, now: Instant) -> bool {
+        if id == self.id || id.is_empty() || addr.is_empty() {
+            return false;
+        }
+        match self.members.get_mut(id) {
+            Some(m) => {
+                if heartbeat > m.heartbeat {
+                    m.heartbeat = heartbeat;
+                    m.addr = addr.to_string();
+                    m.last_seen = now;
+                    m.health = Health::Alive;
+                }
+                false
+            }
+            None => {
+                self.members.insert(id.to_string(), Member {
+                    id: id.to_string(),
+                    addr: addr.to_string(),
+                    heartbeat,
+                    last_seen: now,
+                    health: Health::Alive,
+                });
+                true
+            }
+        }
+    }
+
+    /// 경과 시간으로 상태 재계산 + 오래 죽은 멤버 퇴출 — 퇴출 수 반환
+    pub fn refresh(&mut self, now: Instant) -> usize {
+        let cfg = &self.config;
+        for m in self.members.values_mut() {
+            let age = now.saturating_duration_since(m.last_seen);
+            m.health = if age >= cfg.dead_after {
+                Health::Dead
+            } else if age >= cfg.suspect_after {
+                Health::Suspect
+            } else {
+                Health::Alive
+            };
+        }
+        let before = self.members.len();
+        let evict_after = cfg.evict_after;
+        self.members.retain(|_, m| now.saturating_duration_since(m.last_seen) < evict_after);
+        before - self.members.len()
+    }
+
+    /// 보낼 목록 — 자기 자신 포함, 죽은 멤버 제외
+    pub fn digest(&self) -> Vec<Digest> {
+        let mut out = vec![(self.id.clone(), self.addr.clone(), self.heartbeat)];
+        let mut others: Vec<&Member> = self.members.values().filter(|m| m.health != Health::Dead).collect();
+        others.sort_by(|a, b| a.id.cmp(&b.id));
+        out.extend(others.into_iter().map(|m| (m.id.clone(), m.addr.clone(), m.heartbeat)));
+        out
+    }
+
+    /// 이번 주기 교환 대상 — 죽지 않은 멤버 중 무작위 fanout개, 없으면 시드
+    pub fn targets(&self) -> Vec<String> {
+        let mut live: Vec<&str> = self.members.values()
+            .filter(|m| m.health != Health::Dead)
+            .map(|m| m.addr.as_str())
+            .collect();
+        if live.is_empty() {
+            return self.config.seeds.iter().filter(|s| **s != self.addr).cloned().collect();
+        }
+        // Fisher–Yates 앞부분만
+        live.sort();
+        let take = self.config.fanout.min(live.len());
+        let n = live.len();
+        for i in 0..take {
+            let r = u32::from_le_bytes(crate::crypto::random_bytes()) as usize;
+            live.swap(i, i + r % (n - i));
+        }
+        live.truncate(take);
+        live.into_iter().map(String::from).collect()
+    }
+
+    pub fn members(&self) -> impl Iterator<Item = &Member> {
+        self.members.values()
+    }
+
+    /// 분산 노드의 피어 표에 반영 — 새로 추가한 피어 수 반환
+    /// 주소는 host:port 형식만, 죽은 멤버는 Offline으로 표시
+    pub fn sync_into(&self, node: &mut DistributedNode) -> usize {
+        let now = now_ms();
+        let mut added = 0;
+        for m in self.members.values() {
+            let Some((host, port)) = m.addr.rsplit_once(':') else { continue };
+            let Ok(port) = port.parse::<u16>() else { continue };
+            if !node.peers.contains_key(&m.id) {
+                node.add_peer(Peer::new(NodeId::new(&m.id, &node.id.region, node.id.shard), host, port));
+                added += 1;
+            }
+            if let Some(p) = node.peers.get_mut(&m.id) {
+                p.trit_state = m.health.trit();
+                p.last_heartbeat = now.saturating_sub(m.last_seen.elapsed().as_millis() as u64);
+                if m.health == Health::Dead {
+                    p.state = NodeState::Offline;
+                } else if p.state == NodeState::Offline {
+                    p.state = NodeState::Follower;
+                }
+            }
+        }
+        added
+    }
+
+    // ── 메시지 처리 (서버 측) ──
+
+    pub fn handle(&mut self, msg: &CtpMessage) -> CtpMessage {
+        let text = payload_text(&msg.payload);
+        let mut parts = text.split('|');
+        match parts.next() {
+            Some("P") => match parse_digest(&mut parts) {
+                Some((id, addr, hb)) => {
+                    self.merge(&id, &addr, hb, Instant::now());
+                    CtpMessage::response(StatusCode::Success, TritBuffer::new())
+                }
+                None => CtpMessage::response(StatusCode::Error, TritBuffer::new()),
+            },
+            Some("L") => {
+                let k = parts.next().and_then(|k| k.parse::<usize>().ok());
+                match k.and_then(|k| self.digest().into_iter().nth(k)) {
+                    Some(entry) => match encode_entry("P", &entry) {
+                        Some(payload) => CtpMessage::response(StatusCode::Success, payload),
+                        // 너무 긴 항목은 건너뛰기 (빈 P)
+                        None => CtpMessage::response(StatusCode::Success, TritBuffer::new()),
+                    },
+                    None if k.is_some() => CtpMessage::response(StatusCode::Neutral, TritBuffer::new()),
+                    None => CtpMessage::response(StatusCode::Error, TritBuffer::new()),
+                }
+            }
+            _ => CtpMessage::response(StatusCode::Error, TritBuffer::new()),
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// 항목 인코딩 — 페이로드에 안 들어가거나 6-trit로 못 담는 문자가 있으면 None
+fn encode_entry(kind: &str, (id, addr, hb): &Digest) -> Option<TritBuffer> {
+    let text = format!("{}|{}|{}|{}", kind, id, addr, hb);
+    if text.chars().count() > MAX_ENTRY_CHARS || text.chars().any(|c| c as u32 > 364) {
+        return None;
+    }
+    let mut buf = TritBuffer::new();
+    buf.push_string(&text);
+    Some(buf)
+}
+
+fn payload_text(payload: &TritBuffer) -> String {
+    payload.words().filter_map(|w| char::from_u32(w.max(0) as u32)).collect()
+}
+
+fn parse_digest<'a>(parts: &mut impl Iterator<Item = &'a str>) -> Option<Digest> {
+    let id = parts.next()?.to_string();
+    let addr = parts.next()?.to_string();
+    let hb = parts.next()?.parse().ok()?;
+    Some((id, addr, hb))
+}
+
+fn text_message(text: &str) -> CtpMessage {
+    let mut payload = TritBuffer::new();
+    payload.push_string(text);
+    CtpMessage::request(payload)
+}
+
+/// 한 피어와 교환 — 내 목록을 밀어 넣고 상대 목록을 받아 온다
+/// 받은 항목 목록 반환 (병합은 호출자가)
+pub fn exchange(pool: &CtpPool, addr: &str, digest: &[Digest]) -> io::Result<Vec<Digest>> {
+    let timeout = pool.timeout;
+    pool.with_client(addr, |client| {
+        let pushes: Vec<_> = digest.iter()
+            .filter_map(|d| encode_entry("P", d))
+            .map(|payload| client.send(CtpMessage::request(payload)))
+            .collect::<io::Result<_>>()?;
+        for p in pushes {
+            p.wait(timeout)?;
+        }
+
+        let mut pulled = Vec::new();
+        for k in 0..MAX_PULL {
+            let answer = client.request(text_message(&format!("L|{}", k)), timeout)?;
+            if answer.status != StatusCode::Success {
+                break;
+            }
+            let text = payload_text(&answer.payload);
+            let mut parts = text.split('|');
+            if parts.next() == Some("P") {
+                if let Some(d) = parse_digest(&mut parts) {
+                    pulled.push(d);
+                }
+            }
+        }
+        Ok(pulled)
+    })
+}
+
+/// 한 주기 — 하트비트 증가, 상태 갱신, 대상들과 교환 후 병합
+/// 새로 알게 된 멤버 수 반환
+pub fn gossip_round(membership: &Mutex<Membership>) -> usize {
+    let (targets, digest, pool) = {
+        let mut m = lock(membership);
+        m.beat();
+        m.refresh(Instant::now());
+        m.stats_rounds += 1;
+        (m.targets(), m.digest(), Arc::clone(&m.pool))
+    };
+    let mut discovered = 0;
+    for addr in targets {
+        // 잠금 밖에서 네트워크 — 상대도 동시에 나에게 교환을 걸 수 있다
+        match exchange(&pool, &addr, &digest) {
+            Ok(entries) => {
+                let mut m = lock(membership);
+                let now = Instant::now();
+                for (id, peer_addr, hb) in entries {
+                    if m.merge(&id, &peer_addr, hb, now) {
+                        discovered += 1;
+                    }
+                }
+            }
+            Err(_) => lock(membership).stats_failed += 1,
+        }
+    }
+    discovered
+}
+
+fn lock(membership: &Mutex<Membership>) -> std::sync::MutexGuard<'_, Membership> {
+    membership.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ── 실행 노드 ──
+
+/// 가십 노드 — 리스너 스레드 + 주기 스레드
+/// 주기 스레드 없이 round()를 직접 불러도 된다 (start_manual)
+pub struct GossipNode {
+    membership: Arc<Mutex<Membership>>,
+    addr: String,
+    interval: Duration,
+    shutdown: ShutdownHandle,
+    // 워치독 재시작이 새 교환 스레드를 보탠다
+    threads: Arc<Mutex<Vec<std::thread::JoinHandle<()>>>>,
+    heartbeat: Arc<Mutex<Option<Heartbeat>>>,
+    // 받은 연결 — 피어가 풀에 붙잡고 있으니 중지할 때 직접 끊는다
+    conns: Arc<Mutex<Vec<TcpStream>>>,
+}
+
+/// 한 주기 교환 후 하트비트 (체크포인트: 현재 멤버 수)
+fn round_and_beat(membership: &Mutex<Membership>, heartbeat: &Mutex<Option<Heartbeat>>) -> usize {
+    let learned = gossip_round(membership);
+    if let Some(hb) = heartbeat.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        let members = lock(membership).members().count();
+        hb.beat(Some(&format!("members={}", members)));
+    }
+    learned
+}
+
+/// 주기 교환 스레드
+fn spawn_ticker(
+    membership: Arc<Mutex<Membership>>,
+    heartbeat: Arc<Mutex<Option<Heartbeat>>>,
+    stop: ShutdownHandle,
+    interval: Duration,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        while !stop.is_stopped() {
+            round_and_beat(&membership, &heartbeat);
+            // 중지를 빨리 알아채도록 잘게 나눠 잔다
+            let until = Instant::now() + interval;
+            while !stop.is_stopped() && Instant::now() < until {
+                std::thread::sleep(ACCEPT_POLL);
+            }
+        }
+    })
+}
+
+impl GossipNode {
+    /// bind 주소에서 대기 시작 (포트 0이면 자동) — 주기 교환도 시작
+    pub fn start(id: &str, bind: &str, config: GossipConfig) -> io::Result<Self> {
+        Self::spawn(id, bind, config, true)
+    }
+
+    /// 리스너만 띄우기 — 교환은 round()로 직접
+    pub fn start_manual(id: &str, bind: &str, config: GossipConfig) -> io::Result<Self> {
+        Self::spawn(id, bind, config, false)
+    }
+
+    fn spawn(id: &str, bind: &str, config: GossipConfig, ticker: bool) -> io::Result<Self> {
+        let listener = TcpListener::bind(bind)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?.to_string();
+        let interval = config.interval;
+        let membership = Arc::new(Mutex::new(Membership::new(id, &addr, config)));
+        let shutdown = ShutdownHandle::default();
+        let conns = Arc::new(Mutex::new(Vec::new()));
+        let mut threads = Vec::new();
+
+        let (m, stop, c) = (Arc::clone(&membership), shutdown.clone(), Arc::clone(&conns));
+        threads.push(std::thread::spawn(move || {
+            while !stop.is_stopped() {
+                match listener.accept() {
+                    Ok((mut stream, _)) => {
+                        let _ = stream.set_nonblocking(false);
+                        if let Ok(clone) = stream.try_clone() {
+                            c.lock().unwrap_or_else(|e| e.into_inner()).push(clone);
+                        }
+                        let m = Arc::clone(&m);
+                        std::thread::spawn(move || {
+                            let _ = TritNetAdapter::serve_framed(&mut stream, |msg| lock(&m).handle(msg));
+                        });
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+                    Err(e) => {
+                        eprintln!("[가십] accept 오류: {}", e);
+                        break;
+                    }
+                }
+            }
+        }));
+
+        let heartbeat = Arc::new(Mutex::new(None));
+        if ticker {
+            threads.push(spawn_ticker(Arc::clone(&membership), Arc::clone(&heartbeat), shutdown.clone(), interval));
+        }
+
+        let threads = Arc::new(Mutex::new(threads));
+        Ok(Self { membership, addr, interval, shutdown, threads, heartbeat, conns })
+    }
+
+    /// 워치독에 노드 동기화로 등록 — 교환 주기마다 하트비트,
+    /// 끊기면 새 교환 스레드를 띄운다 (멤버십은 그대로 이어받는다)
+    /// 중지한 노드는 재시작을 거부하니 stop() 뒤에는 등록을 해제할 것
+    pub fn watch(&mut self, watchdog: &mut Watchdog) {
+        let hb = watchdog.heartbeat_handle(ComponentKind::NodeSync);
+        *self.heartbeat.lock().unwrap_or_else(|e| e.into_inner()) = Some(hb);
+        let (m, hb, stop) = (Arc::clone(&self.membership), Arc::clone(&self.heartbeat), self.shutdown.clone());
+        let (threads, interval) = (Arc::clone(&self.threads), self.interval);
+        let interval_ms = (interval.as_millis() as u64).max(1);
+        watchdog.register(ComponentKind::NodeSync, interval_ms, Box::new(move |_| {
+            if stop.is_stopped() {
+                return Err("노드가 중지됨".into());
+            }
+            let t = spawn_ticker(Arc::clone(&m), Arc::clone(&hb), stop.clone(), interval);
+            threads.lock().unwrap_or_else(|e| e.into_inner()).push(t);
+            Ok(())
+        }));
+    }
+
+    /// 실제 대기 주소 (host:port)
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// 지금 한 주기 실행 — 새로 알게 된 멤버 수
+    pub fn round(&self) -> usize {
+        round_and_beat(&self.membership, &self.heartbeat)
+    }
+
+    /// 현재 멤버 목록 복사 (id 순)
+    pub fn members(&self) -> Vec<Member> {
+        let mut out: Vec<Member> = lock(&self.membership).members().cloned().collect();
+        out.sort_by(|a, b| a.id.cmp(&b.id));
+        out
+    }
+
+    pub fn sync_into(&self, node: &mut DistributedNode) -> usize {
+        lock(&self.membership).sync_into(node)
+    }
+
+    pub fn stop(&mut self) {
+        self.shutdown.stop();
+        let threads: Vec<_> = self.threads.lock().unwrap_or_else(|e| e.into_inner()).drain(..).collect();
+        for t in threads {
+            let _ = t.join();
+        }
+        for conn in self.conns.lock().unwrap_or_else(|e| e.into_inner()).drain(..) {
+            let _ = conn.shutdown(std::net::Shutdown::Both);
+        }
+    }
+}
+
+impl Drop for GossipNode {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_membership_heartbeat_health() {
+        let mut m = Membership::new("a", "127.0.0.1:1", GossipConfig::new());
+        let t0 = Instant::now();
+        assert!(m.merge("b", "127.0.0.1:2", 5, t0));
+        assert!(!m.merge("a", "127.0.0.1:1", 9, t0)); // 자기 자신은 무시
+
+        // 같은 하트비트 소문은 생존 증거가 아님
+        m.merge("b", "127.0.0.1:2", 5, t0 + Duration::from_secs(4));
+        m.refresh(t0 + Duration::from_secs(4));
+        assert_eq!(m.members.get("b").unwrap().health, Health::Suspect);
+
+        m.refresh(t0 + Duration::from_secs(11));
+        assert_eq!(m.members.get("b").unwrap().health.trit(), -1);
+        assert_eq!(m.digest().len(), 1); // 죽은 멤버는 퍼뜨리지 않는다
+
+        // 더 큰 하트비트 → 다시 P
+        m.merge("b", "127.0.0.1:2", 6, t0 + Duration::from_secs(12));
+        m.refresh(t0 + Duration::from_secs(12));
+        assert_eq!(m.members.get("b").unwrap().health, Health::Alive);
+
+        // 오래 죽어 있으면 퇴출
+        assert_eq!(m.refresh(t0 + Duration::from_secs(60)), 1);
+        assert!(m.members.is_empty());
+    }
+
+    #[test]
+    fn test_sync_into_distributed_node() {
+        let mut m = Membership::new("a", "127.0.0.1:1", GossipConfig::new());
+        m.merge("b", "127.0.0.1:7001", 1, Instant::now());
+        m.merge("bad", "no-port", 1, Instant::now());
+        let mut node = DistributedNode::new(NodeId::new("a", "kr", 0));
+        assert_eq!(m.sync_into(&mut node), 1);
+        assert_eq!(m.sync_into(&mut node), 0);
+        let peer = &node.peers["b"];
+        assert_eq!((peer.endpoint().as_str(), peer.trit_state), ("127.0.0.1:7001", 1));
+    }
+
+    #[test]
+    fn test_gossip_mesh_from_single_seed() {
+        let cfg = || GossipConfig { fanout: 8, ..GossipConfig::new() };
+        let a = GossipNode::start_manual("node-a", "127.0.0.1:0", cfg()).unwrap();
+        let b = GossipNode::start_manual("node-b", "127.0.0.1:0", cfg().with_seeds(&[a.addr()])).unwrap();
+        let c = GossipNode::start_manual("node-c", "127.0.0.1:0", cfg().with_seeds(&[a.addr()])).unwrap();
+
+        // b, c는 시드 a만 안다 — a를 거쳐 서로를 찾는다
+        assert_eq!(b.round(), 1);
+        assert_eq!(c.round(), 2);
+        b.round();
+        for node in [&a, &b, &c] {
+            let ids: Vec<String> = node.members().into_iter().map(|m| m.id).collect();
+            assert_eq!(ids.len(), 2, "{:?}", ids);
+            assert!(node.members().iter().all(|m| m.health == Health::Alive));
+        }
+        assert_eq!(lock(&b.membership).members.get("node-c").map(|m| m.health), Some(Health::Alive));
+
+        // 죽은 시드로의 교환은 실패로 집계
+        let dead = GossipNode::start_manual("lonely", "127.0.0.1:0",
+            GossipConfig::new().with_seeds(&["127.0.0.1:1"])).unwrap();
+        assert_eq!(dead.round(), 0);
+        assert_eq!(lock(&dead.membership).stats_failed, 1);
+    }
+
+    #[test]
+    fn test_stalled_sync_restarted_by_watchdog() {
+        use crate::watchdog::WatchdogAction;
+
+        let a = GossipNode::start_manual("node-a", "127.0.0.1:0", GossipConfig::new()).unwrap();
+        let cfg = GossipConfig { interval: Duration::from_millis(20), ..GossipConfig::new().with_seeds(&[a.addr()]) };
+        // 주기 교환이 없는 노드 = 동기화가 멎은 노드
+        let mut b = GossipNode::start_manual("node-b", "127.0.0.1:0", cfg).unwrap();
+        let mut wd = Watchdog::new(3, 3);
+        b.watch(&mut wd);
+
+        let far = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64 + 60_000;
+        assert_eq!(wd.tick_at(far), vec![WatchdogAction::Restarted(ComponentKind::NodeSync)]);
+
+        // 새 교환 스레드가 시드를 찾아 하트비트를 보낸다
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let health = |n: &GossipNode| lock(&n.membership).members.get("node-a").map(|m| m.health);
+        while health(&b).is_none() && Instant::now() < deadline {
+            std::thread::sleep(ACCEPT_POLL);
+        }
+        assert_eq!(health(&b), Some(Health::Alive));
+        std::thread::sleep(Duration::from_millis(50));
+        wd.tick_at(far);
+        assert_eq!(wd.component(&ComponentKind::NodeSync).unwrap().checkpoint.as_deref(), Some("members=1"));
+
+        // 중지 후에는 재시작 거부
+        b.stop();
+        assert!(matches!(wd.tick_at(far + 60_000).as_slice(), [WatchdogAction::RestartFailed(ComponentKind::NodeSync, _)]));
+    }
+}
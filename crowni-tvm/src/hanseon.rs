@@ -8,10 +8,14 @@
 ///!   값 N              → 넣어 N
 ///!   변수 이름 = 값     → 넣어 값 / 저장해 슬롯
 ///!   이름              → 불러와 슬롯
-///!   더 / 빼 / 곱 / 나눠 → 산술
+///!   더 / 빼 / 곱 / 나눠 → 산술 (후위)
+///!   (5 + 3) * 2 / x > 10 → 중위 식 — 우선순위: 비교 < + - < * / % < 단항 -
+///!                         > < 는 3진 (같으면 모름), >= <= == != 는 참/거짓
 ///!   보여줘             → 출력
-///!   만약 { } 아니면 { } → 3진 분기
-///!   반복 N { }         → 루프
+///!   만약 조건 { } 보류 { } 아니면 { } → 3진 분기 (보류 없으면 모름은 아니면으로)
+///!   반복 N { }         → 횟수 루프 (N: 식)
+///!   동안 조건 { }      → 조건이 참(P)인 동안 루프 — 모름 · 거짓이면 끝
+///!   그만 / 계속        → 가장 안쪽 루프 탈출 / 다음 회차
 ///!   함수 이름 { }      → 함수 정의
 ///!   이름()             → 함수 호출
 ///!   질문해 "프롬프트"   → LLM 호출
@@ -21,6 +25,7 @@ use std::collections::HashMap;
 use crate::vm::Instruction;
 use crate::opcode::OpcodeAddr;
 use crate::value::Value;
+use crate::program_limits::{self, ProgramLimitError, ProgramLimits};
 
 // ─────────────────────────────────────────────
 // 토큰
@@ -41,6 +46,9 @@ enum Token {
     Else,              // 아니면
     Neutral,           // 보류 (3진 분기 중간)
     Loop,              // 반복
+    While,             // 동안
+    Break,             // 그만
+    Continue,          // 계속
     Func,              // 함수
     Return,            // 반환
     End,               // 끝
@@ -68,6 +76,19 @@ enum Token {
     RParen,            // )
     Comma,             // ,
 
+    // 중위 연산자
+    Plus,              // +
+    Minus,             // -
+    Star,              // *
+    Slash,             // /
+    Percent,           // %
+    EqEq,              // ==
+    NotEq,             // !=
+    Greater,           // >
+    Less,              // <
+    GreaterEq,         // >=
+    LessEq,            // <=
+
     // 식별자
     Ident(String),
 
@@ -79,12 +100,21 @@ enum Token {
 // 렉서
 // ─────────────────────────────────────────────
 
-fn lex(source: &str) -> Vec<Token> {
+/// 토큰 + 각 토큰의 소스 줄 (1부터)
+fn lex(source: &str) -> (Vec<Token>, Vec<usize>) {
     let mut tokens = Vec::new();
     let mut chars: Vec<char> = source.chars().collect();
     let mut pos = 0;
+    // 글자 위치 → 줄. 토큰은 반복당 최대 1개 — 다음 반복 머리에서 시작 위치의 줄을 기록
+    let line_at: Vec<usize> = chars.iter()
+        .scan(1, |line, &c| { let at = *line; if c == '\n' { *line += 1; } Some(at) })
+        .collect();
+    let mut lines = Vec::new();
+    let mut start = 0;
 
     while pos < chars.len() {
+        if lines.len() < tokens.len() { lines.push(line_at[start]); }
+        start = pos;
         let ch = chars[pos];
 
         // 공백
@@ -100,8 +130,35 @@ fn lex(source: &str) -> Vec<Token> {
             continue;
         }
 
+        // 두 글자 연산자
+        let next = chars.get(pos + 1).copied();
+        let pair = match (ch, next) {
+            ('=', Some('=')) => Some(Token::EqEq),
+            ('!', Some('=')) => Some(Token::NotEq),
+            ('>', Some('=')) => Some(Token::GreaterEq),
+            ('<', Some('=')) => Some(Token::LessEq),
+            _ => None,
+        };
+        if let Some(tok) = pair {
+            tokens.push(tok);
+            pos += 2;
+            continue;
+        }
+
+        // 음수 리터럴은 값 뒤가 아닐 때만 (`x-1`은 빼기)
+        let after_value = matches!(tokens.last(),
+            Some(Token::Int(_) | Token::Float(_) | Token::Str(_) | Token::Trit(_) | Token::Ident(_) | Token::RParen));
+        let negative_literal = ch == '-' && !after_value && next.is_some_and(|c| c.is_ascii_digit());
+
         // 기호
         match ch {
+            '+' => { tokens.push(Token::Plus); pos += 1; continue; }
+            '-' if !negative_literal => { tokens.push(Token::Minus); pos += 1; continue; }
+            '*' => { tokens.push(Token::Star); pos += 1; continue; }
+            '/' => { tokens.push(Token::Slash); pos += 1; continue; }
+            '%' => { tokens.push(Token::Percent); pos += 1; continue; }
+            '>' => { tokens.push(Token::Greater); pos += 1; continue; }
+            '<' => { tokens.push(Token::Less); pos += 1; continue; }
             '=' => { tokens.push(Token::Assign); pos += 1; continue; }
             '{' => { tokens.push(Token::LBrace); pos += 1; continue; }
             '}' => { tokens.push(Token::RBrace); pos += 1; continue; }
@@ -116,15 +173,20 @@ fn lex(source: &str) -> Vec<Token> {
             let quote = ch;
             pos += 1;
             let start = pos;
-            while pos < chars.len() && chars[pos] != quote { pos += 1; }
-            let s: String = chars[start..pos].iter().collect();
-            tokens.push(Token::Str(s));
+            while pos < chars.len() && chars[pos] != quote {
+                if chars[pos] == '\\' { pos += 1; }
+                pos += 1;
+            }
+            let pos_end = pos.min(chars.len());
+            let raw: String = chars[start..pos_end].iter().collect();
+            // 이스케이프 (\n, \", \uXXXX) — 잘못된 이스케이프는 원문 유지
+            tokens.push(Token::Str(crate::assembler::unescape(&raw).unwrap_or(raw)));
             if pos < chars.len() { pos += 1; }
             continue;
         }
 
         // 숫자
-        if ch.is_ascii_digit() || (ch == '-' && pos + 1 < chars.len() && chars[pos+1].is_ascii_digit()) {
+        if ch.is_ascii_digit() || negative_literal {
             let start = pos;
             if ch == '-' { pos += 1; }
             while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') {
@@ -155,6 +217,9 @@ fn lex(source: &str) -> Vec<Token> {
                 "아니면" | "else" => Token::Else,
                 "보류" | "neutral" => Token::Neutral,
                 "반복" | "loop" | "repeat" => Token::Loop,
+                "동안" | "while" => Token::While,
+                "그만" | "break" => Token::Break,
+                "계속" | "continue" => Token::Continue,
                 "함수" | "func" | "fn" => Token::Func,
                 "반환" | "return" => Token::Return,
                 "끝" | "end" | "종료" => Token::End,
@@ -184,8 +249,10 @@ fn lex(source: &str) -> Vec<Token> {
         pos += 1;
     }
 
+    if lines.len() < tokens.len() { lines.push(line_at[start]); }
     tokens.push(Token::Eof);
-    tokens
+    lines.push(line_at.last().copied().unwrap_or(1));
+    (tokens, lines)
 }
 
 // ─────────────────────────────────────────────
@@ -199,17 +266,30 @@ pub struct CompileOutput {
     pub errors: Vec<String>,
     pub variables: usize,
     pub functions: usize,
+    /// 변수 이름 → 슬롯 (VM 전역 `#슬롯`)
+    pub var_slots: HashMap<String, u32>,
+}
+
+/// 컴파일 중인 루프 — 그만 · 계속 점프는 루프 끝에서 주소를 채운다
+struct LoopCtx {
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
 }
 
 /// 한선어 컴파일러
 pub struct HanseonCompiler {
     tokens: Vec<Token>,
+    // 토큰별 소스 줄 · 지금 컴파일 중인 문장의 줄 (0이면 컴파일러가 덧붙인 명령)
+    lines: Vec<usize>,
+    line: usize,
     pos: usize,
     // 변수 테이블: 이름 → 슬롯 번호
     vars: HashMap<String, u32>,
     var_counter: u32,
     // 함수 테이블: 이름 → 명령어 시작 위치
     funcs: HashMap<String, usize>,
+    // 안쪽 루프가 뒤
+    loops: Vec<LoopCtx>,
     output: Vec<Instruction>,
     warnings: Vec<String>,
     errors: Vec<String>,
@@ -217,19 +297,29 @@ pub struct HanseonCompiler {
 
 impl HanseonCompiler {
     pub fn new(source: &str) -> Self {
-        let tokens = lex(source);
+        let (tokens, lines) = lex(source);
         Self {
             tokens,
+            lines,
+            line: 0,
             pos: 0,
             vars: HashMap::new(),
             var_counter: 0,
             funcs: HashMap::new(),
+            loops: Vec::new(),
             output: Vec::new(),
             warnings: Vec::new(),
             errors: Vec::new(),
         }
     }
 
+    /// 앞서 정한 변수 슬롯을 이어 쓴다 (REPL — 입력마다 따로 컴파일해도 같은 전역)
+    pub fn with_vars(mut self, vars: HashMap<String, u32>) -> Self {
+        self.var_counter = vars.values().map(|&s| s + 1).max().unwrap_or(0);
+        self.vars = vars;
+        self
+    }
+
     /// 컴파일 실행
     pub fn compile(mut self) -> CompileOutput {
         while self.peek() != &Token::Eof {
@@ -237,18 +327,18 @@ impl HanseonCompiler {
         }
 
         // 마지막에 HALT 보장
+        self.line = 0;
         if self.output.last().map(|i| i.addr != OpcodeAddr::new(0,2,7)).unwrap_or(true) {
             self.emit(OpcodeAddr::new(0, 2, 7), vec![]); // 종료
         }
 
-        let var_count = self.vars.len();
-        let func_count = self.funcs.len();
         CompileOutput {
             instructions: self.output,
             warnings: self.warnings,
             errors: self.errors,
-            variables: var_count,
-            functions: func_count,
+            variables: self.vars.len(),
+            functions: self.funcs.len(),
+            var_slots: self.vars,
         }
     }
 
@@ -273,17 +363,58 @@ impl HanseonCompiler {
     }
 
     fn emit(&mut self, addr: OpcodeAddr, operands: Vec<Value>) {
-        self.output.push(Instruction::from_addr(addr, operands));
+        let mut inst = Instruction::from_addr(addr, operands);
+        inst.line = (self.line > 0).then_some(self.line);
+        self.output.push(inst);
+    }
+
+    /// 주소를 나중에 채울 점프 — 명령어 위치 반환
+    fn emit_jump(&mut self) -> usize {
+        self.emit(OpcodeAddr::new(0,2,0), vec![Value::Int(0)]);
+        self.output.len() - 1
+    }
+
+    fn patch(&mut self, at: usize, operand: usize, target: usize) {
+        self.output[at].operands[operand] = Value::Int(target as i64);
+    }
+
+    /// `{ ... }` 블록 본문
+    fn compile_block(&mut self) {
+        if self.peek() == &Token::LBrace {
+            self.advance();
+            while self.peek() != &Token::RBrace && self.peek() != &Token::Eof {
+                self.compile_statement();
+            }
+            self.expect(&Token::RBrace);
+        } else {
+            self.errors.push(format!("블록 필요: {{, 실제: {:?}", self.peek()));
+        }
+    }
+
+    /// 이름 없는 슬롯 (루프 카운터)
+    fn hidden_slot(&mut self) -> i64 {
+        self.var_counter += 1;
+        (self.var_counter - 1) as i64
     }
 
     // ── 문장 컴파일 ──
 
+    /// 문장 하나 — 내보내는 명령은 문장 첫 토큰의 줄 (블록 뒤 점프 · 반환은 머리 줄)
     fn compile_statement(&mut self) {
+        let outer = self.line;
+        self.line = self.lines.get(self.pos).copied().unwrap_or(outer);
+        self.compile_statement_inner();
+        self.line = outer;
+    }
+
+    fn compile_statement_inner(&mut self) {
         match self.peek().clone() {
             Token::Val => self.compile_val(),
             Token::Var => self.compile_var(),
             Token::If => self.compile_if(),
             Token::Loop => self.compile_loop(),
+            Token::While => self.compile_while(),
+            Token::Break | Token::Continue => self.compile_loop_jump(),
             Token::Func => self.compile_func(),
             Token::Return => self.compile_return(),
             Token::End => { self.advance(); self.emit(OpcodeAddr::new(0,2,7), vec![]); }
@@ -305,22 +436,73 @@ impl HanseonCompiler {
             Token::Not => { self.advance(); self.emit(OpcodeAddr::new(0,0,7), vec![]); }
             Token::And => { self.advance(); self.emit(OpcodeAddr::new(0,0,8), vec![]); }
 
-            // 리터럴
-            Token::Int(n) => { self.advance(); self.emit(OpcodeAddr::new(0,3,0), vec![Value::Int(n)]); }
-            Token::Float(f) => { self.advance(); self.emit(OpcodeAddr::new(0,3,0), vec![Value::Float(f)]); }
-            Token::Str(s) => { self.advance(); self.emit(OpcodeAddr::new(0,3,0), vec![Value::Str(s)]); }
-            Token::Trit(t) => {
-                self.advance();
-                match t {
-                    1 => self.emit(OpcodeAddr::new(0,0,0), vec![]),  // 참
-                    -1 => self.emit(OpcodeAddr::new(0,0,1), vec![]), // 거짓
-                    _ => self.emit(OpcodeAddr::new(0,0,2), vec![]),  // 모름
-                }
+            // 식 (리터럴 · 변수 · 함수 호출 · 괄호 · 단항 -, 뒤따르는 중위 연산자까지)
+            Token::Int(_) | Token::Float(_) | Token::Str(_) | Token::Trit(_)
+            | Token::Ident(_) | Token::LParen | Token::Minus => self.compile_expr(),
+
+            Token::RBrace => { self.advance(); } // 블록 닫기
+            Token::Eof => {}
+            _ => {
+                let tok = self.advance();
+                self.warnings.push(format!("무시된 토큰: {:?}", tok));
+            }
+        }
+    }
+
+    // ── 중위 식 — 우선순위 등반, 스택 명령으로 펼친다 ──
+    //   (5 + 3) * 2  →  넣어 5 · 넣어 3 · 더해 · 넣어 2 · 곱해
+
+    fn compile_expr(&mut self) {
+        self.compile_binary(0);
+    }
+
+    fn compile_binary(&mut self, min_prec: u8) {
+        self.compile_unary();
+        while let Some((prec, ops)) = binary_op(self.peek()) {
+            if prec < min_prec {
+                break;
+            }
+            self.advance();
+            self.compile_binary(prec + 1); // 왼쪽 결합
+            for &op in ops {
+                self.emit(op, vec![]);
+            }
+        }
+    }
+
+    fn compile_unary(&mut self) {
+        if self.peek() != &Token::Minus {
+            return self.compile_primary();
+        }
+        self.advance();
+        match self.peek().clone() {
+            // 리터럴은 접어서 음수 상수로
+            Token::Int(n) => { self.advance(); self.emit(OpcodeAddr::new(0,3,0), vec![Value::Int(-n)]); }
+            Token::Float(f) => { self.advance(); self.emit(OpcodeAddr::new(0,3,0), vec![Value::Float(-f)]); }
+            _ => {
+                self.compile_unary();
+                self.emit(OpcodeAddr::new(0,1,5), vec![]); // 음수
+            }
+        }
+    }
+
+    fn compile_primary(&mut self) {
+        match self.advance() {
+            Token::Int(n) => self.emit(OpcodeAddr::new(0,3,0), vec![Value::Int(n)]),
+            Token::Float(f) => self.emit(OpcodeAddr::new(0,3,0), vec![Value::Float(f)]),
+            Token::Str(s) => self.emit(OpcodeAddr::new(0,3,0), vec![Value::Str(s)]),
+            Token::Trit(t) => match t {
+                1 => self.emit(OpcodeAddr::new(0,0,0), vec![]),  // 참
+                -1 => self.emit(OpcodeAddr::new(0,0,1), vec![]), // 거짓
+                _ => self.emit(OpcodeAddr::new(0,0,2), vec![]),  // 모름
+            },
+            Token::LParen => {
+                self.compile_expr();
+                self.expect(&Token::RParen);
             }
 
             // 식별자: 변수 로드 또는 함수 호출
             Token::Ident(name) => {
-                self.advance();
                 if self.peek() == &Token::LParen {
                     // 함수 호출
                     self.advance(); // (
@@ -341,13 +523,7 @@ impl HanseonCompiler {
                     self.errors.push(format!("정의되지 않은 변수: {}", name));
                 }
             }
-
-            Token::RBrace => { self.advance(); } // 블록 닫기
-            Token::Eof => {}
-            _ => {
-                let tok = self.advance();
-                self.warnings.push(format!("무시된 토큰: {:?}", tok));
-            }
+            tok => self.errors.push(format!("식 필요, 실제: {:?}", tok)),
         }
     }
 
@@ -384,67 +560,138 @@ impl HanseonCompiler {
         }
     }
 
-    // ── 만약 { P블록 } 보류 { O블록 } 아니면 { T블록 } ──
+    // ── 만약 조건 { P블록 } 보류 { O블록 } 아니면 { T블록 } ──
+    //   조건 · 삼분기 P O T — 조건을 생략하면 스택 맨 위 값으로 가른다
+    //   P: ... 점프 끝 / O: ... 점프 끝 / T: ... / 끝:
     fn compile_if(&mut self) {
         self.advance(); // '만약'
+        self.compile_condition();
+        let branch = self.output.len();
+        self.emit(OpcodeAddr::new(0,4,3), vec![Value::Int(0), Value::Int(0), Value::Int(0)]);
+        let mut exits = Vec::new();
 
         // P 블록 (+1)
-        if self.peek() == &Token::LBrace {
-            self.advance();
-            while self.peek() != &Token::RBrace && self.peek() != &Token::Eof {
-                self.compile_statement();
-            }
-            self.expect(&Token::RBrace);
-        }
+        self.patch(branch, 0, self.output.len());
+        self.compile_block();
+        exits.push(self.emit_jump());
 
-        // 보류 블록 (0) - 선택적
-        if self.peek() == &Token::Neutral {
+        // 보류 블록 (0) - 선택적, 없으면 아니면으로
+        let neutral = self.peek() == &Token::Neutral;
+        if neutral {
             self.advance();
-            if self.peek() == &Token::LBrace {
-                self.advance();
-                while self.peek() != &Token::RBrace && self.peek() != &Token::Eof {
-                    self.compile_statement();
-                }
-                self.expect(&Token::RBrace);
-            }
+            self.patch(branch, 1, self.output.len());
+            self.compile_block();
+            exits.push(self.emit_jump());
         }
 
         // 아니면 블록 (-1) - 선택적
+        self.patch(branch, 2, self.output.len());
+        if !neutral {
+            self.patch(branch, 1, self.output.len());
+        }
         if self.peek() == &Token::Else {
             self.advance();
-            if self.peek() == &Token::LBrace {
-                self.advance();
-                while self.peek() != &Token::RBrace && self.peek() != &Token::Eof {
-                    self.compile_statement();
-                }
-                self.expect(&Token::RBrace);
-            }
+            self.compile_block();
+        }
+
+        let end = self.output.len();
+        for at in exits {
+            self.patch(at, 0, end);
+        }
+    }
+
+    /// 블록 앞 조건 — 중위 식 또는 후위 문장들 (`{` 전까지)
+    fn compile_condition(&mut self) {
+        while !matches!(self.peek(), Token::LBrace | Token::RBrace | Token::Eof) {
+            self.compile_statement();
         }
     }
 
     // ── 반복 N { } ──
+    //   넣어 N · 저장해 #c
+    //   머리: 불러와 #c · 삼분기 몸통 끝 끝     ; 남은 횟수 > 0 이면 몸통
+    //   몸통: ...
+    //   다음: 불러와 #c · 넣어 1 · 빼 · 저장해 #c · 점프 머리   ; 계속 → 다음
+    //   끝:                                                     ; 그만 → 끝
     fn compile_loop(&mut self) {
         self.advance(); // '반복'
-        let count = match self.advance() {
-            Token::Int(n) => n,
-            _ => { self.errors.push("반복 뒤에 횟수 필요".into()); 1 }
-        };
-
-        // 카운터 push
-        self.emit(OpcodeAddr::new(0,3,0), vec![Value::Int(count)]);
+        let start = self.output.len();
+        self.compile_condition();
+        if self.output.len() == start {
+            self.errors.push(format!("반복 뒤에 횟수 필요, 실제: {:?}", self.peek()));
+            self.emit(OpcodeAddr::new(0,3,0), vec![Value::Int(0)]);
+        }
+        let counter = self.hidden_slot();
+        self.emit(OpcodeAddr::new(0,3,7), vec![Value::Int(counter)]);
+
+        let head = self.output.len();
+        self.emit(OpcodeAddr::new(0,3,8), vec![Value::Int(counter)]);
+        let branch = self.output.len();
+        self.emit(OpcodeAddr::new(0,4,3), vec![Value::Int(0), Value::Int(0), Value::Int(0)]);
+        self.patch(branch, 0, self.output.len());
+
+        self.loops.push(LoopCtx { breaks: Vec::new(), continues: Vec::new() });
+        self.compile_block();
+        let ctx = self.loops.pop().unwrap();
+
+        let next = self.output.len();
+        self.emit(OpcodeAddr::new(0,3,8), vec![Value::Int(counter)]);
+        self.emit(OpcodeAddr::new(0,3,0), vec![Value::Int(1)]);
+        self.emit(OpcodeAddr::new(0,1,1), vec![]);
+        self.emit(OpcodeAddr::new(0,3,7), vec![Value::Int(counter)]);
+        self.emit(OpcodeAddr::new(0,2,0), vec![Value::Int(head as i64)]);
+
+        let end = self.output.len();
+        self.patch(branch, 1, end);
+        self.patch(branch, 2, end);
+        self.close_loop(ctx, next, end);
+    }
 
-        let loop_start = self.output.len();
+    // ── 동안 조건 { } ──
+    //   머리: 조건 · 삼분기 몸통 끝 끝   ; 참(P)일 때만 몸통
+    //   몸통: ... · 점프 머리            ; 계속 → 머리, 그만 → 끝
+    //   끝:
+    fn compile_while(&mut self) {
+        self.advance(); // '동안'
+        let head = self.output.len();
+        self.compile_condition();
+        if self.output.len() == head {
+            self.errors.push("동안 뒤에 조건 필요".into());
+        }
+        let branch = self.output.len();
+        self.emit(OpcodeAddr::new(0,4,3), vec![Value::Int(0), Value::Int(0), Value::Int(0)]);
+        self.patch(branch, 0, self.output.len());
+
+        self.loops.push(LoopCtx { breaks: Vec::new(), continues: Vec::new() });
+        self.compile_block();
+        let ctx = self.loops.pop().unwrap();
+        self.emit(OpcodeAddr::new(0,2,0), vec![Value::Int(head as i64)]);
+
+        let end = self.output.len();
+        self.patch(branch, 1, end);
+        self.patch(branch, 2, end);
+        self.close_loop(ctx, head, end);
+    }
 
-        if self.peek() == &Token::LBrace {
-            self.advance();
-            while self.peek() != &Token::RBrace && self.peek() != &Token::Eof {
-                self.compile_statement();
-            }
-            self.expect(&Token::RBrace);
+    // ── 그만 / 계속 ──
+    fn compile_loop_jump(&mut self) {
+        let tok = self.advance();
+        if self.loops.is_empty() {
+            self.errors.push(format!("루프 밖의 {}", if tok == Token::Break { "그만" } else { "계속" }));
+            return;
         }
+        let at = self.emit_jump();
+        let ctx = self.loops.last_mut().unwrap();
+        if tok == Token::Break { ctx.breaks.push(at) } else { ctx.continues.push(at) }
+    }
 
-        // 반복 명령
-        self.emit(OpcodeAddr::new(0,2,4), vec![Value::Int(loop_start as i64)]);
+    fn close_loop(&mut self, ctx: LoopCtx, next: usize, end: usize) {
+        for at in ctx.continues {
+            self.patch(at, 0, next);
+        }
+        for at in ctx.breaks {
+            self.patch(at, 0, end);
+        }
     }
 
     // ── 함수 이름 { } ──
@@ -493,13 +740,70 @@ impl HanseonCompiler {
     }
 }
 
+const OP_ADD: OpcodeAddr = OpcodeAddr { sector: 0, group: 1, command: 0 };
+const OP_SUB: OpcodeAddr = OpcodeAddr { sector: 0, group: 1, command: 1 };
+const OP_MUL: OpcodeAddr = OpcodeAddr { sector: 0, group: 1, command: 2 };
+const OP_DIV: OpcodeAddr = OpcodeAddr { sector: 0, group: 1, command: 3 };
+const OP_MOD: OpcodeAddr = OpcodeAddr { sector: 0, group: 1, command: 4 };
+const OP_FALSE: OpcodeAddr = OpcodeAddr { sector: 0, group: 0, command: 1 };
+const OP_EQ: OpcodeAddr = OpcodeAddr { sector: 0, group: 0, command: 3 };
+const OP_NEQ: OpcodeAddr = OpcodeAddr { sector: 0, group: 0, command: 4 };
+const OP_GT: OpcodeAddr = OpcodeAddr { sector: 0, group: 0, command: 5 };
+const OP_LT: OpcodeAddr = OpcodeAddr { sector: 0, group: 0, command: 6 };
+const OP_NOT: OpcodeAddr = OpcodeAddr { sector: 0, group: 0, command: 7 };
+
+/// 중위 연산자 → (우선순위, 펼친 명령)
+/// `a >= b` = 아니다(크다(a, b) == 거짓) — 같을 때 모름이 아닌 참이 되도록
+fn binary_op(tok: &Token) -> Option<(u8, &'static [OpcodeAddr])> {
+    Some(match tok {
+        Token::EqEq => (1, &[OP_EQ]),
+        Token::NotEq => (1, &[OP_NEQ]),
+        Token::Greater => (1, &[OP_GT]),
+        Token::Less => (1, &[OP_LT]),
+        Token::GreaterEq => (1, &[OP_GT, OP_FALSE, OP_EQ, OP_NOT]),
+        Token::LessEq => (1, &[OP_LT, OP_FALSE, OP_EQ, OP_NOT]),
+        Token::Plus => (2, &[OP_ADD]),
+        Token::Minus => (2, &[OP_SUB]),
+        Token::Star => (3, &[OP_MUL]),
+        Token::Slash => (3, &[OP_DIV]),
+        Token::Percent => (3, &[OP_MOD]),
+        _ => return None,
+    })
+}
+
 /// 한선어 소스 → TVM 프로그램 (원스톱)
 pub fn compile(source: &str) -> CompileOutput {
     HanseonCompiler::new(source).compile()
 }
 
+/// 한도 검사 컴파일 — 토큰 사전 검사(중첩·함수·문자열) 후 컴파일, 마지막에 명령어 수
+/// 깊은 중첩은 재귀 하강 전에 거부된다
+pub fn compile_limited(source: &str, limits: &ProgramLimits) -> Result<CompileOutput, ProgramLimitError> {
+    let compiler = HanseonCompiler::new(source);
+    let mut depth = 0usize;
+    let mut functions = 0usize;
+    for tok in &compiler.tokens {
+        match tok {
+            Token::LBrace | Token::LParen => {
+                depth += 1;
+                program_limits::check_nesting(depth, limits)?;
+            }
+            Token::RBrace | Token::RParen => depth = depth.saturating_sub(1),
+            Token::Func => {
+                functions += 1;
+                program_limits::check_functions(functions, limits)?;
+            }
+            Token::Str(s) => program_limits::check_string(s.len(), limits)?,
+            _ => {}
+        }
+    }
+    let output = compiler.compile();
+    program_limits::check_program(&output.instructions, limits)?;
+    Ok(output)
+}
+
 /// 한선어 → TVM → WASM (전체 파이프라인)
-pub fn compile_to_wasm(source: &str) -> Vec<u8> {
+pub fn compile_to_wasm(source: &str) -> Result<Vec<u8>, String> {
     let output = compile(source);
     crate::compiler::compile_to_wasm(&output.instructions, "crowny")
 }
@@ -541,6 +845,67 @@ mod tests {
         assert!(out.errors.is_empty(), "에러: {:?}", out.errors);
     }
 
+    fn run_captured(source: &str) -> Vec<String> {
+        let out = compile(source);
+        assert!(out.errors.is_empty(), "에러: {:?}", out.errors);
+        let mut vm = crate::vm::TVM::new();
+        vm.captured = Some(Vec::new());
+        vm.load(out.instructions);
+        vm.run().unwrap();
+        vm.captured.unwrap()
+    }
+
+    #[test]
+    fn test_nested_counted_loops() {
+        assert_eq!(run_captured("반복 2 { 반복 3 { 값 1 보여줘 } 값 2 보여줘 }").join(""), "11121112");
+        // 횟수는 변수로도 — 0 · 음수면 몸통을 건너뛴다
+        assert_eq!(run_captured("변수 n = 3\n반복 n { 값 \"a\" 보여줘 }").len(), 3);
+        assert!(run_captured("반복 0 { 값 1 보여줘 }\n반복 -2 { 값 1 보여줘 }").is_empty());
+    }
+
+    #[test]
+    fn test_while_break_continue() {
+        // 안쪽 그만은 안쪽 루프만 빠져나간다
+        assert_eq!(run_captured("반복 2 { 동안 참 { 값 1 보여줘 그만 } 값 2 보여줘 }").join(""), "1212");
+        assert_eq!(run_captured("반복 3 { 값 7 보여줘 계속 값 9 보여줘 }").join(""), "777");
+        // 모름 · 거짓 조건은 한 번도 돌지 않는다
+        assert!(run_captured("동안 모름 { 값 1 보여줘 }\n동안 거짓 { 값 1 보여줘 }").is_empty());
+
+        let out = compile("그만\n반복 { }");
+        assert_eq!(out.errors.len(), 2);
+        assert!(out.errors[0].contains("루프 밖의 그만"));
+    }
+
+    #[test]
+    fn test_infix_precedence_and_unary_minus() {
+        let out = run_captured("변수 x = (5 + 3) * 2\nx 보여줘\n\
+            2 + 3 * 4 - -1 보여줘\n-x + 1 보여줘\nx-1 보여줘\n-(x % 5) 보여줘\n값 10 값 -3 더 보여줘");
+        assert_eq!(out, vec!["16", "15", "-15", "15", "-1", "7"]);
+        // 비교는 산술보다 느슨하게 묶인다
+        assert_eq!(run_captured("1 + 1 == 2 보여줘\n2 * 3 < 5 보여줘"), vec!["P", "T"]);
+    }
+
+    #[test]
+    fn test_if_condition_three_way() {
+        let pick = |x: i64, op: &str, neutral: bool| {
+            let middle = if neutral { " 보류 { 값 0 보여줘 }" } else { "" };
+            run_captured(&format!("변수 x = {}\n만약 x {} 10 {{ 값 1 보여줘 }}{} 아니면 {{ 값 -1 보여줘 }}", x, op, middle)).join("")
+        };
+        assert_eq!(pick(16, ">", false), "1");
+        assert_eq!(pick(3, ">", false), "-1");
+        // 같으면 > 는 모름 — 보류가 없으면 아니면, 있으면 보류
+        assert_eq!(pick(10, ">", false), "-1");
+        assert_eq!(pick(10, ">", true), "0");
+        assert_eq!(pick(10, ">=", true), "1");
+        assert_eq!(pick(10, "<=", true), "1");
+        assert_eq!(pick(11, "<=", true), "-1");
+        // 조건 생략 — 스택 맨 위 값으로
+        assert_eq!(run_captured("거짓\n만약 { 값 1 보여줘 }\n값 2 보여줘"), vec!["2"]);
+
+        assert_eq!(run_captured("변수 i = 0\n동안 i < 3 { i 보여줘\n변수 i = i + 1 }").join(""), "012");
+        assert_eq!(run_captured("변수 n = 2\n반복 n * 2 { 값 1 보여줘 }").len(), 4);
+    }
+
     #[test]
     fn test_llm_call() {
         let out = compile("질문해 \"오늘 날씨?\"\n보여줘\n끝");
@@ -558,7 +923,25 @@ mod tests {
 
     #[test]
     fn test_compile_to_wasm() {
-        let wasm = compile_to_wasm("값 42\n끝");
+        let wasm = compile_to_wasm("값 42\n끝").unwrap();
         assert_eq!(&wasm[0..4], b"\0asm");
     }
+
+    #[test]
+    fn test_compile_limited() {
+        use crate::program_limits::ProgramLimitKind;
+        let deep = format!("{}값 1{}", "만약 { ".repeat(40), " }".repeat(40));
+        let err = compile_limited(&deep, &ProgramLimits::shared()).err().unwrap();
+        assert_eq!((err.kind, err.actual), (ProgramLimitKind::Nesting, 33));
+        assert!(compile_limited(&deep, &ProgramLimits::unlimited()).is_ok());
+
+        // 괄호 중첩도 같은 한도
+        let parens = format!("값 {}1{}", "(".repeat(40), ")".repeat(40));
+        assert_eq!(compile_limited(&parens, &ProgramLimits::shared()).err().unwrap().kind, ProgramLimitKind::Nesting);
+
+        let limits = ProgramLimits { max_instructions: Some(2), ..ProgramLimits::unlimited() };
+        assert_eq!(compile_limited("값 1\n값 2\n더\n끝", &limits).err().unwrap().kind, ProgramLimitKind::Instructions);
+        let limits = ProgramLimits { max_string_bytes: Some(4), ..ProgramLimits::unlimited() };
+        assert_eq!(compile_limited("값 \"너무긴문자열\"", &limits).err().unwrap().kind, ProgramLimitKind::StringSize);
+    }
 }
@@ -1,11 +1,23 @@
 ///! Arena 기반 힙 메모리 — GPT 명세
 ///! - Arena 기반 (인덱스로 접근)
-///! - GC 없음 (v1.0)
-///! - 수동 해제는 명령어로 처리
+///! - 수동 해제는 명령어로 처리 (해제)
+///! - 표시-수거 GC: 살아있는 셀이 임계에 닿으면 할당 전에 VM이 돌린다 (GC실행으로 수동도 가능)
+///!   루트(스택 · 레지스터 · 전역 · 호출 프레임)에서 Addr로 닿는 셀만 살린다
+///!   — 정수는 포인터로 보지 않으므로 주소를 정수로만 들고 있으면 수거될 수 있다
 ///! - 주소는 usize index
 
 use crate::value::Value;
 
+/// 기본 GC 임계 (살아있는 셀 수)
+pub const DEFAULT_GC_THRESHOLD: usize = 1024;
+
+/// 실행 단위 GC 통계
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcStats {
+    pub runs: u64,
+    pub collected: u64,
+}
+
 /// 힙 셀 — 할당/해제 상태 추적
 #[derive(Debug, Clone)]
 struct HeapCell {
@@ -17,6 +29,10 @@ struct HeapCell {
 pub struct Heap {
     cells: Vec<HeapCell>,
     free_list: Vec<usize>,
+    alive: usize,
+    /// 이 수 이상 살아있으면 다음 할당 전에 수거
+    pub threshold: usize,
+    stats: GcStats,
 }
 
 impl Heap {
@@ -24,11 +40,15 @@ impl Heap {
         Self {
             cells: Vec::with_capacity(4096),
             free_list: Vec::new(),
+            alive: 0,
+            threshold: DEFAULT_GC_THRESHOLD,
+            stats: GcStats::default(),
         }
     }
 
     /// 값을 힙에 할당, 주소(인덱스) 반환
     pub fn alloc(&mut self, value: Value) -> usize {
+        self.alive += 1;
         if let Some(idx) = self.free_list.pop() {
             self.cells[idx] = HeapCell { value, alive: true };
             idx
@@ -63,6 +83,7 @@ impl Heap {
             if cell.alive {
                 cell.alive = false;
                 cell.value = Value::Nil;
+                self.alive -= 1;
                 self.free_list.push(addr);
                 return true;
             }
@@ -72,7 +93,7 @@ impl Heap {
 
     /// 할당된 셀 수
     pub fn alive_count(&self) -> usize {
-        self.cells.iter().filter(|c| c.alive).count()
+        self.alive
     }
 
     /// 전체 용량
@@ -80,13 +101,107 @@ impl Heap {
         self.cells.len()
     }
 
+    /// 할당 압력 — 임계에 닿았는가
+    pub fn needs_collect(&self) -> bool {
+        self.alive >= self.threshold
+    }
+
+    /// 표시-수거 — roots에서 닿지 않는 셀을 해제하고 수거 수 반환
+    pub fn collect<'a>(&mut self, roots: impl IntoIterator<Item = &'a Value>) -> usize {
+        let mut marked = vec![false; self.cells.len()];
+        let mut pending = Vec::new();
+        for root in roots {
+            trace(root, &mut pending);
+        }
+        while let Some(addr) = pending.pop() {
+            match self.cells.get(addr) {
+                Some(cell) if cell.alive && !marked[addr] => {
+                    marked[addr] = true;
+                    trace(&cell.value, &mut pending);
+                }
+                _ => {}
+            }
+        }
+
+        let garbage: Vec<usize> = (0..self.cells.len()).filter(|&a| self.cells[a].alive && !marked[a]).collect();
+        for &addr in &garbage {
+            self.free(addr);
+        }
+        self.stats.runs += 1;
+        self.stats.collected += garbage.len() as u64;
+        // 살아남은 셀이 많으면 임계를 올려 할당마다 돌지 않게
+        self.threshold = self.threshold.max(self.alive * 2);
+        garbage.len()
+    }
+
+    pub fn stats(&self) -> GcStats {
+        self.stats
+    }
+
+    /// 실행마다 통계 초기화 (셀은 유지)
+    pub fn reset_stats(&mut self) {
+        self.stats = GcStats::default();
+    }
+
     /// 덤프 (디버그용)
-    pub fn dump(&self) {
-        println!("=== 힙 (할당: {}/{}) ===", self.alive_count(), self.cells.len());
+    pub fn dump_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("=== 힙 (할당: {}/{} · GC {}회 수거 {}) ===",
+            self.alive_count(), self.cells.len(), self.stats.runs, self.stats.collected)];
         for (i, cell) in self.cells.iter().enumerate() {
             if cell.alive {
-                println!("  [&{}] {} ({})", i, cell.value, cell.value.type_name_kr());
+                lines.push(format!("  [&{}] {} ({})", i, cell.value, cell.value.type_name_kr()));
             }
         }
+        lines
+    }
+}
+
+/// 값 안의 힙 주소 수집 (배열 · 객체는 재귀)
+fn trace(value: &Value, out: &mut Vec<usize>) {
+    match value {
+        Value::Addr(addr) => out.push(*addr),
+        Value::Array(items) => items.iter().for_each(|v| trace(v, out)),
+        Value::Object(fields) => fields.values().for_each(|v| trace(v, out)),
+        _ => {}
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_keeps_reachable_chain() {
+        let mut heap = Heap::new();
+        let leaf = heap.alloc(Value::Int(7));
+        let node = heap.alloc(Value::Array(vec![Value::Addr(leaf)]));
+        let lost = heap.alloc(Value::Str("버려짐".into()));
+        let cycle = heap.alloc(Value::Nil);
+        heap.set(cycle, Value::Addr(cycle)); // 스스로 가리키는 순환도 수거
+
+        let roots = [Value::Addr(node), Value::Int(lost as i64)];
+        assert_eq!(heap.collect(roots.iter()), 2);
+        assert_eq!(heap.get(leaf).and_then(Value::as_int), Some(7));
+        assert!(heap.get(lost).is_none() && heap.get(cycle).is_none());
+        assert_eq!(heap.stats(), GcStats { runs: 1, collected: 2 });
+        // 해제된 칸은 재사용
+        let reused = heap.alloc(Value::Int(1));
+        assert!(reused == lost || reused == cycle);
+    }
+
+    #[test]
+    fn test_threshold_grows_with_survivors() {
+        let mut heap = Heap::new();
+        heap.threshold = 4;
+        let kept: Vec<Value> = (0..3).map(|i| Value::Addr(heap.alloc(Value::Int(i)))).collect();
+        heap.alloc(Value::Int(99));
+        assert!(heap.needs_collect());
+        assert_eq!(heap.collect(kept.iter()), 1);
+        assert_eq!(heap.threshold, 6);
+        assert!(!heap.needs_collect());
+        heap.reset_stats();
+        assert_eq!(heap.stats(), GcStats::default());
     }
 }
@@ -0,0 +1,362 @@
+// ═══════════════════════════════════════════════════════════════
+// 다국어 메시지 — 한국어(기본) / English
+// 런타임 메시지(VmError · SysCall · TritShell · CLI 도움말 · 웹서버 오류)를
+// 키 → 문구 카탈로그로 찾는다. 식별자/명령어는 그대로 두고 표시 문구만 바뀐다.
+//
+// 로케일 선택 우선순위:
+//   --lang <ko|en>  >  CROWNY_LANG 환경 변수  >  crowny.toml [i18n] locale  >  ko
+//
+// 웹서버 오류 본문의 JSON 키("상태"/"오류")는 API 호환을 위해 고정 — 값만 번역
+// ═══════════════════════════════════════════════════════════════
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 환경 변수 이름
+pub const ENV_VAR: &str = "CROWNY_LANG";
+
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Ko,
+    En,
+}
+
+impl Locale {
+    /// "ko", "ko_KR.UTF-8", "한국어", "en", "en-US", "english" ...
+    pub fn parse(s: &str) -> Option<Locale> {
+        let lower = s.trim().to_ascii_lowercase();
+        let lang = lower.split(['_', '-', '.']).next().unwrap_or("");
+        match lang {
+            "ko" | "kr" | "korean" | "한국어" => Some(Locale::Ko),
+            "en" | "english" | "영어" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::Ko => "ko",
+            Locale::En => "en",
+        }
+    }
+
+    /// 프로세스 전역으로 적용
+    pub fn install(self) {
+        LOCALE.store(self as u8, Ordering::Relaxed);
+    }
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::Ko,
+    }
+}
+
+// ─────────────────────────────────────────────
+// 로케일 결정
+// ─────────────────────────────────────────────
+
+/// 인자 목록에서 --lang 값 (파싱 오류 메시지도 번역하려고 파서보다 먼저 본다)
+pub fn cli_locale(args: &[String]) -> Option<Locale> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            break;
+        }
+        if let Some(v) = arg.strip_prefix("--lang=") {
+            return Locale::parse(v);
+        }
+        if arg == "--lang" {
+            return iter.next().and_then(|v| Locale::parse(v));
+        }
+    }
+    None
+}
+
+/// crowny.toml 텍스트에서 [i18n] locale — 다른 설정 오류와 무관하게 읽는다
+pub fn config_locale(toml: &str) -> Option<Locale> {
+    match crate::config::parse_toml(toml).ok()?.get("i18n.locale")? {
+        crate::config::TomlValue::Str(s) => Locale::parse(s),
+        _ => None,
+    }
+}
+
+/// 우선순위대로 첫 번째 유효 값
+pub fn resolve(cli: Option<Locale>, env: Option<&str>, config: Option<Locale>) -> Locale {
+    cli.or_else(|| env.and_then(Locale::parse))
+        .or(config)
+        .unwrap_or_default()
+}
+
+/// 실행 환경에서 로케일 결정 (현재 디렉터리의 crowny.toml 포함)
+pub fn detect(args: &[String]) -> Locale {
+    let env = std::env::var(ENV_VAR).ok();
+    let config = std::fs::read_to_string("crowny.toml").ok().and_then(|t| config_locale(&t));
+    resolve(cli_locale(args), env.as_deref(), config)
+}
+
+// ─────────────────────────────────────────────
+// 카탈로그
+// ─────────────────────────────────────────────
+
+/// (키, 한국어, English) — `{}`는 순서대로 채워진다
+const CATALOG: &[(&str, &str, &str)] = &[
+    // VmError
+    ("vm.stack_underflow", "[스택부족] '{}'", "[stack underflow] '{}'"),
+    ("vm.type_error", "[타입오류] {}", "[type error] {}"),
+    ("vm.division_by_zero", "[오류] 0으로 나눌 수 없음", "[error] division by zero"),
+    ("vm.invalid_opcode", "[알수없는명령] ({},{},{})", "[invalid opcode] ({},{},{})"),
+    ("vm.halted", "[종료]", "[halted]"),
+    ("vm.heap_error", "[힙오류] {}", "[heap error] {}"),
+    ("vm.limit_exceeded", "[한도초과] {}", "[limit exceeded] {}"),
+    ("vm.budget_exceeded", "[작업예산초과] {}", "[task budget exceeded] {}"),
+    ("vm.stack_overflow", "[호출스택초과] 깊이 {}", "[call stack overflow] depth {}"),
+    ("vm.custom", "[오류] {}", "[error] {}"),
+    ("limit.cycles", "사이클", "cycles"),
+    ("limit.heap", "힙", "heap"),
+    ("limit.output", "출력", "output"),
+    ("limit.wall_clock", "실행시간", "wall clock"),
+    ("plimit.exceeded", "[프로그램한도] {} {} > {}", "[program limit] {} {} > {}"),
+    ("plimit.line", "{}행", "line {}"),
+    ("plimit.instructions", "명령어 수", "instruction count"),
+    ("plimit.nesting", "중첩 깊이", "nesting depth"),
+    ("plimit.string_size", "문자열 리터럴 바이트", "string literal bytes"),
+    ("plimit.functions", "함수 수", "function count"),
+    // SysCall
+    ("os.out_of_memory", "메모리 부족: {}KB 필요, {}KB 남음", "out of memory: {}KB needed, {}KB free"),
+    ("os.kill_protected", "커널/init 프로세스 종료 불가", "cannot kill kernel/init process"),
+    ("os.no_pid", "PID:{} 없음", "no such process PID:{}"),
+    ("os.no_children", "PID:{} 자식 프로세스 없음", "PID:{} has no child processes"),
+    ("os.is_directory", "디렉토리입니다", "is a directory"),
+    ("os.no_file", "파일 없음", "no such file"),
+    ("os.dir_not_empty", "비어있지 않은 디렉토리", "directory not empty"),
+    // TritShell
+    ("shell.not_found", "crwnsh: '{}' 명령어를 찾을 수 없습니다", "crwnsh: command not found: '{}'"),
+    ("shell.cd_missing", "cd: '{}' 없음", "cd: no such directory: '{}'"),
+    ("shell.cat_missing", "cat: '{}' 없음", "cat: no such file: '{}'"),
+    ("shell.help.title", "━━━ TritShell 명령어 ━━━", "━━━ TritShell commands ━━━"),
+    ("shell.help.ps", "프로세스 목록", "list processes"),
+    ("shell.help.spawn", "프로세스 생성 (이름, 메모리KB)", "spawn process (name, memory KB)"),
+    ("shell.help.kill", "프로세스 종료", "kill process"),
+    ("shell.help.ls", "파일 목록", "list files"),
+    ("shell.help.cd", "디렉토리 이동", "change directory"),
+    ("shell.help.cat", "파일 읽기", "print file"),
+    ("shell.help.mkdir", "디렉토리 생성", "create directory"),
+    ("shell.help.touch", "빈 파일 생성", "create empty file"),
+    ("shell.help.tree", "디렉토리 트리", "directory tree"),
+    ("shell.help.pwd", "현재 경로", "current path"),
+    ("shell.help.env", "환경 변수", "environment variables"),
+    ("shell.help.stat", "시스템 상태", "system status"),
+    ("shell.help.uname", "OS 정보", "OS information"),
+    ("shell.help.whoami", "현재 사용자", "current user"),
+    ("shell.help.history", "명령어 이력", "command history"),
+    ("shell.help.wait", "자식 대기 — 다음 틱에 좀비를 거둔다", "wait for children — zombies reaped on next tick"),
+    ("shell.help.top", "틱을 돌리고 CPU 사용률 표시", "run scheduler ticks and show CPU usage"),
+    ("shell.help.echo", "인자 출력 ($변수 펼침)", "print arguments ($VAR expanded)"),
+    ("shell.help.test", "비교 (= != -eq -lt -z -e) → P/T", "compare (= != -eq -lt -z -e) → P/T"),
+    ("shell.help.crwnsh", "TritFS의 .crwnsh 스크립트 실행", "run a .crwnsh script from TritFS"),
+    ("shell.test_syntax", "test: 잘못된 식 '{}'", "test: malformed expression '{}'"),
+    ("shell.script_missing", "crwnsh: 스크립트 '{}' 없음", "crwnsh: no such script '{}'"),
+    ("shell.script_syntax", "crwnsh: {}행: '{}' 필요", "crwnsh: line {}: expected '{}'"),
+    ("shell.script_unexpected", "crwnsh: {}행: 예상치 못한 '{}'", "crwnsh: line {}: unexpected '{}'"),
+    ("shell.loop_limit", "crwnsh: 반복 {}회 초과 — 중단", "crwnsh: loop exceeded {} iterations — stopped"),
+    ("shell.depth_limit", "crwnsh: 호출 깊이 {} 초과", "crwnsh: call depth {} exceeded"),
+    // CLI
+    ("cli.usage", "사용법", "Usage"),
+    ("cli.aliases", "별칭", "Aliases"),
+    ("cli.commands", "명령", "Commands"),
+    ("cli.options", "옵션", "Options"),
+    ("cli.command_placeholder", "<명령>", "<command>"),
+    ("cli.unknown_command", "알 수 없는 명령: {}", "unknown command: {}"),
+    ("cli.unknown_subcommand", "알 수 없는 하위 명령: {} {}", "unknown subcommand: {} {}"),
+    ("cli.unknown_flag", "알 수 없는 옵션: {}", "unknown option: {}"),
+    ("cli.missing_value", "옵션 값 누락: --{}", "missing option value: --{}"),
+    ("cli.missing_arg", "인자 누락: {} <{}>", "missing argument: {} <{}>"),
+    ("cli.extra_arg", "불필요한 인자: {} ... {}", "unexpected argument: {} ... {}"),
+    ("cli.see_help", "'{} help' 로 사용법 확인", "run '{} help' for usage"),
+    ("cli.bad_lang", "--lang: ko 또는 en ({})", "--lang: expected ko or en ({})"),
+    ("cli.error", "오류: {}", "error: {}"),
+    ("cli.run_header", "=== CROWNIN TVM — {} ({} 명령어) ===", "=== CROWNIN TVM — {} ({} instructions) ==="),
+    ("cli.run_footer", "=== 정상 종료 ({}사이클{}) ===", "=== finished ({} cycles{}) ==="),
+    ("cli.run_gc", " · GC {}회 수거 {}", " · GC {} runs, {} collected"),
+    // 인자 · 옵션 값 자리 (한국어 이름으로 찾는다)
+    ("arg.6trits", "6트릿", "6trits"),
+    ("arg.tx-number", "TX번호", "tx-number"),
+    ("arg.value", "값", "value"),
+    ("arg.term", "검색어", "term"),
+    ("arg.account", "계정", "account"),
+    ("arg.pubkey-hex", "공개키hex", "pubkey-hex"),
+    ("arg.pubkeys", "공개키,...", "pubkey,..."),
+    ("arg.dir", "디렉터리", "dir"),
+    ("arg.message", "메시지", "message"),
+    ("arg.number", "번호", "number"),
+    ("arg.delta", "변화량", "delta"),
+    ("arg.alias", "별칭", "alias"),
+    ("arg.block", "블록", "block"),
+    ("arg.reason", "사유", "reason"),
+    ("arg.shell", "셸", "shell"),
+    ("arg.source", "소스", "source"),
+    ("arg.owner", "소유자", "owner"),
+    ("arg.artifact", "아티팩트", "artifact"),
+    ("arg.operator", "운영자", "operator"),
+    ("arg.name", "이름", "name"),
+    ("arg.name-or-file-hsn", "이름|파일.hsn", "name|file.hsn"),
+    ("arg.schedule", "일정", "schedule"),
+    ("arg.integer", "정수", "integer"),
+    ("arg.address", "주소", "address"),
+    ("arg.addresses", "주소,…", "address,…"),
+    ("arg.subject", "주체", "subject"),
+    ("arg.query", "질의", "query"),
+    ("arg.key", "키", "key"),
+    ("arg.file", "파일", "file"),
+    ("arg.file-hsn", "파일.hsn", "file.hsn"),
+    ("arg.package", "패키지", "package"),
+    ("arg.output", "출력", "output"),
+    ("arg.output-html", "출력.html", "output.html"),
+    ("arg.path", "경로", "path"),
+    ("arg.command", "명령", "command"),
+    ("arg.count", "개수", "count"),
+    ("arg.bytes", "바이트", "bytes"),
+    ("arg.version", "버전", "version"),
+    ("arg.report-html", "보고서.html", "report.html"),
+    ("arg.report-md", "보고서.md", "report.md"),
+    ("arg.session-crs", "세션.crs", "session.crs"),
+    ("arg.at", "시점", "at"),
+    ("arg.secs", "초", "secs"),
+    ("arg.format", "형식", "format"),
+    // 웹서버 오류 본문
+    ("web.body_too_large", "본문 크기 초과", "payload too large"),
+    ("web.body_too_large_detail", "본문 크기 초과 ({} > {})", "payload too large ({} > {})"),
+    ("web.cors_origin", "CORS 오리진 거부", "CORS origin rejected"),
+    ("web.cors_method", "CORS 메서드 거부", "CORS method rejected"),
+    ("web.cors_header", "CORS 헤더 거부", "CORS header rejected"),
+    ("web.ctp_denied", "CTP 권한 거부", "CTP permission denied"),
+    ("web.not_found", "경로 없음", "route not found"),
+    ("web.ctp_auth_required", "CTP 인증 필요", "CTP authentication required"),
+    ("web.rate_limited", "요청 한도 초과", "rate limit exceeded"),
+    ("web.program_limit", "프로그램 한도 초과", "program limit exceeded"),
+    ("web.run_limit", "실행 한도 초과", "execution limit exceeded"),
+    ("web.param_missing", "필수 항목 없음: {}", "missing parameter: {}"),
+    ("web.param_invalid", "잘못된 값: {}={}", "invalid value: {}={}"),
+    // 능력 토큰
+    ("cap.token_missing", "능력 토큰 없음", "capability token missing"),
+    ("cap.token_malformed", "능력 토큰 형식 오류", "malformed capability token"),
+    ("cap.token_signature", "능력 토큰 서명 불일치", "capability token signature mismatch"),
+    ("cap.token_expired", "능력 토큰 만료", "capability token expired"),
+    ("cap.token_scope", "능력 토큰 범위 부족: {}", "capability token lacks scope: {}"),
+];
+
+/// 로케일별 문구 — 없는 키는 키 자체 (누락이 눈에 띄도록)
+pub fn lookup(locale: Locale, key: &'static str) -> &'static str {
+    match CATALOG.iter().find(|(k, _, _)| *k == key) {
+        Some((_, ko, en)) => match locale {
+            Locale::Ko => ko,
+            Locale::En => en,
+        },
+        None => key,
+    }
+}
+
+/// 현재 로케일 문구
+pub fn t(key: &'static str) -> &'static str {
+    lookup(locale(), key)
+}
+
+/// 인자 · 옵션 값 자리 이름 (<파일> → <file>) — 카탈로그에 없으면 그대로
+pub fn lookup_placeholder(locale: Locale, name: &'static str) -> &'static str {
+    match CATALOG.iter().find(|(k, ko, _)| k.starts_with("arg.") && *ko == name) {
+        Some((_, ko, en)) => match locale {
+            Locale::Ko => ko,
+            Locale::En => en,
+        },
+        None => name,
+    }
+}
+
+pub fn placeholder(name: &'static str) -> &'static str {
+    lookup_placeholder(locale(), name)
+}
+
+/// 한국어 원문 + 선택적 영어 번역 중 현재 로케일 것 (CLI 명령 설명 등)
+pub fn pick(ko: &'static str, en: Option<&'static str>) -> &'static str {
+    match (locale(), en) {
+        (Locale::En, Some(en)) => en,
+        _ => ko,
+    }
+}
+
+/// `{}` 자리를 순서대로 채운다 (모자라면 그대로 둔다)
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        match args.next() {
+            Some(a) => out.push_str(&a.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 번역된 문구 — tr!("키") / tr!("키", 인자...)
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::i18n::t($key).to_string()
+    };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::t($key), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+pub(crate) use tr;
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parse_and_priority() {
+        assert_eq!(Locale::parse("en_US.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::parse("ko-KR"), Some(Locale::Ko));
+        assert_eq!(Locale::parse("한국어"), Some(Locale::Ko));
+        assert_eq!(Locale::parse("fr"), None);
+
+        let args: Vec<String> = ["run", "a.hsn", "--lang=en"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(cli_locale(&args), Some(Locale::En));
+        let args: Vec<String> = ["--", "--lang", "en"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(cli_locale(&args), None);
+
+        // CLI > 환경 변수 > 설정 > 기본
+        assert_eq!(resolve(Some(Locale::Ko), Some("en"), Some(Locale::En)), Locale::Ko);
+        assert_eq!(resolve(None, Some("en"), Some(Locale::Ko)), Locale::En);
+        assert_eq!(resolve(None, Some("xx"), Some(Locale::En)), Locale::En);
+        assert_eq!(resolve(None, None, None), Locale::Ko);
+        assert_eq!(config_locale("[i18n]\nlocale = \"en\"\n[log]\nlevel = \"bogus\""), Some(Locale::En));
+    }
+
+    #[test]
+    fn test_catalog_complete_and_fill() {
+        for (key, ko, en) in CATALOG {
+            assert_eq!(ko.matches("{}").count(), en.matches("{}").count(), "{}", key);
+            assert_eq!(CATALOG.iter().filter(|(k, _, _)| k == key).count(), 1, "중복 키 {}", key);
+        }
+        assert_eq!(lookup(Locale::En, "os.no_file"), "no such file");
+        assert_eq!(lookup(Locale::Ko, "없는.키"), "없는.키");
+        assert_eq!(fill(lookup(Locale::En, "os.out_of_memory"), &[&64, &32]),
+            "out of memory: 64KB needed, 32KB free");
+        assert_eq!(fill("{} {}", &[&1]), "1 {}");
+        assert_eq!(lookup_placeholder(Locale::En, "파일"), "file");
+        assert_eq!(lookup_placeholder(Locale::Ko, "파일"), "파일");
+        assert_eq!(lookup_placeholder(Locale::En, "N"), "N");
+        assert_eq!(lookup(Locale::En, "web.run_limit"), "execution limit exceeded");
+    }
+}
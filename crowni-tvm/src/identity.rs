@@ -0,0 +1,217 @@
+// ═══════════════════════════════════════════════════════════════
+// 노드 신원 — Ed25519 키쌍에서 노드 ID를 유도하고 핸드셰이크 · 투표에 서명
+//
+//   노드 ID = "node-" + hex(SHA256(공개 키)) 앞 24자
+//     → 공개 키와 서명 없이는 남의 ID를 주장할 수 없다
+//   보관:  브라우저 → NodeStorage(IndexedDB) key:identity
+//          네이티브 → 비밀 파일 .crowny/secrets/node.key (0600, 시드 hex)
+//   검증:  주장한 ID == 공개 키에서 유도한 ID  그리고  서명 일치
+// ═══════════════════════════════════════════════════════════════
+
+use std::path::Path;
+
+use crate::crypto::{self, Key, Signature};
+
+pub const NODE_ID_PREFIX: &str = "node-";
+/// 네이티브 노드 기본 비밀 키 파일
+pub const SECRET_FILE: &str = ".crowny/secrets/node.key";
+/// NodeStorage 안의 키 이름 (key:identity)
+pub const STORAGE_KEY: &str = "identity";
+
+/// 공개 키 → 노드 ID
+pub fn node_id_for(public: &Key) -> String {
+    format!("{}{}", NODE_ID_PREFIX, &crypto::to_hex(&crypto::sha256(public))[..24])
+}
+
+/// 노드 신원 키쌍 (Ed25519 시드 + 공개 키)
+#[derive(Clone)]
+pub struct NodeIdentity {
+    seed: Key,
+    pub public: Key,
+}
+
+impl NodeIdentity {
+    pub fn generate() -> Self {
+        Self::from_seed(crypto::random_bytes())
+    }
+
+    pub fn from_seed(seed: Key) -> Self {
+        Self { public: crypto::ed25519_public(&seed), seed }
+    }
+
+    /// 저장된 hex 시드에서 복원
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let seed: Key = crypto::from_hex(hex.trim())?.try_into().ok()?;
+        Some(Self::from_seed(seed))
+    }
+
+    /// 저장용 시드 (비밀)
+    pub fn seed(&self) -> &Key {
+        &self.seed
+    }
+
+    pub fn node_id(&self) -> String {
+        node_id_for(&self.public)
+    }
+
+    pub fn public_hex(&self) -> String {
+        crypto::to_hex(&self.public)
+    }
+
+    pub fn sign(&self, payload: &[u8]) -> Signature {
+        crypto::ed25519_sign(&self.seed, payload)
+    }
+
+    /// 비밀 파일에서 읽거나, 없으면 새로 만들어 저장 (재시작해도 같은 ID)
+    pub fn load_or_create(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::from_hex(&text).ok_or_else(|| format!("{}: 손상된 신원 키", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let identity = Self::generate();
+                write_secret(path, &crypto::to_hex(&identity.seed))?;
+                Ok(identity)
+            }
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
+
+impl std::fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NodeIdentity({})", self.node_id())
+    }
+}
+
+/// 소유자만 읽을 수 있는 파일로 기록
+fn write_secret(path: &Path, contents: &str) -> Result<(), String> {
+    use std::io::Write;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut f = options.open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    f.write_all(contents.as_bytes()).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// ─────────────────────────────────────────────
+// 서명 대상 · 검증
+// ─────────────────────────────────────────────
+
+/// 핸드셰이크 서명 대상 — 도메인 구분자로 다른 메시지 서명의 재사용 방지
+pub fn handshake_payload(node_id: &str, node_type: &str, version: &str) -> Vec<u8> {
+    format!("crowny-handshake\0{}\0{}\0{}", node_id, node_type, version).into_bytes()
+}
+
+/// 투표 서명 대상 — kind: "proposal" | "block" | "election"
+pub fn vote_payload(kind: &str, round: u64, voter: &str, vote: i8) -> Vec<u8> {
+    format!("crowny-vote\0{}\0{}\0{}\0{}", kind, round, voter, vote).into_bytes()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdentityError {
+    MissingSignature(String),
+    /// 공개 키에서 유도한 ID와 주장한 ID가 다름
+    IdMismatch { claimed: String, derived: String },
+    BadSignature(String),
+    /// 핸드셰이크로 키를 알리지 않은 노드
+    UnknownKey(String),
+    Malformed(String),
+}
+
+impl std::fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSignature(id) => write!(f, "서명 없음: {}", id),
+            Self::IdMismatch { claimed, derived } => write!(f, "ID 불일치: 주장 {} ≠ 키 {}", claimed, derived),
+            Self::BadSignature(id) => write!(f, "서명 불일치: {}", id),
+            Self::UnknownKey(id) => write!(f, "알 수 없는 공개 키: {}", id),
+            Self::Malformed(what) => write!(f, "형식 오류: {}", what),
+        }
+    }
+}
+
+/// hex 서명 → 바이트
+pub fn parse_signature(hex: &str) -> Result<Signature, IdentityError> {
+    crypto::from_hex(hex).and_then(|b| b.try_into().ok()).ok_or_else(|| IdentityError::Malformed("서명".into()))
+}
+
+pub fn parse_public(hex: &str) -> Result<Key, IdentityError> {
+    crypto::from_hex(hex).and_then(|b| b.try_into().ok()).ok_or_else(|| IdentityError::Malformed("공개 키".into()))
+}
+
+/// 이미 아는 공개 키로 서명만 검사
+pub fn verify(node_id: &str, public: &Key, payload: &[u8], signature: &Signature) -> Result<(), IdentityError> {
+    if crypto::ed25519_verify(public, payload, signature) {
+        Ok(())
+    } else {
+        Err(IdentityError::BadSignature(node_id.to_string()))
+    }
+}
+
+/// ID 주장 검사 — 공개 키가 ID를 만들고, 그 키로 서명이 맞아야 한다
+pub fn verify_claim(claimed_id: &str, public: &Key, payload: &[u8], signature: &Signature) -> Result<(), IdentityError> {
+    let derived = node_id_for(public);
+    if derived != claimed_id {
+        return Err(IdentityError::IdMismatch { claimed: claimed_id.to_string(), derived });
+    }
+    verify(claimed_id, public, payload, signature)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_id_derived_from_key() {
+        let id = NodeIdentity::from_seed([1; 32]);
+        assert!(id.node_id().starts_with(NODE_ID_PREFIX));
+        assert_eq!(id.node_id().len(), NODE_ID_PREFIX.len() + 24);
+        assert_eq!(id.node_id(), NodeIdentity::from_hex(&crypto::to_hex(id.seed())).unwrap().node_id());
+        assert_ne!(id.node_id(), NodeIdentity::from_seed([2; 32]).node_id());
+        assert!(NodeIdentity::from_hex("zz").is_none());
+    }
+
+    #[test]
+    fn test_claim_rejects_foreign_id_and_forged_signature() {
+        let alice = NodeIdentity::from_seed([1; 32]);
+        let mallory = NodeIdentity::from_seed([6; 32]);
+        let payload = handshake_payload(&alice.node_id(), "Full", "0.4.0");
+        let sig = alice.sign(&payload);
+        assert!(verify_claim(&alice.node_id(), &alice.public, &payload, &sig).is_ok());
+
+        // 남의 ID를 자기 키로 주장
+        let forged = mallory.sign(&handshake_payload(&alice.node_id(), "Full", "0.4.0"));
+        assert!(matches!(verify_claim(&alice.node_id(), &mallory.public, &payload, &forged), Err(IdentityError::IdMismatch { .. })));
+        // 서명을 다른 메시지에 재사용
+        let vote = vote_payload("block", 1, &alice.node_id(), 1);
+        assert_eq!(verify_claim(&alice.node_id(), &alice.public, &vote, &sig), Err(IdentityError::BadSignature(alice.node_id())));
+        assert!(parse_signature(&crypto::to_hex(&sig)).is_ok() && parse_signature("00").is_err());
+    }
+
+    #[test]
+    fn test_secret_file_persists_identity() {
+        let dir = std::env::temp_dir().join(format!("crowny-identity-{}", std::process::id()));
+        let path = dir.join("secrets").join("node.key");
+        let _ = std::fs::remove_dir_all(&dir);
+        let first = NodeIdentity::load_or_create(&path).unwrap();
+        let again = NodeIdentity::load_or_create(&path).unwrap();
+        assert_eq!(first.node_id(), again.node_id());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        std::fs::write(&path, "not hex").unwrap();
+        assert!(NodeIdentity::load_or_create(&path).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::output::{JsonObject, say};
 
 // ═══════════════════════════════════════
 // 공통: 3진 판정
@@ -54,6 +55,18 @@ pub struct IndustryDecision {
 #[derive(Debug, Clone)]
 pub enum RiskLevel { Low, Medium, High, Critical }
 
+impl RiskLevel {
+    /// 기계용 코드
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+}
+
 impl std::fmt::Display for RiskLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -65,12 +78,37 @@ impl std::fmt::Display for RiskLevel {
     }
 }
 
+impl IndustryDecision {
+    pub fn ctp_string(&self) -> String {
+        self.ctp.iter().map(|t| match t { 1 => 'P', -1 => 'T', _ => 'O' }).collect()
+    }
+
+    /// 안정 필드 순서 JSON (schema: crowny.industry_decision)
+    pub fn to_json(&self) -> JsonObject {
+        let votes = self.ai_votes.iter()
+            .map(|(model, trit, reason)| JsonObject::new()
+                .str("model", model)
+                .trit("state", trit.val())
+                .str("reason", reason))
+            .collect();
+        JsonObject::schema("crowny.industry_decision")
+            .trit("state", self.consensus.val())
+            .str("category", &self.category)
+            .str("query", &self.query)
+            .float("confidence", self.confidence)
+            .str("risk_level", self.risk_level.code())
+            .str("recommendation", &self.recommendation)
+            .str("ctp", &self.ctp_string())
+            .int("timestamp", self.timestamp as i64)
+            .objects("votes", votes)
+    }
+}
+
 impl std::fmt::Display for IndustryDecision {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ctp: String = self.ctp.iter().map(|t| match t { 1 => 'P', -1 => 'T', _ => 'O' }).collect();
         write!(f, "[{}] {} — {} ({:.0}%) | 위험: {} | CTP: {}",
             self.category, self.consensus, self.recommendation,
-            self.confidence * 100.0, self.risk_level, ctp)
+            self.confidence * 100.0, self.risk_level, self.ctp_string())
     }
 }
 
@@ -537,17 +575,17 @@ fn now_ms() -> u64 {
 
 // ═══ 데모 ═══
 
-pub fn demo_industry() {
-    println!("╔═══════════════════════════════════════════╗");
-    println!("║  Crowny Industry Applications             ║");
-    println!("║  산업 적용 — 의료 · 교육 · 트레이딩 AI     ║");
-    println!("╚═══════════════════════════════════════════╝");
-    println!();
+pub fn demo_industry() -> i8 {
+    say!("╔═══════════════════════════════════════════╗");
+    say!("║  Crowny Industry Applications             ║");
+    say!("║  산업 적용 — 의료 · 교육 · 트레이딩 AI     ║");
+    say!("╚═══════════════════════════════════════════╝");
+    say!();
 
     // ━━━ 1. 의료 AI ━━━
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("  🏥 의료 AI 판단 시스템");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("  🏥 의료 AI 판단 시스템");
+    say!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     let mut med_ai = MedicalAI::new();
 
@@ -560,19 +598,19 @@ pub fn demo_industry() {
         allergies: vec![],
     };
     let d1 = med_ai.evaluate(&patient1, "관상동맥 조영술 시행 여부?");
-    println!("\n  환자: {} ({}/{}세)", patient1.name, patient1.gender, patient1.age);
-    println!("  증상: {:?}", patient1.symptoms);
-    println!("  BP: {}/{} | HR: {} | SpO2: {}% | 체온: {}°C",
+    say!("\n  환자: {} ({}/{}세)", patient1.name, patient1.gender, patient1.age);
+    say!("  증상: {:?}", patient1.symptoms);
+    say!("  BP: {}/{} | HR: {} | SpO2: {}% | 체온: {}°C",
         patient1.vitals.bp_systolic, patient1.vitals.bp_diastolic,
         patient1.vitals.heart_rate, patient1.vitals.spo2, patient1.vitals.temperature);
-    println!("  질문: {}", d1.question);
+    say!("  질문: {}", d1.question);
     for (name, trit, reason) in &d1.decision.ai_votes {
-        println!("    {} → {} — {}", name, trit, reason);
+        say!("    {} → {} — {}", name, trit, reason);
     }
-    println!("  ──────────────────────────");
-    println!("  {}", d1.decision);
+    say!("  ──────────────────────────");
+    say!("  {}", d1.decision);
     if !d1.suggested_tests.is_empty() {
-        println!("  추가 검사: {:?}", d1.suggested_tests);
+        say!("  추가 검사: {:?}", d1.suggested_tests);
     }
 
     // 케이스 2: 고위험 환자
@@ -584,25 +622,25 @@ pub fn demo_industry() {
         allergies: vec!["페니실린".into()],
     };
     let d2 = med_ai.evaluate(&patient2, "응급 수술 시행 여부?");
-    println!("\n  환자: {} ({}/{}세)", patient2.name, patient2.gender, patient2.age);
-    println!("  증상: {:?}", patient2.symptoms);
-    println!("  BP: {}/{} | HR: {} | SpO2: {}% | 혈당: {}",
+    say!("\n  환자: {} ({}/{}세)", patient2.name, patient2.gender, patient2.age);
+    say!("  증상: {:?}", patient2.symptoms);
+    say!("  BP: {}/{} | HR: {} | SpO2: {}% | 혈당: {}",
         patient2.vitals.bp_systolic, patient2.vitals.bp_diastolic,
         patient2.vitals.heart_rate, patient2.vitals.spo2, patient2.vitals.blood_sugar);
-    println!("  질문: {}", d2.question);
+    say!("  질문: {}", d2.question);
     for (name, trit, reason) in &d2.decision.ai_votes {
-        println!("    {} → {} — {}", name, trit, reason);
+        say!("    {} → {} — {}", name, trit, reason);
     }
-    println!("  ──────────────────────────");
-    println!("  {}", d2.decision);
+    say!("  ──────────────────────────");
+    say!("  {}", d2.decision);
     if !d2.contraindications.is_empty() {
-        println!("  금기사항: {:?}", d2.contraindications);
+        say!("  금기사항: {:?}", d2.contraindications);
     }
 
     // ━━━ 2. 교육 AI ━━━
-    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("  📚 교육 AI 어시스턴트");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("  📚 교육 AI 어시스턴트");
+    say!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     let mut edu_ai = EducationAI::new();
 
@@ -618,19 +656,19 @@ pub fn demo_industry() {
         attendance_rate: 0.95,
     };
     let e1 = edu_ai.evaluate(&student1, "심화 수학 올림피아드 과정 진행?");
-    println!("\n  학생: {} ({})", student1.name, student1.grade);
-    println!("  성적: {}", student1.subjects.iter()
+    say!("\n  학생: {} ({})", student1.name, student1.grade);
+    say!("  성적: {}", student1.subjects.iter()
         .map(|s| format!("{}:{:.0}({})", s.subject, s.score, s.trend.label()))
         .collect::<Vec<_>>().join(" | "));
-    println!("  학습유형: {} | 출석: {:.0}%", student1.learning_style, student1.attendance_rate * 100.0);
-    println!("  질문: {}", e1.decision.query);
+    say!("  학습유형: {} | 출석: {:.0}%", student1.learning_style, student1.attendance_rate * 100.0);
+    say!("  질문: {}", e1.decision.query);
     for (name, trit, reason) in &e1.decision.ai_votes {
-        println!("    {} → {} — {}", name, trit, reason);
+        say!("    {} → {} — {}", name, trit, reason);
     }
-    println!("  ──────────────────────────");
-    println!("  {}", e1.decision);
-    println!("  경로: {} | 주 {}시간", e1.recommended_path, e1.weekly_hours);
-    println!("  방법: {:?}", e1.methods);
+    say!("  ──────────────────────────");
+    say!("  {}", e1.decision);
+    say!("  경로: {} | 주 {}시간", e1.recommended_path, e1.weekly_hours);
+    say!("  방법: {:?}", e1.methods);
 
     let student2 = Student {
         id: "S002".into(), name: "최부진".into(), grade: "중3".into(),
@@ -643,22 +681,22 @@ pub fn demo_industry() {
         attendance_rate: 0.72,
     };
     let e2 = edu_ai.evaluate(&student2, "기초 보충 학습 계획?");
-    println!("\n  학생: {} ({})", student2.name, student2.grade);
-    println!("  성적: {}", student2.subjects.iter()
+    say!("\n  학생: {} ({})", student2.name, student2.grade);
+    say!("  성적: {}", student2.subjects.iter()
         .map(|s| format!("{}:{:.0}({})", s.subject, s.score, s.trend.label()))
         .collect::<Vec<_>>().join(" | "));
-    println!("  질문: {}", e2.decision.query);
+    say!("  질문: {}", e2.decision.query);
     for (name, trit, reason) in &e2.decision.ai_votes {
-        println!("    {} → {} — {}", name, trit, reason);
+        say!("    {} → {} — {}", name, trit, reason);
     }
-    println!("  ──────────────────────────");
-    println!("  {}", e2.decision);
-    println!("  집중 과목: {:?} | 주 {}시간", e2.focus_subjects, e2.weekly_hours);
+    say!("  ──────────────────────────");
+    say!("  {}", e2.decision);
+    say!("  집중 과목: {:?} | 주 {}시간", e2.focus_subjects, e2.weekly_hours);
 
     // ━━━ 3. 트레이딩 AI ━━━
-    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("  📈 트레이딩 AI 시그널");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("  📈 트레이딩 AI 시그널");
+    say!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     let mut trade_ai = TradingAI::new();
 
@@ -682,22 +720,36 @@ pub fn demo_industry() {
 
     for market in &markets {
         let signal = trade_ai.analyze(market);
-        println!("\n  {} — ${:.2} ({:+.1}%)", market.symbol, market.price, market.change_24h);
-        println!("  RSI: {:.0} | MACD: {:.2} | BB: {:.2} | F&G: {}",
+        say!("\n  {} — ${:.2} ({:+.1}%)", market.symbol, market.price, market.change_24h);
+        say!("  RSI: {:.0} | MACD: {:.2} | BB: {:.2} | F&G: {}",
             market.rsi, market.macd, market.bollinger_pos, market.fear_greed);
         for (name, trit, reason) in &signal.decision.ai_votes {
-            println!("    {} → {} — {}", name, trit, reason);
+            say!("    {} → {} — {}", name, trit, reason);
         }
-        println!("  ──────────────────────────");
-        println!("  {}", signal.decision);
-        println!("  액션: {} | 진입: ${:.2} | SL: ${:.2} | TP: ${:.2} | 포지션: {:.0}%",
+        say!("  ──────────────────────────");
+        say!("  {}", signal.decision);
+        say!("  액션: {} | 진입: ${:.2} | SL: ${:.2} | TP: ${:.2} | 포지션: {:.0}%",
             signal.action, signal.entry_price, signal.stop_loss, signal.take_profit, signal.position_size_pct);
     }
 
-    println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("✓ 산업 적용 데모 완료");
-    println!("  의료: {} 판단 | 교육: {} 계획 | 트레이딩: {} 시그널",
+    say!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("✓ 산업 적용 데모 완료");
+    say!("  의료: {} 판단 | 교육: {} 계획 | 트레이딩: {} 시그널",
         med_ai.decisions.len(), edu_ai.plans.len(), trade_ai.signals.len());
+
+    if crate::output::is_json() {
+        let decisions = med_ai.decisions.iter().map(|d| &d.decision)
+            .chain(edu_ai.plans.iter().map(|p| &p.decision))
+            .chain(trade_ai.signals.iter().map(|s| &s.decision))
+            .map(|d| d.to_json())
+            .collect();
+        JsonObject::new()
+            .str("command", "industry")
+            .trit("state", 1)
+            .objects("decisions", decisions)
+            .emit();
+    }
+    1
 }
 
 // ═══ 테스트 ═══
@@ -0,0 +1,748 @@
+// ═══════════════════════════════════════════════════════════════
+// 외부 연동 — 웹훅 이벤트 전송 (서명 · 재시도 · 데드레터)
+//
+//   이벤트: task.finished · block.finalized · nft.sold · alert.fired
+//   emit()은 큐에만 넣는다 → deliver_due(now)가 전송 (호출 측 펌프)
+//
+//   요청 헤더:
+//     X-Crowny-Event:     block.finalized
+//     X-Crowny-Delivery:  전송 ID
+//     X-Crowny-Signature: t=<ms>,v1=<hex HMAC-SHA256(secret, "t.본문")>
+//
+//   실패 → 지수 백오프 (base · 2^(시도-1), 상한 max_delay)
+//   max_attempts 도달 → TritStore에 데드레터 (webhook.dead.*, 트릿 T)
+//   관리자 API: 데드레터 조회 · 재전송 (webserver::mount_webhook_admin)
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::crypto;
+use crate::output::JsonObject;
+use crate::trit_store::{StoreValue, TritStore};
+use crate::watchdog::{AlertSink, WatchdogAlert};
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+const DEAD_PREFIX: &str = "webhook.dead.";
+
+// ─────────────────────────────────────────────
+// 이벤트
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    TaskFinished,
+    BlockFinalized,
+    NftSold,
+    AlertFired,
+    StateOverridden,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 5] = [
+        EventKind::TaskFinished, EventKind::BlockFinalized, EventKind::NftSold, EventKind::AlertFired,
+        EventKind::StateOverridden,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EventKind::TaskFinished => "task.finished",
+            EventKind::BlockFinalized => "block.finalized",
+            EventKind::NftSold => "nft.sold",
+            EventKind::AlertFired => "alert.fired",
+            EventKind::StateOverridden => "state.overridden",
+        }
+    }
+}
+
+impl std::str::FromStr for EventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter()
+            .find(|k| k.name() == s)
+            .ok_or_else(|| format!("알 수 없는 이벤트 '{}' (가능: {})", s,
+                Self::ALL.map(EventKind::name).join(", ")))
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// 전송 본문이 되는 이벤트
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    pub id: u64,
+    pub kind: EventKind,
+    pub timestamp: u64,
+    /// data 필드 JSON
+    pub data: String,
+}
+
+impl WebhookEvent {
+    pub fn body(&self) -> String {
+        JsonObject::new()
+            .int("id", self.id as i64)
+            .str("type", self.kind.name())
+            .int("timestamp", self.timestamp as i64)
+            .raw("data", self.data.clone())
+            .build()
+    }
+}
+
+// ─────────────────────────────────────────────
+// 서명
+// ─────────────────────────────────────────────
+
+/// X-Crowny-Signature 값
+pub fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let ts = timestamp.to_string();
+    let mac = crypto::hmac_sha256(secret.as_bytes(), &[ts.as_bytes(), b".", body.as_bytes()]);
+    format!("t={},v1={}", ts, crypto::to_hex(&mac))
+}
+
+// ─────────────────────────────────────────────
+// 전송 수단
+// ─────────────────────────────────────────────
+
+pub trait WebhookTransport {
+    /// POST 후 HTTP 상태 코드 — 연결 실패는 Err
+    fn post(&mut self, url: &str, headers: &[(String, String)], body: &str) -> Result<u16, String>;
+}
+
+/// 평문 HTTP/1.1 POST (https는 앞단 프록시 필요)
+pub struct HttpTransport {
+    pub timeout: Duration,
+}
+
+impl Default for HttpTransport {
+    fn default() -> Self {
+        Self { timeout: Duration::from_secs(5) }
+    }
+}
+
+/// http://host[:port]/path → (host:port, host, path)
+pub(crate) fn split_url(url: &str) -> Result<(String, String, String), String> {
+    let rest = url.strip_prefix("http://")
+        .ok_or_else(|| format!("http:// URL만 지원: {}", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(format!("호스트 없음: {}", url));
+    }
+    let addr = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+    Ok((addr, authority.to_string(), path.to_string()))
+}
+
+impl WebhookTransport for HttpTransport {
+    fn post(&mut self, url: &str, headers: &[(String, String)], body: &str) -> Result<u16, String> {
+        let (addr, host, path) = split_url(url)?;
+        let mut stream = TcpStream::connect(&addr).map_err(|e| format!("{} 연결 실패: {}", addr, e))?;
+        stream.set_read_timeout(Some(self.timeout)).map_err(|e| e.to_string())?;
+        stream.set_write_timeout(Some(self.timeout)).map_err(|e| e.to_string())?;
+
+        let mut req = format!("POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            path, host, body.len());
+        for (k, v) in headers {
+            req.push_str(&format!("{}: {}\r\n", k, v));
+        }
+        req.push_str("\r\n");
+        req.push_str(body);
+        stream.write_all(req.as_bytes()).map_err(|e| e.to_string())?;
+
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line).map_err(|e| e.to_string())?;
+        status_line.split_whitespace().nth(1)
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| format!("잘못된 응답: {:?}", status_line.trim()))
+    }
+}
+
+/// 응답 본문 상한 — 이상한 서버가 끝없이 보내도 멈춘다
+const MAX_RESPONSE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// HTTP 응답 (상태 · 헤더 · 본문)
+#[derive(Debug, Clone)]
+pub struct HttpReply {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpReply {
+    /// 헤더 조회 (대소문자 무시)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+}
+
+/// 평문 HTTP/1.1 요청 한 번 (Connection: close) — 본문이 비어 있으면 Content-Type 생략
+pub fn http_request(method: &str, url: &str, headers: &[(String, String)], body: &str, timeout: Duration) -> Result<HttpReply, String> {
+    let (addr, host, path) = split_url(url)?;
+    let mut stream = TcpStream::connect(&addr).map_err(|e| format!("{} 연결 실패: {}", addr, e))?;
+    stream.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+
+    let mut req = format!("{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n", method, path, host, body.len());
+    if !body.is_empty() && !headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("Content-Type")) {
+        req.push_str("Content-Type: application/json\r\n");
+    }
+    for (k, v) in headers {
+        req.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    req.push_str("\r\n");
+    req.push_str(body);
+    stream.write_all(req.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut raw = Vec::new();
+    stream.take(MAX_RESPONSE_BYTES).read_to_end(&mut raw).map_err(|e| e.to_string())?;
+    let raw = String::from_utf8_lossy(&raw);
+    let (head, body) = raw.split_once("\r\n\r\n").ok_or("잘못된 응답: 헤더 끝 없음")?;
+    let mut lines = head.lines();
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).and_then(|s| s.parse().ok())
+        .ok_or_else(|| format!("잘못된 응답: {:?}", status_line))?;
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let mut reply = HttpReply { status, headers, body: body.to_string() };
+    if reply.header("Transfer-Encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked")) {
+        reply.body = dechunk(&reply.body);
+    }
+    Ok(reply)
+}
+
+fn dechunk(body: &str) -> String {
+    let mut out = String::new();
+    let mut rest = body;
+    while let Some((size, after)) = rest.split_once("\r\n") {
+        let Ok(n) = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16) else { break };
+        if n == 0 || after.len() < n {
+            break;
+        }
+        out.push_str(&after[..n]);
+        rest = after[n..].strip_prefix("\r\n").unwrap_or(&after[n..]);
+    }
+    out
+}
+
+/// 보낸 요청 기록 (URL, 헤더, 본문)
+#[cfg(test)]
+pub type SentRequest = (String, Vec<(String, String)>, String);
+
+/// 메모리 전송 (테스트) — URL별 응답 순서를 미리 지정, 없으면 200
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryTransport {
+    pub responses: HashMap<String, Vec<Result<u16, String>>>,
+    pub sent: Rc<RefCell<Vec<SentRequest>>>,
+}
+
+#[cfg(test)]
+impl MemoryTransport {
+    pub fn respond(mut self, url: &str, results: Vec<Result<u16, String>>) -> Self {
+        self.responses.insert(url.to_string(), results);
+        self
+    }
+}
+
+#[cfg(test)]
+impl WebhookTransport for MemoryTransport {
+    fn post(&mut self, url: &str, headers: &[(String, String)], body: &str) -> Result<u16, String> {
+        self.sent.borrow_mut().push((url.to_string(), headers.to_vec(), body.to_string()));
+        match self.responses.get_mut(url) {
+            Some(queue) if !queue.is_empty() => queue.remove(0),
+            _ => Ok(200),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// 구독 · 전송 기록
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub id: u64,
+    pub url: String,
+    pub events: Vec<EventKind>,
+    secret: String,
+    pub active: bool,
+    pub created_at: u64,
+}
+
+impl Webhook {
+    pub fn wants(&self, kind: EventKind) -> bool {
+        self.active && self.events.contains(&kind)
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .int("id", self.id as i64)
+            .str("url", &self.url)
+            .strs("events", &self.events.iter().map(|e| e.name().to_string()).collect::<Vec<_>>())
+            .bool("active", self.active)
+            .int("created_at", self.created_at as i64)
+    }
+}
+
+/// 재시도 정책
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: 5, base_delay_ms: 1_000, max_delay_ms: 5 * 60_000 }
+    }
+}
+
+impl RetryPolicy {
+    /// attempts번 실패한 뒤 다음 시도까지 대기
+    pub fn backoff(&self, attempts: u32) -> u64 {
+        let shift = attempts.saturating_sub(1).min(32);
+        self.base_delay_ms.saturating_mul(1u64 << shift).min(self.max_delay_ms)
+    }
+}
+
+/// 대기 중인 전송 하나
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub id: u64,
+    pub hook_id: u64,
+    pub event: WebhookEvent,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+    pub last_status: Option<u16>,
+    pub last_error: Option<String>,
+}
+
+/// 저장소의 데드레터
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadLetter {
+    pub delivery_id: u64,
+    pub hook_id: u64,
+    pub event_id: u64,
+    pub kind: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: u64,
+}
+
+impl DeadLetter {
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .int("delivery", self.delivery_id as i64)
+            .int("hook", self.hook_id as i64)
+            .int("event", self.event_id as i64)
+            .str("type", &self.kind)
+            .int("attempts", self.attempts as i64)
+            .str("last_error", &self.last_error)
+            .int("failed_at", self.failed_at as i64)
+    }
+}
+
+/// deliver_due 한 번의 결과
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    pub retrying: usize,
+    pub dead: usize,
+}
+
+impl std::fmt::Display for DeliveryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "전송 {} · 재시도 대기 {} · 데드레터 {}", self.delivered, self.retrying, self.dead)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 디스패처
+// ─────────────────────────────────────────────
+
+pub type SharedWebhooks = Rc<RefCell<WebhookDispatcher>>;
+
+pub struct WebhookDispatcher {
+    hooks: Vec<Webhook>,
+    queue: Vec<Delivery>,
+    transport: Box<dyn WebhookTransport>,
+    pub policy: RetryPolicy,
+    /// 데드레터 저장소
+    pub store: TritStore,
+    next_hook: u64,
+    next_event: u64,
+    next_delivery: u64,
+    pub total_delivered: u64,
+    pub total_failed_attempts: u64,
+}
+
+impl WebhookDispatcher {
+    pub fn new(transport: Box<dyn WebhookTransport>) -> Self {
+        Self {
+            hooks: Vec::new(),
+            queue: Vec::new(),
+            transport,
+            policy: RetryPolicy::default(),
+            store: TritStore::new(),
+            next_hook: 1,
+            next_event: 1,
+            next_delivery: 1,
+            total_delivered: 0,
+            total_failed_attempts: 0,
+        }
+    }
+
+    pub fn with_store(mut self, store: TritStore) -> Self {
+        self.store = store;
+        self
+    }
+
+    pub fn shared(self) -> SharedWebhooks {
+        Rc::new(RefCell::new(self))
+    }
+
+    /// 구독 등록 — URL 형식 · 이벤트 목록 검증
+    pub fn register(&mut self, url: &str, events: &[EventKind], secret: &str) -> Result<u64, String> {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("잘못된 URL: {}", url));
+        }
+        if events.is_empty() {
+            return Err("구독할 이벤트가 없음".into());
+        }
+        if secret.len() < 8 {
+            return Err("서명 비밀은 8자 이상".into());
+        }
+        let id = self.next_hook;
+        self.next_hook += 1;
+        self.hooks.push(Webhook {
+            id, url: url.to_string(), events: events.to_vec(), secret: secret.to_string(),
+            active: true, created_at: now_ms(),
+        });
+        Ok(id)
+    }
+
+    /// 구독 해지 — 대기 중인 전송도 버린다
+    pub fn unregister(&mut self, id: u64) -> bool {
+        let before = self.hooks.len();
+        self.hooks.retain(|h| h.id != id);
+        self.queue.retain(|d| d.hook_id != id);
+        self.hooks.len() != before
+    }
+
+    /// 이벤트 발생 — 구독한 웹훅마다 전송 예약, 예약 수 반환
+    pub fn emit(&mut self, kind: EventKind, data: JsonObject) -> usize {
+        self.emit_at(kind, data, now_ms())
+    }
+
+    pub fn emit_at(&mut self, kind: EventKind, data: JsonObject, now: u64) -> usize {
+        let targets: Vec<u64> = self.hooks.iter().filter(|h| h.wants(kind)).map(|h| h.id).collect();
+        if targets.is_empty() {
+            return 0;
+        }
+        let event = WebhookEvent { id: self.next_event, kind, timestamp: now, data: data.build() };
+        self.next_event += 1;
+        for hook_id in &targets {
+            self.queue.push(Delivery {
+                id: self.next_delivery, hook_id: *hook_id, event: event.clone(),
+                attempts: 0, next_attempt_at: now, last_status: None, last_error: None,
+            });
+            self.next_delivery += 1;
+        }
+        targets.len()
+    }
+
+    /// 시각이 된 전송 시도 — 실패는 백오프, 한도 도달은 데드레터
+    pub fn deliver_due(&mut self, now: u64) -> DeliveryReport {
+        let mut report = DeliveryReport::default();
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queue)
+            .into_iter()
+            .partition(|d| d.next_attempt_at <= now);
+        self.queue = waiting;
+
+        for mut delivery in due {
+            let Some(hook) = self.hooks.iter().find(|h| h.id == delivery.hook_id) else { continue };
+            let body = delivery.event.body();
+            let headers = vec![
+                ("X-Crowny-Event".to_string(), delivery.event.kind.name().to_string()),
+                ("X-Crowny-Delivery".to_string(), delivery.id.to_string()),
+                ("X-Crowny-Signature".to_string(), sign(&hook.secret, now, &body)),
+            ];
+            let outcome = self.transport.post(&hook.url, &headers, &body);
+            delivery.attempts += 1;
+            match outcome {
+                Ok(status) if (200..300).contains(&status) => {
+                    self.total_delivered += 1;
+                    report.delivered += 1;
+                    continue;
+                }
+                Ok(status) => {
+                    delivery.last_status = Some(status);
+                    delivery.last_error = Some(format!("HTTP {}", status));
+                }
+                Err(e) => delivery.last_error = Some(e),
+            }
+            self.total_failed_attempts += 1;
+            if delivery.attempts >= self.policy.max_attempts {
+                self.dead_letter(&delivery, now);
+                report.dead += 1;
+            } else {
+                delivery.next_attempt_at = now + self.policy.backoff(delivery.attempts);
+                self.queue.push(delivery);
+                report.retrying += 1;
+            }
+        }
+        report
+    }
+
+    /// 가장 이른 다음 시도 시각
+    pub fn next_due(&self) -> Option<u64> {
+        self.queue.iter().map(|d| d.next_attempt_at).min()
+    }
+
+    fn dead_key(delivery_id: u64) -> String {
+        format!("{}{:010}", DEAD_PREFIX, delivery_id)
+    }
+
+    fn dead_letter(&mut self, delivery: &Delivery, now: u64) {
+        let mut m = HashMap::new();
+        m.insert("hook".to_string(), StoreValue::Int(delivery.hook_id as i64));
+        m.insert("event".to_string(), StoreValue::Int(delivery.event.id as i64));
+        m.insert("type".to_string(), StoreValue::Text(delivery.event.kind.name().to_string()));
+        m.insert("timestamp".to_string(), StoreValue::Int(delivery.event.timestamp as i64));
+        m.insert("data".to_string(), StoreValue::Text(delivery.event.data.clone()));
+        m.insert("attempts".to_string(), StoreValue::Int(delivery.attempts as i64));
+        m.insert("last_error".to_string(), StoreValue::Text(delivery.last_error.clone().unwrap_or_default()));
+        m.insert("failed_at".to_string(), StoreValue::Int(now as i64));
+        let key = Self::dead_key(delivery.id);
+        self.store.set(&key, StoreValue::Map(m));
+        self.store.set_trit_state(&key, -1);
+    }
+
+    fn read_dead(&self, key: &str) -> Option<(DeadLetter, WebhookEvent)> {
+        let Some(StoreValue::Map(m)) = self.store.peek(key) else { return None };
+        let int = |k: &str| match m.get(k) { Some(StoreValue::Int(n)) => Some(*n as u64), _ => None };
+        let text = |k: &str| match m.get(k) { Some(StoreValue::Text(s)) => Some(s.clone()), _ => None };
+        let kind = text("type")?;
+        let letter = DeadLetter {
+            delivery_id: key.strip_prefix(DEAD_PREFIX)?.parse().ok()?,
+            hook_id: int("hook")?,
+            event_id: int("event")?,
+            kind: kind.clone(),
+            attempts: int("attempts")? as u32,
+            last_error: text("last_error").unwrap_or_default(),
+            failed_at: int("failed_at")?,
+        };
+        let event = WebhookEvent {
+            id: letter.event_id,
+            kind: kind.parse().ok()?,
+            timestamp: int("timestamp")?,
+            data: text("data")?,
+        };
+        Some((letter, event))
+    }
+
+    /// 데드레터 목록 (전송 ID 순)
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        let mut keys: Vec<&String> = self.store.keys().into_iter()
+            .filter(|k| k.starts_with(DEAD_PREFIX))
+            .collect();
+        keys.sort();
+        keys.into_iter().filter_map(|k| self.read_dead(k).map(|(l, _)| l)).collect()
+    }
+
+    /// 관리자 재전송 — 시도 횟수를 비우고 즉시 대기열로 (같은 전송 ID)
+    pub fn replay(&mut self, delivery_id: u64, now: u64) -> Result<(), String> {
+        let key = Self::dead_key(delivery_id);
+        let (letter, event) = self.read_dead(&key).ok_or_else(|| format!("데드레터 #{} 없음", delivery_id))?;
+        if !self.hooks.iter().any(|h| h.id == letter.hook_id) {
+            return Err(format!("웹훅 #{} 이 해지됨", letter.hook_id));
+        }
+        self.store.delete(&key);
+        self.queue.push(Delivery {
+            id: delivery_id, hook_id: letter.hook_id, event,
+            attempts: 0, next_attempt_at: now, last_status: None, last_error: None,
+        });
+        Ok(())
+    }
+
+    /// 데드레터 전부 재전송 — 재예약된 수
+    pub fn replay_all(&mut self, now: u64) -> usize {
+        let ids: Vec<u64> = self.dead_letters().iter().map(|l| l.delivery_id).collect();
+        ids.into_iter().filter(|id| self.replay(*id, now).is_ok()).count()
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .objects("hooks", self.hooks.iter().map(Webhook::to_json).collect())
+            .int("pending", self.queue.len() as i64)
+            .int("dead_letters", self.dead_letters().len() as i64)
+            .int("delivered", self.total_delivered as i64)
+            .int("failed_attempts", self.total_failed_attempts as i64)
+    }
+}
+
+/// 워치독 알림 → alert.fired
+impl AlertSink for SharedWebhooks {
+    fn alert(&mut self, alert: &WatchdogAlert) {
+        self.borrow_mut().emit(EventKind::AlertFired, JsonObject::new()
+            .str("component", &alert.component.to_string())
+            .int("failed_restarts", alert.failed_restarts as i64)
+            .str("last_error", &alert.last_error)
+            .int("timestamp", alert.timestamp as i64));
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dispatcher(transport: MemoryTransport) -> WebhookDispatcher {
+        let mut hub = WebhookDispatcher::new(Box::new(transport));
+        hub.policy = RetryPolicy { max_attempts: 3, base_delay_ms: 100, max_delay_ms: 1_000 };
+        hub
+    }
+
+    #[test]
+    fn test_signed_delivery_and_routing() {
+        let transport = MemoryTransport::default();
+        let sent = transport.sent.clone();
+        let mut hub = dispatcher(transport);
+        let blocks = hub.register("http://ops.local/hooks", &[EventKind::BlockFinalized], "s3cret-key").unwrap();
+        hub.register("http://shop.local/nft", &[EventKind::NftSold], "another-key").unwrap();
+        assert!(hub.register("ftp://x", &[EventKind::NftSold], "another-key").is_err());
+        assert!("block.final".parse::<EventKind>().is_err());
+
+        assert_eq!(hub.emit_at(EventKind::BlockFinalized, JsonObject::new().int("index", 7), 1_000), 1);
+        assert_eq!(hub.emit_at(EventKind::TaskFinished, JsonObject::new(), 1_000), 0);
+        assert_eq!(hub.deliver_due(1_000), DeliveryReport { delivered: 1, retrying: 0, dead: 0 });
+
+        let sent = sent.borrow();
+        let (url, headers, body) = &sent[0];
+        assert_eq!(url, "http://ops.local/hooks");
+        assert!(body.contains("\"type\":\"block.finalized\"") && body.contains("\"index\":7"));
+        let header = |k: &str| headers.iter().find(|(h, _)| h == k).map(|(_, v)| v.as_str()).unwrap();
+        assert_eq!(header("X-Crowny-Event"), "block.finalized");
+        let sig = header("X-Crowny-Signature");
+        assert!(sig.starts_with("t=1000,v1="));
+        assert_eq!(sig, sign("s3cret-key", 1_000, body));
+        assert_ne!(sig, sign("wrong-key!", 1_000, body));
+        assert_ne!(sig, sign("s3cret-key", 1_000, &body.replace('7', "8")));
+
+        assert!(hub.unregister(blocks));
+        assert_eq!(hub.emit_at(EventKind::BlockFinalized, JsonObject::new(), 2_000), 0);
+    }
+
+    #[test]
+    fn test_backoff_dead_letter_and_replay() {
+        let url = "http://flaky.local/";
+        let transport = MemoryTransport::default()
+            .respond(url, vec![Err("연결 거부".into()), Ok(503), Ok(500)]);
+        let sent = transport.sent.clone();
+        let mut hub = dispatcher(transport);
+        hub.register(url, &[EventKind::NftSold], "flaky-secret").unwrap();
+        hub.emit_at(EventKind::NftSold, JsonObject::new().str("nft", "n1").int("price", 500), 0);
+
+        assert_eq!(hub.deliver_due(0).retrying, 1);
+        assert_eq!(hub.next_due(), Some(100));
+        assert_eq!(hub.deliver_due(50), DeliveryReport::default()); // 아직 대기
+        assert_eq!(hub.deliver_due(100).retrying, 1);
+        assert_eq!(hub.next_due(), Some(300)); // 100 · 2^1
+        assert_eq!(hub.deliver_due(300).dead, 1);
+        assert!(hub.queue.is_empty());
+
+        let dead = hub.dead_letters();
+        assert_eq!(dead.len(), 1);
+        assert_eq!((dead[0].kind.as_str(), dead[0].attempts, dead[0].last_error.as_str()), ("nft.sold", 3, "HTTP 500"));
+        assert_eq!(hub.store.filter_by_trit(-1).len(), 1);
+
+        // 관리자 재전송 — 이번엔 200
+        assert!(hub.replay(999, 400).is_err());
+        assert_eq!(hub.replay_all(400), 1);
+        assert!(hub.dead_letters().is_empty());
+        assert_eq!(hub.deliver_due(400).delivered, 1);
+        let sent = sent.borrow();
+        assert_eq!(sent.len(), 4);
+        assert_eq!(sent[3].2, sent[0].2); // 같은 이벤트 본문
+        assert_eq!(RetryPolicy::default().backoff(20), 5 * 60_000);
+    }
+
+    #[test]
+    fn test_chain_and_market_emit_events() {
+        use crate::chain::{sample_chain, Transaction, TxType};
+        use crate::nft::{CrownyNFT, NFTMetadata, NFTRarity};
+        let transport = MemoryTransport::default();
+        let sent = transport.sent.clone();
+        let hub = dispatcher(transport).shared();
+        hub.borrow_mut().register("http://all.local/", &EventKind::ALL, "all-events").unwrap();
+
+        let mut chain = sample_chain();
+        chain.webhooks = Some(hub.clone());
+        chain.submit_tx(Transaction::new("alice", "bob", 10, 10, TxType::Transfer, "웹훅"));
+        let block = chain.produce_block().expect("블록");
+
+        let mut market = CrownyNFT::new();
+        market.webhooks = Some(hub.clone());
+        market.fund("carol", 1_000);
+        let col = market.create_collection("삼진", "TRI", "dave", "", None, 500);
+        let nft = market.mint(&col, "dave", NFTMetadata::new("P", "", ""), NFTRarity::Common).unwrap();
+        market.list(&nft, 800).unwrap();
+        market.buy(&nft, "carol").unwrap();
+
+        assert_eq!(hub.borrow_mut().deliver_due(u64::MAX).delivered, 2);
+        let sent = sent.borrow();
+        assert!(sent[0].2.contains(&format!("\"hash\":\"{}\"", block.hash)));
+        assert!(sent[1].2.contains("\"type\":\"nft.sold\"") && sent[1].2.contains("\"price\":800"));
+    }
+
+    #[test]
+    fn test_http_transport_and_alert_sink() {
+        use std::io::Read;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/crowny", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (mut request, mut buf) = (Vec::new(), [0u8; 1024]);
+            while !request.ends_with(b"}") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 { break; }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n").unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let hub = WebhookDispatcher::new(Box::new(HttpTransport::default())).shared();
+        hub.borrow_mut().register(&url, &[EventKind::AlertFired], "alert-secret").unwrap();
+        let mut sink = hub.clone();
+        sink.alert(&WatchdogAlert {
+            component: crate::watchdog::ComponentKind::Scheduler,
+            failed_restarts: 3,
+            last_error: "재시작 실패".into(),
+            timestamp: 42,
+        });
+        assert_eq!(hub.borrow_mut().deliver_due(now_ms()).delivered, 1);
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /crowny HTTP/1.1"));
+        assert!(request.contains("X-Crowny-Event: alert.fired"));
+        assert!(request.contains("\"failed_restarts\":3"));
+
+        assert!(split_url("https://secure.local/x").is_err());
+        assert_eq!(split_url("http://a.local").unwrap(), ("a.local:80".into(), "a.local".into(), "/".into()));
+    }
+}
@@ -0,0 +1,259 @@
+// ═══════════════════════════════════════════════════
+// Kernel IPC — 스케줄된 태스크 사이의 3진 메시지 채널
+// ═══════════════════════════════════════════════════
+//
+// 커널 태스크는 서로 격리된 클로저 — 채널 핸들(Channel)을 캡처해서 협력한다.
+//
+// 전송 (send):
+//   P = 큐에 넣음 / O = 용량 가득 (나중에 다시) / T = 닫힌 채널
+// 수신 (recv):
+//   P = 메시지 전달 / O = 아직 없음 / T = 닫혔고 남은 메시지도 없음
+//
+// 닫힌 채널도 남은 메시지는 끝까지 전달한다.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::scheduler::TritResult;
+
+/// 채널 ID
+pub type ChannelId = u64;
+
+/// 수신 결과 (3진)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recv {
+    Delivered(String), // P: 메시지 전달
+    Pending,           // O: 대기 중 (비어 있음)
+    Closed,            // T: 닫힘 + 비어 있음
+}
+
+impl Recv {
+    pub fn trit(&self) -> TritResult {
+        match self {
+            Recv::Delivered(_) => TritResult::Success,
+            Recv::Pending => TritResult::Pending,
+            Recv::Closed => TritResult::Failed,
+        }
+    }
+
+    pub fn message(self) -> Option<String> {
+        match self {
+            Recv::Delivered(m) => Some(m),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Recv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recv::Delivered(m) => write!(f, "P(전달) {}", m),
+            Recv::Pending => write!(f, "O(대기)"),
+            Recv::Closed => write!(f, "T(닫힘)"),
+        }
+    }
+}
+
+/// 채널 통계
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelStats {
+    pub sent: u64,
+    pub delivered: u64,
+    /// 빈 채널 수신 (O)
+    pub pending_recvs: u64,
+    /// 가득 찬 채널 전송 (O)
+    pub full_sends: u64,
+    /// 닫힌 채널 전송 (T)
+    pub refused: u64,
+}
+
+struct ChannelInner {
+    queue: VecDeque<String>,
+    capacity: usize,
+    closed: bool,
+    stats: ChannelStats,
+}
+
+/// 채널 핸들 — 복제해서 여러 태스크 클로저에 넘긴다
+#[derive(Clone)]
+pub struct Channel {
+    pub id: ChannelId,
+    inner: Arc<Mutex<ChannelInner>>,
+}
+
+impl Channel {
+    pub fn new(id: ChannelId, capacity: usize) -> Self {
+        Self {
+            id,
+            inner: Arc::new(Mutex::new(ChannelInner {
+                queue: VecDeque::new(),
+                capacity: capacity.max(1),
+                closed: false,
+                stats: ChannelStats::default(),
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ChannelInner> {
+        // 태스크가 패닉해도 채널은 계속 쓸 수 있게
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 전송 — P: 넣음 / O: 가득 참 / T: 닫힘
+    pub fn send(&self, msg: &str) -> TritResult {
+        let mut ch = self.lock();
+        if ch.closed {
+            ch.stats.refused += 1;
+            return TritResult::Failed;
+        }
+        if ch.queue.len() >= ch.capacity {
+            ch.stats.full_sends += 1;
+            return TritResult::Pending;
+        }
+        ch.queue.push_back(msg.to_string());
+        ch.stats.sent += 1;
+        TritResult::Success
+    }
+
+    /// 수신 — P: 전달 / O: 비어 있음 / T: 닫혔고 비어 있음
+    pub fn recv(&self) -> Recv {
+        let mut ch = self.lock();
+        match ch.queue.pop_front() {
+            Some(m) => {
+                ch.stats.delivered += 1;
+                Recv::Delivered(m)
+            }
+            None if ch.closed => Recv::Closed,
+            None => {
+                ch.stats.pending_recvs += 1;
+                Recv::Pending
+            }
+        }
+    }
+
+    /// 닫기 — 이후 전송은 T, 남은 메시지는 계속 수신 가능
+    pub fn close(&self) {
+        self.lock().closed = true;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        self.lock().stats
+    }
+}
+
+impl std::fmt::Debug for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ch = self.lock();
+        f.debug_struct("Channel")
+            .field("id", &self.id)
+            .field("len", &ch.queue.len())
+            .field("capacity", &ch.capacity)
+            .field("closed", &ch.closed)
+            .finish()
+    }
+}
+
+/// 커널 채널 테이블
+#[derive(Debug, Default)]
+pub struct ChannelTable {
+    channels: Vec<Channel>,
+    next_id: ChannelId,
+}
+
+impl ChannelTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create(&mut self, capacity: usize) -> Channel {
+        self.next_id += 1;
+        let ch = Channel::new(self.next_id, capacity);
+        self.channels.push(ch.clone());
+        ch
+    }
+
+    pub fn get(&self, id: ChannelId) -> Option<&Channel> {
+        self.channels.iter().find(|c| c.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Channel> {
+        self.channels.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    pub fn open_count(&self) -> usize {
+        self.channels.iter().filter(|c| !c.is_closed()).count()
+    }
+
+    /// 전체 합계
+    pub fn totals(&self) -> ChannelStats {
+        self.channels.iter().map(Channel::stats).fold(ChannelStats::default(), |mut t, s| {
+            t.sent += s.sent;
+            t.delivered += s.delivered;
+            t.pending_recvs += s.pending_recvs;
+            t.full_sends += s.full_sends;
+            t.refused += s.refused;
+            t
+        })
+    }
+
+    pub fn dump(&self) {
+        let t = self.totals();
+        println!("║  IPC: 채널 {} (열림 {}) 전송:{} 전달:{} 대기수신:{} 가득:{} 거부:{}",
+            self.len(), self.open_count(), t.sent, t.delivered, t.pending_recvs, t.full_sends, t.refused);
+        for ch in &self.channels {
+            println!("║    #{} {}/{} {}", ch.id, ch.len(), ch.capacity(),
+                if ch.is_closed() { "T(닫힘)" } else { "P(열림)" });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_send_recv_states() {
+        let ch = Channel::new(1, 2);
+        assert_eq!(ch.recv(), Recv::Pending);
+        assert_eq!(ch.send("하나"), TritResult::Success);
+        assert_eq!(ch.send("둘"), TritResult::Success);
+        assert_eq!(ch.send("셋"), TritResult::Pending); // 가득 참
+        assert_eq!(ch.recv(), Recv::Delivered("하나".into()));
+
+        // 닫힌 뒤에도 남은 메시지는 전달, 전송은 T
+        ch.close();
+        assert_eq!(ch.send("넷"), TritResult::Failed);
+        assert_eq!(ch.recv().trit(), TritResult::Success);
+        assert_eq!(ch.recv(), Recv::Closed);
+        assert_eq!(ch.stats(), ChannelStats { sent: 2, delivered: 2, pending_recvs: 1, full_sends: 1, refused: 1 });
+    }
+
+    #[test]
+    fn test_channel_table_totals() {
+        let mut table = ChannelTable::new();
+        let a = table.create(4);
+        let b = table.create(4);
+        a.send("x");
+        b.send("y");
+        b.close();
+        assert_eq!(table.get(b.id).unwrap().len(), 1);
+        assert_eq!((table.len(), table.open_count(), table.totals().sent), (2, 1, 2));
+        assert!(table.get(99).is_none());
+    }
+}
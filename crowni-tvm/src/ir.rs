@@ -14,6 +14,7 @@
 pub enum IrOp {
     // ── 스택 (A그룹) ──
     Const(i64),         // 상수 push
+    ConstI32(i32),      // i32 상수 (주소 · 분기 번호)
     ConstF64(f64),      // 실수 상수
     ConstTrit(i8),      // Trit 상수 (-1,0,+1)
     Drop,               // pop & discard
@@ -39,20 +40,34 @@ pub enum IrOp {
     Ge,                 // 이상
     Le,                 // 이하
     Eqz,               // 0인가
+    I32Add,             // i32 더하기 (주소 계산)
+    I32Sub,             // i32 빼기
+    I32LtS,             // i32 작음 (부호)
+    I32LtU,             // i32 작음 (무부호)
+    I32LeU,             // i32 이하 (무부호)
+    I32GtU,             // i32 큼 (무부호)
+    I32GeU,             // i32 이상 (무부호)
+    Select,             // 조건(i32)이 참이면 첫 값, 아니면 둘째 값
 
     // ── 제어흐름 (D그룹) ──
     Block(u32),         // 블록 시작 (label depth)
     Loop(u32),          // 루프 시작
     Br(u32),            // 점프 (label)
     BrIf(u32),          // 조건 점프
+    BrTable(Vec<u32>, u32), // 인덱스 점프 (label 목록, 기본 label)
+    If(Option<IrType>), // 조건 블록 (결과 타입)
+    Else,               // 조건 블록 else
     Call(u32),          // 함수 호출
     Return,             // 반환
     End,                // 블록/함수 종료
     Halt,               // 프로그램 종료
+    Unreachable,        // 트랩 (VM이 오류를 내는 지점)
 
     // ── 메모리 (E그룹) ──
     MemLoad(u32),       // 메모리 읽기 (offset)
     MemStore(u32),      // 메모리 쓰기 (offset)
+    MemStore8(u32),     // i64 하위 1바이트 쓰기 (offset)
+    MemStoreI32(u32),   // i32 쓰기 (offset)
     MemGrow,            // 메모리 확장
 
     // ── 로컬/전역 (F그룹) ──
@@ -65,6 +80,8 @@ pub enum IrOp {
     I64ExtendI32,       // i32→i64
     F64ConvertI64,      // i64→f64
     I64TruncF64,        // f64→i64
+    I32WrapI64,         // i64→i32
+    F64Sqrt,            // f64 제곱근
 
     // ── IO 브릿지 (H그룹) ──
     CallImport(u32),    // import 함수 호출 (인덱스)
@@ -76,7 +93,7 @@ pub enum IrOp {
     TritAnd,            // 3진 AND (min)
     TritOr,             // 3진 OR (max)
     TritNot,            // 3진 NOT (negate)
-    TritBranch,         // 3진 분기 (+1→A, 0→B, -1→C)
+    TritBranch(u32, u32, u32), // 3진 분기 (+1→P, 0→O, -1→T label)
 
     // ── NOP ──
     Nop,
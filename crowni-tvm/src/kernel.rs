@@ -10,6 +10,7 @@
 ///!   2. Scheduler   — 3진 스케줄러 (태스크 관리)
 ///!   3. Permission  — 3진 권한 엔진 (접근 제어)
 ///!   4. Transaction — 3진 트랜잭션 (상태 관리)
+///!   5. IPC         — 3진 메시지 채널 (태스크 협력)
 ///!
 ///! ┌─────────────────────────────────────────┐
 ///! │           Crowny Meta-Kernel            │
@@ -29,10 +30,19 @@
 ///! │        2진 OS (Linux/macOS)             │
 ///! └─────────────────────────────────────────┘
 
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use crate::vm::TVM;
-use crate::scheduler::{TritScheduler, TritPriority, TritResult, TaskFn};
+use crate::scheduler::{TritScheduler, TritPriority, TritResult, TaskFn, TaskId};
 use crate::permission::{PermissionEngine, TritPermission, Action};
 use crate::transaction::{TransactionEngine, TxState, TxId};
+use crate::capability::{ApprovalQueue, Capability, CapabilityManifest, MissingCapability, PreflightReport};
+use crate::vm::Instruction;
+use crate::program_limits::ProgramLimits;
+use crate::ipc::{Channel, ChannelId, ChannelTable, Recv};
+use crate::watchdog::{ComponentKind, Watchdog, WatchdogAction};
 
 // ─────────────────────────────────────────────
 // Kernel Config
@@ -43,6 +53,10 @@ pub struct KernelConfig {
     pub debug: bool,
     pub max_tasks: usize,
     pub default_permission: TritPermission,
+    /// 제출 프로그램 크기·복잡도 한도 (어셈블 시점)
+    pub program_limits: ProgramLimits,
+    /// 새 채널 기본 용량 (메시지 수)
+    pub channel_capacity: usize,
 }
 
 impl Default for KernelConfig {
@@ -51,6 +65,8 @@ impl Default for KernelConfig {
             debug: false,
             max_tasks: 729,  // 3^6, 한선어답게
             default_permission: TritPermission::Review,
+            program_limits: ProgramLimits::unlimited(),
+            channel_capacity: 243, // 3^5
         }
     }
 }
@@ -75,8 +91,21 @@ pub struct CrownyKernel {
     pub config: KernelConfig,
     /// 부팅 이후 실행된 총 연산 수
     pub total_ops: u64,
+    /// 능력 승인 대기열 (O-상태 보류 태스크)
+    pub approvals: ApprovalQueue,
+    /// 태스크 간 메시지 채널
+    pub channels: ChannelTable,
+    /// 보류(O)로 재큐된 보호 실행 태스크의 열린 트랜잭션
+    pending_tx: HashMap<TaskId, TxId>,
+    /// 스케줄러 감시 — 진행 하트비트가 끊기면 복구
+    pub watchdog: Watchdog,
+    /// 워치독 재시작 훅이 세우는 복구 요청 (스케줄러는 커널이 쥐고 있어 watch에서 반영)
+    scheduler_restart: Rc<Cell<bool>>,
 }
 
+/// 스케줄러 하트비트 주기 (ms)
+pub const SCHEDULER_BEAT_MS: u64 = 1000;
+
 /// 커널 상태 (3진)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(i8)]
@@ -107,10 +136,16 @@ impl CrownyKernel {
             state: KernelState::Standby,
             config,
             total_ops: 0,
+            approvals: ApprovalQueue::new(),
+            channels: ChannelTable::new(),
+            pending_tx: HashMap::new(),
+            watchdog: Watchdog::new(3, 3),
+            scheduler_restart: Rc::new(Cell::new(false)),
         };
 
         // 기본 권한 정책 설정
         kernel.init_default_policies();
+        kernel.init_watchdog();
         kernel.state = KernelState::Running;
 
         if kernel.config.debug {
@@ -120,6 +155,40 @@ impl CrownyKernel {
         kernel
     }
 
+    /// 스케줄러를 워치독에 등록
+    fn init_watchdog(&mut self) {
+        let hb = self.watchdog.heartbeat_handle(ComponentKind::Scheduler);
+        self.scheduler.set_heartbeat(hb);
+        let flag = self.scheduler_restart.clone();
+        self.watchdog.register(ComponentKind::Scheduler, SCHEDULER_BEAT_MS, Box::new(move |_| {
+            flag.set(true);
+            Ok(())
+        }));
+    }
+
+    /// 감시 주기 — 드라이버 루프가 주기적으로 호출
+    pub fn watch(&mut self) -> Vec<WatchdogAction> {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default().as_millis() as u64;
+        self.watch_at(now)
+    }
+
+    /// 할 일이 없는 스케줄러는 멈춘 것이 아니므로 대신 박동하고,
+    /// 일이 쌓였는데 진행이 없으면 워치독이 재시작 → 잠금/대기 복구
+    pub fn watch_at(&mut self, now: u64) -> Vec<WatchdogAction> {
+        if self.scheduler.pending_count() == 0 {
+            self.watchdog.heartbeat_at(&ComponentKind::Scheduler, None, now);
+        }
+        let actions = self.watchdog.tick_at(now);
+        if self.scheduler_restart.replace(false) {
+            let requeued = self.scheduler.recover();
+            if self.config.debug {
+                eprintln!("[KERNEL] 스케줄러 복구 — 대기 태스크 {}개 재큐", requeued);
+            }
+        }
+        actions
+    }
+
     /// 기본 정책 초기화
     fn init_default_policies(&mut self) {
         // 커널 자체는 전권
@@ -183,22 +252,32 @@ impl CrownyKernel {
             priority
         };
 
-        self.scheduler.submit(task_name, effective_priority, task_fn);
+        // 대상 자원 잠금 — 보류 중인 보유자는 기다리는 상위 태스크의 우선순위를 상속
+        let task_id = self.scheduler.submit_locked(task_name, effective_priority, object, task_fn);
+        self.pending_tx.insert(task_id, tx_id);
 
-        // Step 4: 실행
-        let exec_result = self.scheduler.execute_one();
+        // Step 4: 실행 — 자기 태스크가 끝날 때까지 (앞선 보류 태스크도 정리)
+        let exec_result = loop {
+            match self.scheduler.execute_one() {
+                Some((id, result)) if id == task_id => break Some(result),
+                Some((id, result)) => { self.settle(id, result); }
+                None => break None,
+            }
+        };
 
         // Step 5: 결과에 따라 commit/rollback
         let (task_result, tx_state) = match exec_result {
-            Some((_, TritResult::Success)) => {
+            Some(TritResult::Success) => {
+                self.pending_tx.remove(&task_id);
                 let state = self.transaction.commit(tx_id).unwrap_or(TxState::RolledBack);
                 (Some(TritResult::Success), Some(state))
             }
-            Some((_, TritResult::Pending)) => {
-                // 보류 → 트랜잭션도 보류 유지
+            Some(TritResult::Pending) => {
+                // 보류 → 트랜잭션도 보류 유지 (재실행 시 settle)
                 (Some(TritResult::Pending), Some(TxState::Pending))
             }
-            Some((_, TritResult::Failed)) | None => {
+            Some(TritResult::Failed) | None => {
+                self.pending_tx.remove(&task_id);
                 let state = self.transaction.rollback(tx_id).unwrap_or(TxState::RolledBack);
                 (Some(TritResult::Failed), Some(state))
             }
@@ -216,6 +295,18 @@ impl CrownyKernel {
         }
     }
 
+    /// 다른 보호 실행 태스크의 결과로 열린 트랜잭션 정리
+    fn settle(&mut self, id: TaskId, result: TritResult) {
+        if result == TritResult::Pending {
+            return;
+        }
+        let Some(tx_id) = self.pending_tx.remove(&id) else { return };
+        let _ = match result {
+            TritResult::Success => self.transaction.commit(tx_id),
+            _ => self.transaction.rollback(tx_id),
+        };
+    }
+
     /// 간단한 태스크 실행 (권한 없이)
     pub fn execute_task(&mut self, name: &str, priority: TritPriority, action: TaskFn) -> TritResult {
         self.total_ops += 1;
@@ -226,10 +317,15 @@ impl CrownyKernel {
         }
     }
 
+    /// 커널 정책(program_limits)으로 어셈블
+    fn assemble_checked(&self, source: &str) -> Result<Vec<Instruction>, String> {
+        crate::assembler::assemble_limited(source, &self.config.program_limits).map_err(|e| e.to_string())
+    }
+
     /// TVM 프로그램 실행 (어셈블리 소스)
     pub fn execute_program(&mut self, source: &str) -> Result<(), String> {
         self.total_ops += 1;
-        let program = crate::assembler::assemble(source);
+        let program = self.assemble_checked(source)?;
         if program.is_empty() {
             return Err("프로그램이 비어있습니다".into());
         }
@@ -237,6 +333,140 @@ impl CrownyKernel {
         self.vm.run().map_err(|e| format!("{}", e))
     }
 
+    /// 능력 부여 — 기본 정책보다 우선
+    pub fn grant_capability(&mut self, subject: &str, cap: Capability) {
+        self.permission.prepend_policy(subject, &cap.object(), cap.action(),
+            TritPermission::Allow, &format!("능력 부여: {}", cap.code()));
+    }
+
+    /// 실행 전 권한 사전분석
+    /// manifest의 각 능력을 권한 엔진에 조회, P가 아닌 것을 부족 목록으로
+    pub fn preflight(&mut self, subject: &str, program: &[Instruction]) -> PreflightReport {
+        let manifest = CapabilityManifest::analyze(program);
+        let mut missing = Vec::new();
+        for entry in &manifest.entries {
+            let cap = entry.capability;
+            let verdict = self.permission.check(subject, &cap.object(), cap.action());
+            if verdict != TritPermission::Allow {
+                missing.push(MissingCapability { capability: cap, verdict, sites: entry.sites.clone() });
+            }
+        }
+        PreflightReport { subject: subject.to_string(), manifest, missing }
+    }
+
+    /// 주체 권한으로 TVM 프로그램 실행 — 사전분석 통과 시에만
+    pub fn execute_program_as(&mut self, subject: &str, source: &str) -> Result<(), String> {
+        self.total_ops += 1;
+        let program = self.assemble_checked(source)?;
+        if program.is_empty() {
+            return Err("프로그램이 비어있습니다".into());
+        }
+        let report = self.preflight(subject, &program);
+        if !report.is_allowed() {
+            return Err(format!("{}", report));
+        }
+        self.vm.load(program);
+        self.vm.run().map_err(|e| format!("{}", e))
+    }
+
+    /// 능력 승인 흐름을 거치는 프로그램 제출
+    /// 허용 → 즉시 실행 / 부족 능력 전부 O → 승인 요청 생성 후 보류 / T 포함 → 거부
+    pub fn submit_program(&mut self, subject: &str, source: &str) -> SubmitOutcome {
+        self.total_ops += 1;
+        let program = match self.assemble_checked(source) {
+            Ok(p) => p,
+            Err(e) => return SubmitOutcome::Refused(e),
+        };
+        if program.is_empty() {
+            return SubmitOutcome::Refused("프로그램이 비어있습니다".into());
+        }
+        let report = self.preflight(subject, &program);
+        match report.verdict() {
+            TritPermission::Allow => {
+                self.vm.load(program);
+                SubmitOutcome::Executed(self.vm.run().map_err(|e| format!("{}", e)))
+            }
+            TritPermission::Review => {
+                let caps = report.missing.iter().map(|m| m.capability).collect();
+                let id = self.approvals.open(subject, source, caps);
+                if self.config.debug {
+                    eprintln!("[KERNEL] 승인 요청 #{} 보류: {}", id, report);
+                }
+                SubmitOutcome::Pending(id)
+            }
+            TritPermission::Deny => SubmitOutcome::Refused(format!("{}", report)),
+        }
+    }
+
+    /// 관리자 판정 권한 확인
+    fn check_approver(&mut self, admin: &str) -> Result<(), String> {
+        match self.permission.check(admin, "승인", Action::Admin) {
+            TritPermission::Allow => Ok(()),
+            p => Err(format!("{}: 승인 권한 없음 ({})", admin, p)),
+        }
+    }
+
+    /// 승인 요청 승인 → 보류 태스크 자동 재개
+    /// 승인된 능력은 이 태스크에만 적용 (영구 부여 아님)
+    pub fn approve(&mut self, admin: &str, id: u64) -> Result<(), String> {
+        self.check_approver(admin)?;
+        let rec = self.approvals.get(id).ok_or_else(|| format!("승인 요청 #{} 없음", id))?.clone();
+        if rec.state != TritPermission::Review {
+            return Err(format!("승인 요청 #{} 이미 판정됨: {}", id, rec.state));
+        }
+
+        // 판정 전 재확인 — 요청 이후 T로 바뀐 능력(승인 대상 포함)이나 범위 밖 능력이 있으면 거절로 기록
+        let program = crate::assembler::assemble(&rec.source);
+        let report = self.preflight(&rec.subject, &program);
+        let blocked = report.missing.iter()
+            .find(|m| m.verdict == TritPermission::Deny || !rec.missing.contains(&m.capability));
+        if let Some(m) = blocked {
+            let reason = format!("{}: 승인 불가 능력 {}={}", rec.subject, m.capability.code(), m.verdict.symbol());
+            self.approvals.decide(id, admin, TritPermission::Deny, &reason)?;
+            return Err(reason);
+        }
+
+        self.approvals.decide(id, admin, TritPermission::Allow, "승인")?;
+        self.total_ops += 1;
+        self.vm.load(program);
+        let outcome = self.vm.run().map_err(|e| format!("{}", e));
+        if let Some(r) = self.approvals.get_mut(id) {
+            r.outcome = Some(outcome.clone());
+        }
+        outcome
+    }
+
+    /// 승인 요청 거절 → T
+    pub fn reject(&mut self, admin: &str, id: u64, reason: &str) -> Result<(), String> {
+        self.check_approver(admin)?;
+        self.approvals.decide(id, admin, TritPermission::Deny, reason)?;
+        Ok(())
+    }
+
+    // ── IPC ──
+
+    /// 채널 생성 — 핸들을 태스크 클로저에 복제해 넘긴다
+    pub fn create_channel(&mut self) -> Channel {
+        self.channels.create(self.config.channel_capacity)
+    }
+
+    /// 채널 전송 — P: 넣음 / O: 가득 참 / T: 닫힘 또는 없는 채널
+    pub fn send(&mut self, id: ChannelId, msg: &str) -> TritResult {
+        self.total_ops += 1;
+        self.channels.get(id).map_or(TritResult::Failed, |ch| ch.send(msg))
+    }
+
+    /// 채널 수신 — P: 전달 / O: 대기 / T: 닫힘 또는 없는 채널
+    pub fn recv(&mut self, id: ChannelId) -> Recv {
+        self.total_ops += 1;
+        self.channels.get(id).map_or(Recv::Closed, Channel::recv)
+    }
+
+    /// 채널 닫기
+    pub fn close_channel(&mut self, id: ChannelId) -> bool {
+        self.channels.get(id).map(Channel::close).is_some()
+    }
+
     /// 커널 종료
     pub fn shutdown(&mut self) {
         // 모든 활성 트랜잭션 롤백
@@ -245,6 +475,11 @@ impl CrownyKernel {
             let _ = self.transaction.rollback(tx_id);
         }
 
+        // 채널 닫기 — 대기 중인 수신자는 남은 메시지 뒤에 T를 받는다
+        for ch in self.channels.iter() {
+            ch.close();
+        }
+
         self.state = KernelState::Shutdown;
         if self.config.debug {
             eprintln!("[KERNEL] Crowny Meta-Kernel 종료");
@@ -261,12 +496,32 @@ impl CrownyKernel {
         self.scheduler.dump();
         self.permission.dump();
         self.transaction.dump();
+        self.channels.dump();
         println!("║  TVM: IP={} 스택={} 힙={} 사이클={}",
             self.vm.ip, self.vm.stack.len(), self.vm.heap.alive_count(), self.vm.cycles);
         println!("╚═══════════════════════════════════════════════════╝");
     }
 }
 
+/// 프로그램 제출 결과 (3진)
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubmitOutcome {
+    Executed(Result<(), String>), // P: 실행됨
+    Pending(u64),                 // O: 승인 요청 ID
+    Refused(String),              // T: 거부 사유
+}
+
+impl std::fmt::Display for SubmitOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubmitOutcome::Executed(Ok(())) => write!(f, "P(실행 완료)"),
+            SubmitOutcome::Executed(Err(e)) => write!(f, "P(실행) 오류: {}", e),
+            SubmitOutcome::Pending(id) => write!(f, "O(승인 대기 #{})", id),
+            SubmitOutcome::Refused(r) => write!(f, "T(거부) {}", r),
+        }
+    }
+}
+
 /// 보호된 실행 결과
 #[derive(Debug)]
 pub struct GuardedResult {
@@ -286,6 +541,12 @@ impl std::fmt::Display for GuardedResult {
 mod tests {
     use super::*;
 
+    /// 능력 회수 — 명시적 차단
+    fn revoke(kernel: &mut CrownyKernel, subject: &str, cap: Capability) {
+        kernel.permission.prepend_policy(subject, &cap.object(), cap.action(),
+            TritPermission::Deny, &format!("능력 회수: {}", cap.code()));
+    }
+
     #[test]
     fn test_kernel_boot() {
         let kernel = CrownyKernel::boot(KernelConfig::default());
@@ -315,10 +576,220 @@ mod tests {
         assert_eq!(result.task_result, None); // 실행 안 됨
     }
 
+    #[test]
+    fn test_guarded_priority_inheritance() {
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+
+        // 쓰기는 O(검토) → 한 단계 강등, 보류 결과로 "장부" 잠금·트랜잭션 유지
+        let r = kernel.execute_guarded(
+            "배치", "장부", Action::Write,
+            "야간정산", TritPriority::Normal,
+            Box::new(|| TritResult::Pending),
+        );
+        assert_eq!(r.task_result, Some(TritResult::Pending));
+        assert_eq!(kernel.transaction.active.len(), 1);
+
+        // 중간 우선순위 작업이 쌓여 있어도
+        kernel.scheduler.submit("통계", TritPriority::Normal, Box::new(|| TritResult::Success));
+        kernel.scheduler.submit("백업", TritPriority::Normal, Box::new(|| TritResult::Success));
+
+        // 같은 자원을 읽는 P 태스크 → 보유자가 상속받아 먼저 끝나고 곧바로 실행
+        let r = kernel.execute_guarded(
+            "사용자", "장부", Action::Read,
+            "잔액조회", TritPriority::High,
+            Box::new(|| TritResult::Success),
+        );
+        assert_eq!(r.task_result, Some(TritResult::Success));
+        assert_eq!(kernel.scheduler.boosts.len(), 1);
+        assert_eq!(kernel.scheduler.pending_count(), 2); // 통계·백업은 아직 대기
+        // 보유자의 열린 트랜잭션 정리 (재실행 실패 → 롤백)
+        assert!(kernel.transaction.active.is_empty());
+        assert_eq!(kernel.transaction.stats_rollback, 1);
+    }
+
+    #[test]
+    fn test_preflight_refuses() {
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+        let src = "넣어 \"a.txt\"\n파일읽기\n넣어 \"b.txt\"\n파일쓰기\n질문해\n종료";
+
+        // 파일읽기는 기본 허용, 쓰기·LLM은 O(검토) → 실행 전 거부
+        let err = kernel.execute_program_as("앱", src).unwrap_err();
+        assert!(err.contains("file.write=O"));
+        assert!(err.contains("llm=O"));
+        assert!(!err.contains("file.read"));
+        assert_eq!(kernel.vm.cycles, 0); // 실행되지 않음
+
+        // 능력 부여 후 통과
+        kernel.grant_capability("앱", Capability::FileWrite);
+        kernel.grant_capability("앱", Capability::Llm);
+        let program = crate::assembler::assemble(src);
+        assert!(kernel.preflight("앱", &program).is_allowed());
+        assert!(kernel.execute_program_as("앱", src).is_ok());
+
+        // 회수 → T
+        revoke(&mut kernel, "앱", Capability::Llm);
+        let report = kernel.preflight("앱", &program);
+        assert_eq!(report.verdict(), TritPermission::Deny);
+        assert_eq!(report.missing[0].sites, vec![4]);
+    }
+
+    #[test]
+    fn test_approval_workflow() {
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+        kernel.permission.add_policy("관리자", "*", Action::Admin, TritPermission::Allow, "관리자 전권");
+        let src = "넣어 \"질문\"\n질문해\n넣어 7\n종료";
+
+        // LLM 능력 O → 보류
+        let id = match kernel.submit_program("앱", src) {
+            SubmitOutcome::Pending(id) => id,
+            other => panic!("보류 기대: {}", other),
+        };
+        assert_eq!(kernel.approvals.pending().len(), 1);
+        assert_eq!(kernel.vm.cycles, 0);
+
+        // 승인 권한 없는 주체
+        assert!(kernel.approve("앱", id).is_err());
+
+        // 관리자 승인 → 자동 재개
+        kernel.approve("관리자", id).unwrap();
+        let rec = kernel.approvals.get(id).unwrap();
+        assert_eq!(rec.state, TritPermission::Allow);
+        assert_eq!(rec.outcome, Some(Ok(())));
+        assert!(kernel.vm.cycles > 0);
+
+        // 일회성 승인 — 다시 제출하면 새 요청
+        let id2 = match kernel.submit_program("앱", src) {
+            SubmitOutcome::Pending(id) => id,
+            other => panic!("보류 기대: {}", other),
+        };
+        kernel.reject("관리자", id2, "예산 초과").unwrap();
+        assert_eq!(kernel.approvals.get(id2).unwrap().state, TritPermission::Deny);
+        assert!(kernel.approve("관리자", id2).is_err());
+    }
+
+    #[test]
+    fn test_approval_refused_after_capability_revoked() {
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+        kernel.permission.add_policy("관리자", "*", Action::Admin, TritPermission::Allow, "관리자 전권");
+        let id = match kernel.submit_program("앱", "넣어 \"질문\"\n질문해\n넣어 7\n종료") {
+            SubmitOutcome::Pending(id) => id,
+            other => panic!("보류 기대: {}", other),
+        };
+
+        // 승인 대상 능력이 대기 중에 T로 회수됨 → 실행하지 않고 T로 기록
+        revoke(&mut kernel, "앱", Capability::Llm);
+        let err = kernel.approve("관리자", id).unwrap_err();
+        assert!(err.contains("llm=T"), "{}", err);
+        let rec = kernel.approvals.get(id).unwrap();
+        assert_eq!((rec.state, rec.outcome.clone()), (TritPermission::Deny, None));
+        assert_eq!(kernel.vm.cycles, 0);
+        assert!(kernel.approve("관리자", id).is_err());
+    }
+
+    #[test]
+    fn test_submit_denied_capability() {
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+        revoke(&mut kernel, "앱", Capability::Http);
+        match kernel.submit_program("앱", "넣어 \"url\"\nHTTP가져와\n종료") {
+            SubmitOutcome::Refused(r) => assert!(r.contains("http=T")),
+            other => panic!("거부 기대: {}", other),
+        }
+        assert!(kernel.approvals.pending().is_empty());
+    }
+
+    #[test]
+    fn test_program_limits_refuse_before_run() {
+        let mut kernel = CrownyKernel::boot(KernelConfig {
+            program_limits: ProgramLimits { max_instructions: Some(2), ..ProgramLimits::unlimited() },
+            ..KernelConfig::default()
+        });
+        let err = kernel.execute_program("넣어 1\n넣어 2\n더해\n종료").unwrap_err();
+        assert!(err.contains("3행"), "{}", err);
+        assert_eq!(kernel.vm.cycles, 0);
+        match kernel.submit_program("앱", "넣어 1\n넣어 2\n더해") {
+            SubmitOutcome::Refused(r) => assert!(r.contains("> 2")),
+            other => panic!("거부 기대: {}", other),
+        }
+        assert!(kernel.execute_program("넣어 1\n종료").is_ok());
+    }
+
     #[test]
     fn test_kernel_shutdown() {
         let mut kernel = CrownyKernel::boot(KernelConfig::default());
         kernel.shutdown();
         assert_eq!(kernel.state, KernelState::Shutdown);
     }
+
+    #[test]
+    fn test_shutdown_closes_channels() {
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+        let ch = kernel.create_channel();
+        kernel.send(ch.id, "마지막");
+        kernel.shutdown();
+        // 닫힌 뒤에도 남은 메시지는 받고, 비면 Closed
+        assert_eq!(kernel.recv(ch.id), Recv::Delivered("마지막".into()));
+        assert_eq!(kernel.recv(ch.id), Recv::Closed);
+    }
+
+    #[test]
+    fn test_channel_between_tasks() {
+        let mut kernel = CrownyKernel::boot(KernelConfig { channel_capacity: 2, ..KernelConfig::default() });
+        let ch = kernel.create_channel();
+
+        // 아직 보낸 것이 없으면 O(대기)
+        assert_eq!(kernel.recv(ch.id), Recv::Pending);
+
+        let tx = ch.clone();
+        let produced = kernel.execute_task("생산", TritPriority::High, Box::new(move || {
+            match (tx.send("작업1"), tx.send("작업2")) {
+                (TritResult::Success, TritResult::Success) => TritResult::Success,
+                _ => TritResult::Failed,
+            }
+        }));
+        assert_eq!(produced, TritResult::Success);
+        assert_eq!(kernel.send(ch.id, "작업3"), TritResult::Pending); // 용량 2
+
+        let rx = ch.clone();
+        let consumed = kernel.execute_task("소비", TritPriority::Normal, Box::new(move || {
+            match rx.recv() {
+                Recv::Delivered(m) if m == "작업1" => TritResult::Success,
+                _ => TritResult::Failed,
+            }
+        }));
+        assert_eq!(consumed, TritResult::Success);
+
+        assert!(kernel.close_channel(ch.id));
+        assert_eq!(kernel.send(ch.id, "늦음"), TritResult::Failed);
+        assert_eq!(kernel.recv(ch.id).message(), Some("작업2".into()));
+        assert_eq!(kernel.recv(ch.id), Recv::Closed);
+        assert_eq!(kernel.recv(404), Recv::Closed);
+        let s = kernel.channels.totals();
+        assert_eq!((s.sent, s.delivered, s.pending_recvs, s.full_sends, s.refused), (2, 2, 1, 1, 1));
+    }
+
+    #[test]
+    fn test_watchdog_recovers_stalled_scheduler() {
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+        let t0 = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+            .unwrap().as_millis() as u64;
+        // 할 일이 없으면 멈춘 것이 아니다
+        assert!(kernel.watch_at(t0 + 10 * SCHEDULER_BEAT_MS).is_empty());
+
+        // 잠금을 쥔 채 보류된 태스크 + 대기자, 그런데 아무도 스케줄러를 돌리지 않음
+        kernel.scheduler.submit_locked("보유", TritPriority::Normal, "장부", Box::new(|| TritResult::Pending));
+        kernel.scheduler.execute_one();
+        kernel.scheduler.submit_locked("대기", TritPriority::High, "장부", Box::new(|| TritResult::Success));
+        assert!(kernel.scheduler.lock_holder("장부").is_some());
+
+        let t1 = t0 + 20 * SCHEDULER_BEAT_MS;
+        assert_eq!(kernel.watch_at(t1), vec![WatchdogAction::Restarted(ComponentKind::Scheduler)]);
+        assert_eq!(kernel.scheduler.stats_recovered, 1);
+        assert_eq!(kernel.scheduler.lock_holder("장부"), None);
+        assert_eq!(kernel.watchdog.component(&ComponentKind::Scheduler).unwrap().restarts, 1);
+
+        // 복구 후 다시 진행 → 더 이상 재시작 없음
+        kernel.scheduler.run_all();
+        assert_eq!(kernel.scheduler.pending_count(), 0);
+        assert!(kernel.watch_at(t1 + 10 * SCHEDULER_BEAT_MS).is_empty());
+    }
 }
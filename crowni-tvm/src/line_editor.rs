@@ -0,0 +1,562 @@
+// ═══════════════════════════════════════════════════════════════
+// 줄 편집기 — REPL 입력 (외부 크레이트 없이 stty로 raw 모드)
+//
+//   ← → Home End / Ctrl-A Ctrl-E   커서 이동
+//   ↑ ↓                           기록 탐색
+//   Ctrl-R                        기록 역검색 (다시 누르면 더 이전 것)
+//   Tab                           단어 완성 (후보가 여럿이면 공통 앞부분까지, 그다음 목록)
+//   Ctrl-U / Ctrl-K / Ctrl-W      커서 앞 · 뒤 · 앞 단어 지우기
+//   Ctrl-C                        이번 입력 취소   Ctrl-D (빈 줄) 끝
+//
+// 기록은 ~/.crowny_history에 한 줄씩 덧붙인다
+// 터미널이 아니거나(파이프) stty가 없으면 read_line으로 대신한다
+// ═══════════════════════════════════════════════════════════════
+
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub const HISTORY_FILE: &str = ".crowny_history";
+/// 파일에 남기는 최대 기록 수 — 넘으면 읽을 때 앞쪽을 버린다
+pub const HISTORY_MAX: usize = 1000;
+
+// ─────────────────────────────────────────────
+// 기록
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// 파일 없는 기록 (테스트 · 기록 비활성)
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// 파일에서 읽기 — 없으면 빈 기록으로 시작하고 첫 입력 때 만든다
+    pub fn load(path: &Path) -> Self {
+        let text = fs::read_to_string(path).unwrap_or_default();
+        let mut entries: Vec<String> = text.lines().filter(|l| !l.trim().is_empty()).map(String::from).collect();
+        let excess = entries.len().saturating_sub(HISTORY_MAX);
+        entries.drain(..excess);
+        Self { entries, path: Some(path.to_path_buf()) }
+    }
+
+    /// ~/.crowny_history (HOME이 없으면 파일 없이)
+    pub fn load_default() -> Self {
+        match std::env::var_os("HOME") {
+            Some(home) => Self::load(&PathBuf::from(home).join(HISTORY_FILE)),
+            None => Self::in_memory(),
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// 빈 줄과 바로 앞과 같은 줄은 남기지 않는다
+    pub fn push(&mut self, line: &str) {
+        let line = line.trim_end();
+        if line.trim().is_empty() || self.entries.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if let Some(path) = &self.path {
+            let appended = fs::OpenOptions::new().create(true).append(true).open(path)
+                .and_then(|mut f| writeln!(f, "{}", line));
+            if let Err(e) = appended {
+                eprintln!("기록 저장 실패 '{}': {}", path.display(), e);
+                self.path = None;
+            }
+        }
+    }
+
+    /// before 앞에서 query를 포함하는 가장 최근 항목
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())].iter().rposition(|e| e.contains(query))
+    }
+}
+
+// ─────────────────────────────────────────────
+// 키 해석
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    /// Ctrl + 영문자 (소문자)
+    Ctrl(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Tab,
+    Unknown,
+}
+
+/// 바이트 열에서 키 하나 — UTF-8 다바이트 문자와 ESC [ · ESC O 시퀀스를 묶는다
+pub fn read_key(bytes: &mut impl Iterator<Item = u8>) -> Option<Key> {
+    let b = bytes.next()?;
+    Some(match b {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b => match bytes.next() {
+            Some(b'[') | Some(b'O') => escape_sequence(bytes),
+            _ => Key::Unknown,
+        },
+        0x01..=0x1a => Key::Ctrl((b'a' + b - 1) as char),
+        0x00..=0x1f => Key::Unknown,
+        _ if b < 0x80 => Key::Char(b as char),
+        _ => {
+            let len = match b {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => return Some(Key::Unknown),
+            };
+            let mut buf = vec![b];
+            buf.extend(bytes.take(len - 1));
+            std::str::from_utf8(&buf).ok().and_then(|s| s.chars().next()).map_or(Key::Unknown, Key::Char)
+        }
+    })
+}
+
+fn escape_sequence(bytes: &mut impl Iterator<Item = u8>) -> Key {
+    match bytes.next() {
+        Some(b'A') => Key::Up,
+        Some(b'B') => Key::Down,
+        Some(b'C') => Key::Right,
+        Some(b'D') => Key::Left,
+        Some(b'H') => Key::Home,
+        Some(b'F') => Key::End,
+        // ESC [ n ~ — 1/7 Home, 4/8 End, 3 Delete
+        Some(d @ b'0'..=b'9') => {
+            let mut n = vec![d];
+            for b in bytes.by_ref() {
+                if b == b'~' { break; }
+                n.push(b);
+            }
+            match n.as_slice() {
+                b"1" | b"7" => Key::Home,
+                b"4" | b"8" => Key::End,
+                b"3" => Key::Delete,
+                _ => Key::Unknown,
+            }
+        }
+        _ => Key::Unknown,
+    }
+}
+
+// ─────────────────────────────────────────────
+// 편집 상태
+// ─────────────────────────────────────────────
+
+/// 키 하나를 먹은 뒤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    Continue,
+    /// Tab — 완성 단어 목록을 가진 쪽이 complete()를 부른다
+    Complete,
+    Submit(String),
+    Cancel,
+    Eof,
+}
+
+/// Ctrl-R 역검색 — hit는 기록 색인
+#[derive(Debug, Clone, Default)]
+struct Search {
+    query: String,
+    hit: Option<usize>,
+}
+
+/// 한 줄 편집 — 터미널과 떼어 두어 키 나열만으로 검사할 수 있다
+#[derive(Debug, Clone, Default)]
+pub struct LineState {
+    buf: Vec<char>,
+    cursor: usize,
+    /// 기록 탐색 중인 색인 (None이면 새 줄)
+    browsing: Option<usize>,
+    /// 탐색 전에 쓰던 줄
+    draft: Vec<char>,
+    search: Option<Search>,
+}
+
+impl LineState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> String {
+        self.buf.iter().collect()
+    }
+
+    pub fn feed(&mut self, key: Key, history: &History) -> Edit {
+        if self.search.is_some() {
+            return self.feed_search(key, history);
+        }
+        match key {
+            Key::Enter => return Edit::Submit(self.text()),
+            Key::Ctrl('c') => return Edit::Cancel,
+            Key::Ctrl('d') if self.buf.is_empty() => return Edit::Eof,
+            Key::Ctrl('d') | Key::Delete if self.cursor < self.buf.len() => {
+                self.buf.remove(self.cursor);
+            }
+            Key::Char(c) => {
+                self.buf.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            Key::Tab => return Edit::Complete,
+            Key::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.buf.remove(self.cursor);
+            }
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(self.buf.len()),
+            Key::Home | Key::Ctrl('a') => self.cursor = 0,
+            Key::End | Key::Ctrl('e') => self.cursor = self.buf.len(),
+            Key::Ctrl('u') => {
+                self.buf.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            Key::Ctrl('k') => self.buf.truncate(self.cursor),
+            Key::Ctrl('w') => {
+                let mut start = self.cursor;
+                while start > 0 && self.buf[start - 1] == ' ' { start -= 1; }
+                while start > 0 && self.buf[start - 1] != ' ' { start -= 1; }
+                self.buf.drain(start..self.cursor);
+                self.cursor = start;
+            }
+            Key::Up | Key::Ctrl('p') => self.browse(history, -1),
+            Key::Down | Key::Ctrl('n') => self.browse(history, 1),
+            Key::Ctrl('r') => self.search = Some(Search { query: String::new(), hit: None }),
+            _ => {}
+        }
+        Edit::Continue
+    }
+
+    /// 커서 앞 단어를 완성 — 하나면 채우고 공백, 여럿이면 공통 앞부분까지.
+    /// 더 채울 것이 없으면 후보 목록을 돌려준다 (영문은 대소문자 무시)
+    pub fn complete(&mut self, words: &[String]) -> Vec<String> {
+        let start = self.buf[..self.cursor].iter().rposition(|c| c.is_whitespace()).map_or(0, |i| i + 1);
+        let prefix: String = self.buf[start..self.cursor].iter().collect::<String>().to_lowercase();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+        let matches: Vec<&String> = words.iter().filter(|w| w.to_lowercase().starts_with(&prefix)).collect();
+        let fill = match matches.as_slice() {
+            [] => return Vec::new(),
+            [only] => format!("{} ", only),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.chars().count(), |n, w| {
+                    first.chars().zip(w.chars()).take(n).take_while(|(a, b)| a.to_lowercase().eq(b.to_lowercase())).count()
+                });
+                if common <= prefix.chars().count() {
+                    return matches.into_iter().cloned().collect();
+                }
+                first.chars().take(common).collect()
+            }
+        };
+        let fill: Vec<char> = fill.chars().collect();
+        let end = self.cursor;
+        self.cursor = start + fill.len();
+        self.buf.splice(start..end, fill);
+        Vec::new()
+    }
+
+    /// ↑(-1) · ↓(+1) — 가장 최근 아래로 내려가면 쓰던 줄로 돌아온다
+    fn browse(&mut self, history: &History, dir: i32) {
+        let len = history.entries().len();
+        let next = match (self.browsing, dir) {
+            (None, -1) if len > 0 => Some(len - 1),
+            (Some(i), -1) => Some(i.saturating_sub(1)),
+            (Some(i), 1) if i + 1 < len => Some(i + 1),
+            (Some(_), 1) => None,
+            _ => return,
+        };
+        if self.browsing.is_none() {
+            self.draft = std::mem::take(&mut self.buf);
+        }
+        self.browsing = next;
+        self.buf = match next {
+            Some(i) => history.entries()[i].chars().collect(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.cursor = self.buf.len();
+    }
+
+    fn feed_search(&mut self, key: Key, history: &History) -> Edit {
+        let search = self.search.as_mut().expect("검색 중");
+        match key {
+            Key::Char(c) => {
+                search.query.push(c);
+                let from = search.hit.map_or(history.entries().len(), |h| h + 1);
+                search.hit = history.search(&search.query, from);
+            }
+            Key::Backspace => {
+                search.query.pop();
+                search.hit = history.search(&search.query, history.entries().len());
+            }
+            Key::Ctrl('r') => {
+                let before = search.hit.unwrap_or(history.entries().len());
+                search.hit = history.search(&search.query, before).or(search.hit);
+            }
+            Key::Ctrl('c') | Key::Ctrl('g') => {
+                self.search = None;
+            }
+            // 그 밖의 키는 찾은 줄을 받아들이고 평소처럼 처리
+            other => {
+                if let Some(i) = search.hit {
+                    self.buf = history.entries()[i].chars().collect();
+                    self.cursor = self.buf.len();
+                }
+                self.search = None;
+                return self.feed(other, history);
+            }
+        }
+        Edit::Continue
+    }
+
+    /// 줄을 다시 그리는 제어 문자열 — 커서는 화면 폭(한글 2칸) 기준으로 되돌린다
+    pub fn render(&self, prompt: &str, history: &History) -> String {
+        let (head, line, cursor) = match &self.search {
+            Some(s) => {
+                let found = s.hit.map_or("", |i| history.entries()[i].as_str());
+                (format!("(역검색 '{}'): ", s.query), found.chars().collect::<Vec<_>>(), found.chars().count())
+            }
+            None => (prompt.to_string(), self.buf.clone(), self.cursor),
+        };
+        let text: String = line.iter().collect();
+        let back: usize = line[cursor..].iter().map(|&c| char_width(c)).sum();
+        let mut out = format!("\r\x1b[K{}{}", head, text);
+        if back > 0 {
+            out.push_str(&format!("\x1b[{}D", back));
+        }
+        out
+    }
+}
+
+/// 터미널 칸 수 — 한글 · CJK · 전각은 2칸
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115f | 0x2e80..=0xa4cf | 0xac00..=0xd7a3 | 0xf900..=0xfaff | 0xfe30..=0xfe4f | 0xff00..=0xff60 | 0xffe0..=0xffe6 => 2,
+        _ => 1,
+    }
+}
+
+// ─────────────────────────────────────────────
+// 터미널
+// ─────────────────────────────────────────────
+
+/// 한 번 읽은 결과
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Line(String),
+    /// Ctrl-C
+    Interrupted,
+    Eof,
+}
+
+/// stty raw 모드 — Drop에서 원래 설정으로 되돌린다
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enter() -> Option<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "-ixon", "min", "1"])?;
+        Some(Self { saved: saved.trim().to_string() })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[self.saved.as_str()]);
+    }
+}
+
+/// stty는 표준입력의 터미널을 설정한다
+fn stty(args: &[&str]) -> Option<String> {
+    let out = Command::new("stty").args(args).stdin(Stdio::inherit()).stderr(Stdio::null()).output().ok()?;
+    out.status.success().then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+pub struct Editor {
+    pub history: History,
+    /// Tab 완성 후보 — 읽기 전에 바꿔 끼울 수 있다 (REPL 변수 이름 등)
+    pub words: Vec<String>,
+}
+
+impl Editor {
+    pub fn new(history: History) -> Self {
+        Self { history, words: Vec::new() }
+    }
+
+    /// 프롬프트를 띄우고 한 줄 — 받은 줄은 기록에 남긴다
+    pub fn read(&mut self, prompt: &str) -> Input {
+        let raw = if io::stdin().is_terminal() { RawMode::enter() } else { None };
+        let input = match raw {
+            Some(_guard) => self.read_raw(prompt),
+            None => read_plain(prompt),
+        };
+        if let Input::Line(line) = &input {
+            self.history.push(line);
+        }
+        input
+    }
+
+    fn read_raw(&mut self, prompt: &str) -> Input {
+        let mut state = LineState::new();
+        let mut out = io::stdout();
+        let _ = write!(out, "{}", state.render(prompt, &self.history));
+        let _ = out.flush();
+        let stdin = io::stdin();
+        let mut bytes = stdin.lock().bytes().map_while(Result::ok);
+        loop {
+            let Some(key) = read_key(&mut bytes) else { return Input::Eof };
+            let edit = state.feed(key, &self.history);
+            let _ = write!(out, "{}", state.render(prompt, &self.history));
+            match edit {
+                Edit::Continue => {
+                    let _ = out.flush();
+                }
+                Edit::Complete => {
+                    let candidates = state.complete(&self.words);
+                    if !candidates.is_empty() {
+                        let _ = write!(out, "\r\n{}\r\n", candidates.join("  "));
+                    }
+                    let _ = write!(out, "{}", state.render(prompt, &self.history));
+                    let _ = out.flush();
+                }
+                Edit::Submit(line) => {
+                    let _ = write!(out, "\r\n");
+                    let _ = out.flush();
+                    return Input::Line(line);
+                }
+                Edit::Cancel => {
+                    let _ = write!(out, "^C\r\n");
+                    let _ = out.flush();
+                    return Input::Interrupted;
+                }
+                Edit::Eof => {
+                    let _ = write!(out, "\r\n");
+                    return Input::Eof;
+                }
+            }
+        }
+    }
+}
+
+fn read_plain(prompt: &str) -> Input {
+    print!("{}", prompt);
+    io::stdout().flush().unwrap_or(());
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => Input::Eof,
+        Ok(_) => Input::Line(line.trim_end_matches(['\n', '\r']).to_string()),
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bytes: &[u8]) -> Vec<Key> {
+        let mut it = bytes.iter().copied();
+        std::iter::from_fn(|| read_key(&mut it)).collect()
+    }
+
+    fn type_keys(state: &mut LineState, history: &History, input: &[Key]) -> Edit {
+        input.iter().map(|&k| state.feed(k, history)).last().unwrap_or(Edit::Continue)
+    }
+
+    #[test]
+    fn test_key_decoding() {
+        assert_eq!(keys("넣어\x1b[D\x1b[3~\x1bOH\x1b[4~\x7f\r".as_bytes()), vec![
+            Key::Char('넣'), Key::Char('어'), Key::Left, Key::Delete, Key::Home, Key::End, Key::Backspace, Key::Enter,
+        ]);
+        assert_eq!(keys(b"\x01\x12\x03\x1b[A\x1b[B"), vec![Key::Ctrl('a'), Key::Ctrl('r'), Key::Ctrl('c'), Key::Up, Key::Down]);
+    }
+
+    #[test]
+    fn test_editing_and_history_browse() {
+        let mut h = History::in_memory();
+        for line in ["넣어 1", "넣어 2", "넣어 2", "  ", "더해"] {
+            h.push(line);
+        }
+        assert_eq!(h.entries(), ["넣어 1", "넣어 2", "더해"]);
+
+        let mut s = LineState::new();
+        let typed: Vec<Key> = "보여".chars().map(Key::Char).collect();
+        type_keys(&mut s, &h, &typed);
+        type_keys(&mut s, &h, &[Key::Up, Key::Up]);
+        assert_eq!(s.text(), "넣어 2");
+        // 맨 아래로 내려오면 쓰던 줄이 돌아온다
+        type_keys(&mut s, &h, &[Key::Down, Key::Down]);
+        assert_eq!(s.text(), "보여");
+        type_keys(&mut s, &h, &[Key::Char('줘'), Key::Home, Key::Char('['), Key::End, Key::Char(']')]);
+        assert_eq!(s.text(), "[보여줘]");
+        type_keys(&mut s, &h, &[Key::Left, Key::Left, Key::Backspace, Key::Ctrl('k')]);
+        assert_eq!((s.text().as_str(), s.cursor), ("[보", 2));
+        // 커서 뒤 한글 한 글자 = 2칸 되돌림
+        assert!(s.render("> ", &h).ends_with("> [보"));
+        type_keys(&mut s, &h, &[Key::Left]);
+        assert!(s.render("> ", &h).ends_with("[보\x1b[2D"));
+        assert_eq!(type_keys(&mut s, &h, &[Key::Ctrl('u'), Key::Ctrl('k'), Key::Ctrl('d')]), Edit::Eof);
+    }
+
+    #[test]
+    fn test_reverse_search_and_history_file() {
+        let path = std::env::temp_dir().join(format!("crowny-history-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut h = History::load(&path);
+        for line in ["넣어 10", "보여줘", "넣어 20", "더해"] {
+            h.push(line);
+        }
+        let h = History::load(&path);
+        assert_eq!(h.entries().len(), 4);
+
+        let mut s = LineState::new();
+        type_keys(&mut s, &h, &[Key::Ctrl('r'), Key::Char('넣')]);
+        assert!(s.render("> ", &h).contains("(역검색 '넣'): 넣어 20"));
+        // 다시 Ctrl-R → 더 이전 항목, Enter로 받아들임
+        let edit = type_keys(&mut s, &h, &[Key::Ctrl('r'), Key::Enter]);
+        assert_eq!(edit, Edit::Submit("넣어 10".into()));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_tab_completion() {
+        let words: Vec<String> = ["더해", "더해라", "ADD", "AND", ".stack", ".스택"].iter().map(|s| s.to_string()).collect();
+        let h = History::in_memory();
+        let mut s = LineState::new();
+        let typed: Vec<Key> = "넣어 1 더".chars().map(Key::Char).collect();
+        type_keys(&mut s, &h, &typed);
+        assert_eq!(s.feed(Key::Tab, &h), Edit::Complete);
+        // 공통 앞부분 "더해"까지 채우고, 더 채울 것이 없으면 후보 목록
+        assert!(s.complete(&words).is_empty());
+        assert_eq!(s.text(), "넣어 1 더해");
+        assert_eq!(s.complete(&words), ["더해", "더해라"]);
+
+        let mut s = LineState::new();
+        type_keys(&mut s, &h, &[Key::Char('a'), Key::Char('d')]);
+        assert!(s.complete(&words).is_empty());
+        assert_eq!((s.text().as_str(), s.cursor), ("ADD ", 4));
+        let mut s = LineState::new();
+        type_keys(&mut s, &h, &[Key::Char('.'), Key::Char('스')]);
+        s.complete(&words);
+        assert_eq!(s.text(), ".스택 ");
+    }
+}
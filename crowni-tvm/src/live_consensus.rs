@@ -2,15 +2,27 @@
 // Crowny Live Consensus — 실제 HTTP 3포트 합의
 // TCP 소켓 연결 · JSON 요청/응답 · 타임아웃 · 폴백
 // Claude:18789 · Gemini:18790 · Sonnet:18791
+// 커밋-공개 모드: phase=commit으로 해시만 모은 뒤 phase=reveal로 공개
 // ═══════════════════════════════════════════════════════════════
 
 use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::collections::HashMap;
+use std::sync::{mpsc, Arc};
+use crate::commit_reveal::{self, CommitRevealRound, SealedVote};
+use crate::crypto;
+use crate::output::{JsonObject, say};
+use crate::trit_store::{StoreValue, TritStore};
+use crate::websocket::EventHub;
 
 fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
 
+/// 노드 대신 폴백으로 만든 투표의 근거 접두사
+pub const FALLBACK_PREFIX: &str = "(폴백)";
+/// 전체 마감 — 노드 요청은 동시에 나가고, 이 시간 안에 온 표만 센다
+pub const DEFAULT_DEADLINE_MS: u64 = 6000;
+
 // ═══════════════════════════════════════
 // 노드 설정
 // ═══════════════════════════════════════
@@ -35,6 +47,18 @@ pub enum NodeStatus {
     Error(String),
 }
 
+impl NodeStatus {
+    /// 기계용 코드
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Online => "online",
+            Self::Offline => "offline",
+            Self::Timeout => "timeout",
+            Self::Error(_) => "error",
+        }
+    }
+}
+
 impl std::fmt::Display for NodeStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -57,6 +81,16 @@ impl ConsensusNode {
 
     /// TCP 연결 + HTTP POST 요청 전송
     pub fn send_request(&mut self, query: &str) -> Result<HttpResponse, String> {
+        self.post(query, "")
+    }
+
+    /// 커밋-공개 단계 요청 — phase: "commit" | "reveal"
+    pub fn send_phase(&mut self, query: &str, phase: &str, round: u64) -> Result<HttpResponse, String> {
+        self.post(query, &format!(r#","phase":"{}","round":{}"#, phase, round))
+    }
+
+    /// extra: 본문 JSON 끝에 덧붙일 필드 (",\"key\":..." 형식)
+    fn post(&mut self, query: &str, extra: &str) -> Result<HttpResponse, String> {
         let start = Instant::now();
         let addr = format!("{}:{}", self.host, self.port);
 
@@ -82,8 +116,8 @@ impl ConsensusNode {
 
         // 2. HTTP POST 요청 생성
         let body = format!(
-            r#"{{"query":"{}","model":"{}","trit_mode":"consensus","ctp":"PPPPOOOOO","timestamp":{}}}"#,
-            query.replace('"', r#"\""#), self.name, now_ms()
+            r#"{{"query":"{}","model":"{}","trit_mode":"consensus","ctp":"PPPPOOOOO","timestamp":{}{}}}"#,
+            query.replace('"', r#"\""#), self.name, now_ms(), extra
         );
 
         let request = format!(
@@ -201,6 +235,28 @@ pub struct ConsensusVote {
     pub latency_ms: u64,
     pub status: NodeStatus,
     pub raw_response: Option<String>,
+    /// 평판 가중치 (새 노드 1.0)
+    pub weight: f64,
+}
+
+impl ConsensusVote {
+    /// 노드 응답이 아니라 폴백 투표인가
+    pub fn is_fallback(&self) -> bool {
+        self.raw_response.is_none() && self.reason.starts_with(FALLBACK_PREFIX)
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let obj = JsonObject::new()
+            .str("node", &self.node_name)
+            .trit("state", self.trit)
+            .str("reason", &self.reason)
+            .int("latency_ms", self.latency_ms as i64)
+            .float("weight", self.weight);
+        match &self.status {
+            NodeStatus::Error(e) => obj.str("status", self.status.code()).str("error", e),
+            status => obj.str("status", status.code()),
+        }
+    }
 }
 
 impl std::fmt::Display for ConsensusVote {
@@ -228,6 +284,20 @@ impl ConsensusResult {
     pub fn ctp_string(&self) -> String {
         self.ctp_header.iter().map(|t| match t { 1 => 'P', -1 => 'T', _ => 'O' }).collect()
     }
+
+    /// 안정 필드 순서 JSON (schema: crowny.live_consensus_result)
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.live_consensus_result")
+            .trit("state", self.consensus_trit)
+            .str("query", &self.query)
+            .float("confidence", self.confidence)
+            .int("nodes_online", self.nodes_online as i64)
+            .int("nodes_total", self.nodes_total as i64)
+            .str("ctp", &self.ctp_string())
+            .int("total_latency_ms", self.total_latency_ms as i64)
+            .int("timestamp", self.timestamp as i64)
+            .objects("votes", self.votes.iter().map(|v| v.to_json()).collect())
+    }
 }
 
 impl std::fmt::Display for ConsensusResult {
@@ -239,14 +309,120 @@ impl std::fmt::Display for ConsensusResult {
     }
 }
 
+// ═══════════════════════════════════════
+// 노드 평판
+// ═══════════════════════════════════════
+
+/// TritStore 키 접두사 — "reputation.<노드 이름>"
+pub const REPUTATION_PREFIX: &str = "reputation.";
+/// `live` 데모가 실행 사이에 평판을 유지하는 저장소
+pub const REPUTATION_DIR: &str = ".crowny/reputation";
+/// 평균 지연이 이만큼이면 가중치 절반
+const LATENCY_HALF_MS: f64 = 5000.0;
+
+/// 노드 평판 — 합의와 일치한 비율 · 응답률 · 평균 지연
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reputation {
+    /// 실제로 응답한 라운드
+    pub rounds: u64,
+    /// 그중 최종 합의와 같은 표
+    pub agreed: u64,
+    /// 오프라인 · 타임아웃 · 미공개
+    pub failures: u64,
+    pub avg_latency_ms: f64,
+}
+
+impl Reputation {
+    /// 일치율 (라플라스 보정 — 기록 없으면 0.5)
+    pub fn accuracy(&self) -> f64 {
+        (self.agreed as f64 + 1.0) / (self.rounds as f64 + 2.0)
+    }
+
+    /// 응답률 (기록 없으면 0.5)
+    pub fn reliability(&self) -> f64 {
+        (self.rounds as f64 + 1.0) / ((self.rounds + self.failures) as f64 + 2.0)
+    }
+
+    /// 표 가중치 — 새 노드 1.0, 항상 맞고 빠른 노드는 4.0에 가까워진다
+    pub fn weight(&self) -> f64 {
+        4.0 * self.accuracy() * self.reliability() / (1.0 + self.avg_latency_ms / LATENCY_HALF_MS)
+    }
+
+    /// 가중치의 3진 요약 — P: 믿을 만함 / O: 보통 / T: 불안정
+    pub fn trit(&self) -> i8 {
+        let w = self.weight();
+        if w >= 1.5 { 1 } else if w < 0.5 { -1 } else { 0 }
+    }
+
+    /// 라운드 결과 반영
+    pub fn record(&mut self, vote: &ConsensusVote, consensus_trit: i8) {
+        if vote.status != NodeStatus::Online || vote.raw_response.is_none() {
+            self.failures += 1;
+            return;
+        }
+        self.rounds += 1;
+        if vote.trit == consensus_trit {
+            self.agreed += 1;
+        }
+        let latency = vote.latency_ms as f64;
+        self.avg_latency_ms = if self.rounds == 1 { latency } else { self.avg_latency_ms * 0.8 + latency * 0.2 };
+    }
+
+    pub fn to_store(&self) -> StoreValue {
+        StoreValue::Map(HashMap::from([
+            ("rounds".to_string(), StoreValue::Int(self.rounds as i64)),
+            ("agreed".to_string(), StoreValue::Int(self.agreed as i64)),
+            ("failures".to_string(), StoreValue::Int(self.failures as i64)),
+            ("avg_latency_ms".to_string(), StoreValue::Float(self.avg_latency_ms)),
+        ]))
+    }
+
+    pub fn from_store(value: &StoreValue) -> Option<Self> {
+        let StoreValue::Map(m) = value else { return None };
+        let int = |k: &str| match m.get(k) { Some(StoreValue::Int(v)) => Some(*v as u64), _ => None };
+        Some(Self {
+            rounds: int("rounds")?,
+            agreed: int("agreed")?,
+            failures: int("failures")?,
+            avg_latency_ms: match m.get("avg_latency_ms") { Some(StoreValue::Float(v)) => *v, _ => 0.0 },
+        })
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .trit("state", self.trit())
+            .float("weight", self.weight())
+            .int("rounds", self.rounds as i64)
+            .int("agreed", self.agreed as i64)
+            .int("failures", self.failures as i64)
+            .float("avg_latency_ms", self.avg_latency_ms)
+    }
+}
+
 // ═══════════════════════════════════════
 // 라이브 합의 엔진
 // ═══════════════════════════════════════
 
+pub const DEFAULT_QUORUM: usize = 2;
+
 pub struct LiveConsensus {
     pub nodes: Vec<ConsensusNode>,
     pub history: Vec<ConsensusResult>,
     pub fallback_enabled: bool,
+    /// 커밋-공개 투표 — 모든 노드의 해시가 모인 뒤에만 trit 공개
+    pub commit_reveal: bool,
+    pub reveal_timeout_ms: u64,
+    /// 투표 · 결과 → WebSocket "vote" / "consensus" 프레임
+    pub events: Option<EventHub>,
+    /// 전체 마감 (ms) — 늦은 노드는 Timeout · O
+    pub deadline_ms: u64,
+    /// false면 모든 표 1.0 (평판은 계속 기록)
+    pub weighted: bool,
+    pub reputations: HashMap<String, Reputation>,
+    /// 있으면 라운드마다 평판을 "reputation.<노드>"로 저장
+    pub reputation_store: Option<TritStore>,
+    /// CTP 정족수 트릿이 P가 되는 최소 온라인 노드 수
+    pub quorum: usize,
 }
 
 impl LiveConsensus {
@@ -259,11 +435,94 @@ impl LiveConsensus {
             ],
             history: Vec::new(),
             fallback_enabled: true,
+            commit_reveal: false,
+            reveal_timeout_ms: commit_reveal::DEFAULT_REVEAL_TIMEOUT_MS,
+            events: None,
+            deadline_ms: DEFAULT_DEADLINE_MS,
+            weighted: true,
+            reputations: HashMap::new(),
+            reputation_store: None,
+            quorum: DEFAULT_QUORUM,
         }
     }
 
     pub fn with_nodes(nodes: Vec<ConsensusNode>) -> Self {
-        Self { nodes, history: Vec::new(), fallback_enabled: true }
+        Self {
+            nodes, history: Vec::new(), fallback_enabled: true,
+            commit_reveal: false, reveal_timeout_ms: commit_reveal::DEFAULT_REVEAL_TIMEOUT_MS,
+            events: None, deadline_ms: DEFAULT_DEADLINE_MS,
+            weighted: true, reputations: HashMap::new(), reputation_store: None,
+            quorum: DEFAULT_QUORUM,
+        }
+    }
+
+    /// 평판 저장소 연결 — 저장된 평판을 불러오고 이후 라운드마다 갱신
+    pub fn with_reputation_store(mut self, store: TritStore) -> Self {
+        for key in store.keys() {
+            let Some(name) = key.strip_prefix(REPUTATION_PREFIX) else { continue };
+            if let Some(rep) = store.peek(key).and_then(Reputation::from_store) {
+                self.reputations.insert(name.to_string(), rep);
+            }
+        }
+        self.reputation_store = Some(store);
+        self
+    }
+
+    pub fn with_weighting(mut self, weighted: bool) -> Self {
+        self.weighted = weighted;
+        self
+    }
+
+    /// 노드 평판 (기록 없으면 기본값)
+    pub fn reputation(&self, node: &str) -> Reputation {
+        self.reputations.get(node).cloned().unwrap_or_default()
+    }
+
+    /// 이번 라운드에 쓸 가중치
+    pub fn weight_of(&self, node: &str) -> f64 {
+        if self.weighted { self.reputation(node).weight() } else { 1.0 }
+    }
+
+    /// 설정된 노드 순서대로 (이름, 평판)
+    pub fn reputation_table(&self) -> Vec<(String, Reputation)> {
+        self.nodes.iter().map(|n| (n.name.clone(), self.reputation(&n.name))).collect()
+    }
+
+    /// 한 노드 평판 초기화 — 기록이 있었으면 true
+    pub fn reset_reputation(&mut self, node: &str) -> bool {
+        if let Some(store) = &mut self.reputation_store {
+            store.delete(&format!("{}{}", REPUTATION_PREFIX, node));
+        }
+        self.reputations.remove(node).is_some()
+    }
+
+    pub fn reset_reputations(&mut self) {
+        let names: Vec<String> = self.reputations.keys().cloned().collect();
+        for name in names {
+            self.reset_reputation(&name);
+        }
+    }
+
+    fn record_reputations(&mut self, votes: &[ConsensusVote], consensus_trit: i8) {
+        for vote in votes {
+            let rep = self.reputations.entry(vote.node_name.clone()).or_default();
+            rep.record(vote, consensus_trit);
+            if let Some(store) = &mut self.reputation_store {
+                let key = format!("{}{}", REPUTATION_PREFIX, vote.node_name);
+                store.set(&key, rep.to_store());
+                store.set_trit_state(&key, rep.trit());
+            }
+        }
+    }
+
+    pub fn with_deadline(mut self, deadline_ms: u64) -> Self {
+        self.deadline_ms = deadline_ms;
+        self
+    }
+
+    pub fn with_commit_reveal(mut self, enabled: bool) -> Self {
+        self.commit_reveal = enabled;
+        self
     }
 
     /// 모든 노드 핑 체크
@@ -279,61 +538,29 @@ impl LiveConsensus {
     /// 3포트 실제 HTTP 합의 실행
     pub fn execute(&mut self, query: &str) -> ConsensusResult {
         let start = Instant::now();
-        let mut votes = Vec::new();
-        let mut online = 0;
-
-        for node in &mut self.nodes {
-            let vote = match node.send_request(query) {
-                Ok(response) => {
-                    online += 1;
-                    // JSON 응답에서 trit 파싱
-                    let trit = Self::parse_trit_from_response(&response.body);
-                    let reason = Self::parse_reason_from_response(&response.body)
-                        .unwrap_or_else(|| format!("HTTP {} ({}ms)", response.status_code, response.latency_ms));
-
-                    ConsensusVote {
-                        node_name: node.name.clone(),
-                        trit,
-                        reason,
-                        latency_ms: response.latency_ms,
-                        status: NodeStatus::Online,
-                        raw_response: Some(response.body),
-                    }
-                }
-                Err(err) => {
-                    // 폴백: 시뮬레이션 투표
-                    if self.fallback_enabled {
-                        let fallback_trit = Self::fallback_vote(query, &node.name);
-                        ConsensusVote {
-                            node_name: node.name.clone(),
-                            trit: fallback_trit,
-                            reason: format!("(폴백) {} — {}", err, Self::fallback_reason(fallback_trit)),
-                            latency_ms: 0,
-                            status: node.status.clone(),
-                            raw_response: None,
-                        }
-                    } else {
-                        ConsensusVote {
-                            node_name: node.name.clone(),
-                            trit: 0,
-                            reason: format!("오프라인: {}", err),
-                            latency_ms: 0,
-                            status: node.status.clone(),
-                            raw_response: None,
-                        }
-                    }
-                }
-            };
-            votes.push(vote);
+        let (mut votes, online) = if self.commit_reveal {
+            self.collect_commit_reveal(query)
+        } else {
+            self.collect_votes(query)
+        };
+        for v in &mut votes {
+            v.weight = self.weight_of(&v.node_name);
+        }
+        if let Some(hub) = &self.events {
+            for v in &votes {
+                hub.publish(JsonObject::new().str("type", "vote").str("query", query).object("vote", v.to_json()));
+            }
         }
 
-        // 합의 계산
+        // 합의 계산 — 평판 가중 합
         let p = votes.iter().filter(|v| v.trit > 0).count();
         let t = votes.iter().filter(|v| v.trit < 0).count();
-        let consensus_trit = if p > t { 1 } else if t > p { -1 } else { 0 };
+        let sum = |pred: fn(i8) -> bool| votes.iter().filter(|v| pred(v.trit)).map(|v| v.weight).sum::<f64>();
+        let (p_w, t_w, o_w) = (sum(|t| t > 0), sum(|t| t < 0), sum(|t| t == 0));
+        let consensus_trit = if p_w > t_w { 1 } else if t_w > p_w { -1 } else { 0 };
 
-        let max_agree = p.max(t).max(votes.len() - p - t);
-        let confidence = if votes.is_empty() { 0.0 } else { max_agree as f64 / votes.len() as f64 };
+        let total_w = p_w + t_w + o_w;
+        let confidence = if total_w > 0.0 { p_w.max(t_w).max(o_w) / total_w } else { 0.0 };
 
         let total_latency = start.elapsed().as_millis() as u64;
 
@@ -342,7 +569,7 @@ impl LiveConsensus {
         ctp[0] = consensus_trit;
         ctp[1] = 1; // permission
         ctp[2] = if p == votes.len() || t == votes.len() { 1 } else { 0 }; // unanimous
-        ctp[3] = if online >= 2 { 1 } else { 0 }; // quorum
+        ctp[3] = if online >= self.quorum { 1 } else { 0 }; // quorum
         ctp[4] = 1; // routing
         for (i, v) in votes.iter().take(4).enumerate() {
             ctp[5 + i] = v.trit;
@@ -354,10 +581,195 @@ impl LiveConsensus {
             timestamp: now_ms(), nodes_online: online, nodes_total: self.nodes.len(),
         };
 
+        if let Some(hub) = &self.events {
+            hub.publish(JsonObject::new().str("type", "consensus").object("result", result.to_json()));
+        }
+        self.record_reputations(&result.votes, consensus_trit);
         self.history.push(result.clone());
         result
     }
 
+    /// want[i]인 노드마다 스레드 하나로 f 실행 — deadline까지 도착한 결과만 Some
+    /// 늦은 노드는 상태를 Timeout으로 두고 스레드는 버린다 (끝나면 결과는 그냥 사라짐)
+    fn fan_out<T, F>(&mut self, want: &[bool], deadline: Instant, f: F) -> Vec<Option<T>>
+    where
+        T: Send + 'static,
+        F: Fn(&mut ConsensusNode) -> T + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let (tx, rx) = mpsc::channel();
+        let mut pending = 0;
+        for (i, node) in self.nodes.iter().enumerate().filter(|(i, _)| want[*i]) {
+            let (mut node, f, tx) = (node.clone(), Arc::clone(&f), tx.clone());
+            std::thread::spawn(move || {
+                let out = f(&mut node);
+                let _ = tx.send((i, node, out));
+            });
+            pending += 1;
+        }
+        drop(tx);
+
+        let mut results: Vec<Option<T>> = self.nodes.iter().map(|_| None).collect();
+        while pending > 0 {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok((i, node, out)) => {
+                    self.nodes[i] = node;
+                    results[i] = Some(out);
+                    pending -= 1;
+                }
+                Err(_) => break,
+            }
+        }
+        for (i, node) in self.nodes.iter_mut().enumerate() {
+            if want[i] && results[i].is_none() {
+                node.status = NodeStatus::Timeout;
+            }
+        }
+        results
+    }
+
+    /// 마감을 넘긴 노드의 표 — 폴백 없이 O (제시간에 온 표로만 합의)
+    fn late_vote(&self, node: &ConsensusNode) -> ConsensusVote {
+        ConsensusVote {
+            node_name: node.name.clone(),
+            trit: 0,
+            reason: format!("타임아웃: 마감 {}ms 안에 응답 없음", self.deadline_ms),
+            latency_ms: self.deadline_ms,
+            status: NodeStatus::Timeout,
+            raw_response: None,
+            weight: 1.0,
+        }
+    }
+
+    /// 모든 노드에 동시에 요청 → (투표, 온라인 수)
+    fn collect_votes(&mut self, query: &str) -> (Vec<ConsensusVote>, usize) {
+        let mut votes = Vec::new();
+        let mut online = 0;
+        let fallback_enabled = self.fallback_enabled;
+        let deadline = Instant::now() + Duration::from_millis(self.deadline_ms);
+        let q = query.to_string();
+        let results = self.fan_out(&vec![true; self.nodes.len()], deadline, move |node| node.send_request(&q));
+
+        for (node, result) in self.nodes.iter().zip(results) {
+            let vote = match result {
+                None => self.late_vote(node),
+                Some(Ok(response)) => {
+                    online += 1;
+                    // JSON 응답에서 trit 파싱
+                    let trit = Self::parse_trit_from_response(&response.body);
+                    let reason = Self::parse_reason_from_response(&response.body)
+                        .unwrap_or_else(|| format!("HTTP {} ({}ms)", response.status_code, response.latency_ms));
+
+                    ConsensusVote {
+                        node_name: node.name.clone(),
+                        trit,
+                        reason,
+                        latency_ms: response.latency_ms,
+                        status: NodeStatus::Online,
+                        raw_response: Some(response.body),
+                        weight: 1.0,
+                    }
+                }
+                Some(Err(err)) => Self::offline_vote(fallback_enabled, query, node, &err),
+            };
+            votes.push(vote);
+        }
+        (votes, online)
+    }
+
+    /// 커밋-공개 수집 — 모든 노드가 해시를 낸 뒤에만 공개 요청
+    fn collect_commit_reveal(&mut self, query: &str) -> (Vec<ConsensusVote>, usize) {
+        let round_no = self.history.len() as u64 + 1;
+        let names: Vec<String> = self.nodes.iter().map(|n| n.name.clone()).collect();
+        let mut round = CommitRevealRound::new(round_no, names, self.reveal_timeout_ms);
+        let mut votes: Vec<Option<ConsensusVote>> = vec![None; self.nodes.len()];
+        let mut latency = vec![0u64; self.nodes.len()];
+        let deadline = Instant::now() + Duration::from_millis(self.deadline_ms);
+
+        // 1단계: 커밋 (동시) — 연결 실패 노드는 기존처럼 폴백/오프라인 표, 늦은 노드는 O
+        let q = query.to_string();
+        let commits = self.fan_out(&vec![true; self.nodes.len()], deadline,
+            move |node| node.send_phase(&q, "commit", round_no));
+        for (i, result) in commits.into_iter().enumerate() {
+            let node = &self.nodes[i];
+            let Some(result) = result else {
+                votes[i] = Some(self.late_vote(node));
+                continue;
+            };
+            let committed = result.and_then(|resp| {
+                latency[i] = resp.latency_ms;
+                let hex = Self::parse_string_field(&resp.body, "commitment")
+                    .ok_or_else(|| format!("{} 커밋 없음: HTTP {}", node.name, resp.status_code))?;
+                round.commit(&node.name, &hex, now_ms()).map_err(|e| e.to_string())
+            });
+            if let Err(err) = committed {
+                votes[i] = Some(Self::offline_vote(self.fallback_enabled, query, node, &err));
+            }
+        }
+        round.open_reveal(now_ms());
+
+        // 2단계: 공개 (동시) — 공개 마감과 전체 마감 중 빠른 쪽까지, 못 온 노드는 미공개
+        let reveal_deadline = deadline.min(Instant::now() + Duration::from_millis(self.reveal_timeout_ms));
+        let want: Vec<bool> = votes.iter().map(Option::is_none).collect();
+        let q = query.to_string();
+        let reveals = self.fan_out(&want, reveal_deadline, move |node| node.send_phase(&q, "reveal", round_no));
+        let mut online = 0;
+        for (i, result) in reveals.into_iter().enumerate() {
+            let node = &self.nodes[i];
+            let Some(Ok(resp)) = result else { continue };
+            latency[i] += resp.latency_ms;
+            let trit = match Self::parse_string_field(&resp.body, "trit").as_deref() {
+                Some("P") => 1, Some("T") => -1, Some("O") => 0, _ => 2,
+            };
+            let Some(salt) = Self::parse_string_field(&resp.body, "salt") else { continue };
+            if round.reveal(&node.name, trit, &salt, now_ms()).is_ok() {
+                online += 1;
+                let reason = Self::parse_reason_from_response(&resp.body)
+                    .unwrap_or_else(|| format!("HTTP {} ({}ms)", resp.status_code, resp.latency_ms));
+                votes[i] = Some(ConsensusVote {
+                    node_name: node.name.clone(), trit, reason,
+                    latency_ms: latency[i], status: NodeStatus::Online, raw_response: Some(resp.body), weight: 1.0,
+                });
+            }
+        }
+
+        // 마감: 공개되지 않은 커밋은 O
+        for ((name, outcome), (i, node)) in round.finish().into_iter().zip(self.nodes.iter().enumerate()) {
+            if votes[i].is_some() { continue; }
+            votes[i] = Some(ConsensusVote {
+                node_name: name, trit: outcome.trit(), reason: outcome.to_string(),
+                latency_ms: latency[i], status: node.status.clone(), raw_response: None, weight: 1.0,
+            });
+        }
+        (votes.into_iter().flatten().collect(), online)
+    }
+
+    /// 연결 실패 노드의 표 — 폴백 시뮬레이션 또는 O
+    fn offline_vote(fallback_enabled: bool, query: &str, node: &ConsensusNode, err: &str) -> ConsensusVote {
+        if fallback_enabled {
+            let fallback_trit = Self::fallback_vote(query, &node.name);
+            ConsensusVote {
+                node_name: node.name.clone(),
+                trit: fallback_trit,
+                reason: format!("{} {} — {}", FALLBACK_PREFIX, err, Self::fallback_reason(fallback_trit)),
+                latency_ms: 0,
+                status: node.status.clone(),
+                raw_response: None,
+                weight: 1.0,
+            }
+        } else {
+            ConsensusVote {
+                node_name: node.name.clone(),
+                trit: 0,
+                reason: format!("오프라인: {}", err),
+                latency_ms: 0,
+                status: node.status.clone(),
+                raw_response: None,
+                weight: 1.0,
+            }
+        }
+    }
+
     // JSON에서 trit 값 추출
     fn parse_trit_from_response(body: &str) -> i8 {
         // {"trit":"P",...} 또는 {"trit":1,...}
@@ -373,8 +785,12 @@ impl LiveConsensus {
     }
 
     fn parse_reason_from_response(body: &str) -> Option<String> {
-        // {"reason":"..."} 추출
-        if let Some(start) = body.find("\"reason\"") {
+        Self::parse_string_field(body, "reason")
+    }
+
+    // {"key":"..."} 문자열 필드 추출
+    fn parse_string_field(body: &str, key: &str) -> Option<String> {
+        if let Some(start) = body.find(&format!("\"{}\"", key)) {
             let rest = &body[start..];
             if let Some(colon) = rest.find(':') {
                 let value_part = rest[colon + 1..].trim();
@@ -410,30 +826,55 @@ impl LiveConsensus {
         lines.push(format!("  노드: {}/{} 온라인", online, self.nodes.len()));
         for node in &self.nodes {
             let latency = node.latency_ms.map(|l| format!("{}ms", l)).unwrap_or("-".into());
-            lines.push(format!("  {} :{} — {} ({})", node.name, node.port, node.status, latency));
+            let rep = self.reputation(&node.name);
+            lines.push(format!("  {} :{} — {} ({}) 가중치 {:.2} [{}/{} 일치]",
+                node.name, node.port, node.status, latency, rep.weight(), rep.agreed, rep.rounds));
         }
         lines.push(format!("  이력: {} 합의 완료", self.history.len()));
         lines.push(format!("  폴백: {}", if self.fallback_enabled { "활성" } else { "비활성" }));
+        if self.commit_reveal {
+            lines.push(format!("  커밋-공개: 활성 (공개 마감 {}ms)", self.reveal_timeout_ms));
+        }
         lines.join("\n")
     }
 }
 
+/// [consensus] 재적재 — 같은 host:port 노드는 상태 · 이름을 유지하고 나머지는 새로 만든다
+impl crate::config::Reloadable for LiveConsensus {
+    fn apply_config(&mut self, config: &crate::config::RuntimeConfig) {
+        let mut old = std::mem::take(&mut self.nodes);
+        for addr in &config.consensus_nodes {
+            let Some((host, port)) = crate::config::parse_node_addr(addr) else { continue };
+            let node = match old.iter().position(|n| n.host == host && n.port == port) {
+                Some(i) => old.remove(i),
+                None => ConsensusNode::new(addr, host, port, "/v1/consensus"),
+            };
+            self.nodes.push(node);
+        }
+        self.quorum = config.consensus_quorum as usize;
+    }
+}
+
 // ═══════════════════════════════════════
 // 간이 HTTP 서버 (테스트용)
 // ═══════════════════════════════════════
 
 use std::net::TcpListener;
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub struct MockConsensusServer {
     pub name: String,
     pub port: u16,
     pub running: Arc<AtomicBool>,
+    /// 커밋만 하고 공개하지 않는 노드 (타임아웃 재현용)
+    pub withhold_reveal: bool,
+    /// 응답 전 지연 (느린 노드 재현용)
+    pub delay_ms: u64,
 }
 
 impl MockConsensusServer {
     pub fn new(name: &str, port: u16) -> Self {
-        Self { name: name.into(), port, running: Arc::new(AtomicBool::new(false)) }
+        Self { name: name.into(), port, running: Arc::new(AtomicBool::new(false)), withhold_reveal: false, delay_ms: 0 }
     }
 
     /// 백그라운드에서 간이 서버 시작 (테스트용)
@@ -445,6 +886,9 @@ impl MockConsensusServer {
         let running = self.running.clone();
         let name = self.name.clone();
         let port = self.port;
+        let withhold_reveal = self.withhold_reveal;
+        let delay = Duration::from_millis(self.delay_ms);
+        let secret: crypto::Key = crypto::random_bytes();
 
         std::thread::spawn(move || {
             while running.load(Ordering::SeqCst) {
@@ -453,6 +897,7 @@ impl MockConsensusServer {
                         let mut buf = [0u8; 4096];
                         let n = stream.read(&mut buf).unwrap_or(0);
                         let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                        std::thread::sleep(delay);
 
                         // 요청에서 query 추출
                         let query = Self::extract_query(&request);
@@ -464,10 +909,25 @@ impl MockConsensusServer {
                             _ => "추가 정보 필요, 보류",
                         };
 
-                        let body = format!(
-                            r#"{{"trit":"{}","node":"{}","port":{},"reason":"{}","query":"{}","timestamp":{}}}"#,
-                            trit_label, name, port, reason, query.replace('"', ""), now_ms()
-                        );
+                        // 커밋-공개: 솔트는 (비밀, 질의, 라운드)에서 유도해 두 요청 사이에 상태 없이 유지
+                        let round = Self::extract_round(&request);
+                        let sealed = SealedVote::with_salt(trit, crypto::hmac_sha256(&secret, &[query.as_bytes(), &round.to_le_bytes()]));
+                        let body = match Self::extract_field(&request, "phase").as_deref() {
+                            Some("commit") => format!(
+                                r#"{{"phase":"commit","node":"{}","round":{},"commitment":"{}"}}"#,
+                                name, round, sealed.commitment_hex(round, &name)
+                            ),
+                            // 공개 보류 노드 — 응답 없이 연결을 닫는다
+                            Some("reveal") if withhold_reveal => continue,
+                            Some("reveal") => format!(
+                                r#"{{"phase":"reveal","trit":"{}","salt":"{}","node":"{}","reason":"{}"}}"#,
+                                trit_label, sealed.salt_hex(), name, reason
+                            ),
+                            _ => format!(
+                                r#"{{"trit":"{}","node":"{}","port":{},"reason":"{}","query":"{}","timestamp":{}}}"#,
+                                trit_label, name, port, reason, query.replace('"', ""), now_ms()
+                            ),
+                        };
 
                         let response = format!(
                             "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-CTP: PPPPOOOOO\r\nX-Trit: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
@@ -488,21 +948,30 @@ impl MockConsensusServer {
     pub fn stop(&self) { self.running.store(false, Ordering::SeqCst); }
 
     fn extract_query(request: &str) -> String {
+        Self::extract_field(request, "query").unwrap_or_else(|| "unknown".into())
+    }
+
+    fn extract_round(request: &str) -> u64 {
+        let Some(start) = request.find("\"round\":") else { return 0 };
+        request[start + 8..].chars().take_while(|c| c.is_ascii_digit()).collect::<String>().parse().unwrap_or(0)
+    }
+
+    fn extract_field(request: &str, key: &str) -> Option<String> {
         if let Some(body_start) = request.find("\r\n\r\n") {
             let body = &request[body_start + 4..];
-            if let Some(q_start) = body.find("\"query\"") {
+            if let Some(q_start) = body.find(&format!("\"{}\"", key)) {
                 let rest = &body[q_start..];
                 if let Some(colon) = rest.find(':') {
                     let val = rest[colon + 1..].trim();
                     if val.starts_with('"') {
                         if let Some(end) = val[1..].find('"') {
-                            return val[1..=end].to_string();
+                            return Some(val[1..=end].to_string());
                         }
                     }
                 }
             }
         }
-        "unknown".into()
+        None
     }
 
     fn decide_trit(query: &str, node_name: &str) -> i8 {
@@ -518,15 +987,15 @@ impl MockConsensusServer {
 
 // ═══ 데모 ═══
 
-pub fn demo_live_consensus() {
-    println!("╔═══════════════════════════════════════════════╗");
-    println!("║  OpenClaw Live Consensus — 실제 HTTP 합의      ║");
-    println!("║  Claude:18789 · Gemini:18790 · Sonnet:18791   ║");
-    println!("╚═══════════════════════════════════════════════╝");
-    println!();
+pub fn demo_live_consensus(commit_reveal: bool, weighted: bool, reset_reputation: bool, deadline_ms: u64) -> i8 {
+    say!("╔═══════════════════════════════════════════════╗");
+    say!("║  OpenClaw Live Consensus — 실제 HTTP 합의      ║");
+    say!("║  Claude:18789 · Gemini:18790 · Sonnet:18791   ║");
+    say!("╚═══════════════════════════════════════════════╝");
+    say!();
 
     // 1. 간이 서버 시작
-    println!("━━━ 1. 합의 노드 시작 ━━━");
+    say!("━━━ 1. 합의 노드 시작 ━━━");
     let servers = vec![
         MockConsensusServer::new("Claude", 18789),
         MockConsensusServer::new("Gemini", 18790),
@@ -536,34 +1005,43 @@ pub fn demo_live_consensus() {
     let mut all_started = true;
     for server in &servers {
         match server.start() {
-            Ok(_) => println!("  [P] {} :{} 시작", server.name, server.port),
+            Ok(_) => say!("  [P] {} :{} 시작", server.name, server.port),
             Err(e) => {
-                println!("  [T] {} :{} — {}", server.name, server.port, e);
+                say!("  [T] {} :{} — {}", server.name, server.port, e);
                 all_started = false;
             }
         }
     }
     if !all_started {
-        println!("  ⚠ 일부 노드 시작 실패 — 폴백 모드 사용");
+        say!("  ⚠ 일부 노드 시작 실패 — 폴백 모드 사용");
     }
     // 서버 준비 대기
     std::thread::sleep(Duration::from_millis(200));
-    println!();
+    say!();
 
     // 2. 헬스 체크
-    println!("━━━ 2. 헬스 체크 ━━━");
-    let mut consensus = LiveConsensus::new();
+    say!("━━━ 2. 헬스 체크 ━━━");
+    let mut consensus = LiveConsensus::new().with_commit_reveal(commit_reveal).with_weighting(weighted)
+        .with_deadline(deadline_ms);
+    // 평판은 실행 사이에도 유지 — 저장소를 못 열면 이번 실행만 메모리에
+    match TritStore::open(REPUTATION_DIR) {
+        Ok(store) => consensus = consensus.with_reputation_store(store),
+        Err(e) => say!("  ⚠ 평판 저장소 {} — {}", REPUTATION_DIR, e),
+    }
+    if reset_reputation {
+        consensus.reset_reputations();
+    }
     let health = consensus.health_check();
     for (name, result) in &health {
         match result {
-            Ok(ms) => println!("  [P] {} — {}ms", name, ms),
-            Err(e) => println!("  [T] {} — {}", name, e),
+            Ok(ms) => say!("  [P] {} — {}ms", name, ms),
+            Err(e) => say!("  [T] {} — {}", name, e),
         }
     }
-    println!();
+    say!();
 
     // 3. 합의 실행
-    println!("━━━ 3. 합의 실행 ━━━");
+    say!("━━━ 3. 합의 실행 ━━━");
     let queries = vec![
         "CRWN 토큰 상장 적합성 평가",
         "TVM 스마트 컨트랙트 보안 감사",
@@ -573,52 +1051,72 @@ pub fn demo_live_consensus() {
     ];
 
     for query in &queries {
-        println!("  질문: \"{}\"", query);
+        say!("  질문: \"{}\"", query);
         let result = consensus.execute(query);
 
         for vote in &result.votes {
             let online = if vote.status == NodeStatus::Online { "📡" } else { "📴" };
-            println!("    {} {}", online, vote);
+            say!("    {} {}", online, vote);
         }
-        println!("  ──→ {}", result);
-        println!();
+        say!("  ──→ {}", result);
+        say!();
     }
 
     // 4. 상세 응답 확인
-    println!("━━━ 4. 원시 HTTP 응답 ━━━");
+    say!("━━━ 4. 원시 HTTP 응답 ━━━");
     if let Some(last) = consensus.history.last() {
         for vote in &last.votes {
-            println!("  [{}] {}:", if vote.status == NodeStatus::Online { "LIVE" } else { "FALLBACK" }, vote.node_name);
+            say!("  [{}] {}:", if vote.status == NodeStatus::Online { "LIVE" } else { "FALLBACK" }, vote.node_name);
             if let Some(raw) = &vote.raw_response {
                 let display: String = raw.chars().take(100).collect();
-                println!("    {}", display);
+                say!("    {}", display);
             } else {
-                println!("    (폴백 응답)");
+                say!("    (폴백 응답)");
             }
         }
     }
-    println!();
+    say!();
 
     // 5. 상태 요약
-    println!("━━━ 5. 상태 요약 ━━━");
-    println!("{}", consensus.status_summary());
-    println!();
+    say!("━━━ 5. 상태 요약 ━━━");
+    say!("{}", consensus.status_summary());
+    say!();
 
     // 6. 합의 이력
-    println!("━━━ 6. 합의 이력 ━━━");
+    say!("━━━ 6. 합의 이력 ━━━");
     for (i, result) in consensus.history.iter().enumerate() {
-        println!("  #{} [{}] \"{}\" — {:.0}% | {}ms | CTP:{}",
+        say!("  #{} [{}] \"{}\" — {:.0}% | {}ms | CTP:{}",
             i + 1, result.label(), result.query,
             result.confidence * 100.0, result.total_latency_ms, result.ctp_string());
     }
-    println!();
+    say!();
+
+    // 7. 노드 평판 (다음 실행의 가중치)
+    say!("━━━ 7. 노드 평판 ━━━");
+    let reputations = consensus.reputation_table();
+    for (name, rep) in &reputations {
+        say!("  [{}] {} — 가중치 {:.2} | 일치 {}/{} · 실패 {} | 평균 {:.0}ms",
+            crate::output::trit_symbol(rep.trit()), name, rep.weight(),
+            rep.agreed, rep.rounds, rep.failures, rep.avg_latency_ms);
+    }
+    say!();
 
     // 서버 중지
     for server in &servers { server.stop(); }
     std::thread::sleep(Duration::from_millis(100));
 
-    println!("✓ OpenClaw Live Consensus 데모 완료 — {} 합의, {} 노드",
+    say!("✓ OpenClaw Live Consensus 데모 완료 — {} 합의, {} 노드",
         consensus.history.len(), consensus.nodes.len());
+
+    if crate::output::is_json() {
+        JsonObject::new()
+            .str("command", "live")
+            .trit("state", 1)
+            .objects("results", consensus.history.iter().map(|r| r.to_json()).collect())
+            .objects("reputations", reputations.iter().map(|(name, rep)| rep.to_json().str("node", name)).collect())
+            .emit();
+    }
+    1
 }
 
 // ═══ 테스트 ═══
@@ -626,6 +1124,7 @@ pub fn demo_live_consensus() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commit_reveal::RevealOutcome;
 
     #[test]
     fn test_node_creation() {
@@ -704,13 +1203,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_commit_reveal_with_mock() {
+        let honest = MockConsensusServer::new("Honest", 19877);
+        let silent = MockConsensusServer { withhold_reveal: true, ..MockConsensusServer::new("Silent", 19878) };
+        if honest.start().is_ok() && silent.start().is_ok() {
+            std::thread::sleep(Duration::from_millis(200));
+
+            let mut consensus = LiveConsensus::with_nodes(vec![
+                ConsensusNode::new("Honest", "127.0.0.1", 19877, "/v1/consensus"),
+                ConsensusNode::new("Silent", "127.0.0.1", 19878, "/v1/consensus"),
+                ConsensusNode::new("Offline", "127.0.0.1", 59998, "/v1/consensus"),
+            ]).with_commit_reveal(true);
+            consensus.fallback_enabled = false;
+
+            let result = consensus.execute("커밋 공개 테스트");
+            // 공개한 노드는 일반 모드와 같은 표
+            assert_eq!(result.votes[0].trit, MockConsensusServer::decide_trit("커밋 공개 테스트", "Honest"));
+            assert!(result.votes[0].raw_response.as_deref().unwrap().contains("\"salt\""));
+            // 커밋만 하고 공개하지 않은 노드 → O
+            assert_eq!(result.votes[1].trit, 0);
+            assert_eq!(result.votes[1].reason, RevealOutcome::Unrevealed.to_string());
+            assert!(result.votes[2].reason.starts_with("오프라인"));
+            assert_eq!(result.nodes_online, 1);
+
+            honest.stop();
+            silent.stop();
+        }
+    }
+
     #[test]
     fn test_consensus_result_ctp() {
         let result = ConsensusResult {
             query: "test".into(),
             votes: vec![
-                ConsensusVote { node_name: "A".into(), trit: 1, reason: "ok".into(), latency_ms: 10, status: NodeStatus::Online, raw_response: None },
-                ConsensusVote { node_name: "B".into(), trit: 1, reason: "ok".into(), latency_ms: 15, status: NodeStatus::Online, raw_response: None },
+                ConsensusVote { node_name: "A".into(), trit: 1, reason: "ok".into(), latency_ms: 10, status: NodeStatus::Online, raw_response: None, weight: 1.0 },
+                ConsensusVote { node_name: "B".into(), trit: 1, reason: "ok".into(), latency_ms: 15, status: NodeStatus::Online, raw_response: None, weight: 1.0 },
             ],
             consensus_trit: 1, confidence: 1.0, total_latency_ms: 25,
             ctp_header: [1, 1, 1, 1, 1, 1, 1, 0, 0], timestamp: 0,
@@ -731,4 +1259,105 @@ mod tests {
         // 폴백이므로 응답은 있지만 raw_response는 None
         assert!(result.votes[0].raw_response.is_none());
     }
+
+    #[test]
+    fn test_votes_published_to_event_hub() {
+        let hub = EventHub::new();
+        let rx = hub.subscribe();
+        let mut consensus = LiveConsensus::with_nodes(vec![
+            ConsensusNode::new("Offline", "127.0.0.1", 59999, "/api"),
+        ]);
+        consensus.events = Some(hub);
+        consensus.execute("이벤트 테스트");
+        let frames: Vec<String> = rx.try_iter().collect();
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].contains("\"type\":\"vote\"") && frames[0].contains("\"node\":\"Offline\""));
+        assert!(frames[1].contains("\"type\":\"consensus\""));
+    }
+
+    #[test]
+    fn test_slow_node_times_out_without_blocking() {
+        let fast = MockConsensusServer::new("Fast", 19879);
+        let slow = MockConsensusServer { delay_ms: 1500, ..MockConsensusServer::new("Slow", 19880) };
+        if fast.start().is_ok() && slow.start().is_ok() {
+            std::thread::sleep(Duration::from_millis(200));
+
+            let mut consensus = LiveConsensus::with_nodes(vec![
+                ConsensusNode::new("Slow", "127.0.0.1", 19880, "/v1/consensus"),
+                ConsensusNode::new("Fast", "127.0.0.1", 19879, "/v1/consensus"),
+            ]).with_deadline(500);
+
+            let start = Instant::now();
+            let result = consensus.execute("마감 테스트");
+            assert!(start.elapsed() < Duration::from_millis(1200), "{:?}", start.elapsed());
+
+            // 늦은 노드는 Timeout · O, 합의는 제시간 표로
+            assert_eq!((result.votes[0].status.clone(), result.votes[0].trit), (NodeStatus::Timeout, 0));
+            assert_eq!(consensus.nodes[0].status, NodeStatus::Timeout);
+            assert_eq!(result.votes[1].status, NodeStatus::Online);
+            assert_eq!(result.consensus_trit, MockConsensusServer::decide_trit("마감 테스트", "Fast"));
+            assert_eq!(result.nodes_online, 1);
+
+            fast.stop();
+            slow.stop();
+        }
+    }
+
+    #[test]
+    fn test_reputation_weight_and_record() {
+        let fresh = Reputation::default();
+        assert!((fresh.weight() - 1.0).abs() < 1e-9);
+        let reliable = Reputation { rounds: 40, agreed: 40, failures: 0, avg_latency_ms: 50.0 };
+        let flaky = Reputation { rounds: 10, agreed: 3, failures: 30, avg_latency_ms: 4000.0 };
+        assert!(reliable.weight() > 3.0 && reliable.trit() == 1);
+        assert!(flaky.weight() < 0.5 && flaky.trit() == -1);
+
+        let mut rep = Reputation::default();
+        let vote = |trit, status, raw: Option<&str>| ConsensusVote {
+            node_name: "A".into(), trit, reason: String::new(), latency_ms: 100,
+            status, raw_response: raw.map(String::from), weight: 1.0,
+        };
+        rep.record(&vote(1, NodeStatus::Online, Some("{}")), 1);
+        rep.record(&vote(-1, NodeStatus::Online, Some("{}")), 1);
+        rep.record(&vote(1, NodeStatus::Timeout, None), 1);
+        assert_eq!((rep.rounds, rep.agreed, rep.failures), (2, 1, 1));
+        assert_eq!(Reputation::from_store(&rep.to_store()), Some(rep));
+    }
+
+    #[test]
+    fn test_weighted_consensus_with_persisted_reputation() {
+        // 폴백 표가 A=P, D=T로 갈리는 질의 — 가중치 없으면 동률 O
+        let query = (0..100).map(|i| format!("가중 합의 {}", i))
+            .find(|q| LiveConsensus::fallback_vote(q, "A") == 1 && LiveConsensus::fallback_vote(q, "D") == -1)
+            .unwrap();
+        let nodes = || vec![
+            ConsensusNode::new("A", "127.0.0.1", 59996, "/api"),
+            ConsensusNode::new("D", "127.0.0.1", 59997, "/api"),
+        ];
+        assert_eq!(LiveConsensus::with_nodes(nodes()).execute(&query).consensus_trit, 0);
+
+        let mut store = TritStore::new();
+        store.set("reputation.A", Reputation { rounds: 30, agreed: 30, failures: 0, avg_latency_ms: 20.0 }.to_store());
+        store.set("reputation.D", Reputation { rounds: 30, agreed: 5, failures: 20, avg_latency_ms: 3000.0 }.to_store());
+        let mut consensus = LiveConsensus::with_nodes(nodes()).with_reputation_store(store);
+        let result = consensus.execute(&query);
+        assert_eq!(result.consensus_trit, 1);
+        assert!(result.votes[0].weight > result.votes[1].weight);
+        assert_eq!(consensus.with_weighting(false).weight_of("A"), 1.0);
+
+        // 오프라인 라운드는 실패로 기록 · 저장 → 다시 열어도 유지
+        let mut consensus = LiveConsensus::with_nodes(nodes()).with_reputation_store(TritStore::new());
+        consensus.reputations.insert("A".into(), Reputation { rounds: 1, ..Default::default() });
+        consensus.execute(&query);
+        let store = consensus.reputation_store.take().unwrap();
+        let reopened = LiveConsensus::with_nodes(nodes()).with_reputation_store(store);
+        assert_eq!(reopened.reputation("A").failures, 1);
+        assert_eq!(reopened.reputation_table().len(), 2);
+
+        let mut reopened = reopened;
+        assert!(reopened.reset_reputation("A"));
+        assert!(!reopened.reputation_store.as_ref().unwrap().exists("reputation.A"));
+        reopened.reset_reputations();
+        assert!(reopened.reputations.is_empty());
+    }
 }
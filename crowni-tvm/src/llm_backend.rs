@@ -0,0 +1,355 @@
+// ═══════════════════════════════════════════════════
+// LLM 백엔드 — OpenAI 호환 · Anthropic messages · 로컬 에코
+// ═══════════════════════════════════════════════════
+//
+// CrownyLlm은 LlmModel마다 백엔드 하나를 고른다 (설정 없으면 에코).
+// 백엔드는 요청 만들기(build_request)와 응답 해석(parse_response)만 정의하고,
+// 전송은 공통 평문 HTTP/1.1 — https 엔드포인트는 앞단 프록시(로컬 게이트웨이)가 필요하다.
+//
+// 응답 → Trit:
+//   P = 정상 종료 (stop / end_turn)
+//   O = 잘림 (length / max_tokens) · 빈 응답 · 429 · 5xx · 연결 실패 (다시 시도할 만함)
+//   T = 거부 (content_filter / refusal) · 4xx · 해석 불가 · API 키 없음
+
+use std::time::Duration;
+
+use crate::car::TritState;
+use crate::integrations::http_request;
+use crate::output::{JsonObject, JsonValue};
+use crate::webserver::{LlmModel, LlmRequest, LlmResponse};
+
+pub const DEFAULT_OPENAI_MODEL: &str = "gpt-4o";
+pub const DEFAULT_ANTHROPIC_MODEL: &str = "claude-3-5-sonnet-latest";
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LlmError {
+    NoApiKey(String),
+    /// 연결 · 전송 · 시간 초과
+    Transport(String),
+    Http { status: u16, body: String },
+    BadResponse(String),
+}
+
+impl LlmError {
+    pub fn trit(&self) -> TritState {
+        match self {
+            LlmError::Transport(_) => TritState::Pending,
+            LlmError::Http { status, .. } if *status == 429 || *status >= 500 => TritState::Pending,
+            _ => TritState::Failed,
+        }
+    }
+}
+
+impl std::fmt::Display for LlmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LlmError::NoApiKey(b) => write!(f, "{}: API 키 없음", b),
+            LlmError::Transport(e) => write!(f, "전송 실패: {}", e),
+            LlmError::Http { status, body } => write!(f, "HTTP {}: {:.200}", status, body),
+            LlmError::BadResponse(e) => write!(f, "응답 해석 실패: {}", e),
+        }
+    }
+}
+
+/// 보낼 HTTP 요청 — 전송 전에 검사할 수 있게 값으로 만든다
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpCall {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+pub trait LlmBackend {
+    fn name(&self) -> &str;
+
+    /// 나중에 받은 API 키 (CrownyLlm::set_api_key)
+    fn set_api_key(&mut self, _key: &str) {}
+
+    fn build_request(&self, req: &LlmRequest) -> Result<HttpCall, LlmError>;
+
+    fn parse_response(&self, status: u16, body: &str) -> Result<LlmResponse, LlmError>;
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// 요청 → 전송 → 해석
+    fn complete(&mut self, req: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        let call = self.build_request(req)?;
+        let (status, body) = http_post(&call, self.timeout())?;
+        self.parse_response(status, &body)
+    }
+}
+
+// ── 전송 ──
+
+/// 평문 HTTP/1.1 POST → (상태, 본문)
+pub fn http_post(call: &HttpCall, timeout: Duration) -> Result<(u16, String), LlmError> {
+    let reply = http_request("POST", &call.url, &call.headers, &call.body, timeout).map_err(LlmError::Transport)?;
+    Ok((reply.status, reply.body))
+}
+
+fn parse_json(status: u16, body: &str) -> Result<JsonValue, LlmError> {
+    if !(200..300).contains(&status) {
+        return Err(LlmError::Http { status, body: body.to_string() });
+    }
+    JsonValue::parse(body).map_err(LlmError::BadResponse)
+}
+
+fn trit_of(text: &str, state: TritState) -> TritState {
+    if text.trim().is_empty() && state == TritState::Success { TritState::Pending } else { state }
+}
+
+// ── OpenAI 호환 (/v1/chat/completions) ──
+
+/// OpenAI · Gemini 호환 엔드포인트 · Ollama · vLLM 등
+pub struct OpenAiBackend {
+    pub base_url: String,
+    pub model: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiBackend {
+    pub fn new(base_url: &str, model: &str) -> Self {
+        Self { base_url: base_url.trim_end_matches('/').to_string(), model: model.to_string(), api_key: None }
+    }
+}
+
+impl LlmBackend for OpenAiBackend {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn set_api_key(&mut self, key: &str) {
+        self.api_key = Some(key.to_string());
+    }
+
+    fn build_request(&self, req: &LlmRequest) -> Result<HttpCall, LlmError> {
+        let mut messages = Vec::new();
+        if let Some(sys) = &req.system {
+            messages.push(JsonObject::new().str("role", "system").str("content", sys));
+        }
+        messages.push(JsonObject::new().str("role", "user").str("content", &req.prompt));
+        let body = JsonObject::new()
+            .str("model", &self.model)
+            .objects("messages", messages)
+            .float("temperature", req.temperature as f64)
+            .int("max_tokens", req.max_tokens as i64)
+            .build();
+        // 로컬 서버는 키 없이도 받는다
+        let headers = self.api_key.iter().map(|k| ("Authorization".to_string(), format!("Bearer {}", k))).collect();
+        Ok(HttpCall { url: format!("{}/v1/chat/completions", self.base_url), headers, body })
+    }
+
+    fn parse_response(&self, status: u16, body: &str) -> Result<LlmResponse, LlmError> {
+        let json = parse_json(status, body)?;
+        let choice = json.get("choices").and_then(|c| c.as_array()).and_then(|c| c.first())
+            .ok_or_else(|| LlmError::BadResponse("choices 없음".into()))?;
+        let text = choice.get("message").and_then(|m| m.get("content")).and_then(|c| c.as_str()).unwrap_or("").to_string();
+        let state = match choice.get("finish_reason").and_then(|r| r.as_str()) {
+            Some("stop") | Some("tool_calls") | None => TritState::Success,
+            Some("length") => TritState::Pending,
+            Some(_) => TritState::Failed, // content_filter 등
+        };
+        let tokens = json.get("usage").and_then(|u| u.get("total_tokens")).and_then(|t| t.as_i64()).unwrap_or(0);
+        Ok(LlmResponse {
+            trit_state: trit_of(&text, state),
+            text,
+            model: LlmModel::Custom(self.model.clone()),
+            tokens_used: tokens.max(0) as u32,
+        })
+    }
+}
+
+// ── Anthropic (/v1/messages) ──
+
+pub struct AnthropicBackend {
+    pub base_url: String,
+    pub model: String,
+    api_key: Option<String>,
+}
+
+impl AnthropicBackend {
+    pub fn new(base_url: &str, model: &str) -> Self {
+        Self { base_url: base_url.trim_end_matches('/').to_string(), model: model.to_string(), api_key: None }
+    }
+}
+
+impl LlmBackend for AnthropicBackend {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn set_api_key(&mut self, key: &str) {
+        self.api_key = Some(key.to_string());
+    }
+
+    fn build_request(&self, req: &LlmRequest) -> Result<HttpCall, LlmError> {
+        let key = self.api_key.as_ref().ok_or_else(|| LlmError::NoApiKey(self.name().to_string()))?;
+        let mut body = JsonObject::new()
+            .str("model", &self.model)
+            .int("max_tokens", req.max_tokens as i64);
+        if let Some(sys) = &req.system {
+            body = body.str("system", sys);
+        }
+        let body = body
+            .objects("messages", vec![JsonObject::new().str("role", "user").str("content", &req.prompt)])
+            .float("temperature", req.temperature as f64)
+            .build();
+        Ok(HttpCall {
+            url: format!("{}/v1/messages", self.base_url),
+            headers: vec![
+                ("x-api-key".to_string(), key.clone()),
+                ("anthropic-version".to_string(), ANTHROPIC_VERSION.to_string()),
+            ],
+            body,
+        })
+    }
+
+    fn parse_response(&self, status: u16, body: &str) -> Result<LlmResponse, LlmError> {
+        let json = parse_json(status, body)?;
+        let blocks = json.get("content").and_then(|c| c.as_array())
+            .ok_or_else(|| LlmError::BadResponse("content 없음".into()))?;
+        let text: String = blocks.iter()
+            .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect();
+        let state = match json.get("stop_reason").and_then(|r| r.as_str()) {
+            Some("end_turn") | Some("stop_sequence") | Some("tool_use") | None => TritState::Success,
+            Some("max_tokens") => TritState::Pending,
+            Some(_) => TritState::Failed, // refusal 등
+        };
+        let usage = |k: &str| json.get("usage").and_then(|u| u.get(k)).and_then(|t| t.as_i64()).unwrap_or(0);
+        Ok(LlmResponse {
+            trit_state: trit_of(&text, state),
+            text,
+            model: LlmModel::Custom(self.model.clone()),
+            tokens_used: (usage("input_tokens") + usage("output_tokens")).max(0) as u32,
+        })
+    }
+}
+
+// ── 로컬 에코 (네트워크 없음) ──
+
+/// 기본 백엔드 — 입력을 돌려주는 시뮬레이션 (데모 · 테스트)
+pub struct EchoBackend {
+    pub label: String,
+}
+
+impl EchoBackend {
+    pub fn new(label: &str) -> Self {
+        Self { label: label.to_string() }
+    }
+}
+
+impl LlmBackend for EchoBackend {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn build_request(&self, req: &LlmRequest) -> Result<HttpCall, LlmError> {
+        Ok(HttpCall { url: "echo://local".into(), headers: Vec::new(), body: req.prompt.clone() })
+    }
+
+    fn parse_response(&self, _status: u16, body: &str) -> Result<LlmResponse, LlmError> {
+        Ok(LlmResponse {
+            text: format!("[{} 응답] 입력 '{}' 에 대한 균형3진 기반 분석 결과입니다.", self.label, body),
+            model: LlmModel::Custom(self.label.clone()),
+            tokens_used: (body.len() as u32 / 2) + 50, // 대략적 토큰 수
+            trit_state: TritState::Success,
+        })
+    }
+
+    fn complete(&mut self, req: &LlmRequest) -> Result<LlmResponse, LlmError> {
+        self.parse_response(200, &req.prompt)
+    }
+}
+
+// ── 설정 ──
+
+/// [llm] 설정 → (연결할 모델, 백엔드) — echo면 None (모든 모델 시뮬레이션)
+pub fn from_config(kind: &str, url: &str, model: &str) -> Option<(LlmModel, Box<dyn LlmBackend>)> {
+    let model_or = |default: &'static str| if model.is_empty() { default } else { model };
+    match kind {
+        "openai" => Some((LlmModel::Gpt4, Box::new(OpenAiBackend::new(url, model_or(DEFAULT_OPENAI_MODEL))))),
+        "anthropic" => Some((LlmModel::Claude, Box::new(AnthropicBackend::new(url, model_or(DEFAULT_ANTHROPIC_MODEL))))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// 한 번 응답하는 HTTP 서버 — 받은 요청 원문을 돌려준다
+    fn serve_once(response: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = vec![0u8; 8192];
+            let mut got = Vec::new();
+            // 헤더 + Content-Length만큼 읽기
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                got.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&got).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let len: usize = head.lines()
+                        .find_map(|l| l.strip_prefix("Content-Length: "))
+                        .and_then(|v| v.parse().ok()).unwrap_or(0);
+                    if body.len() >= len || n == 0 {
+                        break;
+                    }
+                }
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&got).to_string()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_openai_request_and_trit_mapping() {
+        let (url, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n\
+            {\"choices\":[{\"message\":{\"role\":\"assistant\",\"content\":\"P 승인\"},\"finish_reason\":\"stop\"}],\"usage\":{\"total_tokens\":42}}");
+        let mut backend = OpenAiBackend::new(&url, "llama3");
+        backend.set_api_key("sk-test");
+        let req = LlmRequest::new(LlmModel::Local, "3진?").with_system("짧게");
+        let resp = backend.complete(&req).unwrap();
+        assert_eq!((resp.text.as_str(), resp.tokens_used, resp.trit_state), ("P 승인", 42, TritState::Success));
+
+        let sent = server.join().unwrap();
+        assert!(sent.starts_with("POST /v1/chat/completions HTTP/1.1"));
+        assert!(sent.contains("Authorization: Bearer sk-test"));
+        assert!(sent.contains("\"role\":\"system\"") && sent.contains("\"model\":\"llama3\""));
+
+        // 잘림 → O, 거부 → T, 과부하 → O
+        let parse = |body: &str| backend.parse_response(200, body).unwrap().trit_state;
+        assert_eq!(parse(r#"{"choices":[{"message":{"content":"반쯤"},"finish_reason":"length"}]}"#), TritState::Pending);
+        assert_eq!(parse(r#"{"choices":[{"message":{"content":""},"finish_reason":"content_filter"}]}"#), TritState::Failed);
+        assert_eq!(backend.parse_response(503, "busy").unwrap_err().trit(), TritState::Pending);
+        assert_eq!(backend.parse_response(401, "no").unwrap_err().trit(), TritState::Failed);
+    }
+
+    #[test]
+    fn test_anthropic_request_requires_key() {
+        let mut backend = AnthropicBackend::new("http://127.0.0.1:1", DEFAULT_ANTHROPIC_MODEL);
+        let req = LlmRequest::new(LlmModel::Claude, "안녕").with_system("3진으로");
+        assert_eq!(backend.build_request(&req), Err(LlmError::NoApiKey("anthropic".into())));
+
+        backend.set_api_key("ak-test");
+        let call = backend.build_request(&req).unwrap();
+        assert!(call.url.ends_with("/v1/messages"));
+        assert!(call.headers.contains(&("anthropic-version".into(), ANTHROPIC_VERSION.into())));
+        assert!(call.body.contains("\"system\":\"3진으로\"") && call.body.contains("\"max_tokens\":1024"));
+
+        let resp = backend.parse_response(200, r#"{"content":[{"type":"text","text":"좋아요"}],"stop_reason":"max_tokens","usage":{"input_tokens":5,"output_tokens":7}}"#).unwrap();
+        assert_eq!((resp.text.as_str(), resp.tokens_used, resp.trit_state), ("좋아요", 12, TritState::Pending));
+        // 연결 실패는 O (다시 시도)
+        assert_eq!(backend.complete(&req).unwrap_err().trit(), TritState::Pending);
+    }
+}
@@ -8,6 +8,9 @@
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH, Instant};
+use crate::commit_reveal::{self, CommitRevealRound, SealedVote};
+use crate::output::{JsonObject, say};
+use crate::ring_log::RingLog;
 
 // ── AI 모델 엔드포인트 ──
 
@@ -142,6 +145,21 @@ impl AIResponse {
             _ => "보류",
         }
     }
+
+    pub fn to_json(&self) -> JsonObject {
+        let obj = JsonObject::new()
+            .str("endpoint", &self.endpoint_name)
+            .str("model", &self.model_type.to_string())
+            .trit("state", self.trit)
+            .float("confidence", self.confidence)
+            .int("latency_ms", self.latency_ms as i64)
+            .bool("success", self.success)
+            .str("text", &self.text);
+        match &self.error {
+            Some(e) => obj.str("error", e),
+            None => obj,
+        }
+    }
 }
 
 impl std::fmt::Display for AIResponse {
@@ -170,9 +188,20 @@ pub struct ConsensusResult {
     pub ctp_header: [i8; 9],
     pub total_latency_ms: u32,
     pub timestamp: u64,
+    pub strategy: VotingStrategy,
+    /// 1노드 1표 다수결 결과 — 가중 전략과 비교용
+    pub majority_trit: i8,
+    /// Σ(trit·가중치)/Σ가중치 (-1.0 ~ 1.0)
+    pub weighted_score: f64,
 }
 
 impl ConsensusResult {
+    /// 원시 집계 (P, O, T)
+    pub fn tally(&self) -> (usize, usize, usize) {
+        let count = |t: i8| self.responses.iter().filter(|r| r.trit == t).count();
+        (count(1), count(0), count(-1))
+    }
+
     pub fn trit_label(&self) -> &str {
         match self.final_trit {
             1 => "P(성공)",
@@ -188,6 +217,23 @@ impl ConsensusResult {
             _ => 'O',
         }).collect()
     }
+
+    /// 안정 필드 순서 JSON (schema: crowny.consensus_result)
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.consensus_result")
+            .trit("state", self.final_trit)
+            .int("request_id", self.request_id as i64)
+            .str("prompt", &self.prompt)
+            .float("confidence", self.confidence)
+            .bool("unanimous", self.unanimous)
+            .str("ctp", &self.ctp_string())
+            .int("total_latency_ms", self.total_latency_ms as i64)
+            .int("timestamp", self.timestamp as i64)
+            .str("strategy", self.strategy.code())
+            .trit("majority_state", self.majority_trit)
+            .float("weighted_score", self.weighted_score)
+            .objects("responses", self.responses.iter().map(|r| r.to_json()).collect())
+    }
 }
 
 impl std::fmt::Display for ConsensusResult {
@@ -256,6 +302,86 @@ pub fn trit_consensus(votes: &[i8]) -> (i8, f64) {
     (consensus, confidence)
 }
 
+// ── 투표 전략 ──
+
+/// O 데드밴드 기본값 — 가중 점수 |s| ≤ 0.2면 보류
+pub const DEFAULT_DEADBAND: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VotingStrategy {
+    /// 1노드 1표 다수결
+    #[default]
+    Majority,
+    /// 신뢰도 가중합 — |점수| ≤ deadband면 O
+    ConfidenceWeighted { deadband: f64 },
+    /// 이차 투표 — 신뢰도를 크레딧으로 보고 √신뢰도만큼 가중
+    Quadratic { deadband: f64 },
+}
+
+impl VotingStrategy {
+    /// "majority" | "weighted" | "quadratic" (한글: 다수결 · 가중 · 이차)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "majority" | "다수결" => Some(Self::Majority),
+            "weighted" | "가중" => Some(Self::ConfidenceWeighted { deadband: DEFAULT_DEADBAND }),
+            "quadratic" | "이차" => Some(Self::Quadratic { deadband: DEFAULT_DEADBAND }),
+            _ => None,
+        }
+    }
+
+    /// 기계용 코드
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Majority => "majority",
+            Self::ConfidenceWeighted { .. } => "weighted",
+            Self::Quadratic { .. } => "quadratic",
+        }
+    }
+
+    pub fn weight(&self, confidence: f64) -> f64 {
+        let c = confidence.clamp(0.0, 1.0);
+        match self {
+            Self::Majority => 1.0,
+            Self::ConfidenceWeighted { .. } => c,
+            Self::Quadratic { .. } => c.sqrt(),
+        }
+    }
+
+    /// (trit, 신뢰도) 투표 → (합의, 합의 신뢰도, 가중 점수)
+    pub fn decide(&self, votes: &[(i8, f64)]) -> (i8, f64, f64) {
+        let weights: Vec<f64> = votes.iter().map(|&(_, c)| self.weight(c)).collect();
+        let total: f64 = weights.iter().sum();
+        let score = if total > 0.0 {
+            votes.iter().zip(&weights).map(|(&(t, _), w)| t as f64 * w).sum::<f64>() / total
+        } else {
+            0.0
+        };
+        let trit = match self {
+            Self::Majority => {
+                let (trit, confidence) = trit_consensus(&votes.iter().map(|&(t, _)| t).collect::<Vec<_>>());
+                return (trit, confidence, score);
+            }
+            Self::ConfidenceWeighted { deadband } | Self::Quadratic { deadband } => {
+                if score > *deadband { 1 } else if score < -*deadband { -1 } else { 0 }
+            }
+        };
+        // 합의와 같은 표의 가중치 비율
+        let agree: f64 = votes.iter().zip(&weights).filter(|(&(t, _), _)| t == trit).map(|(_, w)| w).sum();
+        let confidence = if total > 0.0 { agree / total } else { 0.0 };
+        (trit, confidence, score)
+    }
+}
+
+impl std::fmt::Display for VotingStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Majority => write!(f, "다수결"),
+            Self::ConfidenceWeighted { deadband } => write!(f, "신뢰도 가중 (O 데드밴드 ±{})", deadband),
+            Self::Quadratic { deadband } => write!(f, "이차 투표 (O 데드밴드 ±{})", deadband),
+        }
+    }
+}
+
 // ── CTP 헤더 생성 ──
 
 pub fn build_ctp_header(consensus: i8, responses: &[AIResponse]) -> [i8; 9] {
@@ -292,20 +418,26 @@ pub fn build_ctp_header(consensus: i8, responses: &[AIResponse]) -> [i8; 9] {
 
 pub struct LocalConsensusEngine {
     pub endpoints: Vec<AIEndpoint>,
-    pub results: Vec<ConsensusResult>,
+    /// 합의 결과 기록 (상한 — 오래된 것부터 축출)
+    pub results: RingLog<ConsensusResult>,
     pub request_counter: u64,
     pub total_consensus_calls: u64,
     pub agreement_rate: f64,
+    /// 커밋-공개 투표 — 모든 해시가 모인 뒤 공개, Busy 엔드포인트는 공개 마감을 놓친다
+    pub commit_reveal: bool,
+    pub strategy: VotingStrategy,
 }
 
 impl LocalConsensusEngine {
     pub fn new() -> Self {
         Self {
             endpoints: Vec::new(),
-            results: Vec::new(),
+            results: RingLog::default(),
             request_counter: 0,
             total_consensus_calls: 0,
             agreement_rate: 0.0,
+            commit_reveal: false,
+            strategy: VotingStrategy::Majority,
         }
     }
 
@@ -360,8 +492,14 @@ impl LocalConsensusEngine {
             });
         }
 
+        if self.commit_reveal {
+            self.seal_and_reveal(req.id, &mut responses);
+        }
+
         let votes: Vec<i8> = responses.iter().map(|r| r.trit).collect();
-        let (final_trit, consensus_confidence) = trit_consensus(&votes);
+        let (majority_trit, _) = trit_consensus(&votes);
+        let weighted: Vec<(i8, f64)> = responses.iter().map(|r| (r.trit, r.confidence)).collect();
+        let (final_trit, consensus_confidence, weighted_score) = self.strategy.decide(&weighted);
         let unanimous = votes.iter().all(|&v| v == final_trit);
         let ctp_header = build_ctp_header(final_trit, &responses);
         let total_latency = start.elapsed().as_millis() as u32;
@@ -385,12 +523,39 @@ impl LocalConsensusEngine {
             ctp_header,
             total_latency_ms: total_latency,
             timestamp: now_ms(),
+            strategy: self.strategy,
+            majority_trit,
+            weighted_score,
         };
 
         self.results.push(result.clone());
         result
     }
 
+    /// 커밋-공개 라운드 — 응답을 봉인해 커밋하고, 모두 커밋된 뒤 공개
+    /// Busy 엔드포인트는 커밋만 하고 마감까지 공개하지 못해 O로 집계된다
+    fn seal_and_reveal(&self, round_no: u64, responses: &mut [AIResponse]) {
+        let names: Vec<String> = responses.iter().map(|r| r.endpoint_name.clone()).collect();
+        let mut round = CommitRevealRound::new(round_no, names, commit_reveal::DEFAULT_REVEAL_TIMEOUT_MS);
+        let sealed: Vec<SealedVote> = responses.iter().map(|r| SealedVote::new(r.trit)).collect();
+        let now = now_ms();
+        for (r, s) in responses.iter().zip(&sealed) {
+            let _ = round.commit(&r.endpoint_name, &s.commitment_hex(round_no, &r.endpoint_name), now);
+        }
+        for ((r, s), ep) in responses.iter().zip(&sealed).zip(&self.endpoints) {
+            if ep.status != EndpointStatus::Busy {
+                let _ = round.reveal(&r.endpoint_name, s.trit, &s.salt_hex(), now);
+            }
+        }
+        for (r, (_, outcome)) in responses.iter_mut().zip(round.finish()) {
+            r.trit = outcome.trit();
+            if !matches!(outcome, commit_reveal::RevealOutcome::Revealed(_)) {
+                r.success = false;
+                r.error = Some(outcome.to_string());
+            }
+        }
+    }
+
     /// HTTP 요청 스펙 생성 (실제 연결용)
     pub fn generate_http_spec(&self, prompt: &str) -> Vec<String> {
         let mut specs = Vec::new();
@@ -502,20 +667,26 @@ fn now_ms() -> u64 {
 
 // ═══ 데모 ═══
 
-pub fn demo_local_consensus() {
-    println!("╔═══════════════════════════════════════════╗");
-    println!("║  Crowny Local Consensus Engine            ║");
-    println!("║  실제 로컬 3진 합의 — OpenClaw 듀얼 브레인  ║");
-    println!("╚═══════════════════════════════════════════╝");
-    println!();
+pub fn demo_local_consensus(commit_reveal: bool, strategy: VotingStrategy) -> i8 {
+    say!("╔═══════════════════════════════════════════╗");
+    say!("║  Crowny Local Consensus Engine            ║");
+    say!("║  실제 로컬 3진 합의 — OpenClaw 듀얼 브레인  ║");
+    say!("╚═══════════════════════════════════════════╝");
+    say!();
 
     // 1. 엔드포인트 설정
-    println!("━━━ 1. OpenClaw 엔드포인트 ━━━");
+    say!("━━━ 1. OpenClaw 엔드포인트 ━━━");
     let mut engine = LocalConsensusEngine::openclaw_default();
+    engine.commit_reveal = commit_reveal;
+    engine.strategy = strategy;
     for ep in &engine.endpoints {
-        println!("  {} {} ({}) — {}", "●", ep.name, ep.url(), ep.model_type);
+        say!("  {} {} ({}) — {}", "●", ep.name, ep.url(), ep.model_type);
     }
-    println!();
+    if commit_reveal {
+        say!("  🔒 커밋-공개 모드 — 해시를 모두 모은 뒤 공개, 미공개는 O");
+    }
+    say!("  투표 전략: {}", strategy);
+    say!();
 
     // 2. 다양한 시나리오 합의
     let scenarios = vec![
@@ -526,46 +697,60 @@ pub fn demo_local_consensus() {
         ("3진법이 2진법보다 효율적인가?", "기술"),
     ];
 
-    println!("━━━ 2. 3진 합의 시나리오 ━━━");
+    say!("━━━ 2. 3진 합의 시나리오 ━━━");
     for (prompt, category) in &scenarios {
-        println!("  📋 [{}] \"{}\"", category, prompt);
+        say!("  📋 [{}] \"{}\"", category, prompt);
         let result = engine.simulate_consensus(prompt);
 
         for resp in &result.responses {
-            println!("    {}", resp);
+            say!("    {}", resp);
+        }
+        say!("    ──────────────────────────");
+        if strategy != VotingStrategy::Majority {
+            let (p, o, t) = result.tally();
+            let majority = match result.majority_trit { 1 => "P", -1 => "T", _ => "O" };
+            say!("    ⚖ 원시 집계 P{}·O{}·T{} → {} | 가중 점수 {:+.2}", p, o, t, majority, result.weighted_score);
         }
-        println!("    ──────────────────────────");
-        println!("    🏛 {}", result);
-        println!();
+        say!("    🏛 {}", result);
+        say!();
     }
 
     // 3. HTTP 스펙 (실제 연결용)
-    println!("━━━ 3. 실제 HTTP 연결 스펙 ━━━");
+    say!("━━━ 3. 실제 HTTP 연결 스펙 ━━━");
     let specs = engine.generate_http_spec("이 프로젝트를 진행해야 할까?");
     for (i, spec) in specs.iter().enumerate() {
-        println!("  [{}/{}] {}", i + 1, specs.len(), &spec[..spec.find('\n').unwrap_or(spec.len())]);
+        say!("  [{}/{}] {}", i + 1, specs.len(), &spec[..spec.find('\n').unwrap_or(spec.len())]);
     }
-    println!("  (전체 curl 명령은 --verbose 옵션으로 확인 가능)");
-    println!();
+    say!("  (전체 curl 명령은 --verbose 옵션으로 확인 가능)");
+    say!();
 
     // 4. 통계
-    println!("━━━ 4. 엔진 통계 ━━━");
-    println!("{}", engine.summary());
-    println!();
+    say!("━━━ 4. 엔진 통계 ━━━");
+    say!("{}", engine.summary());
+    say!();
 
     // 5. 합의 이력
-    println!("━━━ 5. 합의 이력 ━━━");
-    for result in &engine.results {
+    say!("━━━ 5. 합의 이력 ━━━");
+    for result in engine.results.iter() {
         let trit = match result.final_trit { 1 => "P", -1 => "T", _ => "O" };
         let ctp = result.ctp_string();
         let prompt_short = truncate(&result.prompt, 25);
-        println!("  #{} [{}] {} — CTP:{} | {:.0}% | {}ms",
+        say!("  #{} [{}] {} — CTP:{} | {:.0}% | {}ms",
             result.request_id, trit, prompt_short, ctp, result.confidence * 100.0, result.total_latency_ms);
     }
-    println!();
+    say!();
 
-    println!("✓ 로컬 합의 데모 완료 — {} 시나리오, {} 엔드포인트",
+    say!("✓ 로컬 합의 데모 완료 — {} 시나리오, {} 엔드포인트",
         engine.results.len(), engine.endpoints.len());
+
+    if crate::output::is_json() {
+        JsonObject::new()
+            .str("command", "consensus")
+            .trit("state", 1)
+            .objects("results", engine.results.iter().map(|r| r.to_json()).collect())
+            .emit();
+    }
+    1
 }
 
 // ═══ 테스트 ═══
@@ -662,6 +847,57 @@ mod tests {
         assert!(specs[2].contains("18791"));
     }
 
+    #[test]
+    fn test_commit_reveal_busy_endpoint_counts_as_o() {
+        let mut plain = LocalConsensusEngine::openclaw_default();
+        let mut sealed = LocalConsensusEngine::openclaw_default();
+        sealed.commit_reveal = true;
+        let a = plain.simulate_consensus("이 스타트업에 투자해야 할까?");
+        let b = sealed.simulate_consensus("이 스타트업에 투자해야 할까?");
+        // 모두 공개하면 결과는 일반 모드와 같다
+        assert_eq!(a.responses.iter().map(|r| r.trit).collect::<Vec<_>>(), b.responses.iter().map(|r| r.trit).collect::<Vec<_>>());
+
+        // Gemini(P)가 마감까지 공개하지 못하면 O
+        sealed.endpoints[1].status = EndpointStatus::Busy;
+        let c = sealed.simulate_consensus("이 스타트업에 투자해야 할까?");
+        assert_eq!(c.responses[1].trit, 0);
+        assert!(!c.responses[1].success);
+        assert!(c.responses[1].error.as_deref().unwrap().contains("미공개"));
+        assert_eq!(c.final_trit, 0); // P · O · O → 보류
+    }
+
+    #[test]
+    fn test_weighted_voting_deadband() {
+        // 확신 낮은 P 둘 vs 확신 높은 T 하나 — 다수결은 P, 가중합은 보류
+        let votes = [(1, 0.3), (1, 0.3), (-1, 0.95)];
+        assert_eq!(VotingStrategy::Majority.decide(&votes).0, 1);
+        let weighted = VotingStrategy::ConfidenceWeighted { deadband: DEFAULT_DEADBAND };
+        let (trit, _, score) = weighted.decide(&votes);
+        assert!((score - (0.6 - 0.95) / 1.55).abs() < 1e-9);
+        assert_eq!(trit, -1);
+        let (trit, _, score) = VotingStrategy::ConfidenceWeighted { deadband: 0.3 }.decide(&votes);
+        assert!(score.abs() <= 0.3);
+        assert_eq!(trit, 0);
+        // 이차 투표는 √신뢰도 — 확신의 차이를 줄여 P로 기운다
+        let (trit, _, score) = VotingStrategy::Quadratic { deadband: 0.0 }.decide(&votes);
+        assert!(score > 0.0);
+        assert_eq!(trit, 1);
+        assert_eq!(VotingStrategy::parse("가중"), Some(weighted));
+        assert_eq!(VotingStrategy::parse("borda"), None);
+    }
+
+    #[test]
+    fn test_result_keeps_raw_tally_and_weighted_outcome() {
+        let mut engine = LocalConsensusEngine::openclaw_default();
+        engine.strategy = VotingStrategy::ConfidenceWeighted { deadband: DEFAULT_DEADBAND };
+        let result = engine.simulate_consensus("이 스타트업에 투자해야 할까?");
+        assert_eq!(result.tally(), (2, 1, 0));
+        assert_eq!(result.majority_trit, 1);
+        assert!(result.weighted_score > DEFAULT_DEADBAND);
+        let json = result.to_json().build();
+        assert!(json.contains(r#""strategy":"weighted","majority_state":"P","weighted_score":"#));
+    }
+
     #[test]
     fn test_engine_stats() {
         let mut engine = LocalConsensusEngine::openclaw_default();
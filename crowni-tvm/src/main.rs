@@ -11,33 +11,69 @@
 ///!   crowni-tvm info               → 명령어 목록
 ///!   crowni-tvm trit <decimal>     → 10진→균형3진 변환
 ///!   crowni-tvm decode <TOOPPT>    → 6트릿→opcode 디코딩
+///!   crowni-tvm chain block get 3  → 샘플 체인 블록 조회
+///!   crowni-tvm completions bash   → 셸 자동완성 스크립트
+///!   crowni-tvm notebook <doc.md>  → Markdown 속 한선어 셀 실행
+///!   crowni-tvm example [이름]      → 내장 예제 목록/실행
+///!   crowni-tvm migrate --dry-run  → 파일 형식 마이그레이션 미리보기
+///!   crowni-tvm help <명령...>      → 명령별 도움말
+///!   --json / --quiet              → JSON 출력 / 배너 생략 (종료 코드: P=0 T=1 O=2)
+///!                                   run/compile/test/trit/decode/info: {command,state,elapsed_ms,errors,data}
+///!   --lang en                     → 영어 메시지 (CROWNY_LANG / crowny.toml [i18n] locale)
 
 mod trit;
 mod value;
 mod heap;
 mod opcode;
 mod vm;
+mod program_limits;
 mod assembler;
 mod scheduler;
 mod permission;
 mod transaction;
 mod kernel;
+mod ipc;
+mod capability;
+mod watchdog;
+mod config;
 mod network;
+mod crypto;
+mod secure_ctp;
+mod ctp_compress;
+mod integrations;
+mod cron;
+mod billing;
+mod event_log;
+mod rpc;
+mod content;
+mod royalty;
+mod airdrop;
+mod fractional;
+mod account;
 mod bridge;
 mod ir;
 mod wasm_gen;
 mod compiler;
 mod car;
 mod bytecode;
+mod artifacts;
+mod attest;
+mod portfolio;
+mod margin;
+mod query;
+mod ring_log;
+mod conformance;
 mod sectors;
 mod hanseon;
 mod webserver;
+mod llm_backend;
 mod cpm;
 mod trit_test;
 mod debugger;
 mod trit_store;
 mod trit_log;
 mod node;
+mod gossip;
 mod token;
 mod wasm_node;
 mod local_consensus;
@@ -47,11 +83,29 @@ mod browser;
 mod website;
 mod os;
 mod chain;
+mod mempool;
+mod params;
+mod consortium;
 mod live_consensus;
+mod commit_reveal;
+mod admin_override;
+mod soak;
 mod dex;
 mod crossbridge;
 mod nft;
 mod contract_vm;
+mod output;
+mod i18n;
+mod notebook;
+mod repl;
+mod bench;
+mod identity;
+mod provenance;
+mod examples;
+mod migrations;
+mod cli;
+mod websocket;
+mod line_editor;
 
 use std::env;
 use std::fs;
@@ -59,11 +113,13 @@ use std::io::{self, Write};
 
 use trit::Word6;
 use vm::TVM;
-use opcode::{SECTOR_NAMES, GROUP_NAMES_CORE};
 use assembler::assemble;
 use kernel::{CrownyKernel, KernelConfig};
 use scheduler::{TritPriority, TritResult};
 use permission::{TritPermission, Action};
+use output::{JsonObject, say};
+use i18n::tr;
+use cli::{Command, Flag};
 
 const BANNER: &str = r#"
 ╔═══════════════════════════════════════════════════════╗
@@ -80,97 +136,401 @@ const BANNER: &str = r#"
 ╚═══════════════════════════════════════════════════════╝
 "#;
 
+/// 명령 트리 — 도움말·자동완성·파싱이 모두 여기서 나온다
+fn cli_spec() -> Command {
+    Command::new(cli::BIN, "CROWNIN TVM v0.4.0 — 균형3진 Meta-Kernel + 생태계 (인자 없이 실행하면 REPL)")
+        .en("CROWNIN TVM v0.4.0 — balanced ternary Meta-Kernel + ecosystem (REPL when run without arguments)")
+        .flag(Flag::switch("json", "구조화된 JSON 출력 (run/compile/bytecode/attest/trit/decode/info/test/store/chain/config/consensus/industry/dex/live)").en("Structured JSON output (run/compile/bytecode/attest/trit/decode/info/test/store/chain/config/consensus/industry/dex/live)").global())
+        .flag(Flag::switch("quiet", "배너·데모 아트 생략").en("Skip banners and demo art").short('q').global())
+        .flag(Flag::value("lang", "ko|en", "표시 언어 (기본: CROWNY_LANG → crowny.toml → ko)").en("Display language (default: CROWNY_LANG → crowny.toml → ko)").global())
+        .sub(Command::new("run", ".hsn 파일 실행").en("Run a .hsn file").arg("파일")
+            .flag(Flag::value("max-cycles", "N", "실행 사이클 한도").en("Execution cycle limit")))
+        .sub(Command::new("run-bytecode", ".크라운 바이트코드 실행 (체크섬 검증 후 VM에 바로 로드)").en("Run a .크라운 bytecode file (checksum verified, loaded straight into the VM)").alias("바이트코드실행").arg("파일")
+            .flag(Flag::value("max-cycles", "N", "실행 사이클 한도").en("Execution cycle limit")))
+        .sub(Command::new("disasm", ".크라운 · .wasm 역어셈블 (형식은 매직 바이트로 판별)").en("Disassemble a .크라운 or .wasm file (format detected from magic bytes)").alias("역어셈블").arg("파일"))
+        .sub(Command::new("hanseon", "한선어 컴파일+실행 (파일 없으면 데모)").en("Compile and run Hanseon (demo when no file)").alias("한선어").opt_arg("파일"))
+        .sub(Command::new("compile", ".hsn → .wasm 컴파일").en("Compile .hsn → .wasm").alias("컴파일").arg("소스").opt_arg("출력")
+            .flag(Flag::value("target", "env|wasi", "출력 대상 — wasi는 fd_write · _start (기본: env)").en("Output target — wasi uses fd_write and _start (default: env)")))
+        .sub(Command::new("bytecode", ".hsn → .크라운 바이트코드 (스트리밍, 대용량 소스 가능)").en("Compile .hsn → .크라운 bytecode (streaming, handles large sources)").alias("바이트코드").arg("소스").opt_arg("출력")
+            .flag(Flag::switch("progress", "진행률을 stderr에 표시").en("Show progress on stderr")))
+        .sub(Command::new("attest", "재현 빌드 증명 — 소스를 다시 컴파일해 아티팩트(.wasm/.크라운) 해시 검증 후 체인에 기록").en("Reproducible build attestation — recompile the source, verify the artifact (.wasm/.크라운) hash, record on chain").alias("증명").arg("소스").arg("아티팩트")
+            .flag(Flag::value("attester", "이름", "증명자 주소 (기본: local)").en("Attester address (default: local)")))
+        .sub(Command::new("debug", "디버그 모드 실행 (파일 없으면 데모)").en("Run in debug mode (demo when no file)").alias("디버그").opt_arg("파일")
+            .flag(Flag::switch("hanseon", "한선어 소스로 컴파일해 줄 단위 트레이스").en("Compile as Hanseon source and trace by source line"))
+            .flag(Flag::switch("interactive", "대화형 디버거 (stdin 명령 — h로 도움말)").en("Interactive debugger (commands from stdin — h for help)").short('i')))
+        .sub(Command::new("repl", "대화형 REPL · 기록된 세션 재생").en("Interactive REPL / replay a recorded session").alias("대화")
+            .flag(Flag::value("script", "세션.crs", "기록된 입력을 비대화형으로 실행하고 출력 비교").en("Replay recorded inputs non-interactively and compare outputs"))
+            .flag(Flag::switch("update", "--script의 기대 출력을 실제 출력으로 갱신").en("Rewrite --script expectations with actual outputs"))
+            .flag(Flag::value("record", "세션.crs", "입력과 출력을 스크립트로 기록").en("Record inputs and outputs as a script")))
+        .sub(Command::new("notebook", "Markdown 속 ```hanseon 셀 실행").en("Run ```hanseon cells in a Markdown document").alias("노트북").arg("파일")
+            .flag(Flag::switch("write", "결과를 ```output 블록으로 파일에 되쓰기").en("Write results back as ```output blocks"))
+            .flag(Flag::value("html", "출력.html", "HTML 보고서 저장").en("Save HTML report")))
+        .sub(Command::new("example", "내장 예제 목록/실행 (기대 출력 검증)").en("List/run built-in examples (checks expected output)").alias("예제").opt_arg("이름|파일.hsn")
+            .flag(Flag::switch("all", "모든 예제를 스모크 테스트로 실행").en("Run every example as a smoke test")))
+        .sub(Command::new("migrate", "파일 형식 버전 마이그레이션 (원본은 .v{N}.bak 백업)").en("Migrate file format versions (originals backed up as .v{N}.bak)").alias("마이그레이션").rest_args("경로")
+            .flag(Flag::switch("dry-run", "바뀔 내용만 보고하고 파일은 그대로 둔다").en("Report what would change without touching files")))
+        .sub(Command::new("jobs", "예약 작업 목록 (다음 실행 · 마지막 트릿)").en("Scheduled jobs (next run · last trit)").alias("작업")
+            .flag(jobs_store_flag())
+            .sub(Command::new("add", "작업 등록 — 일정: cron · RRULE · 한국어 (매일 09:00, 15분마다)").en("Register a job — schedule: cron · RRULE · Korean (매일 09:00, 15분마다)").arg("이름").arg("일정").arg("파일.hsn")
+                .flag(jobs_store_flag())
+                .flag(Flag::value("owner", "주체", "CAR 제출 주체 (기본: local)").en("CAR subject (default: local)")))
+            .sub(Command::new("remove", "작업 삭제").en("Remove a job").arg("id").flag(jobs_store_flag()))
+            .sub(Command::new("enable", "멈춘 작업 재개 (다음 시각 다시 계산)").en("Resume a paused job (recomputes next run)").arg("id").flag(jobs_store_flag()))
+            .sub(Command::new("disable", "작업 일시 중지 (목록에는 남김)").en("Pause a job (kept in the list)").arg("id").flag(jobs_store_flag()))
+            .sub(Command::new("run", "때가 된 작업을 지금 실행 (놓친 실행은 한 번만)").en("Run due jobs now (missed runs catch up once)").flag(jobs_store_flag())))
+        .sub(Command::new("content", "내용 주소 콘텐츠 목록 (해시 · 크기 · 고정)").en("Content-addressed store listing (hash · size · pins)").alias("콘텐츠")
+            .flag(content_dir_flag())
+            .sub(Command::new("put", "파일 저장 → crwn://content/<hash>").en("Store a file → crwn://content/<hash>").arg("파일")
+                .flag(content_dir_flag())
+                .flag(Flag::value("mime", "형식", "MIME 형식 (기본: application/octet-stream)").en("MIME type (default: application/octet-stream)"))
+                .flag(Flag::value("pin", "소유자", "저장하면서 고정").en("Pin while storing")))
+            .sub(Command::new("pin", "소유자 이름으로 고정 (해시 또는 crwn:// 주소)").en("Pin under an owner (hash or crwn:// URI)").arg("hash").arg("소유자").flag(content_dir_flag()))
+            .sub(Command::new("unpin", "소유자 고정 해제").en("Release an owner's pin").arg("hash").arg("소유자").flag(content_dir_flag()))
+            .sub(Command::new("gc", "고정 없는 콘텐츠 회수").en("Reclaim unpinned content").flag(content_dir_flag())))
+        .sub(Command::new("account", "계정 목록 (주소 · 이름 · 별칭 · 평판) — 서버와 같은 저장소").en("Account listing (address · name · aliases · reputation) — same store as the server").alias("계정")
+            .flag(accounts_dir_flag())
+            .sub(Command::new("create", "공개키로 계정 생성 → crwn:<주소>").en("Create an account from a public key → crwn:<address>").arg("이름").arg("공개키hex").flag(accounts_dir_flag()))
+            .sub(Command::new("show", "계정 상세 + 샘플 체인 잔액 (모든 별칭 합산)").en("Account details + sample chain balance (summed over all aliases)").arg("계정").flag(accounts_dir_flag()))
+            .sub(Command::new("link", "옛 이름을 계정에 연결 (키 없는 legacy 계정은 흡수)").en("Link a legacy name to an account (keyless legacy accounts are absorbed)").arg("계정").arg("별칭").flag(accounts_dir_flag()))
+            .sub(Command::new("key", "키 추가").en("Add a key").arg("계정").arg("이름").arg("공개키hex").flag(accounts_dir_flag()))
+            .sub(Command::new("revoke", "키 폐기 (마지막 활성 키는 불가)").en("Revoke a key (not the last active one)").arg("계정").arg("이름").flag(accounts_dir_flag()))
+            .sub(Command::new("set", "프로필 설정 — 키 name은 표시 이름, 그 밖은 메타데이터").en("Set profile — key name sets the display name, anything else is metadata").arg("계정").arg("키").arg("값").flag(accounts_dir_flag()))
+            .sub(Command::new("reputation", "평판 조정 (±N) → 새 트릿").en("Adjust reputation (±N) → new trit").arg("계정").arg("변화량").flag(accounts_dir_flag())))
+        .sub(Command::new("demo", "TVM 데모").en("TVM demo"))
+        .sub(Command::new("kernel", "Meta-Kernel 데모").en("Meta-Kernel demo").alias("커널"))
+        .sub(Command::new("protocol", "CTP 프로토콜 데모").en("CTP protocol demo").alias("프로토콜")
+            .sub(Command::new("serve", "CTP 서버 — --secure면 노드 키 핸드셰이크 후 종단 암호화").en("CTP server — with --secure, node-key handshake then end-to-end encryption")
+                .flag(Flag::value("listen", "주소", "바인딩 주소 (기본: 127.0.0.1:7293)").en("Bind address (default: 127.0.0.1:7293)"))
+                .flag(Flag::value("gossip", "주소", "가십 피어 발견 바인딩 주소 — 주기 교환 · 워치독 재시작 (노드 ID = --listen 주소)").en("Gossip peer-discovery bind address — periodic exchange, watchdog restarts (node ID = --listen address)"))
+                .flag(Flag::value("seeds", "주소,…", "가십 시드 (다른 노드의 --gossip 주소)").en("Gossip seeds (other nodes' --gossip addresses)"))
+                .flag(secure_flag()).flag(node_key_flag()).flag(trust_flag()))
+            .sub(Command::new("send", "CTP 요청 하나 전송 후 응답 출력").en("Send one CTP request and print the reply").arg("주소").arg("메시지")
+                .flag(secure_flag()).flag(node_key_flag()).flag(trust_flag())))
+        .sub(Command::new("fpga", "FPGA 로드맵 데모").en("FPGA roadmap demo").alias("로드맵"))
+        .sub(Command::new("bench", "2진 vs 3진 밀도·산술·직렬화 실측 (기록 대비 회귀 검사)").en("Measure binary vs ternary density, arithmetic and serialization (regression check against history)").alias("벤치")
+            .flag(Flag::value("iterations", "N", "벤치당 반복 횟수 (기본: 200000)").en("Iterations per benchmark (default: 200000)"))
+            .flag(Flag::value("history", "경로", "기록 파일 (기본: .crowny/bench.jsonl)").en("History file (default: .crowny/bench.jsonl)"))
+            .flag(Flag::switch("no-record", "결과를 기록하지 않음").en("Do not append the result to history")))
+        .sub(Command::new("soak", "장시간 소크 테스트 — 혼합 작업 중 RSS · 저장소 · 기록 길이 · 소켓 표본, 한없이 자라면 T").en("Long-running soak test — sample RSS, store, history lengths and sockets under mixed load; T when any grows without bound").alias("소크")
+            .flag(Flag::value("hours", "N", "실행 시간 (소수 가능, 기본: 1)").en("Duration in hours (fractions allowed, default: 1)"))
+            .flag(Flag::value("rounds", "N", "바퀴 수로 제한 (--hours와 함께면 먼저 닿는 쪽에서 종료)").en("Stop after N rounds (with --hours, whichever limit comes first)"))
+            .flag(Flag::value("sample-every", "N", "표본 간격 (바퀴, 기본: 500)").en("Sample interval in rounds (default: 500)")))
+        .sub(Command::new("wasm", "WASM 변환 데모").en("WASM conversion demo").alias("와즘"))
+        .sub(Command::new("car", "CAR (Application Runtime) 데모").en("CAR (Application Runtime) demo").alias("런타임"))
+        .sub(Command::new("sectors", "729 전체 섹터 데모").en("All 729 sectors demo").alias("섹터"))
+        .sub(Command::new("server", "웹서버 데모").en("Web server demo").alias("서버")
+            .flag(Flag::value("listen", "주소", "실제 TCP 주소에 바인딩해 요청 처리 — ./crowny.toml은 SIGHUP으로 재적재 (예: 127.0.0.1:7293)").en("Bind a real TCP address and serve requests — ./crowny.toml reloads on SIGHUP (e.g. 127.0.0.1:7293)")))
+        .sub(Command::new("llm", "LLM 호출기 데모").en("LLM caller demo").alias("호출기"))
+        .sub(Command::new("cpm", "패키지 매니저 데모").en("Package manager demo").alias("패키지")
+            .sub(Command::new("serve", "패키지 레지스트리 서버 — 게시는 CROWNY_CPM_SECRET으로 서명한 cpm.publish 토큰").en("Package registry server — publishing needs a cpm.publish token signed with CROWNY_CPM_SECRET")
+                .flag(Flag::value("listen", "주소", "바인딩 주소 (기본: 127.0.0.1:7294)").en("Bind address (default: 127.0.0.1:7294)"))
+                .flag(Flag::value("dir", "디렉터리", "게시본 저장소 (기본: .crowny/registry)").en("Published package store (default: .crowny/registry)")))
+            .sub(Command::new("token", "게시 토큰 발급 (CROWNY_CPM_SECRET 필요)").en("Issue a publish token (needs CROWNY_CPM_SECRET)").arg("주체")
+                .flag(Flag::value("days", "N", "유효 기간 (일, 기본: 30)").en("Validity in days (default: 30)")))
+            .sub(Command::new("publish", "패키지 디렉터리 게시 (crowny.toml + src/lib.cws, 토큰: CROWNY_CPM_TOKEN)").en("Publish a package directory (crowny.toml + src/lib.cws, token: CROWNY_CPM_TOKEN)").alias("게시").arg("디렉터리")
+                .flag(Flag::value("registry", "URL", "레지스트리 (기본: $CROWNY_REGISTRY 또는 http://127.0.0.1:7294)").en("Registry (default: $CROWNY_REGISTRY or http://127.0.0.1:7294)")))
+            .sub(Command::new("fetch", "레지스트리에서 받아 설치 — 체크섬 검증, 의존성 포함").en("Download and install from a registry — checksum verified, with dependencies").alias("받기").arg("패키지")
+                .flag(Flag::value("version", "버전", "정확한 버전 (기본: 최신)").en("Exact version (default: latest)"))
+                .flag(Flag::value("registry", "URL", "레지스트리 (기본: $CROWNY_REGISTRY 또는 http://127.0.0.1:7294)").en("Registry (default: $CROWNY_REGISTRY or http://127.0.0.1:7294)"))
+                .flag(Flag::value("dir", "디렉터리", "설치 위치 (기본: ~/.crowny/packages)").en("Install root (default: ~/.crowny/packages)")))
+            .sub(Command::new("search", "레지스트리 패키지 검색 (이름 · 설명)").en("Search a registry by name or description").alias("검색").arg("검색어")
+                .flag(Flag::value("registry", "URL", "레지스트리 (기본: $CROWNY_REGISTRY 또는 http://127.0.0.1:7294)").en("Registry (default: $CROWNY_REGISTRY or http://127.0.0.1:7294)")))
+            .sub(Command::new("install", "내장 레지스트리에서 설치하고 ./crowny.lock 기록").en("Install from the built-in registry and write ./crowny.lock").alias("설치").opt_arg("패키지")
+                .flag(Flag::switch("locked", "./crowny.lock에 고정된 버전 · 체크섬 그대로 설치하고 디스크 소스 검증").en("Install the versions and checksums pinned in ./crowny.lock, then verify the sources on disk"))
+                .flag(Flag::value("dir", "디렉터리", "설치 위치 (기본: ~/.crowny/packages)").en("Install root (default: ~/.crowny/packages)"))))
+        .sub(Command::new("test", "Trit 테스트 프레임워크 데모").en("Trit test framework demo").alias("테스트")
+            .flag(Flag::value("seed", "정수", "속성 테스트 시드 — 실패한 반례 재현").en("Property test seed — reproduces a failing case"))
+            .flag(Flag::value("cases", "개수", "속성마다 생성할 입력 수").en("Inputs generated per property")))
+        .sub(Command::new("store", "영속화 레이어 데모").en("Persistence layer demo").alias("영속화")
+            .flag(Flag::value("dir", "디렉터리", "파일 저장소 — WAL · 스냅샷을 남기고 다음 실행 때 복구").en("File-backed store — keeps WAL and snapshots, recovered on the next run"))
+            .flag(Flag::value("sync", "시점", "WAL fsync: always(기본) · never · N(N개마다)").en("WAL fsync: always (default), never, or N (every N records)")))
+        .sub(Command::new("log", "이벤트 로그 데모").en("Event log demo").alias("로그")
+            .flag(Flag::value("file", "경로", "JSON-lines 파일 싱크 (10MB마다 회전)").en("JSON-lines file sink (rotates every 10MB)"))
+            .flag(Flag::value("max-bytes", "바이트", "이 크기를 넘으면 회전 (기본: 10MB)").en("Rotate past this size (default: 10MB)"))
+            .flag(Flag::value("max-age", "초", "파일을 연 지 이 시간이 지나면 회전").en("Rotate once the file is this old"))
+            .flag(Flag::value("keep", "개수", "보관할 회전 파일 수 (기본: 5)").en("Rotated files to keep (default: 5)")))
+        .sub(Command::new("node", "분산 노드 데모").en("Distributed node demo").alias("노드"))
+        .sub(Command::new("token", "3진 토큰 시스템 데모").en("Ternary token system demo").alias("토큰"))
+        .sub(Command::new("wasm-node", "WASM 브라우저 노드 데모").en("WASM browser node demo").alias("브라우저노드"))
+        .sub(Command::new("consensus", "로컬 3진 합의 데모 (OpenClaw)").en("Local ternary consensus demo (OpenClaw)").alias("합의")
+            .flag(Flag::switch("commit-reveal", "커밋-공개 투표 (해시를 모두 모은 뒤 공개)").en("Commit-reveal voting (reveal only after all hashes arrive)"))
+            .flag(Flag::value("strategy", "majority|weighted|quadratic", "투표 전략 (기본: majority)").en("Voting strategy (default: majority)")))
+        .sub(Command::new("industry", "산업 적용 데모 (의료/교육/트레이딩)").en("Industry demo (medical/education/trading)").alias("산업"))
+        .sub(Command::new("provenance", "합의 근거 보고서 (노드 투표 · CTP 해석 · 폴백 · 감사 로그)").en("Consensus provenance report (votes, CTP interpretation, fallbacks, audit log)").alias("근거").arg("질의")
+            .flag(Flag::switch("live", "OpenClaw 노드에 실제 HTTP 합의 (기본: 로컬 시뮬레이션)").en("Run live HTTP consensus against the OpenClaw nodes (default: local simulation)"))
+            .flag(Flag::value("md", "보고서.md", "Markdown 보고서 저장").en("Save Markdown report"))
+            .flag(Flag::value("html", "보고서.html", "HTML 보고서 저장").en("Save HTML report")))
+        .sub(Command::new("override", "관리자 상태 덮어쓰기 감사 로그 — 해시 체인 검증 (변조 시 T)").en("Admin state override audit log — verify the hash chain (T when tampered)").alias("덮어쓰기")
+            .flag(override_audit_flag())
+            .sub(Command::new("apply", "실행 중인 서버의 보류(O) 작업 · 전송을 P/T로 강제 정산 (토큰: CROWNY_ADMIN_TOKEN)").en("Force-settle a pending task or transfer on the running server to P/T (token: CROWNY_ADMIN_TOKEN)")
+                .arg("task|transfer").arg("ID").arg("P|T").arg("사유")
+                .flag(Flag::value("server", "URL", "서버 (기본: $CROWNY_SERVER 또는 http://127.0.0.1:7293)").en("Server (default: $CROWNY_SERVER or http://127.0.0.1:7293)")))
+            .sub(Command::new("token", "admin.override 토큰 발급 (CROWNY_ADMIN_SECRET 필요, 운영자 = 주체)").en("Issue an admin.override token (needs CROWNY_ADMIN_SECRET, operator = subject)").arg("운영자")
+                .flag(Flag::value("days", "N", "유효 기간 (일, 기본: 1)").en("Validity in days (default: 1)"))))
+        .sub(Command::new("platform", "통합 플랫폼 데모 (Git+Deploy+DB+Runtime+Web3)").en("Integrated platform demo (Git+Deploy+DB+Runtime+Web3)").alias("플랫폼"))
+        .sub(Command::new("browser", "3진 웹브라우저 데모").en("Ternary web browser demo").alias("브라우저"))
+        .sub(Command::new("website", "3진 웹사이트 데모").en("Ternary website demo").alias("웹사이트"))
+        .sub(Command::new("os", "CrownyOS 데모 (프로세스/파일/쉘)").en("CrownyOS demo (processes/files/shell)").alias("운영체제"))
+        .sub(Command::new("chain", "CrownyChain 블록체인 데모 (PoT) · 샘플 체인 조회").en("CrownyChain blockchain demo (PoT) · sample chain queries").alias("체인").alias("블록체인")
+            .sub(Command::new("block", "블록 조회").en("Block queries")
+                .sub(Command::new("get", "번호로 블록 조회").en("Get block by number").arg("번호"))
+                .sub(Command::new("latest", "최신 블록").en("Latest block")))
+            .sub(Command::new("balance", "계정 잔액").en("Account balance").arg("주소"))
+            .sub(Command::new("validators", "밸리데이터 목록").en("Validator list"))
+            .sub(Command::new("params", "프로토콜 파라미터 · 발효 예정 변경 · 감사 기록").en("Protocol parameters, scheduled changes and audit trail").opt_arg("이름"))
+            .sub(Command::new("consortium", "허가형 컨소시엄 데모 (의료 · 교육 파일럿)").en("Permissioned consortium demo (healthcare / education pilot)").alias("컨소시엄"))
+            .sub(Command::new("proof", "트랜잭션 포함 증명 — 블록 헤더의 머클 루트와 대조").en("Transaction inclusion proof — checked against the block header's Merkle root").alias("증명").arg("블록").arg("TX번호"))
+            .sub(Command::new("verify", "체인 무결성 검증").en("Verify chain integrity")))
+        .sub(Command::new("live", "OpenClaw 실제 HTTP 합의 데모").en("OpenClaw live HTTP consensus demo").alias("라이브").alias("live-consensus")
+            .flag(Flag::switch("commit-reveal", "커밋-공개 투표 (해시를 모두 모은 뒤 공개)").en("Commit-reveal voting (reveal only after all hashes arrive)"))
+            .flag(Flag::switch("unweighted", "평판 가중치 없이 노드당 1표").en("One vote per node, ignoring reputation weights"))
+            .flag(Flag::switch("reset-reputation", "저장된 노드 평판을 지우고 시작").en("Clear stored node reputations before running"))
+            .flag(Flag::value("deadline", "ms", "라운드 전체 마감 — 늦은 노드는 타임아웃 O (기본: 6000)").en("Round deadline — late nodes count as a timeout O (default: 6000)")))
+        .sub(Command::new("dex", "CrownyDEX 탈중앙 거래소 데모").en("CrownyDEX decentralized exchange demo").alias("거래소"))
+        .sub(Command::new("bridge", "CrownyBridge 크로스체인 브릿지 데모").en("CrownyBridge cross-chain bridge demo").alias("브릿지"))
+        .sub(Command::new("nft", "CrownyNFT 마켓플레이스 데모").en("CrownyNFT marketplace demo"))
+        .sub(Command::new("contract", "스마트 컨트랙트 VM 데모").en("Smart contract VM demo").alias("스마트").alias("sc"))
+        .sub(Command::new("watchdog", "커널 워치독 (하트비트/재시작) 데모").en("Kernel watchdog (heartbeat/restart) demo").alias("감시"))
+        .sub(Command::new("config", "crowny.toml 런타임 설정 검증").en("Validate crowny.toml runtime config").alias("설정").opt_arg("파일"))
+        .sub(Command::new("all", "전체 데모").en("All demos").alias("전체"))
+        .sub(Command::new("info", "명령어 목록").en("Opcode list"))
+        .sub(Command::new("trit", "10진→균형3진 변환").en("Decimal → balanced ternary").arg("정수"))
+        .sub(Command::new("decode", "6트릿→opcode 디코딩").en("Decode 6 trits → opcode").arg("6트릿"))
+        .sub(Command::new("completions", "셸 자동완성 스크립트 (bash/zsh/fish)").en("Shell completion script (bash/zsh/fish)").arg("셸"))
+        .sub(Command::new("help", "도움말 (명령별: help chain block)").en("Help (per command: help chain block)").rest_args("명령"))
+}
+
+fn override_audit_flag() -> Flag {
+    Flag::value("audit", "경로", "감사 로그 파일 (기본: .crowny/override-audit.tsv — 서버가 쓰는 파일)").en("Audit log file (default: .crowny/override-audit.tsv — the file the server appends to)")
+}
+
+fn secure_flag() -> Flag {
+    Flag::switch("secure", "보안 CTP — 노드 신원 키 상호 인증 + 암호화").en("Secure CTP — mutual node-key authentication + encryption")
+}
+
+fn node_key_flag() -> Flag {
+    Flag::value("key", "경로", "노드 신원 키 파일 — 없으면 생성 (기본: .crowny/node.key)").en("Node identity key file — created when missing (default: .crowny/node.key)")
+}
+
+fn trust_flag() -> Flag {
+    Flag::value("trust", "공개키,...", "허용할 상대 노드 공개 키 (hex, 없으면 모두 허용 후 지문 표시)").en("Allowed peer public keys (hex; without it any peer is accepted and its fingerprint shown)")
+}
+
+fn jobs_store_flag() -> Flag {
+    Flag::value("store", "경로", "작업 파일 (기본: .crowny/jobs.tsv)").en("Job file (default: .crowny/jobs.tsv)")
+}
+
+/// 계정 저장소 — account 명령 · chain balance · 서버가 공유
+const ACCOUNTS_DIR: &str = ".crowny/accounts";
+/// 서버 DEX 오더북 저장소
+const ORDERBOOK_DIR: &str = ".crowny/orderbook";
+
+fn accounts_dir_flag() -> Flag {
+    Flag::value("dir", "디렉터리", "계정 저장소 (기본: .crowny/accounts)").en("Account store (default: .crowny/accounts)")
+}
+
+fn content_dir_flag() -> Flag {
+    Flag::value("dir", "디렉터리", "콘텐츠 저장소 (기본: .crowny/content)").en("Content store (default: .crowny/content)")
+}
+
+/// 명령 없이 .hsn 파일만 주면 run으로 간주
+fn parse_args(spec: &Command, raw: Vec<String>) -> Result<cli::Matches, cli::ParseError> {
+    match cli::parse(spec, &raw) {
+        Err(cli::ParseError::UnknownCommand { path, word })
+            if path.is_empty() && (word.ends_with(".hsn") || word.ends_with(".한선")) =>
+        {
+            let mut with_run = vec!["run".to_string()];
+            with_run.extend(raw);
+            cli::parse(spec, &with_run)
+        }
+        other => other,
+    }
+}
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let spec = cli_spec();
+    let raw: Vec<String> = env::args().skip(1).collect();
+    i18n::detect(&raw).install();
+    let m = match parse_args(&spec, raw) {
+        Ok(m) => m,
+        Err(e) => usage(&format!("{}\n{}", e, tr!("cli.see_help", cli::BIN))),
+    };
+    if let Some(lang) = m.value("lang") {
+        i18n::Locale::parse(lang).unwrap_or_else(|| usage(&tr!("cli.bad_lang", lang))).install();
+    }
+    output::OutputMode { json: m.flag("json"), quiet: m.flag("quiet") }.install();
 
-    if args.len() < 2 {
-        repl();
+    if m.help {
+        print!("{}", cli::help(&spec, &m.path));
         return;
     }
 
-    match args[1].as_str() {
-        "run" => {
-            if args.len() < 3 {
-                eprintln!("사용법: crowni-tvm run <파일.hsn>");
-                return;
-            }
-            run_file(&args[2]);
+    // 마지막 Trit 상태 → 종료 코드 (데모는 P)
+    let mut state: i8 = 1;
+    let arg = |i: usize| m.arg(i).unwrap_or_default();
+
+    match m.path.as_slice() {
+        [] => state = repl(None),
+        ["repl"] => state = match m.value("script") {
+            Some(path) => run_repl_script(path, m.flag("update")),
+            None => repl(m.value("record")),
+        },
+        [cmd @ ("run" | "run-bytecode")] => {
+            let max_cycles = m.value("max-cycles").map(|n| n.parse::<u64>()
+                .unwrap_or_else(|_| usage(&format!("--max-cycles: 정수 필요 ({})", n))));
+            state = if *cmd == "run" { run_file(arg(0), max_cycles) } else { run_bytecode_file(arg(0), max_cycles) };
         }
-        "demo" => run_demo(),
-        "info" => show_info(),
-        "trit" => {
-            if args.len() < 3 {
-                eprintln!("사용법: crowni-tvm trit <정수>");
-                return;
+        ["disasm"] => state = disassemble_file(arg(0)),
+        ["notebook"] => state = run_notebook(arg(0), m.flag("write"), m.value("html")),
+        ["example"] => state = run_example(m.arg(0), m.flag("all")),
+        ["migrate"] => state = run_migrate(&m.args, m.flag("dry-run")),
+        ["jobs", rest @ ..] => state = run_jobs(rest.first().copied(), &m.args, m.value("store"), m.value("owner")),
+        ["account", rest @ ..] => state = run_account(rest.first().copied(), &m.args, m.value("dir")),
+        ["content", rest @ ..] => state = run_content(rest.first().copied(), &m.args, m.value("dir"), m.value("mime"), m.value("pin")),
+        ["demo"] => run_demo(),
+        ["info"] => show_info(),
+        ["trit"] => state = convert_trit(arg(0)),
+        ["decode"] => state = decode_trit_str(arg(0)),
+        ["help"] => {
+            let mut cmd = &spec;
+            let mut path = Vec::new();
+            for word in &m.args {
+                match cmd.find(word) {
+                    Some(sub) => {
+                        path.push(sub.name);
+                        cmd = sub;
+                    }
+                    None => usage(&tr!("cli.unknown_command", m.args.join(" "))),
+                }
             }
-            convert_trit(&args[2]);
+            print!("{}", cli::help(&spec, &path));
         }
-        "decode" => {
-            if args.len() < 3 {
-                eprintln!("사용법: crowni-tvm decode <6트릿문자열>");
-                return;
+        ["completions"] => match cli::Shell::parse(arg(0)) {
+            Some(shell) => print!("{}", cli::completions(&spec, shell)),
+            None => usage(&format!("지원하지 않는 셸: {} (bash/zsh/fish)", arg(0))),
+        },
+        ["kernel"] => run_kernel_demo(),
+        ["protocol"] => run_protocol_demo(),
+        ["protocol", "serve"] => state = ctp_serve(m.value("listen").unwrap_or("127.0.0.1:7293"),
+            m.flag("secure").then(|| (m.value("key"), m.value("trust"))),
+            m.value("gossip").map(|bind| (bind, m.value("seeds")))),
+        ["protocol", "send"] => state = ctp_send(arg(0), arg(1),
+            m.flag("secure").then(|| (m.value("key"), m.value("trust")))),
+        ["fpga"] => run_fpga_demo(),
+        ["bench"] => {
+            let iterations = m.value("iterations").map(|n| n.parse::<usize>()
+                .unwrap_or_else(|_| usage(&format!("--iterations: 정수 필요 ({})", n))));
+            state = run_bench(iterations.unwrap_or(bench::DEFAULT_ITERATIONS), m.value("history"), !m.flag("no-record"));
+        }
+        ["soak"] => {
+            let hours = m.value("hours").map(|n| n.parse::<f64>().ok().filter(|h| *h > 0.0)
+                .unwrap_or_else(|| usage(&format!("--hours: 양수 필요 ({})", n))));
+            let rounds = m.value("rounds").map(|n| n.parse::<u64>()
+                .unwrap_or_else(|_| usage(&format!("--rounds: 정수 필요 ({})", n))));
+            let every = m.value("sample-every").map(|n| n.parse::<u64>()
+                .unwrap_or_else(|_| usage(&format!("--sample-every: 정수 필요 ({})", n))));
+            // --rounds만 주면 시간 제한 없이 바퀴 수로만 멈춘다
+            let mut config = match (hours, rounds) {
+                (None, Some(n)) => soak::SoakConfig::rounds(n),
+                (h, n) => soak::SoakConfig { rounds: n, ..soak::SoakConfig::hours(h.unwrap_or(1.0)) },
+            };
+            config = config.with_sample_every(every.unwrap_or(soak::DEFAULT_SAMPLE_EVERY));
+            state = run_soak(config);
+        }
+        ["wasm"] => run_wasm_demo(),
+        ["car"] => run_car_demo(),
+        ["sectors"] => run_sectors_demo(),
+        ["hanseon"] => match m.arg(0) {
+            Some(path) => compile_hanseon(path),
+            None => run_hanseon_demo(),
+        },
+        ["server"] => match m.value("listen") {
+            Some(addr) => state = serve_http(addr),
+            None => run_server_demo(),
+        },
+        ["llm"] => run_llm_demo(),
+        ["cpm"] => run_cpm_demo(),
+        ["cpm", "serve"] => state = cpm_serve(m.value("listen").unwrap_or("127.0.0.1:7294"), m.value("dir").unwrap_or(".crowny/registry")),
+        ["cpm", "token"] => {
+            let days = m.value("days").map(|n| n.parse::<u64>()
+                .unwrap_or_else(|_| usage(&format!("--days: 정수 필요 ({})", n))));
+            state = cpm_token(arg(0), days.unwrap_or(30));
+        }
+        ["cpm", "publish"] => state = cpm_publish(arg(0), m.value("registry")),
+        ["cpm", "fetch"] => state = cpm_fetch(arg(0), m.value("version"), m.value("registry"), m.value("dir")),
+        ["cpm", "search"] => state = cpm_search(arg(0), m.value("registry")),
+        ["cpm", "install"] => state = cpm_install(m.arg(0), m.flag("locked"), m.value("dir")),
+        ["test"] => {
+            let mut config = trit_test::PropConfig::default();
+            if let Some(n) = m.value("seed") {
+                config = config.with_seed(n.parse().unwrap_or_else(|_| usage(&format!("--seed: 정수 필요 ({})", n))));
             }
-            decode_trit_str(&args[2]);
-        }
-        "help" | "--help" | "-h" => show_help(),
-        "kernel" | "커널" => run_kernel_demo(),
-        "protocol" | "프로토콜" => run_protocol_demo(),
-        "fpga" | "로드맵" => run_fpga_demo(),
-        "wasm" | "와즘" => run_wasm_demo(),
-        "car" | "런타임" => run_car_demo(),
-        "sectors" | "섹터" => run_sectors_demo(),
-        "hanseon" | "한선어" => {
-            if args.len() >= 3 {
-                compile_hanseon(&args[2]);
-            } else {
-                run_hanseon_demo();
+            if let Some(n) = m.value("cases") {
+                config = config.with_cases(n.parse().unwrap_or_else(|_| usage(&format!("--cases: 정수 필요 ({})", n))));
             }
+            state = run_test_demo(config);
         }
-        "server" | "서버" => run_server_demo(),
-        "llm" | "호출기" => run_llm_demo(),
-        "cpm" | "패키지" => run_cpm_demo(),
-        "test" | "테스트" => run_test_demo(),
-        "debug" | "디버그" => {
-            if args.len() >= 3 {
-                debug_file(&args[2]);
-            } else {
-                run_debug_demo();
-            }
+        ["debug"] => match m.arg(0) {
+            Some(path) => debug_file(path, m.flag("hanseon"), m.flag("interactive")),
+            None => run_debug_demo(),
+        },
+        ["store"] => state = run_store_demo(m.value("dir"), m.value("sync")),
+        ["log"] => {
+            let int = |name: &str| m.value(name).map(|n| n.parse::<u64>()
+                .unwrap_or_else(|_| usage(&format!("--{}: 정수 필요 ({})", name, n))));
+            run_log_demo(m.value("file"), int("max-bytes"), int("max-age"), int("keep"));
         }
-        "store" | "영속화" => run_store_demo(),
-        "log" | "로그" => run_log_demo(),
-        "node" | "노드" => node::demo_distributed_node(),
-        "token" | "토큰" => token::demo_token(),
-        "wasm-node" | "브라우저노드" => wasm_node::demo_wasm_browser_node(),
-        "consensus" | "합의" => local_consensus::demo_local_consensus(),
-        "industry" | "산업" => industry::demo_industry(),
-        "platform" | "플랫폼" => platform::demo_platform(),
-        "browser" | "브라우저" => browser::demo_browser(),
-        "website" | "웹사이트" => website::demo_website(),
-        "os" | "운영체제" => os::demo_os(),
-        "chain" | "체인" | "블록체인" => chain::demo_chain(),
-        "live" | "라이브" | "live-consensus" => live_consensus::demo_live_consensus(),
-        "dex" | "거래소" => dex::demo_dex(),
-        "bridge" | "브릿지" => crossbridge::demo_bridge(),
-        "nft" => nft::demo_nft(),
-        "contract" | "스마트" | "sc" => contract_vm::demo_contract_vm(),
-        "compile" | "컴파일" => {
-            if args.len() < 3 {
-                eprintln!("사용법: crowni-tvm compile <소스.hsn> [출력.wasm]");
-                return;
-            }
-            let output = if args.len() >= 4 { &args[3] } else { "output.wasm" };
-            compile_file(&args[2], output);
+        ["node"] => node::demo_distributed_node(project_config().message_log_capacity as usize),
+        ["token"] => token::demo_token(),
+        ["wasm-node"] => wasm_node::demo_wasm_browser_node(project_config().message_log_capacity as usize),
+        ["consensus"] => {
+            let strategy = m.value("strategy").map(|s| local_consensus::VotingStrategy::parse(s)
+                .unwrap_or_else(|| usage(&format!("--strategy: majority/weighted/quadratic 중 하나 ({})", s))));
+            state = local_consensus::demo_local_consensus(m.flag("commit-reveal"), strategy.unwrap_or_default());
         }
-        "bytecode" | "바이트코드" => {
-            if args.len() < 3 {
-                eprintln!("사용법: crowni-tvm bytecode <소스.hsn> [출력.크라운]");
-                return;
-            }
-            let output = if args.len() >= 4 { &args[3] } else { "output.크라운" };
-            bytecode_file(&args[2], output);
+        ["industry"] => state = industry::demo_industry(),
+        ["provenance"] => state = run_provenance(arg(0), m.flag("live"), m.value("md"), m.value("html")),
+        ["override"] => state = run_override_audit(m.value("audit")),
+        ["override", "apply"] => state = run_override_apply(arg(0), arg(1), arg(2), arg(3), m.value("server")),
+        ["override", "token"] => {
+            let days = m.value("days").map(|n| n.parse::<u64>()
+                .unwrap_or_else(|_| usage(&format!("--days: 정수 필요 ({})", n))));
+            state = run_override_token(arg(0), days.unwrap_or(1));
+        }
+        ["platform"] => platform::demo_platform(),
+        ["browser"] => browser::demo_browser(),
+        ["website"] => website::demo_website(),
+        ["os"] => os::demo_os(),
+        ["chain"] => state = chain::demo_chain(),
+        ["chain", "block", "get"] => state = chain_block(Some(arg(0))),
+        ["chain", "block", "latest"] => state = chain_block(None),
+        ["chain", "block"] => print!("{}", cli::help(&spec, &m.path)),
+        ["chain", "balance"] => state = chain_balance(arg(0)),
+        ["chain", "validators"] => state = chain_validators(),
+        ["chain", "params"] => state = chain_params(m.arg(0)),
+        ["chain", "consortium"] => state = consortium::demo_consortium(),
+        ["chain", "proof"] => state = chain_proof(arg(0), arg(1)),
+        ["chain", "verify"] => state = chain_verify(),
+        ["live"] => {
+            let deadline = m.value("deadline").map_or(live_consensus::DEFAULT_DEADLINE_MS, |n| n.parse::<u64>()
+                .unwrap_or_else(|_| usage(&format!("--deadline: 정수 필요 ({})", n))));
+            state = live_consensus::demo_live_consensus(m.flag("commit-reveal"), !m.flag("unweighted"), m.flag("reset-reputation"), deadline);
+        }
+        ["dex"] => state = dex::demo_dex(),
+        ["bridge"] => crossbridge::demo_bridge(),
+        ["nft"] => nft::demo_nft(),
+        ["contract"] => contract_vm::demo_contract_vm(),
+        ["watchdog"] => watchdog::demo_watchdog(),
+        ["config"] => state = check_config(m.arg(0).unwrap_or("crowny.toml")),
+        ["compile"] => {
+            let target = m.value("target").map(|t| compiler::Target::parse(t)
+                .unwrap_or_else(|| usage(&format!("--target: env 또는 wasi ({})", t))))
+                .unwrap_or_default();
+            state = compile_file(arg(0), m.arg(1).unwrap_or("output.wasm"), target);
         }
-        "all" | "전체" => {
+        ["attest"] => state = attest_file(arg(0), arg(1), m.value("attester").unwrap_or("local")),
+        ["bytecode"] => state = bytecode_file(arg(0), m.arg(1).unwrap_or("output.크라운"), m.flag("progress")),
+        ["all"] => {
             run_demo();
             println!("\n{}\n", "═".repeat(60));
             run_kernel_demo();
@@ -193,21 +553,21 @@ fn main() {
             println!("\n{}\n", "═".repeat(60));
             run_cpm_demo();
             println!("\n{}\n", "═".repeat(60));
-            run_test_demo();
+            run_test_demo(trit_test::PropConfig::default());
             println!("\n{}\n", "═".repeat(60));
             run_debug_demo();
             println!("\n{}\n", "═".repeat(60));
-            run_store_demo();
+            run_store_demo(None, None);
             println!("\n{}\n", "═".repeat(60));
-            run_log_demo();
+            run_log_demo(None, None, None, None);
             println!("\n{}\n", "═".repeat(60));
-            node::demo_distributed_node();
+            node::demo_distributed_node(ring_log::MESSAGE_LOG_CAPACITY);
             println!("\n{}\n", "═".repeat(60));
             token::demo_token();
             println!("\n{}\n", "═".repeat(60));
-            wasm_node::demo_wasm_browser_node();
+            wasm_node::demo_wasm_browser_node(ring_log::MESSAGE_LOG_CAPACITY);
             println!("\n{}\n", "═".repeat(60));
-            local_consensus::demo_local_consensus();
+            local_consensus::demo_local_consensus(false, local_consensus::VotingStrategy::Majority);
             println!("\n{}\n", "═".repeat(60));
             industry::demo_industry();
             println!("\n{}\n", "═".repeat(60));
@@ -221,7 +581,7 @@ fn main() {
             println!("\n{}\n", "═".repeat(60));
             chain::demo_chain();
             println!("\n{}\n", "═".repeat(60));
-            live_consensus::demo_live_consensus();
+            live_consensus::demo_live_consensus(false, true, false, live_consensus::DEFAULT_DEADLINE_MS);
             println!("\n{}\n", "═".repeat(60));
             dex::demo_dex();
             println!("\n{}\n", "═".repeat(60));
@@ -230,147 +590,287 @@ fn main() {
             nft::demo_nft();
             println!("\n{}\n", "═".repeat(60));
             contract_vm::demo_contract_vm();
+            println!("\n{}\n", "═".repeat(60));
+            watchdog::demo_watchdog();
         }
-        _ => {
-            // 파일이면 실행
-            if args[1].ends_with(".hsn") || args[1].ends_with(".한선") {
-                run_file(&args[1]);
-            } else {
-                eprintln!("알 수 없는 명령: {}", args[1]);
-                show_help();
-            }
-        }
+        path => usage(&format!("알 수 없는 명령: {}", path.join(" "))),
+    }
+
+    if state != 1 {
+        std::process::exit(output::exit_code(state));
     }
 }
 
-// ── REPL ──
+/// 인자 부족 — 사용법 출력 후 T(1)로 종료
+fn usage(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(output::exit_code(-1));
+}
 
-fn repl() {
-    println!("{}", BANNER);
-    println!("REPL 모드 — 한글 또는 영문 명령어 입력 (종료: 'exit' 또는 Ctrl+C)");
-    println!("명령: .stack .regs .heap .dump .debug .run .reset .info .help\n");
+/// ./crowny.toml — 없거나 잘못되면 기본값
+fn project_config() -> config::RuntimeConfig {
+    fs::read_to_string("crowny.toml").ok()
+        .and_then(|text| config::RuntimeConfig::from_toml(&text).ok())
+        .unwrap_or_default()
+}
 
-    let mut vm = TVM::new();
-    let mut buffer = String::new();
+/// ./crowny.toml [limits] — 없거나 잘못되면 무제한
+fn project_program_limits() -> program_limits::ProgramLimits {
+    project_config().program_limits
+}
 
-    loop {
-        print!("크라운> ");
-        io::stdout().flush().unwrap_or(());
+/// 명령 실패 — stderr 메시지 + (JSON 모드) 실패 객체
+fn fail(command: &str, msg: &str) -> i8 {
+    eprintln!("{}", msg);
+    if output::is_json() {
+        JsonObject::envelope(command, -1, &[msg.to_string()], JsonObject::new()).emit();
+    }
+    -1
+}
 
-        let mut line = String::new();
-        if io::stdin().read_line(&mut line).unwrap_or(0) == 0 {
-            break; // EOF
-        }
-        let line = line.trim();
+// ── REPL ──
 
-        if line.is_empty() { continue; }
-
-        // 메타 명령어
-        match line {
-            "exit" | "quit" | "나가" | "종료해" => break,
-            ".stack" | ".스택" => { vm.dump_stack(); continue; }
-            ".regs" | ".레지스터" => { vm.dump_registers(); continue; }
-            ".heap" | ".힙" => { vm.heap.dump(); continue; }
-            ".dump" | ".덤프" => { vm.dump_all(); continue; }
-            ".debug" | ".디버그" => {
-                vm.debug = !vm.debug;
-                println!("디버그 모드: {}", if vm.debug { "ON" } else { "OFF" });
-                continue;
-            }
-            ".reset" | ".초기화" => {
-                vm = TVM::new();
-                println!("VM 초기화 완료");
-                continue;
-            }
-            ".info" | ".정보" => { show_info(); continue; }
-            ".help" | ".도움" => {
-                println!("명령어: .stack .regs .heap .dump .debug .reset .info .help exit");
-                continue;
+fn repl(record: Option<&str>) -> i8 {
+    output::banner(BANNER);
+    println!("REPL 모드 — 한글 또는 영문 명령어 입력 (종료: 'exit' 또는 Ctrl+C)");
+    println!("명령: .stack .regs .heap .vars .dump .debug .run .reset .info .help");
+    println!("↑↓ 기록 · Ctrl-R 검색 · Tab 완성 · {{ 로 연 한선어 블록은 닫힐 때까지 이어 입력\n");
+
+    // 입력마다 바로 덧붙여 Ctrl+C로 끝나도 기록이 남는다
+    let mut recorder = match record {
+        Some(path) => match fs::write(path, repl::header()).and_then(|_| fs::OpenOptions::new().append(true).open(path)) {
+            Ok(f) => {
+                println!("기록 중: {}\n", path);
+                Some(f)
             }
-            _ => {}
-        }
+            Err(e) => return fail("repl", &format!("기록 파일 열기 실패 '{}': {}", path, e)),
+        },
+        None => None,
+    };
 
-        // .run 으로 버퍼 실행
-        if line == ".run" || line == ".실행" {
-            if buffer.is_empty() {
-                println!("버퍼가 비어있습니다. 명령어를 입력하세요.");
-            } else {
-                let program = assemble(&buffer);
-                if !program.is_empty() {
-                    println!("--- {} 명령어 실행 ---", program.len());
-                    vm.load(program);
-                    match vm.run() {
-                        Ok(()) => println!("--- 정상 종료 ({}사이클) ---", vm.cycles),
-                        Err(e) => println!("--- 오류: {} ---", e),
-                    }
+    let mut session = repl::ReplSession::new();
+    let mut editor = line_editor::Editor::new(line_editor::History::load_default());
+    loop {
+        let prompt = if session.in_block() { repl::CONTINUE_PROMPT } else { repl::PROMPT };
+        editor.words = session.completion_words();
+        let line = match editor.read(prompt) {
+            line_editor::Input::Line(line) => line,
+            line_editor::Input::Interrupted => {
+                if session.cancel_block() {
+                    println!("블록 입력 취소");
                 }
-                buffer.clear();
+                continue;
             }
-            continue;
-        }
+            line_editor::Input::Eof => break,
+        };
+        let line = line.trim();
+        if line.is_empty() && !session.in_block() { continue; }
 
-        // 즉시 실행 모드: 한 줄을 바로 실행
-        let program = assemble(line);
-        if program.is_empty() {
-            // 어셈블 실패 → 버퍼에 추가
-            buffer.push_str(line);
-            buffer.push('\n');
-            continue;
+        let step = session.eval(line);
+        for l in &step.lines {
+            println!("{}", l);
         }
+        if step.exit { break; }
+        if let Some(f) = recorder.as_mut() {
+            if let Err(e) = f.write_all(repl::transcript(line, &step.lines).as_bytes()) {
+                eprintln!("기록 실패: {}", e);
+                recorder = None;
+            }
+        }
+    }
 
-        // 즉시 실행
-        vm.halted = false;
-        let old_ip = vm.ip;
-        let old_prog_len = vm.program.len();
+    println!("\n안녕히. 크라우닌 TVM을 종료합니다.");
+    if let Some(path) = record {
+        println!("기록 저장: {} (재생: {} repl --script {})", path, cli::BIN, path);
+    }
+    1
+}
 
-        // 기존 프로그램 뒤에 추가 실행
-        for inst in &program {
-            vm.program.push(inst.clone());
+/// 기록된 REPL 입력을 비대화형으로 재생하고 기대 출력과 비교
+fn run_repl_script(path: &str, update: bool) -> i8 {
+    let source = match fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => return fail("repl", &format!("파일 읽기 실패 '{}': {}", path, e)),
+    };
+    let script = repl::Script::parse(&source);
+    let run = script.run();
+    let failures = run.failures();
+    let state = if update { 1 } else { run.state() };
+
+    if update {
+        if let Err(e) = fs::write(path, script.render(&run)) {
+            return fail("repl", &format!("쓰기 실패 '{}': {}", path, e));
         }
-        vm.ip = old_prog_len;
-        vm.halted = false;
+    }
 
-        match vm.run() {
-            Ok(()) => {}
-            Err(vm::VmError::Halted) => {}
-            Err(e) => println!("오류: {}", e),
+    if output::is_json() {
+        let mismatches = failures.iter().map(|r| JsonObject::new()
+            .int("line", r.line as i64)
+            .str("input", &r.input)
+            .strs("expected", &r.expected)
+            .strs("actual", &r.actual)).collect();
+        JsonObject::new()
+            .str("command", "repl")
+            .str("file", path)
+            .trit("state", state)
+            .int("inputs", run.results.len() as i64)
+            .int("skipped", run.skipped() as i64)
+            .bool("updated", update)
+            .objects("mismatches", mismatches)
+            .emit();
+    } else {
+        println!("=== REPL 스크립트: {} ({} 입력) ===", path, run.results.len());
+        for r in &failures {
+            println!("  ✗ {}줄  {}", r.line, r.input);
+            for l in &r.expected {
+                println!("        - {}", l);
+            }
+            for l in &r.actual {
+                println!("        + {}", l);
+            }
+        }
+        if run.skipped() > 0 {
+            println!("  exit 이후 {} 입력 생략", run.skipped());
+        }
+        if update {
+            println!("✓ 기대 출력 갱신: {} ({} 곳 변경)", path, failures.len());
+        } else if failures.is_empty() {
+            println!("✓ 모든 출력 일치");
+        } else {
+            println!("✗ {} / {} 입력 불일치", failures.len(), run.results.len());
         }
     }
-
-    println!("\n안녕히. 크라우닌 TVM을 종료합니다.");
+    state
 }
 
 // ── 파일 실행 ──
 
-fn run_file(path: &str) {
+fn run_file(path: &str, max_cycles: Option<u64>) -> i8 {
     let source = match fs::read_to_string(path) {
         Ok(s) => s,
+        Err(e) => return fail("run", &format!("파일 읽기 실패 '{}': {}", path, e)),
+    };
+
+    // 가져와 구문 — ~/.crowny/packages에 설치된 패키지 소스를 뒤에 붙인다
+    let source = if source.lines().any(|l| cpm::parse_import(l).is_some()) {
+        match cpm::CrownyPM::new().with_default_root().link(&source) {
+            Ok(linked) => linked,
+            Err(e) => return fail("run", &e),
+        }
+    } else {
+        source
+    };
+
+    let program = match assembler::assemble_limited(&source, &project_program_limits()) {
+        Ok(p) => p,
         Err(e) => {
-            eprintln!("파일 읽기 실패 '{}': {}", path, e);
-            return;
+            eprintln!("{}", e);
+            if output::is_json() {
+                let data = JsonObject::new().str("file", path).object("limit", e.to_json());
+                JsonObject::envelope("run", -1, &[e.to_string()], data).emit();
+            }
+            return -1;
         }
     };
+    execute_program("run", path, program, Some(&source), max_cycles)
+}
+
+/// .크라운 바이트코드 실행 — 어셈블 없이 VM에 바로 로드 (v1이면 백업 후 v2로 올림)
+fn run_bytecode_file(path: &str, max_cycles: Option<u64>) -> i8 {
+    let program = match bytecode::open_file(std::path::Path::new(path)) {
+        Ok(p) => p,
+        Err(e) => return fail("run-bytecode", &format!("바이트코드 로드 실패 '{}': {}", path, e)),
+    };
+    execute_program("run-bytecode", path, program, None, max_cycles)
+}
+
+/// .크라운 / .wasm 역어셈블 — 매직 바이트로 형식 판별
+fn disassemble_file(path: &str) -> i8 {
+    let data = match fs::read(path) {
+        Ok(d) => d,
+        Err(e) => return fail("disasm", &format!("파일 읽기 실패 '{}': {}", path, e)),
+    };
+    let (format, listing) = if data.starts_with(b"\0asm") {
+        ("wasm", wasm_gen::disassemble(&data).map_err(|e| e.to_string()))
+    } else if bytecode::detect_version(&data).is_some() {
+        // 구버전은 메모리에서만 올려 읽는다 — 역어셈블은 파일을 바꾸지 않는다
+        let listing = migrations::MigrationRegistry::builtin().migrate_bytes("bytecode", &data)
+            .and_then(|(bytes, _)| bytecode::disassemble(&bytes));
+        ("crown", listing)
+    } else {
+        return fail("disasm", &format!("알 수 없는 형식 '{}' (.크라운 또는 .wasm 아님)", path));
+    };
+    let listing = match listing {
+        Ok(l) => l,
+        Err(e) => return fail("disasm", &format!("역어셈블 실패 '{}': {}", path, e)),
+    };
+    if output::is_json() {
+        let lines: Vec<String> = listing.lines().map(str::to_string).collect();
+        JsonObject::new()
+            .str("command", "disasm")
+            .str("file", path)
+            .str("format", format)
+            .trit("state", 1)
+            .strs("lines", &lines)
+            .emit();
+    } else {
+        print!("{}", listing);
+    }
+    1
+}
 
-    let program = assemble(&source);
+/// 프로그램 실행 + 결과 보고 (run · run-bytecode 공통)
+fn execute_program(command: &str, path: &str, program: Vec<vm::Instruction>, source: Option<&str>, max_cycles: Option<u64>) -> i8 {
     if program.is_empty() {
-        eprintln!("프로그램이 비어있습니다.");
-        return;
+        return fail(command, "프로그램이 비어있습니다.");
     }
 
-    println!("=== CROWNIN TVM — {} ({} 명령어) ===", path, program.len());
+    let count = program.len();
+    output::banner(&tr!("cli.run_header", path, count));
     let mut vm = TVM::new();
+    vm.limits = vm::ExecLimits { max_cycles, ..vm::ExecLimits::unlimited() };
+    if output::is_json() {
+        vm.captured = Some(Vec::new());
+    }
     vm.load(program);
 
-    match vm.run() {
-        Ok(()) => println!("\n=== 정상 종료 ({}사이클) ===", vm.cycles),
-        Err(e) => eprintln!("\n=== 오류: {} ===", e),
+    let result = vm.run();
+    let state = if result.is_ok() { 1 } else { -1 };
+
+    if output::is_json() {
+        let mut data = JsonObject::new()
+            .str("file", path)
+            .int("instructions", count as i64)
+            .int("cycles", vm.cycles as i64)
+            .int("gc_runs", vm.heap.stats().runs as i64)
+            .int("gc_collected", vm.heap.stats().collected as i64)
+            .strs("output", &vm.captured.take().unwrap_or_default());
+        if let Some(top) = vm.stack.last() {
+            data = data.str("top", &top.to_string());
+        }
+        let mut errors = Vec::new();
+        if let Err(e) = &result {
+            errors.push(e.to_string());
+            data = data.object("error_at", e.to_json());
+        }
+        JsonObject::envelope(command, state, &errors, data).emit();
+    } else {
+        match result {
+            Ok(()) => {
+                let gc = vm.heap.stats();
+                let gc = if gc.runs > 0 { tr!("cli.run_gc", gc.runs, gc.collected) } else { String::new() };
+                output::banner(&format!("\n{}", tr!("cli.run_footer", vm.cycles, gc)));
+            }
+            Err(e) => eprintln!("\n{}", e.diagnostic(path, source)),
+        }
     }
+    state
 }
 
 // ── 데모 ──
 
 fn run_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ 데모 1: 산술 (10 + 20 = 30) ═══");
     {
         let src = "넣어 10\n넣어 20\n더해\n보여줘\n종료";
@@ -437,122 +937,911 @@ fn run_demo() {
 // ── 명령어 목록 ──
 
 fn show_info() {
-    let opcodes = opcode::build_opcodes();
-    println!("╔═══════════════════════════════════════════════╗");
-    println!("║  CROWNIN TVM — 등록된 명령어 목록              ║");
-    println!("║  729 슬롯 중 {} 개 구현                        ║", opcodes.len());
-    println!("╚═══════════════════════════════════════════════╝");
-
-    // 섹터별 출력
-    for sec in 0..9u8 {
-        let (kr, en) = SECTOR_NAMES[sec as usize];
-        let count = opcodes.iter().filter(|(a, _)| a.sector == sec).count();
-        if count == 0 { continue; }
-
-        println!("\n── 섹터 {}: {} ({}) — {} 명령어 ──", sec, kr, en, count);
-
-        for grp in 0..9u8 {
-            let group_ops: Vec<_> = opcodes.iter()
-                .filter(|(a, _)| a.sector == sec && a.group == grp)
-                .collect();
-            if group_ops.is_empty() { continue; }
-
-            let grp_name = if sec == 0 && (grp as usize) < GROUP_NAMES_CORE.len() {
-                GROUP_NAMES_CORE[grp as usize]
-            } else {
-                "─"
-            };
-            println!("  G{} [{}]:", grp, grp_name);
-            for (addr, meta) in &group_ops {
-                println!("    ({},{},{}) {:10} {:8} pop:{} push:{} oper:{}",
-                    addr.sector, addr.group, addr.command,
-                    meta.name_kr, meta.name_en,
-                    meta.pops, meta.pushes, meta.operands);
-            }
-        }
+    if output::is_json() {
+        let opcodes = opcode::build_opcodes();
+        let mut addrs: Vec<_> = opcodes.keys().copied().collect();
+        addrs.sort_by_key(|a| (a.sector, a.group, a.command));
+        let list = addrs.iter().map(|a| {
+            let m = &opcodes[a];
+            opcode_json(a.sector, a.group, a.command)
+                .str("name_kr", m.name_kr)
+                .str("name_en", m.name_en)
+                .int("pops", m.pops as i64)
+                .int("pushes", m.pushes as i64)
+                .int("operands", m.operands as i64)
+                .str("effect", &format!("{:?}", m.effect))
+        }).collect();
+        let data = JsonObject::new().int("slots", 729).int("implemented", opcodes.len() as i64).objects("opcodes", list);
+        JsonObject::envelope("info", 1, &[], data).emit();
+        return;
+    }
+    for line in opcode::catalog_lines() {
+        println!("{}", line);
     }
 }
 
 // ── 10진 → 균형3진 변환 ──
 
-fn convert_trit(input: &str) {
+fn convert_trit(input: &str) -> i8 {
     match input.parse::<i16>() {
         Ok(val) if (-364..=364).contains(&val) => {
             let w = Word6::from_decimal(val);
-            println!("10진수:  {}", val);
-            println!("균형3진: {} (6트릿)", w);
             let (s, g, c) = w.decode_opcode();
-            println!("opcode:  ({},{},{}) = 섹터:{} 그룹:{} 명령:{}", s, g, c, s, g, c);
-            println!("복원:    {}", w.to_decimal());
+            if output::is_json() {
+                let data = JsonObject::new()
+                    .int("decimal", val as i64)
+                    .str("trits", &w.to_string())
+                    .object("opcode", opcode_json(s, g, c));
+                JsonObject::envelope("trit", 1, &[], data).emit();
+            } else {
+                println!("10진수:  {}", val);
+                println!("균형3진: {} (6트릿)", w);
+                println!("opcode:  ({},{},{}) = 섹터:{} 그룹:{} 명령:{}", s, g, c, s, g, c);
+                println!("복원:    {}", w.to_decimal());
+            }
+            1
         }
-        Ok(val) => eprintln!("6트릿 범위 초과: {} (허용: -364 ~ +364)", val),
-        Err(e) => eprintln!("정수 파싱 실패: {} — {}", input, e),
+        Ok(val) => fail("trit", &format!("6트릿 범위 초과: {} (허용: -364 ~ +364)", val)),
+        Err(e) => fail("trit", &format!("정수 파싱 실패: {} — {}", input, e)),
     }
 }
 
+fn opcode_json(sector: u8, group: u8, command: u8) -> JsonObject {
+    JsonObject::new()
+        .int("sector", sector as i64)
+        .int("group", group as i64)
+        .int("command", command as i64)
+}
+
 // ── 6트릿 문자열 → opcode 디코딩 ──
 
-fn decode_trit_str(input: &str) {
+/// 미등록 opcode는 O(보류) — 형식은 맞지만 의미가 없다
+fn decode_trit_str(input: &str) -> i8 {
     match Word6::from_trit_str(input) {
         Some(w) => {
             let (s, g, c) = w.decode_opcode();
             let opcodes = opcode::build_opcodes();
             let addr = opcode::OpcodeAddr::new(s, g, c);
-            let name = opcodes.get(&addr).map(|m| format!("{} ({})", m.name_kr, m.name_en)).unwrap_or("(미등록)".into());
-            println!("6트릿:   {}", w);
-            println!("10진수:  {}", w.to_decimal());
-            println!("opcode:  ({},{},{}) → {}", s, g, c, name);
+            let meta = opcodes.get(&addr);
+            let state = if meta.is_some() { 1 } else { 0 };
+            if output::is_json() {
+                let mut data = JsonObject::new()
+                    .str("trits", &w.to_string())
+                    .int("decimal", w.to_decimal() as i64)
+                    .object("opcode", opcode_json(s, g, c));
+                if let Some(m) = meta {
+                    data = data.str("name_kr", m.name_kr).str("name_en", m.name_en);
+                }
+                let errors: Vec<String> = meta.is_none().then(|| format!("미등록 opcode ({},{},{})", s, g, c)).into_iter().collect();
+                JsonObject::envelope("decode", state, &errors, data).emit();
+            } else {
+                let name = meta.map(|m| format!("{} ({})", m.name_kr, m.name_en)).unwrap_or("(미등록)".into());
+                println!("6트릿:   {}", w);
+                println!("10진수:  {}", w.to_decimal());
+                println!("opcode:  ({},{},{}) → {}", s, g, c, name);
+            }
+            state
         }
-        None => eprintln!("6트릿 파싱 실패: '{}' (T/O/P 6문자 필요)", input),
+        None => fail("decode", &format!("6트릿 파싱 실패: '{}' (T/O/P 6문자 필요)", input)),
     }
 }
 
-fn show_help() {
-    println!("CROWNIN TVM v0.4.0 — 균형3진 Meta-Kernel + 생태계");
-    println!();
-    println!("사용법:");
-    println!("  crowni-tvm                 REPL (대화형) 모드");
-    println!("  crowni-tvm run <파일>       .hsn 파일 실행");
-    println!("  crowni-tvm hanseon <파일>   한선어 컴파일+실행");
-    println!("  crowni-tvm compile <파일>   .hsn → .wasm 컴파일");
-    println!("  crowni-tvm bytecode <파일>  .hsn → .크라운 바이트코드");
-    println!("  crowni-tvm debug <파일>     디버그 모드 실행");
-    println!("  crowni-tvm demo            TVM 데모");
-    println!("  crowni-tvm kernel          Meta-Kernel 데모");
-    println!("  crowni-tvm protocol        CTP 프로토콜 데모");
-    println!("  crowni-tvm fpga            FPGA 로드맵 데모");
-    println!("  crowni-tvm wasm            WASM 변환 데모");
-    println!("  crowni-tvm car             CAR (Application Runtime) 데모");
-    println!("  crowni-tvm sectors         729 전체 섹터 데모");
-    println!("  crowni-tvm hanseon         한선어 컴파일러 데모");
-    println!("  crowni-tvm server          웹서버 데모");
-    println!("  crowni-tvm llm             LLM 호출기 데모");
-    println!("  crowni-tvm cpm             패키지 매니저 데모");
-    println!("  crowni-tvm test            Trit 테스트 프레임워크 데모");
-    println!("  crowni-tvm debug           디버거 데모");
-    println!("  crowni-tvm store           영속화 레이어 데모");
-    println!("  crowni-tvm log             이벤트 로그 데모");
-    println!("  crowni-tvm node            분산 노드 데모");
-    println!("  crowni-tvm token           3진 토큰 시스템 데모");
-    println!("  crowni-tvm wasm-node       WASM 브라우저 노드 데모");
-    println!("  crowni-tvm consensus       로컬 3진 합의 데모 (OpenClaw)");
-    println!("  crowni-tvm industry        산업 적용 데모 (의료/교육/트레이딩)");
-    println!("  crowni-tvm platform        통합 플랫폼 데모 (Git+Deploy+DB+Runtime+Web3)");
-    println!("  crowni-tvm browser         3진 웹브라우저 데모");
-    println!("  crowni-tvm website         3진 웹사이트 데모");
-    println!("  crowni-tvm os              CrownyOS 데모 (프로세스/파일/쉘)");
-    println!("  crowni-tvm chain           CrownyChain 블록체인 데모 (PoT)");
-    println!("  crowni-tvm live            OpenClaw 실제 HTTP 합의 데모");
-    println!("  crowni-tvm dex             CrownyDEX 탈중앙 거래소 데모");
-    println!("  crowni-tvm bridge          CrownyBridge 크로스체인 브릿지 데모");
-    println!("  crowni-tvm nft             CrownyNFT 마켓플레이스 데모");
-    println!("  crowni-tvm contract        스마트 컨트랙트 VM 데모");
-    println!("  crowni-tvm contract        스마트 컨트랙트 VM 데모");
-    println!("  crowni-tvm all             전체 데모");
-    println!("  crowni-tvm info            명령어 목록");
-    println!("  crowni-tvm trit <정수>      10진→균형3진 변환");
-    println!("  crowni-tvm decode <TTT>     6트릿→opcode 디코딩");
-    println!("  crowni-tvm help            이 도움말");
+// ═══════════════════════════════════════════════
+// 노트북 (.md 속 한선어 셀 실행)
+// ═══════════════════════════════════════════════
+
+/// 합의를 돌리고 근거 보고서 출력 · 저장 — live 면 OpenClaw HTTP, 아니면 로컬 시뮬레이션. 반환값은 합의 판정
+fn run_provenance(query: &str, live: bool, md: Option<&str>, html: Option<&str>) -> i8 {
+    let mut log = trit_log::TritEventLog::new();
+    log.task_start(1, query);
+    let report = if live {
+        let result = live_consensus::LiveConsensus::new().execute(query);
+        for v in &result.votes {
+            log.consensus_vote(1, &v.node_name, v.trit);
+        }
+        log.task_end(1, car::TritState::from_i8(result.consensus_trit));
+        provenance::ProvenanceReport::from_live(&result)
+    } else {
+        let result = local_consensus::LocalConsensusEngine::openclaw_default().simulate_consensus(query);
+        for r in &result.responses {
+            log.consensus_vote(result.request_id as u32, &r.endpoint_name, r.trit);
+        }
+        log.task_end(1, car::TritState::from_i8(result.final_trit));
+        provenance::ProvenanceReport::from_local(&result)
+    }.with_audit(&log, provenance::DEFAULT_AUDIT_WINDOW_MS);
+
+    for (out, body) in [(md, report.to_markdown()), (html, report.to_html())] {
+        if let Some(out) = out {
+            if let Err(e) = fs::write(out, body) {
+                return fail("provenance", &format!("쓰기 실패 '{}': {}", out, e));
+            }
+        }
+    }
+    if output::is_json() {
+        report.to_json().emit();
+    } else if md.is_none() && html.is_none() {
+        print!("{}", report.to_markdown());
+    } else {
+        say!("{} — {} 투표 · 감사 {}건", report.title(), report.votes.len(), report.audit.len());
+    }
+    report.state
+}
+
+// ═══════════════════════════════════════════════
+// 관리자 상태 덮어쓰기 (override · override apply · override token)
+// ═══════════════════════════════════════════════
+
+/// 서버가 덮어쓰기를 기록하는 감사 로그
+const OVERRIDE_AUDIT: &str = ".crowny/override-audit.tsv";
+
+/// 감사 로그 출력 · 체인 검증 — P 정상, O 비어 있음, T 변조
+fn run_override_audit(path: Option<&str>) -> i8 {
+    let path = path.unwrap_or(OVERRIDE_AUDIT);
+    let log = match admin_override::AuditLog::open(path) {
+        Ok(l) => l,
+        Err(e) => return fail("override", &e),
+    };
+    let verified = log.verify();
+    let state = match &verified {
+        Ok(0) => 0,
+        Ok(_) => 1,
+        Err(_) => -1,
+    };
+    if output::is_json() {
+        log.to_json().str("command", "override").str("audit", path).emit();
+        return state;
+    }
+    println!("덮어쓰기 감사 로그 {}건 — {}", log.entries().len(), path);
+    for e in log.entries() {
+        println!("  {}", e);
+    }
+    match verified {
+        Ok(n) => println!("  [{}] 해시 체인 {}건 검증 (head {})", output::trit_symbol(state), n, &log.head()[..16]),
+        Err(e) => eprintln!("  [T] {}", e),
+    }
+    state
+}
+
+/// 실행 중인 서버(server --listen)에 덮어쓰기 요청 — 감사 기록은 서버 쪽 파일에
+fn run_override_apply(kind: &str, id: &str, target: &str, reason: &str, server: Option<&str>) -> i8 {
+    let request = match (kind.parse(), admin_override::parse_target_state(target)) {
+        (Ok(kind), Ok(state)) => admin_override::OverrideRequest {
+            kind, target: id.to_string(), state, justification: reason.to_string(),
+        },
+        (Err(e), _) | (_, Err(e)) => return fail("override apply", &e),
+    };
+    let token = match env::var("CROWNY_ADMIN_TOKEN") {
+        Ok(t) if !t.is_empty() => t,
+        _ => return fail("override apply", "CROWNY_ADMIN_TOKEN 환경 변수가 필요합니다 (override token으로 발급)"),
+    };
+    let url = server.map(String::from)
+        .or_else(|| env::var("CROWNY_SERVER").ok())
+        .unwrap_or_else(|| "http://127.0.0.1:7293".to_string());
+    match admin_override::submit_remote(&url, &token, &request, std::time::Duration::from_secs(10)) {
+        Ok(body) => {
+            if output::is_json() {
+                println!("{}", body);
+            } else {
+                say!("[P] {} {} → {} — {}", request.kind, request.target, output::trit_symbol(request.state), url);
+            }
+            request.state
+        }
+        Err(e) => fail("override apply", &e),
+    }
+}
+
+/// 운영자 토큰 — admin.override 범위만 (와일드카드 토큰은 서버가 거부)
+fn run_override_token(operator: &str, days: u64) -> i8 {
+    let signer = match env::var("CROWNY_ADMIN_SECRET") {
+        Ok(s) if !s.is_empty() => capability::TokenSigner::new(&s),
+        _ => return fail("override token", "CROWNY_ADMIN_SECRET 환경 변수가 필요합니다"),
+    };
+    let token = signer.issue(operator, &[admin_override::OVERRIDE_SCOPE], days * 24 * 60 * 60 * 1000).encode();
+    if output::is_json() {
+        JsonObject::new().str("command", "override token").trit("state", 1).str("operator", operator).str("token", &token).emit();
+    } else {
+        println!("{}", token);
+    }
+    1
+}
+
+fn run_notebook(path: &str, write: bool, html: Option<&str>) -> i8 {
+    let source = match fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => return fail("notebook", &format!("파일 읽기 실패 '{}': {}", path, e)),
+    };
+    let nb = notebook::Notebook::parse(&source);
+    let results = nb.execute();
+    let state = notebook::overall_state(&results);
+
+    if write {
+        if let Err(e) = fs::write(path, nb.render_markdown(&results)) {
+            return fail("notebook", &format!("쓰기 실패 '{}': {}", path, e));
+        }
+    }
+    if let Some(out) = html {
+        let title = std::path::Path::new(path).file_stem()
+            .map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| path.to_string());
+        if let Err(e) = fs::write(out, nb.render_html(&title, &results)) {
+            return fail("notebook", &format!("쓰기 실패 '{}': {}", out, e));
+        }
+    }
+
+    if output::is_json() {
+        let cells = results.iter().map(|r| {
+            let mut obj = JsonObject::new()
+                .int("line", r.line as i64)
+                .trit("state", r.state)
+                .int("cycles", r.cycles as i64)
+                .strs("output", &r.output);
+            if let Some(top) = &r.top {
+                obj = obj.str("top", top);
+            }
+            if let Some(e) = &r.error {
+                obj = obj.str("error", e);
+            }
+            obj
+        }).collect();
+        JsonObject::new()
+            .str("command", "notebook")
+            .str("file", path)
+            .trit("state", state)
+            .objects("cells", cells)
+            .emit();
+    } else {
+        println!("=== 노트북: {} ({} 셀) ===", path, results.len());
+        for r in &results {
+            println!("  {}줄  {}", r.line, r.summary());
+            for l in &r.output {
+                println!("        {}", l);
+            }
+        }
+        if write {
+            println!("✓ 결과 되쓰기: {}", path);
+        }
+        if let Some(out) = html {
+            println!("✓ HTML 저장: {}", out);
+        }
+    }
+    state
+}
+
+// ═══════════════════════════════════════════════
+// 예제 레지스트리 (examples/*.hsn 내장)
+// ═══════════════════════════════════════════════
+
+fn example_json(run: &examples::ExampleRun) -> JsonObject {
+    let mut obj = JsonObject::new()
+        .str("name", &run.name)
+        .trit("state", run.state)
+        .int("cycles", run.cycles as i64)
+        .strs("output", &run.output)
+        .strs("expected", &run.expected);
+    if let Some(e) = &run.error {
+        obj = obj.str("error", e);
+    }
+    obj
+}
+
+fn print_example_run(run: &examples::ExampleRun) {
+    let sym = output::trit_symbol(run.state);
+    match (&run.error, run.first_mismatch()) {
+        (Some(e), _) => println!("  [{}] {:16} 오류: {}", sym, run.name, e),
+        (None, _) if run.state == 0 => println!("  [{}] {:16} 기대 출력 없음 ({}줄 출력)", sym, run.name, run.output.len()),
+        (None, Some((line, exp, act))) => println!("  [{}] {:16} {}번째 줄 — 기대:{} 실제:{}", sym, run.name, line, exp, act),
+        (None, None) => println!("  [{}] {:16} {}줄 일치 · {}사이클", sym, run.name, run.output.len(), run.cycles),
+    }
+}
+
+/// 이름 없으면 목록, --all이면 전체 스모크 테스트, .hsn 경로면 파일을 예제로 실행
+fn run_example(name: Option<&str>, all: bool) -> i8 {
+    let runs: Vec<examples::ExampleRun> = match (name, all) {
+        (_, true) => examples::registry().iter().map(examples::run).collect(),
+        (Some(n), false) => match examples::find(n) {
+            Some(ex) => vec![examples::run(ex)],
+            None if n.ends_with(".hsn") => match fs::read_to_string(n) {
+                Ok(src) => vec![examples::run_source(n, &src)],
+                Err(e) => return fail("example", &format!("파일 읽기 실패 '{}': {}", n, e)),
+            },
+            None => return fail("example", &format!("예제 없음: {} ('crowni-tvm example'로 목록 확인)", n)),
+        },
+        (None, false) => {
+            if output::is_json() {
+                let list = examples::registry().iter()
+                    .map(|e| JsonObject::new().str("name", e.name).str("alias", e.alias).str("title", e.title))
+                    .collect();
+                JsonObject::new().str("command", "example").trit("state", 1).objects("examples", list).emit();
+            } else {
+                println!("내장 예제 ({}개) — crowni-tvm example <이름>", examples::registry().len());
+                for e in examples::registry() {
+                    println!("  {:16} {:8} {}", e.name, e.alias, e.title);
+                }
+            }
+            return 1;
+        }
+    };
+
+    let state = if runs.iter().any(|r| r.state == -1) { -1 } else if runs.iter().all(|r| r.state == 1) { 1 } else { 0 };
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", "example")
+            .trit("state", state)
+            .objects("runs", runs.iter().map(example_json).collect())
+            .emit();
+    } else {
+        if let [single] = runs.as_slice() {
+            for line in &single.output {
+                println!("{}", line);
+            }
+            println!();
+        }
+        for r in &runs {
+            print_example_run(r);
+        }
+    }
+    state
+}
+
+/// 경로가 없으면 현재 디렉터리 전체 — dry-run에서 변경 대기가 있으면 O
+fn run_migrate(paths: &[String], dry_run: bool) -> i8 {
+    let registry = migrations::MigrationRegistry::builtin();
+    let roots: Vec<std::path::PathBuf> = if paths.is_empty() {
+        vec![".".into()]
+    } else {
+        paths.iter().map(Into::into).collect()
+    };
+    let files = migrations::collect_files(&registry, &roots);
+
+    let mut reports = Vec::new();
+    let mut errors = Vec::new();
+    for file in &files {
+        match migrations::migrate_file(&registry, file, dry_run) {
+            Ok(r) => reports.push(r),
+            Err(e) => errors.push(e),
+        }
+    }
+    let state = if !errors.is_empty() { -1 } else { reports.iter().map(|r| r.state()).min().unwrap_or(1) };
+
+    if output::is_json() {
+        let items = reports.iter().map(|r| {
+            let obj = JsonObject::new()
+                .str("path", &r.path.display().to_string())
+                .str("format", r.format)
+                .int("from", r.from as i64)
+                .int("to", r.to as i64)
+                .trit("state", r.state())
+                .strs("steps", &r.steps)
+                .int("bytes_before", r.bytes_before as i64)
+                .int("bytes_after", r.bytes_after as i64);
+            match &r.backup {
+                Some(b) => obj.str("backup", &b.display().to_string()),
+                None => obj,
+            }
+        }).collect();
+        JsonObject::new()
+            .str("command", "migrate")
+            .trit("state", state)
+            .bool("dry_run", dry_run)
+            .objects("files", items)
+            .strs("errors", &errors)
+            .emit();
+        return state;
+    }
+
+    println!("마이그레이션{} — 대상 {}개", if dry_run { " (dry-run)" } else { "" }, files.len());
+    for r in &reports {
+        if r.steps.is_empty() {
+            println!("  [P] {} — {} v{} 최신", r.path.display(), r.format, r.to);
+            continue;
+        }
+        println!("  [{}] {} — {} v{} → v{} ({} → {}바이트)", output::trit_symbol(r.state()),
+            r.path.display(), r.format, r.from, r.to, r.bytes_before, r.bytes_after);
+        for step in &r.steps {
+            println!("        {}", step);
+        }
+        if let Some(b) = &r.backup {
+            println!("        백업: {}", b.display());
+        }
+    }
+    for e in &errors {
+        eprintln!("  [T] {}", e);
+    }
+    state
+}
+
+// ═══════════════════════════════════════════════
+// 예약 작업 (jobs · jobs add/remove/enable/disable/run)
+// ═══════════════════════════════════════════════
+
+fn run_jobs(action: Option<&str>, args: &[String], store: Option<&str>, owner: Option<&str>) -> i8 {
+    let store = store.unwrap_or(".crowny/jobs.tsv");
+    let mut sched = match cron::JobScheduler::open(store) {
+        Ok(s) => s,
+        Err(e) => return fail("jobs", &e),
+    };
+    let now = cron::now_ms();
+    let arg = |i: usize| args.get(i).map(String::as_str).unwrap_or_default();
+
+    let (state, ran) = match action {
+        Some("add") => {
+            let source = match fs::read_to_string(arg(2)) {
+                Ok(s) => s,
+                Err(e) => return fail("jobs add", &format!("파일 읽기 실패 '{}': {}", arg(2), e)),
+            };
+            match sched.add(arg(0), arg(1), &source, owner.unwrap_or("local"), now) {
+                Ok(id) => {
+                    if !output::is_json() {
+                        println!("  [P] 작업 #{} '{}' 등록", id, arg(0));
+                    }
+                    (1, Vec::new())
+                }
+                Err(e) => return fail("jobs add", &e),
+            }
+        }
+        Some("remove") => {
+            let Ok(id) = arg(0).parse::<u64>() else {
+                return fail("jobs remove", &format!("작업 ID는 정수: {}", arg(0)));
+            };
+            match sched.remove(id) {
+                Ok(true) => (1, Vec::new()),
+                Ok(false) => return fail("jobs remove", &format!("작업 없음: {}", id)),
+                Err(e) => return fail("jobs remove", &e),
+            }
+        }
+        Some(toggle @ ("enable" | "disable")) => {
+            let command = format!("jobs {}", toggle);
+            let Ok(id) = arg(0).parse::<u64>() else {
+                return fail(&command, &format!("작업 ID는 정수: {}", arg(0)));
+            };
+            match sched.set_enabled(id, toggle == "enable", now) {
+                Ok(true) => (1, Vec::new()),
+                Ok(false) => return fail(&command, &format!("작업 없음: {}", id)),
+                Err(e) => return fail(&command, &e),
+            }
+        }
+        Some("run") => {
+            let mut car = car::CrownyRuntime::new();
+            match sched.run_due_with(&mut car, now) {
+                Ok(ran) => (ran.iter().map(|(_, t)| *t).min().unwrap_or(0), ran),
+                Err(e) => return fail("jobs run", &e),
+            }
+        }
+        _ => (if sched.jobs().is_empty() { 0 } else { 1 }, Vec::new()),
+    };
+
+    if output::is_json() {
+        let command = action.map(|a| format!("jobs {}", a)).unwrap_or_else(|| "jobs".into());
+        let ran = ran.iter().map(|(id, t)| JsonObject::new().int("id", *id as i64).trit("state", *t)).collect();
+        JsonObject::new()
+            .str("command", &command)
+            .trit("state", state)
+            .str("store", store)
+            .objects("ran", ran)
+            .object("scheduler", sched.to_json())
+            .emit();
+        return state;
+    }
+
+    for (id, t) in &ran {
+        let name = sched.get(*id).map(|j| j.name.as_str()).unwrap_or_default();
+        println!("  [{}] 실행 #{} {}", output::trit_symbol(*t), id, name);
+    }
+    if action == Some("run") && ran.is_empty() {
+        println!("  [O] 실행할 작업 없음");
+    }
+    println!("예약 작업 {}개 — {}", sched.jobs().len(), store);
+    for job in sched.jobs() {
+        let next = match job.next_run {
+            Some(t) if job.enabled => cron::format_local(t, sched.utc_offset_min),
+            _ => "-".into(),
+        };
+        let last = job.last_state().map(output::trit_symbol).unwrap_or("-");
+        println!("  #{:<3} {:<16} {:<20} 다음 {}  마지막 [{}]  실행 {}회",
+            job.id, job.name, job.expr.source, next, last, job.runs);
+    }
+    state
+}
+
+// ═══════════════════════════════════════════════
+// 콘텐츠 저장소 (content · content put/pin/unpin/gc)
+// ═══════════════════════════════════════════════
+
+/// 서버(GET /content/<hash>)와 같은 디렉터리 — 서버는 시작할 때 읽어 들인다
+fn run_content(action: Option<&str>, args: &[String], dir: Option<&str>, mime: Option<&str>, pin: Option<&str>) -> i8 {
+    let dir = dir.unwrap_or(".crowny/content");
+    let mut cs = match trit_store::TritStore::open(dir) {
+        Ok(store) => content::ContentStore::with_store(store),
+        Err(e) => return fail("content", &e),
+    };
+    let command = action.map(|a| format!("content {}", a)).unwrap_or_else(|| "content".into());
+    let arg = |i: usize| args.get(i).map(String::as_str).unwrap_or_default();
+    // pin/unpin은 해시 대신 crwn://content/<hash> 주소도 받는다
+    let hash = content::parse_uri(arg(0)).unwrap_or(arg(0));
+
+    let outcome = match action {
+        Some("put") => fs::read(arg(0))
+            .map_err(|e| format!("파일 읽기 실패 '{}': {}", arg(0), e))
+            .and_then(|bytes| {
+                let mime = mime.unwrap_or("application/octet-stream");
+                match pin {
+                    Some(owner) => cs.put_pinned(&bytes, mime, owner),
+                    None => cs.put(&bytes, mime),
+                }.map_err(|e| e.to_string())
+            })
+            .map(|hash| (format!("저장 {}", content::uri(&hash)), JsonObject::new().str("uri", &content::uri(&hash)))),
+        Some("pin") => cs.pin(hash, arg(1))
+            .map(|_| (format!("고정 {} ← {}", hash, arg(1)), JsonObject::new().str("hash", hash).str("owner", arg(1))))
+            .map_err(|e| e.to_string()),
+        Some("unpin") => cs.unpin(hash, arg(1))
+            .map(|left| (format!("고정 해제 {} — 남은 고정 {}", hash, left), JsonObject::new().str("hash", hash).int("pins", left as i64)))
+            .map_err(|e| e.to_string()),
+        Some("gc") => {
+            let report = cs.gc();
+            Ok((format!("회수 {}개 · {}B", report.removed, report.bytes_freed),
+                JsonObject::new().int("removed", report.removed as i64).int("bytes_freed", report.bytes_freed as i64)))
+        }
+        _ => Ok((String::new(), JsonObject::new())),
+    };
+    let (message, detail) = match outcome {
+        Ok(done) => done,
+        Err(e) => return fail(&command, &e),
+    };
+    if let Some(e) = cs.store.persist_error() {
+        return fail(&command, &format!("{}: {}", dir, e));
+    }
+
+    let list = cs.list();
+    let state = if list.is_empty() { 0 } else { 1 };
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", &command)
+            .trit("state", state)
+            .str("dir", dir)
+            .object("result", detail)
+            .objects("content", list.iter().map(|c| c.to_json()).collect())
+            .emit();
+        return state;
+    }
+    if action.is_some() {
+        println!("  [P] {}", message);
+    }
+    println!("콘텐츠 {}개 — {}", list.len(), dir);
+    for c in &list {
+        let pins = if c.pins.is_empty() { "-".to_string() } else { c.pins.join(",") };
+        println!("  {}  {:<24} {:>8}B  고정 {}", &c.hash[..16], c.mime, c.bytes.len(), pins);
+    }
+    state
+}
+
+/// 계정 저장소 조작 후 목록 (show는 상세)
+fn run_account(action: Option<&str>, args: &[String], dir: Option<&str>) -> i8 {
+    let dir = dir.unwrap_or(ACCOUNTS_DIR);
+    let mut reg = match trit_store::TritStore::open(dir) {
+        Ok(store) => account::AccountRegistry::open(store),
+        Err(e) => return fail("account", &e),
+    };
+    let command = action.map(|a| format!("account {}", a)).unwrap_or_else(|| "account".into());
+    let arg = |i: usize| args.get(i).map(String::as_str).unwrap_or_default();
+    let hex_key = |s: &str| crypto::from_hex(s).filter(|k| !k.is_empty()).ok_or_else(|| format!("공개키는 hex: {}", s));
+    // 계정 인자는 주소 또는 별칭
+    let found = reg.resolve(arg(0)).map(|a| a.id.clone()).ok_or_else(|| format!("계정 없음: {}", arg(0)));
+
+    let outcome = match action {
+        Some("create") => hex_key(arg(1)).and_then(|key| reg.create(arg(0), &key))
+            .map(|id| (format!("계정 생성 {}", id), JsonObject::new().str("id", &id))),
+        Some("show") => found.map(|id| (String::new(), JsonObject::new().str("id", &id))),
+        Some("link") => found.and_then(|id| reg.link_alias(&id, arg(1))
+            .map(|_| (format!("별칭 {} → {}", arg(1), id), JsonObject::new().str("id", &id).str("alias", arg(1))))),
+        Some("key") => found.and_then(|id| hex_key(arg(2)).and_then(|key| reg.add_key(&id, arg(1), &key))
+            .map(|_| (format!("키 추가 {} ← {}", id, arg(1)), JsonObject::new().str("id", &id).str("key", arg(1))))),
+        Some("revoke") => found.and_then(|id| reg.revoke_key(&id, arg(1))
+            .map(|_| (format!("키 폐기 {} — {}", id, arg(1)), JsonObject::new().str("id", &id).str("key", arg(1))))),
+        Some("set") => found.and_then(|id| match arg(1) {
+            "name" => reg.set_display_name(&id, arg(2)),
+            key => reg.set_meta(&id, key, arg(2)),
+        }.map(|_| (format!("{} {} = {}", id, arg(1), arg(2)), JsonObject::new().str("id", &id).str(arg(1), arg(2))))),
+        Some("reputation") => found.and_then(|id| arg(1).parse::<i64>()
+            .map_err(|_| format!("변화량은 정수: {}", arg(1)))
+            .and_then(|delta| reg.adjust_reputation(&id, delta))
+            .map(|trit| (format!("평판 {} → {}", id, output::trit_symbol(trit)), JsonObject::new().str("id", &id).trit("trit", trit)))),
+        _ => Ok((String::new(), JsonObject::new())),
+    };
+    let (message, detail) = match outcome {
+        Ok(done) => done,
+        Err(e) => return fail(&command, &e),
+    };
+    if let Some(e) = reg.store().and_then(|s| s.persist_error()) {
+        return fail(&command, &format!("{}: {}", dir, e));
+    }
+
+    if action == Some("show") {
+        let acc = reg.resolve(arg(0)).expect("위에서 찾은 계정");
+        let chain = chain::sample_chain();
+        let holdings = reg.holdings(&acc.id, &[&chain]);
+        if output::is_json() {
+            acc.to_json()
+                .str("command", &command)
+                .objects("holdings", holdings.iter()
+                    .map(|(l, a, n)| JsonObject::new().str("ledger", l).str("asset", a).int("amount", *n as i64))
+                    .collect())
+                .emit();
+            return acc.trit();
+        }
+        println!("[{}] {}  {}  평판 {}", output::trit_symbol(acc.trit()), acc.id, acc.display_name, acc.reputation);
+        println!("  이름: {}", acc.names().join(", "));
+        for k in &acc.keys {
+            println!("  키 {:<10} {}{}", k.label, k.public_hex, if k.revoked { " (폐기)" } else { "" });
+        }
+        for (k, v) in &acc.metadata {
+            println!("  {} = {}", k, v);
+        }
+        for (ledger, asset, amount) in &holdings {
+            println!("  {} {:<12} {}", ledger, asset, amount);
+        }
+        return acc.trit();
+    }
+
+    let state = if reg.is_empty() { 0 } else { 1 };
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", &command)
+            .trit("state", state)
+            .str("dir", dir)
+            .object("result", detail)
+            .objects("accounts", reg.accounts().map(|a| a.to_json()).collect())
+            .emit();
+        return state;
+    }
+    if action.is_some() {
+        println!("  [P] {}", message);
+    }
+    println!("계정 {}개 — {}", reg.len(), dir);
+    for a in reg.accounts() {
+        let aliases = if a.aliases.is_empty() { "-".to_string() } else { a.aliases.join(",") };
+        println!("  [{}] {}  {:<16} 별칭 {}  키 {}", output::trit_symbol(a.trit()), a.id, a.display_name, aliases, a.active_keys().count());
+    }
+    state
+}
+
+// ═══════════════════════════════════════════════
+// 샘플 체인 조회 (chain block/balance/validators/verify)
+// ═══════════════════════════════════════════════
+
+/// 번호가 없으면 최신 블록
+fn chain_block(index: Option<&str>) -> i8 {
+    let chain = chain::sample_chain();
+    let block = match index {
+        None => chain.latest(),
+        Some(raw) => match raw.parse::<usize>() {
+            Ok(i) => chain.blocks.get(i),
+            Err(_) => return fail("chain block", &format!("블록 번호는 0 이상의 정수: {}", raw)),
+        },
+    };
+    let block = match block {
+        Some(b) => b,
+        None => return fail("chain block", &format!("블록 없음: #{} (높이 {})", index.unwrap_or("-"), chain.height())),
+    };
+
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", "chain block")
+            .trit("state", 1)
+            .object("block", block.to_json())
+            .objects("attestations", block.transactions.iter()
+                .filter_map(attest::Attestation::from_tx).map(|a| a.to_json()).collect())
+            .emit();
+    } else {
+        println!("{}", block);
+        println!("  밸리데이터: {} | 머클: {}", block.validator, block.merkle_root);
+        println!("  prev: {}", block.prev_hash);
+        println!("  hash: {}", block.hash);
+        for tx in &block.transactions {
+            println!("  {}", tx);
+            if let Some(att) = attest::Attestation::from_tx(tx) {
+                println!("      {}", att);
+            }
+        }
+    }
+    1
+}
+
+/// 잔액 0인 미지 계정은 O(알 수 없음)
+/// .crowny/accounts에 있는 주소 · 별칭이면 그 계정의 모든 이름으로 합산
+fn chain_balance(address: &str) -> i8 {
+    let chain = chain::sample_chain();
+    let registry = std::path::Path::new(ACCOUNTS_DIR).exists()
+        .then(|| trit_store::TritStore::open(ACCOUNTS_DIR).ok().map(account::AccountRegistry::open))
+        .flatten();
+    let account = registry.as_ref().and_then(|r| r.resolve(address));
+    let (balance, staked, known) = match (&registry, account) {
+        (Some(reg), Some(acc)) => {
+            let h = reg.holdings(&acc.id, &[&chain]);
+            let sum = |asset: &str| h.iter().filter(|(_, a, _)| a == asset).map(|t| t.2).sum::<u64>();
+            (sum("CRWN"), sum("CRWN:staked"), acc.names().iter().any(|n| chain.balances.contains_key(*n)))
+        }
+        _ => (chain.balance_of(address), chain.stakes.get(address).copied().unwrap_or(0), chain.balances.contains_key(address)),
+    };
+    let state = if known { 1 } else { 0 };
+    if output::is_json() {
+        let mut json = JsonObject::new()
+            .str("command", "chain balance")
+            .trit("state", state)
+            .str("address", address);
+        if let Some(acc) = account {
+            json = json.str("account", &acc.id);
+        }
+        json.int("balance", balance as i64).int("staked", staked as i64).emit();
+    } else {
+        match account {
+            Some(acc) => println!("[{}] {} ({}) {} CRWN", output::trit_symbol(state), address, acc.id, balance),
+            None => println!("[{}] {} {} CRWN", output::trit_symbol(state), address, balance),
+        }
+    }
+    state
+}
+
+fn chain_validators() -> i8 {
+    let chain = chain::sample_chain();
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", "chain validators")
+            .trit("state", 1)
+            .objects("validators", chain.validators.iter().map(|v| v.to_json()).collect())
+            .emit();
+    } else {
+        for v in &chain.validators {
+            println!("{}", v);
+        }
+    }
+    1
+}
+
+/// 이름을 주면 그 파라미터의 값 · 발효 예정 · 변경 이력만
+fn chain_params(name: Option<&str>) -> i8 {
+    let chain = chain::sample_chain();
+    let reg = chain.params.borrow();
+    if let Some(name) = name {
+        let Some(value) = reg.try_get(name) else {
+            return fail("chain params", &format!("알 수 없는 파라미터: {}", name));
+        };
+        let scheduled: Vec<(u64, u64)> = reg.scheduled().into_iter().filter(|(_, n, _)| *n == name).map(|(h, _, v)| (h, v)).collect();
+        let history = reg.history(name);
+        if output::is_json() {
+            JsonObject::new()
+                .str("command", "chain params")
+                .trit("state", 1)
+                .str("name", name)
+                .int("value", value as i64)
+                .objects("scheduled", scheduled.iter()
+                    .map(|(h, v)| JsonObject::new().int("value", *v as i64).int("effective_height", *h as i64)).collect())
+                .objects("history", history.iter().map(|c| c.to_json()).collect())
+                .emit();
+        } else {
+            println!("{} = {}  {}", name, value, params::spec(name).map_or("", |s| s.doc));
+            for (height, value) in scheduled {
+                println!("  [O] #{} → {}", height, value);
+            }
+            for change in history {
+                println!("  [P] {}", change);
+            }
+        }
+        return 1;
+    }
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", "chain params")
+            .trit("state", 1)
+            .object("registry", reg.to_json())
+            .emit();
+    } else {
+        println!("파라미터 (높이 #{})", reg.height());
+        for (name, value) in reg.entries() {
+            println!("  {:<30} {:>8}  {}", name, value, params::spec(name).map_or("", |s| s.doc));
+        }
+        for (height, name, value) in reg.scheduled() {
+            println!("  [O] #{} {} → {}", height, name, value);
+        }
+        for change in &reg.audit {
+            println!("  [P] {}", change);
+        }
+    }
+    1
+}
+
+/// 라이트 클라이언트 관점 — 증명이 헤더 루트로 올라가면 P, 아니면 T
+fn chain_proof(block: &str, tx: &str) -> i8 {
+    let chain = chain::sample_chain();
+    let tx_hash = match (block.parse::<usize>(), tx.parse::<usize>()) {
+        (Ok(b), Ok(t)) => match chain.blocks.get(b).and_then(|b| b.transactions.get(t)) {
+            Some(tx) => tx.hash.clone(),
+            None => return fail("chain proof", &format!("트랜잭션 없음: 블록 #{} · {}번 (높이 {})", b, t, chain.height())),
+        },
+        _ => return fail("chain proof", &format!("블록 · TX 번호는 0 이상의 정수: {} {}", block, tx)),
+    };
+    let proof = match chain.prove_inclusion(&tx_hash) {
+        Some(p) => p,
+        None => return fail("chain proof", &format!("트랜잭션 없음: {}", tx_hash)),
+    };
+    let root = chain.merkle_root_at(proof.block_index).unwrap_or_default();
+    let mut light = wasm_node::BrowserNode::new("light", wasm_node::BrowserNodeType::Light);
+    let state = if light.verify_inclusion(&proof, root) { 1 } else { -1 };
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", "chain proof")
+            .trit("state", state)
+            .object("proof", proof.to_json())
+            .emit();
+    } else {
+        println!("[{}] TX {} ∈ 블록 #{} — {}단계", output::trit_symbol(state), proof.tx_hash, proof.block_index, proof.steps.len());
+        for (i, step) in proof.steps.iter().enumerate() {
+            println!("  {}. 자리 {} · 형제 {}", i + 1, step.position, step.siblings.join(", "));
+        }
+        println!("  루트: {}", root);
+        println!("  라이트 노드: 확인 {} · 거부 {}", light.stats.proofs_verified, light.stats.rejected_messages);
+    }
+    state
+}
+
+fn chain_verify() -> i8 {
+    let chain = chain::sample_chain();
+    let (valid, count) = chain.verify_chain();
+    let state = if valid { 1 } else { -1 };
+    if output::is_json() {
+        JsonObject::new()
+            .str("command", "chain verify")
+            .trit("state", state)
+            .int("height", chain.height() as i64)
+            .int("verified", count as i64)
+            .emit();
+    } else {
+        println!("[{}] 체인 무결성: {} ({} 블록 검증)", output::trit_symbol(state),
+            if valid { "유효" } else { "무효" }, count);
+    }
+    state
+}
+
+// ═══════════════════════════════════════════════
+// 런타임 설정 검증
+// ═══════════════════════════════════════════════
+
+fn check_config(path: &str) -> i8 {
+    match config::ConfigManager::load_file(std::path::Path::new(path)) {
+        Ok(mgr) => {
+            let entries = mgr.current().entries();
+            if output::is_json() {
+                let values = entries.iter()
+                    .fold(JsonObject::new(), |obj, (key, val)| obj.str(key, val));
+                JsonObject::new()
+                    .str("command", "config")
+                    .str("file", path)
+                    .trit("state", 1)
+                    .object("entries", values)
+                    .emit();
+            } else {
+                println!("[P] {} 유효", path);
+                for (key, val) in entries {
+                    println!("  {} = {}", key, val);
+                }
+            }
+            1
+        }
+        Err(errors) => {
+            if output::is_json() {
+                JsonObject::new()
+                    .str("command", "config")
+                    .str("file", path)
+                    .trit("state", -1)
+                    .strs("errors", &errors)
+                    .emit();
+            } else {
+                println!("[T] {} 거부", path);
+                for e in errors {
+                    println!("  - {}", e);
+                }
+            }
+            -1
+        }
+    }
 }
 
 // ═══════════════════════════════════════════════
@@ -560,7 +1849,7 @@ fn show_help() {
 // ═══════════════════════════════════════════════
 
 fn run_kernel_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ Crowny Meta-Kernel 데모 ═══\n");
 
     // ── 커널 부팅 ──
@@ -648,6 +1937,23 @@ fn run_kernel_demo() {
         kernel.transaction.get("이름").unwrap_or("?"));
     println!();
 
+    // 동시 변경 충돌 시나리오
+    println!("  [시나리오C: 동시 변경 충돌]");
+    let snap = kernel.transaction.snapshot();
+    let ta = kernel.transaction.begin("버전 올림A");
+    let tb = kernel.transaction.begin("버전 올림B");
+    let seen = kernel.transaction.read(ta, "버전").unwrap().unwrap_or_default();
+    kernel.transaction.set(tb, "버전", "2.0").unwrap();
+    println!("  TX B: {}", kernel.transaction.commit(tb).unwrap());
+    kernel.transaction.set(ta, "버전", &format!("{}.1", seen)).unwrap();
+    println!("  TX A: {}", kernel.transaction.commit(ta).unwrap());
+    if let Some(report) = kernel.transaction.conflict_report(ta) {
+        println!("  {}", report);
+    }
+    println!("  현재 버전={} / 스냅샷(v{}) 버전={}",
+        kernel.transaction.get("버전").unwrap_or("?"), snap.version, snap.get("버전").unwrap_or("?"));
+    println!();
+
     // ═══ 4. 통합 보호 실행 데모 ═══
     println!("━━━ 4. 통합 보호 실행 (권한→트랜잭션→스케줄러) ━━━\n");
 
@@ -684,6 +1990,26 @@ fn run_kernel_demo() {
     );
     println!("  결과: {}\n", gr3);
 
+    // 보류된 보유자가 잠금을 쥔 채 상위 태스크가 같은 자원을 기다리면 우선순위 상속
+    println!("  ── 우선순위 상속 ──");
+    let hold = kernel.execute_guarded(
+        "배치", "장부", Action::Write,
+        "야간정산", TritPriority::Normal,
+        Box::new(|| TritResult::Pending),
+    );
+    println!("  야간정산: {} · 장부 잠금 보유 #{}",
+        hold, kernel.scheduler.lock_holder("장부").map_or("-".to_string(), |id| id.to_string()));
+    let read = kernel.execute_guarded(
+        "사용자", "장부", Action::Read,
+        "잔액조회", TritPriority::High,
+        Box::new(|| TritResult::Success),
+    );
+    println!("  잔액조회: {}", read);
+    for b in &kernel.scheduler.boosts {
+        println!("  상속: #{} {}→{} (대기 #{} · {})", b.holder, b.from, b.to, b.waiter, b.resource);
+    }
+    println!("  장부 잠금: {}\n", if kernel.scheduler.lock_holder("장부").is_some() { "유지" } else { "해제" });
+
     // ═══ 5. 합의 (Consensus) 데모 ═══
     println!("━━━ 5. 3진 합의 투표 ━━━\n");
     {
@@ -707,6 +2033,102 @@ fn run_kernel_demo() {
     }
     println!();
 
+    // ═══ 6-1. 선점 스케줄링 ═══
+    println!("━━━ 6-1. 선점 스케줄링 (퀀텀 · 에이징) ━━━\n");
+    {
+        use scheduler::{SchedMode, TritScheduler};
+        let load = |src: &str| {
+            let mut vm = TVM::new();
+            vm.load(assembler::assemble(src));
+            vm
+        };
+        let sum = "넣어 1\n넣어 2\n더해\n넣어 3\n더해\n넣어 4\n더해\n넣어 5\n더해\n종료";
+        // 데모라 에이징 대기 0 — 낮음(T) 태스크가 첫 실행 전에 보통(O)으로 승격
+        let mut sched = TritScheduler::new()
+            .with_mode(SchedMode::Preemptive { quantum: 4 })
+            .with_aging(std::time::Duration::ZERO);
+        let a = sched.submit_program("합계A", TritPriority::Normal, load(sum));
+        let b = sched.submit_program("합계B", TritPriority::Normal, load(sum));
+        sched.submit("로그정리", TritPriority::Low, Box::new(|| TritResult::Success));
+        let name = |id| if id == a { "합계A" } else if id == b { "합계B" } else { "로그정리" };
+        for (id, r) in sched.run_all() {
+            println!("  {} → {}", name(id), r);
+        }
+        for id in [a, b] {
+            if let Some(t) = sched.completed_task(id) {
+                println!("  {}: 선점 {}회 · 사이클 {}", t.name, t.slices, t.program.as_ref().map_or(0, |vm| vm.cycles));
+            }
+        }
+        println!("  선점 {} · 에이징 {}", sched.stats_preempted, sched.stats_aged);
+    }
+    println!();
+
+    // ═══ 7. 능력 사전분석 ═══
+    println!("━━━ 7. 능력 사전분석 (실행 전 권한 판정) ━━━\n");
+    let src = "넣어 \"보고서.txt\"\n파일읽기\n질문해\n넣어 \"요약.txt\"\n파일쓰기\n종료";
+    let program = assembler::assemble(src);
+    println!("  {}", capability::CapabilityManifest::analyze(&program));
+    match kernel.execute_program_as("사용자", src) {
+        Ok(()) => println!("  실행 완료"),
+        Err(e) => println!("  거부: {}", e),
+    }
+    kernel.grant_capability("사용자", capability::Capability::Llm);
+    kernel.grant_capability("사용자", capability::Capability::FileWrite);
+    match kernel.execute_program_as("사용자", src) {
+        Ok(()) => println!("  능력 부여 후 → 실행 완료"),
+        Err(e) => println!("  거부: {}", e),
+    }
+    println!();
+
+    // ═══ 8. 능력 승인 흐름 (O-상태 보류) ═══
+    println!("━━━ 8. 능력 승인 흐름 (O → P/T) ━━━\n");
+    let src = "넣어 \"시세\"\nHTTP가져와\n넣어 1\n종료";
+    let outcome = kernel.submit_program("손님", src);
+    println!("  제출: {}", outcome);
+    for rec in kernel.approvals.pending() {
+        println!("  대기: {}", rec);
+    }
+    if let kernel::SubmitOutcome::Pending(id) = outcome {
+        match kernel.approve("관리자", id) {
+            Ok(()) => println!("  관리자 승인 → 자동 재개 완료"),
+            Err(e) => println!("  승인 실패: {}", e),
+        }
+        if let Some(rec) = kernel.approvals.get(id) {
+            println!("  기록: {}", rec);
+        }
+    }
+    if let kernel::SubmitOutcome::Pending(id) = kernel.submit_program("손님", src) {
+        let _ = kernel.reject("관리자", id, "외부 호출 불필요");
+        if let Some(rec) = kernel.approvals.get(id) {
+            println!("  기록: {}", rec);
+        }
+    }
+    println!();
+
+    // ═══ 9. 태스크 간 채널 (IPC) ═══
+    println!("━━━ 9. 태스크 간 메시지 채널 (P/O/T) ━━━\n");
+    let ch = kernel.create_channel();
+    println!("  수신(빈 채널) → {}", kernel.recv(ch.id));
+    let tx = ch.clone();
+    let sent = kernel.execute_task("생산자", TritPriority::High, Box::new(move || tx.send("정산 완료")));
+    println!("  생산자 전송 → {}", sent);
+    let rx = ch.clone();
+    let got = kernel.execute_task("소비자", TritPriority::Normal, Box::new(move || {
+        let r = rx.recv();
+        println!("  소비자 수신 → {}", r);
+        r.trit()
+    }));
+    println!("  소비자 결과 → {}", got);
+    kernel.send(ch.id, "잔여 1");
+    kernel.send(ch.id, "잔여 2");
+    kernel.close_channel(ch.id);
+    // 닫힌 채널도 남은 메시지는 끝까지 전달
+    while let Some(m) = kernel.recv(ch.id).message() {
+        println!("  닫힌 채널에서 수신 → {}", m);
+    }
+    println!("  닫은 뒤 전송 → {} / 수신 → {}", kernel.send(ch.id, "늦음"), kernel.recv(ch.id));
+    println!();
+
     // ── 커널 상태 ──
     println!("━━━ 커널 전체 상태 ━━━");
     kernel.dump();
@@ -716,14 +2138,110 @@ fn run_kernel_demo() {
     println!("\n═══ Crowny Meta-Kernel 데모 완료 ═══");
 }
 
-// ═══════════════════════════════════════════════
-// 균형3진 프로토콜 (CTP) 데모
-// ═══════════════════════════════════════════════
+// ═══════════════════════════════════════════════
+// 균형3진 프로토콜 (CTP) 데모
+// ═══════════════════════════════════════════════
+
+/// 보안 CTP 설정 — (키 파일, 허용 공개 키 목록) → (노드 키쌍, 신뢰 정책)
+fn secure_identity(key: Option<&str>, trust: Option<&str>) -> Result<(secure_ctp::NodeKeypair, secure_ctp::PeerTrust), String> {
+    let path = std::path::Path::new(key.unwrap_or(".crowny/node.key"));
+    let identity = match fs::read_to_string(path) {
+        Ok(hex) => crypto::from_hex(hex.trim())
+            .and_then(|b| <[u8; crypto::KEY_LEN]>::try_from(b.as_slice()).ok())
+            .map(secure_ctp::NodeKeypair::from_secret)
+            .ok_or_else(|| format!("{}: 노드 키 형식 오류", path.display()))?,
+        Err(_) => {
+            let identity = secure_ctp::NodeKeypair::generate();
+            if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+            }
+            fs::write(path, crypto::to_hex(identity.secret())).map_err(|e| format!("{}: {}", path.display(), e))?;
+            identity
+        }
+    };
+    let trust = match trust {
+        None => secure_ctp::PeerTrust::Any,
+        Some(list) => secure_ctp::PeerTrust::Only(list.split(',')
+            .map(|k| crypto::from_hex(k.trim())
+                .and_then(|b| <[u8; crypto::KEY_LEN]>::try_from(b.as_slice()).ok())
+                .ok_or_else(|| format!("--trust: 공개 키 형식 오류 ({})", k)))
+            .collect::<Result<_, _>>()?),
+    };
+    Ok((identity, trust))
+}
+
+type CtpServe = Box<dyn FnOnce() -> std::io::Result<()> + Send>;
+
+fn ctp_serve(addr: &str, secure: Option<(Option<&str>, Option<&str>)>, gossip: Option<(&str, Option<&str>)>) -> i8 {
+    let listen = addr.to_string();
+    let serve: CtpServe = match secure {
+        None => Box::new(move || network::TritNetAdapter::start_server(&listen)),
+        Some((key, trust)) => match secure_identity(key, trust) {
+            Ok((identity, trust)) => {
+                say!("[CTPS] 노드 공개 키 {}", crypto::to_hex(&identity.public));
+                Box::new(move || network::TritNetAdapter::start_secure_server(&listen, &identity, &trust))
+            }
+            Err(e) => return fail("protocol serve", &e),
+        },
+    };
+    let result = match gossip {
+        None => serve(),
+        Some((bind, seeds)) => serve_with_gossip(addr, bind, seeds, serve),
+    };
+    match result {
+        Ok(()) => 1,
+        Err(e) => fail("protocol serve", &format!("{}: {}", addr, e)),
+    }
+}
+
+/// CTP 서버는 스레드에서, 이 스레드는 가십 노드의 워치독 — 교환이 멎으면 새 교환 스레드
+fn serve_with_gossip(id: &str, bind: &str, seeds: Option<&str>, serve: CtpServe) -> std::io::Result<()> {
+    let seeds: Vec<&str> = seeds.unwrap_or_default().split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+    let mut node = gossip::GossipNode::start(id, bind, gossip::GossipConfig::new().with_seeds(&seeds))?;
+    say!("[가십] {} 대기 — 시드 {}개", node.addr(), seeds.len());
+    let mut watchdog = watchdog::Watchdog::new(3, 3);
+    watchdog.add_sink(Box::new(watchdog::StderrSink));
+    node.watch(&mut watchdog);
+
+    let server = std::thread::spawn(serve);
+    let mut view = String::new();
+    while !server.is_finished() {
+        watchdog.tick();
+        let now: Vec<String> = node.members().iter().map(|m| format!("{}={}", m.id, m.health.symbol())).collect();
+        if now.join(" ") != view {
+            view = now.join(" ");
+            say!("[가십] 멤버 {}: {}", now.len(), view);
+        }
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+    node.stop();
+    server.join().unwrap_or_else(|_| Err(std::io::Error::other("CTP 서버 스레드 중단")))
+}
+
+fn ctp_send(addr: &str, text: &str, secure: Option<(Option<&str>, Option<&str>)>) -> i8 {
+    let mut payload = network::TritBuffer::new();
+    payload.push_string(text);
+    let msg = network::CtpMessage::new(network::MessageType::Request, network::StatusCode::Success, payload);
+    let reply = match secure {
+        None => network::TritNetAdapter::send_request(addr, &msg),
+        Some((key, trust)) => match secure_identity(key, trust) {
+            Ok((identity, trust)) => network::TritNetAdapter::send_secure_request(addr, &identity, &trust, &msg),
+            Err(e) => return fail("protocol send", &e),
+        },
+    };
+    match reply {
+        Ok(reply) => {
+            println!("{}", reply);
+            reply.status as i8
+        }
+        Err(e) => fail("protocol send", &format!("{}: {}", addr, e)),
+    }
+}
 
 fn run_protocol_demo() {
     use network::{TritBuffer, NetTrit, CtpMessage, MessageType, StatusCode};
 
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ Crowny Trit Protocol (CTP) 데모 ═══\n");
 
     // ── 1. 트릿 인코딩 ──
@@ -799,11 +2317,103 @@ fn run_protocol_demo() {
     println!("  O( 0) = 중립 (Neutral/Processing)");
     println!("  T(-1) = 오류 (Error)");
     println!();
+    let hello = CtpMessage::handshake(network::MIN_PROTOCOL_VERSION, 4);
+    let agreed = hello.answer_handshake((network::MIN_PROTOCOL_VERSION, network::PROTOCOL_VERSION));
+    println!("  버전 협상: {} [v1~v4] → {:?} · 능력 {}", hello.msg_type, agreed.agreed_version(), agreed.agreed_caps());
+    let refused = CtpMessage::handshake(1, 1).answer_handshake((2, 3));
+    println!("  불일치:   v1 ↔ v2~v3 → {} {:?}", refused.status, refused.agreed_version());
+    println!();
+
+    // ── 6. 복사 없는 뷰 ──
+    println!("━━━ 6. TritSlice 제로카피 ━━━");
+    let view = CtpMessage::parse(serialized.as_slice()).expect("직렬화한 메시지");
+    println!("  페이로드 뷰: {} ({} trits, 복사 없음)", view.payload, view.payload.len());
+    println!("  단어: {:?}", view.payload.words().collect::<Vec<_>>());
+    if let Some((first, rest)) = view.payload.split_at(6) {
+        let rest = if rest.is_empty() { "(비어 있음)".to_string() } else { rest.to_string() };
+        println!("  split_at(6): {} | {}", first, rest);
+    }
+    if let Some(tail) = serialized.slice(serialized.len() - 3..serialized.len()) {
+        println!("  패킷 끝 3 trits: {} (단어 밖 {})", tail, serialized.words().remainder().len());
+    }
+
+    // 빌린 뷰를 소유 버퍼로 바꿔 제자리 수정
+    let mut owned = view.payload.to_buffer();
+    owned.set_word6(0, -42);
+    owned.set(6, NetTrit::O);
+    owned.negate();
+    owned.truncate(6);
+    println!("  수정: set_word6 · set · negate · truncate(6) → {:?} (첫 트릿 {})",
+        owned.words().collect::<Vec<_>>(), owned.as_slice().get(0).map_or('-', NetTrit::symbol));
+    let mut out = [0u8; 16];
+    match serialized.write_into(&mut out) {
+        Ok(n) => println!("  write_into: {} bytes → 호출자 버퍼 {:?}", n, &out[..n]),
+        Err(e) => println!("  write_into 실패: {}", e),
+    }
+    println!();
 
-    // ── 6. TCP 서버/클라이언트 안내 ──
-    println!("━━━ 6. CTP 네트워크 사용법 ━━━");
-    println!("  서버: TritNetAdapter::start_server(\"127.0.0.1:7293\")");
+    // ── 7. 암호화 채널 ──
+    println!("━━━ 7. 보안 CTP (노드 간 종단 암호화) ━━━");
+    {
+        use secure_ctp::{Initiator, NodeKeypair, PeerTrust, Responder};
+        let (alice, bob) = (NodeKeypair::generate(), NodeKeypair::generate());
+        println!("  노드 키: A={} B={}", alice.fingerprint(), bob.fingerprint());
+        let (init, hello) = Initiator::start(&alice);
+        let secured = Responder::respond(&bob, &hello, &PeerTrust::Only(vec![alice.public]))
+            .and_then(|(resp, reply)| {
+                let (a, confirm) = init.finish(&reply, &PeerTrust::Only(vec![bob.public]))?;
+                Ok((a, resp.finish(&confirm)?))
+            });
+        match secured {
+            Ok((mut a, mut b)) => {
+                println!("  핸드셰이크: 3DH + 키 확인 → 세션 {}", a.session_id());
+                let mut vote = TritBuffer::new();
+                vote.push_string("vote:P");
+                let frame = a.seal_message(&CtpMessage::request(vote)).expect("프레임");
+                println!("  투표 프레임: {} bytes 암호문 (ChaCha20-Poly1305)", frame.len());
+                match b.open_message(&frame[4..]) {
+                    Ok(Ok(msg)) => println!("  복호화: {} ✓", msg),
+                    other => println!("  복호화 실패: {:?}", other.err()),
+                }
+                a.rekey();
+                let frame = a.seal(b"state-sync");
+                println!("  재키잉: 에포크 {:?} → 수신 {:?}", a.epochs(), b.open(&frame[4..]).map(|_| b.epochs()));
+            }
+            Err(e) => println!("  핸드셰이크 실패: {}", e),
+        }
+    }
+    println!();
+
+    // ── 8. 전송 압축 ──
+    println!("━━━ 8. CTP 전송 압축 (협상된 LZ) ━━━");
+    {
+        use ctp_compress::CtpCodec;
+        let mut codec = CtpCodec::negotiated(agreed.agreed_caps());
+        let mut small = TritBuffer::new();
+        small.push_string("vote:P");
+        let mut sync = TritBuffer::new();
+        for i in 0..60 { sync.push_word6(i % 3); }
+        for (label, payload) in [("투표 메시지", small), ("상태 동기화", sync)] {
+            let frame = network::encode_frame_with(&mut codec, &CtpMessage::request(payload));
+            println!("  {:<12} → {} bytes", label, frame.len());
+        }
+        println!("  임계값: {}B 미만은 평문 본문", codec.threshold);
+        println!("  통계: {}", codec.stats);
+        let chunk: String = (0..40).map(|i| format!("account:{:04}=balance:{};trit:P\n", i, 1000 + i % 7)).collect();
+        let wasm = compiler::compile_source_to_wasm(&"PUSH 1\nPUSH 2\nADD\nPRINT\n".repeat(40), "sync").expect("분기 없는 프로그램");
+        for (label, bytes) in [("상태 청크", chunk.as_bytes()), ("WASM 아티팩트", &wasm[..])] {
+            println!("  LZ {:<12} {}B → {}B", label, bytes.len(), ctp_compress::compress(bytes).len());
+        }
+    }
+    println!();
+
+    // ── 9. TCP 서버/클라이언트 안내 ──
+    println!("━━━ 9. CTP 네트워크 사용법 ━━━");
+    println!("  서버: TritNetAdapter::start_server(\"127.0.0.1:7293\") — CTP3 프레임 · 연결마다 스레드");
     println!("  클라: TritNetAdapter::send_request(\"127.0.0.1:7293\", &msg)");
+    println!("  파이프라이닝: CtpClient::connect(addr)?.send(msg)? → 핸들.wait(시간) (요청 ID로 순서 무관)");
+    println!("  보안: TritNetAdapter::start_secure_server(addr, &키쌍, &PeerTrust::Only(..))");
+    println!("        TritNetAdapter::send_secure_request(addr, &키쌍, &신뢰, &msg)");
     println!("  포트: 7293 = 3^6 + 3^5 + ... (균형3진 의미)");
     println!();
 
@@ -814,10 +2424,106 @@ fn run_protocol_demo() {
 // FPGA 이전 로드맵 + 물리 매핑 데모
 // ═══════════════════════════════════════════════
 
+/// 2진 vs 3진 실측 — 직전 기록 대비 비율 회귀가 있으면 T
+fn run_soak(config: soak::SoakConfig) -> i8 {
+    let json = output::is_json();
+    if !json {
+        println!("═══ 소크 테스트 ({:.2}시간{}, 표본 {}바퀴마다) ═══\n",
+            config.duration.map_or(0.0, |d| d.as_secs_f64() / 3600.0),
+            config.rounds.map(|n| format!(" · 최대 {}바퀴", n)).unwrap_or_default(), config.sample_every);
+    }
+    let report = soak::run(config, |s| {
+        if !json {
+            let values: Vec<String> = s.values.iter().map(|(n, v)| format!("{}={}", n, v)).collect();
+            println!("  [{:>8}바퀴 {:>7.1}s] {}", s.round, s.elapsed_ms as f64 / 1000.0, values.join(" "));
+        }
+    });
+    let state = report.state();
+    if json {
+        report.to_json().str("command", "soak").emit();
+        return state;
+    }
+
+    println!("\n━━━ 판정 (워밍업 이후 세 구간 평균) ━━━");
+    for v in &report.verdicts {
+        println!("  {} {:20} {:>10.0} → {:>10.0} → {:>10.0}",
+            if v.leaking { "✗" } else { "·" }, v.metric, v.windows[0], v.windows[1], v.windows[2]);
+    }
+    println!("\n  {}바퀴 · {:.1}s · 표본 {} · 경로 오류 {}",
+        report.rounds, report.elapsed_ms as f64 / 1000.0, report.samples.len(), report.errors);
+    match state {
+        1 => println!("  [P] 모든 지표 안정"),
+        0 => println!("  [O] 표본 부족 — 워밍업 이후 {}개 이상 필요 (--sample-every를 줄이거나 더 오래)", soak::MIN_SAMPLES),
+        _ => {
+            let leaks: Vec<&str> = report.leaks().map(|v| v.metric).collect();
+            eprintln!("  [T] 한없이 자라는 지표: {}", leaks.join(", "));
+        }
+    }
+    state
+}
+
+fn run_bench(iterations: usize, history: Option<&str>, save: bool) -> i8 {
+    let path = std::path::Path::new(history.unwrap_or(bench::HISTORY_FILE));
+    let previous = bench::last_recorded(path);
+    let report = bench::run(iterations);
+    let deltas = previous.as_ref().map(|p| report.compare(p)).unwrap_or_default();
+    let regressions = deltas.iter().filter(|d| d.is_regression()).count();
+    let state = if regressions > 0 { -1 } else { 1 };
+
+    if save {
+        if let Err(e) = bench::record(path, &report) {
+            return fail("bench", &format!("기록 실패: {}", e));
+        }
+    }
+
+    if output::is_json() {
+        let deltas = deltas.iter().map(|d| JsonObject::new()
+            .str("name", &d.name)
+            .float("before", d.before)
+            .float("after", d.after)
+            .float("change_pct", (d.change_pct() * 10.0).round() / 10.0)
+            .bool("regression", d.is_regression())).collect();
+        JsonObject::new()
+            .str("command", "bench")
+            .trit("state", state)
+            .object("report", report.to_json())
+            .objects("deltas", deltas)
+            .bool("recorded", save)
+            .emit();
+        return state;
+    }
+
+    println!("═══ 2진 vs 3진 벤치마크 ({}, 반복 {}) ═══\n", report.host, iterations);
+    println!("━━━ 밀도 (실제 패킹) ━━━");
+    println!("  {:24} {:>6} {:>8} {:>8} {:>7}", "단위", "trits", "패킹bit", "최소2진", "효율");
+    for d in &report.density {
+        println!("  {:24} {:>6} {:>8} {:>8} {:>6.1}%", d.unit, d.trits, d.packed_bits, d.binary_bits(), d.efficiency() * 100.0);
+    }
+    println!("\n━━━ 처리량 (ns/연산) ━━━");
+    println!("  {:24} {:>8} {:>10} {:>10} {:>7}", "벤치", "분류", "3진", "2진", "비율");
+    for t in &report.timings {
+        println!("  {:24} {:>8} {:>10.2} {:>10.2} {:>6.2}×", t.name, t.category, t.ternary_ns, t.binary_ns, t.ratio());
+    }
+    if !deltas.is_empty() {
+        println!("\n━━━ 직전 기록 대비 (비율 변화) ━━━");
+        for d in &deltas {
+            println!("  {} {:24} {:>6.2}× → {:>6.2}× ({:+.1}%)",
+                if d.is_regression() { "✗" } else { "·" }, d.name, d.before, d.after, d.change_pct());
+        }
+    }
+    if save {
+        println!("\n✓ 기록: {}", path.display());
+    }
+    if regressions > 0 {
+        println!("✗ 회귀 {}건 (>{}%)", regressions, bench::REGRESSION_PCT);
+    }
+    state
+}
+
 fn run_fpga_demo() {
     use bridge::*;
 
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ FPGA 이전 로드맵 + 물리 매핑 데모 ═══\n");
 
     // ── 로드맵 출력 ──
@@ -845,6 +2551,13 @@ fn run_fpga_demo() {
         println!("  {:4} → {} → 0x{:04X} → {:4} opcode:({},{},{}) ✓",
             v, w, packed, restored.to_decimal(), s, g, c);
     }
+    // 트릿 단위 산술 — 올림은 상위 워드로
+    let (a, b) = (TritWord::from_decimal(300).to_word6(), TritWord::from_decimal(100).to_word6());
+    let sum = a.add(&b);
+    println!("  {} + {} = {} 올림:{} (= {}) {}", a, b, TritWord::from_word6(&sum.low), sum.high, sum.to_wide_decimal(),
+        if sum.overflowed() { "⚠ 오버플로" } else { "✓" });
+    let prod = Word6::from_decimal(-27).mul(&Word6::from_decimal(13));
+    println!("  -27 × 13 = {} (= {}) ✓", prod.low, prod.to_wide_decimal());
     println!();
 
     // ── 3. TritDWord (12 trit) ──
@@ -886,6 +2599,23 @@ fn run_fpga_demo() {
     mem.dump(0, 120);
     println!();
 
+    // ── 5-1. ECC 메모리 ──
+    println!("━━━ 5-1. 3진 ECC 메모리 ([9,6] 해밍, 장애 주입) ━━━");
+    let mut ecc_mem = TritMemory::with_ecc(729 * 6);
+    ecc_mem.write_word(0, &TritWord::from_decimal(42));
+    let hits = ecc_mem.inject_faults(0x3_3333, 12);
+    println!("  장애 주입: {}곳 반전 → 읽기 [0]={}", hits.len(), ecc_mem.read_word(0).to_decimal());
+    if let Some(stats) = ecc_mem.scrub() {
+        println!("  스크러빙: {}", stats);
+    }
+    println!("  저장 비용: {} bytes (ECC 없이 {} bytes)", ecc_mem.packed_bytes(), mem.packed_bytes());
+    for flips in 1..=3 {
+        let trial = ecc_trial(729, flips, 0x3_3333 + flips as u64);
+        println!("  워드당 {}개 오류: 정정 {} · 정정불가 {} · 손상 잔존 {}/729 워드",
+            flips, trial.stats.corrected, trial.stats.uncorrectable, trial.corrupted_words);
+    }
+    println!();
+
     // ── 6. 크기 비교 ──
     println!("━━━ 6. 2진 vs 3진 저장 효율 ━━━");
     println!("  ┌──────────┬──────────┬──────────┬──────────┐");
@@ -900,7 +2630,8 @@ fn run_fpga_demo() {
     println!();
     println!("  정보밀도: log₂(3) ≈ 1.585 bits/trit");
     println!("  2bit 매핑 효율: 1.585/2.0 = 79.2%");
-    println!("  (FPGA 네이티브에서는 100%)\n");
+    println!("  (FPGA 네이티브에서는 100%)");
+    println!("  호스트 실측: {} bench\n", cli::BIN);
 
     println!("═══ FPGA 이전 데모 완료 ═══");
 }
@@ -910,7 +2641,7 @@ fn run_fpga_demo() {
 // ═══════════════════════════════════════════════
 
 fn run_wasm_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ TVM → WASM 변환 데모 ═══\n");
     println!("GPT Spec §3: TVM Bytecode → IR → WASM Module → .wasm binary\n");
 
@@ -920,7 +2651,7 @@ fn run_wasm_demo() {
         let source = "넣어 5\n넣어 3\n더해\n종료";
         println!("  소스: 넣어 5 / 넣어 3 / 더해 / 종료");
 
-        let result = compiler::compile_with_info(source, "산술");
+        let result = compiler::compile_with_info(source, "산술").expect("분기 없는 프로그램");
         println!("  IR ops: {}", result.ir_op_count);
         println!("  WASM: {} bytes", result.wasm_bytes.len());
         println!("  함수: {} 개 (+ import {})", result.func_count, result.import_count);
@@ -943,13 +2674,13 @@ fn run_wasm_demo() {
         let source = "넣어 3\n제곱\n넣어 4\n제곱\n더해\n보여줘\n종료";
         println!("  소스: 넣어 3 / 제곱 / 넣어 4 / 제곱 / 더해 / 보여줘 / 종료");
 
-        let result = compiler::compile_with_info(source, "피타고라스");
+        let result = compiler::compile_with_info(source, "피타고라스").expect("분기 없는 프로그램");
         println!("  IR ops: {}", result.ir_op_count);
         println!("  WASM: {} bytes", result.wasm_bytes.len());
 
         // IR 변환 내용 보기
         let program = assembler::assemble(source);
-        let ir_module = compiler::tvm_to_ir(&program, "피타고라스");
+        let ir_module = compiler::tvm_to_ir(&program, "피타고라스").expect("분기 없는 프로그램");
         println!("  IR 변환:");
         for (i, op) in ir_module.functions[0].body.iter().enumerate() {
             println!("    [{:2}] {:?}", i, op);
@@ -963,12 +2694,12 @@ fn run_wasm_demo() {
         let source = "참\n모름\n그리고\n종료";
         println!("  소스: 참(+1) / 모름(0) / 그리고(AND) / 종료");
 
-        let result = compiler::compile_with_info(source, "삼진논리");
+        let result = compiler::compile_with_info(source, "삼진논리").expect("분기 없는 프로그램");
         println!("  IR ops: {}", result.ir_op_count);
         println!("  WASM: {} bytes", result.wasm_bytes.len());
 
         let program = assembler::assemble(source);
-        let ir_module = compiler::tvm_to_ir(&program, "삼진논리");
+        let ir_module = compiler::tvm_to_ir(&program, "삼진논리").expect("분기 없는 프로그램");
         println!("  IR 변환:");
         for (i, op) in ir_module.functions[0].body.iter().enumerate() {
             println!("    [{:2}] {:?}", i, op);
@@ -982,12 +2713,12 @@ fn run_wasm_demo() {
         let source = "넣어 10\n넣어 20\n크다\n종료";
         println!("  소스: 넣어 10 / 넣어 20 / 크다 / 종료");
 
-        let result = compiler::compile_with_info(source, "비교");
+        let result = compiler::compile_with_info(source, "비교").expect("분기 없는 프로그램");
         println!("  IR ops: {}", result.ir_op_count);
         println!("  WASM: {} bytes", result.wasm_bytes.len());
 
         let program = assembler::assemble(source);
-        let ir_module = compiler::tvm_to_ir(&program, "비교");
+        let ir_module = compiler::tvm_to_ir(&program, "비교").expect("분기 없는 프로그램");
         println!("  IR 변환:");
         for (i, op) in ir_module.functions[0].body.iter().enumerate() {
             println!("    [{:2}] {:?}", i, op);
@@ -998,7 +2729,7 @@ fn run_wasm_demo() {
     // ── 5. WASM 구조 분석 ──
     println!("━━━ 5. WASM 모듈 구조 ━━━");
     {
-        let result = compiler::compile_with_info("넣어 42\n종료", "분석");
+        let result = compiler::compile_with_info("넣어 42\n종료", "분석").expect("분기 없는 프로그램");
         let wasm = &result.wasm_bytes;
         println!("  전체 크기: {} bytes", wasm.len());
         println!("  구조:");
@@ -1100,51 +2831,172 @@ fn run_wasm_demo() {
 // .hsn → .wasm 파일 컴파일
 // ═══════════════════════════════════════════════
 
-fn compile_file(input: &str, output: &str) {
+fn compile_file(input: &str, output: &str, target: compiler::Target) -> i8 {
     let source = match fs::read_to_string(input) {
         Ok(s) => s,
-        Err(e) => {
-            eprintln!("파일 읽기 오류: {} — {}", input, e);
-            return;
-        }
+        Err(e) => return fail("compile", &format!("파일 읽기 오류: {} — {}", input, e)),
     };
 
-    let result = compiler::compile_with_info(&source, input);
+    let result = match compiler::compile_with_target(&source, input, target) {
+        Ok(r) => r,
+        Err(e) => return fail("compile", &format!("컴파일 실패 ({} 쓰지 않음) — {}", output, e)),
+    };
+    if let Err(e) = result.validate() {
+        return fail("compile", &format!("WASM 검증 실패 ({} 쓰지 않음) — {}", output, e));
+    }
 
     match fs::write(output, &result.wasm_bytes) {
         Ok(()) => {
-            println!("✓ 컴파일 완료");
-            println!("  입력: {}", input);
-            println!("  출력: {} ({} bytes)", output, result.wasm_bytes.len());
-            println!("  IR ops: {}", result.ir_op_count);
-            println!("  함수: {} | imports: {}", result.func_count, result.import_count);
-        }
-        Err(e) => {
-            eprintln!("파일 쓰기 오류: {} — {}", output, e);
+            if output::is_json() {
+                let data = JsonObject::new()
+                    .str("input", input)
+                    .str("output", output)
+                    .str("target", target.name())
+                    .int("bytes", result.wasm_bytes.len() as i64)
+                    .int("ir_ops", result.ir_op_count as i64)
+                    .int("functions", result.func_count as i64)
+                    .int("imports", result.import_count as i64);
+                JsonObject::envelope("compile", 1, &[], data).emit();
+            } else {
+                println!("✓ 컴파일 완료 (검증 통과)");
+                println!("  입력: {}", input);
+                println!("  출력: {} ({} bytes, 대상 {})", output, result.wasm_bytes.len(), target.name());
+                println!("  IR ops: {}", result.ir_op_count);
+                println!("  함수: {} | imports: {}", result.func_count, result.import_count);
+            }
+            1
         }
+        Err(e) => fail("compile", &format!("파일 쓰기 오류: {} — {}", output, e)),
     }
 }
 
 // ═══════════════════════════════════════════════
-// .hsn → .크라운 바이트코드 직결화
+// 재현 빌드 증명 (attest)
 // ═══════════════════════════════════════════════
 
-fn bytecode_file(input: &str, output: &str) {
-    let source = match fs::read_to_string(input) {
+/// 재컴파일 → 해시 비교 → 서명된 증명을 샘플 체인 블록에 기록
+fn attest_file(source_path: &str, artifact_path: &str, attester: &str) -> i8 {
+    let source = match fs::read_to_string(source_path) {
         Ok(s) => s,
-        Err(e) => { eprintln!("파일 읽기 오류: {} — {}", input, e); return; }
+        Err(e) => return fail("attest", &format!("파일 읽기 오류: {} — {}", source_path, e)),
     };
-    let program = assembler::assemble(&source);
-    let bytes = bytecode::serialize(&program);
-    match fs::write(output, &bytes) {
-        Ok(()) => {
-            let info = bytecode::analyze(&bytes).unwrap();
-            println!("✓ 바이트코드 저장 완료");
-            println!("  입력: {}", input);
-            println!("  출력: {} ({} bytes)", output, info.byte_size);
-            println!("  명령어: {} | 평균 {:.1} bytes/inst", info.instruction_count, info.avg_bytes_per_inst);
+    let artifact = match fs::read(artifact_path) {
+        Ok(b) => b,
+        Err(e) => return fail("attest", &format!("파일 읽기 오류: {} — {}", artifact_path, e)),
+    };
+    let att = match attest::attest(&source, &artifact, attester) {
+        Ok(a) => a,
+        Err(e) => return fail("attest", &format!("{}: {}", artifact_path, e)),
+    };
+
+    let mut chain = chain::sample_chain();
+    chain.record_attestation(&att);
+    let block = chain.produce_block();
+    // 탐색기에서 보이는 상태 — 블록에 들어간 유효 증명이 있어야 verified
+    let verified = chain.source_verified(&att.artifact_hash).is_some();
+
+    if output::is_json() {
+        let obj = JsonObject::new()
+            .str("command", "attest")
+            .trit("state", att.state)
+            .str("source", source_path)
+            .str("artifact", artifact_path)
+            .bool("source_verified", verified)
+            .object("attestation", att.to_json());
+        match &block {
+            Some(b) => obj.int("block", b.index as i64).str("tx", &b.transactions[0].hash),
+            None => obj,
+        }.emit();
+        return att.state;
+    }
+
+    println!("재현 빌드 증명");
+    println!("  소스:     {} ({})", source_path, att.source_hash);
+    println!("  아티팩트: {} ({})", artifact_path, att.artifact_hash);
+    println!("  재빌드:   {}", if att.rebuilt_hash.is_empty() { "-" } else { &att.rebuilt_hash });
+    println!("  컴파일러: {}", att.compiler);
+    println!("  {}", att);
+    match &block {
+        Some(b) => println!("  체인 기록: Block #{} tx {:.12}", b.index, b.transactions[0].hash),
+        None => println!("  체인 기록: 대기 (블록 생성 실패)"),
+    }
+    println!("  탐색기: {}", if verified { "✓ source verified" } else { "미검증" });
+    att.state
+}
+
+// ═══════════════════════════════════════════════
+// .hsn → .크라운 바이트코드 직결화
+// ═══════════════════════════════════════════════
+
+fn bytecode_file(input: &str, output: &str, progress: bool) -> i8 {
+    // 소스 전체를 읽지 않고 줄 단위로 어셈블 → 바로 기록
+    // 두 번 읽기: 첫 패스는 라벨 위치만, 두 번째 패스에서 앞 참조까지 풀어 기록
+    let open = || fs::File::open(input).map_err(|e| format!("파일 읽기 오류: {} — {}", input, e));
+    let labels = match open() {
+        Ok(f) => assembler::scan_labels(io::BufReader::new(f)),
+        Err(e) => return fail("bytecode", &e),
+    };
+    let file = match open() {
+        Ok(f) => f,
+        Err(e) => return fail("bytecode", &e),
+    };
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut stream = assembler::StreamAssembler::new(io::BufReader::new(file)).total_bytes(total).with_labels(labels);
+    if progress {
+        stream = stream.on_progress(|p| {
+            eprint!("\r[어셈블] {:5.1}% · {}행 · {}명령어", p.percent().unwrap_or(100.0), p.lines, p.instructions);
+        });
+    }
+    let mut writer = match bytecode::StreamWriter::create(std::path::Path::new(output)) {
+        Ok(w) => w,
+        Err(e) => return fail("bytecode", &format!("쓰기 오류: {}", e)),
+    };
+    for inst in stream.by_ref() {
+        if let Err(e) = writer.push(&inst) {
+            let _ = writer.finish();
+            let _ = fs::remove_file(output);
+            return fail("bytecode", &format!("쓰기 오류: {}", e));
+        }
+    }
+    if progress {
+        eprintln!();
+    }
+    let stats = *stream.progress();
+    for (line, e) in stream.errors() {
+        eprintln!("[어셈블러:{}행] {}", line, e);
+    }
+    if stats.errors > stream.errors().len() {
+        eprintln!("[어셈블러] … 외 {}개 오류", stats.errors - stream.errors().len());
+    }
+    // 풀리지 않은 라벨은 실행 시 '주소 필요'로 터진다 — 파일을 남기지 않는다
+    if stats.unresolved_labels > 0 {
+        let _ = writer.finish();
+        let _ = fs::remove_file(output);
+        return fail("bytecode", &format!("정의되지 않은 라벨 참조 {}개", stats.unresolved_labels));
+    }
+
+    match writer.finish() {
+        Ok(info) => {
+            if output::is_json() {
+                JsonObject::new()
+                    .str("command", "bytecode")
+                    .trit("state", 1)
+                    .str("input", input)
+                    .str("output", output)
+                    .int("bytes", info.byte_size as i64)
+                    .int("instructions", info.instruction_count as i64)
+                    .int("lines", stats.lines as i64)
+                    .int("errors", stats.errors as i64)
+                    .emit();
+            } else {
+                println!("✓ 바이트코드 저장 완료");
+                println!("  입력: {} ({}행)", input, stats.lines);
+                println!("  출력: {} ({} bytes)", output, info.byte_size);
+                println!("  명령어: {} | 평균 {:.1} bytes/inst", info.instruction_count, info.avg_bytes_per_inst);
+            }
+            1
         }
-        Err(e) => eprintln!("쓰기 오류: {}", e),
+        Err(e) => fail("bytecode", &format!("쓰기 오류: {}", e)),
     }
 }
 
@@ -1153,10 +3005,12 @@ fn bytecode_file(input: &str, output: &str) {
 // ═══════════════════════════════════════════════
 
 fn run_car_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ CAR (Crowny Application Runtime) 데모 ═══\n");
 
-    let mut runtime = car::CrownyRuntime::new();
+    // 한도 없이 만든 작업에도 적용되는 기본 한도
+    let mut runtime = car::CrownyRuntime::new()
+        .with_task_limits(car::TaskLimits::unlimited().heap(65_536).ms(5_000));
 
     // 1. 소스 실행
     println!("━━━ 1. 소스 실행 (CAR.submit) ━━━");
@@ -1177,7 +3031,41 @@ fn run_car_demo() {
     });
     println!("  결과: {} — {}", result.state, result.data);
 
-    // 4. 상태
+    // 4. 아티팩트 저장소 (같은 소스 두 번 빌드 → 한 번만 저장)
+    println!("\n━━━ 4. 아티팩트 빌드 (내용 주소) ━━━");
+    let src = "넣어 6\n넣어 7\n곱해\n보여줘\n종료";
+    for _ in 0..2 {
+        let result = runtime.build_artifacts("데모", src);
+        println!("  결과: {} — {}", result.state, result.data);
+    }
+    let stats = runtime.artifacts.borrow().stats();
+    println!("  저장: {}개 {}B · 중복 {}회 ({}B 절약)",
+        stats.artifacts, stats.bytes, stats.dedup_hits, stats.bytes_saved);
+
+    // 5. 비동기 작업 (워커 풀 — O로 시작해 P/T로 전이)
+    println!("\n━━━ 5. 비동기 작업 (워커 풀) ━━━");
+    let sources = ["넣어 6\n넣어 7\n곱해\n종료", "넣어 1\n넣어 0\n나눠\n종료", "넣어 2\n넣어 3\n더해\n종료"];
+    let handles: Vec<_> = sources.iter()
+        .filter_map(|src| runtime.run_source_async("데모", src, vm::ExecLimits::unlimited()).ok())
+        .collect();
+    if let Some(last) = handles.last() {
+        let canceled = runtime.cancel(last.task_id);
+        println!("  #{} 취소: {}", last.task_id, canceled);
+    }
+    for h in &handles {
+        if let Some(r) = runtime.wait(h.task_id, std::time::Duration::from_secs(5)) {
+            println!("  #{} → {} — {}", h.task_id, r.state, r.data);
+        }
+    }
+
+    // 6. 작업 한도 (무한 루프 → 사이클 예산 초과로 T)
+    println!("\n━━━ 6. 작업 한도 ━━━");
+    let task = car::AppTask::new(car::TaskType::Execute, "데모", "넣어 0\n점프")
+        .with_limits(car::TaskLimits::unlimited().cycles(1_000));
+    let result = runtime.run_task(task);
+    println!("  결과: {} — {}", result.state, result.data);
+
+    // 7. 상태
     println!();
     runtime.dump();
     println!("\n═══ CAR 데모 완료 ═══");
@@ -1188,7 +3076,7 @@ fn run_car_demo() {
 // ═══════════════════════════════════════════════
 
 fn run_sectors_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ 729 Opcode 전체 섹터 데모 ═══\n");
 
     let map = sectors::build_all_sectors();
@@ -1232,7 +3120,7 @@ fn run_sectors_demo() {
 // ═══════════════════════════════════════════════
 
 fn run_hanseon_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ 한선어 컴파일러 v0.1 데모 ═══\n");
 
     // 1. 기본 산술
@@ -1274,7 +3162,7 @@ fn run_hanseon_demo() {
 
     // 6. 한선어 → WASM
     println!("\n━━━ 6. 한선어 → WASM 직접 변환 ━━━");
-    let wasm = hanseon::compile_to_wasm("값 42\n더\n끝");
+    let wasm = hanseon::compile_to_wasm("값 42\n더\n끝").expect("분기 없는 프로그램");
     println!("  WASM: {} bytes | Magic: {:?}", wasm.len(), &wasm[0..4]);
 
     // 7. 영어도 가능
@@ -1291,7 +3179,10 @@ fn compile_hanseon(input: &str) {
         Ok(s) => s,
         Err(e) => { eprintln!("파일 읽기 오류: {}", e); return; }
     };
-    let out = hanseon::compile(&source);
+    let out = match hanseon::compile_limited(&source, &project_program_limits()) {
+        Ok(out) => out,
+        Err(e) => { eprintln!("  오류: {}", e); return; }
+    };
     if !out.errors.is_empty() {
         for e in &out.errors { eprintln!("  오류: {}", e); }
         return;
@@ -1306,7 +3197,7 @@ fn compile_hanseon(input: &str) {
     vm.load(out.instructions);
     match vm.run() {
         Ok(()) => println!("✓ 실행 완료"),
-        Err(e) => eprintln!("실행 오류: {:?}", e),
+        Err(e) => eprintln!("실행 오류: {} (IP {} · {})", e, e.ip, e.instruction_name),
     }
 }
 
@@ -1314,8 +3205,187 @@ fn compile_hanseon(input: &str) {
 // 웹서버 데모
 // ═══════════════════════════════════════════════
 
+/// 데모 라우트를 실제 TCP 주소에서 제공 — 프로세스 종료까지 대기
+/// GET /ws 로 요청 기록 · 작업 완료 이벤트를 실시간 구독
+/// ./crowny.toml이 있으면 요청 한도에 적용하고 SIGHUP · 파일 변경 시 재적재,
+/// /history/* 로 DEX · NFT · 작업 · 블록 · 요청 기록을 페이지 단위로 조회
+/// CROWNY_ADMIN_SECRET이 있으면 /admin/config 도 열고 (admin.config 토큰),
+/// /admin/override 로 보류(O) 작업을 강제 정산하며 (admin.override 토큰, `override apply`),
+/// 같은 키로 서명한 토큰으로 /dex · /nft 마켓(dex.* · nft.*)과 같은 상태의
+/// /portfolio 손익(portfolio.*) · /account 계정(account.read)을 쓰며,
+/// run.trusted 토큰 소지자에게 /run P 단계 샌드박스를 준다.
+/// 토큰 주체는 .crowny/accounts 계정 주소로 바뀌어 모든 원장을 같은 ID로 조회
+/// /llm 호출(llm.*)은 [llm] 백엔드 설정과 CROWNY_LLM_API_KEY로 실제 모델에 연결,
+/// 같은 질문은 [llm] cache · cache_ttl · cache_size 응답 캐시에서 답한다
+/// CROWNY_CORS_ORIGINS(쉼표 구분)가 있으면 그 오리진만 CORS 허용
+fn serve_http(addr: &str) -> i8 {
+    use std::{cell::RefCell, rc::Rc};
+    let path = std::path::Path::new("crowny.toml");
+    let cfg = if path.exists() {
+        match config::ConfigManager::load_file(path) {
+            Ok(mgr) => mgr,
+            Err(errors) => return fail("server", &errors.join("; ")),
+        }
+    } else {
+        config::ConfigManager::new(config::RuntimeConfig::default())
+    };
+    let cfg = Rc::new(RefCell::new(cfg));
+    config::install_sighup();
+
+    let hub = websocket::EventHub::new();
+    let events = Rc::new(RefCell::new(trit_log::TritEventLog::with_capacity(trit_log::DEFAULT_MAX_EVENTS)));
+    events.borrow_mut().add_sink(Box::new(websocket::HubSink(hub.clone())));
+    let signer = env::var("CROWNY_ADMIN_SECRET").ok()
+        .filter(|s| !s.is_empty())
+        .map(|s| capability::TokenSigner::new(&s));
+    let mut server = webserver::create_server_with_sandbox(webserver::SandboxPolicy::default(), signer.clone());
+    if let Ok(origins) = env::var("CROWNY_CORS_ORIGINS") {
+        let origins: Vec<&str> = origins.split(',').map(str::trim).filter(|o| !o.is_empty()).collect();
+        server = server.with_config(webserver::ServerConfig {
+            cors: Some(webserver::CorsConfig::with_origins(&origins)),
+            ..webserver::ServerConfig::default()
+        });
+    }
+    // 토큰 주체 → 계정 주소 — DEX · NFT · 포트폴리오 잔액이 모두 이 주소로 기록된다
+    let accounts = match trit_store::TritStore::open(ACCOUNTS_DIR) {
+        Ok(store) => account::AccountRegistry::open(store).shared(),
+        Err(e) => return fail("server", &format!("{}: {}", ACCOUNTS_DIR, e)),
+    };
+    server.accounts(accounts.clone());
+    let limiter = Rc::new(RefCell::new(webserver::RateLimiter::new(1, 0.0)));
+    cfg.borrow_mut().attach("rate_limit", limiter.clone());
+    server.add_middleware(webserver::ConfigReload(cfg.clone()));
+    server.add_middleware(webserver::RequestLog::new(events.clone()));
+    server.add_middleware(limiter);
+    // 데드레터는 재시작 뒤에도 재전송할 수 있게 디스크에
+    let hooks = match trit_store::TritStore::open(".crowny/webhooks") {
+        Ok(store) => integrations::WebhookDispatcher::new(Box::new(integrations::HttpTransport::default()))
+            .with_store(store).shared(),
+        Err(e) => return fail("server", &format!(".crowny/webhooks: {}", e)),
+    };
+    server.add_middleware(webserver::WebhookPump(hooks.clone()));
+    // `content put`으로 넣은 자산 — 시작 시점 기준
+    let content = match trit_store::TritStore::open(".crowny/content") {
+        Ok(store) => content::ContentStore::with_store(store).shared(),
+        Err(e) => return fail("server", &format!(".crowny/content: {}", e)),
+    };
+    webserver::mount_content(&mut server, content.clone());
+    // 마켓 API가 구동하는 DEX · NFT 엔진 — 풀 수수료는 [fees] 재적재
+    // 오더북은 디스크 WAL — 재시작 시 체결 기록 기준으로 주문을 다시 올린다
+    let mut engine = dex::CrownyDEX::new();
+    match trit_store::TritStore::open(ORDERBOOK_DIR) {
+        Ok(store) => say!("[서버] 오더북 복구 {}", engine.restore_order_book(store)),
+        Err(e) => return fail("server", &format!("{}: {}", ORDERBOOK_DIR, e)),
+    }
+    let dex = Rc::new(RefCell::new(engine));
+    cfg.borrow_mut().attach("fees", dex.clone());
+    // 체인 · NFT · 컨트랙트 VM이 같은 거버넌스 파라미터 레지스트리를 공유
+    let governance = params::shared();
+    let mut market = nft::CrownyNFT::new().with_params(governance.clone());
+    market.webhooks = Some(hooks.clone());
+    market.content = Some(content);
+    let market = Rc::new(RefCell::new(market));
+    let accounting = billing::Accounting::new().shared();
+    // [llm] 기본 모델 백엔드 — API 키는 설정 파일이 아니라 환경 변수로
+    let mut llm = webserver::CrownyLlm::new();
+    {
+        let cfg = cfg.borrow();
+        let c = cfg.current();
+        if let Some((model, backend)) = llm_backend::from_config(&c.llm_backend, &c.llm_url, &c.llm_model) {
+            if let Ok(key) = env::var("CROWNY_LLM_API_KEY") {
+                llm.set_api_key(&model.to_string(), &key);
+            }
+            llm.set_backend(model.clone(), backend);
+            llm.set_default_model(model);
+        }
+        // 응답 캐시 — llm.cache 디렉터리가 있으면 재시작 뒤에도 적중
+        if c.llm_cache_size > 0 {
+            let store = match &c.llm_cache {
+                Some(dir) => match trit_store::TritStore::open(dir) {
+                    Ok(store) => store,
+                    Err(e) => return fail("server", &format!("llm.cache {}: {}", dir, e)),
+                },
+                None => trit_store::TritStore::new(),
+            };
+            llm = llm.with_cache(store)
+                .with_cache_ttl(c.llm_cache_ttl_secs.saturating_mul(1000))
+                .with_cache_capacity(c.llm_cache_size as usize);
+            llm.purge_cache(billing::now_ms());
+        }
+    }
+    // CAR 빌드와 컨트랙트 배포가 같은 아티팩트 저장소를 공유
+    let artifacts = artifacts::shared();
+    if let Some(signer) = signer {
+        webserver::mount_market_api(&mut server, dex.clone(), market.clone(), signer.clone());
+        let wallets = Rc::new(RefCell::new(token::TokenEngine::new("Crowny Coin", "CRWN", 1_000_000_000, "genesis")));
+        // 에어드롭 진행 상황은 메모리 — 배포 엔진과 함께 재시작 시 초기화
+        webserver::mount_airdrop_api(&mut server, wallets.clone(), market.clone(),
+            Rc::new(RefCell::new(trit_store::TritStore::new())), signer.clone());
+        let portfolio = portfolio::PortfolioService::new(dex.clone(), market.clone()).with_token(wallets.clone());
+        webserver::mount_portfolio_api(&mut server, Rc::new(RefCell::new(portfolio)), signer.clone());
+        webserver::mount_account_api(&mut server, accounts, vec![dex.clone(), market.clone(), wallets], signer.clone());
+        webserver::mount_artifacts_api(&mut server, artifacts.clone(), signer.clone());
+        webserver::mount_llm_api(&mut server, llm.shared(), signer.clone());
+        webserver::mount_config_admin(&mut server, cfg.clone(), signer.clone());
+        webserver::mount_webhook_admin(&mut server, hooks.clone(), signer.clone());
+        webserver::mount_accounting_api(&mut server, accounting.clone(), signer.clone());
+        match cron::JobScheduler::open(".crowny/jobs.tsv") {
+            Ok(jobs) => webserver::mount_jobs_api(&mut server, Rc::new(RefCell::new(jobs)), signer.clone()),
+            Err(e) => eprintln!("[서버] 작업 API 비활성 — {}", e),
+        }
+        // 승인 판정자 신원은 admin.approvals 토큰이 보증
+        let mut kernel = kernel::CrownyKernel::boot(kernel::KernelConfig::default());
+        kernel.permission.add_policy("*", "승인", permission::Action::Admin,
+            permission::TritPermission::Allow, "admin.approvals 토큰 보유자");
+        let kernel = Rc::new(RefCell::new(kernel));
+        webserver::mount_approval_api(&mut server, kernel.clone(), signer.clone());
+        // 레버리지 포지션 — 청산 검사도 같은 커널 스케줄러에서
+        let margin = margin::MarginEngine::new(margin::MarginConfig::default(), 60_000);
+        webserver::mount_margin_api(&mut server, dex.clone(), std::sync::Arc::new(std::sync::Mutex::new(margin)),
+            kernel.clone(), signer.clone());
+        // 보류(O) 작업 · 전송 강제 정산 — admin.override 토큰, 감사 로그는 `override`로 검증
+        match admin_override::AuditLog::open(OVERRIDE_AUDIT) {
+            Ok(audit) => webserver::mount_override_api(&mut server, Rc::new(RefCell::new(crossbridge::CrownyBridge::new())),
+                admin_override::shared(audit), Some(hooks.clone()), signer),
+            Err(e) => eprintln!("[서버] 덮어쓰기 API 비활성 — {}", e),
+        }
+        server.add_middleware(webserver::KernelWatch(kernel));
+    }
+    // 스왑 · 마켓 · 작업 · 블록 · 요청 기록 — 커서 페이지네이션
+    webserver::mount_history_api(&mut server, dex, market, Rc::new(RefCell::new(chain::sample_chain().with_params(governance.clone()))), events);
+    // 컨트랙트 로그 질의 · 구독 — 푸시 알림은 /ws 로
+    let contracts = contract_vm::ContractVM::new().with_artifacts(artifacts.clone()).with_params(governance);
+    let rpc = Rc::new(RefCell::new(rpc::LogRpc::new(Rc::new(RefCell::new(contracts)))));
+    webserver::mount_rpc(&mut server, rpc.clone());
+    server.add_middleware(webserver::RpcPump { rpc, hub: hub.clone() });
+    server.websocket("/ws", hub.clone());
+    let watchdog = watchdog::Watchdog::new(3, 3).shared();
+    watchdog.borrow_mut().add_sink(Box::new(watchdog::StderrSink));
+    watchdog.borrow_mut().add_sink(Box::new(hooks.clone()));
+    server.watch(watchdog.clone());
+    let mut car = car::CrownyRuntime::new().with_events(hub.clone()).with_webhooks(hooks).with_accounting(accounting)
+        .with_artifacts(artifacts);
+    car.set_history_capacity(cfg.borrow().current().history_capacity as usize);
+    if let Some(dir) = cfg.borrow().current().history_spill.clone() {
+        match trit_store::TritStore::open(&dir) {
+            Ok(store) => car.spill_history(store),
+            Err(e) => return fail("server", &format!("history.spill {}: {}", dir, e)),
+        }
+    }
+    server.shutdown_handle().stop_on_signal();
+    match server.listen(addr, &mut car) {
+        Ok(stats) => {
+            say!("[서버] 종료 — 연결 {} · 요청 {} · 거부 {} · 버린 /ws 프레임 {}",
+                stats.connections, stats.requests, stats.rejected, hub.dropped());
+            say!("{}", watchdog.borrow().summary());
+            1
+        }
+        Err(e) => fail("server", &format!("{}: {}", addr, e)),
+    }
+}
+
 fn run_server_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ Crowny 웹서버 데모 ═══\n");
 
     let mut server = webserver::create_demo_server();
@@ -1360,6 +3430,123 @@ fn run_server_demo() {
     let resp = server.handle(&req, &mut car);
     println!("  Status: {}", resp.status);
 
+    // 6. CORS 프리플라이트
+    println!("\n━━━ 6. OPTIONS /run (CORS 프리플라이트) ━━━");
+    let req = webserver::HttpRequest::new(webserver::HttpMethod::Options, "/run")
+        .with_header("Origin", "http://localhost:3000")
+        .with_header("Access-Control-Request-Method", "POST")
+        .with_header("Access-Control-Request-Headers", "X-Crowny-Trit");
+    let resp = server.handle(&req, &mut car);
+    println!("  Status: {} | 허용 헤더: {}", resp.status,
+        resp.headers.get("Access-Control-Allow-Headers").map(|s| s.as_str()).unwrap_or("-"));
+
+    // 6-1. 27-trit 헤더 — 최소 버전만 아는 구버전 클라이언트가 중계 노드를 한 번 거쳐 보낸 요청
+    println!("\n━━━ 6-1. 27-trit CTP 헤더 (버전 범위 · 요청 ID · 홉) ━━━");
+    let v2 = webserver::CtpHeaderV2::new(webserver::CtpHeader::success(), 321)
+        .with_versions((network::MIN_PROTOCOL_VERSION, network::MIN_PROTOCOL_VERSION))
+        .next_hop();
+    println!("  요청: {}", v2);
+    let req = webserver::HttpRequest::new(webserver::HttpMethod::Get, "/")
+        .with_header(webserver::CTP_V2_HEADER, &v2.to_header_str());
+    let resp = server.handle(&req, &mut car);
+    match resp.headers.get(webserver::CTP_V2_HEADER).and_then(|h| webserver::CtpHeaderV2::from_header_str(h)) {
+        Some(reply) => println!("  응답: {} (Status {} · v{})", reply, resp.status, resp.ctp.version()),
+        None => println!("  응답: 27-trit 헤더 없음 (Status {})", resp.status),
+    }
+
+    // 7. 설정 핫 리로드 (관리자 엔드포인트)
+    println!("\n━━━ 7. POST /admin/config (설정 핫 리로드) ━━━");
+    let cfg = std::rc::Rc::new(std::cell::RefCell::new(
+        config::ConfigManager::new(config::RuntimeConfig::default())));
+    let admin_signer = capability::TokenSigner::new("demo-admin-secret");
+    let admin = admin_signer.issue("ops", &[admin_override::CONFIG_SCOPE], 60_000).encode();
+    webserver::mount_config_admin(&mut server, cfg.clone(), admin_signer);
+    for body in ["[log]\nlevel = \"debug\"\n[fees]\ndex_fee_bps = 25", "[consensus]\nquorum = 3"] {
+        let req = webserver::HttpRequest::new(webserver::HttpMethod::Post, "/admin/config")
+            .with_body(body)
+            .with_header(capability::TOKEN_HEADER, &admin);
+        let resp = server.handle(&req, &mut car);
+        println!("  Status: {} | Body: {}", resp.status, resp.body);
+    }
+    println!("  설정 버전: {}", cfg.borrow().version);
+
+    // 8. 마켓 API (DEX + NFT, 서명된 능력 토큰)
+    println!("\n━━━ 8. 마켓 API (DEX/NFT + 능력 토큰) ━━━");
+    let mut dex = dex::CrownyDEX::new();
+    dex.mint("lp", "CRWN", 1_000_000);
+    dex.mint("lp", "USDT", 200_000);
+    dex.mint("alice", "CRWN", 50_000);
+    let pool = dex.create_pool("CRWN", "USDT", 30);
+    dex.add_liquidity("lp", &pool, 1_000_000, 200_000).ok();
+    let mut market = nft::CrownyNFT::new();
+    market.fund("alice", 10_000);
+    let col = market.create_collection("Trit Punks", "TPUNK", "bob", "3진 펑크", Some(27), 500);
+    let nft_id = market.mint(&col, "bob", nft::NFTMetadata::new("Punk #0", "", "ipfs://punk0"), nft::NFTRarity::Rare)
+        .unwrap_or_default();
+    let signer = capability::TokenSigner::new("demo-secret");
+    let alice = signer.issue("alice", &["dex.*", "nft.buy", "nft.read"], 60_000).encode();
+    let bob = signer.issue("bob", &["nft.*"], 60_000).encode();
+    let dex = std::rc::Rc::new(std::cell::RefCell::new(dex));
+    let market = std::rc::Rc::new(std::cell::RefCell::new(market));
+    let portfolio_token = signer.issue("alice", &["portfolio.*"], 60_000).encode();
+    webserver::mount_market_api(&mut server, dex.clone(), market.clone(), signer.clone());
+    let calls = [
+        (webserver::HttpMethod::Post, "/dex/quote", &alice, format!("pool={}&token_in=CRWN&amount=5000", pool)),
+        (webserver::HttpMethod::Post, "/dex/swap", &alice, format!("pool={}&token_in=CRWN&amount=5000", pool)),
+        (webserver::HttpMethod::Post, "/nft/list", &alice, format!("nft={}&price=2000", nft_id)),
+        (webserver::HttpMethod::Post, "/nft/list", &bob, format!("nft={}&price=2000", nft_id)),
+        (webserver::HttpMethod::Get, "/nft/listings", &alice, String::new()),
+        (webserver::HttpMethod::Post, "/nft/buy", &alice, format!("nft={}", nft_id)),
+    ];
+    for (method, path, token, body) in calls {
+        let req = webserver::HttpRequest::new(method, path)
+            .with_header(capability::TOKEN_HEADER, token)
+            .with_body(&body);
+        let resp = server.handle(&req, &mut car);
+        println!("  {} {} → {} | CTP: {}", method, path, resp.status, resp.ctp);
+    }
+
+    // 9. 포트폴리오 API (같은 DEX/NFT 상태)
+    println!("\n━━━ 9. 포트폴리오 API ━━━");
+    let service = portfolio::PortfolioService::new(dex.clone(), market.clone());
+    webserver::mount_portfolio_api(&mut server, std::rc::Rc::new(std::cell::RefCell::new(service)), signer);
+    for (method, path) in [(webserver::HttpMethod::Get, "/portfolio"), (webserver::HttpMethod::Post, "/portfolio/snapshot"),
+                           (webserver::HttpMethod::Get, "/portfolio/history")] {
+        let req = webserver::HttpRequest::new(method, path).with_header(capability::TOKEN_HEADER, &portfolio_token);
+        let resp = server.handle(&req, &mut car);
+        println!("  {} {} → {} | CTP: {}", method, path, resp.status, resp.ctp);
+    }
+
+    // 10. 기록 조회 API (커서 페이지네이션 · 필터)
+    println!("\n━━━ 10. 기록 조회 API ━━━");
+    let events = std::rc::Rc::new(std::cell::RefCell::new(trit_log::TritEventLog::new()));
+    webserver::mount_history_api(&mut server, dex, market,
+        std::rc::Rc::new(std::cell::RefCell::new(chain::sample_chain())), events.clone());
+    for path in ["/history/swaps?account=alice&limit=5", "/history/market?trit=P&order=asc", "/history/tasks?limit=3",
+                 "/history/tasks?trit=X", "/history/blocks?limit=2", "/history/logs?order=asc"] {
+        let req = webserver::HttpRequest::new(webserver::HttpMethod::Get, path);
+        let resp = server.handle(&req, &mut car);
+        println!("  GET {} → {} | {}", path, resp.status, resp.body.chars().take(80).collect::<String>());
+    }
+
+    // 11. 경로 파라미터 + 미들웨어 (기록 → 요청 한도 → CTP 인증)
+    println!("\n━━━ 11. 경로 파라미터 + 미들웨어 ━━━");
+    server.route(webserver::HttpMethod::Get, "/task/:id", |req, _car| {
+        webserver::ok_response(format!("{{\"id\":\"{}\"}}", req.param("id").unwrap_or_default()))
+    });
+    server.add_middleware(webserver::RequestLog::new(events.clone()));
+    server.add_middleware(webserver::RateLimiter::new(3, 0.5));
+    server.add_middleware(webserver::CtpAuth::require(1).under("/task"));
+    for trits in ["PPOOOOOOO", "POOOOOOOO", "PPOOOOOOO", "PPOOOOOOO"] {
+        let req = webserver::HttpRequest::new(webserver::HttpMethod::Get, "/task/1")
+            .with_ctp(webserver::CtpHeader::from_header_str(trits));
+        let resp = server.handle(&req, &mut car);
+        println!("  GET /task/1 [{}] → {} | {}", trits, resp.status, resp.body);
+    }
+    for e in events.borrow().recent(4) {
+        println!("  {}", e.format());
+    }
+
     println!("\n  {}", server.stats());
     car.dump();
     println!("\n═══ 웹서버 데모 완료 ═══");
@@ -1370,7 +3557,7 @@ fn run_server_demo() {
 // ═══════════════════════════════════════════════
 
 fn run_llm_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ Crowny LLM 호출기 데모 ═══\n");
 
     let mut car = car::CrownyRuntime::new();
@@ -1408,12 +3595,212 @@ fn run_llm_demo() {
     println!("\n═══ LLM 호출기 데모 완료 ═══");
 }
 
+// ═══════════════════════════════════════════════
+// CPM 레지스트리 · 게시 · 받기
+// ═══════════════════════════════════════════════
+
+fn cpm_registry_url(flag: Option<&str>) -> String {
+    flag.map(String::from)
+        .or_else(|| env::var("CROWNY_REGISTRY").ok())
+        .unwrap_or_else(|| cpm::DEFAULT_REGISTRY.to_string())
+}
+
+fn cpm_signer(command: &str) -> Result<capability::TokenSigner, i8> {
+    match env::var("CROWNY_CPM_SECRET") {
+        Ok(s) if !s.is_empty() => Ok(capability::TokenSigner::new(&s)),
+        _ => Err(fail(command, "CROWNY_CPM_SECRET 환경 변수가 필요합니다")),
+    }
+}
+
+fn cpm_serve(addr: &str, dir: &str) -> i8 {
+    let signer = match cpm_signer("cpm serve") {
+        Ok(s) => s,
+        Err(state) => return state,
+    };
+    let registry = match cpm::CrownyPM::open_registry(dir) {
+        Ok(r) => r,
+        Err(e) => return fail("cpm serve", &e),
+    };
+    let (total, _, _) = registry.stats();
+    say!("[CPM] 레지스트리 {} — 패키지 {}개 · {}", addr, total, dir);
+    let mut server = webserver::CrownyServer::new(addr.rsplit(':').next().and_then(|p| p.parse().ok()).unwrap_or(0));
+    webserver::mount_registry(&mut server, std::rc::Rc::new(std::cell::RefCell::new(registry)), signer);
+    let mut car = car::CrownyRuntime::new();
+    server.shutdown_handle().stop_on_signal();
+    match server.listen(addr, &mut car) {
+        Ok(stats) => {
+            say!("[CPM] 종료 — 연결 {} · 요청 {}", stats.connections, stats.requests);
+            1
+        }
+        Err(e) => fail("cpm serve", &format!("{}: {}", addr, e)),
+    }
+}
+
+fn cpm_token(subject: &str, days: u64) -> i8 {
+    let signer = match cpm_signer("cpm token") {
+        Ok(s) => s,
+        Err(state) => return state,
+    };
+    let token = signer.issue(subject, &[cpm::PUBLISH_SCOPE], days * 24 * 60 * 60 * 1000).encode();
+    if output::is_json() {
+        JsonObject::new().str("command", "cpm token").trit("state", 1).str("subject", subject).str("token", &token).emit();
+    } else {
+        println!("{}", token);
+    }
+    1
+}
+
+fn cpm_publish(dir: &str, registry: Option<&str>) -> i8 {
+    let dir = std::path::Path::new(dir);
+    let read = |f: &str| fs::read_to_string(dir.join(f)).map_err(|e| format!("{}: {}", dir.join(f).display(), e));
+    let (manifest, source) = match (read(cpm::MANIFEST_FILE), read(cpm::SOURCE_FILE)) {
+        (Ok(m), Ok(s)) => (m, s),
+        (Err(e), _) | (_, Err(e)) => return fail("cpm publish", &e),
+    };
+    let pkg = match cpm::Package::from_toml(&manifest) {
+        Ok(p) => p,
+        Err(e) => return fail("cpm publish", &e),
+    };
+    let url = cpm_registry_url(registry);
+    let mut client = cpm::RegistryClient::new(&url);
+    if let Ok(token) = env::var("CROWNY_CPM_TOKEN") {
+        client = client.with_token(&token);
+    }
+    match client.publish(&pkg, &source) {
+        Ok(checksum) => {
+            if output::is_json() {
+                JsonObject::new().str("command", "cpm publish").trit("state", 1)
+                    .str("name", &pkg.name).str("version", &pkg.version.to_string())
+                    .str("registry", &url).str("checksum", &checksum).emit();
+            } else {
+                println!("[P] 게시 {} v{} → {} (checksum {})", pkg.name, pkg.version, url, checksum);
+            }
+            1
+        }
+        Err(e) => fail("cpm publish", &e),
+    }
+}
+
+fn cpm_fetch(name: &str, version: Option<&str>, registry: Option<&str>, root: Option<&str>) -> i8 {
+    let version = match version.map(|v| cpm::Version::parse(v).ok_or(v)) {
+        Some(Err(v)) => return fail("cpm fetch", &format!("--version: 잘못된 버전 ({})", v)),
+        Some(Ok(v)) => Some(v),
+        None => None,
+    };
+    let mut pm = match root {
+        Some(dir) => cpm::CrownyPM::new().with_root(dir),
+        None => cpm::CrownyPM::new().with_default_root(),
+    };
+    let url = cpm_registry_url(registry);
+    let fetched = match pm.fetch(&cpm::RegistryClient::new(&url), name, version.as_ref()) {
+        Ok(f) => f,
+        Err(e) => return fail("cpm fetch", &e),
+    };
+    // 받은 버전 그대로 설치 (--version 없으면 해석기가 최신을 고른다)
+    let req = version.map(|v| format!("={}", v)).unwrap_or_else(|| "*".into());
+    let resolution = pm.resolve(&[cpm::Dependency::new(name, &req)], false);
+    let result = pm.install_resolved(&resolution);
+    let state = result.state as i8;
+    if output::is_json() {
+        JsonObject::new().str("command", "cpm fetch").trit("state", state).str("registry", &url)
+            .strs("fetched", &fetched).strs("installed", &result.installed).strs("failed", &result.failed)
+            .str("root", &pm.root().map(|r| r.display().to_string()).unwrap_or_default())
+            .emit();
+    } else {
+        for f in &fetched {
+            println!("  ↓ {} (체크섬 확인)", f);
+        }
+        for i in &result.installed {
+            println!("  ✓ {}", i);
+        }
+        for f in &result.failed {
+            eprintln!("  ✗ {}", f);
+        }
+        println!("[{}] {} — {}", output::trit_symbol(state), name, pm.root().map(|r| r.display().to_string()).unwrap_or_else(|| "메모리".into()));
+    }
+    state
+}
+
+fn cpm_search(query: &str, registry: Option<&str>) -> i8 {
+    let url = cpm_registry_url(registry);
+    let found = match cpm::RegistryClient::new(&url).search(query) {
+        Ok(found) => found,
+        Err(e) => return fail("cpm search", &e),
+    };
+    if output::is_json() {
+        JsonObject::new().str("command", "cpm search").trit("state", 1).str("registry", &url)
+            .objects("packages", found.iter().map(|p| p.to_json()).collect())
+            .emit();
+    } else {
+        for pkg in &found {
+            println!("  {} v{} [{}] — {}", pkg.name, pkg.version, pkg.trust, pkg.description);
+        }
+        println!("[P] {}건 — {}", found.len(), url);
+    }
+    1
+}
+
+/// 설치 후 ./crowny.lock 기록 — locked면 잠금 파일대로 설치하고 디스크 소스를 체크섬과 대조
+fn cpm_install(name: Option<&str>, locked: bool, root: Option<&str>) -> i8 {
+    let mut pm = match root {
+        Some(dir) => cpm::CrownyPM::new().with_root(dir),
+        None => cpm::CrownyPM::new().with_default_root(),
+    };
+    let (result, mismatched, lock_path) = match (name, locked) {
+        (Some(name), false) => {
+            let result = pm.install(name);
+            if result.state == car::TritState::Failed {
+                (result, Vec::new(), None)
+            } else {
+                match pm.write_lock(".") {
+                    Ok(path) => (result, Vec::new(), Some(path)),
+                    Err(e) => return fail("cpm install", &e),
+                }
+            }
+        }
+        (None, true) => {
+            let lock = match cpm::Lockfile::read(cpm::LOCK_FILE) {
+                Ok(lock) => lock,
+                Err(e) => return fail("cpm install", &e),
+            };
+            let result = pm.install_locked(&lock);
+            let mismatched = pm.verify_lock(&lock);
+            (result, mismatched, None)
+        }
+        _ => usage("cpm install: 패키지 이름이나 --locked 중 하나만"),
+    };
+    let state = if mismatched.is_empty() { result.state as i8 } else { -1 };
+    if output::is_json() {
+        JsonObject::new().str("command", "cpm install").trit("state", state)
+            .strs("installed", &result.installed).strs("skipped", &result.skipped).strs("failed", &result.failed)
+            .strs("mismatched", &mismatched)
+            .str("lock", &lock_path.map(|p| p.display().to_string()).unwrap_or_default())
+            .str("root", &pm.root().map(|r| r.display().to_string()).unwrap_or_default())
+            .emit();
+    } else {
+        for i in &result.installed {
+            println!("  ✓ {}", i);
+        }
+        for f in &result.failed {
+            eprintln!("  ✗ {}", f);
+        }
+        for m in &mismatched {
+            eprintln!("  ✗ {} — 디스크 소스가 체크섬과 다름", m);
+        }
+        if let Some(path) = &lock_path {
+            println!("  {} 기록", path.display());
+        }
+        println!("[{}] {}", output::trit_symbol(state), pm.root().map(|r| r.display().to_string()).unwrap_or_else(|| "메모리".into()));
+    }
+    state
+}
+
 // ═══════════════════════════════════════════════
 // CPM (Crowny Package Manager) 데모
 // ═══════════════════════════════════════════════
 
 fn run_cpm_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ CPM (Crowny Package Manager) 데모 ═══\n");
 
     let mut cpm = cpm::CrownyPM::new();
@@ -1437,6 +3824,10 @@ fn run_cpm_demo() {
     let result = cpm.install("crowny.edu");
     println!("  상태: {:?}", result.state);
     println!("  설치됨: {:?}", result.installed);
+    println!("  {}:", cpm::LOCK_FILE);
+    for p in cpm.lockfile().packages {
+        println!("    {} v{}  checksum {}", p.name, p.version, p.checksum);
+    }
 
     // 4. import 해석
     println!("\n━━━ 4. import 해석 ━━━");
@@ -1467,6 +3858,17 @@ fn run_cpm_demo() {
     manifest.add_dep("crowny.ai", ">=0.1.0");
     manifest.add_dep("crowny.web", ">=0.1.0");
     println!("{}", manifest.to_toml());
+    let resolution = cpm.resolve_for(&manifest.name, &manifest.dependencies, true);
+    let order: Vec<String> = resolution.order.iter().map(|(n, v)| format!("{} {}", n, v)).collect();
+    println!("  해석 [{}]: {}", resolution.state, order.join(" → "));
+    for conflict in &resolution.conflicts {
+        println!("  {}", conflict);
+    }
+    if let Some(v) = resolution.version_of("crowny.core") {
+        println!("  crowny.core >=0.3.0 → v{}", v);
+    }
+    let result = cpm.install_manifest(&manifest, true);
+    println!("  설치: {:?} {:?}", result.state, result.installed);
 
     // 7. 설치 현황
     let (_, installed, _) = cpm.stats();
@@ -1482,39 +3884,68 @@ fn run_cpm_demo() {
 // Trit Test Framework 데모
 // ═══════════════════════════════════════════════
 
-fn run_test_demo() {
-    println!("{}", BANNER);
-    println!("═══ Trit Test Framework 데모 ═══\n");
-
-    // 1. 코어 테스트
-    println!("━━━ 1. 코어 TVM 테스트 ━━━");
-    let result = trit_test::core_suite().run();
-    print!("{}", result.report());
-
-    // 2. 상태 전이 테스트
-    println!("\n━━━ 2. 상태 전이 규칙 테스트 ━━━");
-    let result = trit_test::transition_suite().run();
-    print!("{}", result.report());
-
-    // 3. CAR 통합 테스트
-    println!("\n━━━ 3. CAR 통합 테스트 ━━━");
-    let result = trit_test::car_suite().run();
-    print!("{}", result.report());
+fn run_test_demo(props: trit_test::PropConfig) -> i8 {
+    output::banner(BANNER);
+    say!("═══ Trit Test Framework 데모 ═══\n");
+
+    // 골든 출력은 첫 실행에 기록, 이후 실행부터 비교
+    let golden = std::env::temp_dir().join("crowny-golden").join("피타고라스.out");
+    let custom = trit_test::TestSuite::new("피타고라스 검증")
+        .with(trit_test::source_test("3²+4²=25", "넣어 3\n제곱\n넣어 4\n제곱\n더해\n종료", 25))
+        .with(trit_test::ProgramTest::new("5²=25", "넣어 5\n제곱\n복사\n보여줘\n종료").expect_output(&["25"]).expect_ints(&[25]))
+        .with(trit_test::ProgramTest::new("3²+4²_출력", "넣어 3\n제곱\n넣어 4\n제곱\n더해\n복사\n보여줘\n종료")
+            .expect_line("25").expect_top(25).expect_golden(golden))
+        .with(trit_test::ProgramTest::new("무한반복_한도", "반복:\n점프 반복")
+            .with_limits(vm::ExecLimits { max_cycles: Some(100), ..vm::ExecLimits::unlimited() })
+            .expect_state(car::TritState::Pending));
+
+    let suites = vec![
+        ("1. 코어 TVM 테스트", trit_test::core_suite()),
+        ("2. 상태 전이 규칙 테스트", trit_test::transition_suite()),
+        ("3. CAR 통합 테스트", trit_test::car_suite()),
+        ("4. 합의 엔진 테스트", trit_test::consensus_suite()),
+        ("5. 커스텀 테스트 (피타고라스)", custom),
+        ("6. 형식 호환성 테스트", conformance::conformance_suite(props.seed, props.cases)),
+        ("7. 속성 기반 테스트", trit_test::property_suite(props)),
+    ];
 
-    // 4. 합의 엔진 테스트
-    println!("\n━━━ 4. 합의 엔진 테스트 ━━━");
-    let result = trit_test::consensus_suite().run();
-    print!("{}", result.report());
+    let mut results = Vec::new();
+    for (i, (title, suite)) in suites.into_iter().enumerate() {
+        if i > 0 {
+            say!();
+        }
+        say!("━━━ {} ━━━", title);
+        let result = suite.run();
+        if output::is_text() {
+            print!("{}", result.report());
+        }
+        results.push(result);
+    }
 
-    // 5. 커스텀 테스트
-    println!("\n━━━ 5. 커스텀 테스트 (피타고라스) ━━━");
-    let mut suite = trit_test::TestSuite::new("피타고라스 검증");
-    suite.add(trit_test::source_test("3²+4²=25", "넣어 3\n제곱\n넣어 4\n제곱\n더해\n종료", 25));
-    suite.add(trit_test::source_test("5²=25", "넣어 5\n제곱\n종료", 25));
-    let result = suite.run();
-    print!("{}", result.report());
+    let failed: usize = results.iter().map(|r| r.failed).sum();
+    let state = if failed == 0 { 1 } else { -1 };
+    if output::is_json() {
+        let suites = results.iter().map(|r| JsonObject::new()
+            .str("name", &r.suite_name)
+            .trit("state", if r.failed == 0 { 1 } else { -1 })
+            .int("total", r.total as i64)
+            .int("passed", r.passed as i64)
+            .int("failed", r.failed as i64)
+            .int("elapsed_ms", r.elapsed_ms as i64)).collect();
+        let errors: Vec<String> = results.iter().flat_map(|r| r.details.iter().flat_map(move |(case, asserts)| {
+            asserts.iter().filter(|a| !a.passed).map(move |a| {
+                format!("{} / {} / {} — 예상:{} 실제:{}", r.suite_name, case, a.name, a.expected, a.actual)
+            })
+        })).collect();
+        let data = JsonObject::new()
+            .int("total", results.iter().map(|r| r.total as i64).sum())
+            .int("failed", failed as i64)
+            .objects("suites", suites);
+        JsonObject::envelope("test", state, &errors, data).emit();
+    }
 
-    println!("\n═══ Trit Test Framework 데모 완료 ═══");
+    say!("\n═══ Trit Test Framework 데모 완료 ═══");
+    state
 }
 
 // ═══════════════════════════════════════════════
@@ -1522,7 +3953,7 @@ fn run_test_demo() {
 // ═══════════════════════════════════════════════
 
 fn run_debug_demo() {
-    println!("{}", BANNER);
+    output::banner(BANNER);
     println!("═══ Trit Debugger 데모 ═══\n");
 
     // 1. 전체 실행 + 트레이스
@@ -1579,12 +4010,26 @@ fn run_debug_demo() {
     println!("\n═══ Trit Debugger 데모 완료 ═══");
 }
 
-fn debug_file(input: &str) {
+fn debug_file(input: &str, hanseon: bool, interactive: bool) {
     let source = match fs::read_to_string(input) {
         Ok(s) => s,
         Err(e) => { eprintln!("파일 읽기 오류: {}", e); return; }
     };
-    let mut dbg = debugger::TritDebugger::from_source(&source);
+    let mut dbg = if hanseon {
+        match debugger::TritDebugger::from_hanseon(&source) {
+            Ok(d) => d,
+            Err(errors) => {
+                for e in errors { eprintln!("한선어 컴파일 오류: {}", e); }
+                return;
+            }
+        }
+    } else {
+        debugger::TritDebugger::from_source(&source)
+    };
+    if interactive {
+        debug_interactive(input, dbg);
+        return;
+    }
     dbg.run_all();
     print!("{}", dbg.dump_trace());
     print!("{}", dbg.dump_stack());
@@ -1592,27 +4037,74 @@ fn debug_file(input: &str) {
     println!("최종값: {:?}", dbg.result_value());
 }
 
+/// 대화형 디버거 — stdin에서 명령을 읽어 실행, q 또는 EOF로 끝
+fn debug_interactive(input: &str, mut dbg: debugger::TritDebugger) {
+    dbg.load();
+    println!("=== Trit Debugger — {} (h: 도움말, q: 종료) ===", input);
+    print!("{}", dbg.info());
+    let stdin = io::stdin();
+    loop {
+        print!("(dbg) ");
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        if stdin.read_line(&mut line).unwrap_or(0) == 0 {
+            println!();
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        match dbg.execute(debugger::parse_debug_cmd(&line)) {
+            Some(out) => print!("{}", out),
+            None => break,
+        }
+    }
+}
+
 // ═══════════════════════════════════════════════
 // Trit Persistent Layer 데모
 // ═══════════════════════════════════════════════
 
-fn run_store_demo() {
-    println!("{}", BANNER);
-    println!("═══ Trit Persistent Layer 데모 ═══\n");
-
-    let mut store = trit_store::TritStore::new();
+fn run_store_demo(dir: Option<&str>, sync: Option<&str>) -> i8 {
+    let sync = match sync.map(trit_store::SyncMode::parse) {
+        None => trit_store::SyncMode::Always,
+        Some(Some(mode)) => mode,
+        Some(None) => return fail("store", "--sync는 always · never · 양의 정수"),
+    };
+    output::banner(BANNER);
+    say!("═══ Trit Persistent Layer 데모 ═══\n");
+
+    let mut store = match dir {
+        Some(dir) => match trit_store::TritStore::open_with(dir, sync) {
+            Ok(store) => store,
+            Err(e) => return fail("store", &format!("저장소 열기 실패: {}", e)),
+        },
+        None => trit_store::TritStore::new(),
+    };
+    if let Some(rec) = store.recovery() {
+        say!("━━━ 0. 복구 ({}) ━━━", dir.unwrap_or_default());
+        say!("  스냅샷: {} | WAL 재생: {}개 | 잘린 꼬리: {} bytes | 항목: {}개",
+            rec.snapshot.map_or("-".to_string(), |id| format!("#{}", id)), rec.replayed, rec.torn_bytes, store.len());
+        if let Some(backup) = &rec.torn_backup {
+            say!("  잘라내기 전 WAL 백업: {}", backup.display());
+        }
+        for bad in &rec.bad_snapshots {
+            say!("  ⚠ 손상 스냅샷 건너뜀: {}", bad);
+        }
+        say!("");
+    }
 
     // 1. 기본 CRUD
-    println!("━━━ 1. 기본 CRUD ━━━");
+    say!("━━━ 1. 기본 CRUD ━━━");
     store.set("서비스명", trit_store::StoreValue::Text("Crowny AI".into()));
     store.set("버전", trit_store::StoreValue::Int(4));
     store.set("활성화", trit_store::StoreValue::Trit(1));
     store.set("정확도", trit_store::StoreValue::Float(0.9731));
-    println!("  저장: 4개 항목");
-    println!("  크기: ~{} bytes", store.estimated_size());
+    say!("  저장: 4개 항목");
+    say!("  크기: ~{} bytes", store.estimated_size());
 
     // 2. Trit 상태 인덱싱
-    println!("\n━━━ 2. Trit 상태 인덱싱 ━━━");
+    say!("\n━━━ 2. Trit 상태 인덱싱 ━━━");
     store.set("task_compile", trit_store::StoreValue::Text("컴파일 완료".into()));
     store.set("task_deploy", trit_store::StoreValue::Text("배포 대기".into()));
     store.set("task_test", trit_store::StoreValue::Text("테스트 실패".into()));
@@ -1621,60 +4113,98 @@ fn run_store_demo() {
     store.set_trit_state("task_test", -1);
 
     let (p, o, t) = store.trit_stats();
-    println!("  상태: P:{} O:{} T:{}", p, o, t);
-    println!("  P 목록: {:?}", store.filter_by_trit(1));
-    println!("  T 목록: {:?}", store.filter_by_trit(-1));
+    say!("  상태: P:{} O:{} T:{}", p, o, t);
+    say!("  P 목록: {:?}", store.filter_by_trit(1));
+    say!("  T 목록: {:?}", store.filter_by_trit(-1));
 
     // 3. 트랜잭션
-    println!("\n━━━ 3. 트랜잭션 ━━━");
+    say!("\n━━━ 3. 트랜잭션 ━━━");
     store.begin();
     store.set("tx_data1", trit_store::StoreValue::Int(100));
     store.set("tx_data2", trit_store::StoreValue::Int(200));
     store.commit();
-    println!("  커밋 후: {}개 항목", store.len());
+    say!("  커밋 후: {}개 항목", store.len());
 
     store.begin();
     store.set("tx_rollback", trit_store::StoreValue::Int(999));
     store.rollback();
-    println!("  롤백 후: {}개 (tx_rollback 없음: {})", store.len(), !store.exists("tx_rollback"));
+    say!("  롤백 후: {}개 (tx_rollback 없음: {})", store.len(), !store.exists("tx_rollback"));
 
     // 4. Snapshot
-    println!("\n━━━ 4. Snapshot/복구 ━━━");
+    say!("\n━━━ 4. Snapshot/복구 ━━━");
     let snap_id = store.snapshot();
-    println!("  Snapshot#{} 생성 ({}개 항목)", snap_id, store.len());
+    say!("  Snapshot#{} 생성 ({}개 항목)", snap_id, store.len());
 
     store.set("임시", trit_store::StoreValue::Text("삭제될 데이터".into()));
     store.delete("버전");
-    println!("  변경 후: {}개 항목", store.len());
+    say!("  변경 후: {}개 항목", store.len());
 
     store.restore(snap_id);
-    println!("  복구 후: {}개 항목 (원래 상태)", store.len());
+    say!("  복구 후: {}개 항목 (원래 상태)", store.len());
 
     // 5. 네임스페이스
-    println!("\n━━━ 5. 네임스페이스 ━━━");
+    say!("\n━━━ 5. 네임스페이스 ━━━");
     let mut ns = trit_store::NamespacedStore::new();
     ns.get_or_create("crowny.ai").set("model", trit_store::StoreValue::Text("Claude".into()));
     ns.get_or_create("crowny.web").set("port", trit_store::StoreValue::Int(7293));
     ns.get_or_create("crowny.token").set("supply", trit_store::StoreValue::Int(729_000_000));
-    println!("  네임스페이스: {}개 | 총 항목: {}", ns.namespaces().len(), ns.total_entries());
+    say!("  네임스페이스: {}개 | 총 항목: {}", ns.namespaces().len(), ns.total_entries());
 
     // 6. 통계
-    println!("\n━━━ 6. 통계 ━━━");
-    println!("  {}", store.stats());
-    println!("  WAL: {}개 엔트리", store.wal_len());
+    say!("\n━━━ 6. 통계 ━━━");
+    say!("  {}", store.stats());
+    say!("  WAL: {}개 엔트리", store.wal_len());
+    if let Err(e) = store.sync() {
+        return fail("store", &format!("fsync 실패: {}", e));
+    }
+    if let Some(e) = store.persist_error() {
+        return fail("store", &format!("파일 기록 실패: {}", e));
+    }
+
+    if output::is_json() {
+        let (p, o, t) = store.trit_stats();
+        JsonObject::new()
+            .str("command", "store")
+            .trit("state", 1)
+            .int("entries", store.len() as i64)
+            .int("estimated_bytes", store.estimated_size() as i64)
+            .int("wal", store.wal_len() as i64)
+            .object("trit", JsonObject::new().int("P", p as i64).int("O", o as i64).int("T", t as i64))
+            .int("namespaces", ns.namespaces().len() as i64)
+            .int("namespace_entries", ns.total_entries() as i64)
+            .emit();
+    }
 
-    println!("\n═══ Trit Persistent Layer 데모 완료 ═══");
+    say!("\n═══ Trit Persistent Layer 데모 완료 ═══");
+    1
 }
 
 // ═══════════════════════════════════════════════
 // Trit Event Log 데모
 // ═══════════════════════════════════════════════
 
-fn run_log_demo() {
-    println!("{}", BANNER);
+fn run_log_demo(file: Option<&str>, max_bytes: Option<u64>, max_age_secs: Option<u64>, keep: Option<u64>) {
+    output::banner(BANNER);
     println!("═══ Trit Event Log (Observability) 데모 ═══\n");
 
     let mut log = trit_log::TritEventLog::new();
+    if let Some(path) = file {
+        match trit_log::RotatingFileSink::open(path) {
+            Ok(mut sink) => {
+                if let Some(bytes) = max_bytes {
+                    sink = sink.max_bytes(bytes);
+                }
+                if let Some(secs) = max_age_secs {
+                    sink = sink.max_age(std::time::Duration::from_secs(secs));
+                }
+                if let Some(n) = keep {
+                    sink = sink.keep(n as usize);
+                }
+                log.add_sink(Box::new(sink));
+            }
+            Err(e) => { eprintln!("로그 파일 열기 실패: {}", e); return; }
+        }
+    }
 
     // 알림 규칙 등록
     log.add_alert(trit_log::AlertRule::new("에러감지", trit_log::Category::Task, trit_log::Level::Error));
@@ -1718,7 +4248,15 @@ fn run_log_demo() {
 
     // 5. 상태 전이
     println!("━━━ 5. 상태 전이 ━━━");
+    use trit_log::{tlog, tlog_debug, tlog_trace};
+    let before = log.recent(usize::MAX).len();
+    tlog_debug!(log, trit_log::Category::State, "scheduler", "큐 깊이 {:?}", [3, 1, 0]);
+    println!("  Info 레벨: tlog_debug! → 인자 포맷 안 함 (기록 {}건 증가)", log.recent(usize::MAX).len() - before);
     log.set_min_level(trit_log::Level::Debug);
+    tlog_debug!(log, trit_log::Category::State, "scheduler", "큐 깊이 {:?}", [3, 1, 0]);
+    tlog_trace!(log, trit_log::Category::State, "scheduler", "틱 {}", 729);
+    tlog!(log, trit_log::Level::Info, trit_log::Category::State, "scheduler", car::TritState::Success,
+        "정적 최소 레벨 {}", trit_log::STATIC_MIN_LEVEL);
     log.state_transition("task_deploy", 0, 1);  // O → P
     log.state_transition("task_test", -1, 0);   // T → O (복구)
     log.state_transition("service", 1, 0);      // P → O (경고)
@@ -1737,5 +4275,56 @@ fn run_log_demo() {
     println!("━━━ 8. 요약 보고서 ━━━");
     print!("{}", log.summary());
 
+    if let Some(path) = file {
+        let flushed = log.flush();
+        let error = flushed.err().or_else(|| log.sink_failures().1.map(str::to_string));
+        match error {
+            None => println!("  → {} (JSON-lines)", path),
+            Some(e) => eprintln!("로그 파일 기록 실패: {}", e),
+        }
+    }
+
     println!("\n═══ Trit Event Log 데모 완료 ═══");
 }
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("crowny-cli-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_bytecode_round_trip_runs_examples() {
+        let dir = temp_dir("bytecode");
+        for ex in examples::registry() {
+            let src = dir.join(format!("{}.hsn", ex.name));
+            let out = dir.join(format!("{}.크라운", ex.name));
+            fs::write(&src, ex.source).unwrap();
+            assert_eq!(bytecode_file(src.to_str().unwrap(), out.to_str().unwrap(), false), 1, "{}", ex.name);
+
+            let program = bytecode::open_file(&out).unwrap();
+            let run = examples::run_program(ex.name, program, examples::expected_output(ex.source));
+            assert_eq!(run.state, 1, "{}: {:?} {:?}", ex.name, run.first_mismatch(), run.error);
+        }
+        // 라벨(앞 참조 + 재귀 호출)을 쓰는 예제 — run-bytecode 경로 그대로
+        let factorial = dir.join("factorial.크라운");
+        assert_eq!(run_bytecode_file(factorial.to_str().unwrap(), Some(100_000)), 1);
+    }
+
+    #[test]
+    fn test_bytecode_undefined_label_fails() {
+        let dir = temp_dir("bytecode-label");
+        let src = dir.join("bad.hsn");
+        let out = dir.join("bad.크라운");
+        fs::write(&src, "넣어 1\n조건점프 없는곳\n종료\n").unwrap();
+        assert_eq!(bytecode_file(src.to_str().unwrap(), out.to_str().unwrap(), false), -1);
+        assert!(!out.exists());
+    }
+}
@@ -0,0 +1,538 @@
+// ═══════════════════════════════════════════════════════════════
+// 마진 — DEX 페어 레버리지 포지션 · 청산 엔진 · 보험 기금
+// 담보(풀의 기준 토큰) × 레버리지 → 포지션, 가격은 TWAP 오라클
+// 청산 검사는 커널 스케줄러 태스크로 주기 실행
+//
+// 경고 판정: O = 경고 증거금 미만 · T = 유지 증거금 미만 → 청산
+//   청산 잔여 담보에서 페널티를 보험 기금으로, 부족분(악성 부채)은 기금이 메운다
+// ═══════════════════════════════════════════════════════════════
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::dex::CrownyDEX;
+use crate::kernel::CrownyKernel;
+use crate::output::JsonObject;
+use crate::scheduler::{TritPriority, TritResult};
+
+// ═══════════════════════════════════════
+// TWAP 오라클
+// ═══════════════════════════════════════
+
+/// 시간 가중 평균 가격 — 순간 가격 조작(한 블록 스왑)으로 청산을 유발하지 못하게 한다
+#[derive(Debug, Clone)]
+pub struct TwapOracle {
+    pub window_ms: u64,
+    observations: HashMap<String, VecDeque<(u64, f64)>>,
+}
+
+impl TwapOracle {
+    pub fn new(window_ms: u64) -> Self {
+        Self { window_ms, observations: HashMap::new() }
+    }
+
+    /// 가격 관측 (시각은 단조 증가해야 한다 — 과거 시각은 무시)
+    pub fn observe(&mut self, pool_id: &str, price: f64, ts: u64) {
+        let obs = self.observations.entry(pool_id.into()).or_default();
+        if obs.back().is_some_and(|(last, _)| ts < *last) || !price.is_finite() || price <= 0.0 {
+            return;
+        }
+        obs.push_back((ts, price));
+        // 창 시작 이전 관측은 하나만 남긴다 (창 시작 시점의 가격)
+        while obs.len() > 1 && obs[1].0 <= ts.saturating_sub(self.window_ms) {
+            obs.pop_front();
+        }
+    }
+
+    /// DEX 전체 풀의 현재 가격 관측 (token_a 가격, token_b 단위)
+    pub fn observe_dex(&mut self, dex: &CrownyDEX, ts: u64) {
+        for pool in dex.pools.values().filter(|p| p.reserve_a > 0 && p.reserve_b > 0) {
+            self.observe(&pool.id, pool.price_a_in_b(), ts);
+        }
+    }
+
+    /// [now - window, now] 구간 TWAP (관측 하나면 그 가격)
+    pub fn twap(&self, pool_id: &str, now: u64) -> Option<f64> {
+        let obs = self.observations.get(pool_id)?;
+        let start = now.saturating_sub(self.window_ms);
+        let (mut weighted, mut span) = (0.0, 0u64);
+        for (i, (ts, price)) in obs.iter().enumerate() {
+            let from = (*ts).max(start);
+            let to = obs.get(i + 1).map_or(now, |(next, _)| *next).min(now);
+            if to > from {
+                weighted += price * (to - from) as f64;
+                span += to - from;
+            }
+        }
+        if span == 0 {
+            obs.back().map(|(_, p)| *p)
+        } else {
+            Some(weighted / span as f64)
+        }
+    }
+}
+
+// ═══════════════════════════════════════
+// 포지션
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionSide { Long, Short }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionStatus { Open, Closed, Liquidated }
+
+#[derive(Debug, Clone)]
+pub struct MarginPosition {
+    pub id: u64,
+    pub owner: String,
+    pub pool_id: String,
+    /// 담보 토큰 (풀의 token_b)
+    pub collateral_token: String,
+    pub side: PositionSide,
+    /// token_a 수량
+    pub size: f64,
+    pub entry_price: f64,
+    pub collateral: u64,
+    pub leverage: f64,
+    pub status: PositionStatus,
+    pub opened_at: u64,
+}
+
+impl MarginPosition {
+    pub fn pnl(&self, price: f64) -> f64 {
+        let diff = price - self.entry_price;
+        match self.side {
+            PositionSide::Long => self.size * diff,
+            PositionSide::Short => -self.size * diff,
+        }
+    }
+
+    pub fn equity(&self, price: f64) -> f64 {
+        self.collateral as f64 + self.pnl(price)
+    }
+
+    pub fn notional(&self, price: f64) -> f64 {
+        self.size * price
+    }
+
+    /// 증거금 비율 = 자기자본 / 명목가
+    pub fn margin_ratio(&self, price: f64) -> f64 {
+        let notional = self.notional(price);
+        if notional <= 0.0 { f64::INFINITY } else { self.equity(price) / notional }
+    }
+}
+
+impl MarginPosition {
+    pub fn to_json(&self) -> JsonObject {
+        let side = match self.side { PositionSide::Long => "long", PositionSide::Short => "short" };
+        let status = match self.status {
+            PositionStatus::Open => "open", PositionStatus::Closed => "closed", PositionStatus::Liquidated => "liquidated",
+        };
+        JsonObject::schema("crowny.margin_position")
+            .int("id", self.id as i64)
+            .str("owner", &self.owner)
+            .str("pool", &self.pool_id)
+            .str("side", side)
+            .str("status", status)
+            .float("size", self.size)
+            .float("entry_price", self.entry_price)
+            .int("collateral", self.collateral as i64)
+            .str("collateral_token", &self.collateral_token)
+            .float("leverage", self.leverage)
+            .int("opened_at", self.opened_at as i64)
+    }
+}
+
+impl std::fmt::Display for MarginPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let side = match self.side { PositionSide::Long => "롱", PositionSide::Short => "숏" };
+        let status = match self.status {
+            PositionStatus::Open => "보유", PositionStatus::Closed => "종료", PositionStatus::Liquidated => "청산",
+        };
+        write!(f, "#{} {} {} {}x{:.1} @ {:.6} — 담보 {} {} ({})", self.id, self.owner, self.pool_id,
+            side, self.leverage, self.entry_price, self.collateral, self.collateral_token, status)
+    }
+}
+
+// ═══════════════════════════════════════
+// 설정 · 이벤트 · 보험 기금
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginConfig {
+    pub max_leverage: f64,
+    /// 유지 증거금 비율 — 미만이면 청산
+    pub maintenance_margin: f64,
+    /// 경고 증거금 비율 — 미만이면 O 경고
+    pub warning_margin: f64,
+    /// 청산 페널티 (명목가 대비, 보험 기금 적립)
+    pub liquidation_penalty: f64,
+}
+
+impl Default for MarginConfig {
+    fn default() -> Self {
+        Self { max_leverage: 10.0, maintenance_margin: 0.05, warning_margin: 0.08, liquidation_penalty: 0.02 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarginEvent {
+    pub position: u64,
+    pub owner: String,
+    /// O = 경고 · T = 청산
+    pub trit: i8,
+    pub price: f64,
+    pub margin_ratio: f64,
+    /// 청산 시 사용자에게 돌려준 담보
+    pub returned: u64,
+    /// 청산 시 보험 기금 적립(+) / 지급(-)
+    pub insurance_delta: i64,
+    pub timestamp: u64,
+}
+
+impl MarginEvent {
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::schema("crowny.margin_event")
+            .trit("state", self.trit)
+            .int("position", self.position as i64)
+            .str("owner", &self.owner)
+            .float("price", self.price)
+            .float("margin_ratio", self.margin_ratio)
+            .int("returned", self.returned as i64)
+            .int("insurance_delta", self.insurance_delta)
+            .int("timestamp", self.timestamp as i64)
+    }
+}
+
+impl std::fmt::Display for MarginEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = if self.trit == -1 { "청산" } else { "경고" };
+        write!(f, "[{}] #{} {} {} — 가격 {:.6} · 증거금 {:.2}%", crate::output::trit_symbol(self.trit),
+            self.position, self.owner, label, self.price, self.margin_ratio * 100.0)?;
+        if self.trit == -1 {
+            write!(f, " · 반환 {} · 기금 {:+}", self.returned, self.insurance_delta)?;
+        }
+        Ok(())
+    }
+}
+
+/// 토큰별 보험 기금 장부
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InsuranceFund {
+    pub balances: HashMap<String, u64>,
+    pub collected: u64,
+    pub paid: u64,
+    /// 기금으로도 메우지 못한 악성 부채
+    pub uncovered: u64,
+}
+
+impl InsuranceFund {
+    pub fn balance(&self, token: &str) -> u64 {
+        self.balances.get(token).copied().unwrap_or(0)
+    }
+
+    pub fn deposit(&mut self, token: &str, amount: u64) {
+        *self.balances.entry(token.into()).or_insert(0) += amount;
+        self.collected += amount;
+    }
+
+    /// 부족분 지급 → 실제 지급액
+    fn cover(&mut self, token: &str, deficit: u64) -> u64 {
+        let bal = self.balances.entry(token.into()).or_insert(0);
+        let paid = deficit.min(*bal);
+        *bal -= paid;
+        self.paid += paid;
+        self.uncovered += deficit - paid;
+        paid
+    }
+}
+
+// ═══════════════════════════════════════
+// 마진 엔진
+// ═══════════════════════════════════════
+
+pub struct MarginEngine {
+    pub config: MarginConfig,
+    pub oracle: TwapOracle,
+    pub positions: Vec<MarginPosition>,
+    /// user → token → 미사용 담보
+    pub collateral: HashMap<String, HashMap<String, u64>>,
+    pub insurance: InsuranceFund,
+    pub events: Vec<MarginEvent>,
+    /// 풀 → (token_a, token_b)
+    pairs: HashMap<String, (String, String)>,
+}
+
+impl MarginEngine {
+    pub fn new(config: MarginConfig, twap_window_ms: u64) -> Self {
+        Self {
+            config, oracle: TwapOracle::new(twap_window_ms), positions: Vec::new(),
+            collateral: HashMap::new(), insurance: InsuranceFund::default(),
+            events: Vec::new(), pairs: HashMap::new(),
+        }
+    }
+
+    /// DEX 페어 등록 + 현재 가격 관측
+    pub fn sync_dex(&mut self, dex: &CrownyDEX, ts: u64) {
+        for pool in dex.pools.values() {
+            self.pairs.insert(pool.id.clone(), (pool.token_a.clone(), pool.token_b.clone()));
+        }
+        self.oracle.observe_dex(dex, ts);
+    }
+
+    pub fn free_collateral(&self, user: &str, token: &str) -> u64 {
+        self.collateral.get(user).and_then(|m| m.get(token)).copied().unwrap_or(0)
+    }
+
+    fn credit(&mut self, user: &str, token: &str, amount: u64) {
+        *self.collateral.entry(user.into()).or_default().entry(token.into()).or_insert(0) += amount;
+    }
+
+    /// DEX 잔액 → 마진 담보
+    pub fn deposit(&mut self, dex: &mut CrownyDEX, user: &str, token: &str, amount: u64) -> Result<(), String> {
+        let bal = dex.balance(user, token);
+        if bal < amount {
+            return Err(format!("{} 잔액 부족 ({})", token, bal));
+        }
+        *dex.balances.get_mut(user).unwrap().get_mut(token).unwrap() -= amount;
+        self.credit(user, token, amount);
+        Ok(())
+    }
+
+    /// 마진 담보 → DEX 잔액
+    pub fn withdraw(&mut self, dex: &mut CrownyDEX, user: &str, token: &str, amount: u64) -> Result<(), String> {
+        let free = self.free_collateral(user, token);
+        if free < amount {
+            return Err(format!("미사용 담보 부족 ({})", free));
+        }
+        *self.collateral.get_mut(user).unwrap().get_mut(token).unwrap() -= amount;
+        dex.mint(user, token, amount);
+        Ok(())
+    }
+
+    /// 포지션 열기 — 담보는 풀의 token_b, 진입가는 TWAP
+    pub fn open_position(&mut self, user: &str, pool_id: &str, side: PositionSide,
+                         collateral: u64, leverage: f64, now: u64) -> Result<u64, String> {
+        let (_, quote) = self.pairs.get(pool_id).cloned().ok_or_else(|| format!("마진 페어 없음: {}", pool_id))?;
+        if !(1.0..=self.config.max_leverage).contains(&leverage) {
+            return Err(format!("레버리지 범위 밖: {:.1} (1~{:.0})", leverage, self.config.max_leverage));
+        }
+        // 진입 직후 경고 구간이면 거부 (1/레버리지 ≥ 경고 증거금)
+        if 1.0 / leverage < self.config.warning_margin {
+            return Err(format!("레버리지 과다: 초기 증거금 {:.2}% < 경고 {:.2}%",
+                100.0 / leverage, self.config.warning_margin * 100.0));
+        }
+        if collateral == 0 {
+            return Err("담보 0".into());
+        }
+        let price = self.oracle.twap(pool_id, now).ok_or_else(|| format!("가격 관측 없음: {}", pool_id))?;
+        let free = self.free_collateral(user, &quote);
+        if free < collateral {
+            return Err(format!("미사용 담보 부족 ({} < {})", free, collateral));
+        }
+        *self.collateral.get_mut(user).unwrap().get_mut(&quote).unwrap() -= collateral;
+        let id = self.positions.len() as u64 + 1;
+        self.positions.push(MarginPosition {
+            id, owner: user.into(), pool_id: pool_id.into(), collateral_token: quote, side,
+            size: collateral as f64 * leverage / price, entry_price: price, collateral, leverage,
+            status: PositionStatus::Open, opened_at: now,
+        });
+        Ok(id)
+    }
+
+    /// 정산 — 자기자본을 담보로 돌려주고 부족분은 보험 기금이 메운다 → (반환액, 기금 증감)
+    fn settle(&mut self, idx: usize, price: f64, penalty: f64) -> (u64, i64) {
+        let pos = &self.positions[idx];
+        let (owner, token) = (pos.owner.clone(), pos.collateral_token.clone());
+        let equity = pos.equity(price);
+        if equity < 0.0 {
+            let paid = self.insurance.cover(&token, (-equity).ceil() as u64);
+            return (0, -(paid as i64));
+        }
+        let equity = equity.floor() as u64;
+        let fee = (penalty.floor() as u64).min(equity);
+        if fee > 0 {
+            self.insurance.deposit(&token, fee);
+        }
+        self.credit(&owner, &token, equity - fee);
+        (equity - fee, fee as i64)
+    }
+
+    /// 포지션 종료 (TWAP 가격) → 실현 손익
+    pub fn close_position(&mut self, user: &str, id: u64, now: u64) -> Result<f64, String> {
+        let idx = self.positions.iter().position(|p| p.id == id).ok_or("포지션 없음")?;
+        let pos = &self.positions[idx];
+        if pos.owner != user {
+            return Err(format!("소유자 아님: {}", user));
+        }
+        if pos.status != PositionStatus::Open {
+            return Err("이미 종료된 포지션".into());
+        }
+        let price = self.oracle.twap(&pos.pool_id, now).ok_or("가격 관측 없음")?;
+        let pnl = pos.pnl(price);
+        self.settle(idx, price, 0.0);
+        self.positions[idx].status = PositionStatus::Closed;
+        Ok(pnl)
+    }
+
+    /// 유지 증거금 검사 — 경고(O)와 청산(T) 이벤트
+    /// 같은 포지션의 경고는 상태가 바뀔 때까지 반복하지 않는다
+    pub fn check(&mut self, now: u64) -> Vec<MarginEvent> {
+        let mut events = Vec::new();
+        for idx in 0..self.positions.len() {
+            let pos = &self.positions[idx];
+            if pos.status != PositionStatus::Open {
+                continue;
+            }
+            let Some(price) = self.oracle.twap(&pos.pool_id, now) else { continue };
+            let ratio = pos.margin_ratio(price);
+            let mut event = MarginEvent {
+                position: pos.id, owner: pos.owner.clone(), trit: 0, price, margin_ratio: ratio,
+                returned: 0, insurance_delta: 0, timestamp: now,
+            };
+            if ratio < self.config.maintenance_margin {
+                let penalty = pos.notional(price) * self.config.liquidation_penalty;
+                let (returned, delta) = self.settle(idx, price, penalty);
+                self.positions[idx].status = PositionStatus::Liquidated;
+                event.trit = -1;
+                event.returned = returned;
+                event.insurance_delta = delta;
+            } else if ratio < self.config.warning_margin {
+                let warned = self.events.iter().rev().find(|e| e.position == pos.id).is_some_and(|e| e.trit == 0);
+                if warned {
+                    continue;
+                }
+            } else {
+                continue;
+            }
+            events.push(event);
+        }
+        self.events.extend(events.iter().cloned());
+        events
+    }
+
+    pub fn open_positions(&self) -> Vec<&MarginPosition> {
+        self.positions.iter().filter(|p| p.status == PositionStatus::Open).collect()
+    }
+}
+
+/// 청산 검사를 커널 스케줄러 태스크로 실행 (높은 우선순위) → 이번 검사의 경고/청산 이벤트
+pub fn schedule_liquidations(kernel: &mut CrownyKernel, engine: &Arc<Mutex<MarginEngine>>, now: u64)
+    -> Result<Vec<MarginEvent>, String> {
+    let before = engine.lock().map_err(|_| "마진 엔진 잠금 실패")?.events.len();
+    let task_engine = Arc::clone(engine);
+    let result = kernel.execute_task("margin.liquidation", TritPriority::High, Box::new(move || {
+        match task_engine.lock() {
+            Ok(mut e) => { e.check(now); TritResult::Success }
+            Err(_) => TritResult::Failed,
+        }
+    }));
+    if result != TritResult::Success {
+        return Err(format!("청산 태스크 실패: {}", result));
+    }
+    let engine = engine.lock().map_err(|_| "마진 엔진 잠금 실패")?;
+    Ok(engine.events[before..].to_vec())
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (CrownyDEX, MarginEngine, String) {
+        let mut dex = CrownyDEX::new();
+        dex.mint("lp", "CRWN", 1_000_000);
+        dex.mint("lp", "USDT", 1_000_000);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("lp", &pool, 1_000_000, 1_000_000).unwrap();
+        dex.mint("alice", "USDT", 10_000);
+        let mut engine = MarginEngine::new(MarginConfig::default(), 60_000);
+        engine.sync_dex(&dex, 0);
+        (dex, engine, pool)
+    }
+
+    #[test]
+    fn test_twap_resists_spike() {
+        let mut oracle = TwapOracle::new(100);
+        oracle.observe("P", 1.0, 0);
+        oracle.observe("P", 2.0, 90);
+        // 90~100 구간만 2.0 → 평균 1.1
+        assert!((oracle.twap("P", 100).unwrap() - 1.1).abs() < 1e-9);
+        assert_eq!(oracle.twap("P", 300), Some(2.0));
+        oracle.observe("P", 5.0, 50); // 과거 시각 무시
+        assert_eq!(oracle.twap("P", 300), Some(2.0));
+        assert_eq!(oracle.twap("없음", 0), None);
+    }
+
+    #[test]
+    fn test_open_close_and_collateral() {
+        let (mut dex, mut engine, pool) = setup();
+        engine.deposit(&mut dex, "alice", "USDT", 1_000).unwrap();
+        assert_eq!(dex.balance("alice", "USDT"), 9_000);
+        assert!(engine.open_position("alice", &pool, PositionSide::Long, 100, 20.0, 0).is_err());
+        assert!(engine.open_position("alice", &pool, PositionSide::Long, 5_000, 5.0, 0).is_err());
+        let id = engine.open_position("alice", &pool, PositionSide::Long, 1_000, 5.0, 0).unwrap();
+        assert_eq!(engine.free_collateral("alice", "USDT"), 0);
+        assert!((engine.positions[0].size - 5_000.0).abs() < 1e-6);
+
+        // 가격 10% 상승 → 롱 +500
+        engine.oracle.observe(&pool, 1.1, 1);
+        let pnl = engine.close_position("alice", id, 1_000_000).unwrap();
+        assert!((pnl - 500.0).abs() < 1e-6);
+        assert_eq!(engine.free_collateral("alice", "USDT"), 1_500);
+        assert!(engine.close_position("alice", id, 1_000_000).is_err());
+        engine.withdraw(&mut dex, "alice", "USDT", 1_500).unwrap();
+        assert_eq!(dex.balance("alice", "USDT"), 10_500);
+    }
+
+    #[test]
+    fn test_warning_then_liquidation_and_insurance() {
+        let (mut dex, mut engine, pool) = setup();
+        engine.deposit(&mut dex, "alice", "USDT", 2_000).unwrap();
+        engine.open_position("alice", &pool, PositionSide::Long, 1_000, 10.0, 0).unwrap();
+        let short = engine.open_position("alice", &pool, PositionSide::Short, 1_000, 10.0, 0).unwrap();
+
+        // 3% 하락 → 롱 증거금 7/97 ≈ 7.2% → 경고 (한 번만)
+        engine.oracle.observe(&pool, 0.97, 1);
+        let events = engine.check(1_000_000);
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].position, events[0].trit), (1, 0));
+        assert!(engine.check(1_000_001).is_empty());
+
+        // 12% 하락 → 자기자본 음수 → 청산, 기금이 비어 있어 악성 부채
+        engine.oracle.observe(&pool, 0.88, 2_000_000);
+        let events = engine.check(3_000_000);
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].trit, events[0].returned), (-1, 0));
+        assert_eq!(engine.insurance.uncovered, 200);
+
+        // 숏은 이익 중 — 가격 반등 후 유지 증거금 미만 청산 시 페널티 적립
+        engine.oracle.observe(&pool, 1.055, 4_000_000);
+        let events = engine.check(5_000_000);
+        assert_eq!((events[0].position, events[0].trit), (short, -1));
+        let fund = engine.insurance.balance("USDT");
+        assert!(fund > 0 && events[0].insurance_delta == fund as i64);
+        assert_eq!(engine.free_collateral("alice", "USDT"), events[0].returned);
+        assert!(engine.open_positions().is_empty());
+    }
+
+    #[test]
+    fn test_scheduled_kernel_task() {
+        let (mut dex, mut engine, pool) = setup();
+        engine.deposit(&mut dex, "alice", "USDT", 1_000).unwrap();
+        engine.open_position("alice", &pool, PositionSide::Long, 1_000, 10.0, 0).unwrap();
+        let engine = Arc::new(Mutex::new(engine));
+        let mut kernel = CrownyKernel::boot(Default::default());
+        assert!(schedule_liquidations(&mut kernel, &engine, 10).unwrap().is_empty());
+
+        // 스왑으로 풀 가격 하락 → TWAP 반영 후 청산
+        dex.mint("whale", "CRWN", 200_000);
+        dex.swap("whale", &pool, "CRWN", 200_000).unwrap();
+        engine.lock().unwrap().sync_dex(&dex, 20);
+        let events = schedule_liquidations(&mut kernel, &engine, 120_000).unwrap();
+        assert_eq!((events.len(), events[0].trit), (1, -1));
+        assert_eq!(kernel.scheduler.stats_success, 2);
+    }
+}
@@ -0,0 +1,310 @@
+// ═══════════════════════════════════════════════════
+// Mempool — 블록에 들어가기 전 트랜잭션 대기열
+// ═══════════════════════════════════════════════════
+//
+// · 계정마다 nonce 순서 유지 — 확정 nonce부터 빈틈없이 이어진 것만 실행 가능
+// · 같은 (계정, nonce)는 수수료를 rbf_bump_pct% 이상 올려야 교체 (replace-by-fee)
+// · ttl_ms 지나면 만료, 가득 차면 가장 싼 꼬리 트랜잭션을 밀어낸다
+// · get_pending(limit) — 수수료 높은 순 + 계정 내 nonce 순, 같은 입력이면 항상 같은 결과
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::chain::Transaction;
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 기본 만료 — 1시간
+pub const DEFAULT_TTL_MS: u64 = 60 * 60 * 1000;
+/// 기본 교체 조건 — 수수료 10% 이상 인상
+pub const DEFAULT_RBF_BUMP_PCT: u64 = 10;
+
+#[derive(Debug, Clone)]
+pub struct PoolEntry {
+    pub tx: Transaction,
+    pub nonce: u64,
+    pub added_at: u64,
+}
+
+/// 접수 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum Admission {
+    Added { nonce: u64 },
+    /// 같은 nonce의 기존 트랜잭션을 교체
+    Replaced { nonce: u64, old_hash: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolError {
+    /// 해시 검증 실패
+    Invalid,
+    Duplicate,
+    /// 이미 블록에 들어간 nonce
+    NonceTooLow { expected: u64, got: u64 },
+    /// 교체하려면 수수료가 required 이상이어야 한다
+    Underpriced { required: u64, got: u64 },
+    /// 가득 찼고 밀어낼 만큼 싼 트랜잭션도 없음
+    Full,
+}
+
+impl std::fmt::Display for MempoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid => write!(f, "T: 트랜잭션 해시 불일치"),
+            Self::Duplicate => write!(f, "T: 이미 대기 중인 트랜잭션"),
+            Self::NonceTooLow { expected, got } => write!(f, "T: nonce {} < 확정 nonce {}", got, expected),
+            Self::Underpriced { required, got } => write!(f, "T: 교체 수수료 부족 ({} < {})", got, required),
+            Self::Full => write!(f, "O: 멤풀 가득 참"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MempoolStats {
+    pub added: u64,
+    pub replaced: u64,
+    pub rejected: u64,
+    pub expired: u64,
+    pub evicted: u64,
+    pub included: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mempool {
+    pub max_size: usize,
+    pub ttl_ms: u64,
+    pub rbf_bump_pct: u64,
+    /// 계정별 확정 nonce (다음에 블록에 들어갈 값)
+    confirmed: HashMap<String, u64>,
+    queues: HashMap<String, BTreeMap<u64, PoolEntry>>,
+    /// 해시 → (계정, nonce)
+    index: HashMap<String, (String, u64)>,
+    pub stats: MempoolStats,
+}
+
+impl Mempool {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            ttl_ms: DEFAULT_TTL_MS,
+            rbf_bump_pct: DEFAULT_RBF_BUMP_PCT,
+            confirmed: HashMap::new(),
+            queues: HashMap::new(),
+            index: HashMap::new(),
+            stats: MempoolStats::default(),
+        }
+    }
+
+    pub fn size(&self) -> usize { self.index.len() }
+
+    pub fn confirmed_nonce(&self, sender: &str) -> u64 {
+        self.confirmed.get(sender).copied().unwrap_or(0)
+    }
+
+    /// 다음에 쓸 nonce — 확정 nonce부터 대기열에 이어진 만큼 건너뛴 값
+    pub fn next_nonce(&self, sender: &str) -> u64 {
+        let mut n = self.confirmed_nonce(sender);
+        if let Some(q) = self.queues.get(sender) {
+            while q.contains_key(&n) { n += 1; }
+        }
+        n
+    }
+
+    /// 이전 API — 성공 여부만
+    pub fn add(&mut self, tx: Transaction) -> bool {
+        self.submit(tx).is_ok()
+    }
+
+    pub fn submit(&mut self, tx: Transaction) -> Result<Admission, MempoolError> {
+        self.submit_at(tx, now_ms())
+    }
+
+    /// 접수 — nonce가 없는 트랜잭션은 그 계정의 다음 nonce를 받는다
+    pub fn submit_at(&mut self, tx: Transaction, now: u64) -> Result<Admission, MempoolError> {
+        let result = self.admit(tx, now);
+        match &result {
+            Ok(Admission::Added { .. }) => self.stats.added += 1,
+            Ok(Admission::Replaced { .. }) => self.stats.replaced += 1,
+            Err(_) => self.stats.rejected += 1,
+        }
+        result
+    }
+
+    fn admit(&mut self, tx: Transaction, now: u64) -> Result<Admission, MempoolError> {
+        if !tx.verify() { return Err(MempoolError::Invalid); }
+        if self.index.contains_key(&tx.hash) { return Err(MempoolError::Duplicate); }
+        let nonce = tx.nonce.unwrap_or_else(|| self.next_nonce(&tx.from));
+        let expected = self.confirmed_nonce(&tx.from);
+        if nonce < expected { return Err(MempoolError::NonceTooLow { expected, got: nonce }); }
+
+        // 교체
+        if let Some(old) = self.queues.get(&tx.from).and_then(|q| q.get(&nonce)) {
+            let required = (old.tx.fee * (100 + self.rbf_bump_pct)).div_ceil(100).max(old.tx.fee + 1);
+            if tx.fee < required { return Err(MempoolError::Underpriced { required, got: tx.fee }); }
+            let old_hash = old.tx.hash.clone();
+            self.index.remove(&old_hash);
+            self.insert(tx, nonce, now);
+            return Ok(Admission::Replaced { nonce, old_hash });
+        }
+
+        if self.size() >= self.max_size {
+            match self.cheapest_tail() {
+                Some((sender, n, fee)) if fee < tx.fee => {
+                    self.remove_entry(&sender, n);
+                    self.stats.evicted += 1;
+                }
+                _ => return Err(MempoolError::Full),
+            }
+        }
+        self.insert(tx, nonce, now);
+        Ok(Admission::Added { nonce })
+    }
+
+    fn insert(&mut self, tx: Transaction, nonce: u64, now: u64) {
+        self.index.insert(tx.hash.clone(), (tx.from.clone(), nonce));
+        self.queues.entry(tx.from.clone()).or_default().insert(nonce, PoolEntry { tx, nonce, added_at: now });
+    }
+
+    fn remove_entry(&mut self, sender: &str, nonce: u64) -> Option<PoolEntry> {
+        let q = self.queues.get_mut(sender)?;
+        let entry = q.remove(&nonce)?;
+        if q.is_empty() { self.queues.remove(sender); }
+        self.index.remove(&entry.tx.hash);
+        Some(entry)
+    }
+
+    /// 밀어낼 후보 — 계정마다 마지막 nonce만 (중간을 빼면 뒤가 막힌다)
+    fn cheapest_tail(&self) -> Option<(String, u64, u64)> {
+        self.queues.iter()
+            .filter_map(|(s, q)| q.iter().next_back().map(|(n, e)| (s.clone(), *n, e.tx.fee)))
+            .min_by(|a, b| a.2.cmp(&b.2).then_with(|| b.0.cmp(&a.0)))
+    }
+
+    /// 만료 — 제거한 수
+    pub fn expire(&mut self, now: u64) -> usize {
+        let stale: Vec<(String, u64)> = self.queues.iter()
+            .flat_map(|(s, q)| q.values()
+                .filter(|e| now.saturating_sub(e.added_at) >= self.ttl_ms)
+                .map(move |e| (s.clone(), e.nonce)))
+            .collect();
+        for (s, n) in &stale {
+            self.remove_entry(s, *n);
+        }
+        self.stats.expired += stale.len() as u64;
+        stale.len()
+    }
+
+    /// 블록 제안용 — 실행 가능한 트랜잭션을 수수료 높은 순으로 최대 limit개
+    /// 같은 계정은 nonce 순서를 지키고, 동률은 먼저 들어온 것 · 해시 순
+    pub fn get_pending(&self, limit: usize) -> Vec<&Transaction> {
+        type Key<'a> = (u64, Reverse<u64>, Reverse<&'a str>);
+        let mut heap: BinaryHeap<(Key<'_>, &str, u64)> = BinaryHeap::new();
+        let key = |e: &PoolEntry| (e.tx.fee, Reverse(e.added_at));
+        for (sender, q) in &self.queues {
+            let head = self.confirmed_nonce(sender);
+            if let Some(e) = q.get(&head) {
+                let (fee, at) = key(e);
+                heap.push(((fee, at, Reverse(e.tx.hash.as_str())), sender.as_str(), head));
+            }
+        }
+        let mut out = Vec::new();
+        while out.len() < limit {
+            let Some((_, sender, nonce)) = heap.pop() else { break };
+            let q = &self.queues[sender];
+            out.push(&q[&nonce].tx);
+            if let Some(e) = q.get(&(nonce + 1)) {
+                let (fee, at) = key(e);
+                heap.push(((fee, at, Reverse(e.tx.hash.as_str())), sender, nonce + 1));
+            }
+        }
+        out
+    }
+
+    /// 블록에 들어간 트랜잭션 제거 + 확정 nonce 전진
+    pub fn remove_included(&mut self, txs: &[Transaction]) {
+        for tx in txs {
+            let Some((sender, nonce)) = self.index.get(&tx.hash).cloned() else { continue };
+            self.remove_entry(&sender, nonce);
+            let c = self.confirmed.entry(sender).or_insert(0);
+            *c = (*c).max(nonce + 1);
+            self.stats.included += 1;
+        }
+        // 확정 nonce보다 낮은 잔여 (교체 경쟁에서 진 것)는 버린다
+        let confirmed = self.confirmed.clone();
+        for (sender, c) in confirmed {
+            let low: Vec<u64> = self.queues.get(&sender).map(|q| q.range(..c).map(|(n, _)| *n).collect()).unwrap_or_default();
+            for n in low {
+                self.remove_entry(&sender, n);
+            }
+        }
+    }
+
+    /// 이전 TxPool API (get_pending + remove_included) — 블록 생성은 확정 서명 뒤에야 제거한다
+    #[cfg(test)]
+    pub fn take_batch(&mut self, max_txs: usize) -> Vec<Transaction> {
+        let batch: Vec<Transaction> = self.get_pending(max_txs).into_iter().cloned().collect();
+        self.remove_included(&batch);
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::TxType;
+
+    fn tx(from: &str, nonce: u64, fee: u64, memo: &str) -> Transaction {
+        Transaction::new(from, "bob", 10, fee, TxType::Transfer, memo).with_nonce(nonce)
+    }
+
+    #[test]
+    fn test_mempool_fee_order_respects_nonces() {
+        let mut pool = Mempool::new(100);
+        pool.submit_at(tx("alice", 0, 1, ""), 10).unwrap();
+        pool.submit_at(tx("alice", 1, 50, ""), 10).unwrap();
+        pool.submit_at(tx("carol", 0, 20, ""), 10).unwrap();
+        pool.submit_at(tx("dave", 5, 99, ""), 10).unwrap(); // 빈틈 — 아직 실행 불가
+
+        let fees: Vec<u64> = pool.get_pending(10).iter().map(|t| t.fee).collect();
+        assert_eq!(fees, vec![20, 1, 50]);
+        assert_eq!(pool.get_pending(2).len(), 2);
+        assert_eq!(pool.next_nonce("alice"), 2);
+
+        let batch = pool.take_batch(2);
+        assert_eq!(batch.len(), 2);
+        assert_eq!((pool.confirmed_nonce("alice"), pool.confirmed_nonce("carol")), (1, 1));
+        assert_eq!(pool.submit_at(tx("alice", 0, 9, "x"), 11), Err(MempoolError::NonceTooLow { expected: 1, got: 0 }));
+        assert_eq!(pool.size(), 2);
+    }
+
+    #[test]
+    fn test_mempool_replace_by_fee_and_expiry() {
+        let mut pool = Mempool::new(100);
+        pool.ttl_ms = 1000;
+        let first = tx("alice", 0, 100, "a");
+        pool.submit_at(first.clone(), 0).unwrap();
+        assert_eq!(pool.submit_at(first.clone(), 0), Err(MempoolError::Duplicate));
+        assert_eq!(pool.submit_at(tx("alice", 0, 105, "b"), 0), Err(MempoolError::Underpriced { required: 110, got: 105 }));
+        assert_eq!(pool.submit_at(tx("alice", 0, 110, "c"), 0),
+            Ok(Admission::Replaced { nonce: 0, old_hash: first.hash.clone() }));
+        assert!(!pool.index.contains_key(&first.hash));
+        assert_eq!(pool.size(), 1);
+
+        pool.submit_at(tx("carol", 0, 1, ""), 800).unwrap();
+        assert_eq!(pool.expire(1000), 1);
+        assert_eq!(pool.get_pending(10)[0].from, "carol");
+        assert_eq!(pool.stats.expired, 1);
+    }
+
+    #[test]
+    fn test_mempool_full_evicts_cheapest_tail() {
+        let mut pool = Mempool::new(2);
+        pool.submit_at(tx("alice", 0, 5, ""), 0).unwrap();
+        pool.submit_at(tx("alice", 1, 1, ""), 0).unwrap();
+        assert_eq!(pool.submit_at(tx("carol", 0, 1, ""), 0), Err(MempoolError::Full));
+        pool.submit_at(tx("carol", 0, 3, ""), 0).unwrap();
+        assert_eq!(pool.next_nonce("alice"), 1); // 꼬리(nonce 1)가 밀려났다
+        assert_eq!(pool.stats.evicted, 1);
+    }
+}
@@ -0,0 +1,335 @@
+// ═══════════════════════════════════════════════════════════════
+// 마이그레이션 — 파일 형식 버전 업그레이드
+// 형식별로 버전 감지기와 단계(from → to)를 등록하고,
+// 열 때 자동으로 최신 버전까지 올린다 (원본은 .v{N}.bak으로 백업)
+//
+//   crowni-tvm migrate [경로...] --dry-run   → 바뀔 내용만 보고
+//
+// 판정: P = 최신 · O = 마이그레이션 대기(dry-run) · T = 실패
+// ═══════════════════════════════════════════════════════════════
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 마이그레이션 대상 형식
+#[derive(Debug, Clone, Copy)]
+pub struct FormatSpec {
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    pub current: u32,
+    /// 파일 내용에서 버전 감지 (형식이 아니면 None)
+    pub detect: fn(&[u8]) -> Option<u32>,
+}
+
+/// 한 단계 변환
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub format: &'static str,
+    pub from: u32,
+    pub to: u32,
+    pub description: &'static str,
+    pub apply: fn(&[u8]) -> Result<Vec<u8>, String>,
+}
+
+pub struct MigrationRegistry {
+    formats: Vec<FormatSpec>,
+    steps: Vec<Migration>,
+}
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self { formats: Vec::new(), steps: Vec::new() }
+    }
+
+    /// 내장 형식 + 단계
+    pub fn builtin() -> Self {
+        let mut reg = Self::new();
+        reg.register_format(FormatSpec {
+            name: "bytecode",
+            extensions: &["크라운", "crown"],
+            current: crate::bytecode::VERSION as u32,
+            detect: crate::bytecode::detect_version,
+        });
+        reg.register(Migration {
+            format: "bytecode", from: 1, to: 2,
+            description: "FNV-1a 체크섬 트레일러 추가 (플래그 0x01)",
+            apply: crate::bytecode::migrate_v1_to_v2,
+        });
+        // 저장소 — 아직 v1뿐, 형식이 바뀌면 여기에 단계를 더한다
+        reg.register_format(FormatSpec {
+            name: "store-wal",
+            extensions: &["wal"],
+            current: crate::trit_store::WAL_VERSION,
+            detect: crate::trit_store::detect_wal_version,
+        });
+        reg.register_format(FormatSpec {
+            name: "store-snapshot",
+            extensions: &["tsnap"],
+            current: crate::trit_store::SNAPSHOT_VERSION,
+            detect: crate::trit_store::detect_snapshot_version,
+        });
+        reg
+    }
+
+    pub fn register_format(&mut self, spec: FormatSpec) {
+        self.formats.push(spec);
+    }
+
+    pub fn register(&mut self, step: Migration) {
+        self.steps.push(step);
+    }
+
+    pub fn format(&self, name: &str) -> Option<&FormatSpec> {
+        self.formats.iter().find(|f| f.name == name)
+    }
+
+    /// 확장자로 형식 찾기
+    pub fn format_for_path(&self, path: &Path) -> Option<&FormatSpec> {
+        let ext = path.extension()?.to_str()?;
+        self.formats.iter().find(|f| f.extensions.contains(&ext))
+    }
+
+    /// from → 최신까지 단계 목록 (끊긴 구간이 있으면 오류)
+    pub fn plan(&self, format: &str, from: u32) -> Result<Vec<&Migration>, String> {
+        let spec = self.format(format).ok_or_else(|| format!("알 수 없는 형식: {}", format))?;
+        if from > spec.current {
+            return Err(format!("{} v{}는 지원 버전(v{})보다 새롭다", format, from, spec.current));
+        }
+        let mut plan = Vec::new();
+        let mut version = from;
+        while version < spec.current {
+            let step = self.steps.iter()
+                .find(|s| s.format == format && s.from == version)
+                .ok_or_else(|| format!("{} v{} → v{} 마이그레이션 없음", format, version, version + 1))?;
+            plan.push(step);
+            version = step.to;
+        }
+        Ok(plan)
+    }
+
+    /// 메모리에서 최신 버전으로 — (결과, 적용한 단계)
+    pub fn migrate_bytes(&self, format: &str, data: &[u8]) -> Result<(Vec<u8>, Vec<&Migration>), String> {
+        let spec = self.format(format).ok_or_else(|| format!("알 수 없는 형식: {}", format))?;
+        let from = (spec.detect)(data).ok_or_else(|| format!("{} 형식이 아님", format))?;
+        let plan = self.plan(format, from)?;
+        let mut bytes = data.to_vec();
+        for step in &plan {
+            bytes = (step.apply)(&bytes)
+                .map_err(|e| format!("{} v{}→v{} 실패: {}", format, step.from, step.to, e))?;
+            if (spec.detect)(&bytes) != Some(step.to) {
+                return Err(format!("{} v{}→v{} 결과 버전 불일치", format, step.from, step.to));
+            }
+        }
+        Ok((bytes, plan))
+    }
+}
+
+// ═══════════════════════════════════════
+// 파일 마이그레이션
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub path: PathBuf,
+    pub format: &'static str,
+    pub from: u32,
+    pub to: u32,
+    /// 적용(또는 적용 예정) 단계 설명
+    pub steps: Vec<String>,
+    pub bytes_before: usize,
+    pub bytes_after: usize,
+    pub backup: Option<PathBuf>,
+    pub dry_run: bool,
+}
+
+impl MigrationReport {
+    /// P: 최신(이미 또는 방금) · O: dry-run으로 변경 대기
+    pub fn state(&self) -> i8 {
+        if self.dry_run && self.from != self.to { 0 } else { 1 }
+    }
+}
+
+/// 백업 경로: 파일.크라운 → 파일.크라운.v1.bak
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    PathBuf::from(name)
+}
+
+/// 파일 하나를 최신 버전으로 — dry_run이면 디스크는 건드리지 않는다
+/// 쓰기 순서: 백업 복사 → 임시 파일 기록 → rename (중간 실패 시 원본 유지)
+pub fn migrate_file(reg: &MigrationRegistry, path: &Path, dry_run: bool) -> Result<MigrationReport, String> {
+    let spec = reg.format_for_path(path)
+        .ok_or_else(|| format!("{}: 마이그레이션 대상 형식이 아님", path.display()))?;
+    let data = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let from = (spec.detect)(&data)
+        .ok_or_else(|| format!("{}: {} 형식이 아님", path.display(), spec.name))?;
+
+    let (migrated, plan) = reg.migrate_bytes(spec.name, &data)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut report = MigrationReport {
+        path: path.to_path_buf(),
+        format: spec.name,
+        from,
+        to: spec.current,
+        steps: plan.iter().map(|s| format!("v{}→v{}: {}", s.from, s.to, s.description)).collect(),
+        bytes_before: data.len(),
+        bytes_after: migrated.len(),
+        backup: None,
+        dry_run,
+    };
+    if dry_run || plan.is_empty() {
+        return Ok(report);
+    }
+
+    let backup = backup_path(path, from);
+    fs::copy(path, &backup).map_err(|e| format!("{}: 백업 실패: {}", backup.display(), e))?;
+    let mut tmp = path.as_os_str().to_os_string();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, &migrated).map_err(|e| format!("{}: 쓰기 실패: {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("{}: 교체 실패: {}", path.display(), e)
+    })?;
+    report.backup = Some(backup);
+    Ok(report)
+}
+
+/// 경로 목록에서 대상 파일 수집 — 디렉터리는 재귀 (숨김·target 제외)
+pub fn collect_files(reg: &MigrationRegistry, roots: &[PathBuf]) -> Vec<PathBuf> {
+    fn walk(reg: &MigrationRegistry, dir: &Path, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        paths.sort();
+        for p in paths {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if name.starts_with('.') || name == "target" {
+                continue;
+            }
+            if p.is_dir() {
+                walk(reg, &p, out);
+            } else if reg.format_for_path(&p).is_some() {
+                out.push(p);
+            }
+        }
+    }
+    let mut out = Vec::new();
+    for root in roots {
+        if root.is_dir() {
+            walk(reg, root, &mut out);
+        } else {
+            out.push(root.clone());
+        }
+    }
+    out
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::assemble;
+    use crate::bytecode;
+
+    fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("crowny-migrate-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("prog.크라운");
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_plan_chain_and_gaps() {
+        let reg = MigrationRegistry::builtin();
+        assert_eq!(reg.plan("bytecode", 1).unwrap().len(), 1);
+        assert!(reg.plan("bytecode", 2).unwrap().is_empty());
+        assert!(reg.plan("bytecode", 3).unwrap_err().contains("새롭다"));
+
+        // 등록되지 않은 구간
+        let mut reg = MigrationRegistry::new();
+        reg.register_format(FormatSpec { name: "x", extensions: &["x"], current: 3, detect: |_| Some(1) });
+        reg.register(Migration { format: "x", from: 1, to: 2, description: "", apply: |d| Ok(d.to_vec()) });
+        assert!(reg.plan("x", 1).unwrap_err().contains("v2 → v3"));
+    }
+
+    #[test]
+    fn test_dry_run_leaves_file() {
+        let v1 = bytecode::serialize_v1(&assemble("넣어 1\n보여줘\n종료"));
+        let path = temp_file("dry", &v1);
+        let reg = MigrationRegistry::builtin();
+
+        let report = migrate_file(&reg, &path, true).unwrap();
+        assert_eq!((report.from, report.to), (1, 2));
+        assert_eq!(report.steps.len(), 1);
+        assert_eq!(report.state(), 0);
+        assert_eq!(report.bytes_after, report.bytes_before + 4);
+        assert_eq!(fs::read(&path).unwrap(), v1);
+        assert!(!backup_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_migrate_with_backup_and_open() {
+        let program = assemble("넣어 7\n넣어 6\n곱해\n보여줘\n종료");
+        let v1 = bytecode::serialize_v1(&program);
+        let path = temp_file("apply", &v1);
+        let reg = MigrationRegistry::builtin();
+
+        let report = migrate_file(&reg, &path, false).unwrap();
+        assert_eq!(report.state(), 1);
+        let backup = report.backup.unwrap();
+        assert_eq!(fs::read(&backup).unwrap(), v1);
+        assert_eq!(fs::read(&path).unwrap(), bytecode::serialize(&program));
+
+        // 이미 최신 — 변경 없음, 백업 없음
+        let again = migrate_file(&reg, &path, false).unwrap();
+        assert!(again.steps.is_empty());
+        assert!(again.backup.is_none());
+
+        // 열기 시 자동 마이그레이션
+        fs::write(&path, &v1).unwrap();
+        assert_eq!(bytecode::open_file(&path).unwrap().len(), 5);
+        assert_eq!(bytecode::detect_version(&fs::read(&path).unwrap()), Some(2));
+    }
+
+    #[test]
+    fn test_corrupt_v1_not_touched() {
+        let v1 = bytecode::serialize_v1(&assemble("넣어 1\n종료"));
+        let broken = &v1[..v1.len() - 3];
+        let path = temp_file("broken", broken);
+        let reg = MigrationRegistry::builtin();
+        assert!(migrate_file(&reg, &path, false).is_err());
+        assert_eq!(fs::read(&path).unwrap(), broken);
+        assert!(!backup_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_store_formats_registered() {
+        let dir = std::env::temp_dir().join(format!("crowny-migrate-{}-store", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        {
+            let mut store = crate::trit_store::TritStore::open(&dir).unwrap();
+            store.set("k", crate::trit_store::StoreValue::Int(1));
+            store.snapshot();
+        }
+        let reg = MigrationRegistry::builtin();
+        let files = collect_files(&reg, std::slice::from_ref(&dir));
+        let names: Vec<&str> = files.iter().filter_map(|p| p.file_name()?.to_str()).collect();
+        assert_eq!(names, ["snap-000001.tsnap", "store.wal"]);
+
+        for file in &files {
+            let report = migrate_file(&reg, file, false).unwrap();
+            assert_eq!((report.from, report.to), (1, 1));
+            assert!(report.backup.is_none());
+        }
+        assert_eq!(reg.format_for_path(&files[1]).unwrap().name, "store-wal");
+        assert!(reg.plan("store-wal", 2).unwrap_err().contains("새롭다"));
+
+        // 재시작 — 열기 경로에서도 그대로 읽힌다
+        let store = crate::trit_store::TritStore::open(&dir).unwrap();
+        assert_eq!(store.len(), 1);
+    }
+}
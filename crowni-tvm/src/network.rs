@@ -10,14 +10,28 @@
 ///! │           Crowny Trit Protocol (CTP)         │
 ///! ├──────────────────────────────────────────────┤
 ///! │ [Magic 6-trit: PTOPTP]                      │
-///! │ [Version: 2-trit]                           │
-///! │ [MessageType: 2-trit]                       │
+///! │ [Version: 2-trit (v1=OP, v2=PT)]            │
+///! │ [MessageType: 2-trit (기본 + 확장)]          │
 ///! │ [Status: 1-trit (P/O/T)]                    │
 ///! │ [PayloadLen: 6-trit (0~728)]                │
 ///! │ [Payload: N trits]                          │
 ///! │ [Checksum: 6-trit]                          │
+///! │ [RequestId: 12-trit, 선택 — 파이프라이닝]   │
 ///! └──────────────────────────────────────────────┘
 ///!
+///! 요청 ID는 체크섬 뒤 꼬리에 붙어 구버전 파서는 무시한다.
+///! CtpClient — 연결 하나에 여러 요청을 띄우고 ID로 응답을 짝짓는다 (순서 무관).
+///!
+///! 버전 협상 (롤링 업그레이드):
+///!   Handshake(OP) [최저][최고] → 양쪽 공통 최고 버전으로 P 응답
+///!   겹치는 버전 없음 / 범위 밖 버전 → 구조화된 T 응답 [코드][최저][최고][값]
+///!   모르는 메시지 타입 → 수신 측이 프레임을 건너뜀
+///!   능력 단어: Handshake [최저][최고][능력] → P [버전][공통 능력]
+///!     CAP_COMPRESS — 큰 페이로드 LZ 압축 (ctp_compress)
+///!
+///! 암호화 전송 (secure_ctp):
+///!   노드 신원 키 3DH 핸드셰이크 → ChaCha20-Poly1305 프레임 · 주기적 재키잉
+///!
 ///! HTTP 위에 얹는 방식:
 ///!   X-Crowny-State: P/O/T
 ///!   X-Crowny-Version: 1.0
@@ -25,9 +39,21 @@
 ///!
 ///! 내부적으로는 2진 바이트로 직렬화하지만
 ///! API는 100% 3진 인터페이스.
+///!
+///! 바이너리 프레임 (원시 TCP):
+///!   [magic "CTP3" 4B][frame ver 1B][trit count 4B BE][trits ⌈N/4⌉B][CRC32 4B BE]
+///!   FrameDecoder — 잘리거나 붙어 온 바이트를 모아 프레임 단위로 꺼낸다
+///!
+///! 핫 패스: TritSlice(빌린 구간) · CtpView(복사 없는 역직렬화)
+///!          serialize_into / write_into / decode_from 으로 버퍼 재사용
 
+use std::collections::HashMap;
 use std::io::{self, Read, Write};
-use std::net::{TcpListener, TcpStream};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::ctp_compress::{self, CtpCodec, FRAME_COMPRESSED};
 
 // ─────────────────────────────────────────────
 // Trit Encoding (물리 매핑)
@@ -94,6 +120,154 @@ impl std::fmt::Display for NetTrit {
 // Trit Buffer (트릿 직렬화/역직렬화)
 // ─────────────────────────────────────────────
 
+/// 값 → 6-trit 균형3진 (상위 트릿 먼저, 범위: -364~+364)
+fn encode_word6(mut val: i16) -> [NetTrit; 6] {
+    let mut trits = [NetTrit::O; 6];
+    for i in (0..6).rev() {
+        let mut r = val % 3;
+        val /= 3;
+        if r > 1 { r -= 3; val += 1; }
+        else if r < -1 { r += 3; val -= 1; }
+        trits[i] = match r {
+            -1 => NetTrit::T,
+            1 => NetTrit::P,
+            _ => NetTrit::O,
+        };
+    }
+    trits
+}
+
+fn decode_word6(trits: &[NetTrit]) -> i16 {
+    trits.iter().fold(0i16, |val, t| val * 3 + *t as i8 as i16)
+}
+
+/// 바이트 하나에 최대 4 trit 패킹 — 남는 자리는 0b11(무효)
+fn pack_byte(chunk: &[NetTrit]) -> u8 {
+    let mut byte: u8 = 0;
+    for (i, t) in chunk.iter().enumerate() {
+        byte |= t.to_2bit() << (6 - i * 2);
+    }
+    for i in chunk.len()..4 {
+        byte |= 0b11 << (6 - i * 2);
+    }
+    byte
+}
+
+/// 트릿 버퍼의 빌린 구간 — 복사 없이 읽기/직렬화
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TritSlice<'a> {
+    trits: &'a [NetTrit],
+}
+
+impl<'a> TritSlice<'a> {
+    pub fn new(trits: &'a [NetTrit]) -> Self {
+        Self { trits }
+    }
+
+    pub fn len(&self) -> usize {
+        self.trits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trits.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<NetTrit> {
+        self.trits.get(index).copied()
+    }
+
+    pub fn as_trits(&self) -> &'a [NetTrit] {
+        self.trits
+    }
+
+    /// 하위 구간 — 범위를 벗어나면 None
+    pub fn slice(&self, range: std::ops::Range<usize>) -> Option<TritSlice<'a>> {
+        self.trits.get(range).map(TritSlice::new)
+    }
+
+    pub fn split_at(&self, mid: usize) -> Option<(TritSlice<'a>, TritSlice<'a>)> {
+        self.trits.split_at_checked(mid).map(|(a, b)| (TritSlice::new(a), TritSlice::new(b)))
+    }
+
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'a, NetTrit>> {
+        self.trits.iter().copied()
+    }
+
+    /// 6-trit 단어 단위 순회 (끝의 6 미만 나머지는 제외)
+    pub fn words(&self) -> Words<'a> {
+        Words { chunks: self.trits.chunks_exact(6) }
+    }
+
+    pub fn read_word6(&self, offset: usize) -> Option<i16> {
+        self.trits.get(offset..offset + 6).map(decode_word6)
+    }
+
+    /// 직렬화에 필요한 바이트 수 (4 trits per byte)
+    pub fn byte_len(&self) -> usize {
+        self.trits.len().div_ceil(4)
+    }
+
+    /// 호출자 버퍼에 직렬화 — 할당 없음, 쓴 바이트 수 반환
+    pub fn write_into(&self, out: &mut [u8]) -> Result<usize, String> {
+        let needed = self.byte_len();
+        if out.len() < needed {
+            return Err(format!("출력 버퍼 부족: {} < {} bytes", out.len(), needed));
+        }
+        for (byte, chunk) in out.iter_mut().zip(self.trits.chunks(4)) {
+            *byte = pack_byte(chunk);
+        }
+        Ok(needed)
+    }
+
+    /// 소유 버퍼로 복사
+    pub fn to_buffer(self) -> TritBuffer {
+        TritBuffer::from_trits(self.trits.to_vec())
+    }
+
+    pub fn to_trit_string(self) -> String {
+        self.trits.iter().map(|t| t.symbol()).collect()
+    }
+}
+
+impl std::fmt::Display for TritSlice<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.trits.iter().try_for_each(|t| write!(f, "{}", t.symbol()))
+    }
+}
+
+/// 6-trit 단어 반복자
+#[derive(Debug, Clone)]
+pub struct Words<'a> {
+    chunks: std::slice::ChunksExact<'a, NetTrit>,
+}
+
+impl<'a> Words<'a> {
+    /// 단어를 이루지 못한 꼬리 트릿
+    pub fn remainder(&self) -> TritSlice<'a> {
+        TritSlice::new(self.chunks.remainder())
+    }
+}
+
+impl Iterator for Words<'_> {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        self.chunks.next().map(decode_word6)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Words<'_> {
+    fn next_back(&mut self) -> Option<i16> {
+        self.chunks.next_back().map(decode_word6)
+    }
+}
+
+impl ExactSizeIterator for Words<'_> {}
+
 /// 트릿 버퍼 — 3진 데이터를 2진 바이트로 직렬화
 #[derive(Debug, Clone)]
 pub struct TritBuffer {
@@ -105,6 +279,10 @@ impl TritBuffer {
         Self { trits: Vec::new() }
     }
 
+    pub fn with_capacity(trits: usize) -> Self {
+        Self { trits: Vec::with_capacity(trits) }
+    }
+
     pub fn from_trits(trits: Vec<NetTrit>) -> Self {
         Self { trits }
     }
@@ -123,34 +301,13 @@ impl TritBuffer {
     }
 
     /// 정수를 6-trit 균형3진으로 인코딩 (범위: -364~+364)
-    pub fn push_word6(&mut self, mut val: i16) {
-        let mut trits = [NetTrit::O; 6];
-        for i in 0..6 {
-            let mut r = val % 3;
-            val /= 3;
-            if r > 1 { r -= 3; val += 1; }
-            else if r < -1 { r += 3; val -= 1; }
-            trits[i] = match r {
-                -1 => NetTrit::T,
-                0 => NetTrit::O,
-                1 => NetTrit::P,
-                _ => NetTrit::O,
-            };
-        }
-        // 상위 트릿부터 push (MST first)
-        for i in (0..6).rev() {
-            self.push(trits[i]);
-        }
+    pub fn push_word6(&mut self, val: i16) {
+        self.trits.extend_from_slice(&encode_word6(val));
     }
 
     /// 6-trit → 정수 디코딩
     pub fn read_word6(&self, offset: usize) -> Option<i16> {
-        if offset + 6 > self.trits.len() { return None; }
-        let mut val: i16 = 0;
-        for i in 0..6 {
-            val = val * 3 + self.trits[offset + i] as i8 as i16;
-        }
-        Some(val)
+        self.as_slice().read_word6(offset)
     }
 
     /// 문자열을 트릿 시퀀스로 인코딩 (각 char → 6-trit)
@@ -160,39 +317,96 @@ impl TritBuffer {
         }
     }
 
+    pub fn extend_from_slice(&mut self, slice: TritSlice<'_>) {
+        self.trits.extend_from_slice(slice.as_trits());
+    }
+
+    // ── 빌린 뷰 ──
+
+    pub fn as_slice(&self) -> TritSlice<'_> {
+        TritSlice::new(&self.trits)
+    }
+
+    pub fn slice(&self, range: std::ops::Range<usize>) -> Option<TritSlice<'_>> {
+        self.as_slice().slice(range)
+    }
+
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, NetTrit>> {
+        self.as_slice().iter()
+    }
+
+    pub fn words(&self) -> Words<'_> {
+        self.as_slice().words()
+    }
+
+    // ── 제자리 수정 ──
+
+    pub fn set(&mut self, index: usize, t: NetTrit) -> bool {
+        match self.trits.get_mut(index) {
+            Some(slot) => { *slot = t; true }
+            None => false,
+        }
+    }
+
+    /// offset 위치의 단어 덮어쓰기
+    pub fn set_word6(&mut self, offset: usize, val: i16) -> bool {
+        match self.trits.get_mut(offset..offset + 6) {
+            Some(slot) => { slot.copy_from_slice(&encode_word6(val)); true }
+            None => false,
+        }
+    }
+
+    /// 모든 트릿 부호 반전 (P↔T)
+    pub fn negate(&mut self) {
+        for t in &mut self.trits {
+            *t = match t { NetTrit::P => NetTrit::T, NetTrit::T => NetTrit::P, NetTrit::O => NetTrit::O };
+        }
+    }
+
+    /// 길이만 0으로 — 할당은 유지 (버퍼 재사용)
+    pub fn clear(&mut self) {
+        self.trits.clear();
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.trits.truncate(len);
+    }
+
     // ── 2진 직렬화 (물리 전송용) ──
 
+    pub fn byte_len(&self) -> usize {
+        self.as_slice().byte_len()
+    }
+
+    /// 호출자 버퍼에 직렬화 — 할당 없음
+    pub fn write_into(&self, out: &mut [u8]) -> Result<usize, String> {
+        self.as_slice().write_into(out)
+    }
+
     /// 트릿 버퍼 → 바이트 배열 (4 trits per byte)
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        let chunks = self.trits.chunks(4);
-        for chunk in chunks {
-            let mut byte: u8 = 0;
-            for (i, t) in chunk.iter().enumerate() {
-                byte |= t.to_2bit() << (6 - i * 2);
-            }
-            // 남은 자리는 0b11(무효)로 패딩
-            for i in chunk.len()..4 {
-                byte |= 0b11 << (6 - i * 2);
-            }
-            bytes.push(byte);
-        }
-        bytes
+        self.trits.chunks(4).map(pack_byte).collect()
     }
 
     /// 바이트 배열 → 트릿 버퍼
     pub fn from_bytes(bytes: &[u8], trit_count: usize) -> Self {
-        let mut trits = Vec::with_capacity(trit_count);
+        let mut buf = Self::with_capacity(trit_count);
+        buf.decode_from(bytes, trit_count);
+        buf
+    }
+
+    /// 기존 할당을 재사용해 바이트 배열을 디코드
+    pub fn decode_from(&mut self, bytes: &[u8], trit_count: usize) {
+        self.trits.clear();
         for byte in bytes {
             for i in 0..4 {
-                if trits.len() >= trit_count { break; }
+                if self.trits.len() >= trit_count { return; }
                 let bits = (byte >> (6 - i * 2)) & 0b11;
                 if let Some(t) = NetTrit::from_2bit(bits) {
-                    trits.push(t);
+                    self.trits.push(t);
                 }
             }
         }
-        Self { trits }
     }
 
     /// 길이
@@ -200,9 +414,21 @@ impl TritBuffer {
         self.trits.len()
     }
 
+    // ── 압축 (ctp_compress) ──
+
+    /// 원본 · RLE · 허프만 중 가장 작은 방식으로 압축 — 머리에 방식 기록
+    pub fn compress(&self) -> Vec<u8> {
+        ctp_compress::compress_trits_best(&self.trits)
+    }
+
+    /// 머리를 보고 방식을 골라 복원
+    pub fn decompress(bytes: &[u8]) -> Result<Self, String> {
+        ctp_compress::decompress_trits(bytes)
+    }
+
     /// 트릿 문자열 표현
     pub fn to_trit_string(&self) -> String {
-        self.trits.iter().map(|t| t.symbol()).collect()
+        self.as_slice().to_trit_string()
     }
 }
 
@@ -223,13 +449,31 @@ pub enum MessageType {
     Request  =  1,  // P = 요청
     Info     =  0,  // O = 정보/알림
     Response = -1,  // T = 응답
+    Handshake = 2,  // OP = 버전 협상 (v2)
 }
 
 impl MessageType {
+    /// 2-trit 타입 필드 [기본, 확장] — v1은 확장 트릿이 항상 O
+    pub fn to_trits(self) -> [NetTrit; 2] {
+        match self {
+            MessageType::Handshake => [NetTrit::O, NetTrit::P],
+            other => [other.to_trit(), NetTrit::O],
+        }
+    }
+
+    /// 모르는 조합은 None — 수신 측은 건너뛴다
+    pub fn from_trits(base: NetTrit, ext: NetTrit) -> Option<Self> {
+        match (base, ext) {
+            (base, NetTrit::O) => Some(Self::from_trit(base)),
+            (NetTrit::O, NetTrit::P) => Some(MessageType::Handshake),
+            _ => None,
+        }
+    }
+
     pub fn to_trit(self) -> NetTrit {
         match self {
             MessageType::Request => NetTrit::P,
-            MessageType::Info => NetTrit::O,
+            MessageType::Info | MessageType::Handshake => NetTrit::O,
             MessageType::Response => NetTrit::T,
         }
     }
@@ -249,6 +493,7 @@ impl std::fmt::Display for MessageType {
             MessageType::Request => write!(f, "요청(P)"),
             MessageType::Info => write!(f, "정보(O)"),
             MessageType::Response => write!(f, "응답(T)"),
+            MessageType::Handshake => write!(f, "협상(OP)"),
         }
     }
 }
@@ -290,6 +535,84 @@ impl std::fmt::Display for StatusCode {
     }
 }
 
+// ─────────────────────────────────────────────
+// 프로토콜 버전 협상
+// ─────────────────────────────────────────────
+
+/// 이 노드가 말하는 최신 버전
+pub const PROTOCOL_VERSION: u8 = 2;
+/// 아직 받아주는 가장 낮은 버전 (롤링 업그레이드 중 구버전 노드)
+pub const MIN_PROTOCOL_VERSION: u8 = 1;
+
+/// 능력 비트: 큰 페이로드 LZ 압축 (ctp_compress)
+pub const CAP_COMPRESS: i16 = 1;
+/// 이 노드가 광고하는 능력
+pub const LOCAL_CAPS: i16 = CAP_COMPRESS;
+
+/// 버전 → 2-trit (t0*3 + t1, 범위 -4..=4) — CTP 헤더와 X-Crowny-Trit 공용
+pub fn version_trits(version: u8) -> [i8; 2] {
+    let v = version.min(4) as i8;
+    let low = (v + 1).rem_euclid(3) - 1;
+    [(v - low) / 3, low]
+}
+
+pub fn version_from_trits(high: i8, low: i8) -> i8 {
+    high * 3 + low
+}
+
+/// 양쪽이 모두 지원하는 가장 높은 버전
+pub fn negotiate_version(local: (u8, u8), remote: (u8, u8)) -> Result<u8, CtpError> {
+    let agreed = local.1.min(remote.1);
+    if agreed >= local.0.max(remote.0) {
+        Ok(agreed)
+    } else {
+        Err(CtpError::NoCommonVersion { local, remote })
+    }
+}
+
+/// CTP 해석 오류 — 구조화된 T 응답으로 되돌려 보낼 수 있다
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CtpError {
+    TooShort,
+    BadMagic,
+    PayloadOverflow,
+    /// 지원 범위 밖 버전
+    UnsupportedVersion { version: i8, min: u8, max: u8 },
+    /// 협상 실패 — 겹치는 버전 없음
+    NoCommonVersion { local: (u8, u8), remote: (u8, u8) },
+    /// 모르는 메시지 타입 (2-trit 값) — 건너뛰어도 안전
+    UnknownType(i8),
+}
+
+impl CtpError {
+    /// T 응답 페이로드의 첫 단어
+    pub fn code(&self) -> i16 {
+        match self {
+            CtpError::TooShort => -1,
+            CtpError::BadMagic => -2,
+            CtpError::PayloadOverflow => -3,
+            CtpError::UnsupportedVersion { .. } => -4,
+            CtpError::NoCommonVersion { .. } => -5,
+            CtpError::UnknownType(_) => -6,
+        }
+    }
+}
+
+impl std::fmt::Display for CtpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CtpError::TooShort => write!(f, "메시지 너무 짧음"),
+            CtpError::BadMagic => write!(f, "매직 넘버 불일치"),
+            CtpError::PayloadOverflow => write!(f, "페이로드 길이 초과"),
+            CtpError::UnsupportedVersion { version, min, max } =>
+                write!(f, "지원하지 않는 버전 v{} (지원: v{}~v{})", version, min, max),
+            CtpError::NoCommonVersion { local, remote } =>
+                write!(f, "공통 버전 없음 (로컬 v{}~v{}, 상대 v{}~v{})", local.0, local.1, remote.0, remote.1),
+            CtpError::UnknownType(code) => write!(f, "알 수 없는 메시지 타입 {}", code),
+        }
+    }
+}
+
 /// CTP 메시지
 #[derive(Debug, Clone)]
 pub struct CtpMessage {
@@ -297,8 +620,17 @@ pub struct CtpMessage {
     pub msg_type: MessageType, // 메시지 종류
     pub status: StatusCode,    // 상태
     pub payload: TritBuffer,   // 페이로드 (트릿 데이터)
+    /// 요청 ID — 응답은 같은 ID로 돌아온다 (없으면 단발 요청)
+    pub request_id: Option<u32>,
 }
 
+/// 헤더 길이: magic(6) + ver(2) + type(2) + status(1) + len(6)
+const HEADER_TRITS: usize = 17;
+/// 요청 ID 꼬리: word6 × 2 (균형 729진 두 자리)
+const REQUEST_ID_TRITS: usize = 12;
+/// 꼬리에 담을 수 있는 최대 요청 ID (364·729 + 364)
+pub const MAX_REQUEST_ID: u32 = 265_720;
+
 /// 매직 넘버: "PTOPTP" (6-trit)
 const MAGIC: [NetTrit; 6] = [
     NetTrit::P, NetTrit::T, NetTrit::O,
@@ -308,10 +640,101 @@ const MAGIC: [NetTrit; 6] = [
 impl CtpMessage {
     pub fn new(msg_type: MessageType, status: StatusCode, payload: TritBuffer) -> Self {
         Self {
-            version: 1,
+            version: PROTOCOL_VERSION,
             msg_type,
             status,
             payload,
+            request_id: None,
+        }
+    }
+
+    /// 요청 ID 붙이기 (1..=MAX_REQUEST_ID)
+    pub fn with_request_id(mut self, id: u32) -> Self {
+        self.request_id = Some(id);
+        self
+    }
+
+    /// 협상된 버전으로 보내기
+    pub fn with_version(mut self, version: u8) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// 협상 요청 — 구버전 노드도 읽도록 최저 버전 헤더로 보낸다
+    /// 페이로드: [최저 버전][최고 버전][능력] — 구버전은 앞의 두 단어만 읽는다
+    pub fn handshake(min: u8, max: u8) -> Self {
+        let mut payload = TritBuffer::new();
+        payload.push_word6(min as i16);
+        payload.push_word6(max as i16);
+        payload.push_word6(LOCAL_CAPS);
+        Self::new(MessageType::Handshake, StatusCode::Neutral, payload).with_version(MIN_PROTOCOL_VERSION)
+    }
+
+    /// 협상 요청의 (최저, 최고)
+    pub fn handshake_range(&self) -> Option<(u8, u8)> {
+        if self.msg_type != MessageType::Handshake { return None; }
+        let min = self.payload.read_word6(0)?;
+        let max = self.payload.read_word6(6)?;
+        (min > 0 && max >= min).then_some((min as u8, max as u8))
+    }
+
+    /// 협상 요청의 능력 — 능력 단어가 없는 구버전은 0
+    pub fn handshake_caps(&self) -> i16 {
+        if self.msg_type != MessageType::Handshake { return 0; }
+        self.payload.read_word6(12).unwrap_or(0).max(0)
+    }
+
+    /// 협상 응답 — 합의 버전(P) 또는 구조화된 T
+    pub fn answer_handshake(&self, local: (u8, u8)) -> Self {
+        let remote = self.handshake_range().unwrap_or((0, 0));
+        match negotiate_version(local, remote) {
+            Ok(v) => {
+                let mut payload = TritBuffer::new();
+                payload.push_word6(v as i16);
+                payload.push_word6(self.handshake_caps() & LOCAL_CAPS);
+                Self::response(StatusCode::Success, payload).with_version(v)
+            }
+            Err(e) => Self::error(&e).with_version(MIN_PROTOCOL_VERSION),
+        }
+    }
+
+    /// 협상 응답 해석 → 합의 버전
+    pub fn agreed_version(&self) -> Result<u8, String> {
+        match (self.status, self.payload.read_word6(0)) {
+            (StatusCode::Success, Some(v)) if v > 0 => Ok(v as u8),
+            (StatusCode::Error, _) => Err(Self::describe_error(&self.payload)),
+            _ => Err("협상 응답 형식 오류".into()),
+        }
+    }
+
+    /// 협상 응답의 공통 능력 — 구버전 응답은 0
+    pub fn agreed_caps(&self) -> i16 {
+        match self.status {
+            StatusCode::Success => self.payload.read_word6(6).unwrap_or(0).max(0),
+            _ => 0,
+        }
+    }
+
+    /// 구조화된 오류 응답 — 페이로드: [코드][최저][최고][받은 값]
+    pub fn error(err: &CtpError) -> Self {
+        let (min, max, got) = match err {
+            CtpError::UnsupportedVersion { version, min, max } => (*min as i16, *max as i16, *version as i16),
+            CtpError::NoCommonVersion { local, remote } => (local.0 as i16, local.1 as i16, remote.1 as i16),
+            CtpError::UnknownType(code) => (MIN_PROTOCOL_VERSION as i16, PROTOCOL_VERSION as i16, *code as i16),
+            _ => (MIN_PROTOCOL_VERSION as i16, PROTOCOL_VERSION as i16, 0),
+        };
+        let mut payload = TritBuffer::new();
+        for w in [err.code(), min, max, got] {
+            payload.push_word6(w);
+        }
+        Self::response(StatusCode::Error, payload)
+    }
+
+    fn describe_error(payload: &TritBuffer) -> String {
+        let w: Vec<i16> = payload.words().collect();
+        match w.as_slice() {
+            [code, min, max, got, ..] => format!("T 응답 코드 {} (상대 지원 v{}~v{}, 값 {})", code, min, max, got),
+            _ => "T 응답".into(),
         }
     }
 
@@ -327,18 +750,27 @@ impl CtpMessage {
 
     /// 직렬화 → 트릿 버퍼
     pub fn serialize(&self) -> TritBuffer {
-        let mut buf = TritBuffer::new();
+        let mut buf = TritBuffer::with_capacity(HEADER_TRITS + self.payload.len() + 6 + REQUEST_ID_TRITS);
+        self.serialize_into(&mut buf);
+        buf
+    }
+
+    /// 기존 버퍼에 직렬화 (내용은 지우고 할당은 재사용)
+    pub fn serialize_into(&self, buf: &mut TritBuffer) {
+        buf.clear();
 
         // Magic (6 trits)
-        for t in &MAGIC { buf.push(*t); }
+        buf.trits.extend_from_slice(&MAGIC);
 
-        // Version (2 trits) — version 1 = OO→OP
-        buf.push_i8(0);
-        buf.push_i8(self.version as i8);
+        // Version (2 trits) — version 1 = OP, 2 = PT
+        for t in version_trits(self.version) {
+            buf.push_i8(t);
+        }
 
-        // MessageType (2 trits)
-        buf.push(self.msg_type.to_trit());
-        buf.push(NetTrit::O); // 예약
+        // MessageType (2 trits) — [기본][확장]
+        for t in self.msg_type.to_trits() {
+            buf.push(t);
+        }
 
         // Status (1 trit)
         buf.push(self.status.to_trit());
@@ -347,59 +779,74 @@ impl CtpMessage {
         buf.push_word6(self.payload.len() as i16);
 
         // Payload
-        for t in &self.payload.trits {
-            buf.push(*t);
-        }
+        buf.extend_from_slice(self.payload.as_slice());
 
         // Checksum (6 trits) — 간단한 체크섬: 모든 트릿 합의 mod 729
-        let sum: i32 = buf.trits.iter().map(|t| *t as i8 as i32).sum();
+        let sum: i32 = buf.iter().map(|t| t as i8 as i32).sum();
         buf.push_word6((sum % 364) as i16);
 
-        buf
+        // RequestId (12 trits, 선택)
+        if let Some(id) = self.request_id.filter(|&id| id > 0 && id <= MAX_REQUEST_ID) {
+            let lo = (id as i32 + 364).rem_euclid(729) - 364;
+            buf.push_word6(((id as i32 - lo) / 729) as i16);
+            buf.push_word6(lo as i16);
+        }
     }
 
     /// 역직렬화 ← 트릿 버퍼
     pub fn deserialize(buf: &TritBuffer) -> Result<Self, String> {
+        Self::parse(buf.as_slice()).map(|view| view.to_message()).map_err(|e| e.to_string())
+    }
+
+    /// 복사 없는 역직렬화 — 페이로드는 입력 버퍼를 빌린다
+    /// 버전 범위 밖 → UnsupportedVersion, 모르는 타입 → UnknownType (건너뛰기)
+    pub fn parse(buf: TritSlice<'_>) -> Result<CtpView<'_>, CtpError> {
         if buf.len() < 18 { // 최소: magic(6) + ver(2) + type(2) + status(1) + len(6) + checksum(6) = 23
-            return Err("메시지 너무 짧음".into());
+            return Err(CtpError::TooShort);
         }
+        let trits = buf.as_trits();
 
         // Magic 확인
-        for i in 0..6 {
-            if buf.trits[i] != MAGIC[i] {
-                return Err("매직 넘버 불일치".into());
-            }
+        if trits[..6] != MAGIC {
+            return Err(CtpError::BadMagic);
         }
 
-        // Version
-        let version = buf.trits[7] as i8;
+        // Version — 범위 밖이면 나머지 필드를 해석하지 않는다
+        let version = version_from_trits(trits[6] as i8, trits[7] as i8);
+        if version < MIN_PROTOCOL_VERSION as i8 || version > PROTOCOL_VERSION as i8 {
+            return Err(CtpError::UnsupportedVersion { version, min: MIN_PROTOCOL_VERSION, max: PROTOCOL_VERSION });
+        }
 
         // MessageType
-        let msg_type = MessageType::from_trit(buf.trits[8]);
+        let msg_type = MessageType::from_trits(trits[8], trits[9])
+            .ok_or(CtpError::UnknownType(trits[8] as i8 * 3 + trits[9] as i8))?;
 
         // Status
-        let status = StatusCode::from_trit(buf.trits[10]);
+        let status = StatusCode::from_trit(trits[10]);
 
         // PayloadLen
-        let payload_len = buf.read_word6(11)
-            .ok_or("페이로드 길이 읽기 실패")? as usize;
+        let payload_len = buf.read_word6(11).ok_or(CtpError::TooShort)?;
+        if payload_len < 0 {
+            return Err(CtpError::PayloadOverflow);
+        }
 
         // Payload
-        let payload_start = 17;
-        let payload_end = payload_start + payload_len;
-        if payload_end > buf.len() {
-            return Err("페이로드 길이 초과".into());
-        }
+        let payload = buf.slice(HEADER_TRITS..HEADER_TRITS + payload_len as usize)
+            .ok_or(CtpError::PayloadOverflow)?;
 
-        let payload = TritBuffer::from_trits(
-            buf.trits[payload_start..payload_end].to_vec()
-        );
+        // RequestId — 체크섬 뒤 꼬리가 있을 때만
+        let id_at = HEADER_TRITS + payload_len as usize + 6;
+        let request_id = buf.slice(id_at..id_at + REQUEST_ID_TRITS)
+            .and_then(|s| Some(s.read_word6(0)? as i32 * 729 + s.read_word6(6)? as i32))
+            .filter(|&id| id > 0)
+            .map(|id| id as u32);
 
-        Ok(Self {
+        Ok(CtpView {
             version: version as u8,
             msg_type,
             status,
             payload,
+            request_id,
         })
     }
 
@@ -415,6 +862,28 @@ impl CtpMessage {
     }
 }
 
+/// 빌린 CTP 메시지 — 수신 버퍼 위의 뷰
+#[derive(Debug, Clone, Copy)]
+pub struct CtpView<'a> {
+    pub version: u8,
+    pub msg_type: MessageType,
+    pub status: StatusCode,
+    pub payload: TritSlice<'a>,
+    pub request_id: Option<u32>,
+}
+
+impl CtpView<'_> {
+    pub fn to_message(self) -> CtpMessage {
+        CtpMessage {
+            version: self.version,
+            msg_type: self.msg_type,
+            status: self.status,
+            payload: self.payload.to_buffer(),
+            request_id: self.request_id,
+        }
+    }
+}
+
 impl std::fmt::Display for CtpMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "CTP[v{} {} {} payload:{}trits]",
@@ -422,6 +891,194 @@ impl std::fmt::Display for CtpMessage {
     }
 }
 
+// ─────────────────────────────────────────────
+// 바이너리 프레임 (길이 접두 + CRC) — 길이 단어 최상위 비트 = 압축 본문
+// ─────────────────────────────────────────────
+
+/// 프레임 매직
+pub const FRAME_MAGIC: [u8; 4] = *b"CTP3";
+/// 프레임 형식 버전 (CTP 프로토콜 버전과 별개)
+pub const FRAME_VERSION: u8 = 1;
+/// magic(4) + ver(1) + 길이 단어(4) — 트릿 수, 압축이면 본문 길이
+const FRAME_HEADER: usize = 9;
+/// CRC32
+const FRAME_TRAILER: usize = 4;
+/// 기본 최대 트릿 수
+pub const MAX_FRAME_TRITS: usize = 1 << 22;
+
+/// CRC-32 (IEEE 802.3, 반사 다항식)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// 프레임 오류 — 잘못된 프레임은 버리고 다음 매직부터 다시 맞춘다
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    BadMagic,
+    UnsupportedVersion(u8),
+    TooLarge(usize),
+    BadCrc { expected: u32, found: u32 },
+    Compression(String),
+    Ctp(CtpError),
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::BadMagic => write!(f, "프레임 매직 불일치"),
+            FrameError::UnsupportedVersion(v) => write!(f, "지원하지 않는 프레임 버전 {}", v),
+            FrameError::TooLarge(n) => write!(f, "프레임 트릿 수 {} 초과", n),
+            FrameError::BadCrc { expected, found } => write!(f, "CRC 불일치 (기대 {:08x}, 실제 {:08x})", expected, found),
+            FrameError::Compression(e) => write!(f, "압축 본문 오류: {}", e),
+            FrameError::Ctp(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// 메시지 → 바이너리 프레임 (평문 본문)
+pub fn encode_binary_frame(msg: &CtpMessage) -> Vec<u8> {
+    encode_frame_with(&mut CtpCodec::new(false), msg)
+}
+
+/// 협상된 코덱으로 프레임 — 임계값 이상이고 줄어들면 압축 본문
+pub fn encode_frame_with(codec: &mut CtpCodec, msg: &CtpMessage) -> Vec<u8> {
+    frame_trits(codec, &msg.serialize())
+}
+
+fn frame_trits(codec: &mut CtpCodec, trits: &TritBuffer) -> Vec<u8> {
+    let (word, body) = codec.encode(trits);
+    let mut frame = Vec::with_capacity(FRAME_HEADER + body.len() + FRAME_TRAILER);
+    frame.extend_from_slice(&FRAME_MAGIC);
+    frame.push(FRAME_VERSION);
+    frame.extend_from_slice(&word.to_be_bytes());
+    frame.extend(body);
+    // CRC 범위: 버전 ~ 페이로드
+    let crc = crc32(&frame[FRAME_MAGIC.len()..]);
+    frame.extend_from_slice(&crc.to_be_bytes());
+    frame
+}
+
+/// 수신 통계
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub frames: u64,
+    pub errors: u64,
+    /// 재동기화로 버린 바이트
+    pub skipped_bytes: u64,
+}
+
+/// 스트리밍 프레임 디코더 — 부분 읽기 · 여러 프레임이 붙은 읽기 모두 처리
+#[derive(Debug, Clone)]
+pub struct FrameDecoder {
+    buf: Vec<u8>,
+    max_trits: usize,
+    scratch: TritBuffer,
+    pub stats: FrameStats,
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameDecoder {
+    pub fn new() -> Self {
+        Self::with_max_trits(MAX_FRAME_TRITS)
+    }
+
+    pub fn with_max_trits(max_trits: usize) -> Self {
+        Self { buf: Vec::new(), max_trits, scratch: TritBuffer::new(), stats: FrameStats::default() }
+    }
+
+    /// 받은 바이트 추가
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// 아직 프레임이 되지 못한 바이트 수
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// 다음 프레임 — None이면 바이트가 더 필요
+    pub fn next_frame(&mut self) -> Option<Result<CtpMessage, FrameError>> {
+        let result = self.decode_one()?;
+        match &result {
+            Ok(_) => self.stats.frames += 1,
+            Err(_) => self.stats.errors += 1,
+        }
+        Some(result)
+    }
+
+    fn decode_one(&mut self) -> Option<Result<CtpMessage, FrameError>> {
+        let have = self.buf.len().min(FRAME_MAGIC.len());
+        if self.buf[..have] != FRAME_MAGIC[..have] {
+            self.resync();
+            return Some(Err(FrameError::BadMagic));
+        }
+        if self.buf.len() < FRAME_HEADER {
+            return None;
+        }
+        let version = self.buf[4];
+        if version != FRAME_VERSION {
+            self.resync();
+            return Some(Err(FrameError::UnsupportedVersion(version)));
+        }
+        let word = u32::from_be_bytes([self.buf[5], self.buf[6], self.buf[7], self.buf[8]]);
+        let compressed = word & FRAME_COMPRESSED != 0;
+        let count = (word & !FRAME_COMPRESSED) as usize;
+        // 압축 본문은 [트릿 수 4B] 뒤에 평문보다 짧아야 한다
+        let body_len = if compressed { count } else { count.div_ceil(4) };
+        if body_len > self.max_trits.div_ceil(4) + 4 || (!compressed && count > self.max_trits) {
+            self.resync();
+            return Some(Err(FrameError::TooLarge(if compressed { body_len * 4 } else { count })));
+        }
+        let total = FRAME_HEADER + body_len + FRAME_TRAILER;
+        if self.buf.len() < total {
+            return None;
+        }
+
+        let body_end = total - FRAME_TRAILER;
+        let found = crc32(&self.buf[FRAME_MAGIC.len()..body_end]);
+        let t = &self.buf[body_end..total];
+        let expected = u32::from_be_bytes([t[0], t[1], t[2], t[3]]);
+        let body = &self.buf[FRAME_HEADER..body_end];
+        let result = if found != expected {
+            Err(FrameError::BadCrc { expected, found })
+        } else if compressed {
+            ctp_compress::decode_body(body, self.max_trits, &mut self.scratch)
+                .map_err(FrameError::Compression)
+                .and_then(|_| CtpMessage::parse(self.scratch.as_slice()).map(|v| v.to_message()).map_err(FrameError::Ctp))
+        } else {
+            self.scratch.decode_from(body, count);
+            CtpMessage::parse(self.scratch.as_slice()).map(|v| v.to_message()).map_err(FrameError::Ctp)
+        };
+        self.buf.drain(..total);
+        Some(result)
+    }
+
+    /// 첫 바이트를 버리고 다음 매직(또는 매직의 앞부분으로 끝나는 꼬리)까지 건너뜀
+    fn resync(&mut self) {
+        let start = (1..self.buf.len())
+            .find(|&i| {
+                let rest = &self.buf[i..];
+                let n = rest.len().min(FRAME_MAGIC.len());
+                rest[..n] == FRAME_MAGIC[..n]
+            })
+            .unwrap_or(self.buf.len());
+        self.stats.skipped_bytes += start as u64;
+        self.buf.drain(..start);
+    }
+}
+
 // ─────────────────────────────────────────────
 // Trit Network Adapter (TCP 래퍼)
 // ─────────────────────────────────────────────
@@ -431,58 +1088,104 @@ impl std::fmt::Display for CtpMessage {
 pub struct TritNetAdapter;
 
 impl TritNetAdapter {
-    /// CTP 메시지를 TCP 스트림으로 전송
-    pub fn send(stream: &mut TcpStream, msg: &CtpMessage) -> io::Result<usize> {
-        let trit_buf = msg.serialize();
-        let bytes = trit_buf.to_bytes();
-
-        // 프레임: [trit_count: 4 bytes BE][trit_data: N bytes]
-        let trit_count = trit_buf.len() as u32;
-        stream.write_all(&trit_count.to_be_bytes())?;
-        stream.write_all(&bytes)?;
+    /// 바이너리 프레임(매직 + 버전 + 길이 + CRC)으로 전송
+    pub fn send_framed<W: Write>(stream: &mut W, msg: &CtpMessage) -> io::Result<usize> {
+        Self::send_with(stream, &mut CtpCodec::new(false), msg)
+    }
+
+    /// 협상된 코덱으로 전송 — 임계값 이상이면 압축 본문
+    pub fn send_with<W: Write>(stream: &mut W, codec: &mut CtpCodec, msg: &CtpMessage) -> io::Result<usize> {
+        let frame = encode_frame_with(codec, msg);
+        stream.write_all(&frame)?;
         stream.flush()?;
+        Ok(frame.len())
+    }
 
-        Ok(4 + bytes.len())
+    /// 바이너리 프레임 수신 — 프레임이 찰 때까지 읽고, 남은 바이트는 디코더에 보관
+    /// 바깥 Err = 전송 오류 · 연결 종료, 안쪽 Err = 프레임 오류 (다음 호출은 재동기화된 위치부터)
+    pub fn recv_framed<R: Read>(stream: &mut R, decoder: &mut FrameDecoder) -> io::Result<Result<CtpMessage, FrameError>> {
+        let mut chunk = [0u8; 4096];
+        loop {
+            if let Some(frame) = decoder.next_frame() {
+                return Ok(frame);
+            }
+            let n = stream.read(&mut chunk)?;
+            if n == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                    format!("프레임 도중 연결 종료 ({}바이트 남음)", decoder.buffered())));
+            }
+            decoder.push(&chunk[..n]);
+        }
     }
 
-    /// TCP 스트림에서 CTP 메시지 수신
-    pub fn recv(stream: &mut TcpStream) -> io::Result<CtpMessage> {
-        // trit_count 읽기
-        let mut len_buf = [0u8; 4];
-        stream.read_exact(&mut len_buf)?;
-        let trit_count = u32::from_be_bytes(len_buf) as usize;
+    /// 버전 + 능력 협상 — 양쪽이 지원하는 최고 버전, 둘 다 CAP_COMPRESS를 광고하면 압축 코덱
+    pub fn negotiate(stream: &mut TcpStream) -> io::Result<(u8, CtpCodec)> {
+        Self::send_framed(stream, &CtpMessage::handshake(MIN_PROTOCOL_VERSION, PROTOCOL_VERSION))?;
+        let answer = Self::recv_framed(stream, &mut FrameDecoder::new())?
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        let version = answer.agreed_version()
+            .map_err(|e| io::Error::new(io::ErrorKind::Unsupported, e))?;
+        Ok((version, CtpCodec::negotiated(answer.agreed_caps())))
+    }
 
-        // 바이트 데이터 읽기
-        let byte_count = (trit_count + 3) / 4; // ceil(trit_count/4)
-        let mut data = vec![0u8; byte_count];
-        stream.read_exact(&mut data)?;
+    /// 서버 응답 — 협상 요청은 합의 버전, 나머지는 에코 (요청 ID 유지)
+    pub fn reply(msg: &CtpMessage) -> CtpMessage {
+        let mut answer = match msg.msg_type {
+            MessageType::Handshake => msg.answer_handshake((MIN_PROTOCOL_VERSION, PROTOCOL_VERSION)),
+            _ => CtpMessage::response(StatusCode::Success, msg.payload.clone()).with_version(msg.version),
+        };
+        answer.request_id = msg.request_id;
+        answer
+    }
 
-        let trit_buf = TritBuffer::from_bytes(&data, trit_count);
-        CtpMessage::deserialize(&trit_buf)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    /// 바이너리 프레임 연결 처리 — 연결이 닫힐 때까지 요청마다 handler 응답 (ID 유지)
+    /// 협상 요청은 직접 답하고 이후 응답은 합의된 코덱으로 보낸다
+    /// 해석 오류는 T 응답 후 계속, 모르는 메시지 타입 · 깨진 프레임은 건너뜀
+    pub fn serve_framed<F>(stream: &mut TcpStream, mut handler: F) -> io::Result<u64>
+    where F: FnMut(&CtpMessage) -> CtpMessage {
+        let mut decoder = FrameDecoder::new();
+        let mut codec = CtpCodec::new(false);
+        let mut served = 0;
+        loop {
+            let answer = match Self::recv_framed(stream, &mut decoder) {
+                Ok(Ok(msg)) if msg.msg_type == MessageType::Handshake => {
+                    codec = CtpCodec::negotiated(msg.handshake_caps() & LOCAL_CAPS);
+                    Self::reply(&msg)
+                }
+                Ok(Ok(msg)) => {
+                    let mut answer = handler(&msg);
+                    answer.request_id = msg.request_id;
+                    answer
+                }
+                Ok(Err(FrameError::Ctp(CtpError::UnknownType(code)))) => {
+                    eprintln!("[CTP] 알 수 없는 메시지 타입 {} — 건너뜀", code);
+                    continue;
+                }
+                Ok(Err(FrameError::Ctp(e))) => CtpMessage::error(&e),
+                Ok(Err(e)) => {
+                    eprintln!("[CTP서버] 프레임 오류: {}", e);
+                    continue;
+                }
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(served),
+                Err(e) => return Err(e),
+            };
+            Self::send_with(stream, &mut codec, &answer)?;
+            served += 1;
+        }
     }
 
-    /// 3진 TCP 서버 시작 (간단한 에코 서버)
+    /// 3진 TCP 서버 — 연결마다 스레드, 바이너리 프레임 · 파이프라이닝 (CtpClient 상대)
     pub fn start_server(addr: &str) -> io::Result<()> {
         let listener = TcpListener::bind(addr)?;
         println!("[CTP서버] {} 에서 대기 중...", addr);
-
         for stream in listener.incoming() {
             match stream {
                 Ok(mut stream) => {
-                    println!("[CTP서버] 연결: {}", stream.peer_addr()?);
-                    match Self::recv(&mut stream) {
-                        Ok(msg) => {
-                            println!("[CTP서버] 수신: {}", msg);
-                            // 에코 응답
-                            let resp = CtpMessage::response(
-                                StatusCode::Success,
-                                msg.payload.clone(),
-                            );
-                            let _ = Self::send(&mut stream, &resp);
+                    std::thread::spawn(move || {
+                        if let Err(e) = Self::serve_framed(&mut stream, Self::reply) {
+                            eprintln!("[CTP서버] 연결 오류: {}", e);
                         }
-                        Err(e) => eprintln!("[CTP서버] 수신 오류: {}", e),
-                    }
+                    });
                 }
                 Err(e) => eprintln!("[CTP서버] 연결 오류: {}", e),
             }
@@ -491,10 +1194,177 @@ impl TritNetAdapter {
     }
 
     /// 3진 TCP 클라이언트 — 메시지 전송 후 응답 수신
+    /// 프로세스 공용 풀의 CtpClient로 보낸다 — 같은 주소는 연결을 재사용
     pub fn send_request(addr: &str, msg: &CtpMessage) -> io::Result<CtpMessage> {
-        let mut stream = TcpStream::connect(addr)?;
-        Self::send(&mut stream, msg)?;
-        Self::recv(&mut stream)
+        static POOL: OnceLock<CtpPool> = OnceLock::new();
+        POOL.get_or_init(|| CtpPool::new(REQUEST_TIMEOUT)).request(addr, msg.clone())
+    }
+}
+
+/// send_request 연결 · 응답 대기 시간
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ─────────────────────────────────────────────
+// CtpClient — 요청 파이프라이닝
+// ─────────────────────────────────────────────
+
+type Waiters = Arc<Mutex<HashMap<u32, mpsc::Sender<CtpMessage>>>>;
+
+/// 진행 중 요청 핸들 — 응답이 도착하면 wait로 꺼낸다
+#[derive(Debug)]
+pub struct CtpRequest {
+    pub id: u32,
+    rx: mpsc::Receiver<CtpMessage>,
+}
+
+impl CtpRequest {
+    /// 응답 대기 — 시간 초과 TimedOut, 연결이 닫히면 UnexpectedEof
+    pub fn wait(&self, timeout: Duration) -> io::Result<CtpMessage> {
+        self.rx.recv_timeout(timeout).map_err(|e| match e {
+            mpsc::RecvTimeoutError::Timeout => io::Error::new(io::ErrorKind::TimedOut, format!("요청 #{} 응답 시간 초과", self.id)),
+            mpsc::RecvTimeoutError::Disconnected => io::Error::new(io::ErrorKind::UnexpectedEof, format!("요청 #{} 응답 전 연결 종료", self.id)),
+        })
+    }
+}
+
+/// 파이프라이닝 CTP 클라이언트 — 연결 하나, 여러 요청 동시 진행
+/// 수신 스레드가 응답의 요청 ID로 기다리는 핸들을 찾아 넘긴다
+pub struct CtpClient {
+    stream: TcpStream,
+    /// connect에서 협상한 송신 코덱
+    pub codec: CtpCodec,
+    next_id: u32,
+    waiters: Waiters,
+    reader: Option<std::thread::JoinHandle<()>>,
+}
+
+impl CtpClient {
+    /// 주소로 연결 (이름 해석 결과를 차례로 시도) 후 버전 · 압축 협상
+    pub fn connect(addr: &str, timeout: Duration) -> io::Result<Self> {
+        let mut last = io::Error::new(io::ErrorKind::InvalidInput, format!("잘못된 주소: {}", addr));
+        for socket in addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&socket, timeout) {
+                Ok(mut stream) => {
+                    stream.set_read_timeout(Some(timeout))?;
+                    let (_, codec) = TritNetAdapter::negotiate(&mut stream)?;
+                    stream.set_read_timeout(None)?;
+                    let mut client = Self::from_stream(stream)?;
+                    client.codec = codec;
+                    return Ok(client);
+                }
+                Err(e) => last = e,
+            }
+        }
+        Err(last)
+    }
+
+    pub fn from_stream(stream: TcpStream) -> io::Result<Self> {
+        let mut incoming = stream.try_clone()?;
+        let waiters: Waiters = Arc::new(Mutex::new(HashMap::new()));
+        let w = Arc::clone(&waiters);
+        let reader = std::thread::spawn(move || {
+            let mut decoder = FrameDecoder::new();
+            loop {
+                match TritNetAdapter::recv_framed(&mut incoming, &mut decoder) {
+                    Ok(Ok(msg)) => {
+                        // 짝이 없는 응답 (시간 초과로 버린 요청 등)은 버린다
+                        let waiter = msg.request_id.and_then(|id| w.lock().ok()?.remove(&id));
+                        if let Some(tx) = waiter {
+                            let _ = tx.send(msg);
+                        }
+                    }
+                    Ok(Err(e)) => eprintln!("[CTP클라] 프레임 오류: {}", e),
+                    Err(_) => break,
+                }
+            }
+            // 남은 요청은 송신자를 버려 UnexpectedEof로 깨운다
+            if let Ok(mut w) = w.lock() {
+                w.clear();
+            }
+        });
+        Ok(Self { stream, codec: CtpCodec::new(false), next_id: 0, waiters, reader: Some(reader) })
+    }
+
+    /// 요청 전송 — 응답을 기다리지 않고 핸들 반환
+    pub fn send(&mut self, msg: CtpMessage) -> io::Result<CtpRequest> {
+        self.next_id = self.next_id % MAX_REQUEST_ID + 1;
+        let id = self.next_id;
+        let (tx, rx) = mpsc::channel();
+        self.waiters.lock().map_err(|_| io::Error::other("대기표 잠금 실패"))?.insert(id, tx);
+        if let Err(e) = TritNetAdapter::send_with(&mut self.stream, &mut self.codec, &msg.with_request_id(id)) {
+            if let Ok(mut w) = self.waiters.lock() {
+                w.remove(&id);
+            }
+            return Err(e);
+        }
+        Ok(CtpRequest { id, rx })
+    }
+
+    /// 보내고 기다리기
+    pub fn request(&mut self, msg: CtpMessage, timeout: Duration) -> io::Result<CtpMessage> {
+        let handle = self.send(msg)?;
+        let answer = handle.wait(timeout);
+        if answer.is_err() {
+            if let Ok(mut w) = self.waiters.lock() {
+                w.remove(&handle.id);
+            }
+        }
+        answer
+    }
+
+    /// 수신 스레드가 끝났으면 (상대가 닫음) 더 쓸 수 없다
+    pub fn is_closed(&self) -> bool {
+        self.reader.as_ref().is_none_or(|r| r.is_finished())
+    }
+}
+
+impl Drop for CtpClient {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(std::net::Shutdown::Both);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+/// 주소별 CtpClient 풀 — 같은 상대에게 가는 요청이 연결 하나를 재사용
+/// 쓰는 동안은 풀에서 꺼내 두고, 오류가 난 연결은 돌려놓지 않는다
+pub struct CtpPool {
+    idle: Mutex<HashMap<String, CtpClient>>,
+    pub timeout: Duration,
+}
+
+impl CtpPool {
+    pub fn new(timeout: Duration) -> Self {
+        Self { idle: Mutex::new(HashMap::new()), timeout }
+    }
+
+    /// 주소의 연결로 f 실행 — 쉬던 연결이 없거나 닫혔으면 새로 연결
+    pub fn with_client<T>(&self, addr: &str, f: impl FnOnce(&mut CtpClient) -> io::Result<T>) -> io::Result<T> {
+        let pooled = self.idle.lock().ok()
+            .and_then(|mut idle| idle.remove(addr))
+            .filter(|c| !c.is_closed());
+        let mut client = match pooled {
+            Some(client) => client,
+            None => CtpClient::connect(addr, self.timeout)?,
+        };
+        let out = f(&mut client)?;
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.insert(addr.to_string(), client);
+        }
+        Ok(out)
+    }
+
+    /// 보내고 기다리기
+    pub fn request(&self, addr: &str, msg: CtpMessage) -> io::Result<CtpMessage> {
+        self.with_client(addr, |client| client.request(msg, self.timeout))
+    }
+}
+
+impl std::fmt::Debug for CtpPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let idle = self.idle.lock().map(|idle| idle.len()).unwrap_or(0);
+        f.debug_struct("CtpPool").field("idle", &idle).field("timeout", &self.timeout).finish()
     }
 }
 
@@ -557,4 +1427,298 @@ mod tests {
         assert!(headers.iter().any(|(k, _)| k == "X-Crowny-State"));
         assert!(headers.iter().any(|(k, v)| k == "Content-Type" && v == "application/x-crowny-trit"));
     }
+
+    #[test]
+    fn test_trit_slice_words_and_views() {
+        let mut buf = TritBuffer::new();
+        for v in [42, -7, 364] { buf.push_word6(v); }
+        buf.push(NetTrit::P);
+        assert_eq!(buf.words().collect::<Vec<_>>(), vec![42, -7, 364]);
+        assert_eq!(buf.words().next_back(), Some(364));
+        assert_eq!(buf.words().remainder().to_trit_string(), "P");
+
+        let mid = buf.slice(6..12).unwrap();
+        assert_eq!(mid.read_word6(0), Some(-7));
+        assert_eq!(mid.as_trits().as_ptr(), buf.trits[6..].as_ptr());
+        assert!(buf.slice(10..30).is_none());
+        let (head, tail) = buf.as_slice().split_at(18).unwrap();
+        assert_eq!((head.words().len(), tail.len()), (3, 1));
+    }
+
+    #[test]
+    fn test_in_place_and_write_into() {
+        let mut buf = TritBuffer::new();
+        buf.push_word6(100);
+        buf.push_word6(5);
+        assert!(buf.set_word6(6, -121));
+        assert!(!buf.set_word6(8, 1));
+        buf.negate();
+        assert_eq!(buf.words().collect::<Vec<_>>(), vec![-100, 121]);
+
+        let mut out = [0u8; 3];
+        assert_eq!(buf.write_into(&mut out), Ok(3));
+        assert_eq!(out.to_vec(), buf.to_bytes());
+        assert!(buf.write_into(&mut [0u8; 2]).is_err());
+
+        let mut reused = TritBuffer::with_capacity(16);
+        reused.decode_from(&out, buf.len());
+        assert_eq!(reused.to_trit_string(), buf.to_trit_string());
+    }
+
+    #[test]
+    fn test_ctp_parse_borrows_payload() {
+        let mut payload = TritBuffer::new();
+        payload.push_string("CTP");
+        let msg = CtpMessage::request(payload);
+        let mut wire = TritBuffer::new();
+        msg.serialize_into(&mut wire);
+        assert_eq!(wire.to_trit_string(), msg.serialize().to_trit_string());
+
+        let view = CtpMessage::parse(wire.as_slice()).unwrap();
+        assert_eq!(view.payload.as_trits().as_ptr(), wire.trits[17..].as_ptr());
+        assert_eq!(view.payload.words().map(|w| w as u8 as char).collect::<String>(), "CTP");
+        assert_eq!(view.to_message().payload.len(), msg.payload.len());
+        assert!(CtpMessage::parse(wire.slice(0..20).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_version_negotiation_rules() {
+        for v in 0..=4u8 {
+            let [h, l] = version_trits(v);
+            assert_eq!(version_from_trits(h, l), v as i8);
+        }
+        assert_eq!(version_trits(1), [0, 1]); // v1 와이어 형식 유지
+        assert_eq!(negotiate_version((1, 2), (1, 4)), Ok(2));
+        assert_eq!(negotiate_version((2, 3), (1, 2)), Ok(2));
+        assert!(matches!(negotiate_version((2, 3), (1, 1)), Err(CtpError::NoCommonVersion { .. })));
+
+        let hello = CtpMessage::handshake(1, 4);
+        let wire = hello.serialize();
+        assert_eq!(wire.trits[6..8], [NetTrit::O, NetTrit::P]); // 최저 버전 헤더
+        let received = CtpMessage::deserialize(&wire).unwrap();
+        assert_eq!(received.handshake_range(), Some((1, 4)));
+        let answer = received.answer_handshake((1, PROTOCOL_VERSION));
+        assert_eq!((answer.version, answer.agreed_version()), (PROTOCOL_VERSION, Ok(PROTOCOL_VERSION)));
+
+        let refused = CtpMessage::handshake(1, 1).answer_handshake((2, 3));
+        assert_eq!(refused.status, StatusCode::Error);
+        assert_eq!(refused.payload.words().collect::<Vec<_>>(), vec![-5, 2, 3, 1]);
+        assert!(refused.agreed_version().is_err());
+    }
+
+    #[test]
+    fn test_unknown_type_and_version_are_structured() {
+        let mut wire = CtpMessage::request(TritBuffer::new()).serialize();
+        wire.set(9, NetTrit::T); // 확장 트릿 — 모르는 타입
+        assert_eq!(CtpMessage::parse(wire.as_slice()).unwrap_err(), CtpError::UnknownType(2));
+
+        let mut wire = CtpMessage::request(TritBuffer::new()).serialize();
+        wire.set(6, NetTrit::P);
+        wire.set(7, NetTrit::P); // v4
+        let err = CtpMessage::parse(wire.as_slice()).unwrap_err();
+        assert_eq!(err, CtpError::UnsupportedVersion { version: 4, min: MIN_PROTOCOL_VERSION, max: PROTOCOL_VERSION });
+        let t = CtpMessage::error(&err);
+        assert_eq!((t.msg_type, t.status), (MessageType::Response, StatusCode::Error));
+        assert_eq!(t.payload.words().collect::<Vec<_>>(), vec![-4, 1, 2, 4]);
+    }
+
+    #[test]
+    fn test_recv_skips_unknown_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut seen = Vec::new();
+            TritNetAdapter::serve_framed(&mut stream, |msg| {
+                seen.push(msg.msg_type);
+                TritNetAdapter::reply(msg)
+            }).unwrap();
+            seen
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut unknown = CtpMessage::request(TritBuffer::new()).serialize();
+        unknown.set(8, NetTrit::P);
+        unknown.set(9, NetTrit::P);
+        stream.write_all(&frame_trits(&mut CtpCodec::new(false), &unknown)).unwrap();
+        // 협상은 서버가 직접 답하고, 모르는 타입은 응답 없이 버린다
+        assert_eq!(TritNetAdapter::negotiate(&mut stream).unwrap().0, PROTOCOL_VERSION);
+        let answer = TritNetAdapter::send_framed(&mut stream, &sample(&[7]))
+            .and_then(|_| TritNetAdapter::recv_framed(&mut stream, &mut FrameDecoder::new()))
+            .unwrap().unwrap();
+        assert_eq!(answer.payload.read_word6(0), Some(7));
+        drop(stream);
+        assert_eq!(server.join().unwrap(), vec![MessageType::Request]);
+    }
+
+    fn sample(words: &[i16]) -> CtpMessage {
+        let mut payload = TritBuffer::new();
+        for &w in words { payload.push_word6(w); }
+        CtpMessage::request(payload)
+    }
+
+    #[test]
+    fn test_binary_frame_fragmentation() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        let msgs = [sample(&[1, 2, 3]), sample(&[]), sample(&(0..60).map(|v| v * 11 - 330).collect::<Vec<_>>())];
+        let wire: Vec<u8> = msgs.iter().flat_map(encode_binary_frame).collect();
+        assert_eq!(&wire[..5], b"CTP3\x01");
+
+        // 1바이트씩 · 불규칙한 조각 · 통째로 — 모두 같은 세 메시지
+        for split in [1usize, 3, 7, 64, wire.len()] {
+            let mut dec = FrameDecoder::new();
+            let mut out = Vec::new();
+            for piece in wire.chunks(split) {
+                dec.push(piece);
+                while let Some(frame) = dec.next_frame() {
+                    out.push(frame.unwrap());
+                }
+            }
+            assert_eq!(out.len(), 3, "조각 {}", split);
+            for (got, want) in out.iter().zip(&msgs) {
+                assert_eq!(got.payload.to_trit_string(), want.payload.to_trit_string());
+            }
+            assert_eq!((dec.buffered(), dec.stats.frames), (0, 3));
+        }
+    }
+
+    #[test]
+    fn test_binary_frame_corruption_resyncs() {
+        let good = encode_binary_frame(&sample(&[42]));
+        let mut bad = good.clone();
+        bad[FRAME_HEADER] ^= 0xff;
+
+        let mut dec = FrameDecoder::new();
+        dec.push(b"junk");
+        dec.push(&bad);
+        dec.push(&good);
+        assert!(matches!(dec.next_frame(), Some(Err(FrameError::BadMagic))));
+        assert!(matches!(dec.next_frame(), Some(Err(FrameError::BadCrc { .. }))));
+        assert_eq!(dec.next_frame().unwrap().unwrap().payload.read_word6(0), Some(42));
+        assert!(dec.next_frame().is_none());
+        assert_eq!((dec.stats.frames, dec.stats.errors, dec.stats.skipped_bytes), (1, 2, 4));
+
+        // 과도한 길이 → 거부
+        let mut dec = FrameDecoder::with_max_trits(16);
+        dec.push(&encode_binary_frame(&sample(&[1, 2, 3])));
+        assert!(matches!(dec.next_frame(), Some(Err(FrameError::TooLarge(_)))));
+    }
+
+    #[test]
+    fn test_recv_framed_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // 두 프레임을 한 번에 + 세 번째는 쪼개서
+            let mut merged = encode_binary_frame(&sample(&[1]));
+            merged.extend(encode_binary_frame(&sample(&[2])));
+            stream.write_all(&merged).unwrap();
+            let third = encode_binary_frame(&sample(&[3]));
+            for piece in third.chunks(5) {
+                stream.write_all(piece).unwrap();
+                stream.flush().unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(2));
+            }
+        });
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut dec = FrameDecoder::new();
+        for want in 1..=3 {
+            let msg = TritNetAdapter::recv_framed(&mut stream, &mut dec).unwrap().unwrap();
+            assert_eq!(msg.payload.read_word6(0), Some(want));
+        }
+        server.join().unwrap();
+        let eof = TritNetAdapter::recv_framed(&mut stream, &mut dec).unwrap_err();
+        assert_eq!(eof.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_request_id_trailer() {
+        let plain = sample(&[7]);
+        let tagged = sample(&[7]).with_request_id(MAX_REQUEST_ID);
+        let (a, b) = (plain.serialize(), tagged.serialize());
+        // 체크섬까지는 같고 꼬리만 추가
+        assert_eq!(b.len(), a.len() + REQUEST_ID_TRITS);
+        assert_eq!(b.slice(0..a.len()).unwrap().to_trit_string(), a.to_trit_string());
+        assert_eq!(CtpMessage::deserialize(&a).unwrap().request_id, None);
+        assert_eq!(CtpMessage::deserialize(&b).unwrap().request_id, Some(MAX_REQUEST_ID));
+        for id in [1, 364, 365, 729, 100_000] {
+            let wire = sample(&[]).with_request_id(id).serialize();
+            assert_eq!(CtpMessage::parse(wire.as_slice()).unwrap().request_id, Some(id));
+        }
+        assert_eq!(TritNetAdapter::reply(&sample(&[1]).with_request_id(9)).request_id, Some(9));
+    }
+
+    #[test]
+    fn test_client_pipelines_out_of_order() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut dec = FrameDecoder::new();
+            let hello = TritNetAdapter::recv_framed(&mut stream, &mut dec).unwrap().unwrap();
+            TritNetAdapter::send_framed(&mut stream, &TritNetAdapter::reply(&hello)).unwrap();
+            let reqs: Vec<CtpMessage> = (0..3)
+                .map(|_| TritNetAdapter::recv_framed(&mut stream, &mut dec).unwrap().unwrap())
+                .collect();
+            // 역순 응답
+            for req in reqs.iter().rev() {
+                TritNetAdapter::send_framed(&mut stream, &TritNetAdapter::reply(req)).unwrap();
+            }
+            // 나머지는 일반 서비스 루프
+            TritNetAdapter::serve_framed(&mut stream, TritNetAdapter::reply).unwrap()
+        });
+
+        let mut client = CtpClient::connect(&addr.to_string(), Duration::from_secs(5)).unwrap();
+        let handles: Vec<CtpRequest> = (1..=3).map(|v| client.send(sample(&[v * 100])).unwrap()).collect();
+        assert_eq!(handles.iter().map(|h| h.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        let wait = Duration::from_secs(5);
+        for (v, h) in (1..=3).zip(&handles).rev() {
+            let answer = h.wait(wait).unwrap();
+            assert_eq!((answer.request_id, answer.payload.read_word6(0)), (Some(h.id), Some(v * 100)));
+        }
+        let answer = client.request(CtpMessage::handshake(MIN_PROTOCOL_VERSION, PROTOCOL_VERSION), wait).unwrap();
+        assert_eq!(answer.agreed_version(), Ok(PROTOCOL_VERSION));
+        assert!(client.waiters.lock().unwrap().is_empty());
+
+        drop(client);
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_pool_reuses_connection_and_replaces_closed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let mut served = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut n = 0;
+                TritNetAdapter::serve_framed(&mut stream, |msg| {
+                    n += 1;
+                    TritNetAdapter::reply(msg)
+                }).unwrap();
+                served.push(n);
+            }
+            served
+        });
+
+        let pool = CtpPool::new(Duration::from_secs(5));
+        for v in 1..=3 {
+            let answer = pool.request(&addr, sample(&[v])).unwrap();
+            assert_eq!(answer.payload.read_word6(0), Some(v));
+        }
+        assert_eq!(pool.idle.lock().unwrap().len(), 1);
+
+        // 상대가 닫은 연결은 버리고 새로 연결
+        pool.idle.lock().unwrap().get(&addr).unwrap().stream.shutdown(std::net::Shutdown::Read).unwrap();
+        let started = std::time::Instant::now();
+        while !pool.idle.lock().unwrap()[&addr].is_closed() && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(pool.request(&addr, sample(&[4])).is_ok());
+        drop(pool);
+        assert_eq!(server.join().unwrap(), vec![3, 1]);
+    }
 }
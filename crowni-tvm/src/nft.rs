@@ -4,8 +4,19 @@
 // 모든 NFT에 P/O/T trit 상태 + CTP 헤더
 // ═══════════════════════════════════════════════════════════════
 
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::content::{self, SharedContent};
+use crate::contract_vm::ContractVM;
+use crate::dex::CrownyDEX;
+use crate::fractional::{FractionalService, BUYOUT_CURRENCY};
+use crate::output::JsonObject;
+use crate::integrations::{EventKind, SharedWebhooks};
+use crate::params::{SharedParams, MARKET_FEE_BPS};
+use crate::royalty::{self, RoyaltyPolicy, SharedRoyalties};
+use crate::query::{Page, Query, Queryable};
 
 fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
 
@@ -43,6 +54,19 @@ impl NFTMetadata {
     }
     pub fn attr(mut self, key: &str, val: &str) -> Self { self.attributes.push((key.into(), val.into())); self }
     pub fn trit_attr(mut self, key: &str, val: i8) -> Self { self.trit_attributes.push((key.into(), val)); self }
+
+    /// 메타데이터 문서 JSON (schema: crowny.nft.metadata) — 콘텐츠 저장소에 그대로 보관
+    pub fn to_json(&self) -> JsonObject {
+        let attrs = self.attributes.iter()
+            .map(|(k, v)| JsonObject::new().str("trait_type", k).str("value", v))
+            .chain(self.trit_attributes.iter().map(|(k, t)| JsonObject::new().str("trait_type", k).trit("value", *t)))
+            .collect();
+        JsonObject::schema("crowny.nft.metadata")
+            .str("name", &self.name)
+            .str("description", &self.description)
+            .str("image", &self.image_uri)
+            .objects("attributes", attrs)
+    }
 }
 
 // ═══════════════════════════════════════
@@ -86,10 +110,35 @@ pub struct NFT {
     pub minted_at: u64,
     pub listed: bool,
     pub price: Option<u64>,
+    /// 내용 주소 메타데이터 (crwn://content/<hash>) — mint_stored로 발행한 경우
+    pub metadata_uri: Option<String>,
 }
 
 impl NFT {
     pub fn trit_label(&self) -> &str { match self.trit_state { 1 => "P", -1 => "T", _ => "O" } }
+
+    /// 안정 필드 순서 JSON (schema: crowny.nft)
+    pub fn to_json(&self) -> JsonObject {
+        let obj = JsonObject::schema("crowny.nft")
+            .trit("state", self.trit_state)
+            .str("id", &self.id)
+            .int("token_id", self.token_id as i64)
+            .str("collection_id", &self.collection_id)
+            .str("name", &self.metadata.name)
+            .str("rarity", &format!("{:?}", self.rarity).to_lowercase())
+            .str("owner", &self.owner)
+            .str("creator", &self.creator)
+            .int("royalty_bps", self.royalty_bps as i64)
+            .bool("listed", self.listed);
+        let obj = match self.price {
+            Some(p) => obj.int("price", p as i64),
+            None => obj,
+        };
+        match &self.metadata_uri {
+            Some(uri) => obj.str("metadata_uri", uri).str("image_uri", &self.metadata.image_uri),
+            None => obj,
+        }
+    }
 }
 
 impl std::fmt::Display for NFT {
@@ -225,6 +274,8 @@ pub struct MarketTx {
     pub to: String,
     pub price: u64,
     pub royalty_paid: u64,
+    /// 로열티 수령자별 금액 (레지스트리 분배)
+    pub royalty_splits: Vec<(String, u64)>,
     pub fee: u64,
     pub tx_type: MarketTxType,
     pub hash: String,
@@ -234,6 +285,27 @@ pub struct MarketTx {
 #[derive(Debug, Clone)]
 pub enum MarketTxType { Sale, AuctionWin, Transfer }
 
+impl MarketTx {
+    /// 안정 필드 순서 JSON (schema: crowny.market_tx)
+    pub fn to_json(&self) -> JsonObject {
+        let ty = match &self.tx_type { MarketTxType::Sale => "sale", MarketTxType::AuctionWin => "auction_win", MarketTxType::Transfer => "transfer" };
+        JsonObject::schema("crowny.market_tx")
+            .trit("state", 1)
+            .str("type", ty)
+            .str("nft_id", &self.nft_id)
+            .str("from", &self.from)
+            .str("to", &self.to)
+            .int("price", self.price as i64)
+            .int("royalty", self.royalty_paid as i64)
+            .objects("royalty_splits", self.royalty_splits.iter()
+                .map(|(r, a)| JsonObject::new().str("recipient", r).int("amount", *a as i64))
+                .collect())
+            .int("fee", self.fee as i64)
+            .str("hash", &self.hash)
+            .int("timestamp", self.timestamp as i64)
+    }
+}
+
 impl std::fmt::Display for MarketTx {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let ty = match &self.tx_type { MarketTxType::Sale => "판매", MarketTxType::AuctionWin => "경매낙찰", MarketTxType::Transfer => "전송" };
@@ -242,6 +314,13 @@ impl std::fmt::Display for MarketTx {
     }
 }
 
+impl Queryable for MarketTx {
+    fn timestamp(&self) -> u64 { self.timestamp }
+    /// 판매/경매 체결 P · 단순 이전 O
+    fn trit(&self) -> i8 { if matches!(self.tx_type, MarketTxType::Transfer) { 0 } else { 1 } }
+    fn involves(&self, account: &str) -> bool { self.from == account || self.to == account }
+}
+
 // ═══════════════════════════════════════
 // NFT 마켓플레이스
 // ═══════════════════════════════════════
@@ -253,10 +332,27 @@ pub struct CrownyNFT {
     pub market_history: Vec<MarketTx>,
     pub balances: HashMap<String, u64>,   // user → CRWN balance
     pub token_counter: u64,
-    pub market_fee_bps: u64,              // 마켓 수수료 (2.5%)
+    pub params: SharedParams,             // 마켓 수수료 등 프로토콜 파라미터
+    /// 로열티 정책 — 모든 판매 경로가 여기서 정산
+    pub royalties: SharedRoyalties,
     pub total_volume: u64,
     pub total_fees: u64,
     pub total_royalties: u64,
+    /// 판매 · 낙찰 → nft.sold 웹훅
+    pub webhooks: Option<SharedWebhooks>,
+    /// 메타데이터 · 이미지 내용 주소 저장소 — 있으면 /nft/mint가 mint_stored로 발행
+    pub content: Option<SharedContent>,
+    /// 전체 NFT (id · 소유자 · 해시) 3진 머클 루트
+    pub state_root: String,
+    /// 루트 갱신 횟수 (배치 민트는 한 번)
+    pub root_updates: u64,
+}
+
+impl crate::account::AccountLedger for CrownyNFT {
+    fn ledger_name(&self) -> &str { "nft" }
+    fn holdings_of(&self, name: &str) -> Vec<(String, u64)> {
+        vec![("nft".into(), self.nfts_by_owner(name).len() as u64), ("CRWN".into(), self.balance(name))]
+    }
 }
 
 impl CrownyNFT {
@@ -265,10 +361,38 @@ impl CrownyNFT {
             collections: HashMap::new(), nfts: HashMap::new(),
             auctions: Vec::new(), market_history: Vec::new(),
             balances: HashMap::new(), token_counter: 0,
-            market_fee_bps: 250, total_volume: 0, total_fees: 0, total_royalties: 0,
+            params: crate::params::shared(), royalties: royalty::shared(), total_volume: 0, total_fees: 0, total_royalties: 0,
+            webhooks: None, content: None,
+            state_root: crate::chain::build_merkle_root(&[]), root_updates: 0,
         }
     }
 
+    /// 상태 루트 재계산 — 소유권이 바뀌는 모든 경로 끝에서 한 번
+    fn refresh_state_root(&mut self) {
+        let mut leaves: Vec<String> = self.nfts.values()
+            .map(|n| trit_hash(&format!("{}:{}:{}", n.id, n.owner, n.hash)))
+            .collect();
+        leaves.sort();
+        self.state_root = crate::chain::build_merkle_root(&leaves);
+        self.root_updates += 1;
+    }
+
+    pub fn with_params(mut self, params: SharedParams) -> Self { self.params = params; self }
+
+    /// 판매 기록 + 웹훅
+    fn record_sale(&mut self, tx: &MarketTx) {
+        self.total_volume += tx.price;
+        self.total_fees += tx.fee;
+        self.total_royalties += tx.royalty_paid;
+        self.market_history.push(tx.clone());
+        if let Some(hooks) = &self.webhooks {
+            hooks.borrow_mut().emit(EventKind::NftSold, tx.to_json());
+        }
+    }
+
+    /// 마켓 수수료 (bps) — 거버넌스 파라미터
+    pub fn market_fee_bps(&self) -> u64 { self.params.borrow().get(MARKET_FEE_BPS) }
+
     pub fn fund(&mut self, user: &str, amount: u64) {
         *self.balances.entry(user.into()).or_insert(0) += amount;
     }
@@ -285,6 +409,27 @@ impl CrownyNFT {
 
     /// NFT 민트
     pub fn mint(&mut self, collection_id: &str, owner: &str, metadata: NFTMetadata, rarity: NFTRarity) -> Result<String, String> {
+        let id = self.mint_one(collection_id, owner, metadata, rarity)?;
+        self.refresh_state_root();
+        Ok(id)
+    }
+
+    /// 배치 민트 (소유자 지정) — 상태 루트는 한 번만 갱신, 발행량이 모자라면 하나도 민트하지 않음
+    pub fn mint_batch_to(&mut self, collection_id: &str, items: Vec<(String, NFTMetadata)>) -> Result<Vec<String>, String> {
+        let col = self.collections.get(collection_id).ok_or("컬렉션 없음")?;
+        if let Some(max) = col.max_supply {
+            if col.minted + items.len() as u64 > max {
+                return Err(format!("최대 발행량 초과: {} + {} > {}", col.minted, items.len(), max));
+            }
+        }
+        let ids = items.into_iter()
+            .map(|(owner, meta)| self.mint_one(collection_id, &owner, meta, NFTRarity::Common))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.refresh_state_root();
+        Ok(ids)
+    }
+
+    fn mint_one(&mut self, collection_id: &str, owner: &str, metadata: NFTMetadata, rarity: NFTRarity) -> Result<String, String> {
         let col = self.collections.get_mut(collection_id).ok_or("컬렉션 없음")?;
         if !col.can_mint() { return Err("최대 발행량 도달".into()); }
 
@@ -298,6 +443,7 @@ impl CrownyNFT {
             rarity, royalty_bps: col.royalty_bps,
             trit_state: 1, hash: trit_hash(&format!("hash:{}:{}", token_id, now_ms())),
             transfer_count: 0, minted_at: now_ms(), listed: false, price: None,
+            metadata_uri: None,
         };
 
         col.minted += 1;
@@ -306,6 +452,35 @@ impl CrownyNFT {
         Ok(nft_id)
     }
 
+    /// 콘텐츠 저장소에 이미지 · 메타데이터를 올리고 민트
+    /// image_uri · metadata_uri는 해시 URI가 되고, 둘 다 NFT id로 고정된다
+    pub fn mint_stored(&mut self, collection_id: &str, owner: &str, mut metadata: NFTMetadata, rarity: NFTRarity, image: Option<(&[u8], &str)>) -> Result<String, String> {
+        let store = self.content.clone().ok_or("콘텐츠 저장소 없음")?;
+        let col = self.collections.get(collection_id).ok_or("컬렉션 없음")?;
+        if !col.can_mint() { return Err("최대 발행량 도달".into()); }
+
+        let mut cs = store.borrow_mut();
+        let image_hash = match image {
+            Some((bytes, mime)) => {
+                let hash = cs.put(bytes, mime).map_err(|e| e.to_string())?;
+                metadata.image_uri = content::uri(&hash);
+                Some(hash)
+            }
+            None => None,
+        };
+        let doc = metadata.to_json().build();
+        let meta_hash = cs.put(doc.as_bytes(), "application/json").map_err(|e| e.to_string())?;
+
+        let nft_id = self.mint(collection_id, owner, metadata, rarity)?;
+        for hash in image_hash.iter().chain([&meta_hash]) {
+            cs.pin(hash, &nft_id).map_err(|e| e.to_string())?;
+        }
+        if let Some(nft) = self.nfts.get_mut(&nft_id) {
+            nft.metadata_uri = Some(content::uri(&meta_hash));
+        }
+        Ok(nft_id)
+    }
+
     /// NFT 리스팅 (판매 등록)
     pub fn list(&mut self, nft_id: &str, price: u64) -> Result<(), String> {
         let nft = self.nfts.get_mut(nft_id).ok_or("NFT 없음")?;
@@ -323,18 +498,23 @@ impl CrownyNFT {
         let buyer_bal = self.balance(buyer);
         if buyer_bal < price { return Err(format!("잔액 부족: {} < {}", buyer_bal, price)); }
         if buyer == nft.owner { return Err("자기 자신에게 구매 불가".into()); }
+        self.settle_sale(nft_id, buyer, price, MarketTxType::Sale)
+    }
 
-        let fee = price * self.market_fee_bps / 10000;
-        let royalty = price * nft.royalty_bps / 10000;
-        let seller_receives = price - fee - royalty;
-
-        // 잔액 이동
-        *self.balances.get_mut(buyer).unwrap() -= price;
-        *self.balances.entry(nft.owner.clone()).or_insert(0) += seller_receives;
-        *self.balances.entry(nft.creator.clone()).or_insert(0) += royalty;
-
-        let seller = nft.owner.clone();
-        let creator = nft.creator.clone();
+    /// 판매 정산 (공통) — 로열티 레지스트리 → 잔액 이동 → 소유권 이전 → 기록
+    fn settle_sale(&mut self, nft_id: &str, buyer: &str, price: u64, tx_type: MarketTxType) -> Result<MarketTx, String> {
+        let nft = self.nfts.get(nft_id).ok_or("NFT 없음")?.clone();
+        let buyer_bal = self.balance(buyer);
+        if buyer_bal < price { return Err(format!("잔액 부족: {} < {}", buyer_bal, price)); }
+        let default = RoyaltyPolicy::single(&nft.creator, nft.royalty_bps);
+        let s = self.royalties.borrow().settle(&nft.collection_id, default, price, self.market_fee_bps())?;
+
+        // 잔액 이동 (수수료는 total_fees로 소각)
+        *self.balances.entry(buyer.into()).or_insert(0) -= price;
+        *self.balances.entry(nft.owner.clone()).or_insert(0) += s.seller_receives;
+        for (recipient, amount) in &s.royalties {
+            *self.balances.entry(recipient.clone()).or_insert(0) += amount;
+        }
 
         // NFT 소유권 이전
         let nft_mut = self.nfts.get_mut(nft_id).unwrap();
@@ -343,6 +523,7 @@ impl CrownyNFT {
         nft_mut.price = None;
         nft_mut.transfer_count += 1;
         nft_mut.trit_state = 1;
+        self.refresh_state_root();
 
         // 컬렉션 통계 업데이트
         if let Some(col) = self.collections.get_mut(&nft.collection_id) {
@@ -355,18 +536,16 @@ impl CrownyNFT {
             col.floor_price = floor;
         }
 
+        let tag = match &tx_type { MarketTxType::AuctionWin => "auction", _ => "sale" };
         let tx = MarketTx {
-            nft_id: nft_id.into(), from: seller, to: buyer.into(),
-            price, royalty_paid: royalty, fee,
-            tx_type: MarketTxType::Sale,
-            hash: trit_hash(&format!("sale:{}:{}:{}", nft_id, price, now_ms())),
+            nft_id: nft_id.into(), from: nft.owner, to: buyer.into(),
+            price, royalty_paid: s.royalty_total(), royalty_splits: s.royalties, fee: s.fee,
+            tx_type,
+            hash: trit_hash(&format!("{}:{}:{}:{}", tag, nft_id, price, now_ms())),
             timestamp: now_ms(),
         };
 
-        self.total_volume += price;
-        self.total_fees += fee;
-        self.total_royalties += royalty;
-        self.market_history.push(tx.clone());
+        self.record_sale(&tx);
         Ok(tx)
     }
 
@@ -391,44 +570,16 @@ impl CrownyNFT {
     /// 경매 종료 + 정산
     pub fn end_auction(&mut self, auction_idx: usize) -> Result<Option<MarketTx>, String> {
         let auction = self.auctions.get_mut(auction_idx).ok_or("경매 없음")?;
+        // 낙찰자 잔액은 입찰 뒤 바뀔 수 있다 — 정산 불가면 경매를 닫지 않음
+        if let Some(bid) = auction.highest_bidder().filter(|b| b.amount >= auction.reserve_price) {
+            let bal = self.balances.get(&bid.bidder).copied().unwrap_or(0);
+            if bal < bid.amount { return Err(format!("낙찰자 잔액 부족: {} < {}", bal, bid.amount)); }
+        }
+        let nft_id = auction.nft_id.clone();
         let winner = auction.end();
 
         if let Some(winning_bid) = winner {
-            let nft_id = auction.nft_id.clone();
-            let seller = auction.seller.clone();
-            let nft = self.nfts.get(&nft_id).ok_or("NFT 없음")?.clone();
-            let price = winning_bid.amount;
-
-            let fee = price * self.market_fee_bps / 10000;
-            let royalty = price * nft.royalty_bps / 10000;
-            let seller_receives = price - fee - royalty;
-
-            *self.balances.entry(winning_bid.bidder.clone()).or_insert(0) -= price.min(self.balance(&winning_bid.bidder));
-            *self.balances.entry(seller.clone()).or_insert(0) += seller_receives;
-            *self.balances.entry(nft.creator.clone()).or_insert(0) += royalty;
-
-            let nft_mut = self.nfts.get_mut(&nft_id).unwrap();
-            nft_mut.owner = winning_bid.bidder.clone();
-            nft_mut.listed = false;
-            nft_mut.transfer_count += 1;
-            nft_mut.trit_state = 1;
-
-            if let Some(col) = self.collections.get_mut(&nft.collection_id) {
-                col.total_volume += price;
-            }
-
-            let tx = MarketTx {
-                nft_id, from: seller, to: winning_bid.bidder,
-                price, royalty_paid: royalty, fee,
-                tx_type: MarketTxType::AuctionWin,
-                hash: trit_hash(&format!("auction:{}:{}", price, now_ms())),
-                timestamp: now_ms(),
-            };
-            self.total_volume += price;
-            self.total_fees += fee;
-            self.total_royalties += royalty;
-            self.market_history.push(tx.clone());
-            Ok(Some(tx))
+            self.settle_sale(&nft_id, &winning_bid.bidder, winning_bid.amount, MarketTxType::AuctionWin).map(Some)
         } else {
             // reserve 미달 → 유찰
             let nft_id = &self.auctions[auction_idx].nft_id;
@@ -445,9 +596,15 @@ impl CrownyNFT {
         let nft = self.nfts.get_mut(nft_id).ok_or("NFT 없음")?;
         nft.owner = to.into();
         nft.transfer_count += 1;
+        self.refresh_state_root();
         Ok(())
     }
 
+    /// 마켓 거래 기록 조회 (계정 = 보낸/받은 쪽)
+    pub fn market_txs(&self, q: &Query) -> Page<&MarketTx> {
+        q.page_slice(&self.market_history)
+    }
+
     pub fn nfts_by_owner(&self, owner: &str) -> Vec<&NFT> {
         self.nfts.values().filter(|n| n.owner == owner).collect()
     }
@@ -461,6 +618,56 @@ impl CrownyNFT {
 
 // ═══ 데모 ═══
 
+fn demo_fractional(market: &Rc<RefCell<CrownyNFT>>, nft_id: &str) {
+    let curator = market.borrow().nfts[nft_id].owner.clone();
+    let mut dex = CrownyDEX::new();
+    dex.mint(&curator, BUYOUT_CURRENCY, 50_000);
+    dex.mint("carol", BUYOUT_CURRENCY, 20_000);
+    dex.mint("dave", BUYOUT_CURRENCY, 200_000);
+    let mut svc = FractionalService::new(market.clone(), Rc::new(RefCell::new(dex)),
+        Rc::new(RefCell::new(ContractVM::new())));
+
+    let vault = match svc.fractionalize(nft_id, &curator, "SONATA", 1_000) {
+        Ok(v) => v,
+        Err(e) => { println!("  [T] 분할 실패: {}", e); return; }
+    };
+    println!("  🔒 {} → 금고 {} | SONATA 1,000 지분 → {}", market.borrow().nfts[nft_id].metadata.name, vault, curator);
+
+    {
+        let mut dex = svc.dex.borrow_mut();
+        dex.transfer(&curator, "alice", "SONATA", 150).ok();
+        let pool = dex.create_pool("SONATA", BUYOUT_CURRENCY, 30);
+        dex.add_liquidity(&curator, &pool, 200, 20_000).ok();
+        match dex.swap("carol", &pool, BUYOUT_CURRENCY, 5_000) {
+            Ok(s) => println!("  🔄 carol: 5,000 CRWN → {} SONATA (풀 {})", s.amount_out, pool),
+            Err(e) => println!("  [T] 스왑 실패: {}", e),
+        }
+    }
+
+    match svc.offer(&vault, "dave", 120_000) {
+        Ok(()) => println!("  📨 dave 바이아웃 제안: 120,000 CRWN 예치"),
+        Err(e) => { println!("  [T] 제안 실패: {}", e); return; }
+    }
+    for (voter, trit) in [("alice", -1), ("carol", 0), (curator.as_str(), 1)] {
+        match svc.vote(&vault, voter, trit) {
+            Ok(w) => println!("  🗳  {}: {} ({} 지분)", voter, ["T", "O", "P"][(trit + 1) as usize], w),
+            Err(e) => println!("  [T] {} 투표 실패: {}", voter, e),
+        }
+    }
+    match svc.resolve(&vault) {
+        Ok(1) => println!("  [P] 바이아웃 수락 — NFT → {}", market.borrow().nfts[nft_id].owner),
+        Ok(0) => println!("  [O] 투표 진행 중"),
+        Ok(_) => println!("  [T] 바이아웃 거절 — 예치금 반환"),
+        Err(e) => println!("  [T] {}", e),
+    }
+    for holder in [curator.as_str(), "alice", "carol"] {
+        if let Ok(paid) = svc.redeem(&vault, holder) {
+            println!("  💸 {} 상환: {} CRWN", holder, paid);
+        }
+    }
+    println!("  {}", svc.vaults[&vault].to_json().build());
+}
+
 pub fn demo_nft() {
     println!("╔═══════════════════════════════════════════════╗");
     println!("║  Crowny NFT — 3진 NFT 마켓플레이스              ║");
@@ -623,8 +830,17 @@ pub fn demo_nft() {
     for tx in &market.market_history { println!("  {}", tx); }
     println!();
 
-    // 11. 요약
-    println!("━━━ 10. 요약 ━━━");
+    // 11. 분할 소유 — 낙찰자가 Mythic NFT를 금고에 잠그고 지분을 발행
+    println!("━━━ 10. 분할 소유 (금고 · 지분 · 바이아웃) ━━━");
+    let market = Rc::new(RefCell::new(market));
+    if let Some(mythic_id) = minted_ids.get(7) {
+        demo_fractional(&market, mythic_id);
+    }
+    println!();
+    let market = Rc::try_unwrap(market).ok().expect("금고 서비스 해제").into_inner();
+
+    // 12. 요약
+    println!("━━━ 11. 요약 ━━━");
     println!("{}", market.summary());
     println!();
     println!("✓ Crowny NFT 데모 완료");
@@ -778,6 +994,85 @@ mod tests {
         assert_eq!(m.nfts_by_owner("bob").len(), 1);
     }
 
+    #[test]
+    fn test_royalty_registry_on_every_sale_path() {
+        let mut m = CrownyNFT::new();
+        for u in ["bob", "carol", "dave"] { m.fund(u, 100_000); }
+        let col = m.create_collection("T", "T", "alice", "d", None, 1000);
+        m.royalties.borrow_mut().set_policy(&col, RoyaltyPolicy::split(1_000, &[("alice", 7_000), ("zoe", 3_000)]).unwrap()).unwrap();
+        let id = m.mint(&col, "alice", NFTMetadata::new("A", "d", "i"), NFTRarity::Rare).unwrap();
+        let supply = |m: &CrownyNFT| m.balances.values().sum::<u64>() + m.total_fees;
+        let before = supply(&m);
+
+        m.list(&id, 10_010).unwrap();
+        let sale = m.buy(&id, "bob").unwrap();
+        assert_eq!(sale.royalty_splits, vec![("alice".to_string(), 701), ("zoe".to_string(), 300)]);
+        let ai = m.start_auction(&id, 100, 500, 1).unwrap();
+        m.bid(ai, "carol", 5_003).unwrap();
+        let win = m.end_auction(ai).unwrap().unwrap();
+        assert_eq!(win.royalty_splits.iter().map(|(_, a)| a).sum::<u64>(), win.royalty_paid);
+
+        assert_eq!(supply(&m), before);
+        assert_eq!(m.total_royalties, sale.royalty_paid + win.royalty_paid);
+        assert_eq!(m.balance("zoe"), 300 + 150);
+        assert!(m.market_history.iter().all(|t| t.fee + t.royalty_paid <= t.price));
+        assert_eq!(m.nfts[&id].owner, "carol");
+    }
+
+    #[test]
+    fn test_mint_batch_single_root_update() {
+        let mut m = CrownyNFT::new();
+        let col = m.create_collection("T", "T", "alice", "d", Some(5), 0);
+        let empty_root = m.state_root.clone();
+        let metas = (0..4).map(|i| ("alice".to_string(), NFTMetadata::new(&format!("#{}", i), "d", "i"))).collect();
+        let ids = m.mint_batch_to(&col, metas).unwrap();
+        assert_eq!((ids.len(), m.root_updates), (4, 1));
+        assert!(ids.iter().all(|id| m.nfts[id].owner == "alice"));
+        assert_ne!(m.state_root, empty_root);
+
+        // 발행량 초과 → 원자적으로 거부
+        let root = m.state_root.clone();
+        assert!(m.mint_batch_to(&col, vec![("bob".into(), NFTMetadata::new("a", "d", "i")), ("bob".into(), NFTMetadata::new("b", "d", "i"))]).is_err());
+        assert_eq!((m.nfts.len(), m.state_root.clone(), m.collections[&col].minted), (4, root.clone(), 4));
+        m.transfer(&ids[0], "bob").unwrap();
+        assert_ne!(m.state_root, root);
+    }
+
+    #[test]
+    fn test_auction_unfunded_winner_stays_open() {
+        let mut m = CrownyNFT::new();
+        m.fund("bob", 5_000);
+        let col = m.create_collection("T", "T", "alice", "d", None, 0);
+        let id = m.mint(&col, "alice", NFTMetadata::new("A", "d", "i"), NFTRarity::Common).unwrap();
+        let ai = m.start_auction(&id, 100, 1_000, 1).unwrap();
+        m.bid(ai, "bob", 4_000).unwrap();
+        m.balances.insert("bob".into(), 10);
+        assert!(m.end_auction(ai).is_err());
+        assert_eq!(m.auctions[ai].status, AuctionStatus::Active);
+        assert_eq!(m.nfts[&id].owner, "alice");
+    }
+
+    #[test]
+    fn test_mint_stored_pins_content() {
+        let store = crate::content::ContentStore::new().shared();
+        let mut m = CrownyNFT::new();
+        m.content = Some(store.clone());
+        let col = m.create_collection("T", "T", "alice", "d", None, 0);
+        let meta = NFTMetadata::new("용", "d", "crwn://art/x.png").trit_attr("불", 1);
+        let id = m.mint_stored(&col, "alice", meta, NFTRarity::Epic, Some((b"\x89PNG", "image/png"))).unwrap();
+
+        let nft = &m.nfts[&id];
+        let image = crate::content::parse_uri(&nft.metadata.image_uri).unwrap();
+        assert_eq!(store.borrow().get(image).unwrap().pins, vec![id.clone()]);
+        let meta_uri = nft.metadata_uri.as_deref().unwrap();
+        let doc = String::from_utf8(store.borrow().get(crate::content::parse_uri(meta_uri).unwrap()).unwrap().bytes).unwrap();
+        assert!(doc.contains(&format!(r#""image":"{}""#, nft.metadata.image_uri)) && doc.contains(r#""value":"P""#));
+        assert!(nft.to_json().build().contains("\"metadata_uri\":\"crwn://content/"));
+        // 고정된 콘텐츠는 gc에서 살아남는다
+        assert_eq!(store.borrow_mut().gc().removed, 0);
+        assert!(CrownyNFT::new().mint_stored(&col, "a", NFTMetadata::new("A", "d", "i"), NFTRarity::Common, None).is_err());
+    }
+
     #[test]
     fn test_summary() {
         let m = CrownyNFT::new();
@@ -5,6 +5,8 @@
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::identity::{self, IdentityError, NodeIdentity};
+use crate::ring_log::{RingLog, MESSAGE_LOG_CAPACITY};
 
 // ── 노드 상태 ──
 
@@ -15,6 +17,8 @@ pub enum NodeState {
     Leader,
     Offline,
     Partitioned,
+    /// 컨소시엄 허용 목록 밖 — 상태 동기화만, 선거 · 정족수 제외
+    Observer,
 }
 
 impl std::fmt::Display for NodeState {
@@ -25,6 +29,7 @@ impl std::fmt::Display for NodeState {
             Self::Leader => write!(f, "Leader"),
             Self::Offline => write!(f, "Offline"),
             Self::Partitioned => write!(f, "Partitioned"),
+            Self::Observer => write!(f, "Observer"),
         }
     }
 }
@@ -43,6 +48,11 @@ impl NodeId {
         Self { id: id.to_string(), region: region.to_string(), shard }
     }
 
+    /// 신원 키에서 유도한 ID (identity::SECRET_FILE에 보관하면 재시작해도 유지)
+    pub fn from_identity(identity: &NodeIdentity, region: &str, shard: u32) -> Self {
+        Self::new(&identity.node_id(), region, shard)
+    }
+
     pub fn generate(region: &str, shard: u32) -> Self {
         let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
         let id = format!("node-{}-{}-{}", region, shard, ts % 100000);
@@ -168,6 +178,35 @@ impl std::fmt::Display for SyncMessage {
     }
 }
 
+impl SyncMessage {
+    pub fn sender(&self) -> &NodeId {
+        match self {
+            Self::Heartbeat { from, .. } | Self::VoteRequest { from, .. } | Self::VoteResponse { from, .. }
+            | Self::StateSync { from, .. } | Self::StateSyncAck { from, .. } | Self::Partition { from, .. }
+            | Self::Rejoin { from, .. } => from,
+        }
+    }
+
+    /// 서명 대상 바이트
+    fn signing_payload(&self) -> Vec<u8> {
+        format!("crowny-sync\0{:?}", self).into_bytes()
+    }
+}
+
+/// 보낸 노드의 공개 키와 서명이 붙은 동기화 메시지 (신원 키 없는 노드는 둘 다 빈 값)
+#[derive(Debug, Clone)]
+pub struct SignedSync {
+    pub message: SyncMessage,
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl SignedSync {
+    pub fn is_signed(&self) -> bool {
+        !self.signature.is_empty()
+    }
+}
+
 // ── 분산 노드 ──
 
 pub struct DistributedNode {
@@ -181,8 +220,12 @@ pub struct DistributedNode {
     pub election_timeout_ms: u64,
     pub state_version: u64,
     pub state_data: HashMap<String, String>,
-    pub message_log: Vec<SyncMessage>,
+    pub message_log: RingLog<SyncMessage>,
     pub vote_log: Vec<TritVote>,
+    /// 컨소시엄 모드 허용 목록 (None이면 공개)
+    pub allow_list: Option<Vec<String>>,
+    /// 서명용 신원 키 — 있으면 id는 키에서 유도된 값
+    pub identity: Option<NodeIdentity>,
 }
 
 impl DistributedNode {
@@ -198,14 +241,77 @@ impl DistributedNode {
             election_timeout_ms: 5000,
             state_version: 0,
             state_data: HashMap::new(),
-            message_log: Vec::new(),
+            message_log: RingLog::new(MESSAGE_LOG_CAPACITY),
             vote_log: Vec::new(),
+            allow_list: None,
+            identity: None,
+        }
+    }
+
+    /// 신원 키로 ID를 정하는 노드
+    pub fn with_identity(identity: NodeIdentity, region: &str, shard: u32) -> Self {
+        let mut node = Self::new(NodeId::from_identity(&identity, region, shard));
+        node.identity = Some(identity);
+        node
+    }
+
+    /// 메시지 로그 상한 (crowny.toml [node] message_log)
+    pub fn with_message_log_capacity(mut self, capacity: usize) -> Self {
+        self.message_log.set_capacity(capacity);
+        self
+    }
+
+    /// 보낼 메시지에 서명 — 신원 키가 없으면 서명 없는 봉투
+    pub fn sign_message(&self, message: SyncMessage) -> SignedSync {
+        match &self.identity {
+            Some(identity) => {
+                let signature = crate::crypto::to_hex(&identity.sign(&message.signing_payload()));
+                SignedSync { message, public_key: identity.public_hex(), signature }
+            }
+            None => SignedSync { message, public_key: String::new(), signature: String::new() },
         }
     }
 
+    /// 받은 메시지 검증 — 보낸 ID가 공개 키에서 유도되고 서명이 맞아야 통과
+    pub fn verify_message<'a>(&self, signed: &'a SignedSync) -> Result<&'a SyncMessage, IdentityError> {
+        if !signed.is_signed() {
+            return Err(IdentityError::MissingSignature(signed.message.sender().id.clone()));
+        }
+        let public = identity::parse_public(&signed.public_key)?;
+        let signature = identity::parse_signature(&signed.signature)?;
+        identity::verify_claim(&signed.message.sender().id, &public, &signed.message.signing_payload(), &signature)?;
+        Ok(&signed.message)
+    }
+
+    /// 컨소시엄 모드 — 목록 밖 피어는 관찰자로 강등
+    pub fn with_allow_list(mut self, allow_list: Vec<String>) -> Self {
+        self.allow_list = Some(allow_list);
+        let ids: Vec<String> = self.peers.keys().cloned().collect();
+        for id in ids {
+            if !self.is_allowed(&id) {
+                if let Some(p) = self.peers.get_mut(&id) {
+                    p.state = NodeState::Observer;
+                }
+            }
+        }
+        self
+    }
+
+    pub fn is_allowed(&self, node_id: &str) -> bool {
+        self.allow_list.as_ref().is_none_or(|list| list.iter().any(|a| a == node_id))
+    }
+
+    /// 선거 · 정족수에 들어가는 피어 (관찰자 제외)
+    pub fn voting_peers(&self) -> Vec<&Peer> {
+        self.peers.values().filter(|p| p.state != NodeState::Observer).collect()
+    }
+
     // ── 피어 관리 ──
 
-    pub fn add_peer(&mut self, peer: Peer) {
+    pub fn add_peer(&mut self, mut peer: Peer) {
+        if !self.is_allowed(&peer.node_id.id) {
+            peer.state = NodeState::Observer;
+        }
         self.peers.insert(peer.node_id.id.clone(), peer);
     }
 
@@ -226,7 +332,7 @@ impl DistributedNode {
     }
 
     pub fn cluster_size(&self) -> usize {
-        self.peers.len() + 1 // +1 for self
+        self.voting_peers().len() + 1 // +1 for self
     }
 
     pub fn quorum_size(&self) -> usize {
@@ -252,7 +358,9 @@ impl DistributedNode {
         if let Some(peer) = self.peers.get_mut(&from.id) {
             peer.last_heartbeat = now_ms();
             peer.term = term;
-            peer.state = NodeState::Follower;
+            if peer.state != NodeState::Observer {
+                peer.state = NodeState::Follower;
+            }
         }
         self.message_log.push(SyncMessage::Heartbeat {
             from: from.clone(), term, leader_id: leader_id.clone(),
@@ -283,7 +391,9 @@ impl DistributedNode {
     }
 
     pub fn receive_vote_request(&mut self, from: &NodeId, term: u64) -> SyncMessage {
-        let vote = if term > self.term && self.voted_for.is_none() {
+        let vote = if !self.is_allowed(&from.id) {
+            -1 // T: 관찰자는 후보가 될 수 없음
+        } else if term > self.term && self.voted_for.is_none() {
             self.term = term;
             self.voted_for = Some(from.clone());
             1  // P: 투표
@@ -383,6 +493,45 @@ impl DistributedNode {
         }
     }
 
+    // ── 수신 ──
+
+    /// 받은 메시지 처리 → 돌려보낼 응답 — 신원 키가 있는 노드는 서명 없는 · 위조된 메시지를 버린다
+    pub fn receive(&mut self, signed: &SignedSync) -> Result<Option<SyncMessage>, IdentityError> {
+        if self.identity.is_some() {
+            self.verify_message(signed)?;
+        }
+        let reply = match &signed.message {
+            SyncMessage::Heartbeat { from, term, leader_id } => {
+                self.receive_heartbeat(from, *term, leader_id);
+                None
+            }
+            SyncMessage::VoteRequest { from, term, .. } => Some(self.receive_vote_request(from, *term)),
+            SyncMessage::VoteResponse { from, term, vote } => {
+                self.vote_log.push(TritVote {
+                    voter: from.clone(),
+                    term: *term,
+                    vote: *vote,
+                    reason: "received".to_string(),
+                    timestamp: now_ms(),
+                });
+                None
+            }
+            SyncMessage::StateSync { version, data, .. } => Some(self.apply_sync(*version, data)),
+            SyncMessage::StateSyncAck { from, version, accepted } => {
+                if let (true, Some(peer)) = (*accepted, self.peers.get_mut(&from.id)) {
+                    peer.synced_version = *version;
+                }
+                None
+            }
+            SyncMessage::Partition { .. } => {
+                self.message_log.push(signed.message.clone());
+                None
+            }
+            SyncMessage::Rejoin { from, last_version } => Some(self.handle_rejoin(from, *last_version)),
+        };
+        Ok(reply)
+    }
+
     // ── 파티션 처리 ──
 
     pub fn detect_partition(&self) -> Vec<NodeId> {
@@ -454,13 +603,16 @@ pub struct ClusterSimulator {
 }
 
 impl ClusterSimulator {
-    pub fn new(count: usize, region: &str) -> Self {
-        let mut nodes = Vec::new();
-        for i in 0..count {
-            let id = NodeId::new(&format!("node-{}", i), region, i as u32);
-            nodes.push(DistributedNode::new(id));
-        }
+    /// 신원 키 노드 클러스터 — 모든 동기화 메시지에 서명하고 받을 때 검증
+    pub fn with_identities(identities: Vec<NodeIdentity>, region: &str) -> Self {
+        let nodes = identities.into_iter().enumerate()
+            .map(|(i, identity)| DistributedNode::with_identity(identity, region, i as u32))
+            .collect();
+        Self::connect(nodes)
+    }
 
+    fn connect(mut nodes: Vec<DistributedNode>) -> Self {
+        let count = nodes.len();
         // 서로 피어 등록
         for i in 0..count {
             for j in 0..count {
@@ -475,6 +627,12 @@ impl ClusterSimulator {
         Self { nodes }
     }
 
+    /// 모든 노드의 메시지 로그 상한
+    pub fn with_message_log_capacity(mut self, capacity: usize) -> Self {
+        self.nodes = self.nodes.into_iter().map(|n| n.with_message_log_capacity(capacity)).collect();
+        self
+    }
+
     pub fn simulate_election(&mut self) -> VoteResult {
         if self.nodes.is_empty() {
             return VoteResult {
@@ -485,28 +643,17 @@ impl ClusterSimulator {
 
         // 노드 0이 선거 시작
         let req = self.nodes[0].start_election();
+        let req = self.nodes[0].sign_message(req);
         let term = self.nodes[0].term;
 
-        // 나머지 노드들이 투표
-        let mut responses = Vec::new();
-        for i in 1..self.nodes.len() {
-            let from = self.nodes[0].id.clone();
-            let resp = self.nodes[i].receive_vote_request(&from, term);
-            responses.push(resp);
-        }
+        // 나머지 노드들이 투표 — 검증 실패한 요청은 버린다
+        let responses: Vec<SignedSync> = self.nodes[1..].iter_mut()
+            .filter_map(|node| node.receive(&req).ok().flatten().map(|resp| node.sign_message(resp)))
+            .collect();
 
         // 응답 수집
-        let voter_id = self.nodes[0].id.clone();
         for resp in &responses {
-            if let SyncMessage::VoteResponse { vote, .. } = resp {
-                self.nodes[0].vote_log.push(TritVote {
-                    voter: voter_id.clone(),
-                    term,
-                    vote: *vote,
-                    reason: "received".to_string(),
-                    timestamp: now_ms(),
-                });
-            }
+            let _ = self.nodes[0].receive(resp);
         }
 
         let won = self.nodes[0].check_election_result();
@@ -526,14 +673,15 @@ impl ClusterSimulator {
         if self.nodes.is_empty() { return; }
 
         // 리더가 상태 설정
-        let version = self.nodes[0].set_state(key, value);
-        let sync_msg = self.nodes[0].create_sync_message();
+        self.nodes[0].set_state(key, value);
+        let sync_msg = self.nodes[0].sign_message(self.nodes[0].create_sync_message());
 
-        // 팔로워에게 전파
-        if let SyncMessage::StateSync { version, data, .. } = sync_msg {
-            for i in 1..self.nodes.len() {
-                self.nodes[i].apply_sync(version, &data);
-            }
+        // 팔로워에게 전파 · 확인 응답 수집
+        let acks: Vec<SignedSync> = self.nodes[1..].iter_mut()
+            .filter_map(|node| node.receive(&sync_msg).ok().flatten().map(|ack| node.sign_message(ack)))
+            .collect();
+        for ack in &acks {
+            let _ = self.nodes[0].receive(ack);
         }
     }
 
@@ -567,16 +715,22 @@ fn now_ms() -> u64 {
 
 // ═══ 데모 ═══
 
-pub fn demo_distributed_node() {
+pub fn demo_distributed_node(message_log_capacity: usize) {
     println!("╔═══════════════════════════════════════════╗");
     println!("║  Crowny Distributed Node System           ║");
     println!("║  분산 노드 — 3진 합의 클러스터             ║");
     println!("╚═══════════════════════════════════════════╝");
     println!();
 
-    // 5노드 클러스터 생성
-    println!("━━━ 1. 클러스터 생성 (5노드) ━━━");
-    let mut cluster = ClusterSimulator::new(5, "ap-northeast-2");
+    // 5노드 클러스터 생성 — 이 노드의 신원은 비밀 파일에서 (재시작해도 같은 ID), 나머지는 새 키
+    println!("━━━ 1. 클러스터 생성 (5노드 · 서명 동기화) ━━━");
+    let local = NodeIdentity::load_or_create(identity::SECRET_FILE).unwrap_or_else(|e| {
+        println!("  신원 키 실패 — 임시 키 사용: {}", e);
+        NodeIdentity::generate()
+    });
+    println!("  이 노드: {} ({})", local.node_id(), identity::SECRET_FILE);
+    let identities = std::iter::once(local).chain((1..5).map(|_| NodeIdentity::generate())).collect();
+    let mut cluster = ClusterSimulator::with_identities(identities, "ap-northeast-2").with_message_log_capacity(message_log_capacity);
     println!("{}", cluster.summary());
     println!();
 
@@ -607,6 +761,44 @@ pub fn demo_distributed_node() {
     println!("  Quorum 유지: {}", if cluster.nodes[0].alive_peers().len() + 1 >= cluster.nodes[0].quorum_size() { "✓" } else { "✗" });
     println!();
 
+    // 서명 검증 — 서명 없는 · 남의 ID를 주장한 메시지는 거부
+    println!("━━━ 5. 서명 없는 메시지 거부 ━━━");
+    let outsider = DistributedNode::new(NodeId::new("node-outsider", "ap-northeast-2", 9));
+    let forged = outsider.sign_message(SyncMessage::Heartbeat {
+        from: cluster.nodes[0].id.clone(), term: 99, leader_id: outsider.id.clone(),
+    });
+    match cluster.nodes[1].receive(&forged) {
+        Ok(_) => println!("  ✗ 위조 하트비트 수락"),
+        Err(e) => println!("  ✓ 거부: {}", e),
+    }
+    println!();
+
+    // 가십 — 시드 하나만 알려 주고 메시 자동 구성
+    println!("━━━ 6. 가십 피어 발견 (CTP/TCP) ━━━");
+    let seed = crate::gossip::GossipNode::start_manual("seed", "127.0.0.1:0", crate::gossip::GossipConfig::new());
+    match seed {
+        Ok(seed) => {
+            let joiners: Vec<_> = ["g1", "g2", "g3"].iter()
+                .filter_map(|id| crate::gossip::GossipNode::start_manual(id, "127.0.0.1:0",
+                    crate::gossip::GossipConfig::new().with_seeds(&[seed.addr()])).ok())
+                .collect();
+            for _ in 0..2 {
+                for g in &joiners {
+                    g.round();
+                }
+            }
+            let mut node = DistributedNode::new(NodeId::new("seed", "ap-northeast-2", 0));
+            let added = seed.sync_into(&mut node);
+            for g in &joiners {
+                let view: Vec<String> = g.members().iter().map(|m| format!("{}={}", m.id, m.health.symbol())).collect();
+                println!("  {} 가 아는 멤버: {}", g.addr(), view.join(" "));
+            }
+            println!("  시드 노드 피어 표에 {} 피어 추가", added);
+        }
+        Err(e) => println!("  가십 리스너 실패: {}", e),
+    }
+    println!();
+
     println!("✓ 분산 노드 데모 완료 — {} 노드 클러스터", cluster.nodes.len());
 }
 
@@ -616,6 +808,14 @@ pub fn demo_distributed_node() {
 mod tests {
     use super::*;
 
+    /// 신원 키 없는 노드 클러스터 (node-0 · node-1 …)
+    fn plain_cluster(count: usize, region: &str) -> ClusterSimulator {
+        let nodes = (0..count)
+            .map(|i| DistributedNode::new(NodeId::new(&format!("node-{}", i), region, i as u32)))
+            .collect();
+        ClusterSimulator::connect(nodes)
+    }
+
     #[test]
     fn test_node_id() {
         let id = NodeId::new("node-0", "kr", 0);
@@ -630,6 +830,17 @@ mod tests {
         assert!(id.id.starts_with("node-us-east-1-"));
     }
 
+    #[test]
+    fn test_message_log_capacity() {
+        let mut cluster = plain_cluster(2, "kr").with_message_log_capacity(3);
+        let (leader, follower) = (cluster.nodes[0].id.clone(), &mut cluster.nodes[1]);
+        for term in 0..10 {
+            follower.receive_heartbeat(&leader, term, &leader);
+        }
+        assert_eq!(follower.message_log.len(), 3);
+        assert_eq!(follower.message_log.oldest_index(), 7);
+    }
+
     #[test]
     fn test_peer_alive() {
         let id = NodeId::new("p1", "kr", 0);
@@ -639,14 +850,14 @@ mod tests {
 
     #[test]
     fn test_cluster_creation() {
-        let cluster = ClusterSimulator::new(3, "kr");
+        let cluster = plain_cluster(3, "kr");
         assert_eq!(cluster.nodes.len(), 3);
         assert_eq!(cluster.nodes[0].peers.len(), 2);
     }
 
     #[test]
     fn test_election() {
-        let mut cluster = ClusterSimulator::new(3, "kr");
+        let mut cluster = plain_cluster(3, "kr");
         let result = cluster.simulate_election();
         assert!(result.total > 0);
         assert_eq!(cluster.nodes[0].state, NodeState::Leader);
@@ -654,7 +865,7 @@ mod tests {
 
     #[test]
     fn test_state_sync() {
-        let mut cluster = ClusterSimulator::new(3, "kr");
+        let mut cluster = plain_cluster(3, "kr");
         cluster.simulate_election();
         cluster.simulate_state_sync("key1", "value1");
         for node in &cluster.nodes {
@@ -675,9 +886,67 @@ mod tests {
         assert_eq!(result.negative, 1);
     }
 
+    #[test]
+    fn test_allow_list_demotes_outsiders() {
+        let cluster = plain_cluster(4, "kr");
+        let mut node = DistributedNode::new(cluster.nodes[0].id.clone())
+            .with_allow_list(vec!["node-0".into(), "node-1".into(), "node-2".into()]);
+        for n in &cluster.nodes[1..] {
+            node.add_peer(Peer::new(n.id.clone(), "127.0.0.1", 7293));
+        }
+        assert_eq!(node.peers["node-3"].state, NodeState::Observer);
+        assert_eq!(node.cluster_size(), 3);
+        assert_eq!(node.quorum_size(), 2);
+
+        let outsider = cluster.nodes[3].id.clone();
+        node.receive_heartbeat(&outsider, 1, &outsider.clone());
+        assert_eq!(node.peers["node-3"].state, NodeState::Observer);
+        let resp = node.receive_vote_request(&outsider, 5);
+        assert!(matches!(resp, SyncMessage::VoteResponse { vote: -1, .. }));
+    }
+
+    #[test]
+    fn test_signed_sync_rejects_claimed_id_mismatch() {
+        let alice = DistributedNode::with_identity(NodeIdentity::from_seed([3; 32]), "kr", 0);
+        let bob = DistributedNode::with_identity(NodeIdentity::from_seed([4; 32]), "kr", 1);
+        assert!(alice.id.id.starts_with(identity::NODE_ID_PREFIX));
+
+        let signed = alice.sign_message(alice.send_heartbeat());
+        assert_eq!(bob.verify_message(&signed).unwrap().sender(), &alice.id);
+
+        // 남의 ID로 보낸 메시지 — 자기 키로 서명해도 ID가 키와 맞지 않는다
+        let mut spoofed = bob.sign_message(SyncMessage::Rejoin { from: alice.id.clone(), last_version: 9 });
+        assert!(matches!(alice.verify_message(&spoofed), Err(IdentityError::IdMismatch { .. })));
+        // 서명 후 내용 변조
+        spoofed = alice.sign_message(SyncMessage::Rejoin { from: alice.id.clone(), last_version: 9 });
+        spoofed.message = SyncMessage::Rejoin { from: alice.id.clone(), last_version: 10 };
+        assert!(matches!(bob.verify_message(&spoofed), Err(IdentityError::BadSignature(_))));
+        // 신원 키 없는 노드의 메시지는 서명 없음
+        let unsigned = DistributedNode::new(NodeId::new("n0", "kr", 0)).sign_message(alice.send_heartbeat());
+        assert_eq!(bob.verify_message(&unsigned).unwrap_err(), IdentityError::MissingSignature(alice.id.id.clone()));
+    }
+
+    #[test]
+    fn test_signed_cluster_rejects_unsigned_sync() {
+        let identities = (1..=3).map(|s| NodeIdentity::from_seed([s; 32])).collect();
+        let mut cluster = ClusterSimulator::with_identities(identities, "kr");
+        cluster.simulate_election();
+        assert_eq!(cluster.nodes[0].state, NodeState::Leader);
+        cluster.simulate_state_sync("key1", "value1");
+        assert!(cluster.nodes.iter().all(|n| n.get_state("key1") == Some(&"value1".to_string())));
+        assert!(cluster.nodes[0].peers.values().all(|p| p.synced_version == 1));
+
+        // 서명 없는 동기화 → 거부, 상태 그대로
+        let leader = cluster.nodes[0].id.clone();
+        let plain = DistributedNode::new(leader.clone())
+            .sign_message(SyncMessage::StateSync { from: leader, version: 9, data: vec![("key1".into(), "forged".into())] });
+        assert!(matches!(cluster.nodes[1].receive(&plain), Err(IdentityError::MissingSignature(_))));
+        assert_eq!(cluster.nodes[1].get_state("key1"), Some(&"value1".to_string()));
+    }
+
     #[test]
     fn test_quorum() {
-        let cluster = ClusterSimulator::new(5, "kr");
+        let cluster = plain_cluster(5, "kr");
         assert_eq!(cluster.nodes[0].cluster_size(), 5);
         assert_eq!(cluster.nodes[0].quorum_size(), 3);
     }
@@ -0,0 +1,276 @@
+// ═══════════════════════════════════════════════════════════════
+// 노트북 — Markdown 안의 ```hanseon 코드 펜스를 순서대로 실행
+// 모든 셀은 하나의 TVM 세션을 공유 (스택·레지스터·힙 유지)
+// 셀 결과(출력·스택 최상위·Trit 상태)는 ```output 블록으로 되써지거나
+// website::render_html로 HTML 보고서가 된다
+// ═══════════════════════════════════════════════════════════════
+
+use crate::assembler::assemble;
+use crate::vm::{ExecLimits, TVM};
+
+/// 실행 대상 펜스 언어
+const EXEC_LANGS: [&str; 3] = ["hanseon", "hsn", "한선어"];
+/// 결과 블록 펜스 언어 — 다시 실행하면 교체된다
+const OUTPUT_LANG: &str = "output";
+/// 셀당 사이클 한도 (문서 속 무한 루프 방지)
+const CELL_MAX_CYCLES: u64 = 1_000_000;
+
+// ═══════════════════════════════════════
+// 문서 구조
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    /// 코드 펜스 밖의 원문
+    Text(Vec<String>),
+    /// 코드 펜스 — fence는 여는 줄 원문, line은 1부터
+    Code { lang: String, skip: bool, fence: String, source: Vec<String>, line: usize },
+}
+
+impl Cell {
+    pub fn is_executable(&self) -> bool {
+        matches!(self, Cell::Code { lang, .. } if EXEC_LANGS.contains(&lang.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notebook {
+    pub cells: Vec<Cell>,
+}
+
+fn fence_info(line: &str) -> Option<(String, bool)> {
+    let info = line.trim_start().strip_prefix("```")?;
+    let mut words = info.split_whitespace();
+    let lang = words.next().unwrap_or("").to_string();
+    let skip = words.any(|w| w == "skip");
+    Some((lang, skip))
+}
+
+impl Notebook {
+    /// Markdown 파싱 — 기존 ```output 블록은 버린다 (재실행 시 교체)
+    pub fn parse(markdown: &str) -> Self {
+        let mut cells = Vec::new();
+        let mut text: Vec<String> = Vec::new();
+        let mut lines = markdown.lines().enumerate();
+
+        while let Some((i, line)) = lines.next() {
+            let Some((lang, skip)) = fence_info(line) else {
+                text.push(line.to_string());
+                continue;
+            };
+            let mut source = Vec::new();
+            for (_, inner) in lines.by_ref() {
+                if inner.trim_start().starts_with("```") {
+                    break;
+                }
+                source.push(inner.to_string());
+            }
+            if lang == OUTPUT_LANG {
+                continue;
+            }
+            if !text.is_empty() {
+                cells.push(Cell::Text(std::mem::take(&mut text)));
+            }
+            cells.push(Cell::Code { lang, skip, fence: line.to_string(), source, line: i + 1 });
+        }
+        if !text.is_empty() {
+            cells.push(Cell::Text(text));
+        }
+        Self { cells }
+    }
+
+    /// 모든 실행 셀을 하나의 세션에서 순서대로 실행
+    pub fn execute(&self) -> Vec<CellResult> {
+        let mut session = NotebookSession::new();
+        self.cells.iter().enumerate()
+            .filter(|(_, c)| c.is_executable())
+            .map(|(i, cell)| session.run(i, cell))
+            .collect()
+    }
+
+    /// 결과를 ```output 블록으로 끼워 넣은 Markdown
+    pub fn render_markdown(&self, results: &[CellResult]) -> String {
+        let mut out = String::new();
+        for (i, cell) in self.cells.iter().enumerate() {
+            match cell {
+                Cell::Text(lines) => {
+                    for l in lines {
+                        out.push_str(l);
+                        out.push('\n');
+                    }
+                }
+                Cell::Code { fence, source, .. } => {
+                    out.push_str(fence);
+                    out.push('\n');
+                    for l in source {
+                        out.push_str(l);
+                        out.push('\n');
+                    }
+                    out.push_str("```\n");
+                    if let Some(r) = results.iter().find(|r| r.cell == i) {
+                        out.push_str(&format!("```{}\n", OUTPUT_LANG));
+                        out.push_str(&r.summary());
+                        out.push('\n');
+                        for l in &r.output {
+                            out.push_str(l);
+                            out.push('\n');
+                        }
+                        out.push_str("```\n");
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    pub fn render_html(&self, title: &str, results: &[CellResult]) -> String {
+        crate::website::render_html(title, &self.render_markdown(results))
+    }
+}
+
+// ═══════════════════════════════════════
+// 실행 세션
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellResult {
+    /// Notebook::cells 인덱스
+    pub cell: usize,
+    /// 문서 내 펜스 줄 번호
+    pub line: usize,
+    /// P: 정상 · O: 건너뜀(skip/빈 셀) · T: 오류
+    pub state: i8,
+    pub output: Vec<String>,
+    pub top: Option<String>,
+    pub cycles: u64,
+    pub error: Option<String>,
+}
+
+impl CellResult {
+    pub fn summary(&self) -> String {
+        let sym = match self.state { 1 => 'P', 0 => 'O', _ => 'T' };
+        match (&self.error, self.state) {
+            (Some(e), _) => format!("[{}] 오류: {}", sym, e),
+            (None, 0) => format!("[{}] 건너뜀", sym),
+            (None, _) => format!("[{}] top={} · {}사이클",
+                sym, self.top.as_deref().unwrap_or("-"), self.cycles),
+        }
+    }
+}
+
+pub struct NotebookSession {
+    pub vm: TVM,
+}
+
+impl NotebookSession {
+    pub fn new() -> Self {
+        let mut vm = TVM::new();
+        vm.limits = ExecLimits { max_cycles: Some(CELL_MAX_CYCLES), ..ExecLimits::unlimited() };
+        Self { vm }
+    }
+
+    pub fn run(&mut self, index: usize, cell: &Cell) -> CellResult {
+        let (skip, source, line) = match cell {
+            Cell::Code { skip, source, line, .. } => (*skip, source.join("\n"), *line),
+            Cell::Text(_) => (true, String::new(), 0),
+        };
+        let mut result = CellResult {
+            cell: index, line, state: 0, output: Vec::new(),
+            top: None, cycles: 0, error: None,
+        };
+
+        let program = assemble(&source);
+        if skip || program.is_empty() {
+            return result;
+        }
+
+        let before = self.vm.cycles;
+        self.vm.captured = Some(Vec::new());
+        self.vm.load_continue(program);
+        let run = self.vm.run();
+
+        result.output = self.vm.captured.take().unwrap_or_default();
+        result.cycles = self.vm.cycles - before;
+        result.top = self.vm.stack.last().map(|v| v.to_string());
+        match run {
+            Ok(()) => result.state = 1,
+            Err(e) => {
+                result.state = -1;
+                result.error = Some(e.to_string());
+            }
+        }
+        result
+    }
+}
+
+/// 전체 상태 — 하나라도 T면 T, 아니면 P
+pub fn overall_state(results: &[CellResult]) -> i8 {
+    if results.iter().any(|r| r.state == -1) { -1 } else { 1 }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "# 튜토리얼\n\n첫 셀:\n\n```hanseon\n넣어 10\n넣어 20\n더해\n복사\n보여줘\n```\n\n둘째 셀은 스택을 이어받는다:\n\n```hanseon\n넣어 2\n곱해\n보여줘\n```\n\n```rust\nfn main() {}\n```\n\n```hanseon skip\n넣어 1\n```\n";
+
+    #[test]
+    fn test_parse_cells() {
+        let nb = Notebook::parse(DOC);
+        assert_eq!(nb.cells.iter().filter(|c| c.is_executable()).count(), 3);
+        let lines: Vec<usize> = nb.cells.iter().filter_map(|c| match c {
+            Cell::Code { line, .. } => Some(*line),
+            _ => None,
+        }).collect();
+        assert_eq!(lines, vec![5, 15, 21, 25]);
+    }
+
+    #[test]
+    fn test_shared_session_and_states() {
+        let nb = Notebook::parse(DOC);
+        let results = nb.execute();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].output, vec!["30"]);
+        assert_eq!(results[0].top.as_deref(), Some("30"));
+        // 두 번째 셀은 첫 셀이 남긴 30을 사용
+        assert_eq!(results[1].output, vec!["60"]);
+        assert_eq!(results[1].state, 1);
+        assert_eq!(results[2].state, 0);
+        assert_eq!(overall_state(&results), 1);
+    }
+
+    #[test]
+    fn test_error_cell_is_t() {
+        let nb = Notebook::parse("```hanseon\n더해\n```\n");
+        let results = nb.execute();
+        assert_eq!(results[0].state, -1);
+        assert!(results[0].error.is_some());
+        assert_eq!(overall_state(&results), -1);
+        assert!(results[0].summary().starts_with("[T] 오류"));
+    }
+
+    #[test]
+    fn test_write_back_is_idempotent() {
+        let nb = Notebook::parse(DOC);
+        let once = nb.render_markdown(&nb.execute());
+        assert!(once.contains("```output\n[P] top=30 · 5사이클\n30\n```"));
+        assert!(once.contains("```output\n[O] 건너뜀\n```"));
+
+        // 이전 output 블록은 버려지고 같은 셀만 남는다
+        let again = Notebook::parse(&once);
+        assert_eq!(again.cells.len(), nb.cells.len());
+        assert_eq!(again.cells.iter().filter(|c| c.is_executable()).count(), 3);
+        let twice = again.render_markdown(&again.execute());
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn test_render_html() {
+        let nb = Notebook::parse(DOC);
+        let html = nb.render_html("튜토리얼", &nb.execute());
+        assert!(html.contains("<h1>튜토리얼</h1>"));
+        assert!(html.contains("<code class=\"language-output\">[P] top=- · 3사이클\n60\n</code>"));
+    }
+}
@@ -128,7 +128,7 @@ pub fn build_opcodes() -> HashMap<OpcodeAddr, OpMeta> {
     m.insert(OpcodeAddr::new(s,4,0), op!("함수",     "FUNC",   0,0,0, Effect::Control));
     m.insert(OpcodeAddr::new(s,4,1), op!("매개변수", "PARAM",  0,1,0, Effect::Stack));
     m.insert(OpcodeAddr::new(s,4,2), op!("돌려줘",   "RETURN", 1,0,0, Effect::Control));
-    m.insert(OpcodeAddr::new(s,4,3), op!("재귀",     "RECUR",  0,0,0, Effect::Control));
+    m.insert(OpcodeAddr::new(s,4,3), op!("삼분기",   "BR3",    1,0,3, Effect::Control));
     m.insert(OpcodeAddr::new(s,4,4), op!("람다",     "LAMBDA", 0,1,0, Effect::Stack));
     m.insert(OpcodeAddr::new(s,4,5), op!("적용해",   "APPLY",  2,1,0, Effect::Stack));
     m.insert(OpcodeAddr::new(s,4,6), op!("묶어",     "BIND",   2,1,0, Effect::Stack));
@@ -182,6 +182,48 @@ pub fn build_opcodes() -> HashMap<OpcodeAddr, OpMeta> {
     m
 }
 
+/// 섹터·그룹별 명령어 목록 (info 표시용) — 그룹 안은 명령 번호순
+pub fn catalog_lines() -> Vec<String> {
+    let opcodes = build_opcodes();
+    let mut lines = vec![
+        "╔═══════════════════════════════════════════════╗".to_string(),
+        "║  CROWNIN TVM — 등록된 명령어 목록              ║".to_string(),
+        format!("║  729 슬롯 중 {} 개 구현                        ║", opcodes.len()),
+        "╚═══════════════════════════════════════════════╝".to_string(),
+    ];
+
+    for sec in 0..9u8 {
+        let (kr, en) = SECTOR_NAMES[sec as usize];
+        let count = opcodes.iter().filter(|(a, _)| a.sector == sec).count();
+        if count == 0 { continue; }
+
+        lines.push(String::new());
+        lines.push(format!("── 섹터 {}: {} ({}) — {} 명령어 ──", sec, kr, en, count));
+
+        for grp in 0..9u8 {
+            let mut group_ops: Vec<_> = opcodes.iter()
+                .filter(|(a, _)| a.sector == sec && a.group == grp)
+                .collect();
+            if group_ops.is_empty() { continue; }
+            group_ops.sort_by_key(|(a, _)| a.command);
+
+            let grp_name = if sec == 0 && (grp as usize) < GROUP_NAMES_CORE.len() {
+                GROUP_NAMES_CORE[grp as usize]
+            } else {
+                "─"
+            };
+            lines.push(format!("  G{} [{}]:", grp, grp_name));
+            for (addr, meta) in &group_ops {
+                lines.push(format!("    ({},{},{}) {:10} {:8} pop:{} push:{} oper:{}",
+                    addr.sector, addr.group, addr.command,
+                    meta.name_kr, meta.name_en,
+                    meta.pops, meta.pushes, meta.operands));
+            }
+        }
+    }
+    lines
+}
+
 /// 이름(한/영) → OpcodeAddr 역방향 조회
 pub fn build_name_lookup(map: &HashMap<OpcodeAddr, OpMeta>) -> HashMap<String, OpcodeAddr> {
     let mut lookup = HashMap::new();
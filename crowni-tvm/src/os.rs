@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::i18n::tr;
 
 fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
 
@@ -75,6 +76,10 @@ impl ProcessPriority {
     pub fn nice(&self) -> i8 {
         match self { Self::High => -10, Self::Normal => 0, Self::Low => 10, Self::Idle => 19 }
     }
+    /// 스케줄러 가중치 — 클수록 vruntime이 느리게 늘어 CPU를 더 받는다
+    pub fn weight(&self) -> u64 {
+        match self { Self::High => 4, Self::Normal => 2, Self::Low => 1, Self::Idle => 1 }
+    }
 }
 
 impl std::fmt::Display for ProcessPriority {
@@ -102,8 +107,27 @@ pub struct Process {
     pub owner: String,
     pub started_at: u64,
     pub syscalls: u64,
+    /// 누적 CPU 시간 (틱)
+    pub cpu_ticks: u64,
+    /// 가중 실행 시간 — 가장 작은 프로세스부터 코어를 받는다
+    pub vruntime: u64,
+    /// wait 중 — 다음 틱에 좀비 자식을 거둔다
+    pub waiting: bool,
+}
+
+impl Process {
+    pub fn is_runnable(&self) -> bool {
+        matches!(self.state, ProcessState::Running | ProcessState::Ready)
+    }
 }
 
+/// 기본 코어 수
+pub const DEFAULT_CORES: usize = 3;
+/// 틱 하나의 vruntime 단위 (가중치로 나눈다)
+const VRUNTIME_SLICE: u64 = 12;
+/// cpu_usage 지수 평균의 새 표본 비중
+const CPU_USAGE_ALPHA: f64 = 0.25;
+
 pub struct ProcessManager {
     pub processes: Vec<Process>,
     pub pid_counter: u32,
@@ -111,6 +135,17 @@ pub struct ProcessManager {
     pub memory_total_kb: u64,
     pub memory_used_kb: u64,
     pub uptime_ms: u64,
+    pub cores: usize,
+    /// 지금까지 돈 스케줄러 틱
+    pub ticks: u64,
+}
+
+impl crate::account::AccountLedger for ProcessManager {
+    fn ledger_name(&self) -> &str { "os" }
+    fn holdings_of(&self, name: &str) -> Vec<(String, u64)> {
+        let owned = self.processes.iter().filter(|p| p.owner == name);
+        vec![("processes".into(), owned.clone().count() as u64), ("memory_kb".into(), owned.map(|p| p.memory_kb).sum())]
+    }
 }
 
 impl ProcessManager {
@@ -122,6 +157,8 @@ impl ProcessManager {
             memory_total_kb: memory_mb * 1024,
             memory_used_kb: 0,
             uptime_ms: now_ms(),
+            cores: DEFAULT_CORES,
+            ticks: 0,
         };
         // PID 0: 커널
         pm.spawn("crowny-kernel", "root", ProcessPriority::High, 2048);
@@ -132,7 +169,7 @@ impl ProcessManager {
 
     pub fn spawn(&mut self, name: &str, owner: &str, priority: ProcessPriority, mem_kb: u64) -> SysCall {
         if self.memory_used_kb + mem_kb > self.memory_total_kb {
-            return SysCall::fail(&format!("메모리 부족: {}KB 필요, {}KB 남음",
+            return SysCall::fail(&tr!("os.out_of_memory",
                 mem_kb, self.memory_total_kb - self.memory_used_kb), 12);
         }
 
@@ -141,12 +178,15 @@ impl ProcessManager {
         self.memory_used_kb += mem_kb;
 
         let parent = if pid > 1 { Some(1) } else { None };
+        // 새 프로세스가 밀린 시간만큼 코어를 독차지하지 않도록 현재 최솟값에서 시작
+        let vruntime = self.processes.iter().filter(|p| p.is_runnable()).map(|p| p.vruntime).min().unwrap_or(0);
 
         self.processes.push(Process {
             pid, name: name.into(), state: ProcessState::Running,
             priority, parent_pid: parent, children: Vec::new(),
             cpu_usage: 0.0, memory_kb: mem_kb, trit_state: 1,
             owner: owner.into(), started_at: now_ms(), syscalls: 0,
+            cpu_ticks: 0, vruntime, waiting: false,
         });
 
         // 부모에 자식 등록
@@ -160,7 +200,7 @@ impl ProcessManager {
     }
 
     pub fn kill(&mut self, pid: u32) -> SysCall {
-        if pid <= 1 { return SysCall::fail("커널/init 프로세스 종료 불가", 1); }
+        if pid <= 1 { return SysCall::fail(crate::i18n::t("os.kill_protected"), 1); }
         if let Some(proc) = self.processes.iter_mut().find(|p| p.pid == pid) {
             proc.state = ProcessState::Zombie;
             proc.trit_state = -1;
@@ -168,7 +208,7 @@ impl ProcessManager {
             let name = proc.name.clone();
             SysCall::ok(&format!("kill PID:{} '{}'", pid, name), None)
         } else {
-            SysCall::fail(&format!("PID:{} 없음", pid), 3)
+            SysCall::fail(&tr!("os.no_pid", pid), 3)
         }
     }
 
@@ -178,7 +218,7 @@ impl ProcessManager {
             proc.trit_state = 0;
             SysCall::ok(&format!("sleep PID:{}", pid), None)
         } else {
-            SysCall::fail(&format!("PID:{} 없음", pid), 3)
+            SysCall::fail(&tr!("os.no_pid", pid), 3)
         }
     }
 
@@ -188,8 +228,79 @@ impl ProcessManager {
             proc.trit_state = 1;
             SysCall::ok(&format!("wake PID:{}", pid), None)
         } else {
-            SysCall::fail(&format!("PID:{} 없음", pid), 3)
+            SysCall::fail(&tr!("os.no_pid", pid), 3)
+        }
+    }
+
+    /// 자식이 끝나기를 기다린다 — 좀비 자식은 다음 tick()에서 거둔다
+    pub fn wait(&mut self, pid: u32) -> SysCall {
+        let Some(proc) = self.processes.iter_mut().find(|p| p.pid == pid && p.state != ProcessState::Zombie) else {
+            return SysCall::fail(&tr!("os.no_pid", pid), 3);
+        };
+        if proc.children.is_empty() {
+            return SysCall::fail(&tr!("os.no_children", pid), 10);
         }
+        proc.waiting = true;
+        proc.state = ProcessState::Sleeping;
+        proc.trit_state = 0;
+        SysCall::pending(&format!("wait PID:{} ({})", pid, proc.children.len()))
+    }
+
+    /// 스케줄러 한 틱:
+    ///   1. wait 중인 부모의 좀비 자식을 거두고 부모를 Ready로
+    ///   2. 실행 가능한 프로세스 중 vruntime이 작은 순서로 cores개를 Running, 나머지는 Ready
+    ///   3. Idle 우선순위는 남는 코어가 있을 때만 돈다
+    ///   4. 실행한 프로세스의 cpu_ticks · vruntime을 늘리고 모두의 cpu_usage를 갱신
+    ///
+    /// 거둔 PID를 돌려준다
+    pub fn tick(&mut self) -> Vec<u32> {
+        self.ticks += 1;
+
+        let mut reaped = Vec::new();
+        let waiting: Vec<(u32, Vec<u32>)> = self.processes.iter()
+            .filter(|p| p.waiting)
+            .map(|p| (p.pid, p.children.clone()))
+            .collect();
+        for (ppid, children) in waiting {
+            let zombies: Vec<u32> = children.into_iter()
+                .filter(|c| self.processes.iter().any(|p| p.pid == *c && p.state == ProcessState::Zombie))
+                .collect();
+            if zombies.is_empty() { continue; }
+            self.processes.retain(|p| !zombies.contains(&p.pid));
+            if let Some(parent) = self.processes.iter_mut().find(|p| p.pid == ppid) {
+                parent.children.retain(|c| !zombies.contains(c));
+                parent.waiting = false;
+                parent.state = ProcessState::Ready;
+                parent.trit_state = 0;
+            }
+            reaped.extend(zombies);
+        }
+
+        let mut runnable: Vec<usize> = (0..self.processes.len()).filter(|&i| self.processes[i].is_runnable()).collect();
+        runnable.sort_by_key(|&i| {
+            let p = &self.processes[i];
+            (p.priority == ProcessPriority::Idle, p.vruntime, p.pid)
+        });
+        let ran: Vec<usize> = runnable.iter().copied().take(self.cores).collect();
+        for &i in &runnable {
+            let p = &mut self.processes[i];
+            if ran.contains(&i) {
+                p.state = ProcessState::Running;
+                p.trit_state = 1;
+                p.cpu_ticks += 1;
+                p.vruntime += VRUNTIME_SLICE / p.priority.weight();
+            } else {
+                p.state = ProcessState::Ready;
+                p.trit_state = 0;
+            }
+        }
+        for (i, p) in self.processes.iter_mut().enumerate() {
+            let sample = if ran.contains(&i) { 100.0 } else { 0.0 };
+            p.cpu_usage += (sample - p.cpu_usage) * CPU_USAGE_ALPHA;
+        }
+        let busy = ran.len() as f64 / self.cores as f64 * 100.0;
+        self.cpu_total += (busy - self.cpu_total) * CPU_USAGE_ALPHA;
+        reaped
     }
 
     pub fn ps(&self) -> Vec<&Process> {
@@ -206,11 +317,12 @@ impl ProcessManager {
 
     pub fn summary(&self) -> String {
         let running = self.running_count();
+        let ready = self.processes.iter().filter(|p| p.state == ProcessState::Ready).count();
         let sleeping = self.processes.iter().filter(|p| p.state == ProcessState::Sleeping).count();
         let zombies = self.processes.iter().filter(|p| p.state == ProcessState::Zombie).count();
         let mem_pct = self.memory_used_kb as f64 / self.memory_total_kb as f64 * 100.0;
-        format!("프로세스: {} (실행:{} 대기:{} 좀비:{}) | 메모리: {}/{}KB ({:.1}%)",
-            self.processes.len(), running, sleeping, zombies,
+        format!("프로세스: {} (실행:{} 준비:{} 대기:{} 좀비:{}) | 메모리: {}/{}KB ({:.1}%)",
+            self.processes.len(), running, ready, sleeping, zombies,
             self.memory_used_kb, self.memory_total_kb, mem_pct)
     }
 }
@@ -399,11 +511,11 @@ impl TritFS {
     pub fn cat(&self, file_id: u64) -> SysCall {
         if let Some(inode) = self.inodes.get(&file_id) {
             if inode.file_type == FileType::Directory {
-                return SysCall::fail("디렉토리입니다", 21);
+                return SysCall::fail(crate::i18n::t("os.is_directory"), 21);
             }
             SysCall::ok(&inode.name, inode.content.clone())
         } else {
-            SysCall::fail("파일 없음", 2)
+            SysCall::fail(crate::i18n::t("os.no_file"), 2)
         }
     }
 
@@ -417,21 +529,21 @@ impl TritFS {
             self.used_bytes = self.used_bytes - old_size + inode.size_bytes;
             SysCall::ok(&format!("write '{}' {}B", inode.name, inode.size_bytes), None)
         } else {
-            SysCall::fail("파일 없음", 2)
+            SysCall::fail(crate::i18n::t("os.no_file"), 2)
         }
     }
 
     pub fn rm(&mut self, file_id: u64) -> SysCall {
         if let Some(inode) = self.inodes.get_mut(&file_id) {
             if inode.file_type == FileType::Directory && !inode.children.is_empty() {
-                return SysCall::fail("비어있지 않은 디렉토리", 39);
+                return SysCall::fail(crate::i18n::t("os.dir_not_empty"), 39);
             }
             inode.trit_state = -1;
             let name = inode.name.clone();
             self.used_bytes = self.used_bytes.saturating_sub(inode.size_bytes);
             SysCall::ok(&format!("rm '{}'", name), None)
         } else {
-            SysCall::fail("파일 없음", 2)
+            SysCall::fail(crate::i18n::t("os.no_file"), 2)
         }
     }
 
@@ -447,6 +559,19 @@ impl TritFS {
         Some(current)
     }
 
+    /// 상대 경로는 dir에서부터 ("..", "." 허용), /로 시작하면 루트에서
+    pub fn resolve_from(&self, dir: u64, path: &str) -> Option<u64> {
+        let mut current = if path.starts_with('/') { 0 } else { dir };
+        for part in path.split('/').filter(|s| !s.is_empty() && *s != ".") {
+            current = if part == ".." {
+                self.inodes.get(&current)?.parent.unwrap_or(0)
+            } else {
+                self.find_child(current, part).filter(|id| self.inodes[id].trit_state >= 0)?
+            };
+        }
+        Some(current)
+    }
+
     pub fn tree(&self, id: u64, depth: usize, max_depth: usize) -> String {
         if depth > max_depth { return String::new(); }
         let mut out = String::new();
@@ -480,6 +605,30 @@ impl TritFS {
 // 3. TritShell — 3진 쉘
 // ═══════════════════════════════════════
 
+/// help 출력 (사용법, 설명 키)
+const SHELL_HELP: &[(&str, &str)] = &[
+    ("ps", "shell.help.ps"),
+    ("spawn <n> <m>", "shell.help.spawn"),
+    ("kill <pid>", "shell.help.kill"),
+    ("wait <pid>", "shell.help.wait"),
+    ("top [n]", "shell.help.top"),
+    ("ls", "shell.help.ls"),
+    ("cd <dir>", "shell.help.cd"),
+    ("cat <file>", "shell.help.cat"),
+    ("mkdir <name>", "shell.help.mkdir"),
+    ("touch <name>", "shell.help.touch"),
+    ("tree", "shell.help.tree"),
+    ("pwd", "shell.help.pwd"),
+    ("env", "shell.help.env"),
+    ("stat", "shell.help.stat"),
+    ("uname", "shell.help.uname"),
+    ("whoami", "shell.help.whoami"),
+    ("history", "shell.help.history"),
+    ("echo <...>", "shell.help.echo"),
+    ("[ a = b ]", "shell.help.test"),
+    ("crwnsh run <f>", "shell.help.crwnsh"),
+];
+
 pub struct TritShell {
     pub user: String,
     pub hostname: String,
@@ -488,6 +637,14 @@ pub struct TritShell {
     pub aliases: HashMap<String, String>,
     pub exit_trit: i8,
     pub output: Vec<String>,
+    /// 셸 변수 (NAME=값) — env와 달리 내보내지 않는다
+    pub vars: HashMap<String, String>,
+    /// 스크립트에서 정의한 함수
+    pub functions: HashMap<String, Vec<ScriptStmt>>,
+    /// 위치 인자 $1..$9
+    pub args: Vec<String>,
+    /// 스크립트 · 함수 중첩 깊이
+    depth: usize,
 }
 
 impl TritShell {
@@ -513,6 +670,10 @@ impl TritShell {
             env, aliases,
             exit_trit: 1,
             output: Vec::new(),
+            vars: HashMap::new(),
+            functions: HashMap::new(),
+            args: Vec::new(),
+            depth: 0,
         }
     }
 
@@ -524,8 +685,96 @@ impl TritShell {
     pub fn execute(&mut self, cmd: &str, pm: &mut ProcessManager, fs: &mut TritFS) -> Vec<String> {
         self.history.push(cmd.into());
         self.output.clear();
-        let parts: Vec<&str> = cmd.trim().split_whitespace().collect();
-        if parts.is_empty() { return Vec::new(); }
+        let words = self.words(cmd);
+        if words.is_empty() { return Vec::new(); }
+        self.dispatch(&words, pm, fs);
+        self.output.clone()
+    }
+
+    /// 따옴표를 벗기고 $변수를 펼쳐 낱말로 — '...' 안은 그대로, "..." 안은 펼친다
+    pub fn words(&self, line: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut cur = String::new();
+        let mut started = false;
+        let mut quote: Option<char> = None;
+        let mut chars = line.trim().chars().peekable();
+        while let Some(c) = chars.next() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (None, '\'' | '"') => {
+                    quote = Some(c);
+                    started = true;
+                }
+                (None, c) if c.is_whitespace() => {
+                    if started {
+                        words.push(std::mem::take(&mut cur));
+                        started = false;
+                    }
+                }
+                (None, '$') => {
+                    // 따옴표 밖에서 펼친 값은 공백으로 다시 나눈다 ($@ → 인자 여럿)
+                    let value = self.expand_var(&mut chars);
+                    let mut pieces = value.split_whitespace().peekable();
+                    if value.starts_with(char::is_whitespace) && started {
+                        words.push(std::mem::take(&mut cur));
+                        started = false;
+                    }
+                    while let Some(piece) = pieces.next() {
+                        cur.push_str(piece);
+                        started = true;
+                        if pieces.peek().is_some() {
+                            words.push(std::mem::take(&mut cur));
+                        }
+                    }
+                    if value.ends_with(char::is_whitespace) && started {
+                        words.push(std::mem::take(&mut cur));
+                        started = false;
+                    }
+                }
+                (Some('"'), '$') => cur.push_str(&self.expand_var(&mut chars)),
+                (_, c) => {
+                    cur.push(c);
+                    started = true;
+                }
+            }
+        }
+        if started {
+            words.push(cur);
+        }
+        words
+    }
+
+    /// $ 뒤 — ? 종료 Trit · # 인자 수 · @ 모든 인자 · 1..9 · NAME · {NAME}
+    fn expand_var(&self, chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+        let name: String = match chars.peek().copied() {
+            Some('?') => { chars.next(); return trit_label(self.exit_trit).into(); }
+            Some('#') => { chars.next(); return self.args.len().to_string(); }
+            Some('@') => { chars.next(); return self.args.join(" "); }
+            Some(d @ '1'..='9') => {
+                chars.next();
+                return self.args.get(d as usize - '1' as usize).cloned().unwrap_or_default();
+            }
+            Some('{') => {
+                chars.next();
+                chars.by_ref().take_while(|&c| c != '}').collect()
+            }
+            Some(c) if c.is_alphanumeric() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') { break; }
+                    name.push(c);
+                    chars.next();
+                }
+                name
+            }
+            _ => return "$".into(),
+        };
+        self.vars.get(&name).or_else(|| self.env.get(&name)).cloned().unwrap_or_default()
+    }
+
+    /// 펼친 낱말 하나를 실행 — 결과는 self.output에 덧붙고 exit_trit에 남는다
+    fn dispatch(&mut self, words: &[String], pm: &mut ProcessManager, fs: &mut TritFS) {
+        let parts: Vec<&str> = words.iter().map(String::as_str).collect();
 
         let resolved = self.aliases.get(parts[0]).cloned();
         let actual_cmd = resolved.as_deref().unwrap_or(parts[0]);
@@ -554,6 +803,30 @@ impl TritShell {
                 self.output.push(format!("  {}", result));
                 self.exit_trit = result.trit;
             }
+            "wait" => {
+                let pid: u32 = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(1);
+                let result = pm.wait(pid);
+                self.output.push(format!("  {}", result));
+                self.exit_trit = result.trit;
+            }
+            "top" => {
+                // 틱을 돌려 표본을 모은 뒤 CPU 사용률 순으로
+                let ticks: u64 = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(10);
+                let reaped: Vec<u32> = (0..ticks).flat_map(|_| pm.tick()).collect();
+                self.output.push(format!("  top — 틱 {} · CPU {:.1}% ({}코어) · 거둠 {:?}",
+                    pm.ticks, pm.cpu_total, pm.cores, reaped));
+                self.output.push(format!("  {}", pm.summary()));
+                self.output.push("  PID  STATE     PRI    %CPU   TIME  NAME".into());
+                self.output.push("  ---  -----     ---    ----   ----  ----".into());
+                let mut procs = pm.ps();
+                procs.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage).then(a.pid.cmp(&b.pid)));
+                for proc in procs {
+                    let trit = match proc.trit_state { 1 => "P", -1 => "T", _ => "O" };
+                    self.output.push(format!("  [{}] {:>3}  {:<10} {:<6} {:>5.1} {:>6}  {}",
+                        trit, proc.pid, proc.state, proc.priority, proc.cpu_usage, proc.cpu_ticks, proc.name));
+                }
+                self.exit_trit = 1;
+            }
             "ls" => {
                 let entries = fs.ls(fs.cwd);
                 for inode in entries {
@@ -579,7 +852,7 @@ impl TritShell {
                     fs.cwd = id;
                     self.exit_trit = 1;
                 } else {
-                    self.output.push(format!("  [T] cd: '{}' 없음", target));
+                    self.output.push(format!("  [T] {}", tr!("shell.cd_missing", target)));
                     self.exit_trit = -1;
                 }
             }
@@ -592,7 +865,7 @@ impl TritShell {
                     }
                     self.exit_trit = result.trit;
                 } else {
-                    self.output.push(format!("  [T] cat: '{}' 없음", name));
+                    self.output.push(format!("  [T] {}", tr!("shell.cat_missing", name)));
                     self.exit_trit = -1;
                 }
             }
@@ -671,30 +944,409 @@ impl TritShell {
                 self.exit_trit = 1;
             }
             "help" => {
-                self.output.push("  ━━━ TritShell 명령어 ━━━".into());
-                self.output.push("  ps            프로세스 목록".into());
-                self.output.push("  spawn <n> <m> 프로세스 생성 (이름, 메모리KB)".into());
-                self.output.push("  kill <pid>    프로세스 종료".into());
-                self.output.push("  ls            파일 목록".into());
-                self.output.push("  cd <dir>      디렉토리 이동".into());
-                self.output.push("  cat <file>    파일 읽기".into());
-                self.output.push("  mkdir <name>  디렉토리 생성".into());
-                self.output.push("  touch <name>  빈 파일 생성".into());
-                self.output.push("  tree          디렉토리 트리".into());
-                self.output.push("  pwd           현재 경로".into());
-                self.output.push("  env           환경 변수".into());
-                self.output.push("  stat          시스템 상태".into());
-                self.output.push("  uname         OS 정보".into());
-                self.output.push("  whoami        현재 사용자".into());
-                self.output.push("  history       명령어 이력".into());
+                self.output.push(format!("  {}", crate::i18n::t("shell.help.title")));
+                for (usage, key) in SHELL_HELP {
+                    self.output.push(format!("  {:<13} {}", usage, crate::i18n::t(key)));
+                }
+                self.exit_trit = 1;
+            }
+            "echo" => {
+                self.output.push(format!("  {}", parts[1..].join(" ")));
                 self.exit_trit = 1;
             }
+            "true" => self.exit_trit = 1,
+            "unknown" => self.exit_trit = 0,
+            "false" => self.exit_trit = -1,
+            "[" | "test" => {
+                let operands = match parts.split_last() {
+                    Some((&"]", rest)) if parts[0] == "[" => &rest[1..],
+                    _ if parts[0] == "test" => &parts[1..],
+                    _ => {
+                        self.output.push(format!("  [T] {}", tr!("shell.test_syntax", parts.join(" "))));
+                        self.exit_trit = -1;
+                        return;
+                    }
+                };
+                self.exit_trit = match test_expr(operands, fs) {
+                    Some(true) => 1,
+                    Some(false) => -1,
+                    None => {
+                        self.output.push(format!("  [T] {}", tr!("shell.test_syntax", parts.join(" "))));
+                        -1
+                    }
+                };
+            }
+            "crwnsh" | "source" | "." => {
+                let rest = if parts.get(1) == Some(&"run") { &parts[2..] } else { &parts[1..] };
+                let Some(path) = rest.first() else {
+                    self.output.push(format!("  [T] {}", tr!("shell.script_missing", "")));
+                    self.exit_trit = -1;
+                    return;
+                };
+                let source = fs.resolve_from(fs.cwd, path).map(|id| fs.cat(id)).and_then(|r| r.data);
+                match source {
+                    Some(source) => {
+                        let args = rest[1..].iter().map(|s| s.to_string()).collect();
+                        self.run_script(&source, args, pm, fs);
+                    }
+                    None => {
+                        self.output.push(format!("  [T] {}", tr!("shell.script_missing", path)));
+                        self.exit_trit = -1;
+                    }
+                }
+            }
             _ => {
-                self.output.push(format!("  [T] crwnsh: '{}' 명령어를 찾을 수 없습니다", actual_cmd));
+                self.output.push(format!("  [T] {}", tr!("shell.not_found", actual_cmd)));
                 self.exit_trit = -1;
             }
         }
-        self.output.clone()
+    }
+}
+
+// ═══════════════════════════════════════
+// 4. crwnsh 스크립트 (.crwnsh)
+// ═══════════════════════════════════════
+//
+//   # 주석 — 문장은 줄 또는 ; 로 나눈다
+//   NAME=값                         셸 변수 ($NAME · ${NAME})
+//   $? 직전 종료 Trit (P/O/T) · $1..$9 · $# · $@ 위치 인자
+//   if [ $? = P ]; then ... elif 조건; then ... pending ... else ... fi
+//       조건 명령의 종료 Trit: P → then · O → pending (없으면 다음 elif/else) · T → 다음
+//   for x in a b c; do ... done     while 조건; do ... done
+//   이름() { ... }                   함수 — 이름 인자... 로 호출, return [P|O|T]
+//   exit [P|O|T]
+//
+//   crwnsh run /crwn/apps/deploy.crwnsh 인자...
+
+/// 루프 하나의 최대 반복 — 넘으면 O(보류)로 멈춘다
+pub const SCRIPT_MAX_ITERATIONS: usize = 10_000;
+/// 스크립트 · 함수 호출 중첩 한도
+pub const SCRIPT_MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptStmt {
+    /// 명령 · 대입 한 문장 (펼치기 전 원문)
+    Command(String),
+    If { branches: Vec<(String, Vec<ScriptStmt>)>, pending: Option<Vec<ScriptStmt>>, otherwise: Option<Vec<ScriptStmt>> },
+    For { var: String, items: String, body: Vec<ScriptStmt> },
+    While { cond: String, body: Vec<ScriptStmt> },
+    Function { name: String, body: Vec<ScriptStmt> },
+}
+
+/// 블록 다음 흐름
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow { Next, Return, Exit }
+
+fn trit_label(trit: i8) -> &'static str {
+    match trit { 1 => "P", -1 => "T", _ => "O" }
+}
+
+/// "P"/"O"/"T" (또는 1/0/-1) → Trit
+fn parse_trit(s: &str) -> Option<i8> {
+    match s {
+        "P" | "1" | "+1" => Some(1),
+        "O" | "0" => Some(0),
+        "T" | "-1" => Some(-1),
+        _ => None,
+    }
+}
+
+/// [ ... ] 식 — 형식이 틀리면 None
+fn test_expr(operands: &[&str], fs: &TritFS) -> Option<bool> {
+    let num = |s: &str| s.parse::<i64>().ok();
+    Some(match operands {
+        [] => false,
+        ["!", rest @ ..] => !test_expr(rest, fs)?,
+        ["-z", s] => s.is_empty(),
+        ["-n", s] => !s.is_empty(),
+        ["-e", path] => fs.resolve_from(fs.cwd, path).is_some(),
+        [s] => !s.is_empty(),
+        [a, "=", b] | [a, "==", b] => a == b,
+        [a, "!=", b] => a != b,
+        [a, op, b] => {
+            let (a, b) = (num(a)?, num(b)?);
+            match *op {
+                "-eq" => a == b,
+                "-ne" => a != b,
+                "-lt" => a < b,
+                "-le" => a <= b,
+                "-gt" => a > b,
+                "-ge" => a >= b,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// 문장 나누기 — 따옴표 밖의 ; 와 줄바꿈, # 주석 제거.
+/// then/do/else/pending 뒤에 이어 쓴 명령과 "이름() { 명령"은 따로 뗀다
+fn script_statements(source: &str) -> Vec<(usize, String)> {
+    let mut out = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let mut quote = None;
+        let mut cur = String::new();
+        let mut pieces = Vec::new();
+        for c in line.chars() {
+            match (quote, c) {
+                (None, '#') if cur.trim().is_empty() || cur.ends_with(char::is_whitespace) => break,
+                (None, ';') => pieces.push(std::mem::take(&mut cur)),
+                (None, '\'' | '"') => { quote = Some(c); cur.push(c); }
+                (Some(q), c) if c == q => { quote = None; cur.push(c); }
+                _ => cur.push(c),
+            }
+        }
+        pieces.push(cur);
+        for piece in pieces {
+            let mut piece = piece.trim().to_string();
+            while !piece.is_empty() {
+                let (head, rest) = split_keyword(&piece);
+                out.push((i + 1, head.to_string()));
+                piece = rest.trim().to_string();
+            }
+        }
+    }
+    out
+}
+
+fn split_keyword(stmt: &str) -> (&str, &str) {
+    for kw in ["then", "do", "else", "pending"] {
+        if let Some(rest) = stmt.strip_prefix(kw) {
+            if rest.starts_with(char::is_whitespace) {
+                return (kw, rest);
+            }
+        }
+    }
+    if let Some(at) = stmt.find("() {") {
+        return stmt.split_at(at + 4);
+    }
+    (stmt, "")
+}
+
+struct ScriptParser {
+    stmts: Vec<(usize, String)>,
+    pos: usize,
+}
+
+impl ScriptParser {
+    fn next(&mut self) -> Option<(usize, String)> {
+        let s = self.stmts.get(self.pos).cloned();
+        self.pos += 1;
+        s
+    }
+
+    fn last_line(&self) -> usize {
+        self.stmts.last().map_or(0, |s| s.0)
+    }
+
+    fn expect(&mut self, kw: &str) -> Result<(), String> {
+        match self.next() {
+            Some((_, s)) if s == kw => Ok(()),
+            Some((line, _)) => Err(tr!("shell.script_syntax", line, kw)),
+            None => Err(tr!("shell.script_syntax", self.last_line(), kw)),
+        }
+    }
+
+    /// ends 중 하나(첫 낱말)를 만날 때까지 — 만난 문장도 돌려준다
+    fn block(&mut self, ends: &[&str]) -> Result<(Vec<ScriptStmt>, String), String> {
+        let mut body = Vec::new();
+        loop {
+            let Some((line, stmt)) = self.next() else {
+                return match ends.last() {
+                    Some(end) => Err(tr!("shell.script_syntax", self.last_line(), end)),
+                    None => Ok((body, String::new())),
+                };
+            };
+            let first = stmt.split_whitespace().next().unwrap_or("");
+            if ends.contains(&first) {
+                return Ok((body, stmt));
+            }
+            if ["then", "do", "fi", "done", "elif", "else", "pending", "}"].contains(&first) {
+                return Err(tr!("shell.script_unexpected", line, first));
+            }
+            body.push(match first {
+                "if" => self.if_stmt(&stmt[2..])?,
+                "for" => {
+                    let mut it = stmt[3..].trim().splitn(3, ' ');
+                    let (var, kw_in, items) = (it.next().unwrap_or(""), it.next(), it.next().unwrap_or(""));
+                    if var.is_empty() || kw_in != Some("in") {
+                        return Err(tr!("shell.script_syntax", line, "for 이름 in ..."));
+                    }
+                    self.expect("do")?;
+                    let (body, _) = self.block(&["done"])?;
+                    ScriptStmt::For { var: var.into(), items: items.into(), body }
+                }
+                "while" => {
+                    self.expect("do")?;
+                    let (body, _) = self.block(&["done"])?;
+                    ScriptStmt::While { cond: stmt[5..].trim().into(), body }
+                }
+                _ if stmt.ends_with("() {") => {
+                    let (body, _) = self.block(&["}"])?;
+                    ScriptStmt::Function { name: stmt.trim_end_matches("() {").trim().into(), body }
+                }
+                _ => ScriptStmt::Command(stmt),
+            });
+        }
+    }
+
+    fn if_stmt(&mut self, cond: &str) -> Result<ScriptStmt, String> {
+        let mut branches = Vec::new();
+        let mut cond = cond.trim().to_string();
+        let (mut pending, mut otherwise) = (None, None);
+        self.expect("then")?;
+        loop {
+            let (body, end) = self.block(&["elif", "pending", "else", "fi"])?;
+            branches.push((std::mem::take(&mut cond), body));
+            match end.split_whitespace().next() {
+                Some("elif") => {
+                    cond = end[4..].trim().to_string();
+                    self.expect("then")?;
+                }
+                Some("pending") | Some("else") => {
+                    let mut end = end;
+                    if end == "pending" {
+                        let (body, next) = self.block(&["else", "fi"])?;
+                        pending = Some(body);
+                        end = next;
+                    }
+                    if end == "else" {
+                        otherwise = Some(self.block(&["fi"])?.0);
+                    }
+                    break;
+                }
+                _ => break,
+            }
+        }
+        Ok(ScriptStmt::If { branches, pending, otherwise })
+    }
+}
+
+/// .crwnsh 원문 → 문장 트리
+pub fn parse_script(source: &str) -> Result<Vec<ScriptStmt>, String> {
+    ScriptParser { stmts: script_statements(source), pos: 0 }.block(&[]).map(|(body, _)| body)
+}
+
+impl TritShell {
+    /// 스크립트 실행 — 위치 인자는 끝나면 되돌린다. 종료 Trit을 돌려준다
+    pub fn run_script(&mut self, source: &str, args: Vec<String>, pm: &mut ProcessManager, fs: &mut TritFS) -> i8 {
+        let body = match parse_script(source) {
+            Ok(body) => body,
+            Err(e) => {
+                self.output.push(format!("  [T] {}", e));
+                self.exit_trit = -1;
+                return -1;
+            }
+        };
+        if self.depth >= SCRIPT_MAX_DEPTH {
+            self.output.push(format!("  [T] {}", tr!("shell.depth_limit", SCRIPT_MAX_DEPTH)));
+            self.exit_trit = -1;
+            return -1;
+        }
+        let saved = std::mem::replace(&mut self.args, args);
+        self.depth += 1;
+        self.exec_block(&body, pm, fs);
+        self.depth -= 1;
+        self.args = saved;
+        self.exit_trit
+    }
+
+    fn exec_block(&mut self, body: &[ScriptStmt], pm: &mut ProcessManager, fs: &mut TritFS) -> Flow {
+        for stmt in body {
+            let flow = match stmt {
+                ScriptStmt::Command(text) => self.exec_command(text, pm, fs),
+                ScriptStmt::If { branches, pending, otherwise } => {
+                    let mut chosen = otherwise.as_ref();
+                    for (cond, body) in branches {
+                        let flow = self.exec_command(cond, pm, fs);
+                        if flow != Flow::Next { return flow; }
+                        match self.exit_trit {
+                            1 => { chosen = Some(body); break; }
+                            0 if pending.is_some() => { chosen = pending.as_ref(); break; }
+                            _ => {}
+                        }
+                    }
+                    match chosen {
+                        Some(body) => self.exec_block(body, pm, fs),
+                        None => Flow::Next,
+                    }
+                }
+                ScriptStmt::For { var, items, body } => {
+                    let mut flow = Flow::Next;
+                    for item in self.words(items).into_iter().take(SCRIPT_MAX_ITERATIONS) {
+                        self.vars.insert(var.clone(), item);
+                        flow = self.exec_block(body, pm, fs);
+                        if flow != Flow::Next { break; }
+                    }
+                    flow
+                }
+                ScriptStmt::While { cond, body } => self.exec_while(cond, body, pm, fs),
+                ScriptStmt::Function { name, body } => {
+                    self.functions.insert(name.clone(), body.clone());
+                    self.exit_trit = 1;
+                    Flow::Next
+                }
+            };
+            if flow != Flow::Next {
+                return flow;
+            }
+        }
+        Flow::Next
+    }
+
+    fn exec_while(&mut self, cond: &str, body: &[ScriptStmt], pm: &mut ProcessManager, fs: &mut TritFS) -> Flow {
+        for _ in 0..SCRIPT_MAX_ITERATIONS {
+            let flow = self.exec_command(cond, pm, fs);
+            if flow != Flow::Next { return flow; }
+            if self.exit_trit != 1 {
+                self.exit_trit = 1;
+                return Flow::Next;
+            }
+            let flow = self.exec_block(body, pm, fs);
+            if flow != Flow::Next { return flow; }
+        }
+        self.output.push(format!("  [O] {}", tr!("shell.loop_limit", SCRIPT_MAX_ITERATIONS)));
+        self.exit_trit = 0;
+        Flow::Next
+    }
+
+    /// 대입 · exit · return · 함수 호출 · 내장 명령
+    fn exec_command(&mut self, text: &str, pm: &mut ProcessManager, fs: &mut TritFS) -> Flow {
+        let words = self.words(text);
+        let Some(first) = words.first() else { return Flow::Next };
+        if let (1, Some((name, value))) = (words.len(), first.split_once('=')) {
+            if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                self.vars.insert(name.into(), value.into());
+                self.exit_trit = 1;
+                return Flow::Next;
+            }
+        }
+        match first.as_str() {
+            "exit" | "return" => {
+                if let Some(arg) = words.get(1) {
+                    self.exit_trit = parse_trit(arg).unwrap_or(-1);
+                }
+                if first == "exit" { Flow::Exit } else { Flow::Return }
+            }
+            name if self.functions.contains_key(name) => {
+                if self.depth >= SCRIPT_MAX_DEPTH {
+                    self.output.push(format!("  [T] {}", tr!("shell.depth_limit", SCRIPT_MAX_DEPTH)));
+                    self.exit_trit = -1;
+                    return Flow::Exit;
+                }
+                let body = self.functions[name].clone();
+                let saved = std::mem::replace(&mut self.args, words[1..].to_vec());
+                self.depth += 1;
+                let flow = self.exec_block(&body, pm, fs);
+                self.depth -= 1;
+                self.args = saved;
+                if flow == Flow::Exit { Flow::Exit } else { Flow::Next }
+            }
+            _ => {
+                self.dispatch(&words, pm, fs);
+                Flow::Next
+            }
+        }
     }
 }
 
@@ -730,6 +1382,18 @@ impl CrownyOS {
 
 // ═══ 데모 ═══
 
+const DEMO_SCRIPT: &str = "# 인자로 받은 서비스를 띄운다
+TARGET=prod
+start() {
+  spawn $1 1024
+  echo \"$1 → $TARGET\"
+  return P
+}
+for svc in $@; do start $svc; done
+[ $# -ge 1 ]
+if [ $? = P ]; then echo \"배포 $# 개 완료\"; else echo '인자 없음'; exit T; fi
+";
+
 pub fn demo_os() {
     println!("╔═══════════════════════════════════════════════╗");
     println!("║  CrownyOS v0.9.0 — 3진 운영체제               ║");
@@ -771,6 +1435,8 @@ pub fn demo_os() {
         "spawn api-worker 1024",
         "ps",
         "kill 9",
+        "wait 1",
+        "top 12",
         "ps",
         "cd crwn",
         "ls",
@@ -787,6 +1453,17 @@ pub fn demo_os() {
         println!();
     }
 
+    // 부팅 스크립트
+    println!("━━━ crwnsh 스크립트 ━━━");
+    let apps = os.fs.resolve_path("/crwn/apps").unwrap_or_else(|| os.fs.mkdir_at(os.fs.resolve_path("/crwn").unwrap_or(0), "apps", "root"));
+    os.fs.create_file_at(apps, "deploy.crwnsh", "root", DEMO_SCRIPT);
+    for line in DEMO_SCRIPT.lines() { println!("  │ {}", line); }
+    let cmd = "crwnsh run /crwn/apps/deploy.crwnsh web-server api-worker";
+    println!("{}{}", os.shell.prompt(), cmd);
+    let output = os.shell.execute(cmd, &mut os.pm, &mut os.fs);
+    for line in &output { println!("{}", line); }
+    println!();
+
     println!("━━━ OS 최종 상태 ━━━");
     println!("  {}", os.pm.summary());
     println!("  {}", os.fs.stat());
@@ -930,4 +1607,101 @@ mod tests {
         assert!(os.booted);
         assert!(os.pm.running_count() >= 6);
     }
+
+    fn run(os: &mut CrownyOS, cmd: &str) -> Vec<String> {
+        os.shell.execute(cmd, &mut os.pm, &mut os.fs)
+    }
+
+    #[test]
+    fn test_shell_vars_and_test() {
+        let mut os = CrownyOS::boot();
+        os.shell.args = vec!["a".into(), "b c".into()];
+        assert_eq!(os.shell.words("x$@ \"$@\""), vec!["xa", "b", "c", "a b c"]);
+        let out = run(&mut os, "echo '$HOME' \"$HOME/x\" $NOPE.");
+        assert_eq!(out, vec![format!("  $HOME {}/x .", os.shell.env["HOME"])]);
+        run(&mut os, "[ 3 -lt 5 ]");
+        assert_eq!(os.shell.exit_trit, 1);
+        run(&mut os, "[ a = b ]");
+        assert_eq!(os.shell.exit_trit, -1);
+        assert_eq!(run(&mut os, "echo $?"), vec!["  T"]);
+        run(&mut os, "[ 3 -lt ]");
+        assert_eq!(os.shell.exit_trit, -1);
+    }
+
+    #[test]
+    fn test_script_control_flow() {
+        let mut os = CrownyOS::boot();
+        let script = "N=0\n\
+            greet() { echo \"hi $1\"; return O; }\n\
+            for x in a b; do greet $x; done\n\
+            if greet c; then echo p; pending echo o; else echo t; fi\n\
+            if [ $N = 1 ]; then echo one; elif [ -e /etc ]; then echo etc; fi\n\
+            while [ $N = 0 ]; do N=1; done; echo \"N=$N\"\n\
+            exit T\n\
+            echo unreachable\n";
+        let code = os.shell.run_script(script, vec![], &mut os.pm, &mut os.fs);
+        assert_eq!(code, -1);
+        assert_eq!(os.shell.output, ["hi a", "hi b", "hi c", "o", "etc", "N=1"].map(|s| format!("  {}", s)));
+    }
+
+    #[test]
+    fn test_script_from_tritfs_and_errors() {
+        let mut os = CrownyOS::boot();
+        let crwn = os.fs.resolve_path("/crwn").unwrap();
+        os.fs.create_file_at(crwn, "boot.crwnsh", "root", "echo \"$# $2\"");
+        run(&mut os, "cd crwn");
+        assert_eq!(run(&mut os, "crwnsh run boot.crwnsh a b"), vec!["  2 b"]);
+        assert!(run(&mut os, "crwnsh run missing.crwnsh")[0].contains("missing.crwnsh"));
+        assert!(parse_script("if true; then echo x").unwrap_err().contains("fi"));
+        assert!(parse_script("done").is_err());
+        os.shell.run_script("while true; do N=1; done", vec![], &mut os.pm, &mut os.fs);
+        assert_eq!(os.shell.exit_trit, 0);
+    }
+
+    #[test]
+    fn test_tick_priority_and_usage() {
+        let mut pm = ProcessManager { cores: 1, ..ProcessManager::new(128) };
+        pm.spawn("busy", "user", ProcessPriority::High, 64);
+        pm.spawn("bg", "user", ProcessPriority::Idle, 64);
+        for _ in 0..30 { pm.tick(); }
+        let ticks = |name: &str| pm.find(name).unwrap().cpu_ticks;
+        assert_eq!(pm.ticks, 30);
+        assert_eq!(pm.running_count(), 1);
+        assert_eq!(ticks("bg"), 0); // Idle은 남는 코어가 없으면 못 돈다
+        // 가중치 4(High) 셋이 공평하게 나눈다
+        assert_eq!(ticks("busy") + ticks("crowny-kernel") + ticks("trit-init"), 30);
+        assert!(ticks("busy") >= 9);
+        assert!(pm.cpu_total > 99.0);
+        pm.sleep_proc(pm.find("busy").unwrap().pid);
+        for _ in 0..10 { pm.tick(); }
+        let busy = pm.find("busy").unwrap();
+        assert!(busy.cpu_usage < 10.0);
+        assert_eq!(busy.state, ProcessState::Sleeping);
+    }
+
+    #[test]
+    fn test_wait_reaps_zombies() {
+        let mut pm = ProcessManager::new(128);
+        pm.spawn("child", "user", ProcessPriority::Normal, 64);
+        assert_eq!(pm.wait(2).trit, -1); // 자식 없음
+        pm.kill(2);
+        assert!(pm.tick().is_empty()); // 부모가 기다리지 않으면 좀비로 남는다
+        assert_eq!(pm.wait(1).trit, 0);
+        assert_eq!(pm.processes[1].state, ProcessState::Sleeping);
+        assert_eq!(pm.tick(), vec![2]);
+        assert!(pm.processes.iter().all(|p| p.pid != 2));
+        assert!(pm.processes[1].children.is_empty());
+        assert!(pm.processes[1].is_runnable());
+    }
+
+    #[test]
+    fn test_shell_top() {
+        let mut os = CrownyOS::boot();
+        let out = run(&mut os, "top 5");
+        assert_eq!(os.pm.ticks, 5);
+        assert!(out[0].contains("틱 5"));
+        assert_eq!(out.len(), 4 + os.pm.ps().len());
+        let usage: Vec<f64> = os.pm.ps().iter().map(|p| p.cpu_usage).collect();
+        assert!(usage.iter().any(|u| *u > 0.0));
+    }
 }
@@ -0,0 +1,481 @@
+// ═══════════════════════════════════════════════════════════════
+// CLI 출력 모드 — 텍스트 / JSON / 조용히
+// ═══════════════════════════════════════════════════════════════
+//
+// 전역 플래그 (cli 파서가 해석):
+//   --json   → 구조화된 JSON 한 줄 (run/compile/trit/decode/test/store/chain/config/consensus/industry/dex)
+//              run/compile/test/trit/decode/info와 명령 실패는 공통 봉투:
+//              {"command","state","elapsed_ms","errors":[..],"data":{..}}
+//   --quiet  → 배너·데모 아트 생략 (-q)
+//
+// 종료 코드는 마지막 Trit 상태에서 결정:
+//   P(+1) → 0   T(-1) → 1   O(0) → 2
+//
+// 결과 타입(TritResult · ConsensusResult · IndustryDecision · SwapResult)의
+// to_json()은 "schema"/"schema_version"으로 시작하는 고정 필드 순서를 따른다.
+// 필드 제거·의미 변경 시 SCHEMA_VERSION을 올린다 (추가는 호환).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// 출력 모드
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputMode {
+    pub json: bool,
+    pub quiet: bool,
+}
+
+impl OutputMode {
+    /// 프로세스 전역으로 적용
+    pub fn install(self) {
+        STARTED.get_or_init(Instant::now);
+        JSON.store(self.json, Ordering::Relaxed);
+        QUIET.store(self.quiet, Ordering::Relaxed);
+    }
+}
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// 사람이 읽는 텍스트를 출력할지 (JSON 모드에서는 stdout을 JSON 전용으로 둔다)
+pub fn is_text() -> bool {
+    !is_json()
+}
+
+/// 출력 모드 적용(프로세스 시작) 이후 경과 시간
+pub fn elapsed_ms() -> u64 {
+    STARTED.get_or_init(Instant::now).elapsed().as_millis() as u64
+}
+
+/// 배너/데모 아트 — --quiet, --json 모두 생략
+pub fn banner(art: &str) {
+    if !is_quiet() && !is_json() {
+        println!("{}", art);
+    }
+}
+
+/// 텍스트 모드에서만 출력하는 println
+macro_rules! say {
+    () => {
+        if $crate::output::is_text() { println!(); }
+    };
+    ($($arg:tt)*) => {
+        if $crate::output::is_text() { println!($($arg)*); }
+    };
+}
+pub(crate) use say;
+
+/// Trit 상태 → 종료 코드
+pub fn exit_code(trit: i8) -> i32 {
+    match trit {
+        1 => 0,
+        0 => 2,
+        _ => 1,
+    }
+}
+
+pub fn trit_symbol(trit: i8) -> &'static str {
+    match trit {
+        1 => "P",
+        0 => "O",
+        _ => "T",
+    }
+}
+
+// ─────────────────────────────────────────────
+// 최소 JSON 작성기 (외부 의존성 없음)
+// ─────────────────────────────────────────────
+
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 결과 JSON 스키마 버전
+pub const SCHEMA_VERSION: i64 = 2;
+
+/// JSON 배열 (이미 직렬화된 원소)
+pub fn array(items: Vec<String>) -> String {
+    format!("[{}]", items.join(","))
+}
+
+/// JSON 객체 빌더 — 키 순서 유지
+#[derive(Debug, Clone, Default)]
+pub struct JsonObject {
+    fields: Vec<(String, String)>,
+}
+
+impl JsonObject {
+    pub fn new() -> Self {
+        Self { fields: Vec::new() }
+    }
+
+    /// 스키마 머리 — 결과 타입 직렬화는 항상 이것으로 시작
+    pub fn schema(name: &str) -> Self {
+        Self::new().str("schema", name).int("schema_version", SCHEMA_VERSION)
+    }
+
+    /// 이미 직렬화된 JSON 값
+    pub fn raw(mut self, key: &str, json: String) -> Self {
+        self.fields.push((key.into(), json));
+        self
+    }
+
+    pub fn str(mut self, key: &str, val: &str) -> Self {
+        self.fields.push((key.into(), escape(val)));
+        self
+    }
+
+    pub fn int(mut self, key: &str, val: i64) -> Self {
+        self.fields.push((key.into(), val.to_string()));
+        self
+    }
+
+    pub fn float(mut self, key: &str, val: f64) -> Self {
+        let v = if val.is_finite() { val.to_string() } else { "null".into() };
+        self.fields.push((key.into(), v));
+        self
+    }
+
+    pub fn bool(mut self, key: &str, val: bool) -> Self {
+        self.fields.push((key.into(), val.to_string()));
+        self
+    }
+
+    /// Trit 상태 — "P"/"O"/"T"
+    pub fn trit(self, key: &str, trit: i8) -> Self {
+        self.str(key, trit_symbol(trit))
+    }
+
+    pub fn strs(mut self, key: &str, vals: &[String]) -> Self {
+        let items: Vec<String> = vals.iter().map(|v| escape(v)).collect();
+        self.fields.push((key.into(), format!("[{}]", items.join(","))));
+        self
+    }
+
+    pub fn objects(mut self, key: &str, vals: Vec<JsonObject>) -> Self {
+        let items: Vec<String> = vals.into_iter().map(|v| v.build()).collect();
+        self.fields.push((key.into(), array(items)));
+        self
+    }
+
+    pub fn object(mut self, key: &str, val: JsonObject) -> Self {
+        self.fields.push((key.into(), val.build()));
+        self
+    }
+
+    pub fn build(self) -> String {
+        let body: Vec<String> = self.fields.into_iter()
+            .map(|(k, v)| format!("{}:{}", escape(&k), v))
+            .collect();
+        format!("{{{}}}", body.join(","))
+    }
+
+    /// 명령 결과 봉투 — 명령마다 다른 필드는 data 아래로, 오류는 항상 배열
+    /// (v2: 최상위 필드를 data로 옮겼다)
+    pub fn envelope(command: &str, state: i8, errors: &[String], data: JsonObject) -> Self {
+        Self::schema("crowny.envelope")
+            .str("command", command)
+            .trit("state", state)
+            .int("elapsed_ms", elapsed_ms() as i64)
+            .strs("errors", errors)
+            .object("data", data)
+    }
+
+    /// stdout으로 한 줄 출력
+    pub fn emit(self) {
+        println!("{}", self.build());
+    }
+}
+
+// ─────────────────────────────────────────────
+// 최소 JSON 읽기 (JSON-RPC 요청 등)
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Array(Vec<JsonValue>),
+    /// 키 순서 유지
+    Object(Vec<(String, JsonValue)>),
+}
+
+/// 중첩 깊이 한도 (악의적 입력 방지)
+const MAX_JSON_DEPTH: usize = 64;
+
+impl JsonValue {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut p = JsonParser { s: text.as_bytes(), pos: 0 };
+        let v = p.value(0)?;
+        p.ws();
+        if p.pos != p.s.len() {
+            return Err(format!("JSON 뒤에 남은 입력 (위치 {})", p.pos));
+        }
+        Ok(v)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self { JsonValue::Str(s) => Some(s), _ => None }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self { JsonValue::Num(n) if n.fract() == 0.0 => Some(*n as i64), _ => None }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self { JsonValue::Num(n) => Some(*n), _ => None }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self { JsonValue::Array(a) => Some(a), _ => None }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    /// 다시 직렬화 (JSON-RPC id 되돌려주기 등)
+    pub fn to_json(&self) -> String {
+        match self {
+            JsonValue::Null => "null".into(),
+            JsonValue::Bool(b) => b.to_string(),
+            JsonValue::Num(n) if n.is_finite() => n.to_string(),
+            JsonValue::Num(_) => "null".into(),
+            JsonValue::Str(s) => escape(s),
+            JsonValue::Array(a) => array(a.iter().map(JsonValue::to_json).collect()),
+            JsonValue::Object(fields) => {
+                let body: Vec<String> = fields.iter().map(|(k, v)| format!("{}:{}", escape(k), v.to_json())).collect();
+                format!("{{{}}}", body.join(","))
+            }
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn ws(&mut self) {
+        while self.s.get(self.pos).is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn err(&self, what: &str) -> String {
+        format!("JSON {} (위치 {})", what, self.pos)
+    }
+
+    fn eat(&mut self, lit: &str) -> bool {
+        if self.s[self.pos..].starts_with(lit.as_bytes()) {
+            self.pos += lit.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, String> {
+        if depth > MAX_JSON_DEPTH {
+            return Err(self.err("중첩이 너무 깊음"));
+        }
+        self.ws();
+        match self.s.get(self.pos) {
+            None => Err(self.err("입력이 끝남")),
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.ws();
+                if self.eat("}") {
+                    return Ok(JsonValue::Object(fields));
+                }
+                loop {
+                    self.ws();
+                    let key = self.string()?;
+                    self.ws();
+                    if !self.eat(":") {
+                        return Err(self.err("':' 필요"));
+                    }
+                    fields.push((key, self.value(depth + 1)?));
+                    self.ws();
+                    if self.eat("}") {
+                        return Ok(JsonValue::Object(fields));
+                    }
+                    if !self.eat(",") {
+                        return Err(self.err("',' 또는 '}' 필요"));
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.ws();
+                if self.eat("]") {
+                    return Ok(JsonValue::Array(items));
+                }
+                loop {
+                    items.push(self.value(depth + 1)?);
+                    self.ws();
+                    if self.eat("]") {
+                        return Ok(JsonValue::Array(items));
+                    }
+                    if !self.eat(",") {
+                        return Err(self.err("',' 또는 ']' 필요"));
+                    }
+                }
+            }
+            Some(b'"') => self.string().map(JsonValue::Str),
+            Some(b't') if self.eat("true") => Ok(JsonValue::Bool(true)),
+            Some(b'f') if self.eat("false") => Ok(JsonValue::Bool(false)),
+            Some(b'n') if self.eat("null") => Ok(JsonValue::Null),
+            Some(b) if *b == b'-' || b.is_ascii_digit() => {
+                let start = self.pos;
+                while self.s.get(self.pos).is_some_and(|b| b"+-.eE".contains(b) || b.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+                let raw = std::str::from_utf8(&self.s[start..self.pos]).unwrap_or_default();
+                raw.parse().map(JsonValue::Num).map_err(|_| self.err("잘못된 숫자"))
+            }
+            Some(_) => Err(self.err("예상하지 못한 문자")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if !self.eat("\"") {
+            return Err(self.err("문자열 필요"));
+        }
+        let mut out = Vec::new();
+        loop {
+            let Some(&b) = self.s.get(self.pos) else { return Err(self.err("닫히지 않은 문자열")) };
+            self.pos += 1;
+            match b {
+                b'"' => return String::from_utf8(out).map_err(|_| self.err("잘못된 UTF-8")),
+                b'\\' => {
+                    let Some(&e) = self.s.get(self.pos) else { return Err(self.err("닫히지 않은 문자열")) };
+                    self.pos += 1;
+                    let c = match e {
+                        b'"' => '"', b'\\' => '\\', b'/' => '/',
+                        b'b' => '\u{8}', b'f' => '\u{c}', b'n' => '\n', b'r' => '\r', b't' => '\t',
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            // 서로게이트 쌍
+                            if (0xd800..0xdc00).contains(&code) && self.eat("\\u") {
+                                let low = self.hex4()?;
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            char::from_u32(code).ok_or_else(|| self.err("잘못된 \\u 이스케이프"))?
+                        }
+                        _ => return Err(self.err("잘못된 이스케이프")),
+                    };
+                    let mut buf = [0u8; 4];
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                b => out.push(b),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let raw = self.s.get(self.pos..self.pos + 4).and_then(|h| std::str::from_utf8(h).ok());
+        let code = raw.and_then(|h| u32::from_str_radix(h, 16).ok()).ok_or_else(|| self.err("잘못된 \\u 이스케이프"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_from_trit() {
+        assert_eq!(exit_code(1), 0);
+        assert_eq!(exit_code(-1), 1);
+        assert_eq!(exit_code(0), 2);
+    }
+
+    #[test]
+    fn test_json_object_escaping() {
+        let json = JsonObject::new()
+            .str("이름", "a\"b\\c\n")
+            .int("n", -3)
+            .bool("ok", true)
+            .trit("state", 0)
+            .strs("out", &["x".into(), "y".into()])
+            .object("inner", JsonObject::new().float("f", 0.5))
+            .build();
+        assert_eq!(json,
+            r#"{"이름":"a\"b\\c\n","n":-3,"ok":true,"state":"O","out":["x","y"],"inner":{"f":0.5}}"#);
+        assert_eq!(JsonObject::new().float("x", f64::NAN).build(), r#"{"x":null}"#);
+        assert_eq!(JsonObject::schema("crowny.test").raw("a", array(vec!["1".into(), "null".into()])).build(),
+            r#"{"schema":"crowny.test","schema_version":2,"a":[1,null]}"#);
+    }
+
+    #[test]
+    fn test_json_value_parse_roundtrip() {
+        let v = JsonValue::parse(r#" {"id": 7, "method":"eth_getLogs", "params":[{"topics":[null,["a\"b","\uD55C\ud83d\ude00"]]}], "x": -1.5e2, "ok": true} "#).unwrap();
+        assert_eq!(v.get("id").and_then(JsonValue::as_i64), Some(7));
+        assert_eq!(v.get("x"), Some(&JsonValue::Num(-150.0)));
+        let topics = v.get("params").and_then(|p| p.as_array()).and_then(|p| p[0].get("topics")).unwrap();
+        assert!(topics.as_array().unwrap()[0].is_null());
+        assert_eq!(topics.to_json(), r#"[null,["a\"b","한😀"]]"#);
+        assert_eq!(JsonValue::parse(&v.to_json()).unwrap(), v);
+
+        for bad in ["", "{", "[1,]", "{\"a\" 1}", "\"abc", "tru", "1 2", "{\"a\":\"\\q\"}"] {
+            assert!(JsonValue::parse(bad).is_err(), "{}", bad);
+        }
+        assert!(JsonValue::parse(&"[".repeat(100)).is_err());
+    }
+
+    #[test]
+    fn test_command_envelope_shape() {
+        let json = JsonObject::envelope("decode", 0, &["미등록".into()], JsonObject::new().int("decimal", 217)).build();
+        let v = JsonValue::parse(&json).unwrap();
+        let keys: Vec<&str> = match &v {
+            JsonValue::Object(fields) => fields.iter().map(|(k, _)| k.as_str()).collect(),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(keys, ["schema", "schema_version", "command", "state", "elapsed_ms", "errors", "data"]);
+        assert_eq!(v.get("schema_version").and_then(JsonValue::as_i64), Some(2));
+        assert_eq!(v.get("state").and_then(JsonValue::as_str), Some("O"));
+        assert!(v.get("elapsed_ms").and_then(JsonValue::as_i64).is_some_and(|ms| ms >= 0));
+        assert_eq!(v.get("errors").and_then(|e| e.as_array()).map(<[_]>::len), Some(1));
+        assert_eq!(v.get("data").and_then(|d| d.get("decimal")).and_then(JsonValue::as_i64), Some(217));
+    }
+}
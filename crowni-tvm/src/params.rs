@@ -0,0 +1,354 @@
+// ═══════════════════════════════════════════════════════════════
+// 프로토콜 파라미터 레지스트리 — 체인 위 단일 파라미터 표
+// 마켓 수수료 · 합의 정족수 · 가스 단가 · 요청 한도를 이름으로 읽는다
+//
+//   읽기: 모든 모듈이 registry.get(이름) 하나로
+//   쓰기: 거버넌스 제안 → 스테이크 가중 투표 → 발효 높이에 적용
+//   감사: 모든 변경 (이전값 → 새값, 높이, 제안) 기록
+//
+// 제안 상태: O = 투표 중 · P = 가결 (발효 대기/적용) · T = 부결
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::output::JsonObject;
+
+pub const MARKET_FEE_BPS: &str = "market.fee_bps";
+pub const DEX_FEE_BPS: &str = "dex.fee_bps";
+pub const CONSENSUS_QUORUM: &str = "consensus.quorum";
+pub const MAX_BLOCK_TXS: &str = "chain.max_block_txs";
+pub const TX_BASE_FEE: &str = "tx.base_fee";
+pub const GAS_SLOAD: &str = "gas.sload";
+pub const GAS_SSTORE: &str = "gas.sstore";
+pub const GAS_TRANSFER: &str = "gas.transfer";
+pub const RATE_LIMIT_PER_MIN: &str = "rate_limit.requests_per_min";
+pub const RATE_LIMIT_BURST: &str = "rate_limit.burst";
+pub const GOV_QUORUM_PCT: &str = "governance.quorum_pct";
+pub const GOV_MIN_DELAY: &str = "governance.min_delay";
+pub const CONSORTIUM_FINALITY_PCT: &str = "consortium.finality_pct";
+
+/// 파라미터 정의 — 기본값은 각 모듈의 기존 상수
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub default: u64,
+    pub min: u64,
+    pub max: u64,
+    pub doc: &'static str,
+}
+
+pub const PARAMS: [ParamSpec; 13] = [
+    ParamSpec { name: MARKET_FEE_BPS, default: 250, min: 0, max: 1_000, doc: "NFT 마켓 수수료 (bps)" },
+    ParamSpec { name: DEX_FEE_BPS, default: 30, min: 0, max: 1_000, doc: "DEX 기본 풀 수수료 (bps)" },
+    ParamSpec { name: CONSENSUS_QUORUM, default: 2, min: 1, max: 729, doc: "PoT 블록 합의 정족수" },
+    ParamSpec { name: MAX_BLOCK_TXS, default: 100, min: 1, max: 19_683, doc: "블록당 최대 트랜잭션" },
+    ParamSpec { name: TX_BASE_FEE, default: 1, min: 0, max: 1_000_000, doc: "전송 최소 수수료 (CRWN)" },
+    ParamSpec { name: GAS_SLOAD, default: 200, min: 1, max: 100_000, doc: "컨트랙트 SLoad 가스" },
+    ParamSpec { name: GAS_SSTORE, default: 500, min: 1, max: 100_000, doc: "컨트랙트 SStore 가스" },
+    ParamSpec { name: GAS_TRANSFER, default: 2_100, min: 1, max: 100_000, doc: "컨트랙트 Transfer 가스" },
+    ParamSpec { name: RATE_LIMIT_PER_MIN, default: 600, min: 1, max: 1_000_000, doc: "노드 API 분당 요청 한도" },
+    ParamSpec { name: RATE_LIMIT_BURST, default: 100, min: 1, max: 100_000, doc: "노드 API 순간 허용량" },
+    ParamSpec { name: GOV_QUORUM_PCT, default: 50, min: 1, max: 100, doc: "가결 찬성 스테이크 비율 (%)" },
+    ParamSpec { name: GOV_MIN_DELAY, default: 2, min: 0, max: 19_683, doc: "가결 후 최소 발효 지연 (블록)" },
+    ParamSpec { name: CONSORTIUM_FINALITY_PCT, default: 67, min: 1, max: 100, doc: "컨소시엄 블록 확정 서명 비율 (%)" },
+];
+
+pub fn spec(name: &str) -> Option<&'static ParamSpec> {
+    PARAMS.iter().find(|s| s.name == name)
+}
+
+/// 모듈 간 공유 핸들
+pub type SharedParams = Rc<RefCell<ParamRegistry>>;
+
+pub fn shared() -> SharedParams {
+    Rc::new(RefCell::new(ParamRegistry::new()))
+}
+
+/// 감사 기록 — 적용된 변경 하나
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamChange {
+    pub name: String,
+    pub old: u64,
+    pub new: u64,
+    pub height: u64,
+    pub proposal_id: u64,
+}
+
+impl ParamChange {
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("name", &self.name)
+            .int("old", self.old as i64)
+            .int("new", self.new as i64)
+            .int("height", self.height as i64)
+            .int("proposal", self.proposal_id as i64)
+    }
+}
+
+impl std::fmt::Display for ParamChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} {}: {} → {} (제안 #{})", self.height, self.name, self.old, self.new, self.proposal_id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamProposal {
+    pub id: u64,
+    pub proposer: String,
+    pub changes: Vec<(String, u64)>,
+    pub effective_height: u64,
+    /// (투표자, 스테이크, trit)
+    pub votes: Vec<(String, u64, i8)>,
+    pub state: i8,
+    pub applied: bool,
+}
+
+impl ParamProposal {
+    pub fn weight(&self, trit: i8) -> u64 {
+        self.votes.iter().filter(|(_, _, t)| *t == trit).map(|(_, w, _)| w).sum()
+    }
+}
+
+impl std::fmt::Display for ParamProposal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self.state { 1 => "P", -1 => "T", _ => "O" };
+        let changes: Vec<String> = self.changes.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        write!(f, "[{}] 제안 #{} by {} — {} @#{} (찬 {} / 반 {} / 기권 {})",
+            s, self.id, self.proposer, changes.join(", "), self.effective_height,
+            self.weight(1), self.weight(-1), self.weight(0))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamRegistry {
+    values: BTreeMap<String, u64>,
+    pub proposals: Vec<ParamProposal>,
+    pub audit: Vec<ParamChange>,
+    height: u64,
+}
+
+impl Default for ParamRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParamRegistry {
+    /// 제네시스 — 모든 파라미터를 기본값으로
+    pub fn new() -> Self {
+        Self {
+            values: PARAMS.iter().map(|s| (s.name.to_string(), s.default)).collect(),
+            proposals: Vec::new(),
+            audit: Vec::new(),
+            height: 0,
+        }
+    }
+
+    /// 현재 값 — 이름은 PARAMS에 등록된 상수여야 한다
+    pub fn get(&self, name: &str) -> u64 {
+        match self.values.get(name) {
+            Some(v) => *v,
+            None => panic!("등록되지 않은 파라미터: {}", name),
+        }
+    }
+
+    pub fn try_get(&self, name: &str) -> Option<u64> {
+        self.values.get(name).copied()
+    }
+
+    /// 과거 높이의 값 — 감사 기록을 거슬러 계산
+    pub fn value_at(&self, name: &str, height: u64) -> Option<u64> {
+        let mut value = self.try_get(name)?;
+        for c in self.audit.iter().rev().filter(|c| c.name == name && c.height > height) {
+            value = c.old;
+        }
+        Some(value)
+    }
+
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &u64)> {
+        self.values.iter()
+    }
+
+    pub fn history(&self, name: &str) -> Vec<&ParamChange> {
+        self.audit.iter().filter(|c| c.name == name).collect()
+    }
+
+    pub fn proposal(&self, id: u64) -> Option<&ParamProposal> {
+        self.proposals.iter().find(|p| p.id == id)
+    }
+
+    /// 변경 제안 — 이름 · 범위 · 발효 높이 검증
+    pub fn propose(&mut self, proposer: &str, changes: Vec<(String, u64)>, effective_height: u64) -> Result<u64, String> {
+        if changes.is_empty() {
+            return Err("변경 없음".into());
+        }
+        for (name, value) in &changes {
+            let s = spec(name).ok_or_else(|| format!("알 수 없는 파라미터: {}", name))?;
+            if *value < s.min || *value > s.max {
+                return Err(format!("{} 범위 밖: {} (허용 {}..={})", name, value, s.min, s.max));
+            }
+        }
+        let earliest = self.height + self.get(GOV_MIN_DELAY);
+        if effective_height < earliest {
+            return Err(format!("발효 높이 #{} — 최소 #{} 이후", effective_height, earliest));
+        }
+        let id = self.proposals.len() as u64 + 1;
+        self.proposals.push(ParamProposal {
+            id,
+            proposer: proposer.to_string(),
+            changes,
+            effective_height,
+            votes: Vec::new(),
+            state: 0,
+            applied: false,
+        });
+        Ok(id)
+    }
+
+    /// 투표 — 스테이크 가중, 투표 중인 제안에 한 번만
+    pub fn vote(&mut self, id: u64, voter: &str, stake: u64, trit: i8) -> Result<(), String> {
+        let p = self.proposals.iter_mut().find(|p| p.id == id).ok_or_else(|| format!("제안 없음: #{}", id))?;
+        if p.state != 0 {
+            return Err(format!("제안 #{} 투표 종료", id));
+        }
+        if stake == 0 {
+            return Err(format!("{} 스테이크 없음", voter));
+        }
+        if p.votes.iter().any(|(v, _, _)| v == voter) {
+            return Err(format!("{} 이미 투표", voter));
+        }
+        p.votes.push((voter.to_string(), stake, trit.signum()));
+        Ok(())
+    }
+
+    /// 집계 — 찬성이 전체 스테이크의 정족수 비율 이상이고 반대보다 많으면 P, 아니면 T
+    pub fn close(&mut self, id: u64, total_stake: u64) -> Result<i8, String> {
+        let quorum_pct = self.get(GOV_QUORUM_PCT);
+        let height = self.height;
+        let p = self.proposals.iter_mut().find(|p| p.id == id).ok_or_else(|| format!("제안 없음: #{}", id))?;
+        if p.state != 0 {
+            return Err(format!("제안 #{} 투표 종료", id));
+        }
+        let (yes, no) = (p.weight(1), p.weight(-1));
+        p.state = if yes * 100 >= total_stake * quorum_pct && yes > no { 1 } else { -1 };
+        // 투표가 길어져 발효 높이를 지났으면 다음 블록에 적용
+        if p.state == 1 && p.effective_height <= height {
+            p.effective_height = height + 1;
+        }
+        Ok(p.state)
+    }
+
+    /// 높이 진행 — 발효 높이에 도달한 가결 제안 적용, 적용된 변경 반환
+    pub fn advance_to(&mut self, height: u64) -> Vec<ParamChange> {
+        self.height = height;
+        let mut applied = Vec::new();
+        let mut due: Vec<usize> = (0..self.proposals.len())
+            .filter(|&i| {
+                let p = &self.proposals[i];
+                p.state == 1 && !p.applied && p.effective_height <= height
+            })
+            .collect();
+        due.sort_by_key(|&i| (self.proposals[i].effective_height, self.proposals[i].id));
+        for i in due {
+            let p = &mut self.proposals[i];
+            p.applied = true;
+            for (name, value) in &p.changes {
+                let old = self.values.insert(name.clone(), *value).unwrap_or(0);
+                applied.push(ParamChange {
+                    name: name.clone(),
+                    old,
+                    new: *value,
+                    height: p.effective_height,
+                    proposal_id: p.id,
+                });
+            }
+        }
+        self.audit.extend(applied.iter().cloned());
+        applied
+    }
+
+    /// 발효 대기 중인 변경 — (발효 높이, 이름, 값)
+    pub fn scheduled(&self) -> Vec<(u64, &str, u64)> {
+        self.proposals.iter()
+            .filter(|p| p.state == 1 && !p.applied)
+            .flat_map(|p| p.changes.iter().map(move |(k, v)| (p.effective_height, k.as_str(), *v)))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let params = self.values.iter()
+            .map(|(k, v)| JsonObject::new().str("name", k).int("value", *v as i64))
+            .collect();
+        let scheduled = self.scheduled().into_iter()
+            .map(|(h, k, v)| JsonObject::new().str("name", k).int("value", v as i64).int("effective_height", h as i64))
+            .collect();
+        JsonObject::new()
+            .int("height", self.height as i64)
+            .objects("params", params)
+            .objects("scheduled", scheduled)
+            .objects("audit", self.audit.iter().map(|c| c.to_json()).collect())
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_bounds() {
+        let mut reg = ParamRegistry::new();
+        assert_eq!(reg.get(MARKET_FEE_BPS), 250);
+        assert_eq!(reg.try_get("unknown"), None);
+        assert!(reg.propose("alice", vec![("unknown".into(), 1)], 10).is_err());
+        assert!(reg.propose("alice", vec![(MARKET_FEE_BPS.into(), 5_000)], 10).is_err());
+        // 최소 지연 (기본 2블록) 전 발효는 거부
+        assert!(reg.propose("alice", vec![(MARKET_FEE_BPS.into(), 100)], 1).is_err());
+        assert!(reg.propose("alice", vec![(MARKET_FEE_BPS.into(), 100)], 2).is_ok());
+    }
+
+    #[test]
+    fn test_governance_schedules_and_audits() {
+        let mut reg = ParamRegistry::new();
+        let id = reg.propose("alice", vec![(MARKET_FEE_BPS.into(), 100), (CONSENSUS_QUORUM.into(), 3)], 5).unwrap();
+        reg.vote(id, "alice", 60, 1).unwrap();
+        assert!(reg.vote(id, "alice", 60, 1).is_err());
+        reg.vote(id, "bob", 30, -1).unwrap();
+        assert_eq!(reg.close(id, 100), Ok(1));
+        assert!(reg.vote(id, "carol", 10, 1).is_err());
+
+        // 발효 높이 전에는 그대로
+        assert!(reg.advance_to(4).is_empty());
+        assert_eq!(reg.get(MARKET_FEE_BPS), 250);
+        assert_eq!(reg.scheduled().len(), 2);
+
+        let applied = reg.advance_to(5);
+        assert_eq!(applied.len(), 2);
+        assert_eq!(reg.get(MARKET_FEE_BPS), 100);
+        assert_eq!(reg.get(CONSENSUS_QUORUM), 3);
+        assert!(reg.scheduled().is_empty());
+        assert!(reg.advance_to(6).is_empty());
+
+        assert_eq!(reg.history(MARKET_FEE_BPS)[0].old, 250);
+        assert_eq!(reg.value_at(MARKET_FEE_BPS, 4), Some(250));
+        assert_eq!(reg.value_at(MARKET_FEE_BPS, 5), Some(100));
+    }
+
+    #[test]
+    fn test_rejected_without_quorum() {
+        let mut reg = ParamRegistry::new();
+        let id = reg.propose("bob", vec![(TX_BASE_FEE.into(), 10)], 3).unwrap();
+        reg.vote(id, "bob", 40, 1).unwrap();
+        assert_eq!(reg.close(id, 100), Ok(-1));
+        reg.advance_to(10);
+        assert_eq!(reg.get(TX_BASE_FEE), 1);
+        assert!(reg.audit.is_empty());
+    }
+}
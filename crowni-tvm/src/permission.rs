@@ -15,6 +15,8 @@
 
 use std::collections::HashMap;
 
+use crate::ring_log::RingLog;
+
 // ─────────────────────────────────────────────
 // 3진 권한 타입
 // ─────────────────────────────────────────────
@@ -161,8 +163,8 @@ pub struct PermissionEngine {
     policies: Vec<PolicyRule>,
     /// 기본 권한 (정책 없을 때)
     pub default_permission: TritPermission,
-    /// 감사 로그
-    audit_log: Vec<AuditEntry>,
+    /// 감사 로그 (상한 — 오래된 것부터 축출)
+    audit_log: RingLog<AuditEntry>,
     /// 판정 통계
     pub stats_allow: u64,
     pub stats_review: u64,
@@ -184,7 +186,7 @@ impl PermissionEngine {
         Self {
             policies: Vec::new(),
             default_permission: TritPermission::Review, // 기본: 검토(O)
-            audit_log: Vec::new(),
+            audit_log: RingLog::default(),
             stats_allow: 0,
             stats_review: 0,
             stats_deny: 0,
@@ -203,6 +205,18 @@ impl PermissionEngine {
         });
     }
 
+    /// 우선 정책 추가 — 기존 규칙(와일드카드 기본값 포함)보다 먼저 매칭
+    pub fn prepend_policy(&mut self, subject: &str, object: &str, action: Action,
+                          permission: TritPermission, reason: &str) {
+        self.policies.insert(0, PolicyRule {
+            subject: subject.to_string(),
+            object: object.to_string(),
+            action,
+            permission,
+            reason: reason.to_string(),
+        });
+    }
+
     /// 권한 확인 — 핵심 함수
     /// 정책을 순서대로 검색, 첫 매칭 규칙의 판정 반환
     /// 매칭 없으면 default_permission
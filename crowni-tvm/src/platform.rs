@@ -6,6 +6,7 @@
 
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::artifacts::SharedArtifacts;
 
 fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
 fn short_hash() -> String { format!("{:07x}", now_ms() % 0xFFFFFFF) }
@@ -170,6 +171,8 @@ pub struct Deployment {
     pub domain: String,
     pub env_vars: HashMap<String, String>,
     pub created_at: u64,
+    /// 배포한 아티팩트 해시 (바이트 대신 참조)
+    pub artifact: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -189,13 +192,20 @@ impl std::fmt::Display for DeployStatus {
 pub struct DeployService {
     pub deployments: Vec<Deployment>,
     pub domains: HashMap<String, String>, // domain → deployment_id
+    pub artifacts: SharedArtifacts,
 }
 
 impl DeployService {
-    pub fn new() -> Self { Self { deployments: Vec::new(), domains: HashMap::new() } }
+    pub fn new() -> Self {
+        Self { deployments: Vec::new(), domains: HashMap::new(), artifacts: crate::artifacts::shared() }
+    }
+
+    fn next_id(&self) -> String {
+        format!("dep-{}-{}", short_hash(), self.deployments.len() + 1)
+    }
 
     pub fn deploy(&mut self, project: &str, framework: &str, domain: &str) -> CTPResponse {
-        let id = format!("dep-{}", short_hash());
+        let id = self.next_id();
         let url = format!("https://{}.crowny.app", project);
         let build_time = 1200 + (now_ms() % 3000);
 
@@ -203,12 +213,32 @@ impl DeployService {
             id: id.clone(), project: project.into(), url: url.clone(),
             status: DeployStatus::Ready, framework: framework.into(),
             build_time_ms: build_time, domain: domain.into(),
-            env_vars: HashMap::new(), created_at: now_ms(),
+            env_vars: HashMap::new(), created_at: now_ms(), artifact: None,
         });
         self.domains.insert(domain.into(), id.clone());
         CTPResponse::ok(&format!("배포 완료: {} → {} ({}ms)", project, url, build_time), Some(url))
     }
 
+    /// 저장소의 아티팩트 해시로 배포 — 참조 1 증가 (빌드 없음)
+    pub fn deploy_artifact(&mut self, project: &str, domain: &str, hash: &str) -> CTPResponse {
+        let retained = self.artifacts.borrow_mut().retain(hash);
+        if let Err(e) = retained {
+            return CTPResponse::fail(&e);
+        }
+        let kind = self.artifacts.borrow().get(hash).map(|a| a.kind.code()).unwrap_or("?");
+        let id = self.next_id();
+        let url = format!("https://{}.crowny.app", project);
+        self.deployments.push(Deployment {
+            id: id.clone(), project: project.into(), url: url.clone(),
+            status: DeployStatus::Ready, framework: kind.into(),
+            build_time_ms: 0, domain: domain.into(),
+            env_vars: HashMap::new(), created_at: now_ms(), artifact: Some(hash.into()),
+        });
+        self.domains.insert(domain.into(), id.clone());
+        let short: String = hash.chars().take(12).collect();
+        CTPResponse::ok(&format!("아티팩트 배포: {} → {} ({})", short, url, id), Some(id))
+    }
+
     pub fn set_env(&mut self, dep_id: &str, key: &str, value: &str) -> CTPResponse {
         if let Some(dep) = self.deployments.iter_mut().find(|d| d.id == dep_id) {
             dep.env_vars.insert(key.into(), value.into());
@@ -491,6 +521,8 @@ impl CrownyPlatform {
             self.git.repos.len(),
             self.git.repos.values().map(|r| r.commits.len()).sum::<usize>()));
         lines.push(format!("  Deploy: {} active", deployed));
+        let store = self.deploy.artifacts.borrow().stats();
+        lines.push(format!("  Artifacts: {} ({} bytes, dedup {})", store.artifacts, store.bytes, store.dedup_hits));
         lines.push(format!("  DB: {} collections, {} docs", cols, docs));
         lines.push(format!("  Runtime: {} running", running));
         lines.push(format!("  Web3: {} contracts, {} wallets",
@@ -527,6 +559,14 @@ pub fn demo_platform() {
     println!("  {}", platform.deploy.deploy("tvm-docs", "Next.js", "docs.crowny.dev"));
     println!("  {}", platform.deploy.deploy("exchange", "React", "exchange.crowny.dev"));
     println!("  {}", platform.deploy.deploy("api-gateway", "Rust", "api.crowny.dev"));
+    // 같은 프로그램을 두 번 빌드해도 아티팩트는 하나 — 배포는 해시를 참조
+    let src = "넣어 6\n넣어 7\n곱해\n보여줘\n종료";
+    let first = platform.deploy.artifacts.borrow_mut().store_build("tvm-app", src);
+    let second = platform.deploy.artifacts.borrow_mut().store_build("tvm-app", src);
+    if let (Ok(first), Ok(_)) = (first, second) {
+        println!("  {}", platform.deploy.deploy_artifact("tvm-app", "app.crowny.dev", &first.wasm));
+        println!("  {}", platform.deploy.deploy_artifact("tvm-app-canary", "canary.crowny.dev", &first.wasm));
+    }
     println!();
 
     // ── 3. DB ──
@@ -608,6 +648,19 @@ mod tests {
         assert_eq!(ds.deployments.len(), 1);
     }
 
+    #[test]
+    fn test_deploy_artifact_refs() {
+        let mut ds = DeployService::new();
+        let hash = ds.artifacts.borrow_mut().put(crate::artifacts::ArtifactKind::Wasm, b"\0asm", "app").unwrap();
+        let a = ds.deploy_artifact("app", "app.crowny.dev", &hash);
+        let b = ds.deploy_artifact("app2", "app2.crowny.dev", &hash);
+        assert_eq!((a.trit, b.trit), (1, 1));
+        assert_eq!(ds.artifacts.borrow().get(&hash).unwrap().refs, 2);
+        assert_eq!(ds.deploy_artifact("x", "x.dev", "0t없음").trit, -1);
+        assert_eq!(ds.deployments[0].artifact.as_deref(), Some(hash.as_str()));
+        assert_eq!(ds.artifacts.borrow_mut().gc().removed, 0);
+    }
+
     #[test]
     fn test_db_insert_query() {
         let mut db = TritDB::new();
@@ -0,0 +1,683 @@
+// ═══════════════════════════════════════════════════════════════
+// 포트폴리오 — 토큰 · DEX · NFT 보유 자산과 손익(P&L) 집계
+// 지갑/거래소 잔액 · LP 포지션(비영구 손실 추정) · NFT(바닥가 평가)
+// 평균단가 원장으로 실현/미실현 손익을 계산하고 시점별로 기록한다
+//
+//   PortfolioService::sync()   — DEX/NFT 거래 기록을 원장에 반영
+//   PortfolioService::report() — 현재 평가 (기준 통화: USDT)
+//
+// 판정: P = 이익 · O = 보합 또는 평가 불가 자산 있음 · T = 손실
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dex::{CrownyDEX, LPAction};
+use crate::nft::{CrownyNFT, MarketTxType};
+use crate::output::JsonObject;
+use crate::token::TokenEngine;
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+/// 기준 통화
+pub const QUOTE: &str = "USDT";
+/// NFT 가격 단위 (마켓 잔액 토큰)
+pub const NFT_CURRENCY: &str = "CRWN";
+
+// ═══════════════════════════════════════
+// 가격표 — DEX 풀 가격에서 유도
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone)]
+pub struct PriceBook {
+    prices: HashMap<String, f64>,
+}
+
+impl PriceBook {
+    pub fn new(quote: &str) -> Self {
+        let mut prices = HashMap::new();
+        prices.insert(quote.to_string(), 1.0);
+        Self { prices }
+    }
+
+    /// 기준 통화 직접 풀 → 한 단계 경유 풀 순서로 가격 결정
+    pub fn from_dex(dex: &CrownyDEX, quote: &str) -> Self {
+        let mut book = Self::new(quote);
+        let mut pools: Vec<_> = dex.pools.values().filter(|p| p.reserve_a > 0 && p.reserve_b > 0).collect();
+        pools.sort_by(|a, b| a.id.cmp(&b.id));
+        for _ in 0..2 {
+            for pool in &pools {
+                let (a, b) = (book.get(&pool.token_a), book.get(&pool.token_b));
+                match (a, b) {
+                    (None, Some(pb)) => book.set(&pool.token_a, pool.price_a_in_b() * pb),
+                    (Some(pa), None) => book.set(&pool.token_b, pool.price_b_in_a() * pa),
+                    _ => {}
+                }
+            }
+        }
+        book
+    }
+
+    pub fn set(&mut self, symbol: &str, price: f64) {
+        self.prices.insert(symbol.to_string(), price);
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<f64> {
+        self.prices.get(symbol).copied()
+    }
+
+    pub fn value(&self, symbol: &str, amount: u64) -> Option<f64> {
+        self.get(symbol).map(|p| p * amount as f64)
+    }
+}
+
+// ═══════════════════════════════════════
+// 평균단가 원장
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Lot {
+    pub qty: f64,
+    pub cost: f64,
+}
+
+/// 사용자 하나의 취득 원가 — 자산 키: 토큰 심볼 · "lp:풀" · "nft:ID"
+#[derive(Debug, Clone, Default)]
+pub struct CostLedger {
+    pub lots: HashMap<String, Lot>,
+    pub realized: f64,
+}
+
+impl CostLedger {
+    pub fn acquire(&mut self, asset: &str, qty: f64, cost: f64) {
+        let lot = self.lots.entry(asset.to_string()).or_default();
+        lot.qty += qty;
+        lot.cost += cost;
+    }
+
+    /// 처분 → 실현 손익 (평균단가). 원장에 없는 수량은 원가 = 처분가 (손익 없음)
+    pub fn dispose(&mut self, asset: &str, qty: f64, proceeds: f64) -> f64 {
+        let Some(lot) = self.lots.get_mut(asset) else { return 0.0 };
+        if lot.qty <= 0.0 || qty <= 0.0 {
+            return 0.0;
+        }
+        let tracked = qty.min(lot.qty);
+        let cost = lot.cost * tracked / lot.qty;
+        let pnl = proceeds * tracked / qty - cost;
+        lot.qty -= tracked;
+        lot.cost -= cost;
+        if lot.qty <= f64::EPSILON {
+            self.lots.remove(asset);
+        }
+        self.realized += pnl;
+        pnl
+    }
+
+    /// 보유 수량 중 원장이 아는 부분의 원가 → (추적 수량, 원가)
+    pub fn basis(&self, asset: &str, held: f64) -> (f64, f64) {
+        match self.lots.get(asset) {
+            Some(lot) if lot.qty > 0.0 => {
+                let tracked = held.min(lot.qty);
+                (tracked, lot.cost * tracked / lot.qty)
+            }
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+// ═══════════════════════════════════════
+// 보유 자산 / 보고서
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenHolding {
+    pub symbol: String,
+    /// "wallet" (토큰 엔진) 또는 "dex" (거래소 잔액)
+    pub source: &'static str,
+    pub amount: u64,
+    pub staked: u64,
+    /// 가격을 모르면 None (평가액 0, 판정 O)
+    pub price: Option<f64>,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpPosition {
+    pub pool_id: String,
+    pub shares: u64,
+    pub share_pct: f64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub value: f64,
+    /// 예치 자산을 그대로 들고 있었을 때의 현재 가치
+    pub hold_value: f64,
+    /// 비영구 손실 추정 (value / hold_value - 1, 음수가 손실)
+    pub impermanent_loss: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NftHolding {
+    pub id: String,
+    pub name: String,
+    pub collection: String,
+    /// 컬렉션 바닥가 (CRWN, 0이면 평가 불가)
+    pub floor: u64,
+    pub value: f64,
+}
+
+/// 시점별 손익
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PnlPoint {
+    pub timestamp: u64,
+    pub value: f64,
+    pub realized: f64,
+    pub unrealized: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PortfolioReport {
+    pub user: String,
+    pub quote: String,
+    pub timestamp: u64,
+    pub tokens: Vec<TokenHolding>,
+    pub lp: Vec<LpPosition>,
+    pub nfts: Vec<NftHolding>,
+    pub total_value: f64,
+    pub cost_basis: f64,
+    pub realized: f64,
+    pub unrealized: f64,
+    /// 가격을 알 수 없는 자산 수
+    pub unpriced: usize,
+}
+
+impl PortfolioReport {
+    pub fn total_pnl(&self) -> f64 {
+        self.realized + self.unrealized
+    }
+
+    pub fn state(&self) -> i8 {
+        let pnl = self.total_pnl();
+        if pnl < -0.005 { -1 } else if self.unpriced > 0 || pnl <= 0.005 { 0 } else { 1 }
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let tokens = self.tokens.iter().map(|t| {
+            let obj = JsonObject::new()
+                .str("symbol", &t.symbol)
+                .str("source", t.source)
+                .int("amount", t.amount as i64)
+                .int("staked", t.staked as i64);
+            match t.price {
+                Some(p) => obj.float("price", p).float("value", t.value),
+                None => obj.float("value", 0.0),
+            }
+        }).collect();
+        let lp = self.lp.iter().map(|p| JsonObject::new()
+            .str("pool_id", &p.pool_id)
+            .int("shares", p.shares as i64)
+            .float("share_pct", p.share_pct)
+            .int("amount_a", p.amount_a as i64)
+            .int("amount_b", p.amount_b as i64)
+            .float("value", p.value)
+            .float("hold_value", p.hold_value)
+            .float("impermanent_loss", p.impermanent_loss)).collect();
+        let nfts = self.nfts.iter().map(|n| JsonObject::new()
+            .str("id", &n.id)
+            .str("name", &n.name)
+            .str("collection", &n.collection)
+            .int("floor", n.floor as i64)
+            .float("value", n.value)).collect();
+        JsonObject::schema("crowny.portfolio")
+            .trit("state", self.state())
+            .str("user", &self.user)
+            .str("quote", &self.quote)
+            .int("timestamp", self.timestamp as i64)
+            .float("total_value", self.total_value)
+            .float("cost_basis", self.cost_basis)
+            .float("realized", self.realized)
+            .float("unrealized", self.unrealized)
+            .int("unpriced", self.unpriced as i64)
+            .objects("tokens", tokens)
+            .objects("lp", lp)
+            .objects("nfts", nfts)
+    }
+
+    /// website.rs .crwn 페이지 — render_html로 HTML 변환
+    pub fn to_crwn_page(&self) -> String {
+        let trit = |v: f64| if v < -0.005 { 'T' } else if v > 0.005 { 'P' } else { 'O' };
+        let mut out = format!("제목: {} 포트폴리오\n언어: ko\n\n# {} 포트폴리오\n\n", self.user, self.user);
+        out.push_str(&format!("[{}] 총 평가액: {:.2} {}\n", crate::output::trit_symbol(self.state()), self.total_value, self.quote));
+        out.push_str(&format!("[{}] 실현 손익: {:+.2}\n", trit(self.realized), self.realized));
+        out.push_str(&format!("[{}] 미실현 손익: {:+.2}\n", trit(self.unrealized), self.unrealized));
+        if self.unpriced > 0 {
+            out.push_str(&format!("[O] 평가 불가 자산: {}개\n", self.unpriced));
+        }
+        out.push_str("\n---\n\n## 토큰\n");
+        for t in &self.tokens {
+            let price = t.price.map(|p| format!("@ {:.4}", p)).unwrap_or_else(|| "가격 없음".into());
+            let staked = if t.staked > 0 { format!(" (스테이킹 {})", t.staked) } else { String::new() };
+            out.push_str(&format!("[{}] {} {} [{}]{} {} = {:.2}\n",
+                if t.price.is_some() { 'P' } else { 'O' }, t.amount, t.symbol, t.source, staked, price, t.value));
+        }
+        if !self.lp.is_empty() {
+            out.push_str("\n## 유동성 포지션\n");
+            for p in &self.lp {
+                out.push_str(&format!("[{}] {} — {} shares ({:.2}%) = {:.2} | 보유 시 {:.2} | IL {:.2}%\n",
+                    trit(p.impermanent_loss), p.pool_id, p.shares, p.share_pct * 100.0,
+                    p.value, p.hold_value, p.impermanent_loss * 100.0));
+            }
+        }
+        if !self.nfts.is_empty() {
+            out.push_str("\n## NFT\n");
+            for n in &self.nfts {
+                let t = if n.floor > 0 { 'P' } else { 'O' };
+                out.push_str(&format!("[{}] {} ({}) — 바닥가 {} {} = {:.2}\n", t, n.name, n.collection, n.floor, NFT_CURRENCY, n.value));
+            }
+        }
+        out
+    }
+}
+
+// ═══════════════════════════════════════
+// 서비스
+// ═══════════════════════════════════════
+
+pub struct PortfolioService {
+    pub dex: Rc<RefCell<CrownyDEX>>,
+    pub nft: Rc<RefCell<CrownyNFT>>,
+    pub token: Option<Rc<RefCell<TokenEngine>>>,
+    pub quote: String,
+    ledgers: HashMap<String, CostLedger>,
+    history: HashMap<String, Vec<PnlPoint>>,
+    /// 이미 반영한 기록 수 (swap / lp / market)
+    cursors: (usize, usize, usize),
+}
+
+impl PortfolioService {
+    pub fn new(dex: Rc<RefCell<CrownyDEX>>, nft: Rc<RefCell<CrownyNFT>>) -> Self {
+        Self {
+            dex, nft, token: None, quote: QUOTE.into(),
+            ledgers: HashMap::new(), history: HashMap::new(), cursors: (0, 0, 0),
+        }
+    }
+
+    pub fn with_token(mut self, engine: Rc<RefCell<TokenEngine>>) -> Self {
+        self.token = Some(engine);
+        self
+    }
+
+    pub fn prices(&self) -> PriceBook {
+        PriceBook::from_dex(&self.dex.borrow(), &self.quote)
+    }
+
+    /// 입금 — 현재 가격을 취득 원가로 기록
+    pub fn record_deposit(&mut self, user: &str, asset: &str, amount: u64) {
+        let cost = self.prices().value(asset, amount).unwrap_or(0.0);
+        self.ledgers.entry(user.into()).or_default().acquire(asset, amount as f64, cost);
+    }
+
+    /// 새 DEX/NFT 기록을 원장에 반영 → 반영한 건수
+    /// 거래 직후 호출하면 거래 시점 가격으로 평가된다
+    pub fn sync(&mut self) -> usize {
+        let prices = self.prices();
+        let dex = self.dex.clone();
+        let nft = self.nft.clone();
+        let dex = dex.borrow();
+        let nft = nft.borrow();
+        let mut applied = 0;
+
+        // 스왑 커서는 기록의 절대 인덱스 — 축출된 기록은 건너뛴다
+        for swap in dex.swap_history.since(self.cursors.0 as u64) {
+            if swap.trader.is_empty() {
+                continue;
+            }
+            // 기준 통화가 한쪽이면 정확한 금액, 아니면 현재 가격
+            let value = if swap.token_out == self.quote {
+                swap.amount_out as f64
+            } else if swap.token_in == self.quote {
+                swap.amount_in as f64
+            } else {
+                prices.value(&swap.token_in, swap.amount_in).unwrap_or(0.0)
+            };
+            let ledger = self.ledgers.entry(swap.trader.clone()).or_default();
+            ledger.dispose(&swap.token_in, swap.amount_in as f64, value);
+            ledger.acquire(&swap.token_out, swap.amount_out as f64, value);
+            applied += 1;
+        }
+        self.cursors.0 = dex.swap_history.end_index() as usize;
+
+        for r in &dex.lp_history[self.cursors.1..] {
+            let Some(pool) = dex.pools.get(&r.pool_id) else { continue };
+            let va = prices.value(&pool.token_a, r.amount_a).unwrap_or(0.0);
+            let vb = prices.value(&pool.token_b, r.amount_b).unwrap_or(0.0);
+            let value = va + vb;
+            let lp_asset = format!("lp:{}", r.pool_id);
+            let ledger = self.ledgers.entry(r.provider.clone()).or_default();
+            match r.action {
+                LPAction::Add => {
+                    ledger.dispose(&pool.token_a, r.amount_a as f64, va);
+                    ledger.dispose(&pool.token_b, r.amount_b as f64, vb);
+                    ledger.acquire(&lp_asset, r.shares_minted as f64, value);
+                }
+                LPAction::Remove => {
+                    ledger.dispose(&lp_asset, r.shares_minted as f64, value);
+                    ledger.acquire(&pool.token_a, r.amount_a as f64, va);
+                    ledger.acquire(&pool.token_b, r.amount_b as f64, vb);
+                }
+            }
+            applied += 1;
+        }
+        self.cursors.1 = dex.lp_history.len();
+
+        let crwn = prices.get(NFT_CURRENCY).unwrap_or(0.0);
+        for tx in &nft.market_history[self.cursors.2..] {
+            if matches!(tx.tx_type, MarketTxType::Transfer) {
+                continue;
+            }
+            let asset = format!("nft:{}", tx.nft_id);
+            let seller_gets = tx.price.saturating_sub(tx.fee + tx.royalty_paid);
+            self.ledgers.entry(tx.to.clone()).or_default().acquire(&asset, 1.0, tx.price as f64 * crwn);
+            self.ledgers.entry(tx.from.clone()).or_default().dispose(&asset, 1.0, seller_gets as f64 * crwn);
+            applied += 1;
+        }
+        self.cursors.2 = nft.market_history.len();
+        applied
+    }
+
+    /// 현재 보유 자산 평가
+    pub fn report(&self, user: &str) -> PortfolioReport {
+        let prices = self.prices();
+        let dex = self.dex.borrow();
+        let market = self.nft.borrow();
+        let empty = CostLedger::default();
+        let ledger = self.ledgers.get(user).unwrap_or(&empty);
+        let mut unpriced = 0;
+        let (mut cost_basis, mut tracked_value) = (0.0, 0.0);
+        let mut held: HashMap<String, u64> = HashMap::new();
+
+        // 토큰 — 지갑 + 거래소
+        let mut tokens = Vec::new();
+        if let Some(engine) = &self.token {
+            let engine = engine.borrow();
+            if let Some(w) = engine.wallets.get(user).filter(|w| w.balance > 0) {
+                tokens.push((engine.token.symbol.clone(), "wallet", w.balance, w.staked));
+            }
+        }
+        if let Some(balances) = dex.balances.get(user) {
+            let mut syms: Vec<_> = balances.iter().filter(|(_, v)| **v > 0).collect();
+            syms.sort();
+            tokens.extend(syms.into_iter().map(|(s, v)| (s.clone(), "dex", *v, 0)));
+        }
+        let tokens: Vec<TokenHolding> = tokens.into_iter().map(|(symbol, source, amount, staked)| {
+            let price = prices.get(&symbol);
+            if price.is_none() {
+                unpriced += 1;
+            }
+            *held.entry(symbol.clone()).or_insert(0) += amount;
+            TokenHolding { value: price.unwrap_or(0.0) * amount as f64, symbol, source, amount, staked, price }
+        }).collect();
+        for (symbol, amount) in &held {
+            let (qty, cost) = ledger.basis(symbol, *amount as f64);
+            cost_basis += cost;
+            tracked_value += prices.get(symbol).unwrap_or(0.0) * qty;
+        }
+
+        // LP 포지션
+        let mut pool_ids: Vec<&String> = dex.pools.keys().collect();
+        pool_ids.sort();
+        let mut lp = Vec::new();
+        for id in pool_ids {
+            let pool = &dex.pools[id];
+            let shares = pool.lp_holders.get(user).copied().unwrap_or(0);
+            if shares == 0 || pool.total_lp_shares == 0 {
+                continue;
+            }
+            let share_pct = shares as f64 / pool.total_lp_shares as f64;
+            let amount_a = (pool.reserve_a as u128 * shares as u128 / pool.total_lp_shares as u128) as u64;
+            let amount_b = (pool.reserve_b as u128 * shares as u128 / pool.total_lp_shares as u128) as u64;
+            let (pa, pb) = (prices.get(&pool.token_a), prices.get(&pool.token_b));
+            if pa.is_none() || pb.is_none() {
+                unpriced += 1;
+            }
+            let value = pa.unwrap_or(0.0) * amount_a as f64 + pb.unwrap_or(0.0) * amount_b as f64;
+
+            // 지분당 예치량 (추가 영수증 평균) → 그대로 보유했을 때의 가치
+            let adds: Vec<_> = dex.lp_history.iter()
+                .filter(|r| r.pool_id == *id && r.provider == user && r.action == LPAction::Add)
+                .collect();
+            let minted: u64 = adds.iter().map(|r| r.shares_minted).sum();
+            let hold_value = if minted == 0 {
+                value
+            } else {
+                let per = shares as f64 / minted as f64;
+                let dep_a: u64 = adds.iter().map(|r| r.amount_a).sum();
+                let dep_b: u64 = adds.iter().map(|r| r.amount_b).sum();
+                (pa.unwrap_or(0.0) * dep_a as f64 + pb.unwrap_or(0.0) * dep_b as f64) * per
+            };
+            let impermanent_loss = if hold_value > 0.0 { value / hold_value - 1.0 } else { 0.0 };
+
+            let (qty, cost) = ledger.basis(&format!("lp:{}", id), shares as f64);
+            cost_basis += cost;
+            tracked_value += value * qty / shares as f64;
+            lp.push(LpPosition { pool_id: id.clone(), shares, share_pct, amount_a, amount_b, value, hold_value, impermanent_loss });
+        }
+
+        // NFT — 컬렉션 바닥가 평가 (바닥가 없으면 평가 불가)
+        let crwn = prices.get(NFT_CURRENCY).unwrap_or(0.0);
+        let mut owned: Vec<_> = market.nfts_by_owner(user);
+        owned.sort_by_key(|n| n.token_id);
+        let nfts: Vec<NftHolding> = owned.into_iter().map(|n| {
+            let col = market.collections.get(&n.collection_id);
+            let floor = floor_price(&market, &n.collection_id)
+                .unwrap_or_else(|| col.map(|c| c.floor_price).unwrap_or(0));
+            if floor == 0 {
+                unpriced += 1;
+            }
+            let value = floor as f64 * crwn;
+            let (qty, cost) = ledger.basis(&format!("nft:{}", n.id), 1.0);
+            cost_basis += cost;
+            tracked_value += value * qty;
+            NftHolding {
+                id: n.id.clone(), name: n.metadata.name.clone(),
+                collection: col.map(|c| c.name.clone()).unwrap_or_default(), floor, value,
+            }
+        }).collect();
+
+        let total_value = tokens.iter().map(|t| t.value).sum::<f64>()
+            + lp.iter().map(|p| p.value).sum::<f64>()
+            + nfts.iter().map(|n| n.value).sum::<f64>();
+        PortfolioReport {
+            user: user.into(), quote: self.quote.clone(), timestamp: now_ms(),
+            tokens, lp, nfts, total_value, cost_basis,
+            realized: ledger.realized, unrealized: tracked_value - cost_basis, unpriced,
+        }
+    }
+
+    /// 동기화 후 평가하고 시점 기록에 추가
+    pub fn snapshot(&mut self, user: &str) -> PortfolioReport {
+        self.sync();
+        let report = self.report(user);
+        self.history.entry(user.into()).or_default().push(PnlPoint {
+            timestamp: report.timestamp, value: report.total_value,
+            realized: report.realized, unrealized: report.unrealized,
+        });
+        report
+    }
+
+    pub fn history(&self, user: &str) -> &[PnlPoint] {
+        self.history.get(user).map(|h| h.as_slice()).unwrap_or(&[])
+    }
+}
+
+/// 현재 리스팅 최저가 (없으면 None → 컬렉션의 마지막 바닥가 사용)
+pub fn floor_price(market: &CrownyNFT, collection_id: &str) -> Option<u64> {
+    market.nfts.values()
+        .filter(|n| n.collection_id == collection_id && n.listed)
+        .filter_map(|n| n.price)
+        .min()
+}
+
+/// 손익 시계열 JSON 배열
+pub fn history_json(points: &[PnlPoint]) -> Vec<JsonObject> {
+    points.iter().map(|p| JsonObject::new()
+        .int("timestamp", p.timestamp as i64)
+        .float("value", p.value)
+        .float("realized", p.realized)
+        .float("unrealized", p.unrealized)).collect()
+}
+
+/// 데모용 — 스왑 · 유동성 · NFT 구매 후 가격이 오른 사용자의 보고서
+pub fn demo_report(user: &str) -> PortfolioReport {
+    use crate::nft::{NFTMetadata, NFTRarity};
+    let mut dex = CrownyDEX::new();
+    dex.mint("pool", "CRWN", 1_000_000);
+    dex.mint("pool", "USDT", 200_000);
+    let pool = dex.create_pool("CRWN", "USDT", 30);
+    dex.add_liquidity("pool", &pool, 1_000_000, 200_000).unwrap();
+    let mut svc = PortfolioService::new(Rc::new(RefCell::new(dex)), Rc::new(RefCell::new(CrownyNFT::new())));
+
+    svc.dex.borrow_mut().mint(user, "USDT", 20_000);
+    svc.record_deposit(user, "USDT", 20_000);
+    svc.dex.borrow_mut().swap(user, &pool, "USDT", 10_000).unwrap();
+    svc.sync();
+    let crwn = svc.dex.borrow().balance(user, "CRWN");
+    svc.dex.borrow_mut().add_liquidity(user, &pool, crwn / 2, 4_000).unwrap();
+    {
+        let mut market = svc.nft.borrow_mut();
+        market.fund(user, 2_000);
+        let col = market.create_collection("삼진 갤러리", "TRI", "artist", "", None, 500);
+        let first = market.mint(&col, "artist", NFTMetadata::new("균형의 삼각", "", ""), NFTRarity::Rare).unwrap();
+        let second = market.mint(&col, "artist", NFTMetadata::new("영점", "", ""), NFTRarity::Common).unwrap();
+        market.list(&first, 1_000).unwrap();
+        market.buy(&first, user).unwrap();
+        market.list(&second, 1_500).unwrap();
+    }
+    svc.sync();
+    // 다른 사용자의 CRWN 매수 → 가격 상승
+    svc.dex.borrow_mut().mint("whale", "USDT", 30_000);
+    svc.dex.borrow_mut().swap("whale", &pool, "USDT", 30_000).unwrap();
+    svc.snapshot(user)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nft::{NFTMetadata, NFTRarity};
+
+    fn setup() -> PortfolioService {
+        let mut dex = CrownyDEX::new();
+        dex.mint("lp", "CRWN", 1_000_000);
+        dex.mint("lp", "USDT", 200_000);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("lp", &pool, 1_000_000, 200_000).unwrap();
+        dex.mint("lp", "ETH", 100);
+        dex.mint("lp", "CRWN", 50_000);
+        let eth = dex.create_pool("ETH", "CRWN", 30);
+        dex.add_liquidity("lp", &eth, 100, 50_000).unwrap();
+        PortfolioService::new(Rc::new(RefCell::new(dex)), Rc::new(RefCell::new(CrownyNFT::new())))
+    }
+
+    #[test]
+    fn test_prices_from_pools() {
+        let svc = setup();
+        let prices = svc.prices();
+        assert_eq!(prices.get("USDT"), Some(1.0));
+        assert!((prices.get("CRWN").unwrap() - 0.2).abs() < 1e-9);
+        // ETH → CRWN → USDT 경유
+        assert!((prices.get("ETH").unwrap() - 100.0).abs() < 1e-6);
+        assert_eq!(prices.get("BTC"), None);
+    }
+
+    #[test]
+    fn test_token_wallet_holding() {
+        let mut engine = TokenEngine::new("Crowny Coin", "CRWN", 1_000, "genesis");
+        engine.transfer("genesis", "alice", 400);
+        let svc = setup().with_token(Rc::new(RefCell::new(engine)));
+        svc.dex.borrow_mut().mint("alice", "CRWN", 100);
+        let report = svc.report("alice");
+        let sources: Vec<_> = report.tokens.iter().map(|t| (t.source, t.amount)).collect();
+        assert_eq!(sources, vec![("wallet", 400), ("dex", 100)]);
+        assert!((report.tokens[0].value - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cost_ledger_average() {
+        let mut l = CostLedger::default();
+        l.acquire("CRWN", 100.0, 10.0);
+        l.acquire("CRWN", 100.0, 30.0);
+        assert!((l.dispose("CRWN", 50.0, 15.0) - 5.0).abs() < 1e-9);
+        assert_eq!(l.basis("CRWN", 1_000.0), (150.0, 30.0));
+        // 원장에 없는 자산은 손익 0
+        assert_eq!(l.dispose("ETH", 1.0, 99.0), 0.0);
+        assert!((l.realized - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_swap_pnl_and_impermanent_loss() {
+        let mut svc = setup();
+        svc.dex.borrow_mut().mint("alice", "USDT", 10_000);
+        svc.record_deposit("alice", "USDT", 10_000);
+        svc.dex.borrow_mut().swap("alice", "CRWN-USDT", "USDT", 10_000).unwrap();
+        svc.sync();
+        let before = svc.report("alice");
+        // 방금 산 CRWN — 원가 10,000 USDT
+        assert!((before.cost_basis - 10_000.0).abs() < 1e-6);
+        assert_eq!(before.realized, 0.0);
+
+        // 다른 사람이 CRWN을 대량 매도 → 가격 하락 → alice 미실현 손실
+        svc.dex.borrow_mut().mint("whale", "CRWN", 300_000);
+        svc.dex.borrow_mut().swap("whale", "CRWN-USDT", "CRWN", 300_000).unwrap();
+        let after = svc.snapshot("alice");
+        assert!(after.unrealized < 0.0);
+        assert_eq!(after.state(), -1);
+        assert_eq!(svc.history("alice").len(), 1);
+
+        // LP는 가격 변동으로 비영구 손실
+        let lp = svc.report("lp");
+        let pos = lp.lp.iter().find(|p| p.pool_id == "CRWN-USDT").unwrap();
+        assert!(pos.impermanent_loss < 0.0);
+        assert!(pos.value > 0.0 && pos.value < pos.hold_value);
+
+        // 되팔면 실현 손익
+        let crwn = svc.dex.borrow().balance("alice", "CRWN");
+        svc.dex.borrow_mut().swap("alice", "CRWN-USDT", "CRWN", crwn).unwrap();
+        svc.sync();
+        let sold = svc.report("alice");
+        assert!(sold.realized < 0.0);
+        assert!(sold.unrealized.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_nft_floor_valuation_and_page() {
+        let mut svc = setup();
+        {
+            let mut market = svc.nft.borrow_mut();
+            market.fund("alice", 5_000);
+            let col = market.create_collection("삼진", "TRI", "bob", "", None, 500);
+            let a = market.mint(&col, "bob", NFTMetadata::new("첫째", "", ""), NFTRarity::Rare).unwrap();
+            let b = market.mint(&col, "bob", NFTMetadata::new("둘째", "", ""), NFTRarity::Common).unwrap();
+            market.list(&a, 1_000).unwrap();
+            market.buy(&a, "alice").unwrap();
+            // 바닥가는 현재 리스팅 최저가 기준
+            market.list(&b, 2_000).unwrap();
+        }
+        svc.sync();
+        let report = svc.report("alice");
+        assert_eq!(report.nfts.len(), 1);
+        assert_eq!(report.nfts[0].floor, 2_000);
+        assert!((report.nfts[0].value - 400.0).abs() < 1e-6);
+        // 1,000 CRWN(200 USDT)에 사서 바닥가 400 USDT
+        assert!((report.unrealized - 200.0).abs() < 1e-6);
+
+        let page = report.to_crwn_page();
+        assert!(page.starts_with("제목: alice 포트폴리오"));
+        assert!(page.contains("## NFT") && page.contains("첫째"));
+        let html = crate::website::render_html("alice", &page);
+        assert!(html.contains("<h1>alice 포트폴리오</h1>"));
+        assert!(report.to_json().build().contains("\"schema\":\"crowny.portfolio\""));
+    }
+}
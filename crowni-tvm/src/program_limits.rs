@@ -0,0 +1,229 @@
+// ═══════════════════════════════════════════════════════════════
+// 프로그램 한도 — 어셈블/컴파일 시점 크기·복잡도 검사
+// 명령어 수 · 중첩 깊이 · 문자열 리터럴 크기 · 함수 수
+// 정책은 커널(KernelConfig) 또는 웹서버(SandboxPolicy)가 넘겨준다
+// 실행 한도(ExecLimits)와 달리 실행 전에 거부 → 공유 서버 보호
+// ═══════════════════════════════════════════════════════════════
+
+use crate::i18n::tr;
+use crate::opcode::OpcodeAddr;
+use crate::output::JsonObject;
+use crate::value::Value;
+use crate::vm::Instruction;
+
+/// 프로그램 한도 — None 이면 무제한
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgramLimits {
+    pub max_instructions: Option<usize>,
+    pub max_nesting: Option<usize>,
+    pub max_string_bytes: Option<usize>,
+    pub max_functions: Option<usize>,
+}
+
+impl ProgramLimits {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// 공유 서버 기본값 (게스트 제출 기준)
+    pub fn shared() -> Self {
+        Self {
+            max_instructions: Some(10_000),
+            max_nesting: Some(32),
+            max_string_bytes: Some(4 * 1024),
+            max_functions: Some(64),
+        }
+    }
+
+    fn check(&self, kind: ProgramLimitKind, actual: usize, line: Option<usize>) -> Result<(), ProgramLimitError> {
+        let limit = match kind {
+            ProgramLimitKind::Instructions => self.max_instructions,
+            ProgramLimitKind::Nesting => self.max_nesting,
+            ProgramLimitKind::StringSize => self.max_string_bytes,
+            ProgramLimitKind::Functions => self.max_functions,
+        };
+        match limit {
+            Some(limit) if actual > limit => Err(ProgramLimitError { kind, limit, actual, line }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 초과된 프로그램 한도 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramLimitKind {
+    Instructions,
+    Nesting,
+    StringSize,
+    Functions,
+}
+
+impl ProgramLimitKind {
+    /// 기계용 코드 (응답 본문/결과 맵/설정 키에 사용)
+    pub fn code(self) -> &'static str {
+        match self {
+            ProgramLimitKind::Instructions => "max_instructions",
+            ProgramLimitKind::Nesting => "max_nesting",
+            ProgramLimitKind::StringSize => "max_string_bytes",
+            ProgramLimitKind::Functions => "max_functions",
+        }
+    }
+
+    pub fn from_code(s: &str) -> Option<Self> {
+        match s {
+            "max_instructions" => Some(ProgramLimitKind::Instructions),
+            "max_nesting" => Some(ProgramLimitKind::Nesting),
+            "max_string_bytes" => Some(ProgramLimitKind::StringSize),
+            "max_functions" => Some(ProgramLimitKind::Functions),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ProgramLimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            ProgramLimitKind::Instructions => "plimit.instructions",
+            ProgramLimitKind::Nesting => "plimit.nesting",
+            ProgramLimitKind::StringSize => "plimit.string_size",
+            ProgramLimitKind::Functions => "plimit.functions",
+        };
+        f.write_str(crate::i18n::t(key))
+    }
+}
+
+/// 구조화된 한도 초과 오류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramLimitError {
+    pub kind: ProgramLimitKind,
+    pub limit: usize,
+    pub actual: usize,
+    /// 소스 줄 번호 (알 수 있을 때, 1부터)
+    pub line: Option<usize>,
+}
+
+impl ProgramLimitError {
+    pub fn to_json(self) -> JsonObject {
+        let obj = JsonObject::schema("crowny.program_limit_error")
+            .trit("state", -1)
+            .str("limit", self.kind.code())
+            .int("max", self.limit as i64)
+            .int("actual", self.actual as i64);
+        match self.line {
+            Some(line) => obj.int("line", line as i64),
+            None => obj,
+        }
+    }
+}
+
+impl std::fmt::Display for ProgramLimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", tr!("plimit.exceeded", self.kind, self.actual, self.limit))?;
+        if let Some(line) = self.line {
+            write!(f, " ({})", tr!("plimit.line", line))?;
+        }
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════
+// 누적 검사기 — 명령어를 하나씩 넣으며 검사
+// 함수(0,4,0)가 중첩을 열고 반환(0,2,3)/돌려줘(0,4,2)가 닫는다
+// ═══════════════════════════════════════
+
+const FUNC: OpcodeAddr = OpcodeAddr { sector: 0, group: 4, command: 0 };
+const RET: OpcodeAddr = OpcodeAddr { sector: 0, group: 2, command: 3 };
+const RETURN: OpcodeAddr = OpcodeAddr { sector: 0, group: 4, command: 2 };
+
+#[derive(Debug, Clone)]
+pub struct ProgramChecker {
+    limits: ProgramLimits,
+    pub instructions: usize,
+    pub functions: usize,
+    depth: usize,
+}
+
+impl ProgramChecker {
+    pub fn new(limits: ProgramLimits) -> Self {
+        Self { limits, instructions: 0, functions: 0, depth: 0 }
+    }
+
+    pub fn push(&mut self, inst: &Instruction, line: Option<usize>) -> Result<(), ProgramLimitError> {
+        self.instructions += 1;
+        self.limits.check(ProgramLimitKind::Instructions, self.instructions, line)?;
+        for op in &inst.operands {
+            if let Value::Str(s) = op {
+                self.limits.check(ProgramLimitKind::StringSize, s.len(), line)?;
+            }
+        }
+        if inst.addr == FUNC {
+            self.functions += 1;
+            self.depth += 1;
+            self.limits.check(ProgramLimitKind::Functions, self.functions, line)?;
+            self.limits.check(ProgramLimitKind::Nesting, self.depth, line)?;
+        } else if inst.addr == RET || inst.addr == RETURN {
+            self.depth = self.depth.saturating_sub(1);
+        }
+        Ok(())
+    }
+}
+
+/// 완성된 프로그램 검사
+pub fn check_program(program: &[Instruction], limits: &ProgramLimits) -> Result<(), ProgramLimitError> {
+    let mut checker = ProgramChecker::new(*limits);
+    program.iter().try_for_each(|inst| checker.push(inst, None))
+}
+
+/// 블록 중첩 깊이 검사 (한선어 토큰 사전 검사용)
+pub fn check_nesting(depth: usize, limits: &ProgramLimits) -> Result<(), ProgramLimitError> {
+    limits.check(ProgramLimitKind::Nesting, depth, None)
+}
+
+pub fn check_string(len: usize, limits: &ProgramLimits) -> Result<(), ProgramLimitError> {
+    limits.check(ProgramLimitKind::StringSize, len, None)
+}
+
+pub fn check_functions(count: usize, limits: &ProgramLimits) -> Result<(), ProgramLimitError> {
+    limits.check(ProgramLimitKind::Functions, count, None)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::assemble;
+
+    #[test]
+    fn test_checker_kinds() {
+        let limits = ProgramLimits { max_instructions: Some(3), ..ProgramLimits::unlimited() };
+        assert!(check_program(&assemble("넣어 1\n넣어 2\n종료"), &limits).is_ok());
+        let err = check_program(&assemble("넣어 1\n넣어 2\n더해\n종료"), &limits).unwrap_err();
+        assert_eq!((err.kind, err.limit, err.actual), (ProgramLimitKind::Instructions, 3, 4));
+
+        let limits = ProgramLimits { max_string_bytes: Some(3), ..ProgramLimits::unlimited() };
+        let err = check_program(&assemble("넣어 \"한글\""), &limits).unwrap_err();
+        assert_eq!((err.kind, err.actual), (ProgramLimitKind::StringSize, 6));
+
+        let limits = ProgramLimits { max_nesting: Some(1), ..ProgramLimits::unlimited() };
+        assert!(check_program(&assemble("함수\n반환\n함수\n반환"), &limits).is_ok());
+        let err = check_program(&assemble("함수\n함수\n반환\n반환"), &limits).unwrap_err();
+        assert_eq!(err.kind, ProgramLimitKind::Nesting);
+
+        let limits = ProgramLimits { max_functions: Some(1), ..ProgramLimits::unlimited() };
+        assert_eq!(check_program(&assemble("함수\n반환\n함수\n반환"), &limits).unwrap_err().kind,
+            ProgramLimitKind::Functions);
+        assert!(check_program(&assemble("함수\n함수\n함수"), &ProgramLimits::unlimited()).is_ok());
+    }
+
+    #[test]
+    fn test_error_json_and_codes() {
+        let err = ProgramLimitError { kind: ProgramLimitKind::Nesting, limit: 32, actual: 33, line: Some(7) };
+        assert_eq!(err.to_json().build(),
+            r#"{"schema":"crowny.program_limit_error","schema_version":2,"state":"T","limit":"max_nesting","max":32,"actual":33,"line":7}"#);
+        for kind in [ProgramLimitKind::Instructions, ProgramLimitKind::Nesting,
+                     ProgramLimitKind::StringSize, ProgramLimitKind::Functions] {
+            assert_eq!(ProgramLimitKind::from_code(kind.code()), Some(kind));
+        }
+    }
+}
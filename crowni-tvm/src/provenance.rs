@@ -0,0 +1,383 @@
+// ═══════════════════════════════════════════════════════════════
+// 근거 보고서 — 합의 결과의 출처(provenance) 문서
+// 의료 · 트레이딩처럼 규제가 있는 곳에서 "왜 이 판정인가"를 남긴다
+//
+//   질의 → 노드별 투표와 근거 · 지연 → CTP 헤더 해석 → 폴백 사용 → 감사 로그
+//
+//   ProvenanceReport::from_live(..) / from_local(..)
+//   .with_audit(&로그, 창)   — 판정 시각 주변의 합의 · 권한 · LLM 이벤트 연결 (투표는 node 필드로)
+//   .to_markdown() / .to_html() — website::render_html 로 HTML 변환
+// ═══════════════════════════════════════════════════════════════
+
+use crate::live_consensus;
+use crate::local_consensus;
+use crate::output::{trit_symbol, JsonObject};
+use crate::trit_log::{Category, Event, TritEventLog};
+
+/// 판정 시각 앞뒤로 감사 이벤트를 찾는 기본 창
+pub const DEFAULT_AUDIT_WINDOW_MS: u64 = 60_000;
+
+/// 보고서 대상 종류
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    LiveConsensus,
+    LocalConsensus,
+}
+
+impl Source {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::LiveConsensus => "live_consensus",
+            Self::LocalConsensus => "local_consensus",
+        }
+    }
+
+    /// CTP [0..4] 칸의 의미 — 생성기마다 [1] · [3] · [4] 해석이 다르다
+    fn ctp_fields(&self) -> [&'static str; 5] {
+        match self {
+            Self::LocalConsensus => ["최종 판정", "모든 모델 응답 성공", "만장일치", "응답 수 충족 (2개 이상)", "평균 지연 (300ms 미만 P · 1초 미만 O)"],
+            Self::LiveConsensus => ["최종 판정", "권한", "만장일치", "정족수 (온라인 2개 이상)", "라우팅"],
+        }
+    }
+}
+
+/// 노드 하나의 투표 기록
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoteRecord {
+    pub node: String,
+    pub state: i8,
+    pub reason: String,
+    pub latency_ms: Option<u64>,
+    pub status: String,
+    /// 노드 응답 대신 폴백 투표를 썼는가
+    pub fallback: bool,
+}
+
+/// 보고서에 연결된 감사 로그 항목 (TritEventLog 이벤트)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditLink {
+    pub id: u64,
+    pub timestamp: u64,
+    pub category: String,
+    pub state: i8,
+    pub source: String,
+    pub message: String,
+    /// 투표 이벤트의 노드 (trit_log::consensus_vote 의 node 필드)
+    pub node: Option<String>,
+}
+
+impl AuditLink {
+    fn from_event(e: &Event) -> Self {
+        Self {
+            id: e.id,
+            timestamp: e.timestamp,
+            category: e.category.to_string(),
+            state: e.trit_state as i8,
+            source: e.source.clone(),
+            message: e.message.clone(),
+            node: e.fields.get("node").cloned(),
+        }
+    }
+
+    /// 보고서 안 앵커 (HTML에서도 같은 문자열)
+    pub fn anchor(&self) -> String {
+        format!("audit-{}", self.id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProvenanceReport {
+    pub id: String,
+    pub source: Source,
+    pub query: String,
+    pub state: i8,
+    pub confidence: f64,
+    pub votes: Vec<VoteRecord>,
+    pub ctp: [i8; 9],
+    pub total_latency_ms: Option<u64>,
+    /// 분야 · 위험도 · 권고처럼 대상별 추가 항목
+    pub details: Vec<(String, String)>,
+    pub timestamp: u64,
+    pub audit: Vec<AuditLink>,
+    /// 원본 결과 JSON (to_json) — 보고서 끝에 그대로 싣는다
+    pub record: String,
+}
+
+impl ProvenanceReport {
+    /// OpenClaw 라이브 합의 결과
+    pub fn from_live(r: &live_consensus::ConsensusResult) -> Self {
+        let votes = r.votes.iter().map(|v| VoteRecord {
+            node: v.node_name.clone(),
+            state: v.trit,
+            reason: v.reason.clone(),
+            latency_ms: Some(v.latency_ms),
+            status: v.status.code().to_string(),
+            fallback: v.is_fallback(),
+        }).collect();
+        Self {
+            id: format!("live-{}", r.timestamp),
+            source: Source::LiveConsensus,
+            query: r.query.clone(),
+            state: r.consensus_trit,
+            confidence: r.confidence,
+            votes,
+            ctp: r.ctp_header,
+            total_latency_ms: Some(r.total_latency_ms),
+            details: vec![("온라인 노드".into(), format!("{}/{}", r.nodes_online, r.nodes_total))],
+            timestamp: r.timestamp,
+            audit: Vec::new(),
+            record: r.to_json().build(),
+        }
+    }
+
+    /// 로컬 시뮬레이션 합의 결과
+    pub fn from_local(r: &local_consensus::ConsensusResult) -> Self {
+        let votes = r.responses.iter().map(|a| VoteRecord {
+            node: a.endpoint_name.clone(),
+            state: a.trit,
+            reason: a.error.clone().unwrap_or_else(|| a.text.clone()),
+            latency_ms: Some(a.latency_ms as u64),
+            status: if a.success { "online" } else { "error" }.to_string(),
+            fallback: false,
+        }).collect();
+        Self {
+            id: format!("local-{}", r.request_id),
+            source: Source::LocalConsensus,
+            query: r.prompt.clone(),
+            state: r.final_trit,
+            confidence: r.confidence,
+            votes,
+            ctp: r.ctp_header,
+            total_latency_ms: Some(r.total_latency_ms as u64),
+            details: vec![("만장일치".into(), if r.unanimous { "예" } else { "아니오" }.into())],
+            timestamp: r.timestamp,
+            audit: Vec::new(),
+            record: r.to_json().build(),
+        }
+    }
+
+    /// 판정 시각 ± window_ms (지연만큼 앞으로 더) 안의 합의 · 권한 · LLM · 작업 이벤트 연결
+    pub fn with_audit(mut self, log: &TritEventLog, window_ms: u64) -> Self {
+        let from = self.timestamp.saturating_sub(self.total_latency_ms.unwrap_or(0) + window_ms);
+        let until = self.timestamp.saturating_add(window_ms);
+        self.audit = log.recent(usize::MAX).iter()
+            .filter(|e| matches!(e.category, Category::Consensus | Category::Permission | Category::Llm | Category::Task))
+            .filter(|e| (from..=until).contains(&e.timestamp))
+            .map(AuditLink::from_event)
+            .collect();
+        self
+    }
+
+    pub fn fallback_count(&self) -> usize {
+        self.votes.iter().filter(|v| v.fallback).count()
+    }
+
+    /// 그 노드가 남긴 투표 감사 항목
+    fn audit_for(&self, node: &str) -> Vec<&AuditLink> {
+        self.audit.iter().filter(|a| a.node.as_deref() == Some(node)).collect()
+    }
+
+    /// CTP 9칸 해석 — (칸, 의미, 값)
+    pub fn ctp_interpretation(&self) -> Vec<(usize, String, i8)> {
+        let fields = self.source.ctp_fields();
+        (0..9).map(|i| {
+            let meaning = match fields.get(i) {
+                Some(f) => f.to_string(),
+                None => match self.votes.get(i - 5) {
+                    Some(v) => format!("{} 투표", v.node),
+                    None => "미사용".to_string(),
+                },
+            };
+            (i, meaning, self.ctp[i])
+        }).collect()
+    }
+
+    pub fn title(&self) -> String {
+        format!("근거 보고서 {}", self.id)
+    }
+
+    /// Markdown (.crwn 부분집합) — [P]/[O]/[T] 줄은 HTML에서 색이 입혀진다
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title());
+        out.push_str(&format!("[{}] 판정: {} — 신뢰도 {:.0}%\n", trit_symbol(self.state), trit_symbol(self.state), self.confidence * 100.0));
+        match self.fallback_count() {
+            0 => out.push_str("[P] 폴백: 사용 안 함\n"),
+            n => out.push_str(&format!("[O] 폴백: {}/{} 노드가 폴백 투표\n", n, self.votes.len())),
+        }
+        out.push_str(&format!("\n질의: {}\n\n", self.query));
+        out.push_str(&format!("대상: {} · 기록 시각(ms): {}", self.source.code(), self.timestamp));
+        if let Some(ms) = self.total_latency_ms {
+            out.push_str(&format!(" · 전체 지연: {}ms", ms));
+        }
+        out.push('\n');
+        for (k, v) in &self.details {
+            out.push_str(&format!("\n{}: {}\n", k, v));
+        }
+
+        out.push_str("\n---\n\n## 노드별 투표\n");
+        for v in &self.votes {
+            let latency = v.latency_ms.map(|ms| format!(" · {}ms", ms)).unwrap_or_default();
+            let fallback = if v.fallback { " · 폴백" } else { "" };
+            let audit: Vec<String> = self.audit_for(&v.node).iter().map(|a| format!("#{}", a.anchor())).collect();
+            let audit = if audit.is_empty() { String::new() } else { format!(" · 감사 {}", audit.join(" ")) };
+            out.push_str(&format!("[{}] {} — {} ({}{}{}){}\n", trit_symbol(v.state), v.node, v.reason, v.status, latency, fallback, audit));
+        }
+
+        out.push_str(&format!("\n## CTP 헤더 {}\n", self.ctp.iter().map(|t| trit_symbol(*t)).collect::<String>()));
+        for (i, meaning, value) in self.ctp_interpretation() {
+            out.push_str(&format!("[{}] [{}] {}\n", trit_symbol(value), i, meaning));
+        }
+
+        out.push_str("\n## 감사 로그\n");
+        if self.audit.is_empty() {
+            out.push_str("\n연결된 감사 항목 없음\n");
+        }
+        for a in &self.audit {
+            out.push_str(&format!("[{}] {} {} {} — {} ({})\n", trit_symbol(a.state), a.anchor(), a.category, a.source, a.message, a.timestamp));
+        }
+
+        out.push_str("\n## 원본 기록\n\n```json\n");
+        out.push_str(&self.record);
+        out.push_str("\n```\n");
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        crate::website::render_html(&self.title(), &self.to_markdown())
+    }
+
+    /// 안정 필드 순서 JSON (schema: crowny.provenance)
+    pub fn to_json(&self) -> JsonObject {
+        let votes = self.votes.iter().map(|v| {
+            let obj = JsonObject::new()
+                .str("node", &v.node)
+                .trit("state", v.state)
+                .str("reason", &v.reason)
+                .str("status", &v.status)
+                .bool("fallback", v.fallback);
+            match v.latency_ms {
+                Some(ms) => obj.int("latency_ms", ms as i64),
+                None => obj,
+            }
+        }).collect();
+        let audit = self.audit.iter().map(|a| JsonObject::new()
+            .int("id", a.id as i64)
+            .int("timestamp", a.timestamp as i64)
+            .str("category", &a.category)
+            .trit("state", a.state)
+            .str("message", &a.message)).collect();
+        JsonObject::schema("crowny.provenance")
+            .trit("state", self.state)
+            .str("id", &self.id)
+            .str("source", self.source.code())
+            .str("query", &self.query)
+            .float("confidence", self.confidence)
+            .str("ctp", &self.ctp.iter().map(|t| trit_symbol(*t)).collect::<String>())
+            .int("fallbacks", self.fallback_count() as i64)
+            .objects("votes", votes)
+            .objects("audit", audit)
+            .raw("record", self.record.clone())
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::car::TritState;
+
+    fn live_result() -> live_consensus::ConsensusResult {
+        let vote = |name: &str, trit, reason: &str, status| live_consensus::ConsensusVote {
+            node_name: name.into(), trit, reason: reason.into(), latency_ms: 40, status, raw_response: None, weight: 1.0,
+        };
+        live_consensus::ConsensusResult {
+            query: "수술 진행?".into(),
+            votes: vec![
+                vote("Claude", 1, "활력징후 안정", live_consensus::NodeStatus::Online),
+                vote("Gemini", -1, &format!("{} 연결 거부 — 리스크 요소 감지", live_consensus::FALLBACK_PREFIX), live_consensus::NodeStatus::Offline),
+                vote("Sonnet", 1, "적합", live_consensus::NodeStatus::Online),
+            ],
+            consensus_trit: 1,
+            confidence: 2.0 / 3.0,
+            total_latency_ms: 120,
+            ctp_header: [1, 1, 0, 1, 1, 1, -1, 1, 0],
+            timestamp: 1_000_000,
+            nodes_online: 2,
+            nodes_total: 3,
+        }
+    }
+
+    #[test]
+    fn test_live_report_fallback_and_ctp() {
+        let report = ProvenanceReport::from_live(&live_result());
+        assert_eq!(report.fallback_count(), 1);
+        let ctp = report.ctp_interpretation();
+        assert_eq!(ctp[3], (3, "정족수 (온라인 2개 이상)".to_string(), 1));
+        assert_eq!(ctp[6], (6, "Gemini 투표".to_string(), -1));
+        assert_eq!(ctp[8].1, "미사용");
+
+        let md = report.to_markdown();
+        assert!(md.contains("[P] 판정: P — 신뢰도 67%"));
+        assert!(md.contains("[O] 폴백: 1/3 노드가 폴백 투표"));
+        assert!(md.contains("[T] Gemini — (폴백) 연결 거부 — 리스크 요소 감지 (offline · 40ms · 폴백)"));
+        assert!(md.contains("질의: 수술 진행?"));
+        assert!(md.contains("\"schema\":\"crowny.live_consensus_result\""));
+    }
+
+    #[test]
+    fn test_audit_entries_linked_within_window() {
+        let mut log = TritEventLog::new();
+        log.consensus_vote(1, "Claude", 1);
+        log.info(Category::System, "boot", "시작", TritState::Success);
+        let now = log.recent(1)[0].timestamp;
+
+        let mut result = live_result();
+        result.timestamp = now;
+        let report = ProvenanceReport::from_live(&result).with_audit(&log, DEFAULT_AUDIT_WINDOW_MS);
+        // System 이벤트는 제외
+        assert_eq!(report.audit.len(), 1);
+        let anchor = report.audit[0].anchor();
+        let md = report.to_markdown();
+        assert!(md.contains(&format!("Claude — 활력징후 안정 (online · 40ms) · 감사 #{}", anchor)));
+
+        // 창 밖의 판정에는 연결되지 않는다
+        result.timestamp = now + 10 * DEFAULT_AUDIT_WINDOW_MS;
+        assert!(ProvenanceReport::from_live(&result).with_audit(&log, DEFAULT_AUDIT_WINDOW_MS).audit.is_empty());
+    }
+
+    #[test]
+    fn test_audit_matches_node_field_not_message() {
+        let mut log = TritEventLog::new();
+        // 메시지에 다른 노드 이름이 들어 있어도 투표자가 아니면 연결하지 않는다
+        log.consensus_vote(1, "Sonnet", 1);
+        log.info(Category::Consensus, "Gate", "Claude 응답 지연", TritState::Pending);
+        let mut result = live_result();
+        result.timestamp = log.recent(1)[0].timestamp;
+
+        let report = ProvenanceReport::from_live(&result).with_audit(&log, DEFAULT_AUDIT_WINDOW_MS);
+        assert_eq!(report.audit.len(), 2);
+        assert_eq!(report.audit[0].node.as_deref(), Some("Sonnet"));
+        assert!(report.audit_for("Claude").is_empty());
+        assert_eq!(report.audit_for("Sonnet").len(), 1);
+        let md = report.to_markdown();
+        assert!(md.contains("Claude — 활력징후 안정 (online · 40ms)\n"));
+    }
+
+    #[test]
+    fn test_local_report_html_export() {
+        let mut engine = local_consensus::LocalConsensusEngine::openclaw_default();
+        let report = ProvenanceReport::from_local(&engine.simulate_consensus("응급 <수술>?"));
+        assert_eq!(report.source, Source::LocalConsensus);
+        assert!(report.votes.iter().all(|v| v.latency_ms.is_some() && !v.fallback));
+
+        let html = report.to_html();
+        assert!(html.contains("<title>근거 보고서 local-"));
+        assert!(html.contains("응급 &lt;수술&gt;?"));
+        assert!(html.contains("<h2>CTP 헤더 "));
+        assert!(html.contains("<code class=\"language-json\">"));
+        let json = report.to_json().build();
+        assert!(json.starts_with("{\"schema\":\"crowny.provenance\""));
+        assert!(json.contains("\"source\":\"local_consensus\""));
+    }
+}
@@ -0,0 +1,221 @@
+// ═══════════════════════════════════════════════════════════════
+// 기록 조회 — 공통 페이지네이션 · 필터 · 정렬
+// 스왑 · 마켓 거래 · 블록 · 로그 · CAR 작업 기록에 같은 질의를 쓴다
+//
+//   커서: 기록의 단조 증가 키 (인덱스 또는 ID) — 다음 페이지는 next_cursor
+//   필터: 시간 범위(since ~ until, ms) · 트릿 상태 · 계정
+//   정렬: asc(오래된 순) / desc(최신 순, 기본)
+//
+// HTTP: ?cursor=&limit=&since=&until=&trit=P&account=&order=asc
+// ═══════════════════════════════════════════════════════════════
+
+use std::collections::HashMap;
+
+use crate::i18n::tr;
+use crate::output::JsonObject;
+
+pub const DEFAULT_LIMIT: usize = 20;
+pub const MAX_LIMIT: usize = 100;
+
+/// 조회 대상 기록
+pub trait Queryable {
+    fn timestamp(&self) -> u64;
+    fn trit(&self) -> i8;
+    /// 계정 필터 — 기록에 관련된 계정인지
+    fn involves(&self, account: &str) -> bool;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    /// 이 키부터 (포함) — None이면 처음/최신부터
+    pub cursor: Option<u64>,
+    pub limit: usize,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub trit: Option<i8>,
+    pub account: Option<String>,
+    pub order: SortOrder,
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Self { cursor: None, limit: DEFAULT_LIMIT, since: None, until: None, trit: None, account: None, order: SortOrder::Desc }
+    }
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self { self.limit = limit.clamp(1, MAX_LIMIT); self }
+    pub fn cursor(mut self, cursor: u64) -> Self { self.cursor = Some(cursor); self }
+    pub fn since(mut self, ms: u64) -> Self { self.since = Some(ms); self }
+    pub fn until(mut self, ms: u64) -> Self { self.until = Some(ms); self }
+    pub fn trit(mut self, trit: i8) -> Self { self.trit = Some(trit.clamp(-1, 1)); self }
+    pub fn account(mut self, account: &str) -> Self { self.account = Some(account.into()); self }
+    pub fn order(mut self, order: SortOrder) -> Self { self.order = order; self }
+
+    /// HTTP 쿼리/폼 파라미터에서 — 알 수 없는 키는 무시, 잘못된 값은 오류
+    pub fn from_params(params: &HashMap<String, String>) -> Result<Self, String> {
+        let num = |key: &str| -> Result<Option<u64>, String> {
+            match params.get(key).filter(|v| !v.is_empty()) {
+                Some(v) => v.parse().map(Some).map_err(|_| tr!("web.param_invalid", key, v)),
+                None => Ok(None),
+            }
+        };
+        let mut q = Self::new();
+        if let Some(cursor) = num("cursor")? {
+            q = q.cursor(cursor);
+        }
+        if let Some(limit) = num("limit")? {
+            q = q.limit(limit as usize);
+        }
+        if let Some(ms) = num("since")? {
+            q = q.since(ms);
+        }
+        if let Some(ms) = num("until")? {
+            q = q.until(ms);
+        }
+        if let Some(t) = params.get("trit").filter(|v| !v.is_empty()) {
+            q = q.trit(match t.as_str() {
+                "P" | "p" | "1" => 1,
+                "O" | "o" | "0" => 0,
+                "T" | "t" | "-1" => -1,
+                _ => return Err(tr!("web.param_invalid", "trit", t)),
+            });
+        }
+        if let Some(account) = params.get("account").filter(|v| !v.is_empty()) {
+            q = q.account(account);
+        }
+        if let Some(o) = params.get("order").filter(|v| !v.is_empty()) {
+            q = q.order(match o.as_str() {
+                "asc" => SortOrder::Asc,
+                "desc" => SortOrder::Desc,
+                _ => return Err(tr!("web.param_invalid", "order", o)),
+            });
+        }
+        Ok(q)
+    }
+
+    pub fn matches<T: Queryable + ?Sized>(&self, item: &T) -> bool {
+        let ts = item.timestamp();
+        self.since.is_none_or(|s| ts >= s)
+            && self.until.is_none_or(|u| ts <= u)
+            && self.trit.is_none_or(|t| item.trit() == t)
+            && self.account.as_deref().is_none_or(|a| item.involves(a))
+    }
+
+    /// (키, 기록) 목록에 적용 — 키는 오름차순이어야 한다
+    pub fn page<'a, T: Queryable + ?Sized + 'a>(
+        &self,
+        items: impl DoubleEndedIterator<Item = (u64, &'a T)>,
+    ) -> Page<&'a T> {
+        let filtered: Box<dyn Iterator<Item = (u64, &'a T)>> = match self.order {
+            SortOrder::Asc => Box::new(items.filter(|(k, _)| self.cursor.is_none_or(|c| *k >= c))),
+            SortOrder::Desc => Box::new(items.rev().filter(|(k, _)| self.cursor.is_none_or(|c| *k <= c))),
+        };
+        let mut page = Page { items: Vec::new(), next_cursor: None };
+        for (key, item) in filtered.filter(|(_, item)| self.matches(*item)) {
+            if page.items.len() == self.limit {
+                page.next_cursor = Some(key);
+                break;
+            }
+            page.items.push(item);
+        }
+        page
+    }
+
+    /// 슬라이스 — 인덱스를 커서로 사용 (추가만 되는 기록)
+    pub fn page_slice<'a, T: Queryable>(&self, items: &'a [T]) -> Page<&'a T> {
+        self.page(items.iter().enumerate().map(|(i, x)| (i as u64, x)))
+    }
+}
+
+/// 조회 결과 한 페이지
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 다음 페이지 커서 (없으면 마지막 페이지)
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page { items: self.items.into_iter().map(f).collect(), next_cursor: self.next_cursor }
+    }
+
+    /// {"schema":..., "items":[...], "count":n, "next_cursor":k|null}
+    pub fn to_json(&self, schema: &str, item: impl Fn(&T) -> JsonObject) -> JsonObject {
+        let obj = JsonObject::schema(schema)
+            .objects("items", self.items.iter().map(item).collect())
+            .int("count", self.items.len() as i64);
+        match self.next_cursor {
+            Some(c) => obj.int("next_cursor", c as i64),
+            None => obj.raw("next_cursor", "null".into()),
+        }
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Rec(u64, i8, &'static str);
+
+    impl Queryable for Rec {
+        fn timestamp(&self) -> u64 { self.0 }
+        fn trit(&self) -> i8 { self.1 }
+        fn involves(&self, account: &str) -> bool { self.2 == account }
+    }
+
+    fn recs() -> Vec<Rec> {
+        (0..10).map(|i| Rec(i * 10, [1, 0, -1][i as usize % 3], if i % 2 == 0 { "a" } else { "b" })).collect()
+    }
+
+    #[test]
+    fn test_cursor_walk_both_orders() {
+        let data = recs();
+        let q = Query::new().limit(4).order(SortOrder::Asc);
+        let first = q.page_slice(&data);
+        assert_eq!(first.items.iter().map(|r| r.0).collect::<Vec<_>>(), vec![0, 10, 20, 30]);
+        assert_eq!(first.next_cursor, Some(4));
+        let last = q.clone().cursor(8).page_slice(&data);
+        assert_eq!((last.items.len(), last.next_cursor), (2, None));
+
+        // 기본: 최신 순
+        let q = Query::new().limit(3);
+        let page = q.page_slice(&data);
+        assert_eq!(page.items[0].0, 90);
+        let next = q.clone().cursor(page.next_cursor.unwrap()).page_slice(&data);
+        assert_eq!(next.items[0].0, 60);
+    }
+
+    #[test]
+    fn test_filters_and_params() {
+        let data = recs();
+        let page = Query::new().since(20).until(70).trit(1).page_slice(&data);
+        assert_eq!(page.items.iter().map(|r| r.0).collect::<Vec<_>>(), vec![60, 30]);
+        let page = Query::new().account("b").limit(100).page_slice(&data);
+        assert_eq!(page.items.len(), 5);
+
+        let params: HashMap<String, String> = [("limit", "500"), ("trit", "T"), ("order", "asc"), ("x", "y")]
+            .iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let q = Query::from_params(&params).unwrap();
+        assert_eq!((q.limit, q.trit, q.order), (MAX_LIMIT, Some(-1), SortOrder::Asc));
+        let bad: HashMap<String, String> = [("since".to_string(), "어제".to_string())].into();
+        assert!(Query::from_params(&bad).is_err());
+
+        let json = Query::new().limit(1).page_slice(&data).to_json("crowny.test_page", |r| JsonObject::new().int("ts", r.0 as i64)).build();
+        assert!(json.contains("\"items\":[{\"ts\":90}]") && json.contains("\"next_cursor\":8"));
+    }
+}
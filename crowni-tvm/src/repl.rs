@@ -0,0 +1,527 @@
+// ═══════════════════════════════════════════════════════════════
+// REPL 세션 — 한 줄 입력 → 출력 줄 목록 (대화형·스크립트 공용)
+//
+//   대화형: crowni-tvm repl [--record 기록.crs]
+//   일괄:   crowni-tvm repl --script 기록.crs [--update]
+//
+//   .crs 형식 — 한 줄에 입력 하나, 그 아래 "#=> " 줄이 기대 출력
+//     # 주석
+//     넣어 10
+//     넣어 20
+//     더해
+//     보여줘
+//     #=> 30
+//   기대 줄이 없는 입력은 "출력 없음"을 기대한다
+//
+// 한선어 블록 — 만약/함수/반복/동안 줄이 { 를 열면 닫힐 때까지 모아
+// 한선어로 컴파일해 같은 VM에서 실행 (변수 슬롯은 세션 내내 유지)
+//   크라운> 변수 x = 5
+//   크라운> 만약 x > 3 {
+//     ..> x 보여줘
+//     ..> }
+//   아니면은 닫는 } 와 같은 줄에: "} 아니면 {"
+// ═══════════════════════════════════════════════════════════════
+
+use std::collections::HashMap;
+use crate::assembler::assemble;
+use crate::hanseon::HanseonCompiler;
+use crate::opcode;
+use crate::value::Value;
+use crate::vm::{self, TVM};
+
+pub const PROMPT: &str = "크라운> ";
+/// 블록이 열려 있는 동안의 프롬프트
+pub const CONTINUE_PROMPT: &str = "    ..> ";
+/// 기대 출력 줄 접두사
+pub const EXPECT_PREFIX: &str = "#=>";
+pub const HELP: &str = "명령어: .stack .regs .heap .vars .dump .debug .run .reset .info .help exit";
+/// 메타 명령어 (한글 별칭 포함) — 탭 완성 대상
+pub const META_COMMANDS: [&str; 24] = [
+    ".stack", ".스택", ".regs", ".레지스터", ".heap", ".힙", ".vars", ".변수", ".dump", ".덤프",
+    ".debug", ".디버그", ".run", ".실행", ".reset", ".초기화", ".info", ".정보", ".help", ".도움",
+    "exit", "quit", "나가", "종료해",
+];
+
+/// 입력 한 줄의 결과
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub lines: Vec<String>,
+    pub exit: bool,
+}
+
+impl Step {
+    fn lines(lines: Vec<String>) -> Self {
+        Self { lines, exit: false }
+    }
+}
+
+/// 블록을 여는 한선어 키워드 — { 가 있어야 블록 (반복 · 함수는 어셈블리 니모닉이기도 하다)
+const BLOCK_KEYWORDS: [&str; 10] = ["만약", "if", "함수", "func", "fn", "반복", "loop", "repeat", "동안", "while"];
+/// 한 줄짜리 한선어 문장
+const STATEMENT_KEYWORDS: [&str; 3] = ["변수", "var", "let"];
+
+/// VM + 여러 줄 버퍼 — 프로그램 출력은 captured로 모아 Step에 담는다
+pub struct ReplSession {
+    pub vm: TVM,
+    buffer: String,
+    /// 모으는 중인 한선어 블록과 열린 { 수
+    block: String,
+    depth: usize,
+    /// 한선어 변수 이름 → 전역 슬롯
+    vars: HashMap<String, u32>,
+}
+
+impl ReplSession {
+    pub fn new() -> Self {
+        Self { vm: TVM::new(), buffer: String::new(), block: String::new(), depth: 0, vars: HashMap::new() }
+    }
+
+    /// 블록이 닫히기를 기다리는 중 — 프롬프트를 CONTINUE_PROMPT로
+    pub fn in_block(&self) -> bool {
+        !self.block.is_empty()
+    }
+
+    /// 모으던 블록 버리기 (Ctrl-C) — 버린 것이 있으면 true
+    pub fn cancel_block(&mut self) -> bool {
+        self.depth = 0;
+        !std::mem::take(&mut self.block).is_empty()
+    }
+
+    pub fn eval(&mut self, line: &str) -> Step {
+        let line = line.trim();
+        if self.in_block() {
+            return self.continue_block(line);
+        }
+        if line.is_empty() {
+            return Step::lines(Vec::new());
+        }
+        let first = line.split_whitespace().next().unwrap_or("");
+        if BLOCK_KEYWORDS.contains(&first) && line.contains('{') {
+            return self.continue_block(line);
+        }
+        if STATEMENT_KEYWORDS.contains(&first) {
+            return Step::lines(self.run_hanseon(line));
+        }
+
+        // 메타 명령어
+        match line {
+            "exit" | "quit" | "나가" | "종료해" => return Step { lines: Vec::new(), exit: true },
+            ".stack" | ".스택" => return Step::lines(self.vm.stack_lines()),
+            ".regs" | ".레지스터" => return Step::lines(self.vm.register_lines()),
+            ".heap" | ".힙" => return Step::lines(self.vm.heap.dump_lines()),
+            ".vars" | ".변수" => return Step::lines(self.var_lines()),
+            ".dump" | ".덤프" => return Step::lines(self.vm.dump_lines()),
+            ".debug" | ".디버그" => {
+                self.vm.debug = !self.vm.debug;
+                return Step::lines(vec![format!("디버그 모드: {}", if self.vm.debug { "ON" } else { "OFF" })]);
+            }
+            ".reset" | ".초기화" => {
+                self.vm = TVM::new();
+                return Step::lines(vec!["VM 초기화 완료".into()]);
+            }
+            ".info" | ".정보" => return Step::lines(opcode::catalog_lines()),
+            ".help" | ".도움" => return Step::lines(vec![HELP.into()]),
+            ".run" | ".실행" => return Step::lines(self.run_buffer()),
+            _ => {}
+        }
+
+        // 즉시 실행 모드: 한 줄을 바로 실행
+        let program = assemble(line);
+        if program.is_empty() {
+            // 어셈블 실패 → 버퍼에 추가
+            self.buffer.push_str(line);
+            self.buffer.push('\n');
+            return Step::lines(Vec::new());
+        }
+
+        // 기존 프로그램 뒤에 추가 실행
+        let old_prog_len = self.vm.program.len();
+        self.vm.program.extend(program);
+        self.vm.ip = old_prog_len;
+        self.vm.halted = false;
+
+        let (mut lines, result) = self.run_captured();
+        match result {
+            Ok(()) => {}
+            Err(e) if matches!(e.kind, vm::VmErrorKind::Halted) => {}
+            // 이번 줄에서 난 오류만 소스를 붙인다 (이전 입력의 줄 번호는 그 입력 기준)
+            Err(e) => {
+                let source = (e.ip >= old_prog_len).then_some(line);
+                lines.extend(e.diagnostic("<입력>", source).lines().map(String::from));
+            }
+        }
+        Step::lines(lines)
+    }
+
+    /// 한선어 변수(슬롯 순) + 이름으로 저장한 전역 (저장해 "이름")
+    pub fn var_lines(&self) -> Vec<String> {
+        let mut named: Vec<(&String, &u32)> = self.vars.iter().collect();
+        named.sort_by_key(|&(_, slot)| *slot);
+        let mut rows: Vec<String> = named.into_iter().map(|(name, slot)| {
+            match self.vm.globals.get(&format!("#{}", slot)) {
+                Some(v) => format!("║ {} = {} ({}, #{}) ║", name, v, v.type_name_kr(), slot),
+                None => format!("║ {} = (값 없음, #{}) ║", name, slot),
+            }
+        }).collect();
+        let mut globals: Vec<(&String, &Value)> = self.vm.globals.iter().filter(|(k, _)| !k.starts_with('#')).collect();
+        globals.sort_by(|a, b| a.0.cmp(b.0));
+        rows.extend(globals.into_iter().map(|(k, v)| format!("║ \"{}\" = {} ({}) ║", k, v, v.type_name_kr())));
+
+        let mut lines = vec![format!("╔══ 변수 ({}) ══╗", rows.len())];
+        lines.extend(rows);
+        lines.push("╚════════════════════════╝".into());
+        lines
+    }
+
+    /// 탭 완성 단어 — 메타 명령어 · 등록된 명령어 니모닉(한글 · 영문) · 한선어 변수 이름
+    pub fn completion_words(&self) -> Vec<String> {
+        let mut words: Vec<String> = META_COMMANDS.iter().map(|s| s.to_string()).collect();
+        for meta in opcode::build_opcodes().values() {
+            words.push(meta.name_kr.to_string());
+            words.push(meta.name_en.to_string());
+        }
+        words.extend(self.vars.keys().cloned());
+        words.sort();
+        words.dedup();
+        words
+    }
+
+    fn continue_block(&mut self, line: &str) -> Step {
+        self.block.push_str(line);
+        self.block.push('\n');
+        self.depth = brace_depth(&self.block);
+        if self.depth > 0 {
+            return Step::lines(Vec::new());
+        }
+        let source = std::mem::take(&mut self.block);
+        Step::lines(self.run_hanseon(&source))
+    }
+
+    /// 한선어 컴파일 → 같은 VM에 이어 실행 (스택 · 힙 · 전역 유지)
+    fn run_hanseon(&mut self, source: &str) -> Vec<String> {
+        let out = HanseonCompiler::new(source).with_vars(self.vars.clone()).compile();
+        if !out.errors.is_empty() {
+            return out.errors.iter().map(|e| format!("[한선어] {}", e)).collect();
+        }
+        self.vars = out.var_slots;
+        self.vm.load_continue(out.instructions);
+        let (mut lines, result) = self.run_captured();
+        match result {
+            Ok(()) => {}
+            Err(e) if matches!(e.kind, vm::VmErrorKind::Halted) => {}
+            Err(e) => lines.extend(e.diagnostic("<블록>", Some(source)).lines().map(String::from)),
+        }
+        lines
+    }
+
+    fn run_buffer(&mut self) -> Vec<String> {
+        if self.buffer.is_empty() {
+            return vec!["버퍼가 비어있습니다. 명령어를 입력하세요.".into()];
+        }
+        let source = std::mem::take(&mut self.buffer);
+        let program = assemble(&source);
+        if program.is_empty() {
+            return Vec::new();
+        }
+        let mut lines = vec![format!("--- {} 명령어 실행 ---", program.len())];
+        self.vm.load(program);
+        let (out, result) = self.run_captured();
+        lines.extend(out);
+        match result {
+            Ok(()) => lines.push(format!("--- 정상 종료 ({}사이클) ---", self.vm.cycles)),
+            Err(e) => lines.extend(e.diagnostic("<버퍼>", Some(&source)).lines().map(String::from)),
+        }
+        lines
+    }
+
+    fn run_captured(&mut self) -> (Vec<String>, Result<(), vm::VmError>) {
+        self.vm.captured = Some(Vec::new());
+        let result = self.vm.run();
+        (self.vm.captured.take().unwrap_or_default(), result)
+    }
+}
+
+/// 문자열 밖의 { } 짝 — 닫는 쪽이 많으면 0
+fn brace_depth(source: &str) -> usize {
+    let (mut depth, mut in_str, mut escaped) = (0usize, false, false);
+    for c in source.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_str => escaped = true,
+            '"' => in_str = !in_str,
+            '{' if !in_str => depth += 1,
+            '}' if !in_str => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    depth
+}
+
+// ═══════════════════════════════════════
+// 스크립트 (.crs)
+// ═══════════════════════════════════════
+
+/// 입력 한 줄 + 그 아래 기대 출력 (line은 1부터)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptEntry {
+    pub line: usize,
+    pub input: String,
+    pub expected: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptItem {
+    /// 주석·빈 줄 원문 — --update 때 그대로 보존
+    Text(String),
+    Input(ScriptEntry),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub items: Vec<ScriptItem>,
+}
+
+/// 기록 파일 머리말
+pub fn header() -> String {
+    "# 크라우닌 REPL 기록 — crowni-tvm repl --script <파일> 로 재생\n".to_string()
+}
+
+/// 입력 하나와 출력 줄들을 .crs 조각으로
+pub fn transcript(input: &str, lines: &[String]) -> String {
+    let mut out = format!("{}\n", input.trim());
+    for l in lines {
+        if l.is_empty() {
+            out.push_str(&format!("{}\n", EXPECT_PREFIX));
+        } else {
+            out.push_str(&format!("{} {}\n", EXPECT_PREFIX, l));
+        }
+    }
+    out
+}
+
+impl Script {
+    pub fn parse(source: &str) -> Self {
+        let mut items = Vec::new();
+        for (i, raw) in source.lines().enumerate() {
+            if let Some(rest) = raw.strip_prefix(EXPECT_PREFIX) {
+                let expected = rest.strip_prefix(' ').unwrap_or(rest).to_string();
+                match items.last_mut() {
+                    Some(ScriptItem::Input(entry)) => entry.expected.push(expected),
+                    // 입력 없는 기대 줄은 주석 취급
+                    _ => items.push(ScriptItem::Text(raw.to_string())),
+                }
+            } else if raw.trim().is_empty() || raw.trim_start().starts_with('#') {
+                items.push(ScriptItem::Text(raw.to_string()));
+            } else {
+                items.push(ScriptItem::Input(ScriptEntry { line: i + 1, input: raw.trim().to_string(), expected: Vec::new() }));
+            }
+        }
+        Self { items }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &ScriptEntry> {
+        self.items.iter().filter_map(|item| match item {
+            ScriptItem::Input(entry) => Some(entry),
+            ScriptItem::Text(_) => None,
+        })
+    }
+
+    /// 새 세션에서 순서대로 실행 — exit 이후 입력은 실행하지 않음
+    pub fn run(&self) -> ScriptRun {
+        let mut session = ReplSession::new();
+        let mut results = Vec::new();
+        for entry in self.entries() {
+            let step = session.eval(&entry.input);
+            results.push(EntryResult {
+                line: entry.line,
+                input: entry.input.clone(),
+                expected: entry.expected.clone(),
+                actual: step.lines,
+            });
+            if step.exit {
+                break;
+            }
+        }
+        ScriptRun { results, total: self.entries().count() }
+    }
+
+    /// 기대 출력을 실제 출력으로 교체한 원문 (--update)
+    pub fn render(&self, run: &ScriptRun) -> String {
+        let mut out = String::new();
+        let mut results = run.results.iter();
+        for item in &self.items {
+            match item {
+                ScriptItem::Text(t) => {
+                    out.push_str(t);
+                    out.push('\n');
+                }
+                ScriptItem::Input(entry) => match results.next() {
+                    Some(r) => out.push_str(&transcript(&entry.input, &r.actual)),
+                    None => out.push_str(&transcript(&entry.input, &entry.expected)),
+                },
+            }
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryResult {
+    pub line: usize,
+    pub input: String,
+    pub expected: Vec<String>,
+    pub actual: Vec<String>,
+}
+
+impl EntryResult {
+    pub fn passed(&self) -> bool {
+        self.expected == self.actual
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptRun {
+    pub results: Vec<EntryResult>,
+    /// 스크립트의 전체 입력 수 (exit로 끊기면 results보다 많다)
+    pub total: usize,
+}
+
+impl ScriptRun {
+    pub fn failures(&self) -> Vec<&EntryResult> {
+        self.results.iter().filter(|r| !r.passed()).collect()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.total - self.results.len()
+    }
+
+    /// P: 모두 일치 / T: 불일치 있음
+    pub fn state(&self) -> i8 {
+        if self.failures().is_empty() { 1 } else { -1 }
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_meta_and_buffer() {
+        let mut s = ReplSession::new();
+        assert!(s.eval("넣어 10").lines.is_empty());
+        assert!(s.eval("넣어 20").lines.is_empty());
+        assert!(s.eval("더해").lines.is_empty());
+        assert_eq!(s.eval("보여줘").lines, vec!["30"]);
+        assert_eq!(s.eval(".debug").lines, vec!["디버그 모드: ON"]);
+        assert_eq!(s.eval(".디버그").lines, vec!["디버그 모드: OFF"]);
+
+        s.eval("넣어 7");
+        let stack = s.eval(".stack").lines;
+        assert_eq!(stack[0], "╔══ 스택 (깊이: 1) ══╗");
+        assert_eq!(s.eval(".reset").lines, vec!["VM 초기화 완료"]);
+        assert_eq!(s.eval(".stack").lines[0], "╔══ 스택 (깊이: 0) ══╗");
+        assert_eq!(s.eval(".run").lines, vec!["버퍼가 비어있습니다. 명령어를 입력하세요."]);
+        assert!(s.eval("종료해").exit);
+    }
+
+    #[test]
+    fn test_session_error_diagnostic() {
+        let mut s = ReplSession::new();
+        let lines = s.eval("빼").lines;
+        assert!(lines[0].starts_with("오류: "));
+        assert_eq!(lines[1], "  --> <입력>:1 (IP 0 · 빼)");
+        assert_eq!(lines.last().unwrap(), "   | ^");
+
+        // 이전 입력의 명령어로 되돌아가 난 오류에는 이번 줄 소스를 붙이지 않는다
+        s.eval("넣어 1");
+        let lines = s.eval("점프 0").lines;
+        assert_eq!(lines[1], "  --> <입력>:1 (IP 0 · 빼)");
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[test]
+    fn test_hanseon_block_spans_lines() {
+        let mut s = ReplSession::new();
+        assert!(s.eval("변수 x = 5").lines.is_empty());
+        assert!(s.eval("만약 x > 3 {").lines.is_empty());
+        assert!(s.in_block());
+        assert!(s.eval("  \"{큼}\" 보여줘").lines.is_empty());
+        assert_eq!(s.eval("} 아니면 { \"작음\" 보여줘 }").lines, vec!["\"{큼}\""]);
+        assert!(!s.in_block());
+
+        // 블록 밖 어셈블리와 같은 VM · 변수는 다음 블록에서도 보인다
+        s.eval("넣어 1");
+        assert_eq!(s.eval("반복 2 { x 보여줘 }").lines, vec!["5", "5"]);
+        assert_eq!(s.eval("보여줘").lines, vec!["1"]);
+
+        s.eval("동안 참 {");
+        assert!(s.cancel_block() && !s.in_block());
+        assert_eq!(s.eval("만약 y { }").lines, vec!["[한선어] 정의되지 않은 변수: y"]);
+    }
+
+    #[test]
+    fn test_vars_and_completion_words() {
+        let mut s = ReplSession::new();
+        assert_eq!(s.eval(".vars").lines, vec!["╔══ 변수 (0) ══╗", "╚════════════════════════╝"]);
+        s.eval("변수 합 = 2 + 3");
+        s.eval("변수 이름 = \"크라운\"");
+        s.eval("넣어 \"설정\"");
+        s.eval("넣어 9");
+        s.eval("저장해");
+        assert_eq!(s.eval(".변수").lines[1..4], [
+            "║ 합 = 5 (정수, #0) ║",
+            "║ 이름 = \"크라운\" (문자열, #1) ║",
+            "║ \"설정\" = 9 (정수) ║",
+        ]);
+
+        // 니모닉은 build_opcodes()에서 — 등록된 명령어가 곧 완성 후보
+        let words = s.completion_words();
+        for meta in opcode::build_opcodes().values() {
+            assert!(words.iter().any(|w| w == meta.name_kr) && words.iter().any(|w| w == meta.name_en), "{}", meta.name_kr);
+        }
+        for w in [".vars", ".변수", "exit", "합", "이름"] {
+            assert!(words.iter().any(|x| x == w), "{}", w);
+        }
+        // 메타 명령어 목록은 모두 실제로 처리된다 (버퍼로 새지 않음)
+        for cmd in META_COMMANDS.iter().filter(|c| c.starts_with('.') && !c.ends_with("run") && **c != ".실행") {
+            assert!(!ReplSession::new().eval(cmd).lines.is_empty(), "{}", cmd);
+        }
+    }
+
+    #[test]
+    fn test_script_detects_mismatch() {
+        let src = "# 덧셈\n넣어 1\n넣어 2\n더해\n보여줘\n#=> 3\n\n넣어 5\n보여줘\n#=> 6\n.help\n#=> 명령어: .stack .regs .heap .vars .dump .debug .run .reset .info .help exit\nexit\n보여줘\n";
+        let script = Script::parse(src);
+        assert_eq!(script.entries().count(), 9);
+        let run = script.run();
+        assert_eq!(run.state(), -1);
+        let failures = run.failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].line, failures[0].actual.clone()), (9, vec!["5".to_string()]));
+        // exit 뒤의 입력은 실행되지 않는다
+        assert_eq!(run.skipped(), 1);
+
+        // --update 후에는 통과, 주석과 빈 줄은 보존
+        let updated = script.render(&run);
+        assert!(updated.starts_with("# 덧셈\n"));
+        assert!(updated.contains("보여줘\n#=> 5\n"));
+        assert_eq!(Script::parse(&updated).run().state(), 1);
+    }
+
+    #[test]
+    fn test_recorded_session_replays() {
+        let inputs = ["넣어 999", "할당", "복사", "읽어", "보여줘", ".heap", ".info", ".dump", "넣어 ((", ".run"];
+        let mut session = ReplSession::new();
+        let mut recorded = header();
+        for input in inputs {
+            let step = session.eval(input);
+            recorded.push_str(&transcript(input, &step.lines));
+        }
+        assert!(recorded.contains("보여줘\n#=> 999\n"));
+        let run = Script::parse(&recorded).run();
+        assert_eq!(run.results.len(), inputs.len());
+        assert_eq!(run.state(), 1, "{:?}", run.failures());
+    }
+}
@@ -0,0 +1,239 @@
+// ═══════════════════════════════════════════════════════════════
+// 기록 링 버퍼 — 장기 실행 프로세스의 기록 상한
+// CAR 작업 기록 · 노드 메시지 로그처럼 계속 쌓이는 Vec을 대체
+//
+//   용량 초과 → 가장 오래된 항목 축출 (TritStore로 내보내기 선택)
+//   조회는 절대 인덱스 기준 — 저장소 + 메모리를 이어서 읽는다
+//
+//   [저장소: spill_from .. first) [메모리: first .. total)
+//   저장 키: {prefix}:{index:010}
+// ═══════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+
+use crate::query::{Page, Query, Queryable};
+use crate::trit_store::{StoreValue, TritStore};
+
+pub const DEFAULT_CAPACITY: usize = 10_000;
+/// 노드 메시지 로그 기본 상한 — 하트비트가 계속 쌓이므로 (crowny.toml [node] message_log)
+pub const MESSAGE_LOG_CAPACITY: usize = 1024;
+
+/// 저장소로 내보낼 수 있는 기록
+pub trait Spill: Sized {
+    fn to_store(&self) -> StoreValue;
+    fn from_store(value: &StoreValue) -> Option<Self>;
+}
+
+struct SpillTarget<T> {
+    store: TritStore,
+    prefix: String,
+    /// 내보내기를 켠 시점의 절대 인덱스 — 그 이전 축출분은 없음
+    from: u64,
+    encode: fn(&T) -> StoreValue,
+    decode: fn(&StoreValue) -> Option<T>,
+}
+
+/// 용량 제한 기록
+pub struct RingLog<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    /// 메모리 첫 항목의 절대 인덱스 (= 지금까지 축출된 수)
+    first: u64,
+    /// Box — TritStore의 WAL도 RingLog라 크기가 재귀한다
+    spill: Option<Box<SpillTarget<T>>>,
+}
+
+impl<T> RingLog<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self { items: VecDeque::new(), capacity: capacity.max(1), first: 0, spill: None }
+    }
+
+    /// 축출 항목을 저장소로 내보내기 — 이후 조회는 저장소까지 이어서 읽는다
+    /// 실행 중 내보내기 켜기 — 이미 축출된 항목은 복구되지 않음
+    pub fn spill_to(&mut self, store: TritStore, prefix: &str)
+    where
+        T: Spill,
+    {
+        self.spill = Some(Box::new(SpillTarget {
+            store,
+            prefix: prefix.into(),
+            from: self.first,
+            encode: T::to_store,
+            decode: T::from_store,
+        }));
+    }
+
+    /// 용량 변경 — 줄이면 즉시 축출
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.items.len() > self.capacity {
+            self.evict();
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.items.len() >= self.capacity {
+            self.evict();
+        }
+        self.items.push_back(item);
+    }
+
+    fn evict(&mut self) {
+        let Some(old) = self.items.pop_front() else { return };
+        if let Some(sp) = &mut self.spill {
+            let key = format!("{}:{:010}", sp.prefix, self.first);
+            sp.store.set(&key, (sp.encode)(&old));
+        }
+        self.first += 1;
+    }
+
+    /// 메모리에 있는 항목 수
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 다음에 넣을 항목의 절대 인덱스 (= 지금까지 넣은 수)
+    pub fn end_index(&self) -> u64 {
+        self.first + self.items.len() as u64
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// 메모리 항목 비우기 — 절대 인덱스는 이어진다 (저장소로 내보내지 않음)
+    pub fn clear(&mut self) {
+        self.first = self.end_index();
+        self.items.clear();
+    }
+
+    /// 읽을 수 있는 가장 오래된 절대 인덱스
+    pub fn oldest_index(&self) -> u64 {
+        self.spill.as_ref().map_or(self.first, |sp| sp.from)
+    }
+
+    /// 저장소로 내보낸 수
+    pub fn spilled(&self) -> u64 {
+        self.spill.as_ref().map_or(0, |sp| self.first - sp.from)
+    }
+
+    /// 메모리 항목만 (오래된 순)
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// 메모리 항목 수정 (관리자 덮어쓰기) — 저장소로 내보낸 항목은 불변
+    pub fn iter_mut(&mut self) -> std::collections::vec_deque::IterMut<'_, T> {
+        self.items.iter_mut()
+    }
+
+    fn load(&self, index: u64) -> Option<T> {
+        let sp = self.spill.as_ref()?;
+        if index < sp.from || index >= self.first {
+            return None;
+        }
+        sp.store.peek(&format!("{}:{:010}", sp.prefix, index)).and_then(sp.decode)
+    }
+}
+
+impl<T: Clone> RingLog<T> {
+    /// index 이후 전부 (저장소 + 메모리)
+    pub fn since(&self, index: u64) -> Vec<T> {
+        let start = index.max(self.oldest_index());
+        let mut out: Vec<T> = (start..self.first).filter_map(|i| self.load(i)).collect();
+        let skip = start.saturating_sub(self.first) as usize;
+        out.extend(self.items.iter().skip(skip).cloned());
+        out
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.since(0)
+    }
+
+    /// 공통 질의 — key는 기록의 커서 키 (기본은 절대 인덱스)
+    pub fn page(&self, q: &Query, key: impl Fn(u64, &T) -> u64) -> Page<T>
+    where
+        T: Queryable,
+    {
+        let start = self.oldest_index();
+        let all = self.to_vec();
+        q.page(all.iter().enumerate().map(|(i, x)| (key(start + i as u64, x), x))).map(T::clone)
+    }
+}
+
+impl<T> Default for RingLog<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl<T> std::fmt::Debug for RingLog<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RingLog")
+            .field("capacity", &self.capacity)
+            .field("len", &self.items.len())
+            .field("first", &self.first)
+            .field("spilled", &self.spilled())
+            .finish()
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Rec(u64);
+
+    impl Spill for Rec {
+        fn to_store(&self) -> StoreValue { StoreValue::Int(self.0 as i64) }
+        fn from_store(value: &StoreValue) -> Option<Self> {
+            match value { StoreValue::Int(n) => Some(Rec(*n as u64)), _ => None }
+        }
+    }
+
+    impl Queryable for Rec {
+        fn timestamp(&self) -> u64 { self.0 }
+        fn trit(&self) -> i8 { 1 }
+        fn involves(&self, _account: &str) -> bool { true }
+    }
+
+    #[test]
+    fn test_bounded_without_store() {
+        let mut log = RingLog::new(3);
+        for i in 0..10 { log.push(Rec(i)); }
+        assert_eq!((log.len(), log.oldest_index()), (3, 7));
+        assert_eq!(log.since(2), vec![Rec(7), Rec(8), Rec(9)]);
+        assert_eq!(log.since(8), vec![Rec(8), Rec(9)]);
+        assert_eq!(log.to_vec(), vec![Rec(7), Rec(8), Rec(9)]);
+        log.set_capacity(1);
+        assert_eq!(log.iter().collect::<Vec<_>>(), vec![&Rec(9)]);
+        assert_eq!((log.end_index(), log.last()), (10, Some(&Rec(9))));
+        // 비워도 절대 인덱스는 이어진다
+        log.clear();
+        log.push(Rec(10));
+        assert_eq!((log.len(), log.oldest_index(), log.end_index()), (1, 10, 11));
+        assert_eq!(log.since(0), vec![Rec(10)]);
+    }
+
+    #[test]
+    fn test_spill_reads_across_store() {
+        let mut log = RingLog::new(4);
+        log.spill_to(TritStore::new(), "rec");
+        for i in 0..10 { log.push(Rec(i * 10)); }
+        assert_eq!((log.len(), log.spilled()), (4, 6));
+        assert_eq!(log.since(0).first(), Some(&Rec(0)));
+        assert_eq!(log.since(4).len(), 6);
+        assert_eq!(log.to_vec().len(), 10);
+
+        let page = log.page(&Query::new().limit(3).order(crate::query::SortOrder::Asc).cursor(4), |i, _| i);
+        assert_eq!(page.items, vec![Rec(40), Rec(50), Rec(60)]);
+        assert_eq!(page.next_cursor, Some(7));
+    }
+}
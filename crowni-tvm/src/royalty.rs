@@ -0,0 +1,194 @@
+// ═══════════════════════════════════════════════════════════════
+// 로열티 레지스트리 — 모든 판매 경로가 같은 규칙으로 정산
+//
+//   경로: 마켓 판매 · 경매 낙찰 (통화 무관 — 금액만 계산, 외부 마켓 어댑터도 같은 settle로)
+//   정책: 로열티율 (bps) + 수령자 분배 (지분 bps 합 = 10000, 예: 70/30)
+//   우선: 컬렉션 재정의 > 민트 시점 기본값 (NFT 창작자 100%)
+//   보존: 수수료 + 로열티 합 + 판매자 몫 = 가격 (나머지 1단위는 첫 수령자)
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::output::JsonObject;
+
+pub const FULL_SHARE_BPS: u64 = 10_000;
+/// 로열티율 상한 (50%)
+pub const MAX_ROYALTY_BPS: u64 = 5_000;
+
+/// 로열티 정책 — 비율 + 분배
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoyaltyPolicy {
+    pub rate_bps: u64,
+    /// (수령자, 지분 bps)
+    pub splits: Vec<(String, u64)>,
+}
+
+impl RoyaltyPolicy {
+    /// 한 명에게 전부
+    pub fn single(recipient: &str, rate_bps: u64) -> Self {
+        Self { rate_bps, splits: vec![(recipient.to_string(), FULL_SHARE_BPS)] }
+    }
+
+    pub fn split(rate_bps: u64, splits: &[(&str, u64)]) -> Result<Self, String> {
+        let policy = Self { rate_bps, splits: splits.iter().map(|(r, s)| (r.to_string(), *s)).collect() };
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rate_bps > MAX_ROYALTY_BPS {
+            return Err(format!("로열티율 {}bps > 상한 {}bps", self.rate_bps, MAX_ROYALTY_BPS));
+        }
+        if self.splits.is_empty() {
+            return Err("로열티 수령자 없음".into());
+        }
+        if self.splits.iter().any(|(r, s)| r.is_empty() || *s == 0) {
+            return Err("수령자 이름과 지분은 비어 있을 수 없음".into());
+        }
+        let total: u64 = self.splits.iter().map(|(_, s)| s).sum();
+        if total != FULL_SHARE_BPS {
+            return Err(format!("지분 합 {}bps ≠ {}bps", total, FULL_SHARE_BPS));
+        }
+        Ok(())
+    }
+
+    /// 로열티 총액을 지분대로 분배 — 내림 후 나머지는 첫 수령자
+    pub fn distribute(&self, royalty: u64) -> Vec<(String, u64)> {
+        let mut out: Vec<(String, u64)> = self.splits.iter()
+            .map(|(r, s)| (r.clone(), (royalty as u128 * *s as u128 / FULL_SHARE_BPS as u128) as u64))
+            .collect();
+        let paid: u64 = out.iter().map(|(_, a)| a).sum();
+        if let Some(first) = out.first_mut() {
+            first.1 += royalty - paid;
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .int("rate_bps", self.rate_bps as i64)
+            .objects("splits", self.splits.iter()
+                .map(|(r, s)| JsonObject::new().str("recipient", r).int("share_bps", *s as i64))
+                .collect())
+    }
+}
+
+/// 판매 정산 결과
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settlement {
+    pub price: u64,
+    pub fee: u64,
+    pub royalties: Vec<(String, u64)>,
+    pub seller_receives: u64,
+}
+
+impl Settlement {
+    pub fn royalty_total(&self) -> u64 {
+        self.royalties.iter().map(|(_, a)| a).sum()
+    }
+
+    /// 보존 검사 — 한 단위도 새거나 생기지 않음
+    pub fn is_balanced(&self) -> bool {
+        self.fee + self.royalty_total() + self.seller_receives == self.price
+    }
+}
+
+pub type SharedRoyalties = Rc<RefCell<RoyaltyRegistry>>;
+
+pub fn shared() -> SharedRoyalties {
+    Rc::new(RefCell::new(RoyaltyRegistry::new()))
+}
+
+/// 컬렉션별 로열티 재정의
+#[derive(Debug, Default)]
+pub struct RoyaltyRegistry {
+    overrides: HashMap<String, RoyaltyPolicy>,
+}
+
+impl RoyaltyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_policy(&mut self, collection_id: &str, policy: RoyaltyPolicy) -> Result<(), String> {
+        policy.validate()?;
+        self.overrides.insert(collection_id.to_string(), policy);
+        Ok(())
+    }
+
+    pub fn clear_policy(&mut self, collection_id: &str) -> bool {
+        self.overrides.remove(collection_id).is_some()
+    }
+
+    /// 적용 정책 — 재정의가 없으면 기본값
+    pub fn policy_for(&self, collection_id: &str, default: RoyaltyPolicy) -> RoyaltyPolicy {
+        self.overrides.get(collection_id).cloned().unwrap_or(default)
+    }
+
+    /// 판매 정산 — 수수료 · 로열티가 가격을 넘으면 거부
+    pub fn settle(&self, collection_id: &str, default: RoyaltyPolicy, price: u64, fee_bps: u64) -> Result<Settlement, String> {
+        let policy = self.policy_for(collection_id, default);
+        policy.validate()?;
+        let fee = (price as u128 * fee_bps as u128 / FULL_SHARE_BPS as u128) as u64;
+        let royalty = (price as u128 * policy.rate_bps as u128 / FULL_SHARE_BPS as u128) as u64;
+        let seller_receives = price.checked_sub(fee + royalty)
+            .ok_or_else(|| format!("수수료 {} + 로열티 {} > 가격 {}", fee, royalty, price))?;
+        let settlement = Settlement { price, fee, royalties: policy.distribute(royalty), seller_receives };
+        debug_assert!(settlement.is_balanced());
+        Ok(settlement)
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_validation() {
+        assert!(RoyaltyPolicy::split(500, &[("a", 7_000), ("b", 3_000)]).is_ok());
+        assert!(RoyaltyPolicy::split(500, &[("a", 7_000), ("b", 2_000)]).is_err());
+        assert!(RoyaltyPolicy::split(500, &[]).is_err());
+        assert!(RoyaltyPolicy::split(500, &[("a", 10_000), ("b", 0)]).is_err());
+        assert!(RoyaltyPolicy::split(MAX_ROYALTY_BPS + 1, &[("a", 10_000)]).is_err());
+    }
+
+    #[test]
+    fn test_override_beats_default() {
+        let mut reg = RoyaltyRegistry::new();
+        let default = RoyaltyPolicy::single("alice", 1_000);
+        let s = reg.settle("col", default.clone(), 10_000, 250).unwrap();
+        assert_eq!(s.royalties, vec![("alice".to_string(), 1_000)]);
+
+        reg.set_policy("col", RoyaltyPolicy::split(500, &[("alice", 7_000), ("bob", 3_000)]).unwrap()).unwrap();
+        let s = reg.settle("col", default.clone(), 10_000, 250).unwrap();
+        assert_eq!(s.royalties, vec![("alice".to_string(), 350), ("bob".to_string(), 150)]);
+        assert_eq!((s.fee, s.seller_receives), (250, 9_250));
+        assert_eq!(reg.settle("other", default.clone(), 10_000, 250).unwrap().royalty_total(), 1_000);
+        assert!(reg.clear_policy("col"));
+        assert!(reg.settle("col", RoyaltyPolicy::single("a", 5_000), 100, 6_000).is_err());
+    }
+
+    #[test]
+    fn test_settlement_conserves_every_price() {
+        let policies = [
+            RoyaltyPolicy::single("solo", 1_000),
+            RoyaltyPolicy::split(777, &[("a", 7_000), ("b", 3_000)]).unwrap(),
+            RoyaltyPolicy::split(MAX_ROYALTY_BPS, &[("a", 3_333), ("b", 3_333), ("c", 3_334)]).unwrap(),
+            RoyaltyPolicy::split(1, &[("a", 1), ("b", 9_999)]).unwrap(),
+        ];
+        let reg = RoyaltyRegistry::new();
+        for policy in &policies {
+            for price in (0..2_000).chain([u64::MAX / 3, u64::MAX]) {
+                for fee_bps in [0, 250, 1_000] {
+                    let s = reg.settle("c", policy.clone(), price, fee_bps).unwrap();
+                    assert!(s.is_balanced(), "{:?} @ {} fee {}", policy, price, fee_bps);
+                    assert_eq!(s.royalty_total(), (price as u128 * policy.rate_bps as u128 / 10_000) as u64);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+// ═══════════════════════════════════════════════════════════════
+// JSON-RPC 2.0 — 컨트랙트 로그 질의 · 구독 (eth_* 호환 부분집합)
+//
+//   eth_blockNumber                      → "0x.."
+//   eth_getLogs          [filter]        → [log, ...]
+//   eth_newFilter        [filter]        → 필터 id (폴링)
+//   eth_getFilterChanges [id]            → 마지막 폴링 이후 새 로그
+//   eth_uninstallFilter  [id]            → bool
+//   eth_subscribe        ["logs", filter] → 구독 id (푸시 — notifications()로 전송)
+//   eth_unsubscribe      [id]            → bool
+//
+//   단건 · 배치 요청, id 없는 알림은 응답하지 않는다
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::contract_vm::ContractVM;
+use crate::event_log::{quantity, LogEntry, LogFilter, LogSubscriptions, SubscriptionKind};
+use crate::output::{array, escape, JsonObject, JsonValue};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// 범위 · 결과 수 한도 초과 (eth 관례)
+pub const LIMIT_EXCEEDED: i64 = -32005;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    fn params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+}
+
+fn response(id: &JsonValue, outcome: Result<String, RpcError>) -> String {
+    let head = JsonObject::new().str("jsonrpc", "2.0").raw("id", id.to_json());
+    match outcome {
+        Ok(result) => head.raw("result", result),
+        Err(e) => head.object("error", JsonObject::new().int("code", e.code).str("message", &e.message)),
+    }.build()
+}
+
+fn dispatch(req: &JsonValue, call: &mut impl FnMut(&str, &[JsonValue]) -> Result<String, RpcError>) -> Option<String> {
+    let id = req.get("id");
+    let method = match (req.get("jsonrpc").and_then(JsonValue::as_str), req.get("method").and_then(JsonValue::as_str)) {
+        (Some("2.0"), Some(m)) => m,
+        _ => return Some(response(id.unwrap_or(&JsonValue::Null), Err(RpcError::new(INVALID_REQUEST, "jsonrpc 2.0 요청이 아님")))),
+    };
+    let outcome = match req.get("params") {
+        None => call(method, &[]),
+        Some(JsonValue::Array(p)) => call(method, p),
+        Some(_) => Err(RpcError::params("params는 목록이어야 함")),
+    };
+    id.map(|id| response(id, outcome))
+}
+
+/// 요청 본문 처리 — 응답할 것이 없으면(알림뿐) None
+pub fn handle(body: &str, mut call: impl FnMut(&str, &[JsonValue]) -> Result<String, RpcError>) -> Option<String> {
+    let req = match JsonValue::parse(body) {
+        Ok(v) => v,
+        Err(e) => return Some(response(&JsonValue::Null, Err(RpcError::new(PARSE_ERROR, e)))),
+    };
+    match &req {
+        JsonValue::Array(batch) if batch.is_empty() => {
+            Some(response(&JsonValue::Null, Err(RpcError::new(INVALID_REQUEST, "빈 배치"))))
+        }
+        JsonValue::Array(batch) => {
+            let out: Vec<String> = batch.iter().filter_map(|r| dispatch(r, &mut call)).collect();
+            (!out.is_empty()).then(|| array(out))
+        }
+        single => dispatch(single, &mut call),
+    }
+}
+
+fn logs_json(logs: &[LogEntry]) -> String {
+    array(logs.iter().map(|l| l.to_json().build()).collect())
+}
+
+/// 컨트랙트 VM 로그에 대한 RPC 서비스
+pub struct LogRpc {
+    pub vm: Rc<RefCell<ContractVM>>,
+    pub subs: LogSubscriptions,
+}
+
+impl LogRpc {
+    pub fn new(vm: Rc<RefCell<ContractVM>>) -> Self {
+        Self { vm, subs: LogSubscriptions::new() }
+    }
+
+    fn filter(&self, params: &[JsonValue], at: usize) -> Result<LogFilter, RpcError> {
+        let latest = self.vm.borrow().block_h;
+        match params.get(at) {
+            None => Ok(LogFilter::new()),
+            Some(f) => LogFilter::from_json(f, latest).map_err(RpcError::params),
+        }
+    }
+
+    fn id(params: &[JsonValue]) -> Result<&str, RpcError> {
+        params.first().and_then(JsonValue::as_str).ok_or_else(|| RpcError::params("id 문자열 필요"))
+    }
+
+    pub fn call(&mut self, method: &str, params: &[JsonValue]) -> Result<String, RpcError> {
+        match method {
+            "eth_blockNumber" => Ok(escape(&quantity(self.vm.borrow().block_h))),
+            "eth_getLogs" => {
+                let mut filter = self.filter(params, 0)?;
+                // 범위 생략 = 최신 블록 (VM 현재 높이)
+                let latest = self.vm.borrow().block_h;
+                filter.from_block.get_or_insert(latest);
+                filter.to_block.get_or_insert(latest);
+                let vm = self.vm.borrow();
+                let page = vm.logs.query(&filter).map_err(|e| RpcError::new(LIMIT_EXCEEDED, e))?;
+                Ok(logs_json(&page.logs))
+            }
+            "eth_newFilter" => {
+                let filter = self.filter(params, 0)?;
+                Ok(escape(&self.subs.add(SubscriptionKind::Poll, filter, &self.vm.borrow().logs)))
+            }
+            "eth_getFilterChanges" => {
+                let id = Self::id(params)?;
+                let logs = self.subs.changes(id, &self.vm.borrow().logs).map_err(RpcError::params)?;
+                Ok(logs_json(&logs))
+            }
+            "eth_subscribe" => {
+                match params.first().and_then(JsonValue::as_str) {
+                    Some("logs") => {}
+                    Some(other) => return Err(RpcError::params(format!("지원하지 않는 구독 '{}' (logs만)", other))),
+                    None => return Err(RpcError::params("구독 종류 필요")),
+                }
+                let filter = self.filter(params, 1)?;
+                Ok(escape(&self.subs.add(SubscriptionKind::Push, filter, &self.vm.borrow().logs)))
+            }
+            "eth_uninstallFilter" | "eth_unsubscribe" => Ok(self.subs.remove(Self::id(params)?).to_string()),
+            other => Err(RpcError::new(METHOD_NOT_FOUND, format!("알 수 없는 메서드 '{}'", other))),
+        }
+    }
+
+    pub fn handle(&mut self, body: &str) -> Option<String> {
+        handle(body, |method, params| self.call(method, params))
+    }
+
+    /// 푸시 구독으로 보낼 새 알림 — 전송 계층(WebSocket 등)이 주기적으로 가져간다
+    pub fn notifications(&mut self) -> Vec<String> {
+        self.subs.notifications(&self.vm.borrow().logs)
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract_vm::{token_contract, ExecCtx};
+
+    fn ctx(caller: &str) -> ExecCtx {
+        ExecCtx { caller: caller.into(), value: 0, block_h: 0, gas_limit: 100_000, args: vec![] }
+    }
+
+    #[test]
+    fn test_envelope_errors_and_batches() {
+        let echo = |m: &str, p: &[JsonValue]| match m {
+            "echo" => Ok(p.first().map(JsonValue::to_json).unwrap_or("null".into())),
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, "없음")),
+        };
+        assert_eq!(handle(r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":["a"]}"#, echo).unwrap(),
+            r#"{"jsonrpc":"2.0","id":1,"result":"a"}"#);
+        assert!(handle("{oops", echo).unwrap().contains("-32700"));
+        assert!(handle(r#"{"id":"x","method":"echo"}"#, echo).unwrap().contains(r#""id":"x","error":{"code":-32600"#));
+        assert!(handle(r#"{"jsonrpc":"2.0","id":2,"method":"nope"}"#, echo).unwrap().contains("-32601"));
+        assert!(handle(r#"{"jsonrpc":"2.0","id":3,"method":"echo","params":{"a":1}}"#, echo).unwrap().contains("-32602"));
+        // 알림은 응답 없음 · 배치는 응답 목록
+        assert_eq!(handle(r#"{"jsonrpc":"2.0","method":"echo"}"#, echo), None);
+        assert_eq!(handle(r#"[{"jsonrpc":"2.0","id":1,"method":"echo","params":[1]},{"jsonrpc":"2.0","method":"echo"}]"#, echo).unwrap(),
+            r#"[{"jsonrpc":"2.0","id":1,"result":1}]"#);
+        assert!(handle("[]", echo).unwrap().contains("-32600"));
+    }
+
+    #[test]
+    fn test_get_logs_filters_and_subscriptions() {
+        let vm = Rc::new(RefCell::new(ContractVM::new()));
+        let (code, abi) = token_contract();
+        let token = vm.borrow_mut().deploy("T", "alice", code, abi);
+        let mut rpc = LogRpc::new(vm.clone());
+        let req = |method: &str, params: &str| format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{}","params":{}}}"#, method, params);
+
+        let filter_id = rpc.handle(&req("eth_newFilter", &format!(r#"[{{"address":"{}","topics":["Transfer"]}}]"#, token))).unwrap();
+        let filter_id = filter_id.split('"').nth(9).unwrap().to_string();
+        assert_eq!(filter_id, "0x1");
+        assert!(rpc.handle(&req("eth_subscribe", r#"["logs",{"topics":[null,"bob"]}]"#)).unwrap().contains(r#""result":"0x2""#));
+
+        for caller in ["alice", "bob", "alice"] {
+            vm.borrow_mut().advance_block();
+            vm.borrow_mut().call(&token, "transfer", ctx(caller));
+        }
+        assert!(rpc.handle(&req("eth_blockNumber", "[]")).unwrap().contains(r#""result":"0x6""#));
+
+        // alice의 Transfer, 블록 4~6
+        let resp = rpc.handle(&req("eth_getLogs", &format!(r#"[{{"fromBlock":"0x4","toBlock":"latest","address":["{}"],"topics":["Transfer","alice"]}}]"#, token))).unwrap();
+        assert_eq!(resp.matches(r#""event":"Transfer""#).count(), 2);
+        assert!(resp.contains(r#""blockNumber":"0x4""#) && resp.contains(r#""blockNumber":"0x6""#));
+        // 범위 생략 = 최신 블록만
+        let latest = rpc.handle(&req("eth_getLogs", "[{}]")).unwrap();
+        assert_eq!(latest.matches("\"blockNumber\"").count(), 1);
+        assert!(rpc.handle(&req("eth_getLogs", r#"[{"fromBlock":"0x0","toBlock":"0x989680"}]"#)).unwrap().contains("-32005"));
+        assert!(rpc.handle(&req("eth_getLogs", r#"[{"fromBlock":"soon"}]"#)).unwrap().contains("-32602"));
+
+        // 폴링 필터 · 푸시 구독
+        let changes = rpc.handle(&req("eth_getFilterChanges", &format!(r#"["{}"]"#, filter_id))).unwrap();
+        assert_eq!(changes.matches(r#""event":"Transfer""#).count(), 3);
+        assert_eq!(rpc.handle(&req("eth_getFilterChanges", &format!(r#"["{}"]"#, filter_id))).unwrap(), r#"{"jsonrpc":"2.0","id":1,"result":[]}"#);
+        let notes = rpc.notifications();
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains(r#""subscription":"0x2""#) && notes[0].contains(r#""topics":["Transfer","bob"]"#));
+        assert!(rpc.notifications().is_empty());
+        assert!(rpc.handle(&req("eth_unsubscribe", r#"["0x2"]"#)).unwrap().contains(r#""result":true"#));
+        assert!(rpc.handle(&req("eth_subscribe", r#"["newHeads"]"#)).unwrap().contains("-32602"));
+    }
+}
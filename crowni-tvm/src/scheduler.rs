@@ -9,10 +9,24 @@
 ///!
 ///! 2진 OS의 스레드/프로세스 개념을 3진 논리로 감싼다.
 ///! 절대 2진 상태를 노출하지 않는다.
+///!
+///! 자원 잠금 + 우선순위 상속:
+///!   보류(O)로 재큐된 태스크는 자원 잠금을 계속 쥔다 (트랜잭션 유지).
+///!   더 높은 우선순위 태스크가 그 자원을 기다리면 보유자가 대기자의
+///!   최고 우선순위를 상속 → 중간 우선순위 태스크에 밀리지 않는다.
+///!   잠금 해제 시 원래 우선순위로 복귀, 대기자 중 최고 우선순위를 깨움.
+///!
+///! 시분할 (선점) 모드:
+///!   TVM 프로그램 태스크를 퀀텀(N 사이클)씩 실행 → 다 못 끝내면 같은 우선순위 큐 뒤로.
+///!   에이징: 낮음(T) 큐에서 오래 기다린 태스크는 보통(O)으로 올려 기아를 막는다.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Instant, Duration};
 
+use crate::ring_log::RingLog;
+use crate::vm::TVM;
+use crate::watchdog::Heartbeat;
+
 // ─────────────────────────────────────────────
 // 3진 상태 타입들
 // ─────────────────────────────────────────────
@@ -104,6 +118,16 @@ pub type TaskId = u64;
 /// 태스크 콜백 타입
 pub type TaskFn = Box<dyn FnOnce() -> TritResult + Send>;
 
+/// 스케줄링 모드
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchedMode {
+    /// 협력 — 태스크가 끝날 때까지 실행
+    #[default]
+    Cooperative,
+    /// 선점 — 프로그램 태스크를 quantum 사이클씩 나눠 실행
+    Preemptive { quantum: u64 },
+}
+
 /// 스케줄러 태스크
 pub struct Task {
     pub id: TaskId,
@@ -118,10 +142,29 @@ pub struct Task {
     /// 재시도 카운터 (3진: 최대 3회)
     pub retries: u8,
     pub max_retries: u8,
+    /// 실행에 필요한 자원 잠금
+    pub resource: Option<String>,
+    /// 상속 전 우선순위 (보류 강등은 여기에 반영)
+    pub base_priority: TritPriority,
+    /// TVM 프로그램 태스크 — 실행 상태를 퀀텀 사이에 보존
+    pub program: Option<Box<TVM>>,
+    /// 선점된 횟수
+    pub slices: u32,
+    /// 마지막으로 큐에 들어간 시각 (에이징 기준)
+    pub queued_at: Instant,
 }
 
 impl Task {
     pub fn new(id: TaskId, name: &str, priority: TritPriority, action: TaskFn) -> Self {
+        Self { action: Some(action), ..Self::blank(id, name, priority) }
+    }
+
+    /// TVM 프로그램 태스크 — 콜백 대신 로드된 VM을 실행
+    pub fn program(id: TaskId, name: &str, priority: TritPriority, vm: TVM) -> Self {
+        Self { program: Some(Box::new(vm)), ..Self::blank(id, name, priority) }
+    }
+
+    fn blank(id: TaskId, name: &str, priority: TritPriority) -> Self {
         Self {
             id,
             name: name.to_string(),
@@ -131,9 +174,14 @@ impl Task {
             created_at: Instant::now(),
             started_at: None,
             finished_at: None,
-            action: Some(action),
+            action: None,
             retries: 0,
             max_retries: 3,  // 3진답게 최대 3회
+            resource: None,
+            base_priority: priority,
+            program: None,
+            slices: 0,
+            queued_at: Instant::now(),
         }
     }
 
@@ -158,6 +206,34 @@ impl std::fmt::Debug for Task {
     }
 }
 
+// ─────────────────────────────────────────────
+// 자원 잠금
+// ─────────────────────────────────────────────
+
+/// 자원 잠금 — 보유자와 대기자
+#[derive(Debug, Clone)]
+pub struct ResourceLock {
+    pub holder: TaskId,
+    pub waiters: Vec<(TaskId, TritPriority)>,
+}
+
+impl ResourceLock {
+    /// 대기자 중 최고 우선순위
+    pub fn ceiling(&self) -> Option<TritPriority> {
+        self.waiters.iter().map(|(_, p)| *p).max()
+    }
+}
+
+/// 우선순위 상속 기록
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityBoost {
+    pub holder: TaskId,
+    pub waiter: TaskId,
+    pub resource: String,
+    pub from: TritPriority,
+    pub to: TritPriority,
+}
+
 // ─────────────────────────────────────────────
 // TritScheduler
 // ─────────────────────────────────────────────
@@ -169,8 +245,14 @@ pub struct TritScheduler {
     queue_high: VecDeque<Task>,
     queue_normal: VecDeque<Task>,
     queue_low: VecDeque<Task>,
-    /// 완료된 태스크 기록
-    completed: Vec<Task>,
+    /// 완료된 태스크 기록 (상한 — 오래된 것부터 축출)
+    completed: RingLog<Task>,
+    /// 자원 잠금 표 (자원 → 보유자/대기자)
+    locks: HashMap<String, ResourceLock>,
+    /// 잠금을 기다리는 태스크
+    blocked: Vec<Task>,
+    /// 우선순위 상속 기록
+    pub boosts: Vec<PriorityBoost>,
     /// 다음 태스크 ID
     next_id: TaskId,
     /// 총 실행 횟수
@@ -179,6 +261,18 @@ pub struct TritScheduler {
     pub stats_success: u64,
     pub stats_pending: u64,
     pub stats_failed: u64,
+    /// 협력/선점 모드
+    pub mode: SchedMode,
+    /// 낮음(T) 태스크가 이만큼 기다리면 보통(O)으로 승격
+    pub aging: Option<Duration>,
+    /// 퀀텀 소진으로 선점된 횟수
+    pub stats_preempted: u64,
+    /// 에이징으로 승격된 횟수
+    pub stats_aged: u64,
+    /// 워치독 복구(recover)로 되살린 횟수
+    pub stats_recovered: u64,
+    /// 진행 하트비트 — 태스크를 하나 실행할 때마다
+    heartbeat: Option<Heartbeat>,
 }
 
 impl TritScheduler {
@@ -187,28 +281,218 @@ impl TritScheduler {
             queue_high: VecDeque::new(),
             queue_normal: VecDeque::new(),
             queue_low: VecDeque::new(),
-            completed: Vec::new(),
+            completed: RingLog::default(),
+            locks: HashMap::new(),
+            blocked: Vec::new(),
+            boosts: Vec::new(),
             next_id: 1,
             total_executed: 0,
             stats_success: 0,
             stats_pending: 0,
             stats_failed: 0,
+            mode: SchedMode::Cooperative,
+            aging: None,
+            stats_preempted: 0,
+            stats_aged: 0,
+            stats_recovered: 0,
+            heartbeat: None,
+        }
+    }
+
+    /// 워치독 하트비트 연결 — 체크포인트는 누적 실행 수
+    pub fn set_heartbeat(&mut self, heartbeat: Heartbeat) {
+        self.heartbeat = Some(heartbeat);
+    }
+
+    fn beat(&self) {
+        if let Some(hb) = &self.heartbeat {
+            hb.beat(Some(&format!("executed={}", self.total_executed)));
         }
     }
 
+    /// 멈춘 스케줄러 복구 — 잠금 표를 비우고 잠금 대기 태스크를 큐로 되돌린다
+    /// (잠금은 다시 꺼낼 때 새로 잡는다) 반환: 되돌린 태스크 수
+    pub fn recover(&mut self) -> usize {
+        self.locks.clear();
+        let blocked = std::mem::take(&mut self.blocked);
+        let n = blocked.len();
+        for task in blocked {
+            self.enqueue(task);
+        }
+        self.stats_recovered += 1;
+        n
+    }
+
+    /// 스케줄링 모드 지정
+    pub fn with_mode(mut self, mode: SchedMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// 낮음(T) 태스크 에이징 대기 시간 지정
+    pub fn with_aging(mut self, after: Duration) -> Self {
+        self.aging = Some(after);
+        self
+    }
+
     /// 태스크 등록 (큐에 넣기)
     pub fn submit(&mut self, name: &str, priority: TritPriority, action: TaskFn) -> TaskId {
         let id = self.next_id;
         self.next_id += 1;
-        let task = Task::new(id, name, priority, action);
+        self.enqueue(Task::new(id, name, priority, action));
+        id
+    }
+
+    /// 자원 잠금이 필요한 태스크 등록 — 잠금은 완료(P/T)까지 유지
+    pub fn submit_locked(&mut self, name: &str, priority: TritPriority, resource: &str, action: TaskFn) -> TaskId {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut task = Task::new(id, name, priority, action);
+        task.resource = Some(resource.to_string());
+        self.enqueue(task);
+        id
+    }
+
+    /// TVM 프로그램 태스크 등록 — 로드된 VM을 넘긴다
+    /// 선점 모드에서는 퀀텀마다 끊어 실행, 정상 종료 P / 오류 T
+    pub fn submit_program(&mut self, name: &str, priority: TritPriority, vm: TVM) -> TaskId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.enqueue(Task::program(id, name, priority, vm));
+        id
+    }
+
+    /// 완료된 태스크 조회 (프로그램 태스크는 VM 상태 포함)
+    pub fn completed_task(&self, id: TaskId) -> Option<&Task> {
+        self.completed.iter().find(|t| t.id == id)
+    }
+
+    /// 에이징 — now 기준으로 aging 이상 기다린 낮음(T) 태스크를 보통(O)으로
+    pub fn age_tasks(&mut self, now: Instant) -> usize {
+        let Some(after) = self.aging else { return 0 };
+        let mut aged = 0;
+        let mut i = 0;
+        while i < self.queue_low.len() {
+            if now.saturating_duration_since(self.queue_low[i].queued_at) >= after {
+                let Some(mut task) = self.queue_low.remove(i) else { break };
+                task.priority = TritPriority::Normal;
+                task.queued_at = now;
+                self.queue_normal.push_back(task);
+                aged += 1;
+            } else {
+                i += 1;
+            }
+        }
+        self.stats_aged += aged as u64;
+        aged
+    }
 
-        match priority {
+    fn enqueue(&mut self, task: Task) {
+        match task.priority {
             TritPriority::High => self.queue_high.push_back(task),
             TritPriority::Normal => self.queue_normal.push_back(task),
             TritPriority::Low => self.queue_low.push_back(task),
         }
+    }
 
-        id
+    /// 깨운 대기자는 같은 우선순위 큐의 맨 앞으로
+    fn enqueue_front(&mut self, task: Task) {
+        match task.priority {
+            TritPriority::High => self.queue_high.push_front(task),
+            TritPriority::Normal => self.queue_normal.push_front(task),
+            TritPriority::Low => self.queue_low.push_front(task),
+        }
+    }
+
+    /// 자원 보유자
+    pub fn lock_holder(&self, resource: &str) -> Option<TaskId> {
+        self.locks.get(resource).map(|l| l.holder)
+    }
+
+    /// 대기 중 태스크의 현재(상속 포함) 우선순위
+    pub fn effective_priority(&self, id: TaskId) -> Option<TritPriority> {
+        [&self.queue_high, &self.queue_normal, &self.queue_low].into_iter()
+            .flat_map(|q| q.iter())
+            .chain(self.blocked.iter())
+            .find(|t| t.id == id)
+            .map(|t| t.priority)
+    }
+
+    /// 완료 기록 수
+    pub fn completed_count(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// 잠금 대기 중인 태스크 수
+    pub fn blocked_count(&self) -> usize {
+        self.blocked.len()
+    }
+
+    /// 보유자가 대기열에 있으면 우선순위를 올려 해당 큐로 옮긴다
+    fn boost(&mut self, holder: TaskId, waiter: TaskId, resource: &str, to: TritPriority) {
+        let mut found = None;
+        for q in [&mut self.queue_high, &mut self.queue_normal, &mut self.queue_low] {
+            if let Some(pos) = q.iter().position(|t| t.id == holder) {
+                if q[pos].priority < to {
+                    found = q.remove(pos);
+                }
+                break;
+            }
+        }
+        let Some(mut task) = found else { return };
+        self.boosts.push(PriorityBoost {
+            holder, waiter, resource: resource.to_string(), from: task.priority, to,
+        });
+        task.priority = to;
+        self.enqueue(task);
+    }
+
+    /// 잠금 시도 — 다른 태스크가 쥐고 있으면 대기 등록 + 보유자 우선순위 상속
+    fn try_lock(&mut self, task: &Task) -> bool {
+        let Some(resource) = task.resource.clone() else { return true };
+        let holder = match self.locks.get_mut(&resource) {
+            None => {
+                self.locks.insert(resource, ResourceLock { holder: task.id, waiters: Vec::new() });
+                return true;
+            }
+            Some(lock) if lock.holder == task.id => return true,
+            Some(lock) => {
+                lock.waiters.push((task.id, task.priority));
+                lock.holder
+            }
+        };
+        self.boost(holder, task.id, &resource, task.priority);
+        false
+    }
+
+    /// 완료된 태스크의 잠금 해제 — 최고 우선순위 대기자를 깨움
+    fn release(&mut self, task: &Task) {
+        let Some(resource) = &task.resource else { return };
+        let Some(lock) = self.locks.get(resource) else { return };
+        if lock.holder != task.id {
+            // 대기 중 취소 — 대기 목록에서만 제거
+            if let Some(lock) = self.locks.get_mut(resource) {
+                lock.waiters.retain(|(id, _)| *id != task.id);
+            }
+            return;
+        }
+        let Some(lock) = self.locks.remove(resource) else { return };
+        let mut waiters = lock.waiters;
+        // 같은 우선순위면 먼저 온 순서
+        waiters.sort_by_key(|w| std::cmp::Reverse(w.1));
+        let Some(&(next, _)) = waiters.first() else { return };
+        self.locks.insert(resource.clone(), ResourceLock { holder: next, waiters: waiters[1..].to_vec() });
+        for id in waiters.iter().map(|(id, _)| *id) {
+            if let Some(pos) = self.blocked.iter().position(|t| t.id == id) {
+                let task = self.blocked.remove(pos);
+                if id == next { self.enqueue_front(task) } else { self.blocked.push(task) }
+            }
+        }
+        // 새 보유자도 남은 대기자의 우선순위를 상속
+        let ceiling = self.locks.get(resource).and_then(|l| l.ceiling());
+        if let (Some(to), Some(&(waiter, _))) = (ceiling, waiters.get(1)) {
+            self.boost(next, waiter, resource, to);
+        }
     }
 
     /// 다음 태스크 꺼내기 (우선순위 순: P → O → T)
@@ -219,14 +503,22 @@ impl TritScheduler {
         None
     }
 
-    /// 단일 태스크 실행
+    /// 단일 태스크 실행 — 잠금에 막힌 태스크는 대기로 옮기고 다음 태스크를 실행
     pub fn execute_one(&mut self) -> Option<(TaskId, TritResult)> {
-        let mut task = self.dequeue()?;
+        self.age_tasks(Instant::now());
+        let mut task = loop {
+            let task = self.dequeue()?;
+            if task.state == TritState::Inactive || self.try_lock(&task) {
+                break task;
+            }
+            self.blocked.push(task);
+        };
 
         // 비활성(취소) 상태면 건너뜀
         if task.state == TritState::Inactive {
             task.result = TritResult::Failed;
             task.finished_at = Some(Instant::now());
+            self.release(&task);
             let id = task.id;
             self.completed.push(task);
             self.stats_failed += 1;
@@ -235,10 +527,32 @@ impl TritScheduler {
 
         // 활성화
         task.state = TritState::Active;
-        task.started_at = Some(Instant::now());
+        if task.slices == 0 {
+            task.started_at = Some(Instant::now());
+        }
 
-        // 실행
-        let result = if let Some(action) = task.action.take() {
+        // 실행 — 프로그램 태스크는 선점 모드면 한 퀀텀만
+        let result = if let Some(mut vm) = task.program.take() {
+            let outcome = match self.mode {
+                SchedMode::Preemptive { quantum } => vm.run_slice(quantum.max(1)),
+                SchedMode::Cooperative => vm.run().map(|_| false),
+            };
+            task.program = Some(vm);
+            match outcome {
+                Ok(true) => {
+                    // 퀀텀 소진 → 선점, 재시도 횟수와 무관하게 같은 우선순위 큐 뒤로
+                    task.slices += 1;
+                    task.state = TritState::Neutral;
+                    task.queued_at = Instant::now();
+                    self.stats_preempted += 1;
+                    let id = task.id;
+                    self.enqueue(task);
+                    return Some((id, TritResult::Pending));
+                }
+                Ok(false) => TritResult::Success,
+                Err(_) => TritResult::Failed,
+            }
+        } else if let Some(action) = task.action.take() {
             action()
         } else {
             TritResult::Failed
@@ -261,11 +575,16 @@ impl TritScheduler {
                     task.state = TritState::Neutral;
                     self.stats_pending += 1;
                     let id = task.id;
-                    // 재큐잉 (우선순위 한 단계 낮춤)
-                    match task.priority {
-                        TritPriority::High => self.queue_normal.push_back(task),
-                        _ => self.queue_low.push_back(task),
-                    }
+                    // 재큐잉 (우선순위 한 단계 낮춤) — 잠금은 유지, 대기자가 있으면 상속
+                    task.base_priority = match task.base_priority {
+                        TritPriority::High => TritPriority::Normal,
+                        _ => TritPriority::Low,
+                    };
+                    let ceiling = task.resource.as_ref()
+                        .and_then(|r| self.locks.get(r))
+                        .and_then(|l| l.ceiling());
+                    task.priority = ceiling.map_or(task.base_priority, |c| c.max(task.base_priority));
+                    self.enqueue(task);
                     return Some((id, TritResult::Pending));
                 } else {
                     task.state = TritState::Inactive;
@@ -278,9 +597,12 @@ impl TritScheduler {
             }
         }
 
+        self.release(&task);
+        task.priority = task.base_priority;
         let id = task.id;
         let res = task.result;
         self.completed.push(task);
+        self.beat();
         Some((id, res))
     }
 
@@ -293,13 +615,20 @@ impl TritScheduler {
         results
     }
 
-    /// 대기 중인 태스크 수
+    /// 대기 중인 태스크 수 (잠금 대기 포함)
     pub fn pending_count(&self) -> usize {
-        self.queue_high.len() + self.queue_normal.len() + self.queue_low.len()
+        self.queue_high.len() + self.queue_normal.len() + self.queue_low.len() + self.blocked.len()
     }
 
     /// 태스크 취소 (상태를 T로 전환)
     pub fn cancel(&mut self, id: TaskId) -> bool {
+        if let Some(pos) = self.blocked.iter().position(|t| t.id == id) {
+            let mut task = self.blocked.remove(pos);
+            task.state = TritState::Inactive;
+            self.release(&task);
+            self.enqueue(task);
+            return true;
+        }
         for q in [&mut self.queue_high, &mut self.queue_normal, &mut self.queue_low] {
             for task in q.iter_mut() {
                 if task.id == id {
@@ -317,6 +646,9 @@ impl TritScheduler {
         println!("║ 대기: P:{} O:{} T:{}  완료:{}",
             self.queue_high.len(), self.queue_normal.len(),
             self.queue_low.len(), self.completed.len());
+        if let SchedMode::Preemptive { quantum } = self.mode {
+            println!("║ 시분할: 퀀텀 {}사이클 선점:{} 에이징:{}", quantum, self.stats_preempted, self.stats_aged);
+        }
         println!("║ 통계: 성공:{} 보류:{} 실패:{} 총:{}",
             self.stats_success, self.stats_pending,
             self.stats_failed, self.total_executed);
@@ -334,6 +666,13 @@ impl TritScheduler {
                 }
             }
         }
+        if !self.locks.is_empty() {
+            println!("║ ── 잠금 (대기:{} 상속:{}) ──", self.blocked_count(), self.boosts.len());
+            for (resource, lock) in &self.locks {
+                let prio = self.effective_priority(lock.holder).map(|p| format!("{}", p)).unwrap_or("-".into());
+                println!("║   {} ← [{:04}] {} 대기자:{}", resource, lock.holder, prio, lock.waiters.len());
+            }
+        }
         println!("╚═══════════════════════════════════════════╝");
     }
 }
@@ -378,4 +717,109 @@ mod tests {
         let r = sched.execute_one().unwrap();
         assert_eq!(r.1, TritResult::Failed);
     }
+
+    #[test]
+    fn test_priority_inheritance_resolves_inversion() {
+        let mut sched = TritScheduler::new();
+
+        // T 태스크가 "장부"를 잡은 채 보류 → 잠금 유지, 더 낮게 재큐
+        let low = sched.submit_locked("정산", TritPriority::Low, "장부", Box::new(|| TritResult::Pending));
+        assert_eq!(sched.execute_one(), Some((low, TritResult::Pending)));
+        assert_eq!(sched.lock_holder("장부"), Some(low));
+
+        // O 태스크 둘 + 같은 자원이 필요한 P 태스크
+        let m1 = sched.submit("보고1", TritPriority::Normal, Box::new(|| TritResult::Success));
+        let m2 = sched.submit("보고2", TritPriority::Normal, Box::new(|| TritResult::Success));
+        let high = sched.submit_locked("결제", TritPriority::High, "장부", Box::new(|| TritResult::Success));
+
+        // 결제가 막히면 보유자가 P 우선순위 상속 → O 태스크보다 먼저 실행
+        let order: Vec<TaskId> = std::iter::from_fn(|| sched.execute_one()).map(|(id, _)| id).collect();
+        assert_eq!(order, vec![low, high, m1, m2]);
+        assert_eq!(sched.boosts, vec![PriorityBoost {
+            holder: low, waiter: high, resource: "장부".into(),
+            from: TritPriority::Low, to: TritPriority::High,
+        }]);
+
+        // 해제 후 원래 우선순위로 복귀, 잠금 없음
+        assert_eq!(sched.lock_holder("장부"), None);
+        assert_eq!(sched.completed.iter().find(|t| t.id == low).map(|t| t.priority), Some(TritPriority::Low));
+        assert_eq!(sched.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_blocked_waiter_cancel_and_handoff() {
+        let mut sched = TritScheduler::new();
+        let holder = sched.submit_locked("보유", TritPriority::Normal, "키", Box::new(|| TritResult::Pending));
+        sched.execute_one();
+
+        let w1 = sched.submit_locked("대기1", TritPriority::High, "키", Box::new(|| TritResult::Success));
+        let w2 = sched.submit_locked("대기2", TritPriority::High, "키", Box::new(|| TritResult::Success));
+        let w3 = sched.submit_locked("대기3", TritPriority::Normal, "키", Box::new(|| TritResult::Success));
+
+        // w1 막힘 → 보유자 상속 → 보유자 실행(재시도 액션 없음 → T) → w1 인계
+        assert_eq!(sched.execute_one(), Some((holder, TritResult::Failed)));
+        assert_eq!(sched.lock_holder("키"), Some(w1));
+        // 잠금 대기 중 취소
+        assert!(sched.cancel(w2));
+        let rest: Vec<_> = std::iter::from_fn(|| sched.execute_one()).collect();
+        assert_eq!(rest, vec![
+            (w1, TritResult::Success),
+            (w2, TritResult::Failed),
+            (w3, TritResult::Success),
+        ]);
+        assert_eq!(sched.lock_holder("키"), None);
+    }
+
+    fn loaded(src: &str) -> TVM {
+        let mut vm = TVM::new();
+        vm.load(crate::assembler::assemble(src));
+        vm
+    }
+
+    #[test]
+    fn test_preemptive_round_robin() {
+        // 10 명령 프로그램 — 퀀텀 4 → 3 슬라이스
+        let src = "넣어 1\n넣어 2\n더해\n넣어 3\n더해\n넣어 4\n더해\n넣어 5\n더해\n종료";
+
+        let mut sched = TritScheduler::new();
+        let a = sched.submit_program("A", TritPriority::Normal, loaded(src));
+        let b = sched.submit_program("B", TritPriority::Normal, loaded(src));
+        assert_eq!(sched.run_all(), vec![(a, TritResult::Success), (b, TritResult::Success)]);
+
+        let mut sched = TritScheduler::new().with_mode(SchedMode::Preemptive { quantum: 4 });
+        let a = sched.submit_program("A", TritPriority::Normal, loaded(src));
+        let b = sched.submit_program("B", TritPriority::Normal, loaded(src));
+        let bad = sched.submit_program("오류", TritPriority::Low, loaded("더해\n종료"));
+        assert_eq!(sched.run_all(), vec![
+            (a, TritResult::Pending), (b, TritResult::Pending),
+            (a, TritResult::Pending), (b, TritResult::Pending),
+            (a, TritResult::Success), (b, TritResult::Success),
+            (bad, TritResult::Failed),
+        ]);
+        let done = sched.completed_task(a).unwrap();
+        assert_eq!((done.slices, done.retries), (2, 0));
+        assert_eq!(done.program.as_ref().map(|vm| vm.cycles), Some(10));
+        assert_eq!(sched.stats_preempted, 4);
+    }
+
+    #[test]
+    fn test_aging_prevents_starvation() {
+        let mut sched = TritScheduler::new()
+            .with_mode(SchedMode::Preemptive { quantum: 2 })
+            .with_aging(Duration::from_secs(1));
+        let low = sched.submit("로그정리", TritPriority::Low, Box::new(|| TritResult::Success));
+        let hog = sched.submit_program("긴작업", TritPriority::Normal, loaded("넣어 1\n넣어 2\n넣어 3\n넣어 4\n넣어 5\n종료"));
+
+        // 아직 기다린 시간이 짧음 → 그대로
+        assert_eq!(sched.age_tasks(Instant::now()), 0);
+        assert_eq!(sched.execute_one(), Some((hog, TritResult::Pending)));
+
+        // 대기 시간 초과 → 보통(O)으로 승격, 긴 작업과 번갈아 실행
+        assert_eq!(sched.age_tasks(Instant::now() + Duration::from_secs(2)), 1);
+        assert_eq!(sched.effective_priority(low), Some(TritPriority::Normal));
+        assert_eq!(sched.execute_one(), Some((hog, TritResult::Pending)));
+        assert_eq!(sched.execute_one(), Some((low, TritResult::Success)));
+        assert_eq!(sched.completed_task(low).map(|t| t.priority), Some(TritPriority::Low));
+        assert_eq!(sched.stats_aged, 1);
+    }
 }
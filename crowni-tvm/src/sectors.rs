@@ -5,15 +5,16 @@
 ///! 섹터 배치:
 ///!   0: 코어(Kernel)       — 기존 구현 완료
 ///!   1: 지능(Intelligence) — AI/LLM/추론
-///!   2: 하드웨어(Hardware)  — FPGA/센서/GPIO
+///!   2: 하드웨어(Hardware)  — FPGA/센서/GPIO/파일/네트워크
 ///!   3: 기억(Memory)       — 고급 메모리/캐시/GC
 ///!   4: 표현(Expression)   — 문자열/정규식/포맷
-///!   5: 초월(Transcendence)— 암호/해시/양자
+///!   5: 초월(Transcendence)— 암호/해시/토큰
 ///!   6: 보안(Security)     — 인증/권한/감사
 ///!   7: 메타(Meta)         — 리플렉션/디버그/프로파일
 ///!   8: 확장(User)         — 사용자 정의/플러그인
 
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use crate::opcode::{OpcodeAddr, OpMeta, Effect};
 
 macro_rules! op {
@@ -38,6 +39,12 @@ pub fn build_all_sectors() -> HashMap<OpcodeAddr, OpMeta> {
     m
 }
 
+/// 729 opcodes 공유 맵 (한 번만 빌드 — 예약 이름 누수 방지)
+pub fn all_sectors() -> &'static HashMap<OpcodeAddr, OpMeta> {
+    static MAP: OnceLock<HashMap<OpcodeAddr, OpMeta>> = OnceLock::new();
+    MAP.get_or_init(build_all_sectors)
+}
+
 // ═══════════════════════════════════════════════
 // 섹터 1: 지능 (Intelligence) — AI/LLM/추론
 // ═══════════════════════════════════════════════
@@ -115,7 +122,7 @@ fn build_sector_1_intelligence(m: &mut HashMap<OpcodeAddr, OpMeta>) {
 }
 
 // ═══════════════════════════════════════════════
-// 섹터 2: 하드웨어 (Hardware) — FPGA/센서/GPIO
+// 섹터 2: 하드웨어 (Hardware) — FPGA/센서/GPIO/파일/네트워크
 // ═══════════════════════════════════════════════
 
 fn build_sector_2_hardware(m: &mut HashMap<OpcodeAddr, OpMeta>) {
@@ -143,8 +150,30 @@ fn build_sector_2_hardware(m: &mut HashMap<OpcodeAddr, OpMeta>) {
     m.insert(OpcodeAddr::new(s,1,7), op!("I2C",        "GPIO_I2C",    2,1,0, Effect::IO));
     m.insert(OpcodeAddr::new(s,1,8), op!("SPI",        "GPIO_SPI",    2,1,0, Effect::IO));
 
-    // G2~G8: 하드웨어 예약
-    for g in 2..=8 {
+    // G2: 파일
+    m.insert(OpcodeAddr::new(s,2,0), op!("파일열기",   "FILE_OPEN",   1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,1), op!("파일읽기",   "FILE_READ",   1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,2), op!("파일쓰기",   "FILE_WRITE",  2,0,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,3), op!("파일추가",   "FILE_APPEND", 2,0,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,4), op!("파일닫기",   "FILE_CLOSE",  1,0,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,5), op!("파일삭제",   "FILE_DELETE", 1,0,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,6), op!("파일목록",   "FILE_LIST",   1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,7), op!("파일존재",   "FILE_EXISTS", 1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,2,8), op!("파일크기",   "FILE_SIZE",   1,1,0, Effect::IO));
+
+    // G3: 네트워크/HTTP
+    m.insert(OpcodeAddr::new(s,3,0), op!("HTTP가져와", "HTTP_GET",    1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,1), op!("HTTP보내",   "HTTP_POST",   2,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,2), op!("HTTP수정",   "HTTP_PUT",    2,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,3), op!("HTTP삭제",   "HTTP_DELETE", 1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,4), op!("헤더설정",   "HTTP_HEADER", 2,0,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,5), op!("응답코드",   "HTTP_STATUS", 0,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,6), op!("소켓열기",   "SOCK_OPEN",   1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,7), op!("소켓보내",   "SOCK_SEND",   2,0,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,3,8), op!("소켓받아",   "SOCK_RECV",   1,1,0, Effect::IO));
+
+    // G4~G8: 하드웨어 예약
+    for g in 4..=8 {
         for c in 0..=8 {
             let nk = format!("하드{}_{}", g, c);
             let ne = format!("HW_{}_{}", g, c);
@@ -264,7 +293,7 @@ fn build_sector_4_expression(m: &mut HashMap<OpcodeAddr, OpMeta>) {
 }
 
 // ═══════════════════════════════════════════════
-// 섹터 5: 초월 (Transcendence) — 암호/해시/양자
+// 섹터 5: 초월 (Transcendence) — 암호/해시/토큰
 // ═══════════════════════════════════════════════
 
 fn build_sector_5_transcendence(m: &mut HashMap<OpcodeAddr, OpMeta>) {
@@ -281,8 +310,19 @@ fn build_sector_5_transcendence(m: &mut HashMap<OpcodeAddr, OpMeta>) {
     m.insert(OpcodeAddr::new(s,0,7), op!("키생성",     "KEYGEN",      1,1,0, Effect::Stack));
     m.insert(OpcodeAddr::new(s,0,8), op!("랜덤",       "RANDOM",      1,1,0, Effect::Stack));
 
-    // G1~G8: 초월 예약
-    for g in 1..=8 {
+    // G1: 토큰/자산
+    m.insert(OpcodeAddr::new(s,1,0), op!("잔액",       "TOKEN_BALANCE",  1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,1), op!("송금해",     "TOKEN_TRANSFER", 3,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,2), op!("위임송금",   "TOKEN_TRANSFER_FROM", 3,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,3), op!("송금승인",   "TOKEN_APPROVE",  2,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,4), op!("허용량",     "TOKEN_ALLOWANCE",2,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,5), op!("발행해",     "TOKEN_MINT",     2,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,6), op!("소각해",     "TOKEN_BURN",     1,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,7), op!("잠가",       "TOKEN_LOCK",     2,1,0, Effect::IO));
+    m.insert(OpcodeAddr::new(s,1,8), op!("잠금풀어",   "TOKEN_UNLOCK",   1,1,0, Effect::IO));
+
+    // G2~G8: 초월 예약
+    for g in 2..=8 {
         for c in 0..=8 {
             let nk = format!("초월{}_{}", g, c);
             let ne = format!("TRANS_{}_{}", g, c);
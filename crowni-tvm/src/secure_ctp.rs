@@ -0,0 +1,648 @@
+// ═══════════════════════════════════════════════════════════════
+// 보안 CTP 채널 — 노드 간 종단 암호화 (noise 방식 핸드셰이크)
+// 합의 투표 · 상태 동기화가 평문으로 오가지 않도록 TCP 위에 한 겹 더
+//
+//   핸드셰이크 (3DH + 키 확인):
+//     → CTPS ver  e_i  s_i
+//     ← e_r  s_r  HMAC(k_c, "R" ‖ h)
+//     → HMAC(k_c, "I" ‖ h)
+//     h   = SHA256(prologue ‖ 앞의 공개값 전부)
+//     ikm = DH(e_i, e_r) ‖ DH(s_i, e_r) ‖ DH(e_i, s_r)
+//     k_i→r, k_r→i, k_c = HKDF(h, ikm)
+//   → 노드 신원 키(s)를 가진 쪽만 같은 키를 얻는다 (상호 인증)
+//
+//   전송 프레임: [길이 4B][에포크 4B][ChaCha20-Poly1305(CTP 프레임)]
+//     안쪽 프레임은 협상된 경우 암호화 전에 압축 (ctp_compress)
+//     nonce = 방향별 카운터 · AAD = 에포크
+//   재키잉: REKEY_INTERVAL 프레임마다 자동, rekey()로 즉시
+//     k' = HKDF(k, "ctp rekey") · 에포크 +1 · 카운터 0
+// ═══════════════════════════════════════════════════════════════
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::crypto::{self, Key, KEY_LEN};
+use crate::ctp_compress::CtpCodec;
+use crate::network::{encode_binary_frame, encode_frame_with, CtpError, CtpMessage, FrameDecoder, FrameError, MessageType, TritNetAdapter, LOCAL_CAPS};
+
+const MAGIC: &[u8; 4] = b"CTPS";
+const PROLOGUE: &[u8] = b"crowny-ctp-secure";
+/// 핸드셰이크 형식 버전
+pub const SECURE_VERSION: u8 = 1;
+/// 이 프레임 수마다 방향별 키 갱신 (3^9)
+pub const REKEY_INTERVAL: u64 = 19_683;
+/// 암호 프레임 최대 크기
+pub const MAX_FRAME: usize = 1 << 20;
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn denied(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::PermissionDenied, msg.into())
+}
+
+// ─────────────────────────────────────────────
+// 노드 신원 키 · 신뢰 정책
+// ─────────────────────────────────────────────
+
+/// 노드 신원 키쌍 (X25519 정적 키)
+#[derive(Clone)]
+pub struct NodeKeypair {
+    secret: Key,
+    pub public: Key,
+}
+
+impl NodeKeypair {
+    pub fn generate() -> Self {
+        Self::from_secret(crypto::random_bytes())
+    }
+
+    pub fn from_secret(secret: Key) -> Self {
+        Self { public: crypto::x25519_base(&secret), secret }
+    }
+
+    pub fn secret(&self) -> &Key {
+        &self.secret
+    }
+
+    /// 표시용 지문
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.public)
+    }
+
+    fn dh(&self, public: &Key) -> io::Result<Key> {
+        let shared = crypto::x25519(&self.secret, public);
+        // 저차 점 → 공유 비밀이 0 — 거부
+        if shared == [0u8; KEY_LEN] {
+            return Err(denied("무효한 공개 키 (저차 점)"));
+        }
+        Ok(shared)
+    }
+}
+
+impl std::fmt::Debug for NodeKeypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NodeKeypair({})", self.fingerprint())
+    }
+}
+
+/// 공개 키 지문 (앞 8바이트 hex)
+pub fn fingerprint(public: &Key) -> String {
+    crypto::to_hex(&public[..8])
+}
+
+/// 상대 노드 신원 키 신뢰 정책
+#[derive(Debug, Clone)]
+pub enum PeerTrust {
+    /// 인증만 하고 신원은 호출자가 확인 (peer_key로 조회)
+    Any,
+    /// 등록된 키만 허용
+    Only(Vec<Key>),
+}
+
+impl PeerTrust {
+    pub fn allows(&self, key: &Key) -> bool {
+        match self {
+            PeerTrust::Any => true,
+            PeerTrust::Only(keys) => keys.iter().any(|k| crypto::ct_eq(k, key)),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// 방향별 암호 상태
+// ─────────────────────────────────────────────
+
+#[derive(Clone)]
+struct CipherState {
+    key: Key,
+    counter: u64,
+    epoch: u32,
+}
+
+impl CipherState {
+    fn new(key: Key) -> Self {
+        Self { key, counter: 0, epoch: 0 }
+    }
+
+    fn nonce(&self) -> [u8; crypto::NONCE_LEN] {
+        let mut n = [0u8; crypto::NONCE_LEN];
+        n[4..].copy_from_slice(&self.counter.to_le_bytes());
+        n
+    }
+
+    fn rekey(&mut self) {
+        let [next] = crypto::hkdf::<1>(&self.key, &[], b"ctp rekey");
+        self.key = next;
+        self.counter = 0;
+        self.epoch = self.epoch.wrapping_add(1);
+    }
+}
+
+// ─────────────────────────────────────────────
+// 핸드셰이크 (입출력 없는 상태 기계)
+// ─────────────────────────────────────────────
+
+const HELLO_LEN: usize = 4 + 1 + KEY_LEN * 2;
+const REPLY_LEN: usize = KEY_LEN * 3;
+const CONFIRM_LEN: usize = 32;
+
+fn key_at(bytes: &[u8], offset: usize) -> Key {
+    let mut k = [0u8; KEY_LEN];
+    k.copy_from_slice(&bytes[offset..offset + KEY_LEN]);
+    k
+}
+
+struct Derived {
+    hash: [u8; 32],
+    i2r: Key,
+    r2i: Key,
+    confirm: Key,
+}
+
+fn derive(hello: &[u8], e_r: &Key, s_r: &Key, dh: [Key; 3]) -> Derived {
+    let mut h = crypto::Sha256::new();
+    h.update(PROLOGUE);
+    h.update(hello);
+    h.update(e_r);
+    h.update(s_r);
+    let hash = h.finish();
+    let [i2r, r2i, confirm] = crypto::hkdf::<3>(&hash, &dh.concat(), b"ctp keys");
+    Derived { hash, i2r, r2i, confirm }
+}
+
+fn confirm_tag(d: &Derived, role: &[u8]) -> [u8; 32] {
+    crypto::hmac_sha256(&d.confirm, &[role, &d.hash])
+}
+
+/// 시작 측 — 첫 메시지를 보낸 뒤 응답을 기다림
+pub struct Initiator {
+    identity: NodeKeypair,
+    ephemeral: NodeKeypair,
+    hello: Vec<u8>,
+}
+
+impl Initiator {
+    pub fn start(identity: &NodeKeypair) -> (Self, Vec<u8>) {
+        let ephemeral = NodeKeypair::generate();
+        let mut hello = Vec::with_capacity(HELLO_LEN);
+        hello.extend_from_slice(MAGIC);
+        hello.push(SECURE_VERSION);
+        hello.extend_from_slice(&ephemeral.public);
+        hello.extend_from_slice(&identity.public);
+        let me = Self { identity: identity.clone(), ephemeral, hello: hello.clone() };
+        (me, hello)
+    }
+
+    /// 응답 검증 → 채널 + 마지막 확인 메시지
+    pub fn finish(self, reply: &[u8], trust: &PeerTrust) -> io::Result<(SecureChannel, Vec<u8>)> {
+        if reply.len() != REPLY_LEN {
+            return Err(invalid(format!("핸드셰이크 응답 길이 {} (기대 {})", reply.len(), REPLY_LEN)));
+        }
+        let (e_r, s_r) = (key_at(reply, 0), key_at(reply, KEY_LEN));
+        if !trust.allows(&s_r) {
+            return Err(denied(format!("신뢰하지 않는 노드 키 {}", fingerprint(&s_r))));
+        }
+        let dh = [self.ephemeral.dh(&e_r)?, self.identity.dh(&e_r)?, self.ephemeral.dh(&s_r)?];
+        let d = derive(&self.hello, &e_r, &s_r, dh);
+        if !crypto::ct_eq(&confirm_tag(&d, b"R"), &reply[KEY_LEN * 2..]) {
+            return Err(denied("응답 측 키 확인 실패 (신원 키 불일치)"));
+        }
+        let confirm = confirm_tag(&d, b"I").to_vec();
+        Ok((SecureChannel::new(d.i2r, d.r2i, s_r, d.hash), confirm))
+    }
+}
+
+/// 응답 측 — 확인 메시지를 기다리는 중
+pub struct Responder {
+    channel: SecureChannel,
+    expected: [u8; 32],
+}
+
+impl Responder {
+    pub fn respond(identity: &NodeKeypair, hello: &[u8], trust: &PeerTrust) -> io::Result<(Self, Vec<u8>)> {
+        if hello.len() != HELLO_LEN || &hello[..4] != MAGIC {
+            return Err(invalid("보안 CTP 핸드셰이크가 아님"));
+        }
+        if hello[4] != SECURE_VERSION {
+            return Err(invalid(format!("지원하지 않는 보안 핸드셰이크 버전 {}", hello[4])));
+        }
+        let (e_i, s_i) = (key_at(hello, 5), key_at(hello, 5 + KEY_LEN));
+        if !trust.allows(&s_i) {
+            return Err(denied(format!("신뢰하지 않는 노드 키 {}", fingerprint(&s_i))));
+        }
+        let ephemeral = NodeKeypair::generate();
+        let dh = [ephemeral.dh(&e_i)?, ephemeral.dh(&s_i)?, identity.dh(&e_i)?];
+        let d = derive(hello, &ephemeral.public, &identity.public, dh);
+
+        let mut reply = Vec::with_capacity(REPLY_LEN);
+        reply.extend_from_slice(&ephemeral.public);
+        reply.extend_from_slice(&identity.public);
+        reply.extend_from_slice(&confirm_tag(&d, b"R"));
+        let expected = confirm_tag(&d, b"I");
+        Ok((Self { channel: SecureChannel::new(d.r2i, d.i2r, s_i, d.hash), expected }, reply))
+    }
+
+    pub fn finish(self, confirm: &[u8]) -> io::Result<SecureChannel> {
+        if !crypto::ct_eq(&self.expected, confirm) {
+            return Err(denied("시작 측 키 확인 실패 (신원 키 불일치)"));
+        }
+        Ok(self.channel)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 보안 채널 (프레임 암·복호화)
+// ─────────────────────────────────────────────
+
+/// 수립된 채널 — 방향별 키 · 카운터 · 에포크
+pub struct SecureChannel {
+    send: CipherState,
+    recv: CipherState,
+    peer: Key,
+    handshake_hash: [u8; 32],
+    /// 자동 재키잉 간격 (프레임 수)
+    pub rekey_interval: u64,
+}
+
+impl SecureChannel {
+    fn new(send: Key, recv: Key, peer: Key, handshake_hash: [u8; 32]) -> Self {
+        Self {
+            send: CipherState::new(send),
+            recv: CipherState::new(recv),
+            peer,
+            handshake_hash,
+            rekey_interval: REKEY_INTERVAL,
+        }
+    }
+
+    /// 상대 노드 신원 공개 키
+    pub fn peer_key(&self) -> &Key {
+        &self.peer
+    }
+
+    /// 세션 식별자 — 양쪽이 같은 값을 가진다
+    pub fn session_id(&self) -> String {
+        crypto::to_hex(&self.handshake_hash[..8])
+    }
+
+    /// (보낸 쪽 에포크, 받은 쪽 에포크)
+    pub fn epochs(&self) -> (u32, u32) {
+        (self.send.epoch, self.recv.epoch)
+    }
+
+    /// 송신 키 즉시 갱신 — 다음 프레임의 에포크로 상대에게 전달된다
+    pub fn rekey(&mut self) {
+        self.send.rekey();
+    }
+
+    /// 평문 → 전송 프레임 [길이][에포크][암호문‖태그]
+    pub fn seal(&mut self, plaintext: &[u8]) -> Vec<u8> {
+        if self.send.counter >= self.rekey_interval {
+            self.send.rekey();
+        }
+        let epoch = self.send.epoch.to_be_bytes();
+        let sealed = crypto::seal(&self.send.key, &self.send.nonce(), &epoch, plaintext);
+        self.send.counter += 1;
+        let mut frame = Vec::with_capacity(8 + sealed.len());
+        frame.extend_from_slice(&((4 + sealed.len()) as u32).to_be_bytes());
+        frame.extend_from_slice(&epoch);
+        frame.extend_from_slice(&sealed);
+        frame
+    }
+
+    /// 프레임 본문(길이 제외) → 평문. 변조·재전송·순서 뒤바뀜은 거부
+    pub fn open(&mut self, body: &[u8]) -> io::Result<Vec<u8>> {
+        let (epoch, sealed) = body.split_at_checked(4).ok_or_else(|| invalid("암호 프레임이 짧음"))?;
+        let epoch = u32::from_be_bytes([epoch[0], epoch[1], epoch[2], epoch[3]]);
+        // 다음 에포크는 사본에서 유도 — 인증에 성공한 프레임만 수신 상태를 옮긴다
+        let mut state = if epoch == self.recv.epoch.wrapping_add(1) {
+            let mut next = self.recv.clone();
+            next.rekey();
+            next
+        } else if epoch == self.recv.epoch {
+            self.recv.clone()
+        } else {
+            return Err(invalid(format!("에포크 불일치: {} (현재 {})", epoch, self.recv.epoch)));
+        };
+        let plain = crypto::open(&state.key, &state.nonce(), &epoch.to_be_bytes(), sealed)
+            .ok_or_else(|| invalid("복호화 실패 (변조 또는 재전송)"))?;
+        state.counter += 1;
+        self.recv = state;
+        Ok(plain)
+    }
+
+    /// CTP 메시지 암호화 — 안쪽은 평문 어댑터와 같은 CTP3 프레임
+    pub fn seal_message(&mut self, msg: &CtpMessage) -> io::Result<Vec<u8>> {
+        Ok(self.seal(&encode_binary_frame(msg)))
+    }
+
+    /// 복호화 후 안쪽 프레임 해석 — 압축 본문 포함
+    pub fn open_message(&mut self, body: &[u8]) -> io::Result<Result<CtpMessage, CtpError>> {
+        let mut decoder = FrameDecoder::new();
+        decoder.push(&self.open(body)?);
+        match decoder.next_frame() {
+            Some(Ok(msg)) if decoder.buffered() == 0 => Ok(Ok(msg)),
+            Some(Err(FrameError::Ctp(e))) => Ok(Err(e)),
+            Some(Err(e)) => Err(invalid(e.to_string())),
+            _ => Err(invalid("안쪽 프레임 길이 불일치")),
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// 스트림 래퍼
+// ─────────────────────────────────────────────
+
+fn write_raw<S: Write>(stream: &mut S, bytes: &[u8]) -> io::Result<()> {
+    stream.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    stream.write_all(bytes)?;
+    stream.flush()
+}
+
+fn read_raw<S: Read>(stream: &mut S, max: usize) -> io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > max {
+        return Err(invalid(format!("프레임 크기 {} > 최대 {}", len, max)));
+    }
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// 암호화된 CTP 스트림
+pub struct SecureStream<S: Read + Write> {
+    stream: S,
+    pub channel: SecureChannel,
+    /// 협상 전에는 압축 꺼짐
+    pub codec: CtpCodec,
+}
+
+impl<S: Read + Write> SecureStream<S> {
+    /// 시작 측 핸드셰이크
+    pub fn connect(mut stream: S, identity: &NodeKeypair, trust: &PeerTrust) -> io::Result<Self> {
+        let (init, hello) = Initiator::start(identity);
+        write_raw(&mut stream, &hello)?;
+        let reply = read_raw(&mut stream, REPLY_LEN)?;
+        let (channel, confirm) = init.finish(&reply, trust)?;
+        write_raw(&mut stream, &confirm)?;
+        Ok(Self { stream, channel, codec: CtpCodec::new(false) })
+    }
+
+    /// 응답 측 핸드셰이크
+    pub fn accept(mut stream: S, identity: &NodeKeypair, trust: &PeerTrust) -> io::Result<Self> {
+        let hello = read_raw(&mut stream, HELLO_LEN)?;
+        let (pending, reply) = Responder::respond(identity, &hello, trust)?;
+        write_raw(&mut stream, &reply)?;
+        let confirm = read_raw(&mut stream, CONFIRM_LEN)?;
+        Ok(Self { channel: pending.finish(&confirm)?, stream, codec: CtpCodec::new(false) })
+    }
+
+    pub fn peer_key(&self) -> &Key {
+        self.channel.peer_key()
+    }
+
+    pub fn send(&mut self, msg: &CtpMessage) -> io::Result<usize> {
+        let inner = encode_frame_with(&mut self.codec, msg);
+        self.write_sealed(&inner)
+    }
+
+    fn write_sealed(&mut self, inner: &[u8]) -> io::Result<usize> {
+        let frame = self.channel.seal(inner);
+        self.stream.write_all(&frame)?;
+        self.stream.flush()?;
+        Ok(frame.len())
+    }
+
+    pub fn recv(&mut self) -> io::Result<CtpMessage> {
+        self.recv_message()?
+            .map_err(|e| invalid(e.to_string()))
+    }
+
+    /// 모르는 메시지 타입은 평문 어댑터처럼 건너뜀
+    pub fn recv_message(&mut self) -> io::Result<Result<CtpMessage, CtpError>> {
+        loop {
+            let body = read_raw(&mut self.stream, MAX_FRAME)?;
+            match self.channel.open_message(&body)? {
+                Err(CtpError::UnknownType(code)) => {
+                    eprintln!("[CTPS] 알 수 없는 메시지 타입 {} — 건너뜀", code);
+                }
+                other => return Ok(other),
+            }
+        }
+    }
+
+    /// 암호 채널 안에서 CTP 버전 · 능력 협상 — 합의되면 압축 코덱 활성화
+    pub fn negotiate(&mut self) -> io::Result<u8> {
+        use crate::network::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+        self.send(&CtpMessage::handshake(MIN_PROTOCOL_VERSION, PROTOCOL_VERSION))?;
+        let answer = self.recv()?;
+        let version = answer.agreed_version()
+            .map_err(|e| io::Error::new(io::ErrorKind::Unsupported, e))?;
+        self.codec = CtpCodec::negotiated(answer.agreed_caps());
+        Ok(version)
+    }
+}
+
+impl TritNetAdapter {
+    /// 암호화 CTP 서버 — 연결마다 핸드셰이크 후 연결이 닫힐 때까지 요청마다 응답
+    pub fn start_secure_server(addr: &str, identity: &NodeKeypair, trust: &PeerTrust) -> io::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        println!("[CTPS서버] {} 에서 대기 중... (노드 키 {})", addr, identity.fingerprint());
+        for stream in listener.incoming() {
+            let result = stream.and_then(|s| Self::serve_secure(s, identity, trust));
+            if let Err(e) = result {
+                eprintln!("[CTPS서버] 오류: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// 보안 연결 하나 처리 — 협상 요청에 답한 뒤부터 합의된 압축 적용
+    pub fn serve_secure(stream: TcpStream, identity: &NodeKeypair, trust: &PeerTrust) -> io::Result<()> {
+        let mut secure = SecureStream::accept(stream, identity, trust)?;
+        println!("[CTPS서버] 연결: 노드 {}", fingerprint(secure.peer_key()));
+        loop {
+            let msg = match secure.recv_message() {
+                Ok(msg) => msg,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            let reply = match &msg {
+                Ok(msg) => Self::reply(msg),
+                Err(e) => CtpMessage::error(e),
+            };
+            secure.send(&reply)?;
+            if let Some(msg) = msg.as_ref().ok().filter(|m| m.msg_type == MessageType::Handshake) {
+                secure.codec = CtpCodec::negotiated(msg.handshake_caps() & LOCAL_CAPS);
+            }
+        }
+    }
+
+    /// 암호화 요청 — 핸드셰이크 · 버전 협상 · 전송 · 응답 수신
+    pub fn send_secure_request(addr: &str, identity: &NodeKeypair, trust: &PeerTrust, msg: &CtpMessage) -> io::Result<CtpMessage> {
+        let mut secure = SecureStream::connect(TcpStream::connect(addr)?, identity, trust)?;
+        secure.negotiate()?;
+        secure.send(msg)?;
+        secure.recv()
+    }
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{MessageType, StatusCode, TritBuffer};
+
+    fn pair(a: &NodeKeypair, b: &NodeKeypair) -> (SecureChannel, SecureChannel) {
+        let (init, hello) = Initiator::start(a);
+        let (resp, reply) = Responder::respond(b, &hello, &PeerTrust::Only(vec![a.public])).unwrap();
+        let (ca, confirm) = init.finish(&reply, &PeerTrust::Only(vec![b.public])).unwrap();
+        (ca, resp.finish(&confirm).unwrap())
+    }
+
+    fn vote() -> CtpMessage {
+        let mut payload = TritBuffer::new();
+        payload.push_string("vote:P");
+        CtpMessage::new(MessageType::Request, StatusCode::Success, payload)
+    }
+
+    #[test]
+    fn test_handshake_and_encrypted_frames() {
+        let (a, b) = (NodeKeypair::generate(), NodeKeypair::generate());
+        let (mut ca, mut cb) = pair(&a, &b);
+        assert_eq!(ca.session_id(), cb.session_id());
+        assert_eq!(ca.peer_key(), &b.public);
+        assert_eq!(cb.peer_key(), &a.public);
+
+        let frame = ca.seal_message(&vote()).unwrap();
+        // 평문 페이로드가 선에 보이지 않음
+        let plain = encode_binary_frame(&vote());
+        assert!(!frame.windows(plain.len() - 4).any(|w| w == &plain[4..]));
+        let got = cb.open_message(&frame[4..]).unwrap().unwrap();
+        assert_eq!(got.payload.to_trit_string(), vote().payload.to_trit_string());
+
+        // 변조 · 재전송 거부
+        let mut tampered = ca.seal_message(&vote()).unwrap();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(cb.open(&tampered[4..]).is_err());
+        assert!(cb.open(&frame[4..]).is_err());
+    }
+
+    #[test]
+    fn test_untrusted_or_impersonated_peer_rejected() {
+        let (a, b, mallory) = (NodeKeypair::generate(), NodeKeypair::generate(), NodeKeypair::generate());
+
+        // 허용 목록 밖
+        let (_, hello) = Initiator::start(&mallory);
+        let err = Responder::respond(&b, &hello, &PeerTrust::Only(vec![a.public])).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        // a의 공개 키를 사칭 (비밀 키 없음) → 양쪽 키 확인 실패
+        let (mut init, mut hello) = Initiator::start(&mallory);
+        hello[5 + KEY_LEN..].copy_from_slice(&a.public);
+        init.hello = hello.clone();
+        let (resp, reply) = Responder::respond(&b, &hello, &PeerTrust::Only(vec![a.public])).unwrap();
+        let err = init.finish(&reply, &PeerTrust::Any).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(resp.finish(&[0u8; CONFIRM_LEN]).is_err());
+    }
+
+    #[test]
+    fn test_rekey_automatic_and_explicit() {
+        let (a, b) = (NodeKeypair::generate(), NodeKeypair::generate());
+        let (mut ca, mut cb) = pair(&a, &b);
+        ca.rekey_interval = 3;
+        for _ in 0..7 {
+            let frame = ca.seal(b"state-sync");
+            assert_eq!(cb.open(&frame[4..]).unwrap(), b"state-sync");
+        }
+        assert_eq!(ca.epochs().0, 2);
+        assert_eq!(cb.epochs().1, 2);
+
+        ca.rekey();
+        let old_key = cb.recv.key;
+        let frame = ca.seal(b"x");
+        assert!(cb.open(&frame[4..]).is_ok());
+        assert_ne!(cb.recv.key, old_key);
+        assert_eq!(cb.epochs().1, 3);
+
+        // 에포크 건너뛰기 거부
+        ca.rekey();
+        ca.rekey();
+        assert!(cb.open(&ca.seal(b"y")[4..]).is_err());
+    }
+
+    #[test]
+    fn test_forged_next_epoch_does_not_advance_receiver() {
+        let (a, b) = (NodeKeypair::generate(), NodeKeypair::generate());
+        let (mut ca, mut cb) = pair(&a, &b);
+
+        // 인증되지 않은 다음 에포크 프레임 → 수신 상태 그대로
+        let mut forged = 1u32.to_be_bytes().to_vec();
+        forged.extend_from_slice(&[0u8; 48]);
+        assert!(cb.open(&forged).is_err());
+        assert_eq!(cb.epochs().1, 0);
+
+        // 정상 프레임은 계속 열린다
+        let frame = ca.seal(b"after-forgery");
+        assert_eq!(cb.open(&frame[4..]).unwrap(), b"after-forgery");
+        ca.rekey();
+        assert_eq!(cb.open(&ca.seal(b"next")[4..]).unwrap(), b"next");
+        assert_eq!(cb.epochs().1, 1);
+    }
+
+    #[test]
+    fn test_secure_tcp_roundtrip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let (server_key, client_key) = (NodeKeypair::generate(), NodeKeypair::generate());
+        let trust_client = PeerTrust::Only(vec![client_key.public]);
+        let server_pub = server_key.public;
+
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut s = SecureStream::accept(stream, &server_key, &trust_client).unwrap();
+            // 협상 → 합의된 압축 적용 + 요청 하나
+            let hello = s.recv().unwrap();
+            s.send(&TritNetAdapter::reply(&hello)).unwrap();
+            s.codec = CtpCodec::negotiated(hello.handshake_caps() & crate::network::LOCAL_CAPS);
+            let msg = s.recv().unwrap();
+            s.send(&TritNetAdapter::reply(&msg)).unwrap();
+            s.codec.stats
+        });
+
+        let stream = TcpStream::connect(&addr).unwrap();
+        let mut s = SecureStream::connect(stream, &client_key, &PeerTrust::Only(vec![server_pub])).unwrap();
+        assert_eq!(s.negotiate().unwrap(), crate::network::PROTOCOL_VERSION);
+        s.send(&vote()).unwrap();
+        let reply = s.recv().unwrap();
+        assert_eq!(reply.status, StatusCode::Success);
+        assert_eq!(reply.payload.to_trit_string(), vote().payload.to_trit_string());
+        assert!(s.codec.enabled);
+        let stats = handle.join().unwrap();
+        assert_eq!(stats.skipped_small, 1);
+    }
+
+    #[test]
+    fn test_secure_server_serves_whole_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let (server_key, client_key) = (NodeKeypair::generate(), NodeKeypair::generate());
+        let server_pub = server_key.public;
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            TritNetAdapter::serve_secure(stream, &server_key, &PeerTrust::Any)
+        });
+
+        let reply = TritNetAdapter::send_secure_request(&addr, &client_key, &PeerTrust::Only(vec![server_pub]), &vote()).unwrap();
+        assert_eq!(reply.status, StatusCode::Success);
+        assert_eq!(reply.payload.to_trit_string(), vote().payload.to_trit_string());
+        handle.join().unwrap().unwrap();
+    }
+}
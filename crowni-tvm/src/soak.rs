@@ -0,0 +1,370 @@
+// ═══════════════════════════════════════════════════════════════
+// 장시간 소크 테스트 — 혼합 작업을 계속 돌리며 자원 · 기록 길이를 표본 추출
+//
+//   한 바퀴: 커널 보호 실행 · 저장소 쓰기/삭제 · 웹서버 요청(메모리 + 루프백 TCP)
+//            · DEX 왕복 스왑 · 로컬 3진 합의
+//   표본:    sample_every 바퀴마다 RSS · 저장소 크기/WAL · 기록 길이 · 열린 소켓
+//   판정:    워밍업(앞 1/4) 이후 표본을 세 구간으로 나눠 평균이 계속 오르고
+//            증가량이 허용치(절대값 · 5%)를 넘으면 한없이 자라는 지표로 본다
+//            — 용량 상한이 있는 RingLog 기록은 평탄해져 통과한다
+//
+//   P 모든 지표 안정 · O 표본 부족 · T 누수 의심 지표 있음
+//   RSS · 소켓은 /proc이 있는 리눅스에서만 잰다
+// ═══════════════════════════════════════════════════════════════
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::car::CrownyRuntime;
+use crate::dex::CrownyDEX;
+use crate::kernel::{CrownyKernel, KernelConfig};
+use crate::local_consensus::LocalConsensusEngine;
+use crate::output::JsonObject;
+use crate::permission::Action;
+use crate::scheduler::{TritPriority, TritResult};
+use crate::trit_store::{StoreValue, TritStore};
+use crate::webserver::{self, CrownyServer, CtpHeader, HttpMethod, HttpRequest};
+
+/// 기본 표본 간격 (바퀴)
+pub const DEFAULT_SAMPLE_EVERY: u64 = 500;
+/// 판정에 필요한 워밍업 이후 최소 표본 수
+pub const MIN_SAMPLES: usize = 6;
+/// 저장소 키 공간 — 쓰기와 삭제가 맞물려 항목 수는 이 안에 머문다
+const KEY_SPACE: u64 = 1000;
+/// 루프백 TCP 요청 간격 (바퀴)
+const SOCKET_EVERY: u64 = 10;
+/// 스왑 계정 보충 단위
+const SOAK_TOP_UP: u64 = 1_000_000;
+/// 증가 허용 비율
+const GROWTH_TOLERANCE: f64 = 0.05;
+
+/// 실행 범위 — 시간 · 바퀴 수 중 먼저 닿는 쪽에서 멈춘다
+#[derive(Debug, Clone, Copy)]
+pub struct SoakConfig {
+    pub duration: Option<Duration>,
+    pub rounds: Option<u64>,
+    pub sample_every: u64,
+}
+
+impl SoakConfig {
+    pub fn hours(hours: f64) -> Self {
+        Self { duration: Some(Duration::from_secs_f64(hours * 3600.0)), rounds: None, sample_every: DEFAULT_SAMPLE_EVERY }
+    }
+
+    pub fn rounds(rounds: u64) -> Self {
+        Self { duration: None, rounds: Some(rounds), sample_every: DEFAULT_SAMPLE_EVERY }
+    }
+
+    pub fn with_sample_every(mut self, rounds: u64) -> Self {
+        self.sample_every = rounds.max(1);
+        self
+    }
+}
+
+// ─────────────────────────────────────────────
+// 표본 · 판정
+// ─────────────────────────────────────────────
+
+/// 지표 — 이름, 누수로 보기 위한 최소 절대 증가량
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metric {
+    pub name: &'static str,
+    pub slack: u64,
+}
+
+pub const METRICS: &[Metric] = &[
+    Metric { name: "rss_kb", slack: 8 * 1024 },
+    Metric { name: "open_sockets", slack: 4 },
+    Metric { name: "store.entries", slack: 16 },
+    Metric { name: "store.bytes", slack: 4096 },
+    Metric { name: "store.wal", slack: 16 },
+    Metric { name: "car.history", slack: 16 },
+    Metric { name: "kernel.completed", slack: 16 },
+    Metric { name: "kernel.audit", slack: 16 },
+    Metric { name: "kernel.tx_history", slack: 16 },
+    Metric { name: "dex.swaps", slack: 16 },
+    Metric { name: "consensus.results", slack: 16 },
+];
+
+/// 한 시점의 지표 값 (잴 수 없는 지표는 빠진다)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub round: u64,
+    pub elapsed_ms: u64,
+    pub values: Vec<(&'static str, u64)>,
+}
+
+impl Sample {
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.values.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+    }
+}
+
+/// 지표별 판정
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub metric: &'static str,
+    pub first: u64,
+    pub last: u64,
+    /// 워밍업 이후 세 구간 평균
+    pub windows: [f64; 3],
+    pub leaking: bool,
+}
+
+impl Verdict {
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .str("metric", self.metric)
+            .int("first", self.first as i64)
+            .int("last", self.last as i64)
+            .float("window1", self.windows[0])
+            .float("window2", self.windows[1])
+            .float("window3", self.windows[2])
+            .bool("leaking", self.leaking)
+    }
+}
+
+/// 한 지표의 증가 판정 — 표본이 모자라면 None
+pub fn judge(metric: &Metric, samples: &[Sample]) -> Option<Verdict> {
+    let series: Vec<u64> = samples.iter().filter_map(|s| s.get(metric.name)).collect();
+    let steady = &series[series.len() / 4..];
+    if steady.len() < MIN_SAMPLES {
+        return None;
+    }
+    let third = steady.len() / 3;
+    let mean = |w: &[u64]| w.iter().sum::<u64>() as f64 / w.len() as f64;
+    let windows = [mean(&steady[..third]), mean(&steady[third..2 * third]), mean(&steady[2 * third..])];
+    let growth = windows[2] - windows[0];
+    let allowed = (metric.slack as f64).max(windows[0] * GROWTH_TOLERANCE);
+    let leaking = windows[0] < windows[1] && windows[1] < windows[2] && growth > allowed;
+    Some(Verdict { metric: metric.name, first: steady[0], last: *steady.last().unwrap(), windows, leaking })
+}
+
+pub struct SoakReport {
+    pub rounds: u64,
+    pub elapsed_ms: u64,
+    pub errors: u64,
+    pub samples: Vec<Sample>,
+    pub verdicts: Vec<Verdict>,
+}
+
+impl SoakReport {
+    fn new(rounds: u64, elapsed_ms: u64, errors: u64, samples: Vec<Sample>) -> Self {
+        let verdicts = METRICS.iter().filter_map(|m| judge(m, &samples)).collect();
+        Self { rounds, elapsed_ms, errors, samples, verdicts }
+    }
+
+    pub fn leaks(&self) -> impl Iterator<Item = &Verdict> {
+        self.verdicts.iter().filter(|v| v.leaking)
+    }
+
+    pub fn state(&self) -> i8 {
+        if self.leaks().next().is_some() {
+            -1
+        } else if self.verdicts.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .trit("state", self.state())
+            .int("rounds", self.rounds as i64)
+            .int("elapsed_ms", self.elapsed_ms as i64)
+            .int("errors", self.errors as i64)
+            .int("samples", self.samples.len() as i64)
+            .objects("verdicts", self.verdicts.iter().map(Verdict::to_json).collect())
+    }
+}
+
+// ─────────────────────────────────────────────
+// 프로세스 자원 (/proc)
+// ─────────────────────────────────────────────
+
+/// 상주 메모리 (KB)
+fn rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// 열린 소켓 파일 디스크립터 수
+fn open_sockets() -> Option<u64> {
+    let fds = std::fs::read_dir("/proc/self/fd").ok()?;
+    Some(fds.filter_map(|e| std::fs::read_link(e.ok()?.path()).ok())
+        .filter(|target| target.to_string_lossy().starts_with("socket:"))
+        .count() as u64)
+}
+
+// ─────────────────────────────────────────────
+// 작업
+// ─────────────────────────────────────────────
+
+struct Workload {
+    kernel: CrownyKernel,
+    store: TritStore,
+    server: CrownyServer,
+    car: CrownyRuntime,
+    listener: Option<TcpListener>,
+    dex: CrownyDEX,
+    pool: String,
+    consensus: LocalConsensusEngine,
+}
+
+impl Workload {
+    fn new() -> Self {
+        let mut dex = CrownyDEX::new();
+        dex.mint("lp", "CRWN", 1_000_000_000);
+        dex.mint("lp", "USDT", 200_000_000);
+        dex.mint("soak", "CRWN", SOAK_TOP_UP);
+        dex.mint("soak", "USDT", SOAK_TOP_UP);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("lp", &pool, 1_000_000_000, 200_000_000).ok();
+        Self {
+            kernel: CrownyKernel::boot(KernelConfig::default()),
+            store: TritStore::new(),
+            server: webserver::create_demo_server(),
+            car: CrownyRuntime::new(),
+            // 루프백을 못 열면 메모리 요청만
+            listener: TcpListener::bind("127.0.0.1:0").ok(),
+            dex,
+            pool,
+            consensus: LocalConsensusEngine::openclaw_default(),
+        }
+    }
+
+    /// 한 바퀴 — 실패한 경로 수
+    fn round(&mut self, round: u64) -> u64 {
+        let mut errors = 0;
+
+        let r = self.kernel.execute_guarded("soak", "soak.data", Action::Read, "soak-read",
+            TritPriority::Normal, Box::new(|| TritResult::Success));
+        errors += u64::from(r.task_result != Some(TritResult::Success));
+        // 서버의 KernelWatch처럼 바퀴마다 감시 — 쌓인 하트비트를 비운다
+        self.kernel.watch();
+
+        self.store.set(&format!("soak:{}", round % KEY_SPACE), StoreValue::Int(round as i64));
+        self.store.delete(&format!("soak:{}", (round + KEY_SPACE / 2) % KEY_SPACE));
+
+        let req = HttpRequest::new(HttpMethod::Post, "/run")
+            .with_body("넣어 7\n넣어 6\n곱해\n종료")
+            .with_ctp(CtpHeader::success());
+        errors += u64::from(self.server.handle(&req, &mut self.car).status != 200);
+        if round.is_multiple_of(SOCKET_EVERY) {
+            errors += u64::from(self.loopback_request().is_err());
+        }
+
+        let token_in = if round.is_multiple_of(2) { "CRWN" } else { "USDT" };
+        // 왕복 수수료로 줄어든 잔액은 채워 넣는다
+        if self.dex.balance("soak", token_in) < SOAK_TOP_UP / 2 {
+            self.dex.mint("soak", token_in, SOAK_TOP_UP);
+        }
+        errors += u64::from(self.dex.swap("soak", &self.pool, token_in, 100).is_err());
+
+        self.consensus.simulate_consensus(&format!("소크 {}", round));
+        errors
+    }
+
+    /// 실제 TCP 연결로 GET / — 연결이 닫히는지(소켓 누수)까지 본다
+    fn loopback_request(&mut self) -> std::io::Result<()> {
+        let Some(listener) = &self.listener else { return Ok(()) };
+        let mut client = TcpStream::connect(listener.local_addr()?)?;
+        client.write_all(b"GET / HTTP/1.1\r\nHost: soak\r\nX-Crowny-Trit: PPOOOOOOO\r\nConnection: close\r\n\r\n")?;
+        let (conn, _) = listener.accept()?;
+        self.server.serve_connection(conn, &mut self.car)?;
+        let mut response = Vec::new();
+        client.read_to_end(&mut response)?;
+        if response.starts_with(b"HTTP/1.1 200") {
+            Ok(())
+        } else {
+            Err(std::io::Error::other("200이 아닌 응답"))
+        }
+    }
+
+    fn sample(&self, round: u64, elapsed_ms: u64) -> Sample {
+        let mut values = vec![
+            ("store.entries", self.store.len() as u64),
+            ("store.bytes", self.store.estimated_size() as u64),
+            ("store.wal", self.store.wal_len() as u64),
+            ("car.history", self.car.history().len() as u64),
+            ("kernel.completed", self.kernel.scheduler.completed_count() as u64),
+            ("kernel.audit", self.kernel.permission.audit_count() as u64),
+            ("kernel.tx_history", self.kernel.transaction.history_len() as u64),
+            ("dex.swaps", self.dex.swap_history.len() as u64),
+            ("consensus.results", self.consensus.results.len() as u64),
+        ];
+        values.extend(rss_kb().map(|v| ("rss_kb", v)));
+        values.extend(open_sockets().map(|v| ("open_sockets", v)));
+        Sample { round, elapsed_ms, values }
+    }
+}
+
+/// 소크 실행 — 표본마다 콜백 (긴 실행의 진행 표시)
+pub fn run(config: SoakConfig, mut on_sample: impl FnMut(&Sample)) -> SoakReport {
+    let mut work = Workload::new();
+    let started = Instant::now();
+    let mut samples = Vec::new();
+    let mut errors = 0;
+    let mut round = 0;
+    loop {
+        let out_of_time = config.duration.is_some_and(|d| started.elapsed() >= d);
+        let out_of_rounds = config.rounds.is_some_and(|n| round >= n);
+        if out_of_time || out_of_rounds {
+            break;
+        }
+        errors += work.round(round);
+        round += 1;
+        if round.is_multiple_of(config.sample_every) {
+            let sample = work.sample(round, started.elapsed().as_millis() as u64);
+            on_sample(&sample);
+            samples.push(sample);
+        }
+    }
+    SoakReport::new(round, started.elapsed().as_millis() as u64, errors, samples)
+}
+
+// ═══ 테스트 ═══
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(name: &'static str, values: &[u64]) -> Vec<Sample> {
+        values.iter().enumerate()
+            .map(|(i, v)| Sample { round: i as u64, elapsed_ms: 0, values: vec![(name, *v)] })
+            .collect()
+    }
+
+    #[test]
+    fn test_judge_flags_unbounded_growth_only() {
+        let metric = Metric { name: "x", slack: 16 };
+        // 선형 증가 → 누수
+        let growing: Vec<u64> = (0..24).map(|i| 100 + i * 50).collect();
+        assert!(judge(&metric, &series("x", &growing)).unwrap().leaking);
+        // 워밍업 후 상한에서 평탄 (RingLog) → 통과
+        let capped: Vec<u64> = (0..24).map(|i| (i * 200).min(1000)).collect();
+        assert!(!judge(&metric, &series("x", &capped)).unwrap().leaking);
+        // 허용치 안의 흔들림 → 통과
+        let jitter: Vec<u64> = (0..24).map(|i| 5000 + i).collect();
+        assert!(!judge(&metric, &series("x", &jitter)).unwrap().leaking);
+        // 표본 부족
+        assert!(judge(&metric, &series("x", &[1, 2, 3])).is_none());
+    }
+
+    #[test]
+    fn test_short_soak_samples_every_path() {
+        let mut seen = 0;
+        let report = run(SoakConfig::rounds(120).with_sample_every(10), |_| seen += 1);
+        assert_eq!((report.rounds, report.errors, seen), (120, 0, 12));
+        let last = report.samples.last().unwrap();
+        assert_eq!(last.get("store.entries"), Some(120));
+        assert_eq!(last.get("dex.swaps"), Some(120));
+        // 상한에 닿기 전까지 바퀴마다 한 건씩 쌓이는 스왑 기록은 자라는 지표로 잡힌다
+        assert!(report.verdicts.iter().any(|v| v.metric == "consensus.results"));
+        assert!(report.leaks().any(|v| v.metric == "dex.swaps"));
+        assert_eq!(report.state(), -1);
+    }
+}
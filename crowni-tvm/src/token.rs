@@ -61,6 +61,40 @@ impl Default for TritPolicy {
     }
 }
 
+impl TritPolicy {
+    /// 분할 소유 지분 — 추가 발행 불가, 소각은 주주 투표(바이아웃) 뒤에만
+    pub fn fractional_shares() -> Self {
+        Self {
+            mintable: TritPerm::Deny,
+            burnable: TritPerm::Pending,
+            transferable: TritPerm::Allow,
+            stakeable: TritPerm::Deny,
+            consensus_required: true,
+        }
+    }
+}
+
+impl CrownyToken {
+    pub fn new(name: &str, symbol: &str, supply: u64, issuer: &str, trit_policy: TritPolicy) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            total_supply: supply,
+            decimals: 9,
+            issuer: issuer.to_string(),
+            created_at: now_ms(),
+            trit_policy,
+        }
+    }
+}
+
+impl crate::account::AccountLedger for TokenEngine {
+    fn ledger_name(&self) -> &str { "token" }
+    fn holdings_of(&self, name: &str) -> Vec<(String, u64)> {
+        vec![(self.token.symbol.clone(), self.balance_of(name)), (format!("{}:staked", self.token.symbol), self.staked_of(name))]
+    }
+}
+
 // ═══════════════════════════════════════════════
 // 지갑
 // ═══════════════════════════════════════════════
@@ -180,15 +214,7 @@ pub struct TokenEngine {
 
 impl TokenEngine {
     pub fn new(name: &str, symbol: &str, supply: u64, issuer: &str) -> Self {
-        let token = CrownyToken {
-            name: name.to_string(),
-            symbol: symbol.to_string(),
-            total_supply: supply,
-            decimals: 9,
-            issuer: issuer.to_string(),
-            created_at: now_ms(),
-            trit_policy: TritPolicy::default(),
-        };
+        let token = CrownyToken::new(name, symbol, supply, issuer, TritPolicy::default());
 
         let mut wallets = HashMap::new();
         let mut issuer_wallet = Wallet::new(issuer);
@@ -9,10 +9,18 @@
 ///!
 ///! WAL(Write-Ahead Log) + 3진 상태 머신
 ///! 2진 DB의 commit/rollback을 3진으로 완전 감싼다.
+///!
+///! 낙관적 동시성 (MVCC):
+///!   키마다 버전 — 쓰기마다 새 버전, 읽기(read)는 본 버전을 기록.
+///!   커밋 시 그 사이 다른 TX가 같은 키를 건드렸으면 T + 충돌 보고서.
+///!   쓰기는 저장소에 바로 반영되고, 확정 값은 커밋 때 따로 기록한다.
+///!   읽기 전용 태스크는 snapshot()으로 확정된 값만 일관되게 본다.
 
 use std::collections::HashMap;
 use std::time::Instant;
 
+use crate::ring_log::RingLog;
+
 // ─────────────────────────────────────────────
 // 트랜잭션 상태
 // ─────────────────────────────────────────────
@@ -67,6 +75,56 @@ pub struct WalEntry {
     pub key: String,
     pub old_value: Option<String>,
     pub new_value: Option<String>,
+    /// 쓰기 전 · 후 키 버전 (0 = 없던 키)
+    pub old_version: u64,
+    pub new_version: u64,
+}
+
+/// 충돌 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    Read,  // 읽은 뒤 다른 TX가 바꿈
+    Write, // 쓴 뒤 다른 TX가 덮어씀
+}
+
+/// 키 충돌 — 기대한 버전과 실제 버전
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub key: String,
+    pub kind: ConflictKind,
+    pub expected: u64,
+    pub found: u64,
+}
+
+/// 커밋 충돌 보고서
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictReport {
+    pub tx_id: TxId,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl std::fmt::Display for ConflictReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TX[{:04}] 충돌 {}건:", self.tx_id, self.conflicts.len())?;
+        for c in &self.conflicts {
+            let kind = match c.kind { ConflictKind::Read => "읽기", ConflictKind::Write => "쓰기" };
+            write!(f, " {}({} v{}→v{})", c.key, kind, c.expected, c.found)?;
+        }
+        Ok(())
+    }
+}
+
+/// 읽기 스냅샷 — 만든 시점의 확정 값 (진행 중 TX의 쓰기 제외)
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub version: u64,
+    data: HashMap<String, String>,
+}
+
+impl Snapshot {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.data.get(key).map(|s| s.as_str())
+    }
 }
 
 /// 트랜잭션
@@ -75,9 +133,13 @@ pub struct Transaction {
     pub id: TxId,
     pub state: TxState,
     pub wal: Vec<WalEntry>,        // 변경 로그
+    /// 읽은 키 → 본 버전 (자기 쓰기 전 첫 읽기만)
+    pub reads: HashMap<String, u64>,
     pub created_at: Instant,
     pub finished_at: Option<Instant>,
     pub label: String,
+    /// 커밋 충돌로 취소된 경우 보고서
+    pub conflict: Option<ConflictReport>,
 }
 
 impl Transaction {
@@ -86,9 +148,11 @@ impl Transaction {
             id,
             state: TxState::Pending,
             wal: Vec::new(),
+            reads: HashMap::new(),
             created_at: Instant::now(),
             finished_at: None,
             label: label.to_string(),
+            conflict: None,
         }
     }
 }
@@ -107,31 +171,56 @@ impl std::fmt::Display for Transaction {
 pub struct TransactionEngine {
     /// 현재 데이터 저장소 (key→value)
     store: HashMap<String, String>,
+    /// 키 버전 (key→마지막 쓰기 버전)
+    versions: HashMap<String, u64>,
+    /// 확정 값 (key→마지막 커밋 값) — 스냅샷의 원본
+    committed: HashMap<String, String>,
+    /// 버전 시계 — 쓰기마다 +1
+    clock: u64,
     /// 활성 트랜잭션
     pub active: HashMap<TxId, Transaction>,
-    /// 완료된 트랜잭션 이력
-    history: Vec<Transaction>,
+    /// 완료된 트랜잭션 이력 (상한 — 오래된 것부터 축출)
+    history: RingLog<Transaction>,
     /// 다음 TX ID
     next_id: TxId,
     /// 통계
     pub stats_commit: u64,
     pub stats_pending: u64,
     pub stats_rollback: u64,
+    /// 커밋 충돌로 취소된 수 (stats_rollback에도 포함)
+    pub stats_conflict: u64,
 }
 
 impl TransactionEngine {
     pub fn new() -> Self {
         Self {
             store: HashMap::new(),
+            versions: HashMap::new(),
+            committed: HashMap::new(),
+            clock: 0,
             active: HashMap::new(),
-            history: Vec::new(),
+            history: RingLog::default(),
             next_id: 1,
             stats_commit: 0,
             stats_pending: 0,
             stats_rollback: 0,
+            stats_conflict: 0,
         }
     }
 
+    /// 키의 현재 버전 (없던 키 0)
+    pub fn version(&self, key: &str) -> u64 {
+        self.versions.get(key).copied().unwrap_or(0)
+    }
+
+    /// 새 버전 발급 → (이전, 새)
+    fn bump(&mut self, key: &str) -> (u64, u64) {
+        let old = self.version(key);
+        self.clock += 1;
+        self.versions.insert(key.to_string(), self.clock);
+        (old, self.clock)
+    }
+
     /// 트랜잭션 시작 → Pending(O) 상태
     pub fn begin(&mut self, label: &str) -> TxId {
         let id = self.next_id;
@@ -152,13 +241,18 @@ impl TransactionEngine {
         }
 
         let old_value = self.store.get(key).cloned();
-        tx.wal.push(WalEntry {
-            key: key.to_string(),
-            old_value,
-            new_value: Some(value.to_string()),
-        });
+        let (old_version, new_version) = self.bump(key);
+        if let Some(tx) = self.active.get_mut(&tx_id) {
+            tx.wal.push(WalEntry {
+                key: key.to_string(),
+                old_value,
+                new_value: Some(value.to_string()),
+                old_version,
+                new_version,
+            });
+        }
 
-        // 즉시 적용 (optimistic)
+        // 즉시 적용 (optimistic) — 충돌은 커밋 때 판정
         self.store.insert(key.to_string(), value.to_string());
         Ok(())
     }
@@ -173,21 +267,81 @@ impl TransactionEngine {
         }
 
         let old_value = self.store.get(key).cloned();
-        tx.wal.push(WalEntry {
-            key: key.to_string(),
-            old_value,
-            new_value: None,  // 삭제
-        });
+        let (old_version, new_version) = self.bump(key);
+        if let Some(tx) = self.active.get_mut(&tx_id) {
+            tx.wal.push(WalEntry {
+                key: key.to_string(),
+                old_value,
+                new_value: None,  // 삭제
+                old_version,
+                new_version,
+            });
+        }
 
         self.store.remove(key);
         Ok(())
     }
 
+    /// 트랜잭션 안에서 읽기 — 본 버전을 기록해 커밋 때 검증
+    pub fn read(&mut self, tx_id: TxId, key: &str) -> Result<Option<String>, String> {
+        let version = self.version(key);
+        let tx = self.active.get_mut(&tx_id)
+            .ok_or_else(|| format!("TX[{}] 존재하지 않음", tx_id))?;
+        if tx.state != TxState::Pending {
+            return Err(format!("TX[{}] 이미 완료됨: {}", tx_id, tx.state));
+        }
+        // 자기 쓰기를 읽는 건 검증 대상 아님
+        if !tx.wal.iter().any(|e| e.key == key) {
+            tx.reads.entry(key.to_string()).or_insert(version);
+        }
+        Ok(self.store.get(key).cloned())
+    }
+
+    /// 커밋 전 충돌 검사 — 키 이름 순
+    fn conflicts(&self, tx: &Transaction) -> Vec<Conflict> {
+        let mut out = Vec::new();
+        let mut written: Vec<&str> = tx.wal.iter().map(|e| e.key.as_str()).collect();
+        written.sort();
+        written.dedup();
+        for key in written {
+            // 마지막 자기 쓰기 이후 다른 TX가 덮어썼나
+            let Some(last) = tx.wal.iter().rev().find(|e| e.key == key) else { continue };
+            let found = self.version(key);
+            if found != last.new_version {
+                out.push(Conflict { key: key.to_string(), kind: ConflictKind::Write, expected: last.new_version, found });
+            }
+        }
+        for (key, &seen) in &tx.reads {
+            // 읽은 뒤 자기가 썼으면 그 직전 버전, 아니면 현재 버전과 비교
+            let found = tx.wal.iter().find(|e| &e.key == key).map_or_else(|| self.version(key), |e| e.old_version);
+            if found != seen {
+                out.push(Conflict { key: key.clone(), kind: ConflictKind::Read, expected: seen, found });
+            }
+        }
+        out.sort_by(|a, b| a.key.cmp(&b.key));
+        out
+    }
+
     /// 커밋 → Committed(P) 상태
+    /// 다른 TX와 충돌하면 자동 롤백 → RolledBack(T), 보고서는 conflict_report()
     pub fn commit(&mut self, tx_id: TxId) -> Result<TxState, String> {
         let mut tx = self.active.remove(&tx_id)
             .ok_or_else(|| format!("TX[{}] 존재하지 않음", tx_id))?;
 
+        let conflicts = self.conflicts(&tx);
+        if !conflicts.is_empty() {
+            tx.conflict = Some(ConflictReport { tx_id, conflicts });
+            self.stats_conflict += 1;
+            return Ok(self.abort(tx));
+        }
+
+        // 키마다 이 TX의 마지막 쓰기가 새 확정 값
+        for entry in &tx.wal {
+            match &entry.new_value {
+                Some(v) => { self.committed.insert(entry.key.clone(), v.clone()); }
+                None => { self.committed.remove(&entry.key); }
+            }
+        }
         tx.state = TxState::Committed;
         tx.finished_at = Some(Instant::now());
         self.stats_commit += 1;
@@ -201,11 +355,25 @@ impl TransactionEngine {
     /// 롤백 → RolledBack(T) 상태
     /// WAL을 역순으로 되돌림
     pub fn rollback(&mut self, tx_id: TxId) -> Result<TxState, String> {
-        let mut tx = self.active.remove(&tx_id)
+        let tx = self.active.remove(&tx_id)
             .ok_or_else(|| format!("TX[{}] 존재하지 않음", tx_id))?;
+        Ok(self.abort(tx))
+    }
 
-        // WAL 역순 적용 (undo)
+    /// WAL 역순 undo → T
+    /// 이미 다른 TX가 덮어쓴 키는 건드리지 않고, 그 TX의 되돌림 기준을 내 이전 값으로 이어 붙인다
+    fn abort(&mut self, mut tx: Transaction) -> TxState {
         for entry in tx.wal.iter().rev() {
+            if self.version(&entry.key) != entry.new_version {
+                let next = self.active.values_mut()
+                    .flat_map(|t| t.wal.iter_mut())
+                    .find(|e| e.key == entry.key && e.old_version == entry.new_version);
+                if let Some(next) = next {
+                    next.old_value = entry.old_value.clone();
+                    next.old_version = entry.old_version;
+                }
+                continue;
+            }
             match &entry.old_value {
                 Some(old) => {
                     self.store.insert(entry.key.clone(), old.clone());
@@ -214,6 +382,12 @@ impl TransactionEngine {
                     self.store.remove(&entry.key);
                 }
             }
+            // 버전도 되돌림 — 덮어쓴 것이 없던 일이 된다
+            if entry.old_version == 0 {
+                self.versions.remove(&entry.key);
+            } else {
+                self.versions.insert(entry.key.clone(), entry.old_version);
+            }
         }
 
         tx.state = TxState::RolledBack;
@@ -223,7 +397,17 @@ impl TransactionEngine {
 
         let state = tx.state;
         self.history.push(tx);
-        Ok(state)
+        state
+    }
+
+    /// 커밋 충돌 보고서 (충돌로 취소된 TX만)
+    pub fn conflict_report(&self, tx_id: TxId) -> Option<&ConflictReport> {
+        self.history.iter().rev().find(|t| t.id == tx_id).and_then(|t| t.conflict.as_ref())
+    }
+
+    /// 읽기 스냅샷 — 지금 시각까지 커밋된 값만 (진행 중 TX의 쓰기 제외)
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot { version: self.clock, data: self.committed.clone() }
     }
 
     /// 값 읽기 (트랜잭션 외부에서도 가능)
@@ -245,6 +429,11 @@ impl TransactionEngine {
     }
 
     /// 활성 트랜잭션 수
+    /// 완료된 트랜잭션 이력 수
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
     pub fn active_count(&self) -> usize {
         self.active.len()
     }
@@ -259,8 +448,8 @@ impl TransactionEngine {
         println!("╔══ 트랜잭션 엔진 상태 ════════════════════╗");
         println!("║ 저장소: {} 키 | 활성TX: {} | 이력: {}",
             self.store.len(), self.active.len(), self.history.len());
-        println!("║ 통계: 확정:{} 보류:{} 취소:{}",
-            self.stats_commit, self.stats_pending, self.stats_rollback);
+        println!("║ 통계: 확정:{} 보류:{} 취소:{} (충돌:{}) 버전:{}",
+            self.stats_commit, self.stats_pending, self.stats_rollback, self.stats_conflict, self.clock);
 
         if !self.active.is_empty() {
             println!("║ ── 활성 트랜잭션 ──");
@@ -333,4 +522,97 @@ mod tests {
         // 0:1:2 → 거부
         assert_eq!(TransactionEngine::consensus(&[Holding, Rejected, Rejected]), Rejected);
     }
+
+    #[test]
+    fn test_write_conflict_first_committer_wins() {
+        let mut engine = TransactionEngine::new();
+        let tx1 = engine.begin("작업1");
+        let tx2 = engine.begin("작업2");
+        engine.set(tx1, "설정", "A").unwrap();
+        engine.set(tx2, "설정", "B").unwrap();
+
+        assert_eq!(engine.commit(tx2).unwrap(), TxState::Committed);
+        // 먼저 쓴 tx1은 덮어쓰였음 → T, tx2의 값은 지켜짐
+        assert_eq!(engine.commit(tx1).unwrap(), TxState::RolledBack);
+        assert_eq!(engine.get("설정"), Some("B"));
+        let report = engine.conflict_report(tx1).unwrap();
+        assert_eq!(report.conflicts, vec![Conflict { key: "설정".into(), kind: ConflictKind::Write, expected: 1, found: 2 }]);
+        assert!(engine.conflict_report(tx2).is_none());
+        assert_eq!((engine.stats_conflict, engine.stats_rollback), (1, 1));
+    }
+
+    #[test]
+    fn test_read_conflict_prevents_lost_update() {
+        let mut engine = TransactionEngine::new();
+        let tx0 = engine.begin("초기화");
+        engine.set(tx0, "잔액", "100").unwrap();
+        engine.commit(tx0).unwrap();
+
+        let tx1 = engine.begin("입금30");
+        let tx2 = engine.begin("입금50");
+        let b1: i64 = engine.read(tx1, "잔액").unwrap().unwrap().parse().unwrap();
+        let b2: i64 = engine.read(tx2, "잔액").unwrap().unwrap().parse().unwrap();
+        engine.set(tx2, "잔액", &(b2 + 50).to_string()).unwrap();
+        assert_eq!(engine.commit(tx2).unwrap(), TxState::Committed);
+
+        engine.set(tx1, "잔액", &(b1 + 30).to_string()).unwrap();
+        assert_eq!(engine.commit(tx1).unwrap(), TxState::RolledBack);
+        assert_eq!(engine.get("잔액"), Some("150"));
+        let report = engine.conflict_report(tx1).unwrap();
+        assert_eq!(report.conflicts[0].kind, ConflictKind::Read);
+        assert!(report.to_string().contains("잔액(읽기"));
+    }
+
+    #[test]
+    fn test_snapshot_and_interleaved_rollback() {
+        let mut engine = TransactionEngine::new();
+        let tx0 = engine.begin("초기화");
+        engine.set(tx0, "키", "원래").unwrap();
+        engine.commit(tx0).unwrap();
+
+        let tx1 = engine.begin("변경1");
+        let tx2 = engine.begin("변경2");
+        engine.set(tx1, "키", "하나").unwrap();
+        engine.set(tx2, "키", "둘").unwrap();
+        engine.set(tx2, "새키", "값").unwrap();
+
+        // 스냅샷은 확정 값만
+        let snap = engine.snapshot();
+        assert_eq!((snap.get("키"), snap.get("새키"), snap.data.len()), (Some("원래"), None, 1));
+
+        // tx1 취소 — tx2가 덮어쓴 값은 그대로, tx2의 되돌림 기준은 "원래"로
+        engine.rollback(tx1).unwrap();
+        assert_eq!(engine.get("키"), Some("둘"));
+        engine.rollback(tx2).unwrap();
+        assert_eq!(engine.get("키"), Some("원래"));
+        assert_eq!(engine.get("새키"), None);
+        assert_eq!(engine.version("새키"), 0);
+        assert_eq!(engine.snapshot().get("키"), snap.get("키"));
+    }
+
+    #[test]
+    fn test_snapshot_sees_commit_over_earlier_active_writer() {
+        let mut engine = TransactionEngine::new();
+        let tx0 = engine.begin("초기화");
+        engine.set(tx0, "키", "원래").unwrap();
+        engine.set(tx0, "지울키", "값").unwrap();
+        engine.commit(tx0).unwrap();
+
+        // B가 먼저 쓰고 진행 중, C가 같은 키를 쓰고 커밋
+        let b = engine.begin("B");
+        let c = engine.begin("C");
+        engine.set(b, "키", "B").unwrap();
+        engine.set(c, "키", "C").unwrap();
+        engine.delete(c, "지울키").unwrap();
+        assert_eq!(engine.commit(c).unwrap(), TxState::Committed);
+
+        // B의 쓰기 전 값이 아니라 C의 확정 값
+        let snap = engine.snapshot();
+        assert_eq!((snap.get("키"), snap.get("지울키")), (Some("C"), None));
+
+        // B는 덮어쓰여 T — 확정 값은 그대로
+        assert_eq!(engine.commit(b).unwrap(), TxState::RolledBack);
+        assert_eq!(engine.snapshot().get("키"), Some("C"));
+        assert_eq!(engine.get("키"), Some("C"));
+    }
 }
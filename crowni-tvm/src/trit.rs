@@ -50,6 +50,22 @@ impl Trit {
         Trit::from_i8(self.to_i8().max(other.to_i8()))
     }
 
+    /// 전가산기 — a + b + 올림 (-3..+3) → (합 자리, 올림)
+    pub fn add_carry(self, other: Trit, carry: Trit) -> (Trit, Trit) {
+        match self.to_i8() + other.to_i8() + carry.to_i8() {
+            -3 => (Trit::O, Trit::T),
+            -2 => (Trit::P, Trit::T),
+            2 => (Trit::T, Trit::P),
+            3 => (Trit::O, Trit::P),
+            s => (Trit::from_i8(s), Trit::O),
+        }
+    }
+
+    /// 트릿 곱 — 올림 없음
+    pub fn product(self, other: Trit) -> Trit {
+        Trit::from_i8(self.to_i8() * other.to_i8())
+    }
+
     /// 문자 → Trit
     pub fn from_char(c: char) -> Option<Self> {
         match c {
@@ -97,6 +113,10 @@ const PAIR_TO_INDEX: [(Trit, Trit); 9] = [
 ];
 
 impl Word6 {
+    pub const ZERO: Word6 = Word6 { trits: [Trit::O; 6] };
+    /// 표현 범위 ±(3^6 - 1)/2
+    pub const MAX: i16 = 364;
+
     pub fn new(trits: [Trit; 6]) -> Self {
         Self { trits }
     }
@@ -149,6 +169,49 @@ impl Word6 {
         (val + 4) as u8
     }
 
+    // ── 균형3진 산술 — i16을 거치지 않고 트릿 단위로 올림 전파 ──
+
+    /// 부호 반전: 각 트릿 T↔P (오버플로 없음)
+    pub fn neg(&self) -> Self {
+        Self { trits: self.trits.map(Trit::not) }
+    }
+
+    /// 덧셈 — 최상위 올림은 high[0]에 보고
+    pub fn add(&self, other: &Word6) -> Word6Result {
+        let mut acc = [Trit::O; 12];
+        acc[..6].copy_from_slice(&self.trits);
+        add_into(&mut acc, &other.trits, 0);
+        Word6Result::split(acc)
+    }
+
+    pub fn sub(&self, other: &Word6) -> Word6Result {
+        self.add(&other.neg())
+    }
+
+    /// 곱셈 — 자리별 부분곱(트릿 곱)을 이동해 12트릿 누산기에 더한다
+    pub fn mul(&self, other: &Word6) -> Word6Result {
+        let mut acc = [Trit::O; 12];
+        for (i, &m) in other.trits.iter().enumerate() {
+            if m != Trit::O {
+                add_into(&mut acc, &self.trits.map(|t| t.product(m)), i);
+            }
+        }
+        Word6Result::split(acc)
+    }
+
+    /// 자리 이동 — n>0: ×3^n (밀려난 상위 트릿은 high), n<0: ÷3^|n| 반올림 (하위 트릿 버림)
+    pub fn shift(&self, n: i32) -> Word6Result {
+        assert!((-6..=6).contains(&n), "이동 범위 초과: {} (허용: -6 ~ +6)", n);
+        let mut acc = [Trit::O; 12];
+        for (i, &t) in self.trits.iter().enumerate() {
+            let at = i as i32 + n;
+            if at >= 0 {
+                acc[at as usize] = t;
+            }
+        }
+        Word6Result::split(acc)
+    }
+
     /// "TOOPPT" 같은 문자열에서 파싱 (상위→하위 순)
     pub fn from_trit_str(s: &str) -> Option<Self> {
         let chars: Vec<char> = s.chars().collect();
@@ -161,6 +224,54 @@ impl Word6 {
     }
 }
 
+/// acc[offset..]에 addend를 더하고 올림을 끝까지 전파
+fn add_into(acc: &mut [Trit; 12], addend: &[Trit; 6], offset: usize) {
+    let mut carry = Trit::O;
+    for (i, slot) in acc.iter_mut().enumerate().skip(offset) {
+        let b = addend.get(i - offset).copied().unwrap_or(Trit::O);
+        if b == Trit::O && carry == Trit::O && i >= offset + 6 {
+            break;
+        }
+        (*slot, carry) = slot.add_carry(b, carry);
+    }
+}
+
+/// Word6 산술 결과 — 값 = high × 3^6 + low. high가 0이 아니면 오버플로
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Word6Result {
+    pub low: Word6,
+    pub high: Word6,
+}
+
+impl Word6Result {
+    fn split(acc: [Trit; 12]) -> Self {
+        let mut low = Word6::ZERO;
+        let mut high = Word6::ZERO;
+        low.trits.copy_from_slice(&acc[..6]);
+        high.trits.copy_from_slice(&acc[6..]);
+        Self { low, high }
+    }
+
+    pub fn overflowed(&self) -> bool {
+        self.high != Word6::ZERO
+    }
+
+    /// 6트릿에 담기면 Some
+    pub fn checked(&self) -> Option<Word6> {
+        (!self.overflowed()).then_some(self.low)
+    }
+
+    /// 상위를 버린 값 (mod 3^6, 균형 범위)
+    pub fn wrapping(&self) -> Word6 {
+        self.low
+    }
+
+    /// 12트릿 전체 값
+    pub fn to_wide_decimal(self) -> i32 {
+        self.high.to_decimal() as i32 * 729 + self.low.to_decimal() as i32
+    }
+}
+
 impl fmt::Display for Word6 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for i in (0..6).rev() {
@@ -209,6 +320,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn word_add_sub_match_integers() {
+        for a in (-364..=364i16).step_by(7) {
+            for b in -364..=364i16 {
+                let (x, y) = (Word6::from_decimal(a), Word6::from_decimal(b));
+                let sum = x.add(&y);
+                assert_eq!(sum.to_wide_decimal(), (a + b) as i32, "{} + {}", a, b);
+                assert_eq!(sum.overflowed(), (a + b).abs() > Word6::MAX);
+                assert_eq!(x.sub(&y).to_wide_decimal(), (a - b) as i32, "{} - {}", a, b);
+            }
+            assert_eq!(Word6::from_decimal(a).neg().to_decimal(), -a);
+        }
+        // 364 + 1 = 365 = 1×729 − 364 → 올림 P, 하위 TTTTTT
+        let r = Word6::from_decimal(364).add(&Word6::from_decimal(1));
+        assert_eq!(r.high.to_decimal(), 1);
+        assert_eq!(r.wrapping().to_string(), "TTTTTT");
+        assert_eq!(r.checked(), None);
+    }
+
+    #[test]
+    fn word_mul_and_shift() {
+        for a in (-364..=364i16).step_by(5) {
+            for b in (-364..=364i16).step_by(3) {
+                let p = Word6::from_decimal(a).mul(&Word6::from_decimal(b));
+                assert_eq!(p.to_wide_decimal(), a as i32 * b as i32, "{} × {}", a, b);
+                assert_eq!(p.checked().is_some(), (a as i32 * b as i32).abs() <= Word6::MAX as i32);
+            }
+        }
+        let w = Word6::from_decimal(40); // OOPPPP
+        assert_eq!(w.shift(2).checked().map(|x| x.to_decimal()), Some(360));
+        assert_eq!(w.shift(3).to_wide_decimal(), 1080);
+        assert!(w.shift(3).overflowed());
+        // 오른쪽 이동은 반올림 나눗셈: 40/3 = 13.3 → 13, 40/9 = 4.4 → 4
+        assert_eq!(w.shift(-1).low.to_decimal(), 13);
+        assert_eq!(w.shift(-2).low.to_decimal(), 4);
+        assert_eq!(Word6::from_decimal(-364).shift(-6).low, Word6::ZERO);
+    }
+
     #[test]
     fn center_is_zero() {
         // (4,4,4) = 중심 = OOOOOO = decimal 0
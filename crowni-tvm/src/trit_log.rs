@@ -14,12 +14,23 @@
 ///!   - 권한 감사 로그
 ///!   - 메트릭 수집 (카운터/게이지/히스토그램)
 ///!   - 알림 규칙 (임계치 초과 시)
+///!   - 싱크 (LogSink) — 회전 파일 싱크: JSON-lines, 크기 · 나이 기준 회전, flush()
 ///!
 ///! 모든 이벤트는 TritState 포함.
+///!
+///! 빠른 경로:
+///!   tlog!/tlog_debug!/tlog_trace! — 레벨 확인 후에만 인자 포맷 (format_args!)
+///!   STATIC_MIN_LEVEL 미만은 컴파일 시점에 제거 (릴리스: Trace, strip-debug-logs: Debug 이하)
+///!   밀려난 이벤트의 버퍼를 재사용 → 정상 상태에서 할당 없음
 
 use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH, Instant};
 use crate::car::TritState;
+use crate::output::JsonObject;
+use crate::query::{Page, Query, Queryable};
 
 // ─────────────────────────────────────────────
 // 이벤트
@@ -49,8 +60,52 @@ impl std::fmt::Display for Level {
     }
 }
 
+/// 컴파일 시점 최소 레벨 — 이보다 낮은 tlog! 호출은 분기째 사라진다
+pub const STATIC_MIN_LEVEL: Level = if cfg!(feature = "strip-debug-logs") {
+    Level::Info
+} else if cfg!(debug_assertions) {
+    Level::Trace
+} else {
+    Level::Debug
+};
+
+/// 컴파일 시점 레벨 필터 (상수 접힘)
+pub const fn static_enabled(level: Level) -> bool {
+    level as u8 >= STATIC_MIN_LEVEL as u8
+}
+
+/// 레벨 확인 후에만 인자를 포맷해 기록
+/// tlog!(log, Level::Debug, Category::State, "출처", TritState::Pending, "{} → {}", a, b)
+macro_rules! tlog {
+    ($log:expr, $level:expr, $cat:expr, $src:expr, $state:expr, $($arg:tt)+) => {{
+        let level: $crate::trit_log::Level = $level;
+        if $crate::trit_log::static_enabled(level) && $log.enabled(level) {
+            $log.log_args(level, $cat, $src, $state, format_args!($($arg)+));
+        }
+    }};
+}
+pub(crate) use tlog;
+
+/// Debug 레벨 단축 — 꺼져 있으면 인자를 평가·포맷하지 않음
+macro_rules! tlog_debug {
+    ($log:expr, $cat:expr, $src:expr, $($arg:tt)+) => {
+        $crate::trit_log::tlog!($log, $crate::trit_log::Level::Debug, $cat, $src,
+            $crate::car::TritState::Pending, $($arg)+)
+    };
+}
+pub(crate) use tlog_debug;
+
+/// Trace 레벨 단축
+macro_rules! tlog_trace {
+    ($log:expr, $cat:expr, $src:expr, $($arg:tt)+) => {
+        $crate::trit_log::tlog!($log, $crate::trit_log::Level::Trace, $cat, $src,
+            $crate::car::TritState::Pending, $($arg)+)
+    };
+}
+pub(crate) use tlog_trace;
+
 /// 이벤트 카테고리
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Category {
     Task,       // Task 실행
     State,      // 상태 전이
@@ -113,6 +168,28 @@ impl Event {
             self.level, trit_ch, self.category,
             self.source, self.message, fields_str)
     }
+
+    /// JSON 객체 — 필드는 키 순서로
+    pub fn to_json(&self) -> JsonObject {
+        let mut keys: Vec<&String> = self.fields.keys().collect();
+        keys.sort();
+        let fields = keys.into_iter().fold(JsonObject::new(), |obj, k| obj.str(k, &self.fields[k]));
+        JsonObject::new()
+            .int("id", self.id as i64)
+            .int("ts", self.timestamp as i64)
+            .str("level", &self.level.to_string())
+            .str("category", &self.category.to_string())
+            .trit("trit", self.trit_state as i8)
+            .str("source", &self.source)
+            .str("message", &self.message)
+            .object("fields", fields)
+    }
+}
+
+impl Queryable for Event {
+    fn timestamp(&self) -> u64 { self.timestamp }
+    fn trit(&self) -> i8 { self.trit_state as i8 }
+    fn involves(&self, account: &str) -> bool { self.source == account }
 }
 
 // ─────────────────────────────────────────────
@@ -220,10 +297,143 @@ impl AlertRule {
     }
 }
 
+// ─────────────────────────────────────────────
+// 싱크 — 기록되는 이벤트를 메모리 밖으로
+// ─────────────────────────────────────────────
+
+pub trait LogSink {
+    fn write(&mut self, event: &Event) -> Result<(), String>;
+    /// 버퍼를 내려 쓰기 (프로세스 종료 전)
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// JSON-lines 파일 싱크 — 크기 · 나이가 넘으면 경로.1 … 경로.N으로 밀어내고 새 파일
+///   {"id":1,"ts":…,"level":"INFO","category":"TASK","trit":"P","source":"CAR","message":"…","fields":{…}}
+pub struct RotatingFileSink {
+    path: PathBuf,
+    writer: Option<std::io::BufWriter<std::fs::File>>,
+    size: u64,
+    // 지금 파일의 첫 이벤트 시각 (ms)
+    opened_at: Option<u64>,
+    max_bytes: u64,
+    max_age_ms: Option<u64>,
+    keep: usize,
+    pub rotations: u64,
+}
+
+impl RotatingFileSink {
+    /// 이어쓰기로 열기 (기본: 10MB · 나이 무제한 · 이전 파일 5개 보관)
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let mut sink = Self {
+            path: path.as_ref().to_path_buf(),
+            writer: None,
+            size: 0,
+            opened_at: None,
+            max_bytes: 10 * 1024 * 1024,
+            max_age_ms: None,
+            keep: 5,
+            rotations: 0,
+        };
+        sink.open_file()?;
+        Ok(sink)
+    }
+
+    pub fn max_bytes(mut self, bytes: u64) -> Self { self.max_bytes = bytes.max(1); self }
+    pub fn max_age(mut self, age: std::time::Duration) -> Self { self.max_age_ms = Some(age.as_millis() as u64); self }
+    pub fn keep(mut self, files: usize) -> Self { self.keep = files; self }
+
+    /// 회전된 파일 경로 (n ≥ 1)
+    pub fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn io(&self, e: std::io::Error) -> String {
+        format!("{}: {}", self.path.display(), e)
+    }
+
+    fn open_file(&mut self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| self.io(e))?;
+        }
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path).map_err(|e| self.io(e))?;
+        let meta = file.metadata().map_err(|e| self.io(e))?;
+        self.size = meta.len();
+        // 이어쓰는 파일의 나이는 만든 시각(없으면 수정 시각)부터
+        self.opened_at = if self.size == 0 {
+            None
+        } else {
+            meta.created().or_else(|_| meta.modified()).ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+        };
+        self.writer = Some(std::io::BufWriter::new(file));
+        Ok(())
+    }
+
+    /// 지금 파일을 경로.1로 밀어내고 새로 연다 — 가장 오래된 것은 지움
+    pub fn rotate(&mut self) -> Result<(), String> {
+        if let Some(mut w) = self.writer.take() {
+            w.flush().map_err(|e| self.io(e))?;
+        }
+        if self.keep == 0 {
+            std::fs::remove_file(&self.path).map_err(|e| self.io(e))?;
+        } else {
+            let _ = std::fs::remove_file(self.rotated_path(self.keep));
+            for n in (1..self.keep).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated_path(n + 1)).map_err(|e| self.io(e))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1)).map_err(|e| self.io(e))?;
+        }
+        self.rotations += 1;
+        self.open_file()
+    }
+}
+
+impl LogSink for RotatingFileSink {
+    fn write(&mut self, event: &Event) -> Result<(), String> {
+        let mut line = event.to_json().build();
+        line.push('\n');
+        let too_big = self.size > 0 && self.size + line.len() as u64 > self.max_bytes;
+        let too_old = match (self.max_age_ms, self.opened_at) {
+            (Some(max), Some(at)) => event.timestamp.saturating_sub(at) >= max,
+            _ => false,
+        };
+        if too_big || too_old {
+            self.rotate()?;
+        }
+        self.opened_at.get_or_insert(event.timestamp);
+        let written = match &mut self.writer {
+            Some(w) => w.write_all(line.as_bytes()),
+            None => return Err(format!("{}: 닫힌 싱크", self.path.display())),
+        };
+        written.map_err(|e| self.io(e))?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        let flushed = match &mut self.writer {
+            Some(w) => w.flush().and_then(|_| w.get_ref().sync_data()),
+            None => Ok(()),
+        };
+        flushed.map_err(|e| self.io(e))
+    }
+}
+
 // ─────────────────────────────────────────────
 // Trit Event Logger
 // ─────────────────────────────────────────────
 
+/// 기본 보관 이벤트 수 — 넘치면 오래된 25% 정리
+pub const DEFAULT_MAX_EVENTS: usize = 10_000;
+
 pub struct TritEventLog {
     events: Vec<Event>,
     event_counter: u64,
@@ -237,8 +447,14 @@ pub struct TritEventLog {
     min_level: Level,
     max_events: usize,
     // 카테고리별 카운트
-    category_counts: HashMap<String, u64>,
+    category_counts: HashMap<Category, u64>,
     trit_counts: [u64; 3], // [T, O, P]
+    // 밀려난 이벤트 — 문자열·필드 버퍼 재사용
+    pool: Vec<Event>,
+    // 싱크 — 기록 실패는 세고 첫 오류만 남긴다 (로깅이 호출자를 멈추지 않게)
+    sinks: Vec<Box<dyn LogSink>>,
+    sink_failures: u64,
+    sink_error: Option<String>,
 }
 
 impl TritEventLog {
@@ -251,10 +467,51 @@ impl TritEventLog {
             alerts: Vec::new(),
             alert_log: Vec::new(),
             min_level: Level::Info,
-            max_events: 10000,
+            max_events: DEFAULT_MAX_EVENTS,
             category_counts: HashMap::new(),
             trit_counts: [0; 3],
+            pool: Vec::new(),
+            sinks: Vec::new(),
+            sink_failures: 0,
+            sink_error: None,
+        }
+    }
+
+    /// 싱크 추가 — 이후 기록되는 이벤트(레벨 필터 통과)마다 호출
+    pub fn add_sink(&mut self, sink: Box<dyn LogSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// 모든 싱크 flush — 첫 오류를 돌려준다
+    pub fn flush(&mut self) -> Result<(), String> {
+        let mut first = Ok(());
+        for sink in &mut self.sinks {
+            if let Err(e) = sink.flush() {
+                if first.is_ok() { first = Err(e); }
+            }
         }
+        first
+    }
+
+    /// 싱크 기록 실패 (횟수, 첫 오류)
+    pub fn sink_failures(&self) -> (u64, Option<&str>) {
+        (self.sink_failures, self.sink_error.as_deref())
+    }
+
+    /// 이벤트 버퍼를 미리 잡아 둔 로거 (장기 실행 서버용)
+    pub fn with_capacity(max_events: usize) -> Self {
+        let max_events = max_events.max(4);
+        let mut log = Self::new();
+        log.max_events = max_events;
+        log.events.reserve_exact(max_events);
+        log.pool.reserve_exact(max_events / 4);
+        log
+    }
+
+    /// 이 레벨 이벤트가 기록되는가 — 포맷 전에 확인
+    #[inline]
+    pub fn enabled(&self, level: Level) -> bool {
+        static_enabled(level) && level >= self.min_level
     }
 
     pub fn set_min_level(&mut self, level: Level) {
@@ -272,13 +529,46 @@ impl TritEventLog {
     /// 이벤트 기록
     pub fn log(&mut self, builder: EventBuilder) {
         self.event_counter += 1;
-        let event = builder.build(self.event_counter, self.now_ms());
 
         // 레벨 필터
-        if event.level < self.min_level { return; }
+        if !self.enabled(builder.level) { return; }
+
+        let event = builder.build(self.event_counter, self.now_ms());
+        self.push_event(event);
+    }
+
+    /// 빠른 경로 — 재사용 버퍼에 바로 포맷 (tlog! 매크로가 호출)
+    pub fn log_args(&mut self, level: Level, category: Category, source: &str, state: TritState, args: std::fmt::Arguments<'_>) {
+        self.event_counter += 1;
+        if !self.enabled(level) { return; }
+
+        let mut event = self.pool.pop().unwrap_or_else(|| Event {
+            id: 0, timestamp: 0, level, category, trit_state: state,
+            source: String::new(), message: String::new(), fields: HashMap::new(),
+        });
+        event.id = self.event_counter;
+        event.timestamp = self.now_ms();
+        event.level = level;
+        event.category = category;
+        event.trit_state = state;
+        event.source.clear();
+        event.source.push_str(source);
+        event.message.clear();
+        let _ = event.message.write_fmt(args);
+        event.fields.clear();
+        self.push_event(event);
+    }
+
+    fn push_event(&mut self, event: Event) {
+        for sink in &mut self.sinks {
+            if let Err(e) = sink.write(&event) {
+                self.sink_failures += 1;
+                self.sink_error.get_or_insert(e);
+            }
+        }
 
         // 카테고리 카운트
-        *self.category_counts.entry(event.category.to_string()).or_insert(0) += 1;
+        *self.category_counts.entry(event.category).or_insert(0) += 1;
 
         // Trit 카운트
         match event.trit_state {
@@ -291,21 +581,16 @@ impl TritEventLog {
         for alert in &mut self.alerts {
             if alert.matches(&event) {
                 alert.triggered_count += 1;
-                // alert_log는 나중에 처리
+                self.alert_log.push((alert.name.clone(), event.id));
             }
         }
-        // 알림 로그 별도 수집
-        let triggered: Vec<String> = self.alerts.iter()
-            .filter(|a| a.matches(&event))
-            .map(|a| a.name.clone())
-            .collect();
-        for name in triggered {
-            self.alert_log.push((name, event.id));
-        }
 
-        // 용량 제한
+        // 용량 제한 — 밀려난 이벤트는 버퍼 풀로
         if self.events.len() >= self.max_events {
-            self.events.drain(0..self.max_events / 4); // 25% 정리
+            let cut = (self.max_events / 4).max(1); // 25% 정리
+            let room = self.pool.capacity().max(cut).saturating_sub(self.pool.len());
+            let drained = self.events.drain(0..cut);
+            self.pool.extend(drained.take(room));
         }
 
         self.events.push(event);
@@ -314,30 +599,33 @@ impl TritEventLog {
     // ── 편의 메서드 ──
 
     pub fn info(&mut self, cat: Category, src: &str, msg: &str, state: TritState) {
-        self.log(EventBuilder::new(cat, msg).level(Level::Info).source(src).trit(state));
+        self.log_args(Level::Info, cat, src, state, format_args!("{}", msg));
     }
 
     pub fn warn(&mut self, cat: Category, src: &str, msg: &str) {
-        self.log(EventBuilder::new(cat, msg).level(Level::Warn).source(src).trit(TritState::Pending));
+        self.log_args(Level::Warn, cat, src, TritState::Pending, format_args!("{}", msg));
     }
 
     pub fn error(&mut self, cat: Category, src: &str, msg: &str) {
-        self.log(EventBuilder::new(cat, msg).level(Level::Error).source(src).trit(TritState::Failed));
+        self.log_args(Level::Error, cat, src, TritState::Failed, format_args!("{}", msg));
     }
 
     pub fn task_start(&mut self, task_id: u64, subject: &str) {
+        if !self.enabled(Level::Info) { self.event_counter += 1; return; }
         self.log(EventBuilder::new(Category::Task, &format!("Task#{} 시작: {}", task_id, subject))
             .level(Level::Info).source("CAR").trit(TritState::Pending)
             .field("task_id", &task_id.to_string()));
     }
 
     pub fn task_end(&mut self, task_id: u64, state: TritState) {
+        if !self.enabled(Level::Info) { self.event_counter += 1; return; }
         self.log(EventBuilder::new(Category::Task, &format!("Task#{} 완료", task_id))
             .level(Level::Info).source("CAR").trit(state)
             .field("task_id", &task_id.to_string()));
     }
 
     pub fn state_transition(&mut self, key: &str, from: i8, to: i8) {
+        if !self.enabled(Level::Debug) { self.event_counter += 1; return; }
         let trit_ch = |v: i8| match v { 1 => "P", -1 => "T", _ => "O" };
         self.log(EventBuilder::new(Category::State,
             &format!("{}: {} → {}", key, trit_ch(from), trit_ch(to)))
@@ -347,16 +635,19 @@ impl TritEventLog {
     }
 
     pub fn consensus_vote(&mut self, round: u32, voter: &str, vote: i8) {
+        if !self.enabled(Level::Info) { self.event_counter += 1; return; }
         let trit_ch = |v: i8| match v { 1 => "P", -1 => "T", _ => "O" };
         self.log(EventBuilder::new(Category::Consensus,
             &format!("Round#{} {} 투표: {}", round, voter, trit_ch(vote)))
             .level(Level::Info).source("Consensus")
+            .field("round", &round.to_string()).field("node", voter)
             .trit(match vote { 1 => TritState::Success, -1 => TritState::Failed, _ => TritState::Pending }));
     }
 
     pub fn permission_check(&mut self, subject: &str, resource: &str, allowed: bool) {
         let state = if allowed { TritState::Success } else { TritState::Failed };
         let level = if allowed { Level::Debug } else { Level::Warn };
+        if !self.enabled(level) { self.event_counter += 1; return; }
         self.log(EventBuilder::new(Category::Permission,
             &format!("{} → {} : {}", subject, resource, if allowed { "허용" } else { "거부" }))
             .level(level).source("Permission").trit(state));
@@ -393,6 +684,11 @@ impl TritEventLog {
         self.events.iter().filter(|e| &e.category == cat).collect()
     }
 
+    /// 이벤트 조회 (커서 = 이벤트 ID, 계정 = 출처) — 오래된 이벤트가 밀려나도 커서는 유지된다
+    pub fn query(&self, q: &Query) -> Page<&Event> {
+        q.page(self.events.iter().map(|e| (e.id, e)))
+    }
+
     /// Trit 상태 필터
     pub fn filter_trit(&self, state: TritState) -> Vec<&Event> {
         self.events.iter().filter(|e| e.trit_state == state).collect()
@@ -530,6 +826,73 @@ mod tests {
         assert_eq!(log.alert_log.len(), 2);
     }
 
+    /// 포맷될 때마다 세는 인자
+    struct Counted<'a>(&'a std::cell::Cell<u32>);
+
+    impl std::fmt::Display for Counted<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            self.0.set(self.0.get() + 1);
+            write!(f, "값")
+        }
+    }
+
+    #[test]
+    fn test_disabled_level_skips_formatting() {
+        let mut log = TritEventLog::new();
+        let formatted = std::cell::Cell::new(0);
+
+        // Info 기본 → Debug/Trace 인자는 포맷되지 않음
+        tlog_debug!(log, Category::State, "t", "디버그 {}", Counted(&formatted));
+        tlog_trace!(log, Category::State, "t", "추적 {}", Counted(&formatted));
+        assert_eq!(formatted.get(), 0);
+        assert!(log.recent(10).is_empty());
+
+        tlog!(log, Level::Warn, Category::Network, "t", TritState::Pending, "경고 {}", Counted(&formatted));
+        assert_eq!(formatted.get(), 1);
+        assert_eq!(log.recent(1)[0].message, "경고 값");
+
+        log.set_min_level(Level::Debug);
+        tlog_debug!(log, Category::State, "t", "디버그 {}", Counted(&formatted));
+        assert_eq!(formatted.get(), 2);
+        assert_eq!(log.recent(1)[0].level, Level::Debug);
+        assert!(static_enabled(Level::Error));
+    }
+
+    #[test]
+    fn test_min_level_gates_warn_and_errors() {
+        let mut log = TritEventLog::new();
+        log.set_min_level(Level::Warn);
+        log.info(Category::Task, "t", "정보", TritState::Success);
+        tlog!(log, Level::Info, Category::Task, "t", TritState::Success, "정보 {}", 2);
+        log.warn(Category::Network, "t", "지연");
+        log.error(Category::Task, "t", "실패");
+        // log() 로 걸러진 이벤트는 번호를 소비하고, tlog! 는 호출 자체를 건너뛴다
+        assert_eq!(log.total_events(), 3);
+        let kept: Vec<Level> = log.recent(10).iter().map(|e| e.level).collect();
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|l| *l >= Level::Warn));
+        assert_eq!(log.errors().len(), 1);
+        assert_eq!(log.errors()[0].message, "실패");
+
+        log.set_min_level(Level::Error);
+        log.warn(Category::Network, "t", "지연");
+        assert_eq!(log.recent(10).len(), 2);
+    }
+
+    #[test]
+    fn test_event_buffers_recycled() {
+        let mut log = TritEventLog::with_capacity(8);
+        for i in 0..100 {
+            tlog!(log, Level::Info, Category::Task, "car", TritState::Success, "작업 {} 완료", i);
+        }
+        assert!(log.recent(100).len() <= 8);
+        assert_eq!(log.recent(1)[0].message, "작업 99 완료");
+        assert_eq!(log.total_events(), 100);
+        // 밀려난 이벤트가 풀로 돌아와 재사용됨
+        assert!(log.pool.len() <= 2);
+        assert_eq!(log.category_counts.get(&Category::Task), Some(&100));
+    }
+
     #[test]
     fn test_permission_audit() {
         let mut log = TritEventLog::new();
@@ -540,4 +903,72 @@ mod tests {
         let perms = log.filter_category(&Category::Permission);
         assert_eq!(perms.len(), 2);
     }
+
+    fn temp_log_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("crowny-log-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn event_at(id: u64, timestamp: u64) -> Event {
+        EventBuilder::new(Category::Task, &format!("이벤트 {}", id)).build(id, timestamp)
+    }
+
+    #[test]
+    fn test_file_sink_json_lines() {
+        let dir = temp_log_dir("jsonl");
+        let path = dir.join("events.jsonl");
+        let mut log = TritEventLog::new();
+        log.add_sink(Box::new(RotatingFileSink::open(&path).unwrap()));
+        log.info(Category::System, "kernel", "부팅 \"완료\"", TritState::Success);
+        log.log(EventBuilder::new(Category::Task, "실패").level(Level::Error).trit(TritState::Failed).field("task_id", "7"));
+        log.log(EventBuilder::new(Category::Task, "걸러짐").level(Level::Debug)); // 레벨 필터
+        log.flush().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<crate::output::JsonValue> = text.lines().map(|l| crate::output::JsonValue::parse(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].get("message").and_then(|v| v.as_str()), Some("부팅 \"완료\""));
+        assert_eq!(lines[0].get("trit").and_then(|v| v.as_str()), Some("P"));
+        assert_eq!(lines[1].get("level").and_then(|v| v.as_str()), Some("ERROR"));
+        assert_eq!(lines[1].get("category").and_then(|v| v.as_str()), Some("TASK"));
+        assert_eq!(lines[1].get("fields").and_then(|f| f.get("task_id")).and_then(|v| v.as_str()), Some("7"));
+        assert!(lines[1].get("ts").and_then(|v| v.as_i64()).unwrap() > 0);
+        assert_eq!(log.sink_failures(), (0, None));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_file_sink_rotation() {
+        let dir = temp_log_dir("rotate");
+        let path = dir.join("app.log");
+        let line_len = event_at(1, 1000).to_json().build().len() as u64 + 1;
+
+        // 크기: 파일당 2줄, 이전 파일 2개까지
+        let mut sink = RotatingFileSink::open(&path).unwrap().max_bytes(line_len * 2).keep(2);
+        for id in 1..=7 {
+            sink.write(&event_at(id, 1000)).unwrap();
+        }
+        sink.flush().unwrap();
+        assert_eq!(sink.rotations, 3);
+        let ids = |p: &Path| std::fs::read_to_string(p).unwrap().lines()
+            .map(|l| crate::output::JsonValue::parse(l).unwrap().get("id").and_then(|v| v.as_i64()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ids(&path), vec![7]);
+        assert_eq!(ids(&sink.rotated_path(1)), vec![5, 6]);
+        assert_eq!(ids(&sink.rotated_path(2)), vec![3, 4]);
+        assert!(!sink.rotated_path(3).exists()); // 1 · 2는 지워짐
+
+        // 나이: 첫 이벤트부터 1초가 지나면 새 파일
+        let aged = dir.join("aged.log");
+        let mut sink = RotatingFileSink::open(&aged).unwrap().max_age(std::time::Duration::from_secs(1));
+        sink.write(&event_at(1, 10_000)).unwrap();
+        sink.write(&event_at(2, 10_999)).unwrap();
+        sink.write(&event_at(3, 11_000)).unwrap();
+        sink.flush().unwrap();
+        assert_eq!(sink.rotations, 1);
+        assert_eq!(ids(&aged), vec![3]);
+        assert_eq!(ids(&sink.rotated_path(1)), vec![1, 2]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -12,11 +12,24 @@
 ///!   - WAL (Write-Ahead Log) 장애 복원
 ///!   - 트랜잭션 ACID 보장
 ///!   - 3진 상태 인덱싱
+///!   - 파일 영속 — TritStore::open(디렉터리): WAL 파일(추가 전용) + 스냅샷 파일, 시작 시 복구
 ///!
 ///! 구조:
 ///!   Memory Store → WAL → Snapshot → File
+///!
+///! 디렉터리:
+///!   store.wal        "TWAL" v1 + 기록들 [길이 u32][FNV u32][항목 수 u32 + 항목…]
+///!                    트랜잭션 커밋은 기록 하나 — 잘리면 통째로 버려진다
+///!   snap-000001.tsnap "TSNP" v1 + id · 시각 · WAL seq · 데이터 · 상태 + FNV
+///!   복구 = 읽히는 가장 최근 스냅샷 + 그 WAL seq 뒤의 기록 재생
+///!   스냅샷 파일을 쓰고 나면 WAL은 헤더만 남기고 비운다
 
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::ring_log::RingLog;
 use std::time::{SystemTime, UNIX_EPOCH};
 use crate::car::TritState;
 
@@ -38,6 +51,23 @@ pub enum StoreValue {
     Map(HashMap<String, StoreValue>),
 }
 
+impl StoreValue {
+    /// 직렬화 크기 추정 (바이트)
+    pub fn estimated_size(&self) -> usize {
+        match self {
+            StoreValue::Null => 1,
+            StoreValue::Int(_) => 8,
+            StoreValue::Float(_) => 8,
+            StoreValue::Text(s) => s.len() + 2,
+            StoreValue::Bool(_) => 1,
+            StoreValue::Trit(_) => 1,
+            StoreValue::Bytes(b) => b.len() + 4,
+            StoreValue::List(l) => 4 + l.iter().map(StoreValue::estimated_size).sum::<usize>(),
+            StoreValue::Map(m) => 4 + m.iter().map(|(k, v)| k.len() + 1 + v.estimated_size()).sum::<usize>(),
+        }
+    }
+}
+
 impl std::fmt::Display for StoreValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -63,6 +93,8 @@ pub enum WalOp {
     Set { key: String, value: StoreValue },
     Delete { key: String },
     SetTritState { key: String, state: i8 },
+    /// 스냅샷으로 되돌림 — 재생 시 그 스냅샷을 알고 있어야 한다
+    Restore { snapshot_id: u64 },
 }
 
 #[derive(Debug, Clone)]
@@ -83,6 +115,56 @@ pub struct Snapshot {
     pub data: HashMap<String, StoreValue>,
     pub trit_states: HashMap<String, i8>,
     pub entry_count: usize,
+    /// 만들 때까지의 마지막 WAL seq — 복구 시 이 뒤부터 재생
+    pub wal_seq: u64,
+}
+
+// ─────────────────────────────────────────────
+// 파일 영속 설정 · 복구 결과
+// ─────────────────────────────────────────────
+
+/// WAL fsync 시점
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncMode {
+    /// 기록마다 fsync (기본)
+    Always,
+    /// N개 기록마다 fsync
+    Every(usize),
+    /// OS에 맡김 — sync()를 부를 때만
+    Never,
+}
+
+impl SyncMode {
+    /// "always" · "never" · 숫자 N (N개마다)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "always" => Some(SyncMode::Always),
+            "never" => Some(SyncMode::Never),
+            n => n.parse().ok().filter(|&n| n > 0).map(SyncMode::Every),
+        }
+    }
+}
+
+/// 시작 시 복구 결과
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recovery {
+    /// 기준 스냅샷 (None이면 WAL만으로 재구성)
+    pub snapshot: Option<u64>,
+    /// 기준 스냅샷 뒤로 재생한 WAL 항목 수
+    pub replayed: usize,
+    /// 기록 도중 끊긴 WAL 꼬리 — 잘라낸 바이트
+    pub torn_bytes: u64,
+    /// 꼬리를 자르기 전 WAL 사본 (잘라낸 게 없으면 None)
+    pub torn_backup: Option<PathBuf>,
+    /// 손상돼 건너뛴 스냅샷 파일 (이름: 이유)
+    pub bad_snapshots: Vec<String>,
+}
+
+struct StoreFile {
+    dir: PathBuf,
+    wal: File,
+    sync: SyncMode,
+    unsynced: usize,
 }
 
 // ─────────────────────────────────────────────
@@ -95,8 +177,8 @@ pub struct TritStore {
     data: HashMap<String, StoreValue>,
     // Trit 상태 인덱스 (키별 3진 상태)
     trit_index: HashMap<String, i8>,
-    // WAL
-    wal: Vec<WalEntry>,
+    // WAL (메모리 사본 — 상한, 오래된 것부터 축출)
+    wal: RingLog<WalEntry>,
     wal_seq: u64,
     // Snapshot
     snapshots: Vec<Snapshot>,
@@ -108,6 +190,10 @@ pub struct TritStore {
     read_count: u64,
     write_count: u64,
     delete_count: u64,
+    // 파일 영속 (open으로 열었을 때만)
+    file: Option<StoreFile>,
+    recovery: Option<Recovery>,
+    persist_error: Option<String>,
 }
 
 impl TritStore {
@@ -115,7 +201,7 @@ impl TritStore {
         Self {
             data: HashMap::new(),
             trit_index: HashMap::new(),
-            wal: Vec::new(),
+            wal: RingLog::default(),
             wal_seq: 0,
             snapshots: Vec::new(),
             snapshot_counter: 0,
@@ -124,7 +210,116 @@ impl TritStore {
             read_count: 0,
             write_count: 0,
             delete_count: 0,
+            file: None,
+            recovery: None,
+            persist_error: None,
+        }
+    }
+
+    /// 디렉터리 기반 저장소 열기 — 스냅샷 + WAL로 상태 복구, 이후 기록은 파일에도 (fsync 매번)
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, String> {
+        Self::open_with(dir, SyncMode::Always)
+    }
+
+    /// fsync 시점을 정해 열기
+    pub fn open_with(dir: impl AsRef<Path>, sync: SyncMode) -> Result<Self, String> {
+        let dir = dir.as_ref();
+        let io = |e: std::io::Error| format!("{}: {}", dir.display(), e);
+        std::fs::create_dir_all(dir).map_err(io)?;
+
+        let mut store = Self::new();
+        let mut recovery = Recovery::default();
+
+        // 1. 스냅샷 — 손상된 파일은 건너뛴다 (WAL은 스냅샷마다 비우므로 기준보다 새 것이 깨지면 오류)
+        let mut names: Vec<String> = std::fs::read_dir(dir).map_err(io)?
+            .filter_map(|e| e.ok()?.file_name().into_string().ok())
+            .filter(|n| n.starts_with("snap-") && n.ends_with(".tsnap"))
+            .collect();
+        names.sort();
+        for name in names {
+            let decoded = migrate_on_open(&dir.join(&name), detect_snapshot_version)
+                .and_then(|_| std::fs::read(dir.join(&name)).map_err(|e| e.to_string()))
+                .and_then(|bytes| decode_snapshot(&bytes));
+            match decoded {
+                Ok(snap) => store.snapshots.push(snap),
+                Err(e) => recovery.bad_snapshots.push(format!("{}: {}", name, e)),
+            }
+        }
+        store.snapshots.sort_by_key(|s| s.id);
+        store.snapshot_counter = store.snapshots.last().map_or(0, |s| s.id);
+
+        // 2. WAL — 끊긴 꼬리는 잘라내고, 중간 손상은 오류
+        let wal_path = dir.join(WAL_FILE);
+        migrate_on_open(&wal_path, detect_wal_version)?;
+        let bytes = if wal_path.exists() { std::fs::read(&wal_path).map_err(io)? } else { Vec::new() };
+        let (entries, valid_len) = read_wal(&bytes).map_err(|e| format!("{}: {}", wal_path.display(), e))?;
+        recovery.torn_bytes = bytes.len() as u64 - valid_len as u64;
+
+        // 3. 기준 스냅샷 위로 재생
+        let base_seq = match store.snapshots.last() {
+            Some(snap) => {
+                store.data = snap.data.clone();
+                store.trit_index = snap.trit_states.clone();
+                recovery.snapshot = Some(snap.id);
+                snap.wal_seq
+            }
+            None => 0,
+        };
+        let base_id = recovery.snapshot.unwrap_or(0);
+        if let Some(bad) = recovery.bad_snapshots.iter().find(|b| snapshot_id_of(b).is_some_and(|id| id > base_id)) {
+            return Err(format!("{}: 최신 스냅샷 손상 — WAL이 이미 비워져 이전 상태로 복구할 수 없음 ({})", dir.display(), bad));
+        }
+        if let Some(first) = entries.iter().find(|e| e.seq > base_seq) {
+            if first.seq != base_seq + 1 {
+                return Err(format!("{}: WAL이 seq {} 뒤로 이어지지 않음 (첫 기록 seq {})", wal_path.display(), base_seq, first.seq));
+            }
+        }
+        for entry in &entries {
+            if entry.seq > base_seq {
+                store.apply_op(&entry.op);
+                recovery.replayed += 1;
+            }
+        }
+        // 재생한 기록은 메모리에 남기지 않는다 — wal_entries()는 연 뒤의 기록만
+        store.wal_seq = entries.last().map_or(0, |e| e.seq).max(base_seq);
+
+        // 4. WAL 파일 — 꼬리 자르고 이어쓰기 (자르기 전 원본은 .torn.bak으로)
+        if recovery.torn_bytes > 0 {
+            let backup = dir.join(WAL_TORN_BACKUP);
+            std::fs::copy(&wal_path, &backup).map_err(io)?;
+            recovery.torn_backup = Some(backup);
+        }
+        let mut wal = std::fs::OpenOptions::new().create(true).truncate(false).read(true).write(true).open(&wal_path).map_err(io)?;
+        if valid_len == 0 {
+            wal.set_len(0).and_then(|_| wal.write_all(WAL_MAGIC)).and_then(|_| wal.sync_data()).map_err(io)?;
+        } else if recovery.torn_bytes > 0 {
+            wal.set_len(valid_len as u64).and_then(|_| wal.sync_data()).map_err(io)?;
+        }
+        use std::io::Seek;
+        wal.seek(std::io::SeekFrom::End(0)).map_err(io)?;
+
+        store.file = Some(StoreFile { dir: dir.to_path_buf(), wal, sync, unsynced: 0 });
+        store.recovery = Some(recovery);
+        Ok(store)
+    }
+
+    /// open 때의 복구 결과 (메모리 저장소면 None)
+    pub fn recovery(&self) -> Option<&Recovery> {
+        self.recovery.as_ref()
+    }
+
+    /// 파일 기록 중 처음 난 오류 — 기록 API는 실패를 돌려주지 않으므로 여기서 확인
+    pub fn persist_error(&self) -> Option<&str> {
+        self.persist_error.as_deref()
+    }
+
+    /// 밀린 WAL 기록 fsync (SyncMode::Never · Every일 때)
+    pub fn sync(&mut self) -> Result<(), String> {
+        if let Some(file) = &mut self.file {
+            file.wal.sync_data().map_err(|e| format!("{}: {}", file.dir.display(), e))?;
+            file.unsynced = 0;
         }
+        Ok(())
     }
 
     fn now_ms(&self) -> u64 {
@@ -151,6 +346,11 @@ impl TritStore {
         self.data.get(key)
     }
 
+    /// 값 읽기 (읽기 통계 미반영)
+    pub fn peek(&self, key: &str) -> Option<&StoreValue> {
+        self.data.get(key)
+    }
+
     /// 값 삭제
     pub fn delete(&mut self, key: &str) -> bool {
         let op = WalOp::Delete { key: key.to_string() };
@@ -220,12 +420,40 @@ impl TritStore {
     // ── WAL ──
 
     fn append_wal(&mut self, op: WalOp) {
+        let entry = self.next_entry(op);
+        self.persist(std::slice::from_ref(&entry));
+        self.wal.push(entry);
+    }
+
+    fn next_entry(&mut self, op: WalOp) -> WalEntry {
         self.wal_seq += 1;
-        self.wal.push(WalEntry {
-            seq: self.wal_seq,
-            timestamp: self.now_ms(),
-            op,
-        });
+        WalEntry { seq: self.wal_seq, timestamp: self.now_ms(), op }
+    }
+
+    /// 항목들을 WAL 파일에 기록 하나로 추가
+    fn persist(&mut self, entries: &[WalEntry]) {
+        let Some(file) = &mut self.file else { return };
+        let record = encode_wal_record(entries);
+        // 읽을 때 손상으로 볼 크기는 쓰지 않는다
+        if record.len() - 8 > MAX_WAL_RECORD {
+            self.persist_error.get_or_insert(format!("{}: WAL 기록 {} bytes가 최대 {} bytes 초과",
+                file.dir.join(WAL_FILE).display(), record.len() - 8, MAX_WAL_RECORD));
+            return;
+        }
+        let mut result = file.wal.write_all(&record);
+        file.unsynced += 1;
+        let due = match file.sync {
+            SyncMode::Always => true,
+            SyncMode::Every(n) => file.unsynced >= n.max(1),
+            SyncMode::Never => false,
+        };
+        if result.is_ok() && due {
+            result = file.wal.sync_data();
+            file.unsynced = 0;
+        }
+        if let Err(e) = result {
+            self.persist_error.get_or_insert(format!("{}: {}", file.dir.join(WAL_FILE).display(), e));
+        }
     }
 
     fn apply_op(&mut self, op: &WalOp) {
@@ -242,6 +470,12 @@ impl TritStore {
             WalOp::SetTritState { key, state } => {
                 self.trit_index.insert(key.clone(), *state);
             }
+            WalOp::Restore { snapshot_id } => {
+                if let Some(snap) = self.snapshots.iter().find(|s| s.id == *snapshot_id) {
+                    self.data = snap.data.clone();
+                    self.trit_index = snap.trit_states.clone();
+                }
+            }
         }
     }
 
@@ -250,6 +484,22 @@ impl TritStore {
         self.wal.len()
     }
 
+    /// 메모리에 남은 WAL 엔트리 (순서대로)
+    pub fn wal_entries(&self) -> Vec<WalEntry> {
+        self.wal.to_vec()
+    }
+
+    /// WAL 재생으로 저장소 재구성 (재시작 후 복구) — 스냅샷이 없으므로 Restore는 건너뛴다
+    pub fn replay(entries: &[WalEntry]) -> Self {
+        let mut store = Self::new();
+        for entry in entries {
+            store.apply_op(&entry.op);
+            store.wal.push(entry.clone());
+            store.wal_seq = store.wal_seq.max(entry.seq);
+        }
+        store
+    }
+
     // ── 트랜잭션 ──
 
     /// 트랜잭션 시작
@@ -268,8 +518,11 @@ impl TritStore {
         for op in &ops {
             self.apply_op(op);
         }
-        for op in ops {
-            self.append_wal(op);
+        // 커밋 전체가 WAL 기록 하나
+        let entries: Vec<WalEntry> = ops.into_iter().map(|op| self.next_entry(op)).collect();
+        self.persist(&entries);
+        for entry in entries {
+            self.wal.push(entry);
         }
 
         self.tx_active = false;
@@ -286,7 +539,7 @@ impl TritStore {
 
     // ── Snapshot ──
 
-    /// Snapshot 생성
+    /// Snapshot 생성 — 파일 저장소면 snap-N.tsnap도 쓴다 (임시 파일 → rename → WAL 비우기)
     pub fn snapshot(&mut self) -> u64 {
         self.snapshot_counter += 1;
         let snap = Snapshot {
@@ -295,26 +548,50 @@ impl TritStore {
             data: self.data.clone(),
             trit_states: self.trit_index.clone(),
             entry_count: self.data.len(),
+            wal_seq: self.wal_seq,
         };
+        if let Some(file) = &mut self.file {
+            // WAL을 비우기 전에 스냅샷이 디스크에 있어야 하므로 SyncMode와 무관하게 fsync
+            let path = file.dir.join(snapshot_file_name(snap.id));
+            let tmp = path.with_extension("tmp");
+            let written = File::create(&tmp)
+                .and_then(|mut f| {
+                    f.write_all(&encode_snapshot(&snap))?;
+                    f.sync_all()
+                })
+                .and_then(|_| std::fs::rename(&tmp, &path))
+                .and_then(|_| File::open(&file.dir)?.sync_all());
+            let truncated = written.map_err(|e| format!("{}: {}", path.display(), e)).and_then(|_| {
+                use std::io::Seek;
+                let wal = &mut file.wal;
+                wal.set_len(0)
+                    .and_then(|_| wal.seek(std::io::SeekFrom::Start(0)))
+                    .and_then(|_| wal.write_all(WAL_MAGIC))
+                    .and_then(|_| wal.sync_data())
+                    .map_err(|e| format!("{}: {}", file.dir.join(WAL_FILE).display(), e))
+            });
+            match truncated {
+                Ok(()) => {
+                    file.unsynced = 0;
+                    self.wal.clear();
+                }
+                Err(e) => { self.persist_error.get_or_insert(e); }
+            }
+        }
         let id = snap.id;
         self.snapshots.push(snap);
         id
     }
 
-    /// Snapshot 복구
+    /// Snapshot 복구 — WAL에 Restore로 남아 재시작 후에도 같은 상태
     pub fn restore(&mut self, snapshot_id: u64) -> TritState {
-        if let Some(snap) = self.snapshots.iter().find(|s| s.id == snapshot_id) {
-            self.data = snap.data.clone();
-            self.trit_index = snap.trit_states.clone();
-            // WAL에 복구 기록
-            self.append_wal(WalOp::Set {
-                key: "__restore__".to_string(),
-                value: StoreValue::Int(snapshot_id as i64),
-            });
-            TritState::Success
-        } else {
-            TritState::Failed
+        if !self.snapshots.iter().any(|s| s.id == snapshot_id) {
+            return TritState::Failed;
         }
+        let op = WalOp::Restore { snapshot_id };
+        self.apply_op(&op);
+        self.append_wal(op);
+        TritState::Success
     }
 
     /// Snapshot 목록
@@ -328,22 +605,7 @@ impl TritStore {
 
     /// 전체 데이터를 바이트로 직렬화 (크기 계산)
     pub fn estimated_size(&self) -> usize {
-        let mut size = 0usize;
-        for (k, v) in &self.data {
-            size += k.len() + 1;
-            size += match v {
-                StoreValue::Null => 1,
-                StoreValue::Int(_) => 8,
-                StoreValue::Float(_) => 8,
-                StoreValue::Text(s) => s.len() + 2,
-                StoreValue::Bool(_) => 1,
-                StoreValue::Trit(_) => 1,
-                StoreValue::Bytes(b) => b.len() + 4,
-                StoreValue::List(l) => l.len() * 16,
-                StoreValue::Map(m) => m.len() * 32,
-            };
-        }
-        size
+        self.data.iter().map(|(k, v)| k.len() + 1 + v.estimated_size()).sum()
     }
 
     // ── 통계 ──
@@ -373,6 +635,277 @@ impl TritStore {
     }
 }
 
+// ─────────────────────────────────────────────
+// 파일 형식 — WAL 기록 · 스냅샷 (리틀 엔디언, FNV-1a 체크섬)
+// ─────────────────────────────────────────────
+
+const WAL_FILE: &str = "store.wal";
+const WAL_TORN_BACKUP: &str = "store.wal.torn.bak";
+/// WAL 기록 하나의 최대 본문 — 이보다 긴 길이 접두는 끊김이 아니라 손상
+const MAX_WAL_RECORD: usize = 16 * 1024 * 1024;
+const WAL_MAGIC: &[u8; 5] = b"TWAL\x01";
+const SNAP_MAGIC: &[u8; 5] = b"TSNP\x01";
+/// 현재 WAL · 스냅샷 형식 버전 (매직 다섯째 바이트)
+pub const WAL_VERSION: u32 = 1;
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// WAL 파일 버전 감지 — 마이그레이션 레지스트리용
+pub fn detect_wal_version(data: &[u8]) -> Option<u32> {
+    (data.len() >= WAL_MAGIC.len() && data.starts_with(b"TWAL")).then(|| data[4] as u32)
+}
+
+/// 스냅샷 파일 버전 감지 — 마이그레이션 레지스트리용
+pub fn detect_snapshot_version(data: &[u8]) -> Option<u32> {
+    (data.len() >= SNAP_MAGIC.len() && data.starts_with(b"TSNP")).then(|| data[4] as u32)
+}
+
+/// 열기 전 구버전 파일을 최신으로 (백업은 .v{N}.bak) — 형식을 알 수 없으면 읽는 쪽에 맡긴다
+fn migrate_on_open(path: &Path, detect: fn(&[u8]) -> Option<u32>) -> Result<(), String> {
+    let Ok(bytes) = std::fs::read(path) else { return Ok(()) };
+    if detect(&bytes).is_none() {
+        return Ok(());
+    }
+    let registry = crate::migrations::MigrationRegistry::builtin();
+    crate::migrations::migrate_file(&registry, path, false).map(|_| ())
+}
+
+/// "snap-000002.tsnap…" → 2
+fn snapshot_id_of(name: &str) -> Option<u64> {
+    name.strip_prefix("snap-")?.split(".tsnap").next()?.parse().ok()
+}
+
+fn snapshot_file_name(id: u64) -> String {
+    format!("snap-{:06}.tsnap", id)
+}
+
+fn fnv(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c_9dc5u32, |h, b| (h ^ *b as u32).wrapping_mul(0x0100_0193))
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn put_value(out: &mut Vec<u8>, value: &StoreValue) {
+    match value {
+        StoreValue::Null => out.push(0),
+        StoreValue::Int(n) => { out.push(1); out.extend_from_slice(&n.to_le_bytes()); }
+        StoreValue::Float(f) => { out.push(2); out.extend_from_slice(&f.to_le_bytes()); }
+        StoreValue::Text(s) => { out.push(3); put_str(out, s); }
+        StoreValue::Bool(b) => { out.push(4); out.push(*b as u8); }
+        StoreValue::Trit(t) => { out.push(5); out.push(*t as u8); }
+        StoreValue::Bytes(b) => {
+            out.push(6);
+            out.extend_from_slice(&(b.len() as u32).to_le_bytes());
+            out.extend_from_slice(b);
+        }
+        StoreValue::List(items) => {
+            out.push(7);
+            out.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            items.iter().for_each(|v| put_value(out, v));
+        }
+        StoreValue::Map(map) => {
+            out.push(8);
+            out.extend_from_slice(&(map.len() as u32).to_le_bytes());
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            for k in keys {
+                put_str(out, k);
+                put_value(out, &map[k]);
+            }
+        }
+    }
+}
+
+/// 바이트 읽기 커서 — 모자라면 오류
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.bytes.len())
+            .ok_or_else(|| format!("오프셋 {}: 데이터 부족", self.pos))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+    fn u8(&mut self) -> Result<u8, String> { Ok(self.take(1)?[0]) }
+    fn u32(&mut self) -> Result<u32, String> { Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap())) }
+    fn u64(&mut self) -> Result<u64, String> { Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap())) }
+    fn str(&mut self) -> Result<String, String> {
+        let n = self.u32()? as usize;
+        let at = self.pos;
+        String::from_utf8(self.take(n)?.to_vec()).map_err(|_| format!("오프셋 {}: UTF-8 아님", at))
+    }
+    fn trit(&mut self) -> Result<i8, String> {
+        match self.u8()? as i8 {
+            t @ -1..=1 => Ok(t),
+            t => Err(format!("오프셋 {}: 잘못된 trit {}", self.pos - 1, t)),
+        }
+    }
+    fn value(&mut self, depth: usize) -> Result<StoreValue, String> {
+        if depth > 64 {
+            return Err(format!("오프셋 {}: 값 중첩이 너무 깊음", self.pos));
+        }
+        Ok(match self.u8()? {
+            0 => StoreValue::Null,
+            1 => StoreValue::Int(self.u64()? as i64),
+            2 => StoreValue::Float(f64::from_bits(self.u64()?)),
+            3 => StoreValue::Text(self.str()?),
+            4 => StoreValue::Bool(self.u8()? != 0),
+            5 => StoreValue::Trit(self.trit()?),
+            6 => { let n = self.u32()? as usize; StoreValue::Bytes(self.take(n)?.to_vec()) }
+            7 => {
+                let n = self.u32()?;
+                StoreValue::List((0..n).map(|_| self.value(depth + 1)).collect::<Result<_, _>>()?)
+            }
+            8 => {
+                let n = self.u32()?;
+                let mut map = HashMap::new();
+                for _ in 0..n {
+                    let k = self.str()?;
+                    map.insert(k, self.value(depth + 1)?);
+                }
+                StoreValue::Map(map)
+            }
+            tag => return Err(format!("오프셋 {}: 알 수 없는 값 태그 {}", self.pos - 1, tag)),
+        })
+    }
+}
+
+/// WAL 기록 하나: [길이][FNV][항목 수 + (seq · 시각 · 연산)…]
+fn encode_wal_record(entries: &[WalEntry]) -> Vec<u8> {
+    let mut payload = (entries.len() as u32).to_le_bytes().to_vec();
+    for entry in entries {
+        payload.extend_from_slice(&entry.seq.to_le_bytes());
+        payload.extend_from_slice(&entry.timestamp.to_le_bytes());
+        match &entry.op {
+            WalOp::Set { key, value } => { payload.push(1); put_str(&mut payload, key); put_value(&mut payload, value); }
+            WalOp::Delete { key } => { payload.push(2); put_str(&mut payload, key); }
+            WalOp::SetTritState { key, state } => { payload.push(3); put_str(&mut payload, key); payload.push(*state as u8); }
+            WalOp::Restore { snapshot_id } => { payload.push(4); payload.extend_from_slice(&snapshot_id.to_le_bytes()); }
+        }
+    }
+    let mut record = (payload.len() as u32).to_le_bytes().to_vec();
+    record.extend_from_slice(&fnv(&payload).to_le_bytes());
+    record.extend_from_slice(&payload);
+    record
+}
+
+fn decode_wal_payload(payload: &[u8]) -> Result<Vec<WalEntry>, String> {
+    let mut c = Cursor { bytes: payload, pos: 0 };
+    let count = c.u32()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let seq = c.u64()?;
+        let timestamp = c.u64()?;
+        let op = match c.u8()? {
+            1 => WalOp::Set { key: c.str()?, value: c.value(0)? },
+            2 => WalOp::Delete { key: c.str()? },
+            3 => WalOp::SetTritState { key: c.str()?, state: c.trit()? },
+            4 => WalOp::Restore { snapshot_id: c.u64()? },
+            tag => return Err(format!("알 수 없는 WAL 연산 {}", tag)),
+        };
+        entries.push(WalEntry { seq, timestamp, op });
+    }
+    if c.pos != payload.len() {
+        return Err(format!("기록 끝에 남는 {} bytes", payload.len() - c.pos));
+    }
+    Ok(entries)
+}
+
+/// WAL 파일 읽기 → (항목, 온전한 길이). 마지막 기록이 끊겼으면 그 앞까지,
+/// 중간 기록이 깨졌으면 손상 오류. 끊김으로 보는 건 파일 끝에서 남은 바이트가
+/// 헤더 + 최대 기록보다 짧을 때뿐 — 길이 접두가 최대를 넘으면 손상
+fn read_wal(bytes: &[u8]) -> Result<(Vec<WalEntry>, usize), String> {
+    if bytes.len() < WAL_MAGIC.len() {
+        // 헤더를 쓰다 끊김 — 빈 WAL
+        return if WAL_MAGIC.starts_with(bytes) { Ok((Vec::new(), 0)) } else { Err("WAL 헤더 불일치".into()) };
+    }
+    if &bytes[..WAL_MAGIC.len()] != WAL_MAGIC {
+        return Err("WAL 헤더 불일치 (TWAL v1 아님)".into());
+    }
+    let mut entries = Vec::new();
+    let mut pos = WAL_MAGIC.len();
+    while pos < bytes.len() {
+        let rest = &bytes[pos..];
+        if rest.len() < 8 {
+            break;
+        }
+        let len = u32::from_le_bytes(rest[0..4].try_into().unwrap()) as usize;
+        let sum = u32::from_le_bytes(rest[4..8].try_into().unwrap());
+        if len > MAX_WAL_RECORD {
+            return Err(format!("WAL 손상: 오프셋 {} 기록 길이 {} (최대 {})", pos, len, MAX_WAL_RECORD));
+        }
+        // 여기서 모자라면 남은 바이트 < 헤더 + len ≤ 헤더 + 최대 기록 — 쓰다 끊긴 꼬리
+        let Some(payload) = rest.get(8..8 + len) else { break };
+        let last = pos + 8 + len == bytes.len();
+        if fnv(payload) != sum {
+            if last {
+                break;
+            }
+            return Err(format!("WAL 손상: 오프셋 {} 체크섬 불일치", pos));
+        }
+        let decoded = decode_wal_payload(payload).map_err(|e| format!("WAL 손상: 오프셋 {}: {}", pos, e))?;
+        entries.extend(decoded);
+        pos += 8 + len;
+    }
+    Ok((entries, pos))
+}
+
+fn encode_snapshot(snap: &Snapshot) -> Vec<u8> {
+    let mut out = SNAP_MAGIC.to_vec();
+    out.extend_from_slice(&snap.id.to_le_bytes());
+    out.extend_from_slice(&snap.timestamp.to_le_bytes());
+    out.extend_from_slice(&snap.wal_seq.to_le_bytes());
+    let mut keys: Vec<&String> = snap.data.keys().collect();
+    keys.sort();
+    out.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+    for k in keys {
+        put_str(&mut out, k);
+        put_value(&mut out, &snap.data[k]);
+    }
+    let mut states: Vec<(&String, &i8)> = snap.trit_states.iter().collect();
+    states.sort();
+    out.extend_from_slice(&(states.len() as u32).to_le_bytes());
+    for (k, t) in states {
+        put_str(&mut out, k);
+        out.push(*t as u8);
+    }
+    let sum = fnv(&out);
+    out.extend_from_slice(&sum.to_le_bytes());
+    out
+}
+
+fn decode_snapshot(bytes: &[u8]) -> Result<Snapshot, String> {
+    if bytes.len() < SNAP_MAGIC.len() + 4 || &bytes[..SNAP_MAGIC.len()] != SNAP_MAGIC {
+        return Err("스냅샷 헤더 불일치 (TSNP v1 아님)".into());
+    }
+    let (body, sum) = bytes.split_at(bytes.len() - 4);
+    if fnv(body) != u32::from_le_bytes(sum.try_into().unwrap()) {
+        return Err("체크섬 불일치".into());
+    }
+    let mut c = Cursor { bytes: body, pos: SNAP_MAGIC.len() };
+    let (id, timestamp, wal_seq) = (c.u64()?, c.u64()?, c.u64()?);
+    let mut data = HashMap::new();
+    for _ in 0..c.u32()? {
+        let k = c.str()?;
+        data.insert(k, c.value(0)?);
+    }
+    let mut trit_states = HashMap::new();
+    for _ in 0..c.u32()? {
+        let k = c.str()?;
+        trit_states.insert(k, c.trit()?);
+    }
+    if c.pos != body.len() {
+        return Err(format!("끝에 남는 {} bytes", body.len() - c.pos));
+    }
+    Ok(Snapshot { id, timestamp, entry_count: data.len(), data, trit_states, wal_seq })
+}
+
 // ─────────────────────────────────────────────
 // 네임스페이스 (패키지별 분리)
 // ─────────────────────────────────────────────
@@ -482,6 +1015,27 @@ mod tests {
         assert_eq!(store.wal_len(), 3);
     }
 
+    #[test]
+    fn test_wal_replay() {
+        let mut store = TritStore::new();
+        store.set("a", StoreValue::Int(1));
+        store.set_trit_state("a", 1);
+        store.begin();
+        store.set("b", StoreValue::Int(2));
+        store.commit();
+        store.begin();
+        store.set("c", StoreValue::Int(3));
+        store.rollback();
+        store.delete("b");
+
+        let mut back = TritStore::replay(&store.wal_entries());
+        assert_eq!(back.len(), 1);
+        assert!(matches!(back.get("a"), Some(StoreValue::Int(1))));
+        assert_eq!(back.get_trit_state("a"), Some(1));
+        back.set("d", StoreValue::Int(4));
+        assert_eq!(back.wal_entries().last().unwrap().seq, store.wal_len() as u64 + 1);
+    }
+
     #[test]
     fn test_filter_by_trit() {
         let mut store = TritStore::new();
@@ -502,4 +1056,135 @@ mod tests {
         assert_eq!(ns.total_entries(), 2);
         assert_eq!(ns.namespaces().len(), 2);
     }
+
+    fn temp_store_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("crowny-store-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_open_reopen_recovers() {
+        let dir = temp_store_dir("reopen");
+        {
+            let mut store = TritStore::open(&dir).unwrap();
+            assert_eq!(store.recovery(), Some(&Recovery::default()));
+            store.set("a", StoreValue::Int(1));
+            store.set("m", StoreValue::Map(HashMap::from([("k".to_string(), StoreValue::List(vec![StoreValue::Trit(-1), StoreValue::Float(0.5)]))])));
+            store.set_trit_state("a", 1);
+            store.begin();
+            store.set("b", StoreValue::Text("둘".into()));
+            store.commit();
+            store.begin();
+            store.set("c", StoreValue::Int(3));
+            store.rollback();
+            store.delete("m");
+            assert!(store.persist_error().is_none());
+        }
+        let mut store = TritStore::open(&dir).unwrap();
+        assert_eq!(store.recovery().unwrap().replayed, 5);
+        assert_eq!(store.len(), 2);
+        assert!(matches!(store.get("b"), Some(StoreValue::Text(s)) if s == "둘"));
+        assert_eq!(store.get_trit_state("a"), Some(1));
+        // seq는 이어서
+        store.set("d", StoreValue::Int(4));
+        assert_eq!(store.wal_entries().last().unwrap().seq, 6);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_snapshot_files_and_restore_survive_restart() {
+        let dir = temp_store_dir("snap");
+        {
+            let mut store = TritStore::open_with(&dir, SyncMode::Never).unwrap();
+            store.set("x", StoreValue::Int(10));
+            let first = store.snapshot();
+            store.set("x", StoreValue::Int(20));
+            store.snapshot();
+            // 스냅샷을 쓰면 WAL은 비워진다 (파일은 헤더만)
+            assert_eq!(store.wal_len(), 0);
+            assert_eq!(std::fs::read(dir.join(WAL_FILE)).unwrap(), WAL_MAGIC);
+            store.set("y", StoreValue::Int(30));
+            store.restore(first);
+            store.set("z", StoreValue::Bool(true));
+            store.sync().unwrap();
+        }
+        assert!(dir.join("snap-000001.tsnap").exists() && dir.join("snap-000002.tsnap").exists());
+        let mut store = TritStore::open(&dir).unwrap();
+        let rec = store.recovery().unwrap().clone();
+        assert_eq!((rec.snapshot, rec.replayed), (Some(2), 3));
+        assert!(matches!(store.get("x"), Some(StoreValue::Int(10))));
+        assert!(!store.exists("y"));
+        assert!(store.exists("z"));
+        // 재생한 기록은 메모리에 남지 않는다
+        assert_eq!(store.wal_len(), 0);
+
+        // 최신 스냅샷이 깨지면 이전 것으로는 WAL이 이어지지 않음 → 오류 (조용히 되돌리지 않음)
+        let path = dir.join("snap-000002.tsnap");
+        let good = std::fs::read(&path).unwrap();
+        let mut bytes = good.clone();
+        bytes[20] ^= 0xFF;
+        std::fs::write(&path, bytes).unwrap();
+        drop(store);
+        let err = TritStore::open(&dir).err().unwrap();
+        assert!(err.contains("최신 스냅샷 손상") && err.contains("snap-000002.tsnap: 체크섬 불일치"), "{}", err);
+
+        // 기준보다 오래된 스냅샷 손상은 건너뛴다
+        std::fs::write(&path, good).unwrap();
+        let mut store = TritStore::open(&dir).unwrap();
+        assert_eq!(store.snapshot(), 3);
+        drop(store);
+        let path = dir.join("snap-000001.tsnap");
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[20] ^= 0xFF;
+        std::fs::write(&path, bytes).unwrap();
+        let mut store = TritStore::open(&dir).unwrap();
+        let rec = store.recovery().unwrap().clone();
+        assert_eq!((rec.snapshot, rec.replayed), (Some(3), 0));
+        assert!(rec.bad_snapshots[0].starts_with("snap-000001.tsnap: 체크섬 불일치"));
+        assert!(matches!(store.get("x"), Some(StoreValue::Int(10))));
+        assert_eq!(store.len(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_wal_torn_tail_and_corruption() {
+        let dir = temp_store_dir("torn");
+        {
+            let mut store = TritStore::open(&dir).unwrap();
+            store.set("a", StoreValue::Int(1));
+            store.set("b", StoreValue::Int(2));
+        }
+        let wal = dir.join(WAL_FILE);
+        let full = std::fs::read(&wal).unwrap();
+
+        // 마지막 기록이 쓰다 끊김 → 원본을 백업하고 잘라내 앞 기록만
+        std::fs::write(&wal, &full[..full.len() - 3]).unwrap();
+        let store = TritStore::open(&dir).unwrap();
+        let recovery = store.recovery().unwrap().clone();
+        assert!(recovery.torn_bytes > 0);
+        assert_eq!((store.len(), store.exists("b")), (1, false));
+        drop(store);
+        assert!(std::fs::read(&wal).unwrap().len() < full.len() - 3);
+        assert_eq!(std::fs::read(recovery.torn_backup.unwrap()).unwrap(), &full[..full.len() - 3]);
+
+        // 중간 기록의 길이 접두가 최대를 넘음 → 끊김이 아니라 손상 (뒤 기록을 잘라내지 않음)
+        let mut bad = full.clone();
+        bad[WAL_MAGIC.len()..WAL_MAGIC.len() + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&wal, &bad).unwrap();
+        let err = TritStore::open(&dir).err().unwrap();
+        assert!(err.contains("WAL 손상: 오프셋 5 기록 길이 4294967295"), "{}", err);
+        assert_eq!(std::fs::read(&wal).unwrap(), bad);
+
+        // 중간 기록 변조 → 손상 오류 (조용히 버리지 않음)
+        let mut bad = full.clone();
+        bad[WAL_MAGIC.len() + 10] ^= 0xFF;
+        std::fs::write(&wal, &bad).unwrap();
+        let err = TritStore::open(&dir).err().unwrap();
+        assert!(err.contains("WAL 손상: 오프셋 5 체크섬 불일치"), "{}", err);
+
+        std::fs::write(&wal, b"NOPE!").unwrap();
+        assert!(TritStore::open(&dir).err().unwrap().contains("WAL 헤더 불일치"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -11,10 +11,23 @@
 ///!   - 합의 시뮬레이터: 다수결/거부권 테스트
 ///!   - 권한 테스트: 접근 제어 검증
 ///!   - 한선어 프로그램 실행 테스트
+///!   - 스택 · 출력 줄(보여줘) · 종료 상태 · 골든 파일 어서션
+///!   - 선언형 빌더: TestSuite::new(..).with(ProgramTest::new(..).expect_top(8))
+///!   - 속성 기반 테스트: 무작위 Word6 · 명령 나열 + 최소 반례 축소
 ///!   - 테스트 스위트 + 보고서
+///!
+///! 골든 파일: 없으면 만들고 통과, CROWNY_UPDATE_GOLDEN=1이면 다시 쓴다
 
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use crate::car::TritState;
+use crate::conformance::Gen;
+use crate::opcode::{build_opcodes, Effect, OpcodeAddr};
+use crate::trit::Word6;
+use crate::value::Value;
+use crate::vm::{ExecLimits, Instruction, TVM, VmErrorKind};
+
+pub const GOLDEN_UPDATE_ENV: &str = "CROWNY_UPDATE_GOLDEN";
 
 // ─────────────────────────────────────────────
 // TritAssert — 3진 어서션
@@ -89,6 +102,80 @@ impl TritAssert {
         }
     }
 
+    /// 스택 전체 비교 (바닥 → 꼭대기) — 타입까지 같아야 한다 (Int 8 ≠ Str "8")
+    pub fn eq_stack(name: &str, actual: &[Value], expected: &[Value]) -> AssertResult {
+        let same = actual.len() == expected.len()
+            && actual.iter().zip(expected).all(|(a, e)| format!("{:?}", a) == format!("{:?}", e));
+        let show = |vs: &[Value]| format!("[{}]", vs.iter().map(|v| format!("{:?}", v)).collect::<Vec<_>>().join(", "));
+        AssertResult {
+            passed: same,
+            name: name.to_string(),
+            message: if same { "스택 일치".into() } else { "스택 불일치".into() },
+            expected: show(expected),
+            actual: show(actual),
+        }
+    }
+
+    /// 출력 줄 비교 — 실패하면 처음 어긋난 줄을 보여준다
+    pub fn eq_lines(name: &str, actual: &[String], expected: &[&str]) -> AssertResult {
+        let first_diff = (0..actual.len().max(expected.len()))
+            .find(|&i| actual.get(i).map(String::as_str) != expected.get(i).copied());
+        let at = |lines: &[&str], i: usize| lines.get(i).map_or("(없음)".to_string(), |l| format!("{:?}", l));
+        let actual_refs: Vec<&str> = actual.iter().map(String::as_str).collect();
+        AssertResult {
+            passed: first_diff.is_none(),
+            name: name.to_string(),
+            message: match first_diff {
+                None => format!("출력 {}줄 일치", actual.len()),
+                Some(i) => format!("{}번째 줄부터 다름", i + 1),
+            },
+            expected: first_diff.map_or(format!("{}줄", expected.len()), |i| format!("{}행 {}", i + 1, at(expected, i))),
+            actual: first_diff.map_or(format!("{}줄", actual.len()), |i| format!("{}행 {}", i + 1, at(&actual_refs, i))),
+        }
+    }
+
+    /// 출력 어딘가에 그 줄이 있는지
+    pub fn has_line(name: &str, actual: &[String], line: &str) -> AssertResult {
+        let found = actual.iter().any(|l| l == line);
+        AssertResult {
+            passed: found,
+            name: name.to_string(),
+            message: if found { "출력 줄 있음".into() } else { "출력 줄 없음".into() },
+            expected: format!("{:?} 포함", line),
+            actual: format!("{}줄 출력", actual.len()),
+        }
+    }
+
+    /// 골든 파일 비교 — 파일이 없거나 CROWNY_UPDATE_GOLDEN=1이면 actual로 (다시) 쓰고 통과
+    pub fn matches_golden(name: &str, actual: &str, path: &Path) -> AssertResult {
+        let update = std::env::var(GOLDEN_UPDATE_ENV).is_ok_and(|v| v == "1");
+        let existing = std::fs::read_to_string(path).ok();
+        if update || existing.is_none() {
+            let written = path.parent().map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(path, actual));
+            return AssertResult {
+                passed: written.is_ok(),
+                name: name.to_string(),
+                message: match &written {
+                    Ok(()) => format!("골든 기록: {}", path.display()),
+                    Err(e) => format!("골든 기록 실패: {}", e),
+                },
+                expected: path.display().to_string(),
+                actual: format!("{}바이트", actual.len()),
+            };
+        }
+        let expected = existing.unwrap_or_default();
+        let expected_lines: Vec<&str> = expected.lines().collect();
+        let actual_lines: Vec<String> = actual.lines().map(String::from).collect();
+        let mut r = Self::eq_lines(name, &actual_lines, &expected_lines);
+        // 줄이 같아도 끝 줄바꿈까지 같아야 한다
+        r.passed = r.passed && expected == actual;
+        if !r.passed {
+            r.message = format!("골든 불일치 ({}) — {}=1로 갱신", path.display(), GOLDEN_UPDATE_ENV);
+        }
+        r
+    }
+
     /// 상태 전이 규칙 검증: 직접 -1→+1 점프 금지
     pub fn valid_transition(name: &str, from: i8, to: i8) -> AssertResult {
         let valid = (to - from).abs() <= 1;
@@ -165,6 +252,17 @@ impl TestSuite {
         self.cases.push(case);
     }
 
+    /// 선언형 등록 — TestSuite::new(..).with(ProgramTest::new(..)).with(...)
+    pub fn with(mut self, case: impl Into<TestCase>) -> Self {
+        self.cases.push(case.into());
+        self
+    }
+
+    /// 클로저 케이스 등록 (선언형)
+    pub fn case(self, name: &str, runner: impl FnOnce() -> Vec<AssertResult> + 'static) -> Self {
+        self.with(TestCase::new(name, "", runner))
+    }
+
     /// 전체 실행
     pub fn run(self) -> SuiteResult {
         let start = Instant::now();
@@ -249,6 +347,413 @@ pub fn source_test(name: &str, source: &str, expected: i64) -> TestCase {
     })
 }
 
+// ─────────────────────────────────────────────
+// 선언형 프로그램 테스트
+// ─────────────────────────────────────────────
+
+/// 한 번 실행한 결과 — P 정상 종료 · O 한도 초과 · T 오류 (또는 빈 프로그램)
+#[derive(Debug, Clone)]
+pub struct ProgramRun {
+    pub state: TritState,
+    pub stack: Vec<Value>,
+    /// 보여줘 출력 (캡처)
+    pub output: Vec<String>,
+    pub error: Option<String>,
+    pub cycles: u64,
+}
+
+impl ProgramRun {
+    /// 골든 파일 형식 — 출력 줄마다 줄바꿈
+    pub fn output_text(&self) -> String {
+        self.output.iter().map(|l| format!("{}\n", l)).collect()
+    }
+}
+
+/// 출력을 캡처해 실행
+pub fn run_program(source: &str, limits: ExecLimits) -> ProgramRun {
+    let program = crate::assembler::assemble(source);
+    if program.is_empty() {
+        return ProgramRun { state: TritState::Failed, stack: Vec::new(), output: Vec::new(), error: Some("빈 프로그램".into()), cycles: 0 };
+    }
+    let mut vm = crate::vm::TVM::new();
+    vm.limits = limits;
+    vm.captured = Some(Vec::new());
+    vm.load(program);
+    let outcome = vm.run();
+    let state = match &outcome {
+        Ok(()) => TritState::Success,
+        Err(e) if matches!(e.kind, VmErrorKind::LimitExceeded(_)) => TritState::Pending,
+        Err(_) => TritState::Failed,
+    };
+    ProgramRun {
+        state,
+        stack: std::mem::take(&mut vm.stack),
+        output: vm.captured.take().unwrap_or_default(),
+        error: outcome.err().map(|e| e.to_string()),
+        cycles: vm.cycles,
+    }
+}
+
+/// 기대값을 쌓아 TestCase로 — 기대가 없으면 P(정상 종료)만 확인
+///   ProgramTest::new("곱", "넣어 6\n넣어 7\n곱해\n보여줘\n종료").expect_output(&["42"]).expect_stack(vec![])
+pub struct ProgramTest {
+    name: String,
+    source: String,
+    limits: ExecLimits,
+    state: TritState,
+    top: Option<i64>,
+    stack: Option<Vec<Value>>,
+    output: Option<Vec<String>>,
+    lines: Vec<String>,
+    golden: Option<PathBuf>,
+}
+
+impl ProgramTest {
+    pub fn new(name: &str, source: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            // 테스트가 무한 루프로 멈추지 않게
+            limits: ExecLimits { max_cycles: Some(1_000_000), ..ExecLimits::unlimited() },
+            state: TritState::Success,
+            top: None,
+            stack: None,
+            output: None,
+            lines: Vec::new(),
+            golden: None,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: ExecLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 종료 상태 (기본 P)
+    pub fn expect_state(mut self, state: TritState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// 스택 꼭대기 정수
+    pub fn expect_top(mut self, value: i64) -> Self {
+        self.top = Some(value);
+        self
+    }
+
+    /// 스택 전체 (바닥 → 꼭대기)
+    pub fn expect_stack(mut self, values: Vec<Value>) -> Self {
+        self.stack = Some(values);
+        self
+    }
+
+    pub fn expect_ints(self, values: &[i64]) -> Self {
+        self.expect_stack(values.iter().map(|n| Value::Int(*n)).collect())
+    }
+
+    /// 보여줘 출력 전체
+    pub fn expect_output(mut self, lines: &[&str]) -> Self {
+        self.output = Some(lines.iter().map(|l| l.to_string()).collect());
+        self
+    }
+
+    /// 출력 중 한 줄
+    pub fn expect_line(mut self, line: &str) -> Self {
+        self.lines.push(line.to_string());
+        self
+    }
+
+    /// 출력 전체를 골든 파일과 비교
+    pub fn expect_golden(mut self, path: impl Into<PathBuf>) -> Self {
+        self.golden = Some(path.into());
+        self
+    }
+
+    pub fn check(&self) -> Vec<AssertResult> {
+        let run = run_program(&self.source, self.limits);
+        let n = &self.name;
+        let mut results = vec![TritAssert::eq_state(&format!("{}_상태", n), run.state, self.state)];
+        if let (Some(e), false) = (&run.error, run.state == self.state) {
+            results[0].message = format!("{} — {} ({} 사이클)", results[0].message, e, run.cycles);
+        }
+        if let Some(top) = self.top {
+            let actual = run.stack.last().and_then(|v| v.as_int());
+            results.push(match actual {
+                Some(v) => TritAssert::eq_i64(&format!("{}_값", n), v, top),
+                None => AssertResult { passed: false, name: format!("{}_값", n), message: "스택 꼭대기가 정수 아님".into(),
+                    expected: top.to_string(), actual: format!("{:?}", run.stack.last()) },
+            });
+        }
+        if let Some(stack) = &self.stack {
+            results.push(TritAssert::eq_stack(&format!("{}_스택", n), &run.stack, stack));
+        }
+        if let Some(output) = &self.output {
+            let expected: Vec<&str> = output.iter().map(String::as_str).collect();
+            results.push(TritAssert::eq_lines(&format!("{}_출력", n), &run.output, &expected));
+        }
+        for line in &self.lines {
+            results.push(TritAssert::has_line(&format!("{}_출력줄", n), &run.output, line));
+        }
+        if let Some(path) = &self.golden {
+            results.push(TritAssert::matches_golden(&format!("{}_골든", n), &run.output_text(), path));
+        }
+        results
+    }
+}
+
+impl From<ProgramTest> for TestCase {
+    fn from(t: ProgramTest) -> Self {
+        TestCase::new(&t.name.clone(), "한선어 선언형 테스트", move || t.check())
+    }
+}
+
+// ─────────────────────────────────────────────
+// 속성 기반 테스트 — 무작위 입력 + 반례 축소
+// ─────────────────────────────────────────────
+
+/// 반례 축소 후보 — 더 "작은" 값들을 돌려준다 (없으면 더 줄일 수 없음)
+pub trait Shrink: Clone + std::fmt::Debug {
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+impl Shrink for i64 {
+    /// 0 쪽으로: 0 · 절반 · 한 칸
+    fn shrink(&self) -> Vec<Self> {
+        let n = *self;
+        if n == 0 {
+            return Vec::new();
+        }
+        let mut out = vec![0, n / 2, n - n.signum()];
+        out.dedup();
+        out.retain(|&c| c != n);
+        out
+    }
+}
+
+impl Shrink for Word6 {
+    fn shrink(&self) -> Vec<Self> {
+        (self.to_decimal() as i64).shrink().into_iter().map(|v| Word6::from_decimal(v as i16)).collect()
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let left = self.0.shrink().into_iter().map(|a| (a, self.1.clone()));
+        left.chain(self.1.shrink().into_iter().map(|b| (self.0.clone(), b))).collect()
+    }
+}
+
+impl<T: Shrink> Shrink for Vec<T> {
+    /// 원소 하나씩 빼기 → 원소 하나씩 줄이기
+    fn shrink(&self) -> Vec<Self> {
+        let mut out: Vec<Self> = (0..self.len())
+            .map(|i| self.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, x)| x.clone()).collect())
+            .collect();
+        for (i, x) in self.iter().enumerate() {
+            for smaller in x.shrink() {
+                let mut v = self.clone();
+                v[i] = smaller;
+                out.push(v);
+            }
+        }
+        out
+    }
+}
+
+/// 시드 · 케이스 수 · 축소 한도
+#[derive(Debug, Clone, Copy)]
+pub struct PropConfig {
+    pub seed: u64,
+    pub cases: usize,
+    pub max_shrinks: usize,
+}
+
+impl Default for PropConfig {
+    fn default() -> Self {
+        Self { seed: crate::conformance::DEFAULT_SEED, cases: crate::conformance::DEFAULT_CASES, max_shrinks: 1000 }
+    }
+}
+
+impl PropConfig {
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+}
+
+/// 무작위 입력 cases개 검사 — 실패하면 더 줄지 않을 때까지 축소해 최소 반례를 보고
+pub fn for_all<T: Shrink>(
+    name: &str,
+    config: PropConfig,
+    generate: impl Fn(&mut Gen) -> T,
+    check: impl Fn(&T) -> Result<(), String>,
+) -> AssertResult {
+    let mut gen = Gen::new(config.seed);
+    for case in 1..=config.cases {
+        let input = generate(&mut gen);
+        let Err(first) = check(&input) else { continue };
+
+        let (mut smallest, mut why, mut steps) = (input, first, 0usize);
+        'shrinking: while steps < config.max_shrinks {
+            for candidate in smallest.shrink() {
+                if let Err(e) = check(&candidate) {
+                    (smallest, why, steps) = (candidate, e, steps + 1);
+                    continue 'shrinking;
+                }
+            }
+            break;
+        }
+        return AssertResult {
+            passed: false,
+            name: name.into(),
+            message: why,
+            expected: format!("{}건 모두 성립", config.cases),
+            actual: format!("최소 반례 {:?} (시드 {:#x}, {}번째 입력, 축소 {}회)", smallest, config.seed, case, steps),
+        };
+    }
+    AssertResult {
+        passed: true,
+        name: name.into(),
+        message: format!("{}건 통과", config.cases),
+        expected: format!("{}건 모두 성립", config.cases),
+        actual: format!("{}건", config.cases),
+    }
+}
+
+pub fn gen_word6(gen: &mut Gen) -> Word6 {
+    Word6::from_decimal(gen.range(-(Word6::MAX as i64), Word6::MAX as i64) as i16)
+}
+
+/// 스택 효과 검사용 명령 — 넣어 n 또는 메타데이터의 pops/pushes를 들고 있는 명령
+#[derive(Clone, Copy, PartialEq)]
+pub enum GenOp {
+    Push(i64),
+    Op { addr: OpcodeAddr, name: &'static str, pops: u8, pushes: u8 },
+}
+
+impl std::fmt::Debug for GenOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenOp::Push(n) => write!(f, "넣어 {}", n),
+            GenOp::Op { name, .. } => f.write_str(name),
+        }
+    }
+}
+
+impl Shrink for GenOp {
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            GenOp::Push(n) => n.shrink().into_iter().map(GenOp::Push).collect(),
+            GenOp::Op { .. } => Vec::new(),
+        }
+    }
+}
+
+impl GenOp {
+    fn instruction(&self) -> Instruction {
+        match *self {
+            GenOp::Push(n) => Instruction::from_addr(OpcodeAddr::new(0, 3, 0), vec![Value::Int(n)]),
+            GenOp::Op { addr, .. } => Instruction::from_addr(addr, Vec::new()),
+        }
+    }
+}
+
+/// 메타데이터는 있지만 VM이 아직 NOP으로 넘기는 코어 스택 명령 (함수 G4 · 고차 컬렉션 G7)
+const RESERVED_CORE_OPS: [(u8, u8); 9] = [(4, 1), (4, 4), (4, 5), (4, 6), (4, 7), (7, 5), (7, 6), (7, 7), (7, 8)];
+
+/// 코어 섹터의 피연산자 없는 스택 명령 — 깊이가 정해지지 않는 비움(N→0)과 미구현 명령은 뺀다
+pub fn stack_op_pool() -> Vec<GenOp> {
+    let mut pool: Vec<GenOp> = build_opcodes()
+        .into_iter()
+        .filter(|(addr, m)| {
+            addr.sector == 0 && m.effect == Effect::Stack && m.operands == 0 && m.pops + m.pushes > 0
+                && !RESERVED_CORE_OPS.contains(&(addr.group, addr.command))
+        })
+        .map(|(addr, m)| GenOp::Op { addr, name: m.name_kr, pops: m.pops, pushes: m.pushes })
+        .collect();
+    // HashMap 순서에 기대지 않게 — 같은 시드는 같은 프로그램
+    pool.sort_by_key(|op| match op {
+        GenOp::Op { addr, .. } => (addr.group, addr.command),
+        GenOp::Push(_) => (0, 0),
+    });
+    pool
+}
+
+/// 0~max_len개 명령 — 절반 가까이는 넣어라서 스택이 자주 비지 않는다
+pub fn gen_ops(gen: &mut Gen, pool: &[GenOp], max_len: usize) -> Vec<GenOp> {
+    let len = gen.range(0, max_len as i64) as usize;
+    (0..len)
+        .map(|_| match gen.range(0, 4) {
+            0 | 1 => GenOp::Push(gen.range(-20, 20)),
+            _ => pool[gen.range(0, pool.len() as i64 - 1) as usize],
+        })
+        .collect()
+}
+
+/// 한 명령씩 실행하며 깊이 변화 = pushes − pops 확인.
+/// 깊이가 충분한데 스택 부족이면 위반, 0나눗셈 · 타입 오류 등은 거기서 멈춘다
+pub fn check_stack_effects(ops: &[GenOp]) -> Result<(), String> {
+    let mut vm = TVM::new();
+    vm.load(ops.iter().map(GenOp::instruction).collect());
+    for (i, op) in ops.iter().enumerate() {
+        let (pops, pushes) = match *op {
+            GenOp::Push(_) => (0, 1),
+            GenOp::Op { pops, pushes, .. } => (pops as usize, pushes as usize),
+        };
+        let before = vm.stack.len();
+        match vm.step() {
+            Ok(_) => {
+                let expected = before - pops.min(before) + pushes;
+                if before < pops || vm.stack.len() != expected {
+                    return Err(format!("{}번째 {:?}: 깊이 {} → {} (메타데이터 기대 {})", i + 1, op, before, vm.stack.len(), expected));
+                }
+            }
+            Err(e) if matches!(e.kind, VmErrorKind::StackUnderflow(_)) && before >= pops => {
+                return Err(format!("{}번째 {:?}: 깊이 {}인데 스택 부족 — {}", i + 1, op, before, e));
+            }
+            Err(_) => return Ok(()),
+        }
+    }
+    Ok(())
+}
+
+fn expect_same<A: PartialEq + std::fmt::Debug>(what: &str, actual: A, expected: A) -> Result<(), String> {
+    if actual == expected { Ok(()) } else { Err(format!("{}: {:?} ≠ {:?}", what, actual, expected)) }
+}
+
+/// Word6 인코딩 · 산술 불변식 + 명령별 스택 깊이 보존
+pub fn property_suite(config: PropConfig) -> TestSuite {
+    TestSuite::new("속성 기반 테스트")
+        .case("Word6_왕복", move || vec![
+            for_all("10진 왕복", config, gen_word6, |w| expect_same("from_decimal", Word6::from_decimal(w.to_decimal()), *w)),
+            for_all("트릿 문자열 왕복", config, gen_word6, |w| expect_same("from_trit_str", Word6::from_trit_str(&w.to_string()), Some(*w))),
+            for_all("opcode 분해/조립", config, |g| (g.range(0, 8), g.range(0, 8) * 9 + g.range(0, 8)), |&(s, gc)| {
+                let addr = (s as u8, (gc / 9) as u8, (gc % 9) as u8);
+                expect_same("decode_opcode", Word6::encode_opcode(addr.0, addr.1, addr.2).decode_opcode(), addr)
+            }),
+        ])
+        .case("Word6_산술", move || vec![
+            for_all("(a+b)−b = a", config, |g| (gen_word6(g), gen_word6(g)), |(a, b)| {
+                expect_same("wrapping", a.add(b).wrapping().sub(b).wrapping(), *a)
+            }),
+            for_all("a+b 12트릿 값", config, |g| (gen_word6(g), gen_word6(g)), |(a, b)| {
+                expect_same("to_wide_decimal", a.add(b).to_wide_decimal(), a.to_decimal() as i32 + b.to_decimal() as i32)
+            }),
+            for_all("−(−a) = a", config, gen_word6, |a| expect_same("neg", a.neg().neg(), *a)),
+        ])
+        .case("스택_깊이_보존", move || {
+            let pool = stack_op_pool();
+            vec![for_all("pops/pushes 메타데이터", config, move |g| gen_ops(g, &pool, 12), |ops| check_stack_effects(ops))]
+        })
+}
+
 // ─────────────────────────────────────────────
 // 내장 테스트 스위트
 // ─────────────────────────────────────────────
@@ -411,4 +916,77 @@ mod tests {
         let result = consensus_suite().run();
         assert_eq!(result.failed, 0, "합의 테스트 실패:\n{}", result.report());
     }
+
+    #[test]
+    fn test_program_assertions() {
+        let result = TestSuite::new("선언형")
+            .with(ProgramTest::new("출력", "넣어 \"안녕\"\n보여줘\n넣어 2\n넣어 3\n곱해\n복사\n보여줘\n종료")
+                .expect_output(&["\"안녕\"", "6"]).expect_line("6").expect_ints(&[6]).expect_top(6))
+            .with(ProgramTest::new("문자열_스택", "넣어 \"8\"\n종료").expect_stack(vec![Value::Str("8".into())]))
+            .with(ProgramTest::new("0나눗셈", "넣어 1\n넣어 0\n나눠\n종료").expect_state(TritState::Failed))
+            .with(ProgramTest::new("무한루프", "반복:\n점프 반복")
+                .with_limits(ExecLimits { max_cycles: Some(100), ..ExecLimits::unlimited() })
+                .expect_state(TritState::Pending))
+            .case("클로저", || vec![TritAssert::is_success("P", TritState::Success)])
+            .run();
+        assert_eq!((result.total, result.failed), (10, 0), "{}", result.report());
+
+        // 실패는 어긋난 곳을 짚는다
+        let wrong = ProgramTest::new("틀림", "넣어 1\n보여줘\n넣어 8\n종료")
+            .expect_stack(vec![Value::Str("8".into())]).expect_output(&["1", "2"]).check();
+        assert_eq!(wrong.iter().filter(|r| !r.passed).count(), 2);
+        assert_eq!(wrong[1].actual, "[Int(8)]");
+        assert_eq!(wrong[2].message, "2번째 줄부터 다름");
+        assert_eq!((wrong[2].expected.as_str(), wrong[2].actual.as_str()), ("2행 \"2\"", "2행 (없음)"));
+    }
+
+    #[test]
+    fn test_golden_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("crowny-golden-{}/out.txt", std::process::id()));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        let case = |src: &str| ProgramTest::new("골든", src).expect_golden(&path).check();
+
+        // 처음엔 기록하고 통과, 같은 출력은 통과, 바뀐 출력은 실패
+        assert!(case("넣어 1\n보여줘\n넣어 2\n보여줘\n종료").iter().all(|r| r.passed));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n");
+        assert!(case("넣어 1\n보여줘\n넣어 2\n보여줘\n종료").iter().all(|r| r.passed));
+        let changed = case("넣어 1\n보여줘\n넣어 3\n보여줘\n종료");
+        assert!(!changed[1].passed && changed[1].message.contains(GOLDEN_UPDATE_ENV));
+        assert_eq!(changed[1].actual, "2행 \"3\"");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_property_suite_holds() {
+        let result = property_suite(PropConfig::default().with_cases(200)).run();
+        assert_eq!(result.failed, 0, "{}", result.report());
+    }
+
+    #[test]
+    fn test_for_all_shrinks_to_minimal() {
+        let cfg = PropConfig::default().with_seed(7);
+        let r = for_all("100 미만", cfg, |g| g.range(0, 1000), |&n| if n < 100 { Ok(()) } else { Err(format!("{} ≥ 100", n)) });
+        assert!(!r.passed);
+        assert!(r.actual.starts_with("최소 반례 100 "), "{}", r.actual);
+
+        let r = for_all("10 미만 원소", cfg, |g| (0..g.range(0, 8)).map(|_| g.range(-50, 50)).collect::<Vec<i64>>(), |v| {
+            if v.iter().all(|&x| x < 10) { Ok(()) } else { Err("10 이상".into()) }
+        });
+        assert!(r.actual.starts_with("최소 반례 [10] "), "{}", r.actual);
+    }
+
+    #[test]
+    fn test_wrong_stack_metadata_shrinks_to_reproducer() {
+        // 복사를 1→1로 잘못 적으면 넣어 0 + 복사 두 줄로 줄어든다
+        let mut pool = stack_op_pool();
+        for op in pool.iter_mut() {
+            if let GenOp::Op { name: "복사", pushes, .. } = op {
+                *pushes = 1;
+            }
+        }
+        let r = for_all("깊이", PropConfig::default(), move |g| gen_ops(g, &pool, 12), |ops| check_stack_effects(ops));
+        assert!(!r.passed);
+        assert!(r.actual.starts_with("최소 반례 [넣어 0, 복사] "), "{}", r.actual);
+        assert!(r.message.contains("2번째 복사: 깊이 1 → 2"), "{}", r.message);
+    }
 }
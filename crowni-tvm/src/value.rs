@@ -1,6 +1,9 @@
 ///! VM Value 타입 — GPT 명세 기반
 ///! 정수(i64), 실수(f64), 논리(bool), 트릿(i8), 주소(usize),
 ///! 문자열, 배열(Vec<Value>), 객체(HashMap), 없음
+///!
+///! 문자열 길이·인덱스·슬라이스는 바이트가 아니라 자소 묶음(grapheme) 단위:
+///!   "한글" → 2,  "👍🏽" → 1,  "🇰🇷" → 1,  "ᄒᆞᆫ"(옛한글 자모) → 1
 
 use crate::trit::Trit;
 use std::collections::HashMap;
@@ -84,6 +87,39 @@ impl Value {
         }
     }
 
+    /// 길이 — 문자열은 자소 묶음 수, 배열은 원소 수
+    pub fn length(&self) -> Option<i64> {
+        match self {
+            Value::Str(s) => Some(grapheme_len(s) as i64),
+            Value::Array(a) => Some(a.len() as i64),
+            _ => None,
+        }
+    }
+
+    /// i번째 원소/글자 (음수는 끝에서부터) — 범위 밖이면 None
+    pub fn index(&self, i: i64) -> Option<Value> {
+        match self {
+            Value::Str(s) => {
+                let g = graphemes(s);
+                resolve_index(i, g.len()).map(|i| Value::Str(g[i].to_string()))
+            }
+            Value::Array(a) => resolve_index(i, a.len()).map(|i| a[i].clone()),
+            _ => None,
+        }
+    }
+
+    /// [start, end) 부분 — 음수는 끝에서부터, 범위는 잘라서 맞춘다
+    pub fn slice(&self, start: i64, end: i64) -> Option<Value> {
+        match self {
+            Value::Str(s) => Some(Value::Str(substring(s, start, end))),
+            Value::Array(a) => {
+                let (from, to) = clamp_range(start, end, a.len());
+                Some(Value::Array(a[from..to].to_vec()))
+            }
+            _ => None,
+        }
+    }
+
     pub fn type_name_kr(&self) -> &'static str {
         match self {
             Value::Int(_) => "정수",
@@ -128,3 +164,149 @@ impl fmt::Display for Value {
         }
     }
 }
+
+// ─────────────────────────────────────────────
+// 자소 묶음(grapheme cluster) — 외부 의존성 없는 단순화된 UAX #29
+// 결합 문자 · 이모지 수식/ZWJ 시퀀스 · 국기(지역 표시 쌍) · 한글 자모 조합
+// ─────────────────────────────────────────────
+
+const ZWJ: char = '\u{200D}';
+
+/// 앞 글자에 붙는 문자 (결합 부호, 이형 선택자, 피부색 수식, 태그)
+fn is_extend(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F | 0x0483..=0x0489 | 0x0591..=0x05BD | 0x0610..=0x061A
+        | 0x064B..=0x065F | 0x0900..=0x0903 | 0x093A..=0x094F | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF | 0x200C | 0x20D0..=0x20FF | 0x302A..=0x302F
+        | 0x3099..=0x309A | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F
+        | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F | 0xE0100..=0xE01EF)
+}
+
+fn is_regional(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// ZWJ 뒤에 이어 붙는 그림 문자
+fn is_pictographic(c: char) -> bool {
+    matches!(c as u32, 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Jamo { L, V, T, Lv, Lvt, Other }
+
+fn jamo(c: char) -> Jamo {
+    match c as u32 {
+        0x1100..=0x115F | 0xA960..=0xA97C => Jamo::L,
+        0x1160..=0x11A7 | 0xD7B0..=0xD7C6 => Jamo::V,
+        0x11A8..=0x11FF | 0xD7CB..=0xD7FB => Jamo::T,
+        n @ 0xAC00..=0xD7A3 => if (n - 0xAC00) % 28 == 0 { Jamo::Lv } else { Jamo::Lvt },
+        _ => Jamo::Other,
+    }
+}
+
+/// prev와 next 사이가 같은 묶음인지 — ri_run은 현재 묶음의 지역 표시 수
+fn joins(prev: char, next: char, ri_run: usize) -> bool {
+    if prev == '\r' {
+        return next == '\n';
+    }
+    if prev.is_control() || next.is_control() {
+        return false;
+    }
+    if is_extend(next) || next == ZWJ {
+        return true;
+    }
+    if prev == ZWJ && is_pictographic(next) {
+        return true;
+    }
+    if is_regional(prev) && is_regional(next) {
+        return ri_run % 2 == 1;
+    }
+    matches!((jamo(prev), jamo(next)),
+        (Jamo::L, Jamo::L | Jamo::V | Jamo::Lv | Jamo::Lvt)
+        | (Jamo::Lv | Jamo::V, Jamo::V | Jamo::T)
+        | (Jamo::Lvt | Jamo::T, Jamo::T))
+}
+
+/// 문자열 → 자소 묶음 조각
+pub fn graphemes(s: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    let mut ri_run = 0;
+    for (i, c) in s.char_indices() {
+        if let Some(p) = prev {
+            if !joins(p, c, ri_run) {
+                out.push(&s[start..i]);
+                start = i;
+                ri_run = 0;
+            }
+        }
+        if is_regional(c) {
+            ri_run += 1;
+        }
+        prev = Some(c);
+    }
+    if start < s.len() {
+        out.push(&s[start..]);
+    }
+    out
+}
+
+pub fn grapheme_len(s: &str) -> usize {
+    graphemes(s).len()
+}
+
+/// 자소 묶음 기준 부분 문자열 [start, end)
+pub fn substring(s: &str, start: i64, end: i64) -> String {
+    let g = graphemes(s);
+    let (from, to) = clamp_range(start, end, g.len());
+    g[from..to].concat()
+}
+
+fn resolve_index(i: i64, len: usize) -> Option<usize> {
+    let i = if i < 0 { i + len as i64 } else { i };
+    if (0..len as i64).contains(&i) { Some(i as usize) } else { None }
+}
+
+fn clamp_range(start: i64, end: i64, len: usize) -> (usize, usize) {
+    let clamp = |i: i64| (if i < 0 { i + len as i64 } else { i }).clamp(0, len as i64) as usize;
+    let (from, to) = (clamp(start), clamp(end));
+    (from, to.max(from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grapheme_len_mixed_content() {
+        assert_eq!(grapheme_len("크라우닌"), 4);
+        assert_eq!(grapheme_len("한글👍🏽ok"), 5);
+        assert_eq!(grapheme_len("👨‍👩‍👧"), 1);
+        assert_eq!(grapheme_len("🇰🇷🇯🇵"), 2);
+        assert_eq!(grapheme_len("e\u{301}"), 1);
+        // 옛한글 조합형 자모 (ㅎ + ㆍ + ㄴ) → 한 글자
+        assert_eq!(grapheme_len("\u{1112}\u{119E}\u{11AB}"), 1);
+        assert_eq!(grapheme_len("가\u{11A8}"), 1);
+        assert_eq!(grapheme_len("a\r\nb"), 3);
+        assert_eq!(grapheme_len(""), 0);
+    }
+
+    #[test]
+    fn test_index_and_slice_never_split_clusters() {
+        let s = Value::Str("안녕👋🏻세상🇰🇷".into());
+        assert_eq!(s.length(), Some(6));
+        assert_eq!(s.index(2).unwrap().as_str(), Some("👋🏻"));
+        assert_eq!(s.index(-1).unwrap().as_str(), Some("🇰🇷"));
+        assert!(s.index(6).is_none());
+        assert_eq!(s.slice(1, 3).unwrap().as_str(), Some("녕👋🏻"));
+        assert_eq!(s.slice(-3, 100).unwrap().as_str(), Some("세상🇰🇷"));
+        assert_eq!(s.slice(4, 2).unwrap().as_str(), Some(""));
+
+        let arr = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+        assert_eq!(arr.length(), Some(3));
+        assert_eq!(arr.index(-1).unwrap().as_int(), Some(3));
+        assert!(matches!(arr.slice(1, 9), Some(Value::Array(a)) if a.len() == 2));
+        assert!(Value::Int(5).index(0).is_none());
+    }
+}
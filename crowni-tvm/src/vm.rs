@@ -9,44 +9,196 @@
 ///!     program: Vec<Instruction>,
 ///!     halted: bool,
 ///! }
+///!
+///! 호출 규약: 인자는 스택으로 넘기고, 호출 · 반환 사이의 레지스터 R0..R8은 프레임 지역
+///!   호출 라벨   — 호출자 레지스터를 프레임에 보관, 피호출자는 빈 레지스터로 시작
+///!   반환        — 피호출자가 남긴 스택은 그대로 두고 호출자 레지스터 복원
+///!   돌려줘      — 호출 이후 쌓인 임시값을 버리고 값 하나만 남겨 반환
 
 use std::collections::HashMap;
 use std::io::{self, Write};
+use std::time::Instant;
 
 use crate::trit::Trit;
 use crate::value::Value;
 use crate::heap::Heap;
 use crate::opcode::{OpcodeAddr, OpMeta, build_opcodes, build_name_lookup};
+use crate::i18n::tr;
 
 // ─────────────────────────────────────────────
 // Error
 // ─────────────────────────────────────────────
 
 #[derive(Debug)]
-pub enum VmError {
+pub enum VmErrorKind {
     StackUnderflow(String),
     TypeError(String),
     DivisionByZero,
     InvalidOpcode(u8, u8, u8),
     Halted,
     HeapError(String),
+    LimitExceeded(LimitKind),
+    /// 작업 예산(budget 훅) 초과 — ExecLimits와 달리 재시도 대상이 아니다
+    BudgetExceeded(LimitKind),
+    /// 호출 깊이 초과 (끝없는 재귀)
+    StackOverflow(usize),
     Custom(String),
 }
 
+impl std::fmt::Display for VmErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            VmErrorKind::StackUnderflow(op) => tr!("vm.stack_underflow", op),
+            VmErrorKind::TypeError(msg) => tr!("vm.type_error", msg),
+            VmErrorKind::DivisionByZero => tr!("vm.division_by_zero"),
+            VmErrorKind::InvalidOpcode(s, g, c) => tr!("vm.invalid_opcode", s, g, c),
+            VmErrorKind::Halted => tr!("vm.halted"),
+            VmErrorKind::HeapError(msg) => tr!("vm.heap_error", msg),
+            VmErrorKind::LimitExceeded(kind) => tr!("vm.limit_exceeded", kind),
+            VmErrorKind::BudgetExceeded(kind) => tr!("vm.budget_exceeded", kind),
+            VmErrorKind::StackOverflow(depth) => tr!("vm.stack_overflow", depth),
+            VmErrorKind::Custom(msg) => tr!("vm.custom", msg),
+        };
+        f.write_str(&text)
+    }
+}
+
+/// 실행 오류 — 종류 + 어디서 났는지 (ip · 소스 줄 · 명령어 이름)
+#[derive(Debug)]
+pub struct VmError {
+    pub kind: VmErrorKind,
+    /// 오류를 낸 명령어 인덱스
+    pub ip: usize,
+    /// 어셈블러가 기록한 소스 줄 (1부터, 모르면 None)
+    pub source_line: Option<usize>,
+    /// 명령어 이름 (모르면 빈 문자열)
+    pub instruction_name: &'static str,
+}
+
+impl VmError {
+    /// 위치 정보 없는 오류 (디버거 스텝 한도 등)
+    pub fn new(kind: VmErrorKind, ip: usize) -> Self {
+        Self { kind, ip, source_line: None, instruction_name: "" }
+    }
+
+    /// 진단 출력 — origin은 파일 이름 등, source가 있으면 해당 줄을 밑줄과 함께 보여준다
+    ///
+    ///   오류: [오류] 0으로 나눌 수 없음
+    ///     --> prog.hsn:5 (IP 3 · 나눠)
+    ///      |
+    ///    5 | 나눠
+    ///      | ^^
+    pub fn diagnostic(&self, origin: &str, source: Option<&str>) -> String {
+        let mut out = tr!("cli.error", self.kind) + "\n";
+        let at = match self.source_line {
+            Some(line) => format!("{}:{}", origin, line),
+            None => origin.to_string(),
+        };
+        let name = if self.instruction_name.is_empty() { String::new() } else { format!(" · {}", self.instruction_name) };
+        out.push_str(&format!("  --> {} (IP {}{})", at, self.ip, name));
+
+        let text = self.source_line.and_then(|line| source?.lines().nth(line.checked_sub(1)?));
+        if let (Some(line), Some(text)) = (self.source_line, text) {
+            let gutter = " ".repeat(line.to_string().len());
+            let trimmed = text.trim_start();
+            let indent = text.len() - trimmed.len();
+            let width = trimmed.split_whitespace().next().map_or(1, |w| w.chars().count());
+            out.push_str(&format!("\n {} |\n {} | {}\n {} | {}{}",
+                gutter, line, text, gutter, " ".repeat(indent), "^".repeat(width.max(1))));
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> crate::output::JsonObject {
+        let mut obj = crate::output::JsonObject::new()
+            .int("ip", self.ip as i64)
+            .str("instruction", self.instruction_name);
+        if let Some(line) = self.source_line {
+            obj = obj.int("line", line as i64);
+        }
+        obj
+    }
+}
+
 impl std::fmt::Display for VmError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 실행 한도 (샌드박스)
+// ─────────────────────────────────────────────
+
+/// 초과된 한도 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    Cycles,     // 사이클 수
+    Heap,       // 힙 셀 수
+    Output,     // 출력 바이트
+    WallClock,  // 실행 시간
+}
+
+impl LimitKind {
+    /// 기계용 코드 (응답 본문/결과 맵에 사용)
+    pub fn code(self) -> &'static str {
         match self {
-            VmError::StackUnderflow(op) => write!(f, "[스택부족] '{}'", op),
-            VmError::TypeError(msg) => write!(f, "[타입오류] {}", msg),
-            VmError::DivisionByZero => write!(f, "[오류] 0으로 나눌 수 없음"),
-            VmError::InvalidOpcode(s, g, c) => write!(f, "[알수없는명령] ({},{},{})", s, g, c),
-            VmError::Halted => write!(f, "[종료]"),
-            VmError::HeapError(msg) => write!(f, "[힙오류] {}", msg),
-            VmError::Custom(msg) => write!(f, "[오류] {}", msg),
+            LimitKind::Cycles => "cycles",
+            LimitKind::Heap => "heap",
+            LimitKind::Output => "output",
+            LimitKind::WallClock => "wall_clock",
+        }
+    }
+
+    pub fn from_code(s: &str) -> Option<Self> {
+        match s {
+            "cycles" => Some(LimitKind::Cycles),
+            "heap" => Some(LimitKind::Heap),
+            "output" => Some(LimitKind::Output),
+            "wall_clock" => Some(LimitKind::WallClock),
+            _ => None,
         }
     }
 }
 
+impl std::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            LimitKind::Cycles => "limit.cycles",
+            LimitKind::Heap => "limit.heap",
+            LimitKind::Output => "limit.output",
+            LimitKind::WallClock => "limit.wall_clock",
+        };
+        f.write_str(crate::i18n::t(key))
+    }
+}
+
+/// 실행 한도 — None 이면 무제한
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecLimits {
+    pub max_cycles: Option<u64>,
+    pub max_heap: Option<usize>,
+    pub max_output_bytes: Option<usize>,
+    pub max_ms: Option<u64>,
+}
+
+impl ExecLimits {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+}
+
+/// budget 훅에 넘기는 사용량
+#[derive(Debug, Clone, Copy)]
+pub struct BudgetUsage {
+    pub cycles: u64,
+    pub heap: usize,
+    pub started: Instant,
+}
+
+/// 사이클 예산 훅 — run()이 명령어마다 호출, Err면 그 오류로 중단
+pub type BudgetHook = Box<dyn FnMut(&BudgetUsage) -> Result<(), VmErrorKind>>;
+
 // ─────────────────────────────────────────────
 // Instruction (GPT 명세)
 // ─────────────────────────────────────────────
@@ -57,6 +209,7 @@ pub struct Instruction {
     pub opcode: [i8; 6],          // 균형3진 6트릿 (-1,0,+1)
     pub addr: OpcodeAddr,         // 디코딩된 (sector,group,command)
     pub operands: Vec<Value>,     // 추가 피연산자
+    pub line: Option<usize>,      // 소스 줄 (어셈블러가 기록, 1부터)
 }
 
 impl Instruction {
@@ -68,7 +221,7 @@ impl Instruction {
             w.trits[0].to_i8(), w.trits[1].to_i8(), w.trits[2].to_i8(),
             w.trits[3].to_i8(), w.trits[4].to_i8(), w.trits[5].to_i8(),
         ];
-        Self { opcode, addr, operands }
+        Self { opcode, addr, operands, line: None }
     }
 }
 
@@ -76,10 +229,15 @@ impl Instruction {
 // Call Frame
 // ─────────────────────────────────────────────
 
+/// 최대 호출 깊이
+pub const MAX_CALL_DEPTH: usize = 4096;
+
 #[derive(Debug, Clone)]
 pub struct CallFrame {
     pub return_ip: usize,
     pub base_sp: usize,  // 호출 시 스택 깊이
+    /// 호출자 레지스터 — 반환 시 복원
+    pub saved_registers: [Value; 9],
 }
 
 // ─────────────────────────────────────────────
@@ -91,7 +249,7 @@ pub struct TVM {
     pub stack: Vec<Value>,
     /// Arena 힙
     pub heap: Heap,
-    /// 레지스터 9개 (R0..R8)
+    /// 레지스터 9개 (R0..R8) — 현재 프레임 지역
     pub registers: [Value; 9],
     /// 명령어 포인터 (Instruction Pointer)
     pub ip: usize,
@@ -111,6 +269,14 @@ pub struct TVM {
     pub debug: bool,
     /// 실행된 명령어 수 (프로파일링)
     pub cycles: u64,
+    /// 실행 한도 (기본: 무제한)
+    pub limits: ExecLimits,
+    /// 호출 측 예산 훅 (CAR 작업 한도 · 취소)
+    pub budget: Option<BudgetHook>,
+    /// 보여줘로 출력한 누적 바이트
+    pub output_bytes: usize,
+    /// 출력 캡처 (Some이면 stdout 대신 여기에 쌓는다)
+    pub captured: Option<Vec<String>>,
 }
 
 impl TVM {
@@ -130,6 +296,10 @@ impl TVM {
             name_lookup,
             debug: false,
             cycles: 0,
+            limits: ExecLimits::unlimited(),
+            budget: None,
+            output_bytes: 0,
+            captured: None,
         }
     }
 
@@ -141,27 +311,118 @@ impl TVM {
         self.stack.clear();
         self.call_stack.clear();
         self.cycles = 0;
+        self.output_bytes = 0;
+        self.heap.reset_stats();
+    }
+
+    /// 세션 이어서 로드 — 스택·레지스터·힙·전역 변수는 유지 (노트북 셀)
+    pub fn load_continue(&mut self, program: Vec<Instruction>) {
+        self.program = program;
+        self.ip = 0;
+        self.halted = false;
+        self.call_stack.clear();
+    }
+
+    /// 한도 검사 — 사이클/힙/실행시간 (출력은 보여줘에서 검사)
+    fn check_limits(&self, started: Instant) -> Result<(), VmErrorKind> {
+        if let Some(max) = self.limits.max_cycles {
+            if self.cycles > max {
+                return Err(VmErrorKind::LimitExceeded(LimitKind::Cycles));
+            }
+        }
+        if let Some(max) = self.limits.max_heap {
+            if self.heap.alive_count() > max {
+                return Err(VmErrorKind::LimitExceeded(LimitKind::Heap));
+            }
+        }
+        if let Some(max) = self.limits.max_ms {
+            // 시계 조회는 256 사이클마다
+            if self.cycles.is_multiple_of(256) && started.elapsed().as_millis() as u64 > max {
+                return Err(VmErrorKind::LimitExceeded(LimitKind::WallClock));
+            }
+        }
+        Ok(())
     }
 
     // ── 스택 헬퍼 ──
 
-    fn pop(&mut self, op: &str) -> Result<Value, VmError> {
-        self.stack.pop().ok_or_else(|| VmError::StackUnderflow(op.into()))
+    fn pop(&mut self, op: &str) -> Result<Value, VmErrorKind> {
+        self.stack.pop().ok_or_else(|| VmErrorKind::StackUnderflow(op.into()))
     }
 
-    fn pop2_int(&mut self, op: &str) -> Result<(i64, i64), VmError> {
+    /// 분기 대상 — 피연산자(어셈블러 라벨)가 있으면 그것, 없으면 스택에서
+    fn branch_target(&mut self, op: &str, operands: &[Value]) -> Result<Value, VmErrorKind> {
+        match operands.first() {
+            Some(v) => Ok(v.clone()),
+            None => self.pop(op),
+        }
+    }
+
+    /// 전역 이름 — 피연산자 문자열 · 슬롯 번호(`#N`), 없으면 스택의 문자열
+    fn global_key(&mut self, op: &str, operands: &[Value]) -> Result<String, VmErrorKind> {
+        match operands.first() {
+            Some(Value::Str(name)) => Ok(name.clone()),
+            Some(Value::Int(slot)) => Ok(format!("#{}", slot)),
+            Some(_) => Err(VmErrorKind::TypeError(format!("{}: 이름은 문자열", op))),
+            None => match self.pop(op)? {
+                Value::Str(name) => Ok(name),
+                _ => Err(VmErrorKind::TypeError(format!("{}: 이름은 문자열", op))),
+            },
+        }
+    }
+
+    fn addr_of(op: &str, v: &Value) -> Result<usize, VmErrorKind> {
+        v.as_addr().ok_or_else(|| VmErrorKind::TypeError(format!("{}: 주소 필요", op)))
+    }
+
+    /// 프레임 복귀 — 호출자 레지스터 · ip 복원 (최상위면 false)
+    fn return_from_frame(&mut self) -> bool {
+        match self.call_stack.pop() {
+            Some(frame) => {
+                self.registers = frame.saved_registers;
+                self.ip = frame.return_ip;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pop2_int(&mut self, op: &str) -> Result<(i64, i64), VmErrorKind> {
         let b = self.pop(op)?;
         let a = self.pop(op)?;
-        let ai = a.as_int().ok_or_else(|| VmError::TypeError(format!("{}: 정수 필요, got {}", op, a.type_name_kr())))?;
-        let bi = b.as_int().ok_or_else(|| VmError::TypeError(format!("{}: 정수 필요, got {}", op, b.type_name_kr())))?;
+        let ai = a.as_int().ok_or_else(|| VmErrorKind::TypeError(format!("{}: 정수 필요, got {}", op, a.type_name_kr())))?;
+        let bi = b.as_int().ok_or_else(|| VmErrorKind::TypeError(format!("{}: 정수 필요, got {}", op, b.type_name_kr())))?;
         Ok((ai, bi))
     }
 
     // ── 메인 실행 루프 (GPT 명세 §7) ──
 
     pub fn run(&mut self) -> Result<(), VmError> {
+        self.run_loop(None)?;
+        if self.debug {
+            eprintln!("[VM 종료] 총 {}사이클 실행", self.cycles);
+        }
+        Ok(())
+    }
+
+    /// 최대 quantum 명령만 실행 (시분할 스케줄러) — Ok(true)면 아직 실행 중
+    /// 실행시간 한도는 슬라이스마다 새로 잰다
+    pub fn run_slice(&mut self, quantum: u64) -> Result<bool, VmError> {
+        self.run_loop(Some(quantum))?;
+        if self.ip >= self.program.len() {
+            self.halted = true;
+        }
+        Ok(!self.halted)
+    }
+
+    fn run_loop(&mut self, quantum: Option<u64>) -> Result<(), VmError> {
         // GPT: while !vm.halted { let inst = vm.program[vm.ip]; vm.ip += 1; match ... }
+        let started = Instant::now();
+        let until = quantum.map(|q| self.cycles.saturating_add(q));
         while !self.halted {
+            if until.is_some_and(|u| self.cycles >= u) {
+                break;
+            }
             if self.ip >= self.program.len() {
                 self.halted = true;
                 break;
@@ -177,11 +438,13 @@ impl TVM {
                     self.ip - 1, inst.addr, name, self.stack.len(), self.heap.alive_count());
             }
 
-            self.execute(&inst)?;
-        }
-
-        if self.debug {
-            eprintln!("[VM 종료] 총 {}사이클 실행", self.cycles);
+            let at = self.ip - 1;
+            self.execute(&inst).map_err(|k| self.error_at(k, at))?;
+            self.check_limits(started).map_err(|k| self.error_at(k, at))?;
+            if let Some(hook) = self.budget.as_mut() {
+                let usage = BudgetUsage { cycles: self.cycles, heap: self.heap.alive_count(), started };
+                hook(&usage).map_err(|k| self.error_at(k, at))?;
+            }
         }
         Ok(())
     }
@@ -195,18 +458,31 @@ impl TVM {
         let inst = self.program[self.ip].clone();
         self.ip += 1;
         self.cycles += 1;
-        self.execute(&inst)?;
+        let at = self.ip - 1;
+        self.execute(&inst).map_err(|k| self.error_at(k, at))?;
         Ok(!self.halted)
     }
 
+    /// 오류에 위치 붙이기 — ip의 명령어에서 소스 줄 · 이름 조회
+    fn error_at(&self, kind: VmErrorKind, ip: usize) -> VmError {
+        let inst = self.program.get(ip);
+        VmError {
+            kind,
+            ip,
+            source_line: inst.and_then(|i| i.line),
+            instruction_name: inst.and_then(|i| self.opcodes.get(&i.addr)).map_or("", |m| m.name_kr),
+        }
+    }
+
     // ── 명령어 디스패치 ──
 
-    fn execute(&mut self, inst: &Instruction) -> Result<(), VmError> {
+    fn execute(&mut self, inst: &Instruction) -> Result<(), VmErrorKind> {
         let (s, g, c) = (inst.addr.sector, inst.addr.group, inst.addr.command);
 
         match s {
             0 => self.exec_core(g, c, &inst.operands),
-            // 섹터 1~8: 미래 확장. 현재는 NOP.
+            3 => self.exec_memory(g, c),
+            // 나머지 섹터: 미래 확장. 현재는 NOP.
             _ => {
                 // GPT 명세 §9: Reserved → NOP (pop=0 push=0 effect=None)
                 Ok(())
@@ -214,9 +490,39 @@ impl TVM {
         }
     }
 
+    /// 힙 수거 — 루트: 스택 · 레지스터 · 전역 · 호출 프레임의 보관 레지스터
+    pub fn collect_garbage(&mut self) -> usize {
+        let roots = self.stack.iter()
+            .chain(self.registers.iter())
+            .chain(self.globals.values())
+            .chain(self.call_stack.iter().flat_map(|f| f.saved_registers.iter()));
+        self.heap.collect(roots)
+    }
+
+    // ── 섹터 3: 기억 (GC만 구현, 나머지 NOP) ──
+
+    fn exec_memory(&mut self, g: u8, c: u8) -> Result<(), VmErrorKind> {
+        match (g, c) {
+            (1, 0) => { // GC실행 GC_RUN
+                self.collect_garbage();
+            }
+            (1, 1) => { // GC통계 GC_STATS — 이번 실행에서 수거한 셀 수
+                self.stack.push(Value::Int(self.heap.stats().collected as i64));
+            }
+            (1, 2) => { // GC임계 GC_THRESH — pop 살아있는 셀 수 임계
+                let n = self.pop("GC임계")?;
+                let n = n.as_int().filter(|n| *n > 0)
+                    .ok_or_else(|| VmErrorKind::TypeError(format!("GC임계: 양의 정수 필요, got {}", n.type_name_kr())))?;
+                self.heap.threshold = n as usize;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     // ── 섹터 0: 코어 실행 ──
 
-    fn exec_core(&mut self, g: u8, c: u8, operands: &[Value]) -> Result<(), VmError> {
+    fn exec_core(&mut self, g: u8, c: u8, operands: &[Value]) -> Result<(), VmErrorKind> {
         match (g, c) {
             // ════════════════════════════════════════
             // G0: 논리
@@ -283,7 +589,7 @@ impl TVM {
                     (Value::Int(x), Value::Float(y)) => self.stack.push(Value::Float(*x as f64 + y)),
                     (Value::Float(x), Value::Int(y)) => self.stack.push(Value::Float(x + *y as f64)),
                     (Value::Str(x), Value::Str(y)) => self.stack.push(Value::Str(format!("{}{}", x, y))),
-                    _ => return Err(VmError::TypeError("더해: 수치/문자열 필요".into())),
+                    _ => return Err(VmErrorKind::TypeError("더해: 수치/문자열 필요".into())),
                 }
             }
             (1, 1) => { // 빼 SUB
@@ -294,7 +600,7 @@ impl TVM {
                     (Value::Float(x), Value::Float(y)) => self.stack.push(Value::Float(x - y)),
                     (Value::Int(x), Value::Float(y)) => self.stack.push(Value::Float(*x as f64 - y)),
                     (Value::Float(x), Value::Int(y)) => self.stack.push(Value::Float(x - *y as f64)),
-                    _ => return Err(VmError::TypeError("빼: 수치 필요".into())),
+                    _ => return Err(VmErrorKind::TypeError("빼: 수치 필요".into())),
                 }
             }
             (1, 2) => { // 곱해 MUL
@@ -305,27 +611,27 @@ impl TVM {
                     (Value::Float(x), Value::Float(y)) => self.stack.push(Value::Float(x * y)),
                     (Value::Int(x), Value::Float(y)) => self.stack.push(Value::Float(*x as f64 * y)),
                     (Value::Float(x), Value::Int(y)) => self.stack.push(Value::Float(x * *y as f64)),
-                    _ => return Err(VmError::TypeError("곱해: 수치 필요".into())),
+                    _ => return Err(VmErrorKind::TypeError("곱해: 수치 필요".into())),
                 }
             }
             (1, 3) => { // 나눠 DIV
                 let b = self.pop("나눠")?;
                 let a = self.pop("나눠")?;
                 match (&a, &b) {
-                    (Value::Int(_), Value::Int(0)) => return Err(VmError::DivisionByZero),
+                    (Value::Int(_), Value::Int(0)) => return Err(VmErrorKind::DivisionByZero),
                     (Value::Int(x), Value::Int(y)) => self.stack.push(Value::Int(x / y)),
-                    (Value::Float(_, ), Value::Float(y)) if *y == 0.0 => return Err(VmError::DivisionByZero),
+                    (Value::Float(_, ), Value::Float(y)) if *y == 0.0 => return Err(VmErrorKind::DivisionByZero),
                     (Value::Float(x), Value::Float(y)) => self.stack.push(Value::Float(x / y)),
-                    (Value::Int(x), Value::Float(y)) if *y == 0.0 => return Err(VmError::DivisionByZero),
+                    (Value::Int(x), Value::Float(y)) if *y == 0.0 => return Err(VmErrorKind::DivisionByZero),
                     (Value::Int(x), Value::Float(y)) => self.stack.push(Value::Float(*x as f64 / y)),
-                    (Value::Float(x), Value::Int(0)) => return Err(VmError::DivisionByZero),
+                    (Value::Float(x), Value::Int(0)) => return Err(VmErrorKind::DivisionByZero),
                     (Value::Float(x), Value::Int(y)) => self.stack.push(Value::Float(x / *y as f64)),
-                    _ => return Err(VmError::TypeError("나눠: 수치 필요".into())),
+                    _ => return Err(VmErrorKind::TypeError("나눠: 수치 필요".into())),
                 }
             }
             (1, 4) => { // 나머지 MOD
                 let (a, b) = self.pop2_int("나머지")?;
-                if b == 0 { return Err(VmError::DivisionByZero); }
+                if b == 0 { return Err(VmErrorKind::DivisionByZero); }
                 self.stack.push(Value::Int(a % b));
             }
             (1, 5) => { // 음수 NEG
@@ -333,7 +639,7 @@ impl TVM {
                 match a {
                     Value::Int(n) => self.stack.push(Value::Int(-n)),
                     Value::Float(f) => self.stack.push(Value::Float(-f)),
-                    _ => return Err(VmError::TypeError("음수: 수치 필요".into())),
+                    _ => return Err(VmErrorKind::TypeError("음수: 수치 필요".into())),
                 }
             }
             (1, 6) => { // 절댓값 ABS
@@ -341,7 +647,7 @@ impl TVM {
                 match a {
                     Value::Int(n) => self.stack.push(Value::Int(n.abs())),
                     Value::Float(f) => self.stack.push(Value::Float(f.abs())),
-                    _ => return Err(VmError::TypeError("절댓값: 수치 필요".into())),
+                    _ => return Err(VmErrorKind::TypeError("절댓값: 수치 필요".into())),
                 }
             }
             (1, 7) => { // 제곱 SQR
@@ -349,52 +655,53 @@ impl TVM {
                 match a {
                     Value::Int(n) => self.stack.push(Value::Int(n * n)),
                     Value::Float(f) => self.stack.push(Value::Float(f * f)),
-                    _ => return Err(VmError::TypeError("제곱: 수치 필요".into())),
+                    _ => return Err(VmErrorKind::TypeError("제곱: 수치 필요".into())),
                 }
             }
             (1, 8) => { // 제곱근 SQRT
                 let a = self.pop("제곱근")?;
-                let f = a.as_float().ok_or_else(|| VmError::TypeError("제곱근: 수치 필요".into()))?;
+                let f = a.as_float().ok_or_else(|| VmErrorKind::TypeError("제곱근: 수치 필요".into()))?;
                 self.stack.push(Value::Float(f.sqrt()));
             }
 
             // ════════════════════════════════════════
             // G2: 제어 (GPT Core 27 필수)
             // ════════════════════════════════════════
-            (2, 0) => { // 점프 JMP — pop addr, ip = addr
-                let target = self.pop("점프")?;
-                let addr = target.as_addr().ok_or_else(|| VmError::TypeError("점프: 주소 필요".into()))?;
-                self.ip = addr;
+            // 분기 주소는 피연산자(라벨) 또는 스택
+            (2, 0) => { // 점프 JMP — ip = addr
+                let target = self.branch_target("점프", operands)?;
+                self.ip = Self::addr_of("점프", &target)?;
             }
-            (2, 1) => { // 조건점프 JMPIF — pop cond, pop addr. if cond→P then ip=addr
-                let addr_v = self.pop("조건점프")?;
+            (2, 1) => { // 조건점프 JMPIF — pop addr, pop cond. if cond→P then ip=addr
+                let addr_v = self.branch_target("조건점프", operands)?;
                 let cond = self.pop("조건점프")?;
                 if cond.to_trit() == Trit::P {
-                    let addr = addr_v.as_addr().ok_or_else(|| VmError::TypeError("조건점프: 주소 필요".into()))?;
-                    self.ip = addr;
+                    self.ip = Self::addr_of("조건점프", &addr_v)?;
                 }
             }
-            (2, 2) => { // 호출 CALL — pop addr, push frame, ip = addr
-                let target = self.pop("호출")?;
-                let addr = target.as_addr().ok_or_else(|| VmError::TypeError("호출: 주소 필요".into()))?;
+            (2, 2) => { // 호출 CALL — push frame (호출자 레지스터 보관), ip = addr
+                let target = self.branch_target("호출", operands)?;
+                let addr = Self::addr_of("호출", &target)?;
+                if self.call_stack.len() >= MAX_CALL_DEPTH {
+                    return Err(VmErrorKind::StackOverflow(self.call_stack.len()));
+                }
+                let saved_registers = std::mem::replace(&mut self.registers, std::array::from_fn(|_| Value::Nil));
                 self.call_stack.push(CallFrame {
                     return_ip: self.ip,
                     base_sp: self.stack.len(),
+                    saved_registers,
                 });
                 self.ip = addr;
             }
             (2, 3) => { // 반환 RET — pop frame, ip = return_ip
-                if let Some(frame) = self.call_stack.pop() {
-                    self.ip = frame.return_ip;
-                }
                 // 호출 스택 비었으면 무시 (최상위)
+                self.return_from_frame();
             }
-            (2, 4) => { // 반복 LOOP — pop cond, pop addr. if cond!=T then ip=addr
-                let addr_v = self.pop("반복")?;
+            (2, 4) => { // 반복 LOOP — pop addr, pop cond. if cond!=T then ip=addr
+                let addr_v = self.branch_target("반복", operands)?;
                 let cond = self.pop("반복")?;
                 if cond.to_trit() != Trit::T {
-                    let addr = addr_v.as_addr().ok_or_else(|| VmError::TypeError("반복: 주소 필요".into()))?;
-                    self.ip = addr;
+                    self.ip = Self::addr_of("반복", &addr_v)?;
                 }
             }
             (2, 5) => { /* 멈춰 BREAK — 추후 루프 컨텍스트와 연동 */ }
@@ -435,7 +742,17 @@ impl TVM {
             }
             (3, 5) => { // 보여줘 PRINT
                 let a = self.pop("보여줘")?;
-                println!("{}", a);
+                let line = format!("{}", a);
+                self.output_bytes += line.len() + 1;
+                if let Some(max) = self.limits.max_output_bytes {
+                    if self.output_bytes > max {
+                        return Err(VmErrorKind::LimitExceeded(LimitKind::Output));
+                    }
+                }
+                match &mut self.captured {
+                    Some(buf) => buf.push(line),
+                    None => println!("{}", line),
+                }
             }
             (3, 6) => { // 입력해 INPUT
                 print!("입력> ");
@@ -451,28 +768,50 @@ impl TVM {
                     self.stack.push(Value::Str(t));
                 }
             }
+            // 이름은 피연산자(한선어 컴파일러의 슬롯 번호 포함) 또는 스택
             (3, 7) => { // 저장해 STORE — pop value, pop name → globals
                 let val = self.pop("저장해")?;
-                let name = self.pop("저장해")?;
-                if let Value::Str(key) = name {
-                    self.globals.insert(key, val);
-                } else {
-                    return Err(VmError::TypeError("저장해: 이름은 문자열".into()));
-                }
+                let key = self.global_key("저장해", operands)?;
+                self.globals.insert(key, val);
             }
             (3, 8) => { // 불러와 LOAD — pop name → push globals[name]
-                let name = self.pop("불러와")?;
-                if let Value::Str(key) = name {
-                    let val = self.globals.get(&key).cloned().unwrap_or(Value::Nil);
-                    self.stack.push(val);
-                } else {
-                    return Err(VmError::TypeError("불러와: 이름은 문자열".into()));
-                }
+                let key = self.global_key("불러와", operands)?;
+                let val = self.globals.get(&key).cloned().unwrap_or(Value::Nil);
+                self.stack.push(val);
             }
 
             // ════════════════════════════════════════
             // G4: 함수
             // ════════════════════════════════════════
+            (4, 2) => { // 돌려줘 RETURN — pop 값, 호출 이후 임시값 정리 후 값만 남기고 반환
+                let val = self.pop("돌려줘")?;
+                if let Some(frame) = self.call_stack.last() {
+                    let base = frame.base_sp.min(self.stack.len());
+                    self.stack.truncate(base);
+                }
+                self.stack.push(val);
+                if !self.return_from_frame() {
+                    self.halted = true; // 최상위 돌려줘 = 값을 남기고 종료
+                }
+            }
+            (4, 3) => { // 삼분기 BR3 — pop trit, P/O/T 대상 중 하나로 (피연산자 3개 또는 스택: 조건 P O T 순 push)
+                let targets = match operands {
+                    [p, o, t] => [p.clone(), o.clone(), t.clone()],
+                    [] => {
+                        let t = self.pop("삼분기")?;
+                        let o = self.pop("삼분기")?;
+                        [self.pop("삼분기")?, o, t]
+                    }
+                    _ => return Err(VmErrorKind::TypeError(format!("삼분기: 대상 3개 필요, got {}", operands.len()))),
+                };
+                let cond = self.pop("삼분기")?;
+                let target = match cond.to_trit() {
+                    Trit::P => &targets[0],
+                    Trit::O => &targets[1],
+                    Trit::T => &targets[2],
+                };
+                self.ip = Self::addr_of("삼분기", target)?;
+            }
             (4, 8) => { /* NOP 없다 */ }
 
             // ════════════════════════════════════════
@@ -516,11 +855,11 @@ impl TVM {
             // ════════════════════════════════════════
             (6, 2) => { // 던져 THROW
                 let msg = self.pop("던져")?;
-                return Err(VmError::Custom(format!("{}", msg)));
+                return Err(VmErrorKind::Custom(format!("{}", msg)));
             }
             (6, 6) => { // 오류 ERROR
                 let msg = self.pop("오류")?;
-                return Err(VmError::Custom(format!("{}", msg)));
+                return Err(VmErrorKind::Custom(format!("{}", msg)));
             }
             (6, 7) => { // 기록 LOG
                 let msg = self.pop("기록")?;
@@ -530,44 +869,64 @@ impl TVM {
             // ════════════════════════════════════════
             // G7: 컬렉션
             // ════════════════════════════════════════
-            (7, 2) => { // 길이 LEN
+            (7, 2) => { // 길이 LEN — 문자열은 자소 묶음 수
                 let a = self.pop("길이")?;
-                let len = match &a {
-                    Value::Str(s) => s.chars().count() as i64,
-                    Value::Array(arr) => arr.len() as i64,
-                    _ => 0,
+                self.stack.push(Value::Int(a.length().unwrap_or(0)));
+            }
+            (7, 3) => { // 인덱스 INDEX — pop i, pop 컬렉션 → push 원소 (음수는 끝에서부터)
+                let i = self.pop("인덱스")?;
+                let coll = self.pop("인덱스")?;
+                let i = i.as_int().ok_or_else(|| VmErrorKind::TypeError("인덱스: 정수 필요".into()))?;
+                let len = coll.length()
+                    .ok_or_else(|| VmErrorKind::TypeError(format!("인덱스: 문자열/배열 필요, got {}", coll.type_name_kr())))?;
+                let val = coll.index(i)
+                    .ok_or_else(|| VmErrorKind::Custom(format!("인덱스 범위 초과: {} (길이 {})", i, len)))?;
+                self.stack.push(val);
+            }
+            (7, 4) => { // 슬라이스 SLICE — pop 끝, pop 시작, pop 컬렉션 → push [시작, 끝)
+                let end = self.pop("슬라이스")?;
+                let start = self.pop("슬라이스")?;
+                let coll = self.pop("슬라이스")?;
+                let (start, end) = match (start.as_int(), end.as_int()) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return Err(VmErrorKind::TypeError("슬라이스: 정수 범위 필요".into())),
                 };
-                self.stack.push(Value::Int(len));
+                let val = coll.slice(start, end)
+                    .ok_or_else(|| VmErrorKind::TypeError(format!("슬라이스: 문자열/배열 필요, got {}", coll.type_name_kr())))?;
+                self.stack.push(val);
             }
 
             // ════════════════════════════════════════
             // G8: 접근/힙/레지스터
             // ════════════════════════════════════════
-            (8, 3) => { // 할당 ALLOC — pop value → heap, push addr
+            (8, 3) => { // 할당 ALLOC — pop value → heap, push addr (임계면 먼저 수거 — 값은 아직 스택에)
+                if self.heap.needs_collect() {
+                    self.collect_garbage();
+                }
                 let val = self.pop("할당")?;
                 let addr = self.heap.alloc(val);
                 self.stack.push(Value::Addr(addr));
             }
             (8, 4) => { // 해제 FREE — pop addr → heap.free
                 let a = self.pop("해제")?;
-                let addr = a.as_addr().ok_or_else(|| VmError::TypeError("해제: 주소 필요".into()))?;
+                let addr = a.as_addr().ok_or_else(|| VmErrorKind::TypeError("해제: 주소 필요".into()))?;
                 if !self.heap.free(addr) {
-                    return Err(VmError::HeapError(format!("해제 실패: &{}", addr)));
+                    return Err(VmErrorKind::HeapError(format!("해제 실패: &{}", addr)));
                 }
             }
             (8, 5) => { // 읽어 HREAD — pop addr → push heap[addr]
                 let a = self.pop("읽어")?;
-                let addr = a.as_addr().ok_or_else(|| VmError::TypeError("읽어: 주소 필요".into()))?;
+                let addr = a.as_addr().ok_or_else(|| VmErrorKind::TypeError("읽어: 주소 필요".into()))?;
                 let val = self.heap.get(addr).cloned()
-                    .ok_or_else(|| VmError::HeapError(format!("읽기 실패: &{}", addr)))?;
+                    .ok_or_else(|| VmErrorKind::HeapError(format!("읽기 실패: &{}", addr)))?;
                 self.stack.push(val);
             }
             (8, 6) => { // 써 HWRITE — pop value, pop addr → heap[addr] = value
                 let val = self.pop("써")?;
                 let a = self.pop("써")?;
-                let addr = a.as_addr().ok_or_else(|| VmError::TypeError("써: 주소 필요".into()))?;
+                let addr = a.as_addr().ok_or_else(|| VmErrorKind::TypeError("써: 주소 필요".into()))?;
                 if !self.heap.set(addr, val) {
-                    return Err(VmError::HeapError(format!("쓰기 실패: &{}", addr)));
+                    return Err(VmErrorKind::HeapError(format!("쓰기 실패: &{}", addr)));
                 }
             }
             (8, 7) => { // 레지읽기 RLOAD — operands[0]=레지스터번호, push registers[n]
@@ -597,28 +956,32 @@ impl TVM {
 
     // ── 디버그/덤프 ──
 
-    pub fn dump_stack(&self) {
-        println!("╔══ 스택 (깊이: {}) ══╗", self.stack.len());
+    pub fn stack_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("╔══ 스택 (깊이: {}) ══╗", self.stack.len())];
         for (i, v) in self.stack.iter().enumerate().rev() {
-            println!("║ [{:3}] {:30} ({}) ║", i, format!("{}", v), v.type_name_kr());
+            lines.push(format!("║ [{:3}] {:30} ({}) ║", i, format!("{}", v), v.type_name_kr()));
         }
-        println!("╚══════════════════════════════╝");
+        lines.push("╚══════════════════════════════╝".into());
+        lines
     }
 
-    pub fn dump_registers(&self) {
-        println!("╔══ 레지스터 (R0..R8) ══╗");
+    pub fn register_lines(&self) -> Vec<String> {
+        let mut lines = vec!["╔══ 레지스터 (R0..R8) ══╗".to_string()];
         for (i, v) in self.registers.iter().enumerate() {
             if !matches!(v, Value::Nil) {
-                println!("║ R{}: {} ({}) ║", i, v, v.type_name_kr());
+                lines.push(format!("║ R{}: {} ({}) ║", i, v, v.type_name_kr()));
             }
         }
-        println!("╚════════════════════════╝");
+        lines.push("╚════════════════════════╝".into());
+        lines
     }
 
-    pub fn dump_all(&self) {
-        self.dump_stack();
-        self.dump_registers();
-        self.heap.dump();
-        println!("IP: {} | 사이클: {} | 종료: {}", self.ip, self.cycles, self.halted);
+    /// 스택 + 레지스터 + 힙 + 실행 상태
+    pub fn dump_lines(&self) -> Vec<String> {
+        let mut lines = self.stack_lines();
+        lines.extend(self.register_lines());
+        lines.extend(self.heap.dump_lines());
+        lines.push(format!("IP: {} | 사이클: {} | 종료: {}", self.ip, self.cycles, self.halted));
+        lines
     }
 }
@@ -9,6 +9,8 @@
 ///!   Version: 1
 ///!   Sections: Type, Import, Function, Memory, Export, Code
 ///!
+///! validate(): 생성한 바이너리를 다시 읽어 구조 · 타입 검증
+///!
 ///! GPT Spec §3: 변환기 구조
 ///! GPT Spec §7: 실행 흐름
 
@@ -379,6 +381,10 @@ impl WasmBuilder {
                 out.push(0x42); // i64.const
                 out.extend_from_slice(&encode_i64_leb128(*v));
             }
+            IrOp::ConstI32(v) => {
+                out.push(0x41); // i32.const
+                out.extend_from_slice(&encode_i32_leb128(*v));
+            }
             IrOp::ConstF64(v) => {
                 out.push(0x44); // f64.const
                 out.extend_from_slice(&v.to_le_bytes());
@@ -432,6 +438,14 @@ impl WasmBuilder {
             IrOp::Le => { out.push(0x57); } // i64.le_s
             IrOp::Ge => { out.push(0x59); } // i64.ge_s
             IrOp::Eqz => { out.push(0x50); } // i64.eqz
+            IrOp::I32Add => { out.push(0x6A); } // i32.add
+            IrOp::I32Sub => { out.push(0x6B); } // i32.sub
+            IrOp::I32LtS => { out.push(0x48); } // i32.lt_s
+            IrOp::I32LtU => { out.push(0x49); } // i32.lt_u
+            IrOp::I32LeU => { out.push(0x4D); } // i32.le_u
+            IrOp::I32GtU => { out.push(0x4B); } // i32.gt_u
+            IrOp::I32GeU => { out.push(0x4F); } // i32.ge_u
+            IrOp::Select => { out.push(0x1B); } // select
 
             // ── 제어흐름 ──
             IrOp::Block(_) => {
@@ -450,6 +464,19 @@ impl WasmBuilder {
                 out.push(0x0D); // br_if
                 out.extend_from_slice(&encode_u32_leb128(*label));
             }
+            IrOp::BrTable(labels, default) => {
+                out.push(0x0E); // br_table
+                out.extend_from_slice(&encode_u32_leb128(labels.len() as u32));
+                for label in labels {
+                    out.extend_from_slice(&encode_u32_leb128(*label));
+                }
+                out.extend_from_slice(&encode_u32_leb128(*default));
+            }
+            IrOp::If(result) => {
+                out.push(0x04); // if
+                out.push(result.as_ref().map(ir_type_to_wasm).unwrap_or(WASM_VOID));
+            }
+            IrOp::Else => { out.push(0x05); }
             IrOp::Call(idx) => {
                 out.push(0x10); // call
                 out.extend_from_slice(&encode_u32_leb128(*idx));
@@ -459,6 +486,7 @@ impl WasmBuilder {
             IrOp::Halt => {
                 out.push(0x00); // unreachable
             }
+            IrOp::Unreachable => { out.push(0x00); }
 
             // ── 메모리 ──
             IrOp::MemLoad(offset) => {
@@ -471,6 +499,16 @@ impl WasmBuilder {
                 out.push(0x03); // alignment
                 out.extend_from_slice(&encode_u32_leb128(*offset));
             }
+            IrOp::MemStore8(offset) => {
+                out.push(0x3C); // i64.store8
+                out.push(0x00); // alignment (1 byte)
+                out.extend_from_slice(&encode_u32_leb128(*offset));
+            }
+            IrOp::MemStoreI32(offset) => {
+                out.push(0x36); // i32.store
+                out.push(0x02); // alignment (4 bytes)
+                out.extend_from_slice(&encode_u32_leb128(*offset));
+            }
             IrOp::MemGrow => {
                 out.push(0x40); // memory.grow
                 out.push(0x00); // memory index
@@ -498,6 +536,8 @@ impl WasmBuilder {
             IrOp::I64ExtendI32 => { out.push(0xAC); } // i64.extend_i32_s
             IrOp::F64ConvertI64 => { out.push(0xB9); } // f64.convert_i64_s
             IrOp::I64TruncF64 => { out.push(0xB0); }   // i64.trunc_f64_s
+            IrOp::I32WrapI64 => { out.push(0xA7); }    // i32.wrap_i64
+            IrOp::F64Sqrt => { out.push(0x9F); }       // f64.sqrt
 
             // ── IO ──
             IrOp::CallImport(idx) => {
@@ -536,9 +576,16 @@ impl WasmBuilder {
                 // need swap here
                 out.push(0x7D); // i64.sub
             }
-            IrOp::TritBranch => {
-                // 3진 분기: 컴파일러가 if-chain으로 변환
-                out.push(0x01);
+            IrOp::TritBranch(p, o, t) => {
+                // 3진 분기: trit+1 → br_table [T, O] 기본 P (-1→0, 0→1, 그 외→P)
+                out.push(0x42); out.push(0x01); // i64.const 1
+                out.push(0x7C); // i64.add
+                out.push(0xA7); // i32.wrap_i64
+                out.push(0x0E); // br_table
+                out.extend_from_slice(&encode_u32_leb128(2));
+                out.extend_from_slice(&encode_u32_leb128(*t));
+                out.extend_from_slice(&encode_u32_leb128(*o));
+                out.extend_from_slice(&encode_u32_leb128(*p));
             }
 
             // ── NOP ──
@@ -547,6 +594,958 @@ impl WasmBuilder {
     }
 }
 
+// ─────────────────────────────────────────────
+// 검증 — 생성한 바이너리 자체 점검 (MVP)
+// ─────────────────────────────────────────────
+//
+// 섹션 순서 · LEB128 인코딩 · 섹션/본문 크기 · 인덱스 범위를 확인하고,
+// 함수 본문은 피연산자 스택으로 타입을 따라간다 (명세 부록의 검증 알고리즘).
+
+/// WASM 검증 오류 — 바이트 오프셋과 원인
+#[derive(Debug, Clone, PartialEq)]
+pub struct WasmValidationError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for WasmValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "오프셋 0x{:X}: {}", self.offset, self.message)
+    }
+}
+
+type Checked<T> = Result<T, WasmValidationError>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValType { I32, I64, F32, F64 }
+
+impl ValType {
+    fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            WASM_I32 => Some(ValType::I32),
+            WASM_I64 => Some(ValType::I64),
+            0x7D => Some(ValType::F32),
+            WASM_F64 => Some(ValType::F64),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ValType::I32 => "i32",
+            ValType::I64 => "i64",
+            ValType::F32 => "f32",
+            ValType::F64 => "f64",
+        }
+    }
+}
+
+/// 바이트 읽기 — 모든 오류는 현재 위치를 기록
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn err(&self, message: impl Into<String>) -> WasmValidationError {
+        WasmValidationError { offset: self.pos, message: message.into() }
+    }
+
+    fn eof(&self) -> bool {
+        self.pos >= self.end
+    }
+
+    fn byte(&mut self) -> Checked<u8> {
+        if self.eof() {
+            return Err(self.err("예상치 못한 끝"));
+        }
+        self.pos += 1;
+        Ok(self.bytes[self.pos - 1])
+    }
+
+    fn skip(&mut self, n: usize) -> Checked<()> {
+        if self.end - self.pos < n {
+            return Err(self.err(format!("{}바이트 필요, {}바이트 남음", n, self.end - self.pos)));
+        }
+        self.pos += n;
+        Ok(())
+    }
+
+    /// LEB128 — bits 폭을 넘는 바이트 수나 남는 비트가 있으면 오류
+    fn leb(&mut self, bits: u32, signed: bool) -> Checked<i64> {
+        let start = self.pos;
+        let max_bytes = bits.div_ceil(7);
+        let mut result: i64 = 0;
+        for i in 0..max_bytes {
+            let b = self.byte()?;
+            result |= ((b & 0x7F) as i64) << (7 * i);
+            if i == max_bytes - 1 {
+                let used = bits - 7 * i;
+                let rest = (b & 0x7F) >> if signed { used - 1 } else { used };
+                let all_ones = (1u8 << (7 - used + signed as u32)) - 1;
+                if b & 0x80 != 0 {
+                    return Err(WasmValidationError { offset: start, message: format!("LEB128이 {}바이트를 넘음", max_bytes) });
+                }
+                if rest != 0 && !(signed && rest == all_ones) {
+                    return Err(WasmValidationError { offset: start, message: format!("LEB128 값이 {}비트를 넘음", bits) });
+                }
+            }
+            if b & 0x80 == 0 {
+                let shift = 7 * (i + 1);
+                if signed && shift < 64 && b & 0x40 != 0 {
+                    result |= -1i64 << shift;
+                }
+                return Ok(result);
+            }
+        }
+        unreachable!()
+    }
+
+    fn u32(&mut self) -> Checked<u32> {
+        Ok(self.leb(32, false)? as u32)
+    }
+
+    fn sub(&mut self, len: usize, what: &str) -> Checked<Reader<'a>> {
+        if self.end - self.pos < len {
+            return Err(self.err(format!("{} 크기 {}가 남은 {}바이트를 넘음", what, len, self.end - self.pos)));
+        }
+        let r = Reader { bytes: self.bytes, pos: self.pos, end: self.pos + len };
+        self.pos += len;
+        Ok(r)
+    }
+
+    fn name(&mut self) -> Checked<String> {
+        let len = self.u32()? as usize;
+        let at = self.pos;
+        self.skip(len)?;
+        String::from_utf8(self.bytes[at..self.pos].to_vec())
+            .map_err(|_| WasmValidationError { offset: at, message: "이름이 UTF-8이 아님".into() })
+    }
+
+    fn val_type(&mut self) -> Checked<ValType> {
+        let b = self.byte()?;
+        ValType::from_byte(b).ok_or_else(|| WasmValidationError { offset: self.pos - 1, message: format!("알 수 없는 값 타입 0x{:02X}", b) })
+    }
+
+    fn val_types(&mut self) -> Checked<Vec<ValType>> {
+        let n = self.u32()?;
+        (0..n).map(|_| self.val_type()).collect()
+    }
+
+    fn limits(&mut self, max_pages: u32) -> Checked<()> {
+        let flag = self.byte()?;
+        let min = self.u32()?;
+        let max = match flag {
+            0x00 => None,
+            0x01 => Some(self.u32()?),
+            _ => return Err(self.err(format!("알 수 없는 limits 플래그 0x{:02X}", flag))),
+        };
+        if min > max_pages || max.is_some_and(|m| m > max_pages) {
+            return Err(self.err(format!("limits가 {}를 넘음", max_pages)));
+        }
+        if max.is_some_and(|m| m < min) {
+            return Err(self.err("limits: max < min"));
+        }
+        Ok(())
+    }
+
+    /// 섹션 끝까지 다 읽었는가
+    fn finish(&self, what: &str) -> Checked<()> {
+        if self.pos != self.end {
+            return Err(self.err(format!("{} 크기 불일치: {}바이트 남음", what, self.end - self.pos)));
+        }
+        Ok(())
+    }
+}
+
+/// 섹션 이름 · 순서 (data count는 element와 code 사이)
+fn section_info(id: u8) -> Option<(&'static str, u8)> {
+    Some(match id {
+        1 => ("type", 1),
+        2 => ("import", 2),
+        3 => ("function", 3),
+        4 => ("table", 4),
+        5 => ("memory", 5),
+        6 => ("global", 6),
+        7 => ("export", 7),
+        8 => ("start", 8),
+        9 => ("element", 9),
+        12 => ("data count", 10),
+        10 => ("code", 11),
+        11 => ("data", 12),
+        _ => return None,
+    })
+}
+
+const MAX_PAGES: u32 = 65536;
+const MAX_LOCALS: u64 = 50_000;
+
+/// 검증 중 모은 모듈 정보 (인덱스 공간)
+#[derive(Default)]
+struct ModuleInfo {
+    types: Vec<(Vec<ValType>, Vec<ValType>)>,
+    funcs: Vec<u32>,
+    imported_funcs: usize,
+    globals: Vec<(ValType, bool)>,
+    imported_globals: usize,
+    memories: u32,
+    tables: u32,
+    declared_bodies: Option<u32>,
+    code_bodies: Option<u32>,
+}
+
+impl ModuleInfo {
+    fn type_index(&self, r: &Reader, idx: u32) -> Checked<u32> {
+        if (idx as usize) < self.types.len() { Ok(idx) } else {
+            Err(r.err(format!("타입 인덱스 {} 범위 밖 (타입 {}개)", idx, self.types.len())))
+        }
+    }
+
+    fn func_type(&self, r: &Reader, idx: u32) -> Checked<&(Vec<ValType>, Vec<ValType>)> {
+        let ty = self.funcs.get(idx as usize)
+            .ok_or_else(|| r.err(format!("함수 인덱스 {} 범위 밖 (함수 {}개)", idx, self.funcs.len())))?;
+        Ok(&self.types[*ty as usize])
+    }
+}
+
+/// WASM 바이너리 검증 — 첫 오류에서 멈춘다
+pub fn validate(bytes: &[u8]) -> Result<(), WasmValidationError> {
+    let mut r = Reader { bytes, pos: 0, end: bytes.len() };
+    if bytes.len() < 8 || &bytes[0..4] != b"\0asm" {
+        return Err(r.err("매직 \\0asm 없음"));
+    }
+    if bytes[4..8] != [1, 0, 0, 0] {
+        r.pos = 4;
+        return Err(r.err("지원하지 않는 버전 (1만 허용)"));
+    }
+    r.pos = 8;
+
+    let mut m = ModuleInfo::default();
+    let mut last: Option<(&str, u8)> = None;
+    while !r.eof() {
+        let at = r.pos;
+        let id = r.byte()?;
+        let size = r.u32()? as usize;
+        let mut s = r.sub(size, "섹션")?;
+        if id == 0 {
+            s.name()?; // custom — 이름만 확인
+            continue;
+        }
+        let (name, rank) = section_info(id)
+            .ok_or_else(|| WasmValidationError { offset: at, message: format!("알 수 없는 섹션 id {}", id) })?;
+        if let Some((prev, prev_rank)) = last {
+            if rank <= prev_rank {
+                return Err(WasmValidationError { offset: at, message: format!("섹션 순서 위반: {} 섹션이 {} 섹션 뒤에 옴", name, prev) });
+            }
+        }
+        last = Some((name, rank));
+        match id {
+            1 => type_section(&mut s, &mut m)?,
+            2 => import_section(&mut s, &mut m)?,
+            3 => {
+                let n = s.u32()?;
+                for _ in 0..n {
+                    let idx = s.u32()?;
+                    m.funcs.push(m.type_index(&s, idx)?);
+                }
+                m.declared_bodies = Some(n);
+            }
+            4 => {
+                let n = s.u32()?;
+                for _ in 0..n {
+                    s.byte()?;
+                    s.limits(u32::MAX)?;
+                }
+                m.tables += n;
+            }
+            5 => {
+                let n = s.u32()?;
+                for _ in 0..n {
+                    s.limits(MAX_PAGES)?;
+                }
+                m.memories += n;
+                if m.memories > 1 {
+                    return Err(s.err("메모리는 하나만 허용"));
+                }
+            }
+            6 => global_section(&mut s, &mut m)?,
+            7 => export_section(&mut s, &m)?,
+            8 => {
+                let idx = s.u32()?;
+                let (params, results) = m.func_type(&s, idx)?;
+                if !params.is_empty() || !results.is_empty() {
+                    return Err(s.err(format!("start 함수 {}는 [] → [] 여야 함", idx)));
+                }
+            }
+            10 => code_section(&mut s, &mut m)?,
+            _ => s.pos = s.end, // element · data · data count — 내용은 보지 않음
+        }
+        s.finish(&format!("{} 섹션", name))?;
+    }
+
+    let declared = m.declared_bodies.unwrap_or(0);
+    let bodies = m.code_bodies.unwrap_or(0);
+    if declared != bodies {
+        return Err(r.err(format!("function 섹션 {}개 ≠ code 본문 {}개", declared, bodies)));
+    }
+    Ok(())
+}
+
+fn type_section(s: &mut Reader, m: &mut ModuleInfo) -> Checked<()> {
+    let n = s.u32()?;
+    for _ in 0..n {
+        let form = s.byte()?;
+        if form != 0x60 {
+            return Err(WasmValidationError { offset: s.pos - 1, message: format!("functype 0x60 필요, 0x{:02X} 발견", form) });
+        }
+        let params = s.val_types()?;
+        let results = s.val_types()?;
+        m.types.push((params, results));
+    }
+    Ok(())
+}
+
+fn import_section(s: &mut Reader, m: &mut ModuleInfo) -> Checked<()> {
+    let n = s.u32()?;
+    for _ in 0..n {
+        s.name()?;
+        s.name()?;
+        match s.byte()? {
+            0x00 => {
+                let idx = s.u32()?;
+                m.funcs.push(m.type_index(s, idx)?);
+                m.imported_funcs += 1;
+            }
+            0x01 => { s.byte()?; s.limits(u32::MAX)?; m.tables += 1; }
+            0x02 => { s.limits(MAX_PAGES)?; m.memories += 1; }
+            0x03 => {
+                let t = s.val_type()?;
+                let mutable = s.byte()? == 0x01;
+                m.globals.push((t, mutable));
+                m.imported_globals += 1;
+            }
+            k => return Err(WasmValidationError { offset: s.pos - 1, message: format!("알 수 없는 import 종류 0x{:02X}", k) }),
+        }
+    }
+    Ok(())
+}
+
+fn global_section(s: &mut Reader, m: &mut ModuleInfo) -> Checked<()> {
+    let n = s.u32()?;
+    for _ in 0..n {
+        let t = s.val_type()?;
+        let mutable = match s.byte()? {
+            0x00 => false,
+            0x01 => true,
+            b => return Err(WasmValidationError { offset: s.pos - 1, message: format!("전역 가변성 플래그 0x{:02X}", b) }),
+        };
+        // 초기값: 상수 하나 또는 import 전역 읽기 + end
+        let at = s.pos;
+        let init = match s.byte()? {
+            0x41 => { s.leb(32, true)?; ValType::I32 }
+            0x42 => { s.leb(64, true)?; ValType::I64 }
+            0x43 => { s.skip(4)?; ValType::F32 }
+            0x44 => { s.skip(8)?; ValType::F64 }
+            0x23 => {
+                let idx = s.u32()? as usize;
+                if idx >= m.imported_globals {
+                    return Err(s.err(format!("전역 초기값은 import 전역만 읽을 수 있음 ({})", idx)));
+                }
+                m.globals[idx].0
+            }
+            op => return Err(WasmValidationError { offset: at, message: format!("전역 초기값에 상수식이 아닌 0x{:02X}", op) }),
+        };
+        if init != t {
+            return Err(WasmValidationError { offset: at, message: format!("전역 초기값 타입 불일치: {} 필요, {} 발견", t.name(), init.name()) });
+        }
+        if s.byte()? != 0x0B {
+            return Err(WasmValidationError { offset: s.pos - 1, message: "전역 초기값이 end로 끝나지 않음".into() });
+        }
+        m.globals.push((t, mutable));
+    }
+    Ok(())
+}
+
+fn export_section(s: &mut Reader, m: &ModuleInfo) -> Checked<()> {
+    let n = s.u32()?;
+    let mut names = std::collections::HashSet::new();
+    for _ in 0..n {
+        let at = s.pos;
+        let name = s.name()?;
+        let kind = s.byte()?;
+        let idx = s.u32()?;
+        let (what, count) = match kind {
+            0x00 => ("함수", m.funcs.len()),
+            0x01 => ("테이블", m.tables as usize),
+            0x02 => ("메모리", m.memories as usize),
+            0x03 => ("전역", m.globals.len()),
+            _ => return Err(s.err(format!("알 수 없는 export 종류 0x{:02X}", kind))),
+        };
+        if idx as usize >= count {
+            return Err(s.err(format!("export \"{}\": {} 인덱스 {} 범위 밖", name, what, idx)));
+        }
+        if !names.insert(name.clone()) {
+            return Err(WasmValidationError { offset: at, message: format!("export 이름 중복: \"{}\"", name) });
+        }
+    }
+    Ok(())
+}
+
+fn code_section(s: &mut Reader, m: &mut ModuleInfo) -> Checked<()> {
+    let n = s.u32()?;
+    let declared = m.declared_bodies.unwrap_or(0);
+    if n != declared {
+        return Err(s.err(format!("code 본문 {}개 ≠ function 섹션 {}개", n, declared)));
+    }
+    for i in 0..n as usize {
+        let size = s.u32()? as usize;
+        let mut body = s.sub(size, &format!("함수 {} 본문", i))?;
+        let (params, results) = m.types[m.funcs[m.imported_funcs + i] as usize].clone();
+        let mut locals = params;
+        let groups = body.u32()?;
+        let mut total = locals.len() as u64;
+        for _ in 0..groups {
+            let count = body.u32()?;
+            total += count as u64;
+            if total > MAX_LOCALS {
+                return Err(body.err(format!("함수 {} 로컬 수 {} 초과", i, MAX_LOCALS)));
+            }
+            let t = body.val_type()?;
+            locals.extend(std::iter::repeat_n(t, count as usize));
+        }
+        FuncChecker { module: m, locals, results: results.clone(), vals: Vec::new(), ctrls: Vec::new() }
+            .check(&mut body)
+            .map_err(|e| WasmValidationError { message: format!("함수 {}: {}", m.imported_funcs + i, e.message), ..e })?;
+    }
+    m.code_bodies = Some(n);
+    Ok(())
+}
+
+/// 제어 프레임 — block/loop/if/else, 바깥은 함수 자체
+struct Ctrl {
+    opcode: u8,
+    results: Vec<ValType>,
+    height: usize,
+    unreachable: bool,
+}
+
+/// 함수 본문 타입 검사 (None = 도달 불가 구간의 임의 타입)
+struct FuncChecker<'m> {
+    module: &'m ModuleInfo,
+    locals: Vec<ValType>,
+    results: Vec<ValType>,
+    vals: Vec<Option<ValType>>,
+    ctrls: Vec<Ctrl>,
+}
+
+impl FuncChecker<'_> {
+    fn pop_val(&mut self, r: &Reader) -> Checked<Option<ValType>> {
+        let ctrl = self.ctrls.last().expect("제어 프레임");
+        if self.vals.len() == ctrl.height {
+            if ctrl.unreachable {
+                return Ok(None);
+            }
+            return Err(r.err("피연산자 스택 부족"));
+        }
+        Ok(self.vals.pop().flatten())
+    }
+
+    fn pop_expect(&mut self, r: &Reader, want: ValType) -> Checked<()> {
+        match self.pop_val(r)? {
+            Some(got) if got != want => Err(r.err(format!("타입 불일치: {} 필요, {} 발견", want.name(), got.name()))),
+            _ => Ok(()),
+        }
+    }
+
+    fn pop_all(&mut self, r: &Reader, types: &[ValType]) -> Checked<()> {
+        for t in types.iter().rev() {
+            self.pop_expect(r, *t)?;
+        }
+        Ok(())
+    }
+
+    fn push_ctrl(&mut self, opcode: u8, results: Vec<ValType>) {
+        self.ctrls.push(Ctrl { opcode, results, height: self.vals.len(), unreachable: false });
+    }
+
+    fn pop_ctrl(&mut self, r: &Reader) -> Checked<Ctrl> {
+        let results = self.ctrls.last().expect("제어 프레임").results.clone();
+        self.pop_all(r, &results)?;
+        let ctrl = self.ctrls.pop().expect("제어 프레임");
+        if self.vals.len() != ctrl.height {
+            return Err(r.err(format!("블록 끝에 값 {}개가 남음", self.vals.len() - ctrl.height)));
+        }
+        Ok(ctrl)
+    }
+
+    /// br 대상 label의 타입 (loop는 시작으로 가므로 없음)
+    fn label_types(&self, r: &Reader, depth: u32) -> Checked<Vec<ValType>> {
+        let ctrl = self.ctrls.len().checked_sub(depth as usize + 1).map(|i| &self.ctrls[i])
+            .ok_or_else(|| r.err(format!("label 깊이 {} 범위 밖 (열린 블록 {}개)", depth, self.ctrls.len())))?;
+        Ok(if ctrl.opcode == 0x03 { Vec::new() } else { ctrl.results.clone() })
+    }
+
+    fn set_unreachable(&mut self) {
+        let ctrl = self.ctrls.last_mut().expect("제어 프레임");
+        self.vals.truncate(ctrl.height);
+        ctrl.unreachable = true;
+    }
+
+    fn block_type(r: &mut Reader) -> Checked<Vec<ValType>> {
+        let b = r.byte()?;
+        if b == WASM_VOID {
+            return Ok(Vec::new());
+        }
+        ValType::from_byte(b).map(|t| vec![t])
+            .ok_or_else(|| WasmValidationError { offset: r.pos - 1, message: format!("지원하지 않는 블록 타입 0x{:02X}", b) })
+    }
+
+    fn memarg(&self, r: &mut Reader, width: u32) -> Checked<()> {
+        let align = r.u32()?;
+        r.u32()?; // offset
+        if self.module.memories == 0 {
+            return Err(r.err("메모리 없이 메모리 접근"));
+        }
+        if align >= 32 || 1u32 << align > width {
+            return Err(r.err(format!("정렬 2^{}가 접근 폭 {}바이트보다 큼", align, width)));
+        }
+        Ok(())
+    }
+
+    fn check(mut self, r: &mut Reader) -> Checked<()> {
+        use ValType::*;
+        self.push_ctrl(0x02, self.results.clone());
+        while !self.ctrls.is_empty() {
+            if r.eof() {
+                return Err(r.err("함수 본문이 end 없이 끝남"));
+            }
+            let at = r.pos;
+            let op = r.byte()?;
+            let result = (|| -> Checked<()> {
+                match op {
+                    0x00 => self.set_unreachable(),
+                    0x01 => {}
+                    0x02 | 0x03 => {
+                        let results = Self::block_type(r)?;
+                        self.push_ctrl(op, results);
+                    }
+                    0x04 => {
+                        let results = Self::block_type(r)?;
+                        self.pop_expect(r, I32)?;
+                        self.push_ctrl(op, results);
+                    }
+                    0x05 => {
+                        let ctrl = self.pop_ctrl(r)?;
+                        if ctrl.opcode != 0x04 {
+                            return Err(r.err("else가 if 밖에 있음"));
+                        }
+                        self.push_ctrl(0x05, ctrl.results);
+                    }
+                    0x0B => {
+                        let ctrl = self.pop_ctrl(r)?;
+                        if ctrl.opcode == 0x04 && !ctrl.results.is_empty() {
+                            return Err(r.err("else 없는 if는 값을 낼 수 없음"));
+                        }
+                        if self.ctrls.is_empty() {
+                            if !r.eof() {
+                                return Err(r.err(format!("함수 end 뒤에 {}바이트가 남음", r.end - r.pos)));
+                            }
+                        } else {
+                            self.vals.extend(ctrl.results.into_iter().map(Some));
+                        }
+                    }
+                    0x0C => {
+                        let depth = r.u32()?;
+                        let types = self.label_types(r, depth)?;
+                        self.pop_all(r, &types)?;
+                        self.set_unreachable();
+                    }
+                    0x0D => {
+                        let depth = r.u32()?;
+                        let types = self.label_types(r, depth)?;
+                        self.pop_expect(r, I32)?;
+                        self.pop_all(r, &types)?;
+                        self.vals.extend(types.into_iter().map(Some));
+                    }
+                    0x0E => {
+                        let n = r.u32()?;
+                        let labels = (0..n).map(|_| r.u32()).collect::<Checked<Vec<u32>>>()?;
+                        let default = r.u32()?;
+                        let default = self.label_types(r, default)?;
+                        for depth in labels {
+                            if self.label_types(r, depth)? != default {
+                                return Err(r.err(format!("br_table 대상 {}의 타입이 기본 대상과 다름", depth)));
+                            }
+                        }
+                        self.pop_expect(r, I32)?;
+                        self.pop_all(r, &default)?;
+                        self.set_unreachable();
+                    }
+                    0x0F => {
+                        let results = self.results.clone();
+                        self.pop_all(r, &results)?;
+                        self.set_unreachable();
+                    }
+                    0x10 => {
+                        let idx = r.u32()?;
+                        let (params, results) = self.module.func_type(r, idx)?.clone();
+                        self.pop_all(r, &params)?;
+                        self.vals.extend(results.into_iter().map(Some));
+                    }
+                    0x1A => { self.pop_val(r)?; }
+                    0x1B => {
+                        self.pop_expect(r, I32)?;
+                        let a = self.pop_val(r)?;
+                        let b = self.pop_val(r)?;
+                        if let (Some(a), Some(b)) = (a, b) {
+                            if a != b {
+                                return Err(r.err(format!("select 피연산자 타입이 다름: {} / {}", b.name(), a.name())));
+                            }
+                        }
+                        self.vals.push(a.or(b));
+                    }
+                    0x20..=0x22 => {
+                        let idx = r.u32()?;
+                        let t = *self.locals.get(idx as usize)
+                            .ok_or_else(|| r.err(format!("로컬 인덱스 {} 범위 밖 (로컬 {}개)", idx, self.locals.len())))?;
+                        if op != 0x20 {
+                            self.pop_expect(r, t)?;
+                        }
+                        if op != 0x21 {
+                            self.vals.push(Some(t));
+                        }
+                    }
+                    0x23 | 0x24 => {
+                        let idx = r.u32()?;
+                        let (t, mutable) = *self.module.globals.get(idx as usize)
+                            .ok_or_else(|| r.err(format!("전역 인덱스 {} 범위 밖 (전역 {}개)", idx, self.module.globals.len())))?;
+                        if op == 0x23 {
+                            self.vals.push(Some(t));
+                        } else if !mutable {
+                            return Err(r.err(format!("불변 전역 {}에 쓰기", idx)));
+                        } else {
+                            self.pop_expect(r, t)?;
+                        }
+                    }
+                    0x28..=0x35 => {
+                        let (t, width) = match op {
+                            0x28 => (I32, 4), 0x29 => (I64, 8), 0x2A => (F32, 4), 0x2B => (F64, 8),
+                            0x2C | 0x2D => (I32, 1), 0x2E | 0x2F => (I32, 2),
+                            0x30 | 0x31 => (I64, 1), 0x32 | 0x33 => (I64, 2), _ => (I64, 4),
+                        };
+                        self.memarg(r, width)?;
+                        self.pop_expect(r, I32)?;
+                        self.vals.push(Some(t));
+                    }
+                    0x36..=0x3E => {
+                        let (t, width) = match op {
+                            0x36 => (I32, 4), 0x37 => (I64, 8), 0x38 => (F32, 4), 0x39 => (F64, 8),
+                            0x3A => (I32, 1), 0x3B => (I32, 2), 0x3C => (I64, 1), 0x3D => (I64, 2), _ => (I64, 4),
+                        };
+                        self.memarg(r, width)?;
+                        self.pop_expect(r, t)?;
+                        self.pop_expect(r, I32)?;
+                    }
+                    0x3F | 0x40 => {
+                        if r.byte()? != 0x00 {
+                            return Err(r.err("메모리 인덱스는 0이어야 함"));
+                        }
+                        if self.module.memories == 0 {
+                            return Err(r.err("메모리 없이 메모리 접근"));
+                        }
+                        if op == 0x40 {
+                            self.pop_expect(r, I32)?;
+                        }
+                        self.vals.push(Some(I32));
+                    }
+                    0x41 => { r.leb(32, true)?; self.vals.push(Some(I32)); }
+                    0x42 => { r.leb(64, true)?; self.vals.push(Some(I64)); }
+                    0x43 => { r.skip(4)?; self.vals.push(Some(F32)); }
+                    0x44 => { r.skip(8)?; self.vals.push(Some(F64)); }
+                    _ => {
+                        let (params, result) = numeric_signature(op)
+                            .ok_or_else(|| r.err("알 수 없는 opcode"))?;
+                        self.pop_all(r, params)?;
+                        self.vals.push(Some(result));
+                    }
+                }
+                Ok(())
+            })();
+            result.map_err(|e| WasmValidationError {
+                offset: if e.offset == r.pos { at } else { e.offset },
+                message: format!("0x{:02X}: {}", op, e.message),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// 수치 명령 시그니처 (0x45 ~ 0xBF)
+fn numeric_signature(op: u8) -> Option<(&'static [ValType], ValType)> {
+    use ValType::*;
+    Some(match op {
+        0x45 => (&[I32], I32),
+        0x46..=0x4F => (&[I32, I32], I32),
+        0x50 => (&[I64], I32),
+        0x51..=0x5A => (&[I64, I64], I32),
+        0x5B..=0x60 => (&[F32, F32], I32),
+        0x61..=0x66 => (&[F64, F64], I32),
+        0x67..=0x69 => (&[I32], I32),
+        0x6A..=0x78 => (&[I32, I32], I32),
+        0x79..=0x7B => (&[I64], I64),
+        0x7C..=0x8A => (&[I64, I64], I64),
+        0x8B..=0x91 => (&[F32], F32),
+        0x92..=0x98 => (&[F32, F32], F32),
+        0x99..=0x9F => (&[F64], F64),
+        0xA0..=0xA6 => (&[F64, F64], F64),
+        0xA7 => (&[I64], I32),
+        0xA8 | 0xA9 | 0xBC => (&[F32], I32),
+        0xAA | 0xAB => (&[F64], I32),
+        0xAC | 0xAD => (&[I32], I64),
+        0xAE | 0xAF => (&[F32], I64),
+        0xB0 | 0xB1 | 0xBD => (&[F64], I64),
+        0xB2 | 0xB3 | 0xBE => (&[I32], F32),
+        0xB4 | 0xB5 => (&[I64], F32),
+        0xB6 => (&[F64], F32),
+        0xB7 | 0xB8 => (&[I32], F64),
+        0xB9 | 0xBA | 0xBF => (&[I64], F64),
+        0xBB => (&[F32], F64),
+        _ => return None,
+    })
+}
+
+// ─────────────────────────────────────────────
+// 역어셈블 — 섹션 요약 + 함수 본문 명령 목록
+// ─────────────────────────────────────────────
+
+const INT_CMP: [&str; 10] = ["eq", "ne", "lt_s", "lt_u", "gt_s", "gt_u", "le_s", "le_u", "ge_s", "ge_u"];
+const FLOAT_CMP: [&str; 6] = ["eq", "ne", "lt", "gt", "le", "ge"];
+const INT_ARITH: [&str; 18] = [
+    "clz", "ctz", "popcnt", "add", "sub", "mul", "div_s", "div_u", "rem_s", "rem_u",
+    "and", "or", "xor", "shl", "shr_s", "shr_u", "rotl", "rotr",
+];
+const FLOAT_ARITH: [&str; 14] = [
+    "abs", "neg", "ceil", "floor", "trunc", "nearest", "sqrt", "add", "sub", "mul", "div", "min", "max", "copysign",
+];
+const CONVERSIONS: [&str; 25] = [
+    "i32.wrap_i64", "i32.trunc_f32_s", "i32.trunc_f32_u", "i32.trunc_f64_s", "i32.trunc_f64_u",
+    "i64.extend_i32_s", "i64.extend_i32_u", "i64.trunc_f32_s", "i64.trunc_f32_u", "i64.trunc_f64_s",
+    "i64.trunc_f64_u", "f32.convert_i32_s", "f32.convert_i32_u", "f32.convert_i64_s", "f32.convert_i64_u",
+    "f32.demote_f64", "f64.convert_i32_s", "f64.convert_i32_u", "f64.convert_i64_s", "f64.convert_i64_u",
+    "f64.promote_f32", "i32.reinterpret_f32", "i64.reinterpret_f64", "f32.reinterpret_i32", "f64.reinterpret_i64",
+];
+const LOADS: [&str; 14] = [
+    "i32.load", "i64.load", "f32.load", "f64.load", "i32.load8_s", "i32.load8_u", "i32.load16_s",
+    "i32.load16_u", "i64.load8_s", "i64.load8_u", "i64.load16_s", "i64.load16_u", "i64.load32_s", "i64.load32_u",
+];
+const STORES: [&str; 9] = [
+    "i32.store", "i64.store", "f32.store", "f64.store", "i32.store8", "i32.store16", "i64.store8", "i64.store16", "i64.store32",
+];
+
+/// 수치 명령 이름 (0x45 ~ 0xBF)
+fn numeric_name(op: u8) -> Option<String> {
+    let i = op as usize;
+    Some(match op {
+        0x45 => "i32.eqz".into(),
+        0x46..=0x4F => format!("i32.{}", INT_CMP[i - 0x46]),
+        0x50 => "i64.eqz".into(),
+        0x51..=0x5A => format!("i64.{}", INT_CMP[i - 0x51]),
+        0x5B..=0x60 => format!("f32.{}", FLOAT_CMP[i - 0x5B]),
+        0x61..=0x66 => format!("f64.{}", FLOAT_CMP[i - 0x61]),
+        0x67..=0x78 => format!("i32.{}", INT_ARITH[i - 0x67]),
+        0x79..=0x8A => format!("i64.{}", INT_ARITH[i - 0x79]),
+        0x8B..=0x98 => format!("f32.{}", FLOAT_ARITH[i - 0x8B]),
+        0x99..=0xA6 => format!("f64.{}", FLOAT_ARITH[i - 0x99]),
+        0xA7..=0xBF => CONVERSIONS[i - 0xA7].into(),
+        _ => return None,
+    })
+}
+
+fn type_list(types: &[ValType]) -> String {
+    format!("[{}]", types.iter().map(|t| t.name()).collect::<Vec<_>>().join(" "))
+}
+
+/// WASM 바이너리 → 역어셈블 목록 (먼저 validate)
+pub fn disassemble(bytes: &[u8]) -> Result<String, WasmValidationError> {
+    validate(bytes)?;
+    let mut out = format!(";; WASM v1 · {} bytes · 검증 OK\n", bytes.len());
+    let mut r = Reader { bytes, pos: 8, end: bytes.len() };
+    let mut types: Vec<(Vec<ValType>, Vec<ValType>)> = Vec::new();
+    let mut funcs: Vec<u32> = Vec::new();
+    // 함수 인덱스 → 이름 (import module.name 또는 export 이름)
+    let mut names: std::collections::HashMap<u32, String> = std::collections::HashMap::new();
+    let mut imported = 0u32;
+
+    while !r.eof() {
+        let at = r.pos;
+        let id = r.byte()?;
+        let size = r.u32()? as usize;
+        let mut s = r.sub(size, "섹션")?;
+        let name = if id == 0 { "custom" } else { section_info(id).map_or("?", |(n, _)| n) };
+        out.push_str(&format!("\n;; ── {} 섹션 (id {}) · {} bytes @0x{:X}\n", name, id, size, at));
+        match id {
+            1 => {
+                for i in 0..s.u32()? {
+                    s.byte()?;
+                    let (params, results) = (s.val_types()?, s.val_types()?);
+                    out.push_str(&format!("  type {}: {} → {}\n", i, type_list(&params), type_list(&results)));
+                    types.push((params, results));
+                }
+            }
+            2 => {
+                for _ in 0..s.u32()? {
+                    let (module, field) = (s.name()?, s.name()?);
+                    let kind = s.byte()?;
+                    match kind {
+                        0x00 => {
+                            let ty = s.u32()?;
+                            out.push_str(&format!("  import func {}: {}.{} (type {})\n", imported, module, field, ty));
+                            names.insert(imported, format!("{}.{}", module, field));
+                            funcs.push(ty);
+                            imported += 1;
+                        }
+                        0x01 => { s.byte()?; s.limits(u32::MAX)?; out.push_str(&format!("  import table: {}.{}\n", module, field)); }
+                        0x02 => { s.limits(MAX_PAGES)?; out.push_str(&format!("  import memory: {}.{}\n", module, field)); }
+                        _ => {
+                            let t = s.val_type()?;
+                            s.byte()?;
+                            out.push_str(&format!("  import global: {}.{} ({})\n", module, field, t.name()));
+                        }
+                    }
+                }
+            }
+            3 => {
+                for _ in 0..s.u32()? {
+                    funcs.push(s.u32()?);
+                }
+                out.push_str(&format!("  함수 {}개\n", funcs.len() as u32 - imported));
+            }
+            5 => {
+                for _ in 0..s.u32()? {
+                    let flag = s.byte()?;
+                    let min = s.u32()?;
+                    let max = if flag == 0x01 { format!(" ~ {}", s.u32()?) } else { String::new() };
+                    out.push_str(&format!("  memory: {}{} page(s)\n", min, max));
+                }
+            }
+            6 => {
+                for i in 0..s.u32()? {
+                    let t = s.val_type()?;
+                    let mutable = s.byte()? == 0x01;
+                    let init = match s.byte()? {
+                        0x41 => s.leb(32, true)?.to_string(),
+                        0x42 => s.leb(64, true)?.to_string(),
+                        0x43 => { let at = s.pos; s.skip(4)?; f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()).to_string() }
+                        0x44 => { let at = s.pos; s.skip(8)?; f64::from_le_bytes(bytes[at..at + 8].try_into().unwrap()).to_string() }
+                        _ => format!("global.get {}", s.u32()?),
+                    };
+                    s.byte()?; // end
+                    out.push_str(&format!("  global {}: {}{} = {}\n", i, if mutable { "mut " } else { "" }, t.name(), init));
+                }
+            }
+            7 => {
+                for _ in 0..s.u32()? {
+                    let name = s.name()?;
+                    let kind = s.byte()?;
+                    let idx = s.u32()?;
+                    let what = ["func", "table", "memory", "global"].get(kind as usize).copied().unwrap_or("?");
+                    out.push_str(&format!("  export \"{}\": {} {}\n", name, what, idx));
+                    if kind == 0x00 {
+                        names.entry(idx).or_insert(name);
+                    }
+                }
+            }
+            8 => out.push_str(&format!("  start: func {}\n", s.u32()?)),
+            10 => {
+                for i in 0..s.u32()? {
+                    let size = s.u32()? as usize;
+                    let mut body = s.sub(size, "함수 본문")?;
+                    let idx = imported + i;
+                    let (params, results) = &types[funcs[idx as usize] as usize];
+                    let label = names.get(&idx).map(|n| format!(" \"{}\"", n)).unwrap_or_default();
+                    out.push_str(&format!("\n  func {}{}: {} → {} · {} bytes\n", idx, label, type_list(params), type_list(results), size));
+                    let mut locals = Vec::new();
+                    for _ in 0..body.u32()? {
+                        let n = body.u32()?;
+                        locals.push(format!("{}×{}", body.val_type()?.name(), n));
+                    }
+                    if !locals.is_empty() {
+                        out.push_str(&format!("    locals {}\n", locals.join(" ")));
+                    }
+                    disassemble_body(&mut body, &names, &mut out)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// 함수 본문 명령 — `오프셋  들여쓰기 니모닉 즉시값`
+fn disassemble_body(r: &mut Reader, names: &std::collections::HashMap<u32, String>, out: &mut String) -> Checked<()> {
+    let mut depth = 1usize;
+    while !r.eof() {
+        let at = r.pos;
+        let op = r.byte()?;
+        let block_type = |r: &mut Reader| -> Checked<String> {
+            let b = r.byte()?;
+            Ok(ValType::from_byte(b).map(|t| format!(" (result {})", t.name())).unwrap_or_default())
+        };
+        let (text, indent_after) = match op {
+            0x00 => ("unreachable".to_string(), 0),
+            0x01 => ("nop".to_string(), 0),
+            0x02 => (format!("block{}", block_type(r)?), 1),
+            0x03 => (format!("loop{}", block_type(r)?), 1),
+            0x04 => (format!("if{}", block_type(r)?), 1),
+            0x05 => { depth -= 1; ("else".to_string(), 1) }
+            0x0B => { depth = depth.saturating_sub(1); ("end".to_string(), 0) }
+            0x0C => (format!("br {}", r.u32()?), 0),
+            0x0D => (format!("br_if {}", r.u32()?), 0),
+            0x0E => {
+                let labels = (0..r.u32()?).map(|_| r.u32().map(|l| l.to_string())).collect::<Checked<Vec<_>>>()?;
+                (format!("br_table [{}] {}", labels.join(" "), r.u32()?), 0)
+            }
+            0x0F => ("return".to_string(), 0),
+            0x10 => {
+                let idx = r.u32()?;
+                let name = names.get(&idx).map(|n| format!("  ;; {}", n)).unwrap_or_default();
+                (format!("call {}{}", idx, name), 0)
+            }
+            0x1A => ("drop".to_string(), 0),
+            0x1B => ("select".to_string(), 0),
+            0x20 => (format!("local.get {}", r.u32()?), 0),
+            0x21 => (format!("local.set {}", r.u32()?), 0),
+            0x22 => (format!("local.tee {}", r.u32()?), 0),
+            0x23 => (format!("global.get {}", r.u32()?), 0),
+            0x24 => (format!("global.set {}", r.u32()?), 0),
+            0x28..=0x3E => {
+                let name = if op <= 0x35 { LOADS[op as usize - 0x28] } else { STORES[op as usize - 0x36] };
+                let align = r.u32()?;
+                (format!("{} offset={} align={}", name, r.u32()?, 1u32 << align.min(31)), 0)
+            }
+            0x3F => { r.byte()?; ("memory.size".to_string(), 0) }
+            0x40 => { r.byte()?; ("memory.grow".to_string(), 0) }
+            0x41 => (format!("i32.const {}", r.leb(32, true)?), 0),
+            0x42 => (format!("i64.const {}", r.leb(64, true)?), 0),
+            0x43 => {
+                let at = r.pos;
+                r.skip(4)?;
+                (format!("f32.const {}", f32::from_le_bytes(r.bytes[at..at + 4].try_into().unwrap())), 0)
+            }
+            0x44 => {
+                let at = r.pos;
+                r.skip(8)?;
+                (format!("f64.const {}", f64::from_le_bytes(r.bytes[at..at + 8].try_into().unwrap())), 0)
+            }
+            _ => (numeric_name(op).ok_or_else(|| r.err(format!("알 수 없는 opcode 0x{:02X}", op)))?, 0),
+        };
+        out.push_str(&format!("    {:06X}  {}{}\n", at, "  ".repeat(depth), text));
+        depth += indent_after;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -608,4 +1607,75 @@ mod tests {
         assert!(&wasm[0..4] == b"\0asm");
         println!("add() WASM: {} bytes", wasm.len());
     }
+
+    #[test]
+    fn test_validate_generated_modules() {
+        let sources = [
+            "넣어 5\n넣어 3\n더해\n종료",
+            "넣어 5\n호출 팩\n보여줘\n종료\n팩:\n복사\n넣어 1\n크다\n조건점프 재귀\n돌려줘\n\
+             재귀:\n복사\n넣어 1\n빼\n호출 팩\n곱해\n돌려줘",
+            "참\n삼분기 가 나 다\n가:\n나:\n다:\n종료",
+            "",
+        ];
+        for source in sources {
+            let wasm = crate::compiler::compile_source_to_wasm(source, "check").unwrap();
+            assert_eq!(validate(&wasm), Ok(()), "{:?}", source);
+        }
+        let wasm = crate::hanseon::compile_to_wasm("변수 i = 0\n동안 i < 3 { 만약 i == 1 { i 보여줘 }\n변수 i = i + 1 }").unwrap();
+        assert_eq!(validate(&wasm), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_layout() {
+        let header = b"\0asm\x01\0\0\0".to_vec();
+
+        // memory(5) 뒤에 type(1)
+        let mut wasm = header.clone();
+        wasm.extend_from_slice(&[SEC_MEMORY, 3, 1, 0x00, 1, SEC_TYPE, 1, 0]);
+        let err = validate(&wasm).unwrap_err();
+        assert_eq!(err.offset, 13);
+        assert!(err.message.contains("섹션 순서 위반"), "{}", err);
+
+        // 섹션 크기 LEB128이 6바이트
+        let mut wasm = header.clone();
+        wasm.extend_from_slice(&[SEC_TYPE, 0x81, 0x80, 0x80, 0x80, 0x80, 0x00]);
+        assert!(validate(&wasm).unwrap_err().message.contains("LEB128"));
+
+        // 잘린 모듈 — 마지막 섹션이 파일을 넘음
+        let wasm = crate::compiler::compile_source_to_wasm("넣어 1\n종료", "cut").unwrap();
+        let err = validate(&wasm[..wasm.len() - 1]).unwrap_err();
+        assert!(err.message.contains("섹션 크기"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_type_mismatch() {
+        let mut module = IrModule::new("bad");
+        let mut func = IrFunction::new("main");
+        func.results.push(IrType::I64);
+        func.body = vec![IrOp::Const(1), IrOp::ConstI32(2), IrOp::Add];
+        func.is_export = true;
+        module.add_function(func);
+        let err = validate(&WasmBuilder::build(&module)).unwrap_err();
+        assert!(err.message.starts_with("함수 0: 0x7C: 타입 불일치: i64 필요, i32 발견"), "{}", err);
+
+        // 반환 타입이 남는 값과 다름
+        module.functions[0].body = vec![IrOp::ConstI32(1)];
+        let err = validate(&WasmBuilder::build(&module)).unwrap_err();
+        assert!(err.message.contains("0x0B: 타입 불일치"), "{}", err);
+    }
+
+    #[test]
+    fn test_disassemble_listing() {
+        let wasm = crate::compiler::compile_source_to_wasm("넣어 5\n시작:\n넣어 -1\n더해\n복사\n조건점프 시작\n보여줘\n종료", "dis").unwrap();
+        let text = disassemble(&wasm).unwrap();
+        assert!(text.contains("import func 0: env.print (type 0)"), "{}", text);
+        assert!(text.contains("export \"main\": func 3"), "{}", text);
+        assert!(text.contains("func 3 \"main\""), "{}", text);
+        assert!(text.contains("br_table [0 1 2] 4"), "{}", text);
+        assert!(text.contains("call 0  ;; env.print"), "{}", text);
+        assert!(text.contains("i64.const -1"), "{}", text);
+
+        // 검증 실패 모듈은 목록을 만들지 않음
+        assert!(disassemble(&wasm[..wasm.len() - 1]).is_err());
+    }
 }
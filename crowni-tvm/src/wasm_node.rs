@@ -3,8 +3,13 @@
 // 브라우저 경량 노드 — TVM 실행, P2P 합의, 상태 동기화
 // ═══════════════════════════════════════════════════════════════
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::conformance::Gen;
+use crate::crypto::Key;
+use crate::identity::{self, IdentityError, NodeIdentity};
+use crate::output::JsonObject;
+use crate::ring_log::{RingLog, MESSAGE_LOG_CAPACITY};
 
 // ── 브라우저 노드 타입 ──
 
@@ -27,6 +32,20 @@ impl std::fmt::Display for BrowserNodeType {
     }
 }
 
+impl std::str::FromStr for BrowserNodeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Full" => Ok(Self::Full),
+            "Light" => Ok(Self::Light),
+            "Validator" => Ok(Self::Validator),
+            "Observer" => Ok(Self::Observer),
+            _ => Err(format!("알 수 없는 노드 타입: {}", s)),
+        }
+    }
+}
+
 // ── WASM 모듈 매니페스트 ──
 
 #[derive(Debug, Clone)]
@@ -53,7 +72,7 @@ impl WasmManifest {
         Self {
             name: "crowny-wasm".to_string(),
             version: "0.4.0".to_string(),
-            size_bytes: 256_000, // ~250KB 목표
+            size_bytes: 264_000, // ~250KB 목표
             hash: "cb33cb33".to_string(),
             modules: vec![
                 WasmModule {
@@ -61,6 +80,7 @@ impl WasmManifest {
                     exports: vec![
                         "tvm_init".into(), "tvm_execute".into(), "tvm_push".into(),
                         "tvm_pop".into(), "tvm_stack_top".into(), "tvm_reset".into(),
+                        "tvm_alloc".into(), "tvm_free".into(),
                     ],
                     size_bytes: 80_000,
                     critical: true,
@@ -120,6 +140,15 @@ impl WasmManifest {
                     size_bytes: 9_000,
                     critical: true,
                 },
+                WasmModule {
+                    name: "offline_queue".to_string(),
+                    exports: vec![
+                        "txq_enqueue".into(), "txq_pending".into(),
+                        "txq_replay".into(), "txq_set_online".into(),
+                    ],
+                    size_bytes: 8_000,
+                    critical: false,
+                },
             ],
             total_opcodes: 729,
             trit_support: true,
@@ -131,39 +160,46 @@ impl WasmManifest {
 
 #[derive(Debug, Clone)]
 pub enum P2PMessage {
-    Handshake { node_id: String, node_type: BrowserNodeType, version: String },
+    /// public_key · signature는 신원 키가 있는 노드만 (hex)
+    Handshake { node_id: String, node_type: BrowserNodeType, version: String, public_key: Option<String>, signature: Option<String> },
     Heartbeat { node_id: String, timestamp: u64 },
-    TritVote { node_id: String, proposal_id: u64, vote: i8 },
+    TritVote { node_id: String, proposal_id: u64, vote: i8, signature: Option<String> },
     StateRequest { key: String },
     StateResponse { key: String, value: String, version: u64 },
-    TxSubmit { from: String, to: String, amount: u64, memo: String },
+    TxSubmit { from: String, to: String, amount: u64, nonce: u64, memo: String },
     TxConfirm { tx_id: String, trit_state: i8 },
     BlockProposal { block_id: u64, transactions: Vec<String>, proposer: String },
-    BlockVote { block_id: u64, voter: String, vote: i8 },
+    BlockVote { block_id: u64, voter: String, vote: i8, signature: Option<String> },
     Sync { from_version: u64, data: Vec<(String, String)> },
 }
 
 impl std::fmt::Display for P2PMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Handshake { node_id, node_type, version } =>
+            Self::Handshake { node_id, node_type, version, .. } =>
                 write!(f, "🤝 Handshake {} ({}) v{}", short(node_id), node_type, version),
             Self::Heartbeat { node_id, .. } =>
                 write!(f, "♥ Heartbeat {}", short(node_id)),
-            Self::TritVote { node_id, proposal_id, vote } => {
+            Self::TritVote { node_id, proposal_id, vote, .. } => {
                 let v = match vote { 1 => "P", -1 => "T", _ => "O" };
                 write!(f, "🗳 Vote {} from {} on #{}", v, short(node_id), proposal_id)
             }
             Self::StateRequest { key } => write!(f, "❓ StateReq: {}", key),
             Self::StateResponse { key, value, version } => write!(f, "📦 State: {}={} (v{})", key, short(value), version),
-            Self::TxSubmit { from, to, amount, .. } => write!(f, "💸 Tx: {}→{} {} CRWN", short(from), short(to), amount),
+            Self::TxSubmit { from, to, amount, nonce, memo } => {
+                write!(f, "💸 Tx: {}→{} {} CRWN (nonce {})", short(from), short(to), amount, nonce)?;
+                if !memo.is_empty() {
+                    write!(f, " \"{}\"", memo)?;
+                }
+                Ok(())
+            }
             Self::TxConfirm { tx_id, trit_state } => {
                 let s = match trit_state { 1 => "P", -1 => "T", _ => "O" };
                 write!(f, "✓ TxConfirm {} [{}]", short(tx_id), s)
             }
             Self::BlockProposal { block_id, transactions, proposer } =>
                 write!(f, "📦 Block #{} ({} txs) by {}", block_id, transactions.len(), short(proposer)),
-            Self::BlockVote { block_id, voter, vote } => {
+            Self::BlockVote { block_id, voter, vote, .. } => {
                 let v = match vote { 1 => "P", -1 => "T", _ => "O" };
                 write!(f, "🗳 BlockVote #{} {} from {}", block_id, v, short(voter))
             }
@@ -173,10 +209,551 @@ impl std::fmt::Display for P2PMessage {
     }
 }
 
+/// 양방향 링크 키 — 이름 순서로 정규화
+fn link_key(a: &str, b: &str) -> (String, String) {
+    if a <= b { (a.to_string(), b.to_string()) } else { (b.to_string(), a.to_string()) }
+}
+
 fn short(s: &str) -> &str {
     if s.len() > 8 { &s[..8] } else { s }
 }
 
+// ── 영속 저장소 ──
+//
+// 브라우저: IndexedDB (JS 바인딩이 열어 둔 객체 저장소 — 동기 미러 + 비동기 기록)
+// 네이티브: 파일 (테스트 · 데스크톱 노드)
+//
+// 키 배치:
+//   meta:state_version     상태 버전
+//   state:{키}             상태 값
+//   peer:{id}              유형|지연|마지막 확인|동기화
+//   header:{id:010}        제안자|확정|trit|시각|트랜잭션 수
+//   key:{이름}             키 자료 (hex)
+//   txq:{seq:010}          오프라인 큐 항목 (OfflineTxQueue)
+
+/// 브라우저 노드 저장소
+pub trait NodeStorage: std::fmt::Debug {
+    fn get(&self, key: &str) -> Option<String>;
+    fn put(&mut self, key: &str, value: &str) -> Result<(), String>;
+    fn delete(&mut self, key: &str) -> Result<(), String>;
+    /// 접두사로 시작하는 키 (정렬)
+    fn keys(&self, prefix: &str) -> Vec<String>;
+    /// 버퍼된 기록을 내보냄
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// 메모리 저장소 — 새로고침하면 사라짐 (기본)
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    entries: BTreeMap<String, String>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NodeStorage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.get(key).cloned()
+    }
+
+    fn put(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.entries.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), String> {
+        self.entries.remove(key);
+        Ok(())
+    }
+
+    fn keys(&self, prefix: &str) -> Vec<String> {
+        self.entries.range(prefix.to_string()..).map(|(k, _)| k).take_while(|k| k.starts_with(prefix)).cloned().collect()
+    }
+}
+
+/// 파일 저장소 — 한 줄에 "키<TAB>값", 기록마다 임시 파일 → 교체
+#[derive(Debug)]
+pub struct FileStorage {
+    path: std::path::PathBuf,
+    entries: MemoryStorage,
+}
+
+impl FileStorage {
+    /// 파일이 없으면 빈 저장소
+    pub fn open(path: impl Into<std::path::PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let mut entries = MemoryStorage::new();
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                for (n, line) in text.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+                    let (k, v) = line.split_once('\t')
+                        .ok_or_else(|| format!("{}:{}: 형식 오류", path.display(), n + 1))?;
+                    entries.put(&unescape(k), &unescape(v))?;
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        }
+        Ok(Self { path, entries })
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    fn write(&self) -> Result<(), String> {
+        let mut text = String::new();
+        for (k, v) in &self.entries.entries {
+            text.push_str(&escape(k));
+            text.push('\t');
+            text.push_str(&escape(v));
+            text.push('\n');
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, text)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+}
+
+impl NodeStorage for FileStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.get(key)
+    }
+
+    fn put(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.entries.put(key, value)?;
+        self.write()
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), String> {
+        self.entries.delete(key)?;
+        self.write()
+    }
+
+    fn keys(&self, prefix: &str) -> Vec<String> {
+        self.entries.keys(prefix)
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        self.write()
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// IndexedDB 저장소 (wasm 빌드) — JS 바인딩의 CrownyIdbStore가 env로 제공하는 동기 미러를 호출
+/// 미러는 init 시 IndexedDB에서 미리 읽고, 기록은 비동기로 IndexedDB에 반영된다
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+pub struct IndexedDbStorage {
+    store: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+mod idb {
+    extern "C" {
+        /// 값 길이 (없으면 -1) — out_cap 이하면 out에 복사
+        pub fn idb_get(store: *const u8, store_len: usize, key: *const u8, key_len: usize, out: *mut u8, out_cap: usize) -> i32;
+        pub fn idb_put(store: *const u8, store_len: usize, key: *const u8, key_len: usize, val: *const u8, val_len: usize) -> i32;
+        pub fn idb_delete(store: *const u8, store_len: usize, key: *const u8, key_len: usize) -> i32;
+        /// 접두사 키 목록 ('\n' 구분) 길이 — out_cap 이하면 out에 복사
+        pub fn idb_keys(store: *const u8, store_len: usize, prefix: *const u8, prefix_len: usize, out: *mut u8, out_cap: usize) -> i32;
+        pub fn idb_flush(store: *const u8, store_len: usize) -> i32;
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl IndexedDbStorage {
+    /// 객체 저장소 이름 — JS init에서 연 저장소여야 함 (기본 "node")
+    pub fn open(store: &str) -> Self {
+        Self { store: store.to_string() }
+    }
+
+    /// 길이를 먼저 묻고 버퍼를 맞춰 다시 읽는다
+    fn read(call: impl Fn(*mut u8, usize) -> i32) -> Option<String> {
+        let len = call(std::ptr::null_mut(), 0);
+        if len < 0 {
+            return None;
+        }
+        let mut buf = vec![0u8; len as usize];
+        call(buf.as_mut_ptr(), buf.len());
+        String::from_utf8(buf).ok()
+    }
+
+    fn status(code: i32, what: &str) -> Result<(), String> {
+        if code == 0 { Ok(()) } else { Err(format!("IndexedDB {} 실패 ({})", what, code)) }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl NodeStorage for IndexedDbStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        let s = self.store.as_bytes();
+        Self::read(|out, cap| unsafe { idb::idb_get(s.as_ptr(), s.len(), key.as_ptr(), key.len(), out, cap) })
+    }
+
+    fn put(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let s = self.store.as_bytes();
+        Self::status(unsafe { idb::idb_put(s.as_ptr(), s.len(), key.as_ptr(), key.len(), value.as_ptr(), value.len()) }, "put")
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), String> {
+        let s = self.store.as_bytes();
+        Self::status(unsafe { idb::idb_delete(s.as_ptr(), s.len(), key.as_ptr(), key.len()) }, "delete")
+    }
+
+    fn keys(&self, prefix: &str) -> Vec<String> {
+        let s = self.store.as_bytes();
+        Self::read(|out, cap| unsafe { idb::idb_keys(s.as_ptr(), s.len(), prefix.as_ptr(), prefix.len(), out, cap) })
+            .map(|list| list.split('\n').filter(|k| !k.is_empty()).map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        let s = self.store.as_bytes();
+        Self::status(unsafe { idb::idb_flush(s.as_ptr(), s.len()) }, "flush")
+    }
+}
+
+// ── 오프라인 트랜잭션 큐 ──
+//
+// 오프라인(또는 서비스 워커가 페이지 없이 깨어 있는 동안) 만든 트랜잭션은
+// 임시 nonce로 큐에 쌓이고, 재접속 시 체인의 다음 nonce에 맞춰 번호를 다시 매긴 뒤 재전송된다.
+// 상태: O = 대기, P = 전송 완료, T = 거부 (재시도 한도 초과)
+
+/// 재전송 실패가 이만큼 쌓이면 T로 확정
+pub const MAX_REPLAY_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedTx {
+    pub seq: u64,
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    pub nonce: u64,
+    pub memo: String,
+    pub state: i8,
+    pub attempts: u32,
+    pub created_at: u64,
+    pub last_error: Option<String>,
+}
+
+impl QueuedTx {
+    pub fn to_message(&self) -> P2PMessage {
+        P2PMessage::TxSubmit {
+            from: self.from.clone(),
+            to: self.to.clone(),
+            amount: self.amount,
+            nonce: self.nonce,
+            memo: self.memo.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        JsonObject::new()
+            .int("seq", self.seq as i64)
+            .str("from", &self.from)
+            .str("to", &self.to)
+            .int("amount", self.amount as i64)
+            .int("nonce", self.nonce as i64)
+            .str("memo", &self.memo)
+            .trit("state", self.state)
+            .int("attempts", self.attempts as i64)
+            .int("created_at", self.created_at as i64)
+    }
+
+    /// 저장 형식 — 메모는 '|'를 포함할 수 있으므로 마지막 필드
+    fn encode(&self) -> String {
+        format!("{}|{}|{}|{}|{}|{}|{}|{}|{}", self.from, self.to, self.amount, self.nonce, self.state,
+            self.attempts, self.created_at, self.last_error.as_deref().unwrap_or("").replace('|', "/"), self.memo)
+    }
+
+    fn decode(seq: u64, value: &str) -> Option<Self> {
+        let f: Vec<&str> = value.splitn(9, '|').collect();
+        if f.len() != 9 {
+            return None;
+        }
+        Some(Self {
+            seq,
+            from: f[0].to_string(),
+            to: f[1].to_string(),
+            amount: f[2].parse().ok()?,
+            nonce: f[3].parse().ok()?,
+            state: f[4].parse().ok()?,
+            attempts: f[5].parse().ok()?,
+            created_at: f[6].parse().ok()?,
+            last_error: if f[7].is_empty() { None } else { Some(f[7].to_string()) },
+            memo: f[8].to_string(),
+        })
+    }
+}
+
+/// 재전송 결과
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayReport {
+    pub submitted: usize,
+    pub renumbered: usize,
+    pub rejected: usize,
+    pub deferred: usize,
+}
+
+impl std::fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "전송 {} | nonce 재조정 {} | 거부 {} | 보류 {}",
+            self.submitted, self.renumbered, self.rejected, self.deferred)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct OfflineTxQueue {
+    pub entries: Vec<QueuedTx>,
+    next_seq: u64,
+    /// 발신자별 마지막으로 알려진 체인 nonce (다음에 쓸 값)
+    known_nonce: HashMap<String, u64>,
+}
+
+impl OfflineTxQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 임시 nonce — 같은 발신자의 대기 항목 다음 번호, 없으면 알려진 체인 nonce
+    pub fn enqueue(&mut self, from: &str, to: &str, amount: u64, memo: &str) -> &QueuedTx {
+        let nonce = self.entries.iter()
+            .filter(|t| t.from == from && t.state == 0)
+            .map(|t| t.nonce + 1)
+            .max()
+            .unwrap_or_else(|| self.known_nonce.get(from).copied().unwrap_or(0));
+        self.next_seq += 1;
+        self.entries.push(QueuedTx {
+            seq: self.next_seq,
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+            nonce,
+            memo: memo.to_string(),
+            state: 0,
+            attempts: 0,
+            created_at: now_ms(),
+            last_error: None,
+        });
+        &self.entries[self.entries.len() - 1]
+    }
+
+    /// 대기(O) 항목 — UI 조회용
+    pub fn pending(&self) -> Vec<&QueuedTx> {
+        self.entries.iter().filter(|t| t.state == 0).collect()
+    }
+
+    pub fn has_pending(&self, from: &str) -> bool {
+        self.entries.iter().any(|t| t.from == from && t.state == 0)
+    }
+
+    /// 큐를 거치지 않는 즉시 전송용 nonce
+    pub fn take_nonce(&mut self, from: &str) -> u64 {
+        let next = self.known_nonce.entry(from.to_string()).or_insert(0);
+        *next += 1;
+        *next - 1
+    }
+
+    pub fn pending_json(&self) -> String {
+        crate::output::array(self.pending().into_iter().map(|t| t.to_json().build()).collect())
+    }
+
+    /// 발신자의 대기 항목을 체인의 다음 nonce부터 순서대로 다시 번호 매김 — 바뀐 개수
+    pub fn reconcile(&mut self, from: &str, chain_nonce: u64) -> usize {
+        self.known_nonce.insert(from.to_string(), chain_nonce);
+        let mut changed = 0;
+        for (i, tx) in self.entries.iter_mut().filter(|t| t.from == from && t.state == 0).enumerate() {
+            let nonce = chain_nonce + i as u64;
+            if tx.nonce != nonce {
+                tx.nonce = nonce;
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// 재접속 시 재전송 — 발신자별로 nonce를 맞춘 뒤 순서대로 제출
+    /// 일시 실패는 그 발신자의 나머지를 보류 (nonce 공백 방지), 한도를 넘으면 T로 확정하고 뒤 항목을 당긴다
+    pub fn replay<N, S>(&mut self, chain_nonce: N, mut submit: S) -> ReplayReport
+    where
+        N: Fn(&str) -> u64,
+        S: FnMut(&QueuedTx) -> Result<(), String>,
+    {
+        let mut report = ReplayReport::default();
+        let mut senders: Vec<String> = Vec::new();
+        for tx in self.entries.iter().filter(|t| t.state == 0) {
+            if !senders.contains(&tx.from) {
+                senders.push(tx.from.clone());
+            }
+        }
+        for from in senders {
+            let mut next = chain_nonce(&from);
+            report.renumbered += self.reconcile(&from, next);
+            let idxs: Vec<usize> = (0..self.entries.len())
+                .filter(|&i| self.entries[i].from == from && self.entries[i].state == 0)
+                .collect();
+            for (n, &i) in idxs.iter().enumerate() {
+                let tx = &mut self.entries[i];
+                if tx.nonce != next {
+                    tx.nonce = next;
+                    report.renumbered += 1;
+                }
+                tx.attempts += 1;
+                match submit(tx) {
+                    Ok(()) => {
+                        tx.state = 1;
+                        tx.last_error = None;
+                        report.submitted += 1;
+                        next += 1;
+                    }
+                    Err(e) if tx.attempts >= MAX_REPLAY_ATTEMPTS => {
+                        tx.state = -1;
+                        tx.last_error = Some(e);
+                        report.rejected += 1;
+                    }
+                    Err(e) => {
+                        tx.last_error = Some(e);
+                        report.deferred += idxs.len() - n;
+                        break;
+                    }
+                }
+            }
+            self.known_nonce.insert(from, next);
+        }
+        report
+    }
+
+    /// P/T 항목 제거 — 제거한 seq
+    pub fn prune_settled(&mut self) -> Vec<u64> {
+        let removed = self.entries.iter().filter(|t| t.state != 0).map(|t| t.seq).collect();
+        self.entries.retain(|t| t.state == 0);
+        removed
+    }
+
+    fn restore(&mut self, tx: QueuedTx) {
+        self.next_seq = self.next_seq.max(tx.seq);
+        self.entries.push(tx);
+    }
+}
+
+/// 오프라인 큐 wasm 내보내기 — JS 바인딩의 queueTx · pendingTxs · replay · setOnline이 호출
+/// 노드는 IndexedDB "node" 저장소로 열려 페이지와 서비스 워커가 같은 큐를 본다
+#[cfg(target_arch = "wasm32")]
+mod txq_exports {
+    use super::{BrowserNode, BrowserNodeType, IndexedDbStorage, P2PMessage};
+    use std::cell::RefCell;
+
+    extern "C" {
+        fn chain_nonce(addr: *const u8, addr_len: usize) -> u64;
+        /// 0 = 전송, 그 외 = 보류
+        fn tx_submit(json: *const u8, json_len: usize) -> i32;
+    }
+
+    thread_local! {
+        static NODE: RefCell<Option<BrowserNode>> = const { RefCell::new(None) };
+    }
+
+    fn with_node<R>(f: impl FnOnce(&mut BrowserNode) -> R) -> R {
+        NODE.with(|cell| {
+            let mut slot = cell.borrow_mut();
+            let node = slot.get_or_insert_with(|| {
+                BrowserNode::open("browser", BrowserNodeType::Light, Box::new(IndexedDbStorage::open("node")))
+            });
+            f(node)
+        })
+    }
+
+    unsafe fn str_arg<'a>(ptr: *const u8, len: usize) -> &'a str {
+        std::str::from_utf8(std::slice::from_raw_parts(ptr, len)).unwrap_or("")
+    }
+
+    fn submit(msg: &P2PMessage) -> Result<(), String> {
+        let P2PMessage::TxSubmit { from, to, amount, nonce, memo } = msg else {
+            return Err("트랜잭션 아님".to_string());
+        };
+        let json = crate::output::JsonObject::new()
+            .str("from", from).str("to", to).int("amount", *amount as i64)
+            .int("nonce", *nonce as i64).str("memo", memo).build();
+        match unsafe { tx_submit(json.as_ptr(), json.len()) } {
+            0 => Ok(()),
+            code => Err(format!("전송 보류 ({})", code)),
+        }
+    }
+
+    /// 1 = 즉시 전송, 0 = 큐에 저장
+    #[no_mangle]
+    pub unsafe extern "C" fn txq_enqueue(
+        from: *const u8, from_len: usize, to: *const u8, to_len: usize,
+        amount: u64, memo: *const u8, memo_len: usize,
+    ) -> i32 {
+        let (from, to, memo) = (str_arg(from, from_len), str_arg(to, to_len), str_arg(memo, memo_len));
+        with_node(|node| match node.submit_tx(from, to, amount, memo) {
+            Some(msg) if submit(&msg).is_ok() => 1,
+            Some(_) => {
+                // 즉시 전송이 막히면 오프라인으로 보고 큐로 돌림
+                node.go_offline();
+                node.submit_tx(from, to, amount, memo);
+                0
+            }
+            None => 0,
+        })
+    }
+
+    /// 대기 항목 JSON 길이 — out_cap 이하면 out에 복사
+    #[no_mangle]
+    pub unsafe extern "C" fn txq_pending(out: *mut u8, out_cap: usize) -> i32 {
+        let json = with_node(|node| node.tx_queue.pending_json());
+        if json.len() <= out_cap {
+            std::ptr::copy_nonoverlapping(json.as_ptr(), out, json.len());
+        }
+        json.len() as i32
+    }
+
+    /// 전송된 개수
+    #[no_mangle]
+    pub extern "C" fn txq_replay() -> i32 {
+        with_node(|node| {
+            let nonce = |addr: &str| unsafe { chain_nonce(addr.as_ptr(), addr.len()) };
+            node.reconnect(nonce, submit).submitted as i32
+        })
+    }
+
+    #[no_mangle]
+    pub extern "C" fn txq_set_online(online: i32) {
+        with_node(|node| {
+            if online == 0 { node.go_offline() } else { node.online = true }
+        })
+    }
+}
+
 // ── 브라우저 노드 ──
 
 #[derive(Debug)]
@@ -188,8 +765,16 @@ pub struct BrowserNode {
     pub state_version: u64,
     pub pending_votes: Vec<PendingVote>,
     pub blocks: Vec<Block>,
-    pub message_log: Vec<P2PMessage>,
+    pub message_log: RingLog<P2PMessage>,
     pub stats: NodeStats,
+    /// 지난 세션까지 포함한 블록 헤더 (저장소에서 복원)
+    pub header_cache: Vec<BlockHeader>,
+    pub tx_queue: OfflineTxQueue,
+    /// false면 트랜잭션은 큐에만 쌓인다
+    pub online: bool,
+    /// 있으면 ID는 공개 키에서 유도되고, 피어에게도 서명을 요구한다
+    identity: Option<NodeIdentity>,
+    storage: Option<Box<dyn NodeStorage>>,
 }
 
 #[derive(Debug, Clone)]
@@ -199,6 +784,8 @@ pub struct PeerInfo {
     pub latency_ms: u32,
     pub last_seen: u64,
     pub synced: bool,
+    /// 서명된 핸드셰이크로 확인된 신원 키
+    pub public_key: Option<Key>,
 }
 
 #[derive(Debug, Clone)]
@@ -219,6 +806,80 @@ pub struct Block {
     pub timestamp: u64,
 }
 
+/// 블록 헤더 — 트랜잭션 본문 없이 저장되는 체인 요약
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockHeader {
+    pub id: u64,
+    pub proposer: String,
+    pub finalized: bool,
+    pub trit_state: i8,
+    pub timestamp: u64,
+    pub tx_count: usize,
+}
+
+impl BlockHeader {
+    fn of(block: &Block) -> Self {
+        Self {
+            id: block.id,
+            proposer: block.proposer.clone(),
+            finalized: block.finalized,
+            trit_state: block.trit_state,
+            timestamp: block.timestamp,
+            tx_count: block.transactions.len(),
+        }
+    }
+
+    fn encode(&self) -> String {
+        format!("{}|{}|{}|{}|{}", self.proposer, self.finalized, self.trit_state, self.timestamp, self.tx_count)
+    }
+
+    fn decode(id: u64, value: &str) -> Option<Self> {
+        let f: Vec<&str> = value.split('|').collect();
+        if f.len() != 5 {
+            return None;
+        }
+        Some(Self {
+            id,
+            proposer: f[0].to_string(),
+            finalized: f[1].parse().ok()?,
+            trit_state: f[2].parse().ok()?,
+            timestamp: f[3].parse().ok()?,
+            tx_count: f[4].parse().ok()?,
+        })
+    }
+}
+
+impl PeerInfo {
+    fn new(id: &str, node_type: BrowserNodeType) -> Self {
+        Self { id: id.to_string(), node_type, latency_ms: 0, last_seen: now_ms(), synced: false, public_key: None }
+    }
+
+    fn encode(&self) -> String {
+        let key = self.public_key.map(|k| crate::crypto::to_hex(&k)).unwrap_or_default();
+        format!("{}|{}|{}|{}|{}", self.node_type, self.latency_ms, self.last_seen, self.synced, key)
+    }
+
+    /// 키 칸이 없는 이전 형식(4칸)도 읽는다
+    fn decode(id: &str, value: &str) -> Option<Self> {
+        let f: Vec<&str> = value.split('|').collect();
+        if f.len() != 4 && f.len() != 5 {
+            return None;
+        }
+        let public_key = match f.get(4) {
+            Some(hex) if !hex.is_empty() => Some(identity::parse_public(hex).ok()?),
+            _ => None,
+        };
+        Some(Self {
+            id: id.to_string(),
+            node_type: f[0].parse().ok()?,
+            latency_ms: f[1].parse().ok()?,
+            last_seen: f[2].parse().ok()?,
+            synced: f[3].parse().ok()?,
+            public_key,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct NodeStats {
     pub messages_sent: u64,
@@ -228,6 +889,11 @@ pub struct NodeStats {
     pub blocks_finalized: u64,
     pub uptime_ms: u64,
     pub bytes_transferred: u64,
+    pub storage_errors: u64,
+    /// 서명 · ID 검증에 실패해 버린 메시지
+    pub rejected_messages: u64,
+    /// 머클 증명으로 확인한 트랜잭션
+    pub proofs_verified: u64,
 }
 
 impl BrowserNode {
@@ -240,38 +906,243 @@ impl BrowserNode {
             state_version: 0,
             pending_votes: Vec::new(),
             blocks: Vec::new(),
-            message_log: Vec::new(),
+            message_log: RingLog::new(MESSAGE_LOG_CAPACITY),
             stats: NodeStats::default(),
+            header_cache: Vec::new(),
+            tx_queue: OfflineTxQueue::new(),
+            online: true,
+            identity: None,
+            storage: None,
+        }
+    }
+
+    /// 신원 키로 만든 노드 — ID는 공개 키에서 유도
+    pub fn with_identity(identity: NodeIdentity, node_type: BrowserNodeType) -> Self {
+        let mut node = Self::new(&identity.node_id(), node_type);
+        node.identity = Some(identity);
+        node
+    }
+
+    pub fn with_message_log_capacity(mut self, capacity: usize) -> Self {
+        self.message_log.set_capacity(capacity);
+        self
+    }
+
+    /// 저장소(IndexedDB)의 신원 키로 열기 — 없으면 만들어 저장하므로 새로고침해도 같은 ID
+    pub fn open_with_identity(node_type: BrowserNodeType, mut storage: Box<dyn NodeStorage>) -> Result<Self, String> {
+        let key = format!("key:{}", identity::STORAGE_KEY);
+        let identity = match storage.get(&key) {
+            Some(hex) => NodeIdentity::from_hex(&hex).ok_or("저장된 신원 키 손상")?,
+            None => {
+                let identity = NodeIdentity::generate();
+                storage.put(&key, &crate::crypto::to_hex(identity.seed()))?;
+                storage.flush()?;
+                identity
+            }
+        };
+        let mut node = Self::open(&identity.node_id(), node_type, storage);
+        node.identity = Some(identity);
+        Ok(node)
+    }
+
+    pub fn identity(&self) -> Option<&NodeIdentity> {
+        self.identity.as_ref()
+    }
+
+    fn sign(&self, payload: &[u8]) -> Option<String> {
+        self.identity.as_ref().map(|id| crate::crypto::to_hex(&id.sign(payload)))
+    }
+
+    // ── 영속화 ──
+
+    /// 저장소에서 상태 · 피어 테이블 · 헤더 캐시를 복원하고 이후 변경을 기록
+    pub fn open(id: &str, node_type: BrowserNodeType, storage: Box<dyn NodeStorage>) -> Self {
+        let mut node = Self::new(id, node_type);
+        for key in storage.keys("state:") {
+            if let Some(v) = storage.get(&key) {
+                node.state.insert(key["state:".len()..].to_string(), v);
+            }
+        }
+        node.state_version = storage.get("meta:state_version").and_then(|v| v.parse().ok()).unwrap_or(0);
+        for key in storage.keys("peer:") {
+            let id = &key["peer:".len()..];
+            if let Some(peer) = storage.get(&key).and_then(|v| PeerInfo::decode(id, &v)) {
+                node.connected_peers.push(peer);
+            }
+        }
+        for key in storage.keys("header:") {
+            let header = key["header:".len()..].parse().ok()
+                .and_then(|id| storage.get(&key).and_then(|v| BlockHeader::decode(id, &v)));
+            if let Some(h) = header {
+                node.header_cache.push(h);
+            }
+        }
+        for key in storage.keys("txq:") {
+            let tx = key["txq:".len()..].parse().ok()
+                .and_then(|seq| storage.get(&key).and_then(|v| QueuedTx::decode(seq, &v)));
+            if let Some(tx) = tx {
+                node.tx_queue.restore(tx);
+            }
+        }
+        node.storage = Some(storage);
+        node
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        self.storage.is_some()
+    }
+
+    /// 현재 상태 전체를 저장소에 기록
+    pub fn persist(&mut self) -> Result<(), String> {
+        let Some(storage) = self.storage.as_mut() else {
+            return Ok(());
+        };
+        for (k, v) in &self.state {
+            storage.put(&format!("state:{}", k), v)?;
+        }
+        storage.put("meta:state_version", &self.state_version.to_string())?;
+        for peer in &self.connected_peers {
+            storage.put(&format!("peer:{}", peer.id), &peer.encode())?;
+        }
+        for h in &self.header_cache {
+            storage.put(&format!("header:{:010}", h.id), &h.encode())?;
+        }
+        for tx in &self.tx_queue.entries {
+            storage.put(&format!("txq:{:010}", tx.seq), &tx.encode())?;
         }
+        storage.flush()
+    }
+
+    /// 키 자료 저장 (hex)
+    pub fn save_key(&mut self, name: &str, key: &[u8]) -> Result<(), String> {
+        match self.storage.as_mut() {
+            Some(s) => s.put(&format!("key:{}", name), &crate::crypto::to_hex(key)),
+            None => Err("저장소 없음".to_string()),
+        }
+    }
+
+    pub fn load_key(&self, name: &str) -> Option<Vec<u8>> {
+        self.storage.as_ref()?.get(&format!("key:{}", name)).and_then(|v| crate::crypto::from_hex(&v))
+    }
+
+    /// 쓰기 실패는 노드를 멈추지 않고 집계만 한다
+    fn store(&mut self, key: &str, value: &str) {
+        if let Some(s) = self.storage.as_mut() {
+            if s.put(key, value).is_err() {
+                self.stats.storage_errors += 1;
+            }
+        }
+    }
+
+    fn store_peer(&mut self, idx: usize) {
+        let (key, value) = {
+            let peer = &self.connected_peers[idx];
+            (format!("peer:{}", peer.id), peer.encode())
+        };
+        self.store(&key, &value);
+    }
+
+    fn store_header(&mut self, block_idx: usize) {
+        let header = BlockHeader::of(&self.blocks[block_idx]);
+        let value = header.encode();
+        let key = format!("header:{:010}", header.id);
+        match self.header_cache.iter_mut().find(|h| h.id == header.id) {
+            Some(h) => *h = header,
+            None => self.header_cache.push(header),
+        }
+        self.store(&key, &value);
     }
 
     // ── 피어 연결 ──
 
     pub fn connect(&mut self, peer_id: &str, peer_type: BrowserNodeType) -> P2PMessage {
-        self.connected_peers.push(PeerInfo {
-            id: peer_id.to_string(),
-            node_type: peer_type,
-            latency_ms: 0,
-            last_seen: now_ms(),
-            synced: false,
-        });
+        if !self.connected_peers.iter().any(|p| p.id == peer_id) {
+            self.connected_peers.push(PeerInfo::new(peer_id, peer_type));
+            self.store_peer(self.connected_peers.len() - 1);
+        }
         self.stats.messages_sent += 1;
+        self.handshake()
+    }
+
+    /// 자기 소개 — 신원 키가 있으면 공개 키와 서명을 싣는다
+    pub fn handshake(&self) -> P2PMessage {
+        let version = "0.4.0".to_string();
+        let payload = identity::handshake_payload(&self.id, &self.node_type.to_string(), &version);
         P2PMessage::Handshake {
             node_id: self.id.clone(),
             node_type: self.node_type.clone(),
-            version: "0.4.0".to_string(),
+            version,
+            public_key: self.identity.as_ref().map(NodeIdentity::public_hex),
+            signature: self.sign(&payload),
+        }
+    }
+
+    /// 핸드셰이크 수신 — 키가 있으면 ID 유도 · 서명을 검사하고 키를 피어 표에 고정
+    /// 내가 신원 키를 가진 노드면 서명 없는 핸드셰이크는 거부
+    pub fn accept_handshake(&mut self, msg: &P2PMessage) -> Result<(), IdentityError> {
+        let P2PMessage::Handshake { node_id, node_type, version, public_key, signature } = msg else {
+            return Err(IdentityError::Malformed("핸드셰이크 아님".into()));
+        };
+        let checked = match (public_key, signature) {
+            (Some(pk), Some(sig)) => identity::parse_public(pk).and_then(|key| {
+                let payload = identity::handshake_payload(node_id, &node_type.to_string(), version);
+                identity::verify_claim(node_id, &key, &payload, &identity::parse_signature(sig)?).map(|_| Some(key))
+            }),
+            _ if self.identity.is_some() => Err(IdentityError::MissingSignature(node_id.clone())),
+            _ => Ok(None),
+        };
+        let key = checked.inspect_err(|_| self.stats.rejected_messages += 1)?;
+        // 이미 다른 키로 확인된 피어를 가로채려는 시도
+        if let Some(peer) = self.connected_peers.iter().find(|p| p.id == *node_id) {
+            if peer.public_key.is_some() && peer.public_key != key {
+                self.stats.rejected_messages += 1;
+                return Err(IdentityError::BadSignature(node_id.clone()));
+            }
+        }
+        self.handle_handshake(node_id, node_type.clone());
+        if let Some(idx) = self.connected_peers.iter().position(|p| p.id == *node_id) {
+            if key.is_some() {
+                self.connected_peers[idx].public_key = key;
+                self.store_peer(idx);
+            }
         }
+        Ok(())
+    }
+
+    /// 투표 서명 검사 — 핸드셰이크로 알린 키로만 확인
+    fn check_vote(&mut self, voter: &str, payload: &[u8], signature: &Option<String>) -> Result<(), IdentityError> {
+        let key = self.connected_peers.iter().find(|p| p.id == voter).and_then(|p| p.public_key);
+        let result = match (key, signature) {
+            (Some(key), Some(sig)) => identity::parse_signature(sig).and_then(|sig| identity::verify(voter, &key, payload, &sig)),
+            (Some(_), None) => Err(IdentityError::MissingSignature(voter.to_string())),
+            (None, _) if self.identity.is_some() => Err(IdentityError::UnknownKey(voter.to_string())),
+            (None, _) => Ok(()),
+        };
+        result.inspect_err(|_| self.stats.rejected_messages += 1)
+    }
+
+    /// 서명 검사 후 블록 투표 반영
+    pub fn accept_block_vote(&mut self, msg: &P2PMessage) -> Result<(), IdentityError> {
+        let P2PMessage::BlockVote { block_id, voter, vote, signature } = msg else {
+            return Err(IdentityError::Malformed("블록 투표 아님".into()));
+        };
+        self.check_vote(voter, &identity::vote_payload("block", *block_id, voter, *vote), signature)?;
+        self.receive_block_vote(*block_id, voter, *vote);
+        Ok(())
     }
 
     pub fn handle_handshake(&mut self, node_id: &str, node_type: BrowserNodeType) {
-        if !self.connected_peers.iter().any(|p| p.id == node_id) {
-            self.connected_peers.push(PeerInfo {
-                id: node_id.to_string(),
-                node_type,
-                latency_ms: 0,
-                last_seen: now_ms(),
-                synced: false,
-            });
+        match self.connected_peers.iter().position(|p| p.id == node_id) {
+            Some(idx) => {
+                // 지난 세션에서 복원된 피어 — 재접속 시각만 갱신
+                self.connected_peers[idx].last_seen = now_ms();
+                self.connected_peers[idx].node_type = node_type;
+                self.store_peer(idx);
+            }
+            None => {
+                self.connected_peers.push(PeerInfo::new(node_id, node_type));
+                self.store_peer(self.connected_peers.len() - 1);
+            }
         }
         self.stats.messages_received += 1;
     }
@@ -293,6 +1164,7 @@ impl BrowserNode {
             node_id: self.id.clone(),
             proposal_id,
             vote: 1,
+            signature: self.sign(&identity::vote_payload("proposal", proposal_id, &self.id, 1)),
         }
     }
 
@@ -311,16 +1183,23 @@ impl BrowserNode {
             node_id: self.id.clone(),
             proposal_id,
             vote,
+            signature: self.sign(&identity::vote_payload("proposal", proposal_id, &self.id, vote)),
         }
     }
 
-    pub fn receive_vote(&mut self, proposal_id: u64, voter: &str, vote: i8) {
-        if let Some(pv) = self.pending_votes.iter_mut().find(|v| v.proposal_id == proposal_id) {
+    /// 서명 검사 후 제안 투표 반영
+    pub fn receive_vote(&mut self, msg: &P2PMessage) -> Result<(), IdentityError> {
+        let P2PMessage::TritVote { node_id: voter, proposal_id, vote, signature } = msg else {
+            return Err(IdentityError::Malformed("제안 투표 아님".into()));
+        };
+        self.stats.messages_received += 1;
+        self.check_vote(voter, &identity::vote_payload("proposal", *proposal_id, voter, *vote), signature)?;
+        if let Some(pv) = self.pending_votes.iter_mut().find(|v| v.proposal_id == *proposal_id) {
             if !pv.votes.iter().any(|(id, _)| id == voter) {
-                pv.votes.push((voter.to_string(), vote));
+                pv.votes.push((voter.to_string(), *vote));
             }
         }
-        self.stats.messages_received += 1;
+        Ok(())
     }
 
     pub fn tally_vote(&self, proposal_id: u64) -> (i8, f64) {
@@ -339,7 +1218,8 @@ impl BrowserNode {
     // ── 블록 제안 ──
 
     pub fn propose_block(&mut self, transactions: Vec<String>) -> P2PMessage {
-        let block_id = self.blocks.len() as u64 + 1;
+        let cached = self.header_cache.iter().map(|h| h.id).max().unwrap_or(0);
+        let block_id = cached.max(self.blocks.len() as u64) + 1;
         self.blocks.push(Block {
             id: block_id,
             transactions: transactions.clone(),
@@ -349,6 +1229,7 @@ impl BrowserNode {
             trit_state: 0,
             timestamp: now_ms(),
         });
+        self.store_header(self.blocks.len() - 1);
         self.stats.blocks_proposed += 1;
         P2PMessage::BlockProposal {
             block_id,
@@ -365,6 +1246,7 @@ impl BrowserNode {
             block_id,
             voter: self.id.clone(),
             vote,
+            signature: self.sign(&identity::vote_payload("block", block_id, &self.id, vote)),
         }
     }
 
@@ -377,24 +1259,91 @@ impl BrowserNode {
     }
 
     pub fn finalize_block(&mut self, block_id: u64, quorum: usize) -> bool {
-        if let Some(block) = self.blocks.iter_mut().find(|b| b.id == block_id) {
-            let p = block.votes.iter().filter(|(_, v)| *v > 0).count();
-            let t = block.votes.iter().filter(|(_, v)| *v < 0).count();
-            if p >= quorum {
-                block.finalized = true;
-                block.trit_state = 1;
-                self.stats.blocks_finalized += 1;
-                true
-            } else if t >= quorum {
-                block.finalized = true;
-                block.trit_state = -1;
-                false
-            } else {
-                false
-            }
-        } else {
+        let Some(idx) = self.blocks.iter().position(|b| b.id == block_id) else {
+            return false;
+        };
+        let block = &mut self.blocks[idx];
+        let p = block.votes.iter().filter(|(_, v)| *v > 0).count();
+        let t = block.votes.iter().filter(|(_, v)| *v < 0).count();
+        let accepted = if p >= quorum {
+            block.finalized = true;
+            block.trit_state = 1;
+            self.stats.blocks_finalized += 1;
+            true
+        } else if t >= quorum {
+            block.finalized = true;
+            block.trit_state = -1;
             false
+        } else {
+            return false;
+        };
+        self.store_header(idx);
+        accepted
+    }
+
+    // ── 트랜잭션 (오프라인 큐) ──
+
+    /// 온라인이면 바로 전송 메시지, 오프라인이면 큐에 저장하고 None
+    pub fn submit_tx(&mut self, from: &str, to: &str, amount: u64, memo: &str) -> Option<P2PMessage> {
+        // 같은 발신자의 대기 항목이 있으면 nonce 순서를 지키려고 온라인이어도 뒤에 줄 선다
+        if self.online && !self.tx_queue.has_pending(from) {
+            let nonce = self.tx_queue.take_nonce(from);
+            self.stats.messages_sent += 1;
+            return Some(P2PMessage::TxSubmit {
+                from: from.to_string(),
+                to: to.to_string(),
+                amount,
+                nonce,
+                memo: memo.to_string(),
+            });
+        }
+        let tx = self.tx_queue.enqueue(from, to, amount, memo).clone();
+        self.store(&format!("txq:{:010}", tx.seq), &tx.encode());
+        None
+    }
+
+    /// 라이트 노드의 트랜잭션 확인 — 믿는 헤더 루트와 증명만 있으면 된다 (틀리면 거부로 집계)
+    pub fn verify_inclusion(&mut self, proof: &crate::chain::MerkleProof, trusted_root: &str) -> bool {
+        let ok = proof.merkle_root == trusted_root && crate::chain::verify_proof(proof);
+        if ok {
+            self.stats.proofs_verified += 1;
+        } else {
+            self.stats.rejected_messages += 1;
+        }
+        ok
+    }
+
+    pub fn pending_txs(&self) -> Vec<&QueuedTx> {
+        self.tx_queue.pending()
+    }
+
+    pub fn go_offline(&mut self) {
+        self.online = false;
+    }
+
+    /// 재접속 — 큐를 체인 nonce에 맞춰 재전송하고 확정된 항목은 저장소에서 지움
+    pub fn reconnect<N, S>(&mut self, chain_nonce: N, mut submit: S) -> ReplayReport
+    where
+        N: Fn(&str) -> u64,
+        S: FnMut(&P2PMessage) -> Result<(), String>,
+    {
+        self.online = true;
+        let report = self.tx_queue.replay(chain_nonce, |tx| submit(&tx.to_message()));
+        self.stats.messages_sent += report.submitted as u64;
+        let pending: Vec<(String, String)> = self.tx_queue.pending().iter()
+            .map(|t| (format!("txq:{:010}", t.seq), t.encode()))
+            .collect();
+        for (k, v) in pending {
+            self.store(&k, &v);
+        }
+        for seq in self.tx_queue.prune_settled() {
+            if let Some(s) = self.storage.as_mut() {
+                if s.delete(&format!("txq:{:010}", seq)).is_err() {
+                    self.stats.storage_errors += 1;
+                }
+            }
         }
+        report
     }
 
     // ── 상태 ──
@@ -402,6 +1351,8 @@ impl BrowserNode {
     pub fn set_state(&mut self, key: &str, value: &str) {
         self.state_version += 1;
         self.state.insert(key.to_string(), value.to_string());
+        self.store(&format!("state:{}", key), value);
+        self.store("meta:state_version", &self.state_version.to_string());
     }
 
     pub fn get_state(&self, key: &str) -> Option<&String> {
@@ -423,88 +1374,393 @@ impl BrowserNode {
     }
 }
 
+// ── 링크 모델 (지연 · 손실 · 지터) ──
+
+/// 한 방향 전송 지연 분포
+#[derive(Debug, Clone, PartialEq)]
+pub enum LatencyDist {
+    Fixed(u64),
+    Uniform { min_ms: u64, max_ms: u64 },
+    /// 정규분포 (Box–Muller) — 0 미만은 0
+    Normal { mean_ms: f64, stddev_ms: f64 },
+}
+
+impl LatencyDist {
+    fn sample(&self, rng: &mut Gen) -> f64 {
+        match *self {
+            LatencyDist::Fixed(ms) => ms as f64,
+            LatencyDist::Uniform { min_ms, max_ms } => rng.range(min_ms as i64, max_ms.max(min_ms) as i64) as f64,
+            LatencyDist::Normal { mean_ms, stddev_ms } => {
+                let u1 = unit_f64(rng).max(f64::MIN_POSITIVE);
+                let u2 = unit_f64(rng);
+                mean_ms + stddev_ms * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+            }
+        }
+    }
+}
+
+/// [0, 1)
+fn unit_f64(rng: &mut Gen) -> f64 {
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// 링크 특성 — 기본값은 즉시·무손실 (기존 시뮬레이션과 같음)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkModel {
+    pub latency: LatencyDist,
+    /// ±jitter_ms 균등 흔들림
+    pub jitter_ms: u64,
+    /// 패킷 손실률 (%)
+    pub loss_pct: f64,
+}
+
+impl Default for LinkModel {
+    fn default() -> Self {
+        Self::instant()
+    }
+}
+
+impl LinkModel {
+    pub fn instant() -> Self {
+        Self { latency: LatencyDist::Fixed(0), jitter_ms: 0, loss_pct: 0.0 }
+    }
+
+    pub fn lan() -> Self {
+        Self { latency: LatencyDist::Normal { mean_ms: 2.0, stddev_ms: 0.5 }, jitter_ms: 1, loss_pct: 0.0 }
+    }
+
+    /// 같은 대륙 WebRTC (STUN 직결)
+    pub fn webrtc_regional() -> Self {
+        Self { latency: LatencyDist::Normal { mean_ms: 40.0, stddev_ms: 10.0 }, jitter_ms: 8, loss_pct: 0.5 }
+    }
+
+    /// 대륙 간 WebRTC (TURN 중계 포함)
+    pub fn webrtc_global() -> Self {
+        Self { latency: LatencyDist::Normal { mean_ms: 150.0, stddev_ms: 40.0 }, jitter_ms: 25, loss_pct: 1.5 }
+    }
+
+    /// 모바일 셀룰러
+    pub fn mobile() -> Self {
+        Self { latency: LatencyDist::Uniform { min_ms: 80, max_ms: 400 }, jitter_ms: 60, loss_pct: 5.0 }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "instant" => Some(Self::instant()),
+            "lan" => Some(Self::lan()),
+            "regional" => Some(Self::webrtc_regional()),
+            "global" => Some(Self::webrtc_global()),
+            "mobile" => Some(Self::mobile()),
+            _ => None,
+        }
+    }
+
+    pub fn with_loss(mut self, loss_pct: f64) -> Self {
+        self.loss_pct = loss_pct.clamp(0.0, 100.0);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter_ms: u64) -> Self {
+        self.jitter_ms = jitter_ms;
+        self
+    }
+
+    /// 한 메시지의 도착 지연 — None이면 손실
+    pub fn sample(&self, rng: &mut Gen) -> Option<u64> {
+        if self.loss_pct > 0.0 && unit_f64(rng) * 100.0 < self.loss_pct {
+            return None;
+        }
+        let jitter = if self.jitter_ms > 0 { rng.range(-(self.jitter_ms as i64), self.jitter_ms as i64) as f64 } else { 0.0 };
+        Some((self.latency.sample(rng) + jitter).max(0.0).round() as u64)
+    }
+}
+
+/// 시간 모델이 적용된 합의 한 라운드 결과 (시각은 제안 시점 기준 ms)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsensusRound {
+    pub block_id: u64,
+    pub finalized: bool,
+    pub state: i8,
+    /// 제안자가 정족수를 모은 시각
+    pub finality_ms: Option<u64>,
+    /// 노드별 확정 시각 (확정 통지 도착) — 못 받으면 None
+    pub node_finality: Vec<(String, Option<u64>)>,
+    pub messages_sent: usize,
+    pub messages_lost: usize,
+    /// 투표 타임아웃 뒤에 도착해 버려진 투표
+    pub late_votes: usize,
+    /// 서명 검증에 실패해 버려진 투표
+    pub rejected_votes: usize,
+}
+
+impl ConsensusRound {
+    /// 모든 노드가 확정을 안 시각
+    pub fn full_finality_ms(&self) -> Option<u64> {
+        self.node_finality.iter().map(|(_, t)| *t).collect::<Option<Vec<_>>>()?.into_iter().max()
+    }
+}
+
+/// 여러 라운드의 확정 시간 분포
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinalityStats {
+    pub rounds: usize,
+    pub finalized: usize,
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+    pub lost_pct: f64,
+}
+
+impl FinalityStats {
+    pub fn finality_rate(&self) -> f64 {
+        if self.rounds == 0 { 0.0 } else { self.finalized as f64 / self.rounds as f64 }
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let opt = |o: JsonObject, k: &str, v: Option<u64>| match v {
+            Some(ms) => o.int(k, ms as i64),
+            None => o.raw(k, "null".into()),
+        };
+        let obj = JsonObject::new()
+            .int("rounds", self.rounds as i64)
+            .int("finalized", self.finalized as i64)
+            .float("lost_pct", self.lost_pct);
+        let obj = opt(obj, "p50_ms", self.p50_ms);
+        let obj = opt(obj, "p95_ms", self.p95_ms);
+        opt(obj, "max_ms", self.max_ms)
+    }
+}
+
 // ── 브라우저 네트워크 시뮬레이터 ──
 
+/// 기본 투표 타임아웃 — 이 안에 정족수가 모이지 않으면 보류(O)
+pub const DEFAULT_VOTE_TIMEOUT_MS: u64 = 2_000;
+pub const DEFAULT_SIM_SEED: u64 = 0x3_3333;
+
 pub struct BrowserNetwork {
     pub nodes: Vec<BrowserNode>,
+    /// 재정의가 없는 모든 링크의 모델
+    pub link_model: LinkModel,
+    /// 노드 쌍별 재정의 (양방향)
+    links: HashMap<(String, String), LinkModel>,
+    pub vote_timeout_ms: u64,
+    /// 추가되는 노드의 메시지 로그 상한 (브라우저 메모리)
+    pub message_log_capacity: usize,
+    rng: Gen,
 }
 
 impl BrowserNetwork {
     pub fn new() -> Self {
-        Self { nodes: Vec::new() }
+        Self {
+            nodes: Vec::new(),
+            link_model: LinkModel::instant(),
+            links: HashMap::new(),
+            vote_timeout_ms: DEFAULT_VOTE_TIMEOUT_MS,
+            message_log_capacity: MESSAGE_LOG_CAPACITY,
+            rng: Gen::new(DEFAULT_SIM_SEED),
+        }
+    }
+
+    pub fn with_link_model(mut self, model: LinkModel) -> Self {
+        self.link_model = model;
+        self
+    }
+
+    pub fn with_vote_timeout(mut self, ms: u64) -> Self {
+        self.vote_timeout_ms = ms;
+        self
+    }
+
+    pub fn with_message_log_capacity(mut self, capacity: usize) -> Self {
+        self.message_log_capacity = capacity;
+        self
+    }
+
+    /// 같은 시드 → 같은 지연·손실 순서 (재현 가능)
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Gen::new(seed);
+        self
+    }
+
+    pub fn set_link(&mut self, a: &str, b: &str, model: LinkModel) {
+        self.links.insert(link_key(a, b), model);
+    }
+
+    pub fn link(&self, a: &str, b: &str) -> &LinkModel {
+        self.links.get(&link_key(a, b)).unwrap_or(&self.link_model)
+    }
+
+    fn send(&mut self, from: usize, to: usize, sent: &mut usize, lost: &mut usize) -> Option<u64> {
+        *sent += 1;
+        let model = self.link(&self.nodes[from].id, &self.nodes[to].id).clone();
+        let delay = model.sample(&mut self.rng);
+        match delay {
+            Some(ms) => {
+                let from_id = self.nodes[from].id.clone();
+                if let Some(peer) = self.nodes[to].connected_peers.iter_mut().find(|p| p.id == from_id) {
+                    peer.latency_ms = ms.min(u32::MAX as u64) as u32;
+                }
+            }
+            None => *lost += 1,
+        }
+        delay
     }
 
     pub fn add_node(&mut self, id: &str, node_type: BrowserNodeType) {
-        self.nodes.push(BrowserNode::new(id, node_type));
+        self.nodes.push(BrowserNode::new(id, node_type).with_message_log_capacity(self.message_log_capacity));
+    }
+
+    /// 신원 키를 가진 노드 추가 — ID는 키에서 유도된다
+    pub fn add_identified_node(&mut self, identity: NodeIdentity, node_type: BrowserNodeType) -> String {
+        let node = BrowserNode::with_identity(identity, node_type).with_message_log_capacity(self.message_log_capacity);
+        let id = node.id.clone();
+        self.nodes.push(node);
+        id
     }
 
+    /// 모든 쌍이 핸드셰이크를 주고받는다 — 검증에 실패한 상대는 키 없이 남아 투표가 거부된다
     pub fn connect_all(&mut self) {
-        let ids: Vec<(String, BrowserNodeType)> = self.nodes.iter()
-            .map(|n| (n.id.clone(), n.node_type.clone()))
-            .collect();
+        let handshakes: Vec<P2PMessage> = self.nodes.iter().map(BrowserNode::handshake).collect();
         for i in 0..self.nodes.len() {
-            for (id, nt) in &ids {
-                if *id != self.nodes[i].id {
-                    self.nodes[i].connect(id, nt.clone());
+            for (j, hs) in handshakes.iter().enumerate() {
+                let P2PMessage::Handshake { node_id, node_type, .. } = hs else { continue };
+                if i != j {
+                    self.nodes[i].connect(node_id, node_type.clone());
+                    let _ = self.nodes[i].accept_handshake(hs);
                 }
             }
         }
     }
 
     pub fn simulate_consensus(&mut self, transactions: Vec<String>) -> (bool, i8) {
-        if self.nodes.is_empty() { return (false, 0); }
+        match self.simulate_round(transactions) {
+            Some(round) => (round.finalized, round.state),
+            None => (false, 0),
+        }
+    }
+
+    /// 노드 0이 제안 → 각 노드는 제안 도착 시 투표 → 제안자가 타임아웃 안에
+    /// 정족수를 모으면 확정하고 투표 묶음과 함께 확정 통지를 보낸다
+    pub fn simulate_round(&mut self, transactions: Vec<String>) -> Option<ConsensusRound> {
+        if self.nodes.is_empty() { return None; }
+        let n = self.nodes.len();
+        let (mut sent, mut lost, mut late, mut rejected) = (0, 0, 0, 0);
+        let timeout = self.vote_timeout_ms;
 
         // 노드 0이 블록 제안
         let _proposal = self.nodes[0].propose_block(transactions.clone());
         let block_id = self.nodes[0].blocks.last().map(|b| b.id).unwrap_or(0);
         let proposer_id = self.nodes[0].id.clone();
-
-        // 나머지 노드도 블록 기록
-        for i in 1..self.nodes.len() {
-            self.nodes[i].blocks.push(Block {
-                id: block_id,
-                transactions: transactions.clone(),
-                proposer: proposer_id.clone(),
-                votes: Vec::new(),
-                finalized: false,
-                trit_state: 0,
-                timestamp: now_ms(),
-            });
+        let new_block = |ts| Block {
+            id: block_id,
+            transactions: transactions.clone(),
+            proposer: proposer_id.clone(),
+            votes: Vec::new(),
+            finalized: false,
+            trit_state: 0,
+            timestamp: ts,
+        };
+
+        // 제안 전파 — 도착한 노드만 블록을 기록하고 투표
+        let mut arrival: Vec<Option<u64>> = vec![Some(0); n];
+        for (i, slot) in arrival.iter_mut().enumerate().skip(1) {
+            *slot = self.send(0, i, &mut sent, &mut lost);
+            if slot.is_some() {
+                self.nodes[i].blocks.push(new_block(now_ms()));
+            }
         }
 
-        // 모든 노드가 투표
-        let node0_id = self.nodes[0].id.clone();
-        let mut all_votes: Vec<(String, i8)> = vec![(node0_id, 1)];
-        for i in 1..self.nodes.len() {
+        // 투표 전파 — (도착 시각, 투표, 서명된 메시지) 를 받는 노드별로
+        let mut inbox: Vec<Vec<(u64, i8, P2PMessage)>> = vec![Vec::new(); n];
+        for (i, at) in arrival.iter().enumerate().skip(1) {
+            let Some(at) = *at else { continue };
             let vote = if self.nodes[i].node_type == BrowserNodeType::Observer { 0 } else { 1 };
-            let _msg = self.nodes[i].vote_block(block_id, vote);
-            let nid = self.nodes[i].id.clone();
-            all_votes.push((nid, vote));
-        }
-
-        // 투표 수집
-        let votes_clone = all_votes.clone();
-        for (voter, vote) in &votes_clone {
-            for i in 0..self.nodes.len() {
-                if self.nodes[i].id != *voter {
-                    self.nodes[i].receive_block_vote(block_id, voter, *vote);
+            let msg = self.nodes[i].vote_block(block_id, vote);
+            for (j, received) in inbox.iter_mut().enumerate() {
+                if j == i { continue; }
+                if let Some(delay) = self.send(i, j, &mut sent, &mut lost) {
+                    if at + delay <= timeout {
+                        received.push((at + delay, vote, msg.clone()));
+                    } else {
+                        late += 1;
+                    }
                 }
             }
         }
+        for (j, votes) in inbox.iter_mut().enumerate() {
+            votes.sort_by_key(|(t, _, _)| *t);
+            // 검증을 통과한 투표만 남긴다
+            let node = &mut self.nodes[j];
+            let before = votes.len();
+            votes.retain(|(_, _, msg)| node.accept_block_vote(msg).is_ok());
+            rejected += before - votes.len();
+        }
 
-        // 합의 확인
-        let quorum = (self.nodes.len() / 2) + 1;
+        // 합의 확인 — 정족수째 찬성표가 도착한 시각이 확정 시각
+        let quorum = (n / 2) + 1;
         let finalized = self.nodes[0].finalize_block(block_id, quorum);
         let state = if finalized { 1 } else { 0 };
+        let finality_ms = finalized.then(|| {
+            inbox[0].iter().filter(|(_, v, _)| *v > 0).map(|(t, _, _)| *t)
+                .nth(quorum.saturating_sub(2)).unwrap_or(0)
+        });
 
-        // 결과 전파
-        if finalized {
-            for i in 1..self.nodes.len() {
+        // 결과 전파 — 확정 통지가 투표 묶음을 실어 나른다
+        let mut node_finality = vec![(self.nodes[0].id.clone(), finality_ms)];
+        let certificate = self.nodes[0].blocks.iter().find(|b| b.id == block_id)
+            .map(|b| b.votes.clone()).unwrap_or_default();
+        for i in 1..n {
+            let reached = match finality_ms {
+                Some(at) => self.send(0, i, &mut sent, &mut lost).map(|d| at + d),
+                None => None,
+            };
+            if reached.is_some() {
+                if !self.nodes[i].blocks.iter().any(|b| b.id == block_id) {
+                    self.nodes[i].blocks.push(new_block(now_ms()));
+                }
+                for (voter, vote) in &certificate {
+                    self.nodes[i].receive_block_vote(block_id, voter, *vote);
+                }
                 self.nodes[i].finalize_block(block_id, quorum);
             }
+            node_finality.push((self.nodes[i].id.clone(), reached));
         }
 
-        (finalized, state)
+        Some(ConsensusRound {
+            block_id,
+            finalized,
+            state,
+            finality_ms,
+            node_finality,
+            messages_sent: sent,
+            messages_lost: lost,
+            late_votes: late,
+            rejected_votes: rejected,
+        })
+    }
+
+    /// 여러 라운드를 돌려 확정 시간 분포를 잰다 (타임아웃 조정용)
+    pub fn measure_finality(&mut self, rounds: usize) -> FinalityStats {
+        let mut times = Vec::new();
+        let (mut sent, mut lost) = (0, 0);
+        for r in 0..rounds {
+            let Some(round) = self.simulate_round(vec![format!("sim-tx-{}", r)]) else { break };
+            sent += round.messages_sent;
+            lost += round.messages_lost;
+            if let Some(ms) = round.finality_ms {
+                times.push(ms);
+            }
+        }
+        times.sort_unstable();
+        let pct = |p: usize| (!times.is_empty()).then(|| times[(times.len() * p / 100).min(times.len() - 1)]);
+        FinalityStats {
+            rounds,
+            finalized: times.len(),
+            p50_ms: pct(50),
+            p95_ms: pct(95),
+            max_ms: times.last().copied(),
+            lost_pct: if sent == 0 { 0.0 } else { lost as f64 * 100.0 / sent as f64 },
+        }
     }
 
     pub fn summary(&self) -> String {
@@ -530,9 +1786,64 @@ pub fn generate_js_bindings() -> String {
     js.push_str("// ═══ Crowny WASM Browser Node ═══\n");
     js.push_str("// Auto-generated JS bindings for TVM WASM\n\n");
 
+    // IndexedDB 저장소 — wasm의 동기 호출을 위해 메모리 미러를 두고 기록은 비동기로 반영
+    js.push_str("class CrownyIdbStore {\n");
+    js.push_str("  constructor(dbName = 'crowny-node') {\n");
+    js.push_str("    this.dbName = dbName;\n");
+    js.push_str("    this.db = null;\n");
+    js.push_str("    this.mirror = new Map(); // store -> Map(key -> value)\n");
+    js.push_str("    this.pending = Promise.resolve();\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  async open(stores) {\n");
+    js.push_str("    this.db = await new Promise((resolve, reject) => {\n");
+    js.push_str("      const req = indexedDB.open(this.dbName, 1);\n");
+    js.push_str("      req.onupgradeneeded = () => {\n");
+    js.push_str("        for (const s of stores) if (!req.result.objectStoreNames.contains(s)) req.result.createObjectStore(s);\n");
+    js.push_str("      };\n");
+    js.push_str("      req.onsuccess = () => resolve(req.result);\n");
+    js.push_str("      req.onerror = () => reject(req.error);\n");
+    js.push_str("    });\n");
+    js.push_str("    for (const s of stores) {\n");
+    js.push_str("      const map = new Map();\n");
+    js.push_str("      await new Promise((resolve, reject) => {\n");
+    js.push_str("        const cur = this.db.transaction(s).objectStore(s).openCursor();\n");
+    js.push_str("        cur.onsuccess = () => {\n");
+    js.push_str("          const c = cur.result;\n");
+    js.push_str("          if (c) { map.set(c.key, c.value); c.continue(); } else resolve();\n");
+    js.push_str("        };\n");
+    js.push_str("        cur.onerror = () => reject(cur.error);\n");
+    js.push_str("      });\n");
+    js.push_str("      this.mirror.set(s, map);\n");
+    js.push_str("    }\n");
+    js.push_str("    return this;\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  _write(store, fn) {\n");
+    js.push_str("    this.pending = this.pending.then(() => new Promise((resolve, reject) => {\n");
+    js.push_str("      const tx = this.db.transaction(store, 'readwrite');\n");
+    js.push_str("      fn(tx.objectStore(store));\n");
+    js.push_str("      tx.oncomplete = () => resolve();\n");
+    js.push_str("      tx.onerror = () => reject(tx.error);\n");
+    js.push_str("    }));\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  get(store, key) { return this.mirror.get(store)?.get(key); }\n");
+    js.push_str("  put(store, key, value) { this.mirror.get(store).set(key, value); this._write(store, os => os.put(value, key)); }\n");
+    js.push_str("  delete(store, key) { this.mirror.get(store).delete(key); this._write(store, os => os.delete(key)); }\n");
+    js.push_str("  keys(store, prefix) { return [...this.mirror.get(store).keys()].filter(k => k.startsWith(prefix)).sort(); }\n");
+    js.push_str("  flush() { return this.pending; }\n");
+    js.push_str("}\n\n");
+
     js.push_str("class CrownyWasmNode {\n");
-    js.push_str("  constructor() {\n");
+    js.push_str("  // serviceWorker: 서비스 워커 안에서 실행 (RTC 없음 — 큐 재전송은 relayUrl로)\n");
+    js.push_str("  constructor({ serviceWorker = false, relayUrl = '/ctp/tx' } = {}) {\n");
     js.push_str("    this.wasm = null;\n");
+    js.push_str("    this.serviceWorker = serviceWorker;\n");
+    js.push_str("    this.relayUrl = relayUrl;\n");
+    js.push_str("    this.outbox = [];\n");
+    js.push_str("    this.chainNonces = new Map();\n");
+    js.push_str("    this.store = new CrownyIdbStore();\n");
     js.push_str("    this.nodeId = crypto.randomUUID();\n");
     js.push_str("    this.peers = new Map();\n");
     js.push_str("    this.state = new Map();\n");
@@ -540,19 +1851,42 @@ pub fn generate_js_bindings() -> String {
     js.push_str("  }\n\n");
 
     js.push_str("  async init(wasmUrl = '/crowny-tvm.wasm') {\n");
+    js.push_str("    // 저장소를 먼저 읽어 두어야 wasm이 시작 시 이전 세션을 복원할 수 있다\n");
+    js.push_str("    await this.store.open(['node']);\n");
+    js.push_str("    const saved = this.store.get('node', 'meta:node_id');\n");
+    js.push_str("    if (saved) this.nodeId = saved; else this.store.put('node', 'meta:node_id', this.nodeId);\n");
     js.push_str("    const response = await fetch(wasmUrl);\n");
     js.push_str("    const bytes = await response.arrayBuffer();\n");
     js.push_str("    const { instance } = await WebAssembly.instantiate(bytes, {\n");
     js.push_str("      env: {\n");
     js.push_str("        print: (ptr, len) => console.log(this._readString(ptr, len)),\n");
     js.push_str("        now_ms: () => BigInt(Date.now()),\n");
+    js.push_str("        idb_get: (sp, sl, kp, kl, out, cap) => this._idbOut(this.store.get(this._readString(sp, sl), this._readString(kp, kl)), out, cap),\n");
+    js.push_str("        idb_put: (sp, sl, kp, kl, vp, vl) => { this.store.put(this._readString(sp, sl), this._readString(kp, kl), this._readString(vp, vl)); return 0; },\n");
+    js.push_str("        idb_delete: (sp, sl, kp, kl) => { this.store.delete(this._readString(sp, sl), this._readString(kp, kl)); return 0; },\n");
+    js.push_str("        idb_keys: (sp, sl, pp, pl, out, cap) => this._idbOut(this.store.keys(this._readString(sp, sl), this._readString(pp, pl)).join('\\n'), out, cap),\n");
+    js.push_str("        idb_flush: () => 0,\n");
+    js.push_str("        chain_nonce: (ap, al) => BigInt(this.chainNonces.get(this._readString(ap, al)) ?? 0),\n");
+    js.push_str("        tx_submit: (ptr, len) => this._submitTx(JSON.parse(this._readString(ptr, len))),\n");
     js.push_str("      }\n");
     js.push_str("    });\n");
     js.push_str("    this.wasm = instance.exports;\n");
     js.push_str("    this.wasm.tvm_init();\n");
+    js.push_str("    this.wasm.txq_set_online(navigator.onLine ? 1 : 0);\n");
+    js.push_str("    if (!this.serviceWorker) {\n");
+    js.push_str("      addEventListener('online', () => this.setOnline(true));\n");
+    js.push_str("      addEventListener('offline', () => this.setOnline(false));\n");
+    js.push_str("    }\n");
     js.push_str("    return this;\n");
     js.push_str("  }\n\n");
 
+    js.push_str("  // 서비스 워커 등록 — 페이지가 닫혀도 Background Sync로 큐를 재전송\n");
+    js.push_str("  async registerServiceWorker(url = '/crowny-sw.js') {\n");
+    js.push_str("    if (!('serviceWorker' in navigator)) return null;\n");
+    js.push_str("    this.registration = await navigator.serviceWorker.register(url, { type: 'module' });\n");
+    js.push_str("    return this.registration;\n");
+    js.push_str("  }\n\n");
+
     js.push_str("  // TVM 실행\n");
     js.push_str("  execute(source) { return this.wasm.tvm_execute(source); }\n");
     js.push_str("  push(value) { this.wasm.tvm_push(value); }\n");
@@ -564,6 +1898,59 @@ pub fn generate_js_bindings() -> String {
     js.push_str("  tritOr(a, b) { return this.wasm.trit_or(a, b); }\n");
     js.push_str("  tritNot(a) { return this.wasm.trit_not(a); }\n\n");
 
+    js.push_str("  // 오프라인 트랜잭션 큐\n");
+    js.push_str("  queueTx(from, to, amount, memo = '') {\n");
+    js.push_str("    const args = [from, to, memo].map(s => this._writeString(s));\n");
+    js.push_str("    const sent = this.wasm.txq_enqueue(...args[0], ...args[1], BigInt(amount), ...args[2]);\n");
+    js.push_str("    args.forEach(([p, l]) => this.wasm.tvm_free(p, l));\n");
+    js.push_str("    if (!sent) this.registration?.sync?.register('crowny-txq');\n");
+    js.push_str("    return this._flushOutbox().then(() => this.store.flush()).then(() => sent === 1);\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  // 대기(O) 항목 — 서비스 워커가 큐를 맡고 있으면 그쪽에 묻는다\n");
+    js.push_str("  async pendingTxs() {\n");
+    js.push_str("    const sw = !this.serviceWorker && navigator.serviceWorker?.controller;\n");
+    js.push_str("    if (sw) {\n");
+    js.push_str("      const { port1, port2 } = new MessageChannel();\n");
+    js.push_str("      const reply = new Promise(resolve => { port1.onmessage = e => resolve(e.data); });\n");
+    js.push_str("      sw.postMessage({ type: 'txq:pending' }, [port2]);\n");
+    js.push_str("      return reply;\n");
+    js.push_str("    }\n");
+    js.push_str("    return JSON.parse(this._callOut((out, cap) => this.wasm.txq_pending(out, cap)));\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  setChainNonce(address, nonce) { this.chainNonces.set(address, nonce); }\n\n");
+
+    js.push_str("  async setOnline(online) {\n");
+    js.push_str("    this.wasm.txq_set_online(online ? 1 : 0);\n");
+    js.push_str("    if (online) return this.replay();\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  // 재접속 재전송 — 체인 nonce에 맞춘 뒤 제출, 전송된 개수 반환\n");
+    js.push_str("  async replay() {\n");
+    js.push_str("    const submitted = this.wasm.txq_replay();\n");
+    js.push_str("    await this._flushOutbox();\n");
+    js.push_str("    await this.store.flush();\n");
+    js.push_str("    return submitted;\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  // 0 = 전송, -1 = 보류 (wasm 큐에 남음)\n");
+    js.push_str("  _submitTx(tx) {\n");
+    js.push_str("    if (!navigator.onLine) return -1;\n");
+    js.push_str("    if (this.serviceWorker) { this.outbox.push(tx); return 0; }\n");
+    js.push_str("    const open = [...this.peers.values()].some(({ dc }) => dc.readyState === 'open');\n");
+    js.push_str("    if (!open) return -1;\n");
+    js.push_str("    this.broadcast({ type: 'tx', ...tx });\n");
+    js.push_str("    return 0;\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  async _flushOutbox() {\n");
+    js.push_str("    const batch = this.outbox.splice(0);\n");
+    js.push_str("    await Promise.all(batch.map(tx => fetch(this.relayUrl, {\n");
+    js.push_str("      method: 'POST', headers: { 'Content-Type': 'application/json' }, body: JSON.stringify(tx),\n");
+    js.push_str("    })));\n");
+    js.push_str("  }\n\n");
+
     js.push_str("  // P2P (WebRTC)\n");
     js.push_str("  async connectPeer(peerId, signalingUrl) {\n");
     js.push_str("    const pc = new RTCPeerConnection({ iceServers: [{ urls: 'stun:stun.l.google.com:19302' }] });\n");
@@ -603,6 +1990,38 @@ pub fn generate_js_bindings() -> String {
     js.push_str("    }\n");
     js.push_str("  }\n\n");
 
+    js.push_str("  _readString(ptr, len) {\n");
+    js.push_str("    return new TextDecoder().decode(new Uint8Array(this.wasm.memory.buffer, ptr, len));\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  _writeString(s) {\n");
+    js.push_str("    const bytes = new TextEncoder().encode(s);\n");
+    js.push_str("    const ptr = this.wasm.tvm_alloc(bytes.length);\n");
+    js.push_str("    new Uint8Array(this.wasm.memory.buffer, ptr, bytes.length).set(bytes);\n");
+    js.push_str("    return [ptr, bytes.length];\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  // 길이를 먼저 묻고 버퍼를 할당해 다시 호출\n");
+    js.push_str("  _callOut(fn) {\n");
+    js.push_str("    const len = fn(0, 0);\n");
+    js.push_str("    const ptr = this.wasm.tvm_alloc(len);\n");
+    js.push_str("    fn(ptr, len);\n");
+    js.push_str("    const s = this._readString(ptr, len);\n");
+    js.push_str("    this.wasm.tvm_free(ptr, len);\n");
+    js.push_str("    return s;\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  // 값 길이를 돌려주고, 버퍼가 충분하면 wasm 메모리에 복사 (없으면 -1)\n");
+    js.push_str("  _idbOut(value, out, cap) {\n");
+    js.push_str("    if (value === undefined) return -1;\n");
+    js.push_str("    const bytes = new TextEncoder().encode(value);\n");
+    js.push_str("    if (bytes.length <= cap) new Uint8Array(this.wasm.memory.buffer, out, bytes.length).set(bytes);\n");
+    js.push_str("    return bytes.length;\n");
+    js.push_str("  }\n\n");
+
+    js.push_str("  // 페이지를 닫기 전에 대기 중인 IndexedDB 기록을 마침\n");
+    js.push_str("  persist() { return this.store.flush(); }\n\n");
+
     js.push_str("  _onVote(msg) { /* 투표 처리 */ }\n");
     js.push_str("  _onBlock(msg) { /* 블록 처리 */ }\n");
     js.push_str("  _onSync(msg) { /* 동기화 처리 */ }\n");
@@ -613,18 +2032,69 @@ pub fn generate_js_bindings() -> String {
     js.push_str("// const node = await new CrownyWasmNode().init();\n");
     js.push_str("// node.push(42); node.push(58); node.execute('add');\n");
     js.push_str("// console.log(node.stackTop()); // 100\n");
+    js.push_str("export { CrownyIdbStore };\n");
     js.push_str("export default CrownyWasmNode;\n");
 
     js
 }
 
+/// 서비스 워커 스크립트 — wasm 캐시, Background Sync 재전송, 페이지의 큐 조회 응답
+pub fn generate_service_worker_js() -> String {
+    let mut js = String::new();
+    js.push_str("// ═══ Crowny Service Worker ═══\n");
+    js.push_str("// Auto-generated — register with node.registerServiceWorker('/crowny-sw.js')\n\n");
+    js.push_str("import CrownyWasmNode from './crowny-node.js';\n\n");
+
+    js.push_str("const CACHE = 'crowny-v0.4.0';\n");
+    js.push_str("const ASSETS = ['/crowny-tvm.wasm', '/crowny-node.js'];\n");
+    js.push_str("let node = null;\n\n");
+
+    js.push_str("// 같은 IndexedDB를 열기 때문에 페이지에서 쌓인 큐가 그대로 보인다\n");
+    js.push_str("async function ensureNode() {\n");
+    js.push_str("  if (!node) node = await new CrownyWasmNode({ serviceWorker: true }).init();\n");
+    js.push_str("  return node;\n");
+    js.push_str("}\n\n");
+
+    js.push_str("self.addEventListener('install', (e) => {\n");
+    js.push_str("  e.waitUntil(caches.open(CACHE).then(c => c.addAll(ASSETS)).then(() => self.skipWaiting()));\n");
+    js.push_str("});\n\n");
+
+    js.push_str("self.addEventListener('activate', (e) => e.waitUntil(self.clients.claim()));\n\n");
+
+    js.push_str("// 오프라인에서도 노드가 뜨도록 wasm · 바인딩은 캐시 우선\n");
+    js.push_str("self.addEventListener('fetch', (e) => {\n");
+    js.push_str("  if (ASSETS.some(a => e.request.url.endsWith(a))) {\n");
+    js.push_str("    e.respondWith(caches.match(e.request).then(r => r || fetch(e.request)));\n");
+    js.push_str("  }\n");
+    js.push_str("});\n\n");
+
+    js.push_str("self.addEventListener('sync', (e) => {\n");
+    js.push_str("  if (e.tag === 'crowny-txq') e.waitUntil(ensureNode().then(n => n.setOnline(true)));\n");
+    js.push_str("});\n\n");
+
+    js.push_str("self.addEventListener('message', (e) => {\n");
+    js.push_str("  const reply = (data) => e.ports[0]?.postMessage(data);\n");
+    js.push_str("  switch (e.data?.type) {\n");
+    js.push_str("    case 'txq:pending': ensureNode().then(n => n.pendingTxs()).then(reply); break;\n");
+    js.push_str("    case 'txq:queue': {\n");
+    js.push_str("      const { from, to, amount, memo } = e.data;\n");
+    js.push_str("      ensureNode().then(n => n.queueTx(from, to, amount, memo)).then(reply);\n");
+    js.push_str("      break;\n");
+    js.push_str("    }\n");
+    js.push_str("    case 'txq:replay': ensureNode().then(n => n.replay()).then(reply); break;\n");
+    js.push_str("  }\n");
+    js.push_str("});\n");
+
+    js
+}
+
 fn now_ms() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
 }
 
 // ═══ 데모 ═══
 
-pub fn demo_wasm_browser_node() {
+pub fn demo_wasm_browser_node(message_log_capacity: usize) {
     println!("╔═══════════════════════════════════════════╗");
     println!("║  Crowny WASM Browser Node                 ║");
     println!("║  브라우저 경량 노드 — P2P 합의 네트워크     ║");
@@ -645,7 +2115,7 @@ pub fn demo_wasm_browser_node() {
 
     // 2. 브라우저 네트워크
     println!("━━━ 2. 브라우저 P2P 네트워크 ━━━");
-    let mut network = BrowserNetwork::new();
+    let mut network = BrowserNetwork::new().with_message_log_capacity(message_log_capacity);
     network.add_node("browser-seoul-1", BrowserNodeType::Full);
     network.add_node("browser-tokyo-2", BrowserNodeType::Full);
     network.add_node("browser-sf-3", BrowserNodeType::Validator);
@@ -685,17 +2155,151 @@ pub fn demo_wasm_browser_node() {
     }
     println!();
 
+    // 4-1. 링크 모델 — 실제 WebRTC 조건에서 확정 시간과 투표 타임아웃
+    println!("━━━ 4-1. 링크 모델 (지연·손실·지터) ━━━");
+    println!("  {:10} {:>8} {:>8} {:>8} {:>8} {:>7}", "모델", "타임아웃", "확정률", "p50", "p95", "손실");
+    for preset in ["lan", "regional", "global", "mobile"] {
+        for timeout in [250, 500, 1_000] {
+            let model = LinkModel::preset(preset).unwrap_or_default();
+            let mut sim = BrowserNetwork::new().with_link_model(model).with_vote_timeout(timeout);
+            for node in &network.nodes {
+                sim.add_node(&node.id, node.node_type.clone());
+            }
+            sim.connect_all();
+            let stats = sim.measure_finality(50);
+            let ms = |v: Option<u64>| v.map(|m| format!("{}ms", m)).unwrap_or_else(|| "-".into());
+            println!("  {:10} {:>7}ms {:>7.0}% {:>8} {:>8} {:>6.1}%",
+                preset, timeout, stats.finality_rate() * 100.0, ms(stats.p50_ms), ms(stats.p95_ms), stats.lost_pct);
+        }
+    }
+    println!();
+
+    // 4-2. 혼합 링크 — 한 노드만 불안정한 모바일 회선
+    println!("━━━ 4-2. 혼합 링크 (노드별 재정의) ━━━");
+    let mut sim = BrowserNetwork::new()
+        .with_link_model(LinkModel::webrtc_regional())
+        .with_vote_timeout(1_000)
+        .with_seed(7);
+    for node in &network.nodes {
+        sim.add_node(&node.id, node.node_type.clone());
+    }
+    sim.connect_all();
+    let mobile = LinkModel::mobile().with_loss(10.0).with_jitter(120);
+    let (first, last) = (network.nodes[0].id.clone(), network.nodes[network.nodes.len() - 1].id.clone());
+    sim.set_link(&first, &last, mobile);
+    if let Some(round) = sim.simulate_round(vec!["tx-mixed".to_string()]) {
+        let ms = |v: Option<u64>| v.map(|m| format!("{}ms", m)).unwrap_or_else(|| "-".into());
+        println!("  제안자 확정 {} | 전체 확정 {} | 손실 {}/{}",
+            ms(round.finality_ms), ms(round.full_finality_ms()), round.messages_lost, round.messages_sent);
+    }
+    println!("  {}", sim.measure_finality(30).to_json().build());
+    println!();
+
     // 5. JS 바인딩
     println!("━━━ 5. JS 바인딩 생성 ━━━");
     let js = generate_js_bindings();
     let js_lines = js.lines().count();
     println!("  Generated: {} lines JavaScript", js_lines);
     println!("  Class: CrownyWasmNode");
-    println!("  Methods: init, execute, push, pop, connectPeer, broadcast, vote, consensus");
+    println!("  Methods: init, execute, push, pop, connectPeer, broadcast, vote, consensus, persist");
+    println!("  Storage: CrownyIdbStore (IndexedDB)");
+    println!();
+
+    // 6. 영속 저장소 — 새로고침 후 복원
+    println!("━━━ 6. 영속 저장소 (세션 복원) ━━━");
+    let path = std::env::temp_dir().join(format!("crowny_wasm_node_demo_{}.kv", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    match FileStorage::open(&path) {
+        Ok(storage) => {
+            println!("  저장소: {}", storage.path().display());
+            let mut node = BrowserNode::open("browser-seoul-1", BrowserNodeType::Full, Box::new(storage));
+            node.connect("browser-tokyo-2", BrowserNodeType::Full);
+            node.set_state("balance:alice", "900");
+            node.propose_block(vec!["tx-persist".to_string()]);
+            node.receive_block_vote(1, "browser-tokyo-2", 1);
+            node.finalize_block(1, 2);
+            let _ = node.save_key("identity", &[0x7a; 32]);
+            // 탭을 닫기 전 전체 스냅샷 기록
+            if node.is_persistent() {
+                if let Err(e) = node.persist() {
+                    println!("  기록 실패: {}", e);
+                }
+            }
+            println!("  세션 1: 피어 {} | 상태 v{} | 헤더 {}", node.peer_count(), node.state_version, node.header_cache.len());
+            drop(node);
+
+            // 새로고침 — 같은 저장소로 다시 열기
+            match FileStorage::open(&path) {
+                Ok(storage) => {
+                    let node = BrowserNode::open("browser-seoul-1", BrowserNodeType::Full, Box::new(storage));
+                    println!("  세션 2: 피어 {} | 상태 v{} | 헤더 {} | 키 {}",
+                        node.peer_count(), node.state_version, node.header_cache.len(),
+                        if node.load_key("identity").is_some() { "복원" } else { "없음" });
+                    if let Some(h) = node.header_cache.first() {
+                        println!("    헤더 #{}: {} 확정={} tx={}", h.id, short(&h.proposer), h.finalized, h.tx_count);
+                    }
+                }
+                Err(e) => println!("  재열기 실패: {}", e),
+            }
+        }
+        Err(e) => println!("  저장소 열기 실패: {}", e),
+    }
+    let _ = std::fs::remove_file(&path);
+    println!();
+
+    // 6-1. 노드 신원 — 서명 키도 저장소에 남아 새로고침 후 같은 ID
+    println!("━━━ 6-1. 노드 신원 (Ed25519) ━━━");
+    let path = std::env::temp_dir().join(format!("crowny_wasm_identity_demo_{}.kv", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let open = || FileStorage::open(&path).and_then(|s| BrowserNode::open_with_identity(BrowserNodeType::Light, Box::new(s)));
+    for session in 1..=2 {
+        match open() {
+            Ok(node) => {
+                let public = node.identity().map(|i| crate::crypto::to_hex(&i.public)).unwrap_or_default();
+                println!("  세션 {}: {} (공개 키 {}…)", session, node.id, &public[..16.min(public.len())]);
+            }
+            Err(e) => println!("  세션 {}: 신원 열기 실패 — {}", session, e),
+        }
+    }
+    let _ = std::fs::remove_file(&path);
+    let mut signed = BrowserNetwork::new().with_message_log_capacity(message_log_capacity);
+    for _ in 0..3 {
+        signed.add_identified_node(NodeIdentity::generate(), BrowserNodeType::Validator);
+    }
+    signed.connect_all();
+    if let Some(round) = signed.simulate_round(vec!["tx-signed".to_string()]) {
+        println!("  서명 투표 라운드: {} (거부 {})", if round.finalized { "확정" } else { "미확정" }, round.rejected_votes);
+    }
+    let voter = signed.nodes[1].id.clone();
+    let forged = P2PMessage::BlockVote { block_id: 1, voter, vote: -1, signature: None };
+    match signed.nodes[0].accept_block_vote(&forged) {
+        Ok(()) => println!("  ✗ 서명 없는 투표 수락"),
+        Err(e) => println!("  ✓ 서명 없는 투표 거부: {}", e),
+    }
+    println!();
+
+    // 7. 오프라인 큐 — 서비스 워커 재전송
+    println!("━━━ 7. 오프라인 트랜잭션 큐 ━━━");
+    let mut node = BrowserNode::new("browser-london-4", BrowserNodeType::Light);
+    node.go_offline();
+    for (to, amount) in [("bob", 10), ("carol", 20), ("dave", 30)] {
+        node.submit_tx("alice", to, amount, "offline");
+    }
+    for tx in node.pending_txs() {
+        println!("  [O] #{} {}→{} {} CRWN (임시 nonce {})", tx.seq, tx.from, tx.to, tx.amount, tx.nonce);
+    }
+    println!("  pendingTxs(): {}", node.tx_queue.pending_json());
+    // 오프라인 동안 다른 기기에서 alice가 트랜잭션 5개를 보냄 → 체인 nonce 5
+    let report = node.reconnect(|_| 5, |msg| {
+        println!("  → {}", msg);
+        Ok(())
+    });
+    println!("  재접속: {}", report);
+    println!("  서비스 워커: {} lines (Background Sync 'crowny-txq')", generate_service_worker_js().lines().count());
     println!();
 
-    // 6. 최종 상태
-    println!("━━━ 6. 네트워크 최종 상태 ━━━");
+    // 8. 최종 상태
+    println!("━━━ 8. 네트워크 최종 상태 ━━━");
     println!("{}", network.summary());
     println!();
 
@@ -729,8 +2333,9 @@ mod tests {
     fn test_vote_tally() {
         let mut node = BrowserNode::new("n1", BrowserNodeType::Full);
         node.propose_vote(1);
-        node.receive_vote(1, "n2", 1);
-        node.receive_vote(1, "n3", -1);
+        for (voter, vote) in [("n2", 1), ("n3", -1)] {
+            node.receive_vote(&P2PMessage::TritVote { node_id: voter.into(), proposal_id: 1, vote, signature: None }).unwrap();
+        }
         let (consensus, confidence) = node.tally_vote(1);
         assert_eq!(consensus, 1); // 2P vs 1T
     }
@@ -766,6 +2371,78 @@ mod tests {
         assert_eq!(state, 1);
     }
 
+    #[test]
+    fn test_link_model_sampling() {
+        let mut rng = Gen::new(7);
+        assert_eq!(LinkModel::instant().sample(&mut rng), Some(0));
+        assert!((0..100).all(|_| LinkModel::instant().with_loss(100.0).sample(&mut rng).is_none()));
+        let jittery = LinkModel { latency: LatencyDist::Fixed(100), jitter_ms: 10, loss_pct: 0.0 };
+        assert!((0..200).all(|_| (90..=110).contains(&jittery.sample(&mut rng).unwrap())));
+        // 평균 근처 · 음수 없음
+        let normal = LinkModel { latency: LatencyDist::Normal { mean_ms: 5.0, stddev_ms: 10.0 }, jitter_ms: 0, loss_pct: 0.0 };
+        let samples: Vec<u64> = (0..2_000).map(|_| normal.sample(&mut rng).unwrap()).collect();
+        let mean = samples.iter().sum::<u64>() as f64 / samples.len() as f64;
+        assert!(samples.contains(&0) && mean > 5.0 && mean < 12.0, "mean {}", mean);
+        // 같은 시드 → 같은 순서
+        let seq = |seed| { let mut g = Gen::new(seed); (0..20).map(|_| LinkModel::mobile().sample(&mut g)).collect::<Vec<_>>() };
+        assert_eq!(seq(42), seq(42));
+    }
+
+    #[test]
+    fn test_latency_delays_finality_and_vote_timeout() {
+        let build = |timeout| {
+            let mut net = BrowserNetwork::new()
+                .with_link_model(LinkModel { latency: LatencyDist::Fixed(50), jitter_ms: 0, loss_pct: 0.0 })
+                .with_vote_timeout(timeout);
+            for id in ["n1", "n2", "n3", "n4", "n5"] {
+                net.add_node(id, BrowserNodeType::Full);
+            }
+            net.connect_all();
+            net
+        };
+        // 제안 50ms + 투표 50ms → 100ms에 확정, 통지는 150ms에 도착
+        let round = build(1_000).simulate_round(vec!["tx".into()]).unwrap();
+        assert!(round.finalized);
+        assert_eq!(round.finality_ms, Some(100));
+        assert_eq!(round.full_finality_ms(), Some(150));
+        assert_eq!((round.messages_lost, round.late_votes), (0, 0));
+
+        // 타임아웃이 왕복보다 짧으면 보류(O)
+        let mut net = build(80);
+        let round = net.simulate_round(vec!["tx".into()]).unwrap();
+        assert_eq!((round.finalized, round.state, round.finality_ms), (false, 0, None));
+        assert_eq!(round.late_votes, 4 * 4);
+        assert!(round.node_finality.iter().all(|(_, t)| t.is_none()));
+        assert_eq!(net.nodes[1].connected_peers.iter().find(|p| p.id == "n1").unwrap().latency_ms, 50);
+    }
+
+    #[test]
+    fn test_lossy_link_override_and_finality_stats() {
+        let mut net = BrowserNetwork::new().with_seed(9);
+        for id in ["a", "b", "c"] {
+            net.add_node(id, BrowserNodeType::Full);
+        }
+        net.connect_all();
+        // a↔c 완전 단절 — c는 제안을 못 받지만 b와 함께 정족수(2)는 채워지고
+        // 확정 통지도 못 받는다
+        net.set_link("c", "a", LinkModel::instant().with_loss(100.0));
+        let round = net.simulate_round(vec!["tx".into()]).unwrap();
+        assert!(round.finalized);
+        assert_eq!(round.node_finality[2], ("c".to_string(), None));
+        assert!(net.nodes[2].blocks.is_empty());
+
+        let mut net = BrowserNetwork::new().with_link_model(LinkModel::mobile()).with_vote_timeout(600).with_seed(3);
+        for id in ["a", "b", "c", "d", "e"] {
+            net.add_node(id, BrowserNodeType::Validator);
+        }
+        net.connect_all();
+        let stats = net.measure_finality(40);
+        assert_eq!(stats.rounds, 40);
+        assert!(stats.finalized > 30 && stats.lost_pct > 0.0);
+        assert!(stats.p50_ms <= stats.p95_ms && stats.p95_ms <= stats.max_ms);
+        assert!(stats.max_ms.unwrap() <= 600);
+    }
+
     #[test]
     fn test_wasm_manifest() {
         let m = WasmManifest::crowny_standard();
@@ -782,6 +2459,209 @@ mod tests {
         assert!(js.contains("WebRTC"));
     }
 
+    #[test]
+    fn test_js_indexeddb_bindings() {
+        let js = generate_js_bindings();
+        assert!(js.contains("indexedDB.open"));
+        assert!(js.contains("idb_get"));
+    }
+
+    #[test]
+    fn test_file_storage_survives_reload() {
+        let path = std::env::temp_dir().join(format!("crowny_wasm_node_{}.kv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut node = BrowserNode::open("n1", BrowserNodeType::Full, Box::new(FileStorage::open(&path).unwrap()));
+        node.connect("n2", BrowserNodeType::Validator);
+        node.set_state("memo", "line1\nline2\ttab\\");
+        node.propose_block(vec!["tx1".into(), "tx2".into()]);
+        node.receive_block_vote(1, "n2", 1);
+        assert!(node.finalize_block(1, 2));
+        node.save_key("identity", &[1, 2, 255]).unwrap();
+        assert_eq!(node.stats.storage_errors, 0);
+        drop(node);
+
+        let mut node = BrowserNode::open("n1", BrowserNodeType::Full, Box::new(FileStorage::open(&path).unwrap()));
+        assert_eq!(node.get_state("memo"), Some(&"line1\nline2\ttab\\".to_string()));
+        assert_eq!(node.state_version, 1);
+        assert_eq!(node.peer_count(), 1);
+        assert_eq!(node.connected_peers[0].node_type, BrowserNodeType::Validator);
+        assert_eq!(node.header_cache.len(), 1);
+        assert!(node.header_cache[0].finalized);
+        assert_eq!(node.header_cache[0].tx_count, 2);
+        assert_eq!(node.load_key("identity"), Some(vec![1, 2, 255]));
+
+        // 재접속은 피어를 중복시키지 않고, 블록 번호는 이어진다
+        node.handle_handshake("n2", BrowserNodeType::Validator);
+        assert_eq!(node.peer_count(), 1);
+        node.propose_block(vec!["tx3".into()]);
+        assert_eq!(node.blocks[0].id, 2);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_offline_queue_replay_reconciles_nonces() {
+        let mut node = BrowserNode::new("n1", BrowserNodeType::Light);
+        assert!(matches!(node.submit_tx("alice", "bob", 1, ""), Some(P2PMessage::TxSubmit { nonce: 0, .. })));
+
+        node.go_offline();
+        assert!(node.submit_tx("alice", "bob", 2, "a").is_none());
+        assert!(node.submit_tx("alice", "carol", 3, "b|c").is_none());
+        assert!(node.submit_tx("erin", "bob", 4, "").is_none());
+        let nonces: Vec<u64> = node.pending_txs().iter().map(|t| t.nonce).collect();
+        assert_eq!(nonces, vec![1, 2, 0]);
+        assert!(node.tx_queue.pending_json().contains("\"state\":\"O\""));
+
+        // 체인은 alice nonce 7, erin은 첫 전송이 일시 실패
+        let mut sent = Vec::new();
+        let report = node.reconnect(|a| if a == "alice" { 7 } else { 0 }, |msg| match msg {
+            P2PMessage::TxSubmit { from, nonce, .. } if from == "erin" => Err(format!("relay down {}", nonce)),
+            P2PMessage::TxSubmit { nonce, .. } => { sent.push(*nonce); Ok(()) }
+            _ => unreachable!(),
+        });
+        assert_eq!(sent, vec![7, 8]);
+        assert_eq!(report.submitted, 2);
+        assert_eq!(report.renumbered, 2);
+        assert_eq!(report.deferred, 1);
+        assert_eq!(node.pending_txs().len(), 1);
+        assert_eq!(node.pending_txs()[0].from, "erin");
+
+        // 한도까지 실패하면 T로 확정되고 큐에서 빠진다
+        for _ in 1..MAX_REPLAY_ATTEMPTS {
+            node.reconnect(|_| 0, |_| Err("nonce too low".into()));
+        }
+        assert!(node.pending_txs().is_empty());
+        // 대기 항목이 없으면 온라인 전송은 체인에 맞춘 다음 nonce
+        assert!(matches!(node.submit_tx("alice", "bob", 5, ""), Some(P2PMessage::TxSubmit { nonce: 9, .. })));
+    }
+
+    #[test]
+    fn test_offline_queue_survives_reload() {
+        let path = std::env::temp_dir().join(format!("crowny_wasm_txq_{}.kv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut node = BrowserNode::open("n1", BrowserNodeType::Light, Box::new(FileStorage::open(&path).unwrap()));
+        node.go_offline();
+        node.submit_tx("alice", "bob", 10, "memo|with|pipes");
+        node.submit_tx("alice", "carol", 20, "");
+        drop(node);
+
+        let mut node = BrowserNode::open("n1", BrowserNodeType::Light, Box::new(FileStorage::open(&path).unwrap()));
+        assert_eq!(node.pending_txs().len(), 2);
+        assert_eq!(node.pending_txs()[0].memo, "memo|with|pipes");
+        node.go_offline();
+        node.submit_tx("alice", "dave", 30, "");
+        assert_eq!(node.pending_txs()[2].seq, 3);
+        assert_eq!(node.reconnect(|_| 0, |_| Ok(())).submitted, 3);
+        drop(node);
+
+        let node = BrowserNode::open("n1", BrowserNodeType::Light, Box::new(FileStorage::open(&path).unwrap()));
+        assert!(node.pending_txs().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_service_worker_bindings() {
+        let sw = generate_service_worker_js();
+        assert!(sw.contains("'sync'"));
+        assert!(sw.contains("txq:pending"));
+        let js = generate_js_bindings();
+        assert!(js.contains("registerServiceWorker"));
+        assert!(js.contains("txq_replay"));
+        let manifest = WasmManifest::crowny_standard();
+        let exports: Vec<&String> = manifest.modules.iter().flat_map(|m| &m.exports).collect();
+        for name in ["txq_enqueue", "txq_pending", "txq_replay", "txq_set_online"] {
+            assert!(exports.iter().any(|e| *e == name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_signed_handshake_and_votes() {
+        let mut net = BrowserNetwork::new();
+        let ids: Vec<String> = (1..=3u8)
+            .map(|s| net.add_identified_node(NodeIdentity::from_seed([s; 32]), BrowserNodeType::Validator))
+            .collect();
+        net.connect_all();
+        assert!(net.nodes[0].connected_peers.iter().all(|p| p.public_key.is_some()));
+        let round = net.simulate_round(vec!["tx".into()]).unwrap();
+        assert!(round.finalized);
+        assert_eq!(round.rejected_votes, 0);
+
+        // 서명 없는 투표 · 다른 블록의 서명을 재사용한 투표는 거부
+        let node = &mut net.nodes[0];
+        let unsigned = P2PMessage::BlockVote { block_id: 9, voter: ids[1].clone(), vote: 1, signature: None };
+        assert_eq!(node.accept_block_vote(&unsigned), Err(IdentityError::MissingSignature(ids[1].clone())));
+        let P2PMessage::BlockVote { signature, .. } = net.nodes[1].vote_block(1, 1) else { unreachable!() };
+        let replayed = P2PMessage::BlockVote { block_id: 9, voter: ids[1].clone(), vote: 1, signature };
+        assert_eq!(net.nodes[0].accept_block_vote(&replayed), Err(IdentityError::BadSignature(ids[1].clone())));
+        assert_eq!(net.nodes[0].stats.rejected_messages, 2);
+
+        // 제안 투표도 같은 키로 검사 — 표를 뒤집은 위조 · 무서명 투표는 집계되지 않는다
+        net.nodes[0].propose_vote(7);
+        let cast = net.nodes[1].cast_vote(7, 1);
+        assert!(net.nodes[0].receive_vote(&cast).is_ok());
+        let P2PMessage::TritVote { signature, .. } = net.nodes[2].cast_vote(7, 1) else { unreachable!() };
+        let flipped = P2PMessage::TritVote { node_id: ids[2].clone(), proposal_id: 7, vote: -1, signature };
+        assert_eq!(net.nodes[0].receive_vote(&flipped), Err(IdentityError::BadSignature(ids[2].clone())));
+        let unsigned = P2PMessage::TritVote { node_id: ids[2].clone(), proposal_id: 7, vote: -1, signature: None };
+        assert_eq!(net.nodes[0].receive_vote(&unsigned), Err(IdentityError::MissingSignature(ids[2].clone())));
+        assert_eq!(net.nodes[0].tally_vote(7), (1, 1.0));
+        assert_eq!(net.nodes[0].stats.rejected_messages, 4);
+    }
+
+    #[test]
+    fn test_forged_identity_rejected() {
+        let mut alice = BrowserNode::with_identity(NodeIdentity::from_seed([1; 32]), BrowserNodeType::Full);
+        let bob = BrowserNode::with_identity(NodeIdentity::from_seed([2; 32]), BrowserNodeType::Full);
+        let mut mallory = BrowserNode::with_identity(NodeIdentity::from_seed([7; 32]), BrowserNodeType::Full);
+        alice.accept_handshake(&bob.handshake()).unwrap();
+
+        // mallory가 bob의 ID를 자기 키로 주장
+        let P2PMessage::Handshake { public_key, signature, .. } = mallory.handshake() else { unreachable!() };
+        let forged = P2PMessage::Handshake {
+            node_id: bob.id.clone(), node_type: BrowserNodeType::Full, version: "0.4.0".into(), public_key, signature,
+        };
+        assert!(matches!(alice.accept_handshake(&forged), Err(IdentityError::IdMismatch { .. })));
+        // 키를 알리지 않은 노드의 투표 · 서명 없는 핸드셰이크
+        let legacy = BrowserNode::new("legacy", BrowserNodeType::Full);
+        assert_eq!(alice.accept_handshake(&legacy.handshake()), Err(IdentityError::MissingSignature("legacy".into())));
+        let mut m = mallory.vote_block(3, 1);
+        assert_eq!(alice.accept_block_vote(&m), Err(IdentityError::UnknownKey(mallory.id.clone())));
+        // 키 없는 노드끼리는 예전처럼 동작
+        let mut plain = BrowserNode::new("plain", BrowserNodeType::Full);
+        plain.accept_handshake(&legacy.handshake()).unwrap();
+        m = P2PMessage::BlockVote { block_id: 3, voter: "legacy".into(), vote: 1, signature: None };
+        assert!(plain.accept_block_vote(&m).is_ok());
+    }
+
+    #[test]
+    fn test_identity_persists_in_storage() {
+        let path = std::env::temp_dir().join(format!("crowny_wasm_identity_{}.kv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut node = BrowserNode::open_with_identity(BrowserNodeType::Light, Box::new(FileStorage::open(&path).unwrap())).unwrap();
+        let id = node.id.clone();
+        assert_eq!(id, identity::node_id_for(&node.identity().unwrap().public));
+        let peer = BrowserNode::with_identity(NodeIdentity::from_seed([5; 32]), BrowserNodeType::Full);
+        node.accept_handshake(&peer.handshake()).unwrap();
+        drop(node);
+
+        let node = BrowserNode::open_with_identity(BrowserNodeType::Light, Box::new(FileStorage::open(&path).unwrap())).unwrap();
+        assert_eq!(node.id, id);
+        assert_eq!(node.connected_peers[0].public_key, Some(peer.identity().unwrap().public));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_memory_storage_prefix_keys() {
+        let mut s = MemoryStorage::new();
+        s.put("peer:a", "1").unwrap();
+        s.put("peer:b", "2").unwrap();
+        s.put("state:x", "3").unwrap();
+        assert_eq!(s.keys("peer:"), vec!["peer:a".to_string(), "peer:b".to_string()]);
+        s.delete("peer:a").unwrap();
+        assert_eq!(s.keys("peer:").len(), 1);
+        assert!(BrowserNode::new("n", BrowserNodeType::Light).save_key("k", &[1]).is_err());
+    }
+
     #[test]
     fn test_state_management() {
         let mut node = BrowserNode::new("n1", BrowserNodeType::Full);
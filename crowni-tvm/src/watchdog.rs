@@ -0,0 +1,444 @@
+// ═══════════════════════════════════════════════════════════════
+// Crowny Watchdog — 커널 감시 모듈
+// 스케줄러 · 웹서버 리스너 · 노드 동기화 · 블록 생산자 하트비트 추적
+// 하트비트 N회 누락 → T 이벤트 → 상태 복구 재시작 → 반복 실패 시 알림 격상
+// ═══════════════════════════════════════════════════════════════
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::car::TritState;
+use crate::trit_log::{Category, EventBuilder, Level, TritEventLog};
+
+fn now_ms() -> u64 { SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64 }
+
+// ═══════════════════════════════════════
+// 감시 대상
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ComponentKind {
+    Scheduler,
+    WebListener,
+    NodeSync,
+    BlockProducer,
+}
+
+impl std::fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComponentKind::Scheduler => write!(f, "scheduler"),
+            ComponentKind::WebListener => write!(f, "web-listener"),
+            ComponentKind::NodeSync => write!(f, "node-sync"),
+            ComponentKind::BlockProducer => write!(f, "block-producer"),
+        }
+    }
+}
+
+/// 재시작 훅 — 마지막 체크포인트를 받아 상태 복구 후 재기동
+pub type RestartFn = Box<dyn FnMut(Option<&str>) -> Result<(), String>>;
+
+/// 감시 중인 컴포넌트
+pub struct Watched {
+    pub kind: ComponentKind,
+    pub interval_ms: u64,
+    pub last_beat: u64,
+    pub checkpoint: Option<String>,
+    pub state: TritState,       // P 정상 / O 재시작 중 / T 격상됨
+    pub restarts: u32,
+    pub failed_restarts: u32,   // 연속 실패
+    restart: RestartFn,
+}
+
+impl Watched {
+    /// 마지막 하트비트 이후 누락 횟수
+    pub fn missed(&self, now: u64) -> u64 {
+        now.saturating_sub(self.last_beat) / self.interval_ms.max(1)
+    }
+}
+
+/// 하트비트 보내기 핸들 — 컴포넌트 쪽(다른 스레드 포함)에 넘긴다
+/// 워치독은 다음 tick에서 모아 반영한다
+#[derive(Clone)]
+pub struct Heartbeat {
+    kind: ComponentKind,
+    tx: Sender<(ComponentKind, Option<String>, u64)>,
+}
+
+impl Heartbeat {
+    pub fn beat(&self, checkpoint: Option<&str>) {
+        self.beat_at(checkpoint, now_ms());
+    }
+
+    /// 워치독이 사라졌으면 조용히 버린다
+    pub fn beat_at(&self, checkpoint: Option<&str>, now: u64) {
+        let _ = self.tx.send((self.kind.clone(), checkpoint.map(str::to_string), now));
+    }
+}
+
+impl std::fmt::Debug for Heartbeat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Heartbeat({})", self.kind)
+    }
+}
+
+// ═══════════════════════════════════════
+// 알림 싱크
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone)]
+pub struct WatchdogAlert {
+    pub component: ComponentKind,
+    pub failed_restarts: u32,
+    pub last_error: String,
+    pub timestamp: u64,
+}
+
+impl std::fmt::Display for WatchdogAlert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[ALERT] {} 재시작 {}회 연속 실패 — {}", self.component, self.failed_restarts, self.last_error)
+    }
+}
+
+pub trait AlertSink {
+    fn alert(&mut self, alert: &WatchdogAlert);
+}
+
+/// 표준 에러 출력 싱크
+pub struct StderrSink;
+
+impl AlertSink for StderrSink {
+    fn alert(&mut self, alert: &WatchdogAlert) {
+        eprintln!("{}", alert);
+    }
+}
+
+/// 메모리 수집 싱크 (테스트용)
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemorySink {
+    pub alerts: Vec<WatchdogAlert>,
+}
+
+#[cfg(test)]
+impl AlertSink for MemorySink {
+    fn alert(&mut self, alert: &WatchdogAlert) {
+        self.alerts.push(alert.clone());
+    }
+}
+
+// ═══════════════════════════════════════
+// 워치독
+// ═══════════════════════════════════════
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchdogAction {
+    Restarted(ComponentKind),
+    RestartFailed(ComponentKind, String),
+    Escalated(ComponentKind),
+}
+
+pub struct Watchdog {
+    pub max_missed: u64,        // 이 횟수 누락 시 재시작
+    pub escalate_after: u32,    // 연속 재시작 실패 이 횟수 도달 시 한 번 알림
+    components: Vec<Watched>,
+    sinks: Vec<Box<dyn AlertSink>>,
+    pub log: TritEventLog,
+    pub total_restarts: u64,
+    pub total_escalations: u64,
+    beat_tx: Sender<(ComponentKind, Option<String>, u64)>,
+    beat_rx: Receiver<(ComponentKind, Option<String>, u64)>,
+}
+
+/// 한 스레드에서 여러 컴포넌트가 나눠 쓰는 워치독
+pub type SharedWatchdog = Rc<RefCell<Watchdog>>;
+
+impl Watchdog {
+    pub fn new(max_missed: u64, escalate_after: u32) -> Self {
+        let (beat_tx, beat_rx) = mpsc::channel();
+        Self {
+            max_missed: max_missed.max(1),
+            escalate_after: escalate_after.max(1),
+            components: Vec::new(),
+            sinks: Vec::new(),
+            log: TritEventLog::new(),
+            total_restarts: 0,
+            total_escalations: 0,
+            beat_tx,
+            beat_rx,
+        }
+    }
+
+    pub fn shared(self) -> SharedWatchdog {
+        Rc::new(RefCell::new(self))
+    }
+
+    pub fn add_sink(&mut self, sink: Box<dyn AlertSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// 컴포넌트 등록 — 등록 시점을 첫 하트비트로 간주
+    pub fn register(&mut self, kind: ComponentKind, interval_ms: u64, restart: RestartFn) {
+        self.register_at(kind, interval_ms, restart, now_ms());
+    }
+
+    pub fn register_at(&mut self, kind: ComponentKind, interval_ms: u64, restart: RestartFn, now: u64) {
+        self.components.retain(|c| c.kind != kind);
+        self.components.push(Watched {
+            kind, interval_ms, last_beat: now, checkpoint: None,
+            state: TritState::Success, restarts: 0, failed_restarts: 0, restart,
+        });
+    }
+
+    /// 등록 해제 — 재시작 훅(과 훅이 쥔 자원)을 놓는다
+    pub fn unregister(&mut self, kind: &ComponentKind) -> bool {
+        let before = self.components.len();
+        self.components.retain(|c| &c.kind != kind);
+        self.components.len() != before
+    }
+
+    /// 컴포넌트에 넘길 하트비트 핸들
+    pub fn heartbeat_handle(&self, kind: ComponentKind) -> Heartbeat {
+        Heartbeat { kind, tx: self.beat_tx.clone() }
+    }
+
+    /// 핸들로 들어온 하트비트 반영
+    fn drain_beats(&mut self) {
+        while let Ok((kind, checkpoint, at)) = self.beat_rx.try_recv() {
+            self.heartbeat_at(&kind, checkpoint.as_deref(), at);
+        }
+    }
+
+    /// 하트비트 — 복구용 체크포인트 동봉 가능
+    pub fn heartbeat_at(&mut self, kind: &ComponentKind, checkpoint: Option<&str>, now: u64) -> bool {
+        match self.components.iter_mut().find(|c| &c.kind == kind) {
+            Some(c) => {
+                c.last_beat = c.last_beat.max(now);
+                if let Some(cp) = checkpoint { c.checkpoint = Some(cp.to_string()); }
+                c.state = TritState::Success;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn tick(&mut self) -> Vec<WatchdogAction> {
+        self.tick_at(now_ms())
+    }
+
+    /// 감시 주기 — 누락 컴포넌트 재시작, 반복 실패 시 격상
+    pub fn tick_at(&mut self, now: u64) -> Vec<WatchdogAction> {
+        self.drain_beats();
+        let mut actions = Vec::new();
+        let mut alerts = Vec::new();
+
+        for c in self.components.iter_mut() {
+            let missed = c.missed(now);
+            if missed < self.max_missed { continue; }
+
+            self.log.log(EventBuilder::new(Category::System, "하트비트 누락")
+                .level(Level::Error).trit(TritState::Failed).source("watchdog")
+                .field("component", &c.kind.to_string())
+                .field("missed", &missed.to_string()));
+
+            // 격상된(T) 컴포넌트는 복구될 때까지 T 유지
+            if c.state != TritState::Failed { c.state = TritState::Pending; }
+            match (c.restart)(c.checkpoint.as_deref()) {
+                Ok(()) => {
+                    c.last_beat = now;
+                    c.restarts += 1;
+                    c.failed_restarts = 0;
+                    self.total_restarts += 1;
+                    self.log.log(EventBuilder::new(Category::System, "재시작 완료")
+                        .level(Level::Warn).trit(TritState::Success).source("watchdog")
+                        .field("component", &c.kind.to_string())
+                        .field("checkpoint", c.checkpoint.as_deref().unwrap_or("-")));
+                    actions.push(WatchdogAction::Restarted(c.kind.clone()));
+                }
+                Err(e) => {
+                    c.failed_restarts += 1;
+                    // 다음 주기에 재시도
+                    c.last_beat = now;
+                    self.log.log(EventBuilder::new(Category::System, "재시작 실패")
+                        .level(Level::Error).trit(TritState::Failed).source("watchdog")
+                        .field("component", &c.kind.to_string())
+                        .field("error", &e));
+                    actions.push(WatchdogAction::RestartFailed(c.kind.clone(), e.clone()));
+
+                    if c.failed_restarts == self.escalate_after {
+                        c.state = TritState::Failed;
+                        self.log.log(EventBuilder::new(Category::System, "알림 격상")
+                            .level(Level::Fatal).trit(TritState::Failed).source("watchdog")
+                            .field("component", &c.kind.to_string()));
+                        alerts.push(WatchdogAlert {
+                            component: c.kind.clone(),
+                            failed_restarts: c.failed_restarts,
+                            last_error: e,
+                            timestamp: now,
+                        });
+                        actions.push(WatchdogAction::Escalated(c.kind.clone()));
+                    }
+                }
+            }
+        }
+
+        for alert in &alerts {
+            self.total_escalations += 1;
+            for sink in self.sinks.iter_mut() {
+                sink.alert(alert);
+            }
+        }
+        actions
+    }
+
+    #[cfg(test)]
+    pub fn component(&self, kind: &ComponentKind) -> Option<&Watched> {
+        self.components.iter().find(|c| &c.kind == kind)
+    }
+
+    pub fn summary(&self) -> String {
+        let mut names: Vec<String> = self.components.iter()
+            .map(|c| format!("{}={}", c.kind, c.state.symbol()))
+            .collect();
+        names.sort();
+        format!("[감시] {} | 재시작:{} 격상:{}", names.join(" "), self.total_restarts, self.total_escalations)
+    }
+}
+
+// ═══════════════════════════════════════
+// 데모
+// ═══════════════════════════════════════
+
+pub fn demo_watchdog() {
+    println!("╔═══════════════════════════════════════════════╗");
+    println!("║  Crowny Watchdog — 컴포넌트 감시/재시작         ║");
+    println!("╚═══════════════════════════════════════════════╝");
+    println!();
+
+    let mut wd = Watchdog::new(3, 2);
+    wd.add_sink(Box::new(StderrSink));
+    let t0 = 1_000_000u64;
+
+    wd.register_at(ComponentKind::Scheduler, 100, Box::new(|_| Ok(())), t0);
+    wd.register_at(ComponentKind::WebListener, 100, Box::new(|_| Ok(())), t0);
+
+    // 노드 동기화: 체크포인트(블록 높이)에서 재개
+    let resumed = Rc::new(RefCell::new(String::new()));
+    let r = resumed.clone();
+    wd.register_at(ComponentKind::NodeSync, 200, Box::new(move |cp| {
+        *r.borrow_mut() = cp.unwrap_or("0").to_string();
+        Ok(())
+    }), t0);
+
+    // 블록 생산자: 재시작 항상 실패
+    wd.register_at(ComponentKind::BlockProducer, 100,
+        Box::new(|_| Err("검증자 키 로드 실패".into())), t0);
+
+    println!("━━━ 1. 정상 하트비트 ━━━");
+    for step in 1..=3 {
+        let now = t0 + step * 100;
+        for k in [ComponentKind::Scheduler, ComponentKind::WebListener, ComponentKind::BlockProducer] {
+            wd.heartbeat_at(&k, None, now);
+        }
+        wd.heartbeat_at(&ComponentKind::NodeSync, Some(&format!("height={}", step * 10)), now);
+        wd.tick_at(now);
+    }
+    println!("  {}", wd.summary());
+
+    println!("\n━━━ 2. 노드 동기화 + 블록 생산자 정지 ━━━");
+    for step in 4..=12 {
+        let now = t0 + step * 100;
+        wd.heartbeat_at(&ComponentKind::Scheduler, None, now);
+        wd.heartbeat_at(&ComponentKind::WebListener, None, now);
+        for action in wd.tick_at(now) {
+            println!("  t+{}ms {:?}", step * 100, action);
+        }
+    }
+    println!("  노드 동기화 복구 지점: {}", resumed.borrow());
+    println!("  {}", wd.summary());
+    println!("\n{}", wd.log.dump_recent(5));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_with_checkpoint() {
+        let mut wd = Watchdog::new(3, 2);
+        let seen = Rc::new(RefCell::new(None));
+        let s = seen.clone();
+        wd.register_at(ComponentKind::NodeSync, 100, Box::new(move |cp| {
+            *s.borrow_mut() = cp.map(|c| c.to_string());
+            Ok(())
+        }), 0);
+
+        wd.heartbeat_at(&ComponentKind::NodeSync, Some("height=42"), 100);
+        assert!(wd.tick_at(300).is_empty()); // 2회 누락 — 아직 정상
+        assert_eq!(wd.tick_at(400), vec![WatchdogAction::Restarted(ComponentKind::NodeSync)]);
+        assert_eq!(seen.borrow().as_deref(), Some("height=42"));
+        assert_eq!(wd.component(&ComponentKind::NodeSync).unwrap().restarts, 1);
+        assert_eq!(wd.log.filter_trit(TritState::Failed).len(), 1);
+    }
+
+    #[test]
+    fn test_escalation_to_sinks() {
+        let mut wd = Watchdog::new(1, 2);
+        wd.add_sink(Box::new(MemorySink::default()));
+        wd.register_at(ComponentKind::BlockProducer, 100, Box::new(|_| Err("down".into())), 0);
+
+        let a1 = wd.tick_at(100);
+        assert_eq!(a1, vec![WatchdogAction::RestartFailed(ComponentKind::BlockProducer, "down".into())]);
+        let a2 = wd.tick_at(200);
+        assert!(a2.contains(&WatchdogAction::Escalated(ComponentKind::BlockProducer)));
+        assert_eq!(wd.total_escalations, 1);
+        assert_eq!(wd.component(&ComponentKind::BlockProducer).unwrap().state, TritState::Failed);
+        // 같은 실패 연속 구간에서는 한 번만 격상
+        assert!(!wd.tick_at(300).contains(&WatchdogAction::Escalated(ComponentKind::BlockProducer)));
+        assert_eq!(wd.total_escalations, 1);
+        assert_eq!(wd.component(&ComponentKind::BlockProducer).unwrap().state, TritState::Failed);
+    }
+
+    #[test]
+    fn test_heartbeat_keeps_alive() {
+        let mut wd = Watchdog::new(2, 1);
+        wd.register_at(ComponentKind::Scheduler, 50, Box::new(|_| Ok(())), 0);
+        for t in (50..=1000).step_by(50) {
+            wd.heartbeat_at(&ComponentKind::Scheduler, None, t);
+            assert!(wd.tick_at(t).is_empty());
+        }
+        assert!(!wd.heartbeat_at(&ComponentKind::NodeSync, None, 0));
+    }
+
+    #[test]
+    fn test_handle_beats_from_thread_then_stall_restarts() {
+        let mut wd = Watchdog::new(3, 3);
+        let restarted = Rc::new(RefCell::new(Vec::new()));
+        let r = restarted.clone();
+        wd.register_at(ComponentKind::WebListener, 100, Box::new(move |cp| {
+            r.borrow_mut().push(cp.map(str::to_string));
+            Ok(())
+        }), 0);
+
+        // 다른 스레드의 하트비트는 다음 tick에서 반영
+        let hb = wd.heartbeat_handle(ComponentKind::WebListener);
+        std::thread::spawn(move || {
+            for t in (100..=1000).step_by(100) {
+                hb.beat_at(Some(&format!("accepted={}", t / 100)), t);
+            }
+        }).join().unwrap();
+        assert!(wd.tick_at(1000).is_empty());
+        assert!(wd.tick_at(1200).is_empty());
+
+        // 하트비트가 멎은 컴포넌트 → 마지막 체크포인트로 재시작
+        assert_eq!(wd.tick_at(1300), vec![WatchdogAction::Restarted(ComponentKind::WebListener)]);
+        assert_eq!(*restarted.borrow(), vec![Some("accepted=10".to_string())]);
+        // 늦게 도착한 옛 하트비트가 시계를 되돌리지 않는다
+        wd.heartbeat_at(&ComponentKind::WebListener, None, 500);
+        assert_eq!(wd.component(&ComponentKind::WebListener).unwrap().last_beat, 1300);
+
+        assert!(wd.unregister(&ComponentKind::WebListener));
+        assert!(wd.tick_at(10_000).is_empty());
+    }
+}
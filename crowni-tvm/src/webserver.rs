@@ -13,9 +13,67 @@
 ///!   프롬프트 → 모델 선택 → API 호출 → Trit 판정 → TritResult 반환
 ///!
 ///! 모든 실행은 CAR 경유. 직접 Meta-Kernel 호출 금지.
+///!
+///! 마켓 API (mount_market_api):
+///!   DEX 견적/스왑/유동성 · NFT 목록/등록/구매/입찰
+///!   X-Crowny-Capability 서명 토큰 필수 — 토큰 subject가 곧 사용자
+///!
+///! 에어드롭 API (mount_airdrop_api):
+///!   CSV 목록에 토큰 · NFT 배포 — 청크 단위, 같은 id로 재개
+///!
+///! 마진 API (mount_margin_api):
+///!   담보 입출금 · 레버리지 포지션 열기/닫기 · 청산 검사 — 같은 토큰 방식
+///!
+///! 포트폴리오 API (mount_portfolio_api):
+///!   보유 자산 평가 · 손익 시계열 — 같은 토큰 방식
+///!
+///! 기록 조회 API (mount_history_api):
+///!   스왑 · 마켓 거래 · CAR 작업 기록 — ?cursor=&limit=&since=&until=&trit=&account=&order=
 
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use crate::car::{TritState, TritResult, ResultData, AppTask, TaskType, CrownyRuntime};
+use crate::vm::{ExecLimits, LimitKind};
+use crate::program_limits::{ProgramLimitKind, ProgramLimits};
+use crate::config::{ConfigManager, Reloadable, RuntimeConfig};
+use crate::integrations::{EventKind, SharedWebhooks};
+use crate::cron::JobScheduler;
+use crate::rpc::LogRpc;
+use crate::content::SharedContent;
+use crate::artifacts::SharedArtifacts;
+use crate::account::{AccountLedger, SharedAccounts};
+use crate::billing::{self, Budget, Resource, SharedAccounting, Usage};
+use crate::capability::{TokenSigner, TOKEN_HEADER};
+use crate::llm_backend::{EchoBackend, LlmBackend};
+use crate::watchdog::{ComponentKind, Heartbeat, SharedWatchdog};
+use crate::cpm::{self, CrownyPM};
+use crate::trit_store::{StoreValue, TritStore};
+use crate::crypto::{sha256, to_hex};
+use crate::admin_override::{self, OverrideRequest, SharedAudit};
+use crate::crossbridge::CrownyBridge;
+use crate::chain::CrownyChain;
+use crate::kernel::{CrownyKernel, SubmitOutcome};
+use crate::dex::CrownyDEX;
+use crate::margin::{schedule_liquidations, MarginEngine, PositionSide};
+use crate::nft::CrownyNFT;
+use crate::royalty::RoyaltyPolicy;
+use crate::airdrop::{self, Airdrop, ChunkLimits};
+use crate::params::ParamRegistry;
+use crate::token::TokenEngine;
+use crate::portfolio::PortfolioService;
+use crate::query::{Page, Query};
+use crate::output::JsonObject;
+use crate::i18n::{self, tr};
+use crate::network::{self, CtpError};
+use crate::trit_log::{Category, EventBuilder, Level, TritEventLog};
+use crate::websocket::{self, EventHub};
 
 // ═══════════════════════════════════════════════
 // CTP (Crowny Trit Protocol) 요청/응답
@@ -48,6 +106,10 @@ impl CtpHeader {
         Self { state: -1, permission: 0, consensus: 0, transaction: -1, routing: 0, reserved: [0; 4] }
     }
 
+    pub fn pending() -> Self {
+        Self { state: 0, permission: 1, consensus: 0, transaction: 0, routing: 1, reserved: [0; 4] }
+    }
+
     /// X-Crowny-Trit 헤더 문자열 파싱
     pub fn from_header_str(s: &str) -> Self {
         let trits: Vec<i8> = s.chars()
@@ -78,6 +140,27 @@ impl CtpHeader {
             t(self.reserved[2]), t(self.reserved[3]))
     }
 
+    /// 프로토콜 버전 — reserved[0..2] (0 = 미지정, 구버전 클라이언트)
+    pub fn version(&self) -> i8 {
+        network::version_from_trits(self.reserved[0], self.reserved[1])
+    }
+
+    pub fn with_version(mut self, version: u8) -> Self {
+        let [high, low] = network::version_trits(version);
+        self.reserved[0] = high;
+        self.reserved[1] = low;
+        self
+    }
+
+    /// 클라이언트가 보낸 최고 버전과 서버 범위(local)로 협상 — 미지정이면 None
+    pub fn negotiate(&self, local: (u8, u8)) -> Option<Result<u8, CtpError>> {
+        match self.version() {
+            0 => None,
+            v if v < 0 => Some(Err(CtpError::UnsupportedVersion { version: v, min: local.0, max: local.1 })),
+            v => Some(network::negotiate_version(local, (1, v as u8))),
+        }
+    }
+
     /// Trit 상태
     pub fn overall_state(&self) -> TritState {
         // 하나라도 -1이면 실패 (하향 안정성 원칙)
@@ -93,6 +176,126 @@ impl std::fmt::Display for CtpHeader {
     }
 }
 
+/// 27-Trit 확장 헤더 — 9-trit 헤더와 별도로 X-Crowny-Trit-V2에 싣는다
+/// 구버전 서버는 이 헤더를 모르므로 응답에 V2가 없으면 클라이언트는 9-trit만 쓴다
+pub const CTP_V2_HEADER: &str = "X-Crowny-Trit-V2";
+pub const CTP_V2_TRITS: usize = 27;
+/// [9..11] 헤더 형식 뱅크 값
+pub const CTP_V2_FORMAT: u8 = 2;
+/// 요청 ID 9-trit 범위 (3^9) — 넘으면 되감는다
+const REQUEST_ID_SPAN: u64 = 19_683;
+/// 홉 수 3-trit 최댓값
+pub const MAX_HOPS: u8 = 13;
+
+/// CTP 27-Trit 헤더
+///   [0..9]   기존 9-trit 헤더 (reserved[0..2] 버전 포함 — 구버전 해석기 호환)
+///   [9..11]  헤더 형식 (= 2)
+///   [11..13] 지원 최소 프로토콜 버전
+///   [13..15] 지원 최대 프로토콜 버전
+///   [15..24] 요청 ID (0..19682, 되감김)
+///   [24..27] 홉 수 (0..13)
+#[derive(Debug, Clone)]
+pub struct CtpHeaderV2 {
+    pub base: CtpHeader,
+    pub versions: (u8, u8),
+    pub request_id: u16,
+    pub hops: u8,
+}
+
+/// 정수 → 균형 3진 (앞이 최상위)
+fn put_balanced(out: &mut [i8], value: i64) {
+    let mut v = value;
+    for slot in out.iter_mut().rev() {
+        let low = (v + 1).rem_euclid(3) - 1;
+        *slot = low as i8;
+        v = (v - low) / 3;
+    }
+}
+
+fn get_balanced(trits: &[i8]) -> i64 {
+    trits.iter().fold(0, |acc, &t| acc * 3 + t as i64)
+}
+
+impl CtpHeaderV2 {
+    /// 이 노드의 지원 버전 범위로 새 요청 헤더
+    pub fn new(base: CtpHeader, request_id: u64) -> Self {
+        Self {
+            base,
+            versions: (network::MIN_PROTOCOL_VERSION, network::PROTOCOL_VERSION),
+            request_id: (request_id % REQUEST_ID_SPAN) as u16,
+            hops: 0,
+        }
+    }
+
+    pub fn with_versions(mut self, versions: (u8, u8)) -> Self {
+        self.versions = versions;
+        self
+    }
+
+    /// 27-trit 문자열 파싱 — 길이나 형식 뱅크가 다르면 None
+    pub fn from_header_str(s: &str) -> Option<Self> {
+        let trits: Vec<i8> = s.chars()
+            .filter_map(|c| match c {
+                'P' | '+' | '1' => Some(1),
+                'O' | '0' => Some(0),
+                'T' | '-' => Some(-1),
+                _ => None,
+            })
+            .collect();
+        if trits.len() != CTP_V2_TRITS || network::version_from_trits(trits[9], trits[10]) != CTP_V2_FORMAT as i8 {
+            return None;
+        }
+        let version = |i: usize| network::version_from_trits(trits[i], trits[i + 1]).max(0) as u8;
+        let base: String = trits[..9].iter().map(|&t| match t { 1 => 'P', -1 => 'T', _ => 'O' }).collect();
+        Some(Self {
+            base: CtpHeader::from_header_str(&base),
+            versions: (version(11), version(13)),
+            request_id: (get_balanced(&trits[15..24]) + (REQUEST_ID_SPAN as i64 - 1) / 2) as u16,
+            hops: get_balanced(&trits[24..27]).clamp(0, MAX_HOPS as i64) as u8,
+        })
+    }
+
+    pub fn to_trits(&self) -> [i8; CTP_V2_TRITS] {
+        let mut t = [0i8; CTP_V2_TRITS];
+        let base = self.base.to_header_str();
+        for (slot, c) in t.iter_mut().zip(base.chars()) {
+            *slot = match c { 'P' => 1, 'T' => -1, _ => 0 };
+        }
+        t[9..11].copy_from_slice(&network::version_trits(CTP_V2_FORMAT));
+        t[11..13].copy_from_slice(&network::version_trits(self.versions.0));
+        t[13..15].copy_from_slice(&network::version_trits(self.versions.1));
+        put_balanced(&mut t[15..24], self.request_id as i64 - (REQUEST_ID_SPAN as i64 - 1) / 2);
+        put_balanced(&mut t[24..27], self.hops.min(MAX_HOPS) as i64);
+        t
+    }
+
+    pub fn to_header_str(&self) -> String {
+        self.to_trits().iter().map(|&v| match v { 1 => 'P', -1 => 'T', _ => 'O' }).collect()
+    }
+
+    /// 중계 노드가 넘길 때 — 홉 수 +1 (상한에서 멈춤)
+    pub fn next_hop(mut self) -> Self {
+        self.hops = (self.hops + 1).min(MAX_HOPS);
+        self
+    }
+
+    /// 버전 뱅크로 협상 — 9-trit 헤더의 단일 버전보다 범위가 정확하다
+    pub fn negotiate(&self, local: (u8, u8)) -> Result<u8, CtpError> {
+        network::negotiate_version(local, self.versions)
+    }
+
+    /// 같은 요청 ID · 홉 수로 응답 헤더 생성
+    pub fn reply(&self, base: &CtpHeader, local: (u8, u8)) -> Self {
+        Self { base: base.clone(), versions: local, request_id: self.request_id, hops: self.hops }
+    }
+}
+
+impl std::fmt::Display for CtpHeaderV2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[CTP2:{} req={} hop={}]", self.base.to_header_str(), self.request_id, self.hops)
+    }
+}
+
 // ═══════════════════════════════════════════════
 // HTTP 요청/응답 (경량 구조체)
 // ═══════════════════════════════════════════════
@@ -104,6 +307,21 @@ pub enum HttpMethod {
     Post,
     Put,
     Delete,
+    Options,
+}
+
+impl HttpMethod {
+    /// 요청 라인 메서드 파싱
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "GET" => Some(HttpMethod::Get),
+            "POST" => Some(HttpMethod::Post),
+            "PUT" => Some(HttpMethod::Put),
+            "DELETE" => Some(HttpMethod::Delete),
+            "OPTIONS" => Some(HttpMethod::Options),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for HttpMethod {
@@ -113,6 +331,7 @@ impl std::fmt::Display for HttpMethod {
             HttpMethod::Post => write!(f, "POST"),
             HttpMethod::Put => write!(f, "PUT"),
             HttpMethod::Delete => write!(f, "DELETE"),
+            HttpMethod::Options => write!(f, "OPTIONS"),
         }
     }
 }
@@ -125,6 +344,11 @@ pub struct HttpRequest {
     pub headers: HashMap<String, String>,
     pub body: String,
     pub ctp: CtpHeader,
+    pub version: String,
+    /// 라우트 패턴에서 뽑은 경로 파라미터 (":id" → "id", 끝의 "*" → "*")
+    pub params: HashMap<String, String>,
+    /// 상대 IP (listen()으로 받은 요청만)
+    pub peer: Option<String>,
 }
 
 impl HttpRequest {
@@ -135,6 +359,43 @@ impl HttpRequest {
             headers: HashMap::new(),
             body: String::new(),
             ctp: CtpHeader::new(),
+            version: "HTTP/1.1".into(),
+            params: HashMap::new(),
+            peer: None,
+        }
+    }
+
+    /// 경로 파라미터 조회
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(|v| v.as_str())
+    }
+
+    /// 쿼리 문자열을 뺀 경로 (라우트 매칭용)
+    pub fn route_path(&self) -> &str {
+        self.path.split_once('?').map_or(&self.path, |(p, _)| p)
+    }
+
+    /// ?key=value&... 파라미터
+    pub fn query(&self) -> HashMap<String, String> {
+        self.path.split_once('?').map(|(_, q)| form_params(q)).unwrap_or_default()
+    }
+
+    /// 헤더 조회 (대소문자 무시)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 연결 유지 여부
+    /// HTTP/1.1: 기본 유지, "Connection: close"면 종료
+    /// HTTP/1.0: "Connection: keep-alive"일 때만 유지
+    pub fn wants_keep_alive(&self) -> bool {
+        let conn = self.header("Connection").map(|v| v.to_ascii_lowercase());
+        match conn.as_deref() {
+            Some("close") => false,
+            Some("keep-alive") => true,
+            _ => self.version != "HTTP/1.0",
         }
     }
 
@@ -160,400 +421,4438 @@ pub struct HttpResponse {
     pub status: u16,
     pub headers: HashMap<String, String>,
     pub body: String,
+    /// 바이너리 본문 — 있으면 body 대신 그대로 전송 (이미지 등 /content)
+    pub bytes: Option<Vec<u8>>,
     pub ctp: CtpHeader,
     pub trit_result: TritResult,
 }
 
+impl HttpResponse {
+    /// 전송할 본문 바이트
+    pub fn body_bytes(&self) -> &[u8] {
+        self.bytes.as_deref().unwrap_or(self.body.as_bytes())
+    }
+}
+
 // ═══════════════════════════════════════════════
-// 라우터
+// 실행 샌드박스 정책
 // ═══════════════════════════════════════════════
 
-/// 라우트 핸들러 타입
-type HandlerFn = Box<dyn Fn(&HttpRequest, &mut CrownyRuntime) -> HttpResponse>;
-
-/// 라우트
-struct Route {
-    method: HttpMethod,
-    path: String,
-    handler: HandlerFn,
-}
+/// P 단계 샌드박스에 필요한 능력 범위
+pub const TRUSTED_RUN_SCOPE: &str = "run.trusted";
 
-/// Crowny 웹서버 (경량)
-pub struct CrownyServer {
-    routes: Vec<Route>,
-    port: u16,
-    request_count: u64,
+/// 권한 트릿별 실행 한도 + 프로그램 한도
+/// [0]=T(차단) [1]=O(게스트) [2]=P(인증)
+#[derive(Debug, Clone, Copy)]
+pub struct SandboxPolicy {
+    pub levels: [ExecLimits; 3],
+    pub programs: [ProgramLimits; 3],
 }
 
-impl CrownyServer {
-    pub fn new(port: u16) -> Self {
-        println!("[서버] Crowny Web Server 초기화 — 포트 {}", port);
-        Self { routes: Vec::new(), port, request_count: 0 }
+impl SandboxPolicy {
+    /// 권한 트릿 → 실행 한도
+    pub fn for_permission(&self, permission: i8) -> ExecLimits {
+        self.levels[(permission.clamp(-1, 1) + 1) as usize]
     }
 
-    /// 라우트 등록
-    pub fn route(
-        &mut self,
-        method: HttpMethod,
-        path: &str,
-        handler: impl Fn(&HttpRequest, &mut CrownyRuntime) -> HttpResponse + 'static,
-    ) {
-        self.routes.push(Route {
-            method,
-            path: path.to_string(),
-            handler: Box::new(handler),
-        });
+    /// 권한 트릿 → 프로그램 한도 (어셈블 시점)
+    pub fn program_for_permission(&self, permission: i8) -> ProgramLimits {
+        self.programs[(permission.clamp(-1, 1) + 1) as usize]
     }
+}
 
-    /// 요청 처리 (시뮬레이션)
-    pub fn handle(&mut self, req: &HttpRequest, car: &mut CrownyRuntime) -> HttpResponse {
-        self.request_count += 1;
+/// 요청의 샌드박스 단계 — P는 run.trusted 토큰으로 인증된 주체만.
+/// 클라이언트가 보낸 CTP 권한 트릿은 T(최소 단계 자청)일 때만 반영한다
+pub fn sandbox_tier(req: &HttpRequest, signer: Option<&TokenSigner>) -> i8 {
+    if req.ctp.permission < 0 {
+        return -1;
+    }
+    signer
+        .and_then(|s| s.verify(req.header(TOKEN_HEADER), TRUSTED_RUN_SCOPE).ok())
+        .map_or(0, |_| 1)
+}
 
-        // CTP 헤더 검증
-        let ctp_state = req.ctp.overall_state();
-        if ctp_state == TritState::Failed {
-            return HttpResponse {
-                status: 403,
-                headers: HashMap::new(),
-                body: "{\"상태\":\"T\",\"오류\":\"CTP 권한 거부\"}".into(),
-                ctp: CtpHeader::failed(),
-                trit_result: TritResult {
-                    state: TritState::Failed,
-                    data: ResultData::Text("CTP 권한 거부".into()),
-                    elapsed_ms: 0,
-                    task_id: 0,
-                },
-            };
+impl Default for SandboxPolicy {
+    fn default() -> Self {
+        Self {
+            levels: [
+                // T: 최소
+                ExecLimits { max_cycles: Some(1_000), max_heap: Some(16), max_output_bytes: Some(256), max_ms: Some(100) },
+                // O: 게스트
+                ExecLimits { max_cycles: Some(10_000), max_heap: Some(256), max_output_bytes: Some(4 * 1024), max_ms: Some(1_000) },
+                // P: 인증 사용자
+                ExecLimits { max_cycles: Some(1_000_000), max_heap: Some(65_536), max_output_bytes: Some(1024 * 1024), max_ms: Some(10_000) },
+            ],
+            programs: [
+                ProgramLimits { max_instructions: Some(100), max_nesting: Some(4), max_string_bytes: Some(256), max_functions: Some(4) },
+                ProgramLimits::shared(),
+                ProgramLimits { max_instructions: Some(100_000), max_nesting: Some(64), max_string_bytes: Some(64 * 1024), max_functions: Some(1_024) },
+            ],
         }
+    }
+}
 
-        // 라우트 매칭
-        for route in &self.routes {
-            if route.method == req.method && route.path == req.path {
-                return (route.handler)(req, car);
-            }
-        }
+/// 한도 초과 → HTTP 상태 코드
+/// 사이클/실행시간: 429 (O, 재시도 가능) / 힙/출력: 413 (T)
+pub fn limit_status(kind: LimitKind) -> u16 {
+    match kind {
+        LimitKind::Cycles | LimitKind::WallClock => 429,
+        LimitKind::Heap | LimitKind::Output => 413,
+    }
+}
 
-        // 404
-        HttpResponse {
-            status: 404,
-            headers: HashMap::new(),
-            body: "{\"상태\":\"T\",\"오류\":\"경로 없음\"}".into(),
-            ctp: CtpHeader::failed(),
-            trit_result: TritResult {
-                state: TritState::Failed,
-                data: ResultData::Text("404".into()),
-                elapsed_ms: 0,
-                task_id: 0,
-            },
-        }
+/// 결과 데이터에서 프로그램 한도 초과 종류 추출
+fn program_limit_of(data: &ResultData) -> Option<ProgramLimitKind> {
+    match data {
+        ResultData::Map(m) => match m.get("한도") {
+            Some(ResultData::Text(code)) => ProgramLimitKind::from_code(code),
+            _ => None,
+        },
+        _ => None,
     }
+}
 
-    pub fn stats(&self) -> String {
-        format!("[서버] 포트:{} 라우트:{} 요청:{}", self.port, self.routes.len(), self.request_count)
+/// 프로그램 한도 초과 → 413 + T (실행 전 거부)
+fn program_limit_response(kind: ProgramLimitKind, result: TritResult) -> HttpResponse {
+    HttpResponse {
+        status: 413,
+        headers: HashMap::new(),
+        body: format!("{{\"상태\":\"T\",\"오류\":\"{}\",\"한도\":\"{}\",\"trit_result\":{}}}",
+            i18n::t("web.program_limit"), kind.code(), result.to_json().build()),
+        bytes: None,
+        ctp: CtpHeader::failed(),
+        trit_result: result,
+    }
+}
+
+/// 결과 데이터에서 한도 초과 종류 추출
+fn limit_of(data: &ResultData) -> Option<LimitKind> {
+    match data {
+        ResultData::Map(m) => match m.get("한도") {
+            Some(ResultData::Text(code)) => LimitKind::from_code(code),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
 // ═══════════════════════════════════════════════
-// LLM 호출기
+// 서버 설정 (CORS / 본문 한도 / Keep-Alive)
 // ═══════════════════════════════════════════════
 
-/// LLM 모델 종류
-#[derive(Debug, Clone, PartialEq)]
-pub enum LlmModel {
-    Claude,
-    Gpt4,
-    Gemini,
-    Local,    // 로컬 모델
-    Custom(String),
+/// CORS 정책
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>, // "*" = 전체 허용
+    pub allowed_methods: Vec<HttpMethod>,
+    pub allowed_headers: Vec<String>,
+    pub max_age_secs: u32,
 }
 
-impl std::fmt::Display for LlmModel {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            LlmModel::Claude => write!(f, "Claude"),
-            LlmModel::Gpt4 => write!(f, "GPT-4"),
-            LlmModel::Gemini => write!(f, "Gemini"),
-            LlmModel::Local => write!(f, "Local"),
-            LlmModel::Custom(s) => write!(f, "{}", s),
+impl CorsConfig {
+    /// 지정한 오리진만 허용
+    pub fn with_origins(origins: &[&str]) -> Self {
+        Self {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allowed_origins.iter().any(|o| o == "*" || o == origin)
+    }
+
+    pub fn allows_method(&self, method: &str) -> bool {
+        self.allowed_methods.iter().any(|m| m.to_string() == method)
+    }
+
+    fn methods_str(&self) -> String {
+        self.allowed_methods.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// 응답에 CORS 헤더 부착
+    fn apply(&self, origin: &str, headers: &mut HashMap<String, String>) {
+        if self.allowed_origins.iter().any(|o| o == "*") {
+            headers.insert("Access-Control-Allow-Origin".into(), "*".into());
+        } else {
+            headers.insert("Access-Control-Allow-Origin".into(), origin.to_string());
+            headers.insert("Vary".into(), "Origin".into());
         }
+        // 브라우저가 Trit 결과 헤더를 읽을 수 있도록
+        headers.insert("Access-Control-Expose-Headers".into(), format!("X-Crowny-Trit, {}", CTP_V2_HEADER));
     }
 }
 
-/// LLM 요청
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec!["*".into()],
+            allowed_methods: vec![
+                HttpMethod::Get, HttpMethod::Post, HttpMethod::Put,
+                HttpMethod::Delete, HttpMethod::Options,
+            ],
+            allowed_headers: vec!["Content-Type".into(), "X-Crowny-Trit".into(), CTP_V2_HEADER.into(), TOKEN_HEADER.into()],
+            max_age_secs: 600,
+        }
+    }
+}
+
+/// 서버 설정
 #[derive(Debug, Clone)]
-pub struct LlmRequest {
-    pub model: LlmModel,
-    pub prompt: String,
-    pub system: Option<String>,
-    pub temperature: f32,
-    pub max_tokens: u32,
-    pub params: HashMap<String, String>,
+pub struct ServerConfig {
+    pub cors: Option<CorsConfig>,     // None = CORS 비활성
+    pub max_body_bytes: usize,
+    pub keep_alive: bool,
+    pub idle_timeout_ms: u64,
+    pub max_requests_per_conn: usize,
+    /// 받아주는 CTP 버전 (최저, 최고) — 롤링 업그레이드 중 최저를 올린다
+    pub protocol_versions: (u8, u8),
+    /// listen() 워커 스레드 수 (연결 읽기/쓰기 담당)
+    pub workers: usize,
 }
 
-impl LlmRequest {
-    pub fn new(model: LlmModel, prompt: &str) -> Self {
+impl Default for ServerConfig {
+    fn default() -> Self {
         Self {
-            model,
-            prompt: prompt.to_string(),
-            system: None,
-            temperature: 0.7,
-            max_tokens: 1024,
-            params: HashMap::new(),
+            cors: Some(CorsConfig::default()),
+            max_body_bytes: 1024 * 1024,
+            keep_alive: true,
+            idle_timeout_ms: 5_000,
+            max_requests_per_conn: 100,
+            protocol_versions: (network::MIN_PROTOCOL_VERSION, network::PROTOCOL_VERSION),
+            workers: 4,
         }
     }
+}
 
-    pub fn with_system(mut self, sys: &str) -> Self {
-        self.system = Some(sys.to_string());
-        self
+/// 상태 코드 → 사유 문구
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        101 => "Switching Protocols",
+        200 => "OK",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        426 => "Upgrade Required",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Unknown",
     }
+}
 
-    pub fn with_temp(mut self, t: f32) -> Self {
-        self.temperature = t;
-        self
+/// 실패 응답 (T)
+fn error_response(status: u16, msg: &str) -> HttpResponse {
+    HttpResponse {
+        status,
+        headers: HashMap::new(),
+        body: format!("{{\"상태\":\"T\",\"오류\":\"{}\"}}", msg.replace('"', "\\\"")),
+        bytes: None,
+        ctp: CtpHeader::failed(),
+        trit_result: TritResult {
+            state: TritState::Failed,
+            data: ResultData::Text(msg.to_string()),
+            elapsed_ms: 0,
+            task_id: 0,
+        },
     }
 }
 
-/// LLM 응답
-#[derive(Debug, Clone)]
-pub struct LlmResponse {
-    pub text: String,
-    pub model: LlmModel,
-    pub tokens_used: u32,
-    pub trit_state: TritState,
-}
+// ═══════════════════════════════════════════════
+// HTTP/1.1 와이어 포맷
+// ═══════════════════════════════════════════════
 
-/// Crowny LLM 호출기 — 다중 모델 라우터
-pub struct CrownyLlm {
-    default_model: LlmModel,
-    call_count: u64,
-    total_tokens: u64,
-    // 모델별 API 키 (시뮬레이션)
-    api_keys: HashMap<String, String>,
+/// 헤더 한 줄 최대 길이 / 최대 헤더 수
+const MAX_HEADER_LINE: usize = 8 * 1024;
+const MAX_HEADERS: usize = 100;
+
+/// 요청 읽기 오류
+#[derive(Debug)]
+pub enum WireError {
+    Closed,               // 상대가 연결 종료
+    Timeout,              // 유휴 시간 초과
+    BodyTooLarge(usize),  // 선언된 본문 크기
+    Malformed(String),
+    Io(std::io::Error),
 }
 
-impl CrownyLlm {
-    pub fn new() -> Self {
-        println!("[LLM] Crowny LLM 호출기 초기화");
-        Self {
-            default_model: LlmModel::Claude,
-            call_count: 0,
-            total_tokens: 0,
-            api_keys: HashMap::new(),
+impl std::fmt::Display for WireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WireError::Closed => write!(f, "[연결종료]"),
+            WireError::Timeout => write!(f, "[유휴시간초과]"),
+            WireError::BodyTooLarge(n) => write!(f, "[본문초과] {} bytes", n),
+            WireError::Malformed(m) => write!(f, "[형식오류] {}", m),
+            WireError::Io(e) => write!(f, "[IO] {}", e),
         }
     }
+}
 
-    pub fn set_api_key(&mut self, model: &str, key: &str) {
-        self.api_keys.insert(model.to_string(), key.to_string());
+impl From<std::io::Error> for WireError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => WireError::Timeout,
+            _ => WireError::Io(e),
+        }
     }
+}
 
-    pub fn set_default_model(&mut self, model: LlmModel) {
-        self.default_model = model;
+/// 한 줄 읽기 (CRLF 제거, 길이 제한)
+fn read_line<R: BufRead>(r: &mut R) -> Result<Option<String>, WireError> {
+    let mut buf = Vec::new();
+    let n = r.by_ref().take(MAX_HEADER_LINE as u64 + 1).read_until(b'\n', &mut buf)?;
+    if n == 0 { return Ok(None); }
+    if buf.len() > MAX_HEADER_LINE {
+        return Err(WireError::Malformed("헤더 줄이 너무 김".into()));
     }
+    while matches!(buf.last(), Some(b'\n') | Some(b'\r')) { buf.pop(); }
+    String::from_utf8(buf).map(Some).map_err(|_| WireError::Malformed("UTF-8 아님".into()))
+}
 
-    /// LLM 호출 (CAR 경유)
-    pub fn call(&mut self, req: LlmRequest, car: &mut CrownyRuntime) -> TritResult {
-        let model_name = req.model.to_string();
-        let prompt = req.prompt.clone();
-
-        let task = AppTask::new(TaskType::LlmCall, &model_name, &prompt)
-            .with_param("temperature", &req.temperature.to_string())
-            .with_param("max_tokens", &req.max_tokens.to_string());
-
-        // 실제 API 호출 시뮬레이션
-        let call_count = &mut self.call_count;
-        let total_tokens = &mut self.total_tokens;
+/// 스트림에서 HTTP 요청 하나 읽기
+/// Content-Length가 한도를 넘으면 본문을 읽기 전에 거부
+pub fn read_request<R: BufRead>(r: &mut R, max_body: usize) -> Result<HttpRequest, WireError> {
+    // 요청 사이 빈 줄 허용
+    let line = loop {
+        match read_line(r)? {
+            None => return Err(WireError::Closed),
+            Some(l) if l.is_empty() => continue,
+            Some(l) => break l,
+        }
+    };
 
-        car.submit(task, |t| {
-            // ── 실제 프로덕션에서는 여기서 HTTP API 호출 ──
-            // 지금은 시뮬레이션
-            let response = simulate_llm_response(&t.payload, &model_name);
+    let mut parts = line.split_whitespace();
+    let (method, path, version) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(m), Some(p), Some(v)) => (m, p, v),
+        _ => return Err(WireError::Malformed(format!("요청 라인: {}", line))),
+    };
+    let method = HttpMethod::parse(method)
+        .ok_or_else(|| WireError::Malformed(format!("메서드: {}", method)))?;
 
-            *call_count += 1;
-            *total_tokens += response.tokens_used as u64;
+    let mut req = HttpRequest::new(method, path);
+    req.version = version.to_string();
 
-            // Trit 판정
-            let state = response.trit_state;
-            (state, ResultData::Text(response.text))
-        })
+    loop {
+        let line = read_line(r)?.ok_or(WireError::Closed)?;
+        if line.is_empty() { break; }
+        if req.headers.len() >= MAX_HEADERS {
+            return Err(WireError::Malformed("헤더 수 초과".into()));
+        }
+        let (k, v) = line.split_once(':')
+            .ok_or_else(|| WireError::Malformed(format!("헤더: {}", line)))?;
+        req.headers.insert(k.trim().to_string(), v.trim().to_string());
     }
 
-    /// 간편 호출
-    pub fn ask(&mut self, prompt: &str, car: &mut CrownyRuntime) -> TritResult {
-        let req = LlmRequest::new(self.default_model.clone(), prompt);
-        self.call(req, car)
+    if let Some(t) = req.header("X-Crowny-Trit") {
+        req.ctp = CtpHeader::from_header_str(t);
     }
 
-    /// 다중 모델 동시 호출 (합의)
-    pub fn consensus_call(
+    let chunked = req.header("Transfer-Encoding")
+        .is_some_and(|t| t.split(',').any(|c| c.trim().eq_ignore_ascii_case("chunked")));
+    if chunked {
+        // 둘 다 있으면 본문 경계가 모호 — 요청 밀반입 방지
+        if req.header("Content-Length").is_some() {
+            return Err(WireError::Malformed("Content-Length와 chunked 동시 사용".into()));
+        }
+        let body = read_chunked(r, max_body)?;
+        req.body = String::from_utf8(body).map_err(|_| WireError::Malformed("본문 UTF-8 아님".into()))?;
+        return Ok(req);
+    }
+
+    let len = match req.header("Content-Length") {
+        Some(v) => v.parse::<usize>()
+            .map_err(|_| WireError::Malformed(format!("Content-Length: {}", v)))?,
+        None => 0,
+    };
+    if len > max_body {
+        return Err(WireError::BodyTooLarge(len));
+    }
+
+    let mut body = vec![0u8; len];
+    r.read_exact(&mut body)?;
+    req.body = String::from_utf8(body).map_err(|_| WireError::Malformed("본문 UTF-8 아님".into()))?;
+    Ok(req)
+}
+
+/// chunked 본문 읽기 — 누적 크기가 한도를 넘으면 거부, 트레일러는 버림
+fn read_chunked<R: BufRead>(r: &mut R, max_body: usize) -> Result<Vec<u8>, WireError> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(r)?.ok_or(WireError::Closed)?;
+        // 청크 확장(";name=value")은 무시
+        let size = line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| WireError::Malformed(format!("청크 크기: {}", line)))?;
+        if size == 0 { break; }
+        let total = body.len().saturating_add(size);
+        if total > max_body {
+            return Err(WireError::BodyTooLarge(total));
+        }
+        let start = body.len();
+        body.resize(total, 0);
+        r.read_exact(&mut body[start..])?;
+        if !read_line(r)?.ok_or(WireError::Closed)?.is_empty() {
+            return Err(WireError::Malformed("청크 뒤 CRLF 없음".into()));
+        }
+    }
+    loop {
+        let line = read_line(r)?.ok_or(WireError::Closed)?;
+        if line.is_empty() { break; }
+    }
+    Ok(body)
+}
+
+/// 버전 불일치 — 426 + 구조화된 T (서버 지원 범위 포함)
+fn version_mismatch_response(e: &CtpError, local: (u8, u8)) -> HttpResponse {
+    let mut resp = error_response(426, &e.to_string());
+    resp.body = JsonObject::schema("crowny.version_mismatch")
+        .trit("state", -1)
+        .int("code", e.code() as i64)
+        .str("error", &e.to_string())
+        .int("min_version", local.0 as i64)
+        .int("max_version", local.1 as i64)
+        .build();
+    resp.ctp = resp.ctp.with_version(local.0);
+    resp
+}
+
+/// HTTP 응답 직렬화
+pub fn write_response<W: Write>(
+    w: &mut W,
+    resp: &HttpResponse,
+    keep_alive: bool,
+    idle_timeout_ms: u64,
+) -> std::io::Result<()> {
+    let mut out = format!("HTTP/1.1 {} {}\r\n", resp.status, reason_phrase(resp.status));
+    let mut keys: Vec<&String> = resp.headers.keys().collect();
+    keys.sort();
+    for k in keys {
+        out.push_str(&format!("{}: {}\r\n", k, resp.headers[k]));
+    }
+    out.push_str(&format!("X-Crowny-Trit: {}\r\n", resp.ctp.to_header_str()));
+    if !resp.headers.contains_key("Content-Type") {
+        out.push_str("Content-Type: application/json; charset=utf-8\r\n");
+    }
+    out.push_str(&format!("Content-Length: {}\r\n", resp.body_bytes().len()));
+    if keep_alive {
+        out.push_str("Connection: keep-alive\r\n");
+        out.push_str(&format!("Keep-Alive: timeout={}\r\n", idle_timeout_ms.div_ceil(1000)));
+    } else {
+        out.push_str("Connection: close\r\n");
+    }
+    out.push_str("\r\n");
+    w.write_all(out.as_bytes())?;
+    w.write_all(resp.body_bytes())?;
+    w.flush()
+}
+
+// ═══════════════════════════════════════════════
+// 라우터
+// ═══════════════════════════════════════════════
+
+/// 라우트 핸들러 타입
+type HandlerFn = Box<dyn Fn(&HttpRequest, &mut CrownyRuntime) -> HttpResponse>;
+
+/// 라우트
+struct Route {
+    method: HttpMethod,
+    path: String,
+    handler: HandlerFn,
+}
+
+impl Route {
+    /// 세그먼트 단위 매칭 — 일치하면 경로 파라미터 반환
+    /// ":name"은 비어 있지 않은 한 세그먼트, "*"는 한 세그먼트,
+    /// 마지막 "*"는 나머지 전체 (비어 있지 않을 때)
+    fn match_path(&self, path: &str) -> Option<HashMap<String, String>> {
+        let mut params = HashMap::new();
+        let mut segs = path.split('/');
+        let mut pattern = self.path.split('/').peekable();
+        while let Some(p) = pattern.next() {
+            if p == "*" && pattern.peek().is_none() {
+                let rest = segs.collect::<Vec<_>>().join("/");
+                if rest.is_empty() { return None; }
+                params.insert("*".into(), rest);
+                return Some(params);
+            }
+            let s = segs.next()?;
+            if let Some(name) = p.strip_prefix(':') {
+                if s.is_empty() { return None; }
+                params.insert(name.to_string(), s.to_string());
+            } else if p != s && (p != "*" || s.is_empty()) {
+                return None;
+            }
+        }
+        segs.next().is_none().then_some(params)
+    }
+}
+
+// ═══════════════════════════════════════════════
+// 미들웨어
+// ═══════════════════════════════════════════════
+
+/// 미들웨어 — 라우팅 전에 등록 순서대로 before, 응답이 정해지면 역순으로 after
+/// (본문 한도 · 프리플라이트 · 버전 불일치 응답에는 끼지 않는다)
+pub trait Middleware {
+    /// Some(응답)이면 뒤의 미들웨어와 핸들러를 건너뛴다
+    fn before(&mut self, _req: &HttpRequest, _car: &mut CrownyRuntime) -> Option<HttpResponse> {
+        None
+    }
+
+    /// before가 불린 미들웨어만 호출된다
+    fn after(&mut self, _req: &HttpRequest, _resp: &mut HttpResponse) {}
+}
+
+/// CTP 권한 트릿 검사 — prefix 아래 경로에 최소 권한 요구
+/// 권한 T는 403, 요구치 미달(게스트 등)은 401
+pub struct CtpAuth {
+    prefix: String,
+    min_permission: i8,
+}
+
+impl CtpAuth {
+    pub fn require(min_permission: i8) -> Self {
+        Self { prefix: "/".into(), min_permission }
+    }
+
+    pub fn under(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+}
+
+impl Middleware for CtpAuth {
+    fn before(&mut self, req: &HttpRequest, _car: &mut CrownyRuntime) -> Option<HttpResponse> {
+        if !req.route_path().starts_with(&self.prefix) || req.ctp.permission >= self.min_permission {
+            return None;
+        }
+        Some(match req.ctp.permission {
+            -1 => error_response(403, i18n::t("web.ctp_denied")),
+            _ => error_response(401, i18n::t("web.ctp_auth_required")),
+        })
+    }
+}
+
+/// 요청 기록 — 응답마다 NET 이벤트 하나 (4xx는 WARN, 5xx는 ERROR)
+pub struct RequestLog {
+    log: Rc<RefCell<TritEventLog>>,
+    started: Option<std::time::Instant>,
+}
+
+impl RequestLog {
+    pub fn new(log: Rc<RefCell<TritEventLog>>) -> Self {
+        Self { log, started: None }
+    }
+}
+
+impl Middleware for RequestLog {
+    fn before(&mut self, _req: &HttpRequest, _car: &mut CrownyRuntime) -> Option<HttpResponse> {
+        self.started = Some(std::time::Instant::now());
+        None
+    }
+
+    fn after(&mut self, req: &HttpRequest, resp: &mut HttpResponse) {
+        let ms = self.started.take().map_or(0, |t| t.elapsed().as_millis());
+        let level = match resp.status {
+            500.. => Level::Error,
+            400.. => Level::Warn,
+            _ => Level::Info,
+        };
+        let event = EventBuilder::new(Category::Network, &format!("{} {} → {}", req.method, req.route_path(), resp.status))
+            .level(level)
+            .trit(resp.trit_result.state)
+            .source("web")
+            .field("method", &req.method.to_string())
+            .field("path", req.route_path())
+            .field("status", &resp.status.to_string())
+            .field("ms", &ms.to_string());
+        self.log.borrow_mut().log(event);
+    }
+}
+
+/// 상대별 토큰 버킷 — 초과 시 429 + O (재시도 가능), Retry-After 부착
+/// 상대 IP가 없는 요청(시뮬레이션)은 한 버킷을 공유한다
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: HashMap<String, (f64, u64)>,   // 키 → (남은 토큰, 마지막 갱신 ms)
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, refill_per_sec: f64) -> Self {
+        Self { capacity: capacity.max(1) as f64, refill_per_sec: refill_per_sec.max(0.0), buckets: HashMap::new() }
+    }
+
+    /// 토큰 하나 소비 — 모자라면 다음 토큰까지 남은 ms
+    pub fn check(&mut self, key: &str, now_ms: u64) -> Result<(), u64> {
+        let bucket = self.buckets.entry(key.to_string()).or_insert((self.capacity, now_ms));
+        let elapsed = now_ms.saturating_sub(bucket.1) as f64 / 1000.0;
+        bucket.0 = (bucket.0 + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.1 = now_ms;
+        if bucket.0 >= 1.0 {
+            bucket.0 -= 1.0;
+            return Ok(());
+        }
+        if self.refill_per_sec == 0.0 {
+            return Err(u64::MAX);
+        }
+        Err(((1.0 - bucket.0) / self.refill_per_sec * 1000.0).ceil() as u64)
+    }
+}
+
+/// [rate_limit] 재적재 — 순간 허용량 = burst, 초당 보충 = 분당 한도 / 60
+impl Reloadable for RateLimiter {
+    fn apply_config(&mut self, config: &RuntimeConfig) {
+        self.capacity = config.rate_limit_burst.max(1) as f64;
+        self.refill_per_sec = config.rate_limit_per_min as f64 / 60.0;
+        for bucket in self.buckets.values_mut() {
+            bucket.0 = bucket.0.min(self.capacity);
+        }
+    }
+}
+
+impl Middleware for RateLimiter {
+    fn before(&mut self, req: &HttpRequest, _car: &mut CrownyRuntime) -> Option<HttpResponse> {
+        let key = req.peer.as_deref().unwrap_or("-");
+        let wait_ms = self.check(key, crate::cron::now_ms()).err()?;
+        let mut resp = error_response(429, i18n::t("web.rate_limited"));
+        resp.body = format!("{{\"상태\":\"O\",\"오류\":\"{}\"}}", i18n::t("web.rate_limited"));
+        resp.ctp = CtpHeader::pending();
+        resp.trit_result.state = TritState::Pending;
+        resp.headers.insert("Retry-After".into(), wait_ms.div_ceil(1000).max(1).to_string());
+        Some(resp)
+    }
+}
+
+/// 공유 미들웨어 — 설정 재적재 등 서버 밖에서도 고칠 수 있게
+impl<M: Middleware> Middleware for Rc<RefCell<M>> {
+    fn before(&mut self, req: &HttpRequest, car: &mut CrownyRuntime) -> Option<HttpResponse> {
+        self.borrow_mut().before(req, car)
+    }
+
+    fn after(&mut self, req: &HttpRequest, resp: &mut HttpResponse) {
+        self.borrow_mut().after(req, resp)
+    }
+}
+
+/// 요청마다 SIGHUP · 파일 변경을 확인해 재적재 — 그 요청부터 새 설정
+pub struct ConfigReload(pub Rc<RefCell<ConfigManager>>);
+
+impl Middleware for ConfigReload {
+    fn before(&mut self, _req: &HttpRequest, car: &mut CrownyRuntime) -> Option<HttpResponse> {
+        let mut mgr = self.0.borrow_mut();
+        if let Some(Ok(changes)) = mgr.check_reload() {
+            if !changes.is_empty() {
+                car.set_history_capacity(mgr.current().history_capacity as usize);
+            }
+        }
+        None
+    }
+}
+
+/// 응답을 보낸 뒤 기한이 된 웹훅 전송 — 재시도 · 데드레터는 디스패처가 판단
+pub struct WebhookPump(pub SharedWebhooks);
+
+impl Middleware for WebhookPump {
+    fn after(&mut self, _req: &HttpRequest, _resp: &mut HttpResponse) {
+        let now = crate::cron::now_ms();
+        if let Ok(mut hooks) = self.0.try_borrow_mut() {
+            if hooks.next_due().is_some_and(|at| at <= now) {
+                hooks.deliver_due(now);
+            }
+        }
+    }
+}
+
+/// 로그 구독 펌프 — 응답 뒤에 eth_subscribe 알림을 허브(/ws)로 민다
+pub struct RpcPump {
+    pub rpc: Rc<RefCell<LogRpc>>,
+    pub hub: EventHub,
+}
+
+impl Middleware for RpcPump {
+    fn after(&mut self, _req: &HttpRequest, _resp: &mut HttpResponse) {
+        let Ok(mut rpc) = self.rpc.try_borrow_mut() else { return };
+        for note in rpc.notifications() {
+            self.hub.publish(JsonObject::new().str("type", "rpc").raw("message", note));
+        }
+    }
+}
+
+/// 요청마다 커널 감시 주기를 돌리는 미들웨어 — 멈춘 스케줄러 복구
+pub struct KernelWatch(pub Rc<RefCell<CrownyKernel>>);
+
+impl Middleware for KernelWatch {
+    fn after(&mut self, _req: &HttpRequest, _resp: &mut HttpResponse) {
+        let Ok(mut kernel) = self.0.try_borrow_mut() else { return };
+        for action in kernel.watch() {
+            eprintln!("[감시] {:?}", action);
+        }
+    }
+}
+
+/// 리스너 중지 핸들 — 다른 스레드에서 stop() 호출
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle(Arc<AtomicBool>);
+
+impl ShutdownHandle {
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// SIGINT · SIGTERM을 받으면 stop() — listen()은 진행 중인 응답을 마치고 통계와 함께 돌아온다
+    pub fn stop_on_signal(&self) {
+        #[cfg(unix)]
+        {
+            extern "C" {
+                fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+            }
+            const SIGINT_NUM: i32 = 2;
+            const SIGTERM_NUM: i32 = 15;
+            unsafe {
+                signal(SIGINT_NUM, on_interrupt);
+                signal(SIGTERM_NUM, on_interrupt);
+            }
+            // 핸들러는 원자 변수만 세우고, 중지는 감시 스레드가 전달
+            let handle = self.clone();
+            thread::spawn(move || {
+                while !INTERRUPTED.load(Ordering::SeqCst) {
+                    thread::sleep(ACCEPT_POLL);
+                }
+                handle.stop();
+            });
+        }
+    }
+}
+
+#[cfg(unix)]
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signum: i32) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// listen() 종료 시 통계
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListenStats {
+    pub connections: usize,
+    pub requests: usize,
+    pub rejected: usize,   // 본문 한도 초과로 읽기 전에 거부
+}
+
+/// 워커 → 디스패처 메시지
+enum Inbound {
+    Request(Box<HttpRequest>, mpsc::Sender<HttpResponse>),
+    Rejected,
+}
+
+/// accept 폴링 간격 (중지 플래그 확인 주기)
+const ACCEPT_POLL: Duration = Duration::from_millis(10);
+
+/// 수락 스레드 하트비트 주기 (ms) — 워치독 누락 판정 기준
+pub const LISTENER_BEAT_MS: u64 = 1000;
+
+/// 수락 스레드 — 중지될 때까지 연결을 워커 큐로 넘기며 하트비트를 보낸다
+fn spawn_acceptor(
+    listener: TcpListener,
+    job_tx: mpsc::Sender<TcpStream>,
+    stop: ShutdownHandle,
+    heartbeat: Option<Heartbeat>,
+) -> thread::JoinHandle<usize> {
+    thread::spawn(move || {
+        let mut accepted = 0;
+        let mut last_beat: Option<Instant> = None;
+        while !stop.is_stopped() {
+            if let Some(hb) = &heartbeat {
+                if last_beat.is_none_or(|t| t.elapsed() >= ACCEPT_POLL * 10) {
+                    hb.beat(Some(&format!("accepted={}", accepted)));
+                    last_beat = Some(Instant::now());
+                }
+            }
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if stream.set_nonblocking(false).is_ok() && job_tx.send(stream).is_ok() {
+                        accepted += 1;
+                    }
+                }
+                Err(_) => thread::sleep(ACCEPT_POLL),
+            }
+        }
+        // job_tx가 닫히면 워커는 남은 연결을 마저 처리하고 끝난다
+        accepted
+    })
+}
+
+/// WebSocket 엔드포인트 — 이 경로로 온 업그레이드 요청은 허브 구독으로 넘어간다
+/// 업그레이드된 연결은 전용 스레드에서 돌고, 워커는 곧바로 다음 연결을 받는다
+#[derive(Debug, Clone)]
+struct WsEndpoint {
+    path: String,
+    hub: EventHub,
+    sessions: Arc<Mutex<Vec<thread::JoinHandle<()>>>>,
+}
+
+impl WsEndpoint {
+    /// 구독 스레드 시작 — 끝난 세션은 이때 정리
+    fn spawn(&self, reader: BufReader<TcpStream>, writer: TcpStream, stop: Option<ShutdownHandle>) {
+        let events = self.hub.subscribe();
+        let handle = thread::spawn(move || {
+            let _ = websocket::serve_events(reader, writer, events, stop.as_ref());
+        });
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|h| !h.is_finished());
+            sessions.push(handle);
+        }
+    }
+
+    /// 모든 구독 스레드가 끝날 때까지 대기
+    fn join(&self) {
+        let sessions: Vec<_> = self.sessions.lock().map(|mut s| s.drain(..).collect()).unwrap_or_default();
+        for h in sessions {
+            let _ = h.join();
+        }
+    }
+}
+
+/// 연결 하나의 keep-alive 루프 — 읽기/쓰기만 하고 응답은 respond가 만든다
+/// respond가 None이면 연결 종료, WebSocket 경로면 업그레이드 후 구독 스레드로 넘기고 돌아온다
+/// 반환: (처리한 요청 수, 413 거부 수)
+fn connection_loop(
+    stream: TcpStream,
+    config: &ServerConfig,
+    stop: Option<&ShutdownHandle>,
+    ws: Option<&WsEndpoint>,
+    mut respond: impl FnMut(HttpRequest) -> Option<HttpResponse>,
+) -> std::io::Result<(usize, usize)> {
+    let idle = config.idle_timeout_ms;
+    stream.set_read_timeout(Some(Duration::from_millis(idle.max(1))))?;
+    let peer = stream.peer_addr().ok().map(|a| a.ip().to_string());
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let mut served = 0;
+    let mut rejected = 0;
+
+    loop {
+        match read_request(&mut reader, config.max_body_bytes) {
+            Ok(mut req) => {
+                served += 1;
+                req.peer = peer.clone();
+                if let Some(ws) = ws.filter(|w| w.path == req.route_path()) {
+                    match websocket::accept_upgrade(&req) {
+                        Ok(accept) => {
+                            websocket::write_handshake(&mut writer, &accept)?;
+                            ws.spawn(reader, writer, stop.cloned());
+                        }
+                        Err((status, msg)) => {
+                            let mut resp = error_response(status, &msg);
+                            if status == 426 {
+                                resp.headers.insert("Upgrade".into(), "websocket".into());
+                                resp.headers.insert("Sec-WebSocket-Version".into(), "13".into());
+                            }
+                            write_response(&mut writer, &resp, false, idle)?;
+                        }
+                    }
+                    return Ok((served, rejected));
+                }
+                let keep = config.keep_alive
+                    && req.wants_keep_alive()
+                    && served < config.max_requests_per_conn
+                    && !stop.is_some_and(|s| s.is_stopped());
+                let resp = match respond(req) {
+                    Some(r) => r,
+                    None => break,
+                };
+                write_response(&mut writer, &resp, keep, idle)?;
+                if !keep { break; }
+            }
+            Err(WireError::BodyTooLarge(n)) => {
+                // 본문을 읽지 않고 바로 거부 후 종료
+                rejected += 1;
+                let resp = error_response(413, &tr!("web.body_too_large_detail", n, config.max_body_bytes));
+                write_response(&mut writer, &resp, false, idle)?;
+                break;
+            }
+            Err(WireError::Malformed(m)) => {
+                let resp = error_response(400, &m);
+                write_response(&mut writer, &resp, false, idle)?;
+                break;
+            }
+            Err(WireError::Closed) | Err(WireError::Timeout) => break,
+            Err(WireError::Io(e)) => return Err(e),
+        }
+    }
+    Ok((served, rejected))
+}
+
+/// Crowny 웹서버 (경량)
+pub struct CrownyServer {
+    routes: Vec<Route>,
+    port: u16,
+    request_count: u64,
+    middleware: Vec<Box<dyn Middleware>>,
+    websocket: Option<WsEndpoint>,
+    shutdown: ShutdownHandle,
+    watchdog: Option<SharedWatchdog>,
+    /// 있으면 능력 토큰 주체를 계정 주소로 바꿔 핸들러에 넘긴다
+    accounts: Option<SharedAccounts>,
+    pub config: ServerConfig,
+}
+
+impl CrownyServer {
+    pub fn new(port: u16) -> Self {
+        println!("[서버] Crowny Web Server 초기화 — 포트 {}", port);
+        Self {
+            routes: Vec::new(),
+            port,
+            request_count: 0,
+            middleware: Vec::new(),
+            websocket: None,
+            shutdown: ShutdownHandle::default(),
+            watchdog: None,
+            accounts: None,
+            config: ServerConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: ServerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 라우트 등록 — "/task/:id" 꼴은 경로 파라미터, 끝의 '*'는 나머지 경로
+    pub fn route(
         &mut self,
-        prompt: &str,
-        models: &[LlmModel],
-        car: &mut CrownyRuntime,
-    ) -> TritResult {
-        let mut results: Vec<TritState> = Vec::new();
-        let mut texts: Vec<String> = Vec::new();
+        method: HttpMethod,
+        path: &str,
+        handler: impl Fn(&HttpRequest, &mut CrownyRuntime) -> HttpResponse + 'static,
+    ) {
+        self.routes.push(Route {
+            method,
+            path: path.to_string(),
+            handler: Box::new(handler),
+        });
+    }
 
-        for model in models {
-            let req = LlmRequest::new(model.clone(), prompt);
-            let result = self.call(req, car);
-            results.push(result.state);
-            if let ResultData::Text(t) = &result.data {
-                texts.push(format!("[{}] {}", model, t));
+    /// 미들웨어 등록 — 등록 순서대로 실행
+    pub fn add_middleware(&mut self, middleware: impl Middleware + 'static) {
+        self.middleware.push(Box::new(middleware));
+    }
+
+    /// WebSocket 이벤트 스트림 경로 등록 (TCP 연결에서만 — 미들웨어를 거치지 않는다)
+    pub fn websocket(&mut self, path: &str, hub: EventHub) {
+        self.websocket = Some(WsEndpoint { path: path.to_string(), hub, sessions: Arc::default() });
+    }
+
+    /// 요청 처리 (시뮬레이션)
+    /// 본문 한도 → 프리플라이트 → 라우팅 → CORS 헤더 부착
+    pub fn handle(&mut self, req: &HttpRequest, car: &mut CrownyRuntime) -> HttpResponse {
+        self.request_count += 1;
+
+        // 27-trit 헤더가 있으면 버전 뱅크 범위로, 없으면 9-trit 단일 버전으로 협상
+        let v2 = req.header(CTP_V2_HEADER).and_then(CtpHeaderV2::from_header_str);
+        let negotiated = match &v2 {
+            Some(h) => Some(h.negotiate(self.config.protocol_versions)),
+            None => req.ctp.negotiate(self.config.protocol_versions),
+        };
+        let mut resp = if req.body.len() > self.config.max_body_bytes {
+            error_response(413, i18n::t("web.body_too_large"))
+        } else if req.method == HttpMethod::Options {
+            self.preflight(req)
+        } else if let Some(Err(e)) = &negotiated {
+            version_mismatch_response(e, self.config.protocol_versions)
+        } else {
+            self.run_middleware(req, car)
+        };
+        if let Some(Ok(v)) = negotiated {
+            resp.ctp = resp.ctp.with_version(v);
+        }
+        if let Some(h) = v2 {
+            let reply = h.reply(&resp.ctp, self.config.protocol_versions);
+            resp.headers.insert(CTP_V2_HEADER.into(), reply.to_header_str());
+        }
+
+        if let (Some(cors), Some(origin)) = (&self.config.cors, req.header("Origin")) {
+            if cors.allows_origin(origin) {
+                cors.apply(origin, &mut resp.headers);
+            }
+        }
+        resp
+    }
+
+    /// OPTIONS 처리 — CORS 프리플라이트 또는 허용 메서드 안내
+    fn preflight(&self, req: &HttpRequest) -> HttpResponse {
+        let mut resp = HttpResponse {
+            status: 204,
+            headers: HashMap::new(),
+            body: String::new(),
+            bytes: None,
+            ctp: CtpHeader::success(),
+            trit_result: TritResult {
+                state: TritState::Success,
+                data: ResultData::None,
+                elapsed_ms: 0,
+                task_id: 0,
+            },
+        };
+
+        let origin = match req.header("Origin") {
+            Some(o) => o,
+            None => {
+                let mut allow: Vec<String> = self.routes.iter()
+                    .filter(|r| r.match_path(req.route_path()).is_some())
+                    .map(|r| r.method.to_string())
+                    .collect();
+                allow.push("OPTIONS".into());
+                resp.headers.insert("Allow".into(), allow.join(", "));
+                return resp;
+            }
+        };
+
+        let cors = match &self.config.cors {
+            Some(c) if c.allows_origin(origin) => c,
+            _ => return error_response(403, i18n::t("web.cors_origin")),
+        };
+        if let Some(m) = req.header("Access-Control-Request-Method") {
+            if !cors.allows_method(m) {
+                return error_response(403, i18n::t("web.cors_method"));
+            }
+        }
+        if let Some(hs) = req.header("Access-Control-Request-Headers") {
+            let denied = hs.split(',')
+                .map(|h| h.trim())
+                .filter(|h| !h.is_empty())
+                .any(|h| !cors.allowed_headers.iter().any(|a| a.eq_ignore_ascii_case(h)));
+            if denied {
+                return error_response(403, i18n::t("web.cors_header"));
+            }
+        }
+
+        resp.headers.insert("Access-Control-Allow-Methods".into(), cors.methods_str());
+        resp.headers.insert("Access-Control-Allow-Headers".into(), cors.allowed_headers.join(", "));
+        resp.headers.insert("Access-Control-Max-Age".into(), cors.max_age_secs.to_string());
+        resp
+    }
+
+    /// 미들웨어 before → 라우팅 → 실행된 미들웨어의 after (역순)
+    fn run_middleware(&mut self, req: &HttpRequest, car: &mut CrownyRuntime) -> HttpResponse {
+        let mut ran = 0;
+        let mut early = None;
+        for m in self.middleware.iter_mut() {
+            ran += 1;
+            if let Some(resp) = m.before(req, car) {
+                early = Some(resp);
+                break;
+            }
+        }
+        let mut resp = match early {
+            Some(resp) => resp,
+            None => self.dispatch(req, car),
+        };
+        for m in self.middleware[..ran].iter_mut().rev() {
+            m.after(req, &mut resp);
+        }
+        resp
+    }
+
+    /// CTP 검증 + 라우트 매칭
+    fn dispatch(&self, req: &HttpRequest, car: &mut CrownyRuntime) -> HttpResponse {
+        // CTP 헤더 검증
+        let ctp_state = req.ctp.overall_state();
+        if ctp_state == TritState::Failed {
+            return error_response(403, i18n::t("web.ctp_denied"));
+        }
+
+        // 라우트 매칭
+        for route in self.routes.iter().filter(|r| r.method == req.method) {
+            if let Some(params) = route.match_path(req.route_path()) {
+                if params.is_empty() {
+                    return (route.handler)(req, car);
+                }
+                let mut req = req.clone();
+                req.params = params;
+                return (route.handler)(&req, car);
             }
         }
 
-        // 3진 다수결
-        let pos = results.iter().filter(|s| **s == TritState::Success).count();
-        let neg = results.iter().filter(|s| **s == TritState::Failed).count();
-        let final_state = if pos > neg { TritState::Success }
-            else if neg > pos { TritState::Failed }
-            else { TritState::Pending };
+        // 404
+        let mut resp = error_response(404, i18n::t("web.not_found"));
+        resp.trit_result.data = ResultData::Text("404".into());
+        resp
+    }
+
+    /// TCP 연결 하나 처리 (HTTP/1.1 keep-alive)
+    /// 유휴 시간 초과, Connection: close, 연결당 요청 한도 도달 시 종료
+    /// 반환: 처리한 요청 수
+    pub fn serve_connection(&mut self, stream: TcpStream, car: &mut CrownyRuntime) -> std::io::Result<usize> {
+        let config = self.config.clone();
+        let ws = self.websocket.clone();
+        let (served, rejected) = connection_loop(stream, &config, None, ws.as_ref(), |req| Some(self.handle(&req, car)))?;
+        if let Some(ws) = &ws {
+            ws.join();
+        }
+        self.request_count += rejected as u64;
+        Ok(served)
+    }
+
+    /// 계정 저장소 연결 — 이후 등록하는 능력 토큰 라우트는 주체 대신 계정 주소로
+    /// 잔액 · 소유권 · 작업을 조회한다 (처음 보는 주체는 legacy 계정 생성)
+    pub fn accounts(&mut self, accounts: SharedAccounts) {
+        self.accounts = Some(accounts);
+    }
+
+    /// 워치독 연결 — listen 동안 수락 스레드를 감시하고, 하트비트가 끊기면
+    /// 같은 소켓으로 새 수락 스레드를 띄운다 (호출 스레드가 대기 틈틈이 tick)
+    pub fn watch(&mut self, watchdog: SharedWatchdog) {
+        self.watchdog = Some(watchdog);
+    }
+
+    /// listen() 중지 핸들 — stop() 후 진행 중인 연결은 다음 응답에서 닫힌다
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// 주소에 바인딩하고 중지될 때까지 요청 처리
+    pub fn listen(&mut self, addr: &str, car: &mut CrownyRuntime) -> std::io::Result<ListenStats> {
+        let listener = TcpListener::bind(addr)?;
+        println!("[서버] {} 대기 중 — 워커 {}개", listener.local_addr()?, self.config.workers.max(1));
+        self.listen_on(listener, car)
+    }
+
+    /// 바인딩된 리스너로 요청 처리
+    /// 수락 스레드 → 워커 풀(파싱·응답 쓰기) → 호출 스레드(라우트 핸들러 실행)
+    /// 핸들러는 Send가 아니므로 CAR와 함께 호출 스레드에만 머문다
+    pub fn listen_on(&mut self, listener: TcpListener, car: &mut CrownyRuntime) -> std::io::Result<ListenStats> {
+        listener.set_nonblocking(true)?;
+        let config = Arc::new(self.config.clone());
+        let (job_tx, job_rx) = mpsc::channel::<TcpStream>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let (inbox, requests) = mpsc::channel::<Inbound>();
+
+        let acceptors = Rc::new(RefCell::new(Vec::new()));
+        let heartbeat = self.watchdog.as_ref()
+            .map(|wd| wd.borrow().heartbeat_handle(ComponentKind::WebListener));
+        if let (Some(wd), Some(hb)) = (&self.watchdog, &heartbeat) {
+            // 재시작 훅이 소켓 복제본과 job_tx를 쥔다 — 중지 후 등록 해제로 놓아준다
+            let spare = listener.try_clone()?;
+            let (job_tx, stop, hb) = (job_tx.clone(), self.shutdown.clone(), hb.clone());
+            let acceptors = Rc::clone(&acceptors);
+            wd.borrow_mut().register(ComponentKind::WebListener, LISTENER_BEAT_MS, Box::new(move |_| {
+                let listener = spare.try_clone().map_err(|e| format!("리스너 복제 실패: {}", e))?;
+                let handle = spawn_acceptor(listener, job_tx.clone(), stop.clone(), Some(hb.clone()));
+                acceptors.borrow_mut().push(handle);
+                Ok(())
+            }));
+        }
+        acceptors.borrow_mut().push(spawn_acceptor(listener, job_tx, self.shutdown.clone(), heartbeat));
+
+        let workers: Vec<_> = (0..config.workers.max(1)).map(|_| {
+            let job_rx = Arc::clone(&job_rx);
+            let config = Arc::clone(&config);
+            let inbox = inbox.clone();
+            let stop = self.shutdown.clone();
+            let ws = self.websocket.clone();
+            thread::spawn(move || {
+                let (reply_tx, reply_rx) = mpsc::channel();
+                loop {
+                    let next = job_rx.lock().ok().and_then(|rx| rx.recv().ok());
+                    let stream = match next {
+                        Some(s) => s,
+                        None => break,
+                    };
+                    let result = connection_loop(stream, &config, Some(&stop), ws.as_ref(), |req| {
+                        inbox.send(Inbound::Request(Box::new(req), reply_tx.clone())).ok()?;
+                        reply_rx.recv().ok()
+                    });
+                    if let Ok((_, rejected)) = result {
+                        for _ in 0..rejected {
+                            let _ = inbox.send(Inbound::Rejected);
+                        }
+                    }
+                }
+            })
+        }).collect();
+        drop(inbox);
+
+        // 모든 워커가 끝나면 채널이 닫힌다 — 기다리는 틈틈이 워치독 tick
+        let mut stats = ListenStats::default();
+        let mut watching = self.watchdog.is_some();
+        loop {
+            match requests.recv_timeout(ACCEPT_POLL * 10) {
+                Ok(Inbound::Request(req, reply)) => {
+                    stats.requests += 1;
+                    let _ = reply.send(self.handle(&req, car));
+                }
+                Ok(Inbound::Rejected) => {
+                    stats.rejected += 1;
+                    self.request_count += 1;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+            if let (true, Some(wd)) = (watching, &self.watchdog) {
+                if self.shutdown.is_stopped() {
+                    wd.borrow_mut().unregister(&ComponentKind::WebListener);
+                    watching = false;
+                } else {
+                    wd.borrow_mut().tick();
+                }
+            }
+        }
+        for w in workers {
+            let _ = w.join();
+        }
+        // 구독 스레드는 중지 플래그를 보고 닫는다
+        if let Some(ws) = &self.websocket {
+            ws.join();
+        }
+        stats.connections = acceptors.borrow_mut().drain(..).map(|a| a.join().unwrap_or(0)).sum();
+        Ok(stats)
+    }
+
+    pub fn stats(&self) -> String {
+        format!("[서버] 포트:{} 라우트:{} 요청:{}", self.port, self.routes.len(), self.request_count)
+    }
+}
+
+// ═══════════════════════════════════════════════
+// LLM 호출기
+// ═══════════════════════════════════════════════
+
+/// LLM 모델 종류
+#[derive(Debug, Clone, PartialEq)]
+pub enum LlmModel {
+    Claude,
+    Gpt4,
+    Gemini,
+    Local,    // 로컬 모델
+    Custom(String),
+}
+
+impl std::fmt::Display for LlmModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LlmModel::Claude => write!(f, "Claude"),
+            LlmModel::Gpt4 => write!(f, "GPT-4"),
+            LlmModel::Gemini => write!(f, "Gemini"),
+            LlmModel::Local => write!(f, "Local"),
+            LlmModel::Custom(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl LlmModel {
+    /// 요청 매개변수 → 모델 (알 수 없는 이름은 Custom)
+    pub fn parse(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "claude" => LlmModel::Claude,
+            "gpt-4" | "gpt4" => LlmModel::Gpt4,
+            "gemini" => LlmModel::Gemini,
+            "local" => LlmModel::Local,
+            _ => LlmModel::Custom(name.to_string()),
+        }
+    }
+}
+
+/// LLM 요청
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    pub model: LlmModel,
+    pub prompt: String,
+    pub system: Option<String>,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub params: HashMap<String, String>,
+    /// 요청 주체 — 토큰 사용량을 이 주체의 테넌트에 계량 (없으면 모델명)
+    pub subject: Option<String>,
+    /// 캐시를 건너뛰고 백엔드에 새로 묻는다 (결과는 캐시에 갱신)
+    pub fresh: bool,
+}
+
+impl LlmRequest {
+    pub fn new(model: LlmModel, prompt: &str) -> Self {
+        Self {
+            model,
+            prompt: prompt.to_string(),
+            system: None,
+            temperature: 0.7,
+            max_tokens: 1024,
+            params: HashMap::new(),
+            subject: None,
+            fresh: false,
+        }
+    }
+
+    pub fn fresh(mut self) -> Self {
+        self.fresh = true;
+        self
+    }
+
+    /// 캐시 키 — 답을 바꿀 수 있는 입력 전부의 해시 (주체는 제외: 같은 질문은 테넌트 간 공유)
+    pub fn cache_key(&self) -> String {
+        let material = format!("{}\u{1f}{}\u{1f}{}\u{1f}{}\u{1f}{}",
+            self.model, self.system.as_deref().unwrap_or(""), self.temperature, self.max_tokens, self.prompt);
+        format!("{}{}", LLM_CACHE_PREFIX, to_hex(&sha256(material.as_bytes())))
+    }
+
+    pub fn for_subject(mut self, subject: &str) -> Self {
+        self.subject = Some(subject.to_string());
+        self
+    }
+
+    pub fn with_system(mut self, sys: &str) -> Self {
+        self.system = Some(sys.to_string());
+        self
+    }
+
+    pub fn with_temp(mut self, t: f32) -> Self {
+        self.temperature = t;
+        self
+    }
+}
+
+/// LLM 응답
+#[derive(Debug, Clone)]
+pub struct LlmResponse {
+    pub text: String,
+    pub model: LlmModel,
+    pub tokens_used: u32,
+    pub trit_state: TritState,
+}
+
+pub const LLM_CACHE_PREFIX: &str = "llm.cache.";
+pub const DEFAULT_LLM_CACHE_TTL_MS: u64 = 60 * 60 * 1000;
+pub const DEFAULT_LLM_CACHE_CAPACITY: usize = 10_000;
+
+/// 캐시 적중 통계
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LlmCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// fresh 요청으로 건너뛴 횟수
+    pub bypassed: u64,
+    pub expired: u64,
+    /// 용량 초과로 밀려난 항목
+    pub evicted: u64,
+    /// 적중으로 아낀 토큰
+    pub tokens_saved: u64,
+}
+
+impl LlmCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 { 0.0 } else { self.hits as f64 / total as f64 }
+    }
+}
+
+/// 캐시 항목 — P(정상 종료) 응답만 저장한다
+fn cache_entry(resp: &LlmResponse, expires_at: u64) -> StoreValue {
+    StoreValue::Map(HashMap::from([
+        ("text".to_string(), StoreValue::Text(resp.text.clone())),
+        ("model".to_string(), StoreValue::Text(resp.model.to_string())),
+        ("tokens".to_string(), StoreValue::Int(resp.tokens_used as i64)),
+        ("trit".to_string(), StoreValue::Trit(resp.trit_state as i8)),
+        ("expires_at".to_string(), StoreValue::Int(expires_at as i64)),
+    ]))
+}
+
+/// (응답, 만료 시각)
+fn cached_response(value: &StoreValue) -> Option<(LlmResponse, u64)> {
+    let StoreValue::Map(m) = value else { return None };
+    let text = |k: &str| match m.get(k) { Some(StoreValue::Text(s)) => Some(s.clone()), _ => None };
+    let int = |k: &str| match m.get(k) { Some(StoreValue::Int(v)) => Some(*v as u64), _ => None };
+    let trit = match m.get("trit") { Some(StoreValue::Trit(t)) => *t, _ => return None };
+    Some((LlmResponse {
+        text: text("text")?,
+        model: LlmModel::Custom(text("model")?),
+        tokens_used: int("tokens")? as u32,
+        trit_state: TritState::from_i8(trit),
+    }, int("expires_at")?))
+}
+
+pub type SharedLlm = Rc<RefCell<CrownyLlm>>;
+
+/// Crowny LLM 호출기 — 다중 모델 라우터
+pub struct CrownyLlm {
+    default_model: LlmModel,
+    call_count: u64,
+    total_tokens: u64,
+    // 모델별 API 키 — 백엔드를 나중에 붙여도 전달된다
+    api_keys: HashMap<String, String>,
+    // 모델별 백엔드 (없으면 에코 시뮬레이션)
+    backends: HashMap<String, Box<dyn LlmBackend>>,
+    // 프롬프트 해시 → 응답 (없으면 캐시 끔)
+    cache: Option<TritStore>,
+    cache_ttl_ms: u64,
+    cache_capacity: usize,
+    cache_stats: LlmCacheStats,
+}
+
+impl CrownyLlm {
+    pub fn new() -> Self {
+        println!("[LLM] Crowny LLM 호출기 초기화");
+        Self {
+            default_model: LlmModel::Claude,
+            call_count: 0,
+            total_tokens: 0,
+            api_keys: HashMap::new(),
+            backends: HashMap::new(),
+            cache: None,
+            cache_ttl_ms: DEFAULT_LLM_CACHE_TTL_MS,
+            cache_capacity: DEFAULT_LLM_CACHE_CAPACITY,
+            cache_stats: LlmCacheStats::default(),
+        }
+    }
+
+    /// 응답 캐시 연결 — 영속 TritStore면 재시작 후에도 적중한다
+    pub fn with_cache(mut self, store: TritStore) -> Self {
+        self.cache = Some(store);
+        self
+    }
+
+    pub fn with_cache_ttl(mut self, ttl_ms: u64) -> Self {
+        self.cache_ttl_ms = ttl_ms;
+        self
+    }
+
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity.max(1);
+        self
+    }
+
+    pub fn shared(self) -> SharedLlm {
+        Rc::new(RefCell::new(self))
+    }
+
+    pub fn cache_len(&self) -> usize {
+        self.cache.as_ref().map_or(0, |c| c.keys().iter().filter(|k| k.starts_with(LLM_CACHE_PREFIX)).count())
+    }
+
+    /// 만료 항목 삭제 → 지운 수
+    pub fn purge_cache(&mut self, now: u64) -> usize {
+        let Some(store) = &mut self.cache else { return 0 };
+        let stale: Vec<String> = store.keys().into_iter()
+            .filter(|k| k.starts_with(LLM_CACHE_PREFIX))
+            .filter(|k| store.peek(k).and_then(cached_response).is_none_or(|(_, exp)| now >= exp))
+            .cloned()
+            .collect();
+        for k in &stale {
+            store.delete(k);
+        }
+        self.cache_stats.expired += stale.len() as u64;
+        stale.len()
+    }
+
+    /// 새 항목 자리 확보 — 만료 항목부터, 그래도 차 있으면 먼저 만료될 항목 순으로 밀어낸다
+    fn make_room(&mut self, now: u64) {
+        if self.cache_len() < self.cache_capacity {
+            return;
+        }
+        self.purge_cache(now);
+        let Some(store) = &mut self.cache else { return };
+        let mut live: Vec<(u64, String)> = store.keys().into_iter()
+            .filter(|k| k.starts_with(LLM_CACHE_PREFIX))
+            .filter_map(|k| store.peek(k).and_then(cached_response).map(|(_, exp)| (exp, k.clone())))
+            .collect();
+        if live.len() < self.cache_capacity {
+            return;
+        }
+        live.sort();
+        let excess = live.len() + 1 - self.cache_capacity;
+        for (_, k) in live.into_iter().take(excess) {
+            store.delete(&k);
+        }
+        self.cache_stats.evicted += excess as u64;
+    }
+
+    /// 유효한 캐시 응답 — 만료된 것은 지우고 None
+    fn cache_lookup(&mut self, key: &str, now: u64) -> Option<LlmResponse> {
+        let store = self.cache.as_mut()?;
+        match store.get(key).and_then(cached_response) {
+            Some((resp, exp)) if now < exp => Some(resp),
+            Some(_) => {
+                store.delete(key);
+                self.cache_stats.expired += 1;
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn set_api_key(&mut self, model: &str, key: &str) {
+        if let Some(b) = self.backends.get_mut(model) {
+            b.set_api_key(key);
+        }
+        self.api_keys.insert(model.to_string(), key.to_string());
+    }
+
+    /// 모델에 실제 백엔드 연결 (OpenAI 호환 · Anthropic · 에코)
+    pub fn set_backend(&mut self, model: LlmModel, mut backend: Box<dyn LlmBackend>) {
+        let name = model.to_string();
+        if let Some(key) = self.api_keys.get(&name) {
+            backend.set_api_key(key);
+        }
+        self.backends.insert(name, backend);
+    }
+
+    pub fn backend_name(&self, model: &LlmModel) -> &str {
+        self.backends.get(&model.to_string()).map(|b| b.name()).unwrap_or("echo")
+    }
+
+    pub fn set_default_model(&mut self, model: LlmModel) {
+        self.default_model = model;
+    }
+
+    /// LLM 호출 (CAR 경유)
+    pub fn call(&mut self, req: LlmRequest, car: &mut CrownyRuntime) -> TritResult {
+        let model_name = req.model.to_string();
+        let prompt = req.prompt.clone();
+
+        let subject = req.subject.clone().unwrap_or_else(|| model_name.clone());
+        let task = AppTask::new(TaskType::LlmCall, &subject, &prompt)
+            .with_param("temperature", &req.temperature.to_string())
+            .with_param("max_tokens", &req.max_tokens.to_string());
+
+        // 캐시 적중 — 백엔드를 부르지 않고 토큰도 계량하지 않는다
+        let key = req.cache_key();
+        let now = billing::now_ms();
+        if self.cache.is_some() {
+            if req.fresh {
+                self.cache_stats.bypassed += 1;
+            } else if let Some(hit) = self.cache_lookup(&key, now) {
+                self.cache_stats.hits += 1;
+                self.cache_stats.tokens_saved += hit.tokens_used as u64;
+                return car.submit_metered(task, |_| {
+                    (hit.trit_state, ResultData::Text(hit.text), Usage::of(Resource::LlmTokens, 0))
+                });
+            } else {
+                self.cache_stats.misses += 1;
+            }
+        }
+
+        if self.cache.is_some() {
+            self.make_room(now);
+        }
+        let call_count = &mut self.call_count;
+        let total_tokens = &mut self.total_tokens;
+        let cache = &mut self.cache;
+        let expires_at = now.saturating_add(self.cache_ttl_ms);
+        let mut echo = EchoBackend::new(&model_name);
+        let backend: &mut dyn LlmBackend = match self.backends.get_mut(&model_name) {
+            Some(b) => b.as_mut(),
+            None => &mut echo,
+        };
+
+        car.submit_metered(task, |_| {
+            *call_count += 1;
+            match backend.complete(&req) {
+                Ok(response) => {
+                    *total_tokens += response.tokens_used as u64;
+                    // 잘리거나 거부된 응답은 다시 물어볼 가치가 있으니 저장하지 않는다
+                    if let (Some(store), TritState::Success) = (cache.as_mut(), response.trit_state) {
+                        store.set(&key, cache_entry(&response, expires_at));
+                    }
+                    let usage = Usage::of(Resource::LlmTokens, response.tokens_used as u64);
+                    (response.trit_state, ResultData::Text(response.text), usage)
+                }
+                // 실패는 토큰 0 — 과금하지 않는다
+                Err(e) => (e.trit(), ResultData::Text(e.to_string()), Usage::of(Resource::LlmTokens, 0)),
+            }
+        })
+    }
+
+    /// 간편 호출
+    pub fn ask(&mut self, prompt: &str, car: &mut CrownyRuntime) -> TritResult {
+        let req = LlmRequest::new(self.default_model.clone(), prompt);
+        self.call(req, car)
+    }
+
+    /// 다중 모델 동시 호출 (합의)
+    pub fn consensus_call(
+        &mut self,
+        prompt: &str,
+        models: &[LlmModel],
+        car: &mut CrownyRuntime,
+    ) -> TritResult {
+        self.consensus_call_with(prompt, models, false, car)
+    }
+
+    /// 합의 호출 — fresh면 캐시를 건너뛰어 모든 모델이 새로 답한다
+    pub fn consensus_call_with(
+        &mut self,
+        prompt: &str,
+        models: &[LlmModel],
+        fresh: bool,
+        car: &mut CrownyRuntime,
+    ) -> TritResult {
+        let mut results: Vec<TritState> = Vec::new();
+        let mut texts: Vec<String> = Vec::new();
+
+        for model in models {
+            let mut req = LlmRequest::new(model.clone(), prompt);
+            req.fresh = fresh;
+            let result = self.call(req, car);
+            results.push(result.state);
+            if let ResultData::Text(t) = &result.data {
+                texts.push(format!("[{}] {}", model, t));
+            }
+        }
+
+        // 3진 다수결
+        let pos = results.iter().filter(|s| **s == TritState::Success).count();
+        let neg = results.iter().filter(|s| **s == TritState::Failed).count();
+        let final_state = if pos > neg { TritState::Success }
+            else if neg > pos { TritState::Failed }
+            else { TritState::Pending };
+
+        TritResult {
+            state: final_state,
+            data: ResultData::Text(texts.join("\n")),
+            elapsed_ms: 0,
+            task_id: 0,
+        }
+    }
+
+    pub fn to_json(&self) -> JsonObject {
+        let json = JsonObject::new()
+            .str("default_model", &self.default_model.to_string())
+            .str("backend", self.backend_name(&self.default_model))
+            .int("calls", self.call_count as i64)
+            .int("tokens", self.total_tokens as i64);
+        if self.cache.is_none() {
+            return json;
+        }
+        let c = &self.cache_stats;
+        json.object("cache", JsonObject::new()
+            .int("entries", self.cache_len() as i64)
+            .int("capacity", self.cache_capacity as i64)
+            .int("hits", c.hits as i64)
+            .int("misses", c.misses as i64)
+            .int("evicted", c.evicted as i64)
+            .int("tokens_saved", c.tokens_saved as i64))
+    }
+
+    pub fn stats(&self) -> String {
+        let mut line = format!("[LLM] 호출:{} 토큰:{} 기본모델:{}", self.call_count, self.total_tokens, self.default_model);
+        if self.cache.is_some() {
+            let c = &self.cache_stats;
+            line.push_str(&format!(" 캐시:{}건 적중:{}/{} ({:.0}%) 절약토큰:{}",
+                self.cache_len(), c.hits, c.hits + c.misses, c.hit_rate() * 100.0, c.tokens_saved));
+        }
+        line
+    }
+}
+
+// ═══════════════════════════════════════════════
+// 기본 라우트 생성 헬퍼
+// ═══════════════════════════════════════════════
+
+/// 기본 Crowny 서버 생성 (데모용 라우트 포함) — 인증 수단이 없으므로 모두 게스트(O) 이하
+pub fn create_demo_server() -> CrownyServer {
+    create_server_with_sandbox(SandboxPolicy::default(), None)
+}
+
+/// 샌드박스 정책을 지정한 서버 생성 — signer가 있으면 run.trusted 토큰 소지자에게 P 단계
+pub fn create_server_with_sandbox(sandbox: SandboxPolicy, signer: Option<TokenSigner>) -> CrownyServer {
+    let mut server = CrownyServer::new(7293);
+    let signer = Rc::new(signer);
+
+    // GET /
+    server.route(HttpMethod::Get, "/", |_req, car| {
+        let result = car.run_source("system", "넣어 1\n종료");
+        HttpResponse {
+            status: 200,
+            headers: HashMap::new(),
+            body: format!("{{\"상태\":\"{}\",\"메시지\":\"Crowny 서버 작동중\"}}", result.state),
+            bytes: None,
+            ctp: CtpHeader::success(),
+            trit_result: result,
+        }
+    });
+
+    // POST /run — 한선어 실행 (인증 단계별 샌드박스)
+    let s = signer.clone();
+    server.route(HttpMethod::Post, "/run", move |req, car| {
+        let tier = sandbox_tier(req, s.as_ref().as_ref());
+        let limits = sandbox.for_permission(tier);
+        let program = sandbox.program_for_permission(tier);
+        let result = car.run_source_checked("web", &req.body, limits, &program);
+
+        if let Some(kind) = program_limit_of(&result.data) {
+            return program_limit_response(kind, result);
+        }
+
+        if let Some(kind) = limit_of(&result.data) {
+            return HttpResponse {
+                status: limit_status(kind),
+                headers: HashMap::new(),
+                body: format!("{{\"상태\":\"{}\",\"오류\":\"{}\",\"한도\":\"{}\"}}",
+                    result.state.symbol(), i18n::t("web.run_limit"), kind.code()),
+                bytes: None,
+                ctp: if result.state == TritState::Pending { CtpHeader::pending() } else { CtpHeader::failed() },
+                trit_result: result,
+            };
+        }
+
+        let status = match result.state {
+            TritState::Success => 200,
+            TritState::Pending => 202,
+            TritState::Failed => 500,
+        };
+        HttpResponse {
+            status,
+            headers: HashMap::new(),
+            body: format!("{{\"상태\":\"{}\",\"결과\":\"{}\",\"trit_result\":{}}}",
+                result.state, result.data, result.to_json().build()),
+            bytes: None,
+            ctp: if result.state == TritState::Success { CtpHeader::success() } else { CtpHeader::failed() },
+            trit_result: result,
+        }
+    });
+
+    // POST /compile — WASM 컴파일
+    server.route(HttpMethod::Post, "/compile", move |req, car| {
+        let program = sandbox.program_for_permission(sandbox_tier(req, signer.as_ref().as_ref()));
+        let result = car.compile_wasm_checked("web", &req.body, &program);
+        if let Some(kind) = program_limit_of(&result.data) {
+            return program_limit_response(kind, result);
+        }
+        let status = if result.state == TritState::Success { 200 } else { 500 };
+        let body_text = match &result.data {
+            ResultData::Bytes(b) => format!("{{\"상태\":\"{}\",\"크기\":{}}}", result.state, b.len()),
+            _ => format!("{{\"상태\":\"{}\"}}", result.state),
+        };
+        HttpResponse {
+            status,
+            headers: HashMap::new(),
+            body: body_text,
+            bytes: None,
+            ctp: if result.state == TritState::Success { CtpHeader::success() } else { CtpHeader::failed() },
+            trit_result: result,
+        }
+    });
+
+    server
+}
+
+/// 관리자 범위 확인 실패 응답 (admin_override::authorize_scope)
+fn admin_denied(signer: &TokenSigner, req: &HttpRequest, scope: &str) -> Option<HttpResponse> {
+    admin_override::authorize_scope(signer, req.header(TOKEN_HEADER), scope, crate::cron::now_ms())
+        .err()
+        .map(|e| error_response(e.status(), &e.to_string()))
+}
+
+/// 설정 관리자 엔드포인트 등록 — admin.config 범위가 명시된 토큰만
+/// GET  /admin/config — 현재 설정 버전
+/// POST /admin/config — 본문(TOML)으로 교체, 본문이 비면 파일 재적재
+pub fn mount_config_admin(server: &mut CrownyServer, config: Rc<RefCell<ConfigManager>>, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+    let (cfg, s) = (config.clone(), signer.clone());
+    server.route(HttpMethod::Get, "/admin/config", move |req, _car| {
+        if let Some(resp) = admin_denied(&s, req, admin_override::CONFIG_SCOPE) { return resp; }
+        let c = cfg.borrow();
+        let fields: Vec<String> = c.current().entries().iter()
+            .map(|(k, v)| format!("\"{}\":\"{}\"", k, v))
+            .collect();
+        ok_response(format!("{{\"상태\":\"P\",\"버전\":{},\"설정\":{{{}}}}}",
+            c.version, fields.join(",")))
+    });
+
+    server.route(HttpMethod::Post, "/admin/config", move |req, car| {
+        if let Some(resp) = admin_denied(&signer, req, admin_override::CONFIG_SCOPE) { return resp; }
+        let mut c = config.borrow_mut();
+        let result = if req.body.trim().is_empty() { c.reload() } else { c.apply_str(&req.body) };
+        match result {
+            Ok(changes) => {
+                car.set_history_capacity(c.current().history_capacity as usize);
+                let list: Vec<String> = changes.iter()
+                    .map(|ch| format!("\"{}\"", ch.to_string().replace('"', "'")))
+                    .collect();
+                ok_response(format!("{{\"상태\":\"P\",\"버전\":{},\"변경\":[{}]}}",
+                    c.version, list.join(",")))
+            }
+            Err(errors) => {
+                let mut resp = error_response(422, &errors.join("; "));
+                resp.trit_result.data = ResultData::List(
+                    errors.into_iter().map(ResultData::Text).collect());
+                resp
+            }
+        }
+    });
+}
+
+/// 웹훅 관리자 엔드포인트 등록 (모두 admin.webhooks 범위가 명시된 토큰 필요)
+/// GET    /admin/webhooks         — 구독 · 대기 · 데드레터 수
+/// POST   /admin/webhooks         — url, events(쉼표 구분), secret → 구독 등록
+/// DELETE /admin/webhooks         — id → 구독 해지
+/// GET    /admin/webhooks/dead    — 데드레터 목록
+/// POST   /admin/webhooks/replay  — id(전송 ID 또는 all) → 재전송 예약
+pub fn mount_webhook_admin(server: &mut CrownyServer, hooks: SharedWebhooks, signer: TokenSigner) {
+    fn json(body: JsonObject) -> HttpResponse {
+        ok_response(body.trit("state", 1).build())
+    }
+    let signer = Rc::new(signer);
+
+    let (h, s) = (hooks.clone(), signer.clone());
+    server.route(HttpMethod::Get, "/admin/webhooks", move |req, _car| {
+        if let Some(resp) = admin_denied(&s, req, admin_override::WEBHOOK_SCOPE) { return resp; }
+        json(h.borrow().to_json())
+    });
+
+    let (h, s) = (hooks.clone(), signer.clone());
+    server.route(HttpMethod::Post, "/admin/webhooks", move |req, _car| {
+        if let Some(resp) = admin_denied(&s, req, admin_override::WEBHOOK_SCOPE) { return resp; }
+        let p = form_params(&req.body);
+        let result = (|| {
+            let events = param(&p, "events")?.split(',')
+                .map(|e| e.trim().parse::<EventKind>())
+                .collect::<Result<Vec<_>, _>>()?;
+            h.borrow_mut().register(param(&p, "url")?, &events, param(&p, "secret")?)
+        })();
+        match result {
+            Ok(id) => json(JsonObject::new().int("id", id as i64)),
+            Err(e) => error_response(422, &e),
+        }
+    });
+
+    let (h, s) = (hooks.clone(), signer.clone());
+    server.route(HttpMethod::Delete, "/admin/webhooks", move |req, _car| {
+        if let Some(resp) = admin_denied(&s, req, admin_override::WEBHOOK_SCOPE) { return resp; }
+        let p = form_params(&req.body);
+        match param_u64(&p, "id") {
+            Ok(id) if h.borrow_mut().unregister(id) => json(JsonObject::new().int("id", id as i64)),
+            Ok(id) => error_response(404, &format!("웹훅 #{} 없음", id)),
+            Err(e) => error_response(422, &e),
+        }
+    });
+
+    let (h, s) = (hooks.clone(), signer.clone());
+    server.route(HttpMethod::Get, "/admin/webhooks/dead", move |req, _car| {
+        if let Some(resp) = admin_denied(&s, req, admin_override::WEBHOOK_SCOPE) { return resp; }
+        let letters = h.borrow().dead_letters();
+        json(JsonObject::new().objects("dead_letters", letters.iter().map(|l| l.to_json()).collect()))
+    });
+
+    let s = signer;
+    server.route(HttpMethod::Post, "/admin/webhooks/replay", move |req, _car| {
+        if let Some(resp) = admin_denied(&s, req, admin_override::WEBHOOK_SCOPE) { return resp; }
+        let p = form_params(&req.body);
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default().as_millis() as u64;
+        let mut hub = hooks.borrow_mut();
+        let result = match p.get("id").map(String::as_str) {
+            Some("all") => Ok(hub.replay_all(now)),
+            _ => param_u64(&p, "id").and_then(|id| hub.replay(id, now).map(|_| 1)),
+        };
+        match result {
+            Ok(n) => json(JsonObject::new().int("replayed", n as i64)),
+            Err(e) => error_response(422, &e),
+        }
+    });
+}
+
+/// 관리자 상태 덮어쓰기 엔드포인트 등록 — admin.override 범위가 명시된 토큰만 (운영자 = subject)
+///   POST /admin/override        kind(task|transfer), id, state(P|T), reason → 보류(O) 대상 강제 정산
+///   GET  /admin/override/audit  → 해시 체인 감사 로그 · 검증 결과
+pub fn mount_override_api(
+    server: &mut CrownyServer,
+    bridge: Rc<RefCell<CrownyBridge>>,
+    audit: SharedAudit,
+    hooks: Option<SharedWebhooks>,
+    signer: TokenSigner,
+) {
+    let signer = Rc::new(signer);
+
+    let (s, log) = (signer.clone(), audit.clone());
+    server.route(HttpMethod::Post, "/admin/override", move |req, car| {
+        let now = crate::cron::now_ms();
+        let token = match admin_override::authorize(&s, req.header(TOKEN_HEADER), now) {
+            Ok(t) => t,
+            Err(e) => return error_response(e.status(), &e.to_string()),
+        };
+        let p = form_params(&req.body);
+        let result = (|| {
+            let request = OverrideRequest {
+                kind: param(&p, "kind")?.parse()?,
+                target: param(&p, "id")?.to_string(),
+                state: admin_override::parse_target_state(param(&p, "state")?)?,
+                justification: param(&p, "reason")?.to_string(),
+            };
+            admin_override::apply(&request, &token.subject, car, &bridge, &mut log.borrow_mut(), hooks.as_ref(), now)
+        })();
+        match result {
+            Ok(entry) => ok_response(entry.to_json().trit("state", entry.after).build()),
+            Err(e) => error_response(422, &e),
+        }
+    });
+
+    server.route(HttpMethod::Get, "/admin/override/audit", move |req, _car| {
+        if let Err(e) = admin_override::authorize(&signer, req.header(TOKEN_HEADER), crate::cron::now_ms()) {
+            return error_response(e.status(), &e.to_string());
+        }
+        ok_response(audit.borrow().to_json().build())
+    });
+}
+
+/// 커널 승인 대기열 엔드포인트 등록 — 판정은 admin.approvals 범위 토큰만 (판정자 = subject)
+/// POST /programs                    — 본문(소스)을 주체 "web"으로 제출 → 실행 · 보류(O) · 거부
+/// GET  /admin/approvals             — 보류 중인 승인 요청
+/// POST /admin/approvals/:id/approve — 능력 재확인 후 승인 → 보류 태스크 재개
+/// POST /admin/approvals/:id/reject  — reason → 거절
+pub fn mount_approval_api(server: &mut CrownyServer, kernel: Rc<RefCell<CrownyKernel>>, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+
+    let k = kernel.clone();
+    server.route(HttpMethod::Post, "/programs", move |req, _car| {
+        match k.borrow_mut().submit_program(WEB_SUBJECT, &req.body) {
+            SubmitOutcome::Executed(Ok(())) => ok_response(JsonObject::new().trit("state", 1).build()),
+            SubmitOutcome::Executed(Err(e)) => error_response(422, &e),
+            SubmitOutcome::Pending(id) => {
+                let mut resp = ok_response(JsonObject::new().trit("state", 0).int("id", id as i64).build());
+                resp.status = 202;
+                resp
+            }
+            SubmitOutcome::Refused(r) => error_response(403, &r),
+        }
+    });
+
+    let (k, s) = (kernel.clone(), signer.clone());
+    server.route(HttpMethod::Get, "/admin/approvals", move |req, _car| {
+        if let Some(resp) = admin_denied(&s, req, admin_override::APPROVAL_SCOPE) { return resp; }
+        let pending: Vec<JsonObject> = k.borrow().approvals.pending().iter()
+            .map(|r| JsonObject::new()
+                .int("id", r.id as i64)
+                .str("subject", &r.subject)
+                .strs("missing", &r.missing.iter().map(|c| c.code().to_string()).collect::<Vec<_>>()))
+            .collect();
+        ok_response(JsonObject::new().trit("state", 1).objects("pending", pending).build())
+    });
+
+    let (k, s) = (kernel.clone(), signer.clone());
+    server.route(HttpMethod::Post, "/admin/approvals/:id/approve", move |req, _car| {
+        let token = match admin_override::authorize_scope(&s, req.header(TOKEN_HEADER),
+            admin_override::APPROVAL_SCOPE, crate::cron::now_ms()) {
+            Ok(t) => t,
+            Err(e) => return error_response(e.status(), &e.to_string()),
+        };
+        let id = match req.param("id").and_then(|v| v.parse::<u64>().ok()) {
+            Some(id) => id,
+            None => return error_response(422, "id 형식 오류"),
+        };
+        let mut kernel = k.borrow_mut();
+        match kernel.approve(&token.subject, id) {
+            Ok(()) => {
+                let outcome = kernel.approvals.get(id).and_then(|r| r.outcome.clone());
+                let body = JsonObject::new().trit("state", 1).int("id", id as i64);
+                ok_response(match outcome {
+                    Some(Err(e)) => body.str("error", &e),
+                    _ => body,
+                }.build())
+            }
+            Err(e) => error_response(409, &e),
+        }
+    });
+
+    server.route(HttpMethod::Post, "/admin/approvals/:id/reject", move |req, _car| {
+        let token = match admin_override::authorize_scope(&signer, req.header(TOKEN_HEADER),
+            admin_override::APPROVAL_SCOPE, crate::cron::now_ms()) {
+            Ok(t) => t,
+            Err(e) => return error_response(e.status(), &e.to_string()),
+        };
+        let p = form_params(&req.body);
+        let result = req.param("id").and_then(|v| v.parse::<u64>().ok())
+            .ok_or_else(|| "id 형식 오류".to_string())
+            .and_then(|id| {
+                let reason = param(&p, "reason")?;
+                kernel.borrow_mut().reject(&token.subject, id, reason).map(|_| id)
+            });
+        match result {
+            Ok(id) => ok_response(JsonObject::new().trit("state", -1).int("id", id as i64).build()),
+            Err(e) => error_response(409, &e),
+        }
+    });
+}
+
+/// HTTP로 제출된 프로그램의 커널 주체
+pub const WEB_SUBJECT: &str = "web";
+
+// ═══════════════════════════════════════════════
+// 마켓 API (DEX + NFT)
+// ═══════════════════════════════════════════════
+
+/// 폼 본문 파싱 — key=value&... (+ 는 공백, %XX 디코딩)
+pub fn form_params(body: &str) -> HashMap<String, String> {
+    fn decode(s: &str) -> String {
+        let bytes = s.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'+' => out.push(b' '),
+                b'%' if i + 2 < bytes.len() => {
+                    let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()
+                        .and_then(|h| u8::from_str_radix(h, 16).ok());
+                    match hex {
+                        Some(b) => { out.push(b); i += 2; }
+                        None => out.push(b'%'),
+                    }
+                }
+                b => out.push(b),
+            }
+            i += 1;
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+    body.trim().split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| match kv.split_once('=') {
+            Some((k, v)) => (decode(k), decode(v)),
+            None => (decode(kv), String::new()),
+        })
+        .collect()
+}
+
+/// 폼 본문 만들기 — form_params의 역 (예약 문자 밖은 %XX)
+pub fn form_body(pairs: &[(&str, &str)]) -> String {
+    let encode = |s: &str| s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect::<String>();
+    pairs.iter().map(|(k, v)| format!("{}={}", encode(k), encode(v))).collect::<Vec<_>>().join("&")
+}
+
+type Params = HashMap<String, String>;
+
+fn param<'a>(p: &'a Params, key: &str) -> Result<&'a str, String> {
+    p.get(key).map(|v| v.as_str()).filter(|v| !v.is_empty())
+        .ok_or_else(|| tr!("web.param_missing", key))
+}
+
+fn param_u64(p: &Params, key: &str) -> Result<u64, String> {
+    let raw = param(p, key)?;
+    raw.parse().map_err(|_| tr!("web.param_invalid", key, raw))
+}
+
+/// 토큰 검증 → 폼 파싱 → CAR 경유 실행 → CTP 헤더가 붙은 응답
+/// 처리기는 (상태 트릿, 결과 JSON)을 돌려준다 — 업무 오류는 422 + T
+fn capability_route(
+    server: &mut CrownyServer,
+    method: HttpMethod,
+    path: &str,
+    scope: &'static str,
+    signer: Rc<TokenSigner>,
+    op: impl Fn(&str, &Params) -> Result<(i8, JsonObject), String> + 'static,
+) {
+    let accounts = server.accounts.clone();
+    server.route(method, path, move |req, car| {
+        let token = match signer.verify(req.header(TOKEN_HEADER), scope) {
+            Ok(t) => t,
+            Err(e) => return error_response(e.status(), &e.to_string()),
+        };
+        let params = form_params(&req.body);
+        let user = match &accounts {
+            Some(accounts) => accounts.borrow_mut().subject(&token.subject).to_string(),
+            None => token.subject.clone(),
+        };
+        let outcome = op(&user, &params).map(|(state, json)| (state, json.build()));
+        let sent = match &outcome { Ok((_, body)) => body.len(), Err(e) => e.len() };
+        let usage = Usage::of(Resource::NetBytes, (req.body.len() + sent) as u64);
+        let result = car.submit_metered(AppTask::new(TaskType::WebRequest, &token.subject, scope), |_t| {
+            match &outcome {
+                Ok((state, body)) => (TritState::from_i8(*state), ResultData::Text(body.clone()), usage),
+                Err(e) => (TritState::Failed, ResultData::Text(e.clone()), usage),
+            }
+        });
+        let (state, body) = match outcome {
+            Ok(ok) => ok,
+            Err(e) => {
+                let mut resp = error_response(422, &e);
+                resp.trit_result = result;
+                return resp;
+            }
+        };
+        let ctp = match state { 1 => CtpHeader::success(), 0 => CtpHeader::pending(), _ => CtpHeader::failed() };
+        HttpResponse {
+            status: 200,
+            headers: HashMap::new(),
+            body: format!("{{\"상태\":\"{}\",\"결과\":{}}}", result.state.symbol(), body),
+            bytes: None,
+            ctp,
+            trit_result: result,
+        }
+    });
+}
+
+/// DEX · NFT 엔드포인트 등록 (본문: key=value&... 폼)
+///   POST /dex/quote      pool, token_in, amount     → dex.quote (상태 변경 없음)
+///   POST /dex/swap       pool, token_in, amount     → dex.swap
+///   POST /dex/liquidity  pool, amount_a, amount_b   → dex.liquidity
+///   GET  /nft/listings                              → nft.read
+///   POST /nft/mint       collection, name, description, image → nft.mint (컬렉션 제작자만,
+///                        콘텐츠 저장소가 붙어 있으면 메타데이터를 내용 주소로 저장 · 고정)
+///   POST /nft/list       nft, price                 → nft.list (소유자만)
+///   POST /nft/buy        nft                        → nft.buy
+///   POST /nft/bid        auction, amount            → nft.bid
+///   POST /nft/royalty    collection, rate_bps, [splits "a:7000,b:3000"] → nft.royalty (컬렉션 제작자만,
+///                        splits가 없으면 제작자 100%) — 이후 모든 판매 경로가 이 정책으로 정산
+///   POST /nft/royalty/clear  collection             → nft.royalty (재정의 해제 → NFT 기본값)
+/// 사용자는 토큰 subject — 본문으로 다른 사용자를 지정할 수 없다
+pub fn mount_market_api(
+    server: &mut CrownyServer,
+    dex: Rc<RefCell<CrownyDEX>>,
+    nft: Rc<RefCell<CrownyNFT>>,
+    signer: TokenSigner,
+) {
+    let signer = Rc::new(signer);
+
+    let d = dex.clone();
+    capability_route(server, HttpMethod::Post, "/dex/quote", "dex.quote", signer.clone(), move |_user, p| {
+        let r = d.borrow().quote(param(p, "pool")?, param(p, "token_in")?, param_u64(p, "amount")?)?;
+        Ok((r.trit, r.to_json()))
+    });
+
+    let d = dex.clone();
+    capability_route(server, HttpMethod::Post, "/dex/swap", "dex.swap", signer.clone(), move |user, p| {
+        let r = d.borrow_mut().swap(user, param(p, "pool")?, param(p, "token_in")?, param_u64(p, "amount")?)?;
+        Ok((1, r.to_json()))
+    });
+
+    capability_route(server, HttpMethod::Post, "/dex/liquidity", "dex.liquidity", signer.clone(), move |user, p| {
+        let r = dex.borrow_mut().add_liquidity(user, param(p, "pool")?,
+            param_u64(p, "amount_a")?, param_u64(p, "amount_b")?)?;
+        Ok((1, r.to_json()))
+    });
+
+    let n = nft.clone();
+    capability_route(server, HttpMethod::Get, "/nft/listings", "nft.read", signer.clone(), move |_user, _p| {
+        let market = n.borrow();
+        let mut listed: Vec<_> = market.nfts.values().filter(|x| x.listed).collect();
+        listed.sort_by_key(|x| x.token_id);
+        let auctions = market.auctions.iter().enumerate()
+            .filter(|(_, a)| a.status == crate::nft::AuctionStatus::Active)
+            .map(|(i, a)| JsonObject::new()
+                .int("auction", i as i64)
+                .str("nft_id", &a.nft_id)
+                .str("seller", &a.seller)
+                .int("current_bid", a.current_bid as i64)
+                .int("bids", a.bids.len() as i64))
+            .collect();
+        Ok((1, JsonObject::schema("crowny.nft_listings")
+            .objects("listings", listed.iter().map(|x| x.to_json()).collect())
+            .objects("auctions", auctions)))
+    });
+
+    let n = nft.clone();
+    capability_route(server, HttpMethod::Post, "/nft/mint", "nft.mint", signer.clone(), move |user, p| {
+        let collection = param(p, "collection")?;
+        let mut market = n.borrow_mut();
+        let creator = market.collections.get(collection).map(|c| c.creator.clone()).ok_or("컬렉션 없음")?;
+        if creator != user {
+            return Err(format!("컬렉션 제작자 아님: {}", user));
+        }
+        let meta = crate::nft::NFTMetadata::new(param(p, "name")?,
+            p.get("description").map(String::as_str).unwrap_or_default(), p.get("image").map(String::as_str).unwrap_or_default());
+        let id = if market.content.is_some() {
+            market.mint_stored(collection, user, meta, crate::nft::NFTRarity::Common, None)?
+        } else {
+            market.mint(collection, user, meta, crate::nft::NFTRarity::Common)?
+        };
+        Ok((1, market.nfts[&id].to_json()))
+    });
+
+    let n = nft.clone();
+    capability_route(server, HttpMethod::Post, "/nft/list", "nft.list", signer.clone(), move |user, p| {
+        let id = param(p, "nft")?;
+        let price = param_u64(p, "price")?;
+        let mut market = n.borrow_mut();
+        let owner = market.nfts.get(id).map(|x| x.owner.clone()).ok_or("NFT 없음")?;
+        if owner != user {
+            return Err(format!("소유자 아님: {}", user));
+        }
+        market.list(id, price)?;
+        Ok((0, market.nfts[id].to_json()))
+    });
+
+    let n = nft.clone();
+    capability_route(server, HttpMethod::Post, "/nft/buy", "nft.buy", signer.clone(), move |user, p| {
+        let tx = n.borrow_mut().buy(param(p, "nft")?, user)?;
+        Ok((1, tx.to_json()))
+    });
+
+    let n = nft.clone();
+    capability_route(server, HttpMethod::Post, "/nft/royalty", "nft.royalty", signer.clone(), move |user, p| {
+        let collection = param(p, "collection")?;
+        let rate_bps = param_u64(p, "rate_bps")?;
+        let policy = match p.get("splits").filter(|s| !s.is_empty()) {
+            Some(raw) => {
+                let splits = raw.split(',')
+                    .map(|kv| kv.split_once(':').and_then(|(r, s)| Some((r.trim(), s.trim().parse().ok()?))))
+                    .collect::<Option<Vec<(&str, u64)>>>()
+                    .ok_or_else(|| tr!("web.param_invalid", "splits", raw))?;
+                RoyaltyPolicy::split(rate_bps, &splits)?
+            }
+            None => RoyaltyPolicy::single(user, rate_bps),
+        };
+        let market = n.borrow();
+        creator_of(&market, collection, user)?;
+        market.royalties.borrow_mut().set_policy(collection, policy.clone())?;
+        Ok((1, policy.to_json().str("collection", collection)))
+    });
+
+    let n = nft.clone();
+    capability_route(server, HttpMethod::Post, "/nft/royalty/clear", "nft.royalty", signer.clone(), move |user, p| {
+        let collection = param(p, "collection")?;
+        let market = n.borrow();
+        creator_of(&market, collection, user)?;
+        let cleared = market.royalties.borrow_mut().clear_policy(collection);
+        Ok((if cleared { 1 } else { 0 }, JsonObject::new().str("collection", collection).bool("cleared", cleared)))
+    });
+
+    capability_route(server, HttpMethod::Post, "/nft/bid", "nft.bid", signer, move |user, p| {
+        let idx = param_u64(p, "auction")? as usize;
+        let amount = param_u64(p, "amount")?;
+        let mut market = nft.borrow_mut();
+        market.bid(idx, user, amount)?;
+        let a = &market.auctions[idx];
+        Ok((0, JsonObject::schema("crowny.nft_bid")
+            .trit("state", 0)
+            .int("auction", idx as i64)
+            .str("nft_id", &a.nft_id)
+            .str("bidder", user)
+            .int("amount", amount as i64)
+            .int("bids", a.bids.len() as i64)))
+    });
+}
+
+/// 컬렉션 제작자만 통과
+fn creator_of(market: &CrownyNFT, collection: &str, user: &str) -> Result<(), String> {
+    let creator = market.collections.get(collection).map(|c| c.creator.as_str()).ok_or("컬렉션 없음")?;
+    if creator != user {
+        return Err(format!("컬렉션 제작자 아님: {}", user));
+    }
+    Ok(())
+}
+
+/// 에어드롭 엔드포인트 등록 — 토큰 subject가 배포자
+///   POST /airdrop  id, csv ("address,amount" 줄), [collection, name], [chunks] → airdrop.run
+///   collection이 있으면 NFT (컬렉션 제작자만, 수령자마다 amount개), 없으면 토큰 전송
+///   chunks를 주면 그만큼만 배포하고 멈춘다 — 같은 id · 목록으로 다시 보내면 이어서
+/// 진행 상황은 store의 "airdrop.<subject>.<id>.*" — 배포 엔진과 같은 수명
+pub fn mount_airdrop_api(
+    server: &mut CrownyServer,
+    wallets: Rc<RefCell<TokenEngine>>,
+    nft: Rc<RefCell<CrownyNFT>>,
+    store: Rc<RefCell<TritStore>>,
+    signer: TokenSigner,
+) {
+    capability_route(server, HttpMethod::Post, "/airdrop", "airdrop.run", Rc::new(signer), move |user, p| {
+        let recipients = airdrop::parse_csv(param(p, "csv")?)?;
+        let chunks = match p.get("chunks") {
+            Some(_) => param_u64(p, "chunks")? as usize,
+            None => usize::MAX,
+        };
+        let limits = ChunkLimits::from_params(&ParamRegistry::new(), ChunkLimits::default().gas_limit);
+        let mut store = store.borrow_mut();
+        let mut drop = Airdrop::open(&format!("{}.{}", user, param(p, "id")?), recipients, limits, &mut store)?;
+        let reports = match p.get("collection").filter(|c| !c.is_empty()) {
+            Some(collection) => {
+                let mut market = nft.borrow_mut();
+                let creator = market.collections.get(collection).map(|c| c.creator.clone()).ok_or("컬렉션 없음")?;
+                if creator != user {
+                    return Err(format!("컬렉션 제작자 아님: {}", user));
+                }
+                let template = crate::nft::NFTMetadata::new(param(p, "name")?, "", "");
+                drop.run(&mut store, chunks, |chunk| airdrop::deliver_nfts(&mut market, collection, &template, chunk))
+            }
+            None => {
+                let mut engine = wallets.borrow_mut();
+                drop.run(&mut store, chunks, |chunk| airdrop::deliver_tokens(&mut engine, user, chunk))
+            }
+        };
+        let state = if !drop.is_done() { 0 } else if drop.failed.is_empty() { 1 } else { -1 };
+        Ok((state, drop.to_json()
+            .int("chunks", reports.len() as i64)
+            .int("delivered", reports.iter().map(|r| r.delivered as i64).sum())))
+    });
+}
+
+/// 마진 엔드포인트 등록 — 토큰 subject가 포지션 소유자, 가격은 요청마다 DEX를 관측한 TWAP
+///   POST /margin/deposit    token, amount                              → margin.trade (DEX 잔액 → 담보)
+///   POST /margin/withdraw   token, amount                              → margin.trade (미사용 담보 → DEX 잔액)
+///   POST /margin/open       pool, side(long|short), collateral, leverage → margin.trade
+///   POST /margin/close      position                                   → margin.trade (TWAP 정산 · 실현 손익)
+///   GET  /margin/positions                                             → margin.read (보유 포지션 · 미사용 담보)
+///   POST /margin/liquidate                                             → margin.liquidate (커널 태스크로 청산 검사)
+pub fn mount_margin_api(
+    server: &mut CrownyServer,
+    dex: Rc<RefCell<CrownyDEX>>,
+    engine: Arc<Mutex<MarginEngine>>,
+    kernel: Rc<RefCell<CrownyKernel>>,
+    signer: TokenSigner,
+) {
+    let signer = Rc::new(signer);
+
+    let (d, e) = (dex.clone(), engine.clone());
+    capability_route(server, HttpMethod::Post, "/margin/deposit", "margin.trade", signer.clone(), move |user, p| {
+        let (token, amount) = (param(p, "token")?, param_u64(p, "amount")?);
+        with_margin(&d, &e, |engine, dex, _| {
+            engine.deposit(dex, user, token, amount)?;
+            Ok((1, margin_collateral_json(engine, user)))
+        })
+    });
+
+    let (d, e) = (dex.clone(), engine.clone());
+    capability_route(server, HttpMethod::Post, "/margin/withdraw", "margin.trade", signer.clone(), move |user, p| {
+        let (token, amount) = (param(p, "token")?, param_u64(p, "amount")?);
+        with_margin(&d, &e, |engine, dex, _| {
+            engine.withdraw(dex, user, token, amount)?;
+            Ok((1, margin_collateral_json(engine, user)))
+        })
+    });
+
+    let (d, e) = (dex.clone(), engine.clone());
+    capability_route(server, HttpMethod::Post, "/margin/open", "margin.trade", signer.clone(), move |user, p| {
+        let pool = param(p, "pool")?;
+        let side = match param(p, "side")? {
+            "long" => PositionSide::Long,
+            "short" => PositionSide::Short,
+            other => return Err(tr!("web.param_invalid", "side", other)),
+        };
+        let collateral = param_u64(p, "collateral")?;
+        let raw = param(p, "leverage")?;
+        let leverage: f64 = raw.parse().map_err(|_| tr!("web.param_invalid", "leverage", raw))?;
+        with_margin(&d, &e, |engine, _, now| {
+            let id = engine.open_position(user, pool, side, collateral, leverage, now)?;
+            Ok((1, engine.positions[id as usize - 1].to_json()))
+        })
+    });
+
+    let (d, e) = (dex.clone(), engine.clone());
+    capability_route(server, HttpMethod::Post, "/margin/close", "margin.trade", signer.clone(), move |user, p| {
+        let id = param_u64(p, "position")?;
+        with_margin(&d, &e, |engine, _, now| {
+            let pnl = engine.close_position(user, id, now)?;
+            Ok((1, engine.positions[id as usize - 1].to_json().float("pnl", pnl)))
+        })
+    });
+
+    let (d, e) = (dex.clone(), engine.clone());
+    capability_route(server, HttpMethod::Get, "/margin/positions", "margin.read", signer.clone(), move |user, _p| {
+        with_margin(&d, &e, |engine, _, _| {
+            let open = engine.open_positions().into_iter()
+                .filter(|pos| pos.owner == user)
+                .map(|pos| pos.to_json())
+                .collect();
+            Ok((1, margin_collateral_json(engine, user).objects("positions", open)))
+        })
+    });
+
+    capability_route(server, HttpMethod::Post, "/margin/liquidate", "margin.liquidate", signer, move |_user, _p| {
+        // 검사 태스크가 엔진을 다시 잠그므로 관측만 하고 풀어 둔다
+        with_margin(&dex, &engine, |_, _, _| Ok(()))?;
+        let events = schedule_liquidations(&mut kernel.borrow_mut(), &engine, crate::cron::now_ms())?;
+        let state = events.iter().map(|e| e.trit).min().unwrap_or(1);
+        Ok((state, JsonObject::schema("crowny.margin_check")
+            .objects("events", events.iter().map(|e| e.to_json()).collect())))
+    });
+}
+
+/// 엔진을 잠그고 현재 DEX 가격을 관측한 뒤 처리
+fn with_margin<T>(
+    dex: &RefCell<CrownyDEX>,
+    engine: &Mutex<MarginEngine>,
+    op: impl FnOnce(&mut MarginEngine, &mut CrownyDEX, u64) -> Result<T, String>,
+) -> Result<T, String> {
+    let now = crate::cron::now_ms();
+    let mut engine = engine.lock().map_err(|_| "마진 엔진 잠금 실패".to_string())?;
+    let mut dex = dex.borrow_mut();
+    engine.sync_dex(&dex, now);
+    op(&mut engine, &mut dex, now)
+}
+
+fn margin_collateral_json(engine: &MarginEngine, user: &str) -> JsonObject {
+    let mut free = JsonObject::new();
+    let mut tokens: Vec<_> = engine.collateral.get(user).into_iter().flatten().collect();
+    tokens.sort();
+    for (token, amount) in tokens {
+        free = free.int(token, *amount as i64);
+    }
+    JsonObject::schema("crowny.margin_account").str("user", user).object("collateral", free)
+}
+
+/// 포트폴리오 엔드포인트 등록 — 토큰 subject의 보유 자산과 손익
+///   GET  /portfolio           → portfolio.read (동기화 후 현재 평가)
+///   GET  /portfolio/history   → portfolio.read (기록된 손익 시계열)
+///   POST /portfolio/snapshot  → portfolio.snapshot (평가를 시계열에 기록)
+pub fn mount_portfolio_api(server: &mut CrownyServer, service: Rc<RefCell<PortfolioService>>, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+
+    let svc = service.clone();
+    capability_route(server, HttpMethod::Get, "/portfolio", "portfolio.read", signer.clone(), move |user, _p| {
+        let mut svc = svc.borrow_mut();
+        svc.sync();
+        let report = svc.report(user);
+        Ok((report.state(), report.to_json()))
+    });
+
+    let svc = service.clone();
+    capability_route(server, HttpMethod::Get, "/portfolio/history", "portfolio.read", signer.clone(), move |user, _p| {
+        let svc = svc.borrow();
+        let points = svc.history(user);
+        let state = if points.is_empty() { 0 } else { 1 };
+        Ok((state, JsonObject::schema("crowny.portfolio_history")
+            .str("user", user)
+            .objects("points", crate::portfolio::history_json(points))))
+    });
+
+    capability_route(server, HttpMethod::Post, "/portfolio/snapshot", "portfolio.snapshot", signer, move |user, _p| {
+        let report = service.borrow_mut().snapshot(user);
+        Ok((report.state(), report.to_json()))
+    });
+}
+
+/// 계정 엔드포인트 등록 — 토큰 주체의 계정과 원장별 보유 자산
+///   GET  /account  → account.read (프로필 · 키 · 별칭 + 모든 이름으로 합산한 잔액)
+pub fn mount_account_api(server: &mut CrownyServer, accounts: SharedAccounts,
+                         ledgers: Vec<Rc<RefCell<dyn AccountLedger>>>, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+    capability_route(server, HttpMethod::Get, "/account", "account.read", signer, move |user, _p| {
+        let accounts = accounts.borrow();
+        let account = accounts.resolve(user).ok_or_else(|| format!("계정 없음: {}", user))?;
+        let borrowed: Vec<_> = ledgers.iter().map(|l| l.borrow()).collect();
+        let refs: Vec<&dyn AccountLedger> = borrowed.iter().map(|l| &**l).collect();
+        let holdings = accounts.holdings(&account.id, &refs).into_iter()
+            .map(|(ledger, asset, amount)| JsonObject::new().str("ledger", &ledger).str("asset", &asset).int("amount", amount as i64))
+            .collect();
+        Ok((account.trit(), account.to_json().objects("holdings", holdings)))
+    });
+}
+
+/// LLM 엔드포인트 등록 — 토큰 사용량은 토큰 주체의 테넌트에 계량
+///   GET  /llm                                → llm.read (기본 모델 · 백엔드 · 호출 통계)
+///   POST /llm  prompt [model system fresh]   → llm.call (P 200 · O 503 다시 시도 · T 502)
+pub fn mount_llm_api(server: &mut CrownyServer, llm: SharedLlm, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+
+    let l = llm.clone();
+    capability_route(server, HttpMethod::Get, "/llm", "llm.read", signer.clone(), move |_user, _p| {
+        Ok((1, l.borrow().to_json()))
+    });
+
+    server.route(HttpMethod::Post, "/llm", move |req, car| {
+        let token = match signer.verify(req.header(TOKEN_HEADER), "llm.call") {
+            Ok(t) => t,
+            Err(e) => return error_response(e.status(), &e.to_string()),
+        };
+        let params = form_params(&req.body);
+        let prompt = match param(&params, "prompt") {
+            Ok(p) => p,
+            Err(e) => return error_response(422, &e),
+        };
+        let mut llm = llm.borrow_mut();
+        let model = params.get("model").map_or_else(|| llm.default_model.clone(), |m| LlmModel::parse(m));
+        let mut request = LlmRequest::new(model.clone(), prompt).for_subject(&token.subject);
+        if let Some(system) = params.get("system") {
+            request = request.with_system(system);
+        }
+        if params.get("fresh").is_some_and(|f| f == "1" || f == "true") {
+            request = request.fresh();
+        }
+        let result = llm.call(request, car);
+        let (status, ctp) = match result.state {
+            TritState::Success => (200, CtpHeader::success()),
+            TritState::Pending => (503, CtpHeader::pending()),
+            TritState::Failed => (502, CtpHeader::failed()),
+        };
+        let json = JsonObject::new().str("model", &model.to_string()).str("text", &result.data.to_string());
+        HttpResponse {
+            status,
+            headers: HashMap::new(),
+            body: format!("{{\"상태\":\"{}\",\"결과\":{}}}", result.state.symbol(), json.build()),
+            bytes: None,
+            ctp,
+            trit_result: result,
+        }
+    });
+}
+
+/// 아티팩트 저장소 엔드포인트 등록 — CAR · 컨트랙트 VM과 같은 저장소
+///   GET  /artifacts               → artifacts.read (통계 + 해시 순 목록)
+///   POST /artifacts/build  source → artifacts.build (CAR 빌드, 같은 소스는 한 번만 저장)
+///   POST /artifacts/pin    hash   → artifacts.manage (gc 제외)
+///   POST /artifacts/unpin  hash   → artifacts.manage
+///   POST /artifacts/release hash  → artifacts.manage (참조 1 감소)
+///   POST /artifacts/gc            → artifacts.manage (참조 0 · 비고정 회수)
+pub fn mount_artifacts_api(server: &mut CrownyServer, artifacts: SharedArtifacts, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+
+    let store = artifacts.clone();
+    capability_route(server, HttpMethod::Get, "/artifacts", "artifacts.read", signer.clone(), move |_user, _p| {
+        let store = store.borrow();
+        let state = if store.stats().artifacts == 0 { 0 } else { 1 };
+        Ok((state, store.to_json()))
+    });
+
+    // 빌드는 CAR 작업으로 — 결과 해시 맵을 그대로 돌려준다
+    let build_signer = signer.clone();
+    server.route(HttpMethod::Post, "/artifacts/build", move |req, car| {
+        let token = match build_signer.verify(req.header(TOKEN_HEADER), "artifacts.build") {
+            Ok(t) => t,
+            Err(e) => return error_response(e.status(), &e.to_string()),
+        };
+        let params = form_params(&req.body);
+        let source = match param(&params, "source") {
+            Ok(s) => s,
+            Err(e) => return error_response(422, &e),
+        };
+        let result = car.build_artifacts(&token.subject, source);
+        let ResultData::Map(hashes) = &result.data else {
+            let mut resp = error_response(422, &result.data.to_string());
+            resp.trit_result = result;
+            return resp;
+        };
+        let mut json = JsonObject::new();
+        for key in ["source", "wasm", "bytecode", "source_map"] {
+            if let Some(ResultData::Text(h)) = hashes.get(key) {
+                json = json.str(key, h);
+            }
+        }
+        HttpResponse {
+            status: 200,
+            headers: HashMap::new(),
+            body: format!("{{\"상태\":\"{}\",\"결과\":{}}}", result.state.symbol(), json.build()),
+            bytes: None,
+            ctp: CtpHeader::success(),
+            trit_result: result,
+        }
+    });
+
+    let store = artifacts.clone();
+    capability_route(server, HttpMethod::Post, "/artifacts/pin", "artifacts.manage", signer.clone(), move |_user, p| {
+        let hash = param(p, "hash")?;
+        store.borrow_mut().pin(hash)?;
+        Ok((1, JsonObject::new().str("pinned", hash)))
+    });
+
+    let store = artifacts.clone();
+    capability_route(server, HttpMethod::Post, "/artifacts/unpin", "artifacts.manage", signer.clone(), move |_user, p| {
+        let hash = param(p, "hash")?;
+        store.borrow_mut().unpin(hash)?;
+        Ok((1, JsonObject::new().str("unpinned", hash)))
+    });
+
+    let store = artifacts.clone();
+    capability_route(server, HttpMethod::Post, "/artifacts/release", "artifacts.manage", signer.clone(), move |_user, p| {
+        let hash = param(p, "hash")?;
+        let refs = store.borrow_mut().release(hash)?;
+        Ok((1, JsonObject::new().str("hash", hash).int("refs", refs as i64)))
+    });
+
+    capability_route(server, HttpMethod::Post, "/artifacts/gc", "artifacts.manage", signer, move |_user, _p| {
+        let report = artifacts.borrow_mut().gc();
+        let state = if report.removed == 0 { 0 } else { 1 };
+        Ok((state, JsonObject::new()
+            .int("removed", report.removed as i64)
+            .int("bytes_freed", report.bytes_freed as i64)))
+    });
+}
+
+/// 예약 작업 엔드포인트 등록 — 소유자는 토큰 subject
+///   GET  /jobs                              → jobs.read (다음 실행 · 마지막 트릿)
+///   POST /jobs         name, schedule, source → jobs.manage (cron · RRULE · 한국어 일정)
+///   POST /jobs/remove  id                   → jobs.manage (소유자만)
+pub fn mount_jobs_api(server: &mut CrownyServer, jobs: Rc<RefCell<JobScheduler>>, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+
+    let sched = jobs.clone();
+    capability_route(server, HttpMethod::Get, "/jobs", "jobs.read", signer.clone(), move |_user, _p| {
+        let sched = sched.borrow();
+        let state = if sched.jobs().is_empty() { 0 } else { 1 };
+        Ok((state, sched.to_json()))
+    });
+
+    let sched = jobs.clone();
+    capability_route(server, HttpMethod::Post, "/jobs", "jobs.manage", signer.clone(), move |user, p| {
+        let mut sched = sched.borrow_mut();
+        let id = sched.add(param(p, "name")?, param(p, "schedule")?, param(p, "source")?, user, crate::cron::now_ms())?;
+        let job = sched.get(id).ok_or("작업 등록 실패")?;
+        Ok((1, job.to_json(sched.utc_offset_min)))
+    });
+
+    capability_route(server, HttpMethod::Post, "/jobs/remove", "jobs.manage", signer, move |user, p| {
+        let id = param_u64(p, "id")?;
+        let mut sched = jobs.borrow_mut();
+        match sched.get(id) {
+            None => return Err(format!("작업 없음: {}", id)),
+            Some(job) if job.owner != user => return Err(format!("작업 {}의 소유자가 아님", id)),
+            Some(_) => {}
+        }
+        sched.remove(id)?;
+        Ok((1, JsonObject::new().int("removed", id as i64)))
+    });
+}
+
+/// 계량 · 과금 엔드포인트 등록 — 테넌트는 토큰 subject의 '/' 앞부분
+///   GET  /accounting          → accounting.read (오늘 사용량 · 예산 · P/O/T 상태)
+///   GET  /accounting/export   ?from=&to=&tenant= → accounting.export (text/csv, 기본 최근 30일 전체)
+///   POST /accounting/budget   tenant, cycles, store_bytes, llm_tokens, net_bytes, warn_pct
+///                             → accounting.admin (한도가 하나도 없으면 예산 해제)
+pub fn mount_accounting_api(server: &mut CrownyServer, accounting: SharedAccounting, signer: TokenSigner) {
+    let signer = Rc::new(signer);
+
+    let acc = accounting.clone();
+    capability_route(server, HttpMethod::Get, "/accounting", "accounting.read", signer.clone(), move |user, _p| {
+        let mut acc = acc.borrow_mut();
+        let (tenant, now) = (billing::tenant_of(user), billing::now_ms());
+        Ok((acc.tenant_state_at(tenant, now), acc.tenant_json(tenant, now)))
+    });
+
+    let acc = accounting.clone();
+    let export_signer = signer.clone();
+    server.route(HttpMethod::Get, "/accounting/export", move |req, _car| {
+        if let Err(e) = export_signer.verify(req.header(TOKEN_HEADER), "accounting.export") {
+            return error_response(e.status(), &e.to_string());
+        }
+        let q = req.query();
+        let acc = acc.borrow();
+        let today = acc.day_of(billing::now_ms());
+        let day = |key: &str, default: i64| match q.get(key).filter(|v| !v.is_empty()) {
+            Some(v) => billing::parse_day(v),
+            None => Ok(default),
+        };
+        let (from, to) = match (day("from", today - 30), day("to", today)) {
+            (Ok(f), Ok(t)) => (f, t),
+            (Err(e), _) | (_, Err(e)) => return error_response(400, &e),
+        };
+        let mut resp = ok_response(acc.to_csv(q.get("tenant").map(String::as_str), from..=to));
+        resp.headers.insert("Content-Type".into(), "text/csv; charset=utf-8".into());
+        resp.headers.insert("Content-Disposition".into(),
+            format!("attachment; filename=\"crowny-usage-{}-{}.csv\"", billing::format_day(from), billing::format_day(to)));
+        resp
+    });
+
+    capability_route(server, HttpMethod::Post, "/accounting/budget", "accounting.admin", signer, move |_user, p| {
+        let tenant = param(p, "tenant")?;
+        let mut budget = Budget::new();
+        for r in Resource::ALL {
+            if p.get(r.name()).is_some_and(|v| !v.is_empty()) {
+                budget = budget.limit(r, param_u64(p, r.name())?);
+            }
+        }
+        if p.get("warn_pct").is_some_and(|v| !v.is_empty()) {
+            let pct = param_u64(p, "warn_pct")?;
+            if !(1..=100).contains(&pct) {
+                return Err(tr!("web.param_invalid", "warn_pct", pct));
+            }
+            budget = budget.warn_at(pct as u8);
+        }
+        let mut acc = accounting.borrow_mut();
+        if Resource::ALL.iter().all(|r| budget.cap(*r).is_none()) {
+            acc.clear_budget(tenant);
+        } else {
+            acc.set_budget(tenant, budget);
+        }
+        let now = billing::now_ms();
+        Ok((acc.tenant_state_at(tenant, now), acc.tenant_json(tenant, now)))
+    });
+}
+
+/// JSON-RPC 엔드포인트 — POST /rpc (eth_getLogs · 필터 · 구독, rpc.rs)
+/// 알림만 담긴 요청은 204
+pub fn mount_rpc(server: &mut CrownyServer, rpc: Rc<RefCell<LogRpc>>) {
+    server.route(HttpMethod::Post, "/rpc", move |req, _car| {
+        match rpc.borrow_mut().handle(&req.body) {
+            Some(body) => ok_response(body),
+            None => HttpResponse { status: 204, body: String::new(), ..ok_response(String::new()) },
+        }
+    });
+}
+
+/// 내용 주소 콘텐츠 — GET /content/<hash> (content.rs)
+/// 꺼낼 때 해시를 다시 확인하고, 불일치면 500으로 거부한다
+pub fn mount_content(server: &mut CrownyServer, store: SharedContent) {
+    server.route(HttpMethod::Get, "/content/*", move |req, _car| {
+        let hash = req.param("*").unwrap_or_default();
+        match store.borrow().get(hash) {
+            Ok(content) => {
+                let mut resp = ok_response(String::new());
+                resp.headers.insert("Content-Type".into(), content.mime.clone());
+                // 내용이 바뀌지 않으므로 영구 캐시
+                resp.headers.insert("Cache-Control".into(), "public, max-age=31536000, immutable".into());
+                resp.headers.insert("ETag".into(), format!("\"{}\"", content.hash));
+                resp.bytes = Some(content.bytes);
+                resp
+            }
+            Err(e) => error_response(e.status(), &e.to_string()),
+        }
+    });
+}
+
+// ═══════════════════════════════════════════════
+// CPM 패키지 레지스트리 (cpm.rs 형식)
+// ═══════════════════════════════════════════════
+
+/// 레지스트리 엔드포인트 등록 — 조회 · 다운로드는 공개, 게시는 cpm.publish 토큰
+pub fn mount_registry(server: &mut CrownyServer, registry: Rc<RefCell<CrownyPM>>, signer: TokenSigner) {
+    let reg = registry.clone();
+    server.route(HttpMethod::Get, "/cpm/search", move |req, _car| {
+        let q = req.query().remove("q").unwrap_or_default();
+        let reg = reg.borrow();
+        let mut found = reg.search(&q);
+        found.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        ok_response(JsonObject::new().objects("packages", found.iter().map(|p| p.to_json()).collect()).build())
+    });
+
+    let reg = registry.clone();
+    server.route(HttpMethod::Get, "/cpm/packages/:name", move |req, _car| {
+        let name = req.param("name").unwrap_or_default();
+        let reg = reg.borrow();
+        let versions = reg.versions(name);
+        if versions.is_empty() {
+            return error_response(404, &format!("패키지 없음: {}", name));
+        }
+        ok_response(JsonObject::new().str("name", name).objects("versions", versions.iter().map(|p| p.to_json()).collect()).build())
+    });
+
+    let reg = registry.clone();
+    server.route(HttpMethod::Get, "/cpm/download/:name", move |req, _car| {
+        let name = req.param("name").unwrap_or_default();
+        let version = match req.query().get("version") {
+            Some(v) => match cpm::Version::parse(v) {
+                Some(v) => Some(v),
+                None => return error_response(400, &format!("잘못된 버전: {}", v)),
+            },
+            None => None,
+        };
+        let reg = reg.borrow();
+        match reg.download(name, version.as_ref()) {
+            Some((pkg, source)) => {
+                let mut resp = ok_response(cpm::package_bundle(pkg, &source));
+                resp.headers.insert("Content-Type".into(), "application/json".into());
+                resp.headers.insert(cpm::CHECKSUM_HEADER.into(), crate::chain::trit_hash(&source));
+                resp
+            }
+            None => error_response(404, &format!("패키지 없음: {}{}", name, version.map(|v| format!("@{}", v)).unwrap_or_default())),
+        }
+    });
+
+    server.route(HttpMethod::Post, "/cpm/publish", move |req, _car| {
+        let token = match signer.verify(req.header(TOKEN_HEADER), cpm::PUBLISH_SCOPE) {
+            Ok(t) => t,
+            Err(e) => return error_response(e.status(), &e.to_string()),
+        };
+        let published = cpm::parse_bundle(&req.body)
+            .and_then(|(pkg, source)| {
+                let summary = (pkg.name.clone(), pkg.version.to_string());
+                registry.borrow_mut().publish_verified(pkg, &source).map(|checksum| (summary, checksum))
+            });
+        match published {
+            Ok(((name, version), checksum)) => {
+                println!("[CPM] 게시 {}@{} by {}", name, version, token.subject);
+                let mut resp = ok_response(JsonObject::new()
+                    .str("name", &name).str("version", &version).str("checksum", &checksum).str("publisher", &token.subject).build());
+                resp.status = 201;
+                resp
+            }
+            Err(e) => error_response(e.status(), &e.to_string()),
+        }
+    });
+}
+
+// ═══════════════════════════════════════════════
+// 기록 조회 API — 공통 질의 (query.rs)
+// ═══════════════════════════════════════════════
+
+/// 질의 파싱 → 페이지 JSON (잘못된 파라미터는 400)
+fn history_response<T>(req: &HttpRequest, schema: &str, fetch: impl FnOnce(&Query) -> Page<T>,
+                       item: impl Fn(&T) -> JsonObject) -> HttpResponse {
+    match Query::from_params(&req.query()) {
+        Ok(q) => ok_response(fetch(&q).to_json(schema, item).build()),
+        Err(e) => error_response(400, &e),
+    }
+}
+
+/// 기록 조회 엔드포인트 등록 (읽기 전용, ?cursor=&limit=&since=&until=&trit=&account=&order=)
+///   GET /history/swaps    DEX 스왑 (account = 거래자)
+///   GET /history/market   NFT 마켓 거래 (account = 보낸/받은 쪽)
+///   GET /history/tasks    CAR 작업 기록 (account = 제출 주체)
+///   GET /history/blocks   체인 블록 (커서 = 블록 번호, account = 검증자 · 트랜잭션 당사자)
+///   GET /history/logs     이벤트 로그 (커서 = 이벤트 ID, account = 출처)
+pub fn mount_history_api(server: &mut CrownyServer, dex: Rc<RefCell<CrownyDEX>>, nft: Rc<RefCell<CrownyNFT>>,
+                         chain: Rc<RefCell<CrownyChain>>, events: Rc<RefCell<TritEventLog>>) {
+    server.route(HttpMethod::Get, "/history/swaps", move |req, _car| {
+        let dex = dex.borrow();
+        history_response(req, "crowny.swap_page", |q| dex.swaps(q), |r| r.to_json())
+    });
+    server.route(HttpMethod::Get, "/history/market", move |req, _car| {
+        let market = nft.borrow();
+        history_response(req, "crowny.market_page", |q| market.market_txs(q), |t| t.to_json())
+    });
+    server.route(HttpMethod::Get, "/history/tasks", move |req, car| {
+        history_response(req, "crowny.task_page", |q| car.task_history(q), |t| JsonObject::new()
+            .int("task_id", t.task_id as i64)
+            .str("type", &t.task_type.to_string())
+            .str("subject", &t.subject)
+            .trit("state", t.state as i8)
+            .int("elapsed_ms", t.elapsed_ms as i64)
+            .int("finished_at", t.finished_at as i64))
+    });
+    server.route(HttpMethod::Get, "/history/blocks", move |req, _car| {
+        let chain = chain.borrow();
+        history_response(req, "crowny.block_page", |q| chain.query_blocks(q), |b| b.to_json())
+    });
+    server.route(HttpMethod::Get, "/history/logs", move |req, _car| {
+        let log = events.borrow();
+        history_response(req, "crowny.log_page", |q| log.query(q), |e| e.to_json())
+    });
+}
+
+/// 성공 응답 (P)
+pub fn ok_response(body: String) -> HttpResponse {
+    HttpResponse {
+        status: 200,
+        headers: HashMap::new(),
+        body,
+        bytes: None,
+        ctp: CtpHeader::success(),
+        trit_result: TritResult {
+            state: TritState::Success,
+            data: ResultData::None,
+            elapsed_ms: 0,
+            task_id: 0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ctp_header() {
+        let h = CtpHeader::from_header_str("PPPOOOOOT");
+        assert_eq!(h.state, 1);
+        assert_eq!(h.permission, 1);
+        assert_eq!(h.consensus, 1);
+        assert_eq!(h.to_header_str(), "PPPOOOOOT");
+    }
+
+    #[test]
+    fn test_server_handle() {
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+
+        // GET /
+        let req = HttpRequest::new(HttpMethod::Get, "/").with_ctp(CtpHeader::success());
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 200);
+
+        // POST /run
+        let req = HttpRequest::new(HttpMethod::Post, "/run")
+            .with_body("넣어 10\n넣어 20\n더해\n종료")
+            .with_ctp(CtpHeader::success());
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.trit_result.state, TritState::Success);
+    }
+
+    #[test]
+    fn test_ctp_denied() {
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+
+        let req = HttpRequest::new(HttpMethod::Get, "/").with_ctp(CtpHeader::failed());
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 403);
+    }
+
+    #[test]
+    fn test_llm_call() {
+        let mut car = CrownyRuntime::new();
+        let mut llm = CrownyLlm::new();
+
+        let result = llm.ask("테스트 질문", &mut car);
+        assert_eq!(result.state, TritState::Success);
+    }
+
+    #[test]
+    fn test_llm_consensus() {
+        let mut car = CrownyRuntime::new();
+        let mut llm = CrownyLlm::new();
+
+        let result = llm.consensus_call(
+            "균형3진 효율?",
+            &[LlmModel::Claude, LlmModel::Gpt4, LlmModel::Gemini],
+            &mut car,
+        );
+        assert_eq!(result.state, TritState::Success);
+    }
+
+    #[test]
+    fn test_llm_cache_hits_and_bypass() {
+        let mut car = CrownyRuntime::new();
+        let mut llm = CrownyLlm::new().with_cache(TritStore::new());
+
+        let first = llm.ask("3진 효율?", &mut car);
+        let second = llm.ask("3진 효율?", &mut car);
+        assert_eq!((first.state, second.state), (TritState::Success, TritState::Success));
+        assert!(matches!((&first.data, &second.data), (ResultData::Text(a), ResultData::Text(b)) if a == b));
+        assert_eq!(llm.call_count, 1);
+        assert_eq!((llm.cache_stats.hits, llm.cache_stats.misses), (1, 1));
+        assert!(llm.cache_stats.tokens_saved > 0);
+
+        // 다른 모델 · 시스템 프롬프트는 다른 키
+        llm.call(LlmRequest::new(LlmModel::Claude, "3진 효율?").with_system("짧게"), &mut car);
+        assert_eq!((llm.call_count, llm.cache_len()), (2, 2));
+
+        // 합의용 fresh 호출은 캐시를 건너뛴다
+        llm.consensus_call_with("3진 효율?", &[LlmModel::Claude, LlmModel::Gpt4], true, &mut car);
+        assert_eq!((llm.call_count, llm.cache_stats.bypassed), (4, 2));
+        llm.consensus_call("3진 효율?", &[LlmModel::Claude, LlmModel::Gpt4], &mut car);
+        assert_eq!((llm.call_count, llm.cache_stats.hits), (4, 3));
+        assert!(llm.stats().contains("적중:3/"));
+    }
+
+    #[test]
+    fn test_llm_cache_capacity_evicts_oldest() {
+        let mut car = CrownyRuntime::new();
+        let mut llm = CrownyLlm::new().with_cache(TritStore::new()).with_cache_capacity(2);
+        for prompt in ["가", "나", "다"] {
+            llm.ask(prompt, &mut car);
+            thread::sleep(Duration::from_millis(2));
+        }
+        assert_eq!((llm.cache_len(), llm.cache_stats.evicted), (2, 1));
+        // 가장 먼저 만료될 "가"가 밀려났다
+        llm.ask("다", &mut car);
+        llm.ask("가", &mut car);
+        assert_eq!((llm.cache_stats.hits, llm.call_count), (1, 4));
+        assert!(llm.to_json().build().contains("\"capacity\":2"));
+    }
+
+    #[test]
+    fn test_llm_cache_ttl_and_persistence() {
+        let mut car = CrownyRuntime::new();
+        // TTL 0 → 즉시 만료
+        let mut llm = CrownyLlm::new().with_cache(TritStore::new()).with_cache_ttl(0);
+        llm.ask("질문", &mut car);
+        llm.ask("질문", &mut car);
+        assert_eq!((llm.call_count, llm.cache_stats.hits, llm.cache_stats.expired), (2, 0, 1));
+        assert_eq!(llm.purge_cache(billing::now_ms()), 1);
+        assert_eq!(llm.cache_len(), 0);
+
+        // 실패 응답은 저장하지 않는다
+        let mut llm = CrownyLlm::new().with_cache(TritStore::new());
+        llm.set_backend(LlmModel::Claude, Box::new(crate::llm_backend::AnthropicBackend::new("http://127.0.0.1:1", "c")));
+        assert_eq!(llm.ask("질문", &mut car).state, TritState::Failed);
+        assert_eq!(llm.cache_len(), 0);
+
+        // 영속 저장소 → 재시작 후에도 적중
+        let dir = std::env::temp_dir().join(format!("crowny-llm-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        {
+            let mut llm = CrownyLlm::new().with_cache(TritStore::open(&dir).unwrap());
+            llm.ask("영속?", &mut car);
+        }
+        let mut llm = CrownyLlm::new().with_cache(TritStore::open(&dir).unwrap());
+        assert_eq!(llm.ask("영속?", &mut car).state, TritState::Success);
+        assert_eq!((llm.call_count, llm.cache_stats.hits), (0, 1));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_llm_backend_per_model() {
+        use crate::llm_backend::AnthropicBackend;
+        let mut car = CrownyRuntime::new();
+        let mut llm = CrownyLlm::new();
+        llm.set_backend(LlmModel::Claude, Box::new(AnthropicBackend::new("http://127.0.0.1:1", "claude-test")));
+        assert_eq!(llm.backend_name(&LlmModel::Claude), "anthropic");
+        assert_eq!(llm.backend_name(&LlmModel::Gpt4), "echo");
+
+        // 키 없음 → T, 토큰 0
+        let result = llm.call(LlmRequest::new(LlmModel::Claude, "안녕"), &mut car);
+        assert_eq!(result.state, TritState::Failed);
+        assert!(matches!(&result.data, ResultData::Text(t) if t.contains("API 키")));
+
+        // 키는 등록된 백엔드로 전달 — 이제 연결 실패 → O
+        llm.set_api_key("Claude", "ak-test");
+        let result = llm.call(LlmRequest::new(LlmModel::Claude, "안녕"), &mut car);
+        assert_eq!(result.state, TritState::Pending);
+
+        // 나머지 모델은 여전히 에코
+        let result = llm.consensus_call("3진?", &[LlmModel::Claude, LlmModel::Gpt4, LlmModel::Gemini], &mut car);
+        assert_eq!(result.state, TritState::Success);
+    }
+
+    #[test]
+    fn test_llm_api_uses_configured_backend() {
+        let cfg = crate::config::RuntimeConfig::from_toml("[llm]\nbackend = \"anthropic\"\nurl = \"http://127.0.0.1:1\"").unwrap();
+        let (model, backend) = crate::llm_backend::from_config(&cfg.llm_backend, &cfg.llm_url, &cfg.llm_model).unwrap();
+        let mut llm = CrownyLlm::new();
+        llm.set_backend(model.clone(), backend);
+        llm.set_default_model(model);
+        let llm = llm.shared();
+
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let caller = signer.issue("acme/alice", &["llm.*"], 60_000).encode();
+        let reader = signer.issue("acme/alice", &["llm.read"], 60_000).encode();
+        mount_llm_api(&mut server, llm.clone(), signer);
+
+        let post = |body: &str, token: &str| HttpRequest::new(HttpMethod::Post, "/llm").with_header(TOKEN_HEADER, token).with_body(body);
+        let resp = server.handle(&HttpRequest::new(HttpMethod::Get, "/llm").with_header(TOKEN_HEADER, &reader), &mut car);
+        assert!(resp.body.contains("\"backend\":\"anthropic\"") && resp.body.contains("\"default_model\":\"Claude\""));
+        assert_eq!(server.handle(&post("prompt=안녕", &reader), &mut car).status, 403);
+        assert_eq!(server.handle(&post("", &caller), &mut car).status, 422);
+
+        // 기본 모델은 설정 백엔드 — 키 없음 → T 502
+        let resp = server.handle(&post("prompt=안녕", &caller), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (502, -1));
+        assert!(resp.body.contains("API 키"));
+        // 키는 등록된 백엔드로 — 이제 연결 실패 → O 503
+        llm.borrow_mut().set_api_key("Claude", "ak-test");
+        assert_eq!(server.handle(&post("prompt=안녕", &caller), &mut car).status, 503);
+        // 다른 모델은 에코
+        let resp = server.handle(&post("prompt=안녕&model=gemini&system=짧게", &caller), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 1));
+        assert!(resp.body.contains("\"model\":\"Gemini\""));
+    }
+
+    #[test]
+    fn test_run_cycle_limit() {
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+
+        // 무한 루프 → 429 + O
+        let req = HttpRequest::new(HttpMethod::Post, "/run")
+            .with_body("넣어 0\n점프")
+            .with_ctp(CtpHeader::from_header_str("POOOOOOOO"));
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 429);
+        assert_eq!(resp.trit_result.state, TritState::Pending);
+        assert_eq!(resp.ctp.state, 0);
+    }
+
+    #[test]
+    fn test_run_heap_limit() {
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+
+        // 해제 없이 할당 반복 → 413 + T
+        let req = HttpRequest::new(HttpMethod::Post, "/run")
+            .with_body("넣어 1\n할당\n넣어 0\n점프")
+            .with_ctp(CtpHeader::from_header_str("POOOOOOOO"));
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 413);
+        assert_eq!(resp.trit_result.state, TritState::Failed);
+        assert!(resp.body.contains("heap"));
+    }
+
+    #[test]
+    fn test_program_limits_reject_pathological_submission() {
+        let signer = TokenSigner::new("서버키");
+        let trusted = signer.issue("ci", &[TRUSTED_RUN_SCOPE], 60_000).encode();
+        let mut server = create_server_with_sandbox(SandboxPolicy::default(), Some(signer));
+        let mut car = CrownyRuntime::new();
+
+        // 게스트(O): 10,000 명령어 한도
+        let huge = "넣어 1\n".repeat(10_001);
+        let req = HttpRequest::new(HttpMethod::Post, "/run").with_body(&huge);
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 413);
+        assert_eq!(resp.trit_result.state, TritState::Failed);
+        assert!(resp.body.contains("\"한도\":\"max_instructions\""));
+        assert!(resp.body.contains("\"행\":10001"));
+
+        // 인증(P)은 통과
+        let req = req.with_header(TOKEN_HEADER, &trusted);
+        assert_eq!(server.handle(&req, &mut car).status, 200);
+
+        // /compile 도 같은 정책
+        let req = HttpRequest::new(HttpMethod::Post, "/compile")
+            .with_body(&format!("넣어 \"{}\"\n종료", "가".repeat(2_000)));
+        assert_eq!(server.handle(&req, &mut car).status, 413);
+    }
+
+    #[test]
+    fn test_sandbox_tier_ignores_forged_header() {
+        let signer = TokenSigner::new("서버키");
+        let trusted = signer.issue("ci", &[TRUSTED_RUN_SCOPE], 60_000).encode();
+        let other = signer.issue("ci", &["dex.*"], 60_000).encode();
+        let forged = TokenSigner::new("다른키").issue("ci", &[TRUSTED_RUN_SCOPE], 60_000).encode();
+        let p = CtpHeader::from_header_str("OPOOOOOOO");
+        let req = |token: Option<&str>, ctp: CtpHeader| {
+            let req = HttpRequest::new(HttpMethod::Post, "/run").with_ctp(ctp);
+            match token { Some(t) => req.with_header(TOKEN_HEADER, t), None => req }
+        };
+        // 헤더로 P를 주장해도 토큰 없으면 게스트
+        assert_eq!(sandbox_tier(&req(None, p.clone()), Some(&signer)), 0);
+        assert_eq!(sandbox_tier(&req(Some(&other), p.clone()), Some(&signer)), 0);
+        assert_eq!(sandbox_tier(&req(Some(&forged), p.clone()), Some(&signer)), 0);
+        assert_eq!(sandbox_tier(&req(Some(&trusted), p.clone()), None), 0);
+        assert_eq!(sandbox_tier(&req(Some(&trusted), p), Some(&signer)), 1);
+        // 클라이언트는 T로 낮출 수만 있다
+        assert_eq!(sandbox_tier(&req(Some(&trusted), CtpHeader::from_header_str("OTOOOOOOO")), Some(&signer)), -1);
+    }
+
+    #[test]
+    fn test_sandbox_policy_levels() {
+        let mut policy = SandboxPolicy::default();
+        assert!(policy.for_permission(1).max_cycles > policy.for_permission(0).max_cycles);
+        policy.levels[1] = ExecLimits { max_cycles: Some(5), ..ExecLimits::unlimited() };
+        assert_eq!(policy.for_permission(0).max_cycles, Some(5));
+        assert_eq!(policy.for_permission(0).max_heap, None);
+        assert!(policy.program_for_permission(1).max_instructions > policy.program_for_permission(0).max_instructions);
+        policy.programs[0] = ProgramLimits::unlimited();
+        assert_eq!(policy.program_for_permission(-1).max_nesting, None);
+    }
+
+    #[test]
+    fn test_cors_preflight() {
+        let mut server = create_demo_server().with_config(ServerConfig {
+            cors: Some(CorsConfig::with_origins(&["https://crowny.app"])),
+            ..ServerConfig::default()
+        });
+        let mut car = CrownyRuntime::new();
+
+        let req = HttpRequest::new(HttpMethod::Options, "/run")
+            .with_header("Origin", "https://crowny.app")
+            .with_header("Access-Control-Request-Method", "POST")
+            .with_header("Access-Control-Request-Headers", "content-type, x-crowny-trit");
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 204);
+        assert_eq!(resp.headers["Access-Control-Allow-Origin"], "https://crowny.app");
+        assert!(resp.headers["Access-Control-Allow-Headers"].contains("X-Crowny-Trit"));
+
+        // 허용되지 않은 오리진
+        let req = HttpRequest::new(HttpMethod::Options, "/run")
+            .with_header("Origin", "https://evil.example")
+            .with_header("Access-Control-Request-Method", "POST");
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 403);
+        assert!(!resp.headers.contains_key("Access-Control-Allow-Origin"));
+    }
+
+    #[test]
+    fn test_cors_on_response() {
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+
+        let req = HttpRequest::new(HttpMethod::Get, "/")
+            .with_ctp(CtpHeader::success())
+            .with_header("origin", "http://localhost:3000");
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.headers["Access-Control-Allow-Origin"], "*");
+        assert_eq!(resp.headers["Access-Control-Expose-Headers"], "X-Crowny-Trit, X-Crowny-Trit-V2");
+
+        // Origin 없는 요청엔 CORS 헤더 없음
+        let req = HttpRequest::new(HttpMethod::Get, "/").with_ctp(CtpHeader::success());
+        let resp = server.handle(&req, &mut car);
+        assert!(!resp.headers.contains_key("Access-Control-Allow-Origin"));
+    }
+
+    #[test]
+    fn test_body_limit() {
+        let mut server = create_demo_server()
+            .with_config(ServerConfig { max_body_bytes: 16, ..ServerConfig::default() });
+        let mut car = CrownyRuntime::new();
+
+        let req = HttpRequest::new(HttpMethod::Post, "/run")
+            .with_body("넣어 1\n넣어 2\n더해\n종료")
+            .with_ctp(CtpHeader::success());
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 413);
+        assert_eq!(resp.trit_result.state, TritState::Failed);
+    }
+
+    #[test]
+    fn test_read_request_wire() {
+        let raw = "POST /run HTTP/1.1\r\nX-Crowny-Trit: PPOOOOOOO\r\nContent-Length: 15\r\n\r\n넣어 1\n종료";
+        let mut r = std::io::Cursor::new(raw.as_bytes());
+        let req = read_request(&mut r, 1024).unwrap();
+        assert_eq!(req.method, HttpMethod::Post);
+        assert_eq!(req.ctp.state, 1);
+        assert_eq!(req.body, "넣어 1\n종료");
+        assert!(req.wants_keep_alive());
+        assert!(matches!(read_request(&mut r, 1024), Err(WireError::Closed)));
+
+        // 본문을 읽기 전에 거부
+        let raw = "POST /run HTTP/1.0\r\nContent-Length: 999999\r\n\r\n";
+        let mut r = std::io::Cursor::new(raw.as_bytes());
+        assert!(matches!(read_request(&mut r, 1024), Err(WireError::BodyTooLarge(999999))));
+
+        let req = HttpRequest { version: "HTTP/1.0".into(), ..HttpRequest::new(HttpMethod::Get, "/") };
+        assert!(!req.wants_keep_alive());
+    }
+
+    #[test]
+    fn test_keep_alive_connection() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut s = TcpStream::connect(addr).unwrap();
+            s.write_all(b"GET / HTTP/1.1\r\nX-Crowny-Trit: PPOOOOOOO\r\n\r\n").unwrap();
+            s.write_all(b"GET /x HTTP/1.1\r\nX-Crowny-Trit: PPOOOOOOO\r\nConnection: close\r\n\r\n").unwrap();
+            let mut out = String::new();
+            s.read_to_string(&mut out).unwrap();
+            out
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let served = server.serve_connection(stream, &mut car).unwrap();
+        assert_eq!(served, 2);
+
+        let out = client.join().unwrap();
+        assert!(out.starts_with("HTTP/1.1 200 OK"));
+        assert!(out.contains("Connection: keep-alive"));
+        assert!(out.contains("HTTP/1.1 404 Not Found"));
+        assert!(out.contains("Connection: close"));
+    }
+
+    #[test]
+    fn test_route_params_and_wildcards() {
+        let mut server = CrownyServer::new(0);
+        let echo = |req: &HttpRequest, _car: &mut CrownyRuntime| {
+            let mut keys: Vec<_> = req.params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            keys.sort();
+            ok_response(keys.join("&"))
+        };
+        server.route(HttpMethod::Get, "/task/:id", echo);
+        server.route(HttpMethod::Get, "/task/:id/log/:line", echo);
+        server.route(HttpMethod::Get, "/node/*/status", echo);
+        server.route(HttpMethod::Get, "/files/*", echo);
+        let mut car = CrownyRuntime::new();
+        let mut get = |path: &str| {
+            let resp = server.handle(&HttpRequest::new(HttpMethod::Get, path).with_ctp(CtpHeader::success()), &mut car);
+            (resp.status, resp.body)
+        };
+
+        assert_eq!(get("/task/42?verbose=1"), (200, "id=42".into()));
+        assert_eq!(get("/task/7/log/3"), (200, "id=7&line=3".into()));
+        assert_eq!(get("/node/n1/status"), (200, String::new()));
+        assert_eq!(get("/files/a/b.txt"), (200, "*=a/b.txt".into()));
+        for miss in ["/task/", "/task", "/task/7/log", "/task/7/extra/x/y", "/node//status", "/files/"] {
+            assert_eq!(get(miss).0, 404, "{}", miss);
+        }
+    }
+
+    #[test]
+    fn test_middleware_chain() {
+        let log = Rc::new(RefCell::new(TritEventLog::new()));
+        let mut server = create_demo_server();
+        server.route(HttpMethod::Get, "/admin/:what", |req, _car| ok_response(req.param("what").unwrap_or_default().into()));
+        server.add_middleware(RequestLog::new(Rc::clone(&log)));
+        server.add_middleware(RateLimiter::new(4, 0.0));
+        server.add_middleware(CtpAuth::require(1).under("/admin"));
+        let mut car = CrownyRuntime::new();
+
+        let guest = HttpRequest::new(HttpMethod::Get, "/admin/keys").with_ctp(CtpHeader::from_header_str("POOOOOOOO"));
+        assert_eq!(server.handle(&guest, &mut car).status, 401);
+        let denied = HttpRequest::new(HttpMethod::Get, "/admin/keys").with_ctp(CtpHeader::from_header_str("PTOOOOOOO"));
+        assert_eq!(server.handle(&denied, &mut car).status, 403);
+        let admin = HttpRequest::new(HttpMethod::Get, "/admin/keys").with_ctp(CtpHeader::success());
+        assert_eq!(server.handle(&admin, &mut car).body, "keys");
+        // 인증 범위 밖은 게스트도 통과
+        let public = HttpRequest::new(HttpMethod::Get, "/").with_ctp(CtpHeader::from_header_str("POOOOOOOO"));
+        assert_eq!(server.handle(&public, &mut car).status, 200);
+
+        // 버킷 4개 소진 → 429 + O, 핸들러까지 가지 않음
+        let resp = server.handle(&admin, &mut car);
+        assert_eq!(resp.status, 429);
+        assert_eq!(resp.trit_result.state, TritState::Pending);
+        assert!(resp.headers.contains_key("Retry-After"));
+
+        let log = log.borrow();
+        let events = log.recent(10);
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0].message, "GET /admin/keys → 401");
+        assert_eq!(events[0].level, Level::Warn);
+        assert_eq!(events[2].level, Level::Info);
+        assert_eq!(events[4].fields.get("status").map(String::as_str), Some("429"));
+    }
+
+    #[test]
+    fn test_rate_limiter_refill() {
+        let mut rl = RateLimiter::new(2, 1.0);
+        assert!(rl.check("a", 0).is_ok());
+        assert!(rl.check("a", 0).is_ok());
+        assert_eq!(rl.check("a", 0), Err(1000));
+        assert!(rl.check("b", 0).is_ok());     // 상대별 버킷
+        assert_eq!(rl.check("a", 600), Err(400));
+        assert!(rl.check("a", 1000).is_ok());
+        // 오래 쉬어도 용량까지만 찬다
+        assert!(rl.check("a", 60_000).is_ok());
+        assert!(rl.check("a", 60_000).is_ok());
+        assert!(rl.check("a", 60_000).is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_follows_config_reload() {
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let mut mgr = ConfigManager::new(RuntimeConfig::default());
+        let limiter = Rc::new(RefCell::new(RateLimiter::new(1, 0.0)));
+        mgr.attach("rate_limit", limiter.clone());
+        server.add_middleware(limiter);
+
+        let get = HttpRequest::new(HttpMethod::Get, "/");
+        for _ in 0..5 {
+            assert_eq!(server.handle(&get, &mut car).status, 200); // 기본 burst 100
+        }
+        mgr.apply_str("[rate_limit]\nrequests_per_min = 60\nburst = 1").unwrap();
+        assert_eq!(server.handle(&get, &mut car).status, 200);
+        let resp = server.handle(&get, &mut car);
+        assert_eq!(resp.status, 429);
+        assert_eq!(resp.headers["Retry-After"], "1");
+    }
+
+    #[test]
+    fn test_websocket_streams_task_events() {
+        use crate::websocket::{encode_frame, read_frame, OP_CLOSE, OP_PING, OP_PONG, OP_TEXT};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let hub = EventHub::new();
+        let mut server = create_demo_server().with_config(ServerConfig { idle_timeout_ms: 200, ..ServerConfig::default() });
+        server.websocket("/ws", hub.clone());
+        let stop = server.shutdown_handle();
+
+        let client = std::thread::spawn(move || {
+            let mut ws = TcpStream::connect(addr).unwrap();
+            ws.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            ws.write_all(b"GET /ws HTTP/1.1\r\nHost: x\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+                           Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n").unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                let mut b = [0u8; 1];
+                ws.read_exact(&mut b).unwrap();
+                head.push(b[0]);
+            }
+            let head = String::from_utf8(head).unwrap();
+
+            // 다른 연결의 실행 → task 프레임
+            let mut http = TcpStream::connect(addr).unwrap();
+            let body = "넣어 1\n종료";
+            http.write_all(format!("POST /run HTTP/1.1\r\nX-Crowny-Trit: PPOOOOOOO\r\nContent-Length: {}\r\n\
+                                    Connection: close\r\n\r\n{}", body.len(), body).as_bytes()).unwrap();
+            http.read_to_string(&mut String::new()).unwrap();
+            let task = read_frame(&mut ws, 1 << 20).unwrap();
+
+            ws.write_all(&encode_frame(OP_PING, b"hi", Some([1, 2, 3, 4]))).unwrap();
+            let pong = read_frame(&mut ws, 1024).unwrap();
+            ws.write_all(&encode_frame(OP_CLOSE, &1000u16.to_be_bytes(), Some([5, 6, 7, 8]))).unwrap();
+            let close = read_frame(&mut ws, 1024).unwrap();
+            stop.stop();
+            (head, task, pong, close)
+        });
+
+        let mut car = CrownyRuntime::new().with_events(hub.clone());
+        let stats = server.listen_on(listener, &mut car).unwrap();
+        let (head, task, pong, close) = client.join().unwrap();
+
+        assert!(head.starts_with("HTTP/1.1 101 Switching Protocols"), "{}", head);
+        assert!(head.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+        assert!(head.contains("X-Crowny-Trit: "));
+        assert_eq!(task.opcode, OP_TEXT);
+        let text = String::from_utf8(task.payload).unwrap();
+        assert!(text.starts_with("{\"type\":\"task\",\"task_id\":1,"), "{}", text);
+        assert!(text.contains("\"subject\":\"web\""));
+        assert_eq!((pong.opcode, pong.masked, pong.payload), (OP_PONG, false, b"hi".to_vec()));
+        assert_eq!((close.opcode, close.payload), (OP_CLOSE, 1000u16.to_be_bytes().to_vec()));
+        assert_eq!(stats.requests, 1);
+        // 끊긴 구독자는 다음 발행 때 정리
+        assert_eq!(hub.publish(JsonObject::new().str("type", "log")), 0);
+    }
+
+    #[test]
+    fn test_websocket_does_not_hold_worker() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let hub = EventHub::new();
+        // 워커 하나 — 구독자가 워커를 붙잡으면 아래 HTTP 요청은 끝나지 않는다
+        let mut server = create_demo_server()
+            .with_config(ServerConfig { idle_timeout_ms: 200, workers: 1, ..ServerConfig::default() });
+        server.websocket("/ws", hub.clone());
+        let stop = server.shutdown_handle();
+
+        let client = std::thread::spawn(move || {
+            let mut ws = TcpStream::connect(addr).unwrap();
+            ws.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            ws.write_all(b"GET /ws HTTP/1.1\r\nHost: x\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+                           Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n").unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                let mut b = [0u8; 1];
+                ws.read_exact(&mut b).unwrap();
+                head.push(b[0]);
+            }
+
+            let mut http = TcpStream::connect(addr).unwrap();
+            http.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            http.write_all(b"GET / HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n").unwrap();
+            let mut resp = String::new();
+            let read = http.read_to_string(&mut resp);
+            stop.stop();
+            (String::from_utf8(head).unwrap(), read.map(|_| resp))
+        });
+
+        let mut car = CrownyRuntime::new().with_events(hub.clone());
+        let stats = server.listen_on(listener, &mut car).unwrap();
+        let (head, resp) = client.join().unwrap();
+        assert!(head.starts_with("HTTP/1.1 101"), "{}", head);
+        let resp = resp.expect("구독 중에도 HTTP 응답");
+        assert!(resp.starts_with("HTTP/1.1 200"), "{}", resp);
+        assert_eq!(stats.requests, 1);
+        // 중지 후 구독 스레드도 끝났다
+        assert_eq!(hub.publish(JsonObject::new().str("type", "log")), 0);
+    }
+
+    #[test]
+    fn test_registry_publish_search_fetch() {
+        use crate::cpm::{Dependency, Package, RegistryClient};
+        use crate::integrations::http_request;
+
+        let dir = std::env::temp_dir().join(format!("crowny-registry-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let signer = TokenSigner::new("registry-secret");
+        let token = signer.issue("acme-ci", &[cpm::PUBLISH_SCOPE], 60_000).encode();
+        let reader = signer.issue("acme-ci", &["jobs.read"], 60_000).encode();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let mut server = CrownyServer::new(0);
+        mount_registry(&mut server, Rc::new(RefCell::new(CrownyPM::open_registry(&dir).unwrap())), signer);
+        let stop = server.shutdown_handle();
+
+        let client = std::thread::spawn(move || {
+            let mut math = CrownyPM::new().info("crowny.core").unwrap().clone();
+            math.name = "acme.math".into();
+            math.exports = vec!["두배".into()];
+            let mut app = math.clone();
+            app.name = "acme.app".into();
+            app.dependencies = vec![Dependency::new("acme.math", "^0.3")];
+            let src = "두배:\n  넣어 2\n  곱해\n  돌려줘\n";
+
+            let anon = RegistryClient::new(&url);
+            let publisher = RegistryClient::new(&url).with_token(&token);
+            let no_token = anon.publish(&math, src).unwrap_err();
+            let wrong_scope = RegistryClient::new(&url).with_token(&reader).publish(&math, src).unwrap_err();
+            let checksum = publisher.publish(&math, src).unwrap();
+            let duplicate = publisher.publish(&math, src).unwrap_err();
+            publisher.publish(&app, "; 앱\n").unwrap();
+            // 체크섬을 속인 본문은 서버가 거부
+            let forged = cpm::package_bundle(&Package { version: cpm::Version::new(9, 0, 0), ..math.clone() }, src)
+                .replace(&checksum, "0tPPPP");
+            let headers = [(TOKEN_HEADER.to_string(), token.clone())];
+            let forged = http_request("POST", &format!("{}/cpm/publish", url), &headers, &forged, Duration::from_secs(5)).unwrap();
+
+            let found = anon.search("acme").unwrap();
+            let (pkg, source) = anon.fetch("acme.math", Some(&cpm::Version::new(0, 3, 0))).unwrap();
+            let missing = anon.fetch("acme.none", None).unwrap_err();
+
+            // 원격 의존성까지 받아 설치
+            let mut local = CrownyPM::new();
+            let fetched = local.fetch(&anon, "acme.app", None).unwrap();
+            let installed = local.install("acme.app");
+            stop.stop();
+            (no_token, wrong_scope, duplicate, forged.status, found.len(), pkg, source, missing, fetched, installed)
+        });
+
+        let mut car = CrownyRuntime::new();
+        server.listen_on(listener, &mut car).unwrap();
+        let (no_token, wrong_scope, duplicate, forged, found, pkg, source, missing, fetched, installed) = client.join().unwrap();
+
+        assert!(no_token.contains("토큰 없음"), "{}", no_token);
+        assert!(wrong_scope.starts_with("HTTP 403"), "{}", wrong_scope);
+        assert!(duplicate.starts_with("HTTP 409"), "{}", duplicate);
+        assert_eq!(forged, 422);
+        assert_eq!(found, 2);
+        // 게시자가 P를 자칭해도 O(검토)로 들어간다
+        assert_eq!((pkg.name.as_str(), pkg.trust), ("acme.math", cpm::TritTrust::Review));
+        assert!(source.contains("두배:"));
+        assert!(missing.starts_with("HTTP 404"), "{}", missing);
+        assert_eq!(fetched, vec!["acme.app v0.3.0".to_string(), "acme.math v0.3.0".to_string()]);
+        assert_eq!(installed.state, TritState::Success);
+
+        // 재시작 후에도 게시본 유지
+        let reopened = CrownyPM::open_registry(&dir).unwrap();
+        assert_eq!(reopened.versions("acme.math").len(), 1);
+        assert_eq!(reopened.download("acme.math", None).unwrap().1, "두배:\n  넣어 2\n  곱해\n  돌려줘\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_read_chunked_request() {
+        let raw = "POST /run HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\
+                   6;ext=1\r\n넣어\r\n3\r\n 7\n\r\n0\r\nX-Trailer: t\r\n\r\n";
+        let req = read_request(&mut std::io::Cursor::new(raw), 1024).unwrap();
+        assert_eq!(req.body, "넣어 7\n");
+
+        // 누적 크기 한도
+        let raw = "POST /run HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nabcd\r\n4\r\nefgh\r\n0\r\n\r\n";
+        assert!(matches!(read_request(&mut std::io::Cursor::new(raw), 6), Err(WireError::BodyTooLarge(8))));
+
+        // Content-Length와 동시 사용, 잘못된 크기
+        let raw = "POST / HTTP/1.1\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n";
+        assert!(matches!(read_request(&mut std::io::Cursor::new(raw), 64), Err(WireError::Malformed(_))));
+        let raw = "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n";
+        assert!(matches!(read_request(&mut std::io::Cursor::new(raw), 64), Err(WireError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_listener_restarted_by_watchdog() {
+        use crate::watchdog::{Watchdog, WatchdogAction};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut server = create_demo_server()
+            .with_config(ServerConfig { idle_timeout_ms: 200, workers: 2, ..ServerConfig::default() });
+        let wd = Watchdog::new(3, 3).shared();
+        server.watch(wd.clone());
+        // 수락 스레드가 멎은 것처럼 시계를 앞당겨 tick → 새 수락 스레드
+        let kicked = Rc::new(RefCell::new(Vec::new()));
+        let (wd2, kicked2) = (wd.clone(), kicked.clone());
+        server.route(HttpMethod::Get, "/kick", move |_, _| {
+            let future = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+                .unwrap().as_millis() as u64 + 60_000;
+            kicked2.borrow_mut().extend(wd2.borrow_mut().tick_at(future));
+            ok_response("kicked".into())
+        });
+        let stop = server.shutdown_handle();
+
+        let client = std::thread::spawn(move || {
+            let get = |path: &str| {
+                let mut s = TcpStream::connect(addr).unwrap();
+                s.write_all(format!("GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path).as_bytes()).unwrap();
+                let mut out = String::new();
+                s.read_to_string(&mut out).unwrap();
+                out
+            };
+            let outs = vec![get("/kick"), get("/"), get("/")];
+            stop.stop();
+            outs
+        });
+
+        let mut car = CrownyRuntime::new();
+        let stats = server.listen_on(listener, &mut car).unwrap();
+        let outs = client.join().unwrap();
+
+        assert!(outs.iter().all(|o| o.starts_with("HTTP/1.1 200 OK")), "{:?}", outs);
+        assert_eq!(*kicked.borrow(), vec![WatchdogAction::Restarted(ComponentKind::WebListener)]);
+        // 두 수락 스레드의 연결 수 합
+        assert_eq!(stats, ListenStats { connections: 3, requests: 3, rejected: 0 });
+        // 중지 후 등록 해제 — 재시작 훅이 쥔 job_tx를 놓아 워커가 끝난다
+        assert!(wd.borrow().component(&ComponentKind::WebListener).is_none());
+        assert_eq!(wd.borrow().total_restarts, 1);
+    }
+
+    #[test]
+    fn test_override_api_over_tcp() {
+        use crate::admin_override::{self, AuditLog, OverrideRequest, TargetKind, OVERRIDE_SCOPE};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let mut car = CrownyRuntime::new();
+        let task = car.submit(AppTask::new(TaskType::Execute, "alice", "stuck"), |_| {
+            (TritState::Pending, ResultData::None)
+        }).task_id;
+        let mut server = create_demo_server();
+        let signer = TokenSigner::new("서버키");
+        let operator = signer.issue("ops", &[OVERRIDE_SCOPE], 60_000).encode();
+        let root = signer.issue("root", &["*"], 60_000).encode();
+        let audit = admin_override::shared(AuditLog::new());
+        mount_override_api(&mut server, Rc::new(RefCell::new(CrownyBridge::new())), audit.clone(), None, signer);
+        let stop = server.shutdown_handle();
+
+        let client = std::thread::spawn(move || {
+            let req = |state, reason: &str| OverrideRequest {
+                kind: TargetKind::Task, target: task.to_string(), state, justification: reason.into(),
+            };
+            let timeout = Duration::from_secs(5);
+            let outs = vec![
+                admin_override::submit_remote(&url, &root, &req(1, "와일드카드"), timeout),
+                admin_override::submit_remote(&url, &operator, &req(1, "LLM 응답 수동 확인 & 승인"), timeout),
+                admin_override::submit_remote(&url, &operator, &req(-1, "재시도"), timeout),
+            ];
+            stop.stop();
+            outs
+        });
+        server.listen_on(listener, &mut car).unwrap();
+        let outs = client.join().unwrap();
+
+        assert!(outs[0].as_ref().unwrap_err().starts_with("HTTP 403"), "{:?}", outs[0]);
+        assert!(outs[1].as_ref().unwrap().contains("LLM 응답 수동 확인 & 승인"));
+        assert!(outs[2].as_ref().unwrap_err().starts_with("HTTP 422"));
+        assert_eq!(car.history().iter().last().unwrap().state, TritState::Success);
+        let audit = audit.borrow();
+        assert_eq!((audit.verify(), audit.entries()[0].operator.as_str()), (Ok(1), "ops"));
+    }
+
+    #[test]
+    fn test_listen_thread_pool() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut server = create_demo_server()
+            .with_config(ServerConfig { idle_timeout_ms: 200, workers: 3, ..ServerConfig::default() });
+        let stop = server.shutdown_handle();
 
-        TritResult {
-            state: final_state,
-            data: ResultData::Text(texts.join("\n")),
-            elapsed_ms: 0,
-            task_id: 0,
+        let clients = std::thread::spawn(move || {
+            let conns: Vec<_> = (0..4).map(|i| std::thread::spawn(move || {
+                let mut s = TcpStream::connect(addr).unwrap();
+                let body = format!("넣어 {}\n종료", i);
+                let req = format!(
+                    "POST /run HTTP/1.1\r\nX-Crowny-Trit: PPOOOOOOO\r\nTransfer-Encoding: chunked\r\n\
+                     Connection: close\r\n\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+                    body.len(), body);
+                s.write_all(req.as_bytes()).unwrap();
+                let mut out = String::new();
+                s.read_to_string(&mut out).unwrap();
+                out
+            })).collect();
+            let outs: Vec<String> = conns.into_iter().map(|c| c.join().unwrap()).collect();
+            stop.stop();
+            outs
+        });
+
+        let mut car = CrownyRuntime::new();
+        let stats = server.listen_on(listener, &mut car).unwrap();
+        let outs = clients.join().unwrap();
+
+        assert_eq!(stats, ListenStats { connections: 4, requests: 4, rejected: 0 });
+        for out in &outs {
+            assert!(out.starts_with("HTTP/1.1 200 OK"), "{}", out);
+            assert!(out.contains("X-Crowny-Trit: "));
+            assert!(out.contains("Connection: close"));
         }
+        assert!(server.stats().contains("요청:4"));
     }
 
-    pub fn stats(&self) -> String {
-        format!("[LLM] 호출:{} 토큰:{} 기본모델:{}", self.call_count, self.total_tokens, self.default_model)
+    #[test]
+    fn test_idle_timeout() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        // 아무것도 보내지 않고 연결만 유지
+        let client = std::thread::spawn(move || {
+            let mut s = TcpStream::connect(addr).unwrap();
+            let mut out = String::new();
+            s.read_to_string(&mut out).unwrap();
+            out
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut server = create_demo_server()
+            .with_config(ServerConfig { idle_timeout_ms: 50, ..ServerConfig::default() });
+        let mut car = CrownyRuntime::new();
+        assert_eq!(server.serve_connection(stream, &mut car).unwrap(), 0);
+        assert_eq!(client.join().unwrap(), "");
     }
-}
 
-/// LLM 응답 시뮬레이션
-fn simulate_llm_response(prompt: &str, model: &str) -> LlmResponse {
-    let text = format!("[{} 응답] 입력 '{}' 에 대한 균형3진 기반 분석 결과입니다.", model, prompt);
-    let tokens = (prompt.len() as u32 / 2) + 50; // 대략적 토큰 수
+    #[test]
+    fn test_config_admin_reload() {
+        use crate::config::RuntimeConfig;
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let cfg = Rc::new(RefCell::new(ConfigManager::new(RuntimeConfig::default())));
+        let signer = TokenSigner::new("서버키");
+        let admin = signer.issue("ops", &[admin_override::CONFIG_SCOPE], 60_000).encode();
+        let wildcard = signer.issue("root", &["*"], 60_000).encode();
+        mount_config_admin(&mut server, cfg.clone(), signer);
+
+        // 조회도 토큰 필요 — CTP 권한 트릿만으로는 안 된다
+        let get = HttpRequest::new(HttpMethod::Get, "/admin/config").with_ctp(CtpHeader::from_header_str("PPOOOOOOO"));
+        assert_eq!(server.handle(&get, &mut car).status, 401);
+        assert_eq!(server.handle(&get.with_header(TOKEN_HEADER, &admin), &mut car).status, 200);
+
+        let req = HttpRequest::new(HttpMethod::Post, "/admin/config")
+            .with_body("[fees]\ndex_fee_bps = 25")
+            .with_header(TOKEN_HEADER, &admin);
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("fees.dex_fee_bps: 30 → 25"));
+        assert_eq!(cfg.borrow().current().dex_fee_bps, 25);
+
+        // 잘못된 설정 → 422, 기존 유지
+        let req = req.with_body("[fees]\ndex_fee_bps = 5000");
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 422);
+        assert_eq!(cfg.borrow().current().dex_fee_bps, 25);
 
-    LlmResponse {
-        text,
-        model: LlmModel::Custom(model.to_string()),
-        tokens_used: tokens,
-        trit_state: TritState::Success,
+        // 와일드카드 토큰 · 위조 CTP 헤더 → 403 / 401
+        let forged = HttpRequest::new(HttpMethod::Post, "/admin/config")
+            .with_body("[fees]\ndex_fee_bps = 1")
+            .with_ctp(CtpHeader::from_header_str("PPOOOOOOO"));
+        assert_eq!(server.handle(&forged, &mut car).status, 401);
+        assert_eq!(server.handle(&forged.with_header(TOKEN_HEADER, &wildcard), &mut car).status, 403);
+        assert_eq!(cfg.borrow().current().dex_fee_bps, 25);
     }
-}
 
-// ═══════════════════════════════════════════════
-// 기본 라우트 생성 헬퍼
-// ═══════════════════════════════════════════════
+    #[test]
+    fn test_approval_api() {
+        use crate::kernel::KernelConfig;
+        use crate::permission::{Action, TritPermission};
+        let mut kernel = CrownyKernel::boot(KernelConfig::default());
+        kernel.permission.add_policy("ops", "승인", Action::Admin, TritPermission::Allow, "운영자");
+        let kernel = Rc::new(RefCell::new(kernel));
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let ops = signer.issue("ops", &[admin_override::APPROVAL_SCOPE], 60_000).encode();
+        let intern = signer.issue("intern", &[admin_override::APPROVAL_SCOPE], 60_000).encode();
+        let other = signer.issue("ops", &[admin_override::WEBHOOK_SCOPE], 60_000).encode();
+        mount_approval_api(&mut server, kernel.clone(), signer);
 
-/// 기본 Crowny 서버 생성 (데모용 라우트 포함)
-pub fn create_demo_server() -> CrownyServer {
-    let mut server = CrownyServer::new(7293);
+        // LLM 능력 O → 202 보류
+        let submit = HttpRequest::new(HttpMethod::Post, "/programs").with_body("넣어 \"질문\"\n질문해\n넣어 7\n종료");
+        let resp = server.handle(&submit, &mut car);
+        assert_eq!(resp.status, 202, "{}", resp.body);
+        let list = HttpRequest::new(HttpMethod::Get, "/admin/approvals").with_header(TOKEN_HEADER, &ops);
+        assert!(server.handle(&list, &mut car).body.contains("\"subject\":\"web\""));
 
-    // GET /
-    server.route(HttpMethod::Get, "/", |_req, car| {
-        let result = car.run_source("system", "넣어 1\n종료");
-        HttpResponse {
-            status: 200,
-            headers: HashMap::new(),
-            body: format!("{{\"상태\":\"{}\",\"메시지\":\"Crowny 서버 작동중\"}}", result.state),
-            ctp: CtpHeader::success(),
-            trit_result: result,
-        }
-    });
+        // 범위 밖 토큰 · 토큰 없음 · 커널 승인 권한 없는 주체
+        let approve = HttpRequest::new(HttpMethod::Post, "/admin/approvals/1/approve");
+        assert_eq!(server.handle(&approve.clone().with_header(TOKEN_HEADER, &other), &mut car).status, 403);
+        assert_eq!(server.handle(&approve, &mut car).status, 401);
+        assert_eq!(server.handle(&approve.clone().with_header(TOKEN_HEADER, &intern), &mut car).status, 409);
 
-    // POST /run — 한선어 실행
-    server.route(HttpMethod::Post, "/run", |req, car| {
-        let result = car.run_source("web", &req.body);
-        let status = match result.state {
-            TritState::Success => 200,
-            TritState::Pending => 202,
-            TritState::Failed => 500,
-        };
-        HttpResponse {
-            status,
-            headers: HashMap::new(),
-            body: format!("{{\"상태\":\"{}\",\"결과\":\"{}\"}}", result.state, result.data),
-            ctp: if result.state == TritState::Success { CtpHeader::success() } else { CtpHeader::failed() },
-            trit_result: result,
-        }
-    });
+        let resp = server.handle(&approve.clone().with_header(TOKEN_HEADER, &ops), &mut car);
+        assert_eq!(resp.status, 200, "{}", resp.body);
+        assert_eq!(kernel.borrow().approvals.get(1).unwrap().decided_by.as_deref(), Some("ops"));
+        assert!(kernel.borrow().vm.cycles > 0);
 
-    // POST /compile — WASM 컴파일
-    server.route(HttpMethod::Post, "/compile", |req, car| {
-        let result = car.compile_wasm("web", &req.body);
-        let status = if result.state == TritState::Success { 200 } else { 500 };
-        let body_text = match &result.data {
-            ResultData::Bytes(b) => format!("{{\"상태\":\"{}\",\"크기\":{}}}", result.state, b.len()),
-            _ => format!("{{\"상태\":\"{}\"}}", result.state),
-        };
-        HttpResponse {
-            status,
-            headers: HashMap::new(),
-            body: body_text,
-            ctp: if result.state == TritState::Success { CtpHeader::success() } else { CtpHeader::failed() },
-            trit_result: result,
-        }
-    });
+        // 다음 요청은 거절
+        assert_eq!(server.handle(&submit, &mut car).status, 202);
+        let reject = HttpRequest::new(HttpMethod::Post, "/admin/approvals/2/reject")
+            .with_body("reason=%EC%98%88%EC%82%B0").with_header(TOKEN_HEADER, &ops);
+        assert_eq!(server.handle(&reject, &mut car).status, 200);
+        assert_eq!(kernel.borrow().approvals.get(2).unwrap().state, TritPermission::Deny);
+        assert!(kernel.borrow().approvals.pending().is_empty());
+    }
 
-    server
-}
+    #[test]
+    fn test_webhook_admin_replay() {
+        use crate::integrations::{MemoryTransport, RetryPolicy, WebhookDispatcher};
+        let transport = MemoryTransport::default().respond("http://ops.local/", vec![Ok(500)]);
+        let mut hub = WebhookDispatcher::new(Box::new(transport));
+        hub.policy = RetryPolicy { max_attempts: 1, ..RetryPolicy::default() };
+        let hooks = hub.shared();
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new().with_webhooks(hooks.clone());
+        let signer = TokenSigner::new("서버키");
+        let admin = signer.issue("ops", &[admin_override::WEBHOOK_SCOPE], 60_000).encode();
+        mount_webhook_admin(&mut server, hooks.clone(), signer);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let req = HttpRequest::new(HttpMethod::Post, "/admin/webhooks")
+            .with_body("url=http%3A%2F%2Fops.local%2F&events=task.finished,+nft.sold&secret=ops-secret")
+            .with_header(TOKEN_HEADER, &admin);
+        let resp = server.handle(&req, &mut car);
+        assert_eq!(resp.status, 200, "{}", resp.body);
+        let bad = req.clone().with_body("url=http%3A%2F%2Fx%2F&events=nope&secret=ops-secret");
+        assert_eq!(server.handle(&bad, &mut car).status, 422);
+        // P 트릿 헤더만 보낸 요청은 토큰이 없으므로 거부
+        let forged = HttpRequest::new(HttpMethod::Post, "/admin/webhooks")
+            .with_body("url=http%3A%2F%2F169.254.169.254%2F&events=task.finished&secret=x")
+            .with_ctp(CtpHeader::from_header_str("PPOOOOOOO"));
+        assert_eq!(server.handle(&forged, &mut car).status, 401);
+
+        // 작업 완료 → 전송 실패 1회 → 데드레터 → 관리자 재전송
+        car.run_source("alice", "넣어 1\n종료");
+        assert_eq!(hooks.borrow_mut().deliver_due(u64::MAX).dead, 1);
+        let dead = HttpRequest::new(HttpMethod::Get, "/admin/webhooks/dead").with_header(TOKEN_HEADER, &admin);
+        let resp = server.handle(&dead, &mut car);
+        assert!(resp.body.contains("\"type\":\"task.finished\""), "{}", resp.body);
+
+        let replay = HttpRequest::new(HttpMethod::Post, "/admin/webhooks/replay").with_body("id=all").with_header(TOKEN_HEADER, &admin);
+        assert!(server.handle(&replay, &mut car).body.contains("\"replayed\":1"));
+        assert!(hooks.borrow().dead_letters().is_empty());
+        assert!(hooks.borrow().next_due().is_some());
+    }
 
     #[test]
-    fn test_ctp_header() {
-        let h = CtpHeader::from_header_str("PPPOOOOOT");
-        assert_eq!(h.state, 1);
-        assert_eq!(h.permission, 1);
-        assert_eq!(h.consensus, 1);
-        assert_eq!(h.to_header_str(), "PPPOOOOOT");
+    fn test_webhook_pump_delivers_after_response() {
+        use crate::integrations::{EventKind, MemoryTransport, WebhookDispatcher};
+        let transport = MemoryTransport::default();
+        let sent = transport.sent.clone();
+        let hooks = WebhookDispatcher::new(Box::new(transport)).shared();
+        hooks.borrow_mut().register("http://ops.local/", &[EventKind::AlertFired], "pump-secret").unwrap();
+        let mut server = create_demo_server();
+        server.add_middleware(WebhookPump(hooks.clone()));
+        let mut car = CrownyRuntime::new();
+
+        server.handle(&HttpRequest::new(HttpMethod::Get, "/"), &mut car);
+        assert!(sent.borrow().is_empty());
+        hooks.borrow_mut().emit(EventKind::AlertFired, JsonObject::new().str("component", "car"));
+        server.handle(&HttpRequest::new(HttpMethod::Get, "/"), &mut car);
+        assert_eq!(sent.borrow().len(), 1);
+        assert!(hooks.borrow().next_due().is_none());
     }
 
     #[test]
-    fn test_server_handle() {
+    fn test_form_params() {
+        let p = form_params("pool=CRWN-USDT&token_in=CRWN&amount=1000&memo=%ED%95%9C+%EA%B8%80&bad=%zz");
+        assert_eq!(p["pool"], "CRWN-USDT");
+        assert_eq!(p["memo"], "한 글");
+        assert_eq!(p["bad"], "%zz");
+        assert_eq!(param_u64(&p, "amount"), Ok(1000));
+        assert!(param_u64(&p, "pool").is_err());
+        assert!(param(&p, "없음").is_err());
+    }
+
+    #[test]
+    fn test_market_api() {
+        use crate::nft::{NFTMetadata, NFTRarity};
+        let mut dex = CrownyDEX::new();
+        dex.mint("lp", "CRWN", 500_000);
+        dex.mint("lp", "USDT", 100_000);
+        dex.mint("alice", "CRWN", 10_000);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("lp", &pool, 500_000, 100_000).unwrap();
+        let mut market = CrownyNFT::new();
+        market.fund("alice", 5_000);
+        let col = market.create_collection("삼진", "TRI", "bob", "", None, 500);
+        let nft_id = market.mint(&col, "bob", NFTMetadata::new("P", "", ""), NFTRarity::Rare).unwrap();
+        let dex = Rc::new(RefCell::new(dex));
+        let market = Rc::new(RefCell::new(market));
+
         let mut server = create_demo_server();
         let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let alice = signer.issue("alice", &["dex.*", "nft.buy", "nft.read"], 60_000).encode();
+        let bob = signer.issue("bob", &["nft.*"], 60_000).encode();
+        mount_market_api(&mut server, dex.clone(), market.clone(), signer);
 
-        // GET /
-        let req = HttpRequest::new(HttpMethod::Get, "/").with_ctp(CtpHeader::success());
-        let resp = server.handle(&req, &mut car);
+        let post = |path: &str, token: &str, body: &str| HttpRequest::new(HttpMethod::Post, path)
+            .with_header(TOKEN_HEADER, token).with_body(body);
+
+        // 토큰 없음 401 · 범위 부족 403
+        let swap = format!("pool={}&token_in=CRWN&amount=1000", pool);
+        assert_eq!(server.handle(&HttpRequest::new(HttpMethod::Post, "/dex/swap").with_body(&swap), &mut car).status, 401);
+        assert_eq!(server.handle(&post("/nft/list", &alice, &format!("nft={}&price=100", nft_id)), &mut car).status, 403);
+
+        // 견적 = 실제 스왑, 견적은 상태를 바꾸지 않음
+        let quote = server.handle(&post("/dex/quote", &alice, &swap), &mut car);
+        assert_eq!(quote.status, 200);
+        assert_eq!(dex.borrow().balance("alice", "USDT"), 0);
+        let resp = server.handle(&post("/dex/swap", &alice, &swap), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 1));
+        let out = dex.borrow().balance("alice", "USDT");
+        assert!(out > 0 && quote.body.contains(&format!("\"amount_out\":{}", out)));
+        assert!(resp.body.contains("crowny.swap_result"));
+
+        // 업무 오류 → 422
+        let resp = server.handle(&post("/dex/swap", &alice, "pool=없음&token_in=CRWN&amount=1"), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (422, -1));
+        assert_eq!(server.handle(&post("/dex/liquidity", &alice, &format!("pool={}", pool)), &mut car).status, 422);
+
+        // NFT: 소유자만 등록 → 목록 → 구매
+        let list = format!("nft={}&price=1000", nft_id);
+        let resp = server.handle(&post("/nft/list", &bob, &list), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 0));
+        let get = HttpRequest::new(HttpMethod::Get, "/nft/listings").with_header(TOKEN_HEADER, &alice);
+        assert!(server.handle(&get, &mut car).body.contains(&nft_id));
+        let resp = server.handle(&post("/nft/buy", &alice, &format!("nft={}", nft_id)), &mut car);
         assert_eq!(resp.status, 200);
+        assert_eq!(market.borrow().nfts[&nft_id].owner, "alice");
+        // alice는 이제 소유자지만 nft.list 범위가 없다
+        assert_eq!(server.handle(&post("/nft/list", &alice, &list), &mut car).status, 403);
+        assert_eq!(server.handle(&post("/nft/list", &bob, &list), &mut car).status, 422);
+    }
 
-        // POST /run
-        let req = HttpRequest::new(HttpMethod::Post, "/run")
-            .with_body("넣어 10\n넣어 20\n더해\n종료")
-            .with_ctp(CtpHeader::success());
-        let resp = server.handle(&req, &mut car);
+    #[test]
+    fn test_royalty_api_splits_apply_to_sales() {
+        use crate::nft::{NFTMetadata, NFTRarity};
+        let mut market = CrownyNFT::new();
+        market.fund("carol", 100_000);
+        let col = market.create_collection("삼진", "TRI", "bob", "", None, 500);
+        let nft_id = market.mint(&col, "bob", NFTMetadata::new("P", "", ""), NFTRarity::Rare).unwrap();
+        market.list(&nft_id, 10_000).unwrap();
+        let market = Rc::new(RefCell::new(market));
+
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let bob = signer.issue("bob", &["nft.royalty"], 60_000).encode();
+        let carol = signer.issue("carol", &["nft.*"], 60_000).encode();
+        mount_market_api(&mut server, Rc::new(RefCell::new(CrownyDEX::new())), market.clone(), signer);
+        let post = |path: &str, token: &str, body: &str| HttpRequest::new(HttpMethod::Post, path)
+            .with_header(TOKEN_HEADER, token).with_body(body);
+
+        // 제작자만 · 지분 합 10000 · 형식 오류 → 422
+        let split = format!("collection={}&rate_bps=1000&splits=bob:7000,zoe:3000", col);
+        assert_eq!(server.handle(&post("/nft/royalty", &carol, &split), &mut car).status, 422);
+        let bad = format!("collection={}&rate_bps=1000&splits=bob:7000,zoe:2000", col);
+        assert_eq!(server.handle(&post("/nft/royalty", &bob, &bad), &mut car).status, 422);
+        let bad = format!("collection={}&rate_bps=1000&splits=bob", col);
+        assert_eq!(server.handle(&post("/nft/royalty", &bob, &bad), &mut car).status, 422);
+        let resp = server.handle(&post("/nft/royalty", &bob, &split), &mut car);
+        assert!(resp.status == 200 && resp.body.contains("\"share_bps\":3000"), "{}", resp.body);
+
+        // 판매가 재정의로 정산 — 10% 를 70/30
+        let resp = server.handle(&post("/nft/buy", &carol, &format!("nft={}", nft_id)), &mut car);
         assert_eq!(resp.status, 200);
-        assert_eq!(resp.trit_result.state, TritState::Success);
+        assert_eq!(market.borrow().balance("zoe"), 300);
+        assert_eq!(market.borrow().market_history[0].royalty_paid, 1_000);
+
+        let clear = format!("collection={}", col);
+        let resp = server.handle(&post("/nft/royalty/clear", &bob, &clear), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 1));
+        let resp = server.handle(&post("/nft/royalty/clear", &bob, &clear), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 0));
     }
 
     #[test]
-    fn test_ctp_denied() {
+    fn test_airdrop_api_resumes_by_id() {
+        let mut engine = TokenEngine::new("Crowny", "CRWN", 1_000_000, "treasury");
+        engine.transfer("treasury", "alice", 10_000);
+        let wallets = Rc::new(RefCell::new(engine));
+        let mut market = CrownyNFT::new();
+        let col = market.create_collection("삼진", "TRI", "bob", "", Some(2), 0);
+        let market = Rc::new(RefCell::new(market));
+
         let mut server = create_demo_server();
         let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let alice = signer.issue("alice", &["airdrop.run"], 60_000).encode();
+        let bob = signer.issue("bob", &["airdrop.run"], 60_000).encode();
+        mount_airdrop_api(&mut server, wallets.clone(), market.clone(), Rc::new(RefCell::new(TritStore::new())), signer);
+        let post = |token: &str, body: &str| HttpRequest::new(HttpMethod::Post, "/airdrop")
+            .with_header(TOKEN_HEADER, token).with_body(body);
 
-        let req = HttpRequest::new(HttpMethod::Get, "/").with_ctp(CtpHeader::failed());
-        let resp = server.handle(&req, &mut car);
-        assert_eq!(resp.status, 403);
+        // 청크 한도 100 — 150명 목록을 한 청크만 보내고 멈춘 뒤 같은 id로 재개
+        let csv: String = (0..150).map(|i| format!("u{:03},10%0A", i)).collect();
+        let resp = server.handle(&post(&alice, &format!("id=d1&chunks=1&csv={}", csv)), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 0));
+        assert!(resp.body.contains("\"cursor\":100"));
+        assert_eq!((wallets.borrow().balance_of("u099"), wallets.borrow().balance_of("u100")), (10, 0));
+        let resp = server.handle(&post(&alice, &format!("id=d1&csv={}", csv)), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 1));
+        assert!(resp.body.contains("\"delivered\":50"));
+        assert_eq!(wallets.borrow().balance_of("u149"), 10);
+        // 같은 id에 다른 목록 → 거부
+        assert_eq!(server.handle(&post(&alice, "id=d1&csv=zed,1"), &mut car).status, 422);
+
+        // NFT: 컬렉션 제작자만, 발행량 초과 청크는 실패로 기록
+        let nft = format!("id=n1&collection={}&name=P&csv=carol%0Adave,2", col);
+        assert_eq!(server.handle(&post(&alice, &nft), &mut car).status, 422);
+        let resp = server.handle(&post(&bob, &nft), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, -1));
+        assert!(resp.body.contains("최대 발행량 초과"));
+        assert!(market.borrow().nfts.is_empty());
     }
 
     #[test]
-    fn test_llm_call() {
+    fn test_margin_api_open_close_and_liquidate() {
+        use crate::margin::MarginConfig;
+        let mut dex = CrownyDEX::new();
+        dex.mint("lp", "CRWN", 500_000);
+        dex.mint("lp", "USDT", 100_000);
+        dex.mint("alice", "USDT", 2_000);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("lp", &pool, 500_000, 100_000).unwrap();
+        let dex = Rc::new(RefCell::new(dex));
+        // 창 0 — TWAP = 현재가 (시각을 돌릴 수 없는 테스트용)
+        let engine = Arc::new(Mutex::new(MarginEngine::new(MarginConfig::default(), 0)));
+        let kernel = Rc::new(RefCell::new(CrownyKernel::boot(Default::default())));
+
+        let mut server = create_demo_server();
         let mut car = CrownyRuntime::new();
-        let mut llm = CrownyLlm::new();
+        let signer = TokenSigner::new("서버키");
+        let alice = signer.issue("alice", &["margin.trade", "margin.read"], 60_000).encode();
+        let bob = signer.issue("bob", &["margin.trade"], 60_000).encode();
+        let keeper = signer.issue("keeper", &["margin.liquidate"], 60_000).encode();
+        mount_margin_api(&mut server, dex.clone(), engine.clone(), kernel, signer);
+        let post = |path: &str, token: &str, body: &str| HttpRequest::new(HttpMethod::Post, path)
+            .with_header(TOKEN_HEADER, token).with_body(body);
 
-        let result = llm.ask("테스트 질문", &mut car);
-        assert_eq!(result.state, TritState::Success);
+        let resp = server.handle(&post("/margin/deposit", &alice, "token=USDT&amount=2000"), &mut car);
+        assert_eq!((resp.status, dex.borrow().balance("alice", "USDT")), (200, 0));
+        let long = format!("pool={}&side=long&collateral=1000&leverage=10", pool);
+        let resp = server.handle(&post("/margin/open", &alice, &long), &mut car);
+        assert!(resp.status == 200 && resp.body.contains("\"side\":\"long\""), "{}", resp.body);
+        let short = format!("pool={}&side=short&collateral=500&leverage=3", pool);
+        assert_eq!(server.handle(&post("/margin/open", &alice, &short), &mut car).status, 200);
+        // 레버리지 초과 · 잘못된 방향 → 422
+        let bad = format!("pool={}&side=long&collateral=100&leverage=20", pool);
+        assert_eq!(server.handle(&post("/margin/open", &alice, &bad), &mut car).status, 422);
+        let bad = format!("pool={}&side=up&collateral=100&leverage=2", pool);
+        assert_eq!(server.handle(&post("/margin/open", &alice, &bad), &mut car).status, 422);
+
+        let positions = HttpRequest::new(HttpMethod::Get, "/margin/positions").with_header(TOKEN_HEADER, &alice);
+        let body = server.handle(&positions, &mut car).body;
+        assert!(body.contains("\"USDT\":500") && body.contains("\"id\":1") && body.contains("\"id\":2"), "{}", body);
+
+        // 남의 포지션은 닫을 수 없다 → 소유자가 닫으면 종료 + 손익
+        assert_eq!(server.handle(&post("/margin/close", &bob, "position=2"), &mut car).status, 422);
+        let resp = server.handle(&post("/margin/close", &alice, "position=2"), &mut car);
+        assert!(resp.body.contains("\"status\":\"closed\"") && resp.body.contains("\"pnl\""), "{}", resp.body);
+        assert_eq!(server.handle(&post("/margin/close", &alice, "position=2"), &mut car).status, 422);
+
+        // 대량 매도로 가격 급락 → 청산 검사에서 10배 롱 청산 (T)
+        dex.borrow_mut().mint("whale", "CRWN", 100_000);
+        dex.borrow_mut().swap("whale", &pool, "CRWN", 100_000).unwrap();
+        assert_eq!(server.handle(&post("/margin/liquidate", &alice, ""), &mut car).status, 403);
+        let resp = server.handle(&post("/margin/liquidate", &keeper, ""), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, -1));
+        assert!(resp.body.contains("crowny.margin_event"), "{}", resp.body);
+        assert!(!server.handle(&positions, &mut car).body.contains("\"id\":1"));
+
+        // 미사용 담보만 인출
+        let free = engine.lock().unwrap().free_collateral("alice", "USDT");
+        let over = format!("token=USDT&amount={}", free + 1);
+        assert_eq!(server.handle(&post("/margin/withdraw", &alice, &over), &mut car).status, 422);
+        let all = format!("token=USDT&amount={}", free);
+        assert_eq!(server.handle(&post("/margin/withdraw", &alice, &all), &mut car).status, 200);
+        assert_eq!(dex.borrow().balance("alice", "USDT"), free);
     }
 
     #[test]
-    fn test_llm_consensus() {
+    fn test_portfolio_api() {
+        let mut dex = CrownyDEX::new();
+        dex.mint("lp", "CRWN", 500_000);
+        dex.mint("lp", "USDT", 100_000);
+        dex.mint("alice", "USDT", 1_000);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("lp", &pool, 500_000, 100_000).unwrap();
+        let dex = Rc::new(RefCell::new(dex));
+        let service = Rc::new(RefCell::new(PortfolioService::new(dex.clone(), Rc::new(RefCell::new(CrownyNFT::new())))));
+
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let reader = signer.issue("alice", &["portfolio.read"], 60_000).encode();
+        let full = signer.issue("alice", &["portfolio.*"], 60_000).encode();
+        mount_portfolio_api(&mut server, service.clone(), signer);
+
+        let req = |method, path: &str, token: &str| HttpRequest::new(method, path).with_header(TOKEN_HEADER, token);
+        assert_eq!(server.handle(&HttpRequest::new(HttpMethod::Get, "/portfolio"), &mut car).status, 401);
+        let resp = server.handle(&req(HttpMethod::Get, "/portfolio", &reader), &mut car);
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("\"user\":\"alice\"") && resp.body.contains("\"symbol\":\"USDT\""));
+
+        // 스냅샷은 별도 범위 · 기록 후 시계열에 나타남
+        assert_eq!(server.handle(&req(HttpMethod::Post, "/portfolio/snapshot", &reader), &mut car).status, 403);
+        assert_eq!(server.handle(&req(HttpMethod::Post, "/portfolio/snapshot", &full), &mut car).status, 200);
+        let resp = server.handle(&req(HttpMethod::Get, "/portfolio/history", &reader), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 1));
+        assert_eq!(service.borrow().history("alice").len(), 1);
+        assert!(resp.body.contains("crowny.portfolio_history"));
+    }
+
+    #[test]
+    fn test_account_api_keys_ledgers_by_address() {
+        let accounts = crate::account::AccountRegistry::default().shared();
+        let id = accounts.borrow_mut().create("alice", &[7u8; 32]).unwrap();
+        let mut dex = CrownyDEX::new();
+        dex.mint(&id.to_string(), "USDT", 1_000);
+        let dex = Rc::new(RefCell::new(dex));
+
+        let mut server = create_demo_server();
+        server.accounts(accounts.clone());
         let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        mount_account_api(&mut server, accounts.clone(), vec![dex.clone()], signer.clone());
+
+        // 주소 토큰 → 그 계정의 보유 자산
+        let token = signer.issue(&id.to_string(), &["account.read"], 60_000).encode();
+        let resp = server.handle(&HttpRequest::new(HttpMethod::Get, "/account").with_header(TOKEN_HEADER, &token), &mut car);
+        assert_eq!((resp.status, resp.ctp.state), (200, 0));
+        assert!(resp.body.contains("\"asset\":\"USDT\"") && resp.body.contains("\"amount\":1000"));
+
+        // 이름 토큰 → legacy 계정으로 해석되어 등록
+        let token = signer.issue("bob", &["account.read"], 60_000).encode();
+        let resp = server.handle(&HttpRequest::new(HttpMethod::Get, "/account").with_header(TOKEN_HEADER, &token), &mut car);
+        assert_eq!(resp.status, 200);
+        assert_eq!(accounts.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_accounting_api_and_budget_block() {
+        let accounting = billing::Accounting::new().shared();
+        let mut car = CrownyRuntime::new().with_accounting(accounting.clone());
+        let mut server = create_demo_server();
+        let signer = TokenSigner::new("서버키");
+        let alice = signer.issue("acme/alice", &["accounting.read"], 60_000).encode();
+        let admin = signer.issue("ops", &["accounting.*"], 60_000).encode();
+        mount_accounting_api(&mut server, accounting.clone(), signer);
+
+        // 실행 사이클 · LLM 토큰 · 저장 바이트 → acme 테넌트
+        assert_eq!(car.run_source("acme/alice", "넣어 1\n넣어 2\n더해\n종료").state, TritState::Success);
         let mut llm = CrownyLlm::new();
+        llm.call(LlmRequest::new(LlmModel::Claude, "안녕").for_subject("acme/bob"), &mut car);
+        let wrote = car.submit_metered(AppTask::new(TaskType::DbQuery, "acme/alice", "k"), |_| {
+            (TritState::Success, ResultData::None, Usage::of(Resource::StoreBytes, 7))
+        });
+        assert_eq!(wrote.state, TritState::Success);
+        let today = accounting.borrow().day_of(billing::now_ms());
+        let used = accounting.borrow().usage("acme", today);
+        assert!(used.cycles > 0 && used.llm_tokens > 0);
+        assert_eq!(used.store_bytes, 7);
 
-        let result = llm.consensus_call(
-            "균형3진 효율?",
-            &[LlmModel::Claude, LlmModel::Gpt4, LlmModel::Gemini],
-            &mut car,
-        );
-        assert_eq!(result.state, TritState::Success);
+        let get = |path: &str, token: &str| HttpRequest::new(HttpMethod::Get, path).with_header(TOKEN_HEADER, token);
+        let resp = server.handle(&get("/accounting", &alice), &mut car);
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("\"tenant\":\"acme\"") && resp.body.contains("\"store_bytes\":7"));
+        assert!(accounting.borrow().usage("acme", today).net_bytes > 0);
+
+        // 예산: 사이클 한도 초과 → T, 새 실행 거부
+        let budget = HttpRequest::new(HttpMethod::Post, "/accounting/budget").with_header(TOKEN_HEADER, &admin);
+        assert_eq!(server.handle(&budget.clone().with_body("tenant=acme&cycles=1"), &mut car).ctp.state, -1);
+        assert_eq!(server.handle(&budget.clone().with_body("tenant=acme&cycles=x"), &mut car).status, 422);
+        let blocked = car.run_source("acme/alice", "넣어 1\n종료");
+        assert_eq!(blocked.state, TritState::Failed);
+        assert!(matches!(&blocked.data, ResultData::Text(t) if t == "예산 초과"));
+        assert_eq!(car.run_source("globex", "넣어 1\n종료").state, TritState::Success);
+        server.handle(&budget.with_body("tenant=acme"), &mut car);
+        assert_eq!(car.run_source("acme/alice", "넣어 1\n종료").state, TritState::Success);
+
+        // CSV 내보내기 — 별도 범위
+        assert_eq!(server.handle(&get("/accounting/export", &alice), &mut car).status, 403);
+        assert_eq!(server.handle(&get("/accounting/export?from=2026-13-01", &admin), &mut car).status, 400);
+        let resp = server.handle(&get("/accounting/export?tenant=acme", &admin), &mut car);
+        assert_eq!(resp.headers.get("Content-Type").map(String::as_str), Some("text/csv; charset=utf-8"));
+        let rows: Vec<&str> = resp.body.lines().collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[1].starts_with(&format!("{},acme,", billing::format_day(today))));
+    }
+
+    #[test]
+    fn test_rpc_get_logs_over_http() {
+        let vm = Rc::new(RefCell::new(crate::contract_vm::ContractVM::new()));
+        let (code, abi) = crate::contract_vm::token_contract();
+        let token = vm.borrow_mut().deploy("T", "alice", code, abi);
+        vm.borrow_mut().call(&token, "init", crate::contract_vm::ExecCtx {
+            caller: "alice".into(), value: 0, block_h: 3, gas_limit: 100_000, args: vec![],
+        });
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        mount_rpc(&mut server, Rc::new(RefCell::new(LogRpc::new(vm))));
+
+        let post = |body: &str| HttpRequest::new(HttpMethod::Post, "/rpc").with_body(body);
+        let resp = server.handle(&post(r#"{"jsonrpc":"2.0","id":9,"method":"eth_getLogs","params":[{"fromBlock":"earliest","topics":["Init"]}]}"#), &mut car);
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.starts_with(r#"{"jsonrpc":"2.0","id":9,"result":[{"address":"#) && resp.body.contains(&token));
+        assert_eq!(server.handle(&post(r#"{"jsonrpc":"2.0","method":"eth_blockNumber"}"#), &mut car).status, 204);
+    }
+
+    #[test]
+    fn test_rpc_subscription_pushed_to_hub() {
+        let vm = Rc::new(RefCell::new(crate::contract_vm::ContractVM::new()));
+        let (code, abi) = crate::contract_vm::token_contract();
+        let token = vm.borrow_mut().deploy("T", "alice", code, abi);
+        let rpc = Rc::new(RefCell::new(LogRpc::new(vm.clone())));
+        let hub = EventHub::new();
+        let frames = hub.subscribe();
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        mount_rpc(&mut server, rpc.clone());
+        server.add_middleware(RpcPump { rpc, hub });
+
+        let post = |body: &str| HttpRequest::new(HttpMethod::Post, "/rpc").with_body(body);
+        let resp = server.handle(&post(r#"{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":["logs",{"topics":["Transfer"]}]}"#), &mut car);
+        assert!(resp.body.contains(r#""result":"0x1""#), "{}", resp.body);
+        assert!(frames.try_recv().is_err());
+
+        vm.borrow_mut().advance_block();
+        vm.borrow_mut().call(&token, "transfer", crate::contract_vm::ExecCtx {
+            caller: "alice".into(), value: 0, block_h: 0, gas_limit: 100_000, args: vec![],
+        });
+        server.handle(&HttpRequest::new(HttpMethod::Get, "/"), &mut car);
+        let frame = frames.try_recv().unwrap();
+        assert!(frame.starts_with(r#"{"type":"rpc","message":{"jsonrpc":"2.0","method":"eth_subscription""#), "{}", frame);
+        assert!(frame.contains(&token));
+        assert!(frames.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_kernel_watch_recovers_stalled_scheduler() {
+        use crate::kernel::KernelConfig;
+        use crate::scheduler::{TritPriority, TritResult};
+        use crate::watchdog::ComponentKind;
+        let kernel = Rc::new(RefCell::new(CrownyKernel::boot(KernelConfig::default())));
+        {
+            let mut k = kernel.borrow_mut();
+            k.scheduler.submit_locked("보유", TritPriority::Normal, "장부", Box::new(|| TritResult::Pending));
+            k.scheduler.execute_one();
+            k.scheduler.submit_locked("대기", TritPriority::High, "장부", Box::new(|| TritResult::Success));
+            // 누락 허용 0회 — 진행 없는 스케줄러를 즉시 멈춘 것으로 판정
+            k.watchdog.max_missed = 0;
+        }
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        server.add_middleware(KernelWatch(kernel.clone()));
+        server.handle(&HttpRequest::new(HttpMethod::Get, "/"), &mut car);
+
+        let k = kernel.borrow();
+        assert_eq!(k.scheduler.stats_recovered, 1);
+        assert_eq!(k.scheduler.lock_holder("장부"), None);
+        assert_eq!(k.watchdog.component(&ComponentKind::Scheduler).unwrap().restarts, 1);
+    }
+
+    #[test]
+    fn test_nft_mint_stores_metadata_content() {
+        let store = crate::content::ContentStore::new().shared();
+        let mut market = CrownyNFT::new();
+        market.content = Some(store.clone());
+        let col = market.create_collection("삼진", "TRI", "bob", "", None, 500);
+        let market = Rc::new(RefCell::new(market));
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let alice = signer.issue("alice", &["nft.*"], 60_000).encode();
+        let bob = signer.issue("bob", &["nft.mint"], 60_000).encode();
+        mount_market_api(&mut server, Rc::new(RefCell::new(CrownyDEX::new())), market.clone(), signer);
+        mount_content(&mut server, store.clone());
+
+        let mint = |token: &str| HttpRequest::new(HttpMethod::Post, "/nft/mint")
+            .with_header(TOKEN_HEADER, token)
+            .with_body(&format!("collection={}&name=%EC%9A%A9&image=crwn://art/x.png", col));
+        assert_eq!(server.handle(&mint(&alice), &mut car).status, 422);
+        let resp = server.handle(&mint(&bob), &mut car);
+        assert_eq!(resp.status, 200, "{}", resp.body);
+        let nft = market.borrow().nfts.values().next().cloned().unwrap();
+        assert_eq!(nft.owner, "bob");
+        let hash = crate::content::parse_uri(nft.metadata_uri.as_deref().unwrap()).unwrap().to_string();
+        assert_eq!(store.borrow().get(&hash).unwrap().pins, vec![nft.id.clone()]);
+        let doc = server.handle(&HttpRequest::new(HttpMethod::Get, &format!("/content/{}", hash)), &mut car);
+        let body = String::from_utf8(doc.bytes.unwrap()).unwrap();
+        assert_eq!(doc.status, 200);
+        assert!(body.contains(r#""name":"용""#) && body.contains("crwn://art/x.png"), "{}", body);
+    }
+
+    #[test]
+    fn test_content_fetch_verifies_hash() {
+        let store = crate::content::ContentStore::new().shared();
+        let png = b"\x89PNG\r\n\x1a\n\xff";
+        let hash = store.borrow_mut().put_pinned(png, "image/png", "nft").unwrap();
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        mount_content(&mut server, store.clone());
+
+        let get = |path: &str| HttpRequest::new(HttpMethod::Get, path);
+        let resp = server.handle(&get(&format!("/content/{}", hash)), &mut car);
+        assert_eq!((resp.status, resp.headers["Content-Type"].as_str()), (200, "image/png"));
+        let mut wire = Vec::new();
+        write_response(&mut wire, &resp, false, 1000).unwrap();
+        assert!(wire.ends_with(png) && String::from_utf8_lossy(&wire).contains("Content-Length: 9\r\n"));
+
+        assert_eq!(server.handle(&get("/content/nothex"), &mut car).status, 400);
+        assert_eq!(server.handle(&get(&format!("/content/{}", crate::content::content_id(b"x"))), &mut car).status, 404);
+        assert_eq!(server.handle(&get("/content/"), &mut car).status, 404);
+        // 저장소 변조 → 거부
+        let mut bad = store.borrow().get(&hash).unwrap();
+        bad.bytes.push(0);
+        let tampered = crate::trit_store::StoreValue::Map([("bytes".to_string(), crate::trit_store::StoreValue::Bytes(bad.bytes)),
+            ("mime".to_string(), crate::trit_store::StoreValue::Text(bad.mime))].into_iter().collect());
+        store.borrow_mut().store.set(&format!("content.{}", hash), tampered);
+        assert_eq!(server.handle(&get(&format!("/content/{}", hash)), &mut car).status, 500);
+    }
+
+    #[test]
+    fn test_jobs_api() {
+        let jobs = Rc::new(RefCell::new(JobScheduler::new()));
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        let signer = TokenSigner::new("서버키");
+        let alice = signer.issue("alice", &["jobs.*"], 60_000).encode();
+        let bob = signer.issue("bob", &["jobs.*"], 60_000).encode();
+        let reader = signer.issue("carol", &["jobs.read"], 60_000).encode();
+        mount_jobs_api(&mut server, jobs.clone(), signer);
+
+        let post = |path: &str, token: &str, body: &str| {
+            HttpRequest::new(HttpMethod::Post, path).with_header(TOKEN_HEADER, token).with_body(body)
+        };
+        let add = "name=%EC%A0%95%EC%82%B0&schedule=%EB%A7%A4%EC%9D%BC+09:00&source=%EB%84%A3%EC%96%B4+1%0A%EC%A2%85%EB%A3%8C";
+        assert_eq!(server.handle(&post("/jobs", &reader, add), &mut car).status, 403);
+        let resp = server.handle(&post("/jobs", &alice, add), &mut car);
+        assert_eq!(resp.status, 200, "{}", resp.body);
+        assert!(resp.body.contains("\"owner\":\"alice\"") && resp.body.contains("\"next_run_local\""));
+        assert_eq!(jobs.borrow().jobs()[0].source, "넣어 1\n종료");
+        // 잘못된 일정 · 같은 이름 → 422
+        assert_eq!(server.handle(&post("/jobs", &alice, "name=x&schedule=*+*&source=a"), &mut car).status, 422);
+        assert_eq!(server.handle(&post("/jobs", &bob, add), &mut car).status, 422);
+
+        let list = HttpRequest::new(HttpMethod::Get, "/jobs").with_header(TOKEN_HEADER, &reader);
+        let resp = server.handle(&list, &mut car);
+        assert!(resp.body.contains("crowny.jobs") && resp.body.contains("매일 09:00"));
+
+        // 소유자만 삭제
+        assert_eq!(server.handle(&post("/jobs/remove", &bob, "id=1"), &mut car).status, 422);
+        assert_eq!(server.handle(&post("/jobs/remove", &alice, "id=1"), &mut car).status, 200);
+        assert!(jobs.borrow().jobs().is_empty());
+    }
+
+    #[test]
+    fn test_artifacts_api() {
+        let artifacts = crate::artifacts::shared();
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new().with_artifacts(artifacts.clone());
+        let signer = TokenSigner::new("서버키");
+        let builder = signer.issue("alice", &["artifacts.*"], 60_000).encode();
+        let reader = signer.issue("bob", &["artifacts.read"], 60_000).encode();
+        mount_artifacts_api(&mut server, artifacts.clone(), signer);
+
+        let post = |path: &str, token: &str, body: &str| {
+            HttpRequest::new(HttpMethod::Post, path).with_header(TOKEN_HEADER, token).with_body(body)
+        };
+        let source = "source=%EB%84%A3%EC%96%B4+1%0A%EC%A2%85%EB%A3%8C";
+        assert_eq!(server.handle(&post("/artifacts/build", &reader, source), &mut car).status, 403);
+        let resp = server.handle(&post("/artifacts/build", &builder, source), &mut car);
+        assert_eq!(resp.status, 200, "{}", resp.body);
+        assert!(resp.body.contains("\"wasm\":\"") && resp.body.contains("\"source_map\":\""));
+        // 같은 소스 재빌드 → 중복 제거
+        server.handle(&post("/artifacts/build", &builder, source), &mut car);
+        assert_eq!(artifacts.borrow().stats().artifacts, 4);
+
+        let list = HttpRequest::new(HttpMethod::Get, "/artifacts").with_header(TOKEN_HEADER, &reader);
+        let resp = server.handle(&list, &mut car);
+        assert!(resp.body.contains("crowny.artifacts") && resp.body.contains("\"refs\":2"));
+
+        let hash = artifacts.borrow().list()[0].hash.clone();
+        assert_eq!(server.handle(&post("/artifacts/pin", &reader, &format!("hash={}", hash)), &mut car).status, 403);
+        assert_eq!(server.handle(&post("/artifacts/pin", &builder, &format!("hash={}", hash)), &mut car).status, 200);
+        assert_eq!(server.handle(&post("/artifacts/pin", &builder, "hash=0t없음"), &mut car).status, 422);
+        let hashes: Vec<String> = artifacts.borrow().list().iter().map(|a| a.hash.clone()).collect();
+        for a in hashes {
+            for _ in 0..2 {
+                server.handle(&post("/artifacts/release", &builder, &format!("hash={}", a)), &mut car);
+            }
+        }
+        // 고정된 하나만 남음
+        let resp = server.handle(&post("/artifacts/gc", &builder, ""), &mut car);
+        assert!(resp.body.contains("\"removed\":3"), "{}", resp.body);
+        assert_eq!(artifacts.borrow().stats().artifacts, 1);
+    }
+
+    #[test]
+    fn test_ctp_version_negotiation() {
+        let mut car = CrownyRuntime::new();
+        let mut server = CrownyServer::new(7293);
+        server.route(HttpMethod::Get, "/ping", |_req, _car| ok_response("{}".into()));
+        let ping = |ctp: &str| HttpRequest::new(HttpMethod::Get, "/ping").with_ctp(CtpHeader::from_header_str(ctp));
+
+        // 미지정(구버전 클라이언트) → 그대로
+        let resp = server.handle(&ping("POOOOOOOO"), &mut car);
+        assert_eq!((resp.status, resp.ctp.version()), (200, 0));
+        // 상위 버전 클라이언트 → 서버 최고 버전으로 낮춤
+        let resp = server.handle(&ping("POOOOPPOO"), &mut car);
+        assert_eq!((resp.status, resp.ctp.version()), (200, network::PROTOCOL_VERSION as i8));
+
+        // 최저 버전을 올린 서버 + v1 클라이언트 → 426 구조화된 T
+        server.config.protocol_versions = (2, 2);
+        let v1 = CtpHeader::from_header_str("POOOO").with_version(1).to_header_str();
+        let resp = server.handle(&ping(&v1), &mut car);
+        assert_eq!(resp.status, 426);
+        assert_eq!(resp.ctp.state, -1);
+        assert!(resp.body.contains("\"schema\":\"crowny.version_mismatch\"") && resp.body.contains("\"min_version\":2"));
+    }
+
+    #[test]
+    fn test_ctp_v2_roundtrip_and_negotiation() {
+        for (id, hops) in [(0u64, 0u8), (1, 1), (19_682, 13), (19_683 + 42, 5)] {
+            let mut h = CtpHeaderV2::new(CtpHeader::success().with_version(2), id);
+            h.hops = hops;
+            let s = h.to_header_str();
+            assert_eq!(s.len(), CTP_V2_TRITS);
+            assert!(s.starts_with(&CtpHeader::success().with_version(2).to_header_str()));
+            let back = CtpHeaderV2::from_header_str(&s).unwrap();
+            assert_eq!((back.request_id as u64, back.hops, back.versions), (id % 19_683, hops, h.versions));
+        }
+        assert_eq!(CtpHeaderV2::new(CtpHeader::new(), 7).next_hop().next_hop().hops, 2);
+        // 9-trit 문자열이나 형식 뱅크가 다른 27-trit는 V2가 아니다
+        assert!(CtpHeaderV2::from_header_str("PPPOOOOOO").is_none());
+        assert!(CtpHeaderV2::from_header_str(&"O".repeat(27)).is_none());
+
+        let mut car = CrownyRuntime::new();
+        let mut server = CrownyServer::new(7293);
+        server.route(HttpMethod::Get, "/ping", |_req, _car| ok_response("{}".into()));
+        let req = CtpHeaderV2::new(CtpHeader::success(), 321).with_versions((1, 4)).next_hop();
+        let resp = server.handle(&HttpRequest::new(HttpMethod::Get, "/ping")
+            .with_header(CTP_V2_HEADER, &req.to_header_str()), &mut car);
+        let reply = CtpHeaderV2::from_header_str(&resp.headers[CTP_V2_HEADER]).unwrap();
+        assert_eq!((reply.request_id, reply.hops, reply.versions), (321, 1, server.config.protocol_versions));
+        assert_eq!(resp.ctp.version(), network::PROTOCOL_VERSION as i8);
+
+        // 9-trit만 보낸 구버전 클라이언트에는 V2를 붙이지 않는다
+        let resp = server.handle(&HttpRequest::new(HttpMethod::Get, "/ping").with_ctp(CtpHeader::success()), &mut car);
+        assert!(!resp.headers.contains_key(CTP_V2_HEADER));
+        // 겹치는 버전이 없으면 426
+        server.config.protocol_versions = (3, 4);
+        let old = CtpHeaderV2::new(CtpHeader::success(), 1).with_versions((1, 2));
+        let resp = server.handle(&HttpRequest::new(HttpMethod::Get, "/ping")
+            .with_header(CTP_V2_HEADER, &old.to_header_str()), &mut car);
+        assert_eq!(resp.status, 426);
+    }
+
+    #[test]
+    fn test_history_api() {
+        let mut dex = CrownyDEX::new();
+        dex.mint("lp", "CRWN", 500_000);
+        dex.mint("lp", "USDT", 100_000);
+        let pool = dex.create_pool("CRWN", "USDT", 30);
+        dex.add_liquidity("lp", &pool, 500_000, 100_000).unwrap();
+        for (user, amount) in [("alice", 1_000), ("bob", 2_000), ("alice", 3_000)] {
+            dex.mint(user, "CRWN", amount);
+            dex.swap(user, &pool, "CRWN", amount).unwrap();
+        }
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        mount_history_api(&mut server, Rc::new(RefCell::new(dex)), Rc::new(RefCell::new(CrownyNFT::new())),
+            Rc::new(RefCell::new(CrownyChain::new())), Rc::new(RefCell::new(TritEventLog::new())));
+
+        let get = |path: &str| HttpRequest::new(HttpMethod::Get, path);
+        let resp = server.handle(&get("/history/swaps?account=alice&limit=1"), &mut car);
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("\"amount_in\":3000") && resp.body.contains("\"next_cursor\":0"));
+        let resp = server.handle(&get("/history/swaps?account=alice&limit=1&cursor=0"), &mut car);
+        assert!(resp.body.contains("\"amount_in\":1000") && resp.body.contains("\"next_cursor\":null"));
+        assert_eq!(server.handle(&get("/history/swaps?trit=X"), &mut car).status, 400);
+        assert!(server.handle(&get("/history/market"), &mut car).body.contains("\"count\":0"));
+        // 위의 요청들도 CAR 작업 기록에 남는다
+        let resp = server.handle(&get("/history/tasks?order=asc&limit=2"), &mut car);
+        assert!(resp.body.contains("crowny.task_page"));
+    }
+
+    #[test]
+    fn test_history_blocks_and_logs() {
+        let chain = crate::chain::sample_chain();
+        let height = chain.height();
+        let mut log = TritEventLog::new();
+        log.info(Category::Task, "alice", "작업 완료", TritState::Success);
+        log.info(Category::Task, "bob", "작업 실패", TritState::Failed);
+        log.info(Category::Network, "alice", "피어 연결", TritState::Success);
+        let mut server = create_demo_server();
+        let mut car = CrownyRuntime::new();
+        mount_history_api(&mut server, Rc::new(RefCell::new(CrownyDEX::new())), Rc::new(RefCell::new(CrownyNFT::new())),
+            Rc::new(RefCell::new(chain)), Rc::new(RefCell::new(log)));
+        let get = |path: &str| HttpRequest::new(HttpMethod::Get, path);
+
+        // 최신 블록부터, 커서 = 블록 번호
+        let resp = server.handle(&get("/history/blocks?limit=1"), &mut car);
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("crowny.block_page"), "{}", resp.body);
+        assert!(resp.body.contains(&format!("\"index\":{}", height)), "{}", resp.body);
+        let resp = server.handle(&get("/history/blocks?order=asc&limit=1"), &mut car);
+        assert!(resp.body.contains("\"index\":0"), "{}", resp.body);
+
+        // 출처 · 트릿 필터
+        let resp = server.handle(&get("/history/logs?account=alice"), &mut car);
+        assert!(resp.body.contains("\"count\":2"), "{}", resp.body);
+        let resp = server.handle(&get("/history/logs?trit=T"), &mut car);
+        assert!(resp.body.contains("작업 실패") && resp.body.contains("\"count\":1"), "{}", resp.body);
+        assert_eq!(server.handle(&get("/history/logs?since=x"), &mut car).status, 400);
     }
 
     #[test]
@@ -480,6 +480,17 @@ CTP PPPPOOOOO
         (trit, log, body)
     }
 
+    /// 포트폴리오 페이지 + API 등록 — /portfolio/<사용자>, /api/portfolio/<사용자>
+    pub fn mount_portfolio(&mut self, report: &crate::portfolio::PortfolioReport) -> String {
+        let path = format!("/portfolio/{}", report.user);
+        let api = format!("/api{}", path);
+        self.router.add("GET", &path, "page:portfolio", 1);
+        self.router.add("GET", &api, "api:portfolio", 1);
+        self.pages.insert(path.clone(), report.to_crwn_page());
+        self.api_data.insert(api, report.to_json().build());
+        path
+    }
+
     pub fn summary(&self) -> String {
         let mut lines = Vec::new();
         lines.push(format!("═══ {} ═══", self.name));
@@ -493,6 +504,101 @@ CTP PPPPOOOOO
     }
 }
 
+// ═══════════════════════════════════════
+// HTML 렌더링 — .crwn / Markdown 부분집합
+// ═══════════════════════════════════════
+
+pub fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// `[P] 텍스트` → (trit, 텍스트)
+fn trit_line(line: &str) -> Option<(char, &str)> {
+    for t in ['P', 'O', 'T'] {
+        if let Some(rest) = line.strip_prefix(&format!("[{}]", t)) {
+            return Some((t, rest.trim()));
+        }
+    }
+    None
+}
+
+/// .crwn 페이지 또는 Markdown을 정적 HTML 문서로
+/// 지원: # 제목, ---, [P]/[O]/[T] 항목, ``` 코드 펜스, 문단
+pub fn render_html(title: &str, source: &str) -> String {
+    let mut body = String::new();
+    let mut para: Vec<&str> = Vec::new();
+    let mut in_list = false;
+    let mut in_fence = false;
+
+    fn flush(body: &mut String, para: &mut Vec<&str>, in_list: &mut bool) {
+        if !para.is_empty() {
+            body.push_str(&format!("<p>{}</p>\n", html_escape(&para.join(" "))));
+            para.clear();
+        }
+        if *in_list {
+            body.push_str("</ul>\n");
+            *in_list = false;
+        }
+    }
+
+    for line in source.lines() {
+        if in_fence {
+            if line.trim_start().starts_with("```") {
+                body.push_str("</code></pre>\n");
+                in_fence = false;
+            } else {
+                body.push_str(&html_escape(line));
+                body.push('\n');
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            flush(&mut body, &mut para, &mut in_list);
+            let lang = lang.split_whitespace().next().unwrap_or("");
+            if lang.is_empty() {
+                body.push_str("<pre><code>");
+            } else {
+                body.push_str(&format!("<pre class=\"cell-{}\"><code class=\"language-{}\">", html_escape(lang), html_escape(lang)));
+            }
+            in_fence = true;
+        } else if trimmed.is_empty() {
+            flush(&mut body, &mut para, &mut in_list);
+        } else if trimmed == "---" {
+            flush(&mut body, &mut para, &mut in_list);
+            body.push_str("<hr>\n");
+        } else if let Some(level) = (1..=6).find(|n| trimmed.starts_with(&format!("{} ", "#".repeat(*n)))) {
+            flush(&mut body, &mut para, &mut in_list);
+            body.push_str(&format!("<h{}>{}</h{}>\n", level, html_escape(trimmed[level..].trim()), level));
+        } else if let Some((t, text)) = trit_line(trimmed) {
+            if !para.is_empty() {
+                body.push_str(&format!("<p>{}</p>\n", html_escape(&para.join(" "))));
+                para.clear();
+            }
+            if !in_list {
+                body.push_str("<ul class=\"trit\">\n");
+                in_list = true;
+            }
+            body.push_str(&format!("<li class=\"trit-{}\">[{}] {}</li>\n", t.to_ascii_lowercase(), t, html_escape(text)));
+        } else {
+            if in_list {
+                body.push_str("</ul>\n");
+                in_list = false;
+            }
+            para.push(trimmed);
+        }
+    }
+    if in_fence {
+        body.push_str("</code></pre>\n");
+    }
+    flush(&mut body, &mut para, &mut in_list);
+
+    format!("<!DOCTYPE html>\n<html lang=\"ko\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>.trit-p{{color:#2e7d32}}.trit-o{{color:#f9a825}}.trit-t{{color:#c62828}}pre{{background:#f5f5f5;padding:8px}}</style>\n\
+             </head>\n<body>\n{}</body>\n</html>\n", html_escape(title), body)
+}
+
 // ═══ 데모 ═══
 
 pub fn demo_website() {
@@ -563,8 +669,20 @@ pub fn demo_website() {
     }
     println!();
 
-    // 6. 사이트 요약
-    println!("━━━ 6. 사이트 요약 ━━━");
+    // 6. 포트폴리오 페이지
+    println!("━━━ 6. 포트폴리오 페이지 ━━━");
+    let mut site = site;
+    let report = crate::portfolio::demo_report("alice");
+    let path = site.mount_portfolio(&report);
+    let (trit, log, body) = site.handle("GET", &path);
+    println!("  [{}] {}", match trit { 1 => "P", -1 => "T", _ => "O" }, log);
+    for line in body.lines().filter(|l| l.starts_with('[')) {
+        println!("    {}", line);
+    }
+    println!();
+
+    // 7. 사이트 요약
+    println!("━━━ 7. 사이트 요약 ━━━");
     println!("{}", site.summary());
     println!();
 
@@ -644,4 +762,28 @@ mod tests {
         let out = ts.execute("출력 \"hello world\"");
         assert_eq!(out[0], "hello world");
     }
+
+    #[test]
+    fn test_portfolio_page() {
+        let mut site = CrownyWebsite::new("Test", 3000);
+        let report = crate::portfolio::demo_report("alice");
+        let path = site.mount_portfolio(&report);
+        let (trit, _, body) = site.handle("GET", &path);
+        assert_eq!(trit, 1);
+        assert!(body.contains("alice 포트폴리오") && body.contains("## 유동성 포지션"));
+        let (_, _, api) = site.handle("GET", "/api/portfolio/alice");
+        assert!(api.contains("\"schema\":\"crowny.portfolio\""));
+    }
+
+    #[test]
+    fn test_render_html() {
+        let html = render_html("<제목>", "# Crowny\n\n[P] 허용 & 통과\n[T] 차단\n\n본문 한 줄\n둘째 줄\n\n```hanseon\n넣어 1 <2>\n```\n---");
+        assert!(html.contains("<title>&lt;제목&gt;</title>"));
+        assert!(html.contains("<h1>Crowny</h1>"));
+        assert!(html.contains("<li class=\"trit-p\">[P] 허용 &amp; 통과</li>"));
+        assert!(html.contains("<li class=\"trit-t\">[T] 차단</li>\n</ul>"));
+        assert!(html.contains("<p>본문 한 줄 둘째 줄</p>"));
+        assert!(html.contains("<code class=\"language-hanseon\">넣어 1 &lt;2&gt;\n</code></pre>"));
+        assert!(html.contains("<hr>"));
+    }
 }
@@ -0,0 +1,354 @@
+// ═══════════════════════════════════════════════════════════════
+// WebSocket 이벤트 스트림 (RFC 6455)
+// GET /ws 업그레이드 뒤 서버가 JSON 텍스트 프레임을 밀어준다
+//
+//   {"type":"log", "event":{...}}           TritEventLog 이벤트 (HubSink)
+//   {"type":"task", "task_id":..}           CAR 작업 완료
+//   {"type":"vote", "query":.., "vote":..}  live_consensus 노드 투표
+//   {"type":"consensus", "result":{...}}    live_consensus 합의 결과
+//
+// 클라이언트 프레임은 ping/close만 처리하고 나머지는 버린다
+// ═══════════════════════════════════════════════════════════════
+
+use std::io::{BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use crate::car::TritState;
+use crate::crypto;
+use crate::i18n;
+use crate::output::JsonObject;
+use crate::trit_log::{Event, LogSink};
+use crate::webserver::{CtpHeader, HttpMethod, HttpRequest, ShutdownHandle, WireError};
+
+/// Sec-WebSocket-Accept 계산용 고정 GUID
+const WS_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+pub const OP_TEXT: u8 = 0x1;
+pub const OP_CLOSE: u8 = 0x8;
+pub const OP_PING: u8 = 0x9;
+pub const OP_PONG: u8 = 0xA;
+
+/// 종료 코드 — 정상 / 서버 종료 / 프로토콜 위반
+pub const CLOSE_NORMAL: u16 = 1000;
+pub const CLOSE_GOING_AWAY: u16 = 1001;
+pub const CLOSE_PROTOCOL: u16 = 1002;
+
+/// 클라이언트 프레임 최대 크기 (제어 프레임만 쓰므로 작게)
+const MAX_CLIENT_FRAME: usize = 64 * 1024;
+/// 구독자별 대기 프레임 상한 — 넘치면 그 구독자 몫은 버린다
+const MAX_QUEUED: usize = 1024;
+/// 중지 플래그 · 상대 종료 확인 주기
+const POLL: Duration = Duration::from_millis(50);
+
+/// Sec-WebSocket-Key → Sec-WebSocket-Accept
+pub fn accept_key(key: &str) -> String {
+    crypto::base64(&crypto::sha1(format!("{}{}", key.trim(), WS_GUID).as_bytes()))
+}
+
+/// 업그레이드 요청 검사 → Sec-WebSocket-Accept 값
+/// CTP 권한 T는 403, 업그레이드 헤더·버전 불일치는 426, 그 밖의 형식 오류는 400
+pub fn accept_upgrade(req: &HttpRequest) -> Result<String, (u16, String)> {
+    if req.ctp.overall_state() == TritState::Failed {
+        return Err((403, i18n::t("web.ctp_denied").to_string()));
+    }
+    let has_token = |name: &str, token: &str| req.header(name)
+        .is_some_and(|v| v.split(',').any(|t| t.trim().eq_ignore_ascii_case(token)));
+    if !has_token("Upgrade", "websocket") {
+        return Err((426, "Upgrade: websocket 필요".into()));
+    }
+    if req.header("Sec-WebSocket-Version") != Some("13") {
+        return Err((426, "Sec-WebSocket-Version: 13만 지원".into()));
+    }
+    if req.method != HttpMethod::Get || !has_token("Connection", "upgrade") {
+        return Err((400, "GET + Connection: Upgrade 필요".into()));
+    }
+    match req.header("Sec-WebSocket-Key") {
+        Some(key) if key.trim().len() == 24 => Ok(accept_key(key)),
+        _ => Err((400, "Sec-WebSocket-Key 형식 오류".into())),
+    }
+}
+
+/// 101 응답 — CTP 헤더도 함께 싣는다
+pub fn write_handshake<W: Write>(w: &mut W, accept: &str) -> std::io::Result<()> {
+    write!(w, "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+               Sec-WebSocket-Accept: {}\r\nX-Crowny-Trit: {}\r\n\r\n",
+        accept, CtpHeader::success().to_header_str())?;
+    w.flush()
+}
+
+// ═══════════════════════════════════════════════
+// 프레임
+// ═══════════════════════════════════════════════
+
+/// 수신 프레임
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub fin: bool,
+    pub opcode: u8,
+    pub masked: bool,
+    pub payload: Vec<u8>,
+}
+
+/// 단일(FIN) 프레임 직렬화 — 서버는 mask 없이, 클라이언트는 mask를 준다
+pub fn encode_frame(opcode: u8, payload: &[u8], mask: Option<[u8; 4]>) -> Vec<u8> {
+    let mut out = vec![0x80 | opcode];
+    let bit = if mask.is_some() { 0x80 } else { 0 };
+    match payload.len() {
+        n if n < 126 => out.push(bit | n as u8),
+        n if n <= 0xffff => {
+            out.push(bit | 126);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            out.push(bit | 127);
+            out.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    match mask {
+        Some(m) => {
+            out.extend_from_slice(&m);
+            out.extend(payload.iter().enumerate().map(|(i, b)| b ^ m[i % 4]));
+        }
+        None => out.extend_from_slice(payload),
+    }
+    out
+}
+
+/// 종료 프레임 (상태 코드만)
+pub fn close_frame(code: u16) -> Vec<u8> {
+    encode_frame(OP_CLOSE, &code.to_be_bytes(), None)
+}
+
+/// 프레임 하나 읽기 — mask가 있으면 풀어서 돌려준다
+pub fn read_frame<R: Read>(r: &mut R, max_len: usize) -> Result<Frame, WireError> {
+    let mut head = [0u8; 2];
+    r.read_exact(&mut head)?;
+    if head[0] & 0x70 != 0 {
+        return Err(WireError::Malformed("RSV 비트 설정됨".into()));
+    }
+    let fin = head[0] & 0x80 != 0;
+    let opcode = head[0] & 0x0f;
+    let masked = head[1] & 0x80 != 0;
+    let len = match head[1] & 0x7f {
+        126 => {
+            let mut b = [0u8; 2];
+            r.read_exact(&mut b)?;
+            u16::from_be_bytes(b) as u64
+        }
+        127 => {
+            let mut b = [0u8; 8];
+            r.read_exact(&mut b)?;
+            u64::from_be_bytes(b)
+        }
+        n => n as u64,
+    };
+    if opcode >= OP_CLOSE && (len > 125 || !fin) {
+        return Err(WireError::Malformed("제어 프레임은 125바이트 이하 단일 프레임".into()));
+    }
+    if len > max_len as u64 {
+        return Err(WireError::BodyTooLarge(len as usize));
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        r.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    r.read_exact(&mut payload)?;
+    if masked {
+        payload.iter_mut().enumerate().for_each(|(i, b)| *b ^= mask[i % 4]);
+    }
+    Ok(Frame { fin, opcode, masked, payload })
+}
+
+// ═══════════════════════════════════════════════
+// 이벤트 허브
+// ═══════════════════════════════════════════════
+
+/// 브로드캐스트 허브 — 발행 측(CAR · 로그 · 합의)과 WebSocket 연결을 잇는다
+/// 스레드 간 공유 가능 (워커 스레드가 구독, 디스패처 스레드가 발행)
+#[derive(Debug, Clone, Default)]
+pub struct EventHub {
+    subscribers: Arc<Mutex<Vec<mpsc::SyncSender<String>>>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 구독 — 연결이 끊겨 수신자가 사라지면 다음 발행 때 정리된다
+    pub fn subscribe(&self) -> mpsc::Receiver<String> {
+        let (tx, rx) = mpsc::sync_channel(MAX_QUEUED);
+        if let Ok(mut subs) = self.subscribers.lock() {
+            subs.push(tx);
+        }
+        rx
+    }
+
+    /// JSON 프레임 하나를 모든 구독자에게 → 남은 구독자 수
+    pub fn publish(&self, frame: JsonObject) -> usize {
+        let text = frame.build();
+        let Ok(mut subs) = self.subscribers.lock() else { return 0 };
+        subs.retain(|tx| match tx.try_send(text.clone()) {
+            Ok(()) => true,
+            Err(mpsc::TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => false,
+        });
+        subs.len()
+    }
+
+    /// 대기열이 넘쳐 버린 프레임 수
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// TritEventLog 싱크 — 기록되는 이벤트를 허브로
+pub struct HubSink(pub EventHub);
+
+impl LogSink for HubSink {
+    fn write(&mut self, event: &Event) -> Result<(), String> {
+        self.0.publish(JsonObject::new().str("type", "log").object("event", event.to_json()));
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════
+// 업그레이드된 연결
+// ═══════════════════════════════════════════════
+
+/// 허브 프레임을 밀어주는 루프 (호출 스레드) + 클라이언트 프레임 읽기 (보조 스레드)
+/// 상대가 닫거나 stop이 켜지면 끝난다 — 반환: 보낸 이벤트 프레임 수
+pub fn serve_events(
+    reader: BufReader<TcpStream>,
+    stream: TcpStream,
+    events: mpsc::Receiver<String>,
+    stop: Option<&ShutdownHandle>,
+) -> std::io::Result<usize> {
+    // 브라우저는 보통 아무것도 보내지 않으므로 유휴 시간 제한을 푼다
+    stream.set_read_timeout(None)?;
+    stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+    let writer = Arc::new(Mutex::new(stream.try_clone()?));
+    let closed = Arc::new(AtomicBool::new(false));
+
+    let client = {
+        let writer = Arc::clone(&writer);
+        let closed = Arc::clone(&closed);
+        thread::spawn(move || client_frames(reader, writer, closed))
+    };
+
+    let mut sent = 0;
+    let mut code = CLOSE_NORMAL;
+    while !closed.load(Ordering::SeqCst) {
+        if stop.is_some_and(|s| s.is_stopped()) {
+            code = CLOSE_GOING_AWAY;
+            break;
+        }
+        match events.recv_timeout(POLL) {
+            Ok(text) => {
+                let frame = encode_frame(OP_TEXT, text.as_bytes(), None);
+                let ok = writer.lock().is_ok_and(|mut w| w.write_all(&frame).is_ok());
+                if !ok { break; }
+                sent += 1;
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    // 상대가 먼저 닫았으면 응답은 읽기 스레드가 이미 보냈다
+    if !closed.load(Ordering::SeqCst) {
+        if let Ok(mut w) = writer.lock() {
+            let _ = w.write_all(&close_frame(code));
+        }
+    }
+    let _ = stream.shutdown(Shutdown::Both);
+    let _ = client.join();
+    Ok(sent)
+}
+
+/// 클라이언트 프레임 처리 — ping에 pong, close에 close로 답하고 종료 표시
+fn client_frames(mut reader: BufReader<TcpStream>, writer: Arc<Mutex<TcpStream>>, closed: Arc<AtomicBool>) {
+    while let Ok(frame) = read_frame(&mut reader, MAX_CLIENT_FRAME) {
+        let Ok(mut w) = writer.lock() else { break };
+        // 클라이언트 → 서버 프레임은 mask 필수
+        if !frame.masked {
+            let _ = w.write_all(&close_frame(CLOSE_PROTOCOL));
+            break;
+        }
+        match frame.opcode {
+            OP_PING => {
+                let pong = encode_frame(OP_PONG, &frame.payload, None);
+                if w.write_all(&pong).is_err() { break; }
+            }
+            OP_CLOSE => {
+                let _ = w.write_all(&encode_frame(OP_CLOSE, frame.payload.get(..2).unwrap_or_default(), None));
+                break;
+            }
+            _ => {}
+        }
+    }
+    closed.store(true, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key_and_upgrade_checks() {
+        // RFC 6455 1.3 예시
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+        let ok = HttpRequest::new(HttpMethod::Get, "/ws")
+            .with_header("Upgrade", "websocket")
+            .with_header("Connection", "keep-alive, Upgrade")
+            .with_header("Sec-WebSocket-Version", "13")
+            .with_header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==");
+        assert_eq!(accept_upgrade(&ok).unwrap(), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+        let status = |req: HttpRequest| accept_upgrade(&req).unwrap_err().0;
+        assert_eq!(status(HttpRequest::new(HttpMethod::Get, "/ws")), 426);
+        assert_eq!(status(ok.clone().with_header("Sec-WebSocket-Version", "8")), 426);
+        assert_eq!(status(ok.clone().with_header("Sec-WebSocket-Key", "짧음")), 400);
+        assert_eq!(status(ok.with_ctp(CtpHeader::failed())), 403);
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let big = "x".repeat(70_000);
+        for (payload, mask) in [("안녕", None), ("ping", Some([1, 2, 3, 4])), (big.as_str(), Some([9, 8, 7, 6]))] {
+            let bytes = encode_frame(OP_TEXT, payload.as_bytes(), mask);
+            let frame = read_frame(&mut bytes.as_slice(), usize::MAX).unwrap();
+            assert_eq!(frame, Frame { fin: true, opcode: OP_TEXT, masked: mask.is_some(), payload: payload.as_bytes().to_vec() });
+        }
+        // 긴 제어 프레임, 한도 초과
+        let bytes = encode_frame(OP_PING, &[0; 126], None);
+        assert!(matches!(read_frame(&mut bytes.as_slice(), 1024), Err(WireError::Malformed(_))));
+        let bytes = encode_frame(OP_TEXT, &[0; 200], None);
+        assert!(matches!(read_frame(&mut bytes.as_slice(), 100), Err(WireError::BodyTooLarge(200))));
+    }
+
+    #[test]
+    fn test_hub_drops_closed_and_full_subscribers() {
+        let hub = EventHub::new();
+        let a = hub.subscribe();
+        let b = hub.subscribe();
+        drop(b);
+        assert_eq!(hub.publish(JsonObject::new().str("type", "task")), 1);
+        assert_eq!(a.recv().unwrap(), "{\"type\":\"task\"}");
+
+        for _ in 0..MAX_QUEUED + 3 {
+            hub.publish(JsonObject::new().str("type", "log"));
+        }
+        assert_eq!(hub.dropped(), 3);
+        assert_eq!(a.try_iter().count(), MAX_QUEUED);
+    }
+}